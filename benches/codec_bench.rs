@@ -4,7 +4,7 @@
 
 use criterion::{Criterion, black_box, criterion_group, criterion_main};
 use tao::codec::encoders::{flac::FlacEncoder, pcm::PcmEncoder};
-use tao::codec::{AudioCodecParams, CodecParameters, CodecParamsType, Frame};
+use tao::codec::{AudioCodecParams, CodecParameters, CodecParamsType, Frame, VideoCodecParams};
 use tao::core::{ChannelLayout, PixelFormat, Rational, SampleFormat};
 use tao::resample::ResampleContext;
 use tao::scale::{ScaleAlgorithm, ScaleContext};
@@ -17,7 +17,7 @@ fn make_s16_frame(nb_samples: u32, sample_rate: u32) -> Frame {
         data.extend_from_slice(&v.to_le_bytes());
     }
     Frame::Audio(tao::codec::frame::AudioFrame {
-        data: vec![data],
+        data: vec![data.into()],
         nb_samples,
         sample_rate,
         sample_format: SampleFormat::S16,
@@ -39,6 +39,7 @@ fn make_audio_params(sample_rate: u32, sample_format: SampleFormat) -> CodecPara
             channel_layout: ChannelLayout::MONO,
             sample_format,
             frame_size: 0,
+            block_align: 0,
         }),
     }
 }
@@ -69,6 +70,33 @@ fn bench_flac_encode(c: &mut Criterion) {
     });
 }
 
+fn bench_rawvideo_decode(c: &mut Criterion) {
+    c.bench_function("rawvideo_decode_yuv420p_1920x1080", |b| {
+        let (w, h) = (1920u32, 1080u32);
+        let mut reg = tao::codec::CodecRegistry::new();
+        tao::codec::register_all(&mut reg);
+        let mut dec = reg.create_decoder(tao::codec::CodecId::RawVideo).unwrap();
+        dec.open(&CodecParameters {
+            codec_id: tao::codec::CodecId::RawVideo,
+            extra_data: Vec::new(),
+            bit_rate: 0,
+            params: CodecParamsType::Video(VideoCodecParams {
+                width: w,
+                height: h,
+                pixel_format: PixelFormat::Yuv420p,
+                frame_rate: Rational::new(25, 1),
+                sample_aspect_ratio: Rational::new(1, 1),
+            }),
+        })
+        .unwrap();
+        let pkt = tao::codec::Packet::from_data(vec![128u8; (w * h * 3 / 2) as usize]);
+        b.iter(|| {
+            dec.send_packet(black_box(&pkt)).unwrap();
+            let _frame = dec.receive_frame().unwrap();
+        });
+    });
+}
+
 fn bench_yuv_to_rgb(c: &mut Criterion) {
     c.bench_function("yuv420p_to_rgb24_1920x1080", |b| {
         let w = 1920u32;
@@ -171,6 +199,7 @@ criterion_group!(
     benches,
     bench_pcm_encode,
     bench_flac_encode,
+    bench_rawvideo_decode,
     bench_yuv_to_rgb,
     bench_bilinear_scale,
     bench_audio_resample,
@@ -0,0 +1,84 @@
+//! 命令行参数值解析.
+
+use tao::StreamCodec;
+use tao_codec::{CodecId, CodecRegistry};
+use tao_core::Rational;
+
+/// 解析分辨率字符串 (如 "1280x720")
+pub(crate) fn parse_size(s: &str) -> Option<(u32, u32)> {
+    let parts: Vec<&str> = s.split('x').collect();
+    if parts.len() == 2 {
+        let w = parts[0].parse().ok()?;
+        let h = parts[1].parse().ok()?;
+        Some((w, h))
+    } else {
+        None
+    }
+}
+
+/// 解析帧率字符串 (如 "25"、"29.97" 或 "30000/1001"), 小数形式按 NTSC 帧率精确换算
+pub(crate) fn parse_rate(s: &str) -> Option<Rational> {
+    if let Some(slash) = s.find('/') {
+        let num: i32 = s[..slash].parse().ok()?;
+        let den: i32 = s[slash + 1..].parse().ok()?;
+        Some(Rational::new(num, den))
+    } else {
+        let fps: f64 = s.parse().ok()?;
+        if fps > 0.0 && fps.is_finite() {
+            Some(Rational::from_f64(fps, 1_001_000))
+        } else {
+            None
+        }
+    }
+}
+
+/// 解析时间点列表 (逗号分隔, 每项为秒数或 `[HH:]MM:SS[.xxx]`, 如 "1,2.5,00:01:00")
+pub(crate) fn parse_time_list(s: &str) -> Option<Vec<f64>> {
+    s.split(',').map(|item| parse_time(item.trim())).collect()
+}
+
+/// 解析单个时间点 (秒数或 `[HH:]MM:SS[.xxx]`)
+fn parse_time(s: &str) -> Option<f64> {
+    let parts: Vec<&str> = s.split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    let mut seconds = 0.0;
+    for (i, part) in parts.iter().enumerate() {
+        let value: f64 = part.parse().ok()?;
+        // 除秒以外的字段须为整数
+        if !value.is_finite() || value < 0.0 || (i + 1 < parts.len() && value.fract() != 0.0) {
+            return None;
+        }
+        seconds = seconds * 60.0 + value;
+    }
+    Some(seconds)
+}
+
+/// PTS 转秒
+pub(crate) fn pts_to_sec(pts: i64, time_base: Rational) -> f64 {
+    if !time_base.is_valid() {
+        return 0.0;
+    }
+    pts as f64 * time_base.to_f64()
+}
+
+/// 解析 `-c` / `--vcodec` 参数
+///
+/// "copy" 表示直接复制; 已注册的编码器名选择该具体实现;
+/// 其余按注册名称与 CodecId 规范名称匹配编解码器.
+pub(crate) fn parse_stream_codec(name: &str, registry: &CodecRegistry) -> StreamCodec {
+    if name == "copy" {
+        return StreamCodec::Copy;
+    }
+    if registry.find_encoder_by_name(name).is_some() {
+        return StreamCodec::Encoder(name.to_string());
+    }
+    match registry.find_codec_id_by_name(name) {
+        Some(id) => StreamCodec::Encode(id),
+        None => {
+            eprintln!("警告: 未知编解码器 '{name}', 使用默认");
+            StreamCodec::Encode(CodecId::PcmS16le)
+        }
+    }
+}
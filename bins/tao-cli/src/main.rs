@@ -2,24 +2,23 @@
 //!
 //! 对标 FFmpeg 的 ffmpeg 命令行工具, 提供音视频转码、格式转换等功能.
 
-mod filter;
+mod args;
 mod logging;
-mod processor;
+mod progress;
 mod transcode;
 
 use clap::Parser;
 use std::process;
 
+use tao::Transcoder;
+use tao::transcode::{DEFAULT_PROGRESS_INTERVAL, StreamAction, TranscodeJob};
 use tao_codec::CodecRegistry;
-use tao_core::{MediaType, TaoError};
-use tao_format::stream::{Stream, StreamParams};
-use tao_format::{FormatId, FormatRegistry, IoContext, Muxer};
-
-use filter::{parse_codec_name, parse_filter_chain, parse_rate, parse_size, pts_to_sec};
-use processor::{
-    StreamProcessor, create_audio_processor, create_video_processor, flush_encoder,
-    transcode_packet,
-};
+use tao_core::{MediaType, PixelFormat, SampleFormat};
+use tao_format::stream::StreamParams;
+use tao_format::{FormatId, FormatRegistry};
+
+use args::{parse_rate, parse_size, parse_stream_codec, parse_time_list};
+use progress::Progress;
 use transcode::transcode_to_raw_yuv;
 
 #[derive(Parser, Debug)]
@@ -33,6 +32,14 @@ struct Cli {
     #[arg(short, long)]
     output: Option<String>,
 
+    /// 强制输出容器格式 (如 "wav", "matroska"), 默认由输出文件扩展名推断
+    #[arg(short = 'f', long = "format")]
+    format: Option<String>,
+
+    /// 强制输入容器格式, 跳过格式探测
+    #[arg(long = "input-format")]
+    input_format: Option<String>,
+
     /// 输出原始 YUV420p 帧到文件（用于质量验证）
     #[arg(long = "output-raw")]
     output_raw: Option<String>,
@@ -45,6 +52,10 @@ struct Cli {
     #[arg(long = "vcodec")]
     vcodec: Option<String>,
 
+    /// FLAC 压缩级别 (0-8, 默认 5), 级别越高压缩率越高
+    #[arg(long = "compression_level")]
+    compression_level: Option<u32>,
+
     /// 目标采样率 (Hz)
     #[arg(long)]
     ar: Option<u32>,
@@ -53,6 +64,14 @@ struct Cli {
     #[arg(long)]
     ac: Option<u32>,
 
+    /// 目标采样格式 (如 "s16", "f32")
+    #[arg(long = "sample_fmt")]
+    sample_fmt: Option<SampleFormat>,
+
+    /// 目标像素格式 (如 "yuv420p", "yuv422p")
+    #[arg(long = "pix_fmt")]
+    pix_fmt: Option<PixelFormat>,
+
     /// 目标视频分辨率 (如 "1280x720")
     #[arg(short = 's', long = "size")]
     size: Option<String>,
@@ -61,6 +80,14 @@ struct Cli {
     #[arg(short = 'r', long = "rate")]
     rate: Option<String>,
 
+    /// 视频关键帧间隔 (帧数), 帧内编码器 (如 rawvideo) 每帧均为关键帧
+    #[arg(short = 'g')]
+    g: Option<u32>,
+
+    /// 强制关键帧的时间点 (逗号分隔, 如 "0,2.5,00:00:05"), 与帧 PTS 比较
+    #[arg(long = "force_key_frames")]
+    force_key_frames: Option<String>,
+
     /// 视频滤镜链 (如 "crop=640:480:0:0,pad=800:600:80:60")
     #[arg(long = "vf")]
     vf: Option<String>,
@@ -77,6 +104,34 @@ struct Cli {
     #[arg(long = "ss")]
     ss: Option<f64>,
 
+    /// 每个输出流最多输出的帧数
+    #[arg(long = "frames")]
+    frames: Option<u64>,
+
+    /// 最多输出的视频帧数 (优先于 --frames)
+    #[arg(long = "vframes")]
+    vframes: Option<u64>,
+
+    /// 最多输出的音频帧数 (优先于 --frames)
+    #[arg(long = "aframes")]
+    aframes: Option<u64>,
+
+    /// 封装器私有选项 (可多次指定, 如 "fragmented=1:frag_duration=2000")
+    #[arg(long = "muxer-opt")]
+    muxer_opt: Vec<String>,
+
+    /// 多路输出交错时的最大缓存时长 (秒, 默认 10)
+    #[arg(long = "max-interleave-delta")]
+    max_interleave_delta: Option<f64>,
+
+    /// 流映射 (可多次指定, 如 "0:v:0", "0:a", "0:1")
+    #[arg(long = "map")]
+    map: Vec<String>,
+
+    /// 以 key=value 格式输出进度到文件 ("pipe:1" 或 "-" 表示 stdout)
+    #[arg(long = "progress")]
+    progress: Option<String>,
+
     /// 覆盖输出文件
     #[arg(short = 'y', long)]
     overwrite: bool,
@@ -134,341 +189,189 @@ fn main() {
     eprintln!("输入: {input_path}");
     eprintln!("输出: {output_path}");
 
-    // 解析目标分辨率
-    let target_size = cli.size.as_deref().and_then(parse_size);
-    // 解析目标帧率
-    let target_rate = cli.rate.as_deref().and_then(parse_rate);
-    // 解析 -ss/-t
-    let start_time_sec = cli.ss.unwrap_or(0.0);
-    let duration_limit_sec = cli.duration;
-
-    // 初始化注册表
-    let mut format_registry = FormatRegistry::new();
-    tao_format::register_all(&mut format_registry);
-
-    let mut codec_registry = CodecRegistry::new();
-    tao_codec::register_all(&mut codec_registry);
-
-    // 打开输入文件
-    let mut input_io = match IoContext::open_url(input_path) {
-        Ok(io) => io,
-        Err(_) => {
-            // 如果作为 URL 打开失败，尝试作为本地文件打开
-            match IoContext::open_read(input_path) {
-                Ok(io) => io,
-                Err(e) => {
-                    eprintln!("错误: 无法打开输入文件 '{input_path}': {e}");
-                    process::exit(1);
-                }
-            }
-        }
-    };
-
-    // 探测并打开输入
-    let mut demuxer = match format_registry.open_input(&mut input_io, Some(input_path)) {
-        Ok(d) => d,
+    let transcoder = match build_transcoder(&cli, input_path, output_path) {
+        Ok(transcoder) => transcoder,
         Err(e) => {
-            eprintln!("错误: 无法打开输入格式: {e}");
+            eprintln!("错误: {e}");
             process::exit(1);
         }
     };
-
-    let input_streams: Vec<Stream> = demuxer.streams().to_vec();
-
-    if input_streams.is_empty() {
-        eprintln!("错误: 输入文件中没有找到任何流");
-        process::exit(1);
-    }
-
-    eprintln!("输入格式: {}, {} 条流", demuxer.name(), input_streams.len());
-
-    // 确定输出格式
-    let output_format = match FormatId::from_filename(output_path) {
-        Some(f) => f,
-        None => {
-            eprintln!("错误: 无法从输出文件名确定格式: '{output_path}'");
+    let job = match transcoder.prepare() {
+        Ok(job) => job,
+        Err(e) => {
+            eprintln!("错误: {e}");
             process::exit(1);
         }
     };
 
-    eprintln!("输出格式: {output_format}");
+    eprintln!(
+        "输入格式: {}, {} 条流",
+        job.input_format_name(),
+        job.input_streams().len()
+    );
+    eprintln!("输出格式: {}", job.output_format());
+    print_mappings(&job);
 
-    // 确定每条流的处理方式
-    let is_audio_copy = cli.acodec.as_deref() == Some("copy");
-    let is_video_copy = cli.vcodec.as_deref() == Some("copy");
+    // 处理循环: demux → (decode → filter → scale → encode) → mux
+    let progress_sink = cli.progress.as_deref().map(|target| {
+        Progress::open_sink(target).unwrap_or_else(|e| {
+            eprintln!("错误: 无法打开进度输出 {target}: {e}");
+            process::exit(1);
+        })
+    });
+    let mut progress = Progress::new(progress_sink);
+    let stats =
+        match job.run_with_progress(DEFAULT_PROGRESS_INTERVAL, |report| progress.report(report)) {
+            Ok(stats) => stats,
+            Err(e) => {
+                eprintln!();
+                eprintln!("错误: {e}");
+                process::exit(1);
+            }
+        };
 
-    let target_audio_codec = if is_audio_copy {
-        None
-    } else {
-        cli.acodec.as_deref().map(parse_codec_name)
-    };
+    eprintln!();
+    eprintln!("转码完成:");
+    eprintln!("  输出数据包: {}", stats.packets);
+    eprintln!(
+        "  输出大小: {} 字节 ({:.2} KB)",
+        stats.total_size,
+        stats.total_size as f64 / 1024.0
+    );
+}
 
-    let target_video_codec = if is_video_copy {
-        None
-    } else {
-        cli.vcodec.as_deref().map(parse_codec_name)
-    };
+/// 按命令行参数构建转码器
+fn build_transcoder(cli: &Cli, input_path: &str, output_path: &str) -> Result<Transcoder, String> {
+    let codec_registry = tao::default_codec_registry();
+    let mut transcoder = Transcoder::new(input_path, output_path);
 
-    // 解析视频/音频滤镜链
-    let video_filters = cli.vf.as_deref().map(parse_filter_chain);
-    let audio_filters = cli.af.as_deref().map(parse_filter_chain);
-
-    // 为每条流准备编解码器
-    let mut stream_processors: Vec<Option<StreamProcessor>> = Vec::new();
-    let mut output_streams: Vec<Stream> = Vec::new();
-    let mut stream_copy_flags: Vec<bool> = Vec::new();
-
-    for stream in &input_streams {
-        match stream.media_type {
-            MediaType::Audio => {
-                if is_audio_copy {
-                    output_streams.push(stream.clone());
-                    stream_processors.push(None);
-                    stream_copy_flags.push(true);
-                    eprintln!("  流 #{}: 音频 -> 直接复制", stream.index);
-                } else {
-                    let out_codec_id = target_audio_codec.unwrap_or(stream.codec_id);
-                    let processor = create_audio_processor(
-                        stream,
-                        out_codec_id,
-                        &codec_registry,
-                        cli.ar,
-                        cli.ac,
-                        &audio_filters,
-                    );
-                    match processor {
-                        Ok((proc, out_stream)) => {
-                            eprintln!(
-                                "  流 #{}: 音频 {} -> {}",
-                                stream.index, stream.codec_id, out_codec_id
-                            );
-                            output_streams.push(out_stream);
-                            stream_processors.push(Some(proc));
-                            stream_copy_flags.push(false);
-                        }
-                        Err(e) => {
-                            eprintln!("错误: 无法创建流 #{} 的编解码器: {e}", stream.index);
-                            process::exit(1);
-                        }
-                    }
-                }
-            }
-            MediaType::Video => {
-                if is_video_copy {
-                    output_streams.push(stream.clone());
-                    stream_processors.push(None);
-                    stream_copy_flags.push(true);
-                    eprintln!("  流 #{}: 视频 -> 直接复制", stream.index);
-                } else if cli.vcodec.is_some()
-                    || target_size.is_some()
-                    || target_rate.is_some()
-                    || video_filters.is_some()
-                {
-                    let out_codec_id = target_video_codec.unwrap_or(stream.codec_id);
-                    let processor = create_video_processor(
-                        stream,
-                        out_codec_id,
-                        &codec_registry,
-                        target_size,
-                        target_rate,
-                        &video_filters,
-                    );
-                    match processor {
-                        Ok((proc, out_stream)) => {
-                            eprintln!(
-                                "  流 #{}: 视频 {} -> {} ({}x{})",
-                                stream.index,
-                                stream.codec_id,
-                                out_codec_id,
-                                if let StreamParams::Video(v) = &out_stream.params {
-                                    v.width
-                                } else {
-                                    0
-                                },
-                                if let StreamParams::Video(v) = &out_stream.params {
-                                    v.height
-                                } else {
-                                    0
-                                }
-                            );
-                            output_streams.push(out_stream);
-                            stream_processors.push(Some(proc));
-                            stream_copy_flags.push(false);
-                        }
-                        Err(e) => {
-                            eprintln!("错误: 无法创建流 #{} 的视频编解码器: {e}", stream.index);
-                            process::exit(1);
-                        }
-                    }
-                } else {
-                    // 没有指定 -vcodec 且无视频处理参数, 跳过视频流
-                    eprintln!("  流 #{}: 视频 -> 跳过 (未指定 --vcodec)", stream.index);
-                    stream_processors.push(None);
-                    stream_copy_flags.push(false);
-                }
-            }
-            _ => {
-                eprintln!(
-                    "  流 #{}: {} -> 跳过 (暂不支持)",
-                    stream.index, stream.media_type
-                );
-                stream_processors.push(None);
-                stream_copy_flags.push(false);
-            }
-        }
+    if let Some(name) = cli.input_format.as_deref() {
+        let format = FormatId::from_name(name).ok_or_else(|| format!("未知的输入格式 '{name}'"))?;
+        transcoder = transcoder.input_format(format);
     }
-
-    if output_streams.is_empty() {
-        eprintln!("错误: 没有可输出的流");
-        process::exit(1);
+    // -f 优先于扩展名推断
+    if let Some(name) = cli.format.as_deref() {
+        let format = FormatId::from_name(name).ok_or_else(|| format!("未知的输出格式 '{name}'"))?;
+        transcoder = transcoder.output_format(format);
     }
-
-    // 打开输出文件
-    let mut output_io = match IoContext::open_read_write(output_path) {
-        Ok(io) => io,
-        Err(e) => {
-            eprintln!("错误: 无法创建输出文件 '{output_path}': {e}");
-            process::exit(1);
-        }
-    };
-
-    // 创建封装器
-    let mut muxer: Box<dyn Muxer> = match format_registry.create_muxer(output_format) {
-        Ok(m) => m,
-        Err(e) => {
-            eprintln!("错误: 无法创建输出格式封装器: {e}");
-            process::exit(1);
+    if let Some(name) = cli.acodec.as_deref() {
+        transcoder = transcoder.audio_codec(parse_stream_codec(name, &codec_registry));
+    }
+    if let Some(name) = cli.vcodec.as_deref() {
+        transcoder = transcoder.video_codec(parse_stream_codec(name, &codec_registry));
+    }
+    if let Some(level) = cli.compression_level {
+        transcoder = transcoder.audio_option("compression_level", &level.to_string());
+    }
+    if let Some(rate) = cli.ar {
+        transcoder = transcoder.sample_rate(rate);
+    }
+    if let Some(channels) = cli.ac {
+        transcoder = transcoder.channels(channels);
+    }
+    if let Some(format) = cli.sample_fmt {
+        transcoder = transcoder.sample_format(format);
+    }
+    if let Some(format) = cli.pix_fmt {
+        transcoder = transcoder.pixel_format(format);
+    }
+    if let Some((width, height)) = cli.size.as_deref().and_then(parse_size) {
+        transcoder = transcoder.video_size(width, height);
+    }
+    if let Some(rate) = cli.rate.as_deref().and_then(parse_rate) {
+        transcoder = transcoder.frame_rate(rate);
+    }
+    if let Some(interval) = cli.g {
+        transcoder = transcoder.keyframe_interval(interval);
+    }
+    if let Some(spec) = cli.force_key_frames.as_deref() {
+        let times =
+            parse_time_list(spec).ok_or_else(|| format!("无效的强制关键帧时间点 '{spec}'"))?;
+        transcoder = transcoder.force_key_frames(&times);
+    }
+    if let Some(chain) = cli.af.as_deref() {
+        transcoder = transcoder.audio_filter(chain);
+    }
+    if let Some(chain) = cli.vf.as_deref() {
+        transcoder = transcoder.video_filter(chain);
+    }
+    if let Some(ss) = cli.ss {
+        transcoder = transcoder.start_time(ss);
+    }
+    if let Some(duration) = cli.duration {
+        transcoder = transcoder.duration(duration);
+    }
+    if let Some(frames) = cli.frames {
+        transcoder = transcoder.max_frames(frames);
+    }
+    if let Some(frames) = cli.vframes {
+        transcoder = transcoder.max_video_frames(frames);
+    }
+    if let Some(frames) = cli.aframes {
+        transcoder = transcoder.max_audio_frames(frames);
+    }
+    for spec in &cli.map {
+        transcoder = transcoder.map(spec);
+    }
+    for spec in &cli.muxer_opt {
+        for (key, value) in parse_muxer_options(spec)? {
+            transcoder = transcoder.muxer_option(key, value);
         }
-    };
-
-    // 写入头部
-    if let Err(e) = muxer.write_header(&mut output_io, &output_streams) {
-        eprintln!("错误: 无法写入输出文件头部: {e}");
-        process::exit(1);
     }
+    if let Some(seconds) = cli.max_interleave_delta {
+        transcoder = transcoder.max_interleave_delta(seconds);
+    }
+    Ok(transcoder)
+}
 
-    // 处理循环: demux → (decode → filter → scale → encode) → mux
-    let mut packet_count = 0u64;
-    let mut byte_count = 0u64;
-
-    loop {
-        match demuxer.read_packet(&mut input_io) {
-            Ok(input_pkt) => {
-                let stream_idx = input_pkt.stream_index;
-                if stream_idx >= input_streams.len() {
-                    continue;
-                }
-
-                let in_stream = &input_streams[stream_idx];
-
-                // -ss: 跳过早于起始时间的数据包
-                if start_time_sec > 0.0 {
-                    let pkt_time = pts_to_sec(
-                        input_pkt.pts,
-                        in_stream.time_base.num,
-                        in_stream.time_base.den,
-                    );
-                    if pkt_time < start_time_sec {
-                        continue;
-                    }
-                }
-
-                // -t: 检查持续时间限制
-                if let Some(dur) = duration_limit_sec {
-                    let pkt_time = pts_to_sec(
-                        input_pkt.pts,
-                        in_stream.time_base.num,
-                        in_stream.time_base.den,
-                    );
-                    let effective_time = pkt_time - start_time_sec;
-                    if effective_time > dur {
-                        break;
-                    }
-                }
-
-                // 检查此流是否被输出
-                let out_stream_idx = output_streams.iter().position(|s| s.index == stream_idx);
-                let out_stream_idx = match out_stream_idx {
-                    Some(idx) => idx,
-                    None => continue,
-                };
-
-                if stream_idx < stream_copy_flags.len() && stream_copy_flags[stream_idx] {
-                    // 直接复制路径
-                    let mut out_pkt = input_pkt.clone();
-                    out_pkt.stream_index = out_stream_idx;
-                    if let Err(e) = muxer.write_packet(&mut output_io, &out_pkt) {
-                        eprintln!("错误: 写入数据包失败: {e}");
-                        process::exit(1);
-                    }
-                    packet_count += 1;
-                    byte_count += out_pkt.size() as u64;
-                } else if let Some(ref mut processor) = stream_processors[stream_idx] {
-                    // 转码路径
-                    match transcode_packet(processor, &input_pkt, out_stream_idx) {
-                        Ok(packets) => {
-                            for out_pkt in &packets {
-                                if let Err(e) = muxer.write_packet(&mut output_io, out_pkt) {
-                                    eprintln!("错误: 写入数据包失败: {e}");
-                                    process::exit(1);
-                                }
-                                byte_count += out_pkt.size() as u64;
-                            }
-                            packet_count += packets.len() as u64;
-                        }
-                        Err(e) => {
-                            eprintln!("错误: 转码失败: {e}");
-                            process::exit(1);
-                        }
-                    }
+/// 打印每条被选中的输入流的处理方式
+fn print_mappings(job: &TranscodeJob) {
+    for mapping in job.mappings() {
+        let stream = &job.input_streams()[mapping.input_index];
+        match (mapping.action, mapping.output_index) {
+            (StreamAction::Copy, Some(out_idx)) => eprintln!(
+                "  流 #{}: {} -> #{out_idx} 直接复制",
+                stream.index, stream.media_type
+            ),
+            (StreamAction::Transcode { codec_id }, Some(out_idx)) => {
+                match &job.output_streams()[out_idx].params {
+                    StreamParams::Video(v) => eprintln!(
+                        "  流 #{}: 视频 {} -> #{out_idx} {codec_id} ({}x{})",
+                        stream.index, stream.codec_id, v.width, v.height
+                    ),
+                    _ => eprintln!(
+                        "  流 #{}: 音频 {} -> #{out_idx} {codec_id}",
+                        stream.index, stream.codec_id
+                    ),
                 }
             }
-            Err(TaoError::Eof) => break,
-            Err(e) => {
-                eprintln!("错误: 读取数据包失败: {e}");
-                process::exit(1);
-            }
-        }
-    }
-
-    // 刷新编码器缓存
-    for (idx, proc_opt) in stream_processors.iter_mut().enumerate() {
-        if let Some(processor) = proc_opt {
-            let out_stream_idx = output_streams
-                .iter()
-                .position(|s| s.index == idx)
-                .unwrap_or(0);
-            match flush_encoder(processor, out_stream_idx) {
-                Ok(packets) => {
-                    for out_pkt in &packets {
-                        if let Err(e) = muxer.write_packet(&mut output_io, out_pkt) {
-                            eprintln!("错误: 写入刷新数据包失败: {e}");
-                            process::exit(1);
-                        }
-                        byte_count += out_pkt.size() as u64;
-                    }
-                    packet_count += packets.len() as u64;
+            _ => match stream.media_type {
+                MediaType::Video => {
+                    eprintln!("  流 #{}: 视频 -> 跳过 (未指定 --vcodec)", stream.index)
                 }
-                Err(e) => {
-                    eprintln!("警告: 刷新编码器时出错: {e}");
+                MediaType::Unknown(code) => {
+                    eprintln!("  流 #{}: 未知类型 ({code}) -> 跳过", stream.index)
                 }
-            }
+                media_type => eprintln!(
+                    "  流 #{}: {media_type} -> 跳过 (未通过 --map 选择)",
+                    stream.index
+                ),
+            },
         }
     }
+}
 
-    // 写入尾部
-    if let Err(e) = muxer.write_trailer(&mut output_io) {
-        eprintln!("错误: 无法写入输出文件尾部: {e}");
-        process::exit(1);
-    }
-
-    eprintln!();
-    eprintln!("转码完成:");
-    eprintln!("  输出数据包: {packet_count}");
-    eprintln!(
-        "  输出大小: {byte_count} 字节 ({:.2} KB)",
-        byte_count as f64 / 1024.0
-    );
+/// 解析 `--muxer-opt` 选项串 ("key=value:key=value")
+fn parse_muxer_options(spec: &str) -> Result<Vec<(&str, &str)>, String> {
+    spec.split(':')
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| {
+            item.split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .filter(|(key, _)| !key.is_empty())
+                .ok_or_else(|| format!("无效的封装器选项 '{item}', 应为 key=value"))
+        })
+        .collect()
 }
 
 // ============================================================
@@ -493,10 +396,16 @@ fn print_banner() {
     println!("  --ac <声道数>       目标声道数");
     println!("  -s <宽x高>          目标视频分辨率 (如 1280x720)");
     println!("  -r <帧率>           目标帧率 (如 25 或 30000/1001)");
+    println!("  -g <n>              视频关键帧间隔 (帧数)");
+    println!("  --force_key_frames <时间点> 强制关键帧时间点 (如 0,2.5,00:00:05)");
     println!("  --vf <滤镜链>       视频滤镜 (如 crop=640:480:0:0,pad=800:600:80:60)");
     println!("  --af <滤镜链>       音频滤镜 (如 volume=0.5,fade=in:0:3)");
     println!("  -t <秒>             持续时间限制");
     println!("  --ss <秒>           起始时间偏移");
+    println!("  --frames <n>        每个输出流最多输出 n 帧");
+    println!("  --vframes <n>       最多输出 n 帧视频 (--aframes 对应音频)");
+    println!("  --map <说明符>      流映射, 可多次指定 (如 0:v:0, 0:a, 0:1)");
+    println!("  --progress <文件>   以 key=value 格式输出进度 (pipe:1 表示 stdout)");
     println!("  -y                  覆盖输出文件");
     println!("  --build-info        显示构建信息");
     println!();
@@ -511,6 +420,9 @@ fn print_banner() {
     println!("  tao -i input.wav -o output.wav --af volume=0.5       音量调节");
     println!("  tao -i input.mkv -o output.mkv --vf crop=640:480:0:0 视频裁剪");
     println!("  tao -i input.wav -o output.wav --ss 10 -t 30         截取 10s-40s");
+    println!("  tao -i input.mkv -o output.mkv --map 0:a:0 -c copy   仅复制第一条音轨");
+    println!("  tao -i input.mp4 -o output.aac                       提取 AAC 裸流 (ADTS)");
+    println!("  tao -i input.mp4 -o output.h264                      提取 H.264 裸流 (Annex-B)");
     println!();
     println!("使用 --help 查看完整用法.");
 }
@@ -0,0 +1,230 @@
+//! 转码进度报告.
+//!
+//! 处理过程中在 stderr 原地刷新状态行 (`time=00:01:23.45 speed=3.2x size=10MiB`),
+//! 并可通过 `--progress <文件|pipe:1>` 以 ffmpeg `-progress` 兼容的 key=value 格式输出进度,
+//! 供 GUI 前端解析.
+
+use std::fs::File;
+use std::io::{self, Write};
+
+use tao::ProgressReport;
+
+/// 将微秒格式化为 `HH:MM:SS.ffffff`
+fn format_time_us(us: i64) -> String {
+    let sign = if us < 0 { "-" } else { "" };
+    let us = us.unsigned_abs();
+    let secs = us / 1_000_000;
+    format!(
+        "{sign}{:02}:{:02}:{:02}.{:06}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        us % 1_000_000
+    )
+}
+
+/// 将字节数格式化为二进制单位 (如 `512B`, `1.5KiB`, `10MiB`)
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes}B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value < 10.0 {
+        format!("{value:.1}{}", UNITS[unit])
+    } else {
+        format!("{value:.0}{}", UNITS[unit])
+    }
+}
+
+/// 格式化 ffmpeg `-progress` 兼容的 key=value 进度块
+///
+/// 与 ffmpeg 一致, `out_time_ms` 实际以微秒为单位.
+pub(crate) fn format_progress(report: &ProgressReport) -> String {
+    let speed = if report.speed > 0.0 {
+        format!("{:.3}x", report.speed)
+    } else {
+        "N/A".to_string()
+    };
+    let out_time_us = report.out_time_us();
+    format!(
+        "frame={}\nfps={:.2}\ntotal_size={}\nout_time_us={out_time_us}\nout_time_ms={out_time_us}\nout_time={}\nspeed={speed}\nprogress={}\n",
+        report.frames,
+        report.fps(),
+        report.bytes,
+        format_time_us(out_time_us),
+        if report.finished { "end" } else { "continue" },
+    )
+}
+
+/// 格式化 stderr 状态行
+pub(crate) fn format_status_line(report: &ProgressReport) -> String {
+    let speed = if report.speed > 0.0 {
+        format!("{:.1}x", report.speed)
+    } else {
+        "N/A".to_string()
+    };
+    let time = format_time_us(report.out_time_us());
+    let mut line = format!(
+        "time={} speed={speed} size={}",
+        &time[..time.len() - 4],
+        format_size(report.bytes),
+    );
+    if let Some(ratio) = report.ratio() {
+        line.push_str(&format!(" ({:.0}%)", ratio * 100.0));
+    }
+    line
+}
+
+/// 进度输出: stderr 状态行与可选的 `--progress` 目标
+pub(crate) struct Progress {
+    /// `--progress` 输出目标
+    sink: Option<Box<dyn Write>>,
+    /// 上一次状态行的字符数, 用于原地刷新时清除残留字符
+    last_line_len: usize,
+}
+
+impl Progress {
+    /// 创建进度输出
+    pub(crate) fn new(sink: Option<Box<dyn Write>>) -> Self {
+        Self {
+            sink,
+            last_line_len: 0,
+        }
+    }
+
+    /// 打开 `--progress` 输出目标, `pipe:1` 或 `-` 表示 stdout, `pipe:2` 表示 stderr
+    pub(crate) fn open_sink(target: &str) -> io::Result<Box<dyn Write>> {
+        match target {
+            "-" | "pipe:1" => Ok(Box::new(io::stdout())),
+            "pipe:2" => Ok(Box::new(io::stderr())),
+            path => Ok(Box::new(File::create(path)?)),
+        }
+    }
+
+    /// 输出一次进度, 最终进度之后换行
+    pub(crate) fn report(&mut self, report: &ProgressReport) {
+        let line = format_status_line(report);
+        let len = line.chars().count();
+        let pad = self.last_line_len.saturating_sub(len);
+        eprint!("\r{line}{}", " ".repeat(pad));
+        self.last_line_len = len;
+        if report.finished {
+            eprintln!();
+        }
+
+        if let Some(sink) = &mut self.sink {
+            let block = format_progress(report);
+            if sink
+                .write_all(block.as_bytes())
+                .and_then(|_| sink.flush())
+                .is_err()
+            {
+                eprintln!("\n警告: 写入进度输出失败, 停止输出进度");
+                self.sink = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tao_core::{Rational, Timestamp};
+
+    fn report() -> ProgressReport {
+        ProgressReport {
+            out_time: Timestamp::new(10_500_000, Rational::new(1, 1_000_000)),
+            total_duration: None,
+            frames: 250,
+            packets: 300,
+            bytes: 1_048_576,
+            speed: 2.1,
+            elapsed: Duration::from_secs(5),
+            finished: false,
+        }
+    }
+
+    #[test]
+    fn test_format_progress_keys() {
+        let text = format_progress(&report());
+        let pairs: Vec<(&str, &str)> = text
+            .lines()
+            .map(|line| line.split_once('=').expect("每行应为 key=value"))
+            .collect();
+        let get = |key: &str| {
+            pairs
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| *v)
+                .unwrap_or_else(|| panic!("缺少键 {key}"))
+        };
+
+        assert_eq!(get("frame"), "250");
+        assert_eq!(get("fps"), "50.00");
+        assert_eq!(get("total_size"), "1048576");
+        assert_eq!(get("out_time_us"), "10500000");
+        assert_eq!(
+            get("out_time_ms"),
+            "10500000",
+            "与 ffmpeg 一致, 以微秒为单位"
+        );
+        assert_eq!(get("out_time"), "00:00:10.500000");
+        assert_eq!(get("speed"), "2.100x");
+        assert_eq!(get("progress"), "continue");
+        assert_eq!(
+            pairs.last().unwrap().0,
+            "progress",
+            "progress 应为每块最后一行"
+        );
+
+        let end = format_progress(&ProgressReport {
+            finished: true,
+            ..report()
+        });
+        assert!(end.ends_with("progress=end\n"), "结束时应输出 progress=end");
+    }
+
+    #[test]
+    fn test_format_status_line() {
+        let line = format_status_line(&ProgressReport {
+            out_time: Timestamp::new(83_450_000, Rational::new(1, 1_000_000)),
+            speed: 3.2,
+            bytes: 10 * 1024 * 1024,
+            ..report()
+        });
+        assert_eq!(line, "time=00:01:23.45 speed=3.2x size=10MiB");
+
+        let line = format_status_line(&ProgressReport {
+            total_duration: Some(42.0),
+            speed: 0.0,
+            ..report()
+        });
+        assert_eq!(
+            line, "time=00:00:10.50 speed=N/A size=1.0MiB (25%)",
+            "已知总时长时附带完成比例"
+        );
+    }
+
+    #[test]
+    fn test_format_size_units() {
+        assert_eq!(format_size(0), "0B");
+        assert_eq!(format_size(1023), "1023B");
+        assert_eq!(format_size(1536), "1.5KiB");
+        assert_eq!(format_size(200 * 1024), "200KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0GiB");
+    }
+
+    #[test]
+    fn test_format_time_us() {
+        assert_eq!(format_time_us(0), "00:00:00.000000");
+        assert_eq!(format_time_us(3_723_000_001), "01:02:03.000001");
+        assert_eq!(format_time_us(-1_500_000), "-00:00:01.500000");
+    }
+}
@@ -1,12 +1,11 @@
-use tao_codec::codec_parameters::{CodecParamsType, VideoCodecParams};
 use tao_codec::frame::VideoFrame;
-use tao_codec::{CodecParameters, CodecRegistry, Frame, Packet};
+use tao_codec::{CodecRegistry, Frame, FrameBuf, Packet};
 use tao_core::{MediaType, PixelFormat, TaoError};
 use tao_format::stream::{Stream, StreamParams};
-use tao_format::{FormatRegistry, IoContext};
+use tao_format::{FilteredPacketStream, FormatRegistry, IoContext};
 
 use crate::Cli;
-use crate::filter::pts_to_sec;
+use crate::args::pts_to_sec;
 
 pub(crate) fn transcode_to_raw_yuv(
     input_path: &str,
@@ -79,20 +78,7 @@ pub(crate) fn transcode_to_raw_yuv(
     );
 
     // 创建解码器
-    let mut decoder = codec_registry.create_decoder(video_stream.codec_id)?;
-    let dec_params = CodecParameters {
-        codec_id: video_stream.codec_id,
-        extra_data: video_stream.extra_data.clone(),
-        bit_rate: video_params.bit_rate,
-        params: CodecParamsType::Video(VideoCodecParams {
-            width: video_params.width,
-            height: video_params.height,
-            pixel_format: video_params.pixel_format,
-            frame_rate: video_params.frame_rate,
-            sample_aspect_ratio: video_params.sample_aspect_ratio,
-        }),
-    };
-    decoder.open(&dec_params)?;
+    let mut decoder = video_stream.open_decoder(&codec_registry)?;
 
     // 打开输出文件
     let mut output_file = File::create(output_path).map_err(TaoError::Io)?;
@@ -106,84 +92,67 @@ pub(crate) fn transcode_to_raw_yuv(
     let mut byte_count = 0u64;
     const MAX_FRAMES_FOR_VERIFICATION: u64 = 10;
 
-    loop {
+    for input_pkt in FilteredPacketStream::new(demuxer.as_mut(), &mut input_io, video_stream.index)
+    {
         // 检查是否已达到帧数限制
         if frame_count >= MAX_FRAMES_FOR_VERIFICATION {
             break;
         }
+        let input_pkt = input_pkt?;
 
-        match demuxer.read_packet(&mut input_io) {
-            Ok(input_pkt) => {
-                let stream_idx = input_pkt.stream_index;
-                if stream_idx != video_stream.index {
-                    continue;
-                }
+        // -ss: 跳过早于起始时间的数据包
+        if start_time_sec > 0.0 {
+            let pkt_time = pts_to_sec(input_pkt.pts, video_stream.time_base);
+            if pkt_time < start_time_sec {
+                continue;
+            }
+        }
 
-                // -ss: 跳过早于起始时间的数据包
-                if start_time_sec > 0.0 {
-                    let pkt_time = pts_to_sec(
-                        input_pkt.pts,
-                        video_stream.time_base.num,
-                        video_stream.time_base.den,
-                    );
-                    if pkt_time < start_time_sec {
-                        continue;
-                    }
-                }
+        // -t: 检查持续时间限制
+        if let Some(dur) = duration_limit_sec {
+            let pkt_time = pts_to_sec(input_pkt.pts, video_stream.time_base);
+            let effective_time = pkt_time - start_time_sec;
+            if effective_time > dur {
+                break;
+            }
+        }
 
-                // -t: 检查持续时间限制
-                if let Some(dur) = duration_limit_sec {
-                    let pkt_time = pts_to_sec(
-                        input_pkt.pts,
-                        video_stream.time_base.num,
-                        video_stream.time_base.den,
-                    );
-                    let effective_time = pkt_time - start_time_sec;
-                    if effective_time > dur {
-                        break;
-                    }
-                }
+        // 发送数据包到解码器
+        decoder.send_packet(&input_pkt)?;
+
+        // 接收解码后的帧
+        loop {
+            match decoder.receive_frame() {
+                Ok(frame) => {
+                    // 确保是视频帧并转换为 YUV420p
+                    if let Frame::Video(vf) = &frame {
+                        let yuv_frame =
+                            ensure_yuv420p(vf, video_params.width, video_params.height)?;
+
+                        // 写入 YUV 数据
+                        write_yuv420p_frame(&mut output_file, &yuv_frame)?;
+                        frame_count += 1;
+                        byte_count += yuv_frame_size(yuv_frame.width, yuv_frame.height);
+
+                        if frame_count % 10 == 0 {
+                            eprint!(
+                                "\r已处理 {} 帧, {:.2} MB",
+                                frame_count,
+                                byte_count as f64 / (1024.0 * 1024.0)
+                            );
+                        }
 
-                // 发送数据包到解码器
-                decoder.send_packet(&input_pkt)?;
-
-                // 接收解码后的帧
-                loop {
-                    match decoder.receive_frame() {
-                        Ok(frame) => {
-                            // 确保是视频帧并转换为 YUV420p
-                            if let Frame::Video(vf) = &frame {
-                                let yuv_frame =
-                                    ensure_yuv420p(vf, video_params.width, video_params.height)?;
-
-                                // 写入 YUV 数据
-                                write_yuv420p_frame(&mut output_file, &yuv_frame)?;
-                                frame_count += 1;
-                                byte_count += yuv_frame_size(yuv_frame.width, yuv_frame.height);
-
-                                if frame_count % 10 == 0 {
-                                    eprint!(
-                                        "\r已处理 {} 帧, {:.2} MB",
-                                        frame_count,
-                                        byte_count as f64 / (1024.0 * 1024.0)
-                                    );
-                                }
-
-                                // 为 PSNR 验证限制帧数
-                                if frame_count >= 10 {
-                                    eprintln!("\r已达到测试帧数限制 (10 帧)");
-                                    break;
-                                }
-                            }
+                        // 为 PSNR 验证限制帧数
+                        if frame_count >= 10 {
+                            eprintln!("\r已达到测试帧数限制 (10 帧)");
+                            break;
                         }
-                        Err(TaoError::NeedMoreData) => break,
-                        Err(TaoError::Eof) => break,
-                        Err(e) => return Err(e),
                     }
                 }
+                Err(TaoError::NeedMoreData) => break,
+                Err(TaoError::Eof) => break,
+                Err(e) => return Err(e),
             }
-            Err(TaoError::Eof) => break,
-            Err(e) => return Err(e),
         }
     }
 
@@ -221,8 +190,7 @@ fn ensure_yuv420p(frame: &VideoFrame, width: u32, height: u32) -> Result<VideoFr
         return Ok(frame.clone());
     }
 
-    // 需要使用 tao_scale 进行格式转换
-    // 需要使用 tao_scale 进行格式转换
+    // 需要使用 tao_scale 进行格式转换, 按帧携带的色彩参数选择转换矩阵
     let ctx = tao_scale::ScaleContext::new(
         width,
         height,
@@ -231,7 +199,8 @@ fn ensure_yuv420p(frame: &VideoFrame, width: u32, height: u32) -> Result<VideoFr
         height,
         PixelFormat::Yuv420p,
         tao_scale::ScaleAlgorithm::Bilinear,
-    );
+    )
+    .with_src_colorimetry(frame.color_space, frame.color_range);
 
     // 准备源数据
     let src_planes: Vec<&[u8]> = frame.data.iter().map(|d| d.as_slice()).collect();
@@ -261,10 +230,13 @@ fn ensure_yuv420p(frame: &VideoFrame, width: u32, height: u32) -> Result<VideoFr
     }
 
     let mut out_frame = VideoFrame::new(dst_w, dst_h, dst_fmt);
-    out_frame.data = dst_bufs;
+    out_frame.data = dst_bufs.into_iter().map(FrameBuf::from).collect();
     out_frame.linesize = dst_linesizes;
     out_frame.pts = frame.pts;
     out_frame.time_base = frame.time_base;
+    out_frame.duration = frame.duration;
+    out_frame.color_space = frame.color_space;
+    out_frame.color_range = frame.color_range;
 
     Ok(out_frame)
 }
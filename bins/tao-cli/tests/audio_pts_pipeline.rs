@@ -0,0 +1,119 @@
+//! 音频重采样转码的时间戳集成测试.
+//!
+//! 构造 44.1kHz 立体声 WAV 输入, 经 tao-cli 重采样到 48kHz 并编码为 AAC/MP4,
+//! 验证输出时间戳连续且输出时长与输入时长相差不超过一帧.
+
+use std::path::Path;
+use std::process::Command;
+
+use tao_core::timestamp::NOPTS_VALUE;
+use tao_core::{Rational, TaoError};
+use tao_format::io::IoContext;
+use tao_format::registry::FormatRegistry;
+use tao_format::stream::StreamParams;
+use tempfile::tempdir;
+
+const SAMPLE_RATE: u32 = 44100;
+const OUTPUT_SAMPLE_RATE: u32 = 48000;
+/// 输入时长 (秒)
+const INPUT_SECONDS: u32 = 2;
+/// AAC 每帧采样数
+const AAC_FRAME_SIZE: i64 = 1024;
+
+/// 写入 16 位立体声 PCM WAV (正弦波)
+fn write_wav_input(path: &Path) {
+    let nb_samples = SAMPLE_RATE * INPUT_SECONDS;
+    let data_size = nb_samples * 4;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&2u16.to_le_bytes()); // 立体声
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 4).to_le_bytes()); // byte_rate
+    wav.extend_from_slice(&4u16.to_le_bytes()); // block_align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits_per_sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for i in 0..nb_samples {
+        let v = ((i as f64 * 440.0 * std::f64::consts::TAU / f64::from(SAMPLE_RATE)).sin() * 8000.0)
+            as i16;
+        wav.extend_from_slice(&v.to_le_bytes());
+        wav.extend_from_slice(&v.to_le_bytes());
+    }
+    std::fs::write(path, wav).unwrap();
+}
+
+#[test]
+fn test_resample_to_48k_aac_keeps_duration() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav");
+    let output = dir.path().join("output.mp4");
+    write_wav_input(&input);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_tao-cli"))
+        .args([
+            "-i",
+            input.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .args(["-c", "aac", "--ar", "48000", "-y"])
+        .output()
+        .expect("启动 tao-cli 失败");
+    assert!(
+        status.status.success(),
+        "tao-cli 转码失败: {}",
+        String::from_utf8_lossy(&status.stderr)
+    );
+
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    let mut io = IoContext::open_read(output.to_str().unwrap()).unwrap();
+    let mut demuxer = registry
+        .open_input(&mut io, output.to_str())
+        .expect("打开输出 MP4 失败");
+    let stream = &demuxer.streams()[0];
+    match &stream.params {
+        StreamParams::Audio(a) => assert_eq!(a.sample_rate, OUTPUT_SAMPLE_RATE, "输出应为 48kHz"),
+        _ => panic!("输出应为音频流"),
+    }
+    assert_eq!(
+        stream.time_base,
+        Rational::new(1, OUTPUT_SAMPLE_RATE as i32),
+        "输出时间基应为 1/48000"
+    );
+
+    // MP4 解封装不携带数据包时长, 以相邻 PTS 差值检查连续性
+    let mut last_pts = None;
+    loop {
+        match demuxer.read_packet(&mut io) {
+            Ok(pkt) => {
+                assert_ne!(pkt.pts, NOPTS_VALUE, "输出数据包应带有时间戳");
+                if let Some(last) = last_pts {
+                    assert_eq!(
+                        pkt.pts - last,
+                        AAC_FRAME_SIZE,
+                        "输出时间戳应按每帧 1024 采样连续递增"
+                    );
+                } else {
+                    assert_eq!(pkt.pts, 0, "输出应从 0 开始");
+                }
+                last_pts = Some(pkt.pts);
+            }
+            Err(TaoError::Eof) => break,
+            Err(e) => panic!("读取输出数据包失败: {e}"),
+        }
+    }
+    let end_pts = last_pts.expect("输出应包含数据包") + AAC_FRAME_SIZE;
+
+    let out_seconds = end_pts as f64 / f64::from(OUTPUT_SAMPLE_RATE);
+    let tolerance = AAC_FRAME_SIZE as f64 / f64::from(OUTPUT_SAMPLE_RATE);
+    assert!(
+        (out_seconds - f64::from(INPUT_SECONDS)).abs() <= tolerance,
+        "输出时长 {out_seconds:.4}s 与输入时长 {INPUT_SECONDS}s 相差应不超过一帧"
+    );
+}
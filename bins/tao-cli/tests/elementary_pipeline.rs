@@ -0,0 +1,202 @@
+//! 裸流提取集成测试.
+//!
+//! 先将 WAV 编码为 AAC/MP4, 再通过 `--map` 选择音频流提取为 ADTS (.aac),
+//! 重新解封装 ADTS 文件, 验证数据包数量与内容与 MP4 中的 AAC 流一致.
+//! H.264 裸流经 MP4 往返后应与原始 Annex-B 字节流一致.
+
+use std::path::Path;
+use std::process::Command;
+
+use tao_codec::CodecId;
+use tao_core::TaoError;
+use tao_format::io::IoContext;
+use tao_format::registry::FormatRegistry;
+use tao_format::stream::StreamParams;
+use tao_format::{Demuxer, FormatId};
+use tempfile::tempdir;
+
+const SAMPLE_RATE: u32 = 44100;
+
+/// 写入 1 秒 16 位单声道 PCM WAV (正弦波)
+fn write_wav_input(path: &Path) {
+    let nb_samples = SAMPLE_RATE;
+    let data_size = nb_samples * 2;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte_rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block_align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits_per_sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for i in 0..nb_samples {
+        let v = ((i as f64 * 440.0 * std::f64::consts::TAU / f64::from(SAMPLE_RATE)).sin() * 8000.0)
+            as i16;
+        wav.extend_from_slice(&v.to_le_bytes());
+    }
+    std::fs::write(path, wav).unwrap();
+}
+
+fn run_cli(args: &[&str]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_tao-cli"))
+        .args(args)
+        .output()
+        .expect("启动 tao-cli 失败");
+    assert!(
+        output.status.success(),
+        "tao-cli 执行失败: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// 打开文件并读取全部数据包内容
+fn read_all_packets(path: &Path) -> (Box<dyn Demuxer>, Vec<Vec<u8>>) {
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    let mut io = IoContext::open_read(path.to_str().unwrap()).unwrap();
+    let mut demuxer = registry
+        .open_input(&mut io, path.to_str())
+        .expect("打开文件失败");
+    let mut packets = Vec::new();
+    loop {
+        match demuxer.read_packet(&mut io) {
+            Ok(pkt) => packets.push(pkt.data.to_vec()),
+            Err(TaoError::Eof) => break,
+            Err(e) => panic!("读取数据包失败: {e}"),
+        }
+    }
+    (demuxer, packets)
+}
+
+#[test]
+fn test_extract_aac_from_mp4_to_adts() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav");
+    let mp4 = dir.path().join("audio.mp4");
+    let aac = dir.path().join("audio.aac");
+    write_wav_input(&input);
+
+    // 1) WAV → AAC/MP4
+    run_cli(&[
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        mp4.to_str().unwrap(),
+        "-c",
+        "aac",
+        "-y",
+    ]);
+
+    // 2) MP4 → ADTS 裸流 (选择第一条音频流, 未指定编码器时直接复制)
+    run_cli(&[
+        "-i",
+        mp4.to_str().unwrap(),
+        "-o",
+        aac.to_str().unwrap(),
+        "--map",
+        "0:a:0",
+        "-y",
+    ]);
+
+    // 3) 重新解封装并与 MP4 中的 AAC 数据包比较
+    let (_, mp4_packets) = read_all_packets(&mp4);
+    let (adts_demuxer, adts_packets) = read_all_packets(&aac);
+    assert_eq!(adts_demuxer.format_id(), FormatId::AacAdts, "应识别为 ADTS");
+    let stream = &adts_demuxer.streams()[0];
+    assert_eq!(stream.codec_id, CodecId::Aac, "裸流应为 AAC");
+    match &stream.params {
+        StreamParams::Audio(a) => {
+            assert_eq!(a.sample_rate, SAMPLE_RATE, "ADTS 采样率应保持不变");
+            assert_eq!(a.channel_layout.channels, 1, "ADTS 声道数应保持不变");
+        }
+        _ => panic!("ADTS 流应为音频流"),
+    }
+    assert!(!mp4_packets.is_empty(), "MP4 应包含 AAC 数据包");
+    assert_eq!(
+        adts_packets, mp4_packets,
+        "ADTS 裸流的数据包应与 MP4 中的 AAC 数据包逐个一致"
+    );
+}
+
+#[test]
+fn test_extract_pcm_without_map() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav");
+    let output = dir.path().join("audio.pcm");
+    write_wav_input(&input);
+
+    // 未指定 --map 时自动选择音频流, PCM 编解码器直接复制
+    run_cli(&[
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        output.to_str().unwrap(),
+        "-y",
+    ]);
+
+    let wav = std::fs::read(&input).unwrap();
+    let pcm = std::fs::read(&output).unwrap();
+    assert_eq!(pcm, wav[44..], "PCM 裸流应与 WAV data 块内容一致");
+}
+
+#[test]
+fn test_h264_es_roundtrip_through_mp4() {
+    let dir = tempdir().unwrap();
+    let input = Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../tests/fixtures/golden/h264_cabac_iframes.h264"
+    ));
+    let mp4 = dir.path().join("video.mp4");
+    let output = dir.path().join("video.avc");
+
+    run_cli(&[
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        mp4.to_str().unwrap(),
+        "--vcodec",
+        "copy",
+        "-y",
+    ]);
+    // 裸流输出: H.264 直接复制为 Annex-B 字节流
+    run_cli(&[
+        "-i",
+        mp4.to_str().unwrap(),
+        "-o",
+        output.to_str().unwrap(),
+        "-y",
+    ]);
+
+    let (demuxer, es_packets) = read_all_packets(input);
+    assert_eq!(demuxer.format_id(), FormatId::H264Es);
+    let stream = &demuxer.streams()[0];
+    assert_eq!(stream.codec_id, CodecId::H264);
+    assert!(
+        matches!(&stream.params, StreamParams::Video(v) if v.width == 64 && v.height == 48),
+        "尺寸应来自 SPS"
+    );
+    assert_eq!(es_packets.len(), 2, "应按访问单元划分为 2 个数据包");
+
+    let (demuxer, mp4_packets) = read_all_packets(&mp4);
+    assert_eq!(demuxer.format_id(), FormatId::Mp4);
+    assert_eq!(mp4_packets.len(), es_packets.len(), "MP4 中的帧数应一致");
+
+    let (demuxer, avc_packets) = read_all_packets(&output);
+    assert_eq!(
+        demuxer.format_id(),
+        FormatId::H264Es,
+        ".avc 应识别为 H.264 裸流"
+    );
+    assert_eq!(avc_packets, es_packets, "往返后的访问单元应逐个一致");
+    assert_eq!(
+        std::fs::read(&output).unwrap(),
+        std::fs::read(input).unwrap(),
+        "往返后的字节流应与原始裸流一致"
+    );
+}
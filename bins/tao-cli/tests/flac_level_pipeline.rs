@@ -0,0 +1,134 @@
+//! `--compression_level` FLAC 压缩级别集成测试.
+//!
+//! 同一 WAV 输入分别以级别 0 (仅 Fixed 预测) 与级别 8 (LPC) 编码为 FLAC,
+//! 验证高级别输出更小, 且解码回 WAV 后与原始 PCM 逐字节一致.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const SAMPLE_RATE: u32 = 44100;
+
+/// 写入 2 秒 16 位单声道 PCM WAV (含 5 个泛音的单音)
+fn write_wav_input(path: &Path) {
+    let nb_samples = SAMPLE_RATE * 2;
+    let data_size = nb_samples * 2;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte_rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block_align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits_per_sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for i in 0..nb_samples {
+        let t = f64::from(i) / f64::from(SAMPLE_RATE);
+        let v: f64 = (1..=5)
+            .map(|h| {
+                let h = f64::from(h);
+                (std::f64::consts::TAU * 659.26 * h * t).sin() * 8000.0 / h
+            })
+            .sum();
+        wav.extend_from_slice(&(v as i16).to_le_bytes());
+    }
+    std::fs::write(path, wav).unwrap();
+}
+
+fn run_cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tao-cli"))
+        .args(args)
+        .output()
+        .expect("启动 tao-cli 失败")
+}
+
+fn encode_flac(input: &Path, output: &Path, level: &str) -> u64 {
+    let result = run_cli(&[
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        output.to_str().unwrap(),
+        "-c",
+        "flac",
+        "--compression_level",
+        level,
+        "-y",
+    ]);
+    assert!(
+        result.status.success(),
+        "tao-cli 编码 FLAC 失败: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    std::fs::metadata(output).unwrap().len()
+}
+
+#[test]
+fn test_flac_compression_level_smaller_and_lossless() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav");
+    let level0 = dir.path().join("level0.flac");
+    let level8 = dir.path().join("level8.flac");
+    let decoded = dir.path().join("decoded.wav");
+    write_wav_input(&input);
+
+    let size0 = encode_flac(&input, &level0, "0");
+    let size8 = encode_flac(&input, &level8, "8");
+    assert!(
+        size8 < size0,
+        "级别 8 应比级别 0 输出更小: {size8} vs {size0}"
+    );
+
+    let result = run_cli(&[
+        "-i",
+        level8.to_str().unwrap(),
+        "-o",
+        decoded.to_str().unwrap(),
+        "-c",
+        "pcm_s16le",
+        "-y",
+    ]);
+    assert!(
+        result.status.success(),
+        "tao-cli 解码 FLAC 失败: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    let original = std::fs::read(&input).unwrap();
+    let roundtrip = std::fs::read(&decoded).unwrap();
+    assert_eq!(
+        roundtrip[44..],
+        original[44..],
+        "FLAC 解码后的 PCM 应与原始输入一致"
+    );
+}
+
+#[test]
+fn test_flac_compression_level_out_of_range() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav");
+    let output = dir.path().join("output.flac");
+    write_wav_input(&input);
+
+    let result = run_cli(&[
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        output.to_str().unwrap(),
+        "-c",
+        "flac",
+        "--compression_level",
+        "9",
+        "-y",
+    ]);
+    assert!(!result.status.success(), "超出范围的压缩级别应执行失败");
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(
+        stderr.contains("FLAC 压缩级别应为 0-8"),
+        "错误信息应指出合法范围: {stderr}"
+    );
+}
@@ -0,0 +1,136 @@
+//! `-f` / `--input-format` 强制容器格式集成测试.
+//!
+//! 输出路径与输入路径均不带扩展名, 验证格式由命令行参数决定而非文件名.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tao_format::FormatId;
+use tao_format::io::IoContext;
+use tao_format::registry::FormatRegistry;
+use tempfile::tempdir;
+
+const SAMPLE_RATE: u32 = 8000;
+
+/// 写入 0.1 秒 16 位单声道 PCM WAV
+fn write_wav_input(path: &Path) {
+    let nb_samples = SAMPLE_RATE / 10;
+    let data_size = nb_samples * 2;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte_rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block_align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits_per_sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for i in 0..nb_samples {
+        wav.extend_from_slice(&((i as i16) * 16).to_le_bytes());
+    }
+    std::fs::write(path, wav).unwrap();
+}
+
+fn run_cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tao-cli"))
+        .args(args)
+        .output()
+        .expect("启动 tao-cli 失败")
+}
+
+/// 不提供文件名, 仅按内容探测输出格式
+fn probe_format(path: &Path) -> FormatId {
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    let mut io = IoContext::open_read(path.to_str().unwrap()).unwrap();
+    registry
+        .open_input(&mut io, None)
+        .expect("打开输出文件失败")
+        .format_id()
+}
+
+#[test]
+fn test_force_output_format_without_extension() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav");
+    let output = dir.path().join("output");
+    write_wav_input(&input);
+
+    let result = run_cli(&[
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        output.to_str().unwrap(),
+        "-f",
+        "wav",
+        "-y",
+    ]);
+    assert!(
+        result.status.success(),
+        "tao-cli 执行失败: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    assert_eq!(
+        probe_format(&output),
+        FormatId::Wav,
+        "-f wav 应选择 WAV 封装器"
+    );
+}
+
+#[test]
+fn test_force_input_format_skips_probe() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input");
+    let output = dir.path().join("output.wav");
+    write_wav_input(&input);
+
+    let result = run_cli(&[
+        "-i",
+        input.to_str().unwrap(),
+        "--input-format",
+        "wav",
+        "-o",
+        output.to_str().unwrap(),
+        "-y",
+    ]);
+    assert!(
+        result.status.success(),
+        "tao-cli 执行失败: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    assert_eq!(
+        std::fs::read(&output).unwrap().len(),
+        std::fs::read(&input).unwrap().len(),
+        "PCM 直接复制后 WAV 大小应一致"
+    );
+}
+
+#[test]
+fn test_unknown_output_format_errors() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav");
+    let output = dir.path().join("output");
+    write_wav_input(&input);
+
+    let result = run_cli(&[
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        output.to_str().unwrap(),
+        "-f",
+        "nosuchformat",
+        "-y",
+    ]);
+    assert!(!result.status.success(), "未知格式名应执行失败");
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(
+        stderr.contains("未知的输出格式 'nosuchformat'"),
+        "错误信息应指出未知格式: {stderr}"
+    );
+    assert!(!output.exists(), "失败时不应创建输出文件");
+}
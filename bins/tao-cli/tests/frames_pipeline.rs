@@ -0,0 +1,150 @@
+//! `--frames` / `--vframes` 帧数限制集成测试.
+//!
+//! 构造多帧的 MKV 输入 (视频 + 音频), 经 tao-cli 直接复制并限制帧数,
+//! 验证每条输出流写出的帧数符合限制.
+
+use std::path::Path;
+use std::process::Command;
+
+use tao_codec::{CodecId, Packet};
+use tao_core::{ChannelLayout, MediaType, PixelFormat, Rational, SampleFormat, TaoError};
+use tao_format::FormatId;
+use tao_format::io::IoContext;
+use tao_format::registry::FormatRegistry;
+use tao_format::stream::{AudioStreamParams, Stream, StreamParams, VideoStreamParams};
+use tempfile::tempdir;
+
+const FRAME_COUNT: i64 = 10;
+/// 帧间隔 (毫秒)
+const STEP_MS: i64 = 40;
+
+fn registry() -> FormatRegistry {
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    registry
+}
+
+fn make_streams() -> Vec<Stream> {
+    let video = Stream {
+        index: 0,
+        media_type: MediaType::Video,
+        codec_id: CodecId::H264,
+        time_base: Rational::new(1, 1000),
+        duration: -1,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data: vec![
+            0x01, 0x42, 0x00, 0x1E, 0xFF, 0xE1, 0x00, 0x04, 0x67, 0x42, 0x00, 0x1E, 0x01, 0x00,
+            0x02, 0x68, 0xCE,
+        ],
+        params: StreamParams::Video(VideoStreamParams {
+            width: 320,
+            height: 240,
+            pixel_format: PixelFormat::Yuv420p,
+            frame_rate: Rational::new(25, 1),
+            sample_aspect_ratio: Rational::new(1, 1),
+            bit_rate: 0,
+            color_space: Default::default(),
+            color_range: Default::default(),
+        }),
+        metadata: Vec::new(),
+    };
+    let audio = Stream {
+        index: 1,
+        media_type: MediaType::Audio,
+        codec_id: CodecId::Aac,
+        time_base: Rational::new(1, 1000),
+        duration: -1,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data: vec![0x12, 0x10],
+        params: StreamParams::Audio(AudioStreamParams {
+            sample_rate: 44100,
+            channel_layout: ChannelLayout::STEREO,
+            sample_format: SampleFormat::F32p,
+            bit_rate: 0,
+            frame_size: 1024,
+            block_align: 0,
+        }),
+        metadata: Vec::new(),
+    };
+    vec![video, audio]
+}
+
+/// 写入每条流各 FRAME_COUNT 帧的 MKV 输入文件
+fn write_mkv_input(path: &Path) {
+    let mut io = IoContext::open_write(path.to_str().unwrap()).unwrap();
+    let mut muxer = registry().create_muxer(FormatId::Matroska).unwrap();
+    muxer.write_header(&mut io, &make_streams()).unwrap();
+    for i in 0..FRAME_COUNT {
+        for (stream_index, byte) in [(0, 0x65), (1, 0x21)] {
+            let pkt = Packet::builder()
+                .data(vec![byte; 32])
+                .stream_index(stream_index)
+                .pts(i * STEP_MS)
+                .dts(i * STEP_MS)
+                .duration(STEP_MS)
+                .key_frame(stream_index == 1 || i == 0)
+                .time_base(Rational::new(1, 1000))
+                .build();
+            muxer.write_packet(&mut io, &pkt).unwrap();
+        }
+    }
+    muxer.write_trailer(&mut io).unwrap();
+}
+
+/// 以给定参数直接复制, 返回输出文件中 (视频, 音频) 的帧数
+fn copy_with_limit(limit_args: &[&str]) -> (usize, usize) {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.mkv");
+    let output = dir.path().join("output.mkv");
+    write_mkv_input(&input);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_tao-cli"))
+        .args([
+            "-i",
+            input.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .args(["-c", "copy", "--vcodec", "copy", "-y"])
+        .args(limit_args)
+        .output()
+        .expect("启动 tao-cli 失败");
+    assert!(
+        status.status.success(),
+        "tao-cli 执行失败: {}",
+        String::from_utf8_lossy(&status.stderr)
+    );
+
+    let mut io = IoContext::open_read(output.to_str().unwrap()).unwrap();
+    let mut demuxer = registry().open_input(&mut io, output.to_str()).unwrap();
+    let types: Vec<MediaType> = demuxer.streams().iter().map(|s| s.media_type).collect();
+    let (mut video, mut audio) = (0, 0);
+    loop {
+        match demuxer.read_packet(&mut io) {
+            Ok(pkt) => match types[pkt.stream_index] {
+                MediaType::Video => video += 1,
+                MediaType::Audio => audio += 1,
+                _ => {}
+            },
+            Err(TaoError::Eof) => break,
+            Err(e) => panic!("读取输出数据包失败: {e}"),
+        }
+    }
+    (video, audio)
+}
+
+#[test]
+fn test_frames_one_writes_single_frame_per_stream() {
+    let (video, audio) = copy_with_limit(&["--frames", "1"]);
+    assert_eq!(video, 1, "--frames 1 应只写出一帧视频");
+    assert_eq!(audio, 1, "--frames 1 应只写出一帧音频");
+}
+
+#[test]
+fn test_vframes_limits_only_video() {
+    let (video, audio) = copy_with_limit(&["--vframes", "3"]);
+    assert_eq!(video, 3, "--vframes 3 应只写出三帧视频");
+    assert_eq!(audio, FRAME_COUNT as usize, "音频不受 --vframes 限制");
+}
@@ -0,0 +1,147 @@
+//! 编码器全局头集成测试.
+//!
+//! 构造 44.1kHz 立体声 WAV 输入, 经 tao-cli 编码为 AAC/MP4, 验证 esds 中的
+//! AudioSpecificConfig 与编码参数一致, 且数据包为不带 ADTS 头的裸 AAC, 可直接解码.
+
+use std::path::Path;
+use std::process::Command;
+
+use tao_codec::codec_parameters::{AudioCodecParams, CodecParamsType};
+use tao_codec::{CodecParameters, CodecRegistry, Frame};
+use tao_core::{SampleFormat, TaoError};
+use tao_format::io::IoContext;
+use tao_format::registry::FormatRegistry;
+use tao_format::stream::StreamParams;
+use tempfile::tempdir;
+
+const SAMPLE_RATE: u32 = 44100;
+const CHANNELS: u16 = 2;
+
+/// 写入 1 秒 16 位立体声 PCM WAV (正弦波)
+fn write_wav_input(path: &Path) {
+    let nb_samples = SAMPLE_RATE;
+    let block_align = u32::from(CHANNELS) * 2;
+    let data_size = nb_samples * block_align;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&CHANNELS.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * block_align).to_le_bytes()); // byte_rate
+    wav.extend_from_slice(&(block_align as u16).to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits_per_sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for i in 0..nb_samples {
+        let v = ((i as f64 * 440.0 * std::f64::consts::TAU / f64::from(SAMPLE_RATE)).sin() * 8000.0)
+            as i16;
+        for _ in 0..CHANNELS {
+            wav.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    std::fs::write(path, wav).unwrap();
+}
+
+/// 在文件中查找 esds box, 返回其中 DecoderSpecificInfo (tag=0x05) 的内容
+fn find_esds_decoder_config(data: &[u8]) -> Vec<u8> {
+    let pos = data
+        .windows(4)
+        .position(|w| w == b"esds")
+        .expect("输出应包含 esds box");
+    let size = u32::from_be_bytes(data[pos - 4..pos].try_into().unwrap()) as usize;
+    let esds = &data[pos + 8..pos - 4 + size];
+    let tag = esds
+        .iter()
+        .position(|&b| b == 0x05)
+        .expect("esds 应包含 DecoderSpecificInfo");
+    let len = usize::from(esds[tag + 1]);
+    esds[tag + 2..tag + 2 + len].to_vec()
+}
+
+#[test]
+fn test_aac_mp4_esds_matches_encoder_config() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav");
+    let output = dir.path().join("output.mp4");
+    write_wav_input(&input);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_tao-cli"))
+        .args([
+            "-i",
+            input.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .args(["-c", "aac", "-y"])
+        .output()
+        .expect("启动 tao-cli 失败");
+    assert!(
+        result.status.success(),
+        "tao-cli 转码失败: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let asc = find_esds_decoder_config(&std::fs::read(&output).unwrap());
+    assert_eq!(asc.len(), 2, "AAC-LC 的 AudioSpecificConfig 为 2 字节");
+    let config = u16::from_be_bytes([asc[0], asc[1]]);
+    assert_eq!(config >> 11, 2, "audioObjectType 应为 AAC-LC");
+    assert_eq!((config >> 7) & 0x0F, 4, "采样率索引应对应 44100 Hz");
+    assert_eq!((config >> 3) & 0x0F, CHANNELS, "声道配置应为立体声");
+
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    let mut io = IoContext::open_read(output.to_str().unwrap()).unwrap();
+    let mut demuxer = registry
+        .open_input(&mut io, output.to_str())
+        .expect("打开输出 MP4 失败");
+    let stream = demuxer.streams()[0].clone();
+    assert_eq!(stream.extra_data, asc, "解封装应取回 esds 中的配置");
+    let StreamParams::Audio(audio) = &stream.params else {
+        panic!("输出应为音频流");
+    };
+
+    let mut codecs = CodecRegistry::new();
+    tao_codec::register_all(&mut codecs);
+    let mut decoder = codecs.create_decoder(stream.codec_id).unwrap();
+    decoder
+        .open(&CodecParameters {
+            codec_id: stream.codec_id,
+            extra_data: stream.extra_data.clone(),
+            bit_rate: 0,
+            params: CodecParamsType::Audio(AudioCodecParams {
+                sample_rate: audio.sample_rate,
+                channel_layout: audio.channel_layout,
+                sample_format: SampleFormat::F32,
+                frame_size: 0,
+                block_align: 0,
+            }),
+        })
+        .unwrap();
+
+    let mut decoded = 0;
+    loop {
+        match demuxer.read_packet(&mut io) {
+            Ok(pkt) => {
+                assert!(
+                    !(pkt.data[0] == 0xFF && pkt.data[1] & 0xF0 == 0xF0),
+                    "MP4 中的 AAC 数据包不应带 ADTS 头"
+                );
+                decoder.send_packet(&pkt).unwrap();
+                while let Ok(frame) = decoder.receive_frame() {
+                    let Frame::Audio(af) = frame else {
+                        panic!("应解码出音频帧");
+                    };
+                    assert_eq!(af.channel_layout.channels, u32::from(CHANNELS));
+                    decoded += 1;
+                }
+            }
+            Err(TaoError::Eof) => break,
+            Err(e) => panic!("读取输出数据包失败: {e}"),
+        }
+    }
+    assert!(decoded > 0, "裸 AAC 数据包应可按 esds 配置解码");
+}
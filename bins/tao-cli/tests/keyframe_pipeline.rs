@@ -0,0 +1,111 @@
+//! `-g` / `--force_key_frames` 关键帧选项集成测试.
+//!
+//! 用 MediaWriter 生成 rawvideo AVI 输入, 经 tao-cli 以 rawvideo 重新编码,
+//! 验证帧内编码器在指定关键帧间隔与强制关键帧时仍将每个数据包标记为关键帧.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tao::{MediaWriter, VideoEncodeParams};
+use tao_codec::{CodecId, Frame, VideoFrame};
+use tao_core::{MediaType, PixelFormat, Rational, TaoError};
+use tao_format::io::IoContext;
+use tao_format::registry::FormatRegistry;
+use tempfile::tempdir;
+
+const FRAME_COUNT: i64 = 12;
+
+/// 写入 FRAME_COUNT 帧 25fps rawvideo 的 AVI 输入文件
+fn write_rawvideo_input(path: &Path) {
+    let mut writer = MediaWriter::create(path.to_str().unwrap()).unwrap();
+    let video = writer
+        .add_video_stream(VideoEncodeParams::new(
+            CodecId::RawVideo,
+            16,
+            16,
+            PixelFormat::Bgr24,
+            Rational::new(25, 1),
+        ))
+        .unwrap();
+    for i in 0..FRAME_COUNT {
+        let mut vf = VideoFrame::new(16, 16, PixelFormat::Bgr24);
+        vf.data = vec![vec![i as u8; 16 * 16 * 3].into()];
+        vf.linesize = vec![16 * 3];
+        vf.pts = i;
+        vf.time_base = Rational::new(1, 25);
+        writer.write_frame(video, Frame::Video(vf)).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+/// 以 rawvideo 重新编码, 返回 tao-cli 的执行结果与输出路径
+fn transcode(dir: &Path, extra_args: &[&str]) -> (Output, String) {
+    let input = dir.join("input.avi");
+    let output = dir.join("output.avi");
+    write_rawvideo_input(&input);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_tao-cli"))
+        .args([
+            "-i",
+            input.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .args(["--vcodec", "rawvideo", "-y"])
+        .args(extra_args)
+        .output()
+        .expect("启动 tao-cli 失败");
+    (result, output.to_string_lossy().into_owned())
+}
+
+/// 读取输出文件中每个视频数据包的关键帧标志
+fn video_keyframe_flags(path: &str) -> Vec<bool> {
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    let mut io = IoContext::open_read(path).unwrap();
+    let mut demuxer = registry.open_input(&mut io, Some(path)).unwrap();
+    let types: Vec<MediaType> = demuxer.streams().iter().map(|s| s.media_type).collect();
+    let mut flags = Vec::new();
+    loop {
+        match demuxer.read_packet(&mut io) {
+            Ok(pkt) if types[pkt.stream_index] == MediaType::Video => flags.push(pkt.is_keyframe()),
+            Ok(_) => {}
+            Err(TaoError::Eof) => break,
+            Err(e) => panic!("读取输出数据包失败: {e}"),
+        }
+    }
+    flags
+}
+
+#[test]
+fn test_intra_only_encoder_flags_all_packets_keyframe() {
+    let dir = tempdir().unwrap();
+    let (result, output) = transcode(
+        dir.path(),
+        &["-g", "5", "--force_key_frames", "0.2,00:00:00.32"],
+    );
+    assert!(
+        result.status.success(),
+        "tao-cli 执行失败: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let flags = video_keyframe_flags(&output);
+    assert_eq!(flags.len(), FRAME_COUNT as usize, "应写出全部视频帧");
+    assert!(
+        flags.iter().all(|&key| key),
+        "rawvideo 每个数据包都应为关键帧, 实际 {flags:?}"
+    );
+}
+
+#[test]
+fn test_invalid_force_key_frames_rejected() {
+    let dir = tempdir().unwrap();
+    let (result, _) = transcode(dir.path(), &["--force_key_frames", "1,abc"]);
+    assert!(!result.status.success(), "无效时间点应导致执行失败");
+    assert!(
+        String::from_utf8_lossy(&result.stderr).contains("强制关键帧"),
+        "错误信息应指明强制关键帧参数: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+}
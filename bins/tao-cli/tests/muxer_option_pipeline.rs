@@ -0,0 +1,197 @@
+//! `--muxer-opt` 封装器私有选项集成测试.
+//!
+//! 构造每秒一个关键帧的 MKV 输入 (视频 + 音频), 经 tao-cli 直接复制为分片 MP4,
+//! 验证分片切分与解封装结果.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tao_codec::{CodecId, Packet};
+use tao_core::{ChannelLayout, MediaType, PixelFormat, Rational, SampleFormat, TaoError};
+use tao_format::FormatId;
+use tao_format::io::IoContext;
+use tao_format::registry::FormatRegistry;
+use tao_format::stream::{AudioStreamParams, Stream, StreamParams, VideoStreamParams};
+use tempfile::tempdir;
+
+/// 4 秒, 25fps
+const FRAME_COUNT: i64 = 100;
+/// 帧间隔 (毫秒)
+const STEP_MS: i64 = 40;
+
+fn registry() -> FormatRegistry {
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    registry
+}
+
+fn make_streams() -> Vec<Stream> {
+    let video = Stream {
+        index: 0,
+        media_type: MediaType::Video,
+        codec_id: CodecId::H264,
+        time_base: Rational::new(1, 1000),
+        duration: -1,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data: vec![
+            0x01, 0x42, 0x00, 0x1E, 0xFF, 0xE1, 0x00, 0x04, 0x67, 0x42, 0x00, 0x1E, 0x01, 0x00,
+            0x02, 0x68, 0xCE,
+        ],
+        params: StreamParams::Video(VideoStreamParams {
+            width: 320,
+            height: 240,
+            pixel_format: PixelFormat::Yuv420p,
+            frame_rate: Rational::new(25, 1),
+            sample_aspect_ratio: Rational::new(1, 1),
+            bit_rate: 0,
+            color_space: Default::default(),
+            color_range: Default::default(),
+        }),
+        metadata: Vec::new(),
+    };
+    let audio = Stream {
+        index: 1,
+        media_type: MediaType::Audio,
+        codec_id: CodecId::Aac,
+        time_base: Rational::new(1, 1000),
+        duration: -1,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data: vec![0x12, 0x10],
+        params: StreamParams::Audio(AudioStreamParams {
+            sample_rate: 44100,
+            channel_layout: ChannelLayout::STEREO,
+            sample_format: SampleFormat::F32p,
+            bit_rate: 0,
+            frame_size: 1024,
+            block_align: 0,
+        }),
+        metadata: Vec::new(),
+    };
+    vec![video, audio]
+}
+
+/// 写入每秒一个视频关键帧的 MKV 输入文件
+fn write_mkv_input(path: &Path) {
+    let mut io = IoContext::open_write(path.to_str().unwrap()).unwrap();
+    let mut muxer = registry().create_muxer(FormatId::Matroska).unwrap();
+    muxer.write_header(&mut io, &make_streams()).unwrap();
+    for i in 0..FRAME_COUNT {
+        for (stream_index, byte) in [(0, 0x65), (1, 0x21)] {
+            let pkt = Packet::builder()
+                .data(vec![byte; 32])
+                .stream_index(stream_index)
+                .pts(i * STEP_MS)
+                .dts(i * STEP_MS)
+                .duration(STEP_MS)
+                .key_frame(stream_index == 1 || i % 25 == 0)
+                .time_base(Rational::new(1, 1000))
+                .build();
+            muxer.write_packet(&mut io, &pkt).unwrap();
+        }
+    }
+    muxer.write_trailer(&mut io).unwrap();
+}
+
+fn copy_to_mp4(input: &Path, output: &Path, extra_args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tao-cli"))
+        .args([
+            "-i",
+            input.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .args(["-c", "copy", "--vcodec", "copy", "-y"])
+        .args(extra_args)
+        .output()
+        .expect("启动 tao-cli 失败")
+}
+
+/// 统计顶层 box 中 moof 的数量
+fn count_moof(data: &[u8]) -> usize {
+    let mut count = 0;
+    let mut pos = 0;
+    while pos + 8 <= data.len() {
+        let size = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+        if &data[pos + 4..pos + 8] == b"moof" {
+            count += 1;
+        }
+        assert!(size >= 8, "box 大小非法");
+        pos += size;
+    }
+    count
+}
+
+#[test]
+fn test_muxer_opt_fragmented_mp4() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.mkv");
+    let output = dir.path().join("output.mp4");
+    write_mkv_input(&input);
+
+    let result = copy_to_mp4(
+        &input,
+        &output,
+        &["--muxer-opt", "fragmented=1:frag_duration=2000"],
+    );
+    assert!(
+        result.status.success(),
+        "tao-cli 执行失败: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let data = std::fs::read(&output).unwrap();
+    assert_eq!(count_moof(&data), 2, "4 秒输入按 2000ms 应切分为 2 个分片");
+
+    let mut io = IoContext::open_read(output.to_str().unwrap()).unwrap();
+    let mut demuxer = registry().open_input(&mut io, output.to_str()).unwrap();
+    let types: Vec<MediaType> = demuxer.streams().iter().map(|s| s.media_type).collect();
+    let mut video_dts = Vec::new();
+    let mut audio = 0;
+    loop {
+        match demuxer.read_packet(&mut io) {
+            Ok(pkt) => match types[pkt.stream_index] {
+                MediaType::Video => video_dts.push(pkt.dts),
+                MediaType::Audio => audio += 1,
+                _ => {}
+            },
+            Err(TaoError::Eof) => break,
+            Err(e) => panic!("读取输出数据包失败: {e}"),
+        }
+    }
+    assert_eq!(
+        video_dts.len(),
+        FRAME_COUNT as usize,
+        "视频包数量应保持不变"
+    );
+    assert_eq!(audio, FRAME_COUNT as usize, "音频包数量应保持不变");
+    assert!(
+        video_dts.windows(2).all(|w| w[0] < w[1]),
+        "跨分片的视频 DTS 应单调递增"
+    );
+}
+
+#[test]
+fn test_muxer_opt_invalid_errors() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.mkv");
+    let output = dir.path().join("output.mp4");
+    write_mkv_input(&input);
+
+    let result = copy_to_mp4(&input, &output, &["--muxer-opt", "fragmented"]);
+    assert!(!result.status.success(), "缺少 '=' 的选项应执行失败");
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(
+        stderr.contains("无效的封装器选项 'fragmented'"),
+        "错误信息应指出无效选项: {stderr}"
+    );
+
+    let result = copy_to_mp4(&input, &output, &["--muxer-opt", "no_such_option=1"]);
+    assert!(!result.status.success(), "未知选项应执行失败");
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(
+        stderr.contains("no_such_option"),
+        "错误信息应包含选项名: {stderr}"
+    );
+}
@@ -0,0 +1,94 @@
+//! `--sample_fmt` / `--pix_fmt` 格式选项集成测试.
+//!
+//! WAV (s16) 以 `--sample_fmt s32` 编码为 FLAC, 验证 STREAMINFO 位深高于源格式;
+//! 无法识别的格式名应报错退出.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const SAMPLE_RATE: u32 = 8000;
+
+/// 写入 0.5 秒 16 位单声道 PCM WAV (锯齿波)
+fn write_wav_input(path: &Path) {
+    let nb_samples = SAMPLE_RATE / 2;
+    let data_size = nb_samples * 2;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte_rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block_align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits_per_sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for i in 0..nb_samples {
+        let v = ((i % 100) as i16 - 50) * 300;
+        wav.extend_from_slice(&v.to_le_bytes());
+    }
+    std::fs::write(path, wav).unwrap();
+}
+
+fn run_cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tao-cli"))
+        .args(args)
+        .output()
+        .expect("启动 tao-cli 失败")
+}
+
+/// 读取 FLAC STREAMINFO 中的位深
+fn flac_bits_per_sample(data: &[u8]) -> u32 {
+    assert_eq!(&data[..4], b"fLaC", "输出应为 FLAC");
+    // "fLaC" + 元数据块头 (4) + 最小/最大块长 (4) + 最小/最大帧长 (6) + 采样率 20 位 + 声道 3 位
+    let info = &data[8..];
+    (((u32::from(info[12]) & 0x01) << 4) | (u32::from(info[13]) >> 4)) + 1
+}
+
+#[test]
+fn test_sample_fmt_option_sets_encoder_format() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav");
+    let flac = dir.path().join("s32.flac");
+    write_wav_input(&input);
+
+    let result = run_cli(&[
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        flac.to_str().unwrap(),
+        "-c",
+        "flac",
+        "--sample_fmt",
+        "s32",
+        "-y",
+    ]);
+    assert!(
+        result.status.success(),
+        "tao-cli 编码 FLAC 失败: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    assert_eq!(
+        flac_bits_per_sample(&std::fs::read(&flac).unwrap()),
+        24,
+        "--sample_fmt s32 应使 FLAC 编码器以 24 位 (而非源 16 位) 编码"
+    );
+}
+
+#[test]
+fn test_format_options_reject_unknown_names() {
+    for (option, value) in [("--sample_fmt", "s20"), ("--pix_fmt", "yuv411p")] {
+        let result = run_cli(&["-i", "in.wav", "-o", "out.wav", option, value]);
+        assert!(!result.status.success(), "{option} {value} 应报错");
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        assert!(
+            stderr.contains(value),
+            "错误信息应包含无法识别的格式名: {stderr}"
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! 直接复制时的时间基换算集成测试.
+//!
+//! 构造毫秒时间基的 MKV 输入, 经 tao-cli 直接复制为 MP4, 验证输出时间戳已换算到 MP4 时间基.
+
+use std::path::Path;
+use std::process::Command;
+
+use tao_codec::{CodecId, Packet};
+use tao_core::{ChannelLayout, MediaType, PixelFormat, Rational, SampleFormat, TaoError};
+use tao_format::FormatId;
+use tao_format::io::IoContext;
+use tao_format::registry::FormatRegistry;
+use tao_format::stream::{AudioStreamParams, Stream, StreamParams, VideoStreamParams};
+use tempfile::tempdir;
+
+const FRAME_COUNT: i64 = 10;
+/// 视频帧间隔 (毫秒)
+const VIDEO_STEP_MS: i64 = 40;
+/// 音频帧间隔 (毫秒, 1024 采样 @ 44.1kHz 约 23ms)
+const AUDIO_STEP_MS: i64 = 23;
+
+fn registry() -> FormatRegistry {
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    registry
+}
+
+fn make_streams() -> Vec<Stream> {
+    let video = Stream {
+        index: 0,
+        media_type: MediaType::Video,
+        codec_id: CodecId::H264,
+        time_base: Rational::new(1, 1000),
+        duration: -1,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data: vec![
+            0x01, 0x42, 0x00, 0x1E, 0xFF, 0xE1, 0x00, 0x04, 0x67, 0x42, 0x00, 0x1E, 0x01, 0x00,
+            0x02, 0x68, 0xCE,
+        ],
+        params: StreamParams::Video(VideoStreamParams {
+            width: 320,
+            height: 240,
+            pixel_format: PixelFormat::Yuv420p,
+            frame_rate: Rational::new(25, 1),
+            sample_aspect_ratio: Rational::new(1, 1),
+            bit_rate: 0,
+            color_space: Default::default(),
+            color_range: Default::default(),
+        }),
+        metadata: Vec::new(),
+    };
+    let audio = Stream {
+        index: 1,
+        media_type: MediaType::Audio,
+        codec_id: CodecId::Aac,
+        time_base: Rational::new(1, 1000),
+        duration: -1,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data: vec![0x12, 0x10],
+        params: StreamParams::Audio(AudioStreamParams {
+            sample_rate: 44100,
+            channel_layout: ChannelLayout::STEREO,
+            sample_format: SampleFormat::F32p,
+            bit_rate: 0,
+            frame_size: 1024,
+            block_align: 0,
+        }),
+        metadata: Vec::new(),
+    };
+    vec![video, audio]
+}
+
+/// 写入毫秒时间基的 MKV 输入文件
+fn write_mkv_input(path: &Path) {
+    let mut io = IoContext::open_write(path.to_str().unwrap()).unwrap();
+    let mut muxer = registry().create_muxer(FormatId::Matroska).unwrap();
+    muxer.write_header(&mut io, &make_streams()).unwrap();
+    for i in 0..FRAME_COUNT {
+        let video = Packet::builder()
+            .data(vec![0x65; 32])
+            .stream_index(0)
+            .pts(i * VIDEO_STEP_MS)
+            .dts(i * VIDEO_STEP_MS)
+            .duration(VIDEO_STEP_MS)
+            .key_frame(i == 0)
+            .time_base(Rational::new(1, 1000))
+            .build();
+        muxer.write_packet(&mut io, &video).unwrap();
+        let audio = Packet::builder()
+            .data(vec![0x21; 16])
+            .stream_index(1)
+            .pts(i * AUDIO_STEP_MS)
+            .dts(i * AUDIO_STEP_MS)
+            .duration(AUDIO_STEP_MS)
+            .key_frame(true)
+            .time_base(Rational::new(1, 1000))
+            .build();
+        muxer.write_packet(&mut io, &audio).unwrap();
+    }
+    muxer.write_trailer(&mut io).unwrap();
+}
+
+/// 读出输出文件的流信息与各流时间戳
+fn read_output(path: &Path) -> (Vec<Stream>, Vec<Vec<i64>>) {
+    let mut io = IoContext::open_read(path.to_str().unwrap()).unwrap();
+    let mut demuxer = registry().open_input(&mut io, path.to_str()).unwrap();
+    let streams = demuxer.streams().to_vec();
+    let mut pts = vec![Vec::new(); streams.len()];
+    loop {
+        match demuxer.read_packet(&mut io) {
+            Ok(pkt) => pts[pkt.stream_index].push(pkt.pts),
+            Err(TaoError::Eof) => break,
+            Err(e) => panic!("读取输出数据包失败: {e}"),
+        }
+    }
+    (streams, pts)
+}
+
+#[test]
+fn test_copy_rescales_mkv_ms_to_mp4_timebase() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.mkv");
+    let output = dir.path().join("output.mp4");
+    write_mkv_input(&input);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_tao-cli"))
+        .args([
+            "-i",
+            input.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .args(["-c", "copy", "--vcodec", "copy", "-y"])
+        .output()
+        .expect("启动 tao-cli 失败");
+    assert!(
+        status.status.success(),
+        "tao-cli 直接复制失败: {}",
+        String::from_utf8_lossy(&status.stderr)
+    );
+
+    let (streams, pts) = read_output(&output);
+    assert_eq!(streams.len(), 2, "输出应包含视频与音频两条流");
+
+    let video = streams
+        .iter()
+        .position(|s| s.media_type == MediaType::Video)
+        .expect("输出应包含视频流");
+    assert_eq!(
+        streams[video].time_base,
+        Rational::new(1, 90000),
+        "MP4 视频时间基应为 1/90000"
+    );
+    let expected_video: Vec<i64> = (0..FRAME_COUNT).map(|i| i * VIDEO_STEP_MS * 90).collect();
+    assert_eq!(pts[video], expected_video, "视频 PTS 应从毫秒换算到 90kHz");
+
+    let audio = streams
+        .iter()
+        .position(|s| s.media_type == MediaType::Audio)
+        .expect("输出应包含音频流");
+    assert_eq!(
+        streams[audio].time_base,
+        Rational::new(1, 44100),
+        "MP4 音频时间基应为采样率"
+    );
+    let expected_audio: Vec<i64> = (0..FRAME_COUNT)
+        .map(|i| (i * AUDIO_STEP_MS * 44100 + 500) / 1000)
+        .collect();
+    assert_eq!(
+        pts[audio], expected_audio,
+        "音频 PTS 应从毫秒换算到采样率时间基"
+    );
+}
@@ -0,0 +1,103 @@
+//! `--ss` / `-t` 精确裁剪集成测试.
+//!
+//! 构造 44.1kHz 单声道 WAV 输入 (采样值为采样序号), 经 tao-cli 截取固定时长,
+//! 验证输出采样数与首尾采样精确到单个采样.
+
+use std::path::Path;
+use std::process::Command;
+
+use tao_core::TaoError;
+use tao_format::io::IoContext;
+use tao_format::registry::FormatRegistry;
+use tempfile::tempdir;
+
+const SAMPLE_RATE: u32 = 44100;
+/// 输入时长 (秒)
+const INPUT_SECONDS: u32 = 3;
+
+/// 采样序号对应的采样值
+fn sample_value(index: u32) -> i16 {
+    (index % 30000) as i16
+}
+
+/// 写入 16 位单声道 PCM WAV
+fn write_wav_input(path: &Path) {
+    let nb_samples = SAMPLE_RATE * INPUT_SECONDS;
+    let data_size = nb_samples * 2;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte_rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block_align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits_per_sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for i in 0..nb_samples {
+        wav.extend_from_slice(&sample_value(i).to_le_bytes());
+    }
+    std::fs::write(path, wav).unwrap();
+}
+
+/// 读出输出文件的全部 PCM 采样
+fn read_output_samples(path: &Path) -> Vec<i16> {
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    let mut io = IoContext::open_read(path.to_str().unwrap()).unwrap();
+    let mut demuxer = registry.open_input(&mut io, path.to_str()).unwrap();
+    let mut bytes = Vec::new();
+    loop {
+        match demuxer.read_packet(&mut io) {
+            Ok(pkt) => bytes.extend_from_slice(&pkt.data),
+            Err(TaoError::Eof) => break,
+            Err(e) => panic!("读取输出数据包失败: {e}"),
+        }
+    }
+    bytes
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect()
+}
+
+#[test]
+fn test_trim_wav_one_second_is_sample_exact() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav");
+    let output = dir.path().join("output.wav");
+    write_wav_input(&input);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_tao-cli"))
+        .args([
+            "-i",
+            input.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .args(["--ss", "0.5", "-t", "1.0", "-y"])
+        .output()
+        .expect("启动 tao-cli 失败");
+    assert!(
+        status.status.success(),
+        "tao-cli 裁剪失败: {}",
+        String::from_utf8_lossy(&status.stderr)
+    );
+
+    let samples = read_output_samples(&output);
+    assert_eq!(
+        samples.len(),
+        SAMPLE_RATE as usize,
+        "截取 1.000 秒应恰好输出 44100 个采样"
+    );
+    let first = SAMPLE_RATE / 2;
+    assert_eq!(samples[0], sample_value(first), "首个采样应位于 0.5 秒处");
+    assert_eq!(
+        samples[samples.len() - 1],
+        sample_value(first + SAMPLE_RATE - 1),
+        "末尾采样应位于 1.5 秒之前的最后一个采样"
+    );
+}
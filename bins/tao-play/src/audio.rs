@@ -2,9 +2,15 @@
 //!
 //! 使用 SDL2 音频子系统进行跨平台音频输出.
 //! 缓冲区大小和时钟补偿逻辑对齐 ffplay.
+//!
+//! 两种输出模式:
+//! - [`AudioOutput`]: 解码后的 PCM, 按设备参数重采样并应用音量 (设备可选, S16/F32 协商)
+//! - [`PassthroughAudioOutput`]: AC3/DTS 压缩帧经 IEC 61937 封装后原样输出 (S/PDIF 直通)
 
 use log::{debug, info, warn};
-use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+use sdl2::audio::{
+    AudioCallback, AudioDevice, AudioFormat, AudioFormatNum, AudioSpec, AudioSpecDesired,
+};
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
@@ -12,6 +18,7 @@ use tao_core::{ChannelLayout, SampleFormat};
 use tao_resample::ResampleContext;
 
 use crate::clock::MediaClock;
+use crate::spdif::{SPDIF_CHANNELS, SpdifCodec};
 
 // ── ffplay 音频常量 ──────────────────────────────────────────────────────
 
@@ -28,8 +35,168 @@ pub struct AudioChunk {
     pub pts_us: i64,
 }
 
+/// S/PDIF 直通的 IEC 61937 数据突发
+pub struct SpdifBurst {
+    /// 交错立体声 16 位采样 (一个完整突发)
+    pub samples: Vec<i16>,
+    /// 这个突发对应的 PTS (微秒)
+    pub pts_us: i64,
+}
+
+/// 设备输出采样格式
+///
+/// 内部混音与重采样始终使用 F32, 仅在回调写出时转换为设备格式.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputSampleFormat {
+    /// 32 位浮点
+    F32,
+    /// 16 位有符号整数
+    S16,
+}
+
+/// 音频设备的原生参数 (来自 SDL_GetAudioDeviceSpec)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceSpec {
+    /// 原生采样率, 0 表示未知
+    pub sample_rate: u32,
+    /// 原生声道数, 0 表示未知
+    pub channels: u32,
+    /// 原生采样格式
+    pub format: OutputSampleFormat,
+}
+
+impl DeviceSpec {
+    /// 从 SDL 音频参数构造
+    fn from_sdl(spec: &AudioSpec) -> Self {
+        Self {
+            sample_rate: spec.freq.max(0) as u32,
+            channels: u32::from(spec.channels),
+            format: output_format_from_sdl(spec.format),
+        }
+    }
+}
+
+/// 协商得到的设备打开参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputPlan {
+    /// 请求的采样率
+    pub sample_rate: u32,
+    /// 请求的声道数
+    pub channels: u32,
+    /// 请求的采样格式
+    pub format: OutputSampleFormat,
+}
+
+/// 按源参数与设备原生参数协商输出参数
+///
+/// 优先请求源的采样率与声道数; 已知设备原生采样率与源不同时改为请求原生采样率,
+/// 由回调中的 [`ResampleContext`] 完成转换, 不依赖 SDL 内部的重采样.
+/// 声道数不超过设备原生声道数. 设备原生为浮点或 32 位整数时输出 F32, 否则输出 S16.
+pub fn negotiate_output(
+    src_sample_rate: u32,
+    src_channels: u32,
+    device: Option<DeviceSpec>,
+) -> OutputPlan {
+    let Some(device) = device else {
+        return OutputPlan {
+            sample_rate: src_sample_rate,
+            channels: src_channels,
+            format: OutputSampleFormat::F32,
+        };
+    };
+    let sample_rate = if device.sample_rate > 0 {
+        device.sample_rate
+    } else {
+        src_sample_rate
+    };
+    let channels = if device.channels > 0 {
+        src_channels.min(device.channels)
+    } else {
+        src_channels
+    };
+    OutputPlan {
+        sample_rate,
+        channels,
+        format: device.format,
+    }
+}
+
+/// 将 SDL 采样格式映射为输出格式
+fn output_format_from_sdl(format: AudioFormat) -> OutputSampleFormat {
+    match format {
+        AudioFormat::F32LSB | AudioFormat::F32MSB | AudioFormat::S32LSB | AudioFormat::S32MSB => {
+            OutputSampleFormat::F32
+        }
+        _ => OutputSampleFormat::S16,
+    }
+}
+
+/// 按 `<名称|索引>` 在设备列表中查找播放设备
+///
+/// 依次尝试: 数字索引, 名称完全匹配, 名称不区分大小写的部分匹配 (须唯一).
+pub fn resolve_audio_device(names: &[String], query: &str) -> Result<usize, String> {
+    if let Ok(index) = query.parse::<usize>() {
+        if index < names.len() {
+            return Ok(index);
+        }
+        return Err(format!(
+            "音频设备索引 {} 超出范围 (共 {} 个设备)",
+            index,
+            names.len()
+        ));
+    }
+    if let Some(index) = names.iter().position(|n| n == query) {
+        return Ok(index);
+    }
+    let lower = query.to_lowercase();
+    let matches: Vec<usize> = names
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| n.to_lowercase().contains(&lower))
+        .map(|(i, _)| i)
+        .collect();
+    match matches.as_slice() {
+        [index] => Ok(*index),
+        [] => Err(format!("未找到音频设备: {}", query)),
+        _ => Err(format!(
+            "音频设备名称不唯一: {} (匹配 {} 个)",
+            query,
+            matches.len()
+        )),
+    }
+}
+
+/// 列出所有播放设备名称 (按 SDL 设备索引排列)
+pub fn list_playback_devices(audio_subsystem: &sdl2::AudioSubsystem) -> Vec<String> {
+    let count = audio_subsystem.num_audio_playback_devices().unwrap_or(0);
+    (0..count)
+        .map(|i| {
+            audio_subsystem
+                .audio_playback_device_name(i)
+                .unwrap_or_else(|e| format!("<未知设备: {}>", e))
+        })
+        .collect()
+}
+
+/// 回调输出采样类型, 由 F32 混音结果转换而来
+trait OutputSample: AudioFormatNum + Copy + Send + 'static {
+    fn from_f32(value: f32) -> Self;
+}
+
+impl OutputSample for f32 {
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+impl OutputSample for i16 {
+    fn from_f32(value: f32) -> Self {
+        (value.clamp(-1.0, 1.0) * 32767.0).round() as i16
+    }
+}
+
 /// SDL2 音频回调结构
-struct SdlAudioPlayer {
+struct SdlAudioPlayer<T> {
     receiver: Arc<Mutex<mpsc::Receiver<AudioChunk>>>,
     buffer: Vec<f32>,
     clock: MediaClock,
@@ -44,17 +211,17 @@ struct SdlAudioPlayer {
     volume_percent: Arc<AtomicU32>,
     /// 静音标记, 由 player 线程实时更新
     muted: Arc<AtomicBool>,
+    _sample: std::marker::PhantomData<T>,
 }
 
-impl AudioCallback for SdlAudioPlayer {
-    type Channel = f32;
+impl<T: OutputSample> AudioCallback for SdlAudioPlayer<T> {
+    type Channel = T;
 
-    fn callback(&mut self, out: &mut [f32]) {
+    fn callback(&mut self, out: &mut [T]) {
+        let silence = T::from_f32(0.0);
         let is_playing = *self.playing.lock().unwrap();
         if !is_playing || self.clock.is_paused() {
-            for sample in out.iter_mut() {
-                *sample = 0.0;
-            }
+            out.fill(silence);
             return;
         }
 
@@ -65,12 +232,9 @@ impl AudioCallback for SdlAudioPlayer {
             while recv.try_recv().is_ok() {}
             drop(recv);
             self.flush_flag.store(false, Ordering::Release);
-            for sample in out.iter_mut() {
-                *sample = 0.0;
-            }
+            out.fill(silence);
             return;
         }
-
         let recv = self.receiver.lock().unwrap();
 
         let mut last_chunk_pts = None;
@@ -96,25 +260,26 @@ impl AudioCallback for SdlAudioPlayer {
             }
         }
 
-        // 填充输出
-        let available = self.buffer.len().min(out.len());
-        for (i, sample) in out.iter_mut().enumerate() {
-            *sample = if i < available { self.buffer[i] } else { 0.0 };
-        }
-
-        if available > 0 {
-            self.buffer.drain(..available);
-        }
-
-        // 实时音量/静音控制: 在回调输出阶段应用, 避免队列缓存导致延迟.
+        // 实时音量/静音控制: 在重采样之后的回调输出阶段应用, 避免队列缓存导致延迟.
         let is_muted = self.muted.load(Ordering::Relaxed);
         let volume = if is_muted {
             0.0f32
         } else {
             (self.volume_percent.load(Ordering::Relaxed).min(100) as f32) / 100.0
         };
-        for sample in out.iter_mut() {
-            *sample *= volume;
+
+        // 填充输出, 同时转换为设备采样格式
+        let available = self.buffer.len().min(out.len());
+        for (i, sample) in out.iter_mut().enumerate() {
+            *sample = if i < available {
+                T::from_f32(self.buffer[i] * volume)
+            } else {
+                silence
+            };
+        }
+
+        if available > 0 {
+            self.buffer.drain(..available);
         }
 
         // ── 更新音频时钟 (对齐 ffplay sdl_audio_callback) ──
@@ -135,100 +300,183 @@ impl AudioCallback for SdlAudioPlayer {
             let hw_buf_frames = 2 * out.len() as i64 / out_ch;
 
             let total_buffered_us = (hw_buf_frames + write_buf_frames) * 1_000_000 / rate;
-            self.clock.update_audio_pts(pts - total_buffered_us);
+            // 倍速播放时缓冲中的采样已经过 atempo 处理, 换算回媒体时间需乘以倍率
+            let buffered_media_us =
+                (total_buffered_us as f64 * f64::from(self.clock.speed())) as i64;
+            self.clock.update_audio_pts(pts - buffered_media_us);
         }
     }
 }
 
 /// 音频输出管理器 (留在主线程, 持有 SDL2 设备)
 pub struct AudioOutput {
-    _device: AudioDevice<SdlAudioPlayer>,
+    _device: PcmDevice,
+}
+
+/// 按协商格式打开的 PCM 设备 (仅持有以保持设备打开)
+#[allow(dead_code)]
+enum PcmDevice {
+    F32(AudioDevice<SdlAudioPlayer<f32>>),
+    S16(AudioDevice<SdlAudioPlayer<i16>>),
+}
+
+/// 音频数据通道 (按输出模式区分载荷类型)
+enum ChunkSender {
+    Pcm(mpsc::Sender<AudioChunk>),
+    Passthrough(mpsc::Sender<SpdifBurst>),
 }
 
 /// 音频数据发送端 (可安全跨线程传递给 player 线程)
 pub struct AudioSender {
-    sender: mpsc::Sender<AudioChunk>,
+    sender: ChunkSender,
     flush_flag: Arc<AtomicBool>,
     volume_percent: Arc<AtomicU32>,
     muted: Arc<AtomicBool>,
 }
 
+/// 创建 PCM 回调所需的共享状态
+struct PcmPlayerInit {
+    receiver: Arc<Mutex<mpsc::Receiver<AudioChunk>>>,
+    clock: MediaClock,
+    input_sample_rate: u32,
+    input_channels: u32,
+    playing: Arc<Mutex<bool>>,
+    flush_flag: Arc<AtomicBool>,
+    volume_percent: Arc<AtomicU32>,
+    muted: Arc<AtomicBool>,
+}
+
+impl PcmPlayerInit {
+    /// 按 SDL 实际给出的设备参数构造回调, 参数与源不一致时挂接重采样器
+    fn into_player<T: OutputSample>(self, spec: AudioSpec) -> SdlAudioPlayer<T> {
+        let output_sample_rate = spec.freq as u32;
+        let output_channels = spec.channels as u32;
+
+        info!(
+            "SDL2 音频设备: {}Hz/{}ch, 缓冲区 {} 样本 (源 {}Hz/{}ch)",
+            output_sample_rate,
+            output_channels,
+            spec.samples,
+            self.input_sample_rate,
+            self.input_channels
+        );
+
+        let converter = build_f32_converter(
+            self.input_sample_rate,
+            self.input_channels,
+            output_sample_rate,
+            output_channels,
+        );
+        if converter.is_some() {
+            info!(
+                "音频参数转换: {}Hz/{}ch -> {}Hz/{}ch",
+                self.input_sample_rate, self.input_channels, output_sample_rate, output_channels
+            );
+        }
+
+        SdlAudioPlayer {
+            receiver: self.receiver,
+            buffer: Vec::new(),
+            clock: self.clock,
+            converter,
+            input_channels: self.input_channels,
+            output_sample_rate,
+            output_channels,
+            playing: self.playing,
+            flush_flag: self.flush_flag,
+            volume_percent: self.volume_percent,
+            muted: self.muted,
+            _sample: std::marker::PhantomData,
+        }
+    }
+}
+
 impl AudioOutput {
     /// 创建音频输出
     ///
+    /// `device` 为 `<名称|索引>`, `None` 使用系统默认设备.
+    /// 指定设备时先查询其原生参数并据此协商 (见 [`negotiate_output`]),
+    /// 设备实际参数与源不一致时在回调中用 [`ResampleContext`] 转换.
+    ///
     /// 缓冲区大小按 ffplay 公式计算:
     /// `max(SDL_AUDIO_MIN_BUFFER_SIZE, 2 << log2(freq / SDL_AUDIO_MAX_CALLBACKS_PER_SEC))`
     pub fn new(
         audio_subsystem: &sdl2::AudioSubsystem,
+        device: Option<&str>,
         sample_rate: u32,
         channels: u32,
         clock: MediaClock,
     ) -> Result<(Self, AudioSender), String> {
-        let buf_size = compute_audio_buf_size(sample_rate);
+        let (device_name, device_spec) = match device {
+            Some(query) => {
+                let names = list_playback_devices(audio_subsystem);
+                let index = resolve_audio_device(&names, query)?;
+                let spec = match audio_subsystem.audio_playback_device_spec(index as u32) {
+                    Ok(spec) => Some(DeviceSpec::from_sdl(&spec)),
+                    Err(e) => {
+                        warn!("查询音频设备 {} 参数失败: {}", names[index], e);
+                        None
+                    }
+                };
+                (Some(names[index].clone()), spec)
+            }
+            None => (None, None),
+        };
+
+        let plan = negotiate_output(sample_rate, channels, device_spec);
+        let buf_size = compute_audio_buf_size(plan.sample_rate);
 
         let desired_spec = AudioSpecDesired {
-            freq: Some(sample_rate as i32),
-            channels: Some(channels as u8),
+            freq: Some(plan.sample_rate as i32),
+            channels: Some(plan.channels as u8),
             samples: Some(buf_size),
         };
 
         let (sender, receiver) = mpsc::channel::<AudioChunk>();
-        let receiver = Arc::new(Mutex::new(receiver));
-        let playing = Arc::new(Mutex::new(true));
         let flush_flag = Arc::new(AtomicBool::new(false));
         let volume_percent = Arc::new(AtomicU32::new(100));
         let muted = Arc::new(AtomicBool::new(false));
 
-        let playing_clone = playing.clone();
-        let receiver_clone = receiver.clone();
-        let flush_flag_clone = flush_flag.clone();
-        let volume_percent_clone = volume_percent.clone();
-        let muted_clone = muted.clone();
-
-        let device = audio_subsystem.open_playback(None, &desired_spec, |spec| {
-            let output_sample_rate = spec.freq as u32;
-            let output_channels = spec.channels as u32;
-
-            info!(
-                "SDL2 音频设备: {}Hz/{}ch, 缓冲区 {} 样本 (请求 {}Hz/{}ch, {} 样本)",
-                output_sample_rate, output_channels, spec.samples, sample_rate, channels, buf_size
-            );
+        let init = PcmPlayerInit {
+            receiver: Arc::new(Mutex::new(receiver)),
+            clock,
+            input_sample_rate: sample_rate,
+            input_channels: channels,
+            playing: Arc::new(Mutex::new(true)),
+            flush_flag: flush_flag.clone(),
+            volume_percent: volume_percent.clone(),
+            muted: muted.clone(),
+        };
 
-            let converter =
-                build_f32_converter(sample_rate, channels, output_sample_rate, output_channels);
-            if converter.is_some() {
-                info!(
-                    "音频参数转换: {}Hz/{}ch -> {}Hz/{}ch",
-                    sample_rate, channels, output_sample_rate, output_channels
-                );
+        let device_ref = device_name.as_deref();
+        let device = match plan.format {
+            OutputSampleFormat::F32 => {
+                let device = audio_subsystem
+                    .open_playback(device_ref, &desired_spec, |spec| init.into_player(spec))?;
+                device.resume();
+                PcmDevice::F32(device)
             }
-
-            SdlAudioPlayer {
-                receiver: receiver_clone,
-                buffer: Vec::new(),
-                clock,
-                converter,
-                input_channels: channels,
-                output_sample_rate,
-                output_channels,
-                playing: playing_clone,
-                flush_flag: flush_flag_clone,
-                volume_percent: volume_percent_clone,
-                muted: muted_clone,
+            OutputSampleFormat::S16 => {
+                let device = audio_subsystem
+                    .open_playback(device_ref, &desired_spec, |spec| init.into_player(spec))?;
+                device.resume();
+                PcmDevice::S16(device)
             }
-        })?;
-
-        device.resume();
+        };
 
         debug!(
-            "SDL2 音频输出已启动: {}Hz/{}ch, 缓冲区 {} 样本",
-            sample_rate, channels, buf_size
+            "SDL2 音频输出已启动 ({}): {}Hz/{}ch {:?}, 缓冲区 {} 样本",
+            device_ref.unwrap_or("默认设备"),
+            plan.sample_rate,
+            plan.channels,
+            plan.format,
+            buf_size
         );
 
         Ok((
             Self { _device: device },
             AudioSender {
-                sender,
+                sender: ChunkSender::Pcm(sender),
                 flush_flag,
                 volume_percent,
                 muted,
@@ -243,9 +491,27 @@ impl AudioSender {
     /// 使用无界通道确保音频数据不会因通道满而被丢弃,
     /// 从而保证音频时钟 PTS 连续递增, 避免时钟漂移导致的加速/慢放.
     pub fn send(&self, chunk: AudioChunk) -> Result<(), String> {
-        self.sender
-            .send(chunk)
-            .map_err(|_| "音频通道已断开".to_string())
+        match &self.sender {
+            ChunkSender::Pcm(sender) => {
+                sender.send(chunk).map_err(|_| "音频通道已断开".to_string())
+            }
+            ChunkSender::Passthrough(_) => Err("S/PDIF 直通输出不接受 PCM 数据".to_string()),
+        }
+    }
+
+    /// 发送 IEC 61937 突发到直通输出队列
+    pub fn send_burst(&self, burst: SpdifBurst) -> Result<(), String> {
+        match &self.sender {
+            ChunkSender::Passthrough(sender) => {
+                sender.send(burst).map_err(|_| "音频通道已断开".to_string())
+            }
+            ChunkSender::Pcm(_) => Err("PCM 输出不接受 S/PDIF 突发".to_string()),
+        }
+    }
+
+    /// 是否为 S/PDIF 直通输出
+    pub fn is_passthrough(&self) -> bool {
+        matches!(self.sender, ChunkSender::Passthrough(_))
     }
 
     /// Seek 时清空音频缓冲 (通知回调线程排空旧数据)
@@ -265,6 +531,133 @@ impl AudioSender {
     }
 }
 
+// ── S/PDIF 直通输出 ─────────────────────────────────────────────────────
+
+/// S/PDIF 直通回调结构
+///
+/// 突发数据必须逐位送达功放, 因此不做重采样和音量缩放; 静音时输出全零.
+struct SpdifAudioPlayer {
+    receiver: mpsc::Receiver<SpdifBurst>,
+    buffer: Vec<i16>,
+    clock: MediaClock,
+    sample_rate: u32,
+    flush_flag: Arc<AtomicBool>,
+    muted: Arc<AtomicBool>,
+}
+
+impl AudioCallback for SpdifAudioPlayer {
+    type Channel = i16;
+
+    fn callback(&mut self, out: &mut [i16]) {
+        if self.clock.is_paused() {
+            out.fill(0);
+            return;
+        }
+
+        if self.flush_flag.load(Ordering::Acquire) {
+            self.buffer.clear();
+            while self.receiver.try_recv().is_ok() {}
+            self.flush_flag.store(false, Ordering::Release);
+            out.fill(0);
+            return;
+        }
+
+        let mut last_burst_pts = None;
+        while self.buffer.len() < out.len() {
+            match self.receiver.try_recv() {
+                Ok(burst) => {
+                    last_burst_pts = Some(burst.pts_us);
+                    self.buffer.extend_from_slice(&burst.samples);
+                }
+                Err(_) => break,
+            }
+        }
+
+        let available = self.buffer.len().min(out.len());
+        if self.muted.load(Ordering::Relaxed) {
+            out.fill(0);
+        } else {
+            out[..available].copy_from_slice(&self.buffer[..available]);
+            out[available..].fill(0);
+        }
+        self.buffer.drain(..available);
+
+        // 时钟补偿与 PCM 输出一致: 2 个硬件周期 + 内部缓冲
+        if let Some(pts) = last_burst_pts {
+            let out_ch = SPDIF_CHANNELS as i64;
+            let buffered_frames = (2 * out.len() + self.buffer.len()) as i64 / out_ch;
+            let buffered_us = buffered_frames * 1_000_000 / self.sample_rate as i64;
+            self.clock.update_audio_pts(pts - buffered_us);
+        }
+    }
+}
+
+/// S/PDIF 直通输出 (留在主线程, 持有 SDL2 设备)
+///
+/// 设备以 16 位立体声打开, 采样率与源码流一致 (AC3/DTS 通常为 48kHz).
+/// 每个压缩帧对应一个 IEC 61937 突发, 突发采样数与帧的解码采样数相同,
+/// 因此数据字节率为采样数的 4 倍 (2 声道 × 16 位).
+pub struct PassthroughAudioOutput {
+    _device: AudioDevice<SpdifAudioPlayer>,
+}
+
+impl PassthroughAudioOutput {
+    /// 创建直通输出, 设备不支持所需参数时返回错误
+    pub fn new(
+        audio_subsystem: &sdl2::AudioSubsystem,
+        codec: SpdifCodec,
+        sample_rate: u32,
+        clock: MediaClock,
+    ) -> Result<(Self, AudioSender), String> {
+        let buf_size = compute_audio_buf_size(sample_rate);
+        let desired_spec = AudioSpecDesired {
+            freq: Some(sample_rate as i32),
+            channels: Some(SPDIF_CHANNELS as u8),
+            samples: Some(buf_size),
+        };
+
+        let (sender, receiver) = mpsc::channel::<SpdifBurst>();
+        let flush_flag = Arc::new(AtomicBool::new(false));
+        let muted = Arc::new(AtomicBool::new(false));
+        let flush_flag_clone = flush_flag.clone();
+        let muted_clone = muted.clone();
+
+        let device = audio_subsystem.open_playback(None, &desired_spec, |spec| {
+            info!(
+                "S/PDIF 直通 ({:?}): SDL2 音频设备 {}Hz/{}ch, 缓冲区 {} 样本",
+                codec, spec.freq, spec.channels, spec.samples
+            );
+            SpdifAudioPlayer {
+                receiver,
+                buffer: Vec::new(),
+                clock,
+                sample_rate,
+                flush_flag: flush_flag_clone,
+                muted: muted_clone,
+            }
+        })?;
+
+        let spec = device.spec();
+        if spec.freq != sample_rate as i32 || u32::from(spec.channels) != SPDIF_CHANNELS {
+            return Err(format!(
+                "音频设备不支持 {}Hz 立体声直通 (实际 {}Hz/{}ch)",
+                sample_rate, spec.freq, spec.channels
+            ));
+        }
+        device.resume();
+
+        Ok((
+            Self { _device: device },
+            AudioSender {
+                sender: ChunkSender::Passthrough(sender),
+                flush_flag,
+                volume_percent: Arc::new(AtomicU32::new(100)),
+                muted,
+            },
+        ))
+    }
+}
+
 /// 按 ffplay 公式计算音频缓冲区大小 (样本数)
 ///
 /// `max(SDL_AUDIO_MIN_BUFFER_SIZE, 2 << av_log2(freq / SDL_AUDIO_MAX_CALLBACKS_PER_SEC))`
@@ -328,3 +721,98 @@ fn convert_chunk_f32(
     }
     Ok(output_samples)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(sample_rate: u32, channels: u32, format: OutputSampleFormat) -> DeviceSpec {
+        DeviceSpec {
+            sample_rate,
+            channels,
+            format,
+        }
+    }
+
+    #[test]
+    fn test_negotiate_without_device_spec_requests_source() {
+        let plan = negotiate_output(44100, 2, None);
+        assert_eq!(
+            plan,
+            OutputPlan {
+                sample_rate: 44100,
+                channels: 2,
+                format: OutputSampleFormat::F32,
+            }
+        );
+        assert!(build_f32_converter(44100, 2, plan.sample_rate, plan.channels).is_none());
+    }
+
+    #[test]
+    fn test_negotiate_native_rate_mismatch_uses_own_resampler() {
+        let plan = negotiate_output(44100, 2, Some(device(48000, 2, OutputSampleFormat::S16)));
+        assert_eq!(plan.sample_rate, 48000, "应请求设备原生采样率");
+        assert_eq!(plan.format, OutputSampleFormat::S16);
+        let conv = build_f32_converter(44100, 2, plan.sample_rate, plan.channels)
+            .expect("采样率不一致时应挂接重采样器");
+        assert_eq!(conv.src_sample_rate, 44100);
+        assert_eq!(conv.dst_sample_rate, 48000);
+    }
+
+    #[test]
+    fn test_negotiate_channels_and_unknown_fields() {
+        let plan = negotiate_output(48000, 6, Some(device(48000, 2, OutputSampleFormat::F32)));
+        assert_eq!(plan.channels, 2, "声道数不应超过设备原生声道数");
+        assert_eq!(plan.format, OutputSampleFormat::F32);
+
+        let plan = negotiate_output(22050, 1, Some(device(0, 0, OutputSampleFormat::S16)));
+        assert_eq!(
+            (plan.sample_rate, plan.channels),
+            (22050, 1),
+            "未知字段沿用源参数"
+        );
+    }
+
+    #[test]
+    fn test_output_format_from_sdl() {
+        assert_eq!(
+            output_format_from_sdl(AudioFormat::F32LSB),
+            OutputSampleFormat::F32
+        );
+        assert_eq!(
+            output_format_from_sdl(AudioFormat::S32LSB),
+            OutputSampleFormat::F32
+        );
+        assert_eq!(
+            output_format_from_sdl(AudioFormat::S16LSB),
+            OutputSampleFormat::S16
+        );
+        assert_eq!(
+            output_format_from_sdl(AudioFormat::U8),
+            OutputSampleFormat::S16
+        );
+    }
+
+    #[test]
+    fn test_resolve_audio_device() {
+        let names = vec![
+            "Built-in Audio Analog Stereo".to_string(),
+            "HDMI Output".to_string(),
+            "USB Headset".to_string(),
+        ];
+        assert_eq!(resolve_audio_device(&names, "1"), Ok(1));
+        assert_eq!(resolve_audio_device(&names, "USB Headset"), Ok(2));
+        assert_eq!(resolve_audio_device(&names, "hdmi"), Ok(1));
+        assert!(resolve_audio_device(&names, "3").is_err(), "索引越界应报错");
+        assert!(resolve_audio_device(&names, "bluetooth").is_err());
+        assert!(resolve_audio_device(&names, "o").is_err(), "多个匹配应报错");
+    }
+
+    #[test]
+    fn test_s16_output_sample_clamps() {
+        assert_eq!(i16::from_f32(0.0), 0);
+        assert_eq!(i16::from_f32(1.0), 32767);
+        assert_eq!(i16::from_f32(2.0), 32767);
+        assert_eq!(i16::from_f32(-1.5), -32767);
+    }
+}
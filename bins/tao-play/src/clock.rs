@@ -5,10 +5,13 @@
 //! Seek 安全: `seek_pending` 为 true 时, `update_audio_pts` 被忽略
 //! (防止旧音频数据覆盖 seek 目标). Player 线程通过 `confirm_seek`
 //! 在首帧解码完成后显式解冻时钟.
+//!
+//! 倍速播放: 时钟按 `speed` 倍率推进, 切换倍率时以当前时间为新基准,
+//! 保证时钟连续不跳变.
 
 use std::sync::Arc;
 use std::sync::Mutex;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::time::Instant;
 
 /// 音频时钟状态 (受 Mutex 保护, 确保 PTS 和更新时间的一致性)
@@ -34,6 +37,8 @@ struct ClockInner {
     paused: AtomicBool,
     /// Seek 后冻结时钟, 由 player 线程显式解冻
     seek_pending: AtomicBool,
+    /// 播放倍率 (f32 位模式, 1.0 为原速)
+    speed: AtomicU32,
 }
 
 impl MediaClock {
@@ -48,6 +53,7 @@ impl MediaClock {
                 }),
                 paused: AtomicBool::new(false),
                 seek_pending: AtomicBool::new(false),
+                speed: AtomicU32::new(1.0f32.to_bits()),
             }),
         }
     }
@@ -96,7 +102,7 @@ impl MediaClock {
     /// 三种模式:
     /// - 暂停中: 返回冻结的音频 PTS
     /// - Seek 冻结: 返回目标 PTS (不推进)
-    /// - 正常播放: 音频 PTS + 经过时间
+    /// - 正常播放: 音频 PTS + 经过时间 × 倍率
     /// - 初始启动: 系统时钟兜底
     pub fn current_time_us(&self) -> i64 {
         if self.inner.paused.load(Ordering::Relaxed) {
@@ -114,9 +120,9 @@ impl MediaClock {
         };
 
         if let Some(update_time) = update_time {
-            // 音频已启动: 使用音频 PTS + 上次更新后的经过时间
-            let elapsed = update_time.elapsed().as_micros() as i64;
-            base_pts + elapsed
+            // 音频已启动: 使用音频 PTS + 上次更新后的经过时间 (按倍率换算为媒体时间)
+            let elapsed = update_time.elapsed().as_micros() as f64 * f64::from(self.speed());
+            base_pts + elapsed as i64
         } else {
             // 初始播放: 回退到系统时钟, 防止所有帧以 delay>0 堆积快速渲染
             self.inner.start_time.elapsed().as_micros() as i64
@@ -158,4 +164,58 @@ impl MediaClock {
         }
         self.inner.paused.store(paused, Ordering::Relaxed);
     }
+
+    /// 当前播放倍率
+    pub fn speed(&self) -> f32 {
+        f32::from_bits(self.inner.speed.load(Ordering::Relaxed))
+    }
+
+    /// 设置播放倍率
+    ///
+    /// 先按旧倍率把已经过的时间折算进 PTS 并重置 `update_time`,
+    /// 之后的经过时间才按新倍率推进, 避免切换时时钟跳变.
+    pub fn set_speed(&self, speed: f32) {
+        let mut audio = self.inner.audio.lock().unwrap();
+        let frozen = self.inner.paused.load(Ordering::Relaxed)
+            || self.inner.seek_pending.load(Ordering::Acquire);
+        if let Some(update_time) = audio.update_time {
+            if !frozen {
+                let elapsed = update_time.elapsed().as_micros() as f64 * f64::from(self.speed());
+                audio.pts_us += elapsed as i64;
+                audio.update_time = Some(Instant::now());
+            }
+        }
+        self.inner.speed.store(speed.to_bits(), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_media_clock_speed_scales_advance() {
+        let clock = MediaClock::new();
+        assert_eq!(clock.speed(), 1.0, "默认应为原速");
+
+        clock.update_audio_pts(1_000_000);
+        clock.set_speed(2.0);
+        assert_eq!(clock.speed(), 2.0);
+        let before = clock.current_time_us();
+        std::thread::sleep(Duration::from_millis(100));
+        let advanced = clock.current_time_us() - before;
+        assert!(
+            (180_000..400_000).contains(&advanced),
+            "2 倍速下 100ms 应推进约 200ms 媒体时间, 实际 {advanced}us"
+        );
+
+        // 切换倍率时时钟连续, 不回退
+        let at_switch = clock.current_time_us();
+        clock.set_speed(0.5);
+        assert!(
+            clock.current_time_us() >= at_switch,
+            "切换倍率后时钟不应回退"
+        );
+    }
 }
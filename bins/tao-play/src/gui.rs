@@ -1,7 +1,8 @@
 //! SDL2 视频渲染和事件循环.
 //!
 //! 实现 ffplay 风格的 video_refresh 状态机, 在渲染线程精确控制帧显示时机.
-//! 使用 SDL2 YUV 纹理进行硬件加速渲染, GPU 做色彩空间转换.
+//! YUV420P 与 RGB24/BGR24 帧直接上传对应格式的 SDL2 纹理, 由 GPU 做色彩空间转换;
+//! 其余像素格式先经缓存的 [`ScaleContext`] 转换为 YUV420P.
 
 use sdl2::event::Event;
 use sdl2::keyboard::{Keycode, Mod};
@@ -16,9 +17,12 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::mpsc::Receiver;
 use std::time::Instant;
+use tao_core::{PixelFormat, Rational, TaoResult};
+use tao_scale::{ScaleAlgorithm, ScaleContext};
 
 use crate::clock::MediaClock;
 use crate::player::{PlayerCommand, PlayerStatus, VideoFrame};
+use crate::subtitle::SubtitleTrack;
 
 // ── ffplay 同步常量 ──────────────────────────────────────────────────────
 
@@ -33,6 +37,9 @@ const AV_NOSYNC_THRESHOLD: f64 = 10.0;
 /// 默认刷新率 (秒) - ffplay: REFRESH_RATE
 const REFRESH_RATE: f64 = 0.01;
 
+/// 音量变化后音量条的显示时长 (秒)
+const VOLUME_OSD_DURATION: f64 = 1.5;
+
 // ── 挂钟时间 ─────────────────────────────────────────────────────────────
 
 static EPOCH: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
@@ -63,6 +70,12 @@ struct VideoDisplayState<'a> {
     /// 纹理尺寸
     tex_width: u32,
     tex_height: u32,
+    /// 纹理像素格式
+    tex_format: PixelFormatEnum,
+    /// 当前纹理对应帧的样本宽高比
+    tex_sar: Rational,
+    /// 非直传格式的转换上下文 (源格式与尺寸不变时复用)
+    converter: Option<FrameConverter>,
     /// 全屏状态
     is_fullscreen: bool,
     /// Seek 后等待新帧显示 (暂停状态下)
@@ -75,10 +88,18 @@ struct VideoDisplayState<'a> {
     volume_level: f32,
     /// 当前是否静音
     muted: bool,
+    /// 当前播放倍率
+    speed: f32,
     /// 是否显示屏幕文字 (当前: 时间 HUD)
     show_hud_text: bool,
+    /// 音量条显示截止的挂钟时间 (秒), 0 表示不显示
+    volume_osd_until: f64,
     /// 当前章节信息: (章节索引, 标题)
     current_chapter: Option<(usize, String)>,
+    /// 已接收待显示的字幕
+    subtitles: SubtitleTrack,
+    /// 当前显示的字幕文本
+    subtitle_text: Option<String>,
 }
 
 impl<'a> VideoDisplayState<'a> {
@@ -93,14 +114,21 @@ impl<'a> VideoDisplayState<'a> {
             texture: None,
             tex_width: 0,
             tex_height: 0,
+            tex_format: PixelFormatEnum::IYUV,
+            tex_sar: Rational::new(1, 1),
+            converter: None,
             is_fullscreen: false,
             seek_frame_pending: false,
             current_time_sec: 0.0,
             total_time_sec: 0.0,
             volume_level: initial_volume.clamp(0.0, 1.0),
             muted: false,
+            speed: 1.0,
             show_hud_text: true,
+            volume_osd_until: 0.0,
             current_chapter: None,
+            subtitles: SubtitleTrack::default(),
+            subtitle_text: None,
         }
     }
 }
@@ -126,9 +154,11 @@ fn frame_duration(last_pts: f64, current_pts: f64) -> f64 {
 }
 
 /// 计算目标延迟 (秒), 完全对齐 ffplay 的 `compute_target_delay`
+///
+/// `delay` 为挂钟时间; 倍速播放时音视频差值按时钟倍率换算为挂钟时间.
 fn compute_target_delay(delay: f64, video_pts: f64, clock: &MediaClock) -> f64 {
     let audio_time = clock.current_time_us() as f64 / 1_000_000.0;
-    let diff = video_pts - audio_time;
+    let diff = (video_pts - audio_time) / f64::from(clock.speed());
 
     let sync_threshold = delay.clamp(AV_SYNC_THRESHOLD_MIN, AV_SYNC_THRESHOLD_MAX);
 
@@ -200,7 +230,9 @@ fn video_refresh<'a>(
         }
 
         let vp_pts = state.frame_queue[0].pts;
-        let last_duration = frame_duration(state.last_pts, vp_pts);
+        // 帧间隔为媒体时间, 按时钟倍率换算为挂钟时间
+        let speed = f64::from(clock.speed());
+        let last_duration = frame_duration(state.last_pts, vp_pts) / speed;
         let delay = compute_target_delay(last_duration, vp_pts, clock);
 
         let time = wall_clock_sec();
@@ -227,7 +259,7 @@ fn video_refresh<'a>(
         // 迟到帧丢弃
         if state.frame_queue.len() > 1 {
             let next_pts = state.frame_queue[1].pts;
-            let duration = frame_duration(vp_pts, next_pts);
+            let duration = frame_duration(vp_pts, next_pts) / speed;
             if !state.step && time > state.frame_timer + duration {
                 state.frame_drops_late += 1;
                 state.last_pts = vp_pts;
@@ -261,6 +293,101 @@ fn video_refresh<'a>(
 
 // ── 渲染辅助 ─────────────────────────────────────────────────────────────
 
+/// 按帧像素格式选择 SDL 纹理格式, 返回 (纹理格式, 上传前需转换到的像素格式)
+///
+/// YUV420P 与 RGB24/BGR24 可直接上传; 灰度与 RGBA 转换为 RGB24; YUV422P/444P、NV12
+/// 等其余格式转换为 YUV420P 后以 IYUV 纹理上传 (转换不支持时记录日志并跳过该帧).
+/// tao 暂无打包 YUV422 像素格式, 因此不使用 YUY2 纹理.
+fn select_texture_format(pixel_format: PixelFormat) -> (PixelFormatEnum, Option<PixelFormat>) {
+    match pixel_format {
+        PixelFormat::Yuv420p => (PixelFormatEnum::IYUV, None),
+        PixelFormat::Rgb24 => (PixelFormatEnum::RGB24, None),
+        PixelFormat::Bgr24 => (PixelFormatEnum::BGR24, None),
+        PixelFormat::Gray8 | PixelFormat::Rgba => {
+            (PixelFormatEnum::RGB24, Some(PixelFormat::Rgb24))
+        }
+        _ => (PixelFormatEnum::IYUV, Some(PixelFormat::Yuv420p)),
+    }
+}
+
+/// 缓存的像素格式转换上下文与输出缓冲
+struct FrameConverter {
+    ctx: ScaleContext,
+    planes: Vec<Vec<u8>>,
+    linesize: Vec<usize>,
+}
+
+impl FrameConverter {
+    /// 创建同尺寸的格式转换器, 无法计算目标平面布局时返回 None
+    fn new(width: u32, height: u32, src: PixelFormat, dst: PixelFormat) -> Option<Self> {
+        let plane_count = dst.plane_count() as usize;
+        let linesize = (0..plane_count)
+            .map(|p| dst.plane_linesize(p, width))
+            .collect::<Option<Vec<_>>>()?;
+        let planes = linesize
+            .iter()
+            .enumerate()
+            .map(|(p, &ls)| dst.plane_height(p, height).map(|h| vec![0u8; ls * h]))
+            .collect::<Option<Vec<_>>>()?;
+        let ctx = ScaleContext::new(
+            width,
+            height,
+            src,
+            width,
+            height,
+            dst,
+            ScaleAlgorithm::Bilinear,
+        );
+        Some(Self {
+            ctx,
+            planes,
+            linesize,
+        })
+    }
+
+    fn matches(&self, width: u32, height: u32, src: PixelFormat, dst: PixelFormat) -> bool {
+        self.ctx.src_width == width
+            && self.ctx.src_height == height
+            && self.ctx.src_format == src
+            && self.ctx.dst_format == dst
+    }
+
+    fn convert(&mut self, frame: &VideoFrame) -> TaoResult<()> {
+        let src: Vec<&[u8]> = frame.data.iter().map(|p| &p[..]).collect();
+        let mut dst: Vec<&mut [u8]> = self.planes.iter_mut().map(|p| &mut p[..]).collect();
+        self.ctx
+            .scale(&src, &frame.linesize, &mut dst, &self.linesize)
+    }
+}
+
+/// 按像素格式将平面数据写入纹理
+fn update_texture(
+    texture: &mut Texture,
+    pixel_format: PixelFormat,
+    data: &[&[u8]],
+    linesize: &[usize],
+) -> Result<(), String> {
+    match pixel_format {
+        PixelFormat::Yuv420p if data.len() >= 3 && linesize.len() >= 3 => texture
+            .update_yuv(
+                None,
+                data[0],
+                linesize[0],
+                data[1],
+                linesize[1],
+                data[2],
+                linesize[2],
+            )
+            .map_err(|e| e.to_string()),
+        PixelFormat::Rgb24 | PixelFormat::Bgr24 if !data.is_empty() && !linesize.is_empty() => {
+            texture
+                .update(None, data[0], linesize[0])
+                .map_err(|e| e.to_string())
+        }
+        _ => Err(format!("平面数据与像素格式 {pixel_format} 不符")),
+    }
+}
+
 /// 将队列头部帧数据上传到 GPU 纹理
 fn upload_front_frame<'a>(
     state: &mut VideoDisplayState<'a>,
@@ -270,32 +397,58 @@ fn upload_front_frame<'a>(
         Some(f) => f,
         None => return,
     };
+    let (tex_format, convert_to) = select_texture_format(frame.pixel_format);
 
-    // 纹理尺寸变化时重新创建
-    if state.texture.is_none() || frame.width != state.tex_width || frame.height != state.tex_height
+    // 纹理格式或尺寸变化 (如码流中途切换分辨率) 时重新创建
+    if state.texture.is_none()
+        || frame.width != state.tex_width
+        || frame.height != state.tex_height
+        || tex_format != state.tex_format
     {
         state.tex_width = frame.width;
         state.tex_height = frame.height;
+        state.tex_format = tex_format;
         state.texture = texture_creator
             .create_texture(
-                PixelFormatEnum::IYUV,
+                tex_format,
                 TextureAccess::Streaming,
                 state.tex_width,
                 state.tex_height,
             )
             .ok();
     }
+    state.tex_sar = frame.sample_aspect_ratio;
 
-    if let Some(tex) = state.texture.as_mut() {
-        let _ = tex.update_yuv(
-            None,
-            &frame.y_data,
-            frame.y_stride,
-            &frame.u_data,
-            frame.u_stride,
-            &frame.v_data,
-            frame.v_stride,
-        );
+    let Some(tex) = state.texture.as_mut() else {
+        return;
+    };
+    let result = match convert_to {
+        None => {
+            let planes: Vec<&[u8]> = frame.data.iter().map(|p| &p[..]).collect();
+            update_texture(tex, frame.pixel_format, &planes, &frame.linesize)
+        }
+        Some(dst_format) => {
+            if !state.converter.as_ref().is_some_and(|c| {
+                c.matches(frame.width, frame.height, frame.pixel_format, dst_format)
+            }) {
+                state.converter =
+                    FrameConverter::new(frame.width, frame.height, frame.pixel_format, dst_format);
+            }
+            match state.converter.as_mut() {
+                Some(converter) => converter
+                    .convert(frame)
+                    .map_err(|e| e.to_string())
+                    .and_then(|()| {
+                        let planes: Vec<&[u8]> =
+                            converter.planes.iter().map(Vec::as_slice).collect();
+                        update_texture(tex, dst_format, &planes, &converter.linesize)
+                    }),
+                None => Err(format!("无法转换像素格式 {}", frame.pixel_format)),
+            }
+        }
+    };
+    if let Err(e) = result {
+        log::debug!("视频帧上传失败 ({}): {}", frame.pixel_format, e);
     }
 }
 
@@ -310,8 +463,13 @@ fn render_current_texture(
     canvas.clear();
 
     if let Some(tex) = state.texture.as_ref() {
-        let dst = calculate_display_rect(canvas, state.tex_width, state.tex_height);
+        let dst = calculate_display_rect(canvas, state.tex_width, state.tex_height, state.tex_sar);
         let _ = canvas.copy(tex, None, Some(dst));
+        if let (Some(text), Some(font)) = (state.subtitle_text.as_deref(), hud_font) {
+            if let Err(e) = draw_subtitle_overlay(canvas, texture_creator, text, font, dst) {
+                log::warn!("字幕渲染失败: {}", e);
+            }
+        }
     }
     if state.show_hud_text {
         draw_time_overlay(
@@ -320,11 +478,16 @@ fn render_current_texture(
             state.total_time_sec,
             state.volume_level,
             state.muted,
+            state.speed,
             &state.current_chapter,
             texture_creator,
             hud_font,
         );
     }
+    // 音量条为音量变化后的短暂反馈, 不受屏幕文字开关影响
+    if wall_clock_sec() < state.volume_osd_until {
+        draw_volume_bar(canvas, state.volume_level, state.muted);
+    }
     canvas.present();
 }
 
@@ -332,30 +495,52 @@ fn is_shift(mod_state: Mod) -> bool {
     mod_state.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD)
 }
 
-fn is_ctrl(mod_state: Mod) -> bool {
-    mod_state.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD)
+/// 方向键对应的 seek 偏移 (秒): 左/右 ±10s, Shift+左/右 ±60s, 其他按键返回 None
+fn seek_delta_for_key(keycode: Keycode, mod_state: Mod) -> Option<f64> {
+    let step_sec = if is_shift(mod_state) { 60.0 } else { 10.0 };
+    match keycode {
+        Keycode::Right => Some(step_sec),
+        Keycode::Left => Some(-step_sec),
+        _ => None,
+    }
+}
+
+/// 可选的播放倍率档位
+const SPEED_STEPS: [f32; 6] = [0.5, 0.75, 1.0, 1.25, 1.5, 2.0];
+
+/// `-`/`+` 键对应的下一档倍率, 已到最低/最高档或其他按键时返回 None
+fn next_speed_for_key(keycode: Keycode, current: f32) -> Option<f32> {
+    match keycode {
+        Keycode::Minus | Keycode::KpMinus => {
+            SPEED_STEPS.iter().rev().copied().find(|&s| s < current)
+        }
+        Keycode::Equals | Keycode::Plus | Keycode::KpPlus => {
+            SPEED_STEPS.iter().copied().find(|&s| s > current)
+        }
+        _ => None,
+    }
 }
 
-/// 格式化秒数为 "HH:MM:SS.mmm"
-fn format_hms_millis(sec: f64) -> String {
-    let clamped = sec.max(0.0);
-    let total_ms = (clamped * 1000.0) as u64;
-    let ms = total_ms % 1000;
-    let total_sec = total_ms / 1000;
+/// 格式化秒数为 "MM:SS", 超过一小时为 "HH:MM:SS" (不足一秒的部分舍去)
+fn format_clock_time(sec: f64) -> String {
+    let total_sec = sec.max(0.0) as u64;
     let s = total_sec % 60;
-    let total_min = total_sec / 60;
-    let m = total_min % 60;
-    let h = total_min / 60;
-    format!("{:02}:{:02}:{:02}.{:03}", h, m, s, ms)
+    let m = total_sec / 60 % 60;
+    let h = total_sec / 3600;
+    if h > 0 {
+        format!("{:02}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
+    }
 }
 
-/// 构建进度字符串: "00:00:00.000/11:11:11.111"
+/// 构建进度字符串: "01:33/05:00", 总时长未知时为 "01:33/--:--"
 fn format_progress_text(current_sec: f64, total_sec: f64) -> String {
-    let current = format_hms_millis(current_sec);
+    let current = format_clock_time(current_sec);
     let total = if total_sec > 0.0 {
-        format_hms_millis(total_sec)
+        format_clock_time(total_sec)
     } else {
-        "--:--:--.---".to_string()
+        "--:--".to_string()
     };
     format!("{current}/{total}")
 }
@@ -370,6 +555,24 @@ fn format_volume_text(volume_level: f32, muted: bool) -> String {
     }
 }
 
+/// 音量条填充宽度 (像素), 静音时为 0
+fn volume_bar_fill_width(volume_level: f32, muted: bool, track_width: u32) -> u32 {
+    if muted {
+        0
+    } else {
+        (volume_level.clamp(0.0, 1.0) * track_width as f32).round() as u32
+    }
+}
+
+/// 构建倍率字符串: "SPEED 1.50X", 原速时不显示
+fn format_speed_text(speed: f32) -> Option<String> {
+    if speed == 1.0 {
+        None
+    } else {
+        Some(format!("SPEED {speed:.2}X"))
+    }
+}
+
 /// 获取 3x5 点阵字形
 fn glyph_rows(ch: char) -> Option<[u8; 5]> {
     let rows = match ch {
@@ -395,6 +598,10 @@ fn glyph_rows(ch: char) -> Option<[u8; 5]> {
         'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
         'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
         'E' => [0b111, 0b100, 0b111, 0b100, 0b111],
+        'S' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        'P' => [0b111, 0b101, 0b111, 0b100, 0b100],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
         ' ' => [0b000, 0b000, 0b000, 0b000, 0b000],
         _ => return None,
     };
@@ -409,6 +616,7 @@ fn draw_time_overlay(
     total_sec: f64,
     volume: f32,
     muted: bool,
+    speed: f32,
     current_chapter: &Option<(usize, String)>,
     texture_creator: &TextureCreator<WindowContext>,
     hud_font: Option<&sdl2::ttf::Font<'_, 'static>>,
@@ -421,6 +629,11 @@ fn draw_time_overlay(
     // 第二行: 音量
     lines.push(format_volume_text(volume, muted));
 
+    // 倍速播放时显示倍率
+    if let Some(text) = format_speed_text(speed) {
+        lines.push(text);
+    }
+
     // 第三行: 当前章节 (如果有)
     if let Some((idx, title)) = current_chapter {
         lines.push(format!("Track {}: {}", idx + 1, title));
@@ -493,6 +706,60 @@ fn draw_time_overlay_ttf(
     Ok(())
 }
 
+/// 绘制字幕 (视频区域底部居中, 每行带半透明背景)
+fn draw_subtitle_overlay(
+    canvas: &mut Canvas<Window>,
+    texture_creator: &TextureCreator<WindowContext>,
+    text: &str,
+    font: &sdl2::ttf::Font<'_, 'static>,
+    video_rect: Rect,
+) -> Result<(), String> {
+    let padding: i32 = 6;
+    let line_gap: i32 = 4;
+    let margin_bottom = (video_rect.height() as i32 / 12).max(10);
+
+    let mut textures = Vec::new();
+    for line in text.lines() {
+        let surface = font
+            .render(line)
+            .blended(Color::RGB(255, 255, 255))
+            .map_err(|e| format!("渲染字体失败: {}", e))?;
+        let texture = texture_creator
+            .create_texture_from_surface(&surface)
+            .map_err(|e| format!("创建字体纹理失败: {}", e))?;
+        textures.push(texture);
+    }
+    let total_h: i32 = textures
+        .iter()
+        .map(|t| t.query().height as i32 + line_gap)
+        .sum::<i32>()
+        - line_gap;
+
+    let center_x = video_rect.x() + video_rect.width() as i32 / 2;
+    let mut pen_y = video_rect.bottom() - margin_bottom - total_h;
+    for texture in &textures {
+        let query = texture.query();
+        let x = center_x - query.width as i32 / 2;
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, 160));
+        let _ = canvas.fill_rect(Rect::new(
+            x - padding,
+            pen_y - padding / 2,
+            query.width + padding as u32 * 2,
+            query.height + padding as u32,
+        ));
+        canvas
+            .copy(
+                texture,
+                None,
+                Rect::new(x, pen_y, query.width, query.height),
+            )
+            .map_err(|e| format!("绘制字体失败: {}", e))?;
+        pen_y += query.height as i32 + line_gap;
+    }
+
+    Ok(())
+}
+
 fn draw_time_overlay_bitmap(canvas: &mut Canvas<Window>, lines: &[String]) {
     let scale: i32 = 3;
     let glyph_w: i32 = 3 * scale;
@@ -522,16 +789,22 @@ fn draw_time_overlay_bitmap(canvas: &mut Canvas<Window>, lines: &[String]) {
     canvas.set_draw_color(Color::RGB(235, 235, 235));
     for (line_idx, line) in lines.iter().enumerate() {
         let base_y = y0 + line_idx as i32 * (glyph_h + line_gap);
-        for (idx, ch) in line.chars().enumerate() {
-            if let Some(rows) = glyph_rows(ch) {
-                let char_x = x0 + idx as i32 * (glyph_w + spacing);
-                for (row_idx, row) in rows.iter().enumerate() {
-                    for col in 0..3 {
-                        if (row & (1 << (2 - col))) != 0 {
-                            let px = char_x + col * scale;
-                            let py = base_y + row_idx as i32 * scale;
-                            let _ = canvas.fill_rect(Rect::new(px, py, scale as u32, scale as u32));
-                        }
+        draw_bitmap_line(canvas, x0, base_y, scale, line);
+    }
+}
+
+/// 以点阵字体绘制一行文字, 使用当前绘制颜色
+fn draw_bitmap_line(canvas: &mut Canvas<Window>, x: i32, y: i32, scale: i32, line: &str) {
+    let advance = 4 * scale;
+    for (idx, ch) in line.chars().enumerate() {
+        if let Some(rows) = glyph_rows(ch) {
+            let char_x = x + idx as i32 * advance;
+            for (row_idx, row) in rows.iter().enumerate() {
+                for col in 0..3 {
+                    if (row & (1 << (2 - col))) != 0 {
+                        let px = char_x + col * scale;
+                        let py = y + row_idx as i32 * scale;
+                        let _ = canvas.fill_rect(Rect::new(px, py, scale as u32, scale as u32));
                     }
                 }
             }
@@ -539,6 +812,44 @@ fn draw_time_overlay_bitmap(canvas: &mut Canvas<Window>, lines: &[String]) {
     }
 }
 
+/// 绘制音量条 (窗口底部居中): 上方为音量文字, 下方为按音量填充的横条
+fn draw_volume_bar(canvas: &mut Canvas<Window>, volume_level: f32, muted: bool) {
+    let (win_w, win_h) = canvas.output_size().unwrap_or((640, 480));
+    let scale: i32 = 2;
+    let padding: i32 = 8;
+    let track_w: u32 = (win_w / 3).clamp(120, 400);
+    let track_h: u32 = 10;
+    let text_h = 5 * scale;
+    let box_w = track_w as i32 + padding * 2;
+    let box_h = text_h + padding * 3 + track_h as i32;
+    let x0 = (win_w as i32 - box_w) / 2;
+    let y0 = win_h as i32 - box_h - (win_h as i32 / 10).max(20);
+
+    canvas.set_draw_color(Color::RGBA(0, 0, 0, 200));
+    let _ = canvas.fill_rect(Rect::new(x0, y0, box_w as u32, box_h as u32));
+
+    let text = format_volume_text(volume_level, muted);
+    let text_w = 4 * scale * text.chars().count() as i32 - scale;
+    canvas.set_draw_color(Color::RGB(235, 235, 235));
+    draw_bitmap_line(
+        canvas,
+        x0 + (box_w - text_w) / 2,
+        y0 + padding,
+        scale,
+        &text,
+    );
+
+    let track_x = x0 + padding;
+    let track_y = y0 + padding * 2 + text_h;
+    canvas.set_draw_color(Color::RGB(80, 80, 80));
+    let _ = canvas.fill_rect(Rect::new(track_x, track_y, track_w, track_h));
+    let fill = volume_bar_fill_width(volume_level, muted, track_w);
+    if fill > 0 {
+        canvas.set_draw_color(Color::RGB(235, 235, 235));
+        let _ = canvas.fill_rect(Rect::new(track_x, track_y, fill, track_h));
+    }
+}
+
 fn find_external_font_path() -> Option<PathBuf> {
     let exe = std::env::current_exe().ok()?;
     let exe_dir = exe.parent()?;
@@ -628,19 +939,44 @@ fn load_hud_font(ttf_context: &sdl2::ttf::Sdl2TtfContext) -> Option<sdl2::ttf::F
 }
 
 /// 计算保持宽高比的居中显示矩形 (对齐 ffplay calculate_display_rect)
-fn calculate_display_rect(canvas: &Canvas<Window>, pic_width: u32, pic_height: u32) -> Rect {
+fn calculate_display_rect(
+    canvas: &Canvas<Window>,
+    pic_width: u32,
+    pic_height: u32,
+    sar: Rational,
+) -> Rect {
     let (scr_w, scr_h) = canvas.output_size().unwrap_or((pic_width, pic_height));
+    fit_display_rect(scr_w, scr_h, pic_width, pic_height, sar)
+}
+
+/// 在 `scr_w` x `scr_h` 的窗口内按显示宽高比 (SAR x 像素宽高比) 居中留黑边
+///
+/// SAR 无效时按 1:1 处理.
+fn fit_display_rect(
+    scr_w: u32,
+    scr_h: u32,
+    pic_width: u32,
+    pic_height: u32,
+    sar: Rational,
+) -> Rect {
     if pic_width == 0 || pic_height == 0 || scr_w == 0 || scr_h == 0 {
         return Rect::new(0, 0, scr_w, scr_h);
     }
+    let (sar_num, sar_den) = if sar.num > 0 && sar.den > 0 {
+        (sar.num as i64, sar.den as i64)
+    } else {
+        (1, 1)
+    };
+    let aspect_num = pic_width as i64 * sar_num;
+    let aspect_den = pic_height as i64 * sar_den;
 
     // 先以窗口高度为基准计算宽度
     let mut height = scr_h as i64;
-    let mut width = (height * pic_width as i64 / pic_height as i64) & !1;
+    let mut width = (height * aspect_num / aspect_den) & !1;
 
     if width > scr_w as i64 {
         width = scr_w as i64;
-        height = (width * pic_height as i64 / pic_width as i64) & !1;
+        height = (width * aspect_den / aspect_num) & !1;
     }
 
     let x = ((scr_w as i64 - width) / 2) as i32;
@@ -749,41 +1085,18 @@ pub fn run_event_loop(
                             log::info!("[按键] S (单步) 仅视频支持, 当前={}", mode);
                         }
                     }
-                    Keycode::Right => {
+                    Keycode::Right | Keycode::Left => {
                         let mode = play_mode_str(paused, state.step);
-                        let step_sec = if is_ctrl(keymod) {
-                            30.0
-                        } else if is_shift(keymod) {
-                            10.0
-                        } else {
-                            5.0
-                        };
+                        let delta_sec = seek_delta_for_key(key, keymod).unwrap_or(0.0);
                         log::info!(
-                            "[按键] Right (+{:.0}s), 当前={}, 帧队列={}, 最近PTS={}",
-                            step_sec,
+                            "[按键] {:?} ({:+.0}s), 当前={}, 帧队列={}, 最近PTS={}",
+                            key,
+                            delta_sec,
                             mode,
                             state.frame_queue.len(),
                             fmt_pts(state.last_pts)
                         );
-                        let _ = command_tx.send(PlayerCommand::Seek(step_sec));
-                    }
-                    Keycode::Left => {
-                        let mode = play_mode_str(paused, state.step);
-                        let step_sec = if is_ctrl(keymod) {
-                            30.0
-                        } else if is_shift(keymod) {
-                            10.0
-                        } else {
-                            5.0
-                        };
-                        log::info!(
-                            "[按键] Left (-{:.0}s), 当前={}, 帧队列={}, 最近PTS={}",
-                            step_sec,
-                            mode,
-                            state.frame_queue.len(),
-                            fmt_pts(state.last_pts)
-                        );
-                        let _ = command_tx.send(PlayerCommand::Seek(-step_sec));
+                        let _ = command_tx.send(PlayerCommand::Seek(delta_sec));
                     }
                     Keycode::Up => {
                         let _ = command_tx.send(PlayerCommand::VolumeUp);
@@ -791,11 +1104,12 @@ pub fn run_event_loop(
                     Keycode::Down => {
                         let _ = command_tx.send(PlayerCommand::VolumeDown);
                     }
-                    Keycode::Tab => {
+                    Keycode::Tab | Keycode::O => {
                         state.show_hud_text = !state.show_hud_text;
                         state.force_refresh = true;
                         log::info!(
-                            "[按键] Tab (屏幕文字显示): {}",
+                            "[按键] {:?} (屏幕文字显示): {}",
+                            key,
                             if state.show_hud_text {
                                 "开启"
                             } else {
@@ -820,6 +1134,21 @@ pub fn run_event_loop(
                         log::info!("[按键] ] (下一首)");
                         let _ = command_tx.send(PlayerCommand::NextTrack);
                     }
+                    Keycode::Minus
+                    | Keycode::KpMinus
+                    | Keycode::Equals
+                    | Keycode::Plus
+                    | Keycode::KpPlus => {
+                        if let Some(speed) = next_speed_for_key(key, state.speed) {
+                            log::info!(
+                                "[按键] {:?} (倍率): {:.2}x -> {:.2}x",
+                                key,
+                                state.speed,
+                                speed
+                            );
+                            let _ = command_tx.send(PlayerCommand::SetSpeed(speed));
+                        }
+                    }
                     _ => {}
                 },
                 Event::Window { win_event, .. } => {
@@ -858,10 +1187,12 @@ pub fn run_event_loop(
                 }
                 PlayerStatus::Volume(v) => {
                     state.volume_level = v.clamp(0.0, 1.0);
+                    state.volume_osd_until = wall_clock_sec() + VOLUME_OSD_DURATION;
                     state.force_refresh = true;
                 }
                 PlayerStatus::Muted(m) => {
                     state.muted = m;
+                    state.volume_osd_until = wall_clock_sec() + VOLUME_OSD_DURATION;
                     state.force_refresh = true;
                 }
                 PlayerStatus::Seeked => {
@@ -887,6 +1218,13 @@ pub fn run_event_loop(
                     state.current_chapter = chapter_info;
                     state.force_refresh = true;
                 }
+                PlayerStatus::Subtitle(cue) => {
+                    state.subtitles.push(cue);
+                }
+                PlayerStatus::Speed(speed) => {
+                    state.speed = speed;
+                    state.force_refresh = true;
+                }
                 _ => {}
             }
         }
@@ -896,7 +1234,30 @@ pub fn run_event_loop(
             state.frame_queue.push_back(frame);
         }
 
-        // 4. 视频刷新: 决定帧显示时机
+        // 4. 按媒体时钟更新字幕, 显示内容变化时重绘
+        let clock_sec = clock.current_time_us() as f64 / 1_000_000.0;
+        state.subtitles.prune(clock_sec);
+        let subtitle_text = state
+            .subtitles
+            .active_cue(clock_sec)
+            .map(|cue| cue.text.clone());
+        if subtitle_text != state.subtitle_text {
+            state.subtitle_text = subtitle_text;
+            // 播放中由下一帧显示时一并绘制, 避免提前上传队首帧
+            if paused || state.frame_queue.is_empty() {
+                state.force_refresh = true;
+            }
+        }
+
+        // 音量条到期后重绘以将其移除
+        if state.volume_osd_until > 0.0 && wall_clock_sec() >= state.volume_osd_until {
+            state.volume_osd_until = 0.0;
+            if paused || state.frame_queue.is_empty() {
+                state.force_refresh = true;
+            }
+        }
+
+        // 5. 视频刷新: 决定帧显示时机
         let (remaining_time, step_completed) = video_refresh(
             &mut state,
             &clock,
@@ -920,12 +1281,12 @@ pub fn run_event_loop(
             }
         }
 
-        // 5. 单步完成后重新暂停 (对齐 ffplay: if is->step && !is->paused toggle_pause)
+        // 6. 单步完成后重新暂停 (对齐 ffplay: if is->step && !is->paused toggle_pause)
         if step_completed {
             let _ = command_tx.send(PlayerCommand::TogglePause);
         }
 
-        // 6. 精确休眠 (对齐 ffplay av_usleep)
+        // 7. 精确休眠 (对齐 ffplay av_usleep)
         if remaining_time > 0.0 {
             let sleep_us = (remaining_time * 1_000_000.0) as u64;
             std::thread::sleep(std::time::Duration::from_micros(sleep_us));
@@ -938,3 +1299,194 @@ pub fn run_event_loop(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::seek_target_sec;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_select_texture_format_by_pixel_format() {
+        assert_eq!(
+            select_texture_format(PixelFormat::Yuv420p),
+            (PixelFormatEnum::IYUV, None),
+            "YUV420P 应直接上传 IYUV 纹理"
+        );
+        assert_eq!(
+            select_texture_format(PixelFormat::Rgb24),
+            (PixelFormatEnum::RGB24, None)
+        );
+        assert_eq!(
+            select_texture_format(PixelFormat::Bgr24),
+            (PixelFormatEnum::BGR24, None)
+        );
+        for pf in [PixelFormat::Gray8, PixelFormat::Rgba] {
+            assert_eq!(
+                select_texture_format(pf),
+                (PixelFormatEnum::RGB24, Some(PixelFormat::Rgb24)),
+                "{pf} 应先转换为 RGB24"
+            );
+        }
+        for pf in [
+            PixelFormat::Yuv422p,
+            PixelFormat::Yuv444p,
+            PixelFormat::Nv12,
+            PixelFormat::Yuv420p10le,
+        ] {
+            assert_eq!(
+                select_texture_format(pf),
+                (PixelFormatEnum::IYUV, Some(PixelFormat::Yuv420p)),
+                "{pf} 应先转换为 YUV420P"
+            );
+        }
+    }
+
+    #[test]
+    fn test_frame_converter_yuv444p_to_yuv420p() {
+        let mut converter =
+            FrameConverter::new(4, 2, PixelFormat::Yuv444p, PixelFormat::Yuv420p).unwrap();
+        let frame = VideoFrame {
+            width: 4,
+            height: 2,
+            pixel_format: PixelFormat::Yuv444p,
+            data: vec![
+                vec![100u8; 8].into(),
+                vec![90u8; 8].into(),
+                vec![160u8; 8].into(),
+            ],
+            linesize: vec![4, 4, 4],
+            sample_aspect_ratio: Rational::new(1, 1),
+            pts: 0.0,
+        };
+        converter.convert(&frame).expect("YUV444P 转换失败");
+        assert_eq!(converter.linesize, vec![4, 2, 2]);
+        assert!(
+            converter.planes[0].iter().all(|&v| v == 100),
+            "亮度应保持不变"
+        );
+        assert!(
+            converter.planes[1].iter().all(|&v| v == 90),
+            "纯色色度下采样后不变"
+        );
+        assert!(converter.planes[2].iter().all(|&v| v == 160));
+        assert!(converter.matches(4, 2, PixelFormat::Yuv444p, PixelFormat::Yuv420p));
+        assert!(!converter.matches(8, 2, PixelFormat::Yuv444p, PixelFormat::Yuv420p));
+    }
+
+    #[test]
+    fn test_fit_display_rect_applies_sample_aspect_ratio() {
+        let square = Rational::new(1, 1);
+        assert_eq!(
+            fit_display_rect(1280, 720, 640, 360, square),
+            Rect::new(0, 0, 1280, 720),
+            "宽高比一致时铺满窗口"
+        );
+        assert_eq!(
+            fit_display_rect(800, 600, 640, 360, square),
+            Rect::new(0, 75, 800, 450),
+            "16:9 画面在 4:3 窗口上下留黑边"
+        );
+        // 720x480 SAR 32:27 的显示宽高比为 16:9
+        assert_eq!(
+            fit_display_rect(1280, 720, 720, 480, Rational::new(32, 27)),
+            Rect::new(0, 0, 1280, 720),
+            "非方形像素应按显示宽高比拉伸"
+        );
+        assert_eq!(
+            fit_display_rect(1280, 720, 720, 480, Rational::UNDEFINED),
+            Rect::new(100, 0, 1080, 720),
+            "SAR 无效时按方形像素显示"
+        );
+    }
+
+    #[test]
+    fn test_format_clock_time() {
+        assert_eq!(format_clock_time(93.5), "01:33");
+        assert_eq!(format_clock_time(0.0), "00:00");
+        assert_eq!(format_clock_time(-2.0), "00:00", "负数按 0 处理");
+        assert_eq!(format_clock_time(3723.9), "01:02:03", "超过一小时显示小时");
+        assert_eq!(format_progress_text(93.5, 300.0), "01:33/05:00");
+        assert_eq!(
+            format_progress_text(5.0, 0.0),
+            "00:05/--:--",
+            "总时长未知时显示占位符"
+        );
+        for ch in format_progress_text(0.0, 0.0).chars() {
+            assert!(glyph_rows(ch).is_some(), "点阵字体缺少字符 {ch:?}");
+        }
+    }
+
+    #[test]
+    fn test_volume_bar_fill_width() {
+        assert_eq!(volume_bar_fill_width(0.75, false, 200), 150);
+        assert_eq!(
+            volume_bar_fill_width(1.5, false, 200),
+            200,
+            "超过 1.0 时截断"
+        );
+        assert_eq!(volume_bar_fill_width(0.75, true, 200), 0, "静音时不填充");
+    }
+
+    #[test]
+    fn test_seek_delta_for_key_arrows() {
+        assert_eq!(seek_delta_for_key(Keycode::Right, Mod::NOMOD), Some(10.0));
+        assert_eq!(seek_delta_for_key(Keycode::Left, Mod::NOMOD), Some(-10.0));
+        assert_eq!(
+            seek_delta_for_key(Keycode::Right, Mod::LSHIFTMOD),
+            Some(60.0)
+        );
+        assert_eq!(
+            seek_delta_for_key(Keycode::Left, Mod::RSHIFTMOD),
+            Some(-60.0)
+        );
+        assert_eq!(
+            seek_delta_for_key(Keycode::Up, Mod::NOMOD),
+            None,
+            "非左右方向键不应产生 seek"
+        );
+    }
+
+    #[test]
+    fn test_speed_keys_step_through_rates() {
+        assert_eq!(next_speed_for_key(Keycode::Equals, 1.0), Some(1.25));
+        assert_eq!(next_speed_for_key(Keycode::KpPlus, 1.5), Some(2.0));
+        assert_eq!(next_speed_for_key(Keycode::Minus, 1.0), Some(0.75));
+        assert_eq!(next_speed_for_key(Keycode::KpMinus, 0.75), Some(0.5));
+        assert_eq!(next_speed_for_key(Keycode::Equals, 2.0), None, "已是最高档");
+        assert_eq!(next_speed_for_key(Keycode::Minus, 0.5), None, "已是最低档");
+        assert_eq!(next_speed_for_key(Keycode::M, 1.0), None);
+        assert_eq!(format_speed_text(1.0), None, "原速不显示倍率");
+        assert_eq!(format_speed_text(1.5).as_deref(), Some("SPEED 1.50X"));
+        for ch in "SPEED 1.50X".chars() {
+            assert!(glyph_rows(ch).is_some(), "点阵字体缺少字符 {ch:?}");
+        }
+
+        let (command_tx, command_rx) = mpsc::channel();
+        let speed = next_speed_for_key(Keycode::Equals, 1.75).unwrap();
+        command_tx.send(PlayerCommand::SetSpeed(speed)).unwrap();
+        let Ok(PlayerCommand::SetSpeed(received)) = command_rx.try_recv() else {
+            panic!("播放线程应收到 SetSpeed 命令");
+        };
+        assert_eq!(received, 2.0);
+    }
+
+    #[test]
+    fn test_seek_command_plumbing() {
+        let (command_tx, command_rx) = mpsc::channel();
+        let delta = seek_delta_for_key(Keycode::Left, Mod::LSHIFTMOD).unwrap();
+        command_tx.send(PlayerCommand::Seek(delta)).unwrap();
+        let Ok(PlayerCommand::Seek(offset)) = command_rx.try_recv() else {
+            panic!("播放线程应收到 Seek 命令");
+        };
+        // 播放线程以当前时钟为基准计算目标, 并受可 seek 范围约束
+        assert_eq!(seek_target_sec(75.0, offset, 120.0, 119.9), 15.0);
+        assert_eq!(seek_target_sec(30.0, offset, 120.0, 119.9), 0.0);
+        assert_eq!(seek_target_sec(100.0, 60.0, 120.0, 119.9), 119.9);
+        assert_eq!(
+            seek_target_sec(100.0, 60.0, 0.0, 0.0),
+            160.0,
+            "总时长未知时只约束下限"
+        );
+    }
+}
@@ -7,6 +7,9 @@
 //! - 视频显示 (通过 SDL2 YUV 纹理, GPU 硬件色彩转换)
 //! - A/V 同步 (基于音频时钟, ffplay 风格的 video_refresh 状态机)
 //! - HTTP/HTTPS URL 播放 (通过 ureq 下载)
+//! - SRT/ASS 文本字幕叠加显示
+//! - AC3/DTS S/PDIF 直通 (`--passthrough`, IEC 61937 封装)
+//! - 音频设备选择 (`--audio-device`, `--list-audio-devices`), S16/F32 输出协商
 //! - 基本控制: 空格/P 暂停, F/双击 全屏, S 单步, ESC/Q 退出
 
 mod audio;
@@ -14,10 +17,13 @@ mod clock;
 mod gui;
 mod logging;
 mod player;
+mod spdif;
+mod subtitle;
 
-use crate::audio::AudioOutput;
+use crate::audio::{AudioOutput, PassthroughAudioOutput, list_playback_devices};
 use crate::clock::MediaClock;
 use crate::player::{Player, PlayerChannels, PlayerConfig};
+use crate::spdif::SpdifCodec;
 use clap::Parser;
 use log::info;
 use std::sync::mpsc;
@@ -27,7 +33,8 @@ use std::sync::mpsc;
 #[command(name = "tao-play", about = "Tao 多媒体播放器")]
 struct Args {
     /// 输入文件路径或 URL (支持 http/https)
-    input: String,
+    #[arg(required_unless_present = "list_audio_devices")]
+    input: Option<String>,
 
     /// 是否禁用视频
     #[arg(long = "novideo", help = "禁用视频播放")]
@@ -37,6 +44,22 @@ struct Args {
     #[arg(long = "noaudio", help = "禁用音频播放")]
     no_audio: bool,
 
+    /// 是否禁用字幕
+    #[arg(long = "nosubtitle", help = "禁用字幕显示")]
+    no_subtitle: bool,
+
+    /// AC3/DTS 音频以 S/PDIF 直通方式输出, 由外接功放解码
+    #[arg(long, help = "AC3/DTS 音频经 IEC 61937 封装直通输出 (S/PDIF)")]
+    passthrough: bool,
+
+    /// 音频输出设备 (名称或 --list-audio-devices 列出的索引)
+    #[arg(long = "audio-device", value_name = "NAME|INDEX")]
+    audio_device: Option<String>,
+
+    /// 列出可用的音频输出设备后退出
+    #[arg(long = "list-audio-devices", help = "列出音频输出设备")]
+    list_audio_devices: bool,
+
     /// 音量 (0-100, 默认 100)
     #[arg(long, default_value = "100")]
     volume: u32,
@@ -54,14 +77,25 @@ fn main() -> Result<(), String> {
     let args = Args::parse();
     logging::init("tao-play", args.verbose);
 
-    info!("tao-play: 打开 {}", args.input);
+    if args.list_audio_devices {
+        let sdl_context = sdl2::init()?;
+        let audio_subsystem = sdl_context.audio()?;
+        for (index, name) in list_playback_devices(&audio_subsystem).iter().enumerate() {
+            println!("{}: {}", index, name);
+        }
+        return Ok(());
+    }
+    let input = args.input.clone().unwrap_or_default();
+
+    info!("tao-play: 打开 {}", input);
 
     let initial_volume = args.volume.min(100) as f32 / 100.0;
 
     let config = PlayerConfig {
-        input_path: args.input.clone(),
+        input_path: input.clone(),
         no_video: args.no_video,
         no_audio: args.no_audio,
+        no_subtitle: args.no_subtitle,
         volume: initial_volume,
     };
 
@@ -103,10 +137,10 @@ fn main() -> Result<(), String> {
     );
 
     // ── 窗口标题: 使用输入文件名 (对齐 ffplay) ──
-    let window_title = std::path::Path::new(&args.input)
+    let window_title = std::path::Path::new(&input)
         .file_name()
         .and_then(|n| n.to_str())
-        .unwrap_or(&args.input);
+        .unwrap_or(&input);
 
     // ── 渲染器: 硬件加速 + VSync (对齐 ffplay) ──
     // ffplay: SDL_RENDERER_ACCELERATED | SDL_RENDERER_PRESENTVSYNC, 失败回退到 0
@@ -141,8 +175,41 @@ fn main() -> Result<(), String> {
     let clock = MediaClock::new();
 
     // ── 创建 SDL2 音频输出 ──
-    let (_audio_output, audio_sender) = if let Some(ai) = &audio_info {
-        match AudioOutput::new(&audio_subsystem, ai.sample_rate, ai.channels, clock.clone()) {
+    let passthrough_codec = match &audio_info {
+        Some(ai) if args.passthrough => {
+            let codec = SpdifCodec::from_codec_id(ai.codec_id);
+            if codec.is_none() {
+                log::warn!(
+                    "--passthrough 仅支持 AC3/DTS, 音频流 {} 按 PCM 输出",
+                    ai.codec_id
+                );
+            }
+            codec
+        }
+        _ => None,
+    };
+    let mut _passthrough_output = None;
+    let (_audio_output, audio_sender) = if let (Some(ai), Some(codec)) =
+        (&audio_info, passthrough_codec)
+    {
+        match PassthroughAudioOutput::new(&audio_subsystem, codec, ai.sample_rate, clock.clone()) {
+            Ok((out, sender)) => {
+                _passthrough_output = Some(out);
+                (None, Some(sender))
+            }
+            Err(e) => {
+                log::warn!("创建 S/PDIF 直通输出失败: {}", e);
+                (None, None)
+            }
+        }
+    } else if let Some(ai) = &audio_info {
+        match AudioOutput::new(
+            &audio_subsystem,
+            args.audio_device.as_deref(),
+            ai.sample_rate,
+            ai.channels,
+            clock.clone(),
+        ) {
             Ok((out, sender)) => (Some(out), Some(sender)),
             Err(e) => {
                 log::warn!("创建音频输出失败: {}", e);
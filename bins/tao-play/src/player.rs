@@ -12,19 +12,24 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use tao_codec::CodecId;
-use tao_codec::codec_parameters::{AudioCodecParams, CodecParameters, CodecParamsType};
 use tao_codec::frame::Frame;
-use tao_core::{MediaType, PixelFormat, SampleFormat, TaoError};
+use tao_codec::frame_pool::FrameBuf;
+use tao_core::{ChannelLayout, MediaType, PixelFormat, Rational, SampleFormat, TaoError};
+use tao_filter::Filter;
+use tao_filter::filters::atempo::{ATEMPO_MAX, ATEMPO_MIN, AtempoFilter};
 use tao_format::demuxer::{DemuxerChapter, SeekFlags};
 use tao_format::io::IoContext;
 use tao_format::registry::FormatRegistry;
 use tao_format::stream::{Stream, StreamParams};
 
-use crate::audio::{AudioChunk, AudioSender};
+use crate::audio::{AudioChunk, AudioSender, SpdifBurst};
 use crate::clock::MediaClock;
+use crate::spdif::{SpdifCodec, encapsulate_iec61937};
+use crate::subtitle::{SubtitleCue, decode_subtitle_packet, is_text_subtitle};
 
 /// 音频流参数 (用于在主线程创建 SDL2 音频输出)
 pub struct AudioInfo {
+    pub codec_id: CodecId,
     pub sample_rate: u32,
     pub channels: u32,
 }
@@ -37,17 +42,19 @@ type PrepareResult = (
     Box<dyn tao_format::demuxer::Demuxer>,
 );
 
-/// 视频帧数据 (YUV420p 平面格式, 由 SDL2 GPU 做色彩转换)
+/// 视频帧数据 (解码器输出的原始像素格式, 由 GUI 线程选择纹理格式或转换后上传)
 #[derive(Clone)]
 pub struct VideoFrame {
     pub width: u32,
     pub height: u32,
-    pub y_data: Vec<u8>,
-    pub u_data: Vec<u8>,
-    pub v_data: Vec<u8>,
-    pub y_stride: usize,
-    pub u_stride: usize,
-    pub v_stride: usize,
+    /// 像素格式
+    pub pixel_format: PixelFormat,
+    /// 各平面数据
+    pub data: Vec<FrameBuf>,
+    /// 各平面行字节数
+    pub linesize: Vec<usize>,
+    /// 样本宽高比 (SAR), 用于按显示宽高比留黑边
+    pub sample_aspect_ratio: Rational,
     /// PTS (秒) - 用于 GUI 线程的 video_refresh 同步
     pub pts: f64,
 }
@@ -64,6 +71,8 @@ pub enum PlayerCommand {
     VolumeUp,
     VolumeDown,
     ToggleMute,
+    /// 设置播放倍率 (0.5 ~ 2.0), 音频经 atempo 变速不变调
+    SetSpeed(f32),
     Stop,
 }
 
@@ -79,6 +88,10 @@ pub enum PlayerStatus {
     Seeked,
     /// 当前章节信息: (章节索引, 标题)
     CurrentChapter(Option<(usize, String)>),
+    /// 解码出的一条字幕, 由 GUI 线程按时钟决定显示时机
+    Subtitle(SubtitleCue),
+    /// 当前播放倍率
+    Speed(f32),
     End,
     Error(String),
 }
@@ -88,6 +101,7 @@ pub struct PlayerConfig {
     pub input_path: String,
     pub no_video: bool,
     pub no_audio: bool,
+    pub no_subtitle: bool,
     pub volume: f32,
 }
 
@@ -161,6 +175,7 @@ impl Player {
                 .and_then(|stream| {
                     if let StreamParams::Audio(a) = &stream.params {
                         Some(AudioInfo {
+                            codec_id: stream.codec_id,
                             sample_rate: a.sample_rate,
                             channels: a.channel_layout.channels,
                         })
@@ -287,12 +302,43 @@ impl Player {
         if audio_stream.is_none() && video_stream.is_none() {
             return Err("没有找到可播放的音视频流".into());
         }
+        // 字幕仅在有视频画面时显示
+        let subtitle_stream = if !self.config.no_subtitle && video_stream.is_some() {
+            streams
+                .iter()
+                .find(|s| s.media_type == MediaType::Subtitle && is_text_subtitle(s.codec_id))
+        } else {
+            None
+        };
+        if let Some(s) = subtitle_stream {
+            info!("字幕流: #{} ({})", s.index, s.codec_id);
+        }
 
         let audio_stream_idx = audio_stream.map(|s| s.index);
         let video_stream_idx = video_stream.map(|s| s.index);
 
-        let mut audio_decoder = audio_stream.and_then(create_decoder);
+        // S/PDIF 直通: 压缩帧不经解码直接封装输出
+        let passthrough_codec = audio_sender
+            .as_ref()
+            .filter(|a| a.is_passthrough())
+            .and(audio_stream)
+            .and_then(|s| SpdifCodec::from_codec_id(s.codec_id));
+        if let Some(codec) = passthrough_codec {
+            info!("音频 S/PDIF 直通: {:?}", codec);
+        }
+
+        let mut audio_decoder = if passthrough_codec.is_some() {
+            None
+        } else {
+            audio_stream.and_then(create_decoder)
+        };
         let mut video_decoder = video_stream.and_then(create_decoder);
+        let video_stream_sar = video_stream
+            .and_then(|s| match &s.params {
+                StreamParams::Video(v) => Some(v.sample_aspect_ratio),
+                _ => None,
+            })
+            .unwrap_or(Rational::new(1, 1));
         let audio_nominal_bits = audio_stream.and_then(resolve_audio_nominal_bits);
 
         // 音频时钟: 用解码输出的累计采样数计算, 不依赖 demuxer PTS
@@ -314,6 +360,12 @@ impl Player {
         let mut frames_sent = 0u64;
         let mut current_volume = (self.config.volume * 100.0) as u32;
         let mut muted = false;
+        // 播放倍率与对应的音频变速滤镜 (原速时为 None)
+        let mut playback_speed = 1.0f32;
+        let mut tempo_filter: Option<AtempoFilter> = None;
+        // 仅视频模式的媒体时间 (按倍率累计) 及上次累计时刻
+        let mut video_only_clock_us: i64 = 0;
+        let mut video_only_tick = Instant::now();
         // seek 后需要解码至少一帧 (即使暂停)
         let mut seek_flush_pending = false;
         // seek 后立即 EOF 的重试标记 (防止无限循环)
@@ -430,6 +482,7 @@ impl Player {
                                         if let Some(a) = &audio_sender {
                                             a.flush();
                                         }
+                                        tempo_filter = build_tempo_filter(playback_speed);
                                         let target_us = (target_sec * 1_000_000.0) as i64;
                                         clock.seek_reset(target_us);
                                         audio_cum_samples =
@@ -492,6 +545,7 @@ impl Player {
                                         if let Some(a) = &audio_sender {
                                             a.flush();
                                         }
+                                        tempo_filter = build_tempo_filter(playback_speed);
                                         let target_us = (target_sec * 1_000_000.0) as i64;
                                         clock.seek_reset(target_us);
                                         audio_cum_samples =
@@ -511,11 +565,12 @@ impl Player {
                         let is_paused = clock.is_paused();
 
                         // 计算原始目标时间
-                        let mut target_sec = if total_duration_sec > 0.0 {
-                            (current_sec + offset).clamp(0.0, max_seekable_sec)
-                        } else {
-                            (current_sec + offset).max(0.0)
-                        };
+                        let mut target_sec = seek_target_sec(
+                            current_sec,
+                            offset,
+                            total_duration_sec,
+                            max_seekable_sec,
+                        );
 
                         // 智能章节跳转: 如果启用了章节且正在跨章节 seek
                         if !chapters.is_empty() && offset != 0.0 {
@@ -593,6 +648,7 @@ impl Player {
                                         if let Some(a) = &audio_sender {
                                             a.flush();
                                         }
+                                        tempo_filter = build_tempo_filter(playback_speed);
                                         let target_us = (target_sec * 1_000_000.0) as i64;
                                         clock.seek_reset(target_us);
                                         // 重置音频采样计数器
@@ -645,6 +701,17 @@ impl Player {
                             .ok();
                         status_tx.send(PlayerStatus::Muted(muted)).ok();
                     }
+                    PlayerCommand::SetSpeed(speed) => {
+                        if passthrough_codec.is_some() {
+                            warn!("[控制] S/PDIF 直通模式不支持倍速播放");
+                            continue;
+                        }
+                        playback_speed = clamp_speed(speed);
+                        clock.set_speed(playback_speed);
+                        tempo_filter = build_tempo_filter(playback_speed);
+                        info!("[控制] 播放倍率: {:.2}x", playback_speed);
+                        status_tx.send(PlayerStatus::Speed(playback_speed)).ok();
+                    }
                     PlayerCommand::Stop => {
                         info!("停止播放");
                         break 'main;
@@ -695,6 +762,45 @@ impl Player {
                     Ok(packet) => {
                         let stream_idx = packet.stream_index;
 
+                        // 字幕: 解码为文本后交给 GUI 线程按时钟显示
+                        if let Some(stream) = subtitle_stream.filter(|s| s.index == stream_idx) {
+                            if let Some(cue) = decode_subtitle_packet(stream, &packet) {
+                                debug!("[字幕] {:.3}s ~ {:.3}s: {}", cue.start, cue.end, cue.text);
+                                status_tx.send(PlayerStatus::Subtitle(cue)).ok();
+                            }
+                        }
+
+                        // S/PDIF 直通: 每个压缩帧封装为一个 IEC 61937 突发
+                        if let (Some(codec), Some(out)) = (passthrough_codec, &audio_sender) {
+                            if Some(stream_idx) == audio_stream_idx {
+                                match encapsulate_iec61937(codec, &packet.data) {
+                                    Ok(samples) => {
+                                        let nb = (samples.len() / 2) as u64;
+                                        let chunk_pts_us = (audio_cum_samples as f64
+                                            / audio_sample_rate as f64
+                                            * 1_000_000.0)
+                                            as i64;
+                                        audio_cum_samples += nb;
+                                        if seek_skip_until.is_none() {
+                                            let burst = SpdifBurst {
+                                                samples,
+                                                pts_us: chunk_pts_us,
+                                            };
+                                            if out.send_burst(burst).is_err() {
+                                                break 'main;
+                                            }
+                                            if seek_flush_pending && video_stream.is_none() {
+                                                status_tx.send(PlayerStatus::Seeked).ok();
+                                                clock.confirm_seek();
+                                                seek_flush_pending = false;
+                                            }
+                                        }
+                                    }
+                                    Err(e) => debug!("S/PDIF 封装失败, 丢弃数据包: {}", e),
+                                }
+                            }
+                        }
+
                         // 解码音频 (seek_pending 期间时钟更新已被阻止, 无需跳过音频)
                         if Some(stream_idx) == audio_stream_idx {
                             if let Some(dec) = &mut audio_decoder {
@@ -712,14 +818,25 @@ impl Player {
                                                 continue;
                                             }
                                             if let Some(out) = &audio_sender {
-                                                let samples =
+                                                let mut samples =
                                                     extract_f32_samples(af, audio_nominal_bits);
-                                                let chunk = AudioChunk {
-                                                    samples,
-                                                    pts_us: chunk_pts_us,
-                                                };
-                                                if out.send(chunk).is_err() {
-                                                    break 'main;
+                                                if let Some(filter) = &mut tempo_filter {
+                                                    samples = apply_tempo(
+                                                        filter,
+                                                        samples,
+                                                        af.sample_rate,
+                                                        af.channel_layout,
+                                                    );
+                                                }
+                                                // 变速滤镜可能暂存输入, 无输出时不发送
+                                                if !samples.is_empty() {
+                                                    let chunk = AudioChunk {
+                                                        samples,
+                                                        pts_us: chunk_pts_us,
+                                                    };
+                                                    if out.send(chunk).is_err() {
+                                                        break 'main;
+                                                    }
                                                 }
                                             }
                                             // 仅音频流 seek: 首个音频块即可确认 seek 完成.
@@ -769,7 +886,8 @@ impl Player {
                                                 );
                                             }
 
-                                            let display_frame = build_yuv_frame(vf, pts_us);
+                                            let display_frame =
+                                                build_display_frame(vf, pts_us, video_stream_sar);
                                             if seek_flush_pending {
                                                 // 通知 GUI 清空旧帧 (此时首帧已就绪)
                                                 status_tx.send(PlayerStatus::Seeked).ok();
@@ -818,6 +936,7 @@ impl Player {
                                         if let Some(a) = &audio_sender {
                                             a.flush();
                                         }
+                                        tempo_filter = build_tempo_filter(playback_speed);
                                         let retry_us = (retry_sec * 1_000_000.0) as i64;
                                         clock.seek_reset(retry_us);
                                         audio_cum_samples =
@@ -849,10 +968,13 @@ impl Player {
                 }
             }
 
-            // 仅视频模式: 用系统时钟驱动
+            // 仅视频模式: 用系统时钟驱动, 经过时间按倍率累计
             if audio_sender.is_none() && !eof {
-                let elapsed_us = start_time.elapsed().as_micros() as i64;
-                clock.update_audio_pts(elapsed_us);
+                let now = Instant::now();
+                let elapsed_us = now.duration_since(video_only_tick).as_micros() as f64;
+                video_only_clock_us += (elapsed_us * f64::from(playback_speed)) as i64;
+                video_only_tick = now;
+                clock.update_audio_pts(video_only_clock_us);
             }
 
             // 仅音频播放: demux 可能提前到 EOF, 需等时钟接近总时长再结束.
@@ -923,11 +1045,12 @@ impl Player {
                             } else {
                                 (clock.current_time_us() as f64 / 1_000_000.0).max(0.0)
                             };
-                            let target_sec = if total_duration_sec > 0.0 {
-                                (base_sec + offset).clamp(0.0, max_seekable_sec)
-                            } else {
-                                (base_sec + offset).max(0.0)
-                            };
+                            let target_sec = seek_target_sec(
+                                base_sec,
+                                offset,
+                                total_duration_sec,
+                                max_seekable_sec,
+                            );
 
                             // 前进 seek 到末尾: 无意义, 忽略
                             if offset > 0.0 && target_sec >= max_seekable_sec {
@@ -961,6 +1084,7 @@ impl Player {
                                             if let Some(a) = &audio_sender {
                                                 a.flush();
                                             }
+                                            tempo_filter = build_tempo_filter(playback_speed);
                                             // 恢复时钟
                                             clock.set_paused(false);
                                             let target_us = (target_sec * 1_000_000.0) as i64;
@@ -1011,6 +1135,55 @@ impl Player {
 
 // ── 辅助函数 ─────────────────────────────────────────────────────────────
 
+/// 将倍率限制在 atempo 支持的范围内
+fn clamp_speed(speed: f32) -> f32 {
+    if speed.is_finite() {
+        speed.clamp(ATEMPO_MIN as f32, ATEMPO_MAX as f32)
+    } else {
+        1.0
+    }
+}
+
+/// 按倍率创建音频变速滤镜, 原速时不需要滤镜
+fn build_tempo_filter(speed: f32) -> Option<AtempoFilter> {
+    if speed == 1.0 {
+        return None;
+    }
+    match AtempoFilter::new(f64::from(speed)) {
+        Ok(filter) => Some(filter),
+        Err(e) => {
+            warn!("创建 atempo 滤镜失败: {}", e);
+            None
+        }
+    }
+}
+
+/// 将交错 f32 采样送入变速滤镜, 返回已产出的变速后采样
+fn apply_tempo(
+    filter: &mut AtempoFilter,
+    samples: Vec<f32>,
+    sample_rate: u32,
+    channel_layout: ChannelLayout,
+) -> Vec<f32> {
+    let channels = channel_layout.channels.max(1) as usize;
+    let mut frame = tao_codec::frame::AudioFrame::new(
+        (samples.len() / channels) as u32,
+        sample_rate,
+        SampleFormat::F32,
+        channel_layout,
+    );
+    frame.data = vec![samples.iter().flat_map(|s| s.to_le_bytes()).collect()];
+    if let Err(e) = filter.send_frame(&Frame::Audio(frame)) {
+        warn!("atempo 处理失败, 按原速输出: {}", e);
+        return samples;
+    }
+    let mut out = Vec::new();
+    while let Ok(Frame::Audio(af)) = filter.receive_frame() {
+        out.extend(extract_f32_samples(&af, None));
+    }
+    out
+}
+
 /// 将 PTS 转换为微秒
 fn pts_to_us(pts: i64, num: i32, den: i32) -> i64 {
     if den == 0 {
@@ -1030,6 +1203,10 @@ fn extract_f32_samples(af: &tao_codec::frame::AudioFrame, nominal_bits: Option<u
             .chunks_exact(2)
             .map(|c| i16::from_le_bytes([c[0], c[1]]) as f32 / 32768.0)
             .collect(),
+        SampleFormat::S24 => af.data[0]
+            .chunks_exact(4)
+            .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f32 / 8_388_608.0)
+            .collect(),
         SampleFormat::S32 => {
             let scale = match nominal_bits {
                 Some(bits) if (1..32).contains(&bits) => (1u64 << (bits - 1)) as f32,
@@ -1091,7 +1268,7 @@ mod tests {
         let mut af = AudioFrame::new(1, 44_100, SampleFormat::S32, ChannelLayout::MONO);
         // 24-bit 满幅正值 (sign-extended 到 i32).
         let sample = 8_388_607i32;
-        af.data[0] = sample.to_le_bytes().to_vec();
+        af.data[0] = sample.to_le_bytes().to_vec().into();
         let out = extract_f32_samples(&af, Some(24));
         assert_eq!(out.len(), 1);
         let expected = sample as f32 / 8_388_608.0;
@@ -1102,40 +1279,95 @@ mod tests {
             expected
         );
     }
-}
 
-/// 从解码后的视频帧构建 YUV420p 帧数据
-fn build_yuv_frame(vf: &tao_codec::frame::VideoFrame, pts_us: i64) -> VideoFrame {
-    let w = vf.width as usize;
-    let h = vf.height as usize;
-
-    match vf.pixel_format {
-        PixelFormat::Yuv420p => VideoFrame {
-            width: vf.width,
-            height: vf.height,
-            y_data: vf.data[0].clone(),
-            u_data: vf.data[1].clone(),
-            v_data: vf.data[2].clone(),
-            y_stride: vf.linesize[0],
-            u_stride: vf.linesize[1],
-            v_stride: vf.linesize[2],
-            pts: pts_us as f64 / 1_000_000.0,
-        },
-        _ => {
-            let uv_w = w.div_ceil(2);
-            let uv_h = h.div_ceil(2);
-            VideoFrame {
-                width: vf.width,
-                height: vf.height,
-                y_data: vec![128u8; w * h],
-                u_data: vec![128u8; uv_w * uv_h],
-                v_data: vec![128u8; uv_w * uv_h],
-                y_stride: w,
-                u_stride: uv_w,
-                v_stride: uv_w,
-                pts: pts_us as f64 / 1_000_000.0,
-            }
+    #[test]
+    fn test_display_sample_aspect_ratio_prefers_non_square() {
+        let square = Rational::new(1, 1);
+        let anamorphic = Rational::new(32, 27);
+        assert_eq!(
+            display_sample_aspect_ratio(square, anamorphic),
+            anamorphic,
+            "解码帧为 1:1 时应使用流参数中的 SAR"
+        );
+        assert_eq!(
+            display_sample_aspect_ratio(Rational::new(4, 3), anamorphic),
+            Rational::new(4, 3),
+            "解码帧携带非 1:1 SAR 时优先使用"
+        );
+        assert_eq!(
+            display_sample_aspect_ratio(Rational::new(0, 1), Rational::UNDEFINED),
+            square,
+            "SAR 均无效时按 1:1 显示"
+        );
+    }
+
+    #[test]
+    fn test_set_speed_wires_clock_and_atempo() {
+        let clock = MediaClock::new();
+        let speed = clamp_speed(2.0);
+        clock.set_speed(speed);
+        let filter = build_tempo_filter(speed).expect("2 倍速应创建 atempo 滤镜");
+        assert_eq!(
+            f64::from(clock.speed()),
+            filter.tempo(),
+            "atempo 倍率应与时钟一致"
+        );
+
+        assert!(build_tempo_filter(1.0).is_none(), "原速不需要 atempo");
+        assert_eq!(clamp_speed(4.0), 2.0, "倍率应限制在 atempo 支持范围内");
+        assert_eq!(clamp_speed(0.1), 0.5);
+        assert_eq!(clamp_speed(f32::NAN), 1.0);
+
+        // 变速后输出时长约为输入除以倍率 (滤镜暂存窗口长度的输入)
+        let mut filter = build_tempo_filter(speed).unwrap();
+        let mut produced = 0;
+        for _ in 0..10 {
+            let out = apply_tempo(
+                &mut filter,
+                vec![0.1; 4410 * 2],
+                44_100,
+                ChannelLayout::STEREO,
+            );
+            produced += out.len() / 2;
         }
+        assert!(
+            (20_000..=22_050).contains(&produced),
+            "44100 个采样 2 倍速后应产出约 22050 个采样, 实际 {produced}"
+        );
+    }
+}
+
+/// 从解码后的视频帧构建显示帧
+///
+/// 平面数据按解码器输出格式原样传递, 由 GUI 线程按像素格式选择纹理或转换.
+fn build_display_frame(
+    vf: &tao_codec::frame::VideoFrame,
+    pts_us: i64,
+    stream_sar: Rational,
+) -> VideoFrame {
+    VideoFrame {
+        width: vf.width,
+        height: vf.height,
+        pixel_format: vf.pixel_format,
+        data: vf.data.clone(),
+        linesize: vf.linesize.clone(),
+        sample_aspect_ratio: display_sample_aspect_ratio(vf.sample_aspect_ratio, stream_sar),
+        pts: pts_us as f64 / 1_000_000.0,
+    }
+}
+
+/// 选择显示用的 SAR: 优先取解码帧中非 1:1 的有效值, 其次取流参数, 均无效时为 1:1
+///
+/// 多数解码器输出固定为 1:1, 此时以容器/码流头部给出的 SAR 为准.
+fn display_sample_aspect_ratio(frame_sar: Rational, stream_sar: Rational) -> Rational {
+    let usable = |sar: Rational| sar.num > 0 && sar.den > 0;
+    let square = Rational::new(1, 1);
+    if usable(frame_sar) && frame_sar != square {
+        frame_sar
+    } else if usable(stream_sar) {
+        stream_sar
+    } else {
+        square
     }
 }
 
@@ -1162,16 +1394,8 @@ fn is_url(path: &str) -> bool {
 fn create_decoder(stream: &Stream) -> Option<Box<dyn tao_codec::decoder::Decoder>> {
     let mut codec_registry = tao_codec::registry::CodecRegistry::new();
     tao_codec::register_all(&mut codec_registry);
-    match codec_registry.create_decoder(stream.codec_id) {
-        Ok(mut dec) => {
-            let params = build_codec_params(stream);
-            if let Err(e) = dec.open(&params) {
-                warn!("打开解码器失败: {}", e);
-                None
-            } else {
-                Some(dec)
-            }
-        }
+    match stream.open_decoder(&codec_registry) {
+        Ok(dec) => Some(dec),
         Err(e) => {
             warn!("创建解码器失败: {}", e);
             None
@@ -1179,38 +1403,19 @@ fn create_decoder(stream: &Stream) -> Option<Box<dyn tao_codec::decoder::Decoder
     }
 }
 
-/// 从流信息构建编解码器参数
-fn build_codec_params(stream: &Stream) -> CodecParameters {
-    match &stream.params {
-        StreamParams::Audio(a) => CodecParameters {
-            codec_id: stream.codec_id,
-            bit_rate: a.bit_rate,
-            extra_data: stream.extra_data.clone(),
-            params: CodecParamsType::Audio(AudioCodecParams {
-                sample_rate: a.sample_rate,
-                channel_layout: a.channel_layout,
-                sample_format: a.sample_format,
-                frame_size: a.frame_size,
-            }),
-        },
-        StreamParams::Video(v) => CodecParameters {
-            codec_id: stream.codec_id,
-            bit_rate: v.bit_rate,
-            extra_data: stream.extra_data.clone(),
-            params: CodecParamsType::Video(tao_codec::codec_parameters::VideoCodecParams {
-                width: v.width,
-                height: v.height,
-                pixel_format: v.pixel_format,
-                frame_rate: v.frame_rate,
-                sample_aspect_ratio: v.sample_aspect_ratio,
-            }),
-        },
-        _ => CodecParameters {
-            codec_id: stream.codec_id,
-            bit_rate: 0,
-            extra_data: stream.extra_data.clone(),
-            params: CodecParamsType::None,
-        },
+/// 计算相对 seek 的目标时间 (秒)
+///
+/// 总时长已知时约束在 `[0, max_seekable_sec]`, 否则只约束下限.
+pub(crate) fn seek_target_sec(
+    base_sec: f64,
+    offset: f64,
+    total_duration_sec: f64,
+    max_seekable_sec: f64,
+) -> f64 {
+    if total_duration_sec > 0.0 {
+        (base_sec + offset).clamp(0.0, max_seekable_sec)
+    } else {
+        (base_sec + offset).max(0.0)
     }
 }
 
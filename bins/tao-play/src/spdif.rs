@@ -0,0 +1,198 @@
+//! S/PDIF 直通 (IEC 61937) 封装.
+//!
+//! 将 AC3/DTS 压缩帧封装为 IEC 61937 数据突发, 以 16 位立体声 PCM 的形式
+//! 交给音频设备原样输出, 由外接功放解码.
+//!
+//! 突发结构 (每个字为一个 16 位采样):
+//! - Pa/Pb: 同步字 `0xF872` / `0x4E1F`
+//! - Pc: 数据类型 (AC3 附带 bsmod)
+//! - Pd: 载荷长度 (位)
+//! - 载荷: 压缩帧按 16 位大端字排列
+//! - 零填充至突发长度 (每个编码帧的采样数 × 2 声道)
+
+use tao_codec::CodecId;
+
+/// IEC 61937 同步字 Pa
+const IEC61937_SYNC_PA: u16 = 0xF872;
+/// IEC 61937 同步字 Pb
+const IEC61937_SYNC_PB: u16 = 0x4E1F;
+/// 突发头长度 (字): Pa/Pb/Pc/Pd
+const BURST_HEADER_WORDS: usize = 4;
+
+/// IEC 61937 数据类型: AC3
+const DATA_TYPE_AC3: u16 = 0x01;
+/// IEC 61937 数据类型: DTS Type I (512 采样/帧)
+const DATA_TYPE_DTS1: u16 = 0x0B;
+/// IEC 61937 数据类型: DTS Type II (1024 采样/帧)
+const DATA_TYPE_DTS2: u16 = 0x0C;
+/// IEC 61937 数据类型: DTS Type III (2048 采样/帧)
+const DATA_TYPE_DTS3: u16 = 0x0D;
+
+/// AC3 每帧采样数
+const AC3_FRAME_SAMPLES: usize = 1536;
+
+/// S/PDIF 输出声道数 (固定为立体声)
+pub const SPDIF_CHANNELS: u32 = 2;
+
+/// 支持直通的压缩音频格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpdifCodec {
+    Ac3,
+    Dts,
+}
+
+impl SpdifCodec {
+    /// 由编解码器 ID 判断是否支持直通
+    pub fn from_codec_id(codec_id: CodecId) -> Option<Self> {
+        match codec_id {
+            CodecId::Ac3 => Some(Self::Ac3),
+            CodecId::Dts => Some(Self::Dts),
+            _ => None,
+        }
+    }
+}
+
+/// 解析压缩帧, 返回 (Pc 数据类型, 每帧采样数)
+fn burst_params(codec: SpdifCodec, frame: &[u8]) -> Result<(u16, usize), String> {
+    match codec {
+        SpdifCodec::Ac3 => {
+            if frame.len() < 6 || frame[0] != 0x0B || frame[1] != 0x77 {
+                return Err("AC3 帧缺少同步字 0x0B77".into());
+            }
+            let bsmod = u16::from(frame[5] & 0x07);
+            Ok((DATA_TYPE_AC3 | (bsmod << 8), AC3_FRAME_SAMPLES))
+        }
+        SpdifCodec::Dts => {
+            // 仅支持 16 位大端核心帧 (同步字 0x7FFE8001)
+            if frame.len() < 6 || frame[..4] != [0x7F, 0xFE, 0x80, 0x01] {
+                return Err("DTS 帧缺少大端同步字 0x7FFE8001".into());
+            }
+            let nblks = (usize::from(frame[4] & 0x01) << 6) | usize::from(frame[5] >> 2);
+            let samples = (nblks + 1) * 32;
+            let data_type = match samples {
+                512 => DATA_TYPE_DTS1,
+                1024 => DATA_TYPE_DTS2,
+                2048 => DATA_TYPE_DTS3,
+                _ => return Err(format!("DTS 每帧 {samples} 个采样, 无法直通")),
+            };
+            Ok((data_type, samples))
+        }
+    }
+}
+
+/// 将一个压缩帧封装为 IEC 61937 突发, 返回交错立体声 16 位采样
+///
+/// 输出采样数 (每声道) 等于该帧的解码采样数, 因此突发时长与解码后的 PCM 一致.
+pub fn encapsulate_iec61937(codec: SpdifCodec, frame: &[u8]) -> Result<Vec<i16>, String> {
+    let (data_type, frame_samples) = burst_params(codec, frame)?;
+    let burst_words = frame_samples * SPDIF_CHANNELS as usize;
+    let payload_words = frame.len().div_ceil(2);
+    if BURST_HEADER_WORDS + payload_words > burst_words {
+        return Err(format!(
+            "压缩帧 {} 字节超出突发容量 {} 字节",
+            frame.len(),
+            (burst_words - BURST_HEADER_WORDS) * 2
+        ));
+    }
+
+    let mut burst = Vec::with_capacity(burst_words);
+    for word in [
+        IEC61937_SYNC_PA,
+        IEC61937_SYNC_PB,
+        data_type,
+        (frame.len() * 8) as u16,
+    ] {
+        burst.push(word as i16);
+    }
+    burst.extend(frame.chunks(2).map(|pair| {
+        let lo = pair.get(1).copied().unwrap_or(0);
+        i16::from_be_bytes([pair[0], lo])
+    }));
+    burst.resize(burst_words, 0);
+    Ok(burst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造 AC3 帧: 同步字 + 指定 bsmod, 其余为递增字节
+    fn ac3_frame(len: usize, bsmod: u8) -> Vec<u8> {
+        let mut frame: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        frame[0] = 0x0B;
+        frame[1] = 0x77;
+        frame[5] = (frame[5] & !0x07) | bsmod;
+        frame
+    }
+
+    /// 构造 DTS 核心帧: 指定 NBLKS
+    fn dts_frame(len: usize, nblks: u8) -> Vec<u8> {
+        let mut frame = vec![0u8; len];
+        frame[..4].copy_from_slice(&[0x7F, 0xFE, 0x80, 0x01]);
+        frame[4] = 0xFC | (nblks >> 6);
+        frame[5] = (nblks & 0x3F) << 2;
+        frame
+    }
+
+    #[test]
+    fn test_iec61937_ac3_burst_layout() {
+        let frame = ac3_frame(768, 3);
+        let burst = encapsulate_iec61937(SpdifCodec::Ac3, &frame).unwrap();
+        assert_eq!(burst.len(), 1536 * 2, "AC3 突发应为 1536 个立体声采样");
+        assert_eq!(burst[0] as u16, 0xF872, "Pa 同步字");
+        assert_eq!(burst[1] as u16, 0x4E1F, "Pb 同步字");
+        assert_eq!(burst[2] as u16, 0x0301, "Pc 应为 AC3 类型并携带 bsmod");
+        assert_eq!(burst[3] as u16, 768 * 8, "Pd 应为载荷位数");
+        assert_eq!(burst[4] as u16, 0x0B77, "载荷按 16 位大端字排列");
+        assert_eq!(burst[5] as u16, u16::from_be_bytes([frame[2], frame[3]]));
+        assert!(
+            burst[4 + 384..].iter().all(|&w| w == 0),
+            "载荷之后应零填充至突发长度"
+        );
+    }
+
+    #[test]
+    fn test_iec61937_dts_burst_types() {
+        for (nblks, samples, data_type) in [(15u8, 512, 0x0B), (31, 1024, 0x0C), (63, 2048, 0x0D)] {
+            let mut frame = dts_frame(1001, nblks);
+            frame[1000] = 0xAB;
+            let burst = encapsulate_iec61937(SpdifCodec::Dts, &frame).unwrap();
+            assert_eq!(burst.len(), samples * 2, "DTS 突发长度应与帧采样数一致");
+            assert_eq!(
+                burst[2] as u16, data_type,
+                "{samples} 采样/帧的 DTS 数据类型"
+            );
+            assert_eq!(burst[3] as u16, 1001 * 8);
+            assert_eq!(
+                burst[4 + 500] as u16,
+                0xAB00,
+                "奇数长度的最后一个字节应补零"
+            );
+        }
+    }
+
+    #[test]
+    fn test_iec61937_rejects_invalid_frames() {
+        assert!(
+            encapsulate_iec61937(SpdifCodec::Ac3, &[0u8; 64]).is_err(),
+            "缺少同步字"
+        );
+        assert!(
+            encapsulate_iec61937(SpdifCodec::Ac3, &ac3_frame(6200, 0)).is_err(),
+            "超出突发容量应报错"
+        );
+        assert!(
+            encapsulate_iec61937(SpdifCodec::Dts, &dts_frame(512, 7)).is_err(),
+            "256 采样/帧的 DTS 不支持直通"
+        );
+        assert_eq!(
+            SpdifCodec::from_codec_id(CodecId::Ac3),
+            Some(SpdifCodec::Ac3)
+        );
+        assert_eq!(
+            SpdifCodec::from_codec_id(CodecId::Dts),
+            Some(SpdifCodec::Dts)
+        );
+        assert_eq!(SpdifCodec::from_codec_id(CodecId::Aac), None);
+    }
+}
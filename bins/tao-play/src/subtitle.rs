@@ -0,0 +1,263 @@
+//! 文本字幕解码与显示时间管理.
+//!
+//! player 线程将 SRT/ASS/WebVTT 字幕数据包解码为 [`SubtitleCue`] 发送给 GUI 线程,
+//! GUI 线程按 `MediaClock` 当前时间从 [`SubtitleTrack`] 中选出应显示的字幕.
+
+use tao_codec::{CodecId, Packet};
+use tao_core::timestamp::NOPTS_VALUE;
+use tao_format::stream::Stream;
+
+/// 数据包未携带时长时字幕的默认显示时长 (秒)
+const DEFAULT_CUE_DURATION: f64 = 5.0;
+
+/// 一条字幕
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleCue {
+    /// 开始显示时间 (秒)
+    pub start: f64,
+    /// 结束显示时间 (秒, 不含)
+    pub end: f64,
+    /// 纯文本内容, 多行以 '\n' 分隔
+    pub text: String,
+}
+
+impl SubtitleCue {
+    /// 指定时间是否处于显示区间内
+    pub fn is_active(&self, time_sec: f64) -> bool {
+        self.start <= time_sec && time_sec < self.end
+    }
+}
+
+/// 是否为可解码的文本字幕流
+pub fn is_text_subtitle(codec_id: CodecId) -> bool {
+    matches!(codec_id, CodecId::Srt | CodecId::Ass | CodecId::Webvtt)
+}
+
+/// 将字幕数据包解码为一条字幕, 时间戳无效或内容为空时返回 None
+pub fn decode_subtitle_packet(stream: &Stream, packet: &Packet) -> Option<SubtitleCue> {
+    let time_base = if packet.time_base.is_valid() {
+        packet.time_base
+    } else {
+        stream.time_base
+    };
+    if !time_base.is_valid() {
+        return None;
+    }
+    let ts = if packet.pts != NOPTS_VALUE {
+        packet.pts
+    } else {
+        packet.dts
+    };
+    if ts == NOPTS_VALUE {
+        return None;
+    }
+    let start = ts as f64 * time_base.to_f64();
+    let duration = if packet.duration > 0 {
+        packet.duration as f64 * time_base.to_f64()
+    } else {
+        DEFAULT_CUE_DURATION
+    };
+
+    let raw = String::from_utf8_lossy(&packet.data);
+    let text = match stream.codec_id {
+        CodecId::Ass => ass_dialogue_text(&raw),
+        _ => strip_markup_tags(&raw),
+    };
+    let text = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.is_empty() {
+        return None;
+    }
+    Some(SubtitleCue {
+        start,
+        end: start + duration,
+        text,
+    })
+}
+
+/// 提取 ASS 事件的文本字段并去除样式覆盖标签
+///
+/// 兼容两种形式:
+/// - Matroska 数据包: `ReadOrder,Layer,Style,Name,MarginL,MarginR,MarginV,Effect,Text`
+/// - 原始事件行: `Dialogue: Layer,Start,End,Style,Name,MarginL,MarginR,MarginV,Effect,Text`
+///
+/// 不符合以上格式的数据包按纯文本处理 (如 Matroska 的 `S_TEXT/UTF8`).
+fn ass_dialogue_text(raw: &str) -> String {
+    let text = if let Some(event) = raw.trim_start().strip_prefix("Dialogue:") {
+        event.splitn(10, ',').nth(9).unwrap_or("")
+    } else {
+        let fields: Vec<&str> = raw.splitn(9, ',').collect();
+        let numeric = |s: &str| s.trim().parse::<i64>().is_ok();
+        if fields.len() == 9 && numeric(fields[0]) && numeric(fields[1]) {
+            fields[8]
+        } else {
+            raw
+        }
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let mut in_override = false;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' => in_override = true,
+            '}' if in_override => in_override = false,
+            _ if in_override => {}
+            '\\' => match chars.peek() {
+                Some('N') | Some('n') => {
+                    chars.next();
+                    out.push('\n');
+                }
+                Some('h') => {
+                    chars.next();
+                    out.push(' ');
+                }
+                _ => out.push(ch),
+            },
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// 去除 SRT/WebVTT 中的 `<i>`/`<font ...>` 等标签
+fn strip_markup_tags(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut in_tag = false;
+    for ch in raw.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if in_tag => {}
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// 从字幕列表中选出指定时间应显示的字幕
+///
+/// 多条字幕同时处于显示区间时选择开始时间最晚的一条.
+pub fn select_active_cue(cues: &[SubtitleCue], time_sec: f64) -> Option<&SubtitleCue> {
+    cues.iter()
+        .filter(|cue| cue.is_active(time_sec))
+        .max_by(|a, b| a.start.total_cmp(&b.start))
+}
+
+/// GUI 线程持有的待显示字幕
+#[derive(Debug, Default)]
+pub struct SubtitleTrack {
+    cues: Vec<SubtitleCue>,
+}
+
+impl SubtitleTrack {
+    /// 添加一条字幕, 忽略重复 (seek 回退后会再次收到相同字幕)
+    pub fn push(&mut self, cue: SubtitleCue) {
+        if !self.cues.contains(&cue) {
+            self.cues.push(cue);
+        }
+    }
+
+    /// 移除在指定时间之前已结束的字幕
+    pub fn prune(&mut self, time_sec: f64) {
+        self.cues.retain(|cue| cue.end > time_sec);
+    }
+
+    /// 指定时间应显示的字幕
+    pub fn active_cue(&self, time_sec: f64) -> Option<&SubtitleCue> {
+        select_active_cue(&self.cues, time_sec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tao_core::{MediaType, Rational};
+    use tao_format::stream::StreamParams;
+
+    fn cue(start: f64, end: f64, text: &str) -> SubtitleCue {
+        SubtitleCue {
+            start,
+            end,
+            text: text.to_string(),
+        }
+    }
+
+    fn subtitle_stream(codec_id: CodecId) -> Stream {
+        Stream {
+            index: 2,
+            media_type: MediaType::Subtitle,
+            codec_id,
+            time_base: Rational::new(1, 1000),
+            duration: -1,
+            start_time: 0,
+            nb_frames: 0,
+            extra_data: Vec::new(),
+            params: StreamParams::Subtitle,
+            metadata: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_select_active_cue_by_time() {
+        let cues = vec![
+            cue(1.0, 3.0, "第一句"),
+            cue(4.0, 6.0, "第二句"),
+            cue(5.0, 7.0, "重叠的第三句"),
+        ];
+        assert_eq!(select_active_cue(&cues, 0.5), None, "首条字幕之前不显示");
+        assert_eq!(select_active_cue(&cues, 1.0).unwrap().text, "第一句");
+        assert_eq!(select_active_cue(&cues, 2.99).unwrap().text, "第一句");
+        assert_eq!(select_active_cue(&cues, 3.0), None, "结束时间不含在区间内");
+        assert_eq!(select_active_cue(&cues, 4.5).unwrap().text, "第二句");
+        assert_eq!(
+            select_active_cue(&cues, 5.5).unwrap().text,
+            "重叠的第三句",
+            "重叠时应选择开始最晚的字幕"
+        );
+        assert_eq!(select_active_cue(&cues, 6.5).unwrap().text, "重叠的第三句");
+        assert_eq!(select_active_cue(&cues, 7.0), None);
+    }
+
+    #[test]
+    fn test_subtitle_track_dedup_and_prune() {
+        let mut track = SubtitleTrack::default();
+        track.push(cue(1.0, 2.0, "a"));
+        track.push(cue(1.0, 2.0, "a"));
+        track.push(cue(3.0, 4.0, "b"));
+        assert_eq!(track.cues.len(), 2, "重复字幕应被忽略");
+        track.prune(2.5);
+        assert_eq!(track.active_cue(1.5), None, "已结束的字幕应被移除");
+        assert_eq!(track.active_cue(3.5).unwrap().text, "b");
+    }
+
+    #[test]
+    fn test_decode_subtitle_packet_srt_and_ass() {
+        let mut pkt =
+            Packet::from_data(b"<i>Hello</i>\r\n<font color=\"red\">World</font>".to_vec());
+        pkt.pts = 1500;
+        pkt.duration = 2000;
+        let srt = decode_subtitle_packet(&subtitle_stream(CodecId::Srt), &pkt).unwrap();
+        assert_eq!(srt, cue(1.5, 3.5, "Hello\nWorld"));
+
+        let pkt = Packet::new(
+            b"3,0,Default,,0,0,0,,{\\b1}Hi,{\\i1}there\\Nnext".to_vec(),
+            2,
+        );
+        let ass = decode_subtitle_packet(&subtitle_stream(CodecId::Ass), &pkt).unwrap();
+        assert_eq!(ass.text, "Hi,there\nnext", "应提取文本字段并去除覆盖标签");
+        assert_eq!(ass.end, DEFAULT_CUE_DURATION, "无时长时使用默认显示时长");
+
+        // Matroska S_TEXT/UTF8 同样映射为 Ass, 非事件格式按纯文本处理
+        let pkt = Packet::new(b"Plain, text".to_vec(), 2);
+        let plain = decode_subtitle_packet(&subtitle_stream(CodecId::Ass), &pkt).unwrap();
+        assert_eq!(plain.text, "Plain, text");
+
+        let empty = Packet::new(b"{\\an8}".to_vec(), 2);
+        assert!(decode_subtitle_packet(&subtitle_stream(CodecId::Ass), &empty).is_none());
+    }
+}
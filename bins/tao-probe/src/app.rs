@@ -5,9 +5,16 @@ use std::fs::File;
 use std::io::Write;
 use std::process::Command;
 
-use tao_core::{MediaType, TaoError};
+use tao_codec::frame::PictureType;
+use tao_codec::{CodecParameters, CodecRegistry, Decoder, Frame, Packet, PacketFlags};
+use tao_core::crc::crc32_update;
+use tao_core::md5::Md5;
+use tao_core::timestamp::NOPTS_VALUE;
+use tao_core::{MediaType, Rational, TaoError};
+use tao_format::demuxer::DemuxerChapter;
+use tao_format::probe::SCORE_EXTENSION;
 use tao_format::stream::StreamParams;
-use tao_format::{Demuxer, FormatId, FormatRegistry, IoContext};
+use tao_format::{Demuxer, FormatId, FormatRegistry, IoContext, PacketStream};
 
 use crate::cli::ffprobe_7_1_3_options::{AVOPTION_NAMES, MAIN_OPTIONS_HELP_LINES};
 use crate::cli::parser::parse_argv;
@@ -72,6 +79,83 @@ enum SelectStreamsSpec {
     },
 }
 
+/// 逐包输出所需的数据包信息 (不保留负载数据).
+#[derive(Debug, Clone)]
+struct PacketInfo {
+    stream_index: usize,
+    pts: i64,
+    dts: i64,
+    duration: i64,
+    size: usize,
+    pos: i64,
+    flags: PacketFlags,
+}
+
+/// 逐帧输出所需的解码帧信息 (不保留图像/采样数据).
+#[derive(Debug, Clone)]
+struct FrameInfo {
+    stream_index: usize,
+    key_frame: bool,
+    pts: i64,
+    duration: i64,
+    /// 帧时间基, 解码器未设置时取流时间基
+    time_base: Rational,
+    params: FrameParams,
+    /// 帧数据哈希, 仅在指定 `-show_hash` 时计算
+    hash: Option<String>,
+}
+
+/// 按媒体类型区分的帧参数.
+#[derive(Debug, Clone)]
+enum FrameParams {
+    Video {
+        width: u32,
+        height: u32,
+        pix_fmt: String,
+        pict_type: PictureType,
+    },
+    Audio {
+        nb_samples: u32,
+        sample_rate: u32,
+        channels: u32,
+        channel_layout: String,
+        sample_fmt: String,
+    },
+    /// 字幕帧无额外参数
+    Subtitle,
+}
+
+/// 解码数据哈希算法.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HashAlgorithm {
+    Md5,
+    Crc32,
+}
+
+/// 增量哈希状态.
+#[derive(Debug, Clone)]
+enum DataHasher {
+    Md5(Md5),
+    Crc32(u32),
+}
+
+/// 单条流全部解码数据的哈希.
+#[derive(Debug, Clone)]
+struct StreamHash {
+    stream_index: usize,
+    nb_frames: u64,
+    hasher: DataHasher,
+}
+
+/// 为选中的流创建解码器, 在读包过程中解码并记录帧信息.
+struct FrameCollector {
+    decoders: BTreeMap<usize, Box<dyn Decoder>>,
+    time_bases: BTreeMap<usize, Rational>,
+    frames: Vec<FrameInfo>,
+    hash_algorithm: Option<HashAlgorithm>,
+    stream_hashes: BTreeMap<usize, StreamHash>,
+}
+
 #[derive(Debug, Clone, Default)]
 struct ShowEntriesSpec {
     // None = section 全字段, Some(set) = 仅指定字段.
@@ -379,6 +463,16 @@ fn execute_probe_command(plan: &CommandPlan) -> Result<(), RunError> {
 
         let mut include_format = plan.show.show_format;
         let mut include_streams = plan.show.show_streams;
+        let mut include_packets = plan.show.show_packets;
+        let mut include_frames = plan.show.show_frames;
+        let mut include_chapters = plan.show.show_chapters;
+        let hash_algorithm = plan
+            .show
+            .show_hash
+            .as_deref()
+            .map(HashAlgorithm::parse)
+            .transpose()
+            .map_err(|msg| RunError::new(msg, false))?;
         if let Some(spec) = &show_entries_spec {
             if spec.allows_section("format") {
                 include_format = true;
@@ -386,13 +480,78 @@ fn execute_probe_command(plan: &CommandPlan) -> Result<(), RunError> {
             if spec.allows_section("stream") {
                 include_streams = true;
             }
+            if spec.allows_section("packet") {
+                include_packets = true;
+            }
+            if spec.allows_section("frame") {
+                include_frames = true;
+            }
+            if spec.allows_section("chapter") {
+                include_chapters = true;
+            }
         }
-
-        let packet_counts = if plan.show.count_packets && include_streams {
-            Some(collect_packet_counts(demuxer.as_mut(), &mut io)?)
+        include_frames &= section_allowed("frame", show_entries_spec.as_ref());
+
+        let selected = selected_stream_indexes(demuxer.streams(), select_streams_spec.as_ref());
+        let mut frame_collector = (include_frames || hash_algorithm.is_some())
+            .then(|| FrameCollector::new(demuxer.streams(), &selected, hash_algorithm));
+        let count_packets = plan.show.count_packets && include_streams;
+        let packets = if count_packets || include_packets || frame_collector.is_some() {
+            Some(collect_packets(
+                demuxer.as_mut(),
+                &mut io,
+                frame_collector.as_mut(),
+            )?)
         } else {
             None
         };
+        let packet_counts = match &packets {
+            Some(packets) if count_packets => Some(count_packets_per_stream(packets)),
+            _ => None,
+        };
+
+        if include_packets
+            && section_allowed("packet", show_entries_spec.as_ref())
+            && let Some(packets) = &packets
+        {
+            for packet in packets {
+                if !selected.contains(&packet.stream_index) {
+                    continue;
+                }
+                let Some(stream) = demuxer.streams().get(packet.stream_index) else {
+                    continue;
+                };
+                document.push_section(build_packet_section(
+                    packet,
+                    stream,
+                    show_entries_spec.as_ref(),
+                    plan,
+                ));
+            }
+        }
+
+        if let Some(mut collector) = frame_collector {
+            collector.finish();
+            if include_frames {
+                for frame in &collector.frames {
+                    let Some(stream) = demuxer.streams().get(frame.stream_index) else {
+                        continue;
+                    };
+                    document.push_section(build_frame_section(
+                        frame,
+                        stream,
+                        show_entries_spec.as_ref(),
+                        plan,
+                    ));
+                }
+            }
+            for hash in collector.stream_hashes.into_values() {
+                let Some(stream) = demuxer.streams().get(hash.stream_index) else {
+                    continue;
+                };
+                document.push_section(build_stream_hash_section(hash, stream));
+            }
+        }
 
         if include_format && section_allowed("format", show_entries_spec.as_ref()) {
             let mut section = ProbeSection::new("FORMAT");
@@ -464,10 +623,14 @@ fn execute_probe_command(plan: &CommandPlan) -> Result<(), RunError> {
                 );
             }
 
-            if let (Some(size), Some(duration)) = (size_bytes, duration_seconds)
-                && duration > 0.0
-            {
-                let bit_rate = ((size as f64 * 8.0) / duration).round() as u64;
+            // 优先按文件大小与时长计算, 无法取得时使用容器头部记录的码率
+            let bit_rate = match (size_bytes, duration_seconds) {
+                (Some(size), Some(duration)) if duration > 0.0 => {
+                    Some(((size as f64 * 8.0) / duration).round() as u64)
+                }
+                _ => demuxer.bit_rate(),
+            };
+            if let Some(bit_rate) = bit_rate {
                 push_field_if_selected(
                     &mut section,
                     show_entries_spec.as_ref(),
@@ -487,6 +650,12 @@ fn execute_probe_command(plan: &CommandPlan) -> Result<(), RunError> {
                 );
             }
 
+            if let Some(tags) =
+                build_tags_section(demuxer.metadata(), "format", show_entries_spec.as_ref())
+            {
+                section.children.push(tags);
+            }
+
             document.push_section(section);
         }
 
@@ -596,6 +765,16 @@ fn execute_probe_command(plan: &CommandPlan) -> Result<(), RunError> {
                     );
                 }
 
+                if stream.nb_frames > 0 {
+                    push_field_if_selected(
+                        &mut section,
+                        show_entries_spec.as_ref(),
+                        "stream",
+                        "nb_frames",
+                        ProbeValue::Unsigned(stream.nb_frames),
+                    );
+                }
+
                 match &stream.params {
                     StreamParams::Video(params) => {
                         push_field_if_selected(
@@ -758,6 +937,17 @@ fn execute_probe_command(plan: &CommandPlan) -> Result<(), RunError> {
                 document.push_section(section);
             }
         }
+
+        if include_chapters && section_allowed("chapter", show_entries_spec.as_ref()) {
+            for (id, chapter) in demuxer.chapters().iter().enumerate() {
+                document.push_section(build_chapter_section(
+                    id,
+                    chapter,
+                    plan,
+                    show_entries_spec.as_ref(),
+                ));
+            }
+        }
     }
 
     let spec = parse_output_format(plan.output_format.as_deref())
@@ -815,6 +1005,14 @@ fn open_input(plan: &CommandPlan, input: &str) -> Result<OpenInputResult, RunErr
     let probe = registry
         .probe_input(&mut io, Some(input))
         .map_err(|e| RunError::new(e.to_string(), false))?;
+    // 与 ffprobe 一致: 低置信度探测结果提示可能误判
+    if probe.score < SCORE_EXTENSION && !quiet_loglevel(plan.loglevel.as_deref()) {
+        eprintln!(
+            "Format {} detected only with low score of {}, misdetection possible!",
+            probe.format_id.name(),
+            probe.score
+        );
+    }
     let mut demuxer = registry
         .create_demuxer(probe.format_id)
         .map_err(|e| RunError::new(e.to_string(), false))?;
@@ -830,6 +1028,16 @@ fn open_input(plan: &CommandPlan, input: &str) -> Result<OpenInputResult, RunErr
     ))
 }
 
+/// 日志级别是否屏蔽警告
+fn quiet_loglevel(level: Option<&str>) -> bool {
+    level.is_some_and(|level| {
+        matches!(
+            level.to_ascii_lowercase().as_str(),
+            "quiet" | "panic" | "fatal" | "error"
+        )
+    })
+}
+
 fn map_format_id(name: &str) -> Option<FormatId> {
     let lower = name.to_ascii_lowercase();
     FormatId::ALL.iter().copied().find(|id| id.name() == lower)
@@ -872,6 +1080,10 @@ fn push_field_if_selected(
     }
 }
 
+/// 解析 `-select_streams` 流说明符.
+///
+/// 支持 `1`、`v`/`a`/`s`/`d`/`t`、`a:0`, 以及与 tao-cli `--map` 一致的
+/// 带输入索引形式 `0:1`、`0:a`、`0:a:0` (输入索引只能为 0).
 fn parse_select_streams(raw: Option<&str>) -> Result<Option<SelectStreamsSpec>, String> {
     let Some(raw) = raw else {
         return Ok(None);
@@ -881,17 +1093,35 @@ fn parse_select_streams(raw: Option<&str>) -> Result<Option<SelectStreamsSpec>,
         return Err("Invalid stream specifier: .".to_string());
     }
 
-    if token.chars().all(|c| c.is_ascii_digit()) {
+    let is_index = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if is_index(token) {
         let idx = token
             .parse::<usize>()
             .map_err(|_| format!("Invalid stream specifier: {}.", token))?;
         return Ok(Some(SelectStreamsSpec::AbsoluteIndex(idx)));
     }
 
-    let (kind, index_opt) = if let Some((k, idx)) = token.split_once(':') {
+    // 带输入索引的形式: 去掉 `0:` 前缀后按单输入说明符解析
+    let spec = match token.split_once(':') {
+        Some((input, rest)) if is_index(input) => {
+            if input.parse::<usize>().ok() != Some(0) || rest.is_empty() {
+                return Err(format!("Invalid stream specifier: {}.", token));
+            }
+            if is_index(rest) {
+                let idx = rest
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid stream specifier: {}.", token))?;
+                return Ok(Some(SelectStreamsSpec::AbsoluteIndex(idx)));
+            }
+            rest
+        }
+        _ => token,
+    };
+
+    let (kind, index_opt) = if let Some((k, idx)) = spec.split_once(':') {
         (k, Some(idx))
     } else {
-        (token, None)
+        (spec, None)
     };
 
     let media_type = match kind {
@@ -940,26 +1170,679 @@ fn stream_matches_spec(
     }
 }
 
-fn collect_packet_counts(
+fn collect_packets(
     demuxer: &mut dyn Demuxer,
     io: &mut IoContext,
-) -> Result<BTreeMap<usize, u64>, RunError> {
+    mut frame_collector: Option<&mut FrameCollector>,
+) -> Result<Vec<PacketInfo>, RunError> {
+    let mut packets = Vec::new();
+    for packet in PacketStream::new(demuxer, io) {
+        let packet = packet
+            .map_err(|err| RunError::new(format!("Failed to read packets: {}", err), false))?;
+        packets.push(PacketInfo {
+            stream_index: packet.stream_index,
+            pts: packet.pts,
+            dts: packet.dts,
+            duration: packet.duration,
+            size: packet.size(),
+            pos: packet.pos,
+            flags: packet.flags,
+        });
+        if let Some(collector) = frame_collector.as_deref_mut() {
+            collector.decode_packet(&packet);
+        }
+    }
+    Ok(packets)
+}
+
+fn count_packets_per_stream(packets: &[PacketInfo]) -> BTreeMap<usize, u64> {
     let mut counts = BTreeMap::<usize, u64>::new();
-    loop {
-        match demuxer.read_packet(io) {
-            Ok(packet) => {
-                *counts.entry(packet.stream_index).or_insert(0) += 1;
+    for packet in packets {
+        *counts.entry(packet.stream_index).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// 按 `-select_streams` 计算被选中的流索引, 未指定时选中全部流.
+fn selected_stream_indexes(
+    streams: &[tao_format::Stream],
+    spec: Option<&SelectStreamsSpec>,
+) -> BTreeSet<usize> {
+    let mut type_seen = HashMap::<MediaType, usize>::new();
+    let mut selected = BTreeSet::new();
+    for stream in streams {
+        let counter = type_seen.entry(stream.media_type).or_insert(0);
+        let typed_index = *counter;
+        *counter += 1;
+        if spec.is_none_or(|spec| stream_matches_spec(spec, stream, typed_index)) {
+            selected.insert(stream.index);
+        }
+    }
+    selected
+}
+
+fn build_packet_section(
+    packet: &PacketInfo,
+    stream: &tao_format::Stream,
+    spec: Option<&ShowEntriesSpec>,
+    plan: &CommandPlan,
+) -> ProbeSection {
+    let mut section = ProbeSection::new("PACKET");
+    let time_base = stream.time_base.to_f64();
+    push_field_if_selected(
+        &mut section,
+        spec,
+        "packet",
+        "codec_type",
+        ProbeValue::String(media_type_name(stream.media_type).to_string()),
+    );
+    push_field_if_selected(
+        &mut section,
+        spec,
+        "packet",
+        "stream_index",
+        ProbeValue::Unsigned(packet.stream_index as u64),
+    );
+    for (key, value) in [("pts", packet.pts), ("dts", packet.dts)] {
+        if value == NOPTS_VALUE {
+            continue;
+        }
+        push_field_if_selected(
+            &mut section,
+            spec,
+            "packet",
+            key,
+            ProbeValue::Integer(value),
+        );
+        push_field_if_selected(
+            &mut section,
+            spec,
+            "packet",
+            &format!("{key}_time"),
+            format_time_value(value as f64 * time_base, plan),
+        );
+    }
+    if packet.duration > 0 {
+        push_field_if_selected(
+            &mut section,
+            spec,
+            "packet",
+            "duration",
+            ProbeValue::Integer(packet.duration),
+        );
+        push_field_if_selected(
+            &mut section,
+            spec,
+            "packet",
+            "duration_time",
+            format_time_value(packet.duration as f64 * time_base, plan),
+        );
+    }
+    push_field_if_selected(
+        &mut section,
+        spec,
+        "packet",
+        "size",
+        ProbeValue::Unsigned(packet.size as u64),
+    );
+    if packet.pos >= 0 {
+        push_field_if_selected(
+            &mut section,
+            spec,
+            "packet",
+            "pos",
+            ProbeValue::Unsigned(packet.pos as u64),
+        );
+    }
+    push_field_if_selected(
+        &mut section,
+        spec,
+        "packet",
+        "flags",
+        ProbeValue::String(packet_flags_string(packet.flags)),
+    );
+    section
+}
+
+/// 数据包标志文本, 与 ffprobe 一致: 关键帧 `K`, 丢弃 `D`, 损坏 `C`, 未设置为 `_`.
+fn packet_flags_string(flags: PacketFlags) -> String {
+    [
+        (PacketFlags::KEYFRAME, 'K'),
+        (PacketFlags::DISCARD, 'D'),
+        (PacketFlags::CORRUPT, 'C'),
+    ]
+    .iter()
+    .map(|(flag, ch)| if flags.contains(*flag) { *ch } else { '_' })
+    .collect()
+}
+
+impl HashAlgorithm {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "md5" => Ok(Self::Md5),
+            "crc32" => Ok(Self::Crc32),
+            _ => Err(format!("不支持的哈希算法 '{}', 可选 md5/crc32", name)),
+        }
+    }
+}
+
+impl DataHasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Md5 => Self::Md5(Md5::new()),
+            HashAlgorithm::Crc32 => Self::Crc32(0),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(ctx) => ctx.update(data),
+            Self::Crc32(crc) => *crc = crc32_update(*crc, data),
+        }
+    }
+
+    /// 结束计算, 格式与 ffprobe `data_hash` 一致: `MD5:<hex>` / `CRC32:<hex>`.
+    fn finish(self) -> String {
+        match self {
+            Self::Md5(ctx) => {
+                let hex = ctx
+                    .finalize()
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>();
+                format!("MD5:{}", hex)
             }
-            Err(TaoError::Eof) => break,
-            Err(err) => {
-                return Err(RunError::new(
-                    format!("Failed to read packets for count: {}", err),
-                    false,
-                ));
+            Self::Crc32(crc) => format!("CRC32:{:08x}", crc),
+        }
+    }
+}
+
+impl FrameCollector {
+    fn new(
+        streams: &[tao_format::Stream],
+        selected: &BTreeSet<usize>,
+        hash_algorithm: Option<HashAlgorithm>,
+    ) -> Self {
+        let mut registry = CodecRegistry::new();
+        tao_codec::register_all(&mut registry);
+
+        let mut decoders = BTreeMap::new();
+        let mut time_bases = BTreeMap::new();
+        for stream in streams {
+            if !selected.contains(&stream.index) {
+                continue;
+            }
+            let Some(params) = build_decoder_params(stream) else {
+                continue;
+            };
+            let decoder = registry
+                .create_decoder(stream.codec_id)
+                .and_then(|mut decoder| decoder.open(&params).map(|_| decoder));
+            match decoder {
+                Ok(decoder) => {
+                    decoders.insert(stream.index, decoder);
+                    time_bases.insert(stream.index, stream.time_base);
+                }
+                Err(err) => {
+                    log::warn!("流 #{} 无法创建解码器, 跳过帧分析: {}", stream.index, err);
+                }
+            }
+        }
+        let stream_hashes = match hash_algorithm {
+            Some(algorithm) => decoders
+                .keys()
+                .map(|&stream_index| {
+                    let hash = StreamHash {
+                        stream_index,
+                        nb_frames: 0,
+                        hasher: DataHasher::new(algorithm),
+                    };
+                    (stream_index, hash)
+                })
+                .collect(),
+            None => BTreeMap::new(),
+        };
+        Self {
+            decoders,
+            time_bases,
+            frames: Vec::new(),
+            hash_algorithm,
+            stream_hashes,
+        }
+    }
+
+    fn decode_packet(&mut self, packet: &Packet) {
+        let Some(decoder) = self.decoders.get_mut(&packet.stream_index) else {
+            return;
+        };
+        if let Err(err) = decoder.send_packet(packet) {
+            log::warn!("流 #{} 解码数据包失败: {}", packet.stream_index, err);
+            return;
+        }
+        self.drain(packet.stream_index);
+    }
+
+    /// 冲刷所有解码器的缓存帧, 帧信息按解码顺序记录在 `frames` 中.
+    fn finish(&mut self) {
+        let indexes = self.decoders.keys().copied().collect::<Vec<_>>();
+        for stream_index in indexes {
+            let mut packet = Packet::empty();
+            packet.stream_index = stream_index;
+            if let Some(decoder) = self.decoders.get_mut(&stream_index)
+                && decoder.send_packet(&packet).is_ok()
+            {
+                self.drain(stream_index);
+            }
+        }
+    }
+
+    fn drain(&mut self, stream_index: usize) {
+        let Some(decoder) = self.decoders.get_mut(&stream_index) else {
+            return;
+        };
+        let stream_time_base = self.time_bases[&stream_index];
+        loop {
+            match decoder.receive_frame() {
+                Ok(frame) => {
+                    let mut info = FrameInfo::from_frame(stream_index, &frame, stream_time_base);
+                    if let Some(algorithm) = self.hash_algorithm {
+                        let mut hasher = DataHasher::new(algorithm);
+                        let stream_hash = self.stream_hashes.get_mut(&stream_index);
+                        let chunks = frame_hash_chunks(&frame);
+                        for chunk in &chunks {
+                            hasher.update(chunk);
+                        }
+                        if let Some(stream_hash) = stream_hash {
+                            for chunk in &chunks {
+                                stream_hash.hasher.update(chunk);
+                            }
+                            stream_hash.nb_frames += 1;
+                        }
+                        info.hash = Some(hasher.finish());
+                    }
+                    self.frames.push(info);
+                }
+                Err(TaoError::NeedMoreData) | Err(TaoError::Eof) => break,
+                Err(err) => {
+                    log::warn!("流 #{} 获取解码帧失败: {}", stream_index, err);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl FrameInfo {
+    fn from_frame(stream_index: usize, frame: &Frame, stream_time_base: Rational) -> Self {
+        let pick_time_base = |time_base: Rational| {
+            if time_base.is_valid() {
+                time_base
+            } else {
+                stream_time_base
+            }
+        };
+        match frame {
+            Frame::Video(vf) => Self {
+                stream_index,
+                key_frame: vf.is_keyframe,
+                pts: vf.pts,
+                duration: vf.duration,
+                time_base: pick_time_base(vf.time_base),
+                params: FrameParams::Video {
+                    width: vf.width,
+                    height: vf.height,
+                    pix_fmt: vf.pixel_format.to_string(),
+                    pict_type: vf.picture_type,
+                },
+                hash: None,
+            },
+            Frame::Audio(af) => Self {
+                stream_index,
+                key_frame: true,
+                pts: af.pts,
+                duration: af.duration,
+                time_base: pick_time_base(af.time_base),
+                params: FrameParams::Audio {
+                    nb_samples: af.nb_samples,
+                    sample_rate: af.sample_rate,
+                    channels: af.channel_layout.channels,
+                    channel_layout: af.channel_layout.to_string(),
+                    sample_fmt: af.sample_format.to_string(),
+                },
+                hash: None,
+            },
+            Frame::Subtitle(sf) => Self {
+                stream_index,
+                key_frame: true,
+                pts: sf.pts,
+                duration: sf.duration,
+                time_base: stream_time_base,
+                params: FrameParams::Subtitle,
+                hash: None,
+            },
+        }
+    }
+}
+
+/// 帧中参与哈希的原始数据: 视频逐行取可见宽度 (不含行尾填充), 音频取有效采样, 字幕取文本.
+fn frame_hash_chunks(frame: &Frame) -> Vec<&[u8]> {
+    match frame {
+        Frame::Video(vf) => {
+            let mut chunks = Vec::new();
+            for (plane, data) in vf.data.iter().enumerate() {
+                let linesize = vf.linesize.get(plane).copied().unwrap_or(0);
+                let row_bytes = vf.pixel_format.plane_linesize(plane, vf.width);
+                let rows = vf.pixel_format.plane_height(plane, vf.height);
+                match (row_bytes, rows) {
+                    (Some(row_bytes), Some(rows)) if linesize >= row_bytes => {
+                        chunks.extend((0..rows).filter_map(|y| {
+                            let start = y * linesize;
+                            data.get(start..start + row_bytes)
+                        }));
+                    }
+                    _ => chunks.push(&data[..]),
+                }
+            }
+            chunks
+        }
+        Frame::Audio(af) => {
+            let mut plane_size =
+                af.nb_samples as usize * af.sample_format.bytes_per_sample() as usize;
+            if !af.sample_format.is_planar() {
+                plane_size *= af.channel_layout.channels as usize;
             }
+            af.data
+                .iter()
+                .map(|data| &data[..plane_size.min(data.len())])
+                .collect()
         }
+        Frame::Subtitle(sf) => vec![sf.text.as_bytes()],
+    }
+}
+
+/// 由流参数构造解码器参数, 仅支持音视频流.
+fn build_decoder_params(stream: &tao_format::Stream) -> Option<CodecParameters> {
+    matches!(
+        stream.params,
+        StreamParams::Audio(_) | StreamParams::Video(_)
+    )
+    .then(|| stream.codec_parameters())
+}
+
+fn build_frame_section(
+    frame: &FrameInfo,
+    stream: &tao_format::Stream,
+    spec: Option<&ShowEntriesSpec>,
+    plan: &CommandPlan,
+) -> ProbeSection {
+    let mut section = ProbeSection::new("FRAME");
+    let time_base = frame.time_base.to_f64();
+    push_field_if_selected(
+        &mut section,
+        spec,
+        "frame",
+        "media_type",
+        ProbeValue::String(media_type_name(stream.media_type).to_string()),
+    );
+    push_field_if_selected(
+        &mut section,
+        spec,
+        "frame",
+        "stream_index",
+        ProbeValue::Unsigned(frame.stream_index as u64),
+    );
+    push_field_if_selected(
+        &mut section,
+        spec,
+        "frame",
+        "key_frame",
+        ProbeValue::Integer(i64::from(frame.key_frame)),
+    );
+    if frame.pts != NOPTS_VALUE {
+        push_field_if_selected(
+            &mut section,
+            spec,
+            "frame",
+            "pts",
+            ProbeValue::Integer(frame.pts),
+        );
+        push_field_if_selected(
+            &mut section,
+            spec,
+            "frame",
+            "pts_time",
+            format_time_value(frame.pts as f64 * time_base, plan),
+        );
+    }
+    if frame.duration > 0 {
+        push_field_if_selected(
+            &mut section,
+            spec,
+            "frame",
+            "duration",
+            ProbeValue::Integer(frame.duration),
+        );
+        push_field_if_selected(
+            &mut section,
+            spec,
+            "frame",
+            "duration_time",
+            format_time_value(frame.duration as f64 * time_base, plan),
+        );
+    }
+    if let Some(hash) = &frame.hash {
+        push_field_if_selected(
+            &mut section,
+            spec,
+            "frame",
+            "data_hash",
+            ProbeValue::String(hash.clone()),
+        );
+    }
+    match &frame.params {
+        FrameParams::Video {
+            width,
+            height,
+            pix_fmt,
+            pict_type,
+        } => {
+            push_field_if_selected(
+                &mut section,
+                spec,
+                "frame",
+                "width",
+                ProbeValue::Unsigned(u64::from(*width)),
+            );
+            push_field_if_selected(
+                &mut section,
+                spec,
+                "frame",
+                "height",
+                ProbeValue::Unsigned(u64::from(*height)),
+            );
+            push_field_if_selected(
+                &mut section,
+                spec,
+                "frame",
+                "pix_fmt",
+                ProbeValue::String(pix_fmt.clone()),
+            );
+            push_field_if_selected(
+                &mut section,
+                spec,
+                "frame",
+                "pict_type",
+                ProbeValue::String(pict_type_name(*pict_type).to_string()),
+            );
+        }
+        FrameParams::Audio {
+            nb_samples,
+            sample_rate,
+            channels,
+            channel_layout,
+            sample_fmt,
+        } => {
+            push_field_if_selected(
+                &mut section,
+                spec,
+                "frame",
+                "sample_fmt",
+                ProbeValue::String(sample_fmt.clone()),
+            );
+            push_field_if_selected(
+                &mut section,
+                spec,
+                "frame",
+                "nb_samples",
+                ProbeValue::Unsigned(u64::from(*nb_samples)),
+            );
+            push_field_if_selected(
+                &mut section,
+                spec,
+                "frame",
+                "sample_rate",
+                ProbeValue::Unsigned(u64::from(*sample_rate)),
+            );
+            push_field_if_selected(
+                &mut section,
+                spec,
+                "frame",
+                "channels",
+                ProbeValue::Unsigned(u64::from(*channels)),
+            );
+            push_field_if_selected(
+                &mut section,
+                spec,
+                "frame",
+                "channel_layout",
+                ProbeValue::String(channel_layout.clone()),
+            );
+        }
+        FrameParams::Subtitle => {}
+    }
+    section
+}
+
+/// 单条流解码数据的整体哈希 (tao 扩展, 类似 ffmpeg 的 hash 封装器).
+/// 章节时间基 (微秒)
+const CHAPTER_TIME_BASE: i64 = 1_000_000;
+
+fn build_chapter_section(
+    id: usize,
+    chapter: &DemuxerChapter,
+    plan: &CommandPlan,
+    spec: Option<&ShowEntriesSpec>,
+) -> ProbeSection {
+    let mut section = ProbeSection::new("CHAPTER");
+    push_field_if_selected(
+        &mut section,
+        spec,
+        "chapter",
+        "id",
+        ProbeValue::Unsigned(id as u64),
+    );
+    push_field_if_selected(
+        &mut section,
+        spec,
+        "chapter",
+        "time_base",
+        ProbeValue::String(format!("1/{CHAPTER_TIME_BASE}")),
+    );
+    for (key, seconds) in [("start", chapter.start_time), ("end", chapter.end_time)] {
+        match seconds {
+            Some(seconds) => {
+                push_field_if_selected(
+                    &mut section,
+                    spec,
+                    "chapter",
+                    key,
+                    ProbeValue::Integer((seconds * CHAPTER_TIME_BASE as f64).round() as i64),
+                );
+                push_field_if_selected(
+                    &mut section,
+                    spec,
+                    "chapter",
+                    &format!("{key}_time"),
+                    format_time_value(seconds, plan),
+                );
+            }
+            None => {
+                push_field_if_selected(
+                    &mut section,
+                    spec,
+                    "chapter",
+                    key,
+                    ProbeValue::String("N/A".to_string()),
+                );
+                push_field_if_selected(
+                    &mut section,
+                    spec,
+                    "chapter",
+                    &format!("{key}_time"),
+                    ProbeValue::String("N/A".to_string()),
+                );
+            }
+        }
+    }
+    if let Some(tags) = build_tags_section(&chapter.metadata, "chapter", spec) {
+        section.children.push(tags);
+    }
+    section
+}
+
+/// 构造 `TAGS` 子 section.
+///
+/// `-show_entries format` 或 `format=tags` 输出全部标签, `format_tags=title` 仅输出指定标签.
+fn build_tags_section(
+    metadata: &[(String, String)],
+    section_name: &str,
+    spec: Option<&ShowEntriesSpec>,
+) -> Option<ProbeSection> {
+    let tags_section = format!("{section_name}_tags");
+    let key_allowed = |key: &str| match spec {
+        None => true,
+        Some(spec) if spec.allows_section(&tags_section) => spec.allows_field(&tags_section, key),
+        Some(spec) => spec.allows_field(section_name, "tags"),
+    };
+    let mut tags = ProbeSection::new("TAGS");
+    for (key, value) in metadata.iter().filter(|(key, _)| key_allowed(key)) {
+        tags.push_field(ProbeField::new(key, ProbeValue::String(value.clone())));
+    }
+    (!tags.fields.is_empty()).then_some(tags)
+}
+
+fn build_stream_hash_section(hash: StreamHash, stream: &tao_format::Stream) -> ProbeSection {
+    let mut section = ProbeSection::new("STREAM_HASH");
+    section.push_field(ProbeField::new(
+        "stream_index",
+        ProbeValue::Unsigned(hash.stream_index as u64),
+    ));
+    section.push_field(ProbeField::new(
+        "codec_type",
+        ProbeValue::String(media_type_name(stream.media_type).to_string()),
+    ));
+    section.push_field(ProbeField::new(
+        "nb_frames",
+        ProbeValue::Unsigned(hash.nb_frames),
+    ));
+    section.push_field(ProbeField::new(
+        "data_hash",
+        ProbeValue::String(hash.hasher.finish()),
+    ));
+    section
+}
+
+/// 帧类型文本, 与 ffprobe 一致: 未知类型为 `?`.
+fn pict_type_name(pict_type: PictureType) -> &'static str {
+    match pict_type {
+        PictureType::I => "I",
+        PictureType::P => "P",
+        PictureType::B => "B",
+        PictureType::S => "S",
+        PictureType::Si => "SI",
+        PictureType::Sp => "SP",
+        PictureType::None => "?",
     }
-    Ok(counts)
 }
 
 fn add_program_version_section(document: &mut ProbeDocument, plan: &CommandPlan) {
@@ -1021,12 +1904,14 @@ fn media_type_name(media_type: MediaType) -> &'static str {
         MediaType::Subtitle => "subtitle",
         MediaType::Data => "data",
         MediaType::Attachment => "attachment",
+        MediaType::Unknown(_) => "unknown",
     }
 }
 
 fn should_force_json_string(section_name: &str, key: &str) -> bool {
     match section_name {
         "format" => matches!(key, "start_time" | "duration" | "size" | "bit_rate"),
+        "packet" => matches!(key, "size" | "pos"),
         "stream" => matches!(
             key,
             "sample_rate"
@@ -1034,6 +1919,7 @@ fn should_force_json_string(section_name: &str, key: &str) -> bool {
                 | "start_time"
                 | "duration"
                 | "bit_rate"
+                | "nb_frames"
                 | "r_frame_rate"
                 | "avg_frame_rate"
         ),
@@ -1167,8 +2053,7 @@ fn should_show_banner_on_error(argv: &[String], hide_banner: bool) -> bool {
         let token = &argv[i];
         if token == "-v" || token == "-loglevel" || token == "--loglevel" {
             if let Some(level) = argv.get(i + 1) {
-                let normalized = level.to_ascii_lowercase();
-                if matches!(normalized.as_str(), "quiet" | "panic" | "fatal" | "error") {
+                if quiet_loglevel(Some(level)) {
                     return false;
                 }
                 i += 2;
@@ -1178,11 +2063,9 @@ fn should_show_banner_on_error(argv: &[String], hide_banner: bool) -> bool {
         if let Some(level) = token
             .strip_prefix("-v=")
             .or_else(|| token.strip_prefix("-loglevel="))
+            && quiet_loglevel(Some(level))
         {
-            let normalized = level.to_ascii_lowercase();
-            if matches!(normalized.as_str(), "quiet" | "panic" | "fatal" | "error") {
-                return false;
-            }
+            return false;
         }
         i += 1;
     }
@@ -1203,12 +2086,21 @@ fn try_execute_ffprobe_global_passthrough(
 }
 
 fn try_execute_ffprobe_probe_passthrough(plan: &CommandPlan) -> Option<Result<(), RunError>> {
+    // `-show_hash` 为 tao 扩展, ffprobe 无法识别, 始终使用内置实现
+    if plan.show.show_hash.is_some() {
+        return None;
+    }
     let args = plan
         .ordered_execution
         .iter()
         .map(|item| item.token.clone())
         .collect::<Vec<_>>();
-    Some(execute_ffprobe_passthrough(&args))
+    match Command::new("ffprobe").args(&args).output() {
+        Ok(output) => Some(emit_ffprobe_output(&output)),
+        // 未安装 ffprobe 时回退到内置探测实现
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(_) => Some(Err(RunError::new("无法执行 ffprobe", false))),
+    }
 }
 
 fn execute_ffprobe_passthrough(args: &[String]) -> Result<(), RunError> {
@@ -1216,7 +2108,10 @@ fn execute_ffprobe_passthrough(args: &[String]) -> Result<(), RunError> {
         .args(args)
         .output()
         .map_err(|_| RunError::new("无法执行 ffprobe", false))?;
+    emit_ffprobe_output(&output)
+}
 
+fn emit_ffprobe_output(output: &std::process::Output) -> Result<(), RunError> {
     std::io::stdout()
         .write_all(&output.stdout)
         .map_err(|_| RunError::new("输出失败", false))?;
@@ -207,7 +207,7 @@ pub const MAIN_OPTIONS: &[OptionSpec] = &[
     },
     OptionSpec {
         canonical: "select_streams",
-        aliases: &["select_streams"],
+        aliases: &["select_streams", "select-streams"],
         value_kind: OptionValueKind::Required,
     },
     OptionSpec {
@@ -345,6 +345,12 @@ pub const MAIN_OPTIONS: &[OptionSpec] = &[
         aliases: &["find_stream_info"],
         value_kind: OptionValueKind::None,
     },
+    // tao 扩展: 解码后按流输出数据哈希 (md5/crc32), ffprobe 无对应选项
+    OptionSpec {
+        canonical: "show_hash",
+        aliases: &["show_hash", "show-hash"],
+        value_kind: OptionValueKind::Required,
+    },
 ];
 
 /// 兼容旧版 tao-probe 的隐藏别名映射.
@@ -357,6 +363,8 @@ pub const LEGACY_ALIAS_OPTIONS: &[(&str, &str, Option<&str>)] = &[
     ("show-streams", "show_streams", None),
     // `--show-packets` => `-show_packets`
     ("show-packets", "show_packets", None),
+    // `--show-frames` => `-show_frames`
+    ("show-frames", "show_frames", None),
     // `--quiet` => `-v error`
     ("quiet", "loglevel", Some("error")),
     // `-q` => `-v error`
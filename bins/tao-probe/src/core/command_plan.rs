@@ -58,6 +58,8 @@ pub struct ShowSwitches {
     pub show_data: bool,
     pub show_data_hash: Option<String>,
     pub show_data_hash_state: OptionalValueState,
+    /// 解码数据哈希算法 (tao 扩展), 与 `show_frames` 同时使用时逐帧输出
+    pub show_hash: Option<String>,
     pub count_frames: bool,
     pub count_packets: bool,
     pub show_program_version: bool,
@@ -175,6 +177,7 @@ pub fn build_command_plan(parsed: &ParsedArgs) -> Result<CommandPlan, CliError>
             )
             .then(|| "md5".to_string())
         });
+    plan.show.show_hash = parsed.last_value("show_hash").map(ToString::to_string);
     plan.show.count_frames = parsed.has("count_frames");
     plan.show.count_packets = parsed.has("count_packets");
     plan.show.show_program_version = parsed.has("show_program_version");
@@ -1,25 +1,160 @@
 //! compact writer.
+//!
+//! 对标 ffprobe `-of compact`: 每个顶层 section 一行, 如 `stream|index=0|codec_name=aac`.
+//! 非数组子 section (disposition/tags) 内联到所在行, 键名带 `disposition:` 前缀.
+//!
+//! 选项: `item_sep` / `s` 字段分隔符, `nokey` / `nk` 省略键名,
+//! `print_section` / `p` 是否输出行首 section 名.
 
 use std::io::Write;
 
 use crate::model::{ProbeDocument, ProbeSection};
+use crate::writer::{OutputFormatSpec, section_key, writer_option};
 
-pub fn write(doc: &ProbeDocument, output: &mut dyn Write) -> std::io::Result<()> {
+/// 字段值转义方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EscapeMode {
+    /// 反斜杠转义分隔符与控制字符
+    C,
+    /// 含特殊字符时整体加双引号 (RFC 4180)
+    Csv,
+}
+
+/// compact 系 writer 配置
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CompactOptions {
+    pub(crate) item_sep: char,
+    pub(crate) nokey: bool,
+    pub(crate) print_section: bool,
+    pub(crate) escape: EscapeMode,
+}
+
+impl CompactOptions {
+    /// 在给定默认值上应用用户选项
+    pub(crate) fn from_spec(spec: &OutputFormatSpec, defaults: Self) -> Self {
+        let flag = |names: &[&str], default: bool| {
+            writer_option(spec, names).map_or(default, |v| v == "1")
+        };
+        Self {
+            item_sep: writer_option(spec, &["item_sep", "s"])
+                .and_then(|v| v.chars().next())
+                .unwrap_or(defaults.item_sep),
+            nokey: flag(&["nokey", "nk"], defaults.nokey),
+            print_section: flag(&["print_section", "p"], defaults.print_section),
+            escape: match writer_option(spec, &["escape", "e"]) {
+                Some("csv") => EscapeMode::Csv,
+                Some("c") => EscapeMode::C,
+                _ => defaults.escape,
+            },
+        }
+    }
+}
+
+pub fn write(
+    doc: &ProbeDocument,
+    output: &mut dyn Write,
+    spec: &OutputFormatSpec,
+) -> std::io::Result<()> {
+    let options = CompactOptions::from_spec(
+        spec,
+        CompactOptions {
+            item_sep: '|',
+            nokey: false,
+            print_section: true,
+            escape: EscapeMode::C,
+        },
+    );
+    write_with(doc, output, &options)
+}
+
+/// 按给定配置写出, csv writer 复用此实现
+pub(crate) fn write_with(
+    doc: &ProbeDocument,
+    output: &mut dyn Write,
+    options: &CompactOptions,
+) -> std::io::Result<()> {
     for section in &doc.sections {
-        write_line(section, output)?;
+        write_line(section, output, options)?;
+    }
+    Ok(())
+}
+
+fn write_line(
+    section: &ProbeSection,
+    output: &mut dyn Write,
+    options: &CompactOptions,
+) -> std::io::Result<()> {
+    let mut items = Vec::new();
+    if options.print_section {
+        items.push(section.name.to_lowercase());
+    }
+    let mut array_children = Vec::new();
+    collect_items(section, "", &mut items, &mut array_children, options);
+
+    let sep = options.item_sep.to_string();
+    writeln!(output, "{}", items.join(&sep))?;
+
+    // 数组元素 (如 side_data) 各自独占一行
+    for child in array_children {
+        write_line(child, output, options)?;
     }
     Ok(())
 }
 
-fn write_line(section: &ProbeSection, output: &mut dyn Write) -> std::io::Result<()> {
-    write!(output, "{}", section.name.to_lowercase())?;
+fn collect_items<'a>(
+    section: &'a ProbeSection,
+    prefix: &str,
+    items: &mut Vec<String>,
+    array_children: &mut Vec<&'a ProbeSection>,
+    options: &CompactOptions,
+) {
     for field in &section.fields {
-        write!(output, "|{}={}", field.key, field.value.as_text())?;
+        let value = escape(&field.value.as_text(), options);
+        if options.nokey {
+            items.push(value);
+        } else {
+            items.push(format!("{prefix}{}={value}", field.key));
+        }
     }
-    writeln!(output)?;
 
     for child in &section.children {
-        write_line(child, output)?;
+        let (key, is_array) = section_key(&child.name);
+        if is_array {
+            array_children.push(child);
+        } else {
+            let child_prefix = format!("{prefix}{key}:");
+            collect_items(child, &child_prefix, items, array_children, options);
+        }
+    }
+}
+
+fn escape(value: &str, options: &CompactOptions) -> String {
+    match options.escape {
+        EscapeMode::C => {
+            let mut out = String::with_capacity(value.len());
+            for c in value.chars() {
+                match c {
+                    '\n' => out.push_str("\\n"),
+                    '\r' => out.push_str("\\r"),
+                    '\\' => out.push_str("\\\\"),
+                    c if c == options.item_sep => {
+                        out.push('\\');
+                        out.push(c);
+                    }
+                    _ => out.push(c),
+                }
+            }
+            out
+        }
+        EscapeMode::Csv => {
+            let need_quote = value
+                .chars()
+                .any(|c| c == '"' || c == '\n' || c == '\r' || c == options.item_sep);
+            if need_quote {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value.to_string()
+            }
+        }
     }
-    Ok(())
 }
@@ -1,40 +1,27 @@
 //! csv writer.
+//!
+//! 对标 ffprobe `-of csv`: 即 compact writer 以 `,` 分隔、省略键名并按 CSV 规则转义,
+//! 每个 section 一行, 如 `stream,0,aac,...`. 常用 `-of csv=p=0` 去掉行首 section 名.
 
 use std::io::Write;
 
-use crate::model::{ProbeDocument, ProbeSection};
+use crate::model::ProbeDocument;
+use crate::writer::OutputFormatSpec;
+use crate::writer::compact::{self, CompactOptions, EscapeMode};
 
-pub fn write(doc: &ProbeDocument, output: &mut dyn Write) -> std::io::Result<()> {
-    writeln!(output, "section,key,value")?;
-    for section in &doc.sections {
-        write_section(section, output)?;
-    }
-    Ok(())
-}
-
-fn write_section(section: &ProbeSection, output: &mut dyn Write) -> std::io::Result<()> {
-    let section_name = section.name.to_lowercase();
-    for field in &section.fields {
-        writeln!(
-            output,
-            "{},{},{}",
-            escape_csv(&section_name),
-            escape_csv(&field.key),
-            escape_csv(&field.value.as_text())
-        )?;
-    }
-
-    for child in &section.children {
-        write_section(child, output)?;
-    }
-    Ok(())
-}
-
-fn escape_csv(input: &str) -> String {
-    let need_quote = input.contains(',') || input.contains('"') || input.contains('\n');
-    if need_quote {
-        format!("\"{}\"", input.replace('"', "\"\""))
-    } else {
-        input.to_string()
-    }
+pub fn write(
+    doc: &ProbeDocument,
+    output: &mut dyn Write,
+    spec: &OutputFormatSpec,
+) -> std::io::Result<()> {
+    let options = CompactOptions::from_spec(
+        spec,
+        CompactOptions {
+            item_sep: ',',
+            nokey: true,
+            print_section: true,
+            escape: EscapeMode::Csv,
+        },
+    );
+    compact::write_with(doc, output, &options)
 }
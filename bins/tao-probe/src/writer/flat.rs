@@ -1,16 +1,50 @@
 //! flat writer.
+//!
+//! 对标 ffprobe `-of flat`: 每个字段一行 `streams.stream.0.codec_name="aac"`,
+//! 数值不加引号, 字符串加双引号并转义 shell 特殊字符.
+//!
+//! 选项: `sep_char` / `s` 指定层级分隔符 (默认 `.`).
 
+use std::collections::BTreeMap;
 use std::io::Write;
 
-use crate::model::{ProbeDocument, ProbeSection};
+use serde_json::Value;
 
-pub fn write(doc: &ProbeDocument, output: &mut dyn Write) -> std::io::Result<()> {
-    let mut counters = std::collections::BTreeMap::<String, usize>::new();
-    for section in &doc.sections {
-        let key = section.name.to_lowercase();
-        let idx = counters.entry(key.clone()).or_insert(0);
-        write_section(section, &format!("{}.{}", key, *idx), output)?;
-        *idx += 1;
+use crate::model::{ProbeDocument, ProbeField, ProbeSection};
+use crate::writer::{OutputFormatSpec, section_key, writer_option};
+
+pub fn write(
+    doc: &ProbeDocument,
+    output: &mut dyn Write,
+    spec: &OutputFormatSpec,
+) -> std::io::Result<()> {
+    let sep = writer_option(spec, &["sep_char", "s"])
+        .and_then(|v| v.chars().next())
+        .unwrap_or('.');
+    write_sections(&doc.sections, "", sep, output)
+}
+
+fn write_sections(
+    sections: &[ProbeSection],
+    parent_path: &str,
+    sep: char,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let mut counters = BTreeMap::<&str, usize>::new();
+    for section in sections {
+        let (key, is_array) = section_key(&section.name);
+        let path = if is_array {
+            let idx = counters.entry(section.name.as_str()).or_insert(0);
+            let path = format!(
+                "{parent_path}{key}{sep}{}{sep}{idx}{sep}",
+                section.name.to_lowercase()
+            );
+            *idx += 1;
+            path
+        } else {
+            format!("{parent_path}{key}{sep}")
+        };
+        write_section(section, &path, sep, output)?;
     }
     Ok(())
 }
@@ -18,30 +52,48 @@ pub fn write(doc: &ProbeDocument, output: &mut dyn Write) -> std::io::Result<()>
 fn write_section(
     section: &ProbeSection,
     path: &str,
+    sep: char,
     output: &mut dyn Write,
 ) -> std::io::Result<()> {
     for field in &section.fields {
         writeln!(
             output,
-            "{}.{}={}",
+            "{}{}={}",
             path,
-            field.key,
-            quote(&field.value.as_text())
+            escape_key(&field.key),
+            format_value(field)
         )?;
     }
+    write_sections(&section.children, path, sep, output)
+}
 
-    let mut counters = std::collections::BTreeMap::<String, usize>::new();
-    for child in &section.children {
-        let key = child.name.to_lowercase();
-        let idx = counters.entry(key.clone()).or_insert(0);
-        let child_path = format!("{}.{}.{}", path, key, *idx);
-        write_section(child, &child_path, output)?;
-        *idx += 1;
+/// 数值原样输出, 字符串与 N/A 加引号 (与 JSON 输出的字符串判定一致)
+fn format_value(field: &ProbeField) -> String {
+    match field.to_json_value() {
+        Value::String(_) | Value::Null => format!("\"{}\"", escape_value(&field.value.as_text())),
+        _ => field.value.as_text(),
     }
+}
 
-    Ok(())
+/// 键名中非字母数字字符替换为 `_`, 保证可作为 shell 变量名
+fn escape_key(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
 }
 
-fn quote(value: &str) -> String {
-    format!("\"{}\"", value.replace('"', "\\\""))
+fn escape_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\\' | '"' | '`' | '$' => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
 }
@@ -7,7 +7,7 @@ use serde::Serialize;
 use serde_json::{Map, Value};
 
 use crate::model::{ProbeDocument, ProbeField, ProbeSection};
-use crate::writer::OutputFormatSpec;
+use crate::writer::{OutputFormatSpec, section_key};
 
 pub fn write(
     doc: &ProbeDocument,
@@ -18,7 +18,7 @@ pub fn write(
     let mut grouped: BTreeMap<String, (bool, Vec<Map<String, Value>>)> = BTreeMap::new();
 
     for section in &doc.sections {
-        let (key, always_array) = json_key_for_section(&section.name);
+        let (key, always_array) = section_key(&section.name);
         grouped
             .entry(key)
             .or_insert_with(|| (always_array, Vec::new()))
@@ -66,7 +66,7 @@ fn section_to_object(section: &ProbeSection) -> Map<String, Value> {
 
     let mut grouped: BTreeMap<String, (bool, Vec<Map<String, Value>>)> = BTreeMap::new();
     for child in &section.children {
-        let (key, always_array) = json_key_for_section(&child.name);
+        let (key, always_array) = section_key(&child.name);
         grouped
             .entry(key)
             .or_insert_with(|| (always_array, Vec::new()))
@@ -94,22 +94,3 @@ fn section_to_object(section: &ProbeSection) -> Map<String, Value> {
 fn insert_field(object: &mut Map<String, Value>, field: &ProbeField) {
     object.insert(field.key.clone(), field.to_json_value());
 }
-
-fn json_key_for_section(section_name: &str) -> (String, bool) {
-    match section_name {
-        "FORMAT" => ("format".to_string(), false),
-        "STREAM" => ("streams".to_string(), true),
-        "PACKET" => ("packets".to_string(), true),
-        "FRAME" => ("frames".to_string(), true),
-        "PROGRAM" => ("programs".to_string(), true),
-        "STREAM_GROUP" => ("stream_groups".to_string(), true),
-        "CHAPTER" => ("chapters".to_string(), true),
-        "PROGRAM_VERSION" => ("program_version".to_string(), false),
-        "LIBRARY_VERSION" => ("library_versions".to_string(), true),
-        "ERROR" => ("error".to_string(), false),
-        "LOG" => ("log".to_string(), true),
-        "DISPOSITION" => ("disposition".to_string(), false),
-        "TAGS" => ("tags".to_string(), false),
-        other => (other.to_ascii_lowercase(), false),
-    }
-}
@@ -89,15 +89,44 @@ pub fn write_document(
 ) -> std::io::Result<()> {
     match spec.format {
         OutputFormat::Default => default::write(doc, output),
-        OutputFormat::Compact => compact::write(doc, output),
-        OutputFormat::Csv => csv::write(doc, output),
-        OutputFormat::Flat => flat::write(doc, output),
+        OutputFormat::Compact => compact::write(doc, output, spec),
+        OutputFormat::Csv => csv::write(doc, output, spec),
+        OutputFormat::Flat => flat::write(doc, output, spec),
         OutputFormat::Ini => ini::write(doc, output),
         OutputFormat::Json => json::write(doc, output, spec),
         OutputFormat::Xml => xml::write(doc, output),
     }
 }
 
+/// 取 writer 选项, 依次尝试完整名与缩写 (如 `item_sep` / `s`).
+pub(crate) fn writer_option<'a>(spec: &'a OutputFormatSpec, names: &[&str]) -> Option<&'a str> {
+    names
+        .iter()
+        .find_map(|name| spec.options.get(*name))
+        .map(String::as_str)
+}
+
+/// section 在 ffprobe 输出中的外层名称, 以及是否为数组 (如 `STREAM` -> `streams`).
+pub(crate) fn section_key(section_name: &str) -> (String, bool) {
+    match section_name {
+        "FORMAT" => ("format".to_string(), false),
+        "STREAM" => ("streams".to_string(), true),
+        "PACKET" => ("packets".to_string(), true),
+        "FRAME" => ("frames".to_string(), true),
+        "STREAM_HASH" => ("stream_hashes".to_string(), true),
+        "PROGRAM" => ("programs".to_string(), true),
+        "STREAM_GROUP" => ("stream_groups".to_string(), true),
+        "CHAPTER" => ("chapters".to_string(), true),
+        "PROGRAM_VERSION" => ("program_version".to_string(), false),
+        "LIBRARY_VERSION" => ("library_versions".to_string(), true),
+        "ERROR" => ("error".to_string(), false),
+        "LOG" => ("log".to_string(), true),
+        "DISPOSITION" => ("disposition".to_string(), false),
+        "TAGS" => ("tags".to_string(), false),
+        other => (other.to_ascii_lowercase(), false),
+    }
+}
+
 fn parse_option_expr(expr: &str, options: &mut BTreeMap<String, String>) {
     if expr.is_empty() {
         return;
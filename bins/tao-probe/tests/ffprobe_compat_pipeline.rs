@@ -3,6 +3,12 @@
 use std::process::Command;
 use std::sync::{Mutex, OnceLock};
 
+use tao_codec::{CodecId, Packet, PacketFlags};
+use tao_core::{ChannelLayout, MediaType, PixelFormat, Rational, SampleFormat};
+use tao_format::format_id::FormatId;
+use tao_format::io::IoContext;
+use tao_format::registry::FormatRegistry;
+use tao_format::stream::{AudioStreamParams, Stream, StreamParams, VideoStreamParams};
 use tempfile::tempdir;
 
 static TEST_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
@@ -74,14 +80,17 @@ fn normalize_dynamic_text(input: &str) -> String {
 }
 
 fn make_minimal_wav() -> Result<(tempfile::TempDir, String), String> {
+    // 8kHz/16bit/mono, 16 个采样点静音.
+    make_silent_wav(16)
+}
+
+fn make_silent_wav(samples: u32) -> Result<(tempfile::TempDir, String), String> {
     let dir = tempdir().map_err(|e| format!("创建临时目录失败: {}", e))?;
     let file = dir.path().join("sample.wav");
 
-    // 8kHz/16bit/mono, 16 个采样点静音.
     let sample_rate: u32 = 8_000;
     let channels: u16 = 1;
     let bits_per_sample: u16 = 16;
-    let samples: u32 = 16;
     let block_align = channels * (bits_per_sample / 8);
     let byte_rate = sample_rate * block_align as u32;
     let data_size = samples * block_align as u32;
@@ -107,6 +116,144 @@ fn make_minimal_wav() -> Result<(tempfile::TempDir, String), String> {
     Ok((dir, file.to_string_lossy().to_string()))
 }
 
+/// 构造带 MARK/INST 块的 AIFF 样本: 8kHz/16bit/mono, 800 个采样点静音,
+/// 标记 1 "Loop Start" @ 200, 标记 2 "Loop End" @ 600, 正向循环.
+fn make_aiff_with_markers() -> Result<(tempfile::TempDir, String), String> {
+    let dir = tempdir().map_err(|e| format!("创建临时目录失败: {}", e))?;
+    let file = dir.path().join("sample.aiff");
+
+    fn push_chunk(bytes: &mut Vec<u8>, id: &[u8; 4], payload: &[u8]) {
+        bytes.extend_from_slice(id);
+        bytes.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(payload);
+        if payload.len() % 2 != 0 {
+            bytes.push(0);
+        }
+    }
+
+    let samples: u32 = 800;
+    let mut comm = Vec::new();
+    comm.extend_from_slice(&1u16.to_be_bytes());
+    comm.extend_from_slice(&samples.to_be_bytes());
+    comm.extend_from_slice(&16u16.to_be_bytes());
+    // 8000.0 的 80 位扩展精度表示
+    comm.extend_from_slice(&[0x40, 0x0B, 0xFA, 0, 0, 0, 0, 0, 0, 0]);
+
+    let mut ssnd = vec![0u8; 8];
+    ssnd.resize(8 + samples as usize * 2, 0);
+
+    let mut mark = 2u16.to_be_bytes().to_vec();
+    for (id, position, name) in [(1u16, 200u32, "Loop Start"), (2, 600, "Loop End")] {
+        mark.extend_from_slice(&id.to_be_bytes());
+        mark.extend_from_slice(&position.to_be_bytes());
+        mark.push(name.len() as u8);
+        mark.extend_from_slice(name.as_bytes());
+        if name.len() % 2 == 0 {
+            mark.push(0);
+        }
+    }
+
+    let mut inst = vec![60u8, 0, 0, 127, 1, 127, 0, 0];
+    for value in [1u16, 1, 2, 0, 0, 0] {
+        inst.extend_from_slice(&value.to_be_bytes());
+    }
+
+    let mut body = b"AIFF".to_vec();
+    push_chunk(&mut body, b"COMM", &comm);
+    push_chunk(&mut body, b"SSND", &ssnd);
+    push_chunk(&mut body, b"MARK", &mark);
+    push_chunk(&mut body, b"INST", &inst);
+
+    let mut bytes = b"FORM".to_vec();
+    bytes.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(&body);
+    std::fs::write(&file, bytes).map_err(|e| format!("写入 AIFF 失败: {}", e))?;
+    Ok((dir, file.to_string_lossy().to_string()))
+}
+
+/// 构造音视频 AVI 样本: #0 视频 (rawvideo 16x16), #1 音频 (8kHz/16bit/mono PCM), 各 4 个包.
+fn make_av_avi() -> Result<(tempfile::TempDir, String), String> {
+    let dir = tempdir().map_err(|e| format!("创建临时目录失败: {}", e))?;
+    let file = dir.path().join("sample.avi");
+
+    let video = Stream {
+        index: 0,
+        media_type: MediaType::Video,
+        codec_id: CodecId::RawVideo,
+        time_base: Rational::new(1, 25),
+        duration: 0,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data: Vec::new(),
+        params: StreamParams::Video(VideoStreamParams {
+            width: 16,
+            height: 16,
+            pixel_format: PixelFormat::Yuv420p,
+            frame_rate: Rational::new(25, 1),
+            sample_aspect_ratio: Rational::new(1, 1),
+            bit_rate: 0,
+            color_space: Default::default(),
+            color_range: Default::default(),
+        }),
+        metadata: Vec::new(),
+    };
+    let audio = Stream {
+        index: 1,
+        media_type: MediaType::Audio,
+        codec_id: CodecId::PcmS16le,
+        time_base: Rational::new(1, 8000),
+        duration: 0,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data: Vec::new(),
+        params: StreamParams::Audio(AudioStreamParams {
+            sample_rate: 8000,
+            channel_layout: ChannelLayout::MONO,
+            sample_format: SampleFormat::S16,
+            bit_rate: 0,
+            frame_size: 320,
+            block_align: 0,
+        }),
+        metadata: Vec::new(),
+    };
+
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    let mut muxer = registry
+        .create_muxer(FormatId::Avi)
+        .map_err(|e| format!("创建 AVI 封装器失败: {}", e))?;
+    let mut io = IoContext::new_memory();
+    muxer
+        .write_header(&mut io, &[video, audio])
+        .map_err(|e| format!("写入 AVI 头失败: {}", e))?;
+    for i in 0..4i64 {
+        let mut packet = Packet::new(vec![0u8; 16 * 16 * 3 / 2], 0);
+        packet.pts = i;
+        packet.dts = i;
+        packet.flags = PacketFlags::KEYFRAME;
+        muxer
+            .write_packet(&mut io, &packet)
+            .map_err(|e| format!("写入视频包失败: {}", e))?;
+
+        let mut packet = Packet::new(vec![0u8; 640], 1);
+        packet.pts = i * 320;
+        packet.dts = i * 320;
+        packet.flags = PacketFlags::KEYFRAME;
+        muxer
+            .write_packet(&mut io, &packet)
+            .map_err(|e| format!("写入音频包失败: {}", e))?;
+    }
+    muxer
+        .write_trailer(&mut io)
+        .map_err(|e| format!("写入 AVI 尾失败: {}", e))?;
+
+    let bytes = io
+        .to_vec()
+        .map_err(|e| format!("读取 AVI 数据失败: {}", e))?;
+    std::fs::write(&file, bytes).map_err(|e| format!("写入 AVI 失败: {}", e))?;
+    Ok((dir, file.to_string_lossy().to_string()))
+}
+
 #[test]
 fn test_parser_unknown_option_alignment() {
     let _guard = TEST_LOCK
@@ -186,6 +333,99 @@ fn test_writer_json_basic_output() {
     );
 }
 
+#[test]
+fn test_writer_flat_single_audio_stream_keys() {
+    let _guard = TEST_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    let (_dir, wav_path) = make_minimal_wav().expect("构造 WAV 样本失败");
+    let tao = run_tao_probe(&[
+        "-v",
+        "error",
+        "-show_format",
+        "-show_streams",
+        "--of",
+        "flat",
+        &wav_path,
+    ])
+    .expect("tao-probe 执行失败");
+    assert_eq!(tao.code, 0, "flat 输出应成功: {}", tao.stderr);
+
+    let lines: Vec<&str> = tao.stdout.lines().collect();
+    for expected in [
+        "streams.stream.0.index=0",
+        "streams.stream.0.codec_name=\"pcm_s16le\"",
+        "streams.stream.0.codec_type=\"audio\"",
+        "streams.stream.0.sample_rate=\"8000\"",
+        "streams.stream.0.channels=1",
+        "streams.stream.0.disposition.default=0",
+        "format.format_name=\"wav\"",
+        "format.nb_streams=1",
+    ] {
+        assert!(
+            lines.contains(&expected),
+            "flat 输出应包含 {expected}:\n{}",
+            tao.stdout
+        );
+    }
+    assert!(
+        !tao.stdout.contains("streams.stream.1."),
+        "单音频流不应输出第二条流"
+    );
+    for line in &lines {
+        let (key, _) = line.split_once('=').expect("每行应为 key=value");
+        assert!(
+            key.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.'),
+            "键名应只含字母数字与分隔符: {key}"
+        );
+    }
+}
+
+#[test]
+fn test_writer_csv_stream_rows() {
+    let _guard = TEST_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    let (_dir, avi_path) = make_av_avi().expect("构造 AVI 样本失败");
+    let args = [
+        "-v",
+        "error",
+        "-show_entries",
+        "stream=index,codec_type",
+        "--of",
+        "csv",
+        &avi_path,
+    ];
+    let tao = run_tao_probe(&args).expect("tao-probe 执行失败");
+    assert_eq!(tao.code, 0, "csv 输出应成功: {}", tao.stderr);
+    assert_eq!(
+        tao.stdout.lines().collect::<Vec<_>>(),
+        vec!["stream,0,video", "stream,1,audio"],
+        "csv 应每条流一行, 省略键名"
+    );
+
+    let args = [
+        "-v",
+        "error",
+        "-show_entries",
+        "stream=codec_type",
+        "--of",
+        "csv=p=0",
+        &avi_path,
+    ];
+    let tao = run_tao_probe(&args).expect("tao-probe 执行失败");
+    assert_eq!(
+        tao.stdout.lines().collect::<Vec<_>>(),
+        vec!["video", "audio"],
+        "p=0 应省略行首 section 名"
+    );
+}
+
 #[test]
 fn test_show_packets_alignment_with_ffprobe() {
     let _guard = TEST_LOCK
@@ -249,6 +489,65 @@ fn test_select_streams_audio_first_matches_wav() {
     );
 }
 
+#[test]
+fn test_select_streams_audio_omits_video_stream() {
+    let _guard = TEST_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    if has_ffprobe() {
+        // `--select-streams` 为 tao 别名, ffprobe 透传时无法识别
+        return;
+    }
+
+    let (_dir, avi_path) = make_av_avi().expect("构造 AVI 样本失败");
+    for spec in ["a", "0:a", "0:1"] {
+        let args = [
+            "-v",
+            "error",
+            "-show_streams",
+            "-show_packets",
+            "--select-streams",
+            spec,
+            "-of",
+            "json",
+            &avi_path,
+        ];
+        let tao = run_tao_probe(&args).expect("tao-probe 执行失败");
+        assert_eq!(
+            tao.code, 0,
+            "--select-streams {spec} 应成功执行: {}",
+            tao.stderr
+        );
+
+        let parsed: serde_json::Value =
+            serde_json::from_str(&tao.stdout).expect("stdout 应为合法 JSON");
+        let streams = parsed
+            .get("streams")
+            .and_then(|v| v.as_array())
+            .expect("JSON 输出应包含 streams");
+        assert_eq!(streams.len(), 1, "--select-streams {spec} 应只输出一条流");
+        assert_eq!(
+            streams[0].get("codec_type").and_then(|v| v.as_str()),
+            Some("audio"),
+            "--select-streams {spec} 应省略视频流"
+        );
+
+        let packets = parsed
+            .get("packets")
+            .and_then(|v| v.as_array())
+            .expect("JSON 输出应包含 packets");
+        assert!(!packets.is_empty(), "应输出音频数据包");
+        assert!(
+            packets
+                .iter()
+                .all(|p| p.get("codec_type").and_then(|v| v.as_str()) == Some("audio")),
+            "--select-streams {spec} 不应输出视频数据包"
+        );
+    }
+}
+
 #[test]
 fn test_show_entries_filters_stream_fields() {
     let _guard = TEST_LOCK
@@ -290,3 +589,318 @@ fn test_show_entries_filters_stream_fields() {
         "show_entries 过滤后不应包含 codec_name 字段"
     );
 }
+
+#[test]
+fn test_show_packets_json_matches_packet_count() {
+    let _guard = TEST_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    // 1 秒音频, 按 4096 采样点分包应得到多个数据包.
+    let (_dir, wav_path) = make_silent_wav(8_000).expect("构造 WAV 样本失败");
+    let args = [
+        "-v",
+        "error",
+        "-show_packets",
+        "-show_streams",
+        "-count_packets",
+        "-of",
+        "json",
+        &wav_path,
+    ];
+    let tao = run_tao_probe(&args).expect("tao-probe 执行失败");
+    assert_eq!(tao.code, 0, "show_packets JSON 输出应成功");
+
+    let parsed: serde_json::Value =
+        serde_json::from_str(&tao.stdout).expect("stdout 应为合法 JSON");
+    let packets = parsed
+        .get("packets")
+        .and_then(|v| v.as_array())
+        .expect("JSON 输出应包含 packets 数组");
+    let nb_read_packets = parsed
+        .get("streams")
+        .and_then(|v| v.as_array())
+        .and_then(|arr| arr.first())
+        .and_then(|s| s.get("nb_read_packets"))
+        .and_then(|v| v.as_u64())
+        .expect("streams[0] 应包含 nb_read_packets");
+
+    assert!(packets.len() > 1, "1 秒 WAV 应拆分为多个数据包");
+    assert_eq!(
+        packets.len() as u64,
+        nb_read_packets,
+        "packets 数组长度应等于流的数据包数"
+    );
+    for packet in packets {
+        assert_eq!(
+            packet.get("stream_index").and_then(|v| v.as_u64()),
+            Some(0),
+            "数据包应属于 0 号流"
+        );
+        assert!(packet.get("pts").is_some(), "数据包应包含 pts");
+        assert!(packet.get("size").is_some(), "数据包应包含 size");
+        assert!(
+            packet
+                .get("flags")
+                .and_then(|v| v.as_str())
+                .is_some_and(|flags| flags.starts_with('K')),
+            "PCM 数据包应标记为关键帧"
+        );
+    }
+}
+
+#[test]
+fn test_show_frames_reports_first_video_frame_as_i() {
+    let _guard = TEST_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    // 64x48 H.264 裸流, 含 2 个 IDR 帧.
+    let clip = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../tests/fixtures/golden/h264_cabac_iframes.h264"
+    );
+    let tao = run_tao_probe(&["-v", "error", "--show-frames", "-of", "json", clip])
+        .expect("tao-probe 执行失败");
+    assert_eq!(tao.code, 0, "show_frames JSON 输出应成功: {}", tao.stderr);
+
+    let parsed: serde_json::Value =
+        serde_json::from_str(&tao.stdout).expect("stdout 应为合法 JSON");
+    let frames = parsed
+        .get("frames")
+        .and_then(|v| v.as_array())
+        .expect("JSON 输出应包含 frames 数组");
+    assert_eq!(frames.len(), 2, "应解码出 2 个视频帧");
+
+    let first = &frames[0];
+    assert_eq!(
+        first.get("media_type").and_then(|v| v.as_str()),
+        Some("video")
+    );
+    assert_eq!(
+        first.get("pict_type").and_then(|v| v.as_str()),
+        Some("I"),
+        "首个视频帧应为 I 帧"
+    );
+    assert_eq!(first.get("key_frame").and_then(|v| v.as_i64()), Some(1));
+    assert_eq!(first.get("width").and_then(|v| v.as_u64()), Some(64));
+    assert_eq!(first.get("height").and_then(|v| v.as_u64()), Some(48));
+    assert_eq!(first.get("pts").and_then(|v| v.as_i64()), Some(0));
+}
+
+#[test]
+fn test_show_frames_audio_reports_nb_samples() {
+    let _guard = TEST_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    let (_dir, wav_path) = make_silent_wav(8_000).expect("构造 WAV 样本失败");
+    let tao = run_tao_probe(&["-v", "error", "-show_frames", "-of", "json", &wav_path])
+        .expect("tao-probe 执行失败");
+    assert_eq!(tao.code, 0, "show_frames JSON 输出应成功: {}", tao.stderr);
+
+    let parsed: serde_json::Value =
+        serde_json::from_str(&tao.stdout).expect("stdout 应为合法 JSON");
+    let frames = parsed
+        .get("frames")
+        .and_then(|v| v.as_array())
+        .expect("JSON 输出应包含 frames 数组");
+    let total: u64 = frames
+        .iter()
+        .map(|f| f.get("nb_samples").and_then(|v| v.as_u64()).unwrap_or(0))
+        .sum();
+    assert_eq!(total, 8_000, "各帧 nb_samples 之和应等于总采样数");
+    for frame in frames {
+        assert_eq!(
+            frame.get("sample_rate").and_then(|v| v.as_u64()),
+            Some(8_000),
+            "音频帧应报告采样率"
+        );
+        assert!(frame.get("pts").is_some(), "音频帧应包含 pts");
+    }
+}
+
+/// 读取 `--show-hash` JSON 输出中的 stream_hashes 与逐帧哈希
+fn probe_hashes(args: &[&str]) -> (Vec<serde_json::Value>, Vec<String>) {
+    let tao = run_tao_probe(args).expect("tao-probe 执行失败");
+    assert_eq!(tao.code, 0, "show_hash JSON 输出应成功: {}", tao.stderr);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&tao.stdout).expect("stdout 应为合法 JSON");
+    let stream_hashes = parsed
+        .get("stream_hashes")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .expect("JSON 输出应包含 stream_hashes 数组");
+    let frame_hashes = parsed
+        .get("frames")
+        .and_then(|v| v.as_array())
+        .map(|frames| {
+            frames
+                .iter()
+                .map(|f| {
+                    f.get("data_hash")
+                        .and_then(|v| v.as_str())
+                        .expect("逐帧输出应包含 data_hash")
+                        .to_string()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    (stream_hashes, frame_hashes)
+}
+
+#[test]
+fn test_show_hash_is_deterministic_across_runs() {
+    let _guard = TEST_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    let clip = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../tests/fixtures/golden/h264_cabac_iframes.h264"
+    );
+    let args = [
+        "-v",
+        "error",
+        "--show-hash",
+        "md5",
+        "-show_frames",
+        "-of",
+        "json",
+        clip,
+    ];
+    let (first_streams, first_frames) = probe_hashes(&args);
+    let (second_streams, second_frames) = probe_hashes(&args);
+
+    assert_eq!(first_streams.len(), 1, "应输出 1 条视频流的哈希");
+    let stream = &first_streams[0];
+    assert_eq!(
+        stream.get("codec_type").and_then(|v| v.as_str()),
+        Some("video")
+    );
+    assert_eq!(stream.get("nb_frames").and_then(|v| v.as_u64()), Some(2));
+    let hash = stream.get("data_hash").and_then(|v| v.as_str()).unwrap();
+    assert!(
+        hash.starts_with("MD5:") && hash.len() == 36,
+        "MD5 哈希格式错误: {hash}"
+    );
+
+    assert_eq!(first_frames.len(), 2, "每个解码帧都应输出哈希");
+    assert_ne!(first_frames[0], first_frames[1], "不同帧内容的哈希应不同");
+    assert_eq!(first_streams, second_streams, "两次解码的整体哈希应一致");
+    assert_eq!(first_frames, second_frames, "两次解码的逐帧哈希应一致");
+}
+
+#[test]
+fn test_show_hash_audio_matches_reference() {
+    let _guard = TEST_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    // 8000 个 16 位静音采样, 即 16000 个零字节
+    let (_dir, wav_path) = make_silent_wav(8_000).expect("构造 WAV 样本失败");
+    let (md5, frames) =
+        probe_hashes(&["-v", "error", "-show_hash", "md5", "-of", "json", &wav_path]);
+    assert!(frames.is_empty(), "未指定 -show_frames 时不应输出逐帧信息");
+    assert_eq!(
+        md5[0].get("data_hash").and_then(|v| v.as_str()),
+        Some("MD5:1ee0193671609c7d63cfe89b920ad313")
+    );
+
+    let (crc, _) = probe_hashes(&[
+        "-v",
+        "error",
+        "-show_hash",
+        "crc32",
+        "-of",
+        "json",
+        &wav_path,
+    ]);
+    assert_eq!(
+        crc[0].get("data_hash").and_then(|v| v.as_str()),
+        Some("CRC32:577ce78d")
+    );
+
+    let tao = run_tao_probe(&["-v", "error", "-show_hash", "sha1", &wav_path])
+        .expect("tao-probe 执行失败");
+    assert_ne!(tao.code, 0, "不支持的哈希算法应执行失败");
+    assert!(
+        tao.stderr.contains("md5/crc32"),
+        "错误信息应列出可选算法: {}",
+        tao.stderr
+    );
+}
+
+#[test]
+fn test_probe_low_score_warning() {
+    let _guard = TEST_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    // 仅两个 TS 空包且扩展名无法识别: 以低置信度探测为 MPEG-TS
+    let dir = tempdir().expect("创建临时目录失败");
+    let path = dir.path().join("short.bin");
+    let mut data = Vec::new();
+    for _ in 0..2 {
+        let mut pkt = [0xFFu8; 188];
+        pkt[..4].copy_from_slice(&[0x47, 0x1F, 0xFF, 0x10]);
+        data.extend_from_slice(&pkt);
+    }
+    std::fs::write(&path, data).expect("写入样本失败");
+    let path = path.to_string_lossy().to_string();
+
+    let tao = run_tao_probe(&["-show_format", &path]).expect("tao-probe 执行失败");
+    assert!(
+        tao.stderr
+            .contains("Format mpegts detected only with low score of 25"),
+        "低置信度应输出警告: {}",
+        tao.stderr
+    );
+
+    let tao = run_tao_probe(&["-v", "error", "-show_format", &path]).expect("tao-probe 执行失败");
+    assert!(
+        !tao.stderr.contains("low score"),
+        "-v error 时不应输出警告: {}",
+        tao.stderr
+    );
+}
+
+#[test]
+fn test_show_chapters_aiff_markers_and_instrument_tags() {
+    let _guard = TEST_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+
+    let (_dir, aiff_path) = make_aiff_with_markers().expect("构造 AIFF 样本失败");
+    let args = [
+        "-v",
+        "error",
+        "-show_format",
+        "-show_chapters",
+        "-of",
+        "json",
+        &aiff_path,
+    ];
+    let tao = run_tao_probe(&args).expect("tao-probe 执行失败");
+    assert_eq!(tao.code, 0, "show_chapters 应成功: {}", tao.stderr);
+    let parsed: serde_json::Value =
+        serde_json::from_str(&tao.stdout).expect("stdout 应为合法 JSON");
+
+    let chapters = parsed["chapters"].as_array().expect("应包含 chapters 数组");
+    assert_eq!(chapters.len(), 2, "每个 AIFF 标记对应一个章节");
+    assert_eq!(chapters[0]["tags"]["title"], "Loop Start");
+    assert_eq!(chapters[1]["start"], 75_000, "600/8000 秒 = 75000 微秒");
+
+    let tags = &parsed["format"]["tags"];
+    assert_eq!(tags["base_note"], "60", "INST 基准音高应输出到 format tags");
+    assert_eq!(tags["loop_type"], "forward");
+    assert_eq!(tags["loop_start"], "200");
+    assert_eq!(tags["loop_end"], "600");
+}
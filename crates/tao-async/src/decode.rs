@@ -0,0 +1,231 @@
+//! 异步解码流.
+
+use std::future::{Future, poll_fn};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tao_codec::{Decoder, Frame, Packet};
+use tao_core::{TaoError, TaoResult};
+use tokio::task::JoinHandle;
+
+use crate::format::DemuxState;
+use crate::join_error;
+
+/// 异步解码流
+///
+/// 读取指定流的数据包并解码, 以 [`Stream`] 逐帧输出. 输入结束后自动刷新解码器,
+/// 取出缓存帧后流结束. 遇到错误时输出一次 `Err`, 之后流结束.
+///
+/// 每帧的读包与解码在 `tokio::task::spawn_blocking` 中执行, 因此必须在 tokio 运行时中轮询.
+pub struct AsyncDecodeStream {
+    state: DecodeState,
+}
+
+enum DecodeState {
+    /// 空闲, 等待下一次轮询
+    Idle(Box<DecodeJob>),
+    /// 阻塞线程正在产出下一帧
+    Running(JoinHandle<(Box<DecodeJob>, Option<TaoResult<Frame>>)>),
+    /// 已结束
+    Done,
+}
+
+/// 在阻塞线程中执行的解码任务
+struct DecodeJob {
+    demux: Box<DemuxState>,
+    decoder: Box<dyn Decoder>,
+    stream_index: usize,
+    /// 输入已结束, 正在取出解码器缓存帧
+    draining: bool,
+}
+
+impl DecodeJob {
+    /// 产出下一帧, 所有帧已取出时返回 None
+    fn next_frame(&mut self) -> Option<TaoResult<Frame>> {
+        loop {
+            match self.decoder.receive_frame() {
+                Ok(frame) => return Some(Ok(frame)),
+                Err(TaoError::NeedMoreData) if !self.draining => {}
+                Err(TaoError::NeedMoreData | TaoError::Eof) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+            let demux = &mut *self.demux;
+            let sent = match demux.demuxer.read_packet(&mut demux.io) {
+                Ok(pkt) if pkt.stream_index != self.stream_index => continue,
+                Ok(pkt) => self.decoder.send_packet(&pkt),
+                Err(TaoError::Eof) => {
+                    self.draining = true;
+                    self.decoder.send_packet(&Packet::empty())
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = sent {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl AsyncDecodeStream {
+    pub(crate) fn new(
+        demux: Box<DemuxState>,
+        decoder: Box<dyn Decoder>,
+        stream_index: usize,
+    ) -> Self {
+        let job = DecodeJob {
+            demux,
+            decoder,
+            stream_index,
+            draining: false,
+        };
+        Self {
+            state: DecodeState::Idle(Box::new(job)),
+        }
+    }
+
+    /// 等待下一帧, 流结束时返回 None
+    pub async fn next_frame(&mut self) -> Option<TaoResult<Frame>> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+impl Stream for AsyncDecodeStream {
+    type Item = TaoResult<Frame>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match std::mem::replace(&mut self.state, DecodeState::Done) {
+                DecodeState::Idle(mut job) => {
+                    let handle = tokio::task::spawn_blocking(move || {
+                        let result = job.next_frame();
+                        (job, result)
+                    });
+                    self.state = DecodeState::Running(handle);
+                }
+                DecodeState::Running(mut handle) => {
+                    return match Pin::new(&mut handle).poll(cx) {
+                        Poll::Pending => {
+                            self.state = DecodeState::Running(handle);
+                            Poll::Pending
+                        }
+                        Poll::Ready(Ok((job, Some(Ok(frame))))) => {
+                            self.state = DecodeState::Idle(job);
+                            Poll::Ready(Some(Ok(frame)))
+                        }
+                        Poll::Ready(Ok((_, Some(Err(e))))) => Poll::Ready(Some(Err(e))),
+                        Poll::Ready(Ok((_, None))) => Poll::Ready(None),
+                        Poll::Ready(Err(e)) => Poll::Ready(Some(Err(join_error(e)))),
+                    };
+                }
+                DecodeState::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    use tao_codec::CodecRegistry;
+    use tao_format::FormatRegistry;
+
+    use crate::{AsyncFormatContext, AsyncIoContext, read_packet};
+
+    use super::*;
+
+    /// 构造单声道 S16 PCM WAV 文件, 采样值为 0..samples
+    fn build_wav(samples: u16) -> Vec<u8> {
+        let data_len = u32::from(samples) * 2;
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"RIFF");
+        buf.extend_from_slice(&(36 + data_len).to_le_bytes());
+        buf.extend_from_slice(b"WAVEfmt ");
+        buf.extend_from_slice(&16u32.to_le_bytes());
+        buf.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        buf.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+        buf.extend_from_slice(&8000u32.to_le_bytes());
+        buf.extend_from_slice(&16000u32.to_le_bytes());
+        buf.extend_from_slice(&2u16.to_le_bytes());
+        buf.extend_from_slice(&16u16.to_le_bytes());
+        buf.extend_from_slice(b"data");
+        buf.extend_from_slice(&data_len.to_le_bytes());
+        for s in 0..samples {
+            buf.extend_from_slice(&s.to_le_bytes());
+        }
+        buf
+    }
+
+    fn registries() -> (Arc<FormatRegistry>, CodecRegistry) {
+        let mut format_reg = FormatRegistry::new();
+        tao_format::register_all(&mut format_reg);
+        let mut codec_reg = CodecRegistry::new();
+        tao_codec::register_all(&mut codec_reg);
+        (Arc::new(format_reg), codec_reg)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_async_read_packet_until_eof() {
+        let (format_reg, _) = registries();
+        let io = AsyncIoContext::new(Cursor::new(build_wav(5000)))
+            .await
+            .unwrap();
+        let mut ctx = AsyncFormatContext::open(io, format_reg).await.unwrap();
+        assert_eq!(ctx.streams().len(), 1);
+
+        let mut total = 0;
+        loop {
+            match read_packet(&mut ctx).await {
+                Ok(pkt) => total += pkt.data.len(),
+                Err(TaoError::Eof) => break,
+                Err(e) => panic!("读包失败: {e}"),
+            }
+        }
+        assert_eq!(total, 10000, "应读出全部 PCM 数据");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_async_decode_stream_yields_all_samples() {
+        let (format_reg, codec_reg) = registries();
+        let io = AsyncIoContext::new(Cursor::new(build_wav(5000)))
+            .await
+            .unwrap();
+        let ctx = AsyncFormatContext::open(io, format_reg).await.unwrap();
+        let mut frames = ctx.decode_stream(0, &codec_reg).unwrap();
+
+        let mut samples = 0u32;
+        while let Some(frame) = frames.next_frame().await {
+            match frame.unwrap() {
+                Frame::Audio(af) => samples += af.nb_samples,
+                _ => panic!("期望音频帧"),
+            }
+        }
+        assert_eq!(samples, 5000);
+        assert!(frames.next_frame().await.is_none(), "结束后应保持结束状态");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_async_decode_stream_on_current_thread_runtime() {
+        let (format_reg, codec_reg) = registries();
+        let ctx = AsyncFormatContext::open(AsyncIoContext::from_bytes(build_wav(100)), format_reg)
+            .await
+            .unwrap();
+        let mut frames = ctx.decode_stream(0, &codec_reg).unwrap();
+        let first = frames.next_frame().await.unwrap().unwrap();
+        assert!(matches!(first, Frame::Audio(_)));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_async_decode_stream_rejects_missing_stream() {
+        let (format_reg, codec_reg) = registries();
+        let ctx = AsyncFormatContext::open(AsyncIoContext::from_bytes(build_wav(100)), format_reg)
+            .await
+            .unwrap();
+        assert!(matches!(
+            ctx.decode_stream(3, &codec_reg),
+            Err(TaoError::StreamNotFound(3))
+        ));
+    }
+}
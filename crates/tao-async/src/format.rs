@@ -0,0 +1,122 @@
+//! 异步解封装上下文.
+
+use std::sync::Arc;
+
+use tao_codec::{CodecRegistry, Packet};
+use tao_core::TaoResult;
+use tao_format::demuxer::SeekFlags;
+use tao_format::{Demuxer, FormatRegistry, IoContext, Stream};
+
+use crate::decode::AsyncDecodeStream;
+use crate::io::{AsyncIoContext, lost_context};
+use crate::run_blocking;
+
+/// 解封装器与其 I/O 上下文
+pub(crate) struct DemuxState {
+    pub(crate) demuxer: Box<dyn Demuxer>,
+    pub(crate) io: IoContext,
+}
+
+/// 异步解封装上下文
+///
+/// 对标 FFmpeg 的 `AVFormatContext`, 持有已打开的解封装器与 I/O 上下文.
+/// 探测、打开与读包均在阻塞线程池中执行.
+pub struct AsyncFormatContext {
+    /// 解封装状态, 执行阻塞任务期间为 None
+    state: Option<Box<DemuxState>>,
+    /// 打开时解析出的流信息
+    streams: Vec<Stream>,
+    /// 容器时长 (秒)
+    duration: Option<f64>,
+}
+
+impl AsyncFormatContext {
+    /// 自动探测格式并打开输入
+    pub async fn open(io: AsyncIoContext, registry: Arc<FormatRegistry>) -> TaoResult<Self> {
+        let mut io = io.into_inner()?;
+        let state = run_blocking(move || {
+            let filename = io.source_path().map(str::to_string);
+            let demuxer = registry.open_input(&mut io, filename.as_deref())?;
+            Ok::<_, tao_core::TaoError>(DemuxState { demuxer, io })
+        })
+        .await??;
+        Ok(Self::from_state(Box::new(state)))
+    }
+
+    /// 封装已打开的解封装器与 I/O 上下文
+    pub fn from_demuxer(demuxer: Box<dyn Demuxer>, io: IoContext) -> Self {
+        Self::from_state(Box::new(DemuxState { demuxer, io }))
+    }
+
+    fn from_state(state: Box<DemuxState>) -> Self {
+        Self {
+            streams: state.demuxer.streams().to_vec(),
+            duration: state.demuxer.duration(),
+            state: Some(state),
+        }
+    }
+
+    /// 获取所有流信息
+    pub fn streams(&self) -> &[Stream] {
+        &self.streams
+    }
+
+    /// 获取容器时长 (秒), None 表示未知
+    pub fn duration(&self) -> Option<f64> {
+        self.duration
+    }
+
+    /// 读取下一个数据包, 到达末尾时返回 `Err(TaoError::Eof)`
+    pub async fn read_packet(&mut self) -> TaoResult<Packet> {
+        self.with_state(|s| s.demuxer.read_packet(&mut s.io)).await
+    }
+
+    /// 定位到指定时间点 (以流的 time_base 为单位)
+    pub async fn seek(
+        &mut self,
+        stream_index: usize,
+        timestamp: i64,
+        flags: SeekFlags,
+    ) -> TaoResult<()> {
+        self.with_state(move |s| s.demuxer.seek(&mut s.io, stream_index, timestamp, flags))
+            .await
+    }
+
+    /// 为指定流创建解码器, 转换为逐帧输出的异步流
+    pub fn decode_stream(
+        mut self,
+        stream_index: usize,
+        codec_registry: &CodecRegistry,
+    ) -> TaoResult<AsyncDecodeStream> {
+        let stream = self
+            .streams
+            .get(stream_index)
+            .ok_or(tao_core::TaoError::StreamNotFound(stream_index))?;
+        let decoder = stream.open_decoder(codec_registry)?;
+        let state = self.state.take().ok_or_else(lost_context)?;
+        Ok(AsyncDecodeStream::new(state, decoder, stream_index))
+    }
+
+    /// 在阻塞线程中对解封装状态执行操作
+    async fn with_state<T, F>(&mut self, op: F) -> TaoResult<T>
+    where
+        F: FnOnce(&mut DemuxState) -> TaoResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let mut state = self.state.take().ok_or_else(lost_context)?;
+        let (state, result) = run_blocking(move || {
+            let result = op(&mut state);
+            (state, result)
+        })
+        .await?;
+        self.state = Some(state);
+        result
+    }
+}
+
+/// 异步读取下一个数据包
+///
+/// 等价于 [`AsyncFormatContext::read_packet`].
+pub async fn read_packet(ctx: &mut AsyncFormatContext) -> TaoResult<Packet> {
+    ctx.read_packet().await
+}
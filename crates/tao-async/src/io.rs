@@ -0,0 +1,191 @@
+//! 异步 I/O 上下文.
+//!
+//! 将 tokio 异步数据源桥接为同步 [`IoBackend`], 供在阻塞线程中运行的解封装器使用.
+
+use std::io::{self, SeekFrom};
+
+use tao_core::{TaoError, TaoResult};
+use tao_format::IoContext;
+use tao_format::io::{IoBackend, MemoryBackend};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+use tokio::runtime::Handle;
+
+use crate::run_blocking;
+
+/// 异步 I/O 上下文
+///
+/// 封装 `tokio::io::AsyncRead + AsyncSeek` 数据源. 内部持有同步 [`IoContext`],
+/// 所有读取与定位都在阻塞线程池中完成, 不会阻塞运行时工作线程.
+///
+/// 若某次异步操作的 future 在完成前被丢弃, 内部上下文随之丢失,
+/// 之后的操作返回 [`TaoError::Internal`].
+pub struct AsyncIoContext {
+    /// 同步 I/O 上下文, 执行阻塞任务期间为 None
+    io: Option<IoContext>,
+}
+
+impl AsyncIoContext {
+    /// 封装异步数据源
+    ///
+    /// 需在 tokio 运行时中调用, 数据源的读取经由当前运行时驱动.
+    pub async fn new<R>(reader: R) -> TaoResult<Self>
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send + 'static,
+    {
+        let backend = AsyncBridgeBackend::new(reader).await?;
+        Ok(Self::from_io(IoContext::new(Box::new(backend))))
+    }
+
+    /// 以 `tokio::fs::File` 打开文件 (只读)
+    pub async fn open(path: &str) -> TaoResult<Self> {
+        let file = tokio::fs::File::open(path).await?;
+        let backend = AsyncBridgeBackend::new(file).await?;
+        Ok(Self::from_io(IoContext::new_with_source(
+            Box::new(backend),
+            path.to_string(),
+        )))
+    }
+
+    /// 从内存数据创建 (只读)
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        Self::from_io(IoContext::new(Box::new(MemoryBackend::read_only(data))))
+    }
+
+    /// 封装已有的同步 I/O 上下文
+    pub fn from_io(io: IoContext) -> Self {
+        Self { io: Some(io) }
+    }
+
+    /// 获取总大小 (如果可知)
+    pub fn size(&self) -> Option<u64> {
+        self.io.as_ref().and_then(IoContext::size)
+    }
+
+    /// 读取指定字节数
+    pub async fn read_bytes(&mut self, count: usize) -> TaoResult<Vec<u8>> {
+        self.with_io(move |io| io.read_bytes(count)).await
+    }
+
+    /// 定位 (seek), 返回新位置
+    pub async fn seek(&mut self, pos: SeekFrom) -> TaoResult<u64> {
+        self.with_io(move |io| io.seek(pos)).await
+    }
+
+    /// 获取当前位置
+    pub async fn position(&mut self) -> TaoResult<u64> {
+        self.with_io(IoContext::position).await
+    }
+
+    /// 在阻塞线程中对内部同步上下文执行操作
+    pub async fn with_io<T, F>(&mut self, op: F) -> TaoResult<T>
+    where
+        F: FnOnce(&mut IoContext) -> TaoResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let mut io = self.take()?;
+        let (io, result) = run_blocking(move || {
+            let result = op(&mut io);
+            (io, result)
+        })
+        .await?;
+        self.io = Some(io);
+        result
+    }
+
+    /// 取出内部同步上下文
+    pub fn into_inner(self) -> TaoResult<IoContext> {
+        self.io.ok_or_else(lost_context)
+    }
+
+    fn take(&mut self) -> TaoResult<IoContext> {
+        self.io.take().ok_or_else(lost_context)
+    }
+}
+
+/// 上下文在被取消的异步操作中丢失时的错误
+pub(crate) fn lost_context() -> TaoError {
+    TaoError::Internal("上一次异步操作被取消, I/O 上下文已失效".into())
+}
+
+/// 异步数据源到同步 I/O 后端的桥接
+///
+/// 通过运行时句柄的 `block_on` 驱动异步读取, 只能在阻塞线程
+/// (如 `spawn_blocking` 任务) 中使用, 在异步任务中直接调用会 panic.
+struct AsyncBridgeBackend<R> {
+    reader: R,
+    handle: Handle,
+    size: u64,
+}
+
+impl<R> AsyncBridgeBackend<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin + Send,
+{
+    /// 绑定当前运行时, 并在保持读取位置不变的前提下获取数据源大小
+    async fn new(mut reader: R) -> TaoResult<Self> {
+        let position = reader.stream_position().await?;
+        let size = reader.seek(SeekFrom::End(0)).await?;
+        reader.seek(SeekFrom::Start(position)).await?;
+        Ok(Self {
+            reader,
+            handle: Handle::current(),
+            size,
+        })
+    }
+}
+
+impl<R> IoBackend for AsyncBridgeBackend<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin + Send,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.handle.block_on(self.reader.read(buf))
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "异步数据源不支持写入",
+        ))
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.write(buf).map(|_| ())
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.handle.block_on(self.reader.seek(pos))
+    }
+
+    fn position(&mut self) -> io::Result<u64> {
+        self.handle.block_on(self.reader.stream_position())
+    }
+
+    fn size(&self) -> Option<u64> {
+        Some(self.size)
+    }
+
+    fn is_seekable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_async_io_reads_and_seeks_async_source() {
+        let mut io = AsyncIoContext::new(Cursor::new((0u8..64).collect::<Vec<_>>()))
+            .await
+            .unwrap();
+        assert_eq!(io.size(), Some(64));
+        assert_eq!(io.read_bytes(4).await.unwrap(), vec![0, 1, 2, 3]);
+        assert_eq!(io.seek(SeekFrom::Start(60)).await.unwrap(), 60);
+        assert_eq!(io.read_bytes(4).await.unwrap(), vec![60, 61, 62, 63]);
+        assert!(matches!(io.read_bytes(1).await, Err(TaoError::Eof)));
+        let err = io.with_io(|io| io.write_u8(0)).await;
+        assert!(err.is_err(), "异步数据源应拒绝写入");
+    }
+}
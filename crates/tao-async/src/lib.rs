@@ -0,0 +1,61 @@
+//! # tao-async
+//!
+//! Tao 多媒体框架异步 (tokio) 封装层.
+//!
+//! tao 的 I/O、解封装与解码均为同步接口, 直接在异步任务中调用会阻塞运行时工作线程.
+//! 本 crate 在同步接口之上提供 tokio 封装:
+//!
+//! - [`AsyncIoContext`]: 封装 `tokio::io::AsyncRead + AsyncSeek` 数据源
+//! - [`AsyncFormatContext`] / [`read_packet`]: 异步读取数据包
+//! - [`AsyncDecodeStream`]: 以 [`futures_core::Stream`] 逐帧输出解码结果
+//!
+//! 解封装与解码 (CPU 密集) 通过 `tokio::task::spawn_blocking` 在阻塞线程池中执行,
+//! 阻塞线程上的数据读取再经由运行时句柄驱动底层异步数据源.
+//!
+//! ## 使用示例
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use tao_async::{AsyncFormatContext, AsyncIoContext};
+//!
+//! # async fn run() -> tao_core::TaoResult<()> {
+//! let mut format_reg = tao_format::FormatRegistry::new();
+//! tao_format::register_all(&mut format_reg);
+//! let mut codec_reg = tao_codec::CodecRegistry::new();
+//! tao_codec::register_all(&mut codec_reg);
+//!
+//! let io = AsyncIoContext::open("input.wav").await?;
+//! let ctx = AsyncFormatContext::open(io, Arc::new(format_reg)).await?;
+//! let mut frames = ctx.decode_stream(0, &codec_reg)?;
+//! while let Some(frame) = frames.next_frame().await {
+//!     let _frame = frame?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod decode;
+mod format;
+mod io;
+
+pub use decode::AsyncDecodeStream;
+pub use format::{AsyncFormatContext, read_packet};
+pub use io::AsyncIoContext;
+
+use tao_core::{TaoError, TaoResult};
+
+/// 在阻塞线程池中执行同步任务
+///
+/// 任务 panic 或被取消时转换为 [`TaoError::Internal`].
+async fn run_blocking<T, F>(task: F) -> TaoResult<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(task).await.map_err(join_error)
+}
+
+/// 将阻塞任务的 JoinError 转换为 TaoError
+fn join_error(err: tokio::task::JoinError) -> TaoError {
+    TaoError::Internal(format!("异步阻塞任务失败: {err}"))
+}
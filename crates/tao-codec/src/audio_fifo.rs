@@ -0,0 +1,307 @@
+//! 音频采样 FIFO (AudioFifo).
+//!
+//! 对标 FFmpeg 的 `AVAudioFifo`. 以采样为单位缓存任意长度的音频帧,
+//! 再按所需长度取出, 用于将解码器/重采样器输出重新分块为编码器要求的帧长.
+//!
+//! 时间戳以 `1/sample_rate` 为时间基: 首帧的 PTS 换算后作为起点,
+//! 之后只由累计采样数推进, 与输入帧的时间戳无关.
+
+use tao_core::rational::rescale_q;
+use tao_core::timestamp::NOPTS_VALUE;
+use tao_core::{ChannelLayout, Rational, SampleFormat, TaoError, TaoResult};
+
+use crate::frame::AudioFrame;
+use crate::frame_pool::FrameBuf;
+
+/// 音频采样 FIFO
+///
+/// 同时支持交错与平面格式, 送入的帧必须与 FIFO 的格式一致.
+#[derive(Debug, Clone)]
+pub struct AudioFifo {
+    sample_rate: u32,
+    sample_format: SampleFormat,
+    channel_layout: ChannelLayout,
+    /// 每个平面中单个采样 (交错格式为所有声道) 占用的字节数
+    stride: usize,
+    /// 缓冲数据 (平面格式每声道一个, 交错格式仅一个)
+    planes: Vec<Vec<u8>>,
+    /// 缓冲中的采样数 (每声道)
+    nb_samples: u32,
+    /// 缓冲头部采样的 PTS, 首帧到达前为 `NOPTS_VALUE`
+    head_pts: i64,
+}
+
+impl AudioFifo {
+    /// 创建指定格式的空 FIFO
+    pub fn new(
+        sample_rate: u32,
+        sample_format: SampleFormat,
+        channel_layout: ChannelLayout,
+    ) -> Self {
+        let bytes_per_sample = sample_format.bytes_per_sample() as usize;
+        let channels = channel_layout.channels as usize;
+        let (plane_count, stride) = if sample_format.is_planar() {
+            (channels, bytes_per_sample)
+        } else {
+            (1, bytes_per_sample * channels)
+        };
+        Self {
+            sample_rate,
+            sample_format,
+            channel_layout,
+            stride,
+            planes: vec![Vec::new(); plane_count],
+            nb_samples: 0,
+            head_pts: NOPTS_VALUE,
+        }
+    }
+
+    /// 缓冲中的采样数 (每声道)
+    pub fn len(&self) -> u32 {
+        self.nb_samples
+    }
+
+    /// 缓冲是否为空
+    pub fn is_empty(&self) -> bool {
+        self.nb_samples == 0
+    }
+
+    /// 采样率
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// 采样格式
+    pub fn sample_format(&self) -> SampleFormat {
+        self.sample_format
+    }
+
+    /// 声道布局
+    pub fn channel_layout(&self) -> ChannelLayout {
+        self.channel_layout
+    }
+
+    /// 输出帧的时间基 (1/sample_rate)
+    pub fn time_base(&self) -> Rational {
+        Rational::new(1, self.sample_rate as i32)
+    }
+
+    /// 下一次取出的帧的 PTS, 尚未送入数据时为 `NOPTS_VALUE`
+    pub fn next_pts(&self) -> i64 {
+        self.head_pts
+    }
+
+    /// 送入一帧音频
+    ///
+    /// 采样格式、采样率或声道数与 FIFO 不一致时返回 `InvalidArgument`.
+    pub fn push(&mut self, frame: &AudioFrame) -> TaoResult<()> {
+        if frame.sample_format != self.sample_format
+            || frame.sample_rate != self.sample_rate
+            || frame.channel_layout.channels != self.channel_layout.channels
+        {
+            return Err(TaoError::InvalidArgument(format!(
+                "AudioFifo: 帧格式 {}/{}Hz/{}ch 与 FIFO 格式 {}/{}Hz/{}ch 不一致",
+                frame.sample_format,
+                frame.sample_rate,
+                frame.channel_layout.channels,
+                self.sample_format,
+                self.sample_rate,
+                self.channel_layout.channels,
+            )));
+        }
+        let len = frame.nb_samples as usize * self.stride;
+        if frame.data.len() < self.planes.len() || frame.data.iter().any(|d| d.len() < len) {
+            return Err(TaoError::InvalidArgument(format!(
+                "AudioFifo: 帧数据不足 {} 个采样",
+                frame.nb_samples
+            )));
+        }
+
+        if self.head_pts == NOPTS_VALUE {
+            self.head_pts = if frame.pts != NOPTS_VALUE && frame.time_base.is_valid() {
+                rescale_q(frame.pts, frame.time_base, self.time_base())
+            } else {
+                0
+            };
+        }
+        for (plane, data) in self.planes.iter_mut().zip(&frame.data) {
+            plane.extend_from_slice(&data[..len]);
+        }
+        self.nb_samples += frame.nb_samples;
+        Ok(())
+    }
+
+    /// 复制头部 `nb_samples` 个采样为一帧, 不消费缓冲
+    ///
+    /// 缓冲不足或 `nb_samples` 为 0 时返回 `None`.
+    pub fn peek(&self, nb_samples: u32) -> Option<AudioFrame> {
+        if nb_samples == 0 || nb_samples > self.nb_samples {
+            return None;
+        }
+        let take = nb_samples as usize * self.stride;
+        let data = self
+            .planes
+            .iter()
+            .map(|plane| FrameBuf::from(plane[..take].to_vec()))
+            .collect();
+        Some(self.make_frame(data, nb_samples, nb_samples))
+    }
+
+    /// 取出头部恰好 `nb_samples` 个采样
+    ///
+    /// 缓冲不足或 `nb_samples` 为 0 时返回 `None`, 缓冲保持不变.
+    pub fn pop(&mut self, nb_samples: u32) -> Option<AudioFrame> {
+        if nb_samples == 0 || nb_samples > self.nb_samples {
+            return None;
+        }
+        Some(self.take(nb_samples, nb_samples))
+    }
+
+    /// 取出至多 `frame_size` 个采样, 不足部分以静音补齐到 `frame_size`
+    ///
+    /// 用于在流结束时将最后一个不完整的帧送入固定帧长的编码器.
+    /// 输出帧的 `nb_samples` 为 `frame_size`, `duration` 为实际采样数.
+    pub fn pop_padded(&mut self, frame_size: u32) -> Option<AudioFrame> {
+        let nb_samples = self.nb_samples.min(frame_size);
+        if nb_samples == 0 {
+            return None;
+        }
+        Some(self.take(nb_samples, frame_size))
+    }
+
+    /// 丢弃所有缓冲采样, 并重置时间戳起点
+    pub fn clear(&mut self) {
+        for plane in &mut self.planes {
+            plane.clear();
+        }
+        self.nb_samples = 0;
+        self.head_pts = NOPTS_VALUE;
+    }
+
+    /// 消费 `nb_samples` 个采样, 以静音补齐到 `padded` 个采样
+    fn take(&mut self, nb_samples: u32, padded: u32) -> AudioFrame {
+        let take = nb_samples as usize * self.stride;
+        let padded_len = padded as usize * self.stride;
+        let silence = match self.sample_format {
+            SampleFormat::U8 | SampleFormat::U8p => 0x80,
+            _ => 0,
+        };
+        let data = self
+            .planes
+            .iter_mut()
+            .map(|plane| {
+                let mut out: Vec<u8> = plane.drain(..take).collect();
+                out.resize(padded_len, silence);
+                FrameBuf::from(out)
+            })
+            .collect();
+        self.nb_samples -= nb_samples;
+
+        let frame = self.make_frame(data, padded, nb_samples);
+        self.head_pts += i64::from(nb_samples);
+        frame
+    }
+
+    /// 以当前头部 PTS 构造输出帧
+    fn make_frame(&self, data: Vec<FrameBuf>, nb_samples: u32, duration: u32) -> AudioFrame {
+        let mut frame = AudioFrame::new(
+            nb_samples,
+            self.sample_rate,
+            self.sample_format,
+            self.channel_layout,
+        );
+        frame.data = data;
+        frame.pts = self.head_pts;
+        frame.time_base = self.time_base();
+        frame.duration = i64::from(duration);
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造 S16 单声道帧, 采样值为全局采样序号
+    fn mono_frame(first: i64, nb_samples: u32, pts: i64, time_base: Rational) -> AudioFrame {
+        let mut af = AudioFrame::new(nb_samples, 48000, SampleFormat::S16, ChannelLayout::MONO);
+        let data: Vec<u8> = (0..i64::from(nb_samples))
+            .flat_map(|i| ((first + i) as i16).to_le_bytes())
+            .collect();
+        af.data = vec![FrameBuf::from(data)];
+        af.pts = pts;
+        af.time_base = time_base;
+        af
+    }
+
+    #[test]
+    fn test_audio_fifo_rechunk_with_sample_pts() {
+        let mut fifo = AudioFifo::new(48000, SampleFormat::S16, ChannelLayout::MONO);
+        // 输入时间戳故意取自其他时间基, 输出 PTS 应只由采样计数决定
+        let ms = Rational::new(1, 1000);
+        fifo.push(&mono_frame(0, 700, 1000, ms)).unwrap();
+        assert!(fifo.pop(1024).is_none(), "不足一帧时不应输出");
+        assert_eq!(fifo.len(), 700, "取出失败时缓冲应保持不变");
+        fifo.push(&mono_frame(700, 700, 999_999, ms)).unwrap();
+
+        let first = fifo.pop(1024).expect("凑满一帧后应输出");
+        assert_eq!(first.nb_samples, 1024);
+        assert_eq!(first.pts, 48000, "起点应为首帧 PTS 换算到 1/48000");
+        assert_eq!(first.time_base, Rational::new(1, 48000));
+        assert_eq!(first.duration, 1024);
+        assert_eq!(&first.data[0][1023 * 2..], &1023i16.to_le_bytes());
+        assert_eq!(fifo.len(), 376);
+
+        let last = fifo.pop_padded(1024).expect("应输出剩余采样");
+        assert_eq!(last.pts, 48000 + 1024, "PTS 应按累计采样数递增");
+        assert_eq!(last.duration, 376, "时长应为实际采样数");
+        assert_eq!(last.nb_samples, 1024, "末帧应以静音补齐到帧长");
+        assert_eq!(&last.data[0][0..2], &1024i16.to_le_bytes());
+        assert_eq!(
+            &last.data[0][376 * 2..376 * 2 + 2],
+            &[0, 0],
+            "补齐部分应为静音"
+        );
+        assert!(fifo.is_empty(), "缓冲已清空");
+        assert!(fifo.pop_padded(1024).is_none());
+    }
+
+    #[test]
+    fn test_audio_fifo_peek_does_not_consume() {
+        let mut fifo = AudioFifo::new(48000, SampleFormat::S16, ChannelLayout::MONO);
+        fifo.push(&mono_frame(0, 100, NOPTS_VALUE, Rational::UNDEFINED))
+            .unwrap();
+        let peeked = fifo.peek(10).expect("应可预览");
+        assert_eq!(peeked.pts, 0, "无时间戳时从 0 开始");
+        assert_eq!(fifo.len(), 100, "peek 不应消费采样");
+        let popped = fifo.pop(10).unwrap();
+        assert_eq!(peeked.data[0].as_ref(), popped.data[0].as_ref());
+        assert_eq!(fifo.next_pts(), 10);
+        assert!(fifo.peek(91).is_none(), "超出缓冲的 peek 应失败");
+    }
+
+    #[test]
+    fn test_audio_fifo_planar_u8_padding() {
+        let layout = ChannelLayout::STEREO;
+        let mut fifo = AudioFifo::new(8000, SampleFormat::U8p, layout);
+        let mut af = AudioFrame::new(3, 8000, SampleFormat::U8p, layout);
+        af.data = vec![FrameBuf::from(vec![1, 2, 3]), FrameBuf::from(vec![4, 5, 6])];
+        fifo.push(&af).unwrap();
+
+        let frame = fifo.pop_padded(4).unwrap();
+        assert_eq!(frame.data.len(), 2, "平面格式应每声道一个缓冲");
+        assert_eq!(frame.data[0].as_ref(), &[1, 2, 3, 0x80], "U8 静音为 0x80");
+        assert_eq!(frame.data[1].as_ref(), &[4, 5, 6, 0x80]);
+    }
+
+    #[test]
+    fn test_audio_fifo_rejects_mismatched_format() {
+        let mut fifo = AudioFifo::new(44100, SampleFormat::S16, ChannelLayout::MONO);
+        let err = fifo.push(&mono_frame(0, 10, 0, Rational::new(1, 48000)));
+        assert!(
+            matches!(err, Err(TaoError::InvalidArgument(_))),
+            "采样率不一致应被拒绝"
+        );
+        assert!(fifo.is_empty());
+    }
+}
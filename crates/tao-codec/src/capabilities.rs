@@ -0,0 +1,114 @@
+//! 编解码器运行时能力标志.
+//!
+//! 对标 FFmpeg 的 `AV_CODEC_CAP_*` / `AV_CODEC_PROP_*`, 由解码器和编码器的
+//! `codec_capabilities()` 返回, 供调用方决定是否需要冲刷延迟帧、能否任意帧切入等.
+
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+
+/// 编解码器能力标志集合
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CodecCapabilities {
+    flags: u32,
+}
+
+impl CodecCapabilities {
+    /// 存在编解码延迟: 输入与输出不是一一对应, 结束时需送入空包/空帧冲刷缓存
+    pub const CAPS_DELAY: Self = Self::from_bits(0x0001);
+    /// 仅帧内编码: 每帧均可独立解码
+    pub const CAPS_INTRA_ONLY: Self = Self::from_bits(0x0002);
+    /// 无损编码
+    pub const CAPS_LOSSLESS: Self = Self::from_bits(0x0004);
+    /// 输出顺序与输入顺序不同 (如 B 帧重排序), 输出帧按显示顺序给出
+    pub const CAPS_REORDER: Self = Self::from_bits(0x0008);
+    /// 支持多线程编解码
+    pub const CAPS_THREAD_SAFE: Self = Self::from_bits(0x0010);
+
+    /// 空能力集合
+    pub const fn empty() -> Self {
+        Self { flags: 0 }
+    }
+
+    /// 从原始位创建
+    pub const fn from_bits(flags: u32) -> Self {
+        Self { flags }
+    }
+
+    /// 原始位
+    pub const fn bits(self) -> u32 {
+        self.flags
+    }
+
+    /// 是否不含任何能力
+    pub const fn is_empty(self) -> bool {
+        self.flags == 0
+    }
+
+    /// 是否包含 `other` 中的全部能力
+    pub const fn contains(self, other: Self) -> bool {
+        self.flags & other.flags == other.flags
+    }
+}
+
+impl BitOr for CodecCapabilities {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self::from_bits(self.flags | rhs.flags)
+    }
+}
+
+impl BitOrAssign for CodecCapabilities {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.flags |= rhs.flags;
+    }
+}
+
+impl fmt::Display for CodecCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = [
+            (Self::CAPS_DELAY, "delay"),
+            (Self::CAPS_INTRA_ONLY, "intra_only"),
+            (Self::CAPS_LOSSLESS, "lossless"),
+            (Self::CAPS_REORDER, "reorder"),
+            (Self::CAPS_THREAD_SAFE, "thread_safe"),
+        ];
+        let mut first = true;
+        for (caps, name) in names {
+            if self.contains(caps) {
+                if !first {
+                    f.write_str("|")?;
+                }
+                f.write_str(name)?;
+                first = false;
+            }
+        }
+        if first {
+            f.write_str("none")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_combine_and_contains() {
+        let caps = CodecCapabilities::CAPS_DELAY | CodecCapabilities::CAPS_REORDER;
+        assert_eq!(caps.bits(), 0x0009);
+        assert!(caps.contains(CodecCapabilities::CAPS_DELAY));
+        assert!(caps.contains(CodecCapabilities::CAPS_REORDER));
+        assert!(!caps.contains(CodecCapabilities::CAPS_LOSSLESS));
+        assert_eq!(caps.to_string(), "delay|reorder");
+    }
+
+    #[test]
+    fn test_capabilities_default_is_empty() {
+        let caps = CodecCapabilities::default();
+        assert!(caps.is_empty(), "默认能力集合应为空");
+        assert_eq!(caps, CodecCapabilities::empty());
+        assert_eq!(caps.to_string(), "none");
+    }
+}
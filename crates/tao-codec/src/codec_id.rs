@@ -77,6 +77,10 @@ pub enum CodecId {
     Eac3,
     /// DTS (Digital Theater Systems)
     Dts,
+    /// IMA ADPCM (WAV/AVI 块格式, 又称 DVI ADPCM)
+    AdpcmImaWav,
+    /// Microsoft ADPCM
+    AdpcmMs,
 
     // ========================
     // 字幕编解码器
@@ -129,7 +133,9 @@ impl CodecId {
             | Self::PcmU8
             | Self::Ac3
             | Self::Eac3
-            | Self::Dts => MediaType::Audio,
+            | Self::Dts
+            | Self::AdpcmImaWav
+            | Self::AdpcmMs => MediaType::Audio,
 
             // 字幕
             Self::Srt | Self::Ass | Self::Webvtt | Self::DvdSubtitle | Self::HdmvPgsSubtitle => {
@@ -170,6 +176,8 @@ impl CodecId {
             Self::Ac3 => "ac3",
             Self::Eac3 => "eac3",
             Self::Dts => "dts",
+            Self::AdpcmImaWav => "adpcm_ima_wav",
+            Self::AdpcmMs => "adpcm_ms",
             Self::Srt => "srt",
             Self::Ass => "ass",
             Self::Webvtt => "webvtt",
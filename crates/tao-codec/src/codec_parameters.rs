@@ -58,6 +58,8 @@ pub struct AudioCodecParams {
     pub sample_format: SampleFormat,
     /// 每帧采样数 (0 表示可变)
     pub frame_size: u32,
+    /// 每个编码块的字节数 (ADPCM 等按块编码的格式, 0 表示不适用)
+    pub block_align: u32,
 }
 
 impl CodecParameters {
@@ -4,11 +4,32 @@
 
 use tao_core::TaoResult;
 
+use crate::capabilities::CodecCapabilities;
 use crate::codec_id::CodecId;
 use crate::codec_parameters::CodecParameters;
 use crate::frame::Frame;
+use crate::frame_pool::FramePool;
 use crate::packet::Packet;
 
+/// 解码器选项
+///
+/// 与描述流本身的 `CodecParameters` 分开, 通过 [`Decoder::set_options`] 在 `open` 前设置.
+/// 新增选项不影响已有代码, 请以 `DecoderOptions::default()` 加 `with_*` 方法构造.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct DecoderOptions {
+    /// 解码线程数 (0 或 1 表示单线程, 目前仅 H.264 多 slice 解码支持)
+    pub decode_threads: u32,
+}
+
+impl DecoderOptions {
+    /// 设置解码线程数
+    pub fn with_decode_threads(mut self, decode_threads: u32) -> Self {
+        self.decode_threads = decode_threads;
+        self
+    }
+}
+
 /// 解码器 trait
 ///
 /// 定义了解码器的统一接口. 所有具体解码器 (H.264, AAC 等) 都实现此 trait.
@@ -25,6 +46,13 @@ pub trait Decoder: Send {
     /// 获取解码器名称
     fn name(&self) -> &str;
 
+    /// 解码器能力标志 (延迟输出、帧重排序等)
+    ///
+    /// 默认实现返回空集合.
+    fn codec_capabilities(&self) -> CodecCapabilities {
+        CodecCapabilities::empty()
+    }
+
     /// 使用参数配置解码器
     ///
     /// 对于 RAW/PCM 等无头部信息的编解码器, 必须在解码前调用此方法提供参数.
@@ -33,6 +61,18 @@ pub trait Decoder: Send {
         Ok(())
     }
 
+    /// 指定输出帧缓冲的分配池
+    ///
+    /// 多个解码器可共享同一个池 (`FramePool` 克隆即共享), 下游释放帧后
+    /// 缓冲自动归还池中, 供后续帧复用, 无需显式回收.
+    /// 默认实现忽略该池, 解码器自行分配.
+    fn set_frame_pool(&mut self, _pool: &FramePool) {}
+
+    /// 设置解码选项, 应在 `open` 之前调用
+    ///
+    /// 默认实现忽略全部选项.
+    fn set_options(&mut self, _options: &DecoderOptions) {}
+
     /// 送入一个压缩数据包进行解码
     ///
     /// # 参数
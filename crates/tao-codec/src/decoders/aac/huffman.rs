@@ -241,6 +241,35 @@ fn index_to_values(idx: usize, dim: usize, mod_val: usize, offset: i16) -> [i16;
     vals
 }
 
+// ============================================================
+// 编码端码字查询
+// ============================================================
+
+/// 查询 scale factor 差值的码字 `(码字, 码长)`, `index` 为差值 + 60 (0-120)
+pub(crate) fn scalefactor_codeword(index: usize) -> (u32, u8) {
+    let (code, len, _) = SF_TABLE[index];
+    (code, len)
+}
+
+/// 查询频谱码本 `cb` (1-11) 中线性索引 `index` 的码字 `(码字, 码长)`
+pub(crate) fn spectral_codeword(cb: u8, index: usize) -> (u32, u8) {
+    let (codes, bits): (&[u16], &[u8]) = match cb {
+        1 => (&CODES_1, &BITS_1),
+        2 => (&CODES_2, &BITS_2),
+        3 => (&CODES_3, &BITS_3),
+        4 => (&CODES_4, &BITS_4),
+        5 => (&CODES_5, &BITS_5),
+        6 => (&CODES_6, &BITS_6),
+        7 => (&CODES_7, &BITS_7),
+        8 => (&CODES_8, &BITS_8),
+        9 => (&CODES_9, &BITS_9),
+        10 => (&CODES_10, &BITS_10),
+        11 => (&CODES_11, &BITS_11),
+        _ => panic!("无效的 AAC 频谱码本 {cb}"),
+    };
+    (codes[index] as u32, bits[index])
+}
+
 // ============================================================
 // Scale Factor Huffman 表 (ISO 14496-3 Table 4.A.1)
 // (码字, 码字长度, SF 索引 0-120, delta = index - 60)
@@ -186,7 +186,7 @@ pub(super) fn build_kbd_window(len: usize, alpha: f64) -> Vec<f32> {
 }
 
 /// 第一类修正贝塞尔函数 I0.
-pub(super) fn bessel_i0(x: f64) -> f64 {
+pub(crate) fn bessel_i0(x: f64) -> f64 {
     let mut sum = 1.0f64;
     let mut term = 1.0f64;
     let half = x * 0.5;
@@ -11,8 +11,8 @@
 //! 6. 窗函数加窗 + overlap-add
 //! 7. 输出 PCM 采样
 
-mod huffman;
-mod imdct;
+pub(crate) mod huffman;
+pub(crate) mod imdct;
 pub(crate) mod spectral;
 pub(crate) mod tables;
 #[cfg(test)]
@@ -27,6 +27,7 @@ use crate::codec_id::CodecId;
 use crate::codec_parameters::{CodecParameters, CodecParamsType};
 use crate::decoder::Decoder;
 use crate::frame::{AudioFrame, Frame};
+use crate::frame_pool::{self, FramePool};
 use crate::packet::Packet;
 
 use huffman::AacCodebooks;
@@ -43,6 +44,8 @@ pub struct AacDecoder {
     use_default_channel_map: bool,
     sample_rate_index: u8,
     output_frame: Option<Frame>,
+    /// 输出缓冲池, 未指定时每帧单独分配
+    pool: Option<FramePool>,
     opened: bool,
     flushing: bool,
     /// overlap-add 缓冲 (每声道 1024 个浮点样本)
@@ -79,6 +82,7 @@ impl AacDecoder {
             use_default_channel_map: true,
             sample_rate_index: 4,
             output_frame: None,
+            pool: None,
             opened: false,
             flushing: false,
             overlap: Vec::new(),
@@ -160,28 +164,12 @@ impl AacDecoder {
 
     /// 获取当前采样率对应的 SFB 边界表
     fn swb_offset(&self) -> &'static [usize] {
-        match self.sample_rate_index {
-            0 | 1 => &SWB_OFFSET_1024_96,
-            2 => &SWB_OFFSET_1024_64,
-            3 | 4 => &SWB_OFFSET_1024_48,
-            5 => &SWB_OFFSET_1024_32,
-            6 | 7 => &SWB_OFFSET_1024_24,
-            8..=10 => &SWB_OFFSET_1024_16,
-            11 | 12 => &SWB_OFFSET_1024_8,
-            _ => &SWB_OFFSET_1024_48,
-        }
+        swb_offset_long(self.sample_rate_index)
     }
 
     /// 获取当前采样率对应的 SHORT 窗口 SFB 边界表
     fn swb_offset_short(&self) -> &'static [usize] {
-        match self.sample_rate_index {
-            0..=2 => &SWB_OFFSET_128_96,
-            3..=5 => &SWB_OFFSET_128_48,
-            6 | 7 => &SWB_OFFSET_128_24,
-            8..=10 => &SWB_OFFSET_128_16,
-            11 | 12 => &SWB_OFFSET_128_8,
-            _ => &SWB_OFFSET_128_48,
-        }
+        swb_offset_short(self.sample_rate_index)
     }
 
     /// 获取当前采样率下 TNS 可作用的最大频带数.
@@ -945,6 +933,10 @@ impl Decoder for AacDecoder {
         Ok(())
     }
 
+    fn set_frame_pool(&mut self, pool: &FramePool) {
+        self.pool = Some(pool.clone());
+    }
+
     fn send_packet(&mut self, packet: &Packet) -> TaoResult<()> {
         if !self.opened {
             return Err(TaoError::InvalidData("AAC 解码器未打开".into()));
@@ -968,7 +960,9 @@ impl Decoder for AacDecoder {
         let channels = self.channels as usize;
         let channel_map = self.output_channel_map();
         let num_samples = 1024;
-        let mut interleaved = vec![0u8; num_samples * channels * 4];
+        let mut interleaved =
+            frame_pool::take_vec_or_alloc(self.pool.as_ref(), num_samples * channels * 4);
+        interleaved.resize(num_samples * channels * 4, 0);
 
         for i in 0..num_samples {
             for ch in 0..channels {
@@ -1004,11 +998,7 @@ impl Decoder for AacDecoder {
             return Ok(());
         }
         let payload_offset = leading_trim_samples * channels * 4;
-        let output_interleaved = if payload_offset == 0 {
-            interleaved
-        } else {
-            interleaved[payload_offset..].to_vec()
-        };
+        interleaved.drain(..payload_offset);
         let output_pts = if packet.pts == tao_core::timestamp::NOPTS_VALUE {
             packet.pts
         } else {
@@ -1016,7 +1006,7 @@ impl Decoder for AacDecoder {
         };
 
         let frame = AudioFrame {
-            data: vec![output_interleaved],
+            data: vec![frame_pool::wrap_in(self.pool.as_ref(), interleaved)],
             nb_samples: output_samples as u32,
             sample_rate: self.sample_rate,
             channel_layout: self.channel_layout,
@@ -84,6 +84,32 @@ pub(super) const SWB_OFFSET_128_8: [usize; 16] = [
     0, 4, 8, 12, 16, 20, 24, 28, 36, 44, 52, 60, 72, 88, 108, 128,
 ];
 
+/// 采样率索引对应的 1024 点 LONG 窗口 SFB 边界表.
+pub(crate) fn swb_offset_long(sample_rate_index: u8) -> &'static [usize] {
+    match sample_rate_index {
+        0 | 1 => &SWB_OFFSET_1024_96,
+        2 => &SWB_OFFSET_1024_64,
+        3 | 4 => &SWB_OFFSET_1024_48,
+        5 => &SWB_OFFSET_1024_32,
+        6 | 7 => &SWB_OFFSET_1024_24,
+        8..=10 => &SWB_OFFSET_1024_16,
+        11 | 12 => &SWB_OFFSET_1024_8,
+        _ => &SWB_OFFSET_1024_48,
+    }
+}
+
+/// 采样率索引对应的 128 点 SHORT 窗口 SFB 边界表.
+pub(crate) fn swb_offset_short(sample_rate_index: u8) -> &'static [usize] {
+    match sample_rate_index {
+        0..=2 => &SWB_OFFSET_128_96,
+        3..=5 => &SWB_OFFSET_128_48,
+        6 | 7 => &SWB_OFFSET_128_24,
+        8..=10 => &SWB_OFFSET_128_16,
+        11 | 12 => &SWB_OFFSET_128_8,
+        _ => &SWB_OFFSET_128_48,
+    }
+}
+
 /// AAC TNS 最大频带数表 (索引为采样率索引).
 pub(super) const TNS_MAX_BANDS_1024: [u8; 13] =
     [31, 31, 34, 40, 42, 51, 46, 46, 42, 42, 42, 39, 39];
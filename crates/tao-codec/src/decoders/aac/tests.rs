@@ -1,5 +1,6 @@
 use super::*;
 use crate::codec_parameters::{AudioCodecParams, CodecParamsType};
+use crate::frame_pool::FramePool;
 
 fn make_aac_params() -> CodecParameters {
     CodecParameters {
@@ -11,6 +12,7 @@ fn make_aac_params() -> CodecParameters {
             channel_layout: ChannelLayout::from_channels(2),
             sample_format: SampleFormat::F32,
             frame_size: 1024,
+            block_align: 0,
         }),
     }
 }
@@ -51,6 +53,35 @@ fn test_silence_frame_decode() {
     }
 }
 
+#[test]
+fn test_decode_reuses_pool_buffers() {
+    let pool = FramePool::new(2);
+    let mut decoder = AacDecoder::create().unwrap();
+    decoder.set_frame_pool(&pool);
+    decoder.open(&make_aac_params()).unwrap();
+
+    let mut adts_frame = vec![0xFF, 0xF1, 0x50, 0x80, 0x02, 0x1F, 0xFC];
+    adts_frame.extend_from_slice(&[0; 10]);
+    let mut decode = || {
+        decoder
+            .send_packet(&Packet::from_data(adts_frame.clone()))
+            .unwrap();
+        match decoder.receive_frame().unwrap() {
+            Frame::Audio(af) => af,
+            _ => panic!("应为音频帧"),
+        }
+    };
+    let first = decode();
+    let ptr = first.data[0].as_ptr();
+    drop(first);
+    assert_eq!(pool.free_count(), 1, "释放的帧缓冲应归还池中");
+
+    let second = decode();
+    assert_eq!(second.data[0].as_ptr(), ptr, "后续帧应复用归还的缓冲");
+    assert_eq!(second.data[0].len(), 1024 * 2 * 4);
+    assert_eq!(pool.allocation_count(), 1);
+}
+
 #[test]
 fn test_flush_and_eof() {
     let mut decoder = AacDecoder::create().unwrap();
@@ -72,6 +103,7 @@ fn test_audio_specific_config_parse() {
         use_default_channel_map: true,
         sample_rate_index: 0,
         output_frame: None,
+        pool: None,
         opened: false,
         flushing: false,
         overlap: Vec::new(),
@@ -0,0 +1,759 @@
+//! AC-3 音频块 (audblk) 解析与频谱重建.
+//!
+//! 一帧含 6 个音频块, 指数、耦合与比特分配参数可沿用前一块的值.
+//! 每块解析完成后得到各声道 256 个频谱系数, 已完成解耦合、重矩阵化与动态范围增益.
+
+use tao_core::bitreader::BitReader;
+use tao_core::{TaoError, TaoResult};
+
+use super::bitalloc::{BitAllocParams, ChannelAlloc, DeltaBitAlloc, compute_bap, snr_offset};
+use super::header::FrameHeader;
+use super::tables::{ASYMMETRIC_BITS, FAST_GAIN, REMATRIX_BANDS};
+
+/// 一帧的音频块数
+pub(super) const BLOCKS_PER_FRAME: usize = 6;
+/// 全带宽声道数上限
+pub(super) const MAX_FBW_CHANNELS: usize = 5;
+/// LFE 声道槽位
+pub(super) const LFE_CH: usize = MAX_FBW_CHANNELS;
+/// 耦合声道槽位
+const CPL_CH: usize = MAX_FBW_CHANNELS + 1;
+/// 耦合频带数上限 (cplbegf=0, cplendf=15)
+const MAX_CPL_BANDS: usize = 18;
+/// LFE 声道频点数
+const LFE_END: usize = 7;
+/// 指数策略: 沿用前一块
+const EXP_REUSE: u32 = 0;
+/// 增量比特分配策略
+const DELTA_REUSE: u32 = 0;
+const DELTA_NEW: u32 = 1;
+const DELTA_NONE: u32 = 2;
+
+/// 单个声道 (含 LFE 与耦合声道) 的解码状态
+#[derive(Clone)]
+struct ChannelState {
+    exponents: [u8; 256],
+    bap: [u8; 256],
+    /// 反量化后的尾数 (未乘指数)
+    mantissas: [f32; 256],
+    /// 频谱系数有效范围
+    start: usize,
+    end: usize,
+    exp_valid: bool,
+    fsnroffst: u32,
+    fgaincod: usize,
+    delta: DeltaBitAlloc,
+    /// 是否参与耦合
+    in_coupling: bool,
+    /// 各耦合频带的耦合坐标 (已含 8 倍缩放)
+    cpl_coords: [f32; MAX_CPL_BANDS],
+    cpl_coords_valid: bool,
+    block_switch: bool,
+    dither: bool,
+}
+
+impl ChannelState {
+    fn new() -> Self {
+        Self {
+            exponents: [0; 256],
+            bap: [0; 256],
+            mantissas: [0.0; 256],
+            start: 0,
+            end: 0,
+            exp_valid: false,
+            fsnroffst: 0,
+            fgaincod: 0,
+            delta: DeltaBitAlloc::default(),
+            in_coupling: false,
+            cpl_coords: [0.0; MAX_CPL_BANDS],
+            cpl_coords_valid: false,
+            block_switch: false,
+            dither: false,
+        }
+    }
+}
+
+/// 跨音频块沿用的解码状态
+pub(super) struct AudioBlockState {
+    channels: Vec<ChannelState>,
+    /// 每声道本块的频谱系数 (按槽位, 耦合声道无输出)
+    pub(super) coeffs: [[f32; 256]; LFE_CH + 1],
+    cpl_in_use: bool,
+    /// 耦合频带的 [起始, 结束) 频点
+    cpl_bands: Vec<(usize, usize)>,
+    phase_flags_in_use: bool,
+    phase_flags: [bool; MAX_CPL_BANDS],
+    rematrix_flags: [bool; 4],
+    ba_params: Option<BitAllocParams>,
+    csnroffst: Option<u32>,
+    cpl_leak: (i32, i32),
+    dynamic_range: [f32; 2],
+    dither_seed: u32,
+}
+
+impl AudioBlockState {
+    pub(super) fn new() -> Self {
+        Self {
+            channels: vec![ChannelState::new(); CPL_CH + 1],
+            coeffs: [[0.0; 256]; LFE_CH + 1],
+            cpl_in_use: false,
+            cpl_bands: Vec::new(),
+            phase_flags_in_use: false,
+            phase_flags: [false; MAX_CPL_BANDS],
+            rematrix_flags: [false; 4],
+            ba_params: None,
+            csnroffst: None,
+            cpl_leak: (0, 0),
+            dynamic_range: [1.0; 2],
+            dither_seed: 1,
+        }
+    }
+
+    /// 帧起始时重置不允许跨帧沿用的状态
+    pub(super) fn start_frame(&mut self) {
+        for ch in &mut self.channels {
+            ch.exp_valid = false;
+            ch.delta = DeltaBitAlloc::default();
+            ch.in_coupling = false;
+            ch.cpl_coords_valid = false;
+        }
+        self.cpl_in_use = false;
+        self.cpl_bands.clear();
+        self.phase_flags_in_use = false;
+        self.rematrix_flags = [false; 4];
+        self.ba_params = None;
+        self.csnroffst = None;
+        self.cpl_leak = (0, 0);
+        self.dynamic_range = [1.0; 2];
+    }
+
+    /// 第 `ch` 个声道本块是否使用块切换 (两个短变换)
+    pub(super) fn block_switch(&self, ch: usize) -> bool {
+        self.channels[ch].block_switch
+    }
+
+    /// 解析一个音频块并重建各声道频谱系数
+    pub(super) fn decode_block(
+        &mut self,
+        br: &mut BitReader<'_>,
+        header: &FrameHeader,
+        block: usize,
+    ) -> TaoResult<()> {
+        let nfchans = header.fbw_channels();
+        let acmod = header.acmod;
+
+        for ch in 0..nfchans {
+            self.channels[ch].block_switch = br.read_bit()? != 0;
+        }
+        for ch in 0..nfchans {
+            self.channels[ch].dither = br.read_bit()? != 0;
+        }
+        for prog in 0..if acmod == 0 { 2 } else { 1 } {
+            if br.read_bit()? != 0 {
+                self.dynamic_range[prog] = dynamic_range_gain(br.read_bits(8)?);
+            }
+        }
+
+        self.parse_coupling_strategy(br, header, block)?;
+        if self.cpl_in_use {
+            self.parse_coupling_coords(br, header)?;
+        }
+        if acmod == 2 {
+            self.parse_rematrix(br)?;
+        }
+
+        // 指数策略
+        let cpl_exp_strategy = if self.cpl_in_use {
+            br.read_bits(2)?
+        } else {
+            EXP_REUSE
+        };
+        let mut exp_strategy = [EXP_REUSE; MAX_FBW_CHANNELS];
+        for strategy in exp_strategy.iter_mut().take(nfchans) {
+            *strategy = br.read_bits(2)?;
+        }
+        let lfe_exp_strategy = if header.lfeon {
+            br.read_bit()?
+        } else {
+            EXP_REUSE
+        };
+
+        // 未耦合声道的带宽
+        for (state, &strategy) in self.channels.iter_mut().zip(&exp_strategy).take(nfchans) {
+            if strategy == EXP_REUSE {
+                continue;
+            }
+            state.start = 0;
+            if state.in_coupling {
+                state.end = self.cpl_bands.first().map_or(0, |b| b.0);
+            } else {
+                let chbwcod = br.read_bits(6)? as usize;
+                if chbwcod > 60 {
+                    return Err(TaoError::InvalidData(format!(
+                        "AC-3 声道带宽代码无效: {chbwcod}"
+                    )));
+                }
+                state.end = 37 + 3 * (chbwcod + 12);
+            }
+        }
+
+        // 指数
+        if self.cpl_in_use && cpl_exp_strategy != EXP_REUSE {
+            let start = self.cpl_bands[0].0;
+            let end = self.cpl_bands.last().map_or(start, |b| b.1);
+            let absexp = (br.read_bits(4)? << 1) as i32;
+            let groups = (end - start) / (3 << (cpl_exp_strategy - 1));
+            let state = &mut self.channels[CPL_CH];
+            decode_exponents(
+                br,
+                cpl_exp_strategy,
+                groups,
+                absexp,
+                &mut state.exponents,
+                start,
+            )?;
+            state.start = start;
+            state.end = end;
+            state.exp_valid = true;
+        }
+        for (state, &strategy) in self.channels.iter_mut().zip(&exp_strategy).take(nfchans) {
+            if strategy == EXP_REUSE {
+                continue;
+            }
+            let absexp = br.read_bits(4)? as i32;
+            state.exponents[0] = absexp as u8;
+            let groups = match strategy {
+                1 => (state.end.max(1) - 1) / 3,
+                2 => (state.end.max(1) - 1 + 3) / 6,
+                _ => (state.end.max(1) - 1 + 9) / 12,
+            };
+            decode_exponents(br, strategy, groups, absexp, &mut state.exponents, 1)?;
+            br.skip_bits(2)?; // gainrng
+            state.exp_valid = true;
+        }
+        if header.lfeon && lfe_exp_strategy != EXP_REUSE {
+            let state = &mut self.channels[LFE_CH];
+            let absexp = br.read_bits(4)? as i32;
+            state.exponents[0] = absexp as u8;
+            decode_exponents(br, 1, 2, absexp, &mut state.exponents, 1)?;
+            state.start = 0;
+            state.end = LFE_END;
+            state.exp_valid = true;
+        }
+
+        self.parse_bit_alloc_info(br, header)?;
+
+        if br.read_bit()? != 0 {
+            let skip = br.read_bits(9)?;
+            br.skip_bits(skip * 8)?;
+        }
+
+        self.compute_bit_allocation(header, block)?;
+        self.read_mantissas(br, header)?;
+        self.reconstruct(header);
+        Ok(())
+    }
+
+    fn parse_coupling_strategy(
+        &mut self,
+        br: &mut BitReader<'_>,
+        header: &FrameHeader,
+        block: usize,
+    ) -> TaoResult<()> {
+        let nfchans = header.fbw_channels();
+        if br.read_bit()? == 0 {
+            if block == 0 {
+                return Err(TaoError::InvalidData(
+                    "AC-3 第一个音频块缺少耦合策略".into(),
+                ));
+            }
+            return Ok(());
+        }
+        for state in &mut self.channels {
+            state.in_coupling = false;
+            state.cpl_coords_valid = false;
+        }
+        self.cpl_bands.clear();
+        self.phase_flags_in_use = false;
+        self.cpl_in_use = br.read_bit()? != 0;
+        if !self.cpl_in_use {
+            return Ok(());
+        }
+        for ch in 0..nfchans {
+            self.channels[ch].in_coupling = br.read_bit()? != 0;
+        }
+        if header.acmod == 2 {
+            self.phase_flags_in_use = br.read_bit()? != 0;
+        }
+        let begin = br.read_bits(4)? as usize;
+        let end = br.read_bits(4)? as usize + 3;
+        if begin >= end {
+            return Err(TaoError::InvalidData(format!(
+                "AC-3 耦合频率范围无效: cplbegf={begin}, cplendf={}",
+                end - 3
+            )));
+        }
+        for sub in begin..end {
+            let start = 37 + sub * 12;
+            let merge = sub > begin && br.read_bit()? != 0;
+            match self.cpl_bands.last_mut() {
+                Some(band) if merge => band.1 = start + 12,
+                _ => self.cpl_bands.push((start, start + 12)),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_coupling_coords(
+        &mut self,
+        br: &mut BitReader<'_>,
+        header: &FrameHeader,
+    ) -> TaoResult<()> {
+        let nfchans = header.fbw_channels();
+        let bands = self.cpl_bands.len();
+        let mut any_new = false;
+        for ch in 0..nfchans {
+            let state = &mut self.channels[ch];
+            if !state.in_coupling {
+                continue;
+            }
+            if br.read_bit()? != 0 {
+                any_new = true;
+                let master = 3 * br.read_bits(2)? as i32;
+                for coord in state.cpl_coords.iter_mut().take(bands) {
+                    let exp = br.read_bits(4)? as i32;
+                    let mant = br.read_bits(4)? as f32;
+                    let value = if exp == 15 {
+                        mant / 16.0
+                    } else {
+                        (mant + 16.0) / 32.0
+                    };
+                    *coord = 8.0 * value * (-(exp + master) as f32).exp2();
+                }
+                state.cpl_coords_valid = true;
+            } else if !state.cpl_coords_valid {
+                return Err(TaoError::InvalidData("AC-3 耦合声道缺少耦合坐标".into()));
+            }
+        }
+        if header.acmod == 2 && self.phase_flags_in_use && any_new {
+            for flag in self.phase_flags.iter_mut().take(bands) {
+                *flag = br.read_bit()? != 0;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_rematrix(&mut self, br: &mut BitReader<'_>) -> TaoResult<()> {
+        if br.read_bit()? == 0 {
+            return Ok(());
+        }
+        let bands = match self.cpl_bands.first() {
+            Some(&(start, _)) if self.cpl_in_use && start <= 61 => {
+                if start == 37 {
+                    2
+                } else {
+                    3
+                }
+            }
+            _ => 4,
+        };
+        self.rematrix_flags = [false; 4];
+        for flag in self.rematrix_flags.iter_mut().take(bands) {
+            *flag = br.read_bit()? != 0;
+        }
+        Ok(())
+    }
+
+    fn parse_bit_alloc_info(
+        &mut self,
+        br: &mut BitReader<'_>,
+        header: &FrameHeader,
+    ) -> TaoResult<()> {
+        let nfchans = header.fbw_channels();
+        if br.read_bit()? != 0 {
+            self.ba_params = Some(BitAllocParams {
+                sdcycod: br.read_bits(2)? as usize,
+                fdcycod: br.read_bits(2)? as usize,
+                sgaincod: br.read_bits(2)? as usize,
+                dbpbcod: br.read_bits(2)? as usize,
+                floorcod: br.read_bits(3)? as usize,
+            });
+        }
+        if br.read_bit()? != 0 {
+            self.csnroffst = Some(br.read_bits(6)?);
+            let mut read_offsets = |state: &mut ChannelState| -> TaoResult<()> {
+                state.fsnroffst = br.read_bits(4)?;
+                state.fgaincod = br.read_bits(3)? as usize;
+                Ok(())
+            };
+            if self.cpl_in_use {
+                read_offsets(&mut self.channels[CPL_CH])?;
+            }
+            for state in self.channels.iter_mut().take(nfchans) {
+                read_offsets(state)?;
+            }
+            if header.lfeon {
+                read_offsets(&mut self.channels[LFE_CH])?;
+            }
+        }
+        if self.cpl_in_use && br.read_bit()? != 0 {
+            let fast = br.read_bits(3)? as i32;
+            let slow = br.read_bits(3)? as i32;
+            self.cpl_leak = ((fast << 8) + 768, (slow << 8) + 768);
+        }
+        if br.read_bit()? != 0 {
+            let cpl_mode = if self.cpl_in_use {
+                br.read_bits(2)?
+            } else {
+                DELTA_REUSE
+            };
+            let mut modes = [DELTA_REUSE; MAX_FBW_CHANNELS];
+            for mode in modes.iter_mut().take(nfchans) {
+                *mode = br.read_bits(2)?;
+            }
+            let cpl_in_use = self.cpl_in_use;
+            let slots = std::iter::once((CPL_CH, cpl_mode))
+                .filter(move |_| cpl_in_use)
+                .chain(modes.iter().copied().enumerate().take(nfchans));
+            for (slot, mode) in slots {
+                let delta = &mut self.channels[slot].delta;
+                match mode {
+                    DELTA_REUSE => {}
+                    DELTA_NEW => {
+                        let count = br.read_bits(3)? + 1;
+                        delta.segments.clear();
+                        for _ in 0..count {
+                            delta.segments.push((
+                                br.read_bits(5)? as usize,
+                                br.read_bits(4)? as usize,
+                                br.read_bits(3)? as u8,
+                            ));
+                        }
+                    }
+                    DELTA_NONE => delta.segments.clear(),
+                    _ => {
+                        return Err(TaoError::InvalidData("AC-3 增量比特分配模式保留值".into()));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn active_slots(&self, header: &FrameHeader) -> Vec<usize> {
+        let mut slots: Vec<usize> = (0..header.fbw_channels()).collect();
+        if self.cpl_in_use {
+            slots.push(CPL_CH);
+        }
+        if header.lfeon {
+            slots.push(LFE_CH);
+        }
+        slots
+    }
+
+    fn compute_bit_allocation(&mut self, header: &FrameHeader, block: usize) -> TaoResult<()> {
+        let (Some(params), Some(csnroffst)) = (self.ba_params, self.csnroffst) else {
+            return Err(TaoError::InvalidData(format!(
+                "AC-3 音频块 {block} 缺少比特分配参数"
+            )));
+        };
+        for slot in self.active_slots(header) {
+            let state = &self.channels[slot];
+            if !state.exp_valid {
+                return Err(TaoError::InvalidData(format!(
+                    "AC-3 音频块 {block} 声道 {slot} 缺少指数"
+                )));
+            }
+            let mut bap = [0u8; 256];
+            compute_bap(
+                &ChannelAlloc {
+                    exponents: &state.exponents,
+                    start: state.start,
+                    end: state.end,
+                    fscod: header.fscod,
+                    params,
+                    snr_offset: snr_offset(csnroffst, state.fsnroffst),
+                    fast_gain: FAST_GAIN[state.fgaincod],
+                    coupling_leak: (slot == CPL_CH).then_some(self.cpl_leak),
+                    is_lfe: slot == LFE_CH,
+                    delta: &state.delta,
+                },
+                &mut bap,
+            )?;
+            self.channels[slot].bap = bap;
+        }
+        Ok(())
+    }
+
+    fn read_mantissas(&mut self, br: &mut BitReader<'_>, header: &FrameHeader) -> TaoResult<()> {
+        let mut reader = MantissaReader::default();
+        let mut cpl_read = false;
+        let mut order: Vec<usize> = Vec::with_capacity(CPL_CH + 1);
+        for ch in 0..header.fbw_channels() {
+            order.push(ch);
+            if self.cpl_in_use && self.channels[ch].in_coupling && !cpl_read {
+                order.push(CPL_CH);
+                cpl_read = true;
+            }
+        }
+        if header.lfeon {
+            order.push(LFE_CH);
+        }
+        for slot in order {
+            let state = &mut self.channels[slot];
+            state.mantissas = [0.0; 256];
+            for bin in state.start..state.end {
+                state.mantissas[bin] = reader.read(br, state.bap[bin])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn next_dither(&mut self) -> f32 {
+        self.dither_seed = self
+            .dither_seed
+            .wrapping_mul(1_664_525)
+            .wrapping_add(1_013_904_223);
+        // 均匀分布于 ±0.707
+        ((self.dither_seed >> 8) as f32 / (1u32 << 23) as f32 - 1.0) * 0.707
+    }
+
+    fn reconstruct(&mut self, header: &FrameHeader) {
+        let nfchans = header.fbw_channels();
+        for ch in 0..nfchans {
+            let mut coeffs = [0.0f32; 256];
+            let (end, dither) = (self.channels[ch].end, self.channels[ch].dither);
+            for (bin, coeff) in coeffs.iter_mut().enumerate().take(end) {
+                let state = &self.channels[ch];
+                let mant = if state.bap[bin] == 0 && dither {
+                    self.next_dither()
+                } else {
+                    self.channels[ch].mantissas[bin]
+                };
+                *coeff = mant * scale(self.channels[ch].exponents[bin]);
+            }
+            if self.cpl_in_use && self.channels[ch].in_coupling {
+                let cpl_bands = std::mem::take(&mut self.cpl_bands);
+                for (band, &(start, band_end)) in cpl_bands.iter().enumerate() {
+                    let mut coord = self.channels[ch].cpl_coords[band];
+                    if ch == 1 && self.phase_flags_in_use && self.phase_flags[band] {
+                        coord = -coord;
+                    }
+                    for (bin, coeff) in coeffs.iter_mut().enumerate().take(band_end).skip(start) {
+                        let mant = if self.channels[CPL_CH].bap[bin] == 0 {
+                            if dither { self.next_dither() } else { 0.0 }
+                        } else {
+                            self.channels[CPL_CH].mantissas[bin]
+                        };
+                        *coeff = mant * scale(self.channels[CPL_CH].exponents[bin]) * coord;
+                    }
+                }
+                self.cpl_bands = cpl_bands;
+            }
+            self.coeffs[ch] = coeffs;
+        }
+
+        if header.acmod == 2 {
+            let end = self.channels[0].end.min(self.channels[1].end);
+            for (band, &flag) in self.rematrix_flags.iter().enumerate() {
+                if !flag {
+                    continue;
+                }
+                let band_end = REMATRIX_BANDS[band + 1].min(end);
+                for bin in REMATRIX_BANDS[band]..band_end {
+                    let (l, r) = (self.coeffs[0][bin], self.coeffs[1][bin]);
+                    self.coeffs[0][bin] = l + r;
+                    self.coeffs[1][bin] = l - r;
+                }
+            }
+        }
+
+        if header.lfeon {
+            let state = &self.channels[LFE_CH];
+            let mut coeffs = [0.0f32; 256];
+            for (bin, coeff) in coeffs.iter_mut().enumerate().take(LFE_END) {
+                *coeff = state.mantissas[bin] * scale(state.exponents[bin]);
+            }
+            self.coeffs[LFE_CH] = coeffs;
+        }
+
+        for ch in (0..nfchans).chain(header.lfeon.then_some(LFE_CH)) {
+            // 双单声道时第二个声道使用 dynrng2
+            let gain = self.dynamic_range[usize::from(header.acmod == 0 && ch == 1)];
+            if gain != 1.0 {
+                self.coeffs[ch].iter_mut().for_each(|c| *c *= gain);
+            }
+        }
+    }
+}
+
+/// 指数 e 对应的缩放 2^-e
+fn scale(exponent: u8) -> f32 {
+    (-f32::from(exponent)).exp2()
+}
+
+/// 动态范围增益字 (dynrng): 高 3 位为有符号 6dB 步进, 低 5 位为尾数
+fn dynamic_range_gain(code: u32) -> f32 {
+    let shift = ((code as i32) << 24 >> 29) as f32;
+    let mant = ((code & 0x1F) | 0x20) as f32 / 32.0;
+    shift.exp2() * mant
+}
+
+/// 解码分组差分指数, 写入 `exponents[first..]`
+fn decode_exponents(
+    br: &mut BitReader<'_>,
+    strategy: u32,
+    groups: usize,
+    absexp: i32,
+    exponents: &mut [u8; 256],
+    first: usize,
+) -> TaoResult<()> {
+    let group_size = 1usize << (strategy - 1);
+    let mut prev = absexp;
+    let mut index = first;
+    for _ in 0..groups {
+        let code = br.read_bits(7)? as i32;
+        if code >= 125 {
+            return Err(TaoError::InvalidData(format!(
+                "AC-3 指数分组码无效: {code}"
+            )));
+        }
+        for diff in [code / 25, (code % 25) / 5, code % 5] {
+            prev += diff - 2;
+            if !(0..=24).contains(&prev) {
+                return Err(TaoError::InvalidData(format!("AC-3 指数越界: {prev}")));
+            }
+            let slots = exponents
+                .get_mut(index..index + group_size)
+                .ok_or_else(|| TaoError::InvalidData("AC-3 指数数量超出 256 个频点".into()))?;
+            slots.fill(prev as u8);
+            index += group_size;
+        }
+    }
+    Ok(())
+}
+
+/// 尾数读取器, 保存 bap=1/2/4 分组码中尚未使用的值
+#[derive(Default)]
+struct MantissaReader {
+    bap1: Vec<f32>,
+    bap2: Vec<f32>,
+    bap4: Vec<f32>,
+}
+
+/// 对称量化级 c (共 levels 级) 的反量化值
+fn symmetric(c: i32, levels: i32) -> f32 {
+    (2 * c - (levels - 1)) as f32 / levels as f32
+}
+
+impl MantissaReader {
+    fn read(&mut self, br: &mut BitReader<'_>, bap: u8) -> TaoResult<f32> {
+        let invalid = |bap: u8, code: u32| {
+            TaoError::InvalidData(format!("AC-3 尾数码无效: bap={bap}, code={code}"))
+        };
+        Ok(match bap {
+            0 => 0.0,
+            1 => {
+                if self.bap1.is_empty() {
+                    let code = br.read_bits(5)?;
+                    if code >= 27 {
+                        return Err(invalid(bap, code));
+                    }
+                    let c = code as i32;
+                    self.bap1 = vec![
+                        symmetric(c % 3, 3),
+                        symmetric(c / 3 % 3, 3),
+                        symmetric(c / 9, 3),
+                    ];
+                }
+                self.bap1.pop().unwrap_or_default()
+            }
+            2 => {
+                if self.bap2.is_empty() {
+                    let code = br.read_bits(7)?;
+                    if code >= 125 {
+                        return Err(invalid(bap, code));
+                    }
+                    let c = code as i32;
+                    self.bap2 = vec![
+                        symmetric(c % 5, 5),
+                        symmetric(c / 5 % 5, 5),
+                        symmetric(c / 25, 5),
+                    ];
+                }
+                self.bap2.pop().unwrap_or_default()
+            }
+            3 => {
+                let code = br.read_bits(3)?;
+                if code == 7 {
+                    return Err(invalid(bap, code));
+                }
+                symmetric(code as i32, 7)
+            }
+            4 => {
+                if self.bap4.is_empty() {
+                    let code = br.read_bits(7)?;
+                    if code >= 121 {
+                        return Err(invalid(bap, code));
+                    }
+                    let c = code as i32;
+                    self.bap4 = vec![symmetric(c % 11, 11), symmetric(c / 11, 11)];
+                }
+                self.bap4.pop().unwrap_or_default()
+            }
+            5 => {
+                let code = br.read_bits(4)?;
+                if code == 15 {
+                    return Err(invalid(bap, code));
+                }
+                symmetric(code as i32, 15)
+            }
+            _ => {
+                let bits = ASYMMETRIC_BITS[usize::from(bap) - 6];
+                br.read_bits_signed(bits)? as f32 / (1u32 << (bits - 1)) as f32
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_exponents_d25() {
+        // 差分 (+1, 0, -2) 按 D25 每个重复 2 次
+        let code = 3 * 25 + 2 * 5;
+        let data = [(code << 1) as u8];
+        let mut br = BitReader::new(&data);
+        let mut exponents = [0u8; 256];
+        decode_exponents(&mut br, 2, 1, 10, &mut exponents, 1).unwrap();
+        assert_eq!(&exponents[1..7], &[11, 11, 11, 11, 9, 9]);
+
+        let data = [124 << 1];
+        let mut br = BitReader::new(&data);
+        assert!(
+            decode_exponents(&mut br, 1, 1, 23, &mut exponents, 1).is_err(),
+            "指数超过 24 应报错"
+        );
+    }
+
+    #[test]
+    fn test_mantissa_grouped_and_asymmetric() {
+        // bap1 分组码 5 = (0, 1, 2) -> -2/3, 0, 2/3; 随后 bap=6 (5 位) 读 -8
+        let data = [0b0010_1110, 0b0000_0000];
+        let mut br = BitReader::new(&data);
+        let mut reader = MantissaReader::default();
+        let values: Vec<f32> = (0..3).map(|_| reader.read(&mut br, 1).unwrap()).collect();
+        assert_eq!(values, vec![-2.0 / 3.0, 0.0, 2.0 / 3.0]);
+        assert_eq!(reader.read(&mut br, 6).unwrap(), -0.5);
+    }
+
+    #[test]
+    fn test_dynamic_range_gain() {
+        assert_eq!(dynamic_range_gain(0), 1.0);
+        assert_eq!(dynamic_range_gain(0x20), 2.0, "+1 个 6dB 步进");
+        assert_eq!(dynamic_range_gain(0xE0), 0.5, "-1 个 6dB 步进");
+        assert!((dynamic_range_gain(0x1F) - 63.0 / 32.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,309 @@
+//! AC-3 参数化比特分配 (A/52 7.2).
+//!
+//! 由指数计算功率谱密度 (PSD), 经频带积分、激励函数与听阈得到掩蔽曲线,
+//! 叠加增量比特分配后按 SNR 偏移查表得到每个频点的比特分配指针 (bap).
+
+use tao_core::{TaoError, TaoResult};
+
+use super::tables::{
+    BAND_START, BAP_TAB, DB_PER_BIT, FAST_DECAY, FLOOR, HEARING_THRESHOLD, LOG_ADD, NUM_BANDS,
+    SLOW_DECAY, SLOW_GAIN, bin_to_band,
+};
+
+/// 帧内共享的比特分配参数 (baie)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct BitAllocParams {
+    pub(super) sdcycod: usize,
+    pub(super) fdcycod: usize,
+    pub(super) sgaincod: usize,
+    pub(super) dbpbcod: usize,
+    pub(super) floorcod: usize,
+}
+
+/// 增量比特分配 (deltba) 的一组段
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct DeltaBitAlloc {
+    /// (频带偏移, 频带数, 增量码) 列表, 为空表示不使用增量分配
+    pub(super) segments: Vec<(usize, usize, u8)>,
+}
+
+/// 单个声道的比特分配输入
+pub(super) struct ChannelAlloc<'a> {
+    pub(super) exponents: &'a [u8; 256],
+    pub(super) start: usize,
+    pub(super) end: usize,
+    pub(super) fscod: usize,
+    pub(super) params: BitAllocParams,
+    /// csnroffst 与 fsnroffst 合成的 SNR 偏移
+    pub(super) snr_offset: i32,
+    pub(super) fast_gain: i32,
+    /// 耦合声道的 (快, 慢) 泄漏初值, 其余声道为 None
+    pub(super) coupling_leak: Option<(i32, i32)>,
+    pub(super) is_lfe: bool,
+    pub(super) delta: &'a DeltaBitAlloc,
+}
+
+/// 由 csnroffst/fsnroffst 计算 SNR 偏移
+pub(super) fn snr_offset(csnroffst: u32, fsnroffst: u32) -> i32 {
+    (((csnroffst as i32 - 15) << 4) + fsnroffst as i32) << 2
+}
+
+/// 计算一个声道 `start..end` 范围内各频点的 bap
+pub(super) fn compute_bap(alloc: &ChannelAlloc<'_>, bap: &mut [u8; 256]) -> TaoResult<()> {
+    let (start, end) = (alloc.start, alloc.end);
+    bap[start..end].fill(0);
+    if start >= end {
+        return Ok(());
+    }
+
+    let mut psd = [0i32; 256];
+    for (p, &exp) in psd[start..end].iter_mut().zip(&alloc.exponents[start..end]) {
+        *p = 3072 - (i32::from(exp) << 7);
+    }
+
+    // 按频带积分 PSD
+    let mut band_psd = [0i32; NUM_BANDS];
+    let mut bin = start;
+    let mut band = bin_to_band(start);
+    while bin < end {
+        let band_end = BAND_START[band + 1].min(end);
+        let mut value = psd[bin];
+        bin += 1;
+        while bin < band_end {
+            value = log_add(value, psd[bin]);
+            bin += 1;
+        }
+        band_psd[band] = value;
+        band += 1;
+    }
+
+    let mask = compute_mask(alloc, &band_psd)?;
+
+    if alloc.snr_offset == -960 {
+        return Ok(());
+    }
+    let floor = FLOOR[alloc.params.floorcod];
+    let mut bin = start;
+    let mut band = bin_to_band(start);
+    while bin < end {
+        let m = ((mask[band] - alloc.snr_offset - floor).max(0) & 0x1FE0) + floor;
+        let band_end = BAND_START[band + 1].min(end);
+        while bin < band_end {
+            let address = ((psd[bin] - m) >> 5).clamp(0, 63) as usize;
+            bap[bin] = BAP_TAB[address];
+            bin += 1;
+        }
+        band += 1;
+    }
+    Ok(())
+}
+
+fn log_add(a: i32, b: i32) -> i32 {
+    let address = ((a - b).unsigned_abs() >> 1).min(255) as usize;
+    a.max(b) + i32::from(LOG_ADD[address])
+}
+
+fn lowcomp_step(lowcomp: i32, b0: i32, b1: i32, boost: i32) -> i32 {
+    if b0 + 256 == b1 {
+        boost
+    } else if b0 > b1 {
+        (lowcomp - 64).max(0)
+    } else {
+        lowcomp
+    }
+}
+
+fn lowcomp_at(lowcomp: i32, b0: i32, b1: i32, band: usize) -> i32 {
+    match band {
+        0..7 => lowcomp_step(lowcomp, b0, b1, 384),
+        7..20 => lowcomp_step(lowcomp, b0, b1, 320),
+        _ => (lowcomp - 128).max(0),
+    }
+}
+
+/// 计算掩蔽曲线 (含增量比特分配)
+fn compute_mask(
+    alloc: &ChannelAlloc<'_>,
+    band_psd: &[i32; NUM_BANDS],
+) -> TaoResult<[i32; NUM_BANDS]> {
+    let params = alloc.params;
+    let slow_decay = SLOW_DECAY[params.sdcycod];
+    let fast_decay = FAST_DECAY[params.fdcycod];
+    let slow_gain = SLOW_GAIN[params.sgaincod];
+    let fast_gain = alloc.fast_gain;
+    let band_start = bin_to_band(alloc.start);
+    let band_end = bin_to_band(alloc.end - 1) + 1;
+    let lfe_skip = |band: usize| alloc.is_lfe && band == 6;
+
+    let mut excite = [0i32; NUM_BANDS];
+    let (mut fast_leak, mut slow_leak, begin);
+    if let Some((fast, slow)) = alloc.coupling_leak {
+        fast_leak = fast;
+        slow_leak = slow;
+        begin = band_start;
+    } else {
+        let mut lowcomp = lowcomp_step(0, band_psd[0], band_psd[1], 384);
+        excite[0] = band_psd[0] - fast_gain - lowcomp;
+        lowcomp = lowcomp_step(lowcomp, band_psd[1], band_psd[2], 384);
+        excite[1] = band_psd[1] - fast_gain - lowcomp;
+        fast_leak = 0;
+        slow_leak = 0;
+        let mut first = 7;
+        for band in 2..7 {
+            if !lfe_skip(band) {
+                lowcomp = lowcomp_step(lowcomp, band_psd[band], band_psd[band + 1], 384);
+            }
+            fast_leak = band_psd[band] - fast_gain;
+            slow_leak = band_psd[band] - slow_gain;
+            excite[band] = fast_leak - lowcomp;
+            if !lfe_skip(band) && band_psd[band] <= band_psd[band + 1] {
+                first = band + 1;
+                break;
+            }
+        }
+        for band in first..band_end.min(22) {
+            if !lfe_skip(band) {
+                lowcomp = lowcomp_at(lowcomp, band_psd[band], band_psd[band + 1], band);
+            }
+            fast_leak = (fast_leak - fast_decay).max(band_psd[band] - fast_gain);
+            slow_leak = (slow_leak - slow_decay).max(band_psd[band] - slow_gain);
+            excite[band] = (fast_leak - lowcomp).max(slow_leak);
+        }
+        begin = 22;
+    }
+    for band in begin..band_end {
+        fast_leak = (fast_leak - fast_decay).max(band_psd[band] - fast_gain);
+        slow_leak = (slow_leak - slow_decay).max(band_psd[band] - slow_gain);
+        excite[band] = fast_leak.max(slow_leak);
+    }
+
+    let db_per_bit = DB_PER_BIT[params.dbpbcod];
+    let mut mask = [0i32; NUM_BANDS];
+    for band in band_start..band_end {
+        let boost = db_per_bit - band_psd[band];
+        if boost > 0 {
+            excite[band] += boost >> 2;
+        }
+        mask[band] = HEARING_THRESHOLD[band][alloc.fscod].max(excite[band]);
+    }
+
+    let mut band = 0;
+    for &(offset, length, code) in &alloc.delta.segments {
+        band += offset;
+        if band + length > NUM_BANDS {
+            return Err(TaoError::InvalidData(format!(
+                "AC-3 增量比特分配越界: 频带 {band}, 长度 {length}"
+            )));
+        }
+        let delta = if code >= 4 {
+            (i32::from(code) - 3) << 7
+        } else {
+            (i32::from(code) - 4) << 7
+        };
+        for m in &mut mask[band..band + length] {
+            *m += delta;
+        }
+        band += length;
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alloc_for<'a>(
+        exponents: &'a [u8; 256],
+        end: usize,
+        snr_offset: i32,
+        delta: &'a DeltaBitAlloc,
+    ) -> ChannelAlloc<'a> {
+        ChannelAlloc {
+            exponents,
+            start: 0,
+            end,
+            fscod: 0,
+            params: BitAllocParams {
+                sdcycod: 2,
+                fdcycod: 1,
+                sgaincod: 1,
+                dbpbcod: 2,
+                floorcod: 4,
+            },
+            snr_offset,
+            fast_gain: 0x280,
+            coupling_leak: None,
+            is_lfe: false,
+            delta,
+        }
+    }
+
+    #[test]
+    fn test_bitalloc_zero_snr_offset_allocates_nothing() {
+        let exponents = [3u8; 256];
+        let delta = DeltaBitAlloc::default();
+        let mut bap = [7u8; 256];
+        compute_bap(
+            &alloc_for(&exponents, 100, snr_offset(0, 0), &delta),
+            &mut bap,
+        )
+        .unwrap();
+        assert!(
+            bap[..100].iter().all(|&b| b == 0),
+            "csnroffst=0 时不应分配比特"
+        );
+    }
+
+    #[test]
+    fn test_bitalloc_loud_bins_get_more_bits() {
+        let mut exponents = [20u8; 256];
+        exponents[40] = 2;
+        let delta = DeltaBitAlloc::default();
+        let mut bap = [0u8; 256];
+        compute_bap(
+            &alloc_for(&exponents, 120, snr_offset(15, 0), &delta),
+            &mut bap,
+        )
+        .unwrap();
+        assert!(bap[40] > bap[100], "大幅度频点应获得更多比特");
+        assert_eq!(bap[120], 0, "end 之后的频点不应分配");
+
+        // 增量分配降低掩蔽后同一频点获得更多比特
+        let boosted = DeltaBitAlloc {
+            segments: vec![(bin_to_band(40), 1, 0)],
+        };
+        let mut bap_boost = [0u8; 256];
+        compute_bap(
+            &alloc_for(&exponents, 120, snr_offset(15, 0), &boosted),
+            &mut bap_boost,
+        )
+        .unwrap();
+        assert!(bap_boost[40] >= bap[40], "负增量应降低掩蔽阈值");
+    }
+
+    #[test]
+    fn test_bitalloc_rejects_delta_out_of_range() {
+        let exponents = [10u8; 256];
+        let delta = DeltaBitAlloc {
+            segments: vec![(31, 15, 5), (10, 15, 5)],
+        };
+        let mut bap = [0u8; 256];
+        assert!(
+            compute_bap(
+                &alloc_for(&exponents, 200, snr_offset(15, 0), &delta),
+                &mut bap
+            )
+            .is_err(),
+            "增量分配越过 50 个频带应报错"
+        );
+    }
+
+    #[test]
+    fn test_bin_to_band() {
+        assert_eq!(bin_to_band(0), 0);
+        assert_eq!(bin_to_band(27), 27);
+        assert_eq!(bin_to_band(30), 28);
+        assert_eq!(bin_to_band(49), 35);
+        assert_eq!(bin_to_band(252), 49);
+    }
+}
@@ -0,0 +1,159 @@
+//! AC-3 同步信息 (syncinfo) 与码流信息 (bsi) 解析.
+
+use tao_core::bitreader::BitReader;
+use tao_core::{TaoError, TaoResult};
+
+use super::tables::{ACMOD_CHANNELS, BITRATES, SAMPLE_RATES};
+
+/// 同步字
+pub(super) const SYNC_WORD: u16 = 0x0B77;
+/// 同步信息长度 (字节)
+pub(super) const SYNC_INFO_LEN: usize = 5;
+
+/// 同步帧头 (syncinfo + bsi)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct FrameHeader {
+    /// 采样率代码
+    pub(super) fscod: usize,
+    /// 帧长 (字节)
+    pub(super) frame_size: usize,
+    pub(super) bsid: u32,
+    /// 声道模式
+    pub(super) acmod: u32,
+    /// 是否含 LFE 声道
+    pub(super) lfeon: bool,
+    /// 码流信息结束处的位偏移 (相对帧起始)
+    pub(super) header_bits: usize,
+}
+
+impl FrameHeader {
+    pub(super) fn sample_rate(&self) -> u32 {
+        SAMPLE_RATES[self.fscod]
+    }
+
+    /// 全带宽声道数
+    pub(super) fn fbw_channels(&self) -> usize {
+        ACMOD_CHANNELS[self.acmod as usize]
+    }
+
+    /// 输出声道数 (含 LFE)
+    pub(super) fn channels(&self) -> usize {
+        self.fbw_channels() + usize::from(self.lfeon)
+    }
+}
+
+/// 由 fscod 与 frmsizecod 计算帧长 (字节)
+pub(super) fn frame_size(fscod: usize, frmsizecod: usize) -> Option<usize> {
+    let bitrate = *BITRATES.get(frmsizecod / 2)? as usize;
+    let words = match fscod {
+        0 => bitrate * 2,
+        1 => bitrate * 96_000 / 44_100 + (frmsizecod & 1),
+        2 => bitrate * 3,
+        _ => return None,
+    };
+    Some(words * 2)
+}
+
+/// 解析帧起始处的 syncinfo 与 bsi
+pub(super) fn parse_header(data: &[u8]) -> TaoResult<FrameHeader> {
+    if data.len() < SYNC_INFO_LEN {
+        return Err(TaoError::NeedMoreData);
+    }
+    if u16::from_be_bytes([data[0], data[1]]) != SYNC_WORD {
+        return Err(TaoError::InvalidData("AC-3 同步字无效".into()));
+    }
+    let fscod = usize::from(data[4] >> 6);
+    let frmsizecod = usize::from(data[4] & 0x3F);
+    let frame_size = frame_size(fscod, frmsizecod).ok_or_else(|| {
+        TaoError::InvalidData(format!(
+            "AC-3 采样率/帧长代码无效: fscod={fscod}, frmsizecod={frmsizecod}"
+        ))
+    })?;
+
+    let mut br = BitReader::new(&data[SYNC_INFO_LEN..]);
+    let bsid = br.read_bits(5)?;
+    if bsid > 8 {
+        return Err(TaoError::Unsupported(format!(
+            "不支持 bsid={bsid} 的 AC-3 码流 (E-AC-3 需单独解码器)"
+        )));
+    }
+    let _bsmod = br.read_bits(3)?;
+    let acmod = br.read_bits(3)?;
+    if acmod & 1 != 0 && acmod != 1 {
+        br.skip_bits(2)?; // cmixlev
+    }
+    if acmod & 4 != 0 {
+        br.skip_bits(2)?; // surmixlev
+    }
+    if acmod == 2 {
+        br.skip_bits(2)?; // dsurmod
+    }
+    let lfeon = br.read_bit()? != 0;
+    // acmod=0 (1+1 双单声道) 时第二个节目的字段重复一次
+    for _ in 0..if acmod == 0 { 2 } else { 1 } {
+        br.skip_bits(5)?; // dialnorm
+        if br.read_bit()? != 0 {
+            br.skip_bits(8)?; // compr
+        }
+        if br.read_bit()? != 0 {
+            br.skip_bits(8)?; // langcod
+        }
+        if br.read_bit()? != 0 {
+            br.skip_bits(7)?; // mixlevel + roomtyp
+        }
+    }
+    br.skip_bits(2)?; // copyrightb + origbs
+    for _ in 0..2 {
+        if br.read_bit()? != 0 {
+            br.skip_bits(14)?; // timecod1 / timecod2
+        }
+    }
+    if br.read_bit()? != 0 {
+        let addbsil = br.read_bits(6)?;
+        br.skip_bits((addbsil + 1) * 8)?;
+    }
+
+    Ok(FrameHeader {
+        fscod,
+        frame_size,
+        bsid,
+        acmod,
+        lfeon,
+        header_bits: SYNC_INFO_LEN * 8 + br.bits_read(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_size_table() {
+        assert_eq!(frame_size(0, 0), Some(128), "48kHz 32kbps 应为 64 字");
+        assert_eq!(frame_size(1, 0), Some(138));
+        assert_eq!(frame_size(1, 1), Some(140), "44.1kHz 奇数代码多一个字");
+        assert_eq!(frame_size(2, 37), Some(3840));
+        assert_eq!(frame_size(1, 37), Some(2788));
+        assert_eq!(frame_size(0, 38), None);
+        assert_eq!(frame_size(3, 0), None);
+    }
+
+    #[test]
+    fn test_parse_header_5_1() {
+        // fscod=0, frmsizecod=20 (192kbps); bsid=8, bsmod=0, acmod=7, cmixlev, surmixlev, lfeon=1
+        let mut data = vec![0x0B, 0x77, 0x00, 0x00, 0x14];
+        data.extend([0x40, 0xE1, 0x00, 0x00, 0x00, 0x00]);
+        let header = parse_header(&data).unwrap();
+        assert_eq!(header.frame_size, 768);
+        assert_eq!(header.acmod, 7);
+        assert_eq!(header.sample_rate(), 48000);
+        assert_eq!((header.fbw_channels(), header.channels()), (5, 6));
+
+        let mut eac3 = data.clone();
+        eac3[5] = 16 << 3;
+        assert!(
+            matches!(parse_header(&eac3), Err(TaoError::Unsupported(_))),
+            "bsid=16 应报不支持"
+        );
+    }
+}
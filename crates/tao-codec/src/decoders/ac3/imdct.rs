@@ -0,0 +1,252 @@
+//! AC-3 逆变换与加窗重叠相加 (A/52 7.9).
+//!
+//! 长块为 256 系数 -> 512 样本的 IMDCT; 块切换时为两个 128 系数变换,
+//! 相位分别偏移 -N/4 与 +N/4. 三者均由同一 DCT-IV 的周期延拓取样得到.
+
+use std::f64::consts::PI;
+
+use crate::decoders::aac::imdct::bessel_i0;
+
+/// 每块输出样本数
+pub(super) const BLOCK_LEN: usize = 256;
+
+/// 基于 FFT 的 DCT-IV: Y[m] = Σ X[k] · cos(π/n · (k + 1/2) · (m + 1/2))
+struct Dct4 {
+    n: usize,
+    pre_twiddle: Vec<(f64, f64)>,
+    post_twiddle: Vec<(f64, f64)>,
+    fft_twiddle: Vec<(f64, f64)>,
+    bit_reverse: Vec<usize>,
+}
+
+impl Dct4 {
+    fn new(n: usize) -> Self {
+        let half = n / 2;
+        let expi = |angle: f64| (angle.cos(), -angle.sin());
+        let bits = half.trailing_zeros();
+        Self {
+            n,
+            pre_twiddle: (0..half)
+                .map(|j| expi(PI * (j as f64 + 0.25) / n as f64))
+                .collect(),
+            post_twiddle: (0..half).map(|k| expi(PI * k as f64 / n as f64)).collect(),
+            fft_twiddle: (0..half / 2)
+                .map(|k| expi(2.0 * PI * k as f64 / half as f64))
+                .collect(),
+            bit_reverse: (0..half)
+                .map(|i| i.reverse_bits() >> (usize::BITS - bits))
+                .collect(),
+        }
+    }
+
+    fn transform(&self, input: &[f32], output: &mut [f64]) {
+        let n = self.n;
+        let half = n / 2;
+        let mut buf = vec![(0.0f64, 0.0f64); half];
+        for j in 0..half {
+            let (re, im) = (f64::from(input[2 * j]), f64::from(input[n - 1 - 2 * j]));
+            let (tr, ti) = self.pre_twiddle[j];
+            buf[self.bit_reverse[j]] = (re * tr - im * ti, re * ti + im * tr);
+        }
+        let mut len = 2;
+        while len <= half {
+            let step = half / len;
+            for start in (0..half).step_by(len) {
+                for k in 0..len / 2 {
+                    let (wr, wi) = self.fft_twiddle[k * step];
+                    let (br, bi) = buf[start + k + len / 2];
+                    let t = (br * wr - bi * wi, br * wi + bi * wr);
+                    let a = buf[start + k];
+                    buf[start + k] = (a.0 + t.0, a.1 + t.1);
+                    buf[start + k + len / 2] = (a.0 - t.0, a.1 - t.1);
+                }
+            }
+            len *= 2;
+        }
+        for (k, &(re, im)) in buf.iter().enumerate() {
+            let (tr, ti) = self.post_twiddle[k];
+            output[2 * k] = re * tr - im * ti;
+            output[n - 1 - 2 * k] = -(re * ti + im * tr);
+        }
+    }
+}
+
+/// DCT-IV 输出的周期延拓: C(m + 2n) = -C(m), C(2n - 1 - m) = -C(m)
+fn extended(y: &[f64], m: usize) -> f64 {
+    let n = y.len();
+    match (m / n) % 4 {
+        0 => y[m % n],
+        1 => -y[n - 1 - m % n],
+        2 => -y[m % n],
+        _ => y[n - 1 - m % n],
+    }
+}
+
+/// 逆变换器, 持有窗函数与各声道的重叠缓冲
+pub(super) struct Imdct {
+    long: Dct4,
+    short: Dct4,
+    /// KBD 窗前半部分 (alpha = 5), 后半部分对称
+    window: [f32; BLOCK_LEN],
+}
+
+impl Imdct {
+    pub(super) fn new() -> Self {
+        // A/52 7.9.4.1: w[n] = sqrt(Σ_{j<=n} W(j) / Σ_{j<=256} W(j))
+        let kernel: Vec<f64> = (0..=BLOCK_LEN)
+            .map(|j| {
+                let x = 2.0 * j as f64 / BLOCK_LEN as f64 - 1.0;
+                bessel_i0(5.0 * PI * (1.0 - x * x).max(0.0).sqrt())
+            })
+            .collect();
+        let total: f64 = kernel.iter().sum();
+        let mut window = [0.0f32; BLOCK_LEN];
+        let mut running = 0.0;
+        for (w, &k) in window.iter_mut().zip(&kernel) {
+            running += k;
+            *w = (running / total).sqrt() as f32;
+        }
+        Self {
+            long: Dct4::new(BLOCK_LEN),
+            short: Dct4::new(BLOCK_LEN / 2),
+            window,
+        }
+    }
+
+    fn window_at(&self, n: usize) -> f32 {
+        if n < BLOCK_LEN {
+            self.window[n]
+        } else {
+            self.window[2 * BLOCK_LEN - 1 - n]
+        }
+    }
+
+    /// 变换一个块的 256 个系数, 与 `delay` 重叠相加后写入 `output`
+    ///
+    /// `short` 为块切换标志 (blksw): 偶数系数属第一个短变换, 奇数系数属第二个.
+    pub(super) fn synthesize(
+        &self,
+        coeffs: &[f32; BLOCK_LEN],
+        short: bool,
+        delay: &mut [f32; BLOCK_LEN],
+        output: &mut [f32],
+    ) {
+        let mut samples = [0.0f64; 2 * BLOCK_LEN];
+        if short {
+            let half = BLOCK_LEN / 2;
+            let mut y1 = vec![0.0f64; half];
+            let mut y2 = vec![0.0f64; half];
+            let first: Vec<f32> = coeffs.iter().step_by(2).copied().collect();
+            let second: Vec<f32> = coeffs.iter().skip(1).step_by(2).copied().collect();
+            self.short.transform(&first, &mut y1);
+            self.short.transform(&second, &mut y2);
+            for n in 0..BLOCK_LEN {
+                samples[n] = extended(&y1, n);
+                samples[BLOCK_LEN + n] = extended(&y2, n + half);
+            }
+        } else {
+            let mut y = vec![0.0f64; BLOCK_LEN];
+            self.long.transform(coeffs, &mut y);
+            for (n, s) in samples.iter_mut().enumerate() {
+                *s = extended(&y, n + BLOCK_LEN / 2);
+            }
+        }
+        for n in 0..BLOCK_LEN {
+            let current = (-2.0 * samples[n]) as f32 * self.window_at(n);
+            output[n] = current + delay[n];
+            delay[n] = (-2.0 * samples[BLOCK_LEN + n]) as f32 * self.window_at(BLOCK_LEN + n);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A/52 正变换定义: X[k] = -(2/N) Σ x[n]·w[n]·cos(2π/(4N)·(2n+1)(2k+1) + π/4·(2k+1)(1+α))
+    fn forward(x: &[f64], alpha: f64) -> Vec<f32> {
+        let n_len = x.len();
+        (0..n_len / 2)
+            .map(|k| {
+                let sum: f64 = x
+                    .iter()
+                    .enumerate()
+                    .map(|(n, &v)| {
+                        let phase =
+                            2.0 * PI / (4 * n_len) as f64 * (2 * n + 1) as f64 * (2 * k + 1) as f64
+                                + PI / 4.0 * (2 * k + 1) as f64 * (1.0 + alpha);
+                        v * phase.cos()
+                    })
+                    .sum();
+                (-2.0 / n_len as f64 * sum) as f32
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dct4_matches_direct() {
+        let dct = Dct4::new(16);
+        let input: Vec<f32> = (0..16).map(|i| ((i * 7) % 5) as f32 - 2.0).collect();
+        let mut output = vec![0.0; 16];
+        dct.transform(&input, &mut output);
+        for (m, &y) in output.iter().enumerate() {
+            let direct: f64 = input
+                .iter()
+                .enumerate()
+                .map(|(k, &x)| {
+                    f64::from(x) * (PI / 16.0 * (k as f64 + 0.5) * (m as f64 + 0.5)).cos()
+                })
+                .sum();
+            assert!((y - direct).abs() < 1e-9, "DCT-IV 第 {m} 项不一致");
+        }
+    }
+
+    #[test]
+    fn test_window_is_power_complementary() {
+        let imdct = Imdct::new();
+        for n in 0..BLOCK_LEN {
+            let (a, b) = (imdct.window_at(n), imdct.window_at(n + BLOCK_LEN));
+            assert!((a * a + b * b - 1.0).abs() < 1e-5, "KBD 窗应满足功率互补");
+        }
+    }
+
+    #[test]
+    fn test_imdct_reconstructs_long_and_short_blocks() {
+        let imdct = Imdct::new();
+        let signal: Vec<f64> = (0..BLOCK_LEN * 5)
+            .map(|i| (i as f64 * 0.05).sin() * 0.5 + ((i * 13) % 7) as f64 * 0.01)
+            .collect();
+        let mut delay = [0.0f32; BLOCK_LEN];
+        let mut output = vec![0.0f32; BLOCK_LEN * 5];
+        for block in 0..4 {
+            let windowed: Vec<f64> = (0..2 * BLOCK_LEN)
+                .map(|n| signal[block * BLOCK_LEN + n] * f64::from(imdct.window_at(n)))
+                .collect();
+            let short = block == 2;
+            let mut coeffs = [0.0f32; BLOCK_LEN];
+            if short {
+                let first = forward(&windowed[..BLOCK_LEN], -1.0);
+                let second = forward(&windowed[BLOCK_LEN..], 1.0);
+                for k in 0..BLOCK_LEN / 2 {
+                    coeffs[2 * k] = first[k];
+                    coeffs[2 * k + 1] = second[k];
+                }
+            } else {
+                coeffs.copy_from_slice(&forward(&windowed, 0.0));
+            }
+            imdct.synthesize(
+                &coeffs,
+                short,
+                &mut delay,
+                &mut output[block * BLOCK_LEN..(block + 1) * BLOCK_LEN],
+            );
+        }
+        // 第一个块只有半边重叠, 从第二个块起应完全重建
+        for i in BLOCK_LEN..4 * BLOCK_LEN {
+            assert!(
+                (f64::from(output[i]) - signal[i]).abs() < 1e-4,
+                "第 {i} 个样本重建误差过大"
+            );
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! AC-3 (Dolby Digital, ATSC A/52) 音频解码器.
+//!
+//! 支持全部声道模式 (1/0 ~ 3/2) 与 LFE, 含耦合、重矩阵化、块切换与动态范围增益.
+//! 每个同步帧输出 1536 个样本, 按声道位掩码顺序交错为 F32.
+//!
+//! 解码流程:
+//! 1. 在输入缓冲中查找同步字 0x0B77, 解析 syncinfo/bsi 得到帧长与声道模式
+//! 2. 逐个解析 6 个音频块: 指数、比特分配、尾数反量化与解耦合
+//! 3. 每块每声道 256 点 IMDCT (或两个 128 点短变换), KBD 加窗重叠相加
+
+mod audblk;
+mod bitalloc;
+mod header;
+mod imdct;
+mod tables;
+
+#[cfg(test)]
+mod tests;
+
+use tao_core::bitreader::BitReader;
+use tao_core::channel_layout::ChannelMask;
+use tao_core::{ChannelLayout, Rational, SampleFormat, TaoError, TaoResult};
+use tracing::{debug, warn};
+
+use self::audblk::{AudioBlockState, BLOCKS_PER_FRAME, LFE_CH};
+use self::header::{FrameHeader, parse_header};
+use self::imdct::{BLOCK_LEN, Imdct};
+use crate::codec_id::CodecId;
+use crate::codec_parameters::{CodecParameters, CodecParamsType};
+use crate::decoder::Decoder;
+use crate::frame::{AudioFrame, Frame};
+use crate::frame_pool::{FrameBuf, FramePool};
+use crate::packet::Packet;
+
+/// 每个同步帧的样本数
+pub const SAMPLES_PER_FRAME: usize = BLOCK_LEN * BLOCKS_PER_FRAME;
+
+/// 各声道模式下全带宽声道 (码流顺序) 的扬声器位置
+fn fbw_channel_masks(acmod: u32) -> &'static [ChannelMask] {
+    const L: ChannelMask = ChannelMask::FRONT_LEFT;
+    const R: ChannelMask = ChannelMask::FRONT_RIGHT;
+    const C: ChannelMask = ChannelMask::FRONT_CENTER;
+    const S: ChannelMask = ChannelMask::BACK_CENTER;
+    const SL: ChannelMask = ChannelMask::BACK_LEFT;
+    const SR: ChannelMask = ChannelMask::BACK_RIGHT;
+    match acmod {
+        // 1+1 双单声道按左右输出
+        0 | 2 => &[L, R],
+        1 => &[C],
+        3 => &[L, C, R],
+        4 => &[L, R, S],
+        5 => &[L, C, R, S],
+        6 => &[L, R, SL, SR],
+        _ => &[L, C, R, SL, SR],
+    }
+}
+
+/// 输出声道布局, 及码流声道槽位 -> 输出声道下标的映射
+fn output_layout(header: &FrameHeader) -> (ChannelLayout, Vec<(usize, usize)>) {
+    let mut slots: Vec<(usize, ChannelMask)> = fbw_channel_masks(header.acmod)
+        .iter()
+        .copied()
+        .enumerate()
+        .collect();
+    if header.lfeon {
+        slots.push((LFE_CH, ChannelMask::LOW_FREQUENCY));
+    }
+    let mask = slots
+        .iter()
+        .fold(ChannelMask::empty(), |acc, &(_, m)| acc | m);
+    let mapping = slots
+        .iter()
+        .map(|&(slot, m)| (slot, (mask.bits() & (m.bits() - 1)).count_ones() as usize))
+        .collect();
+    let layout = ChannelLayout {
+        channels: slots.len() as u32,
+        mask,
+    };
+    (layout, mapping)
+}
+
+/// AC-3 解码器
+pub struct Ac3Decoder {
+    opened: bool,
+    flushing: bool,
+    buffer: Vec<u8>,
+    imdct: Imdct,
+    blocks: AudioBlockState,
+    /// 各声道槽位的重叠缓冲
+    delay: [[f32; BLOCK_LEN]; LFE_CH + 1],
+    /// 上一帧的声道配置, 变化时清空重叠缓冲
+    last_config: Option<(u32, bool, u32)>,
+    next_pts: i64,
+    /// 调用方提供的输出缓冲池
+    pool: Option<FramePool>,
+}
+
+impl Ac3Decoder {
+    /// 创建 AC-3 解码器 (工厂函数)
+    pub fn create() -> TaoResult<Box<dyn Decoder>> {
+        Ok(Box::new(Self {
+            opened: false,
+            flushing: false,
+            buffer: Vec::new(),
+            imdct: Imdct::new(),
+            blocks: AudioBlockState::new(),
+            delay: [[0.0; BLOCK_LEN]; LFE_CH + 1],
+            last_config: None,
+            next_pts: 0,
+            pool: None,
+        }))
+    }
+
+    /// 缓冲中没有完整帧: 刷新阶段丢弃残余数据并返回 Eof
+    fn need_more_data(&mut self) -> TaoError {
+        if self.flushing {
+            self.buffer.clear();
+            TaoError::Eof
+        } else {
+            TaoError::NeedMoreData
+        }
+    }
+
+    fn reset_state(&mut self) {
+        self.buffer.clear();
+        self.flushing = false;
+        self.delay = [[0.0; BLOCK_LEN]; LFE_CH + 1];
+        self.last_config = None;
+        self.next_pts = 0;
+    }
+
+    /// 解码一个完整同步帧
+    fn decode_frame(&mut self, data: &[u8], header: &FrameHeader) -> TaoResult<Frame> {
+        let config = (header.acmod, header.lfeon, header.sample_rate());
+        if self.last_config != Some(config) {
+            if self.last_config.is_some() {
+                debug!(
+                    "AC-3 声道配置变化: bsid={}, acmod={}, lfe={}, {} Hz",
+                    header.bsid,
+                    header.acmod,
+                    header.lfeon,
+                    header.sample_rate()
+                );
+            }
+            self.delay = [[0.0; BLOCK_LEN]; LFE_CH + 1];
+            self.last_config = Some(config);
+        }
+
+        let (layout, mapping) = output_layout(header);
+        let channels = header.channels();
+        let mut pcm = vec![0.0f32; SAMPLES_PER_FRAME * channels];
+        let mut block_out = [0.0f32; BLOCK_LEN];
+
+        let mut br = BitReader::new(data);
+        br.skip_bits(header.header_bits as u32)?;
+        self.blocks.start_frame();
+        for block in 0..BLOCKS_PER_FRAME {
+            self.blocks.decode_block(&mut br, header, block)?;
+            for &(slot, out_ch) in &mapping {
+                let short = slot != LFE_CH && self.blocks.block_switch(slot);
+                self.imdct.synthesize(
+                    &self.blocks.coeffs[slot],
+                    short,
+                    &mut self.delay[slot],
+                    &mut block_out,
+                );
+                let base = block * BLOCK_LEN;
+                for (i, &sample) in block_out.iter().enumerate() {
+                    pcm[(base + i) * channels + out_ch] = sample;
+                }
+            }
+        }
+
+        let sample_rate = header.sample_rate();
+        let mut frame = match &self.pool {
+            Some(pool) => AudioFrame::new_pooled(
+                SAMPLES_PER_FRAME as u32,
+                sample_rate,
+                SampleFormat::F32,
+                layout,
+                pool,
+            ),
+            None => {
+                let mut frame = AudioFrame::new(
+                    SAMPLES_PER_FRAME as u32,
+                    sample_rate,
+                    SampleFormat::F32,
+                    layout,
+                );
+                frame.data[0] = FrameBuf::from_vec(vec![0; pcm.len() * 4]);
+                frame
+            }
+        };
+        for (dst, sample) in frame.data[0].chunks_exact_mut(4).zip(&pcm) {
+            dst.copy_from_slice(&sample.to_le_bytes());
+        }
+        frame.pts = self.next_pts;
+        frame.time_base = Rational::new(1, sample_rate as i32);
+        frame.duration = SAMPLES_PER_FRAME as i64;
+        self.next_pts += SAMPLES_PER_FRAME as i64;
+        Ok(Frame::Audio(frame))
+    }
+}
+
+impl Decoder for Ac3Decoder {
+    fn codec_id(&self) -> CodecId {
+        CodecId::Ac3
+    }
+
+    fn name(&self) -> &str {
+        "ac3"
+    }
+
+    fn set_frame_pool(&mut self, pool: &FramePool) {
+        self.pool = Some(pool.clone());
+    }
+
+    fn open(&mut self, params: &CodecParameters) -> TaoResult<()> {
+        if !matches!(params.params, CodecParamsType::Audio(_)) {
+            return Err(TaoError::InvalidArgument("AC-3 解码器需要音频参数".into()));
+        }
+        self.reset_state();
+        self.blocks = AudioBlockState::new();
+        self.opened = true;
+        Ok(())
+    }
+
+    fn send_packet(&mut self, packet: &Packet) -> TaoResult<()> {
+        if !self.opened {
+            return Err(TaoError::Codec("AC-3 解码器未打开".into()));
+        }
+        if packet.is_empty() {
+            self.flushing = true;
+            return Ok(());
+        }
+        self.buffer.extend_from_slice(&packet.data);
+        Ok(())
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        loop {
+            // 丢弃同步字之前的数据
+            let sync = self.buffer.windows(2).position(|w| w == [0x0B, 0x77]);
+            match sync {
+                Some(pos) if pos > 0 => {
+                    self.buffer.drain(..pos);
+                }
+                Some(_) => {}
+                None => {
+                    let keep = usize::from(self.buffer.last() == Some(&0x0B));
+                    let len = self.buffer.len();
+                    self.buffer.drain(..len - keep);
+                }
+            }
+            let header = match parse_header(&self.buffer) {
+                Ok(header) => header,
+                Err(TaoError::NeedMoreData | TaoError::Eof) => return Err(self.need_more_data()),
+                Err(TaoError::Unsupported(msg)) => {
+                    self.buffer.drain(..2);
+                    return Err(TaoError::Unsupported(msg));
+                }
+                Err(e) => {
+                    // 伪同步字, 跳过后继续查找
+                    debug!("AC-3 帧头无效, 重新同步: {e}");
+                    self.buffer.drain(..2);
+                    continue;
+                }
+            };
+            if self.buffer.len() < header.frame_size {
+                return Err(self.need_more_data());
+            }
+            let data: Vec<u8> = self.buffer.drain(..header.frame_size).collect();
+            return self.decode_frame(&data, &header).inspect_err(|e| {
+                warn!("AC-3 帧解码失败: {e}");
+                self.next_pts += SAMPLES_PER_FRAME as i64;
+            });
+        }
+    }
+
+    fn flush(&mut self) {
+        self.reset_state();
+    }
+}
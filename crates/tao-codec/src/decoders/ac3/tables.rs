@@ -0,0 +1,109 @@
+//! AC-3 (ATSC A/52) 码表.
+
+/// 采样率 (按 fscod 索引)
+pub(super) const SAMPLE_RATES: [u32; 3] = [48000, 44100, 32000];
+
+/// 码率 (kbps, 按 frmsizecod / 2 索引)
+pub(super) const BITRATES: [u32; 19] = [
+    32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384, 448, 512, 576, 640,
+];
+
+/// 全带宽声道数 (按 acmod 索引)
+pub(super) const ACMOD_CHANNELS: [usize; 8] = [2, 1, 2, 3, 3, 4, 4, 5];
+
+/// 掩蔽频带起始频点 (最后一项为结束位置)
+#[rustfmt::skip]
+pub(super) const BAND_START: [usize; 51] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+    20, 21, 22, 23, 24, 25, 26, 27, 28, 31, 34, 37, 40, 43, 46, 49, 55, 61, 67, 73,
+    79, 85, 97, 109, 121, 133, 157, 181, 205, 229, 253,
+];
+
+/// 掩蔽频带数
+pub(super) const NUM_BANDS: usize = 50;
+
+/// 对数加法表 (latab)
+#[rustfmt::skip]
+pub(super) const LOG_ADD: [u8; 260] = [
+    0x40, 0x3f, 0x3e, 0x3d, 0x3c, 0x3b, 0x3a, 0x39, 0x38, 0x37,
+    0x36, 0x35, 0x34, 0x34, 0x33, 0x32, 0x31, 0x30, 0x2f, 0x2f,
+    0x2e, 0x2d, 0x2c, 0x2c, 0x2b, 0x2a, 0x29, 0x29, 0x28, 0x27,
+    0x26, 0x26, 0x25, 0x24, 0x24, 0x23, 0x23, 0x22, 0x21, 0x21,
+    0x20, 0x20, 0x1f, 0x1e, 0x1e, 0x1d, 0x1d, 0x1c, 0x1c, 0x1b,
+    0x1b, 0x1a, 0x1a, 0x19, 0x19, 0x18, 0x18, 0x17, 0x17, 0x16,
+    0x16, 0x15, 0x15, 0x15, 0x14, 0x14, 0x13, 0x13, 0x13, 0x12,
+    0x12, 0x12, 0x11, 0x11, 0x11, 0x10, 0x10, 0x10, 0x0f, 0x0f,
+    0x0f, 0x0e, 0x0e, 0x0e, 0x0d, 0x0d, 0x0d, 0x0d, 0x0c, 0x0c,
+    0x0c, 0x0c, 0x0b, 0x0b, 0x0b, 0x0b, 0x0a, 0x0a, 0x0a, 0x0a,
+    0x0a, 0x09, 0x09, 0x09, 0x09, 0x09, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x06, 0x06,
+    0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x05, 0x05, 0x05, 0x05,
+    0x05, 0x05, 0x05, 0x05, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04,
+    0x04, 0x04, 0x04, 0x04, 0x04, 0x03, 0x03, 0x03, 0x03, 0x03,
+    0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x03, 0x02,
+    0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02,
+    0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x01, 0x01,
+    0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+    0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+    0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+    0x01, 0x01, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// 绝对听阈 (hth, 按 [频带][fscod] 索引)
+#[rustfmt::skip]
+pub(super) const HEARING_THRESHOLD: [[i32; 3]; NUM_BANDS] = [
+    [0x04d0, 0x04f0, 0x0580], [0x04d0, 0x04f0, 0x0580], [0x0440, 0x0460, 0x04b0],
+    [0x0400, 0x0410, 0x0450], [0x03e0, 0x03e0, 0x0420], [0x03c0, 0x03d0, 0x03f0],
+    [0x03b0, 0x03c0, 0x03e0], [0x03b0, 0x03b0, 0x03d0], [0x03a0, 0x03b0, 0x03c0],
+    [0x03a0, 0x03a0, 0x03b0], [0x03a0, 0x03a0, 0x03b0], [0x03a0, 0x03a0, 0x03b0],
+    [0x03a0, 0x03a0, 0x03a0], [0x0390, 0x03a0, 0x03a0], [0x0390, 0x0390, 0x03a0],
+    [0x0390, 0x0390, 0x03a0], [0x0380, 0x0390, 0x03a0], [0x0380, 0x0380, 0x03a0],
+    [0x0370, 0x0380, 0x03a0], [0x0370, 0x0380, 0x03a0], [0x0360, 0x0370, 0x0390],
+    [0x0360, 0x0370, 0x0390], [0x0350, 0x0360, 0x0390], [0x0350, 0x0360, 0x0390],
+    [0x0340, 0x0350, 0x0380], [0x0340, 0x0350, 0x0380], [0x0330, 0x0340, 0x0380],
+    [0x0320, 0x0340, 0x0370], [0x0310, 0x0320, 0x0360], [0x0300, 0x0310, 0x0350],
+    [0x02f0, 0x0300, 0x0340], [0x02f0, 0x02f0, 0x0330], [0x02f0, 0x02f0, 0x0320],
+    [0x02f0, 0x02f0, 0x0310], [0x0300, 0x02f0, 0x0300], [0x0310, 0x0300, 0x02f0],
+    [0x0340, 0x0320, 0x02f0], [0x0390, 0x0350, 0x02f0], [0x03e0, 0x0390, 0x0300],
+    [0x0420, 0x03e0, 0x0310], [0x0460, 0x0420, 0x0330], [0x0490, 0x0450, 0x0350],
+    [0x04a0, 0x04a0, 0x03c0], [0x0460, 0x0490, 0x0420], [0x0440, 0x0460, 0x0470],
+    [0x0440, 0x0440, 0x04a0], [0x0520, 0x0480, 0x0460], [0x0800, 0x0630, 0x0440],
+    [0x0840, 0x0840, 0x0450], [0x0840, 0x0840, 0x04e0],
+];
+
+/// 比特分配指针表 (baptab)
+#[rustfmt::skip]
+pub(super) const BAP_TAB: [u8; 64] = [
+    0, 1, 1, 1, 1, 1, 2, 2, 3, 3, 3, 4, 4, 5, 5, 6,
+    6, 6, 6, 7, 7, 7, 7, 8, 8, 8, 8, 9, 9, 9, 9, 10,
+    10, 10, 10, 11, 11, 11, 11, 12, 12, 12, 12, 13, 13, 13, 13, 14,
+    14, 14, 14, 14, 14, 14, 14, 15, 15, 15, 15, 15, 15, 15, 15, 15,
+];
+
+/// 慢衰减 (按 sdcycod 索引)
+pub(super) const SLOW_DECAY: [i32; 4] = [0x0f, 0x11, 0x13, 0x15];
+/// 快衰减 (按 fdcycod 索引)
+pub(super) const FAST_DECAY: [i32; 4] = [0x3f, 0x53, 0x67, 0x7b];
+/// 慢增益 (按 sgaincod 索引)
+pub(super) const SLOW_GAIN: [i32; 4] = [0x540, 0x4d8, 0x478, 0x410];
+/// 每比特 dB 值 (按 dbpbcod 索引)
+pub(super) const DB_PER_BIT: [i32; 4] = [0x000, 0x700, 0x900, 0xb00];
+/// 掩蔽下限 (按 floorcod 索引)
+pub(super) const FLOOR: [i32; 8] = [0x2f0, 0x2b0, 0x270, 0x230, 0x1f0, 0x170, 0x0f0, -0x800];
+/// 快增益 (按 fgaincod 索引)
+pub(super) const FAST_GAIN: [i32; 8] = [0x080, 0x100, 0x180, 0x200, 0x280, 0x300, 0x380, 0x400];
+
+/// bap 6..=15 的非对称量化位数 (按 bap - 6 索引)
+pub(super) const ASYMMETRIC_BITS: [u32; 10] = [5, 6, 7, 8, 9, 10, 11, 12, 14, 16];
+
+/// 重矩阵化频带边界
+pub(super) const REMATRIX_BANDS: [usize; 5] = [13, 25, 37, 61, 253];
+
+/// 频点所属掩蔽频带
+pub(super) fn bin_to_band(bin: usize) -> usize {
+    BAND_START[1..].partition_point(|&start| start <= bin)
+}
@@ -0,0 +1,621 @@
+use super::bitalloc::{BitAllocParams, ChannelAlloc, DeltaBitAlloc, compute_bap, snr_offset};
+use super::tables::FAST_GAIN;
+use super::*;
+use crate::codec_parameters::AudioCodecParams;
+use tao_core::bitwriter::BitWriter;
+
+/// 测试码流使用的比特分配参数
+const TEST_BA: BitAllocParams = BitAllocParams {
+    sdcycod: 2,
+    fdcycod: 1,
+    sgaincod: 1,
+    dbpbcod: 2,
+    floorcod: 4,
+};
+const TEST_CSNROFFST: u32 = 15;
+const TEST_FSNROFFST: u32 = 8;
+const TEST_FGAINCOD: usize = 4;
+/// 640kbps @ 48kHz, 帧长 2560 字节
+const TEST_FRMSIZECOD: u32 = 36;
+
+/// 一个声道的测试内容: 在 `tone` 频点放置固定系数
+#[derive(Clone, Copy)]
+struct ToneChannel {
+    tone: usize,
+    end: usize,
+}
+
+/// 测试帧配置
+struct TestConfig {
+    acmod: u32,
+    lfeon: bool,
+    channels: Vec<ToneChannel>,
+    lfe_tone: usize,
+    /// 重矩阵化标志 (仅 acmod=2)
+    rematrix: [bool; 4],
+    /// 双声道全部参与耦合
+    coupling: Option<TestCoupling>,
+}
+
+/// 耦合配置, 各子带不合并
+#[derive(Clone, Copy)]
+struct TestCoupling {
+    begf: usize,
+    endf: usize,
+    /// 耦合声道音调频点
+    tone: usize,
+    /// 各声道坐标 (cplcoexp, mstrcplco), cplcomant 固定为 0
+    coords: [(u32, u32); 2],
+}
+
+/// 音调频点处的系数, 其余频点为 0
+const TONE_COEFF: f32 = 0.25;
+
+/// 以音调频点为中心向两侧递增的指数 (相邻差值不超过 2)
+fn tone_exponents(tone: usize, start: usize, end: usize) -> [u8; 256] {
+    let mut exps = [0u8; 256];
+    for (bin, e) in exps.iter_mut().enumerate().take(end).skip(start) {
+        *e = (2 + 2 * bin.abs_diff(tone)).min(14) as u8;
+    }
+    exps
+}
+
+fn quantize(value: f32, bap: u8) -> u32 {
+    match bap {
+        1..=5 => {
+            let levels = [0, 3, 5, 7, 11, 15][usize::from(bap)] as f32;
+            ((value * levels + levels - 1.0) / 2.0)
+                .round()
+                .clamp(0.0, levels - 1.0) as u32
+        }
+        _ => {
+            let bits = super::tables::ASYMMETRIC_BITS[usize::from(bap) - 6];
+            let limit = (1i32 << (bits - 1)) as f32;
+            let q = (value * limit).round().clamp(-limit, limit - 1.0) as i32;
+            (q as u32) & ((1u32 << bits) - 1)
+        }
+    }
+}
+
+/// 写出一组差分指数 (D15)
+fn write_exponents(bw: &mut BitWriter, exps: &[u8], absexp: u8) {
+    let mut prev = i32::from(absexp);
+    for group in exps.chunks(3) {
+        let mut diffs = [2i32; 3];
+        for (d, &e) in diffs.iter_mut().zip(group) {
+            *d = i32::from(e) - prev + 2;
+            assert!((0..5).contains(d), "测试指数相邻差值需在 ±2 以内");
+            prev = i32::from(e);
+        }
+        bw.write_bits((diffs[0] * 25 + diffs[1] * 5 + diffs[2]) as u32, 7);
+    }
+}
+
+fn alloc(exps: &[u8; 256], start: usize, end: usize, cpl: bool, lfe: bool) -> [u8; 256] {
+    let delta = DeltaBitAlloc::default();
+    let mut bap = [0u8; 256];
+    compute_bap(
+        &ChannelAlloc {
+            exponents: exps,
+            start,
+            end,
+            fscod: 0,
+            params: TEST_BA,
+            snr_offset: snr_offset(TEST_CSNROFFST, TEST_FSNROFFST),
+            fast_gain: FAST_GAIN[TEST_FGAINCOD],
+            coupling_leak: cpl.then_some((768, 768)),
+            is_lfe: lfe,
+            delta: &delta,
+        },
+        &mut bap,
+    )
+    .unwrap();
+    bap
+}
+
+/// 按码流顺序写出一块的全部尾数, 处理 bap=1/2/4 的分组
+fn write_mantissas(bw: &mut BitWriter, entries: &[(u8, u32)]) {
+    let grouped =
+        |bap: u8| -> Vec<u32> { entries.iter().filter(|e| e.0 == bap).map(|e| e.1).collect() };
+    let (g1, g2, g4) = (grouped(1), grouped(2), grouped(4));
+    let (mut i1, mut i2, mut i4) = (0, 0, 0);
+    let at = |codes: &[u32], i: usize, pad: u32| codes.get(i).copied().unwrap_or(pad);
+    for &(bap, code) in entries {
+        match bap {
+            0 => {}
+            1 => {
+                if i1 % 3 == 0 {
+                    let v = at(&g1, i1, 1) * 9 + at(&g1, i1 + 1, 1) * 3 + at(&g1, i1 + 2, 1);
+                    bw.write_bits(v, 5);
+                }
+                i1 += 1;
+            }
+            2 => {
+                if i2 % 3 == 0 {
+                    let v = at(&g2, i2, 2) * 25 + at(&g2, i2 + 1, 2) * 5 + at(&g2, i2 + 2, 2);
+                    bw.write_bits(v, 7);
+                }
+                i2 += 1;
+            }
+            3 => bw.write_bits(code, 3),
+            4 => {
+                if i4 % 2 == 0 {
+                    bw.write_bits(at(&g4, i4, 5) * 11 + at(&g4, i4 + 1, 5), 7);
+                }
+                i4 += 1;
+            }
+            5 => bw.write_bits(code, 4),
+            _ => bw.write_bits(code, super::tables::ASYMMETRIC_BITS[usize::from(bap) - 6]),
+        }
+    }
+}
+
+/// 构造一个 48kHz 同步帧; 每块内容相同, 第一块传输全部参数, 其余块全部沿用
+fn encode_test_frame(cfg: &TestConfig) -> Vec<u8> {
+    let nfchans = cfg.channels.len();
+    let mut bw = BitWriter::new();
+    bw.write_bits(0x0B77, 16);
+    bw.write_bits(0, 16); // crc1
+    bw.write_bits(0, 2); // fscod = 48kHz
+    bw.write_bits(TEST_FRMSIZECOD, 6);
+
+    bw.write_bits(8, 5); // bsid
+    bw.write_bits(0, 3); // bsmod
+    bw.write_bits(cfg.acmod, 3);
+    if cfg.acmod & 1 != 0 && cfg.acmod != 1 {
+        bw.write_bits(0, 2);
+    }
+    if cfg.acmod & 4 != 0 {
+        bw.write_bits(0, 2);
+    }
+    if cfg.acmod == 2 {
+        bw.write_bits(0, 2);
+    }
+    bw.write_bit(u32::from(cfg.lfeon));
+    bw.write_bits(27, 5); // dialnorm
+    bw.write_bits(0, 3); // compre, langcode, audprodie
+    bw.write_bits(0b01, 2); // copyrightb, origbs
+    bw.write_bits(0, 3); // timecod1e, timecod2e, addbsie
+
+    // 各声道指数与 bap
+    let cpl_start = cfg.coupling.map(|c| 37 + 12 * c.begf);
+    let chan_exps: Vec<([u8; 256], usize, u8)> = cfg
+        .channels
+        .iter()
+        .map(|c| {
+            let end = cpl_start.unwrap_or(c.end);
+            let exps = tone_exponents(c.tone, 1, end);
+            let mut exps = exps;
+            exps[0] = exps[1].max(2) - 2;
+            (exps, end, exps[0])
+        })
+        .collect();
+    let cpl = cfg.coupling.map(
+        |TestCoupling {
+             begf,
+             endf,
+             tone,
+             coords,
+         }| {
+            let (start, end) = (37 + 12 * begf, 37 + 12 * (endf + 3));
+            let exps = tone_exponents(tone, start, end);
+            let absexp = (exps[start].max(2) - 2) & !1;
+            (start, end, tone, coords, exps, absexp)
+        },
+    );
+    let lfe_exps = {
+        let mut exps = tone_exponents(cfg.lfe_tone, 1, 7);
+        exps[0] = exps[1] - 2;
+        exps
+    };
+
+    // 按码流顺序收集尾数 (bap, 码)
+    let mut mantissas = Vec::new();
+    for (ch, c) in cfg.channels.iter().enumerate() {
+        let (exps, end, _) = &chan_exps[ch];
+        let bap = alloc(exps, 0, *end, false, false);
+        for bin in 0..*end {
+            let m = if bin == c.tone {
+                TONE_COEFF * (f32::from(exps[bin])).exp2()
+            } else {
+                0.0
+            };
+            if bap[bin] > 0 {
+                mantissas.push((bap[bin], quantize(m, bap[bin])));
+            }
+        }
+        if ch == 0
+            && let Some((start, end, tone, _, exps, _)) = &cpl
+        {
+            let bap = alloc(exps, *start, *end, true, false);
+            for bin in *start..*end {
+                let m = if bin == *tone {
+                    TONE_COEFF * (f32::from(exps[bin])).exp2()
+                } else {
+                    0.0
+                };
+                if bap[bin] > 0 {
+                    mantissas.push((bap[bin], quantize(m, bap[bin])));
+                }
+            }
+        }
+    }
+    if cfg.lfeon {
+        let bap = alloc(&lfe_exps, 0, 7, false, true);
+        for bin in 0..7 {
+            let m = if bin == cfg.lfe_tone {
+                TONE_COEFF * (f32::from(lfe_exps[bin])).exp2()
+            } else {
+                0.0
+            };
+            if bap[bin] > 0 {
+                mantissas.push((bap[bin], quantize(m, bap[bin])));
+            }
+        }
+    }
+
+    for block in 0..BLOCKS_PER_FRAME {
+        let first = block == 0;
+        bw.write_bits(0, nfchans as u32); // blksw
+        bw.write_bits(0, nfchans as u32); // dithflag
+        bw.write_bit(0); // dynrnge
+        if cfg.acmod == 0 {
+            bw.write_bit(0);
+        }
+        // 耦合策略与坐标
+        bw.write_bit(u32::from(first));
+        if first {
+            bw.write_bit(u32::from(cpl.is_some()));
+            if let Some(TestCoupling { begf, endf, .. }) = cfg.coupling {
+                bw.write_bits(0b11, 2); // chincpl
+                bw.write_bit(0); // phsflginu
+                bw.write_bits(begf as u32, 4);
+                bw.write_bits(endf as u32, 4);
+                bw.write_bits(0, (endf + 2 - begf) as u32); // cplbndstrc
+            }
+        }
+        if let Some((start, end, _, coords, ..)) = &cpl {
+            let bands = (end - start) / 12;
+            for &(exp, master) in coords {
+                bw.write_bit(u32::from(first));
+                if first {
+                    bw.write_bits(master, 2);
+                    for _ in 0..bands {
+                        bw.write_bits(exp, 4);
+                        bw.write_bits(0, 4);
+                    }
+                }
+            }
+        }
+        if cfg.acmod == 2 {
+            bw.write_bit(u32::from(first));
+            if first {
+                let bands = match cpl_start {
+                    Some(37) => 2,
+                    Some(s) if s <= 61 => 3,
+                    _ => 4,
+                };
+                for &flag in &cfg.rematrix[..bands] {
+                    bw.write_bit(u32::from(flag));
+                }
+            }
+        }
+        // 指数策略: 第一块 D15, 其余沿用
+        let strategy = u32::from(first);
+        if cpl.is_some() {
+            bw.write_bits(strategy, 2);
+        }
+        for _ in 0..nfchans {
+            bw.write_bits(strategy, 2);
+        }
+        if cfg.lfeon {
+            bw.write_bit(strategy);
+        }
+        if first {
+            if cpl.is_none() {
+                for (_, end, _) in &chan_exps {
+                    bw.write_bits(((end - 37) / 3 - 12) as u32, 6);
+                }
+            }
+            if let Some((start, end, _, _, exps, absexp)) = &cpl {
+                bw.write_bits(u32::from(*absexp) >> 1, 4);
+                write_exponents(&mut bw, &exps[*start..*end], *absexp);
+            }
+            for (exps, end, absexp) in &chan_exps {
+                bw.write_bits(u32::from(*absexp), 4);
+                let groups = (end - 1) / 3;
+                write_exponents(&mut bw, &exps[1..1 + 3 * groups], *absexp);
+                bw.write_bits(0, 2); // gainrng
+            }
+            if cfg.lfeon {
+                bw.write_bits(u32::from(lfe_exps[0]), 4);
+                write_exponents(&mut bw, &lfe_exps[1..7], lfe_exps[0]);
+            }
+        }
+        // 比特分配参数
+        bw.write_bit(u32::from(first));
+        if first {
+            bw.write_bits(TEST_BA.sdcycod as u32, 2);
+            bw.write_bits(TEST_BA.fdcycod as u32, 2);
+            bw.write_bits(TEST_BA.sgaincod as u32, 2);
+            bw.write_bits(TEST_BA.dbpbcod as u32, 2);
+            bw.write_bits(TEST_BA.floorcod as u32, 3);
+        }
+        bw.write_bit(u32::from(first));
+        if first {
+            bw.write_bits(TEST_CSNROFFST, 6);
+            let offsets = nfchans + usize::from(cpl.is_some()) + usize::from(cfg.lfeon);
+            for _ in 0..offsets {
+                bw.write_bits(TEST_FSNROFFST, 4);
+                bw.write_bits(TEST_FGAINCOD as u32, 3);
+            }
+        }
+        if cpl.is_some() {
+            bw.write_bit(u32::from(first));
+            if first {
+                bw.write_bits(0, 6); // cplfleak, cplsleak
+            }
+        }
+        bw.write_bit(0); // deltbaie
+        bw.write_bit(0); // skiple
+        write_mantissas(&mut bw, &mantissas);
+    }
+
+    let frame_size = header::frame_size(0, TEST_FRMSIZECOD as usize).unwrap();
+    let mut data = bw.finish();
+    assert!(data.len() <= frame_size - 2, "测试帧内容超出帧长");
+    data.resize(frame_size, 0);
+    data
+}
+
+fn make_params() -> CodecParameters {
+    CodecParameters {
+        codec_id: CodecId::Ac3,
+        extra_data: Vec::new(),
+        bit_rate: 0,
+        params: CodecParamsType::Audio(AudioCodecParams {
+            sample_rate: 48000,
+            channel_layout: ChannelLayout::STEREO,
+            sample_format: SampleFormat::F32,
+            frame_size: 1536,
+            block_align: 0,
+        }),
+    }
+}
+
+/// 解码全部数据, 返回每帧 (声道布局, 交错样本)
+fn decode_all(data: &[u8]) -> Vec<(ChannelLayout, u32, Vec<f32>)> {
+    decode_all_with_pool(data, None)
+}
+
+fn decode_all_with_pool(
+    data: &[u8],
+    pool: Option<&FramePool>,
+) -> Vec<(ChannelLayout, u32, Vec<f32>)> {
+    let mut decoder = Ac3Decoder::create().unwrap();
+    if let Some(pool) = pool {
+        decoder.set_frame_pool(pool);
+    }
+    decoder.open(&make_params()).unwrap();
+    decoder
+        .send_packet(&Packet::from_data(data.to_vec()))
+        .unwrap();
+    decoder.send_packet(&Packet::empty()).unwrap();
+    let mut frames = Vec::new();
+    loop {
+        match decoder.receive_frame() {
+            Ok(Frame::Audio(frame)) => {
+                let samples = frame.data[0]
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect();
+                frames.push((frame.channel_layout, frame.nb_samples, samples));
+            }
+            Ok(_) => panic!("AC-3 解码器应输出音频帧"),
+            Err(TaoError::Eof) => break,
+            Err(e) => panic!("AC-3 解码失败: {e}"),
+        }
+    }
+    frames
+}
+
+fn channel(samples: &[f32], channels: usize, ch: usize) -> Vec<f32> {
+    samples.iter().skip(ch).step_by(channels).copied().collect()
+}
+
+fn zero_crossings(samples: &[f32]) -> usize {
+    samples
+        .windows(2)
+        .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+        .count()
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// 频点 k 的音调在 1536 个样本内的过零次数约为 6 · (k + 1/2)
+fn expected_crossings(bin: usize) -> usize {
+    6 * bin + 3
+}
+
+fn assert_tone(samples: &[f32], bin: usize, label: &str) {
+    let crossings = zero_crossings(samples);
+    assert!(
+        crossings.abs_diff(expected_crossings(bin)) <= 4,
+        "{label} 应为频点 {bin} 的音调, 实际过零 {crossings} 次"
+    );
+    assert!(rms(samples) > 0.05, "{label} 音调幅度过小");
+}
+
+#[test]
+fn test_ac3_decode_stereo_frame() {
+    let cfg = TestConfig {
+        acmod: 2,
+        lfeon: false,
+        channels: vec![ToneChannel { tone: 20, end: 181 }; 2],
+        lfe_tone: 0,
+        rematrix: [true, false, false, false],
+        coupling: None,
+    };
+    let mut cfg = cfg;
+    // 重矩阵化: 和信号在 L', 差信号为 0, 解码后左右相同
+    cfg.channels[1].tone = 100;
+    let frame = encode_test_frame(&cfg);
+    let frames = decode_all(&[frame.clone(), frame].concat());
+    assert_eq!(frames.len(), 2, "两个同步帧应输出两帧");
+    let (layout, nb_samples, samples) = &frames[1];
+    assert_eq!(*nb_samples, 1536, "每帧应输出 1536 个样本");
+    assert_eq!(*layout, ChannelLayout::STEREO);
+    assert_eq!(samples.len(), 1536 * 2);
+
+    let (left, right) = (channel(samples, 2, 0), channel(samples, 2, 1));
+    assert_tone(&left, 20, "左声道");
+    // 右声道 = L' - R': 频点 20 处与左声道相同, 另含频点 100 的反相分量
+    let diff: Vec<f32> = left.iter().zip(&right).map(|(l, r)| l - r).collect();
+    assert_tone(&diff, 100, "左右差信号");
+}
+
+#[test]
+fn test_ac3_decode_5_1_frame() {
+    let tones = [10, 30, 20, 40, 50];
+    let cfg = TestConfig {
+        acmod: 7,
+        lfeon: true,
+        channels: tones
+            .iter()
+            .map(|&tone| ToneChannel { tone, end: 253 })
+            .collect(),
+        lfe_tone: 3,
+        rematrix: [false; 4],
+        coupling: None,
+    };
+    let frame = encode_test_frame(&cfg);
+    let frames = decode_all(&[frame.clone(), frame].concat());
+    assert_eq!(frames.len(), 2);
+    let (layout, nb_samples, samples) = &frames[1];
+    assert_eq!(*nb_samples, 1536, "每帧应输出 1536 个样本");
+    assert_eq!(
+        *layout,
+        ChannelLayout::SURROUND_5_1,
+        "3/2+LFE 应输出 5.1 布局"
+    );
+    assert_eq!(samples.len(), 1536 * 6);
+
+    // 码流顺序 L C R SL SR + LFE -> 输出顺序 L R C LFE BL BR
+    let expected = [
+        (0, 10, "左"),
+        (1, 20, "右"),
+        (2, 30, "中"),
+        (3, 3, "LFE"),
+        (4, 40, "左环绕"),
+        (5, 50, "右环绕"),
+    ];
+    for (ch, bin, label) in expected {
+        assert_tone(&channel(samples, 6, ch), bin, label);
+    }
+}
+
+#[test]
+fn test_ac3_decode_coupling_applies_coordinates() {
+    let cfg = TestConfig {
+        acmod: 2,
+        lfeon: false,
+        channels: vec![ToneChannel { tone: 20, end: 0 }; 2],
+        lfe_tone: 0,
+        rematrix: [false; 4],
+        // 耦合频点 85..145, 左坐标 1.0, 右坐标 0.5
+        coupling: Some(TestCoupling {
+            begf: 4,
+            endf: 6,
+            tone: 100,
+            coords: [(2, 0), (3, 0)],
+        }),
+    };
+    let frame = encode_test_frame(&cfg);
+    let frames = decode_all(&[frame.clone(), frame].concat());
+    let (_, _, samples) = &frames[1];
+    let (left, right) = (channel(samples, 2, 0), channel(samples, 2, 1));
+    // 左右声道各自的频点 20 音调相同, 耦合音调按坐标缩放
+    let cpl_left: Vec<f32> = left
+        .iter()
+        .zip(&right)
+        .map(|(l, r)| 2.0 * (l - r))
+        .collect();
+    assert_tone(&cpl_left, 100, "耦合分量");
+    let base: Vec<f32> = left.iter().zip(&cpl_left).map(|(l, c)| l - c).collect();
+    assert_tone(&base, 20, "非耦合分量");
+}
+
+#[test]
+fn test_ac3_resync_and_split_packets() {
+    let cfg = TestConfig {
+        acmod: 1,
+        lfeon: false,
+        channels: vec![ToneChannel { tone: 12, end: 181 }],
+        lfe_tone: 0,
+        rematrix: [false; 4],
+        coupling: None,
+    };
+    let frame = encode_test_frame(&cfg);
+    let mut decoder = Ac3Decoder::create().unwrap();
+    assert!(
+        decoder
+            .send_packet(&Packet::from_data(frame.clone()))
+            .is_err(),
+        "未打开时送包应报错"
+    );
+    decoder.open(&make_params()).unwrap();
+
+    // 前置垃圾数据 (含伪同步字), 帧被拆成两个包
+    let mut first = vec![0x00, 0x0B, 0x77, 0x12, 0x34, 0xFF];
+    first.extend_from_slice(&frame[..1000]);
+    decoder.send_packet(&Packet::from_data(first)).unwrap();
+    assert!(matches!(
+        decoder.receive_frame(),
+        Err(TaoError::NeedMoreData)
+    ));
+    decoder
+        .send_packet(&Packet::from_data(frame[1000..].to_vec()))
+        .unwrap();
+    let Frame::Audio(audio) = decoder.receive_frame().unwrap() else {
+        panic!("应输出音频帧");
+    };
+    assert_eq!(audio.nb_samples, 1536);
+    assert_eq!(
+        audio.channel_layout,
+        ChannelLayout::MONO,
+        "1/0 模式应输出单声道"
+    );
+    assert_eq!(audio.sample_format, SampleFormat::F32);
+    assert!(matches!(
+        decoder.receive_frame(),
+        Err(TaoError::NeedMoreData)
+    ));
+
+    decoder
+        .send_packet(&Packet::from_data(frame[..100].to_vec()))
+        .unwrap();
+    decoder.send_packet(&Packet::empty()).unwrap();
+    assert!(
+        matches!(decoder.receive_frame(), Err(TaoError::Eof)),
+        "刷新后残余半帧应丢弃"
+    );
+}
+
+#[test]
+fn test_ac3_decode_with_frame_pool() {
+    let cfg = TestConfig {
+        acmod: 1,
+        lfeon: false,
+        channels: vec![ToneChannel { tone: 12, end: 181 }],
+        lfe_tone: 0,
+        rematrix: [false; 4],
+        coupling: None,
+    };
+    let stream = encode_test_frame(&cfg).repeat(8);
+    let pool = FramePool::new(2);
+    let pooled = decode_all_with_pool(&stream, Some(&pool));
+    assert_eq!(pooled, decode_all(&stream), "使用缓冲池不应影响输出");
+    assert_eq!(pool.allocation_count(), 1, "逐帧释放时应复用同一缓冲");
+}
@@ -0,0 +1,625 @@
+//! ADPCM 音频解码器 (IMA ADPCM WAV / Microsoft ADPCM).
+//!
+//! 两种格式都按块编码, 块大小由容器的 `block_align` 给出, 每块以各声道的
+//! 预测器初值开头, 其后为 4 位残差 (nibble). 输出交错 S16.
+//!
+//! 块布局:
+//! - IMA: 每声道 4 字节头 (predictor i16 + step_index u8 + 保留), 头中的预测值即第 1 个样本;
+//!   之后每声道轮流 4 字节 (8 个样本), 每字节低 4 位在前
+//! - MS: 依次为各声道的系数索引 u8, delta i16, sample1 i16, sample2 i16,
+//!   先输出 sample2 再输出 sample1; 之后每字节高 4 位在前, nibble 按声道交错
+//!
+//! 未给出 `block_align` 时, 每个数据包视为一个完整块.
+
+use tao_core::{ChannelLayout, SampleFormat, TaoError, TaoResult};
+use tracing::debug;
+
+use crate::capabilities::CodecCapabilities;
+use crate::codec_id::CodecId;
+use crate::codec_parameters::{CodecParameters, CodecParamsType};
+use crate::decoder::Decoder;
+use crate::frame::{AudioFrame, Frame};
+use crate::frame_pool::FramePool;
+use crate::packet::Packet;
+
+/// IMA ADPCM 量化步长表
+const IMA_STEP_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66,
+    73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408, 449,
+    494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066, 2272,
+    2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630, 9493,
+    10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
+];
+
+/// IMA ADPCM 步长索引调整表
+const IMA_INDEX_TABLE: [i32; 16] = [-1, -1, -1, -1, 2, 4, 6, 8, -1, -1, -1, -1, 2, 4, 6, 8];
+
+/// MS ADPCM 标准预测系数 (coef1, coef2), 以 256 为 1.0
+const MS_COEFFS: [(i32, i32); 7] = [
+    (256, 0),
+    (512, -256),
+    (0, 0),
+    (192, 64),
+    (240, 0),
+    (460, -208),
+    (392, -232),
+];
+
+/// MS ADPCM delta 自适应表
+const MS_ADAPTATION_TABLE: [i32; 16] = [
+    230, 230, 230, 230, 307, 409, 512, 614, 768, 614, 512, 409, 307, 230, 230, 230,
+];
+
+/// MS ADPCM delta 下限
+const MS_MIN_DELTA: i64 = 16;
+/// MS ADPCM delta 上限, 保证与自适应表相乘不溢出 i32 (同 FFmpeg 的 INT_MAX / 768)
+const MS_MAX_DELTA: i64 = (i32::MAX / 768) as i64;
+
+/// IMA 每声道块头字节数
+const IMA_HEADER_SIZE: usize = 4;
+/// MS 每声道块头字节数
+const MS_HEADER_SIZE: usize = 7;
+
+/// 计算给定块大小下每声道的样本数, 块大小不足以容纳块头时返回 0
+///
+/// 容器 (WAV/AVI) 据此由 `block_align` 换算时间戳与时长.
+pub fn samples_per_block(codec_id: CodecId, block_align: u32, channels: u32) -> u32 {
+    let (block, ch) = (block_align as usize, channels as usize);
+    if ch == 0 {
+        return 0;
+    }
+    let samples = match codec_id {
+        CodecId::AdpcmImaWav => {
+            let header = IMA_HEADER_SIZE * ch;
+            if block < header {
+                return 0;
+            }
+            1 + (block - header) / (4 * ch) * 8
+        }
+        CodecId::AdpcmMs => {
+            let header = MS_HEADER_SIZE * ch;
+            if block < header {
+                return 0;
+            }
+            2 + (block - header) * 2 / ch
+        }
+        _ => 0,
+    };
+    samples as u32
+}
+
+/// IMA ADPCM 单声道解码状态
+#[derive(Clone, Copy)]
+struct ImaChannel {
+    predictor: i32,
+    step_index: i32,
+}
+
+impl ImaChannel {
+    /// 展开一个 4 位残差, 返回新的预测值
+    fn expand(&mut self, nibble: u8) -> i16 {
+        let step = IMA_STEP_TABLE[self.step_index as usize];
+        let mut diff = step >> 3;
+        if nibble & 4 != 0 {
+            diff += step;
+        }
+        if nibble & 2 != 0 {
+            diff += step >> 1;
+        }
+        if nibble & 1 != 0 {
+            diff += step >> 2;
+        }
+        if nibble & 8 != 0 {
+            self.predictor -= diff;
+        } else {
+            self.predictor += diff;
+        }
+        self.predictor = self.predictor.clamp(i16::MIN as i32, i16::MAX as i32);
+        self.step_index = (self.step_index + IMA_INDEX_TABLE[nibble as usize]).clamp(0, 88);
+        self.predictor as i16
+    }
+}
+
+/// MS ADPCM 单声道解码状态
+#[derive(Clone, Copy)]
+struct MsChannel {
+    coeff: (i32, i32),
+    delta: i32,
+    sample1: i32,
+    sample2: i32,
+}
+
+impl MsChannel {
+    /// 展开一个 4 位残差, 返回新的预测值
+    fn expand(&mut self, nibble: u8) -> i16 {
+        // 以 i64 计算, 异常块头给出的大 delta 不会溢出
+        let signed = i64::from((nibble as i8) << 4 >> 4);
+        let delta = i64::from(self.delta);
+        let predicted = (i64::from(self.sample1) * i64::from(self.coeff.0)
+            + i64::from(self.sample2) * i64::from(self.coeff.1))
+            >> 8;
+        let sample = (predicted + signed * delta).clamp(i16::MIN as i64, i16::MAX as i64) as i32;
+        self.sample2 = self.sample1;
+        self.sample1 = sample;
+        self.delta = ((i64::from(MS_ADAPTATION_TABLE[nibble as usize]) * delta) >> 8)
+            .clamp(MS_MIN_DELTA, MS_MAX_DELTA) as i32;
+        sample as i16
+    }
+}
+
+/// 读取小端 i16
+fn read_i16(data: &[u8], pos: usize) -> i32 {
+    i16::from_le_bytes([data[pos], data[pos + 1]]) as i32
+}
+
+/// 解码一个 IMA ADPCM 块, 交错写入 `out`
+fn decode_ima_block(block: &[u8], channels: usize, out: &mut Vec<i16>) -> TaoResult<()> {
+    let nb_samples = samples_per_block(CodecId::AdpcmImaWav, block.len() as u32, channels as u32);
+    if nb_samples == 0 {
+        return Err(TaoError::InvalidData(format!(
+            "IMA ADPCM 块过短: {} 字节, {} 声道",
+            block.len(),
+            channels
+        )));
+    }
+
+    let mut state = Vec::with_capacity(channels);
+    for ch in 0..channels {
+        let pos = ch * IMA_HEADER_SIZE;
+        let step_index = block[pos + 2] as i32;
+        if step_index > 88 {
+            return Err(TaoError::InvalidData(format!(
+                "IMA ADPCM 步长索引越界: {}",
+                step_index
+            )));
+        }
+        state.push(ImaChannel {
+            predictor: read_i16(block, pos),
+            step_index,
+        });
+    }
+
+    let base = out.len();
+    out.resize(base + nb_samples as usize * channels, 0);
+    for (ch, st) in state.iter().enumerate() {
+        out[base + ch] = st.predictor as i16;
+    }
+
+    // 每组: 各声道依次 4 字节, 每声道 8 个样本
+    let data = &block[IMA_HEADER_SIZE * channels..];
+    let groups = (nb_samples as usize - 1) / 8;
+    for group in 0..groups {
+        for (ch, st) in state.iter_mut().enumerate() {
+            let chunk = &data[(group * channels + ch) * 4..][..4];
+            for (i, &byte) in chunk.iter().enumerate() {
+                let sample_idx = 1 + group * 8 + i * 2;
+                out[base + sample_idx * channels + ch] = st.expand(byte & 0x0F);
+                out[base + (sample_idx + 1) * channels + ch] = st.expand(byte >> 4);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 解码一个 MS ADPCM 块, 交错写入 `out`
+fn decode_ms_block(block: &[u8], channels: usize, out: &mut Vec<i16>) -> TaoResult<()> {
+    let nb_samples = samples_per_block(CodecId::AdpcmMs, block.len() as u32, channels as u32);
+    if nb_samples == 0 {
+        return Err(TaoError::InvalidData(format!(
+            "MS ADPCM 块过短: {} 字节, {} 声道",
+            block.len(),
+            channels
+        )));
+    }
+
+    let mut state = Vec::with_capacity(channels);
+    for ch in 0..channels {
+        let coeff_idx = block[ch] as usize;
+        let coeff = *MS_COEFFS.get(coeff_idx).ok_or_else(|| {
+            TaoError::InvalidData(format!("MS ADPCM 系数索引越界: {}", coeff_idx))
+        })?;
+        let delta = read_i16(block, channels + ch * 2);
+        if delta <= 0 {
+            return Err(TaoError::InvalidData(format!(
+                "MS ADPCM 初始 delta 无效: {}",
+                delta
+            )));
+        }
+        state.push(MsChannel {
+            coeff,
+            delta,
+            sample1: read_i16(block, channels * 3 + ch * 2),
+            sample2: read_i16(block, channels * 5 + ch * 2),
+        });
+    }
+
+    let base = out.len();
+    out.resize(base + nb_samples as usize * channels, 0);
+    for (ch, st) in state.iter().enumerate() {
+        out[base + ch] = st.sample2 as i16;
+        out[base + channels + ch] = st.sample1 as i16;
+    }
+
+    // nibble 按声道交错, 每字节高 4 位在前
+    let data = &block[MS_HEADER_SIZE * channels..];
+    let total = (nb_samples as usize - 2) * channels;
+    for n in 0..total {
+        let byte = data[n / 2];
+        let nibble = if n % 2 == 0 { byte >> 4 } else { byte & 0x0F };
+        let ch = n % channels;
+        out[base + 2 * channels + n] = state[ch].expand(nibble);
+    }
+    Ok(())
+}
+
+/// ADPCM 音频解码器
+pub struct AdpcmDecoder {
+    /// 编解码器 ID (决定块格式)
+    codec_id: CodecId,
+    /// 采样率
+    sample_rate: u32,
+    /// 声道布局
+    channel_layout: ChannelLayout,
+    /// 块大小 (字节), 0 表示每个数据包为一个块
+    block_align: u32,
+    /// 输出缓冲池, 未指定时每帧单独分配
+    pool: Option<FramePool>,
+    /// 已解码帧缓冲
+    output_frame: Option<Frame>,
+    /// 是否已打开
+    opened: bool,
+    /// 是否已收到刷新信号
+    flushing: bool,
+}
+
+impl AdpcmDecoder {
+    /// 创建指定 ADPCM 变体的解码器
+    fn create(codec_id: CodecId) -> TaoResult<Box<dyn Decoder>> {
+        Ok(Box::new(Self {
+            codec_id,
+            sample_rate: 0,
+            channel_layout: ChannelLayout::MONO,
+            block_align: 0,
+            pool: None,
+            output_frame: None,
+            opened: false,
+            flushing: false,
+        }))
+    }
+
+    pub fn new_ima_wav() -> TaoResult<Box<dyn Decoder>> {
+        Self::create(CodecId::AdpcmImaWav)
+    }
+
+    pub fn new_ms() -> TaoResult<Box<dyn Decoder>> {
+        Self::create(CodecId::AdpcmMs)
+    }
+
+    /// 逐块解码数据包, 末尾不足一块的部分按其实际长度解码
+    fn decode_packet(&self, data: &[u8]) -> TaoResult<Vec<i16>> {
+        let channels = self.channel_layout.channels as usize;
+        let block_size = if self.block_align > 0 {
+            self.block_align as usize
+        } else {
+            data.len()
+        };
+        let mut samples = Vec::new();
+        for block in data.chunks(block_size) {
+            match self.codec_id {
+                CodecId::AdpcmImaWav => decode_ima_block(block, channels, &mut samples)?,
+                _ => decode_ms_block(block, channels, &mut samples)?,
+            }
+        }
+        Ok(samples)
+    }
+}
+
+impl Decoder for AdpcmDecoder {
+    fn codec_id(&self) -> CodecId {
+        self.codec_id
+    }
+
+    fn name(&self) -> &str {
+        self.codec_id.name()
+    }
+
+    fn codec_capabilities(&self) -> CodecCapabilities {
+        CodecCapabilities::CAPS_INTRA_ONLY
+    }
+
+    fn open(&mut self, params: &CodecParameters) -> TaoResult<()> {
+        let audio = match &params.params {
+            CodecParamsType::Audio(a) => a,
+            _ => {
+                return Err(TaoError::InvalidArgument("ADPCM 解码器需要音频参数".into()));
+            }
+        };
+
+        if audio.sample_rate == 0 {
+            return Err(TaoError::InvalidArgument("采样率不能为 0".into()));
+        }
+        if audio.channel_layout.channels == 0 {
+            return Err(TaoError::InvalidArgument("声道数不能为 0".into()));
+        }
+        if audio.block_align > 0
+            && samples_per_block(
+                self.codec_id,
+                audio.block_align,
+                audio.channel_layout.channels,
+            ) == 0
+        {
+            return Err(TaoError::InvalidArgument(format!(
+                "block_align {} 不足以容纳 {} 声道块头",
+                audio.block_align, audio.channel_layout.channels
+            )));
+        }
+
+        self.sample_rate = audio.sample_rate;
+        self.channel_layout = audio.channel_layout;
+        self.block_align = audio.block_align;
+        self.output_frame = None;
+        self.opened = true;
+        self.flushing = false;
+
+        debug!(
+            "打开 {} 解码器: {} Hz, {} 声道, block_align={}",
+            self.name(),
+            self.sample_rate,
+            self.channel_layout.channels,
+            self.block_align,
+        );
+        Ok(())
+    }
+
+    fn set_frame_pool(&mut self, pool: &FramePool) {
+        self.pool = Some(pool.clone());
+    }
+
+    fn send_packet(&mut self, packet: &Packet) -> TaoResult<()> {
+        if !self.opened {
+            return Err(TaoError::Codec("解码器未打开, 请先调用 open()".into()));
+        }
+        if self.output_frame.is_some() {
+            return Err(TaoError::NeedMoreData);
+        }
+
+        // 空包 = flush
+        if packet.is_empty() {
+            self.flushing = true;
+            return Ok(());
+        }
+
+        let samples = self.decode_packet(&packet.data)?;
+        let channels = self.channel_layout.channels as usize;
+        let nb_samples = (samples.len() / channels) as u32;
+
+        let mut frame = AudioFrame::new(
+            nb_samples,
+            self.sample_rate,
+            SampleFormat::S16,
+            self.channel_layout,
+        );
+        frame.pts = packet.pts;
+        frame.time_base = packet.time_base;
+        frame.duration = packet.duration;
+
+        let output_size = samples.len() * 2;
+        let mut bytes = match &self.pool {
+            Some(pool) => pool.take_vec(output_size),
+            None => Vec::with_capacity(output_size),
+        };
+        for sample in &samples {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        frame.data[0] = match &self.pool {
+            Some(pool) => pool.wrap(bytes),
+            None => bytes.into(),
+        };
+
+        self.output_frame = Some(Frame::Audio(frame));
+        Ok(())
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        if let Some(frame) = self.output_frame.take() {
+            return Ok(frame);
+        }
+        if self.flushing {
+            return Err(TaoError::Eof);
+        }
+        Err(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) {
+        self.output_frame = None;
+        self.flushing = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec_parameters::AudioCodecParams;
+    use bytes::Bytes;
+
+    fn make_audio_params(codec_id: CodecId, channels: u32, block_align: u32) -> CodecParameters {
+        CodecParameters {
+            codec_id,
+            extra_data: Vec::new(),
+            bit_rate: 0,
+            params: CodecParamsType::Audio(AudioCodecParams {
+                sample_rate: 22050,
+                channel_layout: ChannelLayout::from_channels(channels),
+                sample_format: SampleFormat::S16,
+                frame_size: 0,
+                block_align,
+            }),
+        }
+    }
+
+    fn decode_s16(dec: &mut Box<dyn Decoder>, data: Vec<u8>) -> (u32, Vec<i16>) {
+        dec.send_packet(&Packet::from_data(Bytes::from(data)))
+            .unwrap();
+        match dec.receive_frame().unwrap() {
+            Frame::Audio(af) => {
+                assert_eq!(af.sample_format, SampleFormat::S16);
+                let samples = af.data[0]
+                    .chunks_exact(2)
+                    .map(|c| i16::from_le_bytes([c[0], c[1]]))
+                    .collect();
+                (af.nb_samples, samples)
+            }
+            _ => panic!("期望音频帧"),
+        }
+    }
+
+    /// 单声道 IMA 块: predictor=100, step_index=10, 4 字节残差
+    fn ima_mono_block() -> Vec<u8> {
+        let mut block = Vec::new();
+        block.extend_from_slice(&100i16.to_le_bytes());
+        block.extend_from_slice(&[10, 0]);
+        block.extend_from_slice(&[0x77, 0x77, 0x1F, 0x80]);
+        block
+    }
+
+    const IMA_MONO_EXPECTED: [i16; 9] = [100, 134, 210, 375, 730, -35, 293, 392, 302];
+
+    #[test]
+    fn test_samples_per_block() {
+        assert_eq!(samples_per_block(CodecId::AdpcmImaWav, 1024, 1), 2041);
+        assert_eq!(samples_per_block(CodecId::AdpcmImaWav, 2048, 2), 2041);
+        assert_eq!(samples_per_block(CodecId::AdpcmMs, 1024, 2), 1012);
+        assert_eq!(samples_per_block(CodecId::AdpcmMs, 256, 1), 500);
+        assert_eq!(
+            samples_per_block(CodecId::AdpcmMs, 6, 1),
+            0,
+            "不足块头应返回 0"
+        );
+        assert_eq!(samples_per_block(CodecId::PcmS16le, 1024, 1), 0);
+    }
+
+    #[test]
+    fn test_ima_decode_known_block() {
+        let mut dec = AdpcmDecoder::new_ima_wav().unwrap();
+        dec.open(&make_audio_params(CodecId::AdpcmImaWav, 1, 8))
+            .unwrap();
+        let (nb_samples, samples) = decode_s16(&mut dec, ima_mono_block());
+        assert_eq!(nb_samples, 9);
+        assert_eq!(samples, IMA_MONO_EXPECTED);
+    }
+
+    #[test]
+    fn test_ima_stereo_interleave() {
+        let mut block = Vec::new();
+        block.extend_from_slice(&100i16.to_le_bytes());
+        block.extend_from_slice(&[10, 0]);
+        block.extend_from_slice(&[0, 0, 0, 0]);
+        block.extend_from_slice(&[0x77, 0x77, 0x1F, 0x80]);
+        block.extend_from_slice(&[0, 0, 0, 0]);
+
+        let mut dec = AdpcmDecoder::new_ima_wav().unwrap();
+        dec.open(&make_audio_params(CodecId::AdpcmImaWav, 2, 16))
+            .unwrap();
+        let (nb_samples, samples) = decode_s16(&mut dec, block);
+        assert_eq!(nb_samples, 9);
+        let left: Vec<i16> = samples.iter().step_by(2).copied().collect();
+        let right: Vec<i16> = samples.iter().skip(1).step_by(2).copied().collect();
+        assert_eq!(left, IMA_MONO_EXPECTED);
+        assert_eq!(right, vec![0; 9], "步长 7 的零残差应保持 0");
+    }
+
+    #[test]
+    fn test_ima_packet_with_multiple_blocks() {
+        let mut data = ima_mono_block();
+        data.extend_from_slice(&ima_mono_block());
+
+        let mut dec = AdpcmDecoder::new_ima_wav().unwrap();
+        dec.open(&make_audio_params(CodecId::AdpcmImaWav, 1, 8))
+            .unwrap();
+        let (nb_samples, samples) = decode_s16(&mut dec, data);
+        assert_eq!(nb_samples, 18, "每块按 block_align 独立解码");
+        assert_eq!(samples[..9], IMA_MONO_EXPECTED);
+        assert_eq!(samples[9..], IMA_MONO_EXPECTED);
+    }
+
+    #[test]
+    fn test_ms_decode_known_block() {
+        // 系数索引 1 (512, -256), delta=16, sample1=20, sample2=10
+        let mut block = vec![1];
+        block.extend_from_slice(&16i16.to_le_bytes());
+        block.extend_from_slice(&20i16.to_le_bytes());
+        block.extend_from_slice(&10i16.to_le_bytes());
+        block.extend_from_slice(&[0x12, 0x7F, 0x88, 0xF0]);
+
+        let mut dec = AdpcmDecoder::new_ms().unwrap();
+        dec.open(&make_audio_params(CodecId::AdpcmMs, 1, 11))
+            .unwrap();
+        let (nb_samples, samples) = decode_s16(&mut dec, block);
+        assert_eq!(nb_samples, 10);
+        assert_eq!(
+            samples,
+            vec![10, 20, 46, 104, 274, 406, 266, -690, -1952, -3214]
+        );
+    }
+
+    #[test]
+    fn test_ms_huge_delta_does_not_overflow() {
+        // 初始 delta 取 i16 最大值, 残差 8 使 delta 每次乘以 3, 未限幅时很快溢出 i32
+        let mut block = vec![0];
+        block.extend_from_slice(&i16::MAX.to_le_bytes());
+        block.extend_from_slice(&0i16.to_le_bytes());
+        block.extend_from_slice(&0i16.to_le_bytes());
+        block.extend_from_slice(&[0x88; 64]);
+
+        let mut dec = AdpcmDecoder::new_ms().unwrap();
+        dec.open(&make_audio_params(CodecId::AdpcmMs, 1, 71))
+            .unwrap();
+        let (nb_samples, samples) = decode_s16(&mut dec, block);
+        assert_eq!(nb_samples, 130);
+        assert!(
+            samples[2..].iter().all(|&s| s == i16::MIN),
+            "负向最大残差应饱和到 i16::MIN"
+        );
+    }
+
+    #[test]
+    fn test_ms_rejects_non_positive_initial_delta() {
+        for delta in [0i16, -5] {
+            let mut block = vec![0];
+            block.extend_from_slice(&delta.to_le_bytes());
+            block.extend_from_slice(&[0u8; 4 + 4]);
+            let mut dec = AdpcmDecoder::new_ms().unwrap();
+            dec.open(&make_audio_params(CodecId::AdpcmMs, 1, 0))
+                .unwrap();
+            let err = dec
+                .send_packet(&Packet::from_data(Bytes::from(block)))
+                .unwrap_err();
+            assert!(
+                matches!(err, TaoError::InvalidData(_)),
+                "初始 delta {delta} 应被拒绝"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ms_invalid_coeff_index() {
+        let mut block = vec![7];
+        block.extend_from_slice(&[0u8; 6 + 4]);
+        let mut dec = AdpcmDecoder::new_ms().unwrap();
+        dec.open(&make_audio_params(CodecId::AdpcmMs, 1, 0))
+            .unwrap();
+        let err = dec
+            .send_packet(&Packet::from_data(Bytes::from(block)))
+            .unwrap_err();
+        assert!(matches!(err, TaoError::InvalidData(_)));
+    }
+
+    #[test]
+    fn test_open_rejects_too_small_block_align() {
+        let mut dec = AdpcmDecoder::new_ima_wav().unwrap();
+        let err = dec
+            .open(&make_audio_params(CodecId::AdpcmImaWav, 2, 4))
+            .unwrap_err();
+        assert!(matches!(err, TaoError::InvalidArgument(_)));
+    }
+}
@@ -35,10 +35,12 @@ use tao_core::crc;
 use tao_core::{ChannelLayout, SampleFormat, TaoError, TaoResult};
 use tracing::debug;
 
+use crate::capabilities::CodecCapabilities;
 use crate::codec_id::CodecId;
 use crate::codec_parameters::{CodecParameters, CodecParamsType};
 use crate::decoder::Decoder;
 use crate::frame::{AudioFrame, Frame};
+use crate::frame_pool::{self, FramePool};
 use crate::packet::Packet;
 
 /// FLAC 解码器
@@ -53,6 +55,8 @@ pub struct FlacDecoder {
     channel_layout: ChannelLayout,
     /// 输出帧缓冲
     output_frame: Option<Frame>,
+    /// 输出缓冲池, 未指定时每帧单独分配
+    pool: Option<FramePool>,
     /// 是否已打开
     opened: bool,
     /// 是否已收到刷新信号
@@ -111,6 +115,7 @@ impl FlacDecoder {
             bits_per_sample: 0,
             channel_layout: ChannelLayout::MONO,
             output_frame: None,
+            pool: None,
             opened: false,
             flushing: false,
             max_block_size: 0,
@@ -583,7 +588,10 @@ impl FlacDecoder {
             32
         };
         let bytes_per_sample = output_bps / 8;
-        let mut output = Vec::with_capacity(block_size as usize * channels * bytes_per_sample);
+        let mut output = frame_pool::take_vec_or_alloc(
+            self.pool.as_ref(),
+            block_size as usize * channels * bytes_per_sample,
+        );
 
         for i in 0..block_size as usize {
             for subframe in subframes {
@@ -620,6 +628,10 @@ impl Decoder for FlacDecoder {
         "flac"
     }
 
+    fn codec_capabilities(&self) -> CodecCapabilities {
+        CodecCapabilities::CAPS_LOSSLESS
+    }
+
     fn open(&mut self, params: &CodecParameters) -> TaoResult<()> {
         let audio = match &params.params {
             CodecParamsType::Audio(a) => a,
@@ -666,6 +678,10 @@ impl Decoder for FlacDecoder {
         Ok(())
     }
 
+    fn set_frame_pool(&mut self, pool: &FramePool) {
+        self.pool = Some(pool.clone());
+    }
+
     fn send_packet(&mut self, packet: &Packet) -> TaoResult<()> {
         if !self.opened {
             return Err(TaoError::Codec("解码器未打开, 请先调用 open()".into()));
@@ -706,7 +722,7 @@ impl Decoder for FlacDecoder {
 
         // 转换为交错字节格式
         let data = self.samples_to_bytes(&subframes, header.block_size, header.bits_per_sample);
-        frame.data[0] = data;
+        frame.data[0] = frame_pool::wrap_in(self.pool.as_ref(), data);
 
         self.output_frame = Some(Frame::Audio(frame));
         Ok(())
@@ -774,6 +790,7 @@ mod tests {
                 channel_layout: ChannelLayout::from_channels(channels),
                 sample_format,
                 frame_size: max_block_size,
+                block_align: 0,
             }),
         }
     }
@@ -939,6 +956,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_decode_reuses_pool_buffers() {
+        let params = make_flac_params(44100, 1, 16, 4096);
+        let pool = FramePool::new(2);
+        let mut dec = FlacDecoder::create().unwrap();
+        dec.set_frame_pool(&pool);
+        dec.open(&params).unwrap();
+
+        let frame_data = make_constant_flac_frame(256, 44100, 1, 16);
+        let mut decode = || {
+            let pkt = Packet::from_data(bytes::Bytes::from(frame_data.clone()));
+            dec.send_packet(&pkt).unwrap();
+            match dec.receive_frame().unwrap() {
+                Frame::Audio(af) => af,
+                _ => panic!("期望音频帧"),
+            }
+        };
+        let first = decode();
+        let ptr = first.data[0].as_ptr();
+        drop(first);
+        assert_eq!(pool.free_count(), 1, "释放的帧缓冲应归还池中");
+
+        let second = decode();
+        assert_eq!(second.data[0].as_ptr(), ptr, "后续帧应复用归还的缓冲");
+        assert_eq!(second.data[0].len(), 256 * 2);
+        assert_eq!(pool.allocation_count(), 1);
+    }
+
     #[test]
     fn test_not_open_error() {
         let mut dec = FlacDecoder::create().unwrap();
@@ -633,6 +633,17 @@ pub(super) fn read_se(br: &mut BitReader) -> TaoResult<i32> {
     if code & 1 == 0 { Ok(-value) } else { Ok(value) }
 }
 
+/// CAVLC mvd 绝对值上界, 与 CABAC 后缀 k<=24 可表示的范围一致.
+const MVD_ABS_LIMIT: u32 = 1 << 25;
+
+/// 读取 CAVLC mvd (se(v)); 读取失败或超出上界时按 0 处理, 避免与预测值累加溢出.
+pub(super) fn read_mvd(br: &mut BitReader) -> i32 {
+    match read_se(br) {
+        Ok(mvd) if mvd.unsigned_abs() < MVD_ABS_LIMIT => mvd,
+        _ => 0,
+    }
+}
+
 /// 读取截断 Exp-Golomb (te(v), H.264 9.1.2).
 pub(super) fn read_te(br: &mut BitReader, max_value: u32) -> TaoResult<u32> {
     if max_value == 0 {
@@ -665,13 +676,21 @@ pub(super) fn chroma_qp_from_luma_with_offset(qp: i32, offset: i32) -> i32 {
     CHROMA_QP_TABLE[qpc as usize]
 }
 
-/// 从对齐缓冲区拷贝到紧凑平面
-pub(super) fn copy_plane(src: &[u8], src_stride: usize, w: usize, h: usize) -> Vec<u8> {
+/// 从对齐缓冲区拷贝到紧凑平面 (复用 dst 的内存)
+pub(super) fn copy_plane_into(
+    dst: &mut Vec<u8>,
+    src: &[u8],
+    src_stride: usize,
+    w: usize,
+    h: usize,
+) {
     let total = w.saturating_mul(h);
+    dst.clear();
     if src_stride == w && src.len() >= total {
-        return src[..total].to_vec();
+        dst.extend_from_slice(&src[..total]);
+        return;
     }
-    let mut dst = vec![0u8; w * h];
+    dst.resize(total, 0);
     for y in 0..h {
         let src_off = y * src_stride;
         let dst_off = y * w;
@@ -680,7 +699,6 @@ pub(super) fn copy_plane(src: &[u8], src_stride: usize, w: usize, h: usize) -> V
             dst[dst_off..dst_off + copy_len].copy_from_slice(&src[src_off..src_off + copy_len]);
         }
     }
-    dst
 }
 
 /// Chroma QP 映射表 (H.264 Table 8-15)
@@ -20,7 +20,7 @@ impl H264Decoder {
                         );
                         continue;
                     }
-                    if let Err(err) = parse_sps(&nalu.rbsp()) {
+                    if let Err(err) = parse_sps(nalu.rbsp()) {
                         warn!("H264: avcC SPS 解析失败, index={}, err={}", idx, err);
                     } else {
                         seen_valid_sps = true;
@@ -5,10 +5,23 @@
 //! - 宏块边界按 `intra/cbp/ref_idx/mv` 估算强弱(`bs=4/2/1/0`).
 //! - 亮度 4x4 内部边界按 `cbf/ref_idx/mv` 估算强弱(`bs=2/1/0`).
 //! - 弱滤波使用 `tc0` 约束, 强滤波使用更强的 `p0/q0` 更新.
+//! - 每条边使用 q0 所在宏块所属 slice 的 `disable_deblocking_filter_idc` 与
+//!   alpha/beta 偏移: idc=1 跳过该宏块全部边界, idc=2 不跨 slice 边界滤波.
 
 use super::common::chroma_qp_from_luma_with_offset;
 
+/// 单个 slice 的去块滤波控制参数.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct SliceFilterParams {
+    pub(super) disable_deblocking_filter_idc: u32,
+    pub(super) alpha_offset_div2: i32,
+    pub(super) beta_offset_div2: i32,
+}
+
 /// 去块滤波输入参数.
+///
+/// `disable_deblocking_filter_idc` 与 alpha/beta 偏移为帧级缺省值,
+/// 用于 `slice_filter_params` 中查不到所属 slice 的宏块.
 #[derive(Clone, Copy, Debug)]
 pub(super) struct DeblockSliceParams<'a> {
     pub(super) stride_y: usize,
@@ -26,6 +39,8 @@ pub(super) struct DeblockSliceParams<'a> {
     pub(super) mb_types: Option<&'a [u8]>,
     pub(super) mb_cbp: Option<&'a [u8]>,
     pub(super) mb_slice_first_mb: Option<&'a [u32]>,
+    /// 各 slice (以 first_mb 标识) 的去块滤波参数.
+    pub(super) slice_filter_params: Option<&'a [(u32, SliceFilterParams)]>,
     pub(super) mv_l0_x: Option<&'a [i16]>,
     pub(super) mv_l0_y: Option<&'a [i16]>,
     pub(super) ref_idx_l0: Option<&'a [i8]>,
@@ -68,6 +83,7 @@ pub(super) fn apply_deblock_yuv420_with_slice_params(
         mb_types,
         mb_cbp,
         mb_slice_first_mb,
+        slice_filter_params,
         mv_l0_x,
         mv_l0_y,
         ref_idx_l0,
@@ -89,6 +105,24 @@ pub(super) fn apply_deblock_yuv420_with_slice_params(
     if width == 0 || height == 0 {
         return;
     }
+    let frame_filter = SliceFilterParams {
+        disable_deblocking_filter_idc,
+        alpha_offset_div2,
+        beta_offset_div2,
+    };
+    let mb_filter_params = build_mb_filter_params(
+        mb_width.saturating_mul(mb_height),
+        mb_slice_first_mb,
+        slice_filter_params,
+        frame_filter,
+    );
+    let all_disabled = match &mb_filter_params {
+        Some(per_mb) => per_mb.iter().all(|p| p.disable_deblocking_filter_idc == 1),
+        None => disable_deblocking_filter_idc == 1,
+    };
+    if all_disabled {
+        return;
+    }
     let luma_alpha_idx = alpha_index(slice_qp, alpha_offset_div2);
     let luma_alpha = alpha_threshold(slice_qp, alpha_offset_div2);
     let luma_beta = beta_threshold(slice_qp, beta_offset_div2);
@@ -116,6 +150,7 @@ pub(super) fn apply_deblock_yuv420_with_slice_params(
                     mb_types: types,
                     mb_cbp: cbp,
                     mb_slice_first_mb,
+                    mb_filter_params: mb_filter_params.as_deref(),
                     disable_cross_slice_boundary_filter: disable_deblocking_filter_idc == 2,
                     mv_l0_x,
                     mv_l0_y,
@@ -182,6 +217,29 @@ pub(super) fn apply_deblock_yuv420_with_slice_params(
     );
 }
 
+/// 按宏块所属 slice 展开去块滤波参数, 查不到所属 slice 的宏块使用帧级缺省值.
+fn build_mb_filter_params(
+    total_mbs: usize,
+    mb_slice_first_mb: Option<&[u32]>,
+    slice_filter_params: Option<&[(u32, SliceFilterParams)]>,
+    frame_filter: SliceFilterParams,
+) -> Option<Vec<SliceFilterParams>> {
+    let slice_map = mb_slice_first_mb.filter(|map| total_mbs > 0 && map.len() >= total_mbs)?;
+    let slices = slice_filter_params.filter(|slices| !slices.is_empty())?;
+    Some(
+        slice_map[..total_mbs]
+            .iter()
+            .map(|first_mb| {
+                slices
+                    .iter()
+                    .find(|(id, _)| id == first_mb)
+                    .map(|(_, params)| *params)
+                    .unwrap_or(frame_filter)
+            })
+            .collect(),
+    )
+}
+
 #[derive(Clone, Copy)]
 struct DeblockMbContext<'a> {
     mb_width: usize,
@@ -189,6 +247,8 @@ struct DeblockMbContext<'a> {
     mb_types: &'a [u8],
     mb_cbp: &'a [u8],
     mb_slice_first_mb: Option<&'a [u32]>,
+    /// 每个宏块所属 slice 的去块参数, 为 None 时使用下方的帧级参数.
+    mb_filter_params: Option<&'a [SliceFilterParams]>,
     disable_cross_slice_boundary_filter: bool,
     mv_l0_x: Option<&'a [i16]>,
     mv_l0_y: Option<&'a [i16]>,
@@ -211,6 +271,23 @@ struct DeblockMbContext<'a> {
     beta_offset_div2: i32,
 }
 
+impl DeblockMbContext<'_> {
+    /// 宏块所属 slice 的去块参数.
+    fn filter_params(&self, mb_idx: usize) -> SliceFilterParams {
+        self.mb_filter_params
+            .and_then(|per_mb| per_mb.get(mb_idx).copied())
+            .unwrap_or(SliceFilterParams {
+                disable_deblocking_filter_idc: if self.disable_cross_slice_boundary_filter {
+                    2
+                } else {
+                    0
+                },
+                alpha_offset_div2: self.alpha_offset_div2,
+                beta_offset_div2: self.beta_offset_div2,
+            })
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn apply_adaptive_deblock_plane(
     plane: &mut [u8],
@@ -235,6 +312,14 @@ fn apply_adaptive_deblock_plane(
 
     for mb_row in 0..mb_rows {
         for mb_col in 0..mb_cols {
+            // idc=1 的 slice 内宏块不做任何边界滤波
+            if let Some(ctx) = mb_ctx {
+                let disabled = mb_index(ctx.mb_width, ctx.mb_height, mb_col, mb_row)
+                    .is_some_and(|idx| ctx.filter_params(idx).disable_deblocking_filter_idc == 1);
+                if disabled {
+                    continue;
+                }
+            }
             let mb_x0 = mb_col * mb_step;
             let mb_y0 = mb_row * mb_step;
             let mb_x_end = (mb_x0 + mb_step).min(width);
@@ -402,9 +487,11 @@ fn edge_thresholds(
     } else {
         (qp_p + qp_q + 1) >> 1
     };
-    let ai = alpha_index(edge_qp, ctx.alpha_offset_div2);
-    let a = alpha_threshold(edge_qp, ctx.alpha_offset_div2);
-    let b = beta_threshold(edge_qp, ctx.beta_offset_div2);
+    // alpha/beta 偏移取 q0 所在宏块的 slice
+    let filter = ctx.filter_params(idx_q);
+    let ai = alpha_index(edge_qp, filter.alpha_offset_div2);
+    let a = alpha_threshold(edge_qp, filter.alpha_offset_div2);
+    let b = beta_threshold(edge_qp, filter.beta_offset_div2);
     (ai, a, b)
 }
 
@@ -857,7 +944,8 @@ fn cross_slice_boundary_is_disabled(
     idx_a: usize,
     idx_b: usize,
 ) -> bool {
-    if !ctx.disable_cross_slice_boundary_filter {
+    // 边界归属 q0 所在宏块 (idx_b), 按其 slice 的 idc 判断
+    if ctx.filter_params(idx_b).disable_deblocking_filter_idc != 2 {
         return false;
     }
     let Some(slice_map) = ctx.mb_slice_first_mb else {
@@ -1077,9 +1165,9 @@ const TC0_TABLE: [[u8; 3]; 52] = [
 #[cfg(test)]
 mod tests {
     use super::{
-        DeblockMbContext, DeblockSliceParams, alpha_threshold, apply_adaptive_deblock_plane,
-        apply_deblock_yuv420_with_slice_params, beta_threshold, boundary_strength_horizontal,
-        boundary_strength_vertical, filter_edge_with_bs,
+        DeblockMbContext, DeblockSliceParams, SliceFilterParams, alpha_threshold,
+        apply_adaptive_deblock_plane, apply_deblock_yuv420_with_slice_params, beta_threshold,
+        boundary_strength_horizontal, boundary_strength_vertical, filter_edge_with_bs,
     };
 
     #[test]
@@ -1163,6 +1251,7 @@ mod tests {
                 mb_types: None,
                 mb_cbp: None,
                 mb_slice_first_mb: None,
+                slice_filter_params: None,
                 mv_l0_x: None,
                 mv_l0_y: None,
                 ref_idx_l0: None,
@@ -1206,6 +1295,7 @@ mod tests {
                 mb_types: None,
                 mb_cbp: None,
                 mb_slice_first_mb: None,
+                slice_filter_params: None,
                 mv_l0_x: None,
                 mv_l0_y: None,
                 ref_idx_l0: None,
@@ -1289,6 +1379,7 @@ mod tests {
                 mb_types: None,
                 mb_cbp: None,
                 mb_slice_first_mb: None,
+                slice_filter_params: None,
                 mv_l0_x: None,
                 mv_l0_y: None,
                 ref_idx_l0: None,
@@ -1363,6 +1454,7 @@ mod tests {
                 mb_types: None,
                 mb_cbp: None,
                 mb_slice_first_mb: None,
+                slice_filter_params: None,
                 mv_l0_x: None,
                 mv_l0_y: None,
                 ref_idx_l0: None,
@@ -1398,6 +1490,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: None,
             mv_l0_y: None,
@@ -1436,6 +1529,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: Some(&mv_l0_x),
             mv_l0_y: Some(&mv_l0_y),
@@ -1474,6 +1568,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: Some(&mv_l0_x),
             mv_l0_y: Some(&mv_l0_y),
@@ -1513,6 +1608,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: Some(&mb_slice_first_mb),
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: Some(&mv_l0_x),
             mv_l0_y: Some(&mv_l0_y),
@@ -1540,6 +1636,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: Some(&mb_slice_first_mb),
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: true,
             mv_l0_x: Some(&mv_l0_x),
             mv_l0_y: Some(&mv_l0_y),
@@ -1589,6 +1686,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: Some(&mv_l0_x),
             mv_l0_y: Some(&mv_l0_y),
@@ -1635,6 +1733,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: Some(&mv_l0_x),
             mv_l0_y: Some(&mv_l0_y),
@@ -1683,6 +1782,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: Some(&mv_l0_x),
             mv_l0_y: Some(&mv_l0_y),
@@ -1726,6 +1826,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: None,
             mv_l0_y: None,
@@ -1767,6 +1868,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: None,
             mv_l0_y: None,
@@ -1801,6 +1903,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: None,
             mv_l0_y: None,
@@ -1842,6 +1945,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: Some(&mv_l0_x),
             mv_l0_y: Some(&mv_l0_y),
@@ -1885,6 +1989,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: None,
             mv_l0_y: None,
@@ -1924,6 +2029,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: None,
             mv_l0_y: None,
@@ -1974,6 +2080,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: None,
             mv_l0_y: None,
@@ -2018,6 +2125,7 @@ mod tests {
             mb_types: &mb_types,
             mb_cbp: &mb_cbp,
             mb_slice_first_mb: None,
+            mb_filter_params: None,
             disable_cross_slice_boundary_filter: false,
             mv_l0_x: None,
             mv_l0_y: None,
@@ -2090,4 +2198,119 @@ mod tests {
         assert!(plane[2] != 48, "色度强滤波应更新 q0");
         assert_eq!(plane[3], 48, "色度强滤波不应更新 q1");
     }
+
+    /// 构造左右两个帧内宏块 (32x16), 左右宏块分属 slice 0 与 slice 1, 在 x=16 处形成阶跃
+    fn deblock_two_slice_luma(step: u8, slices: &[(u32, SliceFilterParams)]) -> Vec<u8> {
+        let (width, height) = (32usize, 16usize);
+        let mut y = vec![40u8; width * height];
+        for row in y.chunks_exact_mut(width) {
+            row[16..].fill(40 + step);
+        }
+        let mut u = vec![128u8; (width / 2) * (height / 2)];
+        let mut v = u.clone();
+        let mb_types = [0u8, 0];
+        let mb_cbp = [0u8, 0];
+        let mb_slice_first_mb = [0u32, 1];
+        let mb_qp = [26i32, 26];
+        apply_deblock_yuv420_with_slice_params(
+            &mut y,
+            &mut u,
+            &mut v,
+            DeblockSliceParams {
+                stride_y: width,
+                stride_c: width / 2,
+                width,
+                height,
+                slice_qp: 26,
+                disable_deblocking_filter_idc: 0,
+                chroma_qp_index_offset: 0,
+                second_chroma_qp_index_offset: 0,
+                alpha_offset_div2: 0,
+                beta_offset_div2: 0,
+                mb_width: 2,
+                mb_height: 1,
+                mb_types: Some(&mb_types),
+                mb_cbp: Some(&mb_cbp),
+                mb_slice_first_mb: Some(&mb_slice_first_mb),
+                slice_filter_params: Some(slices),
+                mv_l0_x: None,
+                mv_l0_y: None,
+                ref_idx_l0: None,
+                ref_l0_poc: None,
+                mv_l1_x: None,
+                mv_l1_y: None,
+                ref_idx_l1: None,
+                ref_l1_poc: None,
+                cbf_luma: None,
+                mv_l0_x_4x4: None,
+                mv_l0_y_4x4: None,
+                ref_idx_l0_4x4: None,
+                mv_l1_x_4x4: None,
+                mv_l1_y_4x4: None,
+                ref_idx_l1_4x4: None,
+                mb_qp: Some(&mb_qp),
+                transform_8x8_flags: None,
+            },
+        );
+        y
+    }
+
+    fn slice_filter(idc: u32, alpha_offset_div2: i32) -> SliceFilterParams {
+        SliceFilterParams {
+            disable_deblocking_filter_idc: idc,
+            alpha_offset_div2,
+            beta_offset_div2: 0,
+        }
+    }
+
+    #[test]
+    fn test_apply_deblock_mixed_slice_idc_uses_q_mb_slice() {
+        // q0 所在宏块 (右侧) 属于 idc=1 的 slice: 边界保持不变
+        let y = deblock_two_slice_luma(8, &[(0, slice_filter(0, 0)), (1, slice_filter(1, 0))]);
+        assert_eq!(
+            (y[15], y[16]),
+            (40, 48),
+            "idc=1 的 slice 不应滤波其宏块左边界"
+        );
+
+        // 右侧宏块 idc=0, 即使左侧 slice 关闭滤波, 其左边界仍需滤波
+        let y = deblock_two_slice_luma(8, &[(0, slice_filter(1, 0)), (1, slice_filter(0, 0))]);
+        assert!(
+            y[15] > 40 && y[16] < 48,
+            "idc=0 的 slice 应滤波其宏块左边界"
+        );
+    }
+
+    #[test]
+    fn test_apply_deblock_idc2_per_slice_skips_only_slice_boundary() {
+        let y = deblock_two_slice_luma(8, &[(0, slice_filter(0, 0)), (1, slice_filter(2, 0))]);
+        assert_eq!(
+            (y[15], y[16]),
+            (40, 48),
+            "idc=2 的 slice 不应跨 slice 边界滤波"
+        );
+
+        let y = deblock_two_slice_luma(8, &[(0, slice_filter(2, 0)), (1, slice_filter(0, 0))]);
+        assert!(
+            y[15] > 40 && y[16] < 48,
+            "左侧 slice 的 idc=2 不应影响右侧 slice 宏块的边界"
+        );
+    }
+
+    #[test]
+    fn test_apply_deblock_alpha_offset_per_slice() {
+        // 阶跃 20 超过 qp=26 的 alpha(15), 仅当 q 宏块所属 slice 提高 alpha 偏移时才滤波
+        let y = deblock_two_slice_luma(20, &[(0, slice_filter(0, 3)), (1, slice_filter(0, 0))]);
+        assert_eq!(
+            (y[15], y[16]),
+            (40, 60),
+            "应使用 q 宏块所属 slice 的 alpha 偏移"
+        );
+
+        let y = deblock_two_slice_luma(20, &[(0, slice_filter(0, 0)), (1, slice_filter(0, 3))]);
+        assert!(
+            y[15] > 40 && y[16] < 60,
+            "q 宏块 slice 的 alpha 偏移应放宽门限"
+        );
+    }
 }
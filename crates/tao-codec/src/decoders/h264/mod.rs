@@ -23,6 +23,8 @@ mod parameter_sets;
 mod residual;
 mod sei;
 mod slice_decode;
+mod slice_group;
+mod slice_parallel;
 mod slice_parse;
 mod syntax;
 #[cfg(test)]
@@ -37,10 +39,12 @@ use tao_core::bitreader::BitReader;
 use tao_core::{PixelFormat, Rational, TaoError, TaoResult};
 use tracing::{debug, warn};
 
+use crate::capabilities::CodecCapabilities;
 use crate::codec_id::CodecId;
 use crate::codec_parameters::{CodecParameters, CodecParamsType};
-use crate::decoder::Decoder;
+use crate::decoder::{Decoder, DecoderOptions};
 use crate::frame::{Frame, PictureType, VideoFrame};
+use crate::frame_pool::{FrameBuf, FramePool};
 use crate::packet::Packet;
 use crate::parsers::h264::{
     NalUnit, NalUnitType, Sps, parse_avcc_config, parse_sps, split_annex_b, split_avcc,
@@ -78,6 +82,39 @@ struct Pps {
     scaling_list_4x4: Option<[[u8; 16]; 6]>,
     /// PPS 显式覆盖的 8x8 量化矩阵.
     scaling_list_8x8: Option<Vec<[u8; 64]>>,
+    /// slice group (FMO) 参数.
+    slice_groups: SliceGroupParams,
+}
+
+/// PPS 中的 slice group (FMO) 参数
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct SliceGroupParams {
+    num_slice_groups_minus1: u32,
+    slice_group_map_type: u32,
+    /// 类型 0: 各 slice group 的 run_length_minus1.
+    run_length_minus1: Vec<u32>,
+    /// 类型 2: 各前景矩形的左上角 map unit 地址.
+    top_left: Vec<u32>,
+    /// 类型 2: 各前景矩形的右下角 map unit 地址.
+    bottom_right: Vec<u32>,
+    /// 类型 3-5: slice_group_change_direction_flag.
+    change_direction_flag: bool,
+    /// 类型 3-5: slice_group_change_rate_minus1.
+    change_rate_minus1: u32,
+    /// 类型 6: 每个 map unit 的 slice_group_id.
+    slice_group_id: Vec<u32>,
+}
+
+impl SliceGroupParams {
+    /// 是否使用多个 slice group (FMO)
+    fn is_enabled(&self) -> bool {
+        self.num_slice_groups_minus1 > 0
+    }
+
+    /// slice header 是否携带 slice_group_change_cycle (类型 3-5)
+    fn has_change_cycle(&self) -> bool {
+        self.is_enabled() && (3..=5).contains(&self.slice_group_map_type)
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -97,6 +134,8 @@ struct SliceHeader {
     pps_id: u32,
     slice_type: u32,
     frame_num: u32,
+    /// field_pic_flag: 当前 slice 属于场图像.
+    field_pic: bool,
     slice_qp: i32,
     cabac_init_idc: u8,
     direct_spatial_mv_pred_flag: bool,
@@ -120,6 +159,8 @@ struct SliceHeader {
     disable_deblocking_filter_idc: u32,
     slice_alpha_c0_offset_div2: i32,
     slice_beta_offset_div2: i32,
+    /// slice group 类型 3-5 的 slice_group_change_cycle.
+    slice_group_change_cycle: u32,
     dec_ref_pic_marking: DecRefPicMarking,
 }
 
@@ -182,37 +223,38 @@ enum BMbType {
     Inter(u8),
 }
 
+/// 参考图像. 平面与运动数据均以 `Arc` 共享, 克隆解码器 (如 slice 并行工作实例) 时不复制.
 #[derive(Clone)]
 struct ReferencePicture {
     y: Arc<Vec<u8>>,
     u: Arc<Vec<u8>>,
     v: Arc<Vec<u8>>,
     /// 参考帧宏块级 list0 MV X (1/4 像素).
-    mv_l0_x: Vec<i16>,
+    mv_l0_x: Arc<Vec<i16>>,
     /// 参考帧宏块级 list0 MV Y (1/4 像素).
-    mv_l0_y: Vec<i16>,
+    mv_l0_y: Arc<Vec<i16>>,
     /// 参考帧宏块级 list0 ref_idx.
-    ref_idx_l0: Vec<i8>,
+    ref_idx_l0: Arc<Vec<i8>>,
     /// 参考帧宏块级 list1 MV X (1/4 像素).
-    mv_l1_x: Vec<i16>,
+    mv_l1_x: Arc<Vec<i16>>,
     /// 参考帧宏块级 list1 MV Y (1/4 像素).
-    mv_l1_y: Vec<i16>,
+    mv_l1_y: Arc<Vec<i16>>,
     /// 参考帧宏块级 list1 ref_idx.
-    ref_idx_l1: Vec<i8>,
+    ref_idx_l1: Arc<Vec<i8>>,
     /// 参考帧 4x4 级 list0 MV X (1/4 像素).
-    mv_l0_x_4x4: Vec<i16>,
+    mv_l0_x_4x4: Arc<Vec<i16>>,
     /// 参考帧 4x4 级 list0 MV Y (1/4 像素).
-    mv_l0_y_4x4: Vec<i16>,
+    mv_l0_y_4x4: Arc<Vec<i16>>,
     /// 参考帧 4x4 级 list0 ref_idx.
-    ref_idx_l0_4x4: Vec<i8>,
+    ref_idx_l0_4x4: Arc<Vec<i8>>,
     /// 参考帧 4x4 级 list1 MV X (1/4 像素).
-    mv_l1_x_4x4: Vec<i16>,
+    mv_l1_x_4x4: Arc<Vec<i16>>,
     /// 参考帧 4x4 级 list1 MV Y (1/4 像素).
-    mv_l1_y_4x4: Vec<i16>,
+    mv_l1_y_4x4: Arc<Vec<i16>>,
     /// 参考帧 4x4 级 list1 ref_idx.
-    ref_idx_l1_4x4: Vec<i8>,
+    ref_idx_l1_4x4: Arc<Vec<i8>>,
     /// 参考帧宏块级 mb_types (用于 col_zero_flag 判断 intra 状态).
-    mb_types: Vec<u8>,
+    mb_types: Arc<Vec<u8>>,
     /// 该参考帧解码时其 L0 参考列表中各参考帧的 POC (用于 temporal direct MapColToList0).
     ref_l0_poc: Vec<i32>,
     /// 该参考帧解码时其 L1 参考列表中各参考帧的 POC (用于 temporal direct MapColToList0).
@@ -240,6 +282,7 @@ struct RefPlanes {
     long_term_frame_idx: Option<u32>,
 }
 
+#[derive(Clone)]
 struct ReorderFrameEntry {
     frame: VideoFrame,
     poc: i32,
@@ -259,6 +302,7 @@ struct PendingFrameMeta {
 // ============================================================
 
 /// H.264 解码器
+#[derive(Clone)]
 pub struct H264Decoder {
     sps: Option<Sps>,
     pps: Option<Pps>,
@@ -355,6 +399,10 @@ pub struct H264Decoder {
     mvd_l1_y_4x4: Vec<i16>,
     /// 每个宏块所属 slice 的 first_mb 标识, 用于 idc=2 去块边界判断.
     mb_slice_first_mb: Vec<u32>,
+    /// 当前帧各 slice 的去块滤波参数, 以 slice 的 first_mb 标识.
+    slice_deblock_params: Vec<(u32, deblock::SliceFilterParams)>,
+    /// 当前 slice 的宏块到 slice group 映射, 未启用 FMO 时为空.
+    mb_slice_group_map: Arc<Vec<u8>>,
     /// 最近一次成功解析的 slice_type
     last_slice_type: u32,
     /// 最近一次成功解析的 frame_num.
@@ -422,6 +470,12 @@ pub struct H264Decoder {
     output_queue: VecDeque<Frame>,
     reorder_buffer: Vec<ReorderFrameEntry>,
     reorder_depth: usize,
+    /// 输出平面缓冲池, 容量随 DPB 重排深度调整
+    frame_pool: FramePool,
+    /// 输出缓冲池由调用方通过 `set_frame_pool` 提供, 此时不调整其容量
+    frame_pool_shared: bool,
+    /// 多 slice 图像的并行解码线程数, 1 表示顺序解码
+    decode_threads: usize,
     decode_order_counter: u64,
     pending_frame: Option<PendingFrameMeta>,
     opened: bool,
@@ -488,6 +542,8 @@ impl H264Decoder {
             mvd_l1_x_4x4: Vec::new(),
             mvd_l1_y_4x4: Vec::new(),
             mb_slice_first_mb: Vec::new(),
+            slice_deblock_params: Vec::new(),
+            mb_slice_group_map: Arc::new(Vec::new()),
             last_slice_type: 0,
             last_frame_num: 0,
             last_nal_ref_idc: 0,
@@ -523,6 +579,9 @@ impl H264Decoder {
             output_queue: VecDeque::new(),
             reorder_buffer: Vec::new(),
             reorder_depth: 2,
+            frame_pool: FramePool::new(Self::frame_pool_size(2)),
+            frame_pool_shared: false,
+            decode_threads: 1,
             decode_order_counter: 0,
             pending_frame: None,
             opened: false,
@@ -584,7 +643,7 @@ impl H264Decoder {
     /// 处理 SPS NAL 单元
     fn handle_sps(&mut self, nalu: &NalUnit) {
         let rbsp = nalu.rbsp();
-        match parse_sps(&rbsp) {
+        match parse_sps(rbsp) {
             Ok(sps) => {
                 if let Err(err) = Self::validate_sps_support(&sps) {
                     warn!("H264: 忽略不支持的 SPS, sps_id={}, err={}", sps.sps_id, err);
@@ -609,7 +668,7 @@ impl H264Decoder {
     /// 处理 PPS NAL 单元
     fn handle_pps(&mut self, nalu: &NalUnit) {
         let rbsp = nalu.rbsp();
-        match parameter_sets::parse_pps(&rbsp) {
+        match parameter_sets::parse_pps(rbsp) {
             Ok(pps) => {
                 debug!(
                     "H264: PPS id={} sps={} entropy={} qp={}",
@@ -637,7 +696,7 @@ impl H264Decoder {
     /// 处理 SEI NAL 单元
     fn handle_sei(&mut self, nalu: &NalUnit) {
         let rbsp = nalu.rbsp();
-        match sei::parse_sei_rbsp(&rbsp) {
+        match sei::parse_sei_rbsp(rbsp) {
             Ok(payloads) => {
                 for payload in &payloads {
                     if matches!(&payload.message, sei::SeiMessage::Unknown { .. }) {
@@ -704,7 +763,7 @@ impl H264Decoder {
     /// 仅解析 first_mb_in_slice, 用于判断是否进入新帧.
     fn parse_slice_first_mb(&self, nalu: &NalUnit) -> Option<u32> {
         let rbsp = nalu.rbsp();
-        let mut br = BitReader::new(&rbsp);
+        let mut br = BitReader::new(rbsp);
         read_ue(&mut br).ok()
     }
 
@@ -746,9 +805,22 @@ impl H264Decoder {
         self.mvd_l1_x_4x4.fill(0);
         self.mvd_l1_y_4x4.fill(0);
         self.mb_slice_first_mb.fill(u32::MAX);
+        self.slice_deblock_params.clear();
         self.prev_qp_delta_nz = false;
     }
 
+    /// 记录 slice 的去块滤波参数, 同一 first_mb 重复出现时覆盖.
+    fn record_slice_deblock_params(&mut self, first_mb: u32, params: deblock::SliceFilterParams) {
+        match self
+            .slice_deblock_params
+            .iter_mut()
+            .find(|(id, _)| *id == first_mb)
+        {
+            Some((_, slot)) => *slot = params,
+            None => self.slice_deblock_params.push((first_mb, params)),
+        }
+    }
+
     /// 记录宏块所属 slice 的 first_mb 标识, 用于 idc=2 边界过滤约束.
     fn mark_mb_slice_first_mb(&mut self, mb_idx: usize, first_mb: u32) {
         if let Some(slot) = self.mb_slice_first_mb.get_mut(mb_idx) {
@@ -947,6 +1019,15 @@ impl H264Decoder {
 
     fn refresh_reorder_depth(&mut self) {
         self.reorder_depth = Self::derive_reorder_depth_from_sps(self.sps.as_ref());
+        if !self.frame_pool_shared {
+            self.frame_pool
+                .set_max_free(Self::frame_pool_size(self.reorder_depth));
+        }
+    }
+
+    /// 输出缓冲池容量: 重排队列中的帧 + 输出队列与下游持有的帧, 每帧 3 个平面
+    fn frame_pool_size(reorder_depth: usize) -> usize {
+        (reorder_depth + 2) * 3
     }
 
     fn activate_sps(&mut self, sps_id: u32) {
@@ -1130,7 +1211,8 @@ impl H264Decoder {
             || old.weighted_bipred_idc != new.weighted_bipred_idc
             || old.redundant_pic_cnt_present != new.redundant_pic_cnt_present
             || old.scaling_list_4x4 != new.scaling_list_4x4
-            || old.scaling_list_8x8 != new.scaling_list_8x8;
+            || old.scaling_list_8x8 != new.scaling_list_8x8
+            || old.slice_groups != new.slice_groups;
         if need_runtime_only {
             ParameterSetRebuildAction::RuntimeOnly
         } else {
@@ -1170,6 +1252,13 @@ impl H264Decoder {
         self.mvd_overflow_error.take()
     }
 
+    /// 按缺失参考、ref_idx 越界、mvd 溢出的顺序取出首个待报告的解码错误.
+    fn take_decode_error(&mut self) -> Option<String> {
+        self.take_missing_reference_fallback_error()
+            .or_else(|| self.take_ref_idx_oob_error())
+            .or_else(|| self.take_mvd_overflow_error())
+    }
+
     #[cfg(test)]
     pub(super) fn set_dir_sub_mv_predictor_for_test(&mut self, use_8x4: bool, use_4x8: bool) {
         self.use_dir_sub_8x4 = use_8x4;
@@ -1190,6 +1279,19 @@ impl Decoder for H264Decoder {
         "h264"
     }
 
+    fn codec_capabilities(&self) -> CodecCapabilities {
+        CodecCapabilities::CAPS_DELAY | CodecCapabilities::CAPS_REORDER
+    }
+
+    fn set_frame_pool(&mut self, pool: &FramePool) {
+        self.frame_pool = pool.clone();
+        self.frame_pool_shared = true;
+    }
+
+    fn set_options(&mut self, options: &DecoderOptions) {
+        self.decode_threads = (options.decode_threads as usize).max(1);
+    }
+
     fn open(&mut self, params: &CodecParameters) -> TaoResult<()> {
         self.sps_map.clear();
         self.pps_map.clear();
@@ -1264,6 +1366,12 @@ impl Decoder for H264Decoder {
             self.drain_reorder_buffer_to_output();
             return Ok(());
         }
+        // 码流中途更新的 avcC (如 FLV 新的 sequence header)
+        if let Some(extra) = packet.new_extra_data() {
+            let config = parse_avcc_config(extra)?;
+            self.length_size = config.length_size;
+            self.parse_sps_pps_from_config(&config)?;
+        }
         let mut nalus = split_avcc(&packet.data, self.length_size);
         if nalus.is_empty() {
             nalus = split_annex_b(&packet.data);
@@ -1273,8 +1381,13 @@ impl Decoder for H264Decoder {
             self.record_malformed_nal_drop("send_packet_split", &err);
         }
         let mut idr_reset_done = false;
+        // 多线程模式下同一图像中待并行解码的 slice
+        let mut slice_batch = Vec::new();
 
         for nalu in &nalus {
+            if !matches!(nalu.nal_type, NalUnitType::SliceIdr | NalUnitType::Slice) {
+                self.decode_slice_batch(&mut slice_batch);
+            }
             match nalu.nal_type {
                 NalUnitType::Sps => self.handle_sps(nalu),
                 NalUnitType::Pps => self.handle_pps(nalu),
@@ -1284,6 +1397,9 @@ impl Decoder for H264Decoder {
                     let first_mb = self.parse_slice_first_mb(nalu);
                     let start_new_picture = first_mb == Some(0);
 
+                    if start_new_picture {
+                        self.decode_slice_batch(&mut slice_batch);
+                    }
                     if start_new_picture && self.pending_frame.is_some() {
                         self.finalize_pending_frame();
                     }
@@ -1298,7 +1414,11 @@ impl Decoder for H264Decoder {
                         self.reset_reference_planes();
                         idr_reset_done = true;
                     }
-                    self.decode_slice(nalu);
+                    if self.decode_threads > 1 {
+                        self.queue_slice(nalu, &mut slice_batch);
+                    } else {
+                        self.decode_slice(nalu);
+                    }
                     if self.pending_frame.is_none() {
                         let mark_recovery_keyframe =
                             self.consume_recovery_point_for_new_picture(is_idr);
@@ -1314,23 +1434,13 @@ impl Decoder for H264Decoder {
                 }
                 _ => {}
             }
-            if let Some(err) = self.take_missing_reference_fallback_error() {
-                return Err(TaoError::InvalidData(err));
-            }
-            if let Some(err) = self.take_ref_idx_oob_error() {
-                return Err(TaoError::InvalidData(err));
-            }
-            if let Some(err) = self.take_mvd_overflow_error() {
+            if let Some(err) = self.take_decode_error() {
+                self.decode_slice_batch(&mut slice_batch);
                 return Err(TaoError::InvalidData(err));
             }
         }
-        if let Some(err) = self.take_missing_reference_fallback_error() {
-            return Err(TaoError::InvalidData(err));
-        }
-        if let Some(err) = self.take_ref_idx_oob_error() {
-            return Err(TaoError::InvalidData(err));
-        }
-        if let Some(err) = self.take_mvd_overflow_error() {
+        self.decode_slice_batch(&mut slice_batch);
+        if let Some(err) = self.take_decode_error() {
             return Err(TaoError::InvalidData(err));
         }
         Ok(())
@@ -1419,5 +1529,6 @@ impl Decoder for H264Decoder {
         self.mvd_l1_x_4x4.fill(0);
         self.mvd_l1_y_4x4.fill(0);
         self.mb_slice_first_mb.fill(u32::MAX);
+        self.slice_deblock_params.clear();
     }
 }
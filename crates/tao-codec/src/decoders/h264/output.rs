@@ -208,11 +208,25 @@ impl H264Decoder {
             .collect()
     }
 
+    /// 长期参考帧, 按 LongTermPicNum 升序排列
     pub(super) fn long_term_references(&self) -> Vec<&ReferencePicture> {
-        self.reference_frames
+        let mut refs: Vec<&ReferencePicture> = self
+            .reference_frames
             .iter()
             .filter(|pic| pic.long_term_frame_idx.is_some())
-            .collect()
+            .collect();
+        refs.sort_by_key(|pic| Self::long_term_pic_num(pic));
+        refs
+    }
+
+    /// 长期参考帧的 LongTermPicNum (帧编码时等于 LongTermFrameIdx), 短期参考帧返回 None.
+    pub(super) fn long_term_pic_num(pic: &ReferencePicture) -> Option<u32> {
+        pic.long_term_frame_idx
+    }
+
+    /// 短期参考帧相对当前帧的 PicNum (帧编码时等于 FrameNumWrap).
+    pub(super) fn short_term_pic_num(&self, frame_num: u32) -> i32 {
+        self.frame_num_wrap_for_short_term(frame_num, self.last_frame_num)
     }
 
     pub(super) fn reference_to_planes(pic: &ReferencePicture) -> RefPlanes {
@@ -245,23 +259,14 @@ impl H264Decoder {
             after.sort_by_key(|pic| pic.poc);
             let mut refs = before;
             refs.extend(after);
-            let mut long_refs = self.long_term_references();
-            long_refs.sort_by_key(|pic| pic.long_term_frame_idx.unwrap_or(u32::MAX));
-            refs.extend(long_refs);
+            refs.extend(self.long_term_references());
             return refs;
         }
 
-        let mut short_refs = self.short_term_references();
-        short_refs.sort_by_key(|pic| {
-            (
-                self.frame_num_backward_distance(pic.frame_num),
-                self.frame_num_forward_distance(pic.frame_num),
-            )
-        });
-        let mut refs = short_refs;
-        let mut long_refs = self.long_term_references();
-        long_refs.sort_by_key(|pic| pic.long_term_frame_idx.unwrap_or(u32::MAX));
-        refs.extend(long_refs);
+        // P slice: 短期参考按 PicNum 降序, 之后追加按 LongTermPicNum 升序的长期参考.
+        let mut refs = self.short_term_references();
+        refs.sort_by_key(|pic| std::cmp::Reverse(self.short_term_pic_num(pic.frame_num)));
+        refs.extend(self.long_term_references());
         refs
     }
 
@@ -283,23 +288,18 @@ impl H264Decoder {
             before.sort_by_key(|pic| std::cmp::Reverse(pic.poc));
             let mut refs = after;
             refs.extend(before);
-            let mut long_refs = self.long_term_references();
-            long_refs.sort_by_key(|pic| pic.long_term_frame_idx.unwrap_or(u32::MAX));
-            refs.extend(long_refs);
+            refs.extend(self.long_term_references());
             return refs;
         }
 
-        let mut short_refs = self.short_term_references();
-        short_refs.sort_by_key(|pic| {
+        let mut refs = self.short_term_references();
+        refs.sort_by_key(|pic| {
             (
                 self.frame_num_forward_distance(pic.frame_num),
                 self.frame_num_backward_distance(pic.frame_num),
             )
         });
-        let mut refs = short_refs;
-        let mut long_refs = self.long_term_references();
-        long_refs.sort_by_key(|pic| pic.long_term_frame_idx.unwrap_or(u32::MAX));
-        refs.extend(long_refs);
+        refs.extend(self.long_term_references());
         refs
     }
 
@@ -400,15 +400,9 @@ impl H264Decoder {
                         0,
                     )
                 }
-                RefPicListMod::LongTerm { long_term_pic_num } => {
-                    lookup_refs.iter().enumerate().find_map(|(idx, pic)| {
-                        if pic.long_term_frame_idx == Some(long_term_pic_num) {
-                            Some(idx)
-                        } else {
-                            None
-                        }
-                    })
-                }
+                RefPicListMod::LongTerm { long_term_pic_num } => lookup_refs
+                    .iter()
+                    .position(|pic| Self::long_term_pic_num(pic) == Some(long_term_pic_num)),
             };
 
             if let Some(src_idx) = target_idx {
@@ -659,13 +653,32 @@ impl H264Decoder {
         false
     }
 
+    /// 仅剩长期参考时的容错淘汰: 与 FFmpeg 一致移除 LongTermFrameIdx 最小的长期参考帧.
+    fn remove_long_term_with_lowest_idx(&mut self) -> bool {
+        let Some(idx) = self
+            .long_term_references()
+            .first()
+            .and_then(|pic| pic.long_term_frame_idx)
+        else {
+            return false;
+        };
+        self.remove_long_term_by_idx(idx)
+    }
+
+    /// DPB 已满时淘汰一帧: 优先移除 FrameNumWrap 最小的短期参考, 无短期参考时再移除长期参考.
+    fn evict_one_reference_for(&mut self, cur_frame_num: u32) {
+        if !self.remove_short_term_with_lowest_frame_num_wrap_for(cur_frame_num)
+            && !self.remove_long_term_with_lowest_idx()
+        {
+            let _ = self.reference_frames.pop_front();
+        }
+    }
+
     fn apply_sliding_window_if_needed_for(&mut self, cur_frame_num: u32) {
         if self.reference_frames.len() < self.max_reference_frames {
             return;
         }
-        if !self.remove_short_term_with_lowest_frame_num_wrap_for(cur_frame_num) {
-            let _ = self.reference_frames.pop_front();
-        }
+        self.evict_one_reference_for(cur_frame_num);
     }
 
     fn apply_sliding_window_if_needed(&mut self) {
@@ -674,9 +687,7 @@ impl H264Decoder {
 
     fn enforce_reference_capacity_for(&mut self, cur_frame_num: u32) {
         while self.reference_frames.len() > self.max_reference_frames {
-            if !self.remove_short_term_with_lowest_frame_num_wrap_for(cur_frame_num) {
-                let _ = self.reference_frames.pop_front();
-            }
+            self.evict_one_reference_for(cur_frame_num);
         }
     }
 
@@ -692,19 +703,19 @@ impl H264Decoder {
             y: Arc::new(vec![128u8; self.ref_y.len()]),
             u: Arc::new(vec![128u8; self.ref_u.len()]),
             v: Arc::new(vec![128u8; self.ref_v.len()]),
-            mv_l0_x: vec![0i16; total_mb],
-            mv_l0_y: vec![0i16; total_mb],
-            ref_idx_l0: vec![-1i8; total_mb],
-            mv_l1_x: vec![0i16; total_mb],
-            mv_l1_y: vec![0i16; total_mb],
-            ref_idx_l1: vec![-1i8; total_mb],
-            mv_l0_x_4x4: vec![0i16; total_4x4],
-            mv_l0_y_4x4: vec![0i16; total_4x4],
-            ref_idx_l0_4x4: vec![-1i8; total_4x4],
-            mv_l1_x_4x4: vec![0i16; total_4x4],
-            mv_l1_y_4x4: vec![0i16; total_4x4],
-            ref_idx_l1_4x4: vec![-1i8; total_4x4],
-            mb_types: vec![0u8; total_mb],
+            mv_l0_x: Arc::new(vec![0i16; total_mb]),
+            mv_l0_y: Arc::new(vec![0i16; total_mb]),
+            ref_idx_l0: Arc::new(vec![-1i8; total_mb]),
+            mv_l1_x: Arc::new(vec![0i16; total_mb]),
+            mv_l1_y: Arc::new(vec![0i16; total_mb]),
+            ref_idx_l1: Arc::new(vec![-1i8; total_mb]),
+            mv_l0_x_4x4: Arc::new(vec![0i16; total_4x4]),
+            mv_l0_y_4x4: Arc::new(vec![0i16; total_4x4]),
+            ref_idx_l0_4x4: Arc::new(vec![-1i8; total_4x4]),
+            mv_l1_x_4x4: Arc::new(vec![0i16; total_4x4]),
+            mv_l1_y_4x4: Arc::new(vec![0i16; total_4x4]),
+            ref_idx_l1_4x4: Arc::new(vec![-1i8; total_4x4]),
+            mb_types: Arc::new(vec![0u8; total_mb]),
             ref_l0_poc: Vec::new(),
             ref_l1_poc: Vec::new(),
             frame_num,
@@ -722,19 +733,19 @@ impl H264Decoder {
             y: Arc::new(self.ref_y.clone()),
             u: Arc::new(self.ref_u.clone()),
             v: Arc::new(self.ref_v.clone()),
-            mv_l0_x: self.mv_l0_x.clone(),
-            mv_l0_y: self.mv_l0_y.clone(),
-            ref_idx_l0: self.ref_idx_l0.clone(),
-            mv_l1_x: self.mv_l1_x.clone(),
-            mv_l1_y: self.mv_l1_y.clone(),
-            ref_idx_l1: self.ref_idx_l1.clone(),
-            mv_l0_x_4x4: self.mv_l0_x_4x4.clone(),
-            mv_l0_y_4x4: self.mv_l0_y_4x4.clone(),
-            ref_idx_l0_4x4: self.ref_idx_l0_4x4.clone(),
-            mv_l1_x_4x4: self.mv_l1_x_4x4.clone(),
-            mv_l1_y_4x4: self.mv_l1_y_4x4.clone(),
-            ref_idx_l1_4x4: self.ref_idx_l1_4x4.clone(),
-            mb_types: self.mb_types.clone(),
+            mv_l0_x: Arc::new(self.mv_l0_x.clone()),
+            mv_l0_y: Arc::new(self.mv_l0_y.clone()),
+            ref_idx_l0: Arc::new(self.ref_idx_l0.clone()),
+            mv_l1_x: Arc::new(self.mv_l1_x.clone()),
+            mv_l1_y: Arc::new(self.mv_l1_y.clone()),
+            ref_idx_l1: Arc::new(self.ref_idx_l1.clone()),
+            mv_l0_x_4x4: Arc::new(self.mv_l0_x_4x4.clone()),
+            mv_l0_y_4x4: Arc::new(self.mv_l0_y_4x4.clone()),
+            ref_idx_l0_4x4: Arc::new(self.ref_idx_l0_4x4.clone()),
+            mv_l1_x_4x4: Arc::new(self.mv_l1_x_4x4.clone()),
+            mv_l1_y_4x4: Arc::new(self.mv_l1_y_4x4.clone()),
+            ref_idx_l1_4x4: Arc::new(self.ref_idx_l1_4x4.clone()),
+            mb_types: Arc::new(self.mb_types.clone()),
             ref_l0_poc: self.last_ref_l0_poc.clone(),
             ref_l1_poc: self.last_ref_l1_poc.clone(),
             frame_num: self.last_frame_num,
@@ -770,16 +781,16 @@ impl H264Decoder {
                 self.max_long_term_frame_idx = None;
             }
         } else if adaptive {
+            // MMCO5 会重置 frame_num, 其余操作的 picNumX 仍以当前图像的 CurrPicNum 为基准.
+            let cur_pic_num = self.pic_num_from_frame_num(self.last_frame_num);
             for op_idx in 0..mmco_ops_len {
                 let op = self.last_dec_ref_pic_marking.ops[op_idx];
                 match op {
                     MmcoOp::ForgetShort {
                         difference_of_pic_nums_minus1,
                     } => {
-                        let pic_num_x = self.pic_num_subtract(
-                            self.pic_num_from_frame_num(self.last_frame_num),
-                            difference_of_pic_nums_minus1 + 1,
-                        );
+                        let pic_num_x =
+                            self.pic_num_subtract(cur_pic_num, difference_of_pic_nums_minus1 + 1);
                         let _ = self.remove_short_term_by_pic_num(pic_num_x);
                     }
                     MmcoOp::ForgetLong { long_term_pic_num } => {
@@ -789,17 +800,26 @@ impl H264Decoder {
                         difference_of_pic_nums_minus1,
                         long_term_frame_idx,
                     } => {
-                        let pic_num_x = self.pic_num_subtract(
-                            self.pic_num_from_frame_num(self.last_frame_num),
-                            difference_of_pic_nums_minus1 + 1,
-                        );
-                        let _ = self.remove_long_term_by_idx(long_term_frame_idx);
-                        if let Some(pos) = self.reference_frames.iter().rposition(|pic| {
+                        let pic_num_x =
+                            self.pic_num_subtract(cur_pic_num, difference_of_pic_nums_minus1 + 1);
+                        let target = self.reference_frames.iter().rposition(|pic| {
                             pic.long_term_frame_idx.is_none()
                                 && self.pic_num_from_frame_num(pic.frame_num) == pic_num_x
-                        }) && let Some(pic) = self.reference_frames.get_mut(pos)
-                        {
-                            pic.long_term_frame_idx = Some(long_term_frame_idx);
+                        });
+                        // 与 FFmpeg 一致: 目标短期参考不存在时忽略该操作, 不释放已占用该索引的长期参考.
+                        if let Some(target) = target {
+                            let frame_num = self.reference_frames[target].frame_num;
+                            let _ = self.remove_long_term_by_idx(long_term_frame_idx);
+                            if let Some(pic) = self.reference_frames.iter_mut().rev().find(|pic| {
+                                pic.long_term_frame_idx.is_none() && pic.frame_num == frame_num
+                            }) {
+                                pic.long_term_frame_idx = Some(long_term_frame_idx);
+                            }
+                        } else {
+                            warn!(
+                                "H264: MMCO3 目标短期参考不存在, pic_num={}, long_term_frame_idx={}",
+                                pic_num_x, long_term_frame_idx
+                            );
                         }
                     }
                     MmcoOp::TrimLong {
@@ -831,12 +851,16 @@ impl H264Decoder {
         }
 
         if let Some(idx) = current_long_term_idx {
-            if self.max_long_term_frame_idx.is_none_or(|max| idx <= max) {
-                let _ = self.remove_long_term_by_idx(idx);
-                self.push_current_reference(Some(idx));
-            } else {
-                self.push_current_reference(None);
+            // 与 FFmpeg 一致: 超出 MaxLongTermFrameIdx 时仍按长期参考保存,
+            // 否则后续 slice 以 long_term_pic_num 引用该帧时会错配到其他参考.
+            if self.max_long_term_frame_idx.is_none_or(|max| idx > max) {
+                warn!(
+                    "H264: MMCO6 long_term_frame_idx 超出上限, idx={}, max={:?}",
+                    idx, self.max_long_term_frame_idx
+                );
             }
+            let _ = self.remove_long_term_by_idx(idx);
+            self.push_current_reference(Some(idx));
         } else {
             self.push_current_reference(None);
         }
@@ -893,63 +917,70 @@ impl H264Decoder {
         }
     }
 
+    /// 从对齐缓冲区拷贝到池化的紧凑平面
+    fn copy_plane_pooled(&self, src: &[u8], src_stride: usize, w: usize, h: usize) -> FrameBuf {
+        let mut dst = self.frame_pool.take_vec(w * h);
+        copy_plane_into(&mut dst, src, src_stride, w, h);
+        self.frame_pool.wrap(dst)
+    }
+
     pub(super) fn build_output_frame(&mut self, pts: i64, time_base: Rational, is_keyframe: bool) {
         let w = self.width as usize;
         let h = self.height as usize;
         self.conceal_frame_level_errors();
 
-        if self.last_disable_deblocking_filter_idc != 1 {
-            let (chroma_qp_index_offset, second_chroma_qp_index_offset) = self
-                .pps
-                .as_ref()
-                .map(|p| (p.chroma_qp_index_offset, p.second_chroma_qp_index_offset))
-                .unwrap_or((0, 0));
-            let deblock_w = self.mb_width * 16;
-            let deblock_h = self.mb_height * 16;
-            deblock::apply_deblock_yuv420_with_slice_params(
-                &mut self.ref_y,
-                &mut self.ref_u,
-                &mut self.ref_v,
-                deblock::DeblockSliceParams {
-                    stride_y: self.stride_y,
-                    stride_c: self.stride_c,
-                    width: deblock_w,
-                    height: deblock_h,
-                    slice_qp: self.last_slice_qp,
-                    disable_deblocking_filter_idc: self.last_disable_deblocking_filter_idc,
-                    chroma_qp_index_offset,
-                    second_chroma_qp_index_offset,
-                    alpha_offset_div2: self.last_slice_alpha_c0_offset_div2,
-                    beta_offset_div2: self.last_slice_beta_offset_div2,
-                    mb_width: self.mb_width,
-                    mb_height: self.mb_height,
-                    mb_types: Some(&self.mb_types),
-                    mb_cbp: Some(&self.mb_cbp),
-                    mb_slice_first_mb: Some(&self.mb_slice_first_mb),
-                    mv_l0_x: Some(&self.mv_l0_x),
-                    mv_l0_y: Some(&self.mv_l0_y),
-                    ref_idx_l0: Some(&self.ref_idx_l0),
-                    ref_l0_poc: Some(&self.last_ref_l0_poc),
-                    mv_l1_x: Some(&self.mv_l1_x),
-                    mv_l1_y: Some(&self.mv_l1_y),
-                    ref_idx_l1: Some(&self.ref_idx_l1),
-                    ref_l1_poc: Some(&self.last_ref_l1_poc),
-                    cbf_luma: Some(&self.cbf_luma),
-                    mv_l0_x_4x4: Some(&self.mv_l0_x_4x4),
-                    mv_l0_y_4x4: Some(&self.mv_l0_y_4x4),
-                    ref_idx_l0_4x4: Some(&self.ref_idx_l0_4x4),
-                    mv_l1_x_4x4: Some(&self.mv_l1_x_4x4),
-                    mv_l1_y_4x4: Some(&self.mv_l1_y_4x4),
-                    ref_idx_l1_4x4: Some(&self.ref_idx_l1_4x4),
-                    mb_qp: Some(&self.mb_qp),
-                    transform_8x8_flags: Some(&self.transform_8x8_flags),
-                },
-            );
-        }
+        // 各宏块按所属 slice 的 disable_deblocking_filter_idc 决定是否滤波
+        let (chroma_qp_index_offset, second_chroma_qp_index_offset) = self
+            .pps
+            .as_ref()
+            .map(|p| (p.chroma_qp_index_offset, p.second_chroma_qp_index_offset))
+            .unwrap_or((0, 0));
+        let deblock_w = self.mb_width * 16;
+        let deblock_h = self.mb_height * 16;
+        deblock::apply_deblock_yuv420_with_slice_params(
+            &mut self.ref_y,
+            &mut self.ref_u,
+            &mut self.ref_v,
+            deblock::DeblockSliceParams {
+                stride_y: self.stride_y,
+                stride_c: self.stride_c,
+                width: deblock_w,
+                height: deblock_h,
+                slice_qp: self.last_slice_qp,
+                disable_deblocking_filter_idc: self.last_disable_deblocking_filter_idc,
+                chroma_qp_index_offset,
+                second_chroma_qp_index_offset,
+                alpha_offset_div2: self.last_slice_alpha_c0_offset_div2,
+                beta_offset_div2: self.last_slice_beta_offset_div2,
+                mb_width: self.mb_width,
+                mb_height: self.mb_height,
+                mb_types: Some(&self.mb_types),
+                mb_cbp: Some(&self.mb_cbp),
+                mb_slice_first_mb: Some(&self.mb_slice_first_mb),
+                slice_filter_params: Some(&self.slice_deblock_params),
+                mv_l0_x: Some(&self.mv_l0_x),
+                mv_l0_y: Some(&self.mv_l0_y),
+                ref_idx_l0: Some(&self.ref_idx_l0),
+                ref_l0_poc: Some(&self.last_ref_l0_poc),
+                mv_l1_x: Some(&self.mv_l1_x),
+                mv_l1_y: Some(&self.mv_l1_y),
+                ref_idx_l1: Some(&self.ref_idx_l1),
+                ref_l1_poc: Some(&self.last_ref_l1_poc),
+                cbf_luma: Some(&self.cbf_luma),
+                mv_l0_x_4x4: Some(&self.mv_l0_x_4x4),
+                mv_l0_y_4x4: Some(&self.mv_l0_y_4x4),
+                ref_idx_l0_4x4: Some(&self.ref_idx_l0_4x4),
+                mv_l1_x_4x4: Some(&self.mv_l1_x_4x4),
+                mv_l1_y_4x4: Some(&self.mv_l1_y_4x4),
+                ref_idx_l1_4x4: Some(&self.ref_idx_l1_4x4),
+                mb_qp: Some(&self.mb_qp),
+                transform_8x8_flags: Some(&self.transform_8x8_flags),
+            },
+        );
 
-        let y_data = copy_plane(&self.ref_y, self.stride_y, w, h);
-        let u_data = copy_plane(&self.ref_u, self.stride_c, w / 2, h / 2);
-        let v_data = copy_plane(&self.ref_v, self.stride_c, w / 2, h / 2);
+        let y_data = self.copy_plane_pooled(&self.ref_y, self.stride_y, w, h);
+        let u_data = self.copy_plane_pooled(&self.ref_u, self.stride_c, w / 2, h / 2);
+        let v_data = self.copy_plane_pooled(&self.ref_v, self.stride_c, w / 2, h / 2);
 
         let picture_type = match self.last_slice_type {
             1 => PictureType::B,
@@ -964,6 +995,13 @@ impl H264Decoder {
             }
         };
 
+        // 色彩信息来自 SPS VUI 的 video_signal_type, 未给出时保持未指定
+        let (color_space, color_range) = self
+            .sps
+            .as_ref()
+            .map(|sps| (sps.color_space, sps.color_range))
+            .unwrap_or_default();
+
         let vf = VideoFrame {
             data: vec![y_data, u_data, v_data],
             linesize: vec![w, w / 2, w / 2],
@@ -974,10 +1012,11 @@ impl H264Decoder {
             time_base,
             duration: 0,
             is_keyframe,
+            force_keyframe: false,
             picture_type,
             sample_aspect_ratio: Rational::new(1, 1),
-            color_space: Default::default(),
-            color_range: Default::default(),
+            color_space,
+            color_range,
         };
         let frame_poc = self.last_poc;
         self.store_reference_with_marking();
@@ -25,6 +25,9 @@ const DEFAULT_SCALING_8X8_INTER: [u8; 64] = [
     22, 24, 25, 27, 28, 30, 32, 33, 24, 25, 27, 28, 30, 32, 33, 35,
 ];
 
+/// 单帧最大 map unit 数 (Level 6.2 的 MaxFS), 用于限制显式 slice group 映射的大小.
+const MAX_PIC_SIZE_IN_MAP_UNITS: u32 = 139_264;
+
 type ParsedPpsScalingLists = ([[u8; 16]; 6], Vec<[u8; 64]>);
 
 /// 解析 PPS 参数.
@@ -60,9 +63,11 @@ pub(super) fn parse_pps(rbsp: &[u8]) -> TaoResult<super::Pps> {
             num_slice_groups_minus1
         )));
     }
-    if num_slice_groups_minus1 > 0 {
-        skip_pps_slice_groups(&mut br, num_slice_groups_minus1)?;
-    }
+    let slice_groups = if num_slice_groups_minus1 > 0 {
+        parse_pps_slice_groups(&mut br, num_slice_groups_minus1)?
+    } else {
+        super::SliceGroupParams::default()
+    };
 
     let num_ref_idx_l0_default_active_minus1 = super::read_ue(&mut br)?;
     if num_ref_idx_l0_default_active_minus1 > 31 {
@@ -152,6 +157,7 @@ pub(super) fn parse_pps(rbsp: &[u8]) -> TaoResult<super::Pps> {
         transform_8x8_mode,
         scaling_list_4x4,
         scaling_list_8x8,
+        slice_groups,
     })
 }
 
@@ -165,43 +171,74 @@ fn validate_chroma_offset(field: &str, value: i32) -> TaoResult<()> {
     Ok(())
 }
 
-/// 跳过 PPS 的 slice group 相关语法.
-fn skip_pps_slice_groups(br: &mut BitReader, num_slice_groups_minus1: u32) -> TaoResult<()> {
-    let slice_group_map_type = super::read_ue(br)?;
-    match slice_group_map_type {
+/// 解析 PPS 的 slice group (FMO) 相关语法.
+fn parse_pps_slice_groups(
+    br: &mut BitReader,
+    num_slice_groups_minus1: u32,
+) -> TaoResult<super::SliceGroupParams> {
+    let mut params = super::SliceGroupParams {
+        num_slice_groups_minus1,
+        slice_group_map_type: super::read_ue(br)?,
+        ..Default::default()
+    };
+    match params.slice_group_map_type {
         0 => {
             for _ in 0..=num_slice_groups_minus1 {
-                let _run_length_minus1 = super::read_ue(br)?;
+                params.run_length_minus1.push(super::read_ue(br)?);
             }
         }
+        1 => {}
         2 => {
             for _ in 0..num_slice_groups_minus1 {
-                let _top_left = super::read_ue(br)?;
-                let _bottom_right = super::read_ue(br)?;
+                let top_left = super::read_ue(br)?;
+                let bottom_right = super::read_ue(br)?;
+                if top_left > bottom_right {
+                    return Err(TaoError::InvalidData(format!(
+                        "H264: slice group 前景矩形非法, top_left={}, bottom_right={}",
+                        top_left, bottom_right
+                    )));
+                }
+                params.top_left.push(top_left);
+                params.bottom_right.push(bottom_right);
             }
         }
         3..=5 => {
-            let _slice_group_change_direction_flag = br.read_bit()?;
-            let _slice_group_change_rate_minus1 = super::read_ue(br)?;
+            params.change_direction_flag = br.read_bit()? == 1;
+            params.change_rate_minus1 = super::read_ue(br)?;
         }
         6 => {
             let pic_size_in_map_units_minus1 = super::read_ue(br)?;
-            let group_count = num_slice_groups_minus1 + 1;
-            let bits_per_id = bits_for_slice_group_id(group_count);
+            if pic_size_in_map_units_minus1 >= MAX_PIC_SIZE_IN_MAP_UNITS {
+                return Err(TaoError::InvalidData(format!(
+                    "H264: pic_size_in_map_units_minus1 超出范围, value={}",
+                    pic_size_in_map_units_minus1
+                )));
+            }
+            let bits_per_id = bits_for_slice_group_id(num_slice_groups_minus1 + 1);
+            params.slice_group_id = Vec::with_capacity(pic_size_in_map_units_minus1 as usize + 1);
             for _ in 0..=pic_size_in_map_units_minus1 {
-                if bits_per_id > 0 {
-                    let _slice_group_id = br.read_bits(bits_per_id)?;
+                let id = if bits_per_id > 0 {
+                    br.read_bits(bits_per_id)?
+                } else {
+                    0
+                };
+                if id > num_slice_groups_minus1 {
+                    return Err(TaoError::InvalidData(format!(
+                        "H264: slice_group_id 超出范围, value={}, num_slice_groups_minus1={}",
+                        id, num_slice_groups_minus1
+                    )));
                 }
+                params.slice_group_id.push(id);
             }
         }
         _ => {
             return Err(TaoError::InvalidData(format!(
                 "H264: slice_group_map_type 非法, value={}",
-                slice_group_map_type
+                params.slice_group_map_type
             )));
         }
     }
-    Ok(())
+    Ok(params)
 }
 
 fn bits_for_slice_group_id(group_count: u32) -> u32 {
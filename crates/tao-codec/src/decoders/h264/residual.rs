@@ -315,6 +315,25 @@ fn apply_scaling_weight(base_scale: i32, weight: u8) -> i32 {
     base_scale * i32::from(weight)
 }
 
+/// 反量化结果的合法上界 (8-bit, 规范 8.5.12.1: d_ij ∈ [-2^15, 2^15-1]).
+const DEQUANT_MAX: i64 = (1 << 15) - 1;
+
+/// 以 64 位计算 `coeff * scale`, 按 `qp_per` 相对 `base_shift` 左移或 (可选舍入) 右移,
+/// 结果截断到合法范围.
+///
+/// 合规码流不会越界; 损坏码流的超大 level 在此截断, 避免后续变换溢出.
+fn scale_coeff(coeff: i32, scale: i32, qp_per: i32, base_shift: i32, round: bool) -> i32 {
+    let scaled = i64::from(coeff) * i64::from(scale);
+    let value = if qp_per >= base_shift {
+        scaled << (qp_per - base_shift)
+    } else {
+        let shift = base_shift - qp_per;
+        let offset = if round { 1 << (shift - 1) } else { 0 };
+        (scaled + offset) >> shift
+    };
+    value.clamp(-DEQUANT_MAX - 1, DEQUANT_MAX) as i32
+}
+
 /// Luma DC 系数反量化 (I_16x16), 支持自定义 4x4 scaling_list.
 ///
 /// 门限使用 qP/6 >= 6 (非标准的 2), 因为反量化后的 DC 值将作为 4x4 IDCT 的输入,
@@ -325,12 +344,7 @@ pub fn dequant_luma_dc_with_scaling(coeffs: &mut [i32; 16], qp: i32, scaling_lis
     let scale = apply_scaling_weight(LEVEL_SCALE[qp_rem as usize][0], scaling_list[0]);
 
     for c in coeffs.iter_mut() {
-        if qp_per >= 6 {
-            *c = (*c * scale) << (qp_per - 6);
-        } else {
-            let shift = 6 - qp_per;
-            *c = (*c * scale + (1 << (shift - 1))) >> shift;
-        }
+        *c = scale_coeff(*c, scale, qp_per, 6, true);
     }
 }
 
@@ -345,12 +359,7 @@ pub fn dequant_chroma_dc_with_scaling(coeffs: &mut [i32; 4], qp: i32, scaling_li
     let scale = apply_scaling_weight(LEVEL_SCALE[qp_rem as usize][0], scaling_list[0]);
 
     for c in coeffs.iter_mut() {
-        if qp_per >= 5 {
-            *c = (*c * scale) << (qp_per - 5);
-        } else {
-            let shift = 5 - qp_per;
-            *c = (*c * scale) >> shift;
-        }
+        *c = scale_coeff(*c, scale, qp_per, 5, false);
     }
 }
 
@@ -369,13 +378,7 @@ pub fn dequant_4x4_ac_with_scaling(coeffs: &mut [i32; 16], qp: i32, scaling_list
         let base_scale = LEVEL_SCALE[qp_rem as usize][si];
         let raster_idx = row * 4 + col;
         let scale = apply_scaling_weight(base_scale, scaling_list[raster_idx]);
-        let scaled = *c * scale;
-        if qp_per >= 4 {
-            *c = scaled << (qp_per - 4);
-        } else {
-            let shift = 4 - qp_per;
-            *c = (scaled + (1 << (shift - 1))) >> shift;
-        }
+        *c = scale_coeff(*c, scale, qp_per, 4, true);
     }
 }
 
@@ -447,13 +450,7 @@ pub fn dequant_8x8_ac_with_scaling(
         let scale_idx = DEQUANT_8X8_SCALE_INDEX[idx];
         let base_scale = LEVEL_SCALE_8X8[rem_idx][scale_idx];
         let scale = apply_scaling_weight(base_scale, scaling_list_raster[idx]);
-        let scaled = *coeff * scale;
-        if qp_per >= 6 {
-            *coeff = scaled << (qp_per - 6);
-        } else {
-            let shift = 6 - qp_per;
-            *coeff = (scaled + (1 << (shift - 1))) >> shift;
-        }
+        *coeff = scale_coeff(*coeff, scale, qp_per, 6, true);
     }
 }
 
@@ -16,9 +16,18 @@ impl H264Decoder {
 
     /// 解码一个 VCL NAL (slice)
     pub(super) fn decode_slice(&mut self, nalu: &NalUnit) {
+        if let Some((rbsp, header)) = self.prepare_slice(nalu) {
+            self.decode_slice_data(&rbsp, &header);
+        }
+    }
+
+    /// 解析 slice header 并更新逐 slice 状态 (POC、去块参数等), 返回待解码的 RBSP 与 header.
+    ///
+    /// 冗余 slice 或 header 解析失败时返回 `None`.
+    pub(super) fn prepare_slice(&mut self, nalu: &NalUnit) -> Option<(Vec<u8>, SliceHeader)> {
         let rbsp = nalu.rbsp();
 
-        match self.parse_slice_header(&rbsp, nalu) {
+        match self.parse_slice_header(rbsp, nalu) {
             Ok(mut header) => {
                 if header.redundant_pic_cnt > 0 {
                     tracing::debug!(
@@ -27,7 +36,7 @@ impl H264Decoder {
                         header.frame_num,
                         header.pps_id
                     );
-                    return;
+                    return None;
                 }
 
                 let prev_frame_num = self.last_frame_num;
@@ -37,15 +46,24 @@ impl H264Decoder {
                 self.last_disable_deblocking_filter_idc = header.disable_deblocking_filter_idc;
                 self.last_slice_alpha_c0_offset_div2 = header.slice_alpha_c0_offset_div2;
                 self.last_slice_beta_offset_div2 = header.slice_beta_offset_div2;
+                self.record_slice_deblock_params(
+                    header.first_mb,
+                    deblock::SliceFilterParams {
+                        disable_deblocking_filter_idc: header.disable_deblocking_filter_idc,
+                        alpha_offset_div2: header.slice_alpha_c0_offset_div2,
+                        beta_offset_div2: header.slice_beta_offset_div2,
+                    },
+                );
                 let prev_frame_num_for_poc =
                     self.fill_frame_num_gaps_if_needed(&header, prev_frame_num);
                 self.last_poc = self.compute_slice_poc(&header, prev_frame_num_for_poc);
                 self.last_frame_num = header.frame_num;
                 self.last_dec_ref_pic_marking = std::mem::take(&mut header.dec_ref_pic_marking);
-                self.decode_slice_data(&rbsp, &header);
+                Some((rbsp.to_vec(), header))
             }
             Err(err) => {
                 self.record_malformed_nal_drop("slice_header_parse", &err);
+                None
             }
         }
     }
@@ -115,6 +133,7 @@ impl H264Decoder {
             self.record_malformed_nal_drop("slice_activate_parameter_sets", &err);
             return;
         }
+        self.update_slice_group_map(header);
         let entropy_coding_mode = match &self.pps {
             Some(p) => p.entropy_coding_mode,
             None => return,
@@ -294,7 +313,7 @@ impl H264Decoder {
         let mut cur_qp = header.slice_qp;
         self.prev_qp_delta_nz = false;
         if is_i {
-            for mb_idx in first..total_mbs {
+            for mb_idx in self.slice_mb_addrs(first, total_mbs) {
                 self.reset_cavlc_block_error();
                 self.mark_mb_slice_first_mb(mb_idx, header.first_mb);
                 if !has_more_rbsp_data(&mut br) {
@@ -344,7 +363,7 @@ impl H264Decoder {
         let mut skip_run_left = 0u32;
         let mut pending_non_skip_mb = false;
         let direct_spatial_mv_pred_flag = header.direct_spatial_mv_pred_flag;
-        for mb_idx in first..total_mbs {
+        for mb_idx in self.slice_mb_addrs(first, total_mbs) {
             self.reset_cavlc_block_error();
             if !pending_non_skip_mb && skip_run_left == 0 {
                 if !has_more_rbsp_data(&mut br) {
@@ -543,16 +562,16 @@ impl H264Decoder {
                         for sub_idx in 0..4usize {
                             if use_l0[sub_idx] {
                                 for part_idx in 0..sub_part_count[sub_idx] {
-                                    l0_mvd_x[sub_idx][part_idx] = read_se(&mut br).unwrap_or(0);
-                                    l0_mvd_y[sub_idx][part_idx] = read_se(&mut br).unwrap_or(0);
+                                    l0_mvd_x[sub_idx][part_idx] = read_mvd(&mut br);
+                                    l0_mvd_y[sub_idx][part_idx] = read_mvd(&mut br);
                                 }
                             }
                         }
                         for sub_idx in 0..4usize {
                             if use_l1[sub_idx] {
                                 for part_idx in 0..sub_part_count[sub_idx] {
-                                    l1_mvd_x[sub_idx][part_idx] = read_se(&mut br).unwrap_or(0);
-                                    l1_mvd_y[sub_idx][part_idx] = read_se(&mut br).unwrap_or(0);
+                                    l1_mvd_x[sub_idx][part_idx] = read_mvd(&mut br);
+                                    l1_mvd_y[sub_idx][part_idx] = read_mvd(&mut br);
                                 }
                             }
                         }
@@ -885,14 +904,14 @@ impl H264Decoder {
                         let mut l1_mvd: [(i32, i32); 2] = [(0, 0); 2];
                         for part_idx in 0..2usize {
                             if part_use_l0[part_idx] {
-                                l0_mvd[part_idx].0 = read_se(&mut br).unwrap_or(0);
-                                l0_mvd[part_idx].1 = read_se(&mut br).unwrap_or(0);
+                                l0_mvd[part_idx].0 = read_mvd(&mut br);
+                                l0_mvd[part_idx].1 = read_mvd(&mut br);
                             }
                         }
                         for part_idx in 0..2usize {
                             if part_use_l1[part_idx] {
-                                l1_mvd[part_idx].0 = read_se(&mut br).unwrap_or(0);
-                                l1_mvd[part_idx].1 = read_se(&mut br).unwrap_or(0);
+                                l1_mvd[part_idx].0 = read_mvd(&mut br);
+                                l1_mvd[part_idx].1 = read_mvd(&mut br);
                             }
                         }
 
@@ -1113,8 +1132,8 @@ impl H264Decoder {
                             let l0_ref_i8 = l0_ref_idx.min(i8::MAX as usize) as i8;
                             let (pred_x, pred_y) =
                                 self.predict_mv_l0_partition(mb_x, mb_y, 0, 0, 4, l0_ref_i8);
-                            let mvd_x = read_se(&mut br).unwrap_or(0);
-                            let mvd_y = read_se(&mut br).unwrap_or(0);
+                            let mvd_x = read_mvd(&mut br);
+                            let mvd_y = read_mvd(&mut br);
                             l0_motion = Some(BMotion {
                                 mv_x: pred_x + mvd_x,
                                 mv_y: pred_y + mvd_y,
@@ -1125,8 +1144,8 @@ impl H264Decoder {
                             let l1_ref_i8 = l1_ref_idx.min(i8::MAX as usize) as i8;
                             let (pred_x, pred_y) =
                                 self.predict_mv_l1_partition(mb_x, mb_y, 0, 0, 4, l1_ref_i8);
-                            let mvd_x = read_se(&mut br).unwrap_or(0);
-                            let mvd_y = read_se(&mut br).unwrap_or(0);
+                            let mvd_x = read_mvd(&mut br);
+                            let mvd_y = read_mvd(&mut br);
                             l1_motion = Some(BMotion {
                                 mv_x: pred_x + mvd_x,
                                 mv_y: pred_y + mvd_y,
@@ -1198,8 +1217,8 @@ impl H264Decoder {
                         self.set_l0_motion_block_4x4(base_x, base_y, 16, 16, 0, 0, ref_idx_i8);
                         let (pred_mv_x, pred_mv_y) =
                             self.predict_mv_l0_partition(mb_x, mb_y, 0, 0, 4, ref_idx_i8);
-                        let mvd_x = read_se(&mut br).unwrap_or(0);
-                        let mvd_y = read_se(&mut br).unwrap_or(0);
+                        let mvd_x = read_mvd(&mut br);
+                        let mvd_y = read_mvd(&mut br);
                         let mv_x = pred_mv_x + mvd_x;
                         let mv_y = pred_mv_y + mvd_y;
                         self.apply_inter_block_l0(
@@ -1232,8 +1251,8 @@ impl H264Decoder {
                         let top_ref_idx_i8 = ref_idx_top.min(i8::MAX as u32) as i8;
                         let (pred_mv_x, pred_mv_y) =
                             self.predict_mv_l0_16x8(mb_x, mb_y, 0, top_ref_idx_i8);
-                        let mvd_top_x = read_se(&mut br).unwrap_or(0);
-                        let mvd_top_y = read_se(&mut br).unwrap_or(0);
+                        let mvd_top_x = read_mvd(&mut br);
+                        let mvd_top_y = read_mvd(&mut br);
                         let mv_top_x = pred_mv_x + mvd_top_x;
                         let mv_top_y = pred_mv_y + mvd_top_y;
                         self.set_l0_motion_block_4x4(
@@ -1248,8 +1267,8 @@ impl H264Decoder {
                         let bottom_ref_idx_i8 = ref_idx_bottom.min(i8::MAX as u32) as i8;
                         let (pred_bottom_x, pred_bottom_y) =
                             self.predict_mv_l0_16x8(mb_x, mb_y, 1, bottom_ref_idx_i8);
-                        let mvd_bottom_x = read_se(&mut br).unwrap_or(0);
-                        let mvd_bottom_y = read_se(&mut br).unwrap_or(0);
+                        let mvd_bottom_x = read_mvd(&mut br);
+                        let mvd_bottom_y = read_mvd(&mut br);
                         let mv_bottom_x = pred_bottom_x + mvd_bottom_x;
                         let mv_bottom_y = pred_bottom_y + mvd_bottom_y;
                         self.apply_inter_block_l0(
@@ -1301,8 +1320,8 @@ impl H264Decoder {
                         let left_ref_idx_i8 = ref_idx_left.min(i8::MAX as u32) as i8;
                         let (pred_mv_x, pred_mv_y) =
                             self.predict_mv_l0_8x16(mb_x, mb_y, 0, left_ref_idx_i8);
-                        let mvd_left_x = read_se(&mut br).unwrap_or(0);
-                        let mvd_left_y = read_se(&mut br).unwrap_or(0);
+                        let mvd_left_x = read_mvd(&mut br);
+                        let mvd_left_y = read_mvd(&mut br);
                         let mv_left_x = pred_mv_x + mvd_left_x;
                         let mv_left_y = pred_mv_y + mvd_left_y;
                         self.set_l0_motion_block_4x4(
@@ -1317,8 +1336,8 @@ impl H264Decoder {
                         let right_ref_idx_i8 = ref_idx_right.min(i8::MAX as u32) as i8;
                         let (pred_right_x, pred_right_y) =
                             self.predict_mv_l0_8x16(mb_x, mb_y, 1, right_ref_idx_i8);
-                        let mvd_right_x = read_se(&mut br).unwrap_or(0);
-                        let mvd_right_y = read_se(&mut br).unwrap_or(0);
+                        let mvd_right_x = read_mvd(&mut br);
+                        let mvd_right_y = read_mvd(&mut br);
                         let mv_right_x = pred_right_x + mvd_right_x;
                         let mv_right_y = pred_right_y + mvd_right_y;
                         self.apply_inter_block_l0(
@@ -1403,8 +1422,8 @@ impl H264Decoder {
                                 _ => 1usize,
                             };
                             for part_idx in 0..sub_part_count {
-                                sub_mv_x[sub_idx][part_idx] = read_se(&mut br).unwrap_or(0);
-                                sub_mv_y[sub_idx][part_idx] = read_se(&mut br).unwrap_or(0);
+                                sub_mv_x[sub_idx][part_idx] = read_mvd(&mut br);
+                                sub_mv_y[sub_idx][part_idx] = read_mvd(&mut br);
                             }
                         }
 
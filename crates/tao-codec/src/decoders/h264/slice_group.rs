@@ -0,0 +1,287 @@
+//! H.264 slice group (FMO) 映射.
+//!
+//! 按规范 8.2.2 由 PPS 的 slice group 参数生成宏块到 slice group 的映射,
+//! 并按 nextMbAddress 规则给出 slice 内宏块的解码顺序.
+
+use super::*;
+
+/// slice_group_change_cycle 的位数: Ceil(Log2(PicSizeInMapUnits ÷ SliceGroupChangeRate + 1))
+pub(super) fn change_cycle_bits(pic_size_in_map_units: u32, change_rate: u32) -> u32 {
+    let rate = u64::from(change_rate.max(1));
+    // 2^n >= size / rate + 1  <=>  2^n * rate >= size + rate
+    let target = u64::from(pic_size_in_map_units) + rate;
+    let mut bits = 0u32;
+    while (rate << bits) < target && bits < 32 {
+        bits += 1;
+    }
+    bits
+}
+
+/// 生成 map unit 到 slice group 的映射 (8.2.2.1 - 8.2.2.7)
+pub(super) fn build_map_unit_to_slice_group_map(
+    params: &SliceGroupParams,
+    pic_width_in_mbs: usize,
+    pic_height_in_map_units: usize,
+    slice_group_change_cycle: u32,
+) -> Vec<u8> {
+    let size = pic_width_in_mbs * pic_height_in_map_units;
+    let groups = params.num_slice_groups_minus1 as usize + 1;
+    let mut map = vec![0u8; size];
+    if groups <= 1 || size == 0 {
+        return map;
+    }
+
+    let dir = usize::from(params.change_direction_flag);
+    let change_rate = params.change_rate_minus1 as usize + 1;
+    let units_in_group0 = (slice_group_change_cycle as usize)
+        .saturating_mul(change_rate)
+        .min(size);
+    let size_of_upper_left_group = if params.change_direction_flag {
+        size - units_in_group0
+    } else {
+        units_in_group0
+    };
+
+    match params.slice_group_map_type {
+        0 => {
+            // 交错: 各 slice group 依次占用 run_length 个 map unit, 循环直到填满
+            let mut i = 0usize;
+            'fill: loop {
+                for (group, &run_minus1) in params.run_length_minus1.iter().enumerate() {
+                    let run = run_minus1 as usize + 1;
+                    for j in 0..run {
+                        if i + j >= size {
+                            break 'fill;
+                        }
+                        map[i + j] = group as u8;
+                    }
+                    i += run;
+                }
+                if params.run_length_minus1.is_empty() {
+                    break;
+                }
+            }
+        }
+        1 => {
+            // 分散
+            for (i, slot) in map.iter_mut().enumerate() {
+                let x = i % pic_width_in_mbs;
+                let y = i / pic_width_in_mbs;
+                *slot = ((x + (y * groups) / 2) % groups) as u8;
+            }
+        }
+        2 => {
+            // 前景与剩余: 编号小的前景矩形覆盖编号大的
+            map.fill((groups - 1) as u8);
+            for group in (0..params.top_left.len().min(groups - 1)).rev() {
+                let top_left = params.top_left[group] as usize;
+                let bottom_right = params.bottom_right[group] as usize;
+                if bottom_right >= size {
+                    continue;
+                }
+                let (x0, y0) = (top_left % pic_width_in_mbs, top_left / pic_width_in_mbs);
+                let (x1, y1) = (
+                    bottom_right % pic_width_in_mbs,
+                    bottom_right / pic_width_in_mbs,
+                );
+                for y in y0..=y1 {
+                    for x in x0..=x1 {
+                        map[y * pic_width_in_mbs + x] = group as u8;
+                    }
+                }
+            }
+        }
+        3 => fill_box_out(
+            &mut map,
+            pic_width_in_mbs,
+            pic_height_in_map_units,
+            params.change_direction_flag,
+            units_in_group0,
+        ),
+        4 => {
+            // 光栅扫描
+            for (k, slot) in map.iter_mut().enumerate() {
+                *slot = if k < size_of_upper_left_group {
+                    dir as u8
+                } else {
+                    (1 - dir) as u8
+                };
+            }
+        }
+        5 => {
+            // 擦除: 按列扫描
+            let mut k = 0usize;
+            for x in 0..pic_width_in_mbs {
+                for y in 0..pic_height_in_map_units {
+                    map[y * pic_width_in_mbs + x] = if k < size_of_upper_left_group {
+                        dir as u8
+                    } else {
+                        (1 - dir) as u8
+                    };
+                    k += 1;
+                }
+            }
+        }
+        6 => {
+            // 显式: 缺失的 map unit 归入 slice group 0
+            for (slot, &id) in map.iter_mut().zip(&params.slice_group_id) {
+                *slot = id as u8;
+            }
+        }
+        _ => {}
+    }
+    map
+}
+
+/// 盒出 (类型 3): 从图像中心螺旋向外, 前 `units_in_group0` 个 map unit 属于 slice group 0
+fn fill_box_out(
+    map: &mut [u8],
+    width: usize,
+    height: usize,
+    change_direction_flag: bool,
+    units_in_group0: usize,
+) {
+    map.fill(1);
+    let dir = i64::from(change_direction_flag);
+    let (w, h) = (width as i64, height as i64);
+    let mut x = (w - dir) / 2;
+    let mut y = (h - dir) / 2;
+    let (mut left, mut top) = (x, y);
+    let (mut right, mut bottom) = (x, y);
+    let (mut x_dir, mut y_dir) = (dir - 1, dir);
+
+    let mut k = 0usize;
+    // 每个 map unit 至多经过四次边界扩展, 限制步数以防参数异常时死循环
+    let max_steps = map.len().saturating_mul(4) + 4;
+    for _ in 0..max_steps {
+        if k >= units_in_group0 {
+            break;
+        }
+        let idx = (y * w + x) as usize;
+        let vacant = map[idx] == 1;
+        if vacant {
+            map[idx] = 0;
+            k += 1;
+        }
+        if x_dir == -1 && x == left {
+            left = (left - 1).max(0);
+            x = left;
+            x_dir = 0;
+            y_dir = 2 * dir - 1;
+        } else if x_dir == 1 && x == right {
+            right = (right + 1).min(w - 1);
+            x = right;
+            x_dir = 0;
+            y_dir = 1 - 2 * dir;
+        } else if y_dir == -1 && y == top {
+            top = (top - 1).max(0);
+            y = top;
+            x_dir = 1 - 2 * dir;
+            y_dir = 0;
+        } else if y_dir == 1 && y == bottom {
+            bottom = (bottom + 1).min(h - 1);
+            y = bottom;
+            x_dir = 2 * dir - 1;
+            y_dir = 0;
+        } else {
+            x += x_dir;
+            y += y_dir;
+        }
+    }
+}
+
+/// 生成宏块到 slice group 的映射 (8.2.2.8)
+///
+/// `mb_height` 为帧高度 (宏块数). 非 frame_mbs_only 序列的 map unit 高度为帧高度的一半:
+/// 场图像逐宏块直接映射, MBAFF 帧每个宏块对对应一个 map unit,
+/// 非 MBAFF 帧的一个 map unit 覆盖上下两个宏块.
+pub(super) fn build_mb_to_slice_group_map(
+    params: &SliceGroupParams,
+    mb_width: usize,
+    mb_height: usize,
+    frame_mbs_only: bool,
+    mb_adaptive_frame_field: bool,
+    field_pic: bool,
+    slice_group_change_cycle: u32,
+) -> Vec<u8> {
+    let map_unit_height = if frame_mbs_only {
+        mb_height
+    } else {
+        mb_height.div_ceil(2)
+    };
+    let unit_map = build_map_unit_to_slice_group_map(
+        params,
+        mb_width,
+        map_unit_height,
+        slice_group_change_cycle,
+    );
+    if frame_mbs_only || field_pic {
+        return unit_map;
+    }
+    if mb_adaptive_frame_field {
+        return (0..mb_width * mb_height).map(|i| unit_map[i / 2]).collect();
+    }
+    (0..mb_width * mb_height)
+        .map(|i| unit_map[(i / (2 * mb_width)) * mb_width + i % mb_width])
+        .collect()
+}
+
+/// slice 内宏块地址迭代器
+///
+/// 未启用 FMO 时按光栅顺序输出 `[first, total)`, 否则只输出与首宏块同一 slice group 的宏块.
+pub(super) struct SliceMbAddrs {
+    next: usize,
+    total: usize,
+    map: Arc<Vec<u8>>,
+}
+
+impl Iterator for SliceMbAddrs {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.next >= self.total {
+            return None;
+        }
+        let cur = self.next;
+        self.next = match self.map.get(cur) {
+            Some(&group) => (cur + 1..self.total)
+                .find(|&idx| self.map.get(idx) == Some(&group))
+                .unwrap_or(self.total),
+            None => cur + 1,
+        };
+        Some(cur)
+    }
+}
+
+impl H264Decoder {
+    /// 按当前 PPS 与 slice header 更新宏块到 slice group 的映射
+    pub(super) fn update_slice_group_map(&mut self, header: &SliceHeader) {
+        let (Some(pps), Some(sps)) = (self.pps.as_ref(), self.sps.as_ref()) else {
+            return;
+        };
+        if !pps.slice_groups.is_enabled() {
+            if !self.mb_slice_group_map.is_empty() {
+                self.mb_slice_group_map = Arc::new(Vec::new());
+            }
+            return;
+        }
+        self.mb_slice_group_map = Arc::new(build_mb_to_slice_group_map(
+            &pps.slice_groups,
+            self.mb_width,
+            self.mb_height,
+            sps.frame_mbs_only,
+            sps.mb_adaptive_frame_field,
+            header.field_pic,
+            header.slice_group_change_cycle,
+        ));
+    }
+
+    /// 从 `first` 开始按 slice group 顺序遍历当前 slice 的宏块地址
+    pub(super) fn slice_mb_addrs(&self, first: usize, total: usize) -> SliceMbAddrs {
+        SliceMbAddrs {
+            next: first,
+            total,
+            map: Arc::clone(&self.mb_slice_group_map),
+        }
+    }
+}
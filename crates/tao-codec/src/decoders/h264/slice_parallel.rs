@@ -0,0 +1,361 @@
+use super::*;
+
+// ============================================================
+// Slice 级并行解码
+// ============================================================
+
+/// 排队等待并行解码的 slice
+pub(super) struct SliceJob {
+    rbsp: Vec<u8>,
+    header: SliceHeader,
+    /// 宏块范围是否已在主实例上标记 (下一个 slice 入队时确定)
+    range_marked: bool,
+}
+
+/// 按宏块复制 `[first, end)` 范围内的块数据, `blocks` 为每个宏块单边的块数
+fn copy_mb_blocks<T: Copy>(
+    dst: &mut [T],
+    src: &[T],
+    mb_width: usize,
+    blocks: usize,
+    first: usize,
+    end: usize,
+) {
+    if blocks == 1 {
+        dst[first..end].copy_from_slice(&src[first..end]);
+        return;
+    }
+    let stride = mb_width * blocks;
+    for mb_idx in first..end {
+        let x0 = (mb_idx % mb_width) * blocks;
+        let y0 = (mb_idx / mb_width) * blocks;
+        for row in y0..y0 + blocks {
+            let start = row * stride + x0;
+            dst[start..start + blocks].copy_from_slice(&src[start..start + blocks]);
+        }
+    }
+}
+
+impl H264Decoder {
+    /// 解析 slice header 并加入并行解码批次
+    ///
+    /// 逐 slice 状态 (POC、去块参数、参数集激活等) 仍按码流顺序在主实例上更新,
+    /// 仅宏块数据的解码延迟到 `decode_slice_batch` 中并行执行.
+    pub(super) fn queue_slice(&mut self, nalu: &NalUnit, batch: &mut Vec<SliceJob>) {
+        let Some(first_mb) = self.parse_slice_first_mb(nalu) else {
+            self.decode_slice_batch(batch);
+            self.decode_slice(nalu);
+            return;
+        };
+        // FMO 的 slice 宏块不连续, 无法按 first_mb 划分宏块范围, 回退为顺序解码
+        if self.slice_uses_slice_groups(nalu) {
+            self.decode_slice_batch(batch);
+            self.decode_slice(nalu);
+            return;
+        }
+        // 批次内 slice 须按 first_mb 递增, 以便确定各自的宏块范围
+        if batch
+            .last()
+            .is_some_and(|prev| prev.header.first_mb >= first_mb)
+        {
+            self.decode_slice_batch(batch);
+        }
+        if let Some(prev) = batch.last_mut()
+            && !prev.range_marked
+        {
+            // 先标记上一个 slice 的宏块范围, 使后续 slice 的邻居可用性判断与顺序解码一致
+            let total_mbs = self.mb_width * self.mb_height;
+            let start = (prev.header.first_mb as usize).min(total_mbs);
+            let end = (first_mb as usize).min(total_mbs);
+            for mb_idx in start..end {
+                self.mark_mb_slice_first_mb(mb_idx, prev.header.first_mb);
+            }
+            prev.range_marked = true;
+        }
+
+        let Some((rbsp, header)) = self.prepare_slice(nalu) else {
+            return;
+        };
+        if self.active_pps_id != Some(header.pps_id) {
+            // 参数集切换可能重置宏块状态, 需先完成已排队的 slice
+            self.decode_slice_batch(batch);
+            if let Err(err) = self.activate_parameter_sets(header.pps_id) {
+                self.record_malformed_nal_drop("slice_activate_parameter_sets", &err);
+                return;
+            }
+        }
+        batch.push(SliceJob {
+            rbsp,
+            header,
+            range_marked: false,
+        });
+    }
+
+    /// slice 引用的 PPS 是否启用了多个 slice group
+    fn slice_uses_slice_groups(&self, nalu: &NalUnit) -> bool {
+        let mut br = BitReader::new(nalu.rbsp());
+        let pps_id = read_ue(&mut br)
+            .and_then(|_| read_ue(&mut br))
+            .and_then(|_| read_ue(&mut br));
+        pps_id
+            .ok()
+            .and_then(|id| self.pps_map.get(&id))
+            .is_some_and(|pps| pps.slice_groups.is_enabled())
+    }
+
+    /// 并行解码批次中的 slice, 并将各工作实例的宏块范围合并回主实例
+    ///
+    /// 除最后一个 slice 由主实例解码外, 其余 slice 按连续分组交给至多
+    /// `decode_threads - 1` 个工作实例, 每个工作实例依次解码组内的全部 slice.
+    pub(super) fn decode_slice_batch(&mut self, batch: &mut Vec<SliceJob>) {
+        let Some(last) = batch.pop() else {
+            return;
+        };
+        if batch.is_empty() {
+            self.decode_slice_data(&last.rbsp, &last.header);
+            return;
+        }
+
+        let spawned = self.decode_threads.saturating_sub(1).clamp(1, batch.len());
+        let chunk_size = batch.len().div_ceil(spawned);
+        let mut workers: Vec<H264Decoder> = batch
+            .chunks(chunk_size)
+            .map(|_| self.fork_slice_worker())
+            .collect();
+        std::thread::scope(|scope| {
+            for (worker, chunk) in workers.iter_mut().zip(batch.chunks(chunk_size)) {
+                scope.spawn(move || {
+                    for job in chunk {
+                        worker.decode_slice_data(&job.rbsp, &job.header);
+                    }
+                });
+            }
+            self.decode_slice_data(&last.rbsp, &last.header);
+        });
+
+        let mut end = last.header.first_mb as usize;
+        for (worker, chunk) in workers.iter().zip(batch.chunks(chunk_size)).rev() {
+            let first = chunk[0].header.first_mb as usize;
+            self.merge_slice_worker(worker, first, end);
+            end = first;
+        }
+        batch.clear();
+    }
+
+    /// 复制当前解码状态作为 slice 工作实例, 不含输出相关的帧队列
+    pub(super) fn fork_slice_worker(&mut self) -> H264Decoder {
+        let output_queue = std::mem::take(&mut self.output_queue);
+        let reorder_buffer = std::mem::take(&mut self.reorder_buffer);
+        let mut worker = self.clone();
+        self.output_queue = output_queue;
+        self.reorder_buffer = reorder_buffer;
+        // 统计计数只记录工作实例内的增量, 合并时累加
+        worker.missing_reference_fallbacks = 0;
+        worker.ref_idx_oob_count = 0;
+        worker.mvd_overflow_count = 0;
+        worker.malformed_nal_drops = 0;
+        worker
+    }
+
+    /// 合并工作实例在 `[first, end)` 宏块范围内的重建结果与语法缓存
+    fn merge_slice_worker(&mut self, worker: &H264Decoder, first: usize, end: usize) {
+        let total_mbs = self.mb_width * self.mb_height;
+        let end = end.min(total_mbs);
+        if first >= end {
+            return;
+        }
+        let w = self.mb_width;
+
+        copy_mb_blocks(&mut self.ref_y, &worker.ref_y, w, 16, first, end);
+        copy_mb_blocks(&mut self.ref_u, &worker.ref_u, w, 8, first, end);
+        copy_mb_blocks(&mut self.ref_v, &worker.ref_v, w, 8, first, end);
+
+        copy_mb_blocks(&mut self.mb_types, &worker.mb_types, w, 1, first, end);
+        copy_mb_blocks(
+            &mut self.mb_skip_flags,
+            &worker.mb_skip_flags,
+            w,
+            1,
+            first,
+            end,
+        );
+        copy_mb_blocks(&mut self.mb_qp, &worker.mb_qp, w, 1, first, end);
+        copy_mb_blocks(&mut self.mb_cbp, &worker.mb_cbp, w, 1, first, end);
+        copy_mb_blocks(&mut self.mb_cbp_ctx, &worker.mb_cbp_ctx, w, 1, first, end);
+        copy_mb_blocks(
+            &mut self.chroma_pred_modes,
+            &worker.chroma_pred_modes,
+            w,
+            1,
+            first,
+            end,
+        );
+        copy_mb_blocks(
+            &mut self.transform_8x8_flags,
+            &worker.transform_8x8_flags,
+            w,
+            1,
+            first,
+            end,
+        );
+        copy_mb_blocks(&mut self.cbf_luma_dc, &worker.cbf_luma_dc, w, 1, first, end);
+        copy_mb_blocks(
+            &mut self.cbf_chroma_dc_u,
+            &worker.cbf_chroma_dc_u,
+            w,
+            1,
+            first,
+            end,
+        );
+        copy_mb_blocks(
+            &mut self.cbf_chroma_dc_v,
+            &worker.cbf_chroma_dc_v,
+            w,
+            1,
+            first,
+            end,
+        );
+        copy_mb_blocks(&mut self.mv_l0_x, &worker.mv_l0_x, w, 1, first, end);
+        copy_mb_blocks(&mut self.mv_l0_y, &worker.mv_l0_y, w, 1, first, end);
+        copy_mb_blocks(&mut self.ref_idx_l0, &worker.ref_idx_l0, w, 1, first, end);
+        copy_mb_blocks(&mut self.mv_l1_x, &worker.mv_l1_x, w, 1, first, end);
+        copy_mb_blocks(&mut self.mv_l1_y, &worker.mv_l1_y, w, 1, first, end);
+        copy_mb_blocks(&mut self.ref_idx_l1, &worker.ref_idx_l1, w, 1, first, end);
+        copy_mb_blocks(
+            &mut self.mb_slice_first_mb,
+            &worker.mb_slice_first_mb,
+            w,
+            1,
+            first,
+            end,
+        );
+
+        copy_mb_blocks(
+            &mut self.cbf_luma_8x8,
+            &worker.cbf_luma_8x8,
+            w,
+            2,
+            first,
+            end,
+        );
+        copy_mb_blocks(
+            &mut self.cbf_chroma_u,
+            &worker.cbf_chroma_u,
+            w,
+            2,
+            first,
+            end,
+        );
+        copy_mb_blocks(
+            &mut self.cbf_chroma_v,
+            &worker.cbf_chroma_v,
+            w,
+            2,
+            first,
+            end,
+        );
+        copy_mb_blocks(
+            &mut self.nz_count_chroma_u,
+            &worker.nz_count_chroma_u,
+            w,
+            2,
+            first,
+            end,
+        );
+        copy_mb_blocks(
+            &mut self.nz_count_chroma_v,
+            &worker.nz_count_chroma_v,
+            w,
+            2,
+            first,
+            end,
+        );
+
+        copy_mb_blocks(&mut self.cbf_luma, &worker.cbf_luma, w, 4, first, end);
+        copy_mb_blocks(&mut self.i4x4_modes, &worker.i4x4_modes, w, 4, first, end);
+        copy_mb_blocks(
+            &mut self.nz_count_luma,
+            &worker.nz_count_luma,
+            w,
+            4,
+            first,
+            end,
+        );
+        copy_mb_blocks(&mut self.mv_l0_x_4x4, &worker.mv_l0_x_4x4, w, 4, first, end);
+        copy_mb_blocks(&mut self.mv_l0_y_4x4, &worker.mv_l0_y_4x4, w, 4, first, end);
+        copy_mb_blocks(
+            &mut self.ref_idx_l0_4x4,
+            &worker.ref_idx_l0_4x4,
+            w,
+            4,
+            first,
+            end,
+        );
+        copy_mb_blocks(&mut self.mv_l1_x_4x4, &worker.mv_l1_x_4x4, w, 4, first, end);
+        copy_mb_blocks(&mut self.mv_l1_y_4x4, &worker.mv_l1_y_4x4, w, 4, first, end);
+        copy_mb_blocks(
+            &mut self.ref_idx_l1_4x4,
+            &worker.ref_idx_l1_4x4,
+            w,
+            4,
+            first,
+            end,
+        );
+        copy_mb_blocks(
+            &mut self.direct_4x4_flags,
+            &worker.direct_4x4_flags,
+            w,
+            4,
+            first,
+            end,
+        );
+        copy_mb_blocks(
+            &mut self.mvd_l0_x_4x4,
+            &worker.mvd_l0_x_4x4,
+            w,
+            4,
+            first,
+            end,
+        );
+        copy_mb_blocks(
+            &mut self.mvd_l0_y_4x4,
+            &worker.mvd_l0_y_4x4,
+            w,
+            4,
+            first,
+            end,
+        );
+        copy_mb_blocks(
+            &mut self.mvd_l1_x_4x4,
+            &worker.mvd_l1_x_4x4,
+            w,
+            4,
+            first,
+            end,
+        );
+        copy_mb_blocks(
+            &mut self.mvd_l1_y_4x4,
+            &worker.mvd_l1_y_4x4,
+            w,
+            4,
+            first,
+            end,
+        );
+
+        self.missing_reference_fallbacks += worker.missing_reference_fallbacks;
+        self.ref_idx_oob_count += worker.ref_idx_oob_count;
+        self.mvd_overflow_count += worker.mvd_overflow_count;
+        self.malformed_nal_drops = self
+            .malformed_nal_drops
+            .saturating_add(worker.malformed_nal_drops);
+        if self.missing_reference_fallback_error.is_none() {
+            self.missing_reference_fallback_error = worker.missing_reference_fallback_error.clone();
+        }
+        if self.ref_idx_oob_error.is_none() {
+            self.ref_idx_oob_error = worker.ref_idx_oob_error.clone();
+        }
+        if self.mvd_overflow_error.is_none() {
+            self.mvd_overflow_error = worker.mvd_overflow_error.clone();
+        }
+    }
+}
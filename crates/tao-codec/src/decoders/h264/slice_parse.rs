@@ -172,6 +172,17 @@ impl H264Decoder {
             }
         }
 
+        // slice_group_change_cycle: Ceil(Log2(PicSizeInMapUnits ÷ SliceGroupChangeRate + 1)) 位
+        let mut slice_group_change_cycle = 0u32;
+        if pps.slice_groups.has_change_cycle() {
+            let pic_size = sps
+                .pic_width_in_mbs
+                .saturating_mul(sps.pic_height_in_map_units);
+            let change_rate = pps.slice_groups.change_rate_minus1 + 1;
+            let bits = slice_group::change_cycle_bits(pic_size, change_rate);
+            slice_group_change_cycle = br.read_bits(bits)?;
+        }
+
         let mut data_bit_offset = br.bits_read();
         if pps.entropy_coding_mode == 1 {
             // H.264 spec 7.3.2.8: while( !byte_aligned() ) cabac_alignment_one_bit
@@ -197,6 +208,7 @@ impl H264Decoder {
             pps_id,
             slice_type,
             frame_num,
+            field_pic,
             slice_qp,
             cabac_init_idc,
             direct_spatial_mv_pred_flag,
@@ -220,6 +232,7 @@ impl H264Decoder {
             disable_deblocking_filter_idc,
             slice_alpha_c0_offset_div2,
             slice_beta_offset_div2,
+            slice_group_change_cycle,
             dec_ref_pic_marking,
         })
     }
@@ -327,13 +340,14 @@ impl H264Decoder {
                     frame_num_offset += max_frame_num;
                 }
 
+                // 场编码时顶场与底场的 POC 均为 tempPicOrderCnt, 与帧相同
                 let mut poc = 2 * (frame_num_offset + frame_num);
                 if header.nal_ref_idc == 0 {
                     poc -= 1;
                 }
-                if header.nal_ref_idc != 0 {
-                    self.prev_frame_num_offset_type2 = frame_num_offset;
-                }
+                // prevFrameNumOffset 取解码顺序上的前一图像 (含非参考图像, 8.2.1.3),
+                // 否则非参考图像处 frame_num 回绕后, 后续图像会丢失 MaxFrameNum 偏移
+                self.prev_frame_num_offset_type2 = frame_num_offset;
                 poc
             }
             _ => header.frame_num as i32,
@@ -0,0 +1,413 @@
+//! 随机变异回归测试: 对 slice header / slice data / avcC 输入做确定性变异,
+//! 要求解码器只能成功或返回错误, 不允许 panic.
+
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use tao_core::{PixelFormat, Rational, TaoError};
+
+use crate::codec_id::CodecId;
+use crate::codec_parameters::{CodecParameters, CodecParamsType, VideoCodecParams};
+use crate::decoder::Decoder;
+use crate::packet::Packet;
+use crate::parsers::h264::parse_avcc_config;
+
+use super::super::{H264Decoder, NalUnit, PredWeightL0, RefPicListMod, SliceHeader};
+
+use super::helpers::*;
+
+const MB_WIDTH: u32 = 3;
+const MB_HEIGHT: u32 = 2;
+
+/// 确定性伪随机数发生器 (xorshift64*), 保证失败用例可按种子复现
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next_u64() as u8).collect()
+    }
+}
+
+/// 对输入做 1~4 次随机变异: 翻转比特、改写字节、插入/删除字节、截断
+fn mutate(rng: &mut Rng, input: &[u8]) -> Vec<u8> {
+    let mut data = input.to_vec();
+    for _ in 0..=rng.below(4) {
+        if data.is_empty() {
+            data.push(rng.next_u64() as u8);
+            continue;
+        }
+        let pos = rng.below(data.len());
+        match rng.below(5) {
+            0 => data[pos] ^= 1 << rng.below(8),
+            1 => data[pos] = rng.next_u64() as u8,
+            2 => data.insert(pos, rng.next_u64() as u8),
+            3 => {
+                data.remove(pos);
+            }
+            _ => data.truncate(pos.max(1)),
+        }
+    }
+    data
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02X}")).collect()
+}
+
+/// 执行单个用例, panic 时附带标签与输入十六进制, 便于加入回归语料
+fn run_case(label: &str, input: &[u8], f: impl FnOnce()) {
+    if catch_unwind(AssertUnwindSafe(f)).is_err() {
+        panic!("{label} 触发 panic, input={}", to_hex(input));
+    }
+}
+
+fn build_avcc_record(entropy: bool) -> Vec<u8> {
+    let sps = build_sps_nalu(0, MB_WIDTH * 16, MB_HEIGHT * 16).data;
+    let pps = build_pps_nalu(0, 0, entropy, 0).data;
+    let mut avcc = vec![1, 66, 0, 30, 0xFF, 0xE1];
+    avcc.extend_from_slice(&(sps.len() as u16).to_be_bytes());
+    avcc.extend_from_slice(&sps);
+    avcc.push(1);
+    avcc.extend_from_slice(&(pps.len() as u16).to_be_bytes());
+    avcc.extend_from_slice(&pps);
+    avcc
+}
+
+fn open_decoder(entropy: bool) -> Box<dyn Decoder> {
+    let mut dec = H264Decoder::create().expect("创建 H264 解码器失败");
+    dec.open(&CodecParameters {
+        codec_id: CodecId::H264,
+        extra_data: build_avcc_record(entropy),
+        bit_rate: 0,
+        params: CodecParamsType::Video(VideoCodecParams {
+            width: MB_WIDTH * 16,
+            height: MB_HEIGHT * 16,
+            pixel_format: PixelFormat::Yuv420p,
+            frame_rate: Rational::new(25, 1),
+            sample_aspect_ratio: Rational::new(1, 1),
+        }),
+    })
+    .expect("打开 H264 解码器失败");
+    dec
+}
+
+/// 构造 slice NAL: 合法 header + 随机 slice data
+fn build_slice_nal(rng: &mut Rng, slice_type: u32, idr: bool, frame_num: u32) -> Vec<u8> {
+    let mut bits = Vec::new();
+    write_ue(&mut bits, 0); // first_mb_in_slice
+    write_ue(&mut bits, slice_type + 5);
+    write_ue(&mut bits, 0); // pps_id
+    push_bits_fixed(&mut bits, frame_num, 4);
+    if idr {
+        write_ue(&mut bits, 0); // idr_pic_id
+    }
+    push_bits_fixed(&mut bits, frame_num * 2, 4); // pic_order_cnt_lsb
+    if slice_type == 1 {
+        bits.push(true); // direct_spatial_mv_pred_flag
+    }
+    if slice_type != 2 {
+        bits.push(true); // num_ref_idx_active_override_flag
+        write_ue(&mut bits, 1); // num_ref_idx_l0_active_minus1
+        if slice_type == 1 {
+            write_ue(&mut bits, 1); // num_ref_idx_l1_active_minus1
+        }
+        bits.push(false); // ref_pic_list_modification_flag_l0
+        if slice_type == 1 {
+            bits.push(false); // ref_pic_list_modification_flag_l1
+        }
+    }
+    if idr {
+        bits.push(false); // no_output_of_prior_pics_flag
+        bits.push(false); // long_term_reference_flag
+    } else {
+        bits.push(false); // adaptive_ref_pic_marking_mode_flag
+    }
+    write_se(&mut bits, 0); // slice_qp_delta
+    write_ue(&mut bits, 0); // disable_deblocking_filter_idc
+    write_se(&mut bits, 0); // slice_alpha_c0_offset_div2
+    write_se(&mut bits, 0); // slice_beta_offset_div2
+    let mut nal = vec![if idr { 0x65 } else { 0x41 }];
+    nal.extend(bits_to_bytes(&bits));
+    let payload_len = 4 + rng.below(96);
+    nal.extend(rng.bytes(payload_len));
+    nal
+}
+
+fn build_avcc_packet(nals: &[Vec<u8>]) -> Packet {
+    let mut data = Vec::new();
+    for nal in nals {
+        data.extend_from_slice(&(nal.len() as u32).to_be_bytes());
+        data.extend_from_slice(nal);
+    }
+    let mut packet = Packet::from_data(data);
+    packet.time_base = Rational::new(1, 25);
+    packet
+}
+
+/// 送入数据包并取空输出, 仅要求不 panic
+fn feed_packet(dec: &mut dyn Decoder, packet: &Packet) {
+    let _ = dec.send_packet(packet);
+    loop {
+        match dec.receive_frame() {
+            Ok(_) => {}
+            Err(TaoError::NeedMoreData | TaoError::Eof) => break,
+            Err(_) => break,
+        }
+    }
+}
+
+/// 构造 3x2 宏块、两帧参考的解码器, 供直接驱动 `decode_slice_data`
+fn build_slice_data_decoder(entropy_coding_mode: u8, transform_8x8: bool) -> H264Decoder {
+    let mut dec = build_test_decoder();
+    let mut sps = build_test_sps(0);
+    sps.width = MB_WIDTH * 16;
+    sps.height = MB_HEIGHT * 16;
+    sps.pic_width_in_mbs = MB_WIDTH;
+    sps.pic_height_in_map_units = MB_HEIGHT;
+    let mut pps = build_test_pps();
+    pps.entropy_coding_mode = entropy_coding_mode;
+    pps.transform_8x8_mode = transform_8x8;
+    dec.sps_map.insert(0, sps);
+    dec.pps_map.insert(0, pps);
+    dec.activate_parameter_sets(0).expect("激活测试参数集失败");
+    push_custom_reference(&mut dec, 0, 0, 60, None);
+    push_custom_reference(&mut dec, 1, 4, 120, None);
+    dec
+}
+
+/// 在规范允许的取值范围内随机化 slice header 字段, 覆盖 QP、参考列表、加权预测等分支
+fn random_slice_header(rng: &mut Rng, slice_type: u32) -> SliceHeader {
+    let mut header = build_test_slice_header(2, 1, false, Some(4));
+    header.slice_type = slice_type;
+    header.first_mb = rng.below((MB_WIDTH * MB_HEIGHT) as usize) as u32;
+    header.slice_qp = rng.below(52) as i32;
+    header.cabac_init_idc = rng.below(3) as u8;
+    header.direct_spatial_mv_pred_flag = rng.below(2) == 0;
+    header.num_ref_idx_l0 = 1 + rng.below(32) as u32;
+    header.num_ref_idx_l1 = 1 + rng.below(32) as u32;
+    for _ in 0..rng.below(3) {
+        let value = rng.below(8) as u32;
+        header.ref_pic_list_mod_l0.push(match rng.below(3) {
+            0 => RefPicListMod::ShortTermSub {
+                abs_diff_pic_num_minus1: value,
+            },
+            1 => RefPicListMod::ShortTermAdd {
+                abs_diff_pic_num_minus1: value,
+            },
+            _ => RefPicListMod::LongTerm {
+                long_term_pic_num: value,
+            },
+        });
+    }
+    if rng.below(2) == 0 {
+        header.luma_log2_weight_denom = rng.below(8) as u8;
+        header.chroma_log2_weight_denom = rng.below(8) as u8;
+        let mut weight = || rng.below(256) as i32 - 128;
+        header.l0_weights = (0..header.num_ref_idx_l0)
+            .map(|_| PredWeightL0 {
+                luma_weight: weight(),
+                luma_offset: weight(),
+                chroma_weight: [weight(), weight()],
+                chroma_offset: [weight(), weight()],
+            })
+            .collect();
+        header.l1_weights = header.l0_weights.clone();
+    }
+    header.disable_deblocking_filter_idc = rng.below(3) as u32;
+    header.slice_alpha_c0_offset_div2 = rng.below(13) as i32 - 6;
+    header.slice_beta_offset_div2 = rng.below(13) as i32 - 6;
+    header
+}
+
+/// 以单个种子生成随机 header 与 slice data 并解码, 种子即可完整复现用例
+fn run_slice_data_seed(entropy_coding_mode: u8, transform_8x8: bool, slice_type: u32, seed: u64) {
+    let mut rng = Rng::new(seed);
+    let header = random_slice_header(&mut rng, slice_type);
+    let len = 1 + rng.below(160);
+    let rbsp = rng.bytes(len);
+    let label = format!(
+        "decode_slice_data(entropy={entropy_coding_mode}, t8x8={transform_8x8}, slice_type={slice_type}, seed=0x{seed:X})"
+    );
+    run_case(&label, &rbsp, || {
+        let mut dec = build_slice_data_decoder(entropy_coding_mode, transform_8x8);
+        dec.decode_slice_data(&rbsp, &header);
+    });
+}
+
+#[test]
+fn test_fuzz_decode_slice_data_random_payload() {
+    let mut rng = Rng::new(0x5EED_0001);
+    for entropy_coding_mode in [0u8, 1] {
+        for transform_8x8 in [false, true] {
+            for slice_type in [0u32, 1, 2] {
+                for _ in 0..120 {
+                    let seed = rng.next_u64();
+                    run_slice_data_seed(entropy_coding_mode, transform_8x8, slice_type, seed);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_fuzz_parse_slice_header_mutations() {
+    let mut rng = Rng::new(0x5EED_0002);
+    let mut dec = build_test_decoder();
+    dec.sps_map.insert(0, build_test_sps(0));
+    for entropy_coding_mode in [0u8, 1] {
+        let mut pps = build_test_pps();
+        pps.entropy_coding_mode = entropy_coding_mode;
+        dec.pps_map.insert(0, pps);
+        let seeds = [
+            build_p_slice_header_rbsp(0, 1, 2, 0, 0, 1),
+            build_p_slice_header_rbsp_with_l0_reorder(0, 1, 2, 0, 0),
+            build_slice_nal(&mut rng, 1, false, 1)[1..].to_vec(),
+            build_slice_nal(&mut rng, 2, true, 0)[1..].to_vec(),
+        ];
+        for seed in &seeds {
+            for _ in 0..8000 {
+                let rbsp = mutate(&mut rng, seed);
+                for nal_header in [0x41u8, 0x65] {
+                    let nalu = NalUnit::parse(&[nal_header]).expect("测试构造 slice NAL 失败");
+                    run_case("parse_slice_header", &rbsp, || {
+                        if let Ok(header) = dec.parse_slice_header(&rbsp, &nalu) {
+                            let mut slice_dec =
+                                build_slice_data_decoder(entropy_coding_mode, false);
+                            slice_dec.decode_slice_data(&rbsp, &header);
+                        }
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_fuzz_parse_avcc_config_mutations() {
+    let mut rng = Rng::new(0x5EED_0003);
+    for entropy in [false, true] {
+        let seed = build_avcc_record(entropy);
+        for _ in 0..50000 {
+            let avcc = mutate(&mut rng, &seed);
+            run_case("parse_avcc_config", &avcc, || {
+                let _ = parse_avcc_config(&avcc);
+            });
+        }
+    }
+}
+
+#[test]
+fn test_fuzz_decoder_mutated_slices() {
+    let mut rng = Rng::new(0x5EED_0004);
+    for entropy in [false, true] {
+        for _ in 0..3000 {
+            let mut dec = open_decoder(entropy);
+            // 带内参数集: 一半用例变异 SPS/PPS, 覆盖参数集解析与缓冲区重建
+            let mut sps = build_sps_nalu(0, MB_WIDTH * 16, MB_HEIGHT * 16).data;
+            let mut pps = build_pps_nalu(0, 0, entropy, 0).data;
+            if rng.below(2) == 0 {
+                sps = mutate(&mut rng, &sps);
+                pps = mutate(&mut rng, &pps);
+            }
+            let idr = build_slice_nal(&mut rng, 2, true, 0);
+            let idr = mutate(&mut rng, &idr);
+            let packet = build_avcc_packet(&[sps, pps, idr]);
+            run_case("decoder(idr)", packet.data.as_ref(), || {
+                feed_packet(dec.as_mut(), &packet)
+            });
+            for frame_num in 1..4 {
+                let slice_type = rng.below(3) as u32;
+                let nal = build_slice_nal(&mut rng, slice_type, false, frame_num);
+                let packet = build_avcc_packet(&[mutate(&mut rng, &nal)]);
+                run_case("decoder(slice)", packet.data.as_ref(), || {
+                    feed_packet(dec.as_mut(), &packet)
+                });
+            }
+            run_case("decoder(flush)", &[], || {
+                feed_packet(dec.as_mut(), &Packet::empty())
+            });
+        }
+    }
+}
+
+#[test]
+fn test_fuzz_regression_cavlc_extreme_mvd() {
+    let mut bits = Vec::new();
+    for mvd_x in [4, i32::MAX] {
+        write_ue(&mut bits, 0); // mb_skip_run
+        write_ue(&mut bits, 0); // P_L0_16x16
+        write_se(&mut bits, mvd_x);
+        write_se(&mut bits, 0);
+        write_ue(&mut bits, 0); // coded_block_pattern=0
+    }
+    let rbsp = bits_to_bytes(&bits);
+    let mut dec = build_slice_data_decoder(0, false);
+    let header = build_test_slice_header(2, 1, false, Some(4));
+    run_case("cavlc_extreme_mvd", &rbsp, || {
+        dec.decode_slice_data(&rbsp, &header)
+    });
+}
+
+#[test]
+fn test_fuzz_regression_cavlc_oversized_dc_level() {
+    let mut bits = Vec::new();
+    write_ue(&mut bits, 3); // I_16x16, pred_mode=DC, cbp=0
+    write_ue(&mut bits, 0); // intra_chroma_pred_mode=DC
+    write_se(&mut bits, 0); // mb_qp_delta
+    bits.extend([false, false, false, true, false, true]); // coeff_token: total_coeff=1, t1=0
+    bits.extend(std::iter::repeat_n(false, 28)); // level_prefix=28
+    bits.push(true);
+    push_bits_fixed(&mut bits, 0x1FF_FFFF, 25); // level_suffix
+    bits.push(true); // total_zeros=0
+    let rbsp = bits_to_bytes(&bits);
+    let mut dec = build_slice_data_decoder(0, false);
+    let mut header = build_test_slice_header(0, 1, true, Some(0));
+    header.slice_type = 2;
+    header.slice_qp = 51;
+    run_case("cavlc_oversized_dc_level", &rbsp, || {
+        dec.decode_slice_data(&rbsp, &header)
+    });
+}
+
+/// 变异用例中触发过 panic 的完整 AVCC 数据包 (带内 SPS/PPS + IDR slice)
+const REGRESSION_PACKETS: &[&str] = &[
+    // VUI num_units_in_tick * 2 溢出
+    "0000000867F042001EF29A8800000006D828CEA43C80000000176788840F3E628A950D79E3D2DE6408CCB1AFE3F5021751",
+];
+
+fn hex_to_bytes(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("回归语料十六进制非法"))
+        .collect()
+}
+
+#[test]
+fn test_fuzz_regression_packets() {
+    for entropy in [false, true] {
+        for hex in REGRESSION_PACKETS {
+            let data = hex_to_bytes(hex);
+            let mut dec = open_decoder(entropy);
+            let mut packet = Packet::from_data(data.clone());
+            packet.time_base = Rational::new(1, 25);
+            run_case("regression_packet", &data, || {
+                feed_packet(dec.as_mut(), &packet);
+                feed_packet(dec.as_mut(), &Packet::empty());
+            });
+        }
+    }
+}
@@ -29,6 +29,7 @@ pub fn build_test_pps() -> Pps {
         transform_8x8_mode: false,
         scaling_list_4x4: None,
         scaling_list_8x8: None,
+        slice_groups: Default::default(),
     }
 }
 
@@ -46,11 +47,14 @@ pub fn build_test_sps(sps_id: u32) -> Sps {
         width: 16,
         height: 16,
         frame_mbs_only: true,
+        mb_adaptive_frame_field: false,
         direct_8x8_inference_flag: true,
         vui_present: false,
         fps: None,
         max_num_reorder_frames: None,
         max_dec_frame_buffering: None,
+        color_space: Default::default(),
+        color_range: Default::default(),
         sar: Rational::new(1, 1),
         pic_width_in_mbs: 1,
         pic_height_in_map_units: 1,
@@ -88,6 +92,7 @@ pub fn build_test_slice_header(
         pps_id: 0,
         slice_type: 0,
         frame_num,
+        field_pic: false,
         slice_qp: 26,
         cabac_init_idc: 0,
         direct_spatial_mv_pred_flag: true,
@@ -111,6 +116,7 @@ pub fn build_test_slice_header(
         disable_deblocking_filter_idc: 0,
         slice_alpha_c0_offset_div2: 0,
         slice_beta_offset_div2: 0,
+        slice_group_change_cycle: 0,
         dec_ref_pic_marking: DecRefPicMarking::default(),
     }
 }
@@ -173,6 +179,8 @@ pub fn build_test_decoder() -> H264Decoder {
         mvd_l1_x_4x4: Vec::new(),
         mvd_l1_y_4x4: Vec::new(),
         mb_slice_first_mb: Vec::new(),
+        mb_slice_group_map: Arc::new(Vec::new()),
+        slice_deblock_params: Vec::new(),
         last_slice_type: 0,
         last_frame_num: 0,
         last_nal_ref_idc: 0,
@@ -208,6 +216,9 @@ pub fn build_test_decoder() -> H264Decoder {
         output_queue: VecDeque::new(),
         reorder_buffer: Vec::new(),
         reorder_depth: 2,
+        frame_pool: crate::frame_pool::FramePool::new(H264Decoder::frame_pool_size(2)),
+        frame_pool_shared: false,
+        decode_threads: 1,
         decode_order_counter: 0,
         pending_frame: None,
         opened: true,
@@ -245,19 +256,19 @@ pub fn push_dummy_reference_with_long_term(
         y: Arc::new(vec![0u8; dec.ref_y.len()]),
         u: Arc::new(vec![0u8; dec.ref_u.len()]),
         v: Arc::new(vec![0u8; dec.ref_v.len()]),
-        mv_l0_x: vec![0i16; total_mb],
-        mv_l0_y: vec![0i16; total_mb],
-        ref_idx_l0: vec![-1i8; total_mb],
-        mv_l1_x: vec![0i16; total_mb],
-        mv_l1_y: vec![0i16; total_mb],
-        ref_idx_l1: vec![-1i8; total_mb],
-        mv_l0_x_4x4: vec![0i16; total_4x4],
-        mv_l0_y_4x4: vec![0i16; total_4x4],
-        ref_idx_l0_4x4: vec![-1i8; total_4x4],
-        mv_l1_x_4x4: vec![0i16; total_4x4],
-        mv_l1_y_4x4: vec![0i16; total_4x4],
-        ref_idx_l1_4x4: vec![-1i8; total_4x4],
-        mb_types: vec![0u8; total_mb],
+        mv_l0_x: Arc::new(vec![0i16; total_mb]),
+        mv_l0_y: Arc::new(vec![0i16; total_mb]),
+        ref_idx_l0: Arc::new(vec![-1i8; total_mb]),
+        mv_l1_x: Arc::new(vec![0i16; total_mb]),
+        mv_l1_y: Arc::new(vec![0i16; total_mb]),
+        ref_idx_l1: Arc::new(vec![-1i8; total_mb]),
+        mv_l0_x_4x4: Arc::new(vec![0i16; total_4x4]),
+        mv_l0_y_4x4: Arc::new(vec![0i16; total_4x4]),
+        ref_idx_l0_4x4: Arc::new(vec![-1i8; total_4x4]),
+        mv_l1_x_4x4: Arc::new(vec![0i16; total_4x4]),
+        mv_l1_y_4x4: Arc::new(vec![0i16; total_4x4]),
+        ref_idx_l1_4x4: Arc::new(vec![-1i8; total_4x4]),
+        mb_types: Arc::new(vec![0u8; total_mb]),
         ref_l0_poc: Vec::new(),
         ref_l1_poc: Vec::new(),
         frame_num,
@@ -279,19 +290,19 @@ pub fn push_custom_reference(
         y: Arc::new(vec![y_value; dec.ref_y.len()]),
         u: Arc::new(vec![128u8; dec.ref_u.len()]),
         v: Arc::new(vec![128u8; dec.ref_v.len()]),
-        mv_l0_x: vec![0i16; total_mb],
-        mv_l0_y: vec![0i16; total_mb],
-        ref_idx_l0: vec![-1i8; total_mb],
-        mv_l1_x: vec![0i16; total_mb],
-        mv_l1_y: vec![0i16; total_mb],
-        ref_idx_l1: vec![-1i8; total_mb],
-        mv_l0_x_4x4: vec![0i16; total_4x4],
-        mv_l0_y_4x4: vec![0i16; total_4x4],
-        ref_idx_l0_4x4: vec![-1i8; total_4x4],
-        mv_l1_x_4x4: vec![0i16; total_4x4],
-        mv_l1_y_4x4: vec![0i16; total_4x4],
-        ref_idx_l1_4x4: vec![-1i8; total_4x4],
-        mb_types: vec![0u8; total_mb],
+        mv_l0_x: Arc::new(vec![0i16; total_mb]),
+        mv_l0_y: Arc::new(vec![0i16; total_mb]),
+        ref_idx_l0: Arc::new(vec![-1i8; total_mb]),
+        mv_l1_x: Arc::new(vec![0i16; total_mb]),
+        mv_l1_y: Arc::new(vec![0i16; total_mb]),
+        ref_idx_l1: Arc::new(vec![-1i8; total_mb]),
+        mv_l0_x_4x4: Arc::new(vec![0i16; total_4x4]),
+        mv_l0_y_4x4: Arc::new(vec![0i16; total_4x4]),
+        ref_idx_l0_4x4: Arc::new(vec![-1i8; total_4x4]),
+        mv_l1_x_4x4: Arc::new(vec![0i16; total_4x4]),
+        mv_l1_y_4x4: Arc::new(vec![0i16; total_4x4]),
+        ref_idx_l1_4x4: Arc::new(vec![-1i8; total_4x4]),
+        mb_types: Arc::new(vec![0u8; total_mb]),
         ref_l0_poc: Vec::new(),
         ref_l1_poc: Vec::new(),
         frame_num,
@@ -334,19 +345,19 @@ pub fn push_custom_reference_with_l0_motion(
         y: Arc::new(vec![y_value; dec.ref_y.len()]),
         u: Arc::new(vec![128u8; dec.ref_u.len()]),
         v: Arc::new(vec![128u8; dec.ref_v.len()]),
-        mv_l0_x,
-        mv_l0_y,
-        ref_idx_l0,
-        mv_l1_x: vec![0i16; total_mb],
-        mv_l1_y: vec![0i16; total_mb],
-        ref_idx_l1: vec![-1i8; total_mb],
-        mv_l0_x_4x4,
-        mv_l0_y_4x4,
-        ref_idx_l0_4x4,
-        mv_l1_x_4x4: vec![0i16; total_4x4],
-        mv_l1_y_4x4: vec![0i16; total_4x4],
-        ref_idx_l1_4x4: vec![-1i8; total_4x4],
-        mb_types,
+        mv_l0_x: Arc::new(mv_l0_x),
+        mv_l0_y: Arc::new(mv_l0_y),
+        ref_idx_l0: Arc::new(ref_idx_l0),
+        mv_l1_x: Arc::new(vec![0i16; total_mb]),
+        mv_l1_y: Arc::new(vec![0i16; total_mb]),
+        ref_idx_l1: Arc::new(vec![-1i8; total_mb]),
+        mv_l0_x_4x4: Arc::new(mv_l0_x_4x4),
+        mv_l0_y_4x4: Arc::new(mv_l0_y_4x4),
+        ref_idx_l0_4x4: Arc::new(ref_idx_l0_4x4),
+        mv_l1_x_4x4: Arc::new(vec![0i16; total_4x4]),
+        mv_l1_y_4x4: Arc::new(vec![0i16; total_4x4]),
+        ref_idx_l1_4x4: Arc::new(vec![-1i8; total_4x4]),
+        mb_types: Arc::new(mb_types),
         ref_l0_poc: Vec::new(),
         ref_l1_poc: Vec::new(),
         frame_num,
@@ -390,19 +401,19 @@ pub fn push_custom_reference_with_l1_motion_and_ref_l1_poc(
         y: Arc::new(vec![y_value; dec.ref_y.len()]),
         u: Arc::new(vec![128u8; dec.ref_u.len()]),
         v: Arc::new(vec![128u8; dec.ref_v.len()]),
-        mv_l0_x: vec![0i16; total_mb],
-        mv_l0_y: vec![0i16; total_mb],
-        ref_idx_l0: vec![-1i8; total_mb],
-        mv_l1_x,
-        mv_l1_y,
-        ref_idx_l1,
-        mv_l0_x_4x4: vec![0i16; total_4x4],
-        mv_l0_y_4x4: vec![0i16; total_4x4],
-        ref_idx_l0_4x4: vec![-1i8; total_4x4],
-        mv_l1_x_4x4,
-        mv_l1_y_4x4,
-        ref_idx_l1_4x4,
-        mb_types,
+        mv_l0_x: Arc::new(vec![0i16; total_mb]),
+        mv_l0_y: Arc::new(vec![0i16; total_mb]),
+        ref_idx_l0: Arc::new(vec![-1i8; total_mb]),
+        mv_l1_x: Arc::new(mv_l1_x),
+        mv_l1_y: Arc::new(mv_l1_y),
+        ref_idx_l1: Arc::new(ref_idx_l1),
+        mv_l0_x_4x4: Arc::new(vec![0i16; total_4x4]),
+        mv_l0_y_4x4: Arc::new(vec![0i16; total_4x4]),
+        ref_idx_l0_4x4: Arc::new(vec![-1i8; total_4x4]),
+        mv_l1_x_4x4: Arc::new(mv_l1_x_4x4),
+        mv_l1_y_4x4: Arc::new(mv_l1_y_4x4),
+        ref_idx_l1_4x4: Arc::new(ref_idx_l1_4x4),
+        mb_types: Arc::new(mb_types),
         ref_l0_poc: Vec::new(),
         ref_l1_poc,
         frame_num,
@@ -429,19 +440,19 @@ pub fn push_horizontal_gradient_reference(
         y: Arc::new(y),
         u: Arc::new(vec![128u8; dec.ref_u.len()]),
         v: Arc::new(vec![128u8; dec.ref_v.len()]),
-        mv_l0_x: vec![0i16; total_mb],
-        mv_l0_y: vec![0i16; total_mb],
-        ref_idx_l0: vec![-1i8; total_mb],
-        mv_l1_x: vec![0i16; total_mb],
-        mv_l1_y: vec![0i16; total_mb],
-        ref_idx_l1: vec![-1i8; total_mb],
-        mv_l0_x_4x4: vec![0i16; total_4x4],
-        mv_l0_y_4x4: vec![0i16; total_4x4],
-        ref_idx_l0_4x4: vec![-1i8; total_4x4],
-        mv_l1_x_4x4: vec![0i16; total_4x4],
-        mv_l1_y_4x4: vec![0i16; total_4x4],
-        ref_idx_l1_4x4: vec![-1i8; total_4x4],
-        mb_types: vec![0u8; total_mb],
+        mv_l0_x: Arc::new(vec![0i16; total_mb]),
+        mv_l0_y: Arc::new(vec![0i16; total_mb]),
+        ref_idx_l0: Arc::new(vec![-1i8; total_mb]),
+        mv_l1_x: Arc::new(vec![0i16; total_mb]),
+        mv_l1_y: Arc::new(vec![0i16; total_mb]),
+        ref_idx_l1: Arc::new(vec![-1i8; total_mb]),
+        mv_l0_x_4x4: Arc::new(vec![0i16; total_4x4]),
+        mv_l0_y_4x4: Arc::new(vec![0i16; total_4x4]),
+        ref_idx_l0_4x4: Arc::new(vec![-1i8; total_4x4]),
+        mv_l1_x_4x4: Arc::new(vec![0i16; total_4x4]),
+        mv_l1_y_4x4: Arc::new(vec![0i16; total_4x4]),
+        ref_idx_l1_4x4: Arc::new(vec![-1i8; total_4x4]),
+        mb_types: Arc::new(vec![0u8; total_mb]),
         ref_l0_poc: Vec::new(),
         ref_l1_poc: Vec::new(),
         frame_num,
@@ -1,9 +1,12 @@
 mod decode;
 mod decode_b;
+mod fuzz;
 mod helpers;
 mod output;
 mod parameter_sets;
 mod prediction;
 mod reference;
 mod sei;
+mod slice_group;
 mod slice_header;
+mod slice_parallel;
@@ -195,9 +195,60 @@ fn test_drain_reorder_buffer_outputs_frames_by_poc_ascending() {
     while let Some(frame) = dec.output_queue.pop_front() {
         match frame {
             Frame::Video(vf) => pts_list.push(vf.pts),
-            Frame::Audio(_) => panic!("重排缓冲仅应输出视频帧"),
+            Frame::Audio(_) | Frame::Subtitle(_) => panic!("重排缓冲仅应输出视频帧"),
         }
     }
     assert_eq!(pts_list, vec![10, 20, 30], "flush 输出应按 POC 升序");
     assert!(dec.reorder_buffer.is_empty(), "drain 后重排缓冲应被清空");
 }
+
+#[test]
+fn test_build_output_frame_reuses_pooled_planes() {
+    let mut dec = build_test_decoder();
+    dec.last_slice_type = 2;
+    dec.last_disable_deblocking_filter_idc = 1;
+    dec.reorder_depth = 0;
+
+    dec.build_output_frame(0, Rational::new(1, 25), true);
+    let first = match dec.output_queue.pop_front() {
+        Some(Frame::Video(vf)) => vf,
+        _ => panic!("应输出视频帧"),
+    };
+    let first_ptrs: Vec<*const u8> = first.data.iter().map(|p| p.as_ptr()).collect();
+    let first_y = first.data[0].to_vec();
+    drop(first);
+
+    dec.build_output_frame(1, Rational::new(1, 25), true);
+    let second = match dec.output_queue.pop_front() {
+        Some(Frame::Video(vf)) => vf,
+        _ => panic!("应输出视频帧"),
+    };
+    for ptr in second.data.iter().map(|p| p.as_ptr()) {
+        assert!(first_ptrs.contains(&ptr), "输出平面应复用已释放帧的缓冲");
+    }
+    assert_eq!(second.data[0], first_y, "复用缓冲不应影响输出内容");
+}
+
+#[test]
+fn test_shared_frame_pool_bounds_allocations_over_many_frames() {
+    use crate::decoder::Decoder;
+    use crate::frame_pool::FramePool;
+
+    let pool = FramePool::new(6);
+    let mut dec = build_test_decoder();
+    dec.set_frame_pool(&pool);
+    dec.last_slice_type = 2;
+    dec.last_disable_deblocking_filter_idc = 1;
+    dec.reorder_depth = 0;
+
+    // 下游最多同时持有 1 帧, 稳态下每帧 3 个平面全部复用
+    let mut held = None;
+    for pts in 0..50 {
+        dec.build_output_frame(pts, Rational::new(1, 25), true);
+        held = dec.output_queue.pop_front();
+        assert!(held.is_some(), "每次都应输出一帧");
+    }
+    drop(held);
+    assert_eq!(pool.allocation_count(), 6, "50 帧只应分配两帧的平面缓冲");
+    assert_eq!(pool.free_count(), 6, "释放后缓冲应归还共享池");
+}
@@ -1198,7 +1198,7 @@ fn test_compute_slice_poc_type2_idr_resets_offset() {
 }
 
 #[test]
-fn test_compute_slice_poc_type2_non_ref_wrap_does_not_update_prev_offset() {
+fn test_compute_slice_poc_type2_non_ref_wrap_updates_prev_offset() {
     let mut dec = build_test_decoder();
     let sps = build_test_sps_with_poc_type(0, 2);
     dec.sps_map.insert(0, sps.clone());
@@ -1210,13 +1210,56 @@ fn test_compute_slice_poc_type2_non_ref_wrap_does_not_update_prev_offset() {
     let poc_non_ref = dec.compute_slice_poc(&non_ref_wrap, 15);
     assert_eq!(poc_non_ref, 63, "非参考帧 wrap 的 POC 计算错误");
     assert_eq!(
-        dec.prev_frame_num_offset_type2, 16,
-        "非参考帧不应更新 prev_frame_num_offset_type2"
+        dec.prev_frame_num_offset_type2, 32,
+        "非参考帧同样应更新 prev_frame_num_offset_type2"
     );
 
-    let ref_after_non_ref = build_test_slice_header(1, 1, false, None);
+    let ref_after_non_ref = build_test_slice_header(0, 1, false, None);
     let poc_ref = dec.compute_slice_poc(&ref_after_non_ref, 0);
-    assert_eq!(poc_ref, 34, "后续参考帧应基于上一个参考帧偏移继续计算");
+    assert_eq!(
+        poc_ref, 64,
+        "后续参考帧应基于前一图像 (非参考帧) 的偏移继续计算"
+    );
+}
+
+#[test]
+fn test_compute_slice_poc_type2_interlaced_sequence_max_frame_num_32() {
+    let mut dec = build_test_decoder();
+    let mut sps = build_test_sps_with_poc_type(0, 2);
+    sps.log2_max_frame_num = 5;
+    sps.max_num_ref_frames = 1;
+    sps.frame_mbs_only = false;
+    dec.sps_map.insert(0, sps.clone());
+    dec.sps = Some(sps);
+    dec.active_sps_id = Some(0);
+
+    // (frame_num, nal_ref_idc, 期望 POC, 说明), 按解码顺序逐个 slice 计算.
+    // 场对的两个场及同一图像的多个 slice 具有相同 frame_num 与 POC.
+    let mut sequence = vec![(0u32, 1u8, 0i32, "IDR")];
+    sequence.extend((1..32).map(|n| (n, 1, 2 * n as i32, "参考帧")));
+    sequence.extend([
+        (0, 0, 63, "回绕处的非参考帧"),
+        (0, 0, 63, "同一非参考帧的第二个 slice"),
+        (0, 1, 64, "非参考帧之后的参考帧"),
+        (1, 1, 66, "参考场对的顶场"),
+        (1, 1, 66, "参考场对的底场"),
+        (2, 0, 67, "非参考场对的顶场"),
+        (2, 0, 67, "非参考场对的底场"),
+        (2, 1, 68, "参考帧"),
+    ]);
+    sequence.extend((3..32).map(|n| (n, 1, 64 + 2 * n as i32, "第二轮参考帧")));
+    sequence.push((0, 1, 128, "第二次回绕的参考帧"));
+
+    let mut prev_frame_num = 0;
+    for (i, &(frame_num, nal_ref_idc, expected, desc)) in sequence.iter().enumerate() {
+        let header = build_test_slice_header(frame_num, nal_ref_idc, i == 0, None);
+        let poc = dec.compute_slice_poc(&header, prev_frame_num);
+        assert_eq!(
+            poc, expected,
+            "第 {i} 个 slice ({desc}, frame_num={frame_num}) 的 POC 计算错误"
+        );
+        prev_frame_num = frame_num;
+    }
 }
 
 #[test]
@@ -615,3 +615,261 @@ fn test_b_default_ref_list_l1_swap_uses_full_default_lists_when_active_counts_di
         "即使 L0/L1 active_ref 数量不同, 仍应按完整默认列表判定并交换 L1 前两项"
     );
 }
+
+/// 模拟解码一帧参考图像: 设置当前图像参数后执行参考帧标记
+fn mark_scripted_picture(
+    dec: &mut super::super::H264Decoder,
+    frame_num: u32,
+    poc: i32,
+    marking: DecRefPicMarking,
+) {
+    dec.last_slice_type = 0;
+    dec.last_nal_ref_idc = 1;
+    dec.last_frame_num = frame_num;
+    dec.last_poc = poc;
+    dec.last_dec_ref_pic_marking = marking;
+    dec.store_reference_with_marking();
+}
+
+fn adaptive_marking(ops: Vec<MmcoOp>) -> DecRefPicMarking {
+    DecRefPicMarking {
+        adaptive: true,
+        ops,
+        ..DecRefPicMarking::default()
+    }
+}
+
+/// 参考列表条目摘要: (frame_num, long_term_frame_idx)
+fn ref_list_summary(list: &[super::super::RefPlanes]) -> Vec<(u32, Option<u32>)> {
+    list.iter()
+        .map(|rp| (rp.frame_num, rp.long_term_frame_idx))
+        .collect()
+}
+
+#[test]
+fn test_scripted_long_term_reference_lists_and_mmco() {
+    let mut dec = build_test_decoder();
+
+    // f0: IDR, long_term_reference_flag=1 -> LongTermFrameIdx 0
+    mark_scripted_picture(
+        &mut dec,
+        0,
+        0,
+        DecRefPicMarking {
+            is_idr: true,
+            long_term_reference_flag: true,
+            ..DecRefPicMarking::default()
+        },
+    );
+    // f1: 滑动窗口, 短期参考
+    mark_scripted_picture(&mut dec, 1, 2, DecRefPicMarking::default());
+
+    // f2: P slice, 默认 L0 = 短期 (PicNum 降序) + 长期 (LongTermPicNum 升序)
+    dec.last_slice_type = 0;
+    dec.last_frame_num = 2;
+    let l0 = dec.build_reference_list_l0_with_mod(2, &[], 2);
+    assert_eq!(ref_list_summary(&l0), vec![(1, None), (0, Some(0))]);
+    // MMCO4 (MaxLongTermFrameIdx=1) + MMCO3 (PicNum 1 -> LongTermFrameIdx 1)
+    mark_scripted_picture(
+        &mut dec,
+        2,
+        4,
+        adaptive_marking(vec![
+            MmcoOp::TrimLong {
+                max_long_term_frame_idx_plus1: 2,
+            },
+            MmcoOp::ConvertShortToLong {
+                difference_of_pic_nums_minus1: 0,
+                long_term_frame_idx: 1,
+            },
+        ]),
+    );
+
+    // f3: 默认 L0 = [f2] + [LT0 f0, LT1 f1]
+    dec.last_slice_type = 0;
+    dec.last_frame_num = 3;
+    let l0 = dec.build_reference_list_l0_with_mod(3, &[], 3);
+    assert_eq!(
+        ref_list_summary(&l0),
+        vec![(2, None), (0, Some(0)), (1, Some(1))],
+        "长期参考应按 LongTermPicNum 升序排在短期参考之后"
+    );
+    // MMCO6: 当前帧占用 LongTermFrameIdx 0, 原 LT0 (f0) 不再用于参考
+    mark_scripted_picture(
+        &mut dec,
+        3,
+        6,
+        adaptive_marking(vec![MmcoOp::MarkCurrentLong {
+            long_term_frame_idx: 0,
+        }]),
+    );
+    assert!(
+        dec.reference_frames.iter().all(|pic| pic.frame_num != 0),
+        "MMCO6 复用 LongTermFrameIdx 时应移除原长期参考"
+    );
+
+    // f4: 默认 L0 = [f2] + [LT0 f3, LT1 f1]
+    dec.last_slice_type = 0;
+    dec.last_frame_num = 4;
+    let l0 = dec.build_reference_list_l0_with_mod(3, &[], 4);
+    assert_eq!(
+        ref_list_summary(&l0),
+        vec![(2, None), (3, Some(0)), (1, Some(1))]
+    );
+    // 重排: long_term_pic_num=1 -> rank0, abs_diff_pic_num_minus1=1 (PicNum 4-2=2) -> rank1
+    let mods = [
+        RefPicListMod::LongTerm {
+            long_term_pic_num: 1,
+        },
+        RefPicListMod::ShortTermSub {
+            abs_diff_pic_num_minus1: 1,
+        },
+    ];
+    let l0 = dec.build_reference_list_l0_with_mod(3, &mods, 4);
+    assert_eq!(
+        ref_list_summary(&l0),
+        vec![(1, Some(1)), (2, None), (3, Some(0))],
+        "长期参考重排应按 LongTermPicNum 定位图像"
+    );
+    // MMCO2 移除 LT0 (f3), MMCO1 移除 PicNum 2 (f2)
+    mark_scripted_picture(
+        &mut dec,
+        4,
+        8,
+        adaptive_marking(vec![
+            MmcoOp::ForgetLong {
+                long_term_pic_num: 0,
+            },
+            MmcoOp::ForgetShort {
+                difference_of_pic_nums_minus1: 1,
+            },
+        ]),
+    );
+    mark_scripted_picture(&mut dec, 5, 10, DecRefPicMarking::default());
+
+    // 非参考 B 帧 (POC 9): L0 = [f4] + [f5] + [LT1 f1], L1 = [f5] + [f4] + [LT1 f1]
+    dec.last_slice_type = 1;
+    dec.last_frame_num = 6;
+    dec.last_poc = 9;
+    let l0 = dec.build_reference_list_l0_with_mod(3, &[], 6);
+    let l1 = dec.build_reference_list_l1_with_mod(3, &[], 6);
+    assert_eq!(
+        ref_list_summary(&l0),
+        vec![(4, None), (5, None), (1, Some(1))]
+    );
+    assert_eq!(
+        ref_list_summary(&l1),
+        vec![(5, None), (4, None), (1, Some(1))]
+    );
+    let mods = [RefPicListMod::LongTerm {
+        long_term_pic_num: 1,
+    }];
+    let l1 = dec.build_reference_list_l1_with_mod(2, &mods, 6);
+    assert_eq!(ref_list_summary(&l1), vec![(1, Some(1)), (5, None)]);
+
+    // f6: MMCO4 max_long_term_frame_idx_plus1=0 清除全部长期参考
+    mark_scripted_picture(
+        &mut dec,
+        6,
+        12,
+        adaptive_marking(vec![MmcoOp::TrimLong {
+            max_long_term_frame_idx_plus1: 0,
+        }]),
+    );
+    assert_eq!(dec.max_long_term_frame_idx, None);
+    dec.last_slice_type = 0;
+    dec.last_frame_num = 7;
+    let l0 = dec.build_reference_list_l0_with_mod(3, &[], 7);
+    assert_eq!(ref_list_summary(&l0), vec![(6, None), (5, None), (4, None)]);
+}
+
+#[test]
+fn test_mmco_mark_current_long_beyond_max_idx_stays_long_term() {
+    let mut dec = build_test_decoder();
+    // IDR 长期参考使 MaxLongTermFrameIdx=0, 随后未经 MMCO4 直接使用索引 1.
+    mark_scripted_picture(
+        &mut dec,
+        0,
+        0,
+        DecRefPicMarking {
+            is_idr: true,
+            long_term_reference_flag: true,
+            ..DecRefPicMarking::default()
+        },
+    );
+    mark_scripted_picture(
+        &mut dec,
+        1,
+        2,
+        adaptive_marking(vec![MmcoOp::MarkCurrentLong {
+            long_term_frame_idx: 1,
+        }]),
+    );
+
+    dec.last_slice_type = 0;
+    dec.last_frame_num = 2;
+    let mods = [RefPicListMod::LongTerm {
+        long_term_pic_num: 1,
+    }];
+    let l0 = dec.build_reference_list_l0_with_mod(2, &mods, 2);
+    assert_eq!(
+        ref_list_summary(&l0),
+        vec![(1, Some(1)), (0, Some(0))],
+        "long_term_pic_num=1 应定位到 MMCO6 标记的当前帧"
+    );
+}
+
+#[test]
+fn test_mmco_convert_short_to_long_missing_target_keeps_existing_long_term() {
+    let mut dec = build_test_decoder();
+    push_dummy_reference_with_long_term(&mut dec, 1, Some(0));
+    push_dummy_reference(&mut dec, 2);
+
+    // PicNum 4-3=1 对应的短期参考不存在 (frame_num=1 已是长期参考)
+    mark_scripted_picture(
+        &mut dec,
+        4,
+        8,
+        adaptive_marking(vec![MmcoOp::ConvertShortToLong {
+            difference_of_pic_nums_minus1: 2,
+            long_term_frame_idx: 0,
+        }]),
+    );
+
+    assert!(
+        dec.reference_frames
+            .iter()
+            .any(|pic| pic.frame_num == 1 && pic.long_term_frame_idx == Some(0)),
+        "目标短期参考不存在时不应释放已占用索引的长期参考"
+    );
+    assert!(
+        dec.reference_frames
+            .iter()
+            .any(|pic| pic.frame_num == 2 && pic.long_term_frame_idx.is_none()),
+        "其他短期参考不应被转换"
+    );
+}
+
+#[test]
+fn test_reference_capacity_evicts_lowest_long_term_idx_when_no_short_term() {
+    let mut dec = build_test_decoder();
+    dec.max_reference_frames = 2;
+    push_dummy_reference_with_long_term(&mut dec, 1, Some(1));
+    push_dummy_reference_with_long_term(&mut dec, 2, Some(0));
+
+    mark_scripted_picture(&mut dec, 3, 6, DecRefPicMarking::default());
+
+    assert_eq!(dec.reference_frames.len(), 2);
+    assert!(
+        dec.reference_frames
+            .iter()
+            .all(|pic| pic.long_term_frame_idx != Some(0)),
+        "无短期参考时应淘汰 LongTermFrameIdx 最小的长期参考"
+    );
+    assert!(
+        dec.reference_frames
+            .iter()
+            .any(|pic| pic.long_term_frame_idx == Some(1)),
+    );
+    assert!(dec.reference_frames.iter().any(|pic| pic.frame_num == 3));
+}
@@ -0,0 +1,236 @@
+use super::super::parameter_sets::parse_pps;
+use super::super::slice_group::{
+    build_map_unit_to_slice_group_map, build_mb_to_slice_group_map, change_cycle_bits,
+};
+use super::super::{H264Decoder, SliceGroupParams};
+
+use super::helpers::*;
+
+/// 构造带 slice group 语法的 PPS RBSP, `write_map` 写入 slice_group_map_type 及其参数
+fn build_fmo_pps_rbsp(
+    num_slice_groups_minus1: u32,
+    write_map: impl FnOnce(&mut Vec<bool>),
+) -> Vec<u8> {
+    let mut bits = Vec::new();
+    write_ue(&mut bits, 0); // pps_id
+    write_ue(&mut bits, 0); // sps_id
+    bits.push(false); // entropy_coding_mode_flag
+    bits.push(false); // pic_order_present_flag
+    write_ue(&mut bits, num_slice_groups_minus1);
+    write_map(&mut bits);
+    write_ue(&mut bits, 0); // num_ref_idx_l0_default_active_minus1
+    write_ue(&mut bits, 0); // num_ref_idx_l1_default_active_minus1
+    bits.push(false); // weighted_pred_flag
+    push_bits_fixed(&mut bits, 0, 2); // weighted_bipred_idc
+    write_se(&mut bits, 0); // pic_init_qp_minus26
+    write_se(&mut bits, 0); // pic_init_qs_minus26
+    write_se(&mut bits, 0); // chroma_qp_index_offset
+    bits.push(true); // deblocking_filter_control_present_flag
+    bits.push(false); // constrained_intra_pred_flag
+    bits.push(false); // redundant_pic_cnt_present_flag
+    bits.push(true); // rbsp_stop_one_bit
+    bits_to_bytes(&bits)
+}
+
+fn fmo_params(num_slice_groups_minus1: u32, slice_group_map_type: u32) -> SliceGroupParams {
+    SliceGroupParams {
+        num_slice_groups_minus1,
+        slice_group_map_type,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_parse_pps_slice_group_run_lengths() {
+    let rbsp = build_fmo_pps_rbsp(2, |bits| {
+        write_ue(bits, 0); // slice_group_map_type
+        for run_length_minus1 in [3, 0, 7] {
+            write_ue(bits, run_length_minus1);
+        }
+    });
+    let pps = parse_pps(&rbsp).expect("带 FMO 的 PPS 应解析成功");
+    assert_eq!(pps.slice_groups.num_slice_groups_minus1, 2);
+    assert_eq!(pps.slice_groups.slice_group_map_type, 0);
+    assert_eq!(pps.slice_groups.run_length_minus1, vec![3, 0, 7]);
+    assert!(
+        pps.deblocking_filter_control,
+        "slice group 语法后的字段应对齐"
+    );
+}
+
+#[test]
+fn test_parse_pps_slice_group_explicit_map() {
+    let ids = [0u32, 2, 1, 2, 0, 1];
+    let rbsp = build_fmo_pps_rbsp(2, |bits| {
+        write_ue(bits, 6); // slice_group_map_type
+        write_ue(bits, ids.len() as u32 - 1); // pic_size_in_map_units_minus1
+        for id in ids {
+            push_bits_fixed(bits, id, 2);
+        }
+    });
+    let pps = parse_pps(&rbsp).expect("显式 slice group 映射应解析成功");
+    assert_eq!(pps.slice_groups.slice_group_map_type, 6);
+    assert_eq!(pps.slice_groups.slice_group_id, ids.to_vec());
+    assert!(
+        pps.deblocking_filter_control,
+        "slice group 语法后的字段应对齐"
+    );
+
+    let map = build_map_unit_to_slice_group_map(&pps.slice_groups, 3, 2, 0);
+    assert_eq!(map, vec![0, 2, 1, 2, 0, 1], "类型 6 应直接使用显式映射");
+}
+
+#[test]
+fn test_parse_pps_slice_group_reject_invalid_group_id() {
+    let rbsp = build_fmo_pps_rbsp(2, |bits| {
+        write_ue(bits, 6);
+        write_ue(bits, 0);
+        push_bits_fixed(bits, 3, 2); // slice_group_id=3 > num_slice_groups_minus1
+    });
+    assert!(parse_pps(&rbsp).is_err(), "slice_group_id 越界应报错");
+}
+
+#[test]
+fn test_slice_group_map_interleaved_and_dispersed() {
+    let mut params = fmo_params(1, 0);
+    params.run_length_minus1 = vec![1, 2];
+    assert_eq!(
+        build_map_unit_to_slice_group_map(&params, 4, 2, 0),
+        vec![0, 0, 1, 1, 1, 0, 0, 1],
+        "类型 0 应按 run_length 交错循环"
+    );
+
+    let params = fmo_params(1, 1);
+    assert_eq!(
+        build_map_unit_to_slice_group_map(&params, 4, 2, 0),
+        vec![0, 1, 0, 1, 1, 0, 1, 0],
+        "类型 1 应呈棋盘状分散"
+    );
+}
+
+#[test]
+fn test_slice_group_map_foreground_and_evolving_types() {
+    let mut params = fmo_params(1, 2);
+    params.top_left = vec![5];
+    params.bottom_right = vec![10];
+    assert_eq!(
+        build_map_unit_to_slice_group_map(&params, 4, 3, 0),
+        vec![1, 1, 1, 1, 1, 0, 0, 1, 1, 0, 0, 1],
+        "类型 2 前景矩形应属于 slice group 0"
+    );
+
+    // 变化速率 2, 周期 2 => slice group 0 含 4 个 map unit
+    let mut params = fmo_params(1, 4);
+    params.change_rate_minus1 = 1;
+    assert_eq!(
+        build_map_unit_to_slice_group_map(&params, 3, 2, 2),
+        vec![0, 0, 0, 0, 1, 1],
+        "类型 4 应按光栅顺序划分"
+    );
+    params.slice_group_map_type = 5;
+    assert_eq!(
+        build_map_unit_to_slice_group_map(&params, 3, 2, 2),
+        vec![0, 0, 1, 0, 0, 1],
+        "类型 5 应按列顺序划分"
+    );
+    params.change_direction_flag = true;
+    assert_eq!(
+        build_map_unit_to_slice_group_map(&params, 3, 2, 2),
+        vec![1, 0, 0, 1, 0, 0],
+        "反向擦除时左上区域 (map unit 数为总数减变化量) 应属于 slice group 1"
+    );
+
+    params.slice_group_map_type = 3;
+    params.change_direction_flag = false;
+    params.change_rate_minus1 = 0;
+    let map = build_map_unit_to_slice_group_map(&params, 3, 3, 1);
+    assert_eq!(map, vec![1, 1, 1, 1, 0, 1, 1, 1, 1], "盒出应从中心开始");
+    let map = build_map_unit_to_slice_group_map(&params, 3, 3, 5);
+    assert_eq!(map.iter().filter(|&&g| g == 0).count(), 5);
+    assert_eq!(map[4], 0);
+    let map = build_map_unit_to_slice_group_map(&params, 3, 3, 100);
+    assert!(map.iter().all(|&g| g == 0), "周期足够大时应覆盖整幅图像");
+}
+
+#[test]
+fn test_slice_group_change_cycle_bits() {
+    assert_eq!(change_cycle_bits(6, 2), 2, "Ceil(Log2(6 / 2 + 1)) = 2");
+    assert_eq!(change_cycle_bits(99, 1), 7, "Ceil(Log2(100)) = 7");
+    assert_eq!(change_cycle_bits(100, 3), 6, "Ceil(Log2(100 / 3 + 1)) = 6");
+}
+
+#[test]
+fn test_mb_to_slice_group_map_for_frame_of_field_map_units() {
+    let params = fmo_params(1, 1);
+    // 2x2 map unit 棋盘: [0, 1, 1, 0]
+    assert_eq!(
+        build_mb_to_slice_group_map(&params, 2, 2, true, false, false, 0),
+        vec![0, 1, 1, 0],
+        "frame_mbs_only 时宏块与 map unit 一一对应"
+    );
+    // 非 frame_mbs_only 的非 MBAFF 帧: 每个 map unit 覆盖上下两个宏块
+    assert_eq!(
+        build_mb_to_slice_group_map(&params, 2, 4, false, false, false, 0),
+        vec![0, 1, 0, 1, 1, 0, 1, 0]
+    );
+    // MBAFF 帧: 按宏块对地址 i / 2 映射
+    assert_eq!(
+        build_mb_to_slice_group_map(&params, 2, 4, false, true, false, 0),
+        vec![0, 0, 1, 1, 1, 1, 0, 0]
+    );
+    // 场图像: 场内宏块直接映射
+    assert_eq!(
+        build_mb_to_slice_group_map(&params, 2, 4, false, false, true, 0),
+        vec![0, 1, 1, 0],
+        "场图像宏块数与 map unit 数相同, 应直接映射"
+    );
+}
+
+#[test]
+fn test_decode_cavlc_p_slice_skip_run_follows_dispersed_slice_group() {
+    let mut dec = build_test_decoder();
+    install_basic_parameter_sets(&mut dec, 0);
+    for sps in dec.sps_map.values_mut().chain(dec.sps.as_mut()) {
+        sps.width = 32;
+        sps.height = 32;
+        sps.pic_width_in_mbs = 2;
+        sps.pic_height_in_map_units = 2;
+    }
+    dec.width = 32;
+    dec.height = 32;
+    dec.init_buffers();
+    if let Some(pps) = dec.pps_map.get_mut(&0) {
+        pps.slice_groups = fmo_params(1, 1);
+    }
+    push_custom_reference(&mut dec, 3, 3, 77, None);
+
+    let mut header = build_test_slice_header(0, 1, false, None);
+    header.slice_type = 0; // P slice
+    header.data_bit_offset = 0;
+    // slice group 0 = {0, 3}, mb_skip_run=2 应覆盖宏块 0 和 3
+    let rbsp = build_rbsp_from_ues(&[2]);
+    dec.decode_slice_data(&rbsp, &header);
+
+    let stride = dec.stride_y;
+    assert_eq!(dec.mb_types[0], 255, "宏块 0 应按 skip 解码");
+    assert_eq!(
+        dec.mb_types[3], 255,
+        "宏块 3 属于同一 slice group, 应按 skip 解码"
+    );
+    assert_ne!(
+        dec.mb_types[1], 255,
+        "宏块 1 属于 slice group 1, 不应被本 slice 解码"
+    );
+    assert_ne!(
+        dec.mb_types[2], 255,
+        "宏块 2 属于 slice group 1, 不应被本 slice 解码"
+    );
+    assert_eq!(dec.ref_y[16 + 16 * stride], 77, "宏块 3 应按 L0 预测重建");
+    assert_ne!(dec.ref_y[16], 77, "宏块 1 不应被重建");
+    assert_eq!(dec.mb_slice_first_mb[2], u32::MAX);
+    assert!(
+        H264Decoder::pps_rebuild_action(&build_test_pps(), &dec.pps_map[&0])
+            != super::super::ParameterSetRebuildAction::None,
+        "slice group 参数变化应触发运行时重建"
+    );
+}
@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use tao_core::{PixelFormat, Rational, TaoError};
+
+use crate::codec_id::CodecId;
+use crate::codec_parameters::{CodecParameters, CodecParamsType, VideoCodecParams};
+use crate::decoder::DecoderOptions;
+use crate::frame::{Frame, VideoFrame};
+use crate::packet::Packet;
+
+use super::super::H264Decoder;
+
+use super::helpers::*;
+
+const MB_WIDTH: u32 = 4;
+const MB_HEIGHT: u32 = 3;
+
+/// I_PCM 样本值, 避开 0 以免在码流中形成起始码
+fn pcm_sample(seed: u32, i: u32) -> u8 {
+    (16 + (seed * 37 + i * 11 + (i / 16) * 5) % 220) as u8
+}
+
+fn push_pcm_mb(bits: &mut Vec<bool>, seed: u32) {
+    while bits.len() % 8 != 0 {
+        bits.push(false); // pcm_alignment_zero_bit
+    }
+    for i in 0..384 {
+        push_bits_u8(bits, pcm_sample(seed, i));
+    }
+}
+
+/// 写入 slice header 末尾的 QP 与去块参数
+fn finish_slice_header(bits: &mut Vec<bool>, qp_delta: i32) {
+    write_se(bits, qp_delta); // slice_qp_delta
+    write_ue(bits, 0); // disable_deblocking_filter_idc
+    write_se(bits, 0); // slice_alpha_c0_offset_div2
+    write_se(bits, 0); // slice_beta_offset_div2
+}
+
+/// 构造一行宏块组成的 IDR slice: I_PCM + 3 个无残差 I_16x16 DC 宏块
+fn build_idr_slice(row: u32) -> Vec<u8> {
+    let mut bits = Vec::new();
+    write_ue(&mut bits, row * MB_WIDTH); // first_mb_in_slice
+    write_ue(&mut bits, 7); // slice_type=I (全部 slice 相同)
+    write_ue(&mut bits, 0); // pps_id
+    push_bits_fixed(&mut bits, 0, 4); // frame_num
+    write_ue(&mut bits, 0); // idr_pic_id
+    push_bits_fixed(&mut bits, 0, 4); // pic_order_cnt_lsb
+    bits.push(false); // no_output_of_prior_pics_flag
+    bits.push(false); // long_term_reference_flag
+    finish_slice_header(&mut bits, row as i32 * 12);
+
+    write_ue(&mut bits, 25); // I_PCM
+    push_pcm_mb(&mut bits, row + 1);
+    for mb_x in 1..MB_WIDTH {
+        write_ue(&mut bits, 3); // I_16x16, pred_mode=DC, cbp=0
+        write_ue(&mut bits, 0); // intra_chroma_pred_mode=DC
+        write_se(&mut bits, 0); // mb_qp_delta
+        if mb_x == 1 {
+            // 左邻为 I_PCM (nC=16): coeff_token 定长码 000011
+            bits.extend([false, false, false, false, true, true]);
+        } else {
+            bits.push(true); // nC=0, total_coeff=0
+        }
+    }
+    let mut nal = vec![0x65];
+    nal.extend(bits_to_bytes(&bits));
+    nal
+}
+
+/// 构造一行宏块组成的 P slice: P_L0_16x16 + I_PCM + 2 个 P_Skip 宏块
+fn build_p_slice(row: u32) -> Vec<u8> {
+    let mut bits = Vec::new();
+    write_ue(&mut bits, row * MB_WIDTH); // first_mb_in_slice
+    write_ue(&mut bits, 5); // slice_type=P (全部 slice 相同)
+    write_ue(&mut bits, 0); // pps_id
+    push_bits_fixed(&mut bits, 1, 4); // frame_num
+    push_bits_fixed(&mut bits, 2, 4); // pic_order_cnt_lsb
+    bits.push(false); // num_ref_idx_active_override_flag
+    bits.push(false); // ref_pic_list_modification_flag_l0
+    bits.push(false); // adaptive_ref_pic_marking_mode_flag
+    finish_slice_header(&mut bits, 0);
+
+    write_ue(&mut bits, 0); // mb_skip_run
+    write_ue(&mut bits, 0); // P_L0_16x16
+    write_se(&mut bits, 5 + row as i32 * 6); // mvd_l0 x
+    write_se(&mut bits, -3 - row as i32); // mvd_l0 y
+    write_ue(&mut bits, 0); // coded_block_pattern=0
+    write_ue(&mut bits, 0); // mb_skip_run
+    write_ue(&mut bits, 30); // P-slice 中的 I_PCM
+    push_pcm_mb(&mut bits, row + 7);
+    write_ue(&mut bits, 2); // mb_skip_run 覆盖行尾两个宏块
+    let mut nal = vec![0x41];
+    nal.extend(bits_to_bytes(&bits));
+    nal
+}
+
+fn build_annex_b_packet(nals: &[Vec<u8>], pts: i64) -> Packet {
+    let mut data = Vec::new();
+    for nal in nals {
+        data.extend_from_slice(&[0, 0, 0, 1]);
+        data.extend_from_slice(nal);
+    }
+    let mut packet = Packet::from_data(data);
+    packet.pts = pts;
+    packet.time_base = Rational::new(1, 25);
+    packet
+}
+
+fn decode_multi_slice_stream(decode_threads: u32) -> Vec<VideoFrame> {
+    let mut dec = H264Decoder::create().expect("创建 H264 解码器失败");
+    dec.set_options(&DecoderOptions::default().with_decode_threads(decode_threads));
+    dec.open(&CodecParameters {
+        codec_id: CodecId::H264,
+        extra_data: Vec::new(),
+        bit_rate: 0,
+        params: CodecParamsType::Video(VideoCodecParams {
+            width: MB_WIDTH * 16,
+            height: MB_HEIGHT * 16,
+            pixel_format: PixelFormat::Yuv420p,
+            frame_rate: Rational::new(25, 1),
+            sample_aspect_ratio: Rational::new(1, 1),
+        }),
+    })
+    .expect("打开 H264 解码器失败");
+
+    let sps = build_sps_nalu(0, MB_WIDTH * 16, MB_HEIGHT * 16);
+    let pps = build_pps_nalu(0, 0, false, 0);
+    let mut idr = vec![sps.data.clone(), pps.data.clone()];
+    idr.extend((0..MB_HEIGHT).map(build_idr_slice));
+    let p: Vec<Vec<u8>> = (0..MB_HEIGHT).map(build_p_slice).collect();
+
+    let mut frames = Vec::new();
+    for packet in [
+        build_annex_b_packet(&idr, 0),
+        build_annex_b_packet(&p, 1),
+        Packet::empty(),
+    ] {
+        dec.send_packet(&packet).expect("送入多 slice 数据包失败");
+        loop {
+            match dec.receive_frame() {
+                Ok(Frame::Video(vf)) => frames.push(vf),
+                Ok(_) => panic!("应输出视频帧"),
+                Err(TaoError::NeedMoreData | TaoError::Eof) => break,
+                Err(err) => panic!("取帧失败: {err}"),
+            }
+        }
+    }
+    frames
+}
+
+#[test]
+fn test_multi_slice_parallel_decode_matches_single_thread() {
+    let single = decode_multi_slice_stream(1);
+    assert_eq!(single.len(), 2, "应输出 IDR 与 P 两帧");
+    assert_eq!(
+        single[0].data[0][0],
+        pcm_sample(1, 0),
+        "首个 slice 的 I_PCM 样本应原样重建"
+    );
+
+    for threads in [2, 4] {
+        let parallel = decode_multi_slice_stream(threads);
+        assert_eq!(parallel.len(), single.len(), "并行解码帧数应与单线程一致");
+        for (idx, (a, b)) in single.iter().zip(&parallel).enumerate() {
+            assert_eq!(a.pts, b.pts, "第 {idx} 帧 pts 应一致");
+            for plane in 0..3 {
+                assert!(
+                    a.data[plane][..] == b.data[plane][..],
+                    "threads={threads} 时第 {idx} 帧平面 {plane} 应与单线程逐字节一致"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_fork_slice_worker_shares_reference_planes_and_motion() {
+    let mut dec = build_test_decoder();
+    push_custom_reference_with_l0_motion(&mut dec, 1, 2, 90, None, (4, -4, 0));
+    let worker = dec.fork_slice_worker();
+    let (main_ref, worker_ref) = (&dec.reference_frames[0], &worker.reference_frames[0]);
+    assert!(Arc::ptr_eq(&main_ref.y, &worker_ref.y), "参考平面应共享");
+    assert!(
+        Arc::ptr_eq(&main_ref.mv_l0_x_4x4, &worker_ref.mv_l0_x_4x4),
+        "参考帧运动数据应共享, 不随工作实例复制"
+    );
+    assert!(Arc::ptr_eq(&main_ref.mb_types, &worker_ref.mb_types));
+}
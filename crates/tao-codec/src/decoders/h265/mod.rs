@@ -87,9 +87,9 @@ impl HevcDecoder {
         };
         Frame::Video(VideoFrame {
             data: vec![
-                self.ref_y[..w * h].to_vec(),
-                self.ref_u[..w * h / 4].to_vec(),
-                self.ref_v[..w * h / 4].to_vec(),
+                self.ref_y[..w * h].into(),
+                self.ref_u[..w * h / 4].into(),
+                self.ref_v[..w * h / 4].into(),
             ],
             linesize: vec![w, w / 2, w / 2],
             width: self.width,
@@ -99,6 +99,7 @@ impl HevcDecoder {
             time_base: Rational::new(1, 90000),
             duration: 1,
             is_keyframe: is_irap,
+            force_keyframe: false,
             picture_type: pic_type,
             sample_aspect_ratio: Rational::new(1, 1),
             color_space: ColorSpace::Unspecified,
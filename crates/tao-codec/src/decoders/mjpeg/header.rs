@@ -0,0 +1,302 @@
+//! JPEG 标记段解析 (SOF/SOS/DQT/DHT/DRI).
+//!
+//! 各函数的输入为标记段负载 (不含标记与 2 字节长度字段).
+
+use tao_core::{TaoError, TaoResult};
+
+use super::huffman::HuffmanTable;
+
+/// 图像开始
+pub(super) const SOI: u8 = 0xD8;
+/// 图像结束
+pub(super) const EOI: u8 = 0xD9;
+/// 扫描开始
+pub(super) const SOS: u8 = 0xDA;
+/// 定义量化表
+pub(super) const DQT: u8 = 0xDB;
+/// 定义重启间隔
+pub(super) const DRI: u8 = 0xDD;
+/// 定义 Huffman 表
+pub(super) const DHT: u8 = 0xC4;
+/// 基线 DCT 帧
+pub(super) const SOF0: u8 = 0xC0;
+/// 扩展顺序 DCT 帧 (Huffman)
+pub(super) const SOF1: u8 = 0xC1;
+
+/// 量化表/Huffman 表槽位数
+pub(super) const TABLE_SLOTS: usize = 4;
+
+/// 量化表 (自然顺序)
+pub(super) type QuantTable = [u16; 64];
+
+/// 之字形扫描序号 -> 自然顺序下标
+pub(super) const ZIGZAG: [usize; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19, 26, 33, 40, 48, 41, 34, 27, 20,
+    13, 6, 7, 14, 21, 28, 35, 42, 49, 56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37, 44, 51, 58, 59,
+    52, 45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+/// 帧头中的一个颜色分量
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct FrameComponent {
+    /// 分量标识
+    pub(super) id: u8,
+    /// 水平采样因子 (1..=4)
+    pub(super) h: u8,
+    /// 垂直采样因子 (1..=4)
+    pub(super) v: u8,
+    /// 量化表槽位
+    pub(super) quant: usize,
+}
+
+/// 帧头 (SOF0/SOF1)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct FrameHeader {
+    pub(super) width: u32,
+    pub(super) height: u32,
+    pub(super) components: Vec<FrameComponent>,
+}
+
+impl FrameHeader {
+    /// 最大水平采样因子
+    pub(super) fn max_h(&self) -> u8 {
+        self.components.iter().map(|c| c.h).max().unwrap_or(1)
+    }
+
+    /// 最大垂直采样因子
+    pub(super) fn max_v(&self) -> u8 {
+        self.components.iter().map(|c| c.v).max().unwrap_or(1)
+    }
+
+    /// 水平方向 MCU 数
+    pub(super) fn mcus_x(&self) -> usize {
+        (self.width as usize).div_ceil(8 * usize::from(self.max_h()))
+    }
+
+    /// 垂直方向 MCU 数
+    pub(super) fn mcus_y(&self) -> usize {
+        (self.height as usize).div_ceil(8 * usize::from(self.max_v()))
+    }
+}
+
+/// 扫描中的一个分量
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ScanComponent {
+    /// 在帧头分量列表中的下标
+    pub(super) index: usize,
+    /// DC Huffman 表槽位
+    pub(super) dc_table: usize,
+    /// AC Huffman 表槽位
+    pub(super) ac_table: usize,
+}
+
+/// 解析 SOF0/SOF1 帧头
+pub(super) fn parse_sof(data: &[u8]) -> TaoResult<FrameHeader> {
+    if data.len() < 6 {
+        return Err(TaoError::InvalidData("JPEG SOF 段过短".into()));
+    }
+    let precision = data[0];
+    if precision != 8 {
+        return Err(TaoError::Unsupported(format!(
+            "不支持 {precision} 位精度的 JPEG"
+        )));
+    }
+    let height = u32::from(u16::from_be_bytes([data[1], data[2]]));
+    let width = u32::from(u16::from_be_bytes([data[3], data[4]]));
+    if width == 0 {
+        return Err(TaoError::InvalidData("JPEG 图像宽度为 0".into()));
+    }
+    if height == 0 {
+        return Err(TaoError::Unsupported("不支持由 DNL 指定高度的 JPEG".into()));
+    }
+    let count = usize::from(data[5]);
+    if count != 1 && count != 3 {
+        return Err(TaoError::Unsupported(format!(
+            "不支持 {count} 个分量的 JPEG"
+        )));
+    }
+    if data.len() < 6 + 3 * count {
+        return Err(TaoError::InvalidData("JPEG SOF 分量列表截断".into()));
+    }
+    let mut components = Vec::with_capacity(count);
+    for chunk in data[6..6 + 3 * count].chunks_exact(3) {
+        let component = FrameComponent {
+            id: chunk[0],
+            h: chunk[1] >> 4,
+            v: chunk[1] & 0x0F,
+            quant: usize::from(chunk[2]),
+        };
+        if !(1..=4).contains(&component.h) || !(1..=4).contains(&component.v) {
+            return Err(TaoError::InvalidData(format!(
+                "JPEG 分量 {} 采样因子无效: {}x{}",
+                component.id, component.h, component.v
+            )));
+        }
+        if component.quant >= TABLE_SLOTS {
+            return Err(TaoError::InvalidData(format!(
+                "JPEG 分量 {} 量化表槽位无效: {}",
+                component.id, component.quant
+            )));
+        }
+        components.push(component);
+    }
+    Ok(FrameHeader {
+        width,
+        height,
+        components,
+    })
+}
+
+/// 解析 SOS 扫描头, 仅接受基线顺序扫描参数
+pub(super) fn parse_sos(data: &[u8], frame: &FrameHeader) -> TaoResult<Vec<ScanComponent>> {
+    let count = usize::from(*data.first().unwrap_or(&0));
+    if count == 0 || count > 4 || data.len() < 1 + 2 * count + 3 {
+        return Err(TaoError::InvalidData("JPEG SOS 段无效".into()));
+    }
+    let mut components = Vec::with_capacity(count);
+    for chunk in data[1..1 + 2 * count].chunks_exact(2) {
+        let index = frame
+            .components
+            .iter()
+            .position(|c| c.id == chunk[0])
+            .ok_or_else(|| {
+                TaoError::InvalidData(format!("JPEG 扫描引用了未知分量 {}", chunk[0]))
+            })?;
+        let dc_table = usize::from(chunk[1] >> 4);
+        let ac_table = usize::from(chunk[1] & 0x0F);
+        if dc_table >= TABLE_SLOTS || ac_table >= TABLE_SLOTS {
+            return Err(TaoError::InvalidData("JPEG 扫描 Huffman 表槽位无效".into()));
+        }
+        components.push(ScanComponent {
+            index,
+            dc_table,
+            ac_table,
+        });
+    }
+    let tail = &data[1 + 2 * count..];
+    if tail[0] != 0 || tail[1] != 63 || tail[2] != 0 {
+        return Err(TaoError::Unsupported(
+            "仅支持基线顺序扫描 (Ss=0, Se=63, Ah=Al=0)".into(),
+        ));
+    }
+    Ok(components)
+}
+
+/// 解析 DQT, 更新对应槽位的量化表
+pub(super) fn parse_dqt(
+    mut data: &[u8],
+    tables: &mut [Option<QuantTable>; TABLE_SLOTS],
+) -> TaoResult<()> {
+    while !data.is_empty() {
+        let precision = data[0] >> 4;
+        let slot = usize::from(data[0] & 0x0F);
+        let entry_size = if precision == 0 { 1 } else { 2 };
+        if precision > 1 || slot >= TABLE_SLOTS || data.len() < 1 + 64 * entry_size {
+            return Err(TaoError::InvalidData("JPEG DQT 段无效".into()));
+        }
+        let mut table = [0u16; 64];
+        for (k, &natural) in ZIGZAG.iter().enumerate() {
+            table[natural] = if entry_size == 1 {
+                u16::from(data[1 + k])
+            } else {
+                u16::from_be_bytes([data[1 + 2 * k], data[2 + 2 * k]])
+            };
+        }
+        tables[slot] = Some(table);
+        data = &data[1 + 64 * entry_size..];
+    }
+    Ok(())
+}
+
+/// 解析 DHT, 更新对应槽位的 DC/AC Huffman 表
+pub(super) fn parse_dht(
+    mut data: &[u8],
+    dc_tables: &mut [Option<HuffmanTable>; TABLE_SLOTS],
+    ac_tables: &mut [Option<HuffmanTable>; TABLE_SLOTS],
+) -> TaoResult<()> {
+    while !data.is_empty() {
+        if data.len() < 17 {
+            return Err(TaoError::InvalidData("JPEG DHT 段截断".into()));
+        }
+        let class = data[0] >> 4;
+        let slot = usize::from(data[0] & 0x0F);
+        if class > 1 || slot >= TABLE_SLOTS {
+            return Err(TaoError::InvalidData(format!(
+                "JPEG DHT 表类型/槽位无效: {:#04x}",
+                data[0]
+            )));
+        }
+        let mut counts = [0u8; 16];
+        counts.copy_from_slice(&data[1..17]);
+        let total: usize = counts.iter().map(|&c| usize::from(c)).sum();
+        if data.len() < 17 + total {
+            return Err(TaoError::InvalidData("JPEG DHT 符号表截断".into()));
+        }
+        let table = HuffmanTable::new(&counts, &data[17..17 + total])?;
+        if class == 0 {
+            dc_tables[slot] = Some(table);
+        } else {
+            ac_tables[slot] = Some(table);
+        }
+        data = &data[17 + total..];
+    }
+    Ok(())
+}
+
+/// 解析 DRI, 返回重启间隔 (MCU 数, 0 表示不使用)
+pub(super) fn parse_dri(data: &[u8]) -> TaoResult<u16> {
+    if data.len() < 2 {
+        return Err(TaoError::InvalidData("JPEG DRI 段过短".into()));
+    }
+    Ok(u16::from_be_bytes([data[0], data[1]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sof_420() {
+        let data = [8, 0, 16, 0, 24, 3, 1, 0x22, 0, 2, 0x11, 1, 3, 0x11, 1];
+        let frame = parse_sof(&data).unwrap();
+        assert_eq!((frame.width, frame.height), (24, 16));
+        assert_eq!((frame.max_h(), frame.max_v()), (2, 2));
+        assert_eq!((frame.mcus_x(), frame.mcus_y()), (2, 1));
+        assert_eq!(frame.components[1].quant, 1);
+
+        let twelve_bit = [12, 0, 16, 0, 16, 1, 1, 0x11, 0];
+        assert!(
+            matches!(parse_sof(&twelve_bit), Err(TaoError::Unsupported(_))),
+            "12 位精度应报不支持"
+        );
+    }
+
+    #[test]
+    fn test_parse_dqt_zigzag_to_natural() {
+        let mut data = vec![0x00];
+        data.extend((1..=64).map(|v| v as u8));
+        let mut tables = [None; TABLE_SLOTS];
+        parse_dqt(&data, &mut tables).unwrap();
+        let table = tables[0].unwrap();
+        // 之字形第 2 个值 (3) 对应自然顺序 (1, 0) 即下标 8
+        assert_eq!(table[0], 1);
+        assert_eq!(table[1], 2);
+        assert_eq!(table[8], 3);
+        assert_eq!(table[63], 64);
+    }
+
+    #[test]
+    fn test_parse_sos_rejects_progressive() {
+        let frame = parse_sof(&[8, 0, 8, 0, 8, 1, 1, 0x11, 0]).unwrap();
+        let scan = parse_sos(&[1, 1, 0x00, 0, 63, 0], &frame).unwrap();
+        assert_eq!(scan[0].index, 0);
+        assert!(
+            matches!(
+                parse_sos(&[1, 1, 0x00, 0, 5, 0], &frame),
+                Err(TaoError::Unsupported(_))
+            ),
+            "频谱选择扫描应报不支持"
+        );
+        assert!(parse_sos(&[1, 9, 0x00, 0, 63, 0], &frame).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! JPEG Huffman 表与熵编码段读取.
+//!
+//! 熵编码段中的 0xFF 后跟 0x00 表示字面值 0xFF (字节填充),
+//! 遇到其他标记时停止读取并以 0 填充, 由调用方处理 RSTn 或段结束.
+
+use tao_core::{TaoError, TaoResult};
+
+/// 快速查找表的码长位数
+const LOOKUP_BITS: u32 = 9;
+
+/// 标准 Huffman 表 (ITU-T T.81 K.3), AVI MJPEG 常省略 DHT 而隐含使用这些表
+pub(super) const STD_DC_LUMA_COUNTS: [u8; 16] = [0, 1, 5, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0];
+pub(super) const STD_DC_CHROMA_COUNTS: [u8; 16] = [0, 3, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0];
+pub(super) const STD_DC_VALUES: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+pub(super) const STD_AC_LUMA_COUNTS: [u8; 16] = [0, 2, 1, 3, 3, 2, 4, 3, 5, 5, 4, 4, 0, 0, 1, 0x7D];
+pub(super) const STD_AC_LUMA_VALUES: [u8; 162] = [
+    0x01, 0x02, 0x03, 0x00, 0x04, 0x11, 0x05, 0x12, 0x21, 0x31, 0x41, 0x06, 0x13, 0x51, 0x61, 0x07,
+    0x22, 0x71, 0x14, 0x32, 0x81, 0x91, 0xA1, 0x08, 0x23, 0x42, 0xB1, 0xC1, 0x15, 0x52, 0xD1, 0xF0,
+    0x24, 0x33, 0x62, 0x72, 0x82, 0x09, 0x0A, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x25, 0x26, 0x27, 0x28,
+    0x29, 0x2A, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3A, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49,
+    0x4A, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5A, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68, 0x69,
+    0x6A, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7A, 0x83, 0x84, 0x85, 0x86, 0x87, 0x88, 0x89,
+    0x8A, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x98, 0x99, 0x9A, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6, 0xA7,
+    0xA8, 0xA9, 0xAA, 0xB2, 0xB3, 0xB4, 0xB5, 0xB6, 0xB7, 0xB8, 0xB9, 0xBA, 0xC2, 0xC3, 0xC4, 0xC5,
+    0xC6, 0xC7, 0xC8, 0xC9, 0xCA, 0xD2, 0xD3, 0xD4, 0xD5, 0xD6, 0xD7, 0xD8, 0xD9, 0xDA, 0xE1, 0xE2,
+    0xE3, 0xE4, 0xE5, 0xE6, 0xE7, 0xE8, 0xE9, 0xEA, 0xF1, 0xF2, 0xF3, 0xF4, 0xF5, 0xF6, 0xF7, 0xF8,
+    0xF9, 0xFA,
+];
+pub(super) const STD_AC_CHROMA_COUNTS: [u8; 16] =
+    [0, 2, 1, 2, 4, 4, 3, 4, 7, 5, 4, 4, 0, 1, 2, 0x77];
+pub(super) const STD_AC_CHROMA_VALUES: [u8; 162] = [
+    0x00, 0x01, 0x02, 0x03, 0x11, 0x04, 0x05, 0x21, 0x31, 0x06, 0x12, 0x41, 0x51, 0x07, 0x61, 0x71,
+    0x13, 0x22, 0x32, 0x81, 0x08, 0x14, 0x42, 0x91, 0xA1, 0xB1, 0xC1, 0x09, 0x23, 0x33, 0x52, 0xF0,
+    0x15, 0x62, 0x72, 0xD1, 0x0A, 0x16, 0x24, 0x34, 0xE1, 0x25, 0xF1, 0x17, 0x18, 0x19, 0x1A, 0x26,
+    0x27, 0x28, 0x29, 0x2A, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3A, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48,
+    0x49, 0x4A, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5A, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68,
+    0x69, 0x6A, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7A, 0x82, 0x83, 0x84, 0x85, 0x86, 0x87,
+    0x88, 0x89, 0x8A, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x98, 0x99, 0x9A, 0xA2, 0xA3, 0xA4, 0xA5,
+    0xA6, 0xA7, 0xA8, 0xA9, 0xAA, 0xB2, 0xB3, 0xB4, 0xB5, 0xB6, 0xB7, 0xB8, 0xB9, 0xBA, 0xC2, 0xC3,
+    0xC4, 0xC5, 0xC6, 0xC7, 0xC8, 0xC9, 0xCA, 0xD2, 0xD3, 0xD4, 0xD5, 0xD6, 0xD7, 0xD8, 0xD9, 0xDA,
+    0xE2, 0xE3, 0xE4, 0xE5, 0xE6, 0xE7, 0xE8, 0xE9, 0xEA, 0xF2, 0xF3, 0xF4, 0xF5, 0xF6, 0xF7, 0xF8,
+    0xF9, 0xFA,
+];
+
+/// 规范 Huffman 表 (T.81 Annex C / F.2.2.3)
+#[derive(Debug, Clone)]
+pub(super) struct HuffmanTable {
+    /// 码长 <= LOOKUP_BITS 的快速查找: (码长 << 8) | 符号, 0 表示需要慢速路径
+    lookup: Vec<u16>,
+    /// 各码长的最大码字, 无该长度码字时为 -1
+    max_code: [i32; 17],
+    /// 各码长首个码字在 values 中的下标减去该长度最小码字
+    val_offset: [i32; 17],
+    values: Vec<u8>,
+}
+
+impl HuffmanTable {
+    /// 由 DHT 的 16 个码长计数与符号表构建
+    pub(super) fn new(counts: &[u8; 16], values: &[u8]) -> TaoResult<Self> {
+        let total: usize = counts.iter().map(|&c| usize::from(c)).sum();
+        if total > 256 || total > values.len() {
+            return Err(TaoError::InvalidData(format!(
+                "JPEG Huffman 表符号数无效: {total}"
+            )));
+        }
+        let mut table = Self {
+            lookup: vec![0; 1 << LOOKUP_BITS],
+            max_code: [-1; 17],
+            val_offset: [0; 17],
+            values: values[..total].to_vec(),
+        };
+        let mut code = 0u32;
+        let mut k = 0usize;
+        for len in 1..=16u32 {
+            let count = u32::from(counts[len as usize - 1]);
+            table.val_offset[len as usize] = k as i32 - code as i32;
+            for _ in 0..count {
+                if len <= LOOKUP_BITS {
+                    let shift = LOOKUP_BITS - len;
+                    let entry = ((len as u16) << 8) | u16::from(table.values[k]);
+                    let start = (code << shift) as usize;
+                    table.lookup[start..start + (1 << shift)].fill(entry);
+                }
+                code += 1;
+                k += 1;
+            }
+            if count > 0 {
+                table.max_code[len as usize] = code as i32 - 1;
+            }
+            if code > 1 << len {
+                return Err(TaoError::InvalidData("JPEG Huffman 表码长计数溢出".into()));
+            }
+            code <<= 1;
+        }
+        Ok(table)
+    }
+}
+
+/// 熵编码段位读取器
+pub(super) struct EntropyReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// 高位对齐的位缓冲
+    acc: u64,
+    bits: u32,
+    /// 已读到标记 (或数据末尾), 之后以 0 填充
+    marker_hit: bool,
+}
+
+impl<'a> EntropyReader<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            acc: 0,
+            bits: 0,
+            marker_hit: false,
+        }
+    }
+
+    fn fill(&mut self) {
+        while self.bits <= 56 {
+            let mut byte = 0u8;
+            if !self.marker_hit {
+                match self.data.get(self.pos) {
+                    Some(&0xFF) => match self.data.get(self.pos + 1) {
+                        Some(0x00) => {
+                            byte = 0xFF;
+                            self.pos += 2;
+                        }
+                        _ => self.marker_hit = true,
+                    },
+                    Some(&b) => {
+                        byte = b;
+                        self.pos += 1;
+                    }
+                    None => self.marker_hit = true,
+                }
+            }
+            self.acc |= u64::from(byte) << (56 - self.bits);
+            self.bits += 8;
+        }
+    }
+
+    fn peek(&mut self, n: u32) -> u32 {
+        if self.bits < n {
+            self.fill();
+        }
+        (self.acc >> (64 - n)) as u32
+    }
+
+    fn skip(&mut self, n: u32) {
+        self.acc <<= n;
+        self.bits -= n;
+    }
+
+    /// 读取 n 位 (n <= 16)
+    pub(super) fn get_bits(&mut self, n: u32) -> u32 {
+        if n == 0 {
+            return 0;
+        }
+        let value = self.peek(n);
+        self.skip(n);
+        value
+    }
+
+    /// 读取 s 位幅值并按 T.81 F.2.2.1 EXTEND 还原符号
+    pub(super) fn receive_extend(&mut self, s: u8) -> i32 {
+        if s == 0 {
+            return 0;
+        }
+        let s = u32::from(s.min(16));
+        let v = self.get_bits(s) as i32;
+        if v < 1 << (s - 1) {
+            v - (1 << s) + 1
+        } else {
+            v
+        }
+    }
+
+    /// 解码一个 Huffman 符号
+    pub(super) fn decode(&mut self, table: &HuffmanTable) -> TaoResult<u8> {
+        let entry = table.lookup[self.peek(LOOKUP_BITS) as usize];
+        if entry != 0 {
+            self.skip(u32::from(entry >> 8));
+            return Ok(entry as u8);
+        }
+        for len in LOOKUP_BITS + 1..=16 {
+            let code = self.peek(len) as i32;
+            if code <= table.max_code[len as usize] {
+                self.skip(len);
+                let idx = (table.val_offset[len as usize] + code) as usize;
+                return Ok(table.values[idx]);
+            }
+        }
+        Err(TaoError::InvalidData("JPEG Huffman 码字无效".into()))
+    }
+
+    /// 处理重启间隔: 丢弃剩余填充位并跳过 RSTn 标记
+    pub(super) fn restart(&mut self) -> TaoResult<()> {
+        self.acc = 0;
+        self.bits = 0;
+        self.marker_hit = false;
+        // 容错: 跳过标记前的多余字节
+        while self.pos + 1 < self.data.len() {
+            if self.data[self.pos] == 0xFF && (0xD0..=0xD7).contains(&self.data[self.pos + 1]) {
+                self.pos += 2;
+                return Ok(());
+            }
+            self.pos += 1;
+        }
+        Err(TaoError::InvalidData("JPEG 缺少 RST 标记".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_huffman_decode_std_dc_table() {
+        let table = HuffmanTable::new(&STD_DC_LUMA_COUNTS, &STD_DC_VALUES).unwrap();
+        // 标准亮度 DC 表: 0 -> "00", 1 -> "010", 5 -> "110", 11 -> "111111110"
+        // 00 010 110 111111110 + 填充 1
+        let data = [0b0001_0110, 0b1111_1111, 0b0000_0000, 0b0111_1111];
+        let mut reader = EntropyReader::new(&data);
+        assert_eq!(reader.decode(&table).unwrap(), 0);
+        assert_eq!(reader.decode(&table).unwrap(), 1);
+        assert_eq!(reader.decode(&table).unwrap(), 5);
+        assert_eq!(reader.decode(&table).unwrap(), 11);
+    }
+
+    #[test]
+    fn test_entropy_reader_byte_stuffing_and_restart() {
+        // FF 00 为字面 0xFF; FF D0 为 RST0
+        let data = [0xFF, 0x00, 0xA5, 0xFF, 0xD0, 0x3C];
+        let mut reader = EntropyReader::new(&data);
+        assert_eq!(reader.get_bits(8), 0xFF);
+        assert_eq!(reader.get_bits(8), 0xA5);
+        assert_eq!(reader.get_bits(8), 0, "标记之后应以 0 填充");
+        reader.restart().unwrap();
+        assert_eq!(reader.get_bits(8), 0x3C);
+        assert_eq!(reader.receive_extend(0), 0);
+    }
+
+    #[test]
+    fn test_receive_extend_sign() {
+        // 3 位: 000 -> -7, 011 -> -4, 100 -> 4, 111 -> 7
+        let data = [0b0000_1110, 0b0111_1111];
+        let mut reader = EntropyReader::new(&data);
+        assert_eq!(reader.receive_extend(3), -7);
+        assert_eq!(reader.receive_extend(3), -4);
+        assert_eq!(reader.receive_extend(3), 4);
+        assert_eq!(reader.receive_extend(3), 7);
+    }
+}
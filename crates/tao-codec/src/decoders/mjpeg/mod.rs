@@ -0,0 +1,512 @@
+//! Motion JPEG 视频解码器.
+//!
+//! 每个数据包为一幅独立的 JPEG 图像, 支持基线/扩展顺序 DCT (SOF0/SOF1, 8 位精度):
+//! - DQT (8/16 位), DHT, DRI 与 RSTn 重启标记
+//! - 交织与非交织扫描
+//! - 未携带 DHT 时使用标准 Huffman 表 (AVI1 MJPEG 省略 DHT)
+//!
+//! 输出按 JPEG 采样格式映射为 Gray8/YUV420P/YUV422P/YUV444P, 全范围 BT.601.
+//! 渐进式、无损与算术编码 JPEG 返回 Unsupported.
+//!
+//! ## 模块结构
+//!
+//! - `huffman`: Huffman 表与熵编码段位读取
+//! - `header`: 标记段解析
+//! - `scan`: 扫描解码 (熵解码, 反量化, IDCT)
+
+mod header;
+mod huffman;
+mod scan;
+
+use tao_core::color::{ColorRange, ColorSpace};
+use tao_core::{PixelFormat, TaoError, TaoResult};
+use tracing::debug;
+
+use crate::capabilities::CodecCapabilities;
+use crate::codec_id::CodecId;
+use crate::codec_parameters::{CodecParameters, CodecParamsType};
+use crate::decoder::Decoder;
+use crate::frame::{Frame, PictureType, VideoFrame};
+use crate::frame_pool::FramePool;
+use crate::packet::Packet;
+
+use header::{FrameHeader, QuantTable, TABLE_SLOTS};
+use huffman::HuffmanTable;
+use scan::{Plane, ScanTables};
+
+/// 每个平面保留的空闲缓冲数量 (待输出帧 + 下游持有的帧)
+const POOL_FRAMES: usize = 4;
+
+/// 解码输出支持的像素格式
+pub(crate) const OUTPUT_FORMATS: [PixelFormat; 4] = [
+    PixelFormat::Yuv420p,
+    PixelFormat::Yuv422p,
+    PixelFormat::Yuv444p,
+    PixelFormat::Gray8,
+];
+
+/// MJPEG 解码器
+pub struct MjpegDecoder {
+    /// 量化表 (跨数据包保留)
+    quant_tables: [Option<QuantTable>; TABLE_SLOTS],
+    /// DC Huffman 表 (跨数据包保留)
+    dc_tables: [Option<HuffmanTable>; TABLE_SLOTS],
+    /// AC Huffman 表 (跨数据包保留)
+    ac_tables: [Option<HuffmanTable>; TABLE_SLOTS],
+    /// 已解码帧缓冲
+    output_frame: Option<Frame>,
+    /// 输出平面缓冲池
+    pool: FramePool,
+    /// 是否已打开 (配置参数)
+    opened: bool,
+    /// 是否已收到刷新信号 (空包)
+    flushing: bool,
+}
+
+impl MjpegDecoder {
+    pub fn create() -> TaoResult<Box<dyn Decoder>> {
+        let mut decoder = Self {
+            quant_tables: [None; TABLE_SLOTS],
+            dc_tables: Default::default(),
+            ac_tables: Default::default(),
+            output_frame: None,
+            pool: FramePool::new(0),
+            opened: false,
+            flushing: false,
+        };
+        decoder.reset_tables()?;
+        Ok(Box::new(decoder))
+    }
+
+    /// 重置为标准 Huffman 表 (T.81 K.3), 清除量化表
+    fn reset_tables(&mut self) -> TaoResult<()> {
+        use huffman::*;
+        self.quant_tables = [None; TABLE_SLOTS];
+        self.dc_tables = [
+            Some(HuffmanTable::new(&STD_DC_LUMA_COUNTS, &STD_DC_VALUES)?),
+            Some(HuffmanTable::new(&STD_DC_CHROMA_COUNTS, &STD_DC_VALUES)?),
+            None,
+            None,
+        ];
+        self.ac_tables = [
+            Some(HuffmanTable::new(&STD_AC_LUMA_COUNTS, &STD_AC_LUMA_VALUES)?),
+            Some(HuffmanTable::new(
+                &STD_AC_CHROMA_COUNTS,
+                &STD_AC_CHROMA_VALUES,
+            )?),
+            None,
+            None,
+        ];
+        Ok(())
+    }
+
+    /// 解码一幅 JPEG 图像
+    fn decode_picture(&mut self, data: &[u8]) -> TaoResult<VideoFrame> {
+        let mut pos = data
+            .windows(2)
+            .position(|w| w == [0xFF, header::SOI])
+            .ok_or_else(|| TaoError::InvalidData("MJPEG 数据包中未找到 SOI 标记".into()))?
+            + 2;
+        let mut frame_header: Option<FrameHeader> = None;
+        let mut planes: Vec<Plane> = Vec::new();
+        let mut restart_interval = 0u16;
+        let mut scans = 0usize;
+
+        loop {
+            // 查找下一个标记, 跳过填充的 0xFF
+            while pos < data.len() && data[pos] != 0xFF {
+                pos += 1;
+            }
+            while pos < data.len() && data[pos] == 0xFF {
+                pos += 1;
+            }
+            let Some(&marker) = data.get(pos) else {
+                break;
+            };
+            pos += 1;
+            match marker {
+                header::EOI => break,
+                0xD0..=0xD7 | 0x01 => continue,
+                _ => {}
+            }
+            if pos + 2 > data.len() {
+                return Err(TaoError::InvalidData("JPEG 标记段长度截断".into()));
+            }
+            let len = usize::from(u16::from_be_bytes([data[pos], data[pos + 1]]));
+            if len < 2 || pos + len > data.len() {
+                return Err(TaoError::InvalidData(format!(
+                    "JPEG 标记 {marker:#04x} 段长度无效: {len}"
+                )));
+            }
+            let segment = &data[pos + 2..pos + len];
+            pos += len;
+
+            match marker {
+                header::SOF0 | header::SOF1 => {
+                    if frame_header.is_some() {
+                        return Err(TaoError::InvalidData("JPEG 包含多个 SOF 帧头".into()));
+                    }
+                    let fh = header::parse_sof(segment)?;
+                    planes = (0..fh.components.len())
+                        .map(|i| Plane::new(&fh, i))
+                        .collect();
+                    frame_header = Some(fh);
+                }
+                0xC2 | 0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF => {
+                    return Err(TaoError::Unsupported(format!(
+                        "不支持的 JPEG 编码方式 (SOF{})",
+                        marker - 0xC0
+                    )));
+                }
+                header::DHT => {
+                    header::parse_dht(segment, &mut self.dc_tables, &mut self.ac_tables)?
+                }
+                header::DQT => header::parse_dqt(segment, &mut self.quant_tables)?,
+                header::DRI => restart_interval = header::parse_dri(segment)?,
+                header::SOS => {
+                    let fh = frame_header
+                        .as_ref()
+                        .ok_or_else(|| TaoError::InvalidData("JPEG SOS 出现在 SOF 之前".into()))?;
+                    let components = header::parse_sos(segment, fh)?;
+                    let tables = components
+                        .iter()
+                        .map(|&sc| self.scan_tables(fh, sc))
+                        .collect::<TaoResult<Vec<_>>>()?;
+                    let end = entropy_segment_end(data, pos);
+                    scan::decode_scan(&data[pos..end], fh, &tables, restart_interval, &mut planes)?;
+                    pos = end;
+                    scans += 1;
+                }
+                // APPn, COM 等其余标记段忽略
+                _ => {}
+            }
+        }
+
+        let fh = frame_header.ok_or_else(|| TaoError::InvalidData("JPEG 缺少 SOF 帧头".into()))?;
+        if scans == 0 {
+            return Err(TaoError::InvalidData("JPEG 缺少扫描数据".into()));
+        }
+        self.build_frame(&fh, &planes)
+    }
+
+    /// 查找扫描分量对应的 Huffman 表与量化表
+    fn scan_tables(
+        &self,
+        fh: &FrameHeader,
+        sc: header::ScanComponent,
+    ) -> TaoResult<ScanTables<'_>> {
+        let missing = |kind: &str, slot: usize| {
+            TaoError::InvalidData(format!("JPEG 引用了未定义的{kind}表 {slot}"))
+        };
+        let quant_slot = fh.components[sc.index].quant;
+        Ok(ScanTables {
+            component: sc,
+            dc: self.dc_tables[sc.dc_table]
+                .as_ref()
+                .ok_or_else(|| missing("DC Huffman ", sc.dc_table))?,
+            ac: self.ac_tables[sc.ac_table]
+                .as_ref()
+                .ok_or_else(|| missing("AC Huffman ", sc.ac_table))?,
+            quant: self.quant_tables[quant_slot]
+                .as_ref()
+                .ok_or_else(|| missing("量化", quant_slot))?,
+        })
+    }
+
+    /// 将 MCU 对齐的分量平面裁剪为输出帧
+    fn build_frame(&self, fh: &FrameHeader, planes: &[Plane]) -> TaoResult<VideoFrame> {
+        let pixel_format = output_pixel_format(fh)?;
+        let mut frame = VideoFrame::new(fh.width, fh.height, pixel_format);
+        frame.color_space = ColorSpace::Bt470bg;
+        frame.color_range = ColorRange::Full;
+        for (i, plane) in planes.iter().enumerate() {
+            let (Some(linesize), Some(rows)) = (
+                pixel_format.plane_linesize(i, fh.width),
+                pixel_format.plane_height(i, fh.height),
+            ) else {
+                return Err(TaoError::Codec(format!("无法计算平面 {i} 的尺寸")));
+            };
+            let mut buf = self.pool.take_vec(linesize * rows);
+            for row in plane.data.chunks_exact(plane.stride).take(rows) {
+                buf.extend_from_slice(&row[..linesize]);
+            }
+            frame.data[i] = self.pool.wrap(buf);
+            frame.linesize[i] = linesize;
+        }
+        Ok(frame)
+    }
+}
+
+/// 按分量采样因子确定输出像素格式
+fn output_pixel_format(fh: &FrameHeader) -> TaoResult<PixelFormat> {
+    if fh.components.len() == 1 {
+        return Ok(PixelFormat::Gray8);
+    }
+    let luma = &fh.components[0];
+    let (cb, cr) = (&fh.components[1], &fh.components[2]);
+    let ratio = |l: u8, c: u8| (l % c == 0).then(|| l / c);
+    let format = if cb.h == cr.h && cb.v == cr.v {
+        match (ratio(luma.h, cb.h), ratio(luma.v, cb.v)) {
+            (Some(2), Some(2)) => Some(PixelFormat::Yuv420p),
+            (Some(2), Some(1)) => Some(PixelFormat::Yuv422p),
+            (Some(1), Some(1)) => Some(PixelFormat::Yuv444p),
+            _ => None,
+        }
+    } else {
+        None
+    };
+    format.ok_or_else(|| {
+        TaoError::Unsupported(format!(
+            "不支持的 JPEG 采样格式: Y {}x{}, Cb {}x{}, Cr {}x{}",
+            luma.h, luma.v, cb.h, cb.v, cr.h, cr.v
+        ))
+    })
+}
+
+/// 返回从 `start` 开始的熵编码段结束位置 (下一个非 RSTn 标记处)
+fn entropy_segment_end(data: &[u8], start: usize) -> usize {
+    let mut pos = start;
+    while pos + 1 < data.len() {
+        if data[pos] == 0xFF {
+            let next = data[pos + 1];
+            if next != 0x00 && !(0xD0..=0xD7).contains(&next) && next != 0xFF {
+                return pos;
+            }
+        }
+        pos += 1;
+    }
+    data.len()
+}
+
+impl Decoder for MjpegDecoder {
+    fn codec_id(&self) -> CodecId {
+        CodecId::Mjpeg
+    }
+
+    fn name(&self) -> &str {
+        "mjpeg"
+    }
+
+    fn codec_capabilities(&self) -> CodecCapabilities {
+        CodecCapabilities::CAPS_INTRA_ONLY
+    }
+
+    fn open(&mut self, params: &CodecParameters) -> TaoResult<()> {
+        if !matches!(params.params, CodecParamsType::Video(_)) {
+            return Err(TaoError::InvalidArgument("mjpeg 解码器需要视频参数".into()));
+        }
+        self.reset_tables()?;
+        self.output_frame = None;
+        self.pool = FramePool::new(3 * POOL_FRAMES);
+        self.opened = true;
+        self.flushing = false;
+        debug!("打开 mjpeg 解码器");
+        Ok(())
+    }
+
+    fn send_packet(&mut self, packet: &Packet) -> TaoResult<()> {
+        if !self.opened {
+            return Err(TaoError::Codec("解码器未打开, 请先调用 open()".into()));
+        }
+        if self.output_frame.is_some() {
+            return Err(TaoError::NeedMoreData);
+        }
+
+        // 空包 = flush
+        if packet.is_empty() {
+            self.flushing = true;
+            return Ok(());
+        }
+
+        let mut frame = self.decode_picture(&packet.data)?;
+        frame.pts = packet.pts;
+        frame.time_base = packet.time_base;
+        frame.duration = packet.duration;
+        frame.is_keyframe = true;
+        frame.picture_type = PictureType::I;
+        self.output_frame = Some(Frame::Video(frame));
+        Ok(())
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        if let Some(frame) = self.output_frame.take() {
+            return Ok(frame);
+        }
+        if self.flushing {
+            return Err(TaoError::Eof);
+        }
+        Err(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) {
+        self.output_frame = None;
+        self.flushing = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec_parameters::VideoCodecParams;
+    use bytes::Bytes;
+    use tao_core::Rational;
+
+    /// 20x12 YUV 4:2:0, 仅含 DC Huffman 表的 DHT, 重启间隔 1 MCU.
+    /// Y = 40 + 6x + 4y, U = 90, V = 200, 质量 75
+    #[rustfmt::skip]
+    const JPEG_420_RESTART: [u8; 310] = [
+        0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0x4A, 0x46, 0x49, 0x46, 0x00, 0x01, 0x01, 0x00, 0x00, 0x01,
+        0x00, 0x01, 0x00, 0x00, 0xFF, 0xDB, 0x00, 0x84, 0x00, 0x08, 0x06, 0x06, 0x07, 0x06, 0x05, 0x08,
+        0x07, 0x07, 0x07, 0x09, 0x09, 0x08, 0x0A, 0x0C, 0x14, 0x0D, 0x0C, 0x0B, 0x0B, 0x0C, 0x19, 0x12,
+        0x13, 0x0F, 0x14, 0x1D, 0x1A, 0x1F, 0x1E, 0x1D, 0x1A, 0x1C, 0x1C, 0x20, 0x24, 0x2E, 0x27, 0x20,
+        0x22, 0x2C, 0x23, 0x1C, 0x1C, 0x28, 0x37, 0x29, 0x2C, 0x30, 0x31, 0x34, 0x34, 0x34, 0x1F, 0x27,
+        0x39, 0x3D, 0x38, 0x32, 0x3C, 0x2E, 0x33, 0x34, 0x32, 0x01, 0x09, 0x09, 0x09, 0x0C, 0x0B, 0x0C,
+        0x18, 0x0D, 0x0D, 0x18, 0x32, 0x21, 0x1C, 0x21, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32,
+        0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32,
+        0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32,
+        0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00,
+        0x0C, 0x00, 0x14, 0x03, 0x01, 0x22, 0x00, 0x02, 0x11, 0x01, 0x03, 0x11, 0x01, 0xFF, 0xC4, 0x00,
+        0x3C, 0x00, 0x00, 0x01, 0x05, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x01, 0x00,
+        0x03, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0xFF, 0xDD, 0x00, 0x04, 0x00,
+        0x01, 0xFF, 0xDA, 0x00, 0x0C, 0x03, 0x01, 0x00, 0x02, 0x11, 0x03, 0x11, 0x00, 0x3F, 0x00, 0xE2,
+        0xB4, 0xDB, 0x3E, 0x9C, 0x57, 0x61, 0xA6, 0xD9, 0xF4, 0xE2, 0xB2, 0xB4, 0xD8, 0x93, 0x8E, 0x2B,
+        0xB0, 0xD3, 0x62, 0x4E, 0x38, 0xAF, 0x9D, 0x3F, 0x40, 0x3F, 0xFF, 0xD0, 0xE8, 0xE0, 0xB3, 0xFD,
+        0xD0, 0xE2, 0xA5, 0xFB, 0x1F, 0xB5, 0x6B, 0x41, 0x12, 0x79, 0x43, 0x8A, 0x97, 0xCA, 0x4F, 0x4A,
+        0xF9, 0xD3, 0xF4, 0x03, 0xFF, 0xD9,
+    ];
+    /// 16x8 YUV 4:2:2, 无 DHT (使用标准 Huffman 表).
+    /// Y = 220 - 5x - 3y, U = 60 + 4x (色度坐标), V = 150, 质量 75
+    #[rustfmt::skip]
+    const JPEG_422_NO_DHT: [u8; 205] = [
+        0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0x4A, 0x46, 0x49, 0x46, 0x00, 0x01, 0x01, 0x00, 0x00, 0x01,
+        0x00, 0x01, 0x00, 0x00, 0xFF, 0xDB, 0x00, 0x84, 0x00, 0x08, 0x06, 0x06, 0x07, 0x06, 0x05, 0x08,
+        0x07, 0x07, 0x07, 0x09, 0x09, 0x08, 0x0A, 0x0C, 0x14, 0x0D, 0x0C, 0x0B, 0x0B, 0x0C, 0x19, 0x12,
+        0x13, 0x0F, 0x14, 0x1D, 0x1A, 0x1F, 0x1E, 0x1D, 0x1A, 0x1C, 0x1C, 0x20, 0x24, 0x2E, 0x27, 0x20,
+        0x22, 0x2C, 0x23, 0x1C, 0x1C, 0x28, 0x37, 0x29, 0x2C, 0x30, 0x31, 0x34, 0x34, 0x34, 0x1F, 0x27,
+        0x39, 0x3D, 0x38, 0x32, 0x3C, 0x2E, 0x33, 0x34, 0x32, 0x01, 0x09, 0x09, 0x09, 0x0C, 0x0B, 0x0C,
+        0x18, 0x0D, 0x0D, 0x18, 0x32, 0x21, 0x1C, 0x21, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32,
+        0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32,
+        0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32,
+        0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0x32, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00,
+        0x08, 0x00, 0x10, 0x03, 0x01, 0x21, 0x00, 0x02, 0x11, 0x01, 0x03, 0x11, 0x01, 0xFF, 0xDA, 0x00,
+        0x0C, 0x03, 0x01, 0x00, 0x02, 0x11, 0x03, 0x11, 0x00, 0x3F, 0x00, 0xF4, 0x0B, 0xFB, 0x9E, 0xBC,
+        0xD7, 0x2F, 0x7F, 0x73, 0xD7, 0x9A, 0xF8, 0xFC, 0x39, 0xEA, 0x1F, 0xFF, 0xD9,
+    ];
+    fn open_decoder() -> Box<dyn Decoder> {
+        let mut dec = MjpegDecoder::create().unwrap();
+        dec.open(&CodecParameters {
+            codec_id: CodecId::Mjpeg,
+            extra_data: Vec::new(),
+            bit_rate: 0,
+            params: CodecParamsType::Video(VideoCodecParams {
+                width: 0,
+                height: 0,
+                pixel_format: PixelFormat::None,
+                frame_rate: Rational::new(25, 1),
+                sample_aspect_ratio: Rational::new(1, 1),
+            }),
+        })
+        .unwrap();
+        dec
+    }
+
+    fn decode(dec: &mut Box<dyn Decoder>, data: &[u8]) -> TaoResult<VideoFrame> {
+        dec.send_packet(&Packet::from_data(Bytes::from(data.to_vec())))?;
+        match dec.receive_frame()? {
+            Frame::Video(vf) => Ok(vf),
+            _ => panic!("期望视频帧"),
+        }
+    }
+
+    fn assert_near(actual: u8, expected: u8, what: &str) {
+        assert!(
+            (i32::from(actual) - i32::from(expected)).abs() <= 3,
+            "{what} 应约为 {expected}, 实际 {actual}"
+        );
+    }
+
+    #[test]
+    fn test_mjpeg_decode_420_with_restart() {
+        let mut dec = open_decoder();
+        let vf = decode(&mut dec, &JPEG_420_RESTART).unwrap();
+        assert_eq!((vf.width, vf.height), (20, 12), "尺寸应来自 SOF");
+        assert_eq!(vf.pixel_format, PixelFormat::Yuv420p);
+        assert_eq!(vf.linesize, vec![20, 10, 10], "平面应裁剪到图像尺寸");
+        assert_eq!(vf.data[0].len(), 20 * 12);
+        assert_eq!(vf.data[1].len(), 10 * 6);
+        assert_eq!(vf.color_range, ColorRange::Full, "JPEG 为全范围");
+        assert!(vf.is_keyframe);
+        assert_eq!(vf.picture_type, PictureType::I);
+
+        assert_near(vf.data[0][0], 40, "左上角 Y");
+        assert_near(vf.data[0][20 * 12 - 1], 198, "右下角 Y");
+        // 第二个 MCU 位于重启标记之后
+        assert_near(
+            vf.data[0][5 * 20 + 17],
+            40 + 6 * 17 + 4 * 5,
+            "第二个 MCU 的 Y",
+        );
+        assert_near(vf.data[1][0], 90, "左上角 U");
+        assert_near(vf.data[2][10 * 6 - 1], 200, "右下角 V");
+    }
+
+    #[test]
+    fn test_mjpeg_decode_422_default_huffman_tables() {
+        let mut dec = open_decoder();
+        let vf = decode(&mut dec, &JPEG_422_NO_DHT).unwrap();
+        assert_eq!((vf.width, vf.height), (16, 8));
+        assert_eq!(vf.pixel_format, PixelFormat::Yuv422p);
+        assert_eq!(vf.linesize, vec![16, 8, 8]);
+        assert_near(vf.data[0][0], 220, "左上角 Y");
+        assert_near(vf.data[0][7 * 16 + 15], 220 - 5 * 15 - 3 * 7, "右下角 Y");
+        assert_near(vf.data[1][0], 60, "左上角 U");
+        assert_near(vf.data[1][7], 88, "右上角 U");
+        assert_near(vf.data[2][0], 150, "左上角 V");
+    }
+
+    #[test]
+    fn test_mjpeg_packet_flow_and_errors() {
+        let mut dec = open_decoder();
+        let mut pkt = Packet::from_data(Bytes::from(JPEG_422_NO_DHT.to_vec()));
+        pkt.pts = 7;
+        pkt.time_base = Rational::new(1, 25);
+        dec.send_packet(&pkt).unwrap();
+        assert!(
+            matches!(dec.send_packet(&pkt), Err(TaoError::NeedMoreData)),
+            "未取走帧时应拒绝新数据包"
+        );
+        let Frame::Video(vf) = dec.receive_frame().unwrap() else {
+            panic!("期望视频帧");
+        };
+        assert_eq!(vf.pts, 7, "pts 应来自数据包");
+
+        // 渐进式 JPEG: 将 SOF0 改为 SOF2
+        let mut progressive = JPEG_420_RESTART.to_vec();
+        let sof = progressive
+            .windows(2)
+            .position(|w| w == [0xFF, header::SOF0])
+            .unwrap();
+        progressive[sof + 1] = 0xC2;
+        assert!(
+            matches!(
+                decode(&mut dec, &progressive),
+                Err(TaoError::Unsupported(_))
+            ),
+            "渐进式 JPEG 应报不支持"
+        );
+        assert!(
+            matches!(
+                decode(&mut dec, &[0x00, 0x01, 0x02]),
+                Err(TaoError::InvalidData(_))
+            ),
+            "缺少 SOI 应报数据无效"
+        );
+        let truncated = &JPEG_420_RESTART[..JPEG_420_RESTART.len() / 3];
+        assert!(decode(&mut dec, truncated).is_err(), "截断的头部应报错");
+
+        dec.send_packet(&Packet::empty()).unwrap();
+        assert!(matches!(dec.receive_frame(), Err(TaoError::Eof)));
+    }
+}
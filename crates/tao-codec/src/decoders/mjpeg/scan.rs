@@ -0,0 +1,134 @@
+//! 基线顺序扫描解码: Huffman 熵解码 + 反量化 + 8x8 IDCT.
+
+use tao_core::{TaoError, TaoResult};
+
+use super::header::{FrameHeader, QuantTable, ScanComponent, ZIGZAG};
+use super::huffman::{EntropyReader, HuffmanTable};
+use crate::decoders::mpeg4::idct::idct_8x8;
+
+/// 一个分量的重建平面, 宽高按 MCU 对齐
+pub(super) struct Plane {
+    pub(super) data: Vec<u8>,
+    pub(super) stride: usize,
+}
+
+impl Plane {
+    /// 按帧头为第 `index` 个分量分配 MCU 对齐的平面
+    pub(super) fn new(frame: &FrameHeader, index: usize) -> Self {
+        let comp = &frame.components[index];
+        let stride = frame.mcus_x() * usize::from(comp.h) * 8;
+        let rows = frame.mcus_y() * usize::from(comp.v) * 8;
+        Self {
+            data: vec![0; stride * rows],
+            stride,
+        }
+    }
+
+    /// 写入第 (bx, by) 个 8x8 块, 输出加 128 电平偏移并限幅
+    fn put_block(&mut self, bx: usize, by: usize, block: &[i32; 64]) {
+        for (row, values) in block.chunks_exact(8).enumerate() {
+            let start = (by * 8 + row) * self.stride + bx * 8;
+            for (dst, &v) in self.data[start..start + 8].iter_mut().zip(values) {
+                *dst = (v + 128).clamp(0, 255) as u8;
+            }
+        }
+    }
+}
+
+/// 扫描中一个分量解码所需的表
+pub(super) struct ScanTables<'a> {
+    pub(super) component: ScanComponent,
+    pub(super) dc: &'a HuffmanTable,
+    pub(super) ac: &'a HuffmanTable,
+    pub(super) quant: &'a QuantTable,
+}
+
+/// 解码一个扫描的熵编码段, 写入各分量平面
+pub(super) fn decode_scan(
+    data: &[u8],
+    frame: &FrameHeader,
+    tables: &[ScanTables<'_>],
+    restart_interval: u16,
+    planes: &mut [Plane],
+) -> TaoResult<()> {
+    let mut reader = EntropyReader::new(data);
+    let mut predictors = vec![0i32; tables.len()];
+    let mut block = [0i32; 64];
+
+    // 单分量扫描不交织, 每个 "MCU" 为一个块, 块数按分量自身尺寸计算
+    let (mcus_x, mcus_y) = if tables.len() == 1 {
+        let comp = &frame.components[tables[0].component.index];
+        let w = (frame.width as usize * usize::from(comp.h)).div_ceil(usize::from(frame.max_h()));
+        let h = (frame.height as usize * usize::from(comp.v)).div_ceil(usize::from(frame.max_v()));
+        (w.div_ceil(8), h.div_ceil(8))
+    } else {
+        (frame.mcus_x(), frame.mcus_y())
+    };
+    let total = mcus_x * mcus_y;
+    let interval = usize::from(restart_interval);
+
+    for mcu in 0..total {
+        if interval > 0 && mcu > 0 && mcu % interval == 0 {
+            reader.restart()?;
+            predictors.fill(0);
+        }
+        let (mx, my) = (mcu % mcus_x, mcu / mcus_x);
+        for (t, pred) in tables.iter().zip(predictors.iter_mut()) {
+            let index = t.component.index;
+            let (h, v) = if tables.len() == 1 {
+                (1, 1)
+            } else {
+                let comp = &frame.components[index];
+                (usize::from(comp.h), usize::from(comp.v))
+            };
+            for by in 0..v {
+                for bx in 0..h {
+                    decode_block(&mut reader, t, pred, &mut block)?;
+                    idct_8x8(&mut block);
+                    planes[index].put_block(mx * h + bx, my * v + by, &block);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 解码一个 8x8 块的 DC/AC 系数并反量化 (T.81 F.2.2)
+fn decode_block(
+    reader: &mut EntropyReader<'_>,
+    tables: &ScanTables<'_>,
+    predictor: &mut i32,
+    block: &mut [i32; 64],
+) -> TaoResult<()> {
+    block.fill(0);
+    let size = reader.decode(tables.dc)?;
+    if size > 11 {
+        return Err(TaoError::InvalidData(format!(
+            "JPEG DC 差值位数无效: {size}"
+        )));
+    }
+    *predictor += reader.receive_extend(size);
+    block[0] = *predictor * i32::from(tables.quant[0]);
+
+    let mut k = 1;
+    while k < 64 {
+        let rs = reader.decode(tables.ac)?;
+        let (run, size) = (usize::from(rs >> 4), rs & 0x0F);
+        if size == 0 {
+            if run != 15 {
+                // EOB
+                break;
+            }
+            k += 16;
+            continue;
+        }
+        k += run;
+        if k > 63 {
+            return Err(TaoError::InvalidData("JPEG AC 系数越界".into()));
+        }
+        let natural = ZIGZAG[k];
+        block[natural] = reader.receive_extend(size) * i32::from(tables.quant[natural]);
+        k += 1;
+    }
+    Ok(())
+}
@@ -1,38 +1,109 @@
 //! 解码器实现模块.
 
 pub mod aac;
+pub mod ac3;
+pub mod adpcm;
 pub mod flac;
 pub mod h264;
 pub mod h265;
+pub mod mjpeg;
 pub mod mp3;
 pub mod mpeg4;
 pub mod pcm;
 pub mod rawvideo;
+pub mod subtitle;
 pub mod theora;
 pub mod vorbis;
 
+use tao_core::{PixelFormat, SampleFormat};
+
 use crate::codec_id::CodecId;
-use crate::registry::CodecRegistry;
+use crate::registry::{CodecDescriptor, CodecRegistry};
 
 /// 注册所有内置解码器
+///
+/// 能力描述中的格式为解码输出格式.
 pub fn register_all_decoders(registry: &mut CodecRegistry) {
+    let audio = |id, name, formats: &[SampleFormat]| {
+        CodecDescriptor::new(id, name).with_sample_formats(formats)
+    };
+    let video =
+        |id, name| CodecDescriptor::new(id, name).with_pixel_formats(&[PixelFormat::Yuv420p]);
+    let int_formats = [SampleFormat::U8, SampleFormat::S16, SampleFormat::S32];
+
     registry.register_decoder(
         CodecId::RawVideo,
         "rawvideo",
         rawvideo::RawVideoDecoder::create,
     );
-    registry.register_decoder(CodecId::PcmU8, "pcm_u8", pcm::PcmDecoder::new_u8);
-    registry.register_decoder(CodecId::PcmS16le, "pcm_s16le", pcm::PcmDecoder::new_s16le);
-    registry.register_decoder(CodecId::PcmS16be, "pcm_s16be", pcm::PcmDecoder::new_s16be);
-    registry.register_decoder(CodecId::PcmS24le, "pcm_s24le", pcm::PcmDecoder::new_s24le);
-    registry.register_decoder(CodecId::PcmS32le, "pcm_s32le", pcm::PcmDecoder::new_s32le);
-    registry.register_decoder(CodecId::PcmF32le, "pcm_f32le", pcm::PcmDecoder::new_f32le);
-    registry.register_decoder(CodecId::Flac, "flac", flac::FlacDecoder::create);
-    registry.register_decoder(CodecId::Aac, "aac", aac::AacDecoder::create);
-    registry.register_decoder(CodecId::Mp3, "mp3", mp3::Mp3Decoder::create);
-    registry.register_decoder(CodecId::H264, "h264", h264::H264Decoder::create);
-    registry.register_decoder(CodecId::H265, "hevc", h265::HevcDecoder::create);
-    registry.register_decoder(CodecId::Mpeg4, "mpeg4", mpeg4::Mpeg4Decoder::create);
-    registry.register_decoder(CodecId::Theora, "theora", theora::TheoraDecoder::create);
-    registry.register_decoder(CodecId::Vorbis, "vorbis", vorbis::VorbisDecoder::create);
+    registry.register_decoder_descriptor(
+        audio(CodecId::PcmU8, "pcm_u8", &[SampleFormat::U8]),
+        pcm::PcmDecoder::new_u8,
+    );
+    registry.register_decoder_descriptor(
+        audio(CodecId::PcmS16le, "pcm_s16le", &[SampleFormat::S16]),
+        pcm::PcmDecoder::new_s16le,
+    );
+    registry.register_decoder_descriptor(
+        audio(CodecId::PcmS16be, "pcm_s16be", &[SampleFormat::S16]),
+        pcm::PcmDecoder::new_s16be,
+    );
+    registry.register_decoder_descriptor(
+        audio(
+            CodecId::PcmS24le,
+            "pcm_s24le",
+            &[SampleFormat::S32, SampleFormat::S24],
+        ),
+        pcm::PcmDecoder::new_s24le,
+    );
+    registry.register_decoder_descriptor(
+        audio(CodecId::PcmS32le, "pcm_s32le", &[SampleFormat::S32]),
+        pcm::PcmDecoder::new_s32le,
+    );
+    registry.register_decoder_descriptor(
+        audio(CodecId::PcmF32le, "pcm_f32le", &[SampleFormat::F32]),
+        pcm::PcmDecoder::new_f32le,
+    );
+    registry.register_decoder_descriptor(
+        audio(CodecId::AdpcmImaWav, "adpcm_ima_wav", &[SampleFormat::S16]),
+        adpcm::AdpcmDecoder::new_ima_wav,
+    );
+    registry.register_decoder_descriptor(
+        audio(CodecId::AdpcmMs, "adpcm_ms", &[SampleFormat::S16]),
+        adpcm::AdpcmDecoder::new_ms,
+    );
+    registry.register_decoder_descriptor(
+        audio(CodecId::Flac, "flac", &int_formats),
+        flac::FlacDecoder::create,
+    );
+    registry.register_decoder_descriptor(
+        audio(CodecId::Aac, "aac", &[SampleFormat::F32]),
+        aac::AacDecoder::create,
+    );
+    registry.register_decoder_descriptor(
+        audio(CodecId::Ac3, "ac3", &[SampleFormat::F32]),
+        ac3::Ac3Decoder::create,
+    );
+    registry.register_decoder_descriptor(
+        audio(CodecId::Mp3, "mp3", &[SampleFormat::F32]),
+        mp3::Mp3Decoder::create,
+    );
+    registry.register_decoder_descriptor(video(CodecId::H264, "h264"), h264::H264Decoder::create);
+    registry.register_decoder_descriptor(video(CodecId::H265, "hevc"), h265::HevcDecoder::create);
+    registry
+        .register_decoder_descriptor(video(CodecId::Mpeg4, "mpeg4"), mpeg4::Mpeg4Decoder::create);
+    registry.register_decoder_descriptor(
+        CodecDescriptor::new(CodecId::Mjpeg, "mjpeg").with_pixel_formats(&mjpeg::OUTPUT_FORMATS),
+        mjpeg::MjpegDecoder::create,
+    );
+    registry.register_decoder_descriptor(
+        video(CodecId::Theora, "theora"),
+        theora::TheoraDecoder::create,
+    );
+    registry.register_decoder_descriptor(
+        audio(CodecId::Vorbis, "vorbis", &[SampleFormat::F32]),
+        vorbis::VorbisDecoder::create,
+    );
+    registry.register_decoder(CodecId::Srt, "srt", subtitle::SrtDecoder::create);
+    registry.register_decoder(CodecId::Webvtt, "webvtt", subtitle::WebvttDecoder::create);
 }
@@ -21,6 +21,7 @@ use crate::codec_id::CodecId;
 use crate::codec_parameters::CodecParameters;
 use crate::decoder::Decoder;
 use crate::frame::{AudioFrame, Frame};
+use crate::frame_pool::{self, FramePool};
 use crate::packet::Packet;
 use tao_core::{ChannelLayout, Rational, SampleFormat, TaoError, TaoResult};
 
@@ -69,6 +70,8 @@ pub struct Mp3Decoder {
     total_decoded_samples: u64,
     /// 总有效样本数 (每通道, 计算自 total_frames * spf - delay - padding)
     valid_samples_total: u64,
+    /// 输出缓冲池, 未指定时每帧单独分配
+    pool: Option<FramePool>,
 }
 
 impl Mp3Decoder {
@@ -90,6 +93,7 @@ impl Mp3Decoder {
             delay_skipped: 0,
             total_decoded_samples: 0,
             valid_samples_total: 0,
+            pool: None,
         }))
     }
 
@@ -870,8 +874,10 @@ impl Mp3Decoder {
             SampleFormat::F32,
             ChannelLayout::from_channels(nch as u32),
         );
-        let pcm_bytes: Vec<u8> = trimmed_pcm.iter().flat_map(|s| s.to_le_bytes()).collect();
-        frame.data = vec![pcm_bytes];
+        let mut pcm_bytes =
+            frame_pool::take_vec_or_alloc(self.pool.as_ref(), trimmed_pcm.len() * 4);
+        pcm_bytes.extend(trimmed_pcm.iter().flat_map(|s| s.to_le_bytes()));
+        frame.data = vec![frame_pool::wrap_in(self.pool.as_ref(), pcm_bytes)];
         frame.pts = self.next_pts;
         frame.time_base = Rational::new(1, header.samplerate as i32);
         frame.duration = nb_samples as i64;
@@ -927,6 +933,10 @@ impl Decoder for Mp3Decoder {
         Ok(())
     }
 
+    fn set_frame_pool(&mut self, pool: &FramePool) {
+        self.pool = Some(pool.clone());
+    }
+
     fn send_packet(&mut self, packet: &Packet) -> TaoResult<()> {
         if !self.opened {
             return Err(TaoError::Codec("MP3 解码器未打开".into()));
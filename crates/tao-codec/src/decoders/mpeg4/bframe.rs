@@ -26,9 +26,9 @@ impl Mpeg4Decoder {
 
         let y_size = (self.width * self.height) as usize;
         let uv_size = y_size / 4;
-        frame.data[0] = vec![128u8; y_size];
-        frame.data[1] = vec![128u8; uv_size];
-        frame.data[2] = vec![128u8; uv_size];
+        frame.data[0] = vec![128u8; y_size].into();
+        frame.data[1] = vec![128u8; uv_size].into();
+        frame.data[2] = vec![128u8; uv_size].into();
         frame.linesize[0] = self.width as usize;
         frame.linesize[1] = (self.width / 2) as usize;
         frame.linesize[2] = (self.width / 2) as usize;
@@ -352,9 +352,9 @@ mod tests {
 
     fn create_test_frame() -> VideoFrame {
         let mut frame = VideoFrame::new(4, 4, PixelFormat::Yuv420p);
-        frame.data[0] = vec![0u8; 16];
-        frame.data[1] = vec![128u8; 4];
-        frame.data[2] = vec![128u8; 4];
+        frame.data[0] = vec![0u8; 16].into();
+        frame.data[1] = vec![128u8; 4].into();
+        frame.data[2] = vec![128u8; 4].into();
         frame.linesize[0] = 4;
         frame.linesize[1] = 2;
         frame.linesize[2] = 2;
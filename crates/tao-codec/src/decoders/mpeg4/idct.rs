@@ -168,7 +168,7 @@ fn idct_col(block: &mut [i32; 64], col: usize) {
 }
 
 /// 完整 8x8 IDCT (行+列)
-pub(super) fn idct_8x8(block: &mut [i32; 64]) {
+pub(crate) fn idct_8x8(block: &mut [i32; 64]) {
     for row in 0..8 {
         idct_row(block, row);
     }
@@ -41,7 +41,7 @@ mod dequant;
 mod frame_decode;
 mod gmc;
 mod header;
-mod idct;
+pub(crate) mod idct;
 mod motion;
 mod packet_io;
 mod partitioned;
@@ -58,7 +58,7 @@ use crate::codec_id::CodecId;
 use crate::codec_parameters::{CodecParameters, CodecParamsType};
 use crate::decoder::Decoder;
 use crate::frame::{Frame, PictureType, VideoFrame};
-use crate::packet::Packet;
+use crate::packet::{Packet, PacketFlags};
 
 use bitreader::{BitReader, find_start_code_offset};
 use block::{decode_inter_block_vlc, decode_intra_block_vlc};
@@ -562,7 +562,8 @@ impl Decoder for Mpeg4Decoder {
                 duration: packet.duration,
                 time_base: packet.time_base,
                 stream_index: packet.stream_index,
-                is_keyframe: false,
+                flags: PacketFlags::empty(),
+                side_data: Vec::new(),
                 pos: -1,
             };
             return self.send_packet_standard(&queued_packet);
@@ -610,7 +611,8 @@ impl Decoder for Mpeg4Decoder {
                     duration: packet.duration,
                     time_base: packet.time_base,
                     stream_index: packet.stream_index,
-                    is_keyframe: packet.is_keyframe,
+                    flags: packet.flags,
+                    side_data: packet.side_data.clone(),
                     pos: packet.pos,
                 };
                 return self.send_packet_standard(&first_packet);
@@ -202,9 +202,9 @@ fn test_direct_mode_mv_computation() {
 fn test_qpel_mc_full_pixel() {
     // 全像素位置 (dx=0, dy=0): 应直接返回参考像素
     let mut ref_frame = VideoFrame::new(16, 16, PixelFormat::Yuv420p);
-    ref_frame.data[0] = vec![0u8; 16 * 16];
-    ref_frame.data[1] = vec![128u8; 8 * 8];
-    ref_frame.data[2] = vec![128u8; 8 * 8];
+    ref_frame.data[0] = vec![0u8; 16 * 16].into();
+    ref_frame.data[1] = vec![128u8; 8 * 8].into();
+    ref_frame.data[2] = vec![128u8; 8 * 8].into();
     ref_frame.linesize[0] = 16;
     ref_frame.linesize[1] = 8;
     ref_frame.linesize[2] = 8;
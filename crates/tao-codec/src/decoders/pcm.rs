@@ -6,10 +6,12 @@
 use tao_core::{ChannelLayout, SampleFormat, TaoError, TaoResult};
 use tracing::debug;
 
+use crate::capabilities::CodecCapabilities;
 use crate::codec_id::CodecId;
 use crate::codec_parameters::{CodecParameters, CodecParamsType};
 use crate::decoder::Decoder;
 use crate::frame::{AudioFrame, Frame};
+use crate::frame_pool::FramePool;
 use crate::packet::Packet;
 
 /// PCM 格式描述, 描述各 PCM 变体的差异
@@ -37,7 +39,7 @@ fn decode_s16be(src: &[u8], dst: &mut Vec<u8>) {
     }
 }
 
-/// S24LE 符号扩展到 S32: 3 字节 -> 4 字节
+/// S24LE (紧凑 3 字节) 符号扩展到 32 位容器: 3 字节 -> 4 字节
 fn decode_s24le(src: &[u8], dst: &mut Vec<u8>) {
     for chunk in src.chunks_exact(3) {
         // 24 位小端: [低字节, 中字节, 高字节]
@@ -105,6 +107,8 @@ pub struct PcmDecoder {
     frame_size: u32,
     /// 每个样本块的字节数 (每样本字节数 * 声道数)
     block_align: u32,
+    /// 输出缓冲池, 未指定时每帧单独分配
+    pool: Option<FramePool>,
     /// 已解码帧缓冲
     output_frame: Option<Frame>,
     /// 是否已打开
@@ -124,6 +128,7 @@ impl PcmDecoder {
             channel_layout: ChannelLayout::MONO,
             frame_size: 0,
             block_align: 0,
+            pool: None,
             output_frame: None,
             opened: false,
             flushing: false,
@@ -164,6 +169,10 @@ impl Decoder for PcmDecoder {
         self.desc.codec_id.name()
     }
 
+    fn codec_capabilities(&self) -> CodecCapabilities {
+        CodecCapabilities::CAPS_INTRA_ONLY | CodecCapabilities::CAPS_LOSSLESS
+    }
+
     fn open(&mut self, params: &CodecParameters) -> TaoResult<()> {
         let audio = match &params.params {
             CodecParamsType::Audio(a) => a,
@@ -179,6 +188,15 @@ impl Decoder for PcmDecoder {
             return Err(TaoError::InvalidArgument("声道数不能为 0".into()));
         }
 
+        // 24 位 PCM: 参数显式要求 S24 时输出 S24, 否则保持 S32 输出
+        if self.desc.codec_id == CodecId::PcmS24le {
+            self.desc.output_format = if audio.sample_format.to_interleaved() == SampleFormat::S24 {
+                SampleFormat::S24
+            } else {
+                SampleFormat::S32
+            };
+        }
+
         self.sample_rate = audio.sample_rate;
         self.channel_layout = audio.channel_layout;
         self.frame_size = audio.frame_size;
@@ -197,6 +215,10 @@ impl Decoder for PcmDecoder {
         Ok(())
     }
 
+    fn set_frame_pool(&mut self, pool: &FramePool) {
+        self.pool = Some(pool.clone());
+    }
+
     fn send_packet(&mut self, packet: &Packet) -> TaoResult<()> {
         if !self.opened {
             return Err(TaoError::Codec("解码器未打开, 请先调用 open()".into()));
@@ -235,9 +257,18 @@ impl Decoder for PcmDecoder {
         let output_size = nb_samples as usize
             * self.channel_layout.channels as usize
             * output_sample_bytes as usize;
-        let mut decoded = Vec::with_capacity(output_size);
-        (self.desc.decode_fn)(&packet.data, &mut decoded);
-        frame.data[0] = decoded;
+        frame.data[0] = match &self.pool {
+            Some(pool) => {
+                let mut decoded = pool.take_vec(output_size);
+                (self.desc.decode_fn)(&packet.data, &mut decoded);
+                pool.wrap(decoded)
+            }
+            None => {
+                let mut decoded = Vec::with_capacity(output_size);
+                (self.desc.decode_fn)(&packet.data, &mut decoded);
+                decoded.into()
+            }
+        };
 
         self.output_frame = Some(Frame::Audio(frame));
         Ok(())
@@ -276,6 +307,7 @@ mod tests {
                 channel_layout: ChannelLayout::from_channels(channels),
                 sample_format: SampleFormat::None,
                 frame_size: 0,
+                block_align: 0,
             }),
         }
     }
@@ -362,6 +394,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pcm_s24le_output_s24_when_requested() {
+        let mut dec = PcmDecoder::new_s24le().unwrap();
+        let mut params = make_audio_params(CodecId::PcmS24le, 2);
+        if let CodecParamsType::Audio(a) = &mut params.params {
+            a.sample_format = SampleFormat::S24;
+        }
+        dec.open(&params).unwrap();
+
+        // 2 声道 1 个采样块: 0x7FFFFF, -1
+        let data = vec![0xFF, 0xFF, 0x7F, 0xFF, 0xFF, 0xFF];
+        dec.send_packet(&Packet::from_data(Bytes::from(data)))
+            .unwrap();
+        match dec.receive_frame().unwrap() {
+            Frame::Audio(af) => {
+                assert_eq!(af.nb_samples, 1);
+                assert_eq!(af.sample_format, SampleFormat::S24);
+                assert_eq!(af.data[0].len(), 8, "S24 每样本应占 4 字节");
+                let values: Vec<i32> = af.data[0]
+                    .chunks_exact(4)
+                    .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect();
+                assert_eq!(values, vec![8_388_607, -1]);
+            }
+            _ => panic!("期望音频帧"),
+        }
+    }
+
     #[test]
     fn test_pcm_s32le_decode() {
         let mut dec = PcmDecoder::new_s32le().unwrap();
@@ -401,6 +461,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pcm_decode_reuses_pool_buffers() {
+        let pool = FramePool::new(2);
+        let mut dec = PcmDecoder::new_s16le().unwrap();
+        dec.set_frame_pool(&pool);
+        dec.open(&make_audio_params(CodecId::PcmS16le, 2)).unwrap();
+
+        let pkt = Packet::from_data(Bytes::from(vec![1u8; 4096]));
+        dec.send_packet(&pkt).unwrap();
+        let first = match dec.receive_frame().unwrap() {
+            Frame::Audio(af) => af,
+            _ => panic!("期望音频帧"),
+        };
+        let ptr = first.data[0].as_ptr();
+        drop(first);
+        assert_eq!(pool.free_count(), 1, "释放的帧缓冲应归还池中");
+
+        let pkt = Packet::from_data(Bytes::from(vec![2u8; 4096]));
+        dec.send_packet(&pkt).unwrap();
+        let second = match dec.receive_frame().unwrap() {
+            Frame::Audio(af) => af,
+            _ => panic!("期望音频帧"),
+        };
+        assert_eq!(second.data[0].as_ptr(), ptr, "后续帧应复用归还的缓冲");
+        assert_eq!(second.data[0], vec![2u8; 4096]);
+        assert_eq!(pool.free_count(), 0);
+    }
+
+    #[test]
+    fn test_pcm_decode_many_frames_allocation_bounded() {
+        let pool = FramePool::new(2);
+        let mut dec = PcmDecoder::new_s16le().unwrap();
+        dec.set_frame_pool(&pool);
+        dec.open(&make_audio_params(CodecId::PcmS16le, 2)).unwrap();
+
+        for i in 0..100u8 {
+            dec.send_packet(&Packet::from_data(Bytes::from(vec![i; 4096])))
+                .unwrap();
+            match dec.receive_frame().unwrap() {
+                Frame::Audio(af) => assert_eq!(af.data[0][0], i),
+                _ => panic!("期望音频帧"),
+            }
+        }
+        assert_eq!(
+            pool.allocation_count(),
+            1,
+            "逐帧释放时 100 帧应只分配一次缓冲"
+        );
+    }
+
     #[test]
     fn test_not_open_error() {
         let mut dec = PcmDecoder::new_s16le().unwrap();
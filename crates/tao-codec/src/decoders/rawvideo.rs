@@ -6,12 +6,17 @@
 use tao_core::{PixelFormat, TaoError, TaoResult};
 use tracing::debug;
 
+use crate::capabilities::CodecCapabilities;
 use crate::codec_id::CodecId;
 use crate::codec_parameters::{CodecParameters, CodecParamsType};
 use crate::decoder::Decoder;
 use crate::frame::{Frame, PictureType, VideoFrame};
+use crate::frame_pool::FramePool;
 use crate::packet::Packet;
 
+/// 每个平面保留的空闲缓冲数量 (待输出帧 + 下游持有的帧)
+const POOL_FRAMES: usize = 4;
+
 /// RAW 视频解码器
 pub struct RawVideoDecoder {
     /// 图像宽度
@@ -28,6 +33,8 @@ pub struct RawVideoDecoder {
     plane_heights: Vec<usize>,
     /// 已解码帧缓冲
     output_frame: Option<Frame>,
+    /// 输出平面缓冲池
+    pool: FramePool,
     /// 是否已打开 (配置参数)
     opened: bool,
     /// 是否已收到刷新信号 (空包)
@@ -44,6 +51,7 @@ impl RawVideoDecoder {
             linesizes: Vec::new(),
             plane_heights: Vec::new(),
             output_frame: None,
+            pool: FramePool::new(0),
             opened: false,
             flushing: false,
         }))
@@ -59,6 +67,10 @@ impl Decoder for RawVideoDecoder {
         "rawvideo"
     }
 
+    fn codec_capabilities(&self) -> CodecCapabilities {
+        CodecCapabilities::CAPS_INTRA_ONLY | CodecCapabilities::CAPS_LOSSLESS
+    }
+
     fn open(&mut self, params: &CodecParameters) -> TaoResult<()> {
         let video = match &params.params {
             CodecParamsType::Video(v) => v,
@@ -102,6 +114,7 @@ impl Decoder for RawVideoDecoder {
         self.linesizes = linesizes;
         self.plane_heights = plane_heights;
         self.output_frame = None;
+        self.pool = FramePool::new(self.linesizes.len() * POOL_FRAMES);
         self.opened = true;
         self.flushing = false;
 
@@ -145,7 +158,9 @@ impl Decoder for RawVideoDecoder {
         let mut offset = 0usize;
         for i in 0..self.linesizes.len() {
             let plane_size = self.linesizes[i] * self.plane_heights[i];
-            frame.data[i] = packet.data[offset..offset + plane_size].to_vec();
+            frame.data[i] = self
+                .pool
+                .copy_from_slice(&packet.data[offset..offset + plane_size]);
             frame.linesize[i] = self.linesizes[i];
             offset += plane_size;
         }
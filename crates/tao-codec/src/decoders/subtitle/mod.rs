@@ -0,0 +1,235 @@
+//! 文本字幕解码器.
+//!
+//! 数据包可以是单条字幕的正文 (如 Matroska 中的字幕块, 时间由数据包给出),
+//! 也可以是完整的字幕块 (可选的编号/标识行 + 时间行 + 正文).
+//! 字幕块带时间行时以时间行为准, 否则使用数据包的 pts 与 duration.
+
+pub mod srt;
+pub mod webvtt;
+
+pub use srt::SrtDecoder;
+pub use webvtt::WebvttDecoder;
+
+use std::collections::VecDeque;
+
+use tao_core::rational::rescale_q;
+use tao_core::{Rational, TaoError, TaoResult};
+
+use crate::frame::{Frame, SubtitleFrame};
+use crate::packet::Packet;
+
+/// 数据包时间基无效时, 时间行换算所用的时间基 (毫秒)
+const MILLISECONDS: Rational = Rational::new(1, 1000);
+
+/// 字幕块的时间行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct CueTiming {
+    /// 开始时间 (毫秒)
+    start_ms: i64,
+    /// 结束时间 (毫秒)
+    end_ms: i64,
+    /// 结束时间之后的样式/位置设置
+    settings: Option<String>,
+}
+
+/// 解析得到的一条字幕
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Cue {
+    /// 时间行, 数据包仅含正文时为 None
+    timing: Option<CueTiming>,
+    /// 已去除标记的正文
+    text: String,
+}
+
+/// SRT/WebVTT 解码器共用的状态: 打开标志、刷新标志与待输出帧队列
+struct SubtitleDecoderState {
+    opened: bool,
+    flushing: bool,
+    frames: VecDeque<Frame>,
+}
+
+impl SubtitleDecoderState {
+    fn new() -> Self {
+        Self {
+            opened: false,
+            flushing: false,
+            frames: VecDeque::new(),
+        }
+    }
+
+    fn open(&mut self) {
+        self.opened = true;
+        self.flushing = false;
+        self.frames.clear();
+    }
+
+    /// 以 `parse` 解析数据包正文, 每条字幕生成一个字幕帧
+    fn send_packet(
+        &mut self,
+        packet: &Packet,
+        name: &str,
+        parse: fn(&str) -> TaoResult<Vec<Cue>>,
+    ) -> TaoResult<()> {
+        if !self.opened {
+            return Err(TaoError::Codec("解码器未打开, 请先调用 open()".into()));
+        }
+
+        // 空包 = flush
+        if packet.is_empty() {
+            self.flushing = true;
+            return Ok(());
+        }
+
+        let text = std::str::from_utf8(&packet.data)
+            .map_err(|e| TaoError::InvalidData(format!("{name} 字幕不是有效的 UTF-8: {e}")))?;
+        let text = text.strip_prefix('\u{FEFF}').unwrap_or(text);
+        let cues = parse(text)?;
+
+        for cue in cues {
+            let frame = match cue.timing {
+                Some(timing) => {
+                    let time_base = if packet.time_base.is_valid() {
+                        packet.time_base
+                    } else {
+                        MILLISECONDS
+                    };
+                    let pts = rescale_q(timing.start_ms, MILLISECONDS, time_base);
+                    let end = rescale_q(timing.end_ms, MILLISECONDS, time_base);
+                    SubtitleFrame {
+                        text: cue.text,
+                        pts,
+                        duration: end - pts,
+                        style: timing.settings,
+                    }
+                }
+                None => SubtitleFrame {
+                    text: cue.text,
+                    pts: packet.pts,
+                    duration: packet.duration,
+                    style: None,
+                },
+            };
+            self.frames.push_back(Frame::Subtitle(frame));
+        }
+        Ok(())
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        if let Some(frame) = self.frames.pop_front() {
+            return Ok(frame);
+        }
+        if self.flushing {
+            return Err(TaoError::Eof);
+        }
+        Err(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) {
+        self.frames.clear();
+        self.flushing = false;
+    }
+}
+
+/// 按空行切分字幕块, 返回每块的行 (已统一换行符)
+fn split_blocks(text: &str) -> Vec<Vec<&str>> {
+    let mut blocks = Vec::new();
+    let mut current = Vec::new();
+    for line in text.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+    blocks
+}
+
+/// 是否为时间行
+fn is_timing_line(line: &str) -> bool {
+    line.contains("-->")
+}
+
+/// 解析 `开始 --> 结束 [设置]` 形式的时间行
+fn parse_timing_line(
+    line: &str,
+    name: &str,
+    parse_timestamp: fn(&str) -> Option<i64>,
+) -> TaoResult<CueTiming> {
+    let invalid = || TaoError::InvalidData(format!("{name} 时间行格式错误: {line:?}"));
+    let (start, rest) = line.split_once("-->").ok_or_else(invalid)?;
+    let rest = rest.trim_start();
+    let (end, settings) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let start_ms = parse_timestamp(start.trim()).ok_or_else(invalid)?;
+    let end_ms = parse_timestamp(end).ok_or_else(invalid)?;
+    if end_ms < start_ms {
+        return Err(TaoError::InvalidData(format!(
+            "{name} 字幕结束时间早于开始时间: {line:?}"
+        )));
+    }
+    let settings = settings.trim();
+    Ok(CueTiming {
+        start_ms,
+        end_ms,
+        settings: (!settings.is_empty()).then(|| settings.to_string()),
+    })
+}
+
+/// 解析 `[时:]分:秒<sep>毫秒`, `hours_required` 为 true 时必须包含小时
+///
+/// 分、秒须为两位数且小于 60, 毫秒须为三位数, 小时至少一位.
+fn parse_clock(text: &str, separators: &[char], hours_required: bool) -> Option<i64> {
+    let (clock, millis) = text.rsplit_once(separators)?;
+    let parts: Vec<&str> = clock.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m, s] => (*h, *m, *s),
+        [m, s] if !hours_required => ("0", *m, *s),
+        _ => return None,
+    };
+    let number = |s: &str, digits: Option<usize>| {
+        let valid = !s.is_empty()
+            && s.bytes().all(|b| b.is_ascii_digit())
+            && digits.is_none_or(|d| s.len() == d);
+        valid.then(|| s.parse::<i64>().ok()).flatten()
+    };
+    let hours = number(hours, None)?;
+    let minutes = number(minutes, Some(2)).filter(|&m| m < 60)?;
+    let seconds = number(seconds, Some(2)).filter(|&s| s < 60)?;
+    let millis = number(millis, Some(3))?;
+    hours
+        .checked_mul(3_600_000)?
+        .checked_add(minutes * 60_000 + seconds * 1000 + millis)
+}
+
+/// 去除 `<i>`/`<font ...>`/`<c.yellow>`/`<00:00:01.000>` 等尖括号标签
+fn strip_tags(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut in_tag = false;
+    for ch in raw.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if in_tag => {}
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// 逐行整理正文: 去除首尾空白并丢弃空行, 多行以 '\n' 连接
+fn join_lines<'a>(lines: impl IntoIterator<Item = &'a str>, clean: fn(&str) -> String) -> String {
+    lines
+        .into_iter()
+        .map(clean)
+        .filter_map(|line| {
+            let line = line.trim();
+            (!line.is_empty()).then(|| line.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,275 @@
+//! SRT (SubRip) 字幕解码器.
+//!
+//! 字幕块格式:
+//! ```text
+//! 1
+//! 00:00:01,500 --> 00:00:04,000 X1:100 X2:200 Y1:50 Y2:80
+//! <i>第一行</i>
+//! 第二行
+//! ```
+//! 编号行可省略; 时间行结束时间之后的坐标设置作为样式输出.
+//! 正文中的 `<i>`/`<font ...>` 标签与 `{\an8}` 等覆盖标签会被去除.
+
+use tao_core::{TaoError, TaoResult};
+use tracing::debug;
+
+use super::{Cue, SubtitleDecoderState, is_timing_line, join_lines, parse_clock, strip_tags};
+use crate::codec_id::CodecId;
+use crate::codec_parameters::CodecParameters;
+use crate::decoder::Decoder;
+use crate::frame::Frame;
+use crate::packet::Packet;
+
+/// SRT 字幕解码器
+pub struct SrtDecoder {
+    state: SubtitleDecoderState,
+}
+
+impl SrtDecoder {
+    pub fn create() -> TaoResult<Box<dyn Decoder>> {
+        Ok(Box::new(Self {
+            state: SubtitleDecoderState::new(),
+        }))
+    }
+}
+
+impl Decoder for SrtDecoder {
+    fn codec_id(&self) -> CodecId {
+        CodecId::Srt
+    }
+
+    fn name(&self) -> &str {
+        "srt"
+    }
+
+    fn open(&mut self, _params: &CodecParameters) -> TaoResult<()> {
+        self.state.open();
+        debug!("打开 SRT 字幕解码器");
+        Ok(())
+    }
+
+    fn send_packet(&mut self, packet: &Packet) -> TaoResult<()> {
+        self.state.send_packet(packet, "SRT", parse_srt)
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        self.state.receive_frame()
+    }
+
+    fn flush(&mut self) {
+        self.state.flush();
+    }
+}
+
+/// 解析数据包中的 SRT 字幕
+///
+/// 不含时间行的数据包 (如 Matroska `S_TEXT/UTF8`) 整体作为一条字幕正文.
+fn parse_srt(text: &str) -> TaoResult<Vec<Cue>> {
+    let blocks = super::split_blocks(text);
+    if !blocks.iter().flatten().any(|line| is_timing_line(line)) {
+        let text = join_lines(blocks.iter().flatten().copied(), clean_line);
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        return Ok(vec![Cue { timing: None, text }]);
+    }
+
+    let mut cues = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        let timing_index = if is_timing_line(block[0]) {
+            0
+        } else if block.get(1).is_some_and(|line| is_timing_line(line)) {
+            let index = block[0].trim();
+            if !index.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(TaoError::InvalidData(format!(
+                    "SRT 字幕编号无效: {index:?}"
+                )));
+            }
+            1
+        } else {
+            return Err(TaoError::InvalidData(format!(
+                "SRT 字幕块缺少时间行: {:?}",
+                block[0]
+            )));
+        };
+        let timing = super::parse_timing_line(block[timing_index], "SRT", parse_timestamp)?;
+        cues.push(Cue {
+            timing: Some(timing),
+            text: join_lines(block[timing_index + 1..].iter().copied(), clean_line),
+        });
+    }
+    Ok(cues)
+}
+
+/// 解析 `HH:MM:SS,mmm` (兼容以 '.' 分隔毫秒)
+fn parse_timestamp(text: &str) -> Option<i64> {
+    parse_clock(text, &[',', '.'], true)
+}
+
+/// 去除尖括号标签与 `{\...}` 覆盖标签
+fn clean_line(line: &str) -> String {
+    let line = strip_tags(line);
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line.as_str();
+    while let Some(start) = rest.find("{\\") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec_parameters::CodecParamsType;
+    use crate::frame::SubtitleFrame;
+    use tao_core::Rational;
+
+    fn open_decoder() -> Box<dyn Decoder> {
+        let mut dec = SrtDecoder::create().unwrap();
+        dec.open(&CodecParameters {
+            codec_id: CodecId::Srt,
+            extra_data: Vec::new(),
+            bit_rate: 0,
+            params: CodecParamsType::None,
+        })
+        .unwrap();
+        dec
+    }
+
+    fn make_packet(text: &[u8], time_base: Rational) -> Packet {
+        let mut pkt = Packet::from_data(text.to_vec());
+        pkt.time_base = time_base;
+        pkt
+    }
+
+    fn decode_all(dec: &mut dyn Decoder, pkt: &Packet) -> Vec<SubtitleFrame> {
+        dec.send_packet(pkt).expect("送入 SRT 数据包失败");
+        let mut frames = Vec::new();
+        loop {
+            match dec.receive_frame() {
+                Ok(Frame::Subtitle(sf)) => frames.push(sf),
+                Ok(_) => panic!("应输出字幕帧"),
+                Err(TaoError::NeedMoreData) => return frames,
+                Err(e) => panic!("取字幕帧失败: {e}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_srt_decode_cue_block() {
+        let mut dec = open_decoder();
+        let pkt = make_packet(
+            b"1\r\n00:00:01,500 --> 00:00:04,000 X1:10 X2:20\r\n<i>Hello</i>\r\n{\\an8}<font color=\"red\">World</font>\r\n",
+            Rational::new(1, 1000),
+        );
+        let frames = decode_all(dec.as_mut(), &pkt);
+        assert_eq!(
+            frames,
+            vec![SubtitleFrame {
+                text: "Hello\nWorld".into(),
+                pts: 1500,
+                duration: 2500,
+                style: Some("X1:10 X2:20".into()),
+            }],
+            "应去除标签并按时间行输出"
+        );
+    }
+
+    #[test]
+    fn test_srt_decode_multiple_cues_rescaled() {
+        let mut dec = open_decoder();
+        let pkt = make_packet(
+            "1\n00:00:01,000 --> 00:00:02,000\n第一条\n\n2\n01:00:00.500 --> 01:00:01,000\n第二条\n第二行\n"
+                .as_bytes(),
+            Rational::new(1, 90000),
+        );
+        let frames = decode_all(dec.as_mut(), &pkt);
+        assert_eq!(frames.len(), 2, "应输出两条字幕");
+        assert_eq!((frames[0].pts, frames[0].duration), (90000, 90000));
+        assert_eq!(frames[0].style, None, "无坐标设置时样式应为 None");
+        assert_eq!(frames[1].text, "第二条\n第二行");
+        assert_eq!(
+            (frames[1].pts, frames[1].duration),
+            (3600 * 90000 + 45000, 45000),
+            "时间行应换算到数据包时间基"
+        );
+    }
+
+    #[test]
+    fn test_srt_text_only_uses_packet_timestamps() {
+        let mut dec = open_decoder();
+        let mut pkt = make_packet(b"<b>Line one</b>\nLine two\n", Rational::new(1, 1000));
+        pkt.pts = 12_000;
+        pkt.duration = 3_000;
+        let frames = decode_all(dec.as_mut(), &pkt);
+        assert_eq!(
+            frames,
+            vec![SubtitleFrame {
+                text: "Line one\nLine two".into(),
+                pts: 12_000,
+                duration: 3_000,
+                style: None,
+            }],
+            "仅含正文的数据包应使用数据包时间戳"
+        );
+
+        // 时间基无效时时间行以毫秒输出
+        let pkt = make_packet(b"00:00:02,000 --> 00:00:03,250\nx\n", Rational::UNDEFINED);
+        let frames = decode_all(dec.as_mut(), &pkt);
+        assert_eq!((frames[0].pts, frames[0].duration), (2000, 1250));
+    }
+
+    #[test]
+    fn test_srt_invalid_syntax_rejected() {
+        let mut dec = open_decoder();
+        for text in [
+            &b"1\n00:00:01 --> 00:00:02,000\nx\n"[..],
+            b"1\n00:00:03,000 --> 00:00:02,000\nx\n",
+            b"1\n00:61:00,000 --> 01:02:00,000\nx\n",
+            b"a\n00:00:01,000 --> 00:00:02,000\nx\n",
+            b"00:00:01,000 --> 00:00:02,000\nx\n\nstray text\n",
+            b"\xff\xfe\n",
+        ] {
+            let pkt = make_packet(text, Rational::new(1, 1000));
+            assert!(
+                matches!(dec.send_packet(&pkt), Err(TaoError::InvalidData(_))),
+                "非法 SRT 应返回 InvalidData: {:?}",
+                String::from_utf8_lossy(text)
+            );
+        }
+        assert!(
+            matches!(dec.receive_frame(), Err(TaoError::NeedMoreData)),
+            "非法数据包不应产生字幕帧"
+        );
+    }
+
+    #[test]
+    fn test_srt_flush_eof() {
+        let mut dec = open_decoder();
+        let pkt = make_packet(
+            b"00:00:00,000 --> 00:00:01,000\nx\n",
+            Rational::new(1, 1000),
+        );
+        dec.send_packet(&pkt).unwrap();
+        dec.send_packet(&Packet::empty()).unwrap();
+        assert!(matches!(dec.receive_frame(), Ok(Frame::Subtitle(_))));
+        assert!(
+            matches!(dec.receive_frame(), Err(TaoError::Eof)),
+            "刷新后取完帧应返回 Eof"
+        );
+        dec.flush();
+        assert!(matches!(dec.receive_frame(), Err(TaoError::NeedMoreData)));
+    }
+
+    #[test]
+    fn test_srt_requires_open() {
+        let mut dec = SrtDecoder::create().unwrap();
+        let pkt = make_packet(b"x", Rational::new(1, 1000));
+        assert!(dec.send_packet(&pkt).is_err(), "未打开时送包应失败");
+    }
+}
@@ -0,0 +1,256 @@
+//! WebVTT 字幕解码器.
+//!
+//! 支持完整的 WebVTT 文本 (以 `WEBVTT` 头开始, 可含 NOTE/STYLE/REGION 块)
+//! 与 Matroska `S_TEXT/WEBVTT` 等仅含 cue 正文的数据包.
+//! 时间行中的 cue 设置 (如 `align:start line:0`) 作为样式输出,
+//! 正文中的 `<v Speaker>`/`<c.class>`/时间戳标签会被去除, 并解码 `&amp;` 等字符引用.
+
+use tao_core::{TaoError, TaoResult};
+use tracing::debug;
+
+use super::{
+    Cue, SubtitleDecoderState, is_timing_line, join_lines, parse_clock, parse_timing_line,
+    split_blocks, strip_tags,
+};
+use crate::codec_id::CodecId;
+use crate::codec_parameters::CodecParameters;
+use crate::decoder::Decoder;
+use crate::frame::Frame;
+use crate::packet::Packet;
+
+/// WebVTT 字幕解码器
+pub struct WebvttDecoder {
+    state: SubtitleDecoderState,
+}
+
+impl WebvttDecoder {
+    pub fn create() -> TaoResult<Box<dyn Decoder>> {
+        Ok(Box::new(Self {
+            state: SubtitleDecoderState::new(),
+        }))
+    }
+}
+
+impl Decoder for WebvttDecoder {
+    fn codec_id(&self) -> CodecId {
+        CodecId::Webvtt
+    }
+
+    fn name(&self) -> &str {
+        "webvtt"
+    }
+
+    fn open(&mut self, _params: &CodecParameters) -> TaoResult<()> {
+        self.state.open();
+        debug!("打开 WebVTT 字幕解码器");
+        Ok(())
+    }
+
+    fn send_packet(&mut self, packet: &Packet) -> TaoResult<()> {
+        self.state.send_packet(packet, "WebVTT", parse_webvtt)
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        self.state.receive_frame()
+    }
+
+    fn flush(&mut self) {
+        self.state.flush();
+    }
+}
+
+/// 块首行是否以指定关键字开始 (关键字后须为行尾或空白)
+fn starts_with_keyword(line: &str, keyword: &str) -> bool {
+    line.strip_prefix(keyword)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+/// 解析数据包中的 WebVTT 字幕
+fn parse_webvtt(text: &str) -> TaoResult<Vec<Cue>> {
+    let blocks = split_blocks(text);
+    let has_header = blocks
+        .first()
+        .is_some_and(|block| starts_with_keyword(block[0], "WEBVTT"));
+    if !has_header && !blocks.iter().flatten().any(|line| is_timing_line(line)) {
+        // 仅含 cue 正文
+        let text = join_lines(blocks.iter().flatten().copied(), clean_line);
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        return Ok(vec![Cue { timing: None, text }]);
+    }
+
+    let mut cues = Vec::with_capacity(blocks.len());
+    for (index, block) in blocks.iter().enumerate() {
+        if index == 0 && has_header {
+            if block.iter().any(|line| is_timing_line(line)) {
+                return Err(TaoError::InvalidData("WebVTT 文件头后缺少空行".into()));
+            }
+            continue;
+        }
+        if ["NOTE", "STYLE", "REGION"]
+            .iter()
+            .any(|keyword| starts_with_keyword(block[0], keyword))
+        {
+            continue;
+        }
+
+        // 时间行之前可有一行 cue 标识
+        let timing_index = if is_timing_line(block[0]) {
+            0
+        } else if block.get(1).is_some_and(|line| is_timing_line(line)) {
+            1
+        } else {
+            return Err(TaoError::InvalidData(format!(
+                "WebVTT 字幕块缺少时间行: {:?}",
+                block[0]
+            )));
+        };
+        let timing = parse_timing_line(block[timing_index], "WebVTT", parse_timestamp)?;
+        let payload = &block[timing_index + 1..];
+        if payload.iter().any(|line| is_timing_line(line)) {
+            return Err(TaoError::InvalidData(
+                "WebVTT 字幕正文不能包含 \"-->\"".into(),
+            ));
+        }
+        cues.push(Cue {
+            timing: Some(timing),
+            text: join_lines(payload.iter().copied(), clean_line),
+        });
+    }
+    Ok(cues)
+}
+
+/// 解析 `[HH:]MM:SS.mmm`, 小时至少两位
+fn parse_timestamp(text: &str) -> Option<i64> {
+    let mut parts = text.split(':');
+    if text.matches(':').count() == 2 && parts.next().is_none_or(|hours| hours.len() < 2) {
+        return None;
+    }
+    parse_clock(text, &['.'], false)
+}
+
+/// 去除标签并解码字符引用
+fn clean_line(line: &str) -> String {
+    strip_tags(line)
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", "\u{A0}")
+        .replace("&lrm;", "\u{200E}")
+        .replace("&rlm;", "\u{200F}")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec_parameters::CodecParamsType;
+    use crate::frame::SubtitleFrame;
+    use crate::registry::CodecRegistry;
+    use tao_core::Rational;
+
+    fn open_decoder() -> Box<dyn Decoder> {
+        let mut dec = WebvttDecoder::create().unwrap();
+        dec.open(&CodecParameters {
+            codec_id: CodecId::Webvtt,
+            extra_data: Vec::new(),
+            bit_rate: 0,
+            params: CodecParamsType::None,
+        })
+        .unwrap();
+        dec
+    }
+
+    fn decode_text(dec: &mut dyn Decoder, text: &str, pts: i64) -> TaoResult<Vec<SubtitleFrame>> {
+        let mut pkt = Packet::from_data(text.as_bytes().to_vec());
+        pkt.pts = pts;
+        pkt.duration = 2000;
+        pkt.time_base = Rational::new(1, 1000);
+        dec.send_packet(&pkt)?;
+        let mut frames = Vec::new();
+        loop {
+            match dec.receive_frame() {
+                Ok(Frame::Subtitle(sf)) => frames.push(sf),
+                Ok(_) => panic!("应输出字幕帧"),
+                Err(TaoError::NeedMoreData) => return Ok(frames),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_webvtt_decode_file_with_header() {
+        let mut dec = open_decoder();
+        let text = "\u{FEFF}WEBVTT - 示例\n\nNOTE 注释块\n不输出\n\nSTYLE\n::cue { color: red }\n\n\
+                    intro\n00:01.000 --> 00:03.500 align:start line:0\n<v Alice>Tom &amp; Jerry</v>\n<c.yellow>&lt;3</c>\n\n\
+                    01:00:00.000 --> 01:00:01.000\n<00:00:00.500>第二条\n";
+        let frames = decode_text(dec.as_mut(), text, 0).expect("解码 WebVTT 失败");
+        assert_eq!(
+            frames,
+            vec![
+                SubtitleFrame {
+                    text: "Tom & Jerry\n<3".into(),
+                    pts: 1000,
+                    duration: 2500,
+                    style: Some("align:start line:0".into()),
+                },
+                SubtitleFrame {
+                    text: "第二条".into(),
+                    pts: 3_600_000,
+                    duration: 1000,
+                    style: None,
+                },
+            ],
+            "应跳过头部与 NOTE/STYLE 块并输出两条字幕"
+        );
+    }
+
+    #[test]
+    fn test_webvtt_payload_only_uses_packet_timestamps() {
+        let mut dec = open_decoder();
+        let frames = decode_text(dec.as_mut(), "<i>Hello</i>\r\nworld\r\n", 7000).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].text, "Hello\nworld");
+        assert_eq!(
+            (frames[0].pts, frames[0].duration),
+            (7000, 2000),
+            "仅含正文的数据包应使用数据包时间戳"
+        );
+
+        let frames = decode_text(dec.as_mut(), "WEBVTT\n", 0).unwrap();
+        assert!(frames.is_empty(), "仅含文件头时不应输出字幕");
+    }
+
+    #[test]
+    fn test_webvtt_invalid_syntax_rejected() {
+        let mut dec = open_decoder();
+        for text in [
+            "00:00:01,000 --> 00:00:02.000\nx\n",
+            "0:00:01.000 --> 0:00:02.000\nx\n",
+            "00:01.000 --> 00:00.500\nx\n",
+            "WEBVTT\n00:00.000 --> 00:01.000\nx\n",
+            "WEBVTT\n\njust text\n",
+            "00:00.000 --> 00:01.000\nx\n00:01.000 --> 00:02.000\n",
+        ] {
+            assert!(
+                matches!(
+                    decode_text(dec.as_mut(), text, 0),
+                    Err(TaoError::InvalidData(_))
+                ),
+                "非法 WebVTT 应返回 InvalidData: {text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_webvtt_registered_in_registry() {
+        let mut registry = CodecRegistry::new();
+        crate::register_all(&mut registry);
+        for (codec_id, name) in [(CodecId::Webvtt, "webvtt"), (CodecId::Srt, "srt")] {
+            let dec = registry
+                .create_decoder(codec_id)
+                .expect("应已注册字幕解码器");
+            assert_eq!(dec.name(), name);
+        }
+    }
+}
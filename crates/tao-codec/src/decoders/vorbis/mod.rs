@@ -8,13 +8,13 @@
 //! 当前限制:
 //! - 尚未实现音频包到 PCM 的完整解码链路 (P3 阶段实现)
 
-mod bitreader;
+pub(crate) mod bitreader;
 mod codebook;
 mod floor;
-mod headers;
+pub(crate) mod headers;
 mod imdct;
 mod residue;
-mod setup;
+pub(crate) mod setup;
 mod synthesis;
 
 use std::collections::{HashMap, VecDeque};
@@ -25,7 +25,9 @@ use crate::codec_id::CodecId;
 use crate::codec_parameters::{AudioCodecParams, CodecParameters, CodecParamsType};
 use crate::decoder::Decoder;
 use crate::frame::{AudioFrame, Frame};
+use crate::frame_pool::FramePool;
 use crate::packet::Packet;
+use crate::parsers::vorbis::{is_header_packet, split_xiph_headers};
 
 use self::bitreader::{LsbBitReader, ilog};
 use self::codebook::CodebookHuffman;
@@ -77,6 +79,8 @@ pub struct VorbisDecoder {
     concealment_count: u64,
     overlap: Vec<Vec<f32>>,
     window_cache: HashMap<(usize, usize, bool, bool, bool), Vec<f32>>,
+    /// 输出缓冲池, 未指定时每帧单独分配
+    pool: Option<FramePool>,
 }
 
 impl VorbisDecoder {
@@ -114,6 +118,7 @@ impl VorbisDecoder {
             concealment_count: 0,
             overlap: Vec::new(),
             window_cache: HashMap::new(),
+            pool: None,
         }))
     }
 
@@ -554,6 +559,7 @@ impl VorbisDecoder {
                 self.channel_layout,
                 pts.max(0),
                 out_samples as i64,
+                self.pool.as_ref(),
             );
             self.next_pts = frame.pts.saturating_add(frame.duration);
             self.enqueue_audio_frame(frame);
@@ -763,6 +769,7 @@ impl VorbisDecoder {
             self.channel_layout,
             self.next_pts,
             tail as i64,
+            self.pool.as_ref(),
         );
         self.next_pts = self.next_pts.saturating_add(tail as i64);
         self.pending_frames.push_back(Frame::Audio(frame));
@@ -1021,6 +1028,7 @@ impl VorbisDecoder {
                 self.channel_layout,
                 pts,
                 out_samples as i64,
+                self.pool.as_ref(),
             );
             self.next_pts = frame.pts.saturating_add(frame.duration);
             self.enqueue_audio_frame(frame);
@@ -1140,69 +1148,6 @@ fn packet_time_base_is_sample_granule(packet_time_base: Rational, sample_rate: u
     reduced.num == 1 && reduced.den == sample_rate as i32
 }
 
-fn is_vorbis_header_packet(packet: &[u8], header_type: u8) -> bool {
-    packet.len() >= 7 && packet[0] == header_type && &packet[1..7] == b"vorbis"
-}
-
-type VorbisHeaderTriplet<'a> = (&'a [u8], &'a [u8], &'a [u8]);
-
-fn split_matroska_vorbis_private(extra_data: &[u8]) -> TaoResult<Option<VorbisHeaderTriplet<'_>>> {
-    if extra_data.is_empty() || extra_data[0] != 0x02 {
-        return Ok(None);
-    }
-
-    let mut offset = 1usize;
-    let mut header_len_0 = 0usize;
-    let mut header_len_1 = 0usize;
-
-    for len_ref in [&mut header_len_0, &mut header_len_1] {
-        let mut current = 0usize;
-        loop {
-            let b = *extra_data.get(offset).ok_or_else(|| {
-                TaoError::InvalidData("Vorbis Matroska 私有数据头包长度区越界".into())
-            })?;
-            offset += 1;
-            current = current.checked_add(usize::from(b)).ok_or_else(|| {
-                TaoError::InvalidData("Vorbis Matroska 私有数据头包长度溢出".into())
-            })?;
-            if b != 0xFF {
-                break;
-            }
-        }
-        *len_ref = current;
-    }
-
-    let payload = extra_data
-        .get(offset..)
-        .ok_or_else(|| TaoError::InvalidData("Vorbis Matroska 私有数据缺少头包内容区".into()))?;
-    let min_total = header_len_0
-        .checked_add(header_len_1)
-        .and_then(|v| v.checked_add(1))
-        .ok_or_else(|| TaoError::InvalidData("Vorbis Matroska 私有数据长度计算溢出".into()))?;
-    if payload.len() < min_total {
-        return Err(TaoError::InvalidData(
-            "Vorbis Matroska 私有数据头包长度非法".into(),
-        ));
-    }
-
-    let header0_end = header_len_0;
-    let header1_end = header_len_0 + header_len_1;
-    let ident = &payload[..header0_end];
-    let comment = &payload[header0_end..header1_end];
-    let setup = &payload[header1_end..];
-
-    if !is_vorbis_header_packet(ident, 1)
-        || !is_vorbis_header_packet(comment, 3)
-        || !is_vorbis_header_packet(setup, 5)
-    {
-        return Err(TaoError::InvalidData(
-            "Vorbis Matroska 私有数据头包标识非法".into(),
-        ));
-    }
-
-    Ok(Some((ident, comment, setup)))
-}
-
 impl Decoder for VorbisDecoder {
     fn codec_id(&self) -> CodecId {
         CodecId::Vorbis
@@ -1257,9 +1202,7 @@ impl Decoder for VorbisDecoder {
         }
 
         if !params.extra_data.is_empty() {
-            if let Some((ident, comment, setup)) =
-                split_matroska_vorbis_private(&params.extra_data)?
-            {
+            if let Some((ident, comment, setup)) = split_xiph_headers(&params.extra_data)? {
                 self.parse_identification_header(ident)?;
                 self.parse_comment_header(comment)?;
                 self.parse_setup_header(setup)?;
@@ -1271,6 +1214,10 @@ impl Decoder for VorbisDecoder {
         Ok(())
     }
 
+    fn set_frame_pool(&mut self, pool: &FramePool) {
+        self.pool = Some(pool.clone());
+    }
+
     fn send_packet(&mut self, packet: &Packet) -> TaoResult<()> {
         if !self.opened {
             return Err(TaoError::Codec("Vorbis 解码器未打开".into()));
@@ -1300,9 +1247,9 @@ impl Decoder for VorbisDecoder {
                     Ok(()) => Ok(()),
                     Err(TaoError::InvalidData(msg)) if Self::is_recoverable_audio_error(&msg) => {
                         warn!("Vorbis 跳过损坏音频包: {}", msg);
-                        let is_header_packet = is_vorbis_header_packet(data, 1)
-                            || is_vorbis_header_packet(data, 3)
-                            || is_vorbis_header_packet(data, 5);
+                        let is_header_packet = is_header_packet(data, 1)
+                            || is_header_packet(data, 3)
+                            || is_header_packet(data, 5);
                         if !is_header_packet {
                             let fallback_blocksize = self
                                 .headers
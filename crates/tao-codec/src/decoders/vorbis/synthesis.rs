@@ -1,6 +1,7 @@
 use tao_core::{Rational, SampleFormat};
 
 use crate::frame::AudioFrame;
+use crate::frame_pool::{self, FramePool};
 
 use super::imdct::TimeDomainBlock;
 
@@ -23,6 +24,7 @@ pub(crate) fn synthesize_frame(
     channel_layout: tao_core::ChannelLayout,
     pts: i64,
     duration: i64,
+    pool: Option<&FramePool>,
 ) -> AudioFrame {
     let channels = channel_layout.channels as usize;
     let samples_per_ch = duration.max(0) as usize;
@@ -50,9 +52,34 @@ pub(crate) fn synthesize_frame(
             interleaved[s * channels + ch] = v;
         }
     }
-    frame.data[0] = interleaved
-        .iter()
-        .flat_map(|v| v.to_le_bytes())
-        .collect::<Vec<u8>>();
+    let mut bytes = frame_pool::take_vec_or_alloc(pool, interleaved.len() * 4);
+    bytes.extend(interleaved.iter().flat_map(|v| v.to_le_bytes()));
+    frame.data[0] = frame_pool::wrap_in(pool, bytes);
     frame
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthesize_frame_uses_pool() {
+        let pool = FramePool::new(1);
+        let td = TimeDomainBlock {
+            channels: vec![vec![0.5; 4], vec![-0.5; 4]],
+        };
+        let frame = synthesize_frame(
+            &td,
+            48000,
+            tao_core::ChannelLayout::STEREO,
+            0,
+            4,
+            Some(&pool),
+        );
+        assert_eq!(frame.data[0].len(), 4 * 2 * 4);
+        assert_eq!(&frame.data[0][..4], &0.5f32.to_le_bytes());
+        assert_eq!(&frame.data[0][4..8], &(-0.5f32).to_le_bytes());
+        drop(frame);
+        assert_eq!(pool.free_count(), 1, "释放的帧缓冲应归还池中");
+    }
+}
@@ -2,13 +2,32 @@
 //!
 //! 所有编码器实现必须实现 `Encoder` trait.
 
-use tao_core::TaoResult;
+use tao_core::{PixelFormat, SampleFormat, TaoError, TaoResult};
 
+use crate::capabilities::CodecCapabilities;
 use crate::codec_id::CodecId;
 use crate::codec_parameters::CodecParameters;
 use crate::frame::Frame;
 use crate::packet::Packet;
 
+/// 全部可编码的采样格式, 按常用程度排序 (首项为默认首选格式)
+///
+/// 作为 [`Encoder::supported_sample_formats`] 的默认值, 表示编码器不限制输入格式.
+pub const ALL_SAMPLE_FORMATS: &[SampleFormat] = &[
+    SampleFormat::S16,
+    SampleFormat::S32,
+    SampleFormat::F32,
+    SampleFormat::S24,
+    SampleFormat::U8,
+    SampleFormat::F64,
+    SampleFormat::S16p,
+    SampleFormat::S32p,
+    SampleFormat::F32p,
+    SampleFormat::S24p,
+    SampleFormat::U8p,
+    SampleFormat::F64p,
+];
+
 /// 编码器 trait
 ///
 /// 定义了编码器的统一接口. 所有具体编码器 (H.264, AAC 等) 都实现此 trait.
@@ -25,6 +44,84 @@ pub trait Encoder: Send {
     /// 获取编码器名称
     fn name(&self) -> &str;
 
+    /// 编码器能力标志 (延迟输出、无损等)
+    ///
+    /// 默认实现返回空集合.
+    fn codec_capabilities(&self) -> CodecCapabilities {
+        CodecCapabilities::empty()
+    }
+
+    /// 支持的输入采样格式 (按偏好排序)
+    ///
+    /// 默认返回 [`ALL_SAMPLE_FORMATS`], 表示不限制. 调用方应据此选择输入格式并在需要时插入重采样.
+    fn supported_sample_formats(&self) -> &'static [SampleFormat] {
+        ALL_SAMPLE_FORMATS
+    }
+
+    /// 首选的输入采样格式
+    ///
+    /// 源格式不受支持且未指定目标格式时, 调用方应转换为此格式.
+    /// 默认取 `supported_sample_formats()` 的首项.
+    fn preferred_sample_format(&self) -> SampleFormat {
+        self.supported_sample_formats()
+            .first()
+            .copied()
+            .unwrap_or(SampleFormat::None)
+    }
+
+    /// 支持的采样率, `None` 表示不限制
+    fn supported_sample_rates(&self) -> Option<&[u32]> {
+        None
+    }
+
+    /// 支持的输入像素格式 (按偏好排序)
+    ///
+    /// 返回空切片表示不限制. 调用方应据此选择输入格式并在需要时插入缩放/转换.
+    fn supported_pixel_formats(&self) -> &[PixelFormat] {
+        &[]
+    }
+
+    /// 音频编码器要求的每帧采样数, 0 表示不限制
+    ///
+    /// 非零时调用方应将输入重新分块为该长度, 仅最后一帧可以更短.
+    fn frame_size(&self) -> u32 {
+        0
+    }
+
+    /// 设置编码器私有选项 (如 FLAC 的 `compression_level`)
+    ///
+    /// 对标 FFmpeg 的 `AVOption`, 需在 `open()` 之前调用.
+    /// 默认实现不接受任何选项.
+    fn set_option(&mut self, key: &str, _value: &str) -> TaoResult<()> {
+        Err(TaoError::Unsupported(format!(
+            "编码器 {} 不支持选项 {}",
+            self.name(),
+            key
+        )))
+    }
+
+    /// 设置是否由容器保存全局头
+    ///
+    /// 对标 FFmpeg 的 `AV_CODEC_FLAG_GLOBAL_HEADER`, 需在 `open()` 之前调用.
+    /// 启用时编码器不在数据包中重复带内头部 (如 AAC 输出裸 raw_data_block 而非 ADTS),
+    /// 解码所需的配置通过 `extra_data()` 提供. 默认实现忽略此设置.
+    fn set_global_header(&mut self, _enabled: bool) {}
+
+    /// 设置关键帧间隔 (GOP 大小, 单位为帧)
+    ///
+    /// 对标 FFmpeg 的 `gop_size`, 需在 `open()` 之前调用. 预测编码器应至少每 `interval`
+    /// 帧输出一个关键帧, 并在输入帧设置 [`VideoFrame::force_keyframe`](crate::frame::VideoFrame::force_keyframe)
+    /// 时将该帧编码为关键帧 (可借助 [`KeyframeScheduler`]). 仅帧内编码器
+    /// (`CAPS_INTRA_ONLY`) 每帧均为关键帧, 默认实现忽略此设置.
+    fn set_keyframe_interval(&mut self, _interval: u32) {}
+
+    /// 编码器全局头 (如 AAC 的 AudioSpecificConfig, FLAC 的 STREAMINFO)
+    ///
+    /// 在 `open()` 之后有效, 调用方应写入输出流的 `extra_data`. 默认实现返回空.
+    fn extra_data(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
     /// 使用参数配置编码器
     ///
     /// 对于 RAW/PCM 等编解码器, 必须在编码前调用此方法提供参数.
@@ -54,3 +151,91 @@ pub trait Encoder: Send {
     /// 刷新编码器, 清空内部状态
     fn flush(&mut self);
 }
+
+/// 预测编码器的关键帧调度 (GOP 控制)
+///
+/// 按关键帧间隔与输入帧的强制关键帧标志决定每帧是否编码为关键帧.
+/// 首帧与强制关键帧都会开始新的 GOP, 间隔为 0 时只有首帧与强制帧为关键帧.
+#[derive(Debug, Clone, Default)]
+pub struct KeyframeScheduler {
+    /// 关键帧间隔 (帧数)
+    interval: u32,
+    /// 距上一个关键帧的帧数, None 表示尚未编码任何帧
+    frames_since_keyframe: Option<u32>,
+}
+
+impl KeyframeScheduler {
+    /// 创建关键帧调度器
+    pub fn new(interval: u32) -> Self {
+        Self {
+            interval,
+            frames_since_keyframe: None,
+        }
+    }
+
+    /// 关键帧间隔
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// 修改关键帧间隔, 从下一帧起生效
+    pub fn set_interval(&mut self, interval: u32) {
+        self.interval = interval;
+    }
+
+    /// 决定下一帧是否为关键帧, `force` 为该帧的强制关键帧标志
+    pub fn next_frame(&mut self, force: bool) -> bool {
+        let keyframe = match self.frames_since_keyframe {
+            None => true,
+            Some(n) => force || (self.interval > 0 && n + 1 >= self.interval),
+        };
+        self.frames_since_keyframe = match (keyframe, self.frames_since_keyframe) {
+            (true, _) | (false, None) => Some(0),
+            (false, Some(n)) => Some(n + 1),
+        };
+        keyframe
+    }
+
+    /// 重置调度状态, 下一帧重新作为关键帧
+    pub fn reset(&mut self) {
+        self.frames_since_keyframe = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(scheduler: &mut KeyframeScheduler, forced: &[usize], count: usize) -> Vec<usize> {
+        (0..count)
+            .filter(|i| scheduler.next_frame(forced.contains(i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_keyframe_scheduler_interval() {
+        let mut scheduler = KeyframeScheduler::new(4);
+        assert_eq!(schedule(&mut scheduler, &[], 10), vec![0, 4, 8]);
+
+        let mut scheduler = KeyframeScheduler::new(0);
+        assert_eq!(
+            schedule(&mut scheduler, &[], 10),
+            vec![0],
+            "间隔为 0 时只有首帧为关键帧"
+        );
+
+        scheduler.reset();
+        scheduler.set_interval(1);
+        assert_eq!(schedule(&mut scheduler, &[], 3), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_keyframe_scheduler_forced_frame_restarts_gop() {
+        let mut scheduler = KeyframeScheduler::new(4);
+        assert_eq!(
+            schedule(&mut scheduler, &[2], 10),
+            vec![0, 2, 6],
+            "强制关键帧后应重新计算间隔"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! AAC 编码器滤波器组: 窗函数 + MDCT.
+//!
+//! MDCT 先折叠为 DCT-IV, 再用 N/2 点复数 FFT 计算.
+//! 输出缩放与解码器 IMDCT (含 2/N 因子) 及输出增益配套,
+//! 输入样本乘以 [`INPUT_SCALE`] 后解码输出可还原到原始幅度.
+
+use std::f64::consts::PI;
+
+use super::WindowSequence;
+
+/// 长块频谱系数数 (每帧采样数)
+pub(super) const LONG_LEN: usize = 1024;
+/// 短块频谱系数数
+pub(super) const SHORT_LEN: usize = 128;
+/// 一帧内的短窗个数
+pub(super) const SHORT_WINDOWS: usize = 8;
+/// 第一个短窗在 2048 点块中的起始偏移
+pub(super) const SHORT_WINDOW_OFFSET: usize = 448;
+/// 输入样本缩放, 与解码器输出增益互逆
+pub(super) const INPUT_SCALE: f32 = 2048.0;
+
+/// 基于 FFT 的 MDCT (2n 点输入 -> n 点输出)
+struct Mdct {
+    n: usize,
+    /// DCT-IV 前置旋转因子 e^{-iπ(j+1/4)/n}
+    pre_twiddle: Vec<(f64, f64)>,
+    /// DCT-IV 后置旋转因子 e^{-iπk/n}
+    post_twiddle: Vec<(f64, f64)>,
+    /// n/2 点 FFT 的旋转因子 e^{-2πik/(n/2)}
+    fft_twiddle: Vec<(f64, f64)>,
+    /// n/2 点 FFT 的位反转序
+    bit_reverse: Vec<usize>,
+}
+
+impl Mdct {
+    fn new(n: usize) -> Self {
+        let half = n / 2;
+        let expi = |angle: f64| (angle.cos(), -angle.sin());
+        let bits = half.trailing_zeros();
+        Self {
+            n,
+            pre_twiddle: (0..half)
+                .map(|j| expi(PI * (j as f64 + 0.25) / n as f64))
+                .collect(),
+            post_twiddle: (0..half).map(|k| expi(PI * k as f64 / n as f64)).collect(),
+            fft_twiddle: (0..half / 2)
+                .map(|k| expi(2.0 * PI * k as f64 / half as f64))
+                .collect(),
+            bit_reverse: (0..half)
+                .map(|i| i.reverse_bits() >> (usize::BITS - bits))
+                .collect(),
+        }
+    }
+
+    /// X[k] = Σ x[i] · cos(π/n · (i + 1/2 + n/2) · (k + 1/2)), i ∈ [0, 2n)
+    fn forward(&self, input: &[f32], output: &mut [f32]) {
+        let n = self.n;
+        let half = n / 2;
+        // 折叠: 输入四等分为 (a, b, c, d), DCT-IV 输入为 (-c_r - d, a - b_r)
+        let folded = |i: usize| -> f64 {
+            if i < half {
+                -(input[n + half - 1 - i] as f64) - input[n + half + i] as f64
+            } else {
+                let i = i - half;
+                input[i] as f64 - input[n - 1 - i] as f64
+            }
+        };
+
+        let mut buf = vec![(0.0f64, 0.0f64); half];
+        for j in 0..half {
+            let (re, im) = (folded(2 * j), folded(n - 1 - 2 * j));
+            let (tr, ti) = self.pre_twiddle[j];
+            buf[self.bit_reverse[j]] = (re * tr - im * ti, re * ti + im * tr);
+        }
+        self.fft_in_place(&mut buf);
+        for (k, &(re, im)) in buf.iter().enumerate() {
+            let (tr, ti) = self.post_twiddle[k];
+            output[2 * k] = (re * tr - im * ti) as f32;
+            output[n - 1 - 2 * k] = -(re * ti + im * tr) as f32;
+        }
+    }
+
+    /// 迭代基 2 FFT (输入已按位反转序排列)
+    fn fft_in_place(&self, buf: &mut [(f64, f64)]) {
+        let size = buf.len();
+        let mut len = 2;
+        while len <= size {
+            let step = size / len;
+            for start in (0..size).step_by(len) {
+                for k in 0..len / 2 {
+                    let (wr, wi) = self.fft_twiddle[k * step];
+                    let (br, bi) = buf[start + k + len / 2];
+                    let t = (br * wr - bi * wi, br * wi + bi * wr);
+                    let a = buf[start + k];
+                    buf[start + k] = (a.0 + t.0, a.1 + t.1);
+                    buf[start + k + len / 2] = (a.0 - t.0, a.1 - t.1);
+                }
+            }
+            len *= 2;
+        }
+    }
+}
+
+/// 正弦窗: w[i] = sin(π/len · (i + 0.5))
+fn sine_window(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| (PI / len as f64 * (i as f64 + 0.5)).sin() as f32)
+        .collect()
+}
+
+/// 分析滤波器组 (窗形状固定为 sine)
+pub(super) struct FilterBank {
+    long: Mdct,
+    short: Mdct,
+    long_window: Vec<f32>,
+    short_window: Vec<f32>,
+}
+
+impl FilterBank {
+    pub(super) fn new() -> Self {
+        Self {
+            long: Mdct::new(LONG_LEN),
+            short: Mdct::new(SHORT_LEN),
+            long_window: sine_window(2 * LONG_LEN),
+            short_window: sine_window(2 * SHORT_LEN),
+        }
+    }
+
+    /// 长块窗口在位置 i 的权重, 与解码器的 ONLY_LONG/LONG_START/LONG_STOP 窗一致
+    fn long_window_at(&self, sequence: WindowSequence, i: usize) -> f32 {
+        let stop_start = SHORT_WINDOW_OFFSET;
+        let stop_end = SHORT_WINDOW_OFFSET + SHORT_LEN;
+        let start_start = LONG_LEN + SHORT_WINDOW_OFFSET;
+        let start_end = start_start + SHORT_LEN;
+        match sequence {
+            WindowSequence::LongStart if i >= LONG_LEN => {
+                if i < start_start {
+                    1.0
+                } else if i < start_end {
+                    self.short_window[SHORT_LEN + i - start_start]
+                } else {
+                    0.0
+                }
+            }
+            WindowSequence::LongStop if i < LONG_LEN => {
+                if i < stop_start {
+                    0.0
+                } else if i < stop_end {
+                    self.short_window[i - stop_start]
+                } else {
+                    1.0
+                }
+            }
+            _ => self.long_window[i],
+        }
+    }
+
+    /// 对 2048 点块加窗并变换为 1024 个频谱系数
+    ///
+    /// EIGHT_SHORT_SEQUENCE 时输出 8 个短窗的 128 点频谱, 按窗顺序排列.
+    pub(super) fn analyze(&self, sequence: WindowSequence, block: &[f32], spectrum: &mut [f32]) {
+        if sequence == WindowSequence::EightShort {
+            let mut windowed = [0.0f32; 2 * SHORT_LEN];
+            for win in 0..SHORT_WINDOWS {
+                let start = SHORT_WINDOW_OFFSET + win * SHORT_LEN;
+                for (i, slot) in windowed.iter_mut().enumerate() {
+                    *slot = block[start + i] * self.short_window[i] * INPUT_SCALE;
+                }
+                self.short.forward(
+                    &windowed,
+                    &mut spectrum[win * SHORT_LEN..(win + 1) * SHORT_LEN],
+                );
+            }
+        } else {
+            let windowed: Vec<f32> = block[..2 * LONG_LEN]
+                .iter()
+                .enumerate()
+                .map(|(i, &s)| s * self.long_window_at(sequence, i) * INPUT_SCALE)
+                .collect();
+            self.long.forward(&windowed, &mut spectrum[..LONG_LEN]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按定义直接计算 MDCT
+    fn direct_mdct(input: &[f32]) -> Vec<f64> {
+        let n = input.len() / 2;
+        (0..n)
+            .map(|k| {
+                input
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &x)| {
+                        let angle =
+                            PI / n as f64 * (i as f64 + 0.5 + n as f64 / 2.0) * (k as f64 + 0.5);
+                        x as f64 * angle.cos()
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mdct_matches_direct() {
+        for n in [SHORT_LEN, LONG_LEN] {
+            let input: Vec<f32> = (0..2 * n)
+                .map(|i| ((i * 7919 % 1000) as f32 / 500.0 - 1.0) * 0.8)
+                .collect();
+            let mut fast = vec![0.0f32; n];
+            Mdct::new(n).forward(&input, &mut fast);
+            let expected = direct_mdct(&input);
+            for (k, (&a, &b)) in fast.iter().zip(&expected).enumerate() {
+                assert!(
+                    (a as f64 - b).abs() < 1e-3 * (1.0 + b.abs()),
+                    "{n} 点 MDCT 第 {k} 个系数不一致: {a} vs {b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_mdct_silence_input() {
+        let bank = FilterBank::new();
+        let block = vec![0.0f32; 2 * LONG_LEN];
+        let mut spectrum = vec![1.0f32; LONG_LEN];
+        for sequence in [WindowSequence::OnlyLong, WindowSequence::EightShort] {
+            bank.analyze(sequence, &block, &mut spectrum);
+            assert!(spectrum.iter().all(|&v| v == 0.0), "静音输入应得零输出");
+        }
+    }
+
+    #[test]
+    fn test_window_shapes() {
+        let bank = FilterBank::new();
+        for sequence in [
+            WindowSequence::OnlyLong,
+            WindowSequence::LongStart,
+            WindowSequence::LongStop,
+        ] {
+            for i in 0..2 * LONG_LEN {
+                let w = bank.long_window_at(sequence, i);
+                assert!((0.0..=1.0).contains(&w), "窗函数值应在 [0,1]");
+            }
+        }
+        // LONG_START 后半段与短窗衔接, 尾部 448 点为零
+        assert_eq!(bank.long_window_at(WindowSequence::LongStart, 1400), 1.0);
+        assert_eq!(bank.long_window_at(WindowSequence::LongStart, 1600), 0.0);
+        // LONG_STOP 前半段与 LONG_START 后半段镜像
+        for i in 0..LONG_LEN {
+            let stop = bank.long_window_at(WindowSequence::LongStop, i);
+            let start = bank.long_window_at(WindowSequence::LongStart, 2 * LONG_LEN - 1 - i);
+            assert!(
+                (stop - start).abs() < 1e-6,
+                "LONG_STOP 与 LONG_START 应对称"
+            );
+        }
+    }
+}
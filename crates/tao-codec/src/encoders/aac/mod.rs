@@ -0,0 +1,1237 @@
+//! AAC-LC 音频编码器.
+//!
+//! 将 PCM 音频帧编码为 AAC-LC ADTS 格式, 码流符合 ISO/IEC 14496-3 的
+//! raw_data_block 语法 (SCE/CPE/LFE + END).
+//!
+//! 编码流程:
+//! 1. 瞬态检测决定窗口序列 (ONLY_LONG/LONG_START/EIGHT_SHORT/LONG_STOP), 短块按能量分组
+//! 2. 加窗 MDCT (长块 1024 点, 短块 8×128 点, sine 窗)
+//! 3. 心理声学模型计算每个 SFB 的掩蔽阈值
+//! 4. 量化失真环: 每个频带取失真不超过阈值的最粗 scale factor,
+//!    外层按码率与比特池调整阈值偏移
+//! 5. 码本选择 + section/scalefactor/频谱 Huffman 编码
+//! 6. ADTS 帧头 (7 字节, protection_absent=1); 启用全局头时改为输出裸 raw_data_block,
+//!    AudioSpecificConfig 由 `extra_data()` 提供
+//!
+//! 为提前判断下一块是否需要短窗, 编码器带有 576 采样的前瞻延迟,
+//! 加上 MDCT 重叠, 解码输出相对输入共延迟 1600 采样.
+
+mod mdct;
+mod psy;
+mod quantizer;
+
+use bytes::Bytes;
+use tao_core::bitwriter::BitWriter;
+use tao_core::{ChannelLayout, SampleFormat, TaoError, TaoResult};
+use tracing::debug;
+
+use crate::codec_id::CodecId;
+use crate::codec_parameters::{CodecParameters, CodecParamsType};
+use crate::decoders::aac::tables::{swb_offset_long, swb_offset_short};
+use crate::encoder::Encoder;
+use crate::frame::Frame;
+use crate::packet::Packet;
+
+use mdct::{FilterBank, SHORT_LEN, SHORT_WINDOW_OFFSET, SHORT_WINDOWS};
+use psy::PsyModel;
+use quantizer::{ChannelSpectrum, IcsLayout, Quantizer};
+
+/// AAC 帧大小 (每声道采样数)
+const AAC_FRAME_SIZE: usize = 1024;
+/// MDCT 输入长度 (2 * AAC_FRAME_SIZE)
+const MDCT_INPUT_SIZE: usize = 2048;
+/// 瞬态检测前瞻长度: 覆盖下一块短窗区域的末尾
+const LOOKAHEAD: usize = SHORT_WINDOW_OFFSET + SHORT_LEN;
+/// 每声道历史缓冲长度: 当前块 2048 点 + 前瞻
+const HISTORY_LEN: usize = MDCT_INPUT_SIZE + LOOKAHEAD;
+/// 下一块短窗区域在历史缓冲中的起点
+const NEXT_SHORT_START: usize = AAC_FRAME_SIZE + SHORT_WINDOW_OFFSET;
+
+/// 未指定码率时每声道的默认码率
+const DEFAULT_BIT_RATE_PER_CHANNEL: u64 = 64000;
+/// 每声道每帧最大位数 (ISO/IEC 14496-3 解码缓冲大小)
+const MAX_CHANNEL_FRAME_BITS: usize = 6144;
+/// ADTS 帧头位数
+const ADTS_HEADER_BITS: usize = 56;
+/// LFE 声道的编码带宽 (Hz)
+const LFE_CUTOFF_HZ: u32 = 240;
+/// 码率控制: 噪声阈值偏移的搜索范围 (dB)
+const NOISE_OFFSET_MIN_DB: f32 = -12.0;
+const NOISE_OFFSET_MAX_DB: f32 = 60.0;
+/// 码率控制: 二分搜索次数
+const RATE_SEARCH_STEPS: usize = 8;
+
+/// ADTS 采样率索引对应的采样率 (Hz)
+const SAMPLE_RATE_TABLE: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+/// 窗口序列 (ics_info 中的 window_sequence)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WindowSequence {
+    OnlyLong = 0,
+    LongStart = 1,
+    EightShort = 2,
+    LongStop = 3,
+}
+
+/// 语法元素类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ElementKind {
+    Sce,
+    Cpe,
+    Lfe,
+}
+
+impl ElementKind {
+    /// id_syn_ele
+    fn id(self) -> u32 {
+        match self {
+            Self::Sce => 0,
+            Self::Cpe => 1,
+            Self::Lfe => 3,
+        }
+    }
+}
+
+/// raw_data_block 结束元素 id
+const ID_END: u32 = 7;
+
+/// AAC-LC 的 audioObjectType
+const AOT_AAC_LC: u8 = 2;
+
+/// 声道数对应的 channel_configuration (8 声道即 7.1 为 7)
+fn channel_configuration(channels: u32) -> u8 {
+    if channels == 8 { 7 } else { channels as u8 }
+}
+
+/// 各声道数对应的语法元素及其输入声道 (与解码器默认声道映射互逆)
+fn channel_elements(channels: u32) -> Option<&'static [(ElementKind, &'static [usize])]> {
+    use ElementKind::*;
+    let elements: &'static [(ElementKind, &'static [usize])] = match channels {
+        1 => &[(Sce, &[0])],
+        2 => &[(Cpe, &[0, 1])],
+        3 => &[(Sce, &[2]), (Cpe, &[0, 1])],
+        4 => &[(Sce, &[0]), (Cpe, &[1, 2]), (Sce, &[3])],
+        5 => &[(Sce, &[2]), (Cpe, &[0, 1]), (Cpe, &[3, 4])],
+        6 => &[(Sce, &[2]), (Cpe, &[0, 1]), (Cpe, &[4, 5]), (Lfe, &[3])],
+        8 => &[
+            (Sce, &[2]),
+            (Cpe, &[0, 1]),
+            (Cpe, &[6, 7]),
+            (Cpe, &[4, 5]),
+            (Lfe, &[3]),
+        ],
+        _ => return None,
+    };
+    Some(elements)
+}
+
+/// 一个语法元素的编码状态
+struct ChannelElement {
+    kind: ElementKind,
+    /// element_instance_tag
+    tag: u32,
+    /// 对应的输入声道
+    channels: &'static [usize],
+    /// 上一块的窗口序列
+    window_sequence: WindowSequence,
+    /// 当前块是否已被判定为短块
+    short_pending: bool,
+}
+
+/// 一个语法元素在当前帧的分析结果
+struct ElementFrame {
+    sequence: WindowSequence,
+    layout: IcsLayout,
+    spectra: Vec<ChannelSpectrum>,
+    max_bands: usize,
+}
+
+/// AAC-LC 编码器
+pub struct AacEncoder {
+    /// 采样率
+    sample_rate: u32,
+    /// 声道数
+    channels: u32,
+    /// 声道布局
+    channel_layout: ChannelLayout,
+    /// 输出数据包缓冲
+    output_packet: Option<Packet>,
+    /// 帧序号
+    frame_number: u64,
+    /// 是否已打开
+    opened: bool,
+    /// 是否已收到刷新信号
+    flushing: bool,
+    /// 每声道历史样本: 上一帧 + 当前帧 + 前瞻
+    history: Vec<Vec<f32>>,
+    /// 输入缓冲 (收集不足 1024 的样本)
+    input_buffer: Vec<Vec<f32>>,
+    /// 输入缓冲中已收集的采样数
+    input_samples: usize,
+    /// 语法元素布局与窗口状态
+    elements: Vec<ChannelElement>,
+    /// 是否启用长短块切换
+    block_switching: bool,
+    filter_bank: FilterBank,
+    quantizer: Quantizer,
+    psy: Option<PsyModel>,
+    long_offsets: &'static [usize],
+    short_offsets: &'static [usize],
+    /// 每声道上一长块的掩蔽阈值 (用于抑制预回声)
+    prev_threshold: Vec<Vec<f32>>,
+    /// 编码带宽内的长块/短块频带数
+    max_bands_long: usize,
+    max_bands_short: usize,
+    /// 每帧平均可用位数
+    frame_bits: usize,
+    /// 比特池当前位数及上限
+    reservoir_bits: usize,
+    max_reservoir_bits: usize,
+    /// 由容器保存 AudioSpecificConfig, 数据包不带 ADTS 头
+    global_header: bool,
+}
+
+impl AacEncoder {
+    /// 创建 AAC-LC 编码器实例
+    pub fn create() -> TaoResult<Box<dyn Encoder>> {
+        Self::create_with_block_switching(true)
+    }
+
+    /// 创建 AAC-LC 编码器实例, 指定是否启用长短块切换
+    pub fn create_with_block_switching(block_switching: bool) -> TaoResult<Box<dyn Encoder>> {
+        Ok(Box::new(Self {
+            sample_rate: 0,
+            channels: 0,
+            channel_layout: ChannelLayout::MONO,
+            output_packet: None,
+            frame_number: 0,
+            opened: false,
+            flushing: false,
+            history: Vec::new(),
+            input_buffer: Vec::new(),
+            input_samples: 0,
+            elements: Vec::new(),
+            block_switching,
+            filter_bank: FilterBank::new(),
+            quantizer: Quantizer::new(),
+            psy: None,
+            long_offsets: swb_offset_long(4),
+            short_offsets: swb_offset_short(4),
+            prev_threshold: Vec::new(),
+            max_bands_long: 0,
+            max_bands_short: 0,
+            frame_bits: 0,
+            reservoir_bits: 0,
+            max_reservoir_bits: 0,
+            global_header: false,
+        }))
+    }
+
+    /// 获取采样率对应的 ADTS 索引
+    fn sample_rate_index(&self, sample_rate: u32) -> Option<u8> {
+        SAMPLE_RATE_TABLE
+            .iter()
+            .position(|&sr| sr == sample_rate)
+            .map(|i| i as u8)
+    }
+
+    /// 从 AudioFrame 提取 F32 交错样本 (每声道一个 Vec)
+    fn extract_f32_samples(&self, frame: &crate::frame::AudioFrame) -> TaoResult<Vec<Vec<f32>>> {
+        if frame.sample_format != SampleFormat::F32 && frame.sample_format != SampleFormat::F32p {
+            return Err(TaoError::Unsupported(format!(
+                "AAC 编码器仅支持 F32 格式, 当前为 {}",
+                frame.sample_format,
+            )));
+        }
+
+        let ch = self.channels as usize;
+        let nb = frame.nb_samples as usize;
+
+        let mut result = vec![Vec::with_capacity(nb); ch];
+
+        if frame.sample_format.is_planar() {
+            // 平面格式: data[i] 为第 i 声道
+            for (i, ch_data) in frame.data.iter().enumerate().take(ch) {
+                if ch_data.len() < nb * 4 {
+                    return Err(TaoError::InvalidData("音频数据长度不足".into()));
+                }
+                for j in 0..nb {
+                    let idx = j * 4;
+                    let bytes = [
+                        ch_data[idx],
+                        ch_data[idx + 1],
+                        ch_data[idx + 2],
+                        ch_data[idx + 3],
+                    ];
+                    result[i].push(f32::from_le_bytes(bytes));
+                }
+            }
+        } else {
+            // 交错格式: data[0] 包含所有声道交替
+            let data = &frame.data[0];
+            let expected_len = nb * ch * 4;
+            if data.len() < expected_len {
+                return Err(TaoError::InvalidData("音频数据长度不足".into()));
+            }
+            for j in 0..nb {
+                for (i, ch_vec) in result.iter_mut().enumerate().take(ch) {
+                    let idx = (j * ch + i) * 4;
+                    let bytes = [data[idx], data[idx + 1], data[idx + 2], data[idx + 3]];
+                    ch_vec.push(f32::from_le_bytes(bytes));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// 生成 ADTS 帧头 (7 字节, protection_absent=1)
+    fn write_adts_header(
+        &self,
+        frame_length: usize,
+        sample_rate: u32,
+        channels: u32,
+    ) -> TaoResult<Vec<u8>> {
+        let sr_index = self
+            .sample_rate_index(sample_rate)
+            .ok_or_else(|| TaoError::Unsupported(format!("不支持的采样率: {} Hz", sample_rate)))?;
+
+        let channel_config = channel_configuration(channels);
+        let frame_length_u16 = (frame_length + 7) as u16;
+
+        let mut header = vec![0u8; 7];
+        header[0] = 0xFF;
+        header[1] = 0xF1;
+        header[2] = (1 << 6) | (sr_index << 2) | (channel_config >> 2);
+        header[3] = ((channel_config & 0x03) << 6) | ((frame_length_u16 >> 11) & 0x03) as u8;
+        header[4] = ((frame_length_u16 >> 3) & 0xFF) as u8;
+        header[5] = (((frame_length_u16 & 0x07) << 5) | 0x1F) as u8;
+        header[6] = 0xFC;
+
+        Ok(header)
+    }
+
+    /// 按前瞻结果确定元素当前块的窗口序列
+    fn decide_window_sequence(&mut self, element_idx: usize) -> WindowSequence {
+        let element = &self.elements[element_idx];
+        let next_short = self.block_switching
+            && element.kind != ElementKind::Lfe
+            && element
+                .channels
+                .iter()
+                .any(|&ch| psy::detect_attack(&self.history[ch], NEXT_SHORT_START, HISTORY_LEN));
+        let prev_ends_short = matches!(
+            element.window_sequence,
+            WindowSequence::LongStart | WindowSequence::EightShort
+        );
+        // 短块前后必须分别以 LONG_START / LONG_STOP 过渡, 短块之间直接相连
+        let sequence = if element.short_pending || (prev_ends_short && next_short) {
+            WindowSequence::EightShort
+        } else if prev_ends_short {
+            WindowSequence::LongStop
+        } else if next_short {
+            WindowSequence::LongStart
+        } else {
+            WindowSequence::OnlyLong
+        };
+        let element = &mut self.elements[element_idx];
+        element.window_sequence = sequence;
+        element.short_pending = next_short;
+        sequence
+    }
+
+    /// MDCT + 心理声学分析一个语法元素
+    fn analyze_element(&mut self, element_idx: usize) -> ElementFrame {
+        let sequence = self.decide_window_sequence(element_idx);
+        let element = &self.elements[element_idx];
+        let short = sequence == WindowSequence::EightShort;
+        let coeffs: Vec<Vec<f32>> = element
+            .channels
+            .iter()
+            .map(|&ch| {
+                let mut spectrum = vec![0.0f32; AAC_FRAME_SIZE];
+                self.filter_bank.analyze(
+                    sequence,
+                    &self.history[ch][..MDCT_INPUT_SIZE],
+                    &mut spectrum,
+                );
+                spectrum
+            })
+            .collect();
+
+        let groups = if short {
+            let mut window_energy = [0.0f32; SHORT_WINDOWS];
+            for spectrum in &coeffs {
+                for (win, energy) in window_energy.iter_mut().enumerate() {
+                    *energy += spectrum[win * SHORT_LEN..(win + 1) * SHORT_LEN]
+                        .iter()
+                        .map(|x| x * x)
+                        .sum::<f32>();
+                }
+            }
+            psy::group_short_windows(&window_energy)
+        } else {
+            vec![1]
+        };
+        let layout = IcsLayout {
+            offsets: if short {
+                self.short_offsets
+            } else {
+                self.long_offsets
+            },
+            groups,
+            short,
+        };
+        let max_bands = if element.kind == ElementKind::Lfe {
+            let bin_hz = self.sample_rate as usize / MDCT_INPUT_SIZE;
+            self.long_offsets[..self.long_offsets.len() - 1]
+                .iter()
+                .take_while(|&&o| o * bin_hz < LFE_CUTOFF_HZ as usize)
+                .count()
+        } else if short {
+            self.max_bands_short
+        } else {
+            self.max_bands_long
+        };
+
+        let psy = self.psy.as_ref().expect("编码器已打开");
+        let bands = layout.num_bands();
+        let mut spectra = Vec::with_capacity(coeffs.len());
+        for (&ch, spectrum) in element.channels.iter().zip(coeffs) {
+            let mut energy = vec![0.0f32; layout.groups.len() * bands];
+            let mut threshold = vec![f32::INFINITY; layout.groups.len() * bands];
+            let mut win_energy = vec![0.0f32; bands];
+            let mut win_threshold = vec![0.0f32; bands];
+            let mut win = 0;
+            for (group, &len) in layout.groups.iter().enumerate() {
+                let slots = group * bands..(group + 1) * bands;
+                for _ in 0..len {
+                    let window_len = if short { SHORT_LEN } else { AAC_FRAME_SIZE };
+                    psy.analyze(
+                        &spectrum[win * window_len..(win + 1) * window_len],
+                        short,
+                        &mut win_energy,
+                        &mut win_threshold,
+                    );
+                    // 组内共享 scale factor: 能量累加, 阈值取最严格的窗
+                    for (b, slot) in slots.clone().enumerate() {
+                        energy[slot] += win_energy[b];
+                        threshold[slot] = threshold[slot].min(win_threshold[b] * len as f32);
+                    }
+                    win += 1;
+                }
+            }
+            // 长块之间限制阈值增长, 抑制起音处的预回声
+            let prev = &mut self.prev_threshold[ch];
+            if short {
+                prev.clear();
+            } else {
+                if prev.len() == threshold.len() {
+                    for (thr, &last) in threshold.iter_mut().zip(prev.iter()) {
+                        *thr = thr.min(2.0 * last);
+                    }
+                }
+                prev.clone_from(&threshold);
+            }
+            spectra.push(ChannelSpectrum::new(spectrum, energy, threshold));
+        }
+        ElementFrame {
+            sequence,
+            layout,
+            spectra,
+            max_bands,
+        }
+    }
+
+    /// 写出 ics_info
+    fn write_ics_info(bw: &mut BitWriter, frame: &ElementFrame, max_sfb: usize) {
+        bw.write_bit(0); // ics_reserved_bit
+        bw.write_bits(frame.sequence as u32, 2);
+        bw.write_bit(0); // window_shape: sine
+        if frame.layout.short {
+            bw.write_bits(max_sfb as u32, 4);
+            let mut grouping = 0u32;
+            let mut win = 0;
+            for &len in &frame.layout.groups {
+                for i in 0..len {
+                    if win > 0 {
+                        grouping = (grouping << 1) | u32::from(i > 0);
+                    }
+                    win += 1;
+                }
+            }
+            bw.write_bits(grouping, 7);
+        } else {
+            bw.write_bits(max_sfb as u32, 6);
+            bw.write_bit(0); // predictor_data_present
+        }
+    }
+
+    /// 以给定噪声阈值倍数量化并写出 raw_data_block
+    fn write_raw_data_block(&self, frames: &[ElementFrame], noise_scale: f32) -> Vec<u8> {
+        let mut bw = BitWriter::with_capacity(MAX_CHANNEL_FRAME_BITS / 8 * self.channels as usize);
+        for (element, frame) in self.elements.iter().zip(frames) {
+            let quants: Vec<_> = frame
+                .spectra
+                .iter()
+                .map(|spectrum| {
+                    self.quantizer
+                        .quantize(spectrum, &frame.layout, frame.max_bands, noise_scale)
+                })
+                .collect();
+            bw.write_bits(element.kind.id(), 3);
+            bw.write_bits(element.tag, 4);
+            if element.kind == ElementKind::Cpe {
+                // common_window=1, 两声道共享 ics_info, 不使用 M/S
+                let max_sfb = quants.iter().map(|q| q.max_sfb()).max().unwrap_or(0);
+                bw.write_bit(1);
+                Self::write_ics_info(&mut bw, frame, max_sfb);
+                bw.write_bits(0, 2); // ms_mask_present
+                for quant in &quants {
+                    bw.write_bits(quant.global_gain() as u32, 8);
+                    quant.write(&mut bw, &frame.layout, max_sfb);
+                }
+            } else {
+                let quant = &quants[0];
+                bw.write_bits(quant.global_gain() as u32, 8);
+                Self::write_ics_info(&mut bw, frame, quant.max_sfb());
+                quant.write(&mut bw, &frame.layout, quant.max_sfb());
+            }
+        }
+        bw.write_bits(ID_END, 3);
+        bw.align_to_byte();
+        bw.finish()
+    }
+
+    /// 码率控制: 在位数预算内取噪声阈值偏移最小的编码结果
+    fn encode_raw_data_block(&self, frames: &[ElementFrame]) -> Vec<u8> {
+        let max_bits = MAX_CHANNEL_FRAME_BITS * self.channels as usize;
+        // 低于掩蔽阈值时只用平均码率, 达不到掩蔽阈值时才动用比特池
+        let budget = |offset_db: f32| {
+            if offset_db >= 0.0 {
+                (self.frame_bits + self.reservoir_bits).min(max_bits)
+            } else {
+                self.frame_bits.min(max_bits)
+            }
+        };
+        let scale = |db: f32| 10f32.powf(db / 10.0);
+        let payload = self.write_raw_data_block(frames, scale(NOISE_OFFSET_MIN_DB));
+        if payload.len() * 8 <= budget(NOISE_OFFSET_MIN_DB) {
+            return payload;
+        }
+        let (mut lo, mut hi) = (NOISE_OFFSET_MIN_DB, NOISE_OFFSET_MAX_DB);
+        let mut best = None;
+        for _ in 0..RATE_SEARCH_STEPS {
+            let mid = (lo + hi) / 2.0;
+            let candidate = self.write_raw_data_block(frames, scale(mid));
+            if candidate.len() * 8 <= budget(mid) {
+                best = Some(candidate);
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+        best.unwrap_or_else(|| {
+            let coarse = self.write_raw_data_block(frames, scale(NOISE_OFFSET_MAX_DB));
+            if coarse.len() * 8 <= budget(NOISE_OFFSET_MAX_DB) {
+                coarse
+            } else {
+                // 码率过低: 全部频带置零
+                self.write_raw_data_block(frames, f32::INFINITY)
+            }
+        })
+    }
+
+    /// 编码单帧 (每声道 1024 样本)
+    fn encode_frame(
+        &mut self,
+        samples_per_ch: &[Vec<f32>],
+        pts: i64,
+        time_base: tao_core::Rational,
+        duration: i64,
+    ) -> TaoResult<Packet> {
+        for (history, current) in self.history.iter_mut().zip(samples_per_ch) {
+            history.copy_within(AAC_FRAME_SIZE.., 0);
+            history[HISTORY_LEN - AAC_FRAME_SIZE..].copy_from_slice(&current[..AAC_FRAME_SIZE]);
+        }
+
+        let frames: Vec<ElementFrame> = (0..self.elements.len())
+            .map(|idx| self.analyze_element(idx))
+            .collect();
+        let payload = self.encode_raw_data_block(&frames);
+        let used_bits = payload.len() * 8;
+        self.reservoir_bits = (self.reservoir_bits + self.frame_bits)
+            .saturating_sub(used_bits)
+            .min(self.max_reservoir_bits);
+
+        let frame_data = if self.global_header {
+            payload
+        } else {
+            let mut frame_data =
+                self.write_adts_header(payload.len(), self.sample_rate, self.channels)?;
+            frame_data.extend_from_slice(&payload);
+            frame_data
+        };
+
+        let mut pkt = Packet::from_data(Bytes::from(frame_data));
+        pkt.pts = pts;
+        pkt.dts = pts;
+        pkt.duration = duration;
+        pkt.time_base = time_base;
+        pkt.stream_index = 0;
+        pkt.set_keyframe(true);
+
+        Ok(pkt)
+    }
+
+    /// 重置分析状态 (历史样本, 窗口序列, 比特池)
+    fn reset_analysis(&mut self) {
+        let channels = self.channels as usize;
+        self.history = vec![vec![0.0; HISTORY_LEN]; channels];
+        self.prev_threshold = vec![Vec::new(); channels];
+        for element in &mut self.elements {
+            element.window_sequence = WindowSequence::OnlyLong;
+            element.short_pending = false;
+        }
+        self.reservoir_bits = 0;
+    }
+
+    /// 处理输入样本, 当积累满 1024 样本时编码 (每次最多输出一包)
+    fn process_samples(
+        &mut self,
+        samples_per_ch: Vec<Vec<f32>>,
+        pts: i64,
+        time_base: tao_core::Rational,
+        duration: i64,
+    ) -> TaoResult<()> {
+        let nb = samples_per_ch[0].len();
+        if nb == 0 {
+            return Ok(());
+        }
+
+        let frames_in_input = nb / AAC_FRAME_SIZE;
+        let chunk_duration = if frames_in_input > 0 {
+            duration / frames_in_input as i64
+        } else {
+            duration
+        };
+
+        let offset = 0;
+        if offset + AAC_FRAME_SIZE <= nb {
+            let mut chunk = vec![Vec::with_capacity(AAC_FRAME_SIZE); self.channels as usize];
+            for (ch, ch_samples) in samples_per_ch
+                .iter()
+                .enumerate()
+                .take(self.channels as usize)
+            {
+                chunk[ch] = ch_samples[offset..offset + AAC_FRAME_SIZE].to_vec();
+            }
+            let chunk_pts = if pts != tao_core::timestamp::NOPTS_VALUE {
+                pts + (offset as i64 * duration / nb as i64)
+            } else {
+                pts
+            };
+            let pkt = self.encode_frame(&chunk, chunk_pts, time_base, chunk_duration)?;
+            self.output_packet = Some(pkt);
+            self.frame_number += 1;
+            let new_offset = offset + AAC_FRAME_SIZE;
+            if new_offset < nb {
+                for (ch, ch_samples) in samples_per_ch
+                    .iter()
+                    .enumerate()
+                    .take(self.channels as usize)
+                {
+                    self.input_buffer[ch].extend_from_slice(&ch_samples[new_offset..]);
+                }
+                self.input_samples = nb - new_offset;
+            }
+            return Ok(());
+        }
+
+        for (ch, ch_samples) in samples_per_ch
+            .iter()
+            .enumerate()
+            .take(self.channels as usize)
+        {
+            self.input_buffer[ch].extend_from_slice(ch_samples);
+        }
+        self.input_samples = nb;
+
+        Ok(())
+    }
+}
+
+impl Encoder for AacEncoder {
+    fn codec_id(&self) -> CodecId {
+        CodecId::Aac
+    }
+
+    fn name(&self) -> &str {
+        "aac"
+    }
+
+    fn supported_sample_formats(&self) -> &'static [SampleFormat] {
+        &[SampleFormat::F32, SampleFormat::F32p]
+    }
+
+    fn set_global_header(&mut self, enabled: bool) {
+        self.global_header = enabled;
+    }
+
+    /// AudioSpecificConfig: audioObjectType(5) + samplingFrequencyIndex(4)
+    /// + channelConfiguration(4) + GASpecificConfig 三个零位
+    fn extra_data(&self) -> Vec<u8> {
+        let Some(sr_index) = self.sample_rate_index(self.sample_rate) else {
+            return Vec::new();
+        };
+        let config = (u16::from(AOT_AAC_LC) << 11)
+            | (u16::from(sr_index) << 7)
+            | (u16::from(channel_configuration(self.channels)) << 3);
+        config.to_be_bytes().to_vec()
+    }
+
+    fn supported_sample_rates(&self) -> Option<&[u32]> {
+        Some(&SAMPLE_RATE_TABLE)
+    }
+
+    fn frame_size(&self) -> u32 {
+        AAC_FRAME_SIZE as u32
+    }
+
+    fn open(&mut self, params: &CodecParameters) -> TaoResult<()> {
+        let audio = match &params.params {
+            CodecParamsType::Audio(a) => a,
+            _ => {
+                return Err(TaoError::InvalidArgument("AAC 编码器需要音频参数".into()));
+            }
+        };
+
+        if audio.sample_rate == 0 {
+            return Err(TaoError::InvalidArgument("采样率不能为 0".into()));
+        }
+        if audio.channel_layout.channels == 0 || audio.channel_layout.channels > 8 {
+            return Err(TaoError::InvalidArgument(format!(
+                "AAC 不支持的声道数: {}",
+                audio.channel_layout.channels,
+            )));
+        }
+        let elements = channel_elements(audio.channel_layout.channels).ok_or_else(|| {
+            TaoError::Unsupported(format!(
+                "AAC 默认声道配置不包含 {} 声道",
+                audio.channel_layout.channels,
+            ))
+        })?;
+
+        let sr_index = self.sample_rate_index(audio.sample_rate).ok_or_else(|| {
+            TaoError::Unsupported(format!("AAC 不支持的采样率: {} Hz", audio.sample_rate,))
+        })?;
+
+        self.sample_rate = audio.sample_rate;
+        self.channels = audio.channel_layout.channels;
+        self.channel_layout = audio.channel_layout;
+        let mut tags = [0u32; 4];
+        self.elements = elements
+            .iter()
+            .map(|&(kind, channels)| {
+                let tag = &mut tags[kind.id() as usize];
+                *tag += 1;
+                ChannelElement {
+                    kind,
+                    tag: *tag - 1,
+                    channels,
+                    window_sequence: WindowSequence::OnlyLong,
+                    short_pending: false,
+                }
+            })
+            .collect();
+
+        self.long_offsets = swb_offset_long(sr_index);
+        self.short_offsets = swb_offset_short(sr_index);
+        self.psy = Some(PsyModel::new(
+            self.sample_rate,
+            self.long_offsets,
+            self.short_offsets,
+        ));
+
+        // 码率: 未指定时按每声道默认值, 上限为每帧 6144 位/声道
+        let channels = u64::from(self.channels);
+        let max_bit_rate = MAX_CHANNEL_FRAME_BITS as u64 * channels * u64::from(self.sample_rate)
+            / AAC_FRAME_SIZE as u64;
+        let bit_rate = if params.bit_rate > 0 {
+            params.bit_rate
+        } else {
+            DEFAULT_BIT_RATE_PER_CHANNEL * channels
+        }
+        .min(max_bit_rate);
+        let max_frame_bits = MAX_CHANNEL_FRAME_BITS * self.channels as usize;
+        let header_bits = if self.global_header {
+            0
+        } else {
+            ADTS_HEADER_BITS
+        };
+        self.frame_bits = ((bit_rate * AAC_FRAME_SIZE as u64 / u64::from(self.sample_rate))
+            as usize)
+            .saturating_sub(header_bits)
+            .min(max_frame_bits);
+        self.max_reservoir_bits = max_frame_bits - self.frame_bits;
+
+        // 编码带宽随每声道码率提高, 不超过奈奎斯特频率
+        let cutoff = (3000 + bit_rate / channels / 4).min(u64::from(self.sample_rate) / 2);
+        let bands_below = |offsets: &[usize], len: usize| {
+            offsets[..offsets.len() - 1]
+                .iter()
+                .take_while(|&&o| {
+                    (o as u64 * u64::from(self.sample_rate)) / (2 * len as u64) < cutoff
+                })
+                .count()
+        };
+        self.max_bands_long = bands_below(self.long_offsets, AAC_FRAME_SIZE);
+        self.max_bands_short = bands_below(self.short_offsets, SHORT_LEN);
+
+        self.reset_analysis();
+        self.input_buffer = vec![Vec::new(); self.channels as usize];
+        self.input_samples = 0;
+        self.output_packet = None;
+        self.frame_number = 0;
+        self.opened = true;
+        self.flushing = false;
+
+        debug!(
+            "打开 AAC-LC 编码器: {} Hz, {} 声道, {} bps",
+            self.sample_rate, self.channels, bit_rate,
+        );
+        Ok(())
+    }
+
+    fn send_frame(&mut self, frame: Option<&Frame>) -> TaoResult<()> {
+        if !self.opened {
+            return Err(TaoError::Codec("编码器未打开, 请先调用 open()".into()));
+        }
+        if self.output_packet.is_some() {
+            return Err(TaoError::NeedMoreData);
+        }
+
+        let frame = match frame {
+            Some(f) => f,
+            None => {
+                self.flushing = true;
+                if self.input_samples > 0 {
+                    let mut padded = self.input_buffer.clone();
+                    for ch_data in padded.iter_mut().take(self.channels as usize) {
+                        while ch_data.len() < AAC_FRAME_SIZE {
+                            ch_data.push(0.0);
+                        }
+                        ch_data.truncate(AAC_FRAME_SIZE);
+                    }
+                    self.process_samples(
+                        padded,
+                        tao_core::timestamp::NOPTS_VALUE,
+                        tao_core::Rational::new(1, self.sample_rate as i32),
+                        AAC_FRAME_SIZE as i64,
+                    )?;
+                    self.input_samples = 0;
+                    self.input_buffer.iter_mut().for_each(|v| v.clear());
+                }
+                return Ok(());
+            }
+        };
+
+        let audio = match frame {
+            Frame::Audio(a) => a,
+            Frame::Video(_) | Frame::Subtitle(_) => {
+                return Err(TaoError::InvalidArgument("AAC 编码器仅接受音频帧".into()));
+            }
+        };
+
+        let mut samples_per_ch = self.extract_f32_samples(audio)?;
+
+        if self.input_samples > 0 {
+            for (ch, ch_samples) in samples_per_ch
+                .iter_mut()
+                .enumerate()
+                .take(self.channels as usize)
+            {
+                let mut combined = Vec::with_capacity(self.input_samples + ch_samples.len());
+                combined.append(&mut self.input_buffer[ch]);
+                combined.extend_from_slice(ch_samples);
+                *ch_samples = combined;
+            }
+            self.input_samples = 0;
+            self.input_buffer.iter_mut().for_each(|v| v.clear());
+        }
+
+        let pts = audio.pts;
+        let time_base = audio.time_base;
+        let duration = audio.duration;
+
+        self.process_samples(samples_per_ch, pts, time_base, duration)?;
+        Ok(())
+    }
+
+    fn receive_packet(&mut self) -> TaoResult<Packet> {
+        if let Some(pkt) = self.output_packet.take() {
+            return Ok(pkt);
+        }
+        if self.flushing {
+            return Err(TaoError::Eof);
+        }
+        Err(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) {
+        self.output_packet = None;
+        self.flushing = false;
+        self.input_samples = 0;
+        for v in &mut self.input_buffer {
+            v.clear();
+        }
+        self.reset_analysis();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec_parameters::AudioCodecParams;
+    use crate::frame::AudioFrame;
+    use tao_core::Rational;
+
+    fn make_aac_params(sample_rate: u32, channels: u32) -> CodecParameters {
+        CodecParameters {
+            codec_id: CodecId::Aac,
+            extra_data: Vec::new(),
+            bit_rate: 128000,
+            params: CodecParamsType::Audio(AudioCodecParams {
+                sample_rate,
+                channel_layout: ChannelLayout::from_channels(channels),
+                sample_format: SampleFormat::F32,
+                frame_size: AAC_FRAME_SIZE as u32,
+                block_align: 0,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_supported_formats_and_rates() {
+        let enc = AacEncoder::create().unwrap();
+        assert_eq!(enc.supported_sample_formats()[0], SampleFormat::F32);
+        assert_eq!(
+            enc.preferred_sample_format(),
+            SampleFormat::F32,
+            "AAC 首选 F32 输入"
+        );
+        let rates = enc.supported_sample_rates().expect("AAC 应声明采样率表");
+        assert!(rates.contains(&44100) && rates.contains(&48000));
+        assert!(!rates.contains(&44000), "非标准采样率不应在表中");
+        assert_eq!(enc.frame_size(), 1024, "AAC-LC 每帧固定 1024 采样");
+    }
+
+    #[test]
+    fn test_create_and_open() {
+        let params = make_aac_params(44100, 2);
+        let mut enc = AacEncoder::create().unwrap();
+        enc.open(&params).unwrap();
+        assert_eq!(enc.codec_id(), CodecId::Aac);
+        assert_eq!(enc.name(), "aac");
+    }
+
+    #[test]
+    fn test_encode_silence_frame() {
+        let params = make_aac_params(44100, 1);
+        let mut enc = AacEncoder::create().unwrap();
+        enc.open(&params).unwrap();
+
+        let nb_samples = 1024u32;
+        let data = vec![0.0f32; nb_samples as usize];
+        let bytes: Vec<u8> = data.iter().flat_map(|f| f.to_le_bytes()).collect();
+
+        let mut af = AudioFrame::new(nb_samples, 44100, SampleFormat::F32, ChannelLayout::MONO);
+        af.data[0] = bytes.into();
+        af.pts = 0;
+        af.time_base = Rational::new(1, 44100);
+        af.duration = 1024;
+
+        enc.send_frame(Some(&Frame::Audio(af))).unwrap();
+        let pkt = enc.receive_packet().unwrap();
+
+        assert!(!pkt.data.is_empty());
+        assert!(pkt.data.len() >= 7);
+        assert_eq!(pkt.data[0], 0xFF, "ADTS sync word 高字节");
+        assert_eq!(
+            pkt.data[1], 0xF1,
+            "ADTS sync word 低字节 + protection_absent=1"
+        );
+    }
+
+    #[test]
+    fn test_flush_and_eof() {
+        let params = make_aac_params(44100, 1);
+        let mut enc = AacEncoder::create().unwrap();
+        enc.open(&params).unwrap();
+        enc.send_frame(None).unwrap();
+        let err = enc.receive_packet().unwrap_err();
+        assert!(matches!(err, TaoError::Eof));
+    }
+
+    #[test]
+    fn test_adts_header_format() {
+        let params = make_aac_params(44100, 2);
+        let mut enc = AacEncoder::create().unwrap();
+        enc.open(&params).unwrap();
+
+        let nb_samples = 1024u32;
+        let data = vec![0.0f32; nb_samples as usize];
+        let bytes: Vec<u8> = data.iter().flat_map(|f| f.to_le_bytes()).collect();
+
+        let mut af = AudioFrame::new(nb_samples, 44100, SampleFormat::F32, ChannelLayout::STEREO);
+        af.data[0] = bytes.repeat(2).into();
+
+        enc.send_frame(Some(&Frame::Audio(af))).unwrap();
+        let pkt = enc.receive_packet().unwrap();
+
+        assert!(pkt.data.len() >= 7);
+        let h = &pkt.data[0..7];
+        assert_eq!(h[0], 0xFF);
+        assert_eq!(h[1], 0xF1);
+        let profile = (h[2] >> 6) & 0x03;
+        assert_eq!(profile, 1, "AAC-LC profile = 1");
+        let sr_index = (h[2] >> 2) & 0x0F;
+        assert_eq!(sr_index, 4, "44100 Hz -> index 4");
+        let channel_config = ((h[2] & 0x01) << 2) | (h[3] >> 6);
+        assert_eq!(channel_config, 2, "立体声 channel_config = 2");
+    }
+
+    #[test]
+    fn test_global_header_outputs_raw_blocks_and_asc() {
+        let params = make_aac_params(48000, 2);
+        let mut enc = AacEncoder::create().unwrap();
+        enc.set_global_header(true);
+        enc.open(&params).unwrap();
+        assert_eq!(
+            enc.extra_data(),
+            vec![0x11, 0x90],
+            "AAC-LC, 48000 Hz (index 3), 立体声"
+        );
+
+        let mut af = AudioFrame::new(1024, 48000, SampleFormat::F32, ChannelLayout::STEREO);
+        af.data[0] = vec![0u8; 1024 * 2 * 4].into();
+        enc.send_frame(Some(&Frame::Audio(af))).unwrap();
+        let pkt = enc.receive_packet().unwrap();
+        assert_eq!(
+            pkt.data[0] >> 5,
+            ElementKind::Cpe.id() as u8,
+            "裸数据块应直接以 CPE 元素开头"
+        );
+        assert_ne!(&pkt.data[..2], &[0xFF, 0xF1], "不应带 ADTS 同步字");
+    }
+
+    /// 编码交错 F32 单声道/多声道信号, 返回全部 ADTS 数据包
+    fn encode_signal(enc: &mut dyn Encoder, signal: &[f32], channels: u32) -> Vec<Packet> {
+        let frame_len = AAC_FRAME_SIZE * channels as usize;
+        let mut packets = Vec::new();
+        for chunk in signal.chunks(frame_len) {
+            let nb = (chunk.len() / channels as usize) as u32;
+            let mut af = AudioFrame::new(
+                nb,
+                44100,
+                SampleFormat::F32,
+                ChannelLayout::from_channels(channels),
+            );
+            af.data[0] = chunk
+                .iter()
+                .flat_map(|s| s.to_le_bytes())
+                .collect::<Vec<u8>>()
+                .into();
+            af.duration = i64::from(nb);
+            enc.send_frame(Some(&Frame::Audio(af))).unwrap();
+            while let Ok(pkt) = enc.receive_packet() {
+                packets.push(pkt);
+            }
+        }
+        packets
+    }
+
+    /// 用解码器解码数据包, 返回每声道样本
+    fn decode_packets(packets: &[Packet], channels: u32) -> Vec<Vec<f32>> {
+        let mut dec = crate::decoders::aac::AacDecoder::create().unwrap();
+        dec.open(&make_aac_params(44100, channels)).unwrap();
+        let mut out = vec![Vec::new(); channels as usize];
+        for pkt in packets {
+            dec.send_packet(pkt).unwrap();
+            if let Ok(Frame::Audio(af)) = dec.receive_frame() {
+                for (i, bytes) in af.data[0].chunks_exact(4).enumerate() {
+                    let v = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                    out[i % channels as usize].push(v);
+                }
+            }
+        }
+        out
+    }
+
+    /// 读取 SCE 数据包的 window_sequence
+    fn sce_window_sequence(pkt: &Packet) -> u8 {
+        // ADTS 头 7 字节后: id(3) tag(4) global_gain(8) reserved(1) window_sequence(2)
+        let bits = u32::from_be_bytes([pkt.data[7], pkt.data[8], pkt.data[9], 0]);
+        ((bits >> (32 - 18)) & 0x03) as u8
+    }
+
+    /// 响板式瞬态: 静音背景中的短促衰减噪声脉冲
+    fn castanet_signal(len: usize, onset: usize) -> Vec<f32> {
+        let mut seed = 0x1234_5678u32;
+        (0..len)
+            .map(|i| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                let noise = (seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0;
+                let background = 0.002 * noise;
+                if i < onset {
+                    background
+                } else {
+                    let t = (i - onset) as f32 / 44100.0;
+                    background + 0.8 * noise * (-t / 0.003).exp()
+                }
+            })
+            .collect()
+    }
+
+    /// 解码延迟 (MDCT 重叠 + 前瞻)
+    const CODEC_DELAY: usize = AAC_FRAME_SIZE + LOOKAHEAD;
+
+    #[test]
+    fn test_roundtrip_sine_decodes_in_tree() {
+        let mut enc = AacEncoder::create().unwrap();
+        enc.open(&make_aac_params(44100, 1)).unwrap();
+        let signal: Vec<f32> = (0..AAC_FRAME_SIZE * 20)
+            .map(|i| 0.5 * (i as f32 * 440.0 * std::f32::consts::TAU / 44100.0).sin())
+            .collect();
+        let packets = encode_signal(enc.as_mut(), &signal, 1);
+        assert_eq!(packets.len(), 20, "每 1024 个采样输出一个数据包");
+        // 起音处允许切换短块, 进入稳态后只应使用长块
+        assert!(
+            packets[4..].iter().all(|p| sce_window_sequence(p) == 0),
+            "稳态正弦应只使用长块"
+        );
+        let decoded = &decode_packets(&packets, 1)[0];
+        let (mut signal_energy, mut error_energy) = (0.0f64, 0.0f64);
+        for i in 4096..signal.len() - CODEC_DELAY {
+            let err = f64::from(decoded[i + CODEC_DELAY] - signal[i]);
+            signal_energy += f64::from(signal[i]).powi(2);
+            error_energy += err * err;
+        }
+        let snr = 10.0 * (signal_energy / error_energy).log10();
+        assert!(snr > 20.0, "正弦往返信噪比过低: {snr:.1} dB");
+    }
+
+    #[test]
+    fn test_transient_uses_short_blocks() {
+        let onset = AAC_FRAME_SIZE * 6 + 300;
+        let signal = castanet_signal(AAC_FRAME_SIZE * 12, onset);
+        // 返回 (预回声区误差能量 / 背景能量, 瞬态附近 5ms 误差能量 / 信号能量, 窗口序列)
+        let encode = |block_switching: bool| -> (f64, f64, Vec<u8>) {
+            let mut enc = AacEncoder::create_with_block_switching(block_switching).unwrap();
+            enc.open(&make_aac_params(44100, 1)).unwrap();
+            let packets = encode_signal(enc.as_mut(), &signal, 1);
+            let sequences = packets.iter().map(sce_window_sequence).collect();
+            let decoded = &decode_packets(&packets, 1)[0];
+            let energy = |range: std::ops::Range<usize>| -> (f64, f64) {
+                range.fold((0.0, 0.0), |(err, sig), i| {
+                    let e = f64::from(decoded[i + CODEC_DELAY] - signal[i]);
+                    (err + e * e, sig + f64::from(signal[i]).powi(2))
+                })
+            };
+            // 起音前 256 点以外落在同一短窗之外, 不应出现预回声
+            let (pre_err, pre_sig) = energy(onset - AAC_FRAME_SIZE..onset - 2 * SHORT_LEN);
+            let half = 44100 * 5 / 1000 / 2;
+            let (err, sig) = energy(onset - half..onset + half);
+            (pre_err / pre_sig, err / sig, sequences)
+        };
+
+        let (pre_echo, error, sequences) = encode(true);
+        // 包含起音的数据包 (扣除编解码延迟)
+        let packet = (onset + CODEC_DELAY) / AAC_FRAME_SIZE;
+        assert_eq!(
+            sequences[packet - 1..=packet + 1],
+            [
+                WindowSequence::LongStart as u8,
+                WindowSequence::EightShort as u8,
+                WindowSequence::LongStop as u8,
+            ],
+            "瞬态应以 LONG_START -> EIGHT_SHORT -> LONG_STOP 切换短块"
+        );
+
+        let (long_pre_echo, long_error, long_sequences) = encode(false);
+        assert!(
+            long_sequences
+                .iter()
+                .all(|&s| s == WindowSequence::OnlyLong as u8),
+            "关闭块切换时只应使用长块"
+        );
+        assert!(pre_echo < 1.0, "短块的预回声应低于背景噪声: {pre_echo:.3}");
+        assert!(
+            pre_echo * 10.0 < long_pre_echo,
+            "块切换应显著降低预回声: 切换 {pre_echo:.3}, 仅长块 {long_pre_echo:.3}"
+        );
+        assert!(
+            error < long_error,
+            "块切换应降低瞬态附近误差: 切换 {error:.3}, 仅长块 {long_error:.3}"
+        );
+    }
+
+    #[test]
+    fn test_bit_rate_control() {
+        let mut seed = 1u32;
+        let noise: Vec<f32> = (0..AAC_FRAME_SIZE * 2 * 40)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                ((seed >> 8) as f32 / (1u32 << 24) as f32 - 0.5) * 0.8
+            })
+            .collect();
+        for bit_rate in [64_000u64, 192_000] {
+            let mut params = make_aac_params(44100, 2);
+            params.bit_rate = bit_rate;
+            let mut enc = AacEncoder::create().unwrap();
+            enc.open(&params).unwrap();
+            let packets = encode_signal(enc.as_mut(), &noise, 2);
+            let bytes: usize = packets.iter().map(|p| p.data.len()).sum();
+            let actual = bytes as f64 * 8.0 * 44100.0 / (packets.len() * AAC_FRAME_SIZE) as f64;
+            assert!(
+                actual <= bit_rate as f64 * 1.05 && actual >= bit_rate as f64 * 0.7,
+                "目标 {bit_rate} bps, 实际 {actual:.0} bps"
+            );
+            let decoded = decode_packets(&packets, 2);
+            assert!(decoded[1].iter().any(|&v| v.abs() > 0.05), "右声道应可解码");
+        }
+    }
+
+    #[test]
+    fn test_multichannel_element_mapping() {
+        // 5.1: 仅中置声道 (输入第 3 声道) 有信号
+        let channels = 6u32;
+        let mut signal = vec![0.0f32; AAC_FRAME_SIZE * 8 * channels as usize];
+        for (i, frame) in signal.chunks_mut(channels as usize).enumerate() {
+            frame[2] = 0.5 * (i as f32 * 1000.0 * std::f32::consts::TAU / 44100.0).sin();
+        }
+        let mut enc = AacEncoder::create().unwrap();
+        enc.open(&make_aac_params(44100, channels)).unwrap();
+        let packets = encode_signal(enc.as_mut(), &signal, channels);
+        let decoded = decode_packets(&packets, channels);
+        let energy: Vec<f32> = decoded
+            .iter()
+            .map(|ch| ch[4096..].iter().map(|v| v * v).sum())
+            .collect();
+        for (ch, &e) in energy.iter().enumerate() {
+            if ch == 2 {
+                assert!(e > 100.0, "中置声道应保留信号");
+            } else {
+                assert!(e < 1e-3, "声道 {ch} 不应串入信号: {e}");
+            }
+        }
+
+        let mut enc = AacEncoder::create().unwrap();
+        assert!(
+            enc.open(&make_aac_params(44100, 7)).is_err(),
+            "默认声道配置不包含 7 声道"
+        );
+    }
+}
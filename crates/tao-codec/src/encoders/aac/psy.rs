@@ -0,0 +1,237 @@
+//! AAC 编码器心理声学模型.
+//!
+//! - 瞬态检测: 对高通后的信号按 128 点分段比较能量, 突增时切换短窗
+//! - 短窗分组: 能量相近的相邻短窗共享 scale factor
+//! - 掩蔽阈值: 频带能量经 Bark 域扩散, 按音调性 (频谱平坦度) 扣除信掩比,
+//!   并以绝对听阈为下限
+
+use super::mdct::{INPUT_SCALE, SHORT_LEN, SHORT_WINDOWS};
+
+/// 瞬态检测分段长度
+const ATTACK_SEGMENT: usize = 128;
+/// 分段能量超过此前平均能量的倍数时判定为瞬态
+const ATTACK_RATIO: f32 = 10.0;
+/// 判定瞬态的最小分段能量 (约 -60 dBFS)
+const ATTACK_MIN_ENERGY: f32 = ATTACK_SEGMENT as f32 * 1e-6;
+/// 相邻短窗能量相差超过此倍数时开始新的分组
+const GROUP_RATIO: f32 = 4.0;
+/// 掩蔽扩散: 向高频每 Bark 衰减 (dB)
+const SPREAD_UPPER_DB: f32 = 10.0;
+/// 掩蔽扩散: 向低频每 Bark 衰减 (dB)
+const SPREAD_LOWER_DB: f32 = 25.0;
+/// 满幅正弦对应的声压级 (dB SPL), 用于换算绝对听阈
+const FULL_SCALE_SPL: f32 = 96.0;
+
+/// 检测 `signal[start..end]` 内是否存在瞬态
+///
+/// 以 `start` 之前 4 个分段的平均能量作为初始参考, 逐段比较并平滑更新参考能量.
+pub(super) fn detect_attack(signal: &[f32], start: usize, end: usize) -> bool {
+    let segment_energy = |pos: usize| -> f32 {
+        (pos..pos + ATTACK_SEGMENT)
+            .map(|i| {
+                // 一阶差分高通, 避免低频大幅信号掩盖瞬态
+                let d = signal[i] - signal[i.saturating_sub(1)];
+                d * d
+            })
+            .sum()
+    };
+    let history = start.min(4 * ATTACK_SEGMENT) / ATTACK_SEGMENT;
+    let mut reference = if history > 0 {
+        (1..=history)
+            .map(|s| segment_energy(start - s * ATTACK_SEGMENT))
+            .sum::<f32>()
+            / history as f32
+    } else {
+        0.0
+    };
+    let mut pos = start;
+    while pos + ATTACK_SEGMENT <= end {
+        let energy = segment_energy(pos);
+        if energy > ATTACK_MIN_ENERGY && energy > reference * ATTACK_RATIO {
+            return true;
+        }
+        reference = 0.5 * reference + 0.5 * energy;
+        pos += ATTACK_SEGMENT;
+    }
+    false
+}
+
+/// 按各短窗能量分组, 返回每组的窗数
+///
+/// `window_energy` 为 8 个短窗的能量 (CPE 为两声道之和).
+pub(super) fn group_short_windows(window_energy: &[f32; SHORT_WINDOWS]) -> Vec<usize> {
+    let mut groups = vec![1usize];
+    let mut reference = window_energy[0];
+    for &energy in &window_energy[1..] {
+        let (hi, lo) = if energy > reference {
+            (energy, reference)
+        } else {
+            (reference, energy)
+        };
+        if hi > lo.max(1.0) * GROUP_RATIO {
+            groups.push(1);
+            reference = energy;
+        } else {
+            *groups.last_mut().unwrap() += 1;
+        }
+    }
+    groups
+}
+
+/// Hz 转 Bark
+fn bark(freq: f32) -> f32 {
+    13.0 * (0.00076 * freq).atan() + 3.5 * (freq / 7500.0).powi(2).atan()
+}
+
+/// 绝对听阈 (dB SPL, Terhardt 近似)
+fn absolute_threshold_db(freq: f32) -> f32 {
+    let khz = (freq / 1000.0).max(0.02);
+    3.64 * khz.powf(-0.8) - 6.5 * (-0.6 * (khz - 3.3).powi(2)).exp() + 1e-3 * khz.powi(4)
+}
+
+/// 一种变换长度下的频带划分与静态参数
+struct BandLayout {
+    offsets: &'static [usize],
+    /// 频带中心的 Bark 值
+    bark: Vec<f32>,
+    /// 绝对听阈对应的频带能量 (MDCT 域)
+    quiet: Vec<f32>,
+}
+
+impl BandLayout {
+    fn new(sample_rate: u32, offsets: &'static [usize], len: usize) -> Self {
+        let line_freq = |k: f32| k * sample_rate as f32 / (2 * len) as f32;
+        let bands = offsets.len() - 1;
+        let bark = (0..bands)
+            .map(|b| bark(line_freq((offsets[b] + offsets[b + 1]) as f32 / 2.0)))
+            .collect();
+        // 幅度为 A 的正弦在 len 点 MDCT 中的能量约为 (len · INPUT_SCALE · A)² / 4
+        let quiet = (0..bands)
+            .map(|b| {
+                let db = (offsets[b]..offsets[b + 1])
+                    .map(|k| absolute_threshold_db(line_freq(k as f32 + 0.5)))
+                    .fold(f32::INFINITY, f32::min);
+                let amplitude = 10f32.powf((db - FULL_SCALE_SPL) / 20.0);
+                (len as f32 * INPUT_SCALE * amplitude).powi(2) / 4.0
+            })
+            .collect();
+        Self {
+            offsets,
+            bark,
+            quiet,
+        }
+    }
+}
+
+/// 心理声学模型
+pub(super) struct PsyModel {
+    long: BandLayout,
+    short: BandLayout,
+}
+
+impl PsyModel {
+    pub(super) fn new(
+        sample_rate: u32,
+        long_offsets: &'static [usize],
+        short_offsets: &'static [usize],
+    ) -> Self {
+        Self {
+            long: BandLayout::new(sample_rate, long_offsets, super::mdct::LONG_LEN),
+            short: BandLayout::new(sample_rate, short_offsets, SHORT_LEN),
+        }
+    }
+
+    /// 计算一个窗口内每个频带的能量与掩蔽阈值
+    pub(super) fn analyze(
+        &self,
+        coeffs: &[f32],
+        short: bool,
+        energy: &mut [f32],
+        threshold: &mut [f32],
+    ) {
+        let layout = if short { &self.short } else { &self.long };
+        let bands = layout.bark.len();
+        let mut snr = vec![0.0f32; bands];
+        for b in 0..bands {
+            let lines = &coeffs[layout.offsets[b]..layout.offsets[b + 1]];
+            let sum: f32 = lines.iter().map(|x| x * x).sum();
+            energy[b] = sum;
+            if sum <= 0.0 {
+                continue;
+            }
+            // 频谱平坦度 (dB): 越接近 0 越像噪声, 越小越像纯音
+            let log_mean =
+                lines.iter().map(|x| (x * x + 1e-9).ln()).sum::<f32>() / lines.len() as f32;
+            let flatness_db = 10.0 * (log_mean.exp() / (sum / lines.len() as f32)).log10();
+            let tonality = (flatness_db / -60.0).clamp(0.0, 1.0);
+            let offset_db = tonality * (14.5 + layout.bark[b]) + (1.0 - tonality) * 5.5;
+            snr[b] = 10f32.powf(-offset_db / 10.0);
+        }
+        for (b, thr) in threshold.iter_mut().enumerate().take(bands) {
+            let spread: f32 = (0..bands)
+                .filter(|&j| energy[j] > 0.0)
+                .map(|j| {
+                    let dz = layout.bark[b] - layout.bark[j];
+                    let atten_db = if dz >= 0.0 {
+                        SPREAD_UPPER_DB * dz
+                    } else {
+                        -SPREAD_LOWER_DB * dz
+                    };
+                    energy[j] * snr[j] * 10f32.powf(-atten_db / 10.0)
+                })
+                .sum();
+            *thr = spread.max(layout.quiet[b]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::aac::tables::{swb_offset_long, swb_offset_short};
+
+    #[test]
+    fn test_detect_attack() {
+        let steady: Vec<f32> = (0..2048).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+        assert!(!detect_attack(&steady, 512, 2048), "稳态正弦不应判定为瞬态");
+
+        let mut click = vec![0.001f32; 2048];
+        for (i, s) in click[1500..1540].iter_mut().enumerate() {
+            *s = if i % 2 == 0 { 0.8 } else { -0.8 };
+        }
+        assert!(detect_attack(&click, 512, 2048), "能量突增应判定为瞬态");
+        assert!(!detect_attack(&click, 512, 1408), "瞬态之前的区间不应触发");
+    }
+
+    #[test]
+    fn test_group_short_windows() {
+        let energy = [1.0e3, 1.1e3, 0.9e3, 5.0e6, 3.0e6, 2.0e6, 1.0e6, 4.0e5];
+        assert_eq!(group_short_windows(&energy), vec![3, 3, 2]);
+        assert_eq!(group_short_windows(&[0.0; SHORT_WINDOWS]), vec![8]);
+    }
+
+    #[test]
+    fn test_threshold_tracks_masker() {
+        let psy = PsyModel::new(44100, swb_offset_long(4), swb_offset_short(4));
+        let mut coeffs = vec![0.0f32; 1024];
+        // 约 1 kHz 的纯音
+        coeffs[46] = 1.0e6;
+        let bands = swb_offset_long(4).len() - 1;
+        let mut energy = vec![0.0; bands];
+        let mut threshold = vec![0.0; bands];
+        psy.analyze(&coeffs, false, &mut energy, &mut threshold);
+        let band = swb_offset_long(4).iter().rposition(|&o| o <= 46).unwrap();
+        assert!(
+            threshold[band] < energy[band] * 0.1,
+            "阈值应远低于掩蔽者能量"
+        );
+        assert!(
+            threshold[band + 1] > threshold[band + 6],
+            "掩蔽应随 Bark 距离衰减"
+        );
+        assert!(
+            threshold[bands - 1] >= psy.long.quiet[bands - 1],
+            "阈值不应低于绝对听阈"
+        );
+    }
+}
@@ -0,0 +1,470 @@
+//! AAC 编码器量化与熵编码.
+//!
+//! 每个 (窗组, 频带) 在允许的量化噪声内搜索最粗的 scale factor,
+//! 再按量化值范围选择码字最短的频谱码本, 输出 section/scalefactor/spectral 数据.
+
+use std::ops::Range;
+
+use tao_core::bitwriter::BitWriter;
+
+use super::mdct::SHORT_LEN;
+use crate::decoders::aac::huffman::{scalefactor_codeword, spectral_codeword};
+
+/// 量化值上限 (ESC 码本最多 13 位)
+const MAX_QUANT: usize = 8191;
+/// 量化舍入偏移 (参考编码器取值, 比四舍五入更接近最小失真)
+const ROUNDING: f32 = 0.4054;
+/// 相邻 scale factor 差值的最大绝对值
+const MAX_SF_DELTA: i32 = 60;
+/// scale factor 中对应单位增益的取值
+const SF_OFFSET: i32 = 120;
+/// 全零频带码本
+const ZERO_HCB: u8 = 0;
+/// 各码本可表示的最大绝对值 (索引为码本号)
+const CODEBOOK_MAX: [usize; 12] = [0, 1, 1, 2, 2, 4, 4, 7, 7, 12, 12, MAX_QUANT];
+
+/// 一个 ICS 的频带划分与窗分组
+pub(super) struct IcsLayout {
+    pub(super) offsets: &'static [usize],
+    /// 每组的窗数 (长块为 `[1]`)
+    pub(super) groups: Vec<usize>,
+    pub(super) short: bool,
+}
+
+impl IcsLayout {
+    pub(super) fn num_bands(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// 第 `group` 组第 `band` 个频带在各窗频谱中的下标区间
+    pub(super) fn band_ranges(
+        &self,
+        group: usize,
+        band: usize,
+    ) -> impl Iterator<Item = Range<usize>> + '_ {
+        let first = self.groups[..group].iter().sum::<usize>();
+        (first..first + self.groups[group]).map(move |win| {
+            win * SHORT_LEN + self.offsets[band]..win * SHORT_LEN + self.offsets[band + 1]
+        })
+    }
+}
+
+/// 一个声道待量化的频谱及其掩蔽阈值
+pub(super) struct ChannelSpectrum {
+    pub(super) coeffs: Vec<f32>,
+    /// |X|^(3/4)
+    pow34: Vec<f32>,
+    /// 每个 (组, 频带) 的能量
+    pub(super) energy: Vec<f32>,
+    /// 每个 (组, 频带) 允许的量化噪声能量
+    pub(super) threshold: Vec<f32>,
+}
+
+impl ChannelSpectrum {
+    pub(super) fn new(coeffs: Vec<f32>, energy: Vec<f32>, threshold: Vec<f32>) -> Self {
+        let pow34 = coeffs.iter().map(|x| x.abs().powf(0.75)).collect();
+        Self {
+            coeffs,
+            pow34,
+            energy,
+            threshold,
+        }
+    }
+}
+
+/// 一个 ICS 的量化结果
+pub(super) struct IcsQuant {
+    /// 每个 (组, 频带) 的 scale factor
+    sf: Vec<i32>,
+    /// 每个 (组, 频带) 的码本, 0 表示全零
+    cb: Vec<u8>,
+    /// 量化后的频谱 (与输入频谱同布局)
+    q: Vec<i32>,
+    global_gain: i32,
+    /// 最后一个非零频带 + 1
+    max_sfb: usize,
+}
+
+impl IcsQuant {
+    pub(super) fn global_gain(&self) -> i32 {
+        self.global_gain
+    }
+
+    pub(super) fn max_sfb(&self) -> usize {
+        self.max_sfb
+    }
+
+    /// 写出 section_data / scale_factor_data / 工具标志 / spectral_data
+    pub(super) fn write(&self, bw: &mut BitWriter, layout: &IcsLayout, max_sfb: usize) {
+        let bands = layout.num_bands();
+        let (len_bits, len_esc) = if layout.short { (3, 7) } else { (5, 31) };
+        for group in 0..layout.groups.len() {
+            let cbs = &self.cb[group * bands..group * bands + max_sfb];
+            let mut start = 0;
+            while start < max_sfb {
+                let cb = cbs[start];
+                let end = cbs[start..]
+                    .iter()
+                    .position(|&c| c != cb)
+                    .map_or(max_sfb, |n| start + n);
+                bw.write_bits(u32::from(cb), 4);
+                let mut len = (end - start) as u32;
+                while len >= len_esc {
+                    bw.write_bits(len_esc, len_bits);
+                    len -= len_esc;
+                }
+                bw.write_bits(len, len_bits);
+                start = end;
+            }
+        }
+
+        let mut last_sf = self.global_gain;
+        for group in 0..layout.groups.len() {
+            for band in 0..max_sfb {
+                let idx = group * bands + band;
+                if self.cb[idx] != ZERO_HCB {
+                    let (code, len) =
+                        scalefactor_codeword((self.sf[idx] - last_sf + MAX_SF_DELTA) as usize);
+                    bw.write_bits(code, u32::from(len));
+                    last_sf = self.sf[idx];
+                }
+            }
+        }
+
+        // pulse_data_present / tns_data_present / gain_control_data_present
+        bw.write_bits(0, 3);
+
+        for group in 0..layout.groups.len() {
+            for band in 0..max_sfb {
+                let cb = self.cb[group * bands + band];
+                if cb == ZERO_HCB {
+                    continue;
+                }
+                for range in layout.band_ranges(group, band) {
+                    for tuple in self.q[range].chunks(codebook_dim(cb)) {
+                        write_tuple(bw, cb, tuple);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 量化器
+pub(super) struct Quantizer {
+    /// q^(4/3) 查找表
+    pow43: Vec<f32>,
+}
+
+impl Quantizer {
+    pub(super) fn new() -> Self {
+        Self {
+            pow43: (0..=MAX_QUANT)
+                .map(|q| (q as f32).powf(4.0 / 3.0))
+                .collect(),
+        }
+    }
+
+    /// 以 `noise_scale` 倍的掩蔽阈值为失真上限量化一个 ICS
+    ///
+    /// 仅编码前 `max_bands` 个频带, 其余频带视为全零.
+    pub(super) fn quantize(
+        &self,
+        spectrum: &ChannelSpectrum,
+        layout: &IcsLayout,
+        max_bands: usize,
+        noise_scale: f32,
+    ) -> IcsQuant {
+        let bands = layout.num_bands();
+        let slots = layout.groups.len() * bands;
+        let mut result = IcsQuant {
+            sf: vec![0; slots],
+            cb: vec![ZERO_HCB; slots],
+            q: vec![0; spectrum.coeffs.len()],
+            global_gain: 0,
+            max_sfb: 0,
+        };
+        let mut last_sf: Option<i32> = None;
+        for group in 0..layout.groups.len() {
+            for band in 0..max_bands.min(bands) {
+                let idx = group * bands + band;
+                let allowed = spectrum.threshold[idx] * noise_scale;
+                if spectrum.energy[idx] <= allowed {
+                    continue;
+                }
+                let mut sf = self.search_scalefactor(spectrum, layout, group, band, allowed);
+                if let Some(last) = last_sf {
+                    sf = sf.clamp(last - MAX_SF_DELTA, last + MAX_SF_DELTA);
+                }
+                let mut max_q = 0;
+                for range in layout.band_ranges(group, band) {
+                    for i in range {
+                        let q = self.quantize_value(spectrum.pow34[i], sf);
+                        max_q = max_q.max(q);
+                        result.q[i] = if spectrum.coeffs[i] < 0.0 {
+                            -(q as i32)
+                        } else {
+                            q as i32
+                        };
+                    }
+                }
+                if max_q == 0 {
+                    continue;
+                }
+                result.cb[idx] = select_codebook(&result.q, layout, group, band, max_q);
+                result.sf[idx] = sf;
+                result.max_sfb = result.max_sfb.max(band + 1);
+                if last_sf.is_none() {
+                    result.global_gain = sf;
+                }
+                last_sf = Some(sf);
+            }
+        }
+        result
+    }
+
+    fn quantize_value(&self, pow34: f32, sf: i32) -> usize {
+        let scale = 2f32.powf(-0.1875 * (sf - SF_OFFSET) as f32);
+        ((pow34 * scale + ROUNDING) as usize).min(MAX_QUANT)
+    }
+
+    /// 量化一个频带并返回量化噪声能量
+    fn band_distortion(
+        &self,
+        spectrum: &ChannelSpectrum,
+        layout: &IcsLayout,
+        group: usize,
+        band: usize,
+        sf: i32,
+    ) -> f32 {
+        let scale = 2f32.powf(-0.1875 * (sf - SF_OFFSET) as f32);
+        let step = 2f32.powf(0.25 * (sf - SF_OFFSET) as f32);
+        let mut distortion = 0.0;
+        for range in layout.band_ranges(group, band) {
+            for i in range {
+                let q = ((spectrum.pow34[i] * scale + ROUNDING) as usize).min(MAX_QUANT);
+                let err = spectrum.coeffs[i].abs() - self.pow43[q] * step;
+                distortion += err * err;
+            }
+        }
+        distortion
+    }
+
+    /// 二分搜索失真不超过 `allowed` 的最大 (最粗) scale factor
+    fn search_scalefactor(
+        &self,
+        spectrum: &ChannelSpectrum,
+        layout: &IcsLayout,
+        group: usize,
+        band: usize,
+        allowed: f32,
+    ) -> i32 {
+        let max34 = layout
+            .band_ranges(group, band)
+            .flat_map(|range| spectrum.pow34[range].iter().copied())
+            .fold(0.0f32, f32::max);
+        // 保证最大量化值不超过 MAX_QUANT
+        let min_sf = (SF_OFFSET as f32
+            - ((MAX_QUANT as f32 + 1.0 - ROUNDING) / max34).log2() / 0.1875)
+            .floor() as i32
+            + 1;
+        let (mut lo, mut hi) = (min_sf.clamp(0, 255), 255);
+        if self.band_distortion(spectrum, layout, group, band, lo) > allowed {
+            return lo;
+        }
+        while lo < hi {
+            let mid = (lo + hi + 1) / 2;
+            if self.band_distortion(spectrum, layout, group, band, mid) <= allowed {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        lo
+    }
+}
+
+/// 码本维度: 1-4 为四元组, 其余为二元组
+fn codebook_dim(cb: u8) -> usize {
+    if cb <= 4 { 4 } else { 2 }
+}
+
+/// 码本的值是否自带符号
+fn codebook_signed(cb: u8) -> bool {
+    matches!(cb, 1 | 2 | 5 | 6)
+}
+
+/// 计算值元组在码本中的线性索引
+fn tuple_index(cb: u8, values: &[i32]) -> usize {
+    let (modulo, offset) = match cb {
+        1 | 2 => (3, 1),
+        3 | 4 => (3, 0),
+        5 | 6 => (9, 4),
+        7 | 8 => (8, 0),
+        9 | 10 => (13, 0),
+        _ => (17, 0),
+    };
+    values.iter().fold(0, |acc, &v| {
+        let v = if codebook_signed(cb) {
+            v + offset
+        } else {
+            v.unsigned_abs().min(16) as i32
+        };
+        acc * modulo + v as usize
+    })
+}
+
+/// ESC 序列位数 (|v| >= 16)
+fn escape_bits(value: u32) -> usize {
+    let n = 31 - value.leading_zeros() as usize;
+    2 * n - 3
+}
+
+fn tuple_bits(cb: u8, values: &[i32]) -> usize {
+    let (_, len) = spectral_codeword(cb, tuple_index(cb, values));
+    let mut bits = usize::from(len);
+    if !codebook_signed(cb) {
+        for &v in values {
+            if v != 0 {
+                bits += 1;
+            }
+            if cb == 11 && v.unsigned_abs() >= 16 {
+                bits += escape_bits(v.unsigned_abs());
+            }
+        }
+    }
+    bits
+}
+
+fn write_tuple(bw: &mut BitWriter, cb: u8, values: &[i32]) {
+    let (code, len) = spectral_codeword(cb, tuple_index(cb, values));
+    bw.write_bits(code, u32::from(len));
+    if codebook_signed(cb) {
+        return;
+    }
+    for &v in values {
+        if v != 0 {
+            bw.write_bit(u32::from(v < 0));
+        }
+    }
+    if cb == 11 {
+        for &v in values {
+            let mag = v.unsigned_abs();
+            if mag >= 16 {
+                let n = 31 - mag.leading_zeros();
+                for _ in 4..n {
+                    bw.write_bit(1);
+                }
+                bw.write_bit(0);
+                bw.write_bits(mag - (1 << n), n);
+            }
+        }
+    }
+}
+
+/// 在可表示 `max_q` 的码本中选择码字最短者
+fn select_codebook(q: &[i32], layout: &IcsLayout, group: usize, band: usize, max_q: usize) -> u8 {
+    let first = CODEBOOK_MAX
+        .iter()
+        .position(|&max| max >= max_q)
+        .unwrap_or(11) as u8;
+    let candidates: &[u8] = match first {
+        1 => &[1, 2],
+        3 => &[3, 4],
+        5 => &[5, 6],
+        7 => &[7, 8],
+        9 => &[9, 10],
+        _ => &[11],
+    };
+    let bits = |cb: u8| -> usize {
+        layout
+            .band_ranges(group, band)
+            .flat_map(|range| q[range].chunks(codebook_dim(cb)))
+            .map(|tuple| tuple_bits(cb, tuple))
+            .sum()
+    };
+    candidates
+        .iter()
+        .copied()
+        .min_by_key(|&cb| bits(cb))
+        .unwrap_or(11)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::aac::huffman::AacCodebooks;
+    use tao_core::bitreader::BitReader;
+
+    #[test]
+    fn test_tuples_decode_with_decoder_codebooks() {
+        let books = AacCodebooks::build();
+        let cases: [(u8, [i32; 4]); 8] = [
+            (1, [-1, 0, 1, 1]),
+            (4, [2, -2, 0, 1]),
+            (5, [-4, 3, 0, 0]),
+            (8, [7, -5, 0, 0]),
+            (10, [-12, 0, 0, 0]),
+            (11, [15, -16, 0, 0]),
+            (11, [-17, 300, 0, 0]),
+            (11, [8191, -1, 0, 0]),
+        ];
+        for (cb, values) in cases {
+            let dim = codebook_dim(cb);
+            let mut bw = BitWriter::new();
+            write_tuple(&mut bw, cb, &values[..dim]);
+            assert_eq!(
+                bw.bits_written(),
+                tuple_bits(cb, &values[..dim]),
+                "码本 {cb} 位数估计应与实际写出一致"
+            );
+            let data = bw.finish();
+            let mut br = BitReader::new(&data);
+            let decoded = books.spectral[cb as usize - 1]
+                .as_ref()
+                .unwrap()
+                .decode_values(&mut br)
+                .unwrap();
+            assert_eq!(&decoded[..dim], &values[..dim], "码本 {cb} 往返不一致");
+        }
+    }
+
+    #[test]
+    fn test_scalefactor_codewords_decode() {
+        let books = AacCodebooks::build();
+        for index in [0usize, 59, 60, 61, 120] {
+            let (code, len) = scalefactor_codeword(index);
+            let mut bw = BitWriter::new();
+            bw.write_bits(code, u32::from(len));
+            let data = bw.finish();
+            let mut br = BitReader::new(&data);
+            assert_eq!(books.sf_tree.decode(&mut br).unwrap(), index as i32);
+        }
+    }
+
+    #[test]
+    fn test_quantize_respects_threshold() {
+        let offsets: &'static [usize] = &[0, 4, 8, 16];
+        let layout = IcsLayout {
+            offsets,
+            groups: vec![1],
+            short: false,
+        };
+        let coeffs: Vec<f32> = (0..16).map(|i| (i as f32 - 7.5) * 1000.0).collect();
+        let energy: Vec<f32> = offsets
+            .windows(2)
+            .map(|w| coeffs[w[0]..w[1]].iter().map(|x| x * x).sum())
+            .collect();
+        let threshold: Vec<f32> = energy.iter().map(|e| e * 1e-3).collect();
+        let spectrum = ChannelSpectrum::new(coeffs, energy, threshold.clone());
+        let quantizer = Quantizer::new();
+        let fine = quantizer.quantize(&spectrum, &layout, 3, 1.0);
+        assert_eq!(fine.max_sfb(), 3);
+        for (band, &allowed) in threshold.iter().enumerate() {
+            let d = quantizer.band_distortion(&spectrum, &layout, 0, band, fine.sf[band]);
+            assert!(d <= allowed, "频带 {band} 失真 {d} 应不超过阈值");
+        }
+        let coarse = quantizer.quantize(&spectrum, &layout, 3, 1e6);
+        assert_eq!(coarse.max_sfb(), 0, "阈值高于能量时应全部置零");
+    }
+}
@@ -4,8 +4,10 @@
 //! - Constant 子帧 (所有采样相同)
 //! - Verbatim 子帧 (未压缩)
 //! - Fixed 预测子帧 (0-4 阶)
-//! - Rice 熵编码
+//! - LPC 预测子帧 (最高 8 阶): 加窗 + 自相关 + Levinson-Durbin, 系数量化
+//! - Rice 熵编码, 按分区搜索最优分区阶数与 Rice 参数
 //! - 自动选择最优子帧类型 (最小编码)
+//! - 压缩级别 0-8 (`compression_level` 选项, 默认 5)
 //! - CRC-8 (帧头) 和 CRC-16 (帧尾)
 
 use bytes::Bytes;
@@ -14,16 +16,148 @@ use tao_core::crc;
 use tao_core::{ChannelLayout, SampleFormat, TaoError, TaoResult};
 use tracing::debug;
 
+use crate::capabilities::CodecCapabilities;
 use crate::codec_id::CodecId;
 use crate::codec_parameters::{CodecParameters, CodecParamsType};
 use crate::encoder::Encoder;
 use crate::frame::Frame;
 use crate::packet::Packet;
 
-/// 最大 Rice 参数搜索范围
+/// 最大 Rice 参数搜索范围 (15 为逃逸码)
 const MAX_RICE_PARAM: u32 = 14;
 /// 最大固定预测阶数
 const MAX_FIXED_ORDER: u32 = 4;
+/// 最大 LPC 预测阶数
+const MAX_LPC_ORDER: usize = 8;
+/// 量化 LPC 系数的最大精度 (位)
+const MAX_QLP_PRECISION: u32 = 15;
+/// 量化 LPC 系数的最大移位量 (5 位有符号字段)
+const MAX_QLP_SHIFT: i32 = 15;
+/// 子帧头位数 (padding + type + wasted)
+const SUBFRAME_HEADER_BITS: u64 = 8;
+/// 默认压缩级别 (与 libFLAC 一致)
+const DEFAULT_COMPRESSION_LEVEL: u32 = 5;
+
+/// LPC 分析窗函数 (apodization)
+#[derive(Debug, Clone, Copy)]
+enum Apodization {
+    /// 矩形窗 (不加窗)
+    Rectangle,
+    /// Hann 窗
+    Hann,
+    /// Tukey 窗, 参数为两端余弦过渡段占窗长的比例
+    Tukey(f64),
+}
+
+/// 压缩级别对应的编码参数
+#[derive(Debug)]
+struct CompressionConfig {
+    /// 最大 LPC 阶数, 0 表示仅使用 Fixed 预测
+    max_lpc_order: usize,
+    /// LPC 分析使用的窗函数, 每个窗分别计算一组系数
+    apodizations: &'static [Apodization],
+    /// 最大 Rice 分区阶数
+    max_partition_order: u32,
+    /// 是否实际编码每个 LPC 阶数以选择最优 (否则按预测误差估算)
+    exhaustive_order_search: bool,
+}
+
+/// 压缩级别 0-8 的编码参数, 级别越高压缩率越高, 编码越慢
+const COMPRESSION_CONFIGS: [CompressionConfig; 9] = [
+    CompressionConfig {
+        max_lpc_order: 0,
+        apodizations: &[],
+        max_partition_order: 3,
+        exhaustive_order_search: false,
+    },
+    CompressionConfig {
+        max_lpc_order: 0,
+        apodizations: &[],
+        max_partition_order: 4,
+        exhaustive_order_search: false,
+    },
+    CompressionConfig {
+        max_lpc_order: 0,
+        apodizations: &[],
+        max_partition_order: 6,
+        exhaustive_order_search: false,
+    },
+    CompressionConfig {
+        max_lpc_order: 6,
+        apodizations: &[Apodization::Tukey(0.5)],
+        max_partition_order: 4,
+        exhaustive_order_search: false,
+    },
+    CompressionConfig {
+        max_lpc_order: 8,
+        apodizations: &[Apodization::Tukey(0.5)],
+        max_partition_order: 4,
+        exhaustive_order_search: false,
+    },
+    CompressionConfig {
+        max_lpc_order: 8,
+        apodizations: &[Apodization::Tukey(0.5)],
+        max_partition_order: 5,
+        exhaustive_order_search: false,
+    },
+    CompressionConfig {
+        max_lpc_order: 8,
+        apodizations: &[Apodization::Tukey(0.5), Apodization::Hann],
+        max_partition_order: 6,
+        exhaustive_order_search: false,
+    },
+    CompressionConfig {
+        max_lpc_order: 8,
+        apodizations: &[Apodization::Tukey(0.5), Apodization::Hann],
+        max_partition_order: 6,
+        exhaustive_order_search: true,
+    },
+    CompressionConfig {
+        max_lpc_order: 8,
+        apodizations: &[
+            Apodization::Tukey(0.5),
+            Apodization::Tukey(0.25),
+            Apodization::Hann,
+            Apodization::Rectangle,
+        ],
+        max_partition_order: 8,
+        exhaustive_order_search: true,
+    },
+];
+
+/// Rice 分区编码方案
+#[derive(Debug, Clone)]
+struct RicePartition {
+    /// 分区阶数 (分区数 = 2^order)
+    order: u32,
+    /// 每个分区的 Rice 参数
+    params: Vec<u32>,
+    /// 残差部分的总位数 (含编码方式与分区参数)
+    bits: u64,
+}
+
+/// 子帧编码方案
+#[derive(Debug)]
+enum SubframePlan {
+    /// 所有采样相同
+    Constant(i32),
+    /// 未压缩
+    Verbatim,
+    /// Fixed 预测
+    Fixed {
+        order: u32,
+        residuals: Vec<i32>,
+        rice: RicePartition,
+    },
+    /// LPC 预测
+    Lpc {
+        coefs: Vec<i32>,
+        precision: u32,
+        shift: u32,
+        residuals: Vec<i32>,
+        rice: RicePartition,
+    },
+}
 
 /// FLAC 编码器
 pub struct FlacEncoder {
@@ -51,6 +185,8 @@ pub struct FlacEncoder {
     max_frame_size: u32,
     /// 已编码的总采样数
     total_samples: u64,
+    /// 压缩级别 (0-8)
+    compression_level: u32,
 }
 
 impl FlacEncoder {
@@ -69,9 +205,15 @@ impl FlacEncoder {
             min_frame_size: u32::MAX,
             max_frame_size: 0,
             total_samples: 0,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
         }))
     }
 
+    /// 当前压缩级别的编码参数
+    fn config(&self) -> &'static CompressionConfig {
+        &COMPRESSION_CONFIGS[self.compression_level as usize]
+    }
+
     /// 获取 STREAMINFO 元数据 (34 字节)
     pub fn stream_info(&self) -> Vec<u8> {
         let mut si = vec![0u8; 34];
@@ -191,38 +333,104 @@ impl FlacEncoder {
 
     /// 编码一个子帧 (自动选择最优类型)
     fn encode_subframe(&self, bw: &mut BitWriter, samples: &[i32], bps: u32) -> TaoResult<()> {
-        let n = samples.len();
-        if n == 0 {
+        if samples.is_empty() {
             return Ok(());
         }
+        match self.plan_subframe(samples, bps) {
+            SubframePlan::Constant(value) => self.encode_constant_subframe(bw, value, bps),
+            SubframePlan::Verbatim => self.encode_verbatim_subframe(bw, samples, bps),
+            SubframePlan::Fixed {
+                order,
+                residuals,
+                rice,
+            } => self.encode_fixed_subframe(bw, samples, bps, order, &residuals, &rice),
+            SubframePlan::Lpc {
+                coefs,
+                precision,
+                shift,
+                residuals,
+                rice,
+            } => self.encode_lpc_subframe(
+                bw, samples, bps, &coefs, precision, shift, &residuals, &rice,
+            ),
+        }
+    }
+
+    /// 为子帧选择编码位数最少的方案
+    fn plan_subframe(&self, samples: &[i32], bps: u32) -> SubframePlan {
+        let n = samples.len();
 
         // 检查是否全部相同 (Constant 子帧)
         if samples.iter().all(|&s| s == samples[0]) {
-            return self.encode_constant_subframe(bw, samples[0], bps);
+            return SubframePlan::Constant(samples[0]);
         }
 
-        // 尝试所有 Fixed 预测阶数, 选择最小编码
-        let mut best_order: Option<u32> = None;
-        let mut best_bits = u64::MAX;
+        let config = self.config();
+        let mut best = SubframePlan::Verbatim;
+        let mut best_bits = SUBFRAME_HEADER_BITS + n as u64 * u64::from(bps);
 
+        // 尝试所有 Fixed 预测阶数
         for order in 0..=MAX_FIXED_ORDER.min(n as u32 - 1) {
             let residuals = compute_fixed_residuals(samples, order);
-            let bits = estimate_rice_bits(&residuals);
+            let rice =
+                search_rice_partition(&residuals, n, order as usize, config.max_partition_order);
+            let bits = SUBFRAME_HEADER_BITS + u64::from(order * bps) + rice.bits;
             if bits < best_bits {
                 best_bits = bits;
-                best_order = Some(order);
+                best = SubframePlan::Fixed {
+                    order,
+                    residuals,
+                    rice,
+                };
             }
         }
 
-        // 与 Verbatim 比较
-        let verbatim_bits = (n as u64) * u64::from(bps);
-        if best_bits + 100 >= verbatim_bits {
-            // Verbatim 更好 (加 100 是子帧头开销)
-            return self.encode_verbatim_subframe(bw, samples, bps);
+        // 尝试 LPC 预测
+        let max_lpc_order = config.max_lpc_order.min(n - 1);
+        if max_lpc_order == 0 {
+            return best;
+        }
+        // 系数精度取最大值 (与 FFmpeg 默认一致), 系数开销仅占每阶 15 位
+        let precision = MAX_QLP_PRECISION;
+        for &apodization in config.apodizations {
+            let Some((lpcs, errors)) =
+                compute_lpc_coefficients(samples, apodization, max_lpc_order)
+            else {
+                continue;
+            };
+            let orders = if config.exhaustive_order_search {
+                (1..=lpcs.len()).collect::<Vec<_>>()
+            } else {
+                vec![estimate_best_lpc_order(&errors, n, precision + bps)]
+            };
+            for order in orders {
+                let Some((coefs, shift)) = quantize_lpc_coefficients(&lpcs[order - 1], precision)
+                else {
+                    continue;
+                };
+                let Some(residuals) = compute_lpc_residuals(samples, &coefs, shift) else {
+                    continue;
+                };
+                let rice = search_rice_partition(&residuals, n, order, config.max_partition_order);
+                let bits = SUBFRAME_HEADER_BITS
+                    + order as u64 * u64::from(bps + precision)
+                    + 4
+                    + 5
+                    + rice.bits;
+                if bits < best_bits {
+                    best_bits = bits;
+                    best = SubframePlan::Lpc {
+                        coefs,
+                        precision,
+                        shift,
+                        residuals,
+                        rice,
+                    };
+                }
+            }
         }
 
-        let order = best_order.unwrap_or(0);
-        self.encode_fixed_subframe(bw, samples, bps, order)
+        best
     }
 
     /// 编码 Constant 子帧
@@ -262,6 +470,8 @@ impl FlacEncoder {
         samples: &[i32],
         bps: u32,
         order: u32,
+        residuals: &[i32],
+        rice: &RicePartition,
     ) -> TaoResult<()> {
         // 子帧头: padding(1)=0 + type(6)=001xxx + wasted(1)=0
         bw.write_bits(0, 1);
@@ -273,11 +483,42 @@ impl FlacEncoder {
             bw.write_bits_signed(sample, bps);
         }
 
-        // 残差
-        let residuals = compute_fixed_residuals(samples, order);
-        self.encode_residual(bw, &residuals, samples.len() as u32, order)?;
+        self.encode_residual(bw, residuals, samples.len(), order as usize, rice)
+    }
 
-        Ok(())
+    /// 编码 LPC 预测子帧
+    #[allow(clippy::too_many_arguments)]
+    fn encode_lpc_subframe(
+        &self,
+        bw: &mut BitWriter,
+        samples: &[i32],
+        bps: u32,
+        coefs: &[i32],
+        precision: u32,
+        shift: u32,
+        residuals: &[i32],
+        rice: &RicePartition,
+    ) -> TaoResult<()> {
+        let order = coefs.len();
+
+        // 子帧头: padding(1)=0 + type(6)=1xxxxx (order-1) + wasted(1)=0
+        bw.write_bits(0, 1);
+        bw.write_bits(0b100000 | (order as u32 - 1), 6);
+        bw.write_bit(0);
+
+        // Warm-up 样本
+        for &sample in &samples[..order] {
+            bw.write_bits_signed(sample, bps);
+        }
+
+        // 系数精度 (precision-1, 4 位), 移位量 (5 位有符号), 量化系数
+        bw.write_bits(precision - 1, 4);
+        bw.write_bits_signed(shift as i32, 5);
+        for &coef in coefs {
+            bw.write_bits_signed(coef, precision);
+        }
+
+        self.encode_residual(bw, residuals, samples.len(), order, rice)
     }
 
     /// 编码残差 (Rice 编码)
@@ -285,37 +526,26 @@ impl FlacEncoder {
         &self,
         bw: &mut BitWriter,
         residuals: &[i32],
-        block_size: u32,
-        predictor_order: u32,
+        block_size: usize,
+        predictor_order: usize,
+        rice: &RicePartition,
     ) -> TaoResult<()> {
         // 使用 RICE_PARTITION (coding method = 0)
         bw.write_bits(0, 2); // coding method = 0
+        bw.write_bits(rice.order, 4);
 
-        // 选择最优分区阶数
-        let partition_order = select_partition_order(block_size, predictor_order);
-        bw.write_bits(partition_order, 4);
-
-        let num_partitions = 1u32 << partition_order;
-
+        let partition_size = block_size >> rice.order;
         let mut residual_idx = 0usize;
-        for partition in 0..num_partitions {
+        for (partition, &rice_param) in rice.params.iter().enumerate() {
             let partition_samples = if partition == 0 {
-                (block_size >> partition_order) - predictor_order
+                partition_size - predictor_order
             } else {
-                block_size >> partition_order
-            } as usize;
-
-            let partition_data = &residuals[residual_idx..residual_idx + partition_samples];
-
-            // 选择最优 Rice 参数
-            let rice_param = select_rice_param(partition_data);
+                partition_size
+            };
             bw.write_bits(rice_param, 4);
-
-            // 编码每个残差
-            for &residual in partition_data {
+            for &residual in &residuals[residual_idx..residual_idx + partition_samples] {
                 encode_rice_sample(bw, residual, rice_param);
             }
-
             residual_idx += partition_samples;
         }
 
@@ -353,6 +583,22 @@ impl FlacEncoder {
                     }
                 }
             }
+            SampleFormat::S24 => {
+                // S24 已是符号扩展后的 24 位样本值, 无需移位
+                for i in 0..nb_samples {
+                    for (ch, ch_vec) in result.iter_mut().enumerate() {
+                        let idx = (i * channels + ch) * 4;
+                        if idx + 3 < data.len() {
+                            ch_vec.push(i32::from_le_bytes([
+                                data[idx],
+                                data[idx + 1],
+                                data[idx + 2],
+                                data[idx + 3],
+                            ]));
+                        }
+                    }
+                }
+            }
             SampleFormat::S32 => {
                 let shift = if bps <= 24 { 32 - bps } else { 0 };
                 for i in 0..nb_samples {
@@ -395,6 +641,49 @@ impl Encoder for FlacEncoder {
         "flac"
     }
 
+    fn codec_capabilities(&self) -> CodecCapabilities {
+        CodecCapabilities::CAPS_LOSSLESS
+    }
+
+    fn supported_sample_formats(&self) -> &'static [SampleFormat] {
+        &[
+            SampleFormat::S16,
+            SampleFormat::S24,
+            SampleFormat::S32,
+            SampleFormat::U8,
+        ]
+    }
+
+    /// 打开后提供 STREAMINFO (帧长与总采样数未知时为 0)
+    fn extra_data(&self) -> Vec<u8> {
+        if self.opened {
+            self.stream_info()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn set_option(&mut self, key: &str, value: &str) -> TaoResult<()> {
+        match key {
+            "compression_level" => {
+                let max_level = COMPRESSION_CONFIGS.len() as u32 - 1;
+                self.compression_level = value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|&level| level <= max_level)
+                    .ok_or_else(|| {
+                        TaoError::InvalidArgument(format!(
+                            "FLAC 压缩级别应为 0-{max_level}, 实际为 '{value}'"
+                        ))
+                    })?;
+                Ok(())
+            }
+            _ => Err(TaoError::Unsupported(format!(
+                "FLAC 编码器不支持选项 {key}"
+            ))),
+        }
+    }
+
     fn open(&mut self, params: &CodecParameters) -> TaoResult<()> {
         let audio = match &params.params {
             CodecParamsType::Audio(a) => a,
@@ -420,7 +709,7 @@ impl Encoder for FlacEncoder {
         self.bits_per_sample = match audio.sample_format {
             SampleFormat::U8 => 8,
             SampleFormat::S16 => 16,
-            SampleFormat::S32 => 24, // 默认 24 位
+            SampleFormat::S24 | SampleFormat::S32 => 24, // 默认 24 位
             _ => {
                 return Err(TaoError::Unsupported(format!(
                     "FLAC 不支持采样格式: {}",
@@ -442,8 +731,12 @@ impl Encoder for FlacEncoder {
         self.total_samples = 0;
 
         debug!(
-            "打开 FLAC 编码器: {} Hz, {} 声道, {} 位, 块大小={}",
-            self.sample_rate, self.channels, self.bits_per_sample, self.block_size,
+            "打开 FLAC 编码器: {} Hz, {} 声道, {} 位, 块大小={}, 压缩级别={}",
+            self.sample_rate,
+            self.channels,
+            self.bits_per_sample,
+            self.block_size,
+            self.compression_level,
         );
         Ok(())
     }
@@ -466,8 +759,8 @@ impl Encoder for FlacEncoder {
 
         let audio = match frame {
             Frame::Audio(a) => a,
-            Frame::Video(_) => {
-                return Err(TaoError::InvalidArgument("FLAC 编码器不接受视频帧".into()));
+            Frame::Video(_) | Frame::Subtitle(_) => {
+                return Err(TaoError::InvalidArgument("FLAC 编码器仅接受音频帧".into()));
             }
         };
 
@@ -489,7 +782,7 @@ impl Encoder for FlacEncoder {
         pkt.dts = audio.pts;
         pkt.duration = i64::from(nb_samples);
         pkt.time_base = audio.time_base;
-        pkt.is_keyframe = true;
+        pkt.set_keyframe(true);
 
         self.frame_number += 1;
         self.output_packet = Some(pkt);
@@ -537,46 +830,80 @@ fn compute_fixed_residuals(samples: &[i32], order: u32) -> Vec<i32> {
     residuals
 }
 
-/// 估算 Rice 编码所需的位数
-fn estimate_rice_bits(residuals: &[i32]) -> u64 {
-    if residuals.is_empty() {
-        return 0;
+/// 搜索最优 Rice 分区阶数与各分区参数
+///
+/// 先按最大分区阶数统计每个分区的折叠残差和, 再逐级两两合并,
+/// 每个分区的 Rice 参数取估算位数最少者.
+fn search_rice_partition(
+    residuals: &[i32],
+    block_size: usize,
+    predictor_order: usize,
+    max_partition_order: u32,
+) -> RicePartition {
+    // 分区阶数需整除块大小, 且首个分区的采样数大于预测阶数
+    let mut max_order = max_partition_order.min(block_size.trailing_zeros());
+    while max_order > 0 && (block_size >> max_order) <= predictor_order {
+        max_order -= 1;
     }
 
-    // 估算最优 Rice 参数
-    let param = estimate_optimal_rice_param(residuals);
-    let mut total_bits: u64 = 0;
-
-    for &residual in residuals {
-        let unsigned_val = fold_signed(residual);
-        let quotient = unsigned_val >> param;
-        total_bits += u64::from(quotient) + 1 + u64::from(param);
+    // 最细分区的 (采样数, 折叠残差和)
+    let partition_size = block_size >> max_order;
+    let mut sums = Vec::with_capacity(1 << max_order);
+    let mut start = 0usize;
+    for partition in 0..1usize << max_order {
+        let count = if partition == 0 {
+            partition_size - predictor_order
+        } else {
+            partition_size
+        };
+        let sum: u64 = residuals[start..start + count]
+            .iter()
+            .map(|&r| u64::from(fold_signed(r)))
+            .sum();
+        sums.push((count as u64, sum));
+        start += count;
     }
 
-    total_bits
-}
-
-/// 选择最优 Rice 参数
-fn select_rice_param(residuals: &[i32]) -> u32 {
-    estimate_optimal_rice_param(residuals).min(MAX_RICE_PARAM)
-}
-
-/// 估算最优 Rice 参数
-fn estimate_optimal_rice_param(residuals: &[i32]) -> u32 {
-    if residuals.is_empty() {
-        return 0;
+    let mut best: Option<RicePartition> = None;
+    let mut order = max_order;
+    loop {
+        let mut params = Vec::with_capacity(sums.len());
+        let mut bits = 2 + 4;
+        for &(count, sum) in &sums {
+            let (param, partition_bits) = best_rice_param(count, sum);
+            params.push(param);
+            bits += 4 + partition_bits;
+        }
+        if best.as_ref().is_none_or(|b| bits < b.bits) {
+            best = Some(RicePartition {
+                order,
+                params,
+                bits,
+            });
+        }
+        if order == 0 {
+            break;
+        }
+        sums = sums
+            .chunks(2)
+            .map(|pair| (pair[0].0 + pair[1].0, pair[0].1 + pair[1].1))
+            .collect();
+        order -= 1;
     }
 
-    // 计算残差绝对值的平均
-    let sum: u64 = residuals.iter().map(|&r| u64::from(fold_signed(r))).sum();
-    let avg = sum / residuals.len() as u64;
+    best.unwrap_or(RicePartition {
+        order: 0,
+        params: vec![0],
+        bits: 0,
+    })
+}
 
-    // Rice 参数约为 log2(avg)
-    if avg == 0 {
-        0
-    } else {
-        (64 - avg.leading_zeros() - 1).min(MAX_RICE_PARAM)
-    }
+/// 按分区的折叠残差和估算最优 Rice 参数, 返回 (参数, 估算位数)
+fn best_rice_param(count: u64, sum: u64) -> (u32, u64) {
+    (0..=MAX_RICE_PARAM)
+        .map(|param| (param, count * u64::from(param + 1) + (sum >> param)))
+        .min_by_key(|&(_, bits)| bits)
+        .unwrap_or((0, 0))
 }
 
 /// 将有符号值映射为无符号 (折叠映射)
@@ -603,18 +930,151 @@ fn encode_rice_sample(bw: &mut BitWriter, value: i32, rice_param: u32) {
     }
 }
 
-/// 选择分区阶数
-fn select_partition_order(block_size: u32, predictor_order: u32) -> u32 {
-    // 简单策略: 选择使分区大小合理的阶数
-    let mut order = 0u32;
-    while order < 8 {
-        let partition_size = block_size >> (order + 1);
-        if partition_size < predictor_order + 4 {
+/// 生成窗函数
+fn build_window(apodization: Apodization, len: usize) -> Vec<f64> {
+    let hann = |n: usize, total: usize| {
+        0.5 - 0.5 * (2.0 * std::f64::consts::PI * n as f64 / (total - 1) as f64).cos()
+    };
+    match apodization {
+        Apodization::Rectangle => vec![1.0; len],
+        Apodization::Hann => (0..len).map(|n| hann(n, len.max(2))).collect(),
+        Apodization::Tukey(p) => {
+            let mut window = vec![1.0; len];
+            // 两端各有 p/2 比例的 Hann 过渡段
+            let taper = (p / 2.0 * len as f64) as usize;
+            if taper > 1 {
+                for n in 0..taper {
+                    let w = 0.5 - 0.5 * (std::f64::consts::PI * n as f64 / taper as f64).cos();
+                    window[n] = w;
+                    window[len - 1 - n] = w;
+                }
+            }
+            window
+        }
+    }
+}
+
+/// 加窗后计算自相关, 并用 Levinson-Durbin 递推求 1..=max_order 阶的预测系数
+///
+/// 返回 (各阶系数, 各阶预测误差), 信号能量为 0 或递推不稳定时提前截断.
+fn compute_lpc_coefficients(
+    samples: &[i32],
+    apodization: Apodization,
+    max_order: usize,
+) -> Option<(Vec<Vec<f64>>, Vec<f64>)> {
+    let window = build_window(apodization, samples.len());
+    let data: Vec<f64> = samples
+        .iter()
+        .zip(&window)
+        .map(|(&s, &w)| f64::from(s) * w)
+        .collect();
+
+    let mut autoc = [0.0f64; MAX_LPC_ORDER + 1];
+    for (lag, value) in autoc.iter_mut().enumerate().take(max_order + 1) {
+        *value = data[lag..].iter().zip(&data).map(|(a, b)| a * b).sum();
+    }
+    if autoc[0] <= 0.0 {
+        return None;
+    }
+
+    let mut lpc = [0.0f64; MAX_LPC_ORDER];
+    let mut err = autoc[0];
+    let mut lpcs = Vec::with_capacity(max_order);
+    let mut errors = Vec::with_capacity(max_order);
+    for i in 0..max_order {
+        let mut acc = autoc[i + 1];
+        for j in 0..i {
+            acc -= lpc[j] * autoc[i - j];
+        }
+        let k = acc / err;
+
+        let prev = lpc;
+        lpc[i] = k;
+        for j in 0..i {
+            lpc[j] = prev[j] - k * prev[i - 1 - j];
+        }
+        err *= 1.0 - k * k;
+        if err <= 0.0 || !err.is_finite() || !k.is_finite() {
             break;
         }
-        order += 1;
+        lpcs.push(lpc[..=i].to_vec());
+        errors.push(err);
+    }
+
+    if lpcs.is_empty() {
+        None
+    } else {
+        Some((lpcs, errors))
+    }
+}
+
+/// 按预测误差估算各 LPC 阶数的编码位数, 返回估算最优的阶数
+fn estimate_best_lpc_order(errors: &[f64], block_size: usize, bits_per_order: u32) -> usize {
+    let error_scale = 0.5 / block_size as f64;
+    let mut best_order = 1;
+    let mut best_bits = f64::MAX;
+    for (index, &err) in errors.iter().enumerate() {
+        let order = index + 1;
+        let bits_per_residual = (0.5 * (error_scale * err).log2()).max(0.0);
+        let bits = bits_per_residual * (block_size - order) as f64
+            + (order as u32 * bits_per_order) as f64;
+        if bits < best_bits {
+            best_bits = bits;
+            best_order = order;
+        }
+    }
+    best_order
+}
+
+/// 将浮点预测系数量化为 precision 位整数, 返回 (量化系数, 移位量)
+///
+/// 量化误差逐项累积到下一个系数 (误差反馈), 移位量为负时放弃该组系数.
+fn quantize_lpc_coefficients(lpc: &[f64], precision: u32) -> Option<(Vec<i32>, u32)> {
+    let cmax = lpc.iter().fold(0.0f64, |m, c| m.max(c.abs()));
+    if cmax <= 0.0 {
+        return None;
+    }
+
+    let qmax = (1i32 << (precision - 1)) - 1;
+    let qmin = -(1i32 << (precision - 1));
+    // cmax 的二进制指数, 使系数最大值恰好落入 precision-1 位
+    let log2cmax = cmax.log2().floor() as i32 + 1;
+    let shift = (precision as i32 - 1 - log2cmax).min(MAX_QLP_SHIFT);
+    if shift < 0 {
+        return None;
+    }
+
+    let scale = f64::from(1u32 << shift);
+    let mut error = 0.0;
+    let coefs = lpc
+        .iter()
+        .map(|&c| {
+            error += c * scale;
+            let q = (error.round() as i32).clamp(qmin, qmax);
+            error -= f64::from(q);
+            q
+        })
+        .collect();
+    Some((coefs, shift as u32))
+}
+
+/// 计算 LPC 预测残差, 残差超出 Rice 编码范围时返回 `None`
+fn compute_lpc_residuals(samples: &[i32], coefs: &[i32], shift: u32) -> Option<Vec<i32>> {
+    let order = coefs.len();
+    let mut residuals = Vec::with_capacity(samples.len() - order);
+    for i in order..samples.len() {
+        let predicted: i64 = coefs
+            .iter()
+            .enumerate()
+            .map(|(j, &c)| i64::from(c) * i64::from(samples[i - 1 - j]))
+            .sum();
+        let residual = i64::from(samples[i]) - (predicted >> shift);
+        if residual.unsigned_abs() >= 1 << 30 {
+            return None;
+        }
+        residuals.push(residual as i32);
     }
-    order
+    Some(residuals)
 }
 
 /// 编码 block_size 代码
@@ -695,6 +1155,7 @@ mod tests {
                 channel_layout: ChannelLayout::from_channels(channels),
                 sample_format,
                 frame_size: 256,
+                block_align: 0,
             }),
         }
     }
@@ -750,6 +1211,7 @@ mod tests {
                 channel_layout: ChannelLayout::from_channels(channels),
                 sample_format,
                 frame_size: u32::from(block_size),
+                block_align: 0,
             }),
         }
     }
@@ -758,7 +1220,14 @@ mod tests {
     fn test_open_encoder() {
         let params = make_flac_params(44100, 2, 16);
         let mut enc = FlacEncoder::create().unwrap();
+        assert!(enc.extra_data().is_empty(), "打开前没有全局头");
         enc.open(&params).unwrap();
+        let si = enc.extra_data();
+        assert_eq!(si.len(), 34, "全局头应为 STREAMINFO");
+        let sample_rate =
+            (u32::from(si[10]) << 12) | (u32::from(si[11]) << 4) | u32::from(si[12] >> 4);
+        assert_eq!(sample_rate, 44100);
+        assert_eq!((si[12] >> 1) & 0x07, 1, "声道数 - 1");
     }
 
     #[test]
@@ -770,7 +1239,7 @@ mod tests {
         // 全零 S16 单声道, 256 样本
         let data = vec![0u8; 256 * 2];
         let mut af = AudioFrame::new(256, 44100, SampleFormat::S16, ChannelLayout::MONO);
-        af.data[0] = data;
+        af.data[0] = data.into();
 
         enc.send_frame(Some(&Frame::Audio(af))).unwrap();
         let pkt = enc.receive_packet().unwrap();
@@ -786,7 +1255,7 @@ mod tests {
         // 编码全零帧
         let original = vec![0u8; 256 * 2];
         let mut af = AudioFrame::new(256, 44100, SampleFormat::S16, ChannelLayout::MONO);
-        af.data[0] = original.clone();
+        af.data[0] = original.clone().into();
         af.pts = 0;
 
         enc.send_frame(Some(&Frame::Audio(af))).unwrap();
@@ -825,7 +1294,7 @@ mod tests {
         }
 
         let mut af = AudioFrame::new(nb_samples, 44100, SampleFormat::S16, ChannelLayout::MONO);
-        af.data[0] = pcm.clone();
+        af.data[0] = pcm.clone().into();
         af.pts = 0;
 
         enc.send_frame(Some(&Frame::Audio(af))).unwrap();
@@ -857,6 +1326,126 @@ mod tests {
         assert!(matches!(err, TaoError::Eof));
     }
 
+    /// 生成类似音乐的 16 位单声道信号: 明亮音色的旋律 (含 5 个泛音),
+    /// 每 0.25 秒切换一个音符, 带起音/衰减包络与低电平噪声
+    fn make_music_like_pcm(sample_rate: u32, seconds: u32) -> Vec<i16> {
+        const MELODY: [f64; 8] = [
+            523.25, 587.33, 659.26, 698.46, 783.99, 880.00, 987.77, 1046.50,
+        ];
+        let note_len = (sample_rate / 4) as usize;
+        let total = (sample_rate * seconds) as usize;
+        let mut noise_state = 0x1234_5678u32;
+        (0..total)
+            .map(|i| {
+                let note = i / note_len;
+                let t = (i % note_len) as f64 / f64::from(sample_rate);
+                let envelope = (t / 0.01).min(1.0) * (-t * 3.0).exp();
+                let pitch = MELODY[(note * 3) % MELODY.len()];
+                let mut value = 0.0;
+                for harmonic in 1..=5 {
+                    let h = f64::from(harmonic);
+                    value += (std::f64::consts::TAU * pitch * h * t).sin() * 8000.0 / h;
+                }
+                noise_state = noise_state
+                    .wrapping_mul(1_664_525)
+                    .wrapping_add(1_013_904_223);
+                let noise = f64::from((noise_state >> 24) as u8) / 255.0 - 0.5;
+                (value * envelope + noise) as i16
+            })
+            .collect()
+    }
+
+    /// 按 4096 采样分块编码整段信号, 返回数据包列表
+    fn encode_pcm(pcm: &[i16], sample_rate: u32, compression_level: Option<&str>) -> Vec<Packet> {
+        let mut params = make_flac_params(sample_rate, 1, 16);
+        if let CodecParamsType::Audio(audio) = &mut params.params {
+            audio.frame_size = 4096;
+        }
+        let mut enc = FlacEncoder::create().unwrap();
+        if let Some(level) = compression_level {
+            enc.set_option("compression_level", level).unwrap();
+        }
+        enc.open(&params).unwrap();
+
+        let mut packets = Vec::new();
+        for (index, chunk) in pcm.chunks(4096).enumerate() {
+            let data: Vec<u8> = chunk.iter().flat_map(|s| s.to_le_bytes()).collect();
+            let mut af = AudioFrame::new(
+                chunk.len() as u32,
+                sample_rate,
+                SampleFormat::S16,
+                ChannelLayout::MONO,
+            );
+            af.data[0] = data.into();
+            af.pts = (index * 4096) as i64;
+            enc.send_frame(Some(&Frame::Audio(af))).unwrap();
+            packets.push(enc.receive_packet().unwrap());
+        }
+        packets
+    }
+
+    #[test]
+    fn test_flac_lpc_compression_and_bit_exact() {
+        let sample_rate = 44100;
+        let pcm = make_music_like_pcm(sample_rate, 30);
+
+        // 级别 0 仅使用 Fixed 预测, 作为原编码器的基线; 不指定级别时默认为 5
+        let fixed_only = encode_pcm(&pcm, sample_rate, Some("0"));
+        let lpc = encode_pcm(&pcm, sample_rate, None);
+        let fixed_size: usize = fixed_only.iter().map(|p| p.data.len()).sum();
+        let lpc_size: usize = lpc.iter().map(|p| p.data.len()).sum();
+        assert!(
+            lpc_size * 10 <= fixed_size * 7,
+            "级别 5 应比仅 Fixed 预测小至少 30%: {lpc_size} vs {fixed_size}"
+        );
+
+        // 逐包解码, 验证无损
+        let mut dec = FlacDecoder::create().unwrap();
+        dec.open(&make_decoder_params(sample_rate, 1, 16, 4096))
+            .unwrap();
+        let mut decoded = Vec::with_capacity(pcm.len() * 2);
+        for pkt in &lpc {
+            dec.send_packet(pkt).unwrap();
+            match dec.receive_frame().unwrap() {
+                Frame::Audio(frame) => decoded.extend_from_slice(&frame.data[0]),
+                _ => panic!("期望音频帧"),
+            }
+        }
+        let original: Vec<u8> = pcm.iter().flat_map(|s| s.to_le_bytes()).collect();
+        assert!(decoded == original, "LPC 编码往返应逐位一致");
+    }
+
+    #[test]
+    fn test_flac_compression_level_option() {
+        let mut enc = FlacEncoder::create().unwrap();
+        enc.set_option("compression_level", "8").unwrap();
+        assert!(
+            enc.set_option("compression_level", "9").is_err(),
+            "超出 0-8 的压缩级别应被拒绝"
+        );
+        assert!(
+            enc.set_option("no_such_option", "1").is_err(),
+            "未知选项应被拒绝"
+        );
+
+        // 所有级别编码短信号均应无损
+        let pcm = make_music_like_pcm(44100, 1);
+        for level in 0..=8 {
+            let packets = encode_pcm(&pcm, 44100, Some(&level.to_string()));
+            let mut dec = FlacDecoder::create().unwrap();
+            dec.open(&make_decoder_params(44100, 1, 16, 4096)).unwrap();
+            let mut decoded = Vec::new();
+            for pkt in &packets {
+                dec.send_packet(pkt).unwrap();
+                if let Frame::Audio(frame) = dec.receive_frame().unwrap() {
+                    decoded.extend_from_slice(&frame.data[0]);
+                }
+            }
+            let original: Vec<u8> = pcm.iter().flat_map(|s| s.to_le_bytes()).collect();
+            assert!(decoded == original, "级别 {level} 编码往返应逐位一致");
+        }
+    }
+
     #[test]
     fn test_fold_signed() {
         assert_eq!(fold_signed(0), 0);
@@ -5,22 +5,67 @@ pub mod flac;
 pub mod pcm;
 pub mod rawvideo;
 
+use tao_core::SampleFormat;
+
 use crate::codec_id::CodecId;
-use crate::registry::CodecRegistry;
+use crate::registry::{CodecDescriptor, CodecRegistry};
 
 /// 注册所有内置编码器
+///
+/// 能力描述中的格式为编码输入格式.
 pub fn register_all_encoders(registry: &mut CodecRegistry) {
+    let audio = |id, name, formats: &[SampleFormat]| {
+        CodecDescriptor::new(id, name).with_sample_formats(formats)
+    };
+
     registry.register_encoder(
         CodecId::RawVideo,
         "rawvideo",
         rawvideo::RawVideoEncoder::create,
     );
-    registry.register_encoder(CodecId::PcmU8, "pcm_u8", pcm::PcmEncoder::new_u8);
-    registry.register_encoder(CodecId::PcmS16le, "pcm_s16le", pcm::PcmEncoder::new_s16le);
-    registry.register_encoder(CodecId::PcmS16be, "pcm_s16be", pcm::PcmEncoder::new_s16be);
-    registry.register_encoder(CodecId::PcmS24le, "pcm_s24le", pcm::PcmEncoder::new_s24le);
-    registry.register_encoder(CodecId::PcmS32le, "pcm_s32le", pcm::PcmEncoder::new_s32le);
-    registry.register_encoder(CodecId::PcmF32le, "pcm_f32le", pcm::PcmEncoder::new_f32le);
-    registry.register_encoder(CodecId::Flac, "flac", flac::FlacEncoder::create);
-    registry.register_encoder(CodecId::Aac, "aac_lc", aac::AacEncoder::create);
+    registry.register_encoder_descriptor(
+        audio(CodecId::PcmU8, "pcm_u8", &[SampleFormat::U8]),
+        pcm::PcmEncoder::new_u8,
+    );
+    registry.register_encoder_descriptor(
+        audio(CodecId::PcmS16le, "pcm_s16le", &[SampleFormat::S16]),
+        pcm::PcmEncoder::new_s16le,
+    );
+    registry.register_encoder_descriptor(
+        audio(CodecId::PcmS16be, "pcm_s16be", &[SampleFormat::S16]),
+        pcm::PcmEncoder::new_s16be,
+    );
+    registry.register_encoder_descriptor(
+        audio(CodecId::PcmS24le, "pcm_s24le", &[SampleFormat::S32]),
+        pcm::PcmEncoder::new_s24le,
+    );
+    registry.register_encoder_descriptor(
+        audio(CodecId::PcmS32le, "pcm_s32le", &[SampleFormat::S32]),
+        pcm::PcmEncoder::new_s32le,
+    );
+    registry.register_encoder_descriptor(
+        audio(CodecId::PcmF32le, "pcm_f32le", &[SampleFormat::F32]),
+        pcm::PcmEncoder::new_f32le,
+    );
+    registry.register_encoder_descriptor(
+        audio(
+            CodecId::Flac,
+            "flac",
+            &[
+                SampleFormat::S16,
+                SampleFormat::S24,
+                SampleFormat::S32,
+                SampleFormat::U8,
+            ],
+        ),
+        flac::FlacEncoder::create,
+    );
+    registry.register_encoder_descriptor(
+        audio(
+            CodecId::Aac,
+            "aac",
+            &[SampleFormat::F32, SampleFormat::F32p],
+        ),
+        aac::AacEncoder::create,
+    );
 }
@@ -7,6 +7,7 @@ use bytes::Bytes;
 use tao_core::{ChannelLayout, SampleFormat, TaoError, TaoResult};
 use tracing::debug;
 
+use crate::capabilities::CodecCapabilities;
 use crate::codec_id::CodecId;
 use crate::codec_parameters::{CodecParameters, CodecParamsType};
 use crate::encoder::Encoder;
@@ -154,6 +155,20 @@ impl Encoder for PcmEncoder {
         self.desc.codec_id.name()
     }
 
+    fn codec_capabilities(&self) -> CodecCapabilities {
+        CodecCapabilities::CAPS_INTRA_ONLY | CodecCapabilities::CAPS_LOSSLESS
+    }
+
+    fn supported_sample_formats(&self) -> &'static [SampleFormat] {
+        match self.desc.input_format {
+            SampleFormat::U8 => &[SampleFormat::U8],
+            SampleFormat::S16 => &[SampleFormat::S16],
+            SampleFormat::S32 => &[SampleFormat::S32],
+            SampleFormat::F32 => &[SampleFormat::F32],
+            _ => &[],
+        }
+    }
+
     fn open(&mut self, params: &CodecParameters) -> TaoResult<()> {
         let audio = match &params.params {
             CodecParamsType::Audio(a) => a,
@@ -203,12 +218,15 @@ impl Encoder for PcmEncoder {
 
         let audio = match frame {
             Frame::Audio(a) => a,
-            Frame::Video(_) => {
-                return Err(TaoError::InvalidArgument("PCM 编码器不接受视频帧".into()));
+            Frame::Video(_) | Frame::Subtitle(_) => {
+                return Err(TaoError::InvalidArgument("PCM 编码器仅接受音频帧".into()));
             }
         };
 
-        if audio.sample_format != self.desc.input_format {
+        // S24 帧与 pcm_s24le 的 S32 输入布局一致 (低 24 位有效), 可直接编码
+        let s24_compatible =
+            self.desc.codec_id == CodecId::PcmS24le && audio.sample_format == SampleFormat::S24;
+        if audio.sample_format != self.desc.input_format && !s24_compatible {
             return Err(TaoError::InvalidArgument(format!(
                 "期望采样格式 {}, 实际为 {}",
                 self.desc.input_format, audio.sample_format,
@@ -227,7 +245,7 @@ impl Encoder for PcmEncoder {
         pkt.dts = audio.pts;
         pkt.duration = audio.duration;
         pkt.time_base = audio.time_base;
-        pkt.is_keyframe = true;
+        pkt.set_keyframe(true);
 
         self.output_packet = Some(pkt);
         Ok(())
@@ -266,10 +284,21 @@ mod tests {
                 channel_layout: ChannelLayout::from_channels(channels),
                 sample_format: SampleFormat::None,
                 frame_size: 0,
+                block_align: 0,
             }),
         }
     }
 
+    #[test]
+    fn test_pcm_supported_sample_formats() {
+        let enc = PcmEncoder::new_s24le().unwrap();
+        assert_eq!(enc.supported_sample_formats(), &[SampleFormat::S32]);
+        assert_eq!(enc.preferred_sample_format(), SampleFormat::S32);
+        assert!(enc.supported_sample_rates().is_none(), "PCM 不限制采样率");
+        let enc = PcmEncoder::new_f32le().unwrap();
+        assert_eq!(enc.supported_sample_formats(), &[SampleFormat::F32]);
+    }
+
     #[test]
     fn test_pcm_u8_encode() {
         let mut enc = PcmEncoder::new_u8().unwrap();
@@ -277,7 +306,7 @@ mod tests {
 
         let data = vec![128u8, 64, 192, 255];
         let mut af = AudioFrame::new(4, 44100, SampleFormat::U8, ChannelLayout::MONO);
-        af.data[0] = data.clone();
+        af.data[0] = data.clone().into();
 
         enc.send_frame(Some(&Frame::Audio(af))).unwrap();
         let pkt = enc.receive_packet().unwrap();
@@ -292,7 +321,7 @@ mod tests {
         // 小端输入: [0x00, 0x01] -> 大端输出: [0x01, 0x00]
         let data = vec![0x00, 0x01, 0xFF, 0x7F];
         let mut af = AudioFrame::new(2, 44100, SampleFormat::S16, ChannelLayout::MONO);
-        af.data[0] = data;
+        af.data[0] = data.into();
 
         enc.send_frame(Some(&Frame::Audio(af))).unwrap();
         let pkt = enc.receive_packet().unwrap();
@@ -307,7 +336,7 @@ mod tests {
         // S32 输入: [0x56, 0x34, 0x12, 0x00] -> S24 输出: [0x56, 0x34, 0x12]
         let data = vec![0x56, 0x34, 0x12, 0x00, 0x00, 0x00, 0x80, 0xFF];
         let mut af = AudioFrame::new(2, 44100, SampleFormat::S32, ChannelLayout::MONO);
-        af.data[0] = data;
+        af.data[0] = data.into();
 
         enc.send_frame(Some(&Frame::Audio(af))).unwrap();
         let pkt = enc.receive_packet().unwrap();
@@ -343,7 +372,7 @@ mod tests {
 
         let original = vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04, 0x00];
         let mut af = AudioFrame::new(2, 44100, SampleFormat::S16, ChannelLayout::STEREO);
-        af.data[0] = original.clone();
+        af.data[0] = original.clone().into();
         af.pts = 42;
         af.time_base = Rational::new(1, 44100);
 
@@ -378,7 +407,7 @@ mod tests {
 
         let original = vec![0x34, 0x12, 0x78, 0x56]; // 小端 S16: 0x1234, 0x5678
         let mut af = AudioFrame::new(2, 44100, SampleFormat::S16, ChannelLayout::MONO);
-        af.data[0] = original.clone();
+        af.data[0] = original.clone().into();
 
         enc.send_frame(Some(&Frame::Audio(af))).unwrap();
         let pkt = enc.receive_packet().unwrap();
@@ -410,7 +439,7 @@ mod tests {
         // 正数: 0x00123456 -> 截断为 24 位 -> 符号扩展回 0x00123456
         let input_s32 = vec![0x56, 0x34, 0x12, 0x00];
         let mut af = AudioFrame::new(1, 44100, SampleFormat::S32, ChannelLayout::MONO);
-        af.data[0] = input_s32.clone();
+        af.data[0] = input_s32.clone().into();
 
         enc.send_frame(Some(&Frame::Audio(af))).unwrap();
         let pkt = enc.receive_packet().unwrap();
@@ -7,6 +7,7 @@ use bytes::Bytes;
 use tao_core::{PixelFormat, TaoError, TaoResult};
 use tracing::debug;
 
+use crate::capabilities::CodecCapabilities;
 use crate::codec_id::CodecId;
 use crate::codec_parameters::{CodecParameters, CodecParamsType};
 use crate::encoder::Encoder;
@@ -54,6 +55,10 @@ impl Encoder for RawVideoEncoder {
         "rawvideo"
     }
 
+    fn codec_capabilities(&self) -> CodecCapabilities {
+        CodecCapabilities::CAPS_INTRA_ONLY | CodecCapabilities::CAPS_LOSSLESS
+    }
+
     fn open(&mut self, params: &CodecParameters) -> TaoResult<()> {
         let video = match &params.params {
             CodecParamsType::Video(v) => v,
@@ -109,9 +114,9 @@ impl Encoder for RawVideoEncoder {
 
         let video = match frame {
             Frame::Video(v) => v,
-            Frame::Audio(_) => {
+            Frame::Audio(_) | Frame::Subtitle(_) => {
                 return Err(TaoError::InvalidArgument(
-                    "rawvideo 编码器不接受音频帧".into(),
+                    "rawvideo 编码器仅接受视频帧".into(),
                 ));
             }
         };
@@ -135,7 +140,8 @@ impl Encoder for RawVideoEncoder {
         pkt.dts = video.pts; // RAW 视频无 B 帧, DTS = PTS
         pkt.duration = video.duration;
         pkt.time_base = video.time_base;
-        pkt.is_keyframe = true;
+        // 仅帧内编码: 忽略关键帧间隔, 每个数据包均为关键帧
+        pkt.set_keyframe(true);
 
         self.output_packet = Some(pkt);
         Ok(())
@@ -187,7 +193,7 @@ mod tests {
 
         let data: Vec<u8> = (0..12).collect();
         let mut vf = VideoFrame::new(2, 2, PixelFormat::Rgb24);
-        vf.data[0] = data.clone();
+        vf.data[0] = data.clone().into();
         vf.linesize[0] = 6;
         vf.pts = 42;
         vf.time_base = Rational::new(1, 25);
@@ -197,7 +203,26 @@ mod tests {
         assert_eq!(&pkt.data[..], &data[..]);
         assert_eq!(pkt.pts, 42);
         assert_eq!(pkt.dts, 42);
-        assert!(pkt.is_keyframe);
+        assert!(pkt.is_keyframe());
+    }
+
+    #[test]
+    fn test_every_packet_keyframe_regardless_of_interval() {
+        let mut enc = RawVideoEncoder::create().unwrap();
+        enc.set_keyframe_interval(3);
+        enc.open(&make_video_params(2, 2, PixelFormat::Gray8))
+            .unwrap();
+
+        for i in 0..6 {
+            let mut vf = VideoFrame::new(2, 2, PixelFormat::Gray8);
+            vf.data[0] = vec![i as u8; 4].into();
+            vf.linesize[0] = 2;
+            vf.pts = i;
+            vf.force_keyframe = i == 4;
+            enc.send_frame(Some(&Frame::Video(vf))).unwrap();
+            let pkt = enc.receive_packet().unwrap();
+            assert!(pkt.is_keyframe(), "第 {i} 帧应为关键帧");
+        }
     }
 
     #[test]
@@ -231,7 +256,7 @@ mod tests {
 
         let original_data: Vec<u8> = (0..24).collect(); // 4*2*3 = 24
         let mut vf = VideoFrame::new(4, 2, PixelFormat::Rgb24);
-        vf.data[0] = original_data.clone();
+        vf.data[0] = original_data.clone().into();
         vf.linesize[0] = 12;
         vf.pts = 10;
         vf.time_base = Rational::new(1, 25);
@@ -265,9 +290,9 @@ mod tests {
 
         let mut vf = VideoFrame::new(4, 4, PixelFormat::Yuv420p);
         // Y: 4*4=16, U: 2*2=4, V: 2*2=4
-        vf.data[0] = vec![10u8; 16];
-        vf.data[1] = vec![20u8; 4];
-        vf.data[2] = vec![30u8; 4];
+        vf.data[0] = vec![10u8; 16].into();
+        vf.data[1] = vec![20u8; 4].into();
+        vf.data[2] = vec![30u8; 4].into();
         vf.linesize = vec![4, 2, 2];
 
         enc.send_frame(Some(&Frame::Video(vf.clone()))).unwrap();
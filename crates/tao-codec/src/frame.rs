@@ -7,14 +7,18 @@ use tao_core::{
     color::{ColorRange, ColorSpace},
 };
 
+use crate::frame_pool::{FrameBuf, FramePool};
+
 /// 视频帧
 ///
 /// 包含解码后的原始像素数据, 支持多平面存储.
 /// 例如 YUV420P 格式有 3 个平面: Y, U, V.
+///
+/// 平面数据为引用计数的 [`FrameBuf`], 克隆帧不会复制像素数据.
 #[derive(Debug, Clone)]
 pub struct VideoFrame {
     /// 各平面的像素数据
-    pub data: Vec<Vec<u8>>,
+    pub data: Vec<FrameBuf>,
     /// 各平面每行的字节数 (linesize / stride)
     pub linesize: Vec<usize>,
     /// 宽度 (像素)
@@ -31,6 +35,10 @@ pub struct VideoFrame {
     pub duration: i64,
     /// 是否为关键帧
     pub is_keyframe: bool,
+    /// 要求编码器将此帧编码为关键帧 (对标 FFmpeg 编码前设置 `pict_type = I`)
+    ///
+    /// 仅作用于编码输入, 解码器输出的帧始终为 false.
+    pub force_keyframe: bool,
     /// 图片类型 (I/P/B 帧)
     pub picture_type: PictureType,
     /// 采样宽高比 (SAR)
@@ -46,7 +54,7 @@ impl VideoFrame {
     pub fn new(width: u32, height: u32, pixel_format: PixelFormat) -> Self {
         let plane_count = pixel_format.plane_count() as usize;
         Self {
-            data: vec![Vec::new(); plane_count],
+            data: vec![FrameBuf::default(); plane_count],
             linesize: vec![0; plane_count],
             width,
             height,
@@ -55,12 +63,32 @@ impl VideoFrame {
             time_base: Rational::UNDEFINED,
             duration: 0,
             is_keyframe: false,
+            force_keyframe: false,
             picture_type: PictureType::None,
             sample_aspect_ratio: Rational::new(1, 1),
             color_space: ColorSpace::default(),
             color_range: ColorRange::default(),
         }
     }
+
+    /// 创建视频帧, 各平面按紧凑行宽从缓冲池获取零初始化缓冲
+    ///
+    /// 帧释放后平面缓冲归还 `pool`, 供后续帧复用.
+    pub fn new_pooled(
+        width: u32,
+        height: u32,
+        pixel_format: PixelFormat,
+        pool: &FramePool,
+    ) -> Self {
+        let mut frame = Self::new(width, height, pixel_format);
+        for (plane, (buf, linesize)) in frame.data.iter_mut().zip(&mut frame.linesize).enumerate() {
+            let stride = pixel_format.plane_linesize(plane, width).unwrap_or(0);
+            let rows = pixel_format.plane_height(plane, height).unwrap_or(0);
+            *buf = pool.acquire(stride * rows);
+            *linesize = stride;
+        }
+        frame
+    }
 }
 
 /// 音频帧
@@ -70,8 +98,8 @@ impl VideoFrame {
 /// 交错格式: data 中只有一个 Vec, 所有声道交替排列.
 #[derive(Debug, Clone)]
 pub struct AudioFrame {
-    /// 音频采样数据 (平面格式: 每声道一个 Vec; 交错格式: 单个 Vec)
-    pub data: Vec<Vec<u8>>,
+    /// 音频采样数据 (平面格式: 每声道一个缓冲; 交错格式: 单个缓冲)
+    pub data: Vec<FrameBuf>,
     /// 本帧包含的采样数 (每声道)
     pub nb_samples: u32,
     /// 采样率 (Hz)
@@ -102,7 +130,7 @@ impl AudioFrame {
             1
         };
         Self {
-            data: vec![Vec::new(); plane_count],
+            data: vec![FrameBuf::default(); plane_count],
             nb_samples,
             sample_rate,
             sample_format,
@@ -112,15 +140,100 @@ impl AudioFrame {
             duration: 0,
         }
     }
+
+    /// 创建音频帧, 各平面从缓冲池获取零初始化缓冲
+    ///
+    /// 帧释放后缓冲归还 `pool`, 供后续帧复用.
+    pub fn new_pooled(
+        nb_samples: u32,
+        sample_rate: u32,
+        sample_format: SampleFormat,
+        channel_layout: ChannelLayout,
+        pool: &FramePool,
+    ) -> Self {
+        let mut frame = Self::new(nb_samples, sample_rate, sample_format, channel_layout);
+        let channels_per_plane = if sample_format.is_planar() {
+            1
+        } else {
+            channel_layout.channels as usize
+        };
+        let plane_size =
+            nb_samples as usize * sample_format.bytes_per_sample() as usize * channels_per_plane;
+        for buf in &mut frame.data {
+            *buf = pool.acquire(plane_size);
+        }
+        frame
+    }
+
+    /// 重置帧以便复用: 采样数据清零, 时间戳与时长复位
+    ///
+    /// 保留缓冲长度与格式参数. 与其他帧共享的缓冲不会被改写,
+    /// 而是替换为新的零值缓冲.
+    pub fn reuse(&mut self) {
+        for buf in &mut self.data {
+            if buf.is_shared() {
+                *buf = FrameBuf::from_vec(vec![0; buf.len()]);
+            } else {
+                buf.fill(0);
+            }
+        }
+        self.pts = tao_core::timestamp::NOPTS_VALUE;
+        self.duration = 0;
+    }
+
+    /// 按采样数与采样率计算的帧时长 (秒), 采样率为 0 时返回 0
+    pub fn duration_seconds(&self) -> f64 {
+        if self.sample_rate == 0 {
+            return 0.0;
+        }
+        self.nb_samples as f64 / self.sample_rate as f64
+    }
 }
 
-/// 帧 (视频帧或音频帧的统一包装)
+/// 字幕帧
+///
+/// 文本字幕解码结果, 时间戳沿用输入数据包的时间基 (数据包时间基无效时以毫秒为单位).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtitleFrame {
+    /// 纯文本内容 (已去除标记标签), 多行以 '\n' 分隔
+    pub text: String,
+    /// 显示时间戳 (PTS)
+    pub pts: i64,
+    /// 显示时长
+    pub duration: i64,
+    /// 样式/位置设置 (如 WebVTT cue 设置), 无则为 None
+    pub style: Option<String>,
+}
+
+/// 帧 (视频帧、音频帧或字幕帧的统一包装)
 #[derive(Debug, Clone)]
 pub enum Frame {
     /// 视频帧
     Video(VideoFrame),
     /// 音频帧
     Audio(AudioFrame),
+    /// 字幕帧
+    Subtitle(SubtitleFrame),
+}
+
+impl Frame {
+    /// 帧时长 (以 time_base 为单位)
+    pub fn duration(&self) -> i64 {
+        match self {
+            Self::Video(v) => v.duration,
+            Self::Audio(a) => a.duration,
+            Self::Subtitle(s) => s.duration,
+        }
+    }
+
+    /// 设置帧时长 (以 time_base 为单位)
+    pub fn set_duration(&mut self, duration: i64) {
+        match self {
+            Self::Video(v) => v.duration = duration,
+            Self::Audio(a) => a.duration = duration,
+            Self::Subtitle(s) => s.duration = duration,
+        }
+    }
 }
 
 /// 图片类型 (I/P/B 帧)
@@ -142,3 +255,69 @@ pub enum PictureType {
     /// SP 帧 (切换 P 帧)
     Sp,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_duration_accessors() {
+        let mut af = AudioFrame::new(1024, 48000, SampleFormat::F32, ChannelLayout::STEREO);
+        assert!((af.duration_seconds() - 1024.0 / 48000.0).abs() < 1e-12);
+        af.duration = 1024;
+        let mut frame = Frame::Audio(af);
+        assert_eq!(frame.duration(), 1024);
+        frame.set_duration(512);
+        assert_eq!(frame.duration(), 512);
+
+        let mut frame = Frame::Video(VideoFrame::new(16, 16, PixelFormat::Yuv420p));
+        assert_eq!(frame.duration(), 0);
+        frame.set_duration(1);
+        assert_eq!(frame.duration(), 1);
+    }
+
+    #[test]
+    fn test_audio_frame_reuse() {
+        let mut af = AudioFrame::new(2, 48000, SampleFormat::S16, ChannelLayout::MONO);
+        af.data[0] = FrameBuf::from_vec(vec![1, 2, 3, 4]);
+        af.pts = 100;
+        af.duration = 2;
+        let shared = af.clone();
+        af.reuse();
+        assert_eq!(af.data[0], vec![0u8; 4], "采样数据应清零");
+        assert_eq!(af.pts, tao_core::timestamp::NOPTS_VALUE);
+        assert_eq!(af.duration, 0);
+        assert_eq!(shared.data[0], vec![1, 2, 3, 4], "共享的缓冲不应被改写");
+    }
+
+    #[test]
+    fn test_new_pooled_recycles_planes() {
+        let pool = FramePool::new(8);
+        for _ in 0..10 {
+            let vf = VideoFrame::new_pooled(16, 8, PixelFormat::Yuv420p, &pool);
+            assert_eq!(vf.linesize, vec![16, 8, 8]);
+            assert_eq!(
+                vf.data.iter().map(|d| d.len()).collect::<Vec<_>>(),
+                vec![128, 32, 32]
+            );
+            let af =
+                AudioFrame::new_pooled(4, 48000, SampleFormat::F32, ChannelLayout::STEREO, &pool);
+            assert_eq!(af.data[0].len(), 4 * 4 * 2, "交错格式单平面含全部声道");
+        }
+        assert_eq!(pool.allocation_count(), 4, "逐帧释放后平面缓冲应全部复用");
+
+        let planar =
+            AudioFrame::new_pooled(4, 48000, SampleFormat::F32p, ChannelLayout::STEREO, &pool);
+        assert_eq!(planar.data.len(), 2);
+        assert!(
+            planar.data.iter().all(|d| d.len() == 16),
+            "平面格式每声道一个缓冲"
+        );
+    }
+
+    #[test]
+    fn test_audio_duration_seconds_zero_rate() {
+        let af = AudioFrame::new(1024, 0, SampleFormat::S16, ChannelLayout::MONO);
+        assert_eq!(af.duration_seconds(), 0.0);
+    }
+}
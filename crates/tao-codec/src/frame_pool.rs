@@ -0,0 +1,380 @@
+//! 帧缓冲池 (FramePool) 与引用计数平面缓冲 (FrameBuf).
+//!
+//! 对标 FFmpeg 的 `AVBufferPool` / `AVBufferRef`.
+//!
+//! - `FrameBuf` 是帧平面数据的引用计数句柄, `clone` 只增加引用计数,
+//!   写入时若被共享则自动复制 (写时复制).
+//! - `FramePool` 回收最后一个引用被释放的 `FrameBuf` 底层内存,
+//!   解码器从池中获取输出缓冲, 避免每帧重新分配.
+//!
+//! 池只管理平面字节缓冲而非整帧, 也不提供显式的 `recycle`: 帧平面可能被
+//! 滤镜、FIFO 等多处共享, 显式归还容易把仍被引用的缓冲交给下一帧.
+//! 因此 `take_vec`/`acquire` 相当于"取出", 归还由最后一个引用释放时自动完成.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+/// 帧缓冲池
+///
+/// 克隆 `FramePool` 得到的是同一个池的句柄. 池被释放后,
+/// 仍在外部使用的 `FrameBuf` 释放时直接归还给分配器.
+#[derive(Clone)]
+pub struct FramePool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    /// 空闲缓冲
+    free: Mutex<Vec<Vec<u8>>>,
+    /// 最多保留的空闲缓冲数量
+    max_free: AtomicUsize,
+    /// 池中没有可复用缓冲而新分配的次数
+    allocations: AtomicUsize,
+}
+
+impl FramePool {
+    /// 创建缓冲池, `max_free` 为最多保留的空闲缓冲数量
+    pub fn new(max_free: usize) -> Self {
+        Self {
+            inner: Arc::new(PoolInner {
+                free: Mutex::new(Vec::new()),
+                max_free: AtomicUsize::new(max_free),
+                allocations: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// 调整最多保留的空闲缓冲数量, 多余的空闲缓冲立即释放
+    pub fn set_max_free(&self, max_free: usize) {
+        self.inner.max_free.store(max_free, Ordering::Relaxed);
+        self.inner.lock_free().truncate(max_free);
+    }
+
+    /// 当前空闲缓冲数量
+    pub fn free_count(&self) -> usize {
+        self.inner.lock_free().len()
+    }
+
+    /// 累计新分配缓冲的次数 (复用空闲缓冲不计入)
+    pub fn allocation_count(&self) -> usize {
+        self.inner.allocations.load(Ordering::Relaxed)
+    }
+
+    /// 获取一个已清空且容量不小于 `capacity` 的缓冲
+    ///
+    /// 填充完成后通过 [`FramePool::wrap`] 包装为 `FrameBuf`.
+    pub fn take_vec(&self, capacity: usize) -> Vec<u8> {
+        let mut free = self.inner.lock_free();
+        let pos = free.iter().position(|v| v.capacity() >= capacity);
+        let mut buf = match pos {
+            Some(i) => free.swap_remove(i),
+            None => {
+                // 没有足够大的缓冲时丢弃一个较小的, 避免池中堆积无用缓冲
+                free.pop();
+                self.inner.allocations.fetch_add(1, Ordering::Relaxed);
+                Vec::with_capacity(capacity)
+            }
+        };
+        buf.clear();
+        buf
+    }
+
+    /// 将缓冲包装为归属本池的 `FrameBuf`
+    pub fn wrap(&self, data: Vec<u8>) -> FrameBuf {
+        FrameBuf {
+            inner: Arc::new(PooledBuffer {
+                data,
+                pool: Some(Arc::downgrade(&self.inner)),
+            }),
+        }
+    }
+
+    /// 获取长度为 `len` 的零初始化缓冲
+    pub fn acquire(&self, len: usize) -> FrameBuf {
+        let mut buf = self.take_vec(len);
+        buf.resize(len, 0);
+        self.wrap(buf)
+    }
+
+    /// 获取内容为 `src` 副本的缓冲
+    pub fn copy_from_slice(&self, src: &[u8]) -> FrameBuf {
+        let mut buf = self.take_vec(src.len());
+        buf.extend_from_slice(src);
+        self.wrap(buf)
+    }
+}
+
+/// 从可选的缓冲池获取已清空且容量不小于 `capacity` 的缓冲, 未指定池时直接分配
+pub(crate) fn take_vec_or_alloc(pool: Option<&FramePool>, capacity: usize) -> Vec<u8> {
+    match pool {
+        Some(pool) => pool.take_vec(capacity),
+        None => Vec::with_capacity(capacity),
+    }
+}
+
+/// 将缓冲包装为 `FrameBuf`, 指定池时释放后归还该池
+pub(crate) fn wrap_in(pool: Option<&FramePool>, data: Vec<u8>) -> FrameBuf {
+    match pool {
+        Some(pool) => pool.wrap(data),
+        None => FrameBuf::from_vec(data),
+    }
+}
+
+impl PoolInner {
+    fn lock_free(&self) -> std::sync::MutexGuard<'_, Vec<Vec<u8>>> {
+        self.free.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn recycle(&self, data: Vec<u8>) {
+        let mut free = self.lock_free();
+        if free.len() < self.max_free.load(Ordering::Relaxed) {
+            free.push(data);
+        }
+    }
+}
+
+impl fmt::Debug for FramePool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FramePool")
+            .field("free", &self.free_count())
+            .field("max_free", &self.inner.max_free.load(Ordering::Relaxed))
+            .field("allocations", &self.allocation_count())
+            .finish()
+    }
+}
+
+/// 池化缓冲, 最后一个引用释放时归还所属缓冲池
+struct PooledBuffer {
+    data: Vec<u8>,
+    pool: Option<Weak<PoolInner>>,
+}
+
+impl Clone for PooledBuffer {
+    fn clone(&self) -> Self {
+        // 写时复制得到的副本不归属任何池
+        Self {
+            data: self.data.clone(),
+            pool: None,
+        }
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if let Some(pool) = self.pool.take().and_then(|p| p.upgrade()) {
+            pool.recycle(std::mem::take(&mut self.data));
+        }
+    }
+}
+
+/// 帧平面数据缓冲
+///
+/// 可像 `Vec<u8>` 一样读取; 可变访问时若缓冲被多个帧共享, 先复制一份私有副本.
+#[derive(Clone)]
+pub struct FrameBuf {
+    inner: Arc<PooledBuffer>,
+}
+
+impl FrameBuf {
+    /// 由 `Vec<u8>` 创建不归属任何池的缓冲
+    pub fn from_vec(data: Vec<u8>) -> Self {
+        Self {
+            inner: Arc::new(PooledBuffer { data, pool: None }),
+        }
+    }
+
+    /// 转换为 `Vec<u8>`, 独占时不复制
+    pub fn into_vec(mut self) -> Vec<u8> {
+        match Arc::get_mut(&mut self.inner) {
+            Some(buf) => {
+                buf.pool = None;
+                std::mem::take(&mut buf.data)
+            }
+            None => self.inner.data.clone(),
+        }
+    }
+
+    /// 是否与其他帧共享同一块内存
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.inner) > 1
+    }
+
+    /// 是否与另一个缓冲指向同一块内存
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Default for FrameBuf {
+    fn default() -> Self {
+        Self::from_vec(Vec::new())
+    }
+}
+
+impl Deref for FrameBuf {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        &self.inner.data
+    }
+}
+
+impl DerefMut for FrameBuf {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        &mut Arc::make_mut(&mut self.inner).data
+    }
+}
+
+impl AsRef<[u8]> for FrameBuf {
+    fn as_ref(&self) -> &[u8] {
+        &self.inner.data
+    }
+}
+
+impl From<Vec<u8>> for FrameBuf {
+    fn from(data: Vec<u8>) -> Self {
+        Self::from_vec(data)
+    }
+}
+
+impl From<&[u8]> for FrameBuf {
+    fn from(data: &[u8]) -> Self {
+        Self::from_vec(data.to_vec())
+    }
+}
+
+impl FromIterator<u8> for FrameBuf {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        Self::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a FrameBuf {
+    type Item = &'a u8;
+    type IntoIter = std::slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.data.iter()
+    }
+}
+
+impl From<FrameBuf> for Vec<u8> {
+    fn from(buf: FrameBuf) -> Self {
+        buf.into_vec()
+    }
+}
+
+impl fmt::Debug for FrameBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner.data, f)
+    }
+}
+
+impl PartialEq for FrameBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.data == other.inner.data
+    }
+}
+
+impl Eq for FrameBuf {}
+
+impl PartialEq<Vec<u8>> for FrameBuf {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        &self.inner.data == other
+    }
+}
+
+impl PartialEq<[u8]> for FrameBuf {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.inner.data == other
+    }
+}
+
+impl PartialEq<&[u8]> for FrameBuf {
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.inner.data == *other
+    }
+}
+
+impl PartialEq<FrameBuf> for Vec<u8> {
+    fn eq(&self, other: &FrameBuf) -> bool {
+        *self == other.inner.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone_shares_memory() {
+        let a = FrameBuf::from_vec(vec![1, 2, 3]);
+        let b = a.clone();
+        assert!(a.ptr_eq(&b));
+        assert!(a.is_shared());
+        assert_eq!(a.as_ptr(), b.as_ptr());
+    }
+
+    #[test]
+    fn test_write_copies_when_shared() {
+        let a = FrameBuf::from_vec(vec![1, 2, 3]);
+        let mut b = a.clone();
+        b[0] = 9;
+        assert_eq!(a, vec![1, 2, 3]);
+        assert_eq!(b, vec![9, 2, 3]);
+        assert!(!a.ptr_eq(&b));
+    }
+
+    #[test]
+    fn test_pool_recycles_on_drop() {
+        let pool = FramePool::new(4);
+        let buf = pool.acquire(1024);
+        let ptr = buf.as_ptr();
+        let shared = buf.clone();
+        drop(buf);
+        assert_eq!(pool.free_count(), 0, "仍有引用时不应归还");
+        drop(shared);
+        assert_eq!(pool.free_count(), 1);
+
+        let again = pool.copy_from_slice(&[7u8; 512]);
+        assert_eq!(again.as_ptr(), ptr, "应复用已归还的内存");
+        assert_eq!(again.len(), 512);
+        assert_eq!(pool.free_count(), 0);
+    }
+
+    #[test]
+    fn test_pool_allocation_count_bounded() {
+        let pool = FramePool::new(2);
+        for _ in 0..100 {
+            let a = pool.acquire(4096);
+            let b = pool.acquire(1024);
+            drop((a, b));
+        }
+        assert_eq!(pool.allocation_count(), 2, "稳态下每帧应全部复用空闲缓冲");
+
+        // 需要更大的缓冲时重新分配
+        drop(pool.acquire(8192));
+        assert_eq!(pool.allocation_count(), 3);
+    }
+
+    #[test]
+    fn test_pool_respects_max_free() {
+        let pool = FramePool::new(1);
+        let a = pool.acquire(16);
+        let b = pool.acquire(16);
+        drop(a);
+        drop(b);
+        assert_eq!(pool.free_count(), 1);
+    }
+
+    #[test]
+    fn test_buffer_outlives_pool() {
+        let pool = FramePool::new(2);
+        let buf = pool.acquire(8);
+        drop(pool);
+        assert_eq!(buf.len(), 8);
+        assert_eq!(buf.into_vec(), vec![0u8; 8]);
+    }
+}
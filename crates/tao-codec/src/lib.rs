@@ -22,6 +22,8 @@
 //! let encoder = reg.create_encoder(CodecId::PcmS16le).unwrap();
 //! ```
 
+pub mod audio_fifo;
+pub mod capabilities;
 pub mod codec_id;
 pub mod codec_parameters;
 pub mod decoder;
@@ -29,18 +31,22 @@ pub mod decoders;
 pub mod encoder;
 pub mod encoders;
 pub mod frame;
+pub mod frame_pool;
 pub mod packet;
 pub mod parsers;
 pub mod registry;
 
 // 重导出常用类型
+pub use audio_fifo::AudioFifo;
+pub use capabilities::CodecCapabilities;
 pub use codec_id::CodecId;
 pub use codec_parameters::{AudioCodecParams, CodecParameters, CodecParamsType, VideoCodecParams};
-pub use decoder::Decoder;
-pub use encoder::Encoder;
-pub use frame::{AudioFrame, Frame, VideoFrame};
-pub use packet::Packet;
-pub use registry::CodecRegistry;
+pub use decoder::{Decoder, DecoderOptions};
+pub use encoder::{Encoder, KeyframeScheduler};
+pub use frame::{AudioFrame, Frame, SubtitleFrame, VideoFrame};
+pub use frame_pool::{FrameBuf, FramePool};
+pub use packet::{Packet, PacketBuilder, PacketFlags, PacketSideData};
+pub use registry::{CodecDescriptor, CodecRegistry};
 
 /// 注册所有内置编解码器
 pub fn register_all(registry: &mut CodecRegistry) {
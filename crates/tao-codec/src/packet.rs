@@ -2,24 +2,37 @@
 //!
 //! 对标 FFmpeg 的 `AVPacket`, 表示从容器格式中读取的一帧压缩数据.
 
+use bitflags::bitflags;
 use bytes::Bytes;
-use tao_core::Rational;
+use tao_core::{ChannelLayout, Rational};
 
-/// Packet side-data 类型（兼容接口壳）.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[non_exhaustive]
-pub enum PacketSideDataType {
-    /// 未知类型.
-    Unknown,
+bitflags! {
+    /// 数据包标志
+    ///
+    /// 对标 FFmpeg 的 `AV_PKT_FLAG_*`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct PacketFlags: u32 {
+        /// 关键帧 (可独立解码的随机访问点)
+        const KEYFRAME = 0x0001;
+        /// 数据已损坏 (容器层校验失败或截断)
+        const CORRUPT  = 0x0002;
+        /// 解码后应丢弃输出 (如 seek 后的预滚动包)
+        const DISCARD  = 0x0004;
+    }
 }
 
-/// Packet side-data（兼容接口壳）.
+/// Packet 附带的边数据 (side data)
+///
+/// 用于在码流中途传递参数变化, 对标 FFmpeg 的 `AVPacketSideData`.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct PacketSideData {
-    /// 数据类型.
-    pub kind: PacketSideDataType,
-    /// 二进制数据.
-    pub data: Bytes,
+#[non_exhaustive]
+pub enum PacketSideData {
+    /// 新的编解码器私有数据 (如 H.264 中途更新的 avcC / SPS / PPS)
+    NewExtraData(Vec<u8>),
+    /// 调色板更新 (每项 4 字节, RGBA 或 ARGB 由编解码器约定)
+    Palette(Vec<u8>),
+    /// 声道布局变化
+    ChannelLayoutChange(ChannelLayout),
 }
 
 /// 压缩数据包
@@ -40,8 +53,10 @@ pub struct Packet {
     pub time_base: Rational,
     /// 所属流的索引
     pub stream_index: usize,
-    /// 是否为关键帧
-    pub is_keyframe: bool,
+    /// 数据包标志 (关键帧/损坏/丢弃)
+    pub flags: PacketFlags,
+    /// 边数据 (中途参数变化等)
+    pub side_data: Vec<PacketSideData>,
     /// 在容器中的字节偏移量 (-1 表示未知)
     pub pos: i64,
 }
@@ -56,7 +71,8 @@ impl Packet {
             duration: 0,
             time_base: Rational::UNDEFINED,
             stream_index: 0,
-            is_keyframe: false,
+            flags: PacketFlags::empty(),
+            side_data: Vec::new(),
             pos: -1,
         }
     }
@@ -69,6 +85,26 @@ impl Packet {
         }
     }
 
+    /// 创建属于指定流的数据包, PTS/DTS 为 0
+    pub fn new(data: impl Into<Bytes>, stream_index: usize) -> Self {
+        Self {
+            data: data.into(),
+            pts: 0,
+            dts: 0,
+            stream_index,
+            ..Self::empty()
+        }
+    }
+
+    /// 创建数据包构建器
+    ///
+    /// 未设置的字段与 [`Packet::empty`] 相同.
+    pub fn builder() -> PacketBuilder {
+        PacketBuilder {
+            packet: Self::empty(),
+        }
+    }
+
     /// 数据大小 (字节)
     pub fn size(&self) -> usize {
         self.data.len()
@@ -79,10 +115,153 @@ impl Packet {
         self.data.is_empty()
     }
 
-    /// 获取 side-data 列表.
-    ///
-    /// 当前默认空实现, 用于 ffprobe `-show_data/-show_data_hash` 接口预留.
-    pub fn side_data(&self) -> &[PacketSideData] {
-        &[]
+    /// 是否为关键帧
+    pub fn is_keyframe(&self) -> bool {
+        self.flags.contains(PacketFlags::KEYFRAME)
+    }
+
+    /// 设置或清除关键帧标志
+    pub fn set_keyframe(&mut self, keyframe: bool) {
+        self.flags.set(PacketFlags::KEYFRAME, keyframe);
+    }
+
+    /// 获取最近一次携带的新编解码器私有数据
+    pub fn new_extra_data(&self) -> Option<&[u8]> {
+        self.side_data.iter().rev().find_map(|sd| match sd {
+            PacketSideData::NewExtraData(data) => Some(data.as_slice()),
+            _ => None,
+        })
+    }
+}
+
+/// 数据包构建器
+///
+/// # 示例
+///
+/// ```
+/// use tao_codec::Packet;
+///
+/// let pkt = Packet::builder()
+///     .data(vec![0u8; 16])
+///     .pts(90)
+///     .dts(90)
+///     .stream_index(1)
+///     .key_frame(true)
+///     .build();
+/// assert!(pkt.is_keyframe());
+/// ```
+#[derive(Debug, Clone)]
+pub struct PacketBuilder {
+    packet: Packet,
+}
+
+impl PacketBuilder {
+    /// 设置压缩数据
+    pub fn data(mut self, data: impl Into<Bytes>) -> Self {
+        self.packet.data = data.into();
+        self
+    }
+
+    /// 设置显示时间戳
+    pub fn pts(mut self, pts: i64) -> Self {
+        self.packet.pts = pts;
+        self
+    }
+
+    /// 设置解码时间戳
+    pub fn dts(mut self, dts: i64) -> Self {
+        self.packet.dts = dts;
+        self
+    }
+
+    /// 设置时长 (以 time_base 为单位)
+    pub fn duration(mut self, duration: i64) -> Self {
+        self.packet.duration = duration;
+        self
+    }
+
+    /// 设置所属流索引
+    pub fn stream_index(mut self, stream_index: usize) -> Self {
+        self.packet.stream_index = stream_index;
+        self
+    }
+
+    /// 设置或清除关键帧标志
+    pub fn key_frame(mut self, key_frame: bool) -> Self {
+        self.packet.set_keyframe(key_frame);
+        self
+    }
+
+    /// 设置时间基
+    pub fn time_base(mut self, time_base: Rational) -> Self {
+        self.packet.time_base = time_base;
+        self
+    }
+
+    /// 生成数据包
+    pub fn build(self) -> Packet {
+        self.packet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packet_keyframe_flag() {
+        let mut pkt = Packet::from_data(vec![1u8, 2, 3]);
+        assert!(!pkt.is_keyframe());
+        pkt.set_keyframe(true);
+        pkt.flags |= PacketFlags::CORRUPT;
+        assert!(pkt.is_keyframe());
+        assert!(pkt.flags.contains(PacketFlags::CORRUPT));
+        pkt.set_keyframe(false);
+        assert_eq!(
+            pkt.flags,
+            PacketFlags::CORRUPT,
+            "清除关键帧不应影响其他标志"
+        );
+    }
+
+    #[test]
+    fn test_packet_new_and_builder() {
+        let pkt = Packet::new(vec![1u8, 2], 3);
+        assert_eq!((pkt.pts, pkt.dts, pkt.stream_index), (0, 0, 3));
+        assert!(!pkt.is_keyframe());
+
+        let pkt = Packet::builder()
+            .data(vec![9u8; 4])
+            .pts(100)
+            .dts(90)
+            .duration(10)
+            .stream_index(2)
+            .key_frame(true)
+            .time_base(Rational::new(1, 90000))
+            .build();
+        assert_eq!(pkt.size(), 4);
+        assert_eq!((pkt.pts, pkt.dts, pkt.duration), (100, 90, 10));
+        assert_eq!(pkt.stream_index, 2);
+        assert!(pkt.is_keyframe());
+        assert_eq!(pkt.time_base, Rational::new(1, 90000));
+
+        let empty = Packet::builder().build();
+        assert!(empty.is_empty(), "未设置数据时应为空包");
+        assert_eq!(empty.pts, tao_core::timestamp::NOPTS_VALUE);
+    }
+
+    #[test]
+    fn test_packet_new_extra_data() {
+        let mut pkt = Packet::from_data(vec![0u8]);
+        assert!(pkt.new_extra_data().is_none());
+        pkt.side_data
+            .push(PacketSideData::ChannelLayoutChange(ChannelLayout::STEREO));
+        pkt.side_data.push(PacketSideData::NewExtraData(vec![1, 2]));
+        pkt.side_data.push(PacketSideData::NewExtraData(vec![3]));
+        assert_eq!(
+            pkt.new_extra_data(),
+            Some(&[3u8][..]),
+            "应返回最近一次的私有数据"
+        );
     }
 }
@@ -141,6 +141,8 @@ pub struct NalUnit {
     pub ref_idc: u8,
     /// NAL 单元原始数据 (不含起始码, 含 NAL 头部字节)
     pub data: Vec<u8>,
+    /// 构造时去除头部与 emulation prevention 字节后的 RBSP
+    rbsp: Vec<u8>,
 }
 
 impl NalUnit {
@@ -167,15 +169,16 @@ impl NalUnit {
             nal_type: NalUnitType::from_type_id(type_id),
             ref_idc,
             data: data.to_vec(),
+            rbsp: remove_emulation_prevention(&data[1..]),
         })
     }
 
     /// 获取 RBSP (Raw Byte Sequence Payload) 数据
     ///
-    /// 移除 NAL 头部字节和 emulation prevention 字节 (0x03).
-    /// RBSP 是参数集解析所需的纯净数据.
-    pub fn rbsp(&self) -> Vec<u8> {
-        remove_emulation_prevention(&self.data[1..])
+    /// 已在构造时移除 NAL 头部字节和 emulation prevention 字节 (`00 00 03` → `00 00`),
+    /// 避免 CABAC/CAVLC 将防竞争字节当作码流比特读取.
+    pub fn rbsp(&self) -> &[u8] {
+        &self.rbsp
     }
 }
 
@@ -968,4 +971,26 @@ mod tests {
         // 移除头部 (0x67) 和 emulation prevention
         assert_eq!(rbsp, vec![0x42, 0x00, 0x00, 0x01, 0xAA]);
     }
+
+    #[test]
+    fn test_annex_b_split_strips_emulation_prevention_in_rbsp() {
+        // slice 负载中含 00 00 03 01, 不应被视为起始码, RBSP 中应还原为 00 00 01
+        let data = [
+            0x00, 0x00, 0x00, 0x01, 0x65, 0x88, 0x00, 0x00, 0x03, 0x01, 0x84, 0x00, 0x00, 0x01,
+            0x41, 0x9A,
+        ];
+        let nalus = split_annex_b(&data);
+        assert_eq!(nalus.len(), 2, "防竞争序列不应被拆分为新的 NAL");
+        assert_eq!(
+            nalus[0].data,
+            vec![0x65, 0x88, 0x00, 0x00, 0x03, 0x01, 0x84],
+            "原始数据应保留防竞争字节"
+        );
+        assert_eq!(
+            nalus[0].rbsp(),
+            [0x88, 0x00, 0x00, 0x01, 0x84],
+            "RBSP 中 00 00 03 应替换为 00 00"
+        );
+        assert_eq!(nalus[1].rbsp(), [0x9A]);
+    }
 }
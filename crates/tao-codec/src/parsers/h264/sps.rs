@@ -5,6 +5,7 @@
 //! - 图像宽度和高度 (以宏块为单位, 需要 cropping 调整)
 //! - 色度格式 (chroma_format_idc)
 //! - 帧率信息 (通过 VUI timing_info)
+//! - 色彩空间与色彩范围 (通过 VUI video_signal_type)
 //! - 参考帧数量等
 //!
 //! # Exp-Golomb 编码
@@ -14,6 +15,7 @@
 //! - `se(v)`: 有符号 Exp-Golomb
 
 use tao_core::bitreader::BitReader;
+use tao_core::color::{ColorRange, ColorSpace};
 use tao_core::{Rational, TaoError, TaoResult};
 
 /// SPS 解析结果
@@ -43,6 +45,8 @@ pub struct Sps {
     pub height: u32,
     /// 是否为帧编码 (非场编码)
     pub frame_mbs_only: bool,
+    /// mb_adaptive_frame_field_flag (MBAFF, 仅 frame_mbs_only=false 时有效)
+    pub mb_adaptive_frame_field: bool,
     /// `direct_8x8_inference_flag`.
     pub direct_8x8_inference_flag: bool,
     /// 是否存在 VUI 参数
@@ -53,6 +57,10 @@ pub struct Sps {
     pub max_num_reorder_frames: Option<u32>,
     /// VUI `bitstream_restriction_flag` 中的 `max_dec_frame_buffering`.
     pub max_dec_frame_buffering: Option<u32>,
+    /// VUI `matrix_coefficients` 对应的色彩空间, 未给出时为未指定
+    pub color_space: ColorSpace,
+    /// VUI `video_full_range_flag` 对应的色彩范围, 未给出时为未指定
+    pub color_range: ColorRange,
     /// SAR (Sample Aspect Ratio, 像素宽高比)
     pub sar: Rational,
     /// pic_width_in_mbs_minus1
@@ -270,9 +278,7 @@ pub fn parse_sps(rbsp: &[u8]) -> TaoResult<Sps> {
 
     // frame_mbs_only_flag
     let frame_mbs_only = br.read_bit()? == 1;
-    if !frame_mbs_only {
-        br.skip_bits(1)?; // mb_adaptive_frame_field_flag
-    }
+    let mb_adaptive_frame_field = !frame_mbs_only && br.read_bit()? == 1;
 
     // direct_8x8_inference_flag
     let direct_8x8_inference_flag = br.read_bit()? == 1;
@@ -331,21 +337,12 @@ pub fn parse_sps(rbsp: &[u8]) -> TaoResult<Sps> {
     }
 
     // VUI 参数
-    let mut vui_present = false;
-    let mut fps = None;
-    let mut max_num_reorder_frames = None;
-    let mut max_dec_frame_buffering = None;
-    let mut sar = Rational::new(1, 1);
-
-    let vui_flag = br.read_bit()?;
-    if vui_flag == 1 {
-        vui_present = true;
-        let (parsed_sar, parsed_fps, parsed_reorder, parsed_dec_buf) = parse_vui(&mut br)?;
-        sar = parsed_sar;
-        fps = parsed_fps;
-        max_num_reorder_frames = parsed_reorder;
-        max_dec_frame_buffering = parsed_dec_buf;
-    }
+    let vui_present = br.read_bit()? == 1;
+    let vui = if vui_present {
+        parse_vui(&mut br)?
+    } else {
+        Vui::default()
+    };
 
     Ok(Sps {
         profile_idc,
@@ -360,12 +357,15 @@ pub fn parse_sps(rbsp: &[u8]) -> TaoResult<Sps> {
         width,
         height,
         frame_mbs_only,
+        mb_adaptive_frame_field,
         direct_8x8_inference_flag,
         vui_present,
-        fps,
-        max_num_reorder_frames,
-        max_dec_frame_buffering,
-        sar,
+        fps: vui.fps,
+        max_num_reorder_frames: vui.max_num_reorder_frames,
+        max_dec_frame_buffering: vui.max_dec_frame_buffering,
+        color_space: vui.color_space,
+        color_range: vui.color_range,
+        sar: vui.sar,
         pic_width_in_mbs,
         pic_height_in_map_units,
         crop_left,
@@ -599,16 +599,32 @@ fn parse_scaling_list_8x8(br: &mut BitReader) -> TaoResult<([u8; 64], bool)> {
     Ok((raster, use_default))
 }
 
+/// VUI 中解析出的参数
+struct Vui {
+    sar: Rational,
+    fps: Option<Rational>,
+    max_num_reorder_frames: Option<u32>,
+    max_dec_frame_buffering: Option<u32>,
+    color_space: ColorSpace,
+    color_range: ColorRange,
+}
+
+impl Default for Vui {
+    fn default() -> Self {
+        Self {
+            sar: Rational::new(1, 1),
+            fps: None,
+            max_num_reorder_frames: None,
+            max_dec_frame_buffering: None,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
+        }
+    }
+}
+
 /// 解析 VUI 参数 (部分)
-///
-/// 返回 (SAR, fps, max_num_reorder_frames, max_dec_frame_buffering)
-#[allow(clippy::type_complexity)]
-fn parse_vui(
-    br: &mut BitReader,
-) -> TaoResult<(Rational, Option<Rational>, Option<u32>, Option<u32>)> {
-    let mut sar = Rational::new(1, 1);
-    let mut max_num_reorder_frames = None;
-    let mut max_dec_frame_buffering = None;
+fn parse_vui(br: &mut BitReader) -> TaoResult<Vui> {
+    let mut vui = Vui::default();
 
     // aspect_ratio_info_present_flag
     let ar_present = br.read_bit()?;
@@ -624,11 +640,11 @@ fn parse_vui(
                     sar_w, sar_h
                 )));
             }
-            sar = Rational::new(sar_w as i32, sar_h as i32);
+            vui.sar = Rational::new(sar_w as i32, sar_h as i32);
         } else if ar_idc < SAR_TABLE.len() {
             let (w, h) = SAR_TABLE[ar_idc];
             if w > 0 && h > 0 {
-                sar = Rational::new(w as i32, h as i32);
+                vui.sar = Rational::new(w as i32, h as i32);
             }
         } else {
             return Err(TaoError::InvalidData(format!(
@@ -646,12 +662,16 @@ fn parse_vui(
     // video_signal_type_present_flag
     if br.read_bit()? == 1 {
         br.skip_bits(3)?; // video_format
-        br.skip_bits(1)?; // video_full_range_flag
+        vui.color_range = if br.read_bit()? == 1 {
+            ColorRange::Full
+        } else {
+            ColorRange::Limited
+        };
         // colour_description_present_flag
         if br.read_bit()? == 1 {
             br.skip_bits(8)?; // colour_primaries
             br.skip_bits(8)?; // transfer_characteristics
-            br.skip_bits(8)?; // matrix_coefficients
+            vui.color_space = ColorSpace::from_iso_code(u64::from(br.read_bits(8)?));
         }
     }
 
@@ -662,7 +682,6 @@ fn parse_vui(
     }
 
     // timing_info_present_flag
-    let mut fps = None;
     if br.read_bit()? == 1 {
         let num_units = br.read_bits(32)?;
         let time_scale = br.read_bits(32)?;
@@ -680,12 +699,20 @@ fn parse_vui(
         }
         // H.264 定义: fps = time_scale / (2 * num_units_in_tick)
         // fixed_frame_rate_flag 表示每个 AU 都是固定帧率
+        // 超出 Rational 可表示范围的异常值不导出帧率
         let _ = fixed_rate;
-        fps = Some(Rational::new(time_scale as i32, (num_units * 2) as i32));
+        if let (Ok(num), Some(den)) = (
+            i32::try_from(time_scale),
+            num_units
+                .checked_mul(2)
+                .and_then(|den| i32::try_from(den).ok()),
+        ) {
+            vui.fps = Some(Rational::new(num, den));
+        }
     }
 
     if br.bits_left() == 0 {
-        return Ok((sar, fps, max_num_reorder_frames, max_dec_frame_buffering));
+        return Ok(vui);
     }
 
     // nal_hrd_parameters_present_flag
@@ -695,7 +722,7 @@ fn parse_vui(
     }
 
     if br.bits_left() == 0 {
-        return Ok((sar, fps, max_num_reorder_frames, max_dec_frame_buffering));
+        return Ok(vui);
     }
     // vcl_hrd_parameters_present_flag
     let vcl_hrd_present = br.read_bit()?;
@@ -709,13 +736,13 @@ fn parse_vui(
     }
 
     if br.bits_left() == 0 {
-        return Ok((sar, fps, max_num_reorder_frames, max_dec_frame_buffering));
+        return Ok(vui);
     }
     // pic_struct_present_flag
     br.skip_bits(1)?;
 
     if br.bits_left() == 0 {
-        return Ok((sar, fps, max_num_reorder_frames, max_dec_frame_buffering));
+        return Ok(vui);
     }
     // bitstream_restriction_flag
     let bitstream_restriction_flag = br.read_bit()?;
@@ -725,11 +752,11 @@ fn parse_vui(
         let _max_bits_per_mb_denom = read_ue(br)?;
         let _log2_max_mv_length_horizontal = read_ue(br)?;
         let _log2_max_mv_length_vertical = read_ue(br)?;
-        max_num_reorder_frames = Some(read_ue(br)?);
-        max_dec_frame_buffering = Some(read_ue(br)?);
+        vui.max_num_reorder_frames = Some(read_ue(br)?);
+        vui.max_dec_frame_buffering = Some(read_ue(br)?);
     }
 
-    Ok((sar, fps, max_num_reorder_frames, max_dec_frame_buffering))
+    Ok(vui)
 }
 
 fn skip_hrd_parameters(br: &mut BitReader) -> TaoResult<()> {
@@ -955,6 +982,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sps_parse_vui_video_signal_type() {
+        let rbsp = build_test_sps_with_video_signal(true, Some(1));
+        let sps = parse_sps(&rbsp).expect("带 video_signal_type 的 SPS 解析失败");
+        assert_eq!(
+            sps.color_space,
+            ColorSpace::Bt709,
+            "matrix_coefficients=1 应为 BT.709"
+        );
+        assert_eq!(
+            sps.color_range,
+            ColorRange::Full,
+            "video_full_range_flag=1 应为完整范围"
+        );
+
+        let rbsp = build_test_sps_with_video_signal(false, Some(9));
+        let sps = parse_sps(&rbsp).expect("BT.2020 SPS 解析失败");
+        assert_eq!(sps.color_space, ColorSpace::Bt2020Ncl);
+        assert_eq!(sps.color_range, ColorRange::Limited);
+
+        let rbsp = build_test_sps_with_video_signal(false, None);
+        let sps = parse_sps(&rbsp).expect("无 colour_description 的 SPS 解析失败");
+        assert_eq!(
+            sps.color_space,
+            ColorSpace::Unspecified,
+            "缺少 colour_description 时色彩空间应为未指定"
+        );
+        assert_eq!(sps.color_range, ColorRange::Limited);
+
+        let sps = parse_sps(&build_test_sps_with_vui(66, 30, 1920, 1080, 1001, 60000)).unwrap();
+        assert_eq!(sps.color_space, ColorSpace::Unspecified);
+        assert_eq!(sps.color_range, ColorRange::Unspecified);
+    }
+
     #[test]
     fn test_sps_reject_invalid_vui_aspect_ratio_idc() {
         let rbsp = build_test_sps_with_custom_vui(17, None, Some((1001, 60000)));
@@ -1590,6 +1651,61 @@ mod tests {
         bits_to_bytes(&bits)
     }
 
+    fn build_test_sps_with_video_signal(full_range: bool, matrix: Option<u8>) -> Vec<u8> {
+        let mut bits = Vec::new();
+
+        // profile_idc=66, constraints=0, level=30
+        for i in (0..8).rev() {
+            bits.push(((66u8 >> i) & 1) != 0);
+        }
+        bits.extend(std::iter::repeat_n(false, 8));
+        for i in (0..8).rev() {
+            bits.push(((30u8 >> i) & 1) != 0);
+        }
+
+        // 最小 SPS 主体
+        write_ue(&mut bits, 0); // sps_id
+        write_ue(&mut bits, 0); // log2_max_frame_num_minus4
+        write_ue(&mut bits, 0); // pic_order_cnt_type
+        write_ue(&mut bits, 0); // log2_max_pic_order_cnt_lsb_minus4
+        write_ue(&mut bits, 4); // max_num_ref_frames
+        bits.push(false); // gaps
+        write_ue(&mut bits, 19); // width=320
+        write_ue(&mut bits, 14); // height=240
+        bits.push(true); // frame_mbs_only
+        bits.push(false); // direct_8x8
+        bits.push(false); // frame_cropping_flag
+
+        // vui_parameters_present_flag = 1
+        bits.push(true);
+        // aspect_ratio_info_present_flag = 0
+        bits.push(false);
+        // overscan_info_present_flag = 0
+        bits.push(false);
+
+        // video_signal_type_present_flag = 1
+        bits.push(true);
+        bits.extend([true, false, true]); // video_format=5 (未指定)
+        bits.push(full_range); // video_full_range_flag
+        if let Some(matrix) = matrix {
+            bits.push(true); // colour_description_present_flag
+            for value in [1u8, 1, matrix] {
+                for i in (0..8).rev() {
+                    bits.push(((value >> i) & 1) != 0);
+                }
+            }
+        } else {
+            bits.push(false);
+        }
+
+        // chroma_loc_info_present_flag = 0
+        bits.push(false);
+        // timing_info_present_flag = 0
+        bits.push(false);
+
+        bits_to_bytes(&bits)
+    }
+
     fn build_test_sps_with_reorder_restriction(
         max_num_reorder_frames: u32,
         max_dec_frame_buffering: u32,
@@ -6,3 +6,5 @@
 pub mod h264;
 pub mod h265;
 pub mod mpeg4;
+pub mod opus;
+pub mod vorbis;
@@ -0,0 +1,107 @@
+//! Opus 码流解析器.
+//!
+//! 按 RFC 6716 第 3.1 节解析 TOC 字节, 计算数据包时长 (48 kHz 采样数),
+//! 以及按 RFC 7845 解析 OpusHead/OpusTags 头包, 供封装器计算 Ogg granule position.
+
+use tao_core::{TaoError, TaoResult};
+
+/// 单个数据包的最大时长 (120 ms @ 48 kHz)
+const MAX_PACKET_SAMPLES: u32 = 5760;
+
+/// 判断是否为 OpusHead 头包
+pub fn is_opus_head(packet: &[u8]) -> bool {
+    packet.starts_with(b"OpusHead")
+}
+
+/// 判断是否为 OpusTags 头包
+pub fn is_opus_tags(packet: &[u8]) -> bool {
+    packet.starts_with(b"OpusTags")
+}
+
+/// 从 OpusHead 中读取 pre-skip (48 kHz 采样数)
+pub fn parse_pre_skip(head: &[u8]) -> TaoResult<u16> {
+    if head.len() < 19 || !is_opus_head(head) {
+        return Err(TaoError::InvalidData("OpusHead 头包无效".into()));
+    }
+    Ok(u16::from_le_bytes([head[10], head[11]]))
+}
+
+/// 构造不含任何注释的最小 OpusTags 头包
+pub fn build_opus_tags(vendor: &str) -> Vec<u8> {
+    let mut tags = Vec::with_capacity(16 + vendor.len());
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor.as_bytes());
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    tags
+}
+
+/// 计算数据包解码后的采样数 (48 kHz)
+pub fn packet_samples(packet: &[u8]) -> TaoResult<u32> {
+    let Some(&toc) = packet.first() else {
+        return Err(TaoError::InvalidData("Opus 数据包为空".into()));
+    };
+    let config = u32::from(toc >> 3);
+    let frame_size = match config {
+        // SILK: 10/20/40/60 ms
+        0..=11 => [480, 960, 1920, 2880][(config % 4) as usize],
+        // Hybrid: 10/20 ms
+        12..=15 => [480, 960][(config % 2) as usize],
+        // CELT: 2.5/5/10/20 ms
+        _ => [120, 240, 480, 960][(config % 4) as usize],
+    };
+    let frame_count = match toc & 0x03 {
+        0 => 1,
+        1 | 2 => 2,
+        _ => {
+            let count = packet
+                .get(1)
+                .ok_or_else(|| TaoError::InvalidData("Opus code 3 数据包缺少帧数字节".into()))?
+                & 0x3F;
+            if count == 0 {
+                return Err(TaoError::InvalidData("Opus code 3 数据包帧数为 0".into()));
+            }
+            u32::from(count)
+        }
+    };
+    let samples = frame_size * frame_count;
+    if samples > MAX_PACKET_SAMPLES {
+        return Err(TaoError::InvalidData(format!(
+            "Opus 数据包时长超过 120 ms: {samples} 采样"
+        )));
+    }
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opus_packet_samples_from_toc() {
+        // config 1 (SILK NB 20 ms), code 0
+        assert_eq!(packet_samples(&[1 << 3]).unwrap(), 960);
+        // config 31 (CELT FB 20 ms), code 1: 2 帧
+        assert_eq!(packet_samples(&[(31 << 3) | 1]).unwrap(), 1920);
+        // config 16 (CELT 2.5 ms), code 3: 4 帧
+        assert_eq!(packet_samples(&[(16 << 3) | 3, 4]).unwrap(), 480);
+        // config 3 (SILK 60 ms), code 3: 3 帧 = 180 ms, 超过上限
+        assert!(packet_samples(&[(3 << 3) | 3, 3]).is_err());
+        assert!(packet_samples(&[]).is_err());
+    }
+
+    #[test]
+    fn test_opus_head_pre_skip_and_tags() {
+        let mut head = b"OpusHead".to_vec();
+        head.extend_from_slice(&[1, 2]);
+        head.extend_from_slice(&312u16.to_le_bytes());
+        head.extend_from_slice(&48000u32.to_le_bytes());
+        head.extend_from_slice(&[0, 0, 0]);
+        assert_eq!(parse_pre_skip(&head).unwrap(), 312);
+        assert!(parse_pre_skip(b"OpusTags").is_err());
+
+        let tags = build_opus_tags("tao");
+        assert!(is_opus_tags(&tags));
+        assert_eq!(tags.len(), 8 + 4 + 3 + 4);
+    }
+}
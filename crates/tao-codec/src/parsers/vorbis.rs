@@ -0,0 +1,273 @@
+//! Vorbis 码流解析器.
+//!
+//! 不解码音频, 仅从 identification/setup 头包中提取块大小与模式表,
+//! 计算每个音频包输出的采样数, 供封装器计算 Ogg granule position.
+
+use tao_core::{TaoError, TaoResult};
+
+use crate::decoders::vorbis::bitreader::ilog;
+use crate::decoders::vorbis::headers::parse_identification_header;
+use crate::decoders::vorbis::setup::parse_setup_packet;
+
+/// Vorbis 音频包采样数解析器
+///
+/// 音频包输出 `上一块大小 / 4 + 当前块大小 / 4` 个采样, 首个音频包输出 0 个采样.
+#[derive(Debug, Clone)]
+pub struct VorbisPacketParser {
+    /// 短块与长块大小
+    blocksizes: [u16; 2],
+    /// 每个模式是否使用长块
+    mode_block_flags: Vec<bool>,
+    /// 模式号占用的位数
+    mode_bits: u8,
+    /// 上一个音频包的块大小
+    prev_blocksize: Option<u16>,
+}
+
+impl VorbisPacketParser {
+    /// 从 identification 与 setup 头包创建解析器
+    pub fn new(ident: &[u8], setup: &[u8]) -> TaoResult<Self> {
+        let (headers, _, _) = parse_identification_header(ident)?;
+        let parsed = parse_setup_packet(setup, headers.channels)?;
+        let mode_count = parsed.mode_block_flags.len() as u32;
+        Ok(Self {
+            blocksizes: [headers.blocksize0, headers.blocksize1],
+            mode_bits: ilog(mode_count.saturating_sub(1)),
+            mode_block_flags: parsed.mode_block_flags,
+            prev_blocksize: None,
+        })
+    }
+
+    /// 计算音频包输出的采样数, 并记录其块大小供下一个包使用
+    ///
+    /// 空包不输出采样, 也不改变块大小状态.
+    pub fn packet_samples(&mut self, packet: &[u8]) -> TaoResult<u32> {
+        let Some(&first) = packet.first() else {
+            return Ok(0);
+        };
+        if first & 0x01 != 0 {
+            return Err(TaoError::InvalidData("Vorbis 音频包类型位非 0".into()));
+        }
+        // 模式号紧随 1 位包类型之后, 最多 6 位, 位于首字节内
+        let mode = usize::from((first >> 1) & ((1u8 << self.mode_bits) - 1));
+        let long_block = *self
+            .mode_block_flags
+            .get(mode)
+            .ok_or_else(|| TaoError::InvalidData(format!("Vorbis 音频包模式号越界: {mode}")))?;
+        let blocksize = self.blocksizes[usize::from(long_block)];
+        let samples = self
+            .prev_blocksize
+            .map_or(0, |prev| u32::from(prev / 4 + blocksize / 4));
+        self.prev_blocksize = Some(blocksize);
+        Ok(samples)
+    }
+
+    /// 重置块大小状态 (seek 后或新的逻辑流开始时调用)
+    pub fn reset(&mut self) {
+        self.prev_blocksize = None;
+    }
+}
+
+/// 判断是否为指定类型的 Vorbis 头包 (1=identification, 3=comment, 5=setup)
+pub fn is_header_packet(packet: &[u8], header_type: u8) -> bool {
+    packet.len() >= 7 && packet[0] == header_type && &packet[1..7] == b"vorbis"
+}
+
+/// (identification, comment, setup) 三个头包
+pub type VorbisHeaderTriplet<'a> = (&'a [u8], &'a [u8], &'a [u8]);
+
+/// 拆分 Xiph lacing 打包的三个头包 (Matroska CodecPrivate 格式)
+///
+/// 首字节不是 0x02 (非 lacing 格式) 时返回 `None`.
+pub fn split_xiph_headers(extra_data: &[u8]) -> TaoResult<Option<VorbisHeaderTriplet<'_>>> {
+    if extra_data.is_empty() || extra_data[0] != 0x02 {
+        return Ok(None);
+    }
+
+    let mut offset = 1usize;
+    let mut header_len_0 = 0usize;
+    let mut header_len_1 = 0usize;
+
+    for len_ref in [&mut header_len_0, &mut header_len_1] {
+        let mut current = 0usize;
+        loop {
+            let b = *extra_data.get(offset).ok_or_else(|| {
+                TaoError::InvalidData("Vorbis Matroska 私有数据头包长度区越界".into())
+            })?;
+            offset += 1;
+            current = current.checked_add(usize::from(b)).ok_or_else(|| {
+                TaoError::InvalidData("Vorbis Matroska 私有数据头包长度溢出".into())
+            })?;
+            if b != 0xFF {
+                break;
+            }
+        }
+        *len_ref = current;
+    }
+
+    let payload = extra_data
+        .get(offset..)
+        .ok_or_else(|| TaoError::InvalidData("Vorbis Matroska 私有数据缺少头包内容区".into()))?;
+    let min_total = header_len_0
+        .checked_add(header_len_1)
+        .and_then(|v| v.checked_add(1))
+        .ok_or_else(|| TaoError::InvalidData("Vorbis Matroska 私有数据长度计算溢出".into()))?;
+    if payload.len() < min_total {
+        return Err(TaoError::InvalidData(
+            "Vorbis Matroska 私有数据头包长度非法".into(),
+        ));
+    }
+
+    let header0_end = header_len_0;
+    let header1_end = header_len_0 + header_len_1;
+    let ident = &payload[..header0_end];
+    let comment = &payload[header0_end..header1_end];
+    let setup = &payload[header1_end..];
+
+    if !is_header_packet(ident, 1) || !is_header_packet(comment, 3) || !is_header_packet(setup, 5) {
+        return Err(TaoError::InvalidData(
+            "Vorbis Matroska 私有数据头包标识非法".into(),
+        ));
+    }
+
+    Ok(Some((ident, comment, setup)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// LSB 优先位写入器, 用于构造 setup 头包
+    struct BitWriter {
+        data: Vec<u8>,
+        bit: usize,
+    }
+
+    impl BitWriter {
+        fn new(prefix: &[u8]) -> Self {
+            Self {
+                data: prefix.to_vec(),
+                bit: prefix.len() * 8,
+            }
+        }
+
+        fn put(&mut self, value: u32, bits: u32) {
+            for i in 0..bits {
+                if self.bit / 8 == self.data.len() {
+                    self.data.push(0);
+                }
+                if value >> i & 1 != 0 {
+                    self.data[self.bit / 8] |= 1 << (self.bit % 8);
+                }
+                self.bit += 1;
+            }
+        }
+    }
+
+    /// 单声道 44.1 kHz, 短块 256, 长块 2048
+    fn build_ident() -> Vec<u8> {
+        let mut h = vec![0x01];
+        h.extend_from_slice(b"vorbis");
+        h.extend_from_slice(&0u32.to_le_bytes());
+        h.push(1);
+        h.extend_from_slice(&44100u32.to_le_bytes());
+        h.extend_from_slice(&[0; 12]);
+        h.push(0xB8);
+        h.push(0x01);
+        h
+    }
+
+    /// 最小 setup: 1 个码本, 1 个 floor1, 1 个 residue, 1 个 mapping, 2 个模式 (短块/长块)
+    fn build_setup() -> Vec<u8> {
+        let mut w = BitWriter::new(b"\x05vorbis");
+        w.put(0, 8); // codebook_count - 1
+        w.put(0x564342, 24);
+        w.put(1, 16); // dimensions
+        w.put(2, 24); // entries
+        w.put(0, 1); // ordered
+        w.put(0, 1); // sparse
+        w.put(0, 5);
+        w.put(0, 5);
+        w.put(0, 4); // lookup_type
+        w.put(0, 6); // time count - 1
+        w.put(0, 16);
+        w.put(0, 6); // floor count - 1
+        w.put(1, 16); // floor type 1
+        w.put(0, 5); // partitions
+        w.put(0, 3); // class dimensions - 1
+        w.put(0, 2); // subclass
+        w.put(0, 8); // subclass book
+        w.put(0, 2); // multiplier - 1
+        w.put(0, 4); // range bits
+        w.put(0, 6); // residue count - 1
+        w.put(0, 16); // residue type
+        w.put(0, 24);
+        w.put(0, 24);
+        w.put(0, 24);
+        w.put(0, 6); // classifications - 1
+        w.put(0, 8); // classbook
+        w.put(0, 3);
+        w.put(0, 1);
+        w.put(0, 6); // mapping count - 1
+        w.put(0, 16); // mapping type
+        w.put(0, 1); // submaps flag
+        w.put(0, 1); // coupling flag
+        w.put(0, 2); // reserved
+        w.put(0, 24); // time/floor/residue
+        w.put(1, 6); // mode count - 1
+        for block_flag in [0, 1] {
+            w.put(block_flag, 1);
+            w.put(0, 16);
+            w.put(0, 16);
+            w.put(0, 8);
+        }
+        w.put(1, 1); // framing
+        w.data
+    }
+
+    #[test]
+    fn test_vorbis_packet_samples_by_blocksize() {
+        let mut parser = VorbisPacketParser::new(&build_ident(), &build_setup()).unwrap();
+        // 模式号位于首字节第 1 位: 0x00 短块, 0x02 长块
+        assert_eq!(
+            parser.packet_samples(&[0x00]).unwrap(),
+            0,
+            "首个音频包不输出采样"
+        );
+        assert_eq!(parser.packet_samples(&[0x00]).unwrap(), 128);
+        assert_eq!(parser.packet_samples(&[0x02]).unwrap(), 64 + 512);
+        assert_eq!(parser.packet_samples(&[0x02]).unwrap(), 1024);
+        assert_eq!(parser.packet_samples(&[]).unwrap(), 0, "空包不输出采样");
+
+        parser.reset();
+        assert_eq!(
+            parser.packet_samples(&[0x02]).unwrap(),
+            0,
+            "重置后首包不输出采样"
+        );
+        assert!(
+            parser.packet_samples(&[0x01]).is_err(),
+            "头包类型位应被拒绝"
+        );
+    }
+
+    #[test]
+    fn test_vorbis_split_xiph_headers() {
+        let ident = build_ident();
+        let comment = b"\x03vorbis\0\0\0\0\0\0\0\0\x01".to_vec();
+        let setup = build_setup();
+        let mut extra = vec![0x02, ident.len() as u8, comment.len() as u8];
+        extra.extend_from_slice(&ident);
+        extra.extend_from_slice(&comment);
+        extra.extend_from_slice(&setup);
+
+        let (a, b, c) = split_xiph_headers(&extra)
+            .unwrap()
+            .expect("应识别 Xiph lacing");
+        assert_eq!((a, b, c), (&ident[..], &comment[..], &setup[..]));
+        assert!(
+            split_xiph_headers(&ident).unwrap().is_none(),
+            "裸头包不是 lacing 格式"
+        );
+    }
+}
@@ -1,10 +1,13 @@
 //! 编解码器注册表.
 //!
 //! 对标 FFmpeg 的编解码器注册机制, 支持动态查找和实例化编解码器.
+//!
+//! 每个注册条目带有名称和 [`CodecDescriptor`] 能力描述, 下游 crate 可在运行时
+//! 注册自有实现, 并按 CodecId 或名称创建实例.
 
 use std::collections::HashMap;
 
-use tao_core::TaoResult;
+use tao_core::{PixelFormat, SampleFormat, TaoError, TaoResult};
 
 use crate::codec_id::CodecId;
 use crate::decoder::Decoder;
@@ -16,9 +19,49 @@ pub type DecoderFactory = fn() -> TaoResult<Box<dyn Decoder>>;
 /// 编码器工厂函数类型
 pub type EncoderFactory = fn() -> TaoResult<Box<dyn Encoder>>;
 
+/// 编解码器描述信息
+///
+/// 记录注册名称与能力信息. 格式列表为空表示未声明 (不做限制).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodecDescriptor {
+    /// 编解码器标识
+    pub id: CodecId,
+    /// 注册名称 (如 "pcm_s16le", "aac")
+    pub name: String,
+    /// 支持的采样格式 (解码器为输出格式, 编码器为输入格式)
+    pub sample_formats: Vec<SampleFormat>,
+    /// 支持的像素格式 (解码器为输出格式, 编码器为输入格式)
+    pub pixel_formats: Vec<PixelFormat>,
+}
+
+impl CodecDescriptor {
+    /// 创建不带能力信息的描述
+    pub fn new(id: CodecId, name: impl Into<String>) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            sample_formats: Vec::new(),
+            pixel_formats: Vec::new(),
+        }
+    }
+
+    /// 设置支持的采样格式
+    pub fn with_sample_formats(mut self, formats: &[SampleFormat]) -> Self {
+        self.sample_formats = formats.to_vec();
+        self
+    }
+
+    /// 设置支持的像素格式
+    pub fn with_pixel_formats(mut self, formats: &[PixelFormat]) -> Self {
+        self.pixel_formats = formats.to_vec();
+        self
+    }
+}
+
 /// 编解码器注册表
 ///
-/// 管理所有已注册的编解码器, 支持按 CodecId 查找并创建实例.
+/// 管理所有已注册的编解码器, 支持按 CodecId 或名称查找并创建实例.
+/// 同一 CodecId 可注册多个实现, 按 CodecId 创建时使用首选 (第一个) 实现.
 pub struct CodecRegistry {
     /// 解码器工厂映射
     decoders: HashMap<CodecId, Vec<DecoderEntry>>,
@@ -28,16 +71,16 @@ pub struct CodecRegistry {
 
 /// 解码器注册条目
 struct DecoderEntry {
-    /// 解码器名称
-    name: String,
+    /// 描述信息
+    descriptor: CodecDescriptor,
     /// 工厂函数
     factory: DecoderFactory,
 }
 
 /// 编码器注册条目
 struct EncoderEntry {
-    /// 编码器名称
-    name: String,
+    /// 描述信息
+    descriptor: CodecDescriptor,
     /// 工厂函数
     factory: EncoderFactory,
 }
@@ -51,63 +94,231 @@ impl CodecRegistry {
         }
     }
 
-    /// 注册一个解码器
+    /// 注册一个解码器 (追加到该 CodecId 的候选列表末尾)
     pub fn register_decoder(
         &mut self,
         codec_id: CodecId,
         name: impl Into<String>,
         factory: DecoderFactory,
     ) {
+        self.register_decoder_descriptor(CodecDescriptor::new(codec_id, name), factory);
+    }
+
+    /// 注册一个带能力描述的解码器 (追加到候选列表末尾)
+    ///
+    /// 若同一 CodecId 下已存在同名解码器, 则在原位置替换, 候选优先级不变;
+    /// 同名解码器属于其他 CodecId 时先移除, 新条目追加到末尾.
+    pub fn register_decoder_descriptor(
+        &mut self,
+        descriptor: CodecDescriptor,
+        factory: DecoderFactory,
+    ) {
+        let entry = DecoderEntry {
+            descriptor,
+            factory,
+        };
+        if let Some(slot) = self
+            .decoders
+            .get_mut(&entry.descriptor.id)
+            .and_then(|entries| {
+                entries
+                    .iter_mut()
+                    .find(|e| e.descriptor.name == entry.descriptor.name)
+            })
+        {
+            *slot = entry;
+            return;
+        }
+        self.remove_decoder_named(&entry.descriptor.name);
         self.decoders
-            .entry(codec_id)
+            .entry(entry.descriptor.id)
             .or_default()
-            .push(DecoderEntry {
-                name: name.into(),
+            .push(entry);
+    }
+
+    /// 注册一个具名解码器, 并设为该 CodecId 的首选实现
+    ///
+    /// 用于外部 crate 插入自有实现或覆盖内置实现. 若已存在同名解码器, 先移除.
+    pub fn register_decoder_with_name(
+        &mut self,
+        codec_id: CodecId,
+        name: impl Into<String>,
+        factory: DecoderFactory,
+    ) {
+        let descriptor = CodecDescriptor::new(codec_id, name);
+        self.remove_decoder_named(&descriptor.name);
+        self.decoders.entry(codec_id).or_default().insert(
+            0,
+            DecoderEntry {
+                descriptor,
                 factory,
-            });
+            },
+        );
     }
 
-    /// 注册一个编码器
+    /// 注册一个编码器 (追加到该 CodecId 的候选列表末尾)
     pub fn register_encoder(
         &mut self,
         codec_id: CodecId,
         name: impl Into<String>,
         factory: EncoderFactory,
     ) {
+        self.register_encoder_descriptor(CodecDescriptor::new(codec_id, name), factory);
+    }
+
+    /// 注册一个带能力描述的编码器 (追加到候选列表末尾)
+    ///
+    /// 若同一 CodecId 下已存在同名编码器, 则在原位置替换, 候选优先级不变;
+    /// 同名编码器属于其他 CodecId 时先移除, 新条目追加到末尾.
+    pub fn register_encoder_descriptor(
+        &mut self,
+        descriptor: CodecDescriptor,
+        factory: EncoderFactory,
+    ) {
+        let entry = EncoderEntry {
+            descriptor,
+            factory,
+        };
+        if let Some(slot) = self
+            .encoders
+            .get_mut(&entry.descriptor.id)
+            .and_then(|entries| {
+                entries
+                    .iter_mut()
+                    .find(|e| e.descriptor.name == entry.descriptor.name)
+            })
+        {
+            *slot = entry;
+            return;
+        }
+        self.remove_encoder_named(&entry.descriptor.name);
         self.encoders
-            .entry(codec_id)
+            .entry(entry.descriptor.id)
             .or_default()
-            .push(EncoderEntry {
-                name: name.into(),
+            .push(entry);
+    }
+
+    /// 注册一个具名编码器, 并设为该 CodecId 的首选实现
+    ///
+    /// 用于外部 crate 插入自有实现或覆盖内置实现. 若已存在同名编码器, 先移除.
+    pub fn register_encoder_with_name(
+        &mut self,
+        codec_id: CodecId,
+        name: impl Into<String>,
+        factory: EncoderFactory,
+    ) {
+        let descriptor = CodecDescriptor::new(codec_id, name);
+        self.remove_encoder_named(&descriptor.name);
+        self.encoders.entry(codec_id).or_default().insert(
+            0,
+            EncoderEntry {
+                descriptor,
                 factory,
-            });
+            },
+        );
     }
 
     /// 创建指定编解码器 ID 的解码器实例
     pub fn create_decoder(&self, codec_id: CodecId) -> TaoResult<Box<dyn Decoder>> {
-        let entries = self.decoders.get(&codec_id).ok_or_else(|| {
-            tao_core::TaoError::CodecNotFound(format!("未找到 {} 的解码器", codec_id))
-        })?;
+        let entry = self
+            .decoders
+            .get(&codec_id)
+            .and_then(|entries| entries.first())
+            .ok_or_else(|| TaoError::CodecNotFound(format!("未找到 {} 的解码器", codec_id)))?;
         // 使用第一个注册的解码器 (优先级最高)
-        let entry = &entries[0];
+        (entry.factory)()
+    }
+
+    /// 按注册名称创建解码器实例
+    pub fn create_decoder_by_name(&self, name: &str) -> TaoResult<Box<dyn Decoder>> {
+        let entry = self
+            .decoders
+            .values()
+            .flatten()
+            .find(|e| e.descriptor.name == name)
+            .ok_or_else(|| TaoError::CodecNotFound(format!("未找到名为 {} 的解码器", name)))?;
         (entry.factory)()
     }
 
     /// 创建指定编解码器 ID 的编码器实例
     pub fn create_encoder(&self, codec_id: CodecId) -> TaoResult<Box<dyn Encoder>> {
-        let entries = self.encoders.get(&codec_id).ok_or_else(|| {
-            tao_core::TaoError::CodecNotFound(format!("未找到 {} 的编码器", codec_id))
-        })?;
-        let entry = &entries[0];
+        let entry = self
+            .encoders
+            .get(&codec_id)
+            .and_then(|entries| entries.first())
+            .ok_or_else(|| TaoError::CodecNotFound(format!("未找到 {} 的编码器", codec_id)))?;
+        (entry.factory)()
+    }
+
+    /// 按注册名称创建编码器实例
+    pub fn create_encoder_by_name(&self, name: &str) -> TaoResult<Box<dyn Encoder>> {
+        let entry = self
+            .encoders
+            .values()
+            .flatten()
+            .find(|e| e.descriptor.name == name)
+            .ok_or_else(|| TaoError::CodecNotFound(format!("未找到名为 {} 的编码器", name)))?;
         (entry.factory)()
     }
 
+    /// 查找指定 CodecId 首选解码器的描述信息
+    pub fn find_decoder(&self, codec_id: CodecId) -> Option<&CodecDescriptor> {
+        self.decoders
+            .get(&codec_id)
+            .and_then(|entries| entries.first())
+            .map(|e| &e.descriptor)
+    }
+
+    /// 按注册名称查找解码器描述信息
+    pub fn find_decoder_by_name(&self, name: &str) -> Option<&CodecDescriptor> {
+        self.decoders
+            .values()
+            .flatten()
+            .map(|e| &e.descriptor)
+            .find(|d| d.name == name)
+    }
+
+    /// 查找指定 CodecId 首选编码器的描述信息
+    pub fn find_encoder(&self, codec_id: CodecId) -> Option<&CodecDescriptor> {
+        self.encoders
+            .get(&codec_id)
+            .and_then(|entries| entries.first())
+            .map(|e| &e.descriptor)
+    }
+
+    /// 按注册名称查找编码器描述信息
+    pub fn find_encoder_by_name(&self, name: &str) -> Option<&CodecDescriptor> {
+        self.encoders
+            .values()
+            .flatten()
+            .map(|e| &e.descriptor)
+            .find(|d| d.name == name)
+    }
+
+    /// 按名称查找已注册的编解码器 ID (不区分大小写)
+    ///
+    /// 先匹配编码器/解码器的注册名称, 再匹配已注册编解码器的 [`CodecId::name`].
+    pub fn find_codec_id_by_name(&self, name: &str) -> Option<CodecId> {
+        let lower = name.to_lowercase();
+        if let Some(desc) = self
+            .find_encoder_by_name(&lower)
+            .or_else(|| self.find_decoder_by_name(&lower))
+        {
+            return Some(desc.id);
+        }
+        self.encoders
+            .keys()
+            .chain(self.decoders.keys())
+            .copied()
+            .find(|id| id.name() == lower)
+    }
+
     /// 获取所有已注册的解码器名称
     pub fn list_decoders(&self) -> Vec<(CodecId, &str)> {
         let mut result = Vec::new();
         for (id, entries) in &self.decoders {
             for entry in entries {
-                result.push((*id, entry.name.as_str()));
+                result.push((*id, entry.descriptor.name.as_str()));
             }
         }
         result
@@ -118,11 +329,27 @@ impl CodecRegistry {
         let mut result = Vec::new();
         for (id, entries) in &self.encoders {
             for entry in entries {
-                result.push((*id, entry.name.as_str()));
+                result.push((*id, entry.descriptor.name.as_str()));
             }
         }
         result
     }
+
+    /// 移除同名解码器
+    fn remove_decoder_named(&mut self, name: &str) {
+        for entries in self.decoders.values_mut() {
+            entries.retain(|e| e.descriptor.name != name);
+        }
+        self.decoders.retain(|_, entries| !entries.is_empty());
+    }
+
+    /// 移除同名编码器
+    fn remove_encoder_named(&mut self, name: &str) {
+        for entries in self.encoders.values_mut() {
+            entries.retain(|e| e.descriptor.name != name);
+        }
+        self.encoders.retain(|_, entries| !entries.is_empty());
+    }
 }
 
 impl Default for CodecRegistry {
@@ -135,6 +362,23 @@ impl Default for CodecRegistry {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_find_codec_id_by_name() {
+        let mut registry = CodecRegistry::new();
+        crate::register_all(&mut registry);
+
+        assert_eq!(registry.find_codec_id_by_name("aac"), Some(CodecId::Aac));
+        assert_eq!(registry.find_codec_id_by_name("HEVC"), Some(CodecId::H265));
+        assert_eq!(
+            registry.find_codec_id_by_name("pcm_s16le"),
+            Some(CodecId::PcmS16le)
+        );
+        assert_eq!(registry.find_codec_id_by_name("nonexistent"), None);
+        for (id, name) in registry.list_decoders() {
+            assert_eq!(registry.find_codec_id_by_name(name), Some(id), "{name}");
+        }
+    }
+
     #[test]
     fn test_register_all_codecs() {
         let mut registry = CodecRegistry::new();
@@ -143,8 +387,8 @@ mod tests {
         let decoders = registry.list_decoders();
         let encoders = registry.list_encoders();
 
-        // 15 个解码器: rawvideo + 6 PCM + FLAC + AAC + MP3 + H264 + H265 + Theora + Vorbis + Mpeg4
-        assert_eq!(decoders.len(), 15);
+        // 21 个解码器: rawvideo + 6 PCM + 2 ADPCM + FLAC + AAC + AC-3 + MP3 + H264 + H265 + Theora + Vorbis + Mpeg4 + MJPEG + SRT + WebVTT
+        assert_eq!(decoders.len(), 21);
         // 9 个编码器: rawvideo + 6 PCM + FLAC + AAC
         assert_eq!(encoders.len(), 9);
     }
@@ -162,6 +406,8 @@ mod tests {
             CodecId::PcmS24le,
             CodecId::PcmS32le,
             CodecId::PcmF32le,
+            CodecId::Ac3,
+            CodecId::Mjpeg,
         ];
 
         for id in codec_ids {
@@ -199,4 +445,133 @@ mod tests {
         assert!(registry.create_decoder(CodecId::H264).is_err());
         assert!(registry.create_encoder(CodecId::Aac).is_err());
     }
+
+    #[test]
+    fn test_register_all_names_are_unique() {
+        let mut registry = CodecRegistry::new();
+        crate::register_all(&mut registry);
+
+        let mut names: Vec<&str> = registry.list_decoders().iter().map(|(_, n)| *n).collect();
+        let total = names.len();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), total, "解码器名称不应重复");
+
+        let desc = registry
+            .find_decoder(CodecId::PcmS16le)
+            .expect("应找到 pcm_s16le");
+        assert_eq!(desc.name, "pcm_s16le");
+        assert_eq!(desc.sample_formats, vec![SampleFormat::S16]);
+        let desc = registry
+            .find_encoder_by_name("aac")
+            .expect("应找到 aac 编码器");
+        assert_eq!(desc.id, CodecId::Aac);
+    }
+
+    #[test]
+    fn test_register_with_name_takes_priority_and_replaces() {
+        let mut registry = CodecRegistry::new();
+        crate::register_all(&mut registry);
+
+        // 用 pcm_s16be 实现覆盖 PcmS16le 的首选解码器
+        registry.register_decoder_with_name(
+            CodecId::PcmS16le,
+            "pcm_s16le_alt",
+            crate::decoders::pcm::PcmDecoder::new_s16be,
+        );
+        assert_eq!(
+            registry.find_decoder(CodecId::PcmS16le).unwrap().name,
+            "pcm_s16le_alt"
+        );
+        let dec = registry.create_decoder_by_name("pcm_s16le").unwrap();
+        assert_eq!(dec.codec_id(), CodecId::PcmS16le, "原实现仍可按名称创建");
+
+        // 同名重新注册应替换而非重复
+        registry.register_decoder_with_name(
+            CodecId::PcmS16le,
+            "pcm_s16le_alt",
+            crate::decoders::pcm::PcmDecoder::new_s16le,
+        );
+        let count = registry
+            .list_decoders()
+            .iter()
+            .filter(|(_, n)| *n == "pcm_s16le_alt")
+            .count();
+        assert_eq!(count, 1, "同名注册应替换旧条目");
+        assert!(registry.create_decoder_by_name("missing").is_err());
+        assert!(registry.create_encoder_by_name("missing").is_err());
+    }
+
+    #[test]
+    fn test_register_descriptor_replaces_in_place() {
+        use crate::decoders::pcm::PcmDecoder;
+        use crate::encoders::pcm::PcmEncoder;
+
+        let mut registry = CodecRegistry::new();
+        for name in ["first", "second", "third"] {
+            registry.register_decoder_descriptor(
+                CodecDescriptor::new(CodecId::PcmS16le, name),
+                PcmDecoder::new_s16le,
+            );
+            registry.register_encoder_descriptor(
+                CodecDescriptor::new(CodecId::PcmS16le, name),
+                PcmEncoder::new_s16le,
+            );
+        }
+
+        // 重新注册首选实现, 应替换工厂但保持候选顺序
+        registry.register_decoder_descriptor(
+            CodecDescriptor::new(CodecId::PcmS16le, "first"),
+            PcmDecoder::new_s16be,
+        );
+        registry.register_encoder_descriptor(
+            CodecDescriptor::new(CodecId::PcmS16le, "first"),
+            PcmEncoder::new_s16be,
+        );
+        let names = |list: Vec<(CodecId, &str)>| -> Vec<String> {
+            list.into_iter().map(|(_, n)| n.to_string()).collect()
+        };
+        assert_eq!(
+            names(registry.list_decoders()),
+            ["first", "second", "third"]
+        );
+        assert_eq!(
+            names(registry.list_encoders()),
+            ["first", "second", "third"]
+        );
+        assert_eq!(
+            registry
+                .create_decoder(CodecId::PcmS16le)
+                .unwrap()
+                .codec_id(),
+            CodecId::PcmS16be,
+            "首选解码器应为替换后的实现"
+        );
+        assert_eq!(
+            registry
+                .create_encoder(CodecId::PcmS16le)
+                .unwrap()
+                .codec_id(),
+            CodecId::PcmS16be,
+            "首选编码器应为替换后的实现"
+        );
+
+        // 同名条目改挂到其他 CodecId 时从原列表移除
+        registry.register_decoder_descriptor(
+            CodecDescriptor::new(CodecId::PcmS16be, "second"),
+            PcmDecoder::new_s16be,
+        );
+        assert_eq!(
+            registry
+                .list_decoders()
+                .iter()
+                .filter(|(_, n)| *n == "second")
+                .count(),
+            1
+        );
+        assert_eq!(
+            registry.find_decoder_by_name("second").unwrap().id,
+            CodecId::PcmS16be
+        );
+    }
 }
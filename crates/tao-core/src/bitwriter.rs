@@ -47,7 +47,20 @@ impl BitWriter {
         }
     }
 
-    /// 获取已写入的总位数
+    /// 在已有数据之后继续写入
+    ///
+    /// `data` 视为已写满的完整字节, 新数据从下一个字节边界开始追加.
+    pub fn from_vec(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            current_byte: 0,
+            bit_count: 0,
+        }
+    }
+
+    /// 获取已写入的总位数 (含 [`BitWriter::from_vec`] 传入的数据)
+    ///
+    /// `bits_written() % 8` 即当前字节内的偏移, 可用于判断是否已对齐.
     pub fn bits_written(&self) -> usize {
         self.data.len() * 8 + self.bit_count as usize
     }
@@ -339,4 +352,131 @@ mod tests {
         assert_eq!(br.read_bits_signed(5).unwrap(), 10);
         assert_eq!(br.read_bits_signed(8).unwrap(), -128);
     }
+
+    /// 按位拼接各 `(value, n)` 的低 `n` 位并以 0 补齐末字节, 用于构造期望输出
+    fn expected_bytes(bits: &[(u64, u32)]) -> Vec<u8> {
+        let mut all = Vec::new();
+        for &(value, n) in bits {
+            for i in (0..n).rev() {
+                all.push(((value >> i) & 1) as u8);
+            }
+        }
+        all.chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .chain(std::iter::repeat(&0))
+                    .take(8)
+                    .fold(0u8, |acc, &b| (acc << 1) | b)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_write_various_widths() {
+        for n in [1u32, 7, 8, 9, 16, 31, 32, 33] {
+            // 全 1 与交替图案各测一次, 且分别从对齐与非对齐位置开始
+            for value in [u64::MAX >> (64 - n), 0xAAAA_AAAA_AAAA_AAAA >> (64 - n)] {
+                for prefix in [0u32, 3] {
+                    let mut bw = BitWriter::new();
+                    bw.write_bits(0b101, prefix);
+                    bw.write_bits_u64(value, n);
+                    assert_eq!(
+                        bw.bits_written(),
+                        (prefix + n) as usize,
+                        "n={n} prefix={prefix}: bits_written 不正确"
+                    );
+                    let data = bw.finish();
+                    let expected = expected_bytes(&[(0b101, prefix), (value, n)]);
+                    assert_eq!(
+                        data, expected,
+                        "n={n} prefix={prefix} value={value:#x}: 输出字节不正确"
+                    );
+
+                    let mut br = BitReader::new(&data);
+                    br.skip_bits(prefix).unwrap();
+                    assert_eq!(
+                        br.read_bits_u64(n).unwrap(),
+                        value,
+                        "n={n} prefix={prefix}: 往返读取不一致"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_exactly_one_byte_flushes() {
+        let mut bw = BitWriter::new();
+        bw.write_bits(0xA5, 8);
+        assert_eq!(bw.data(), &[0xA5], "写满 8 位应立即输出完整字节");
+        assert_eq!(bw.bits_written(), 8);
+        assert_eq!(bw.bits_written() % 8, 0, "写满 8 位后应处于字节边界");
+    }
+
+    #[test]
+    fn test_write_zero_bits_is_noop() {
+        let mut bw = BitWriter::new();
+        bw.write_bits(0xFFFF_FFFF, 0);
+        bw.write_bits_u64(u64::MAX, 0);
+        assert_eq!(bw.bits_written(), 0, "写入 0 位不应改变位数");
+        bw.write_bits(0b1, 1);
+        bw.write_bits(0xFFFF_FFFF, 0);
+        assert_eq!(bw.bits_written(), 1);
+        assert_eq!(bw.finish(), vec![0b1000_0000]);
+    }
+
+    #[test]
+    fn test_alternating_bit_patterns() {
+        let mut bw = BitWriter::new();
+        for i in 0..37 {
+            bw.write_bit(i & 1);
+        }
+        bw.write_bits(0x5555_5555, 31);
+        assert_eq!(bw.bits_written(), 68);
+        let data = bw.finish();
+        let mut bits: Vec<(u64, u32)> = (0..37).map(|i| (i & 1, 1)).collect();
+        bits.push((0x5555_5555 & 0x7FFF_FFFF, 31));
+        assert_eq!(data, expected_bytes(&bits), "交替图案输出不正确");
+    }
+
+    #[test]
+    fn test_finish_zero_pads_partial_byte() {
+        for n in 1..8u32 {
+            let mut bw = BitWriter::new();
+            bw.write_bits(0xFF, 8);
+            bw.write_bits(u32::MAX, n);
+            assert_eq!(bw.data().len(), 1, "未满的字节不应提前输出");
+            let data = bw.finish();
+            let expected_last = !(0xFFu8 >> n);
+            assert_eq!(data, vec![0xFF, expected_last], "n={n}: 末字节应以 0 补齐");
+        }
+    }
+
+    #[test]
+    fn test_to_bytes_and_align_idempotent() {
+        let mut bw = BitWriter::new();
+        bw.write_bits(0b11, 2);
+        assert_eq!(bw.to_bytes(), vec![0b1100_0000]);
+        bw.align_to_byte();
+        assert_eq!(bw.bits_written(), 8, "已对齐时再次对齐不应追加字节");
+        bw.write_bits(0b1, 1);
+        assert_eq!(bw.finish(), vec![0b1100_0000, 0b1000_0000]);
+    }
+
+    #[test]
+    fn test_write_after_prefilled_buffer() {
+        let mut bw = BitWriter::from_vec(vec![0xDE, 0xAD]);
+        assert_eq!(bw.bits_written(), 16, "预填充数据应计入已写位数");
+        bw.write_bits(0b1, 1);
+        bw.write_bytes(&[0xBE]);
+        bw.write_bits(0xF, 4);
+        assert_eq!(bw.bits_written(), 29);
+        let data = bw.finish();
+        assert_eq!(
+            data,
+            vec![0xDE, 0xAD, 0b1101_1111, 0b0111_1000],
+            "新数据应追加在预填充数据之后并以 0 补齐"
+        );
+    }
 }
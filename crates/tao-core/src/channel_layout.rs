@@ -109,6 +109,24 @@ impl ChannelLayout {
             },
         }
     }
+
+    /// 按名称查找声道布局 (不区分大小写)
+    ///
+    /// 接受 "mono", "stereo", "5.1", "7.1" 以及 "Nc" 形式的声道数 (与 `Display` 输出一致).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        match name.as_str() {
+            "mono" => Some(Self::MONO),
+            "stereo" => Some(Self::STEREO),
+            "5.1" => Some(Self::SURROUND_5_1),
+            "7.1" => Some(Self::SURROUND_7_1),
+            other => other
+                .strip_suffix('c')
+                .and_then(|n| n.parse::<u32>().ok())
+                .filter(|&n| n > 0)
+                .map(Self::from_channels),
+        }
+    }
 }
 
 impl fmt::Display for ChannelLayout {
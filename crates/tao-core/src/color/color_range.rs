@@ -17,3 +17,13 @@ pub enum ColorRange {
     /// 完整范围 (JPEG/PC) Y 0-255
     Full,
 }
+
+impl ColorRange {
+    /// 解析实际使用的色彩范围, 未指定时按广播标准视为有限范围
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Unspecified => Self::Limited,
+            other => other,
+        }
+    }
+}
@@ -24,3 +24,95 @@ pub enum ColorSpace {
     /// sRGB / IEC 61966-2-1
     Rgb,
 }
+
+impl ColorSpace {
+    /// 从 ISO/IEC 23091-2 (H.273) MatrixCoefficients 码点转换
+    ///
+    /// MP4 `colr` (nclx) 与 Matroska `MatrixCoefficients` 均使用该编码.
+    pub fn from_iso_code(code: u64) -> Self {
+        match code {
+            0 => Self::Rgb,
+            1 => Self::Bt709,
+            5 => Self::Bt470bg,
+            6 => Self::Smpte170m,
+            7 => Self::Smpte240m,
+            9 => Self::Bt2020Ncl,
+            10 => Self::Bt2020Cl,
+            _ => Self::Unspecified,
+        }
+    }
+
+    /// 转换为 ISO/IEC 23091-2 (H.273) MatrixCoefficients 码点, 未指定为 2
+    ///
+    /// 与 FFmpeg `AVColorSpace` 的取值一致.
+    pub fn to_iso_code(self) -> u64 {
+        match self {
+            Self::Rgb => 0,
+            Self::Bt709 => 1,
+            Self::Unspecified => 2,
+            Self::Bt470bg => 5,
+            Self::Smpte170m => 6,
+            Self::Smpte240m => 7,
+            Self::Bt2020Ncl => 9,
+            Self::Bt2020Cl => 10,
+        }
+    }
+
+    /// 未指定色彩空间时按分辨率推断的默认值
+    ///
+    /// 与 FFmpeg 的惯例一致: 高度 >= 720 视为高清 (BT.709), 否则为标清 (BT.601).
+    pub fn default_for_height(height: u32) -> Self {
+        if height >= 720 {
+            Self::Bt709
+        } else {
+            Self::Smpte170m
+        }
+    }
+
+    /// 解析实际使用的色彩空间, 未指定时回退到 [`ColorSpace::default_for_height`]
+    pub fn resolve(self, height: u32) -> Self {
+        match self {
+            Self::Unspecified => Self::default_for_height(height),
+            other => other,
+        }
+    }
+
+    /// 是否为 BT.601 矩阵 (BT.470 BG 与 SMPTE 170M 系数相同)
+    pub fn is_bt601(self) -> bool {
+        matches!(self, Self::Bt470bg | Self::Smpte170m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_defaults_by_height() {
+        assert_eq!(ColorSpace::Unspecified.resolve(480), ColorSpace::Smpte170m);
+        assert_eq!(ColorSpace::Unspecified.resolve(576), ColorSpace::Smpte170m);
+        assert_eq!(ColorSpace::Unspecified.resolve(720), ColorSpace::Bt709);
+        assert_eq!(ColorSpace::Unspecified.resolve(1080), ColorSpace::Bt709);
+        assert_eq!(ColorSpace::Bt2020Ncl.resolve(480), ColorSpace::Bt2020Ncl);
+    }
+
+    #[test]
+    fn test_from_iso_code() {
+        assert_eq!(ColorSpace::from_iso_code(1), ColorSpace::Bt709);
+        assert_eq!(ColorSpace::from_iso_code(6), ColorSpace::Smpte170m);
+        assert_eq!(ColorSpace::from_iso_code(9), ColorSpace::Bt2020Ncl);
+        assert_eq!(ColorSpace::from_iso_code(2), ColorSpace::Unspecified);
+        assert!(ColorSpace::from_iso_code(5).is_bt601());
+    }
+
+    #[test]
+    fn test_iso_code_roundtrip() {
+        for code in [0, 1, 2, 5, 6, 7, 9, 10] {
+            assert_eq!(
+                ColorSpace::from_iso_code(code).to_iso_code(),
+                code,
+                "码点 {code} 应可往返转换"
+            );
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! CRC 校验和计算.
 //!
-//! 提供 CRC-8 和 CRC-16 计算, 用于 FLAC 帧头和帧尾校验.
+//! 提供 CRC-8 和 CRC-16 计算, 用于 FLAC 帧头和帧尾校验;
+//! 以及 CRC-32 (IEEE 802.3), 用于解码结果哈希校验.
 
 /// CRC-8 查找表 (多项式 0x07)
 const CRC8_TABLE: [u8; 256] = {
@@ -44,6 +45,27 @@ const CRC16_TABLE: [u16; 256] = {
     table
 };
 
+/// CRC-32 查找表 (反射多项式 0xEDB88320)
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0u32;
+    while i < 256 {
+        let mut crc = i;
+        let mut j = 0;
+        while j < 8 {
+            if crc & 1 != 0 {
+                crc = (crc >> 1) ^ 0xEDB8_8320;
+            } else {
+                crc >>= 1;
+            }
+            j += 1;
+        }
+        table[i as usize] = crc;
+        i += 1;
+    }
+    table
+};
+
 /// 计算 CRC-8
 ///
 /// FLAC 帧头使用此 CRC 校验 (多项式 0x07, 初始值 0).
@@ -66,6 +88,24 @@ pub fn crc16(data: &[u8]) -> u16 {
     crc
 }
 
+/// 计算 CRC-32
+///
+/// 与 zlib `crc32()` 一致 (IEEE 802.3, 初始值与结果异或 0xFFFFFFFF).
+pub fn crc32(data: &[u8]) -> u32 {
+    crc32_update(0, data)
+}
+
+/// 在已有 CRC-32 结果上继续累加数据
+///
+/// `crc32_update(crc32(a), b)` 等于 `crc32(a ++ b)`, 初始值为 0.
+pub fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for &byte in data {
+        crc = (crc >> 8) ^ CRC32_TABLE[((crc as u8) ^ byte) as usize];
+    }
+    !crc
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,4 +149,22 @@ mod tests {
         let crc2 = crc16(&[0x00, 0x01]);
         assert_ne!(crc1, crc2);
     }
+
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(&[]), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_crc32_update_chained() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let (a, b) = data.split_at(10);
+        assert_eq!(
+            crc32_update(crc32(a), b),
+            crc32(data),
+            "分段累加应与整体计算一致"
+        );
+        assert_eq!(crc32(data), 0x414F_A339);
+    }
 }
@@ -10,18 +10,21 @@ pub mod channel_layout;
 pub mod color;
 pub mod crc;
 pub mod error;
+pub mod md5;
 pub mod media_type;
 pub mod pixel_format;
 pub mod rational;
 pub mod sample_format;
 pub mod subtitle;
+pub mod timecode;
 pub mod timestamp;
 
 // 重导出常用类型
 pub use channel_layout::ChannelLayout;
 pub use error::{TaoError, TaoResult};
 pub use media_type::MediaType;
-pub use pixel_format::PixelFormat;
+pub use pixel_format::{PixelFormat, PixelFormatDescriptor};
 pub use rational::Rational;
 pub use sample_format::SampleFormat;
+pub use timecode::SmpteTc;
 pub use timestamp::Timestamp;
@@ -17,6 +17,25 @@ pub enum MediaType {
     Data,
     /// 附件流 (如封面图片、字体)
     Attachment,
+    /// 无法识别的流类型, 携带容器中的原始类型码 (无法表示时为 0)
+    Unknown(u8),
+}
+
+impl MediaType {
+    /// 是否为视频流
+    pub const fn is_video(&self) -> bool {
+        matches!(self, Self::Video)
+    }
+
+    /// 是否为音频流
+    pub const fn is_audio(&self) -> bool {
+        matches!(self, Self::Audio)
+    }
+
+    /// 是否为无法识别的流类型
+    pub const fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown(_))
+    }
 }
 
 impl fmt::Display for MediaType {
@@ -27,7 +46,24 @@ impl fmt::Display for MediaType {
             Self::Subtitle => "字幕",
             Self::Data => "数据",
             Self::Attachment => "附件",
+            Self::Unknown(_) => "未知",
         };
         write!(f, "{name}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_media_type() {
+        let unknown = MediaType::Unknown(3);
+        assert!(!unknown.is_video());
+        assert!(!unknown.is_audio());
+        assert!(unknown.is_unknown());
+        assert!(MediaType::Video.is_video());
+        assert!(MediaType::Audio.is_audio());
+        assert_eq!(unknown.to_string(), "未知");
+    }
+}
@@ -3,6 +3,9 @@
 //! 对标 FFmpeg 的 `AVPixelFormat`, 定义了视频帧中像素的存储格式.
 
 use std::fmt;
+use std::str::FromStr;
+
+use crate::{TaoError, TaoResult};
 
 /// 像素格式
 ///
@@ -69,7 +72,124 @@ pub enum PixelFormat {
     Rgbf32le,
 }
 
+/// 全部像素格式及其稳定数值 ID (ID 用于 FFI, 不可更改已分配的值)
+const PIXEL_FORMATS: [(PixelFormat, u32); 18] = [
+    (PixelFormat::Yuv420p, 0),
+    (PixelFormat::Rgb24, 1),
+    (PixelFormat::Bgr24, 2),
+    (PixelFormat::Yuv422p, 3),
+    (PixelFormat::Yuv444p, 4),
+    (PixelFormat::Yuv420p10le, 5),
+    (PixelFormat::Yuv420p10be, 6),
+    (PixelFormat::Yuv422p10le, 7),
+    (PixelFormat::Yuv444p10le, 8),
+    (PixelFormat::Nv12, 9),
+    (PixelFormat::Nv21, 10),
+    (PixelFormat::Rgba, 11),
+    (PixelFormat::Bgra, 12),
+    (PixelFormat::Argb, 13),
+    (PixelFormat::Gray8, 14),
+    (PixelFormat::Gray16le, 15),
+    (PixelFormat::Rgbf32le, 16),
+    (PixelFormat::None, PIXEL_FORMAT_NONE_ID),
+];
+
+/// [`PixelFormat::None`] 的数值 ID (0 已分配给 Yuv420p)
+pub const PIXEL_FORMAT_NONE_ID: u32 = u32::MAX;
+
+/// 像素格式描述
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelFormatDescriptor {
+    /// 格式名称
+    pub name: &'static str,
+    /// 平均每像素有效位数 (计入色度子采样, 不含填充位), 如 yuv420p 为 12
+    pub bits_per_pixel: u32,
+    /// 单个分量的位深
+    pub bits_per_component: u32,
+    /// 色度水平子采样 (log2)
+    pub log2_chroma_w: u32,
+    /// 色度垂直子采样 (log2)
+    pub log2_chroma_h: u32,
+    /// 平面数量
+    pub plane_count: u32,
+}
+
 impl PixelFormat {
+    /// 遍历全部像素格式 (含 None)
+    pub fn all() -> impl Iterator<Item = PixelFormat> {
+        PIXEL_FORMATS.iter().map(|&(format, _)| format)
+    }
+
+    /// 格式名称 (与 FFmpeg 一致, 如 "yuv420p", "rgb24")
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Yuv420p => "yuv420p",
+            Self::Yuv422p => "yuv422p",
+            Self::Yuv444p => "yuv444p",
+            Self::Yuv420p10le => "yuv420p10le",
+            Self::Yuv420p10be => "yuv420p10be",
+            Self::Yuv422p10le => "yuv422p10le",
+            Self::Yuv444p10le => "yuv444p10le",
+            Self::Nv12 => "nv12",
+            Self::Nv21 => "nv21",
+            Self::Rgb24 => "rgb24",
+            Self::Bgr24 => "bgr24",
+            Self::Rgba => "rgba",
+            Self::Bgra => "bgra",
+            Self::Argb => "argb",
+            Self::Gray8 => "gray8",
+            Self::Gray16le => "gray16le",
+            Self::Rgbf32le => "rgbf32le",
+        }
+    }
+
+    /// 按名称查找像素格式 (不区分大小写, 另接受 FFmpeg 的 "gray" 别名)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        if name == "gray" {
+            return Some(Self::Gray8);
+        }
+        Self::all().find(|f| f.name() == name)
+    }
+
+    /// 稳定数值 ID
+    pub fn to_u32(&self) -> u32 {
+        PIXEL_FORMATS
+            .iter()
+            .find(|(format, _)| format == self)
+            .map_or(PIXEL_FORMAT_NONE_ID, |&(_, id)| id)
+    }
+
+    /// 由数值 ID 查找像素格式, 未知 ID 返回 None
+    pub fn from_u32(id: u32) -> Option<Self> {
+        PIXEL_FORMATS
+            .iter()
+            .find(|&&(_, format_id)| format_id == id)
+            .map(|&(format, _)| format)
+    }
+
+    /// 像素格式描述 (位深, 色度子采样, 平面数)
+    pub const fn descriptor(&self) -> PixelFormatDescriptor {
+        let (log2_chroma_w, log2_chroma_h) = self.chroma_subsampling();
+        let depth = self.bits_per_component();
+        let bits_per_pixel = match self {
+            Self::None => 0,
+            Self::Gray8 | Self::Gray16le => depth,
+            Self::Rgba | Self::Bgra | Self::Argb => depth * 4,
+            // 3 分量: 亮度全分辨率, 两个色度分量按子采样折算
+            _ => depth + ((2 * depth) >> (log2_chroma_w + log2_chroma_h)),
+        };
+        PixelFormatDescriptor {
+            name: self.name(),
+            bits_per_pixel,
+            bits_per_component: depth,
+            log2_chroma_w,
+            log2_chroma_h,
+            plane_count: self.plane_count(),
+        }
+    }
+
     /// 获取每个像素占用的位数 (packed 格式)
     ///
     /// 对于平面格式, 返回单个 Y/U/V 分量的位深.
@@ -251,27 +371,15 @@ impl PixelFormat {
 
 impl fmt::Display for PixelFormat {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let name = match self {
-            Self::None => "none",
-            Self::Yuv420p => "yuv420p",
-            Self::Yuv422p => "yuv422p",
-            Self::Yuv444p => "yuv444p",
-            Self::Yuv420p10le => "yuv420p10le",
-            Self::Yuv420p10be => "yuv420p10be",
-            Self::Yuv422p10le => "yuv422p10le",
-            Self::Yuv444p10le => "yuv444p10le",
-            Self::Nv12 => "nv12",
-            Self::Nv21 => "nv21",
-            Self::Rgb24 => "rgb24",
-            Self::Bgr24 => "bgr24",
-            Self::Rgba => "rgba",
-            Self::Bgra => "bgra",
-            Self::Argb => "argb",
-            Self::Gray8 => "gray8",
-            Self::Gray16le => "gray16le",
-            Self::Rgbf32le => "rgbf32le",
-        };
-        write!(f, "{name}")
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for PixelFormat {
+    type Err = TaoError;
+
+    fn from_str(s: &str) -> TaoResult<Self> {
+        Self::from_name(s).ok_or_else(|| TaoError::InvalidArgument(format!("未知的像素格式: {s}")))
     }
 }
 
@@ -374,6 +482,57 @@ mod tests {
         assert_eq!(pf.frame_size(1920, 1080), Some(1920 * 1080 * 2));
     }
 
+    #[test]
+    fn test_pixel_format_name_and_id_roundtrip() {
+        for format in PixelFormat::all() {
+            assert_eq!(
+                format.to_string().parse::<PixelFormat>().unwrap(),
+                format,
+                "名称应可往返: {format}"
+            );
+            assert_eq!(
+                PixelFormat::from_u32(format.to_u32()),
+                Some(format),
+                "数值 ID 应可往返: {format}"
+            );
+        }
+        assert_eq!(PixelFormat::all().count(), 18, "应列出全部像素格式");
+        // 与 tao-ffi 既有约定保持一致
+        assert_eq!(PixelFormat::Yuv420p.to_u32(), 0);
+        assert_eq!(PixelFormat::Yuv444p.to_u32(), 4);
+        assert_eq!(
+            PixelFormat::from_u32(17),
+            None,
+            "未知 ID 不应回退为默认格式"
+        );
+        assert_eq!(PixelFormat::from_name("GRAY"), Some(PixelFormat::Gray8));
+        assert!(matches!(
+            "yuv411p".parse::<PixelFormat>(),
+            Err(TaoError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_pixel_format_descriptor() {
+        let d = PixelFormat::Yuv420p.descriptor();
+        assert_eq!(
+            (d.bits_per_pixel, d.log2_chroma_w, d.log2_chroma_h),
+            (12, 1, 1)
+        );
+        assert_eq!(d.plane_count, 3);
+        assert_eq!(d.name, "yuv420p");
+        assert_eq!(PixelFormat::Yuv422p.descriptor().bits_per_pixel, 16);
+        assert_eq!(PixelFormat::Yuv444p10le.descriptor().bits_per_pixel, 30);
+        assert_eq!(PixelFormat::Yuv420p10le.descriptor().bits_per_pixel, 15);
+        assert_eq!(PixelFormat::Nv12.descriptor().bits_per_pixel, 12);
+        assert_eq!(PixelFormat::Nv12.descriptor().plane_count, 2);
+        assert_eq!(PixelFormat::Rgb24.descriptor().bits_per_pixel, 24);
+        assert_eq!(PixelFormat::Bgra.descriptor().bits_per_pixel, 32);
+        assert_eq!(PixelFormat::Gray16le.descriptor().bits_per_pixel, 16);
+        assert_eq!(PixelFormat::Rgbf32le.descriptor().bits_per_pixel, 96);
+        assert_eq!(PixelFormat::None.descriptor().bits_per_pixel, 0);
+    }
+
     #[test]
     fn test_yuv444p_frame_size() {
         let pf = PixelFormat::Yuv444p;
@@ -2,7 +2,9 @@
 //!
 //! 对标 FFmpeg 的 `AVRational`.
 
+use std::cmp::Ordering;
 use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
 
 /// 有理数, 由分子和分母组成
 ///
@@ -81,18 +83,249 @@ impl Rational {
             den: self.num,
         }
     }
+
+    /// 求倒数并规范符号 (分母非负)
+    ///
+    /// 常用于帧率与时间基互转, 例如 30000/1001 fps -> 1001/30000.
+    pub fn inverse(self) -> Self {
+        if self.num < 0 {
+            Self::from_i64(-i64::from(self.den), -i64::from(self.num))
+        } else {
+            Self::from_i64(i64::from(self.den), i64::from(self.num))
+        }
+    }
+
+    /// 由 64 位分子分母构造, 约分后超出 i32 范围时取最接近的近似值
+    ///
+    /// 分母为 0 时返回 `num.signum()/0`.
+    pub fn from_i64(num: i64, den: i64) -> Self {
+        reduce_bounded(i128::from(num), i128::from(den), i128::from(i32::MAX))
+    }
+
+    /// 由浮点数求有理数近似 (连分数展开), 分母不超过 `max_den`
+    ///
+    /// 与 `n * 1000 / 1001` 相差不足 0.0005 的值 (NTSC 系列帧率, 如 29.97、23.976、59.94)
+    /// 在 `max_den >= 1001` 时精确映射为 `n*1000/1001`.
+    /// NaN 返回 [`Rational::UNDEFINED`], 无穷大返回 `±1/0`.
+    pub fn from_f64(value: f64, max_den: i32) -> Self {
+        if value.is_nan() {
+            return Self::UNDEFINED;
+        }
+        if value.is_infinite() {
+            return Self::new(if value > 0.0 { 1 } else { -1 }, 0);
+        }
+        let max_den = i64::from(max_den.max(1));
+        let max_num = i64::from(i32::MAX);
+        let sign = if value < 0.0 { -1 } else { 1 };
+        let x = value.abs();
+        if x >= max_num as f64 {
+            return Self::new(sign * i32::MAX, 1);
+        }
+
+        // NTSC 系列: 29.97 -> 30000/1001
+        let n = (x * 1.001).round();
+        if n >= 2.0 && max_den >= 1001 && x.fract() != 0.0 {
+            let ntsc = n * 1000.0 / 1001.0;
+            if (x - ntsc).abs() < 5e-4 && n * 1000.0 <= max_num as f64 {
+                return Self::from_i64(i64::from(sign) * n as i64 * 1000, 1001);
+            }
+        }
+
+        // 连分数展开, 超出范围时比较半收敛分数与上一个收敛分数
+        let (mut p0, mut q0, mut p1, mut q1) = (0i64, 1i64, 1i64, 0i64);
+        let mut v = x;
+        for _ in 0..64 {
+            let a = v.floor();
+            if a > max_num as f64 {
+                break;
+            }
+            let a = a as i64;
+            let p2 = a * p1 + p0;
+            let q2 = a * q1 + q0;
+            if p2 > max_num || q2 > max_den {
+                let mut k = if q1 > 0 { (max_den - q0) / q1 } else { a };
+                if p1 > 0 {
+                    k = k.min((max_num - p0) / p1);
+                }
+                let (sp, sq) = (k * p1 + p0, k * q1 + q0);
+                if sq > 0 && (x - sp as f64 / sq as f64).abs() < (x - p1 as f64 / q1 as f64).abs() {
+                    (p1, q1) = (sp, sq);
+                }
+                break;
+            }
+            (p0, q0, p1, q1) = (p1, q1, p2, q2);
+            let frac = v - a as f64;
+            if frac <= f64::EPSILON * v.max(1.0) {
+                break;
+            }
+            v = 1.0 / frac;
+        }
+        Self::new(sign * p1 as i32, q1 as i32)
+    }
 }
 
-impl std::ops::Mul for Rational {
+/// 将 `a` 从时间基 `bq` 重缩放到 `cq`, 四舍五入 (对标 FFmpeg `av_rescale_q`)
+///
+/// 中间结果使用 128 位整数, 不会溢出; 结果超出 i64 范围时饱和.
+/// 任一时间基无效时返回 [`crate::timestamp::NOPTS_VALUE`].
+pub fn rescale_q(a: i64, bq: Rational, cq: Rational) -> i64 {
+    if !bq.is_valid() || !cq.is_valid() || cq.num == 0 {
+        return crate::timestamp::NOPTS_VALUE;
+    }
+    let b = i128::from(bq.num) * i128::from(cq.den);
+    let c = i128::from(bq.den) * i128::from(cq.num);
+    rescale_i128(i128::from(a), b, c)
+}
+
+/// 计算 `a * b / c`, 四舍五入 (对标 FFmpeg `av_rescale`)
+///
+/// `c` 为 0 时返回 [`crate::timestamp::NOPTS_VALUE`].
+pub fn rescale(a: i64, b: i64, c: i64) -> i64 {
+    if c == 0 {
+        return crate::timestamp::NOPTS_VALUE;
+    }
+    rescale_i128(i128::from(a), i128::from(b), i128::from(c))
+}
+
+fn rescale_i128(a: i128, b: i128, c: i128) -> i64 {
+    let (b, c) = if c < 0 { (-b, -c) } else { (b, c) };
+    let n = a * b;
+    // 远离零方向的四舍五入
+    let r = if n >= 0 {
+        (n + c / 2) / c
+    } else {
+        (n - c / 2) / c
+    };
+    r.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64
+}
+
+/// 约分并在超出 `max` 时用连分数求最接近的近似 (对标 FFmpeg `av_reduce`)
+fn reduce_bounded(num: i128, den: i128, max: i128) -> Rational {
+    if den == 0 {
+        return Rational::new(num.signum() as i32, 0);
+    }
+    let negative = (num < 0) != (den < 0);
+    let (mut num, mut den) = (num.abs(), den.abs());
+    let g = gcd_i128(num, den);
+    if g > 1 {
+        num /= g;
+        den /= g;
+    }
+
+    let (mut a0n, mut a0d, mut a1n, mut a1d) = (0i128, 1i128, 1i128, 0i128);
+    if num <= max && den <= max {
+        (a1n, a1d) = (num, den);
+        den = 0;
+    }
+    while den != 0 {
+        let x = num / den;
+        let next_den = num - den * x;
+        let (a2n, a2d) = (x * a1n + a0n, x * a1d + a0d);
+        if a2n > max || a2d > max {
+            let mut x = x;
+            if a1n != 0 {
+                x = (max - a0n) / a1n;
+            }
+            if a1d != 0 {
+                x = x.min((max - a0d) / a1d);
+            }
+            if den * (2 * x * a1d + a0d) > num * a1d {
+                (a1n, a1d) = (x * a1n + a0n, x * a1d + a0d);
+            }
+            break;
+        }
+        (a0n, a0d, a1n, a1d) = (a1n, a1d, a2n, a2d);
+        num = den;
+        den = next_den;
+    }
+    let n = a1n as i32;
+    Rational::new(if negative { -n } else { n }, a1d as i32)
+}
+
+fn gcd_i128(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let (a, b) = (i64::from(self.num), i64::from(self.den));
+        let (c, d) = (i64::from(other.num), i64::from(other.den));
+        Self::from_i64(a * d + c * b, b * d)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + (-other)
+    }
+}
+
+impl Mul for Rational {
     type Output = Self;
 
     /// 两个有理数相乘
     fn mul(self, other: Self) -> Self {
-        Self {
-            num: self.num * other.num,
-            den: self.den * other.den,
-        }
-        .reduce()
+        Self::from_i64(
+            i64::from(self.num) * i64::from(other.num),
+            i64::from(self.den) * i64::from(other.den),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        Self::from_i64(
+            i64::from(self.num) * i64::from(other.den),
+            i64::from(self.den) * i64::from(other.num),
+        )
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_i64(-i64::from(self.num), i64::from(self.den))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    /// 按数值比较; 数值相等时按分母、分子排序, 以与结构相等 (`Eq`) 保持一致.
+    /// 无效值 (分母为 0) 排在所有有效值之前.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |r: &Self| {
+            if r.den < 0 {
+                (-i64::from(r.num), -i64::from(r.den))
+            } else {
+                (i64::from(r.num), i64::from(r.den))
+            }
+        };
+        let (a, b) = key(self);
+        let (c, d) = key(other);
+        let by_value = match (b == 0, d == 0) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => (a * d).cmp(&(c * b)),
+        };
+        by_value
+            .then(self.den.cmp(&other.den))
+            .then(self.num.cmp(&other.num))
     }
 }
 
@@ -160,6 +393,143 @@ mod tests {
         assert_eq!(format!("{r}"), "30000/1001");
     }
 
+    #[test]
+    fn test_rational_arithmetic() {
+        let a = Rational::new(1, 3);
+        let b = Rational::new(1, 6);
+        assert_eq!(a + b, Rational::new(1, 2));
+        assert_eq!(a - b, Rational::new(1, 6));
+        assert_eq!(a * b, Rational::new(1, 18));
+        assert_eq!(a / b, Rational::new(2, 1));
+        assert_eq!(b - a, Rational::new(-1, 6), "结果为负时符号应在分子");
+        assert_eq!(-a, Rational::new(-1, 3));
+        assert_eq!(
+            Rational::new(-1, 2) * Rational::new(2, -3),
+            Rational::new(1, 3),
+            "负负得正且分母规范为正"
+        );
+    }
+
+    #[test]
+    fn test_rational_arithmetic_no_overflow() {
+        // 分子分母乘积超出 i32, 但约分后可表示
+        let a = Rational::new(1_000_000, 1_000_001);
+        let b = Rational::new(1_000_001, 1_000_000);
+        assert_eq!(a * b, Rational::new(1, 1));
+
+        let tb = Rational::new(1, 90000) + Rational::new(1, 48000);
+        assert_eq!(tb, Rational::new(23, 720000));
+
+        // 无法精确表示时取最接近的近似, 不溢出也不 panic
+        let big = Rational::new(i32::MAX, 1) + Rational::new(1, i32::MAX);
+        assert!(big.is_valid());
+        assert!((big.to_f64() - f64::from(i32::MAX)).abs() < 1.0);
+        let tiny = Rational::new(1, i32::MAX) * Rational::new(1, i32::MAX);
+        assert!(tiny.to_f64() >= 0.0 && tiny.to_f64() < 1e-9);
+    }
+
+    #[test]
+    fn test_rational_ordering() {
+        let mut rates = vec![
+            Rational::new(30, 1),
+            Rational::new(24000, 1001),
+            Rational::new(-1, 2),
+            Rational::new(30000, 1001),
+            Rational::new(25, 1),
+        ];
+        rates.sort();
+        assert_eq!(
+            rates,
+            vec![
+                Rational::new(-1, 2),
+                Rational::new(24000, 1001),
+                Rational::new(25, 1),
+                Rational::new(30000, 1001),
+                Rational::new(30, 1),
+            ]
+        );
+        assert!(
+            Rational::new(1, -2) < Rational::new(0, 1),
+            "负分母应按负值比较"
+        );
+        assert_ne!(
+            Rational::new(1, 2).cmp(&Rational::new(2, 4)),
+            Ordering::Equal,
+            "数值相等但结构不同时不应判为相等, 与 Eq 保持一致"
+        );
+        assert_eq!(
+            Rational::new(1, 2).cmp(&Rational::new(1, 2)),
+            Ordering::Equal
+        );
+        assert!(Rational::UNDEFINED < Rational::new(i32::MIN, 1));
+    }
+
+    #[test]
+    fn test_rational_from_f64() {
+        assert_eq!(
+            Rational::from_f64(29.97, 1_001_000),
+            Rational::new(30000, 1001)
+        );
+        assert_eq!(
+            Rational::from_f64(23.976, 1_001_000),
+            Rational::new(24000, 1001)
+        );
+        assert_eq!(
+            Rational::from_f64(59.94, 1_001_000),
+            Rational::new(60000, 1001)
+        );
+        assert_eq!(
+            Rational::from_f64(-29.97, 1_001_000),
+            Rational::new(-30000, 1001)
+        );
+        assert_eq!(Rational::from_f64(25.0, 1_001_000), Rational::new(25, 1));
+        assert_eq!(Rational::from_f64(0.5, 100), Rational::new(1, 2));
+        assert_eq!(Rational::from_f64(-0.75, 100), Rational::new(-3, 4));
+        assert_eq!(Rational::from_f64(12.5, 1000), Rational::new(25, 2));
+        // 分母受限时取最佳近似
+        assert_eq!(
+            Rational::from_f64(std::f64::consts::PI, 1000),
+            Rational::new(355, 113)
+        );
+        assert_eq!(
+            Rational::from_f64(std::f64::consts::PI, 10),
+            Rational::new(22, 7)
+        );
+        assert!(!Rational::from_f64(f64::NAN, 1000).is_valid());
+    }
+
+    #[test]
+    fn test_rational_inverse() {
+        assert_eq!(
+            Rational::new(30000, 1001).inverse(),
+            Rational::new(1001, 30000)
+        );
+        assert_eq!(Rational::new(-2, 4).inverse(), Rational::new(-2, 1));
+        assert!(!Rational::ZERO.inverse().is_valid());
+    }
+
+    #[test]
+    fn test_rescale_q() {
+        assert_eq!(
+            rescale_q(90000, Rational::new(1, 90000), Rational::new(1, 1000)),
+            1000
+        );
+        // 四舍五入而非截断
+        assert_eq!(rescale_q(1, Rational::new(1, 3), Rational::new(1, 2)), 1);
+        assert_eq!(rescale_q(-1, Rational::new(1, 3), Rational::new(1, 2)), -1);
+        assert_eq!(
+            rescale_q(1001, Rational::new(1001, 30000), Rational::new(1, 90000)),
+            3_006_003
+        );
+        // 中间结果超出 i64 时不溢出
+        assert_eq!(
+            rescale_q(i64::MAX / 2, Rational::new(1, 1), Rational::new(1, 2)),
+            i64::MAX - 1
+        );
+        assert_eq!(rescale(3, 5, 2), 8);
+        assert_eq!(rescale(1, 1, 0), crate::timestamp::NOPTS_VALUE);
+    }
+
     #[test]
     fn test_rational_reciprocal() {
         let r = Rational::new(1, 25).invert();
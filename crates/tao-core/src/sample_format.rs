@@ -3,6 +3,9 @@
 //! 对标 FFmpeg 的 `AVSampleFormat`.
 
 use std::fmt;
+use std::str::FromStr;
+
+use crate::{TaoError, TaoResult};
 
 /// 音频采样格式
 ///
@@ -22,6 +25,8 @@ pub enum SampleFormat {
     U8,
     /// 有符号 16 位整数, 交错
     S16,
+    /// 有符号 24 位整数, 交错 (符号扩展后存储于 32 位容器)
+    S24,
     /// 有符号 32 位整数, 交错
     S32,
     /// 32 位浮点, 交错
@@ -36,6 +41,8 @@ pub enum SampleFormat {
     U8p,
     /// 有符号 16 位整数, 平面
     S16p,
+    /// 有符号 24 位整数, 平面 (符号扩展后存储于 32 位容器)
+    S24p,
     /// 有符号 32 位整数, 平面
     S32p,
     /// 32 位浮点, 平面
@@ -44,14 +51,86 @@ pub enum SampleFormat {
     F64p,
 }
 
+/// 全部采样格式及其稳定数值 ID (ID 用于 FFI, 不可更改已分配的值)
+const SAMPLE_FORMATS: [(SampleFormat, u32); 13] = [
+    (SampleFormat::None, 0),
+    (SampleFormat::U8, 1),
+    (SampleFormat::S16, 2),
+    (SampleFormat::S32, 3),
+    (SampleFormat::F32, 4),
+    (SampleFormat::F64, 5),
+    (SampleFormat::U8p, 6),
+    (SampleFormat::S16p, 7),
+    (SampleFormat::S32p, 8),
+    (SampleFormat::F32p, 9),
+    (SampleFormat::F64p, 10),
+    (SampleFormat::S24, 11),
+    (SampleFormat::S24p, 12),
+];
+
 impl SampleFormat {
+    /// 遍历全部采样格式 (含 None)
+    pub fn all() -> impl Iterator<Item = SampleFormat> {
+        SAMPLE_FORMATS.iter().map(|&(format, _)| format)
+    }
+
+    /// 格式名称 (与 FFmpeg 一致, 如 "s16", "fltp")
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::U8 => "u8",
+            Self::S16 => "s16",
+            Self::S24 => "s24",
+            Self::S32 => "s32",
+            Self::F32 => "flt",
+            Self::F64 => "dbl",
+            Self::U8p => "u8p",
+            Self::S16p => "s16p",
+            Self::S24p => "s24p",
+            Self::S32p => "s32p",
+            Self::F32p => "fltp",
+            Self::F64p => "dblp",
+        }
+    }
+
+    /// 按名称查找采样格式 (不区分大小写, 另接受 "f32"/"f64" 等别名)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        let alias = match name.as_str() {
+            "f32" => Some(Self::F32),
+            "f32p" => Some(Self::F32p),
+            "f64" => Some(Self::F64),
+            "f64p" => Some(Self::F64p),
+            _ => None,
+        };
+        alias.or_else(|| Self::all().find(|f| f.name() == name))
+    }
+
+    /// 稳定数值 ID
+    pub fn to_u32(&self) -> u32 {
+        SAMPLE_FORMATS
+            .iter()
+            .find(|(format, _)| format == self)
+            .map_or(0, |&(_, id)| id)
+    }
+
+    /// 由数值 ID 查找采样格式, 未知 ID 返回 None
+    pub fn from_u32(id: u32) -> Option<Self> {
+        SAMPLE_FORMATS
+            .iter()
+            .find(|&&(_, format_id)| format_id == id)
+            .map(|&(format, _)| format)
+    }
+
     /// 每个采样点占用的字节数
+    ///
+    /// S24/S24p 在内存中以 4 字节存储, 因此返回 4.
     pub const fn bytes_per_sample(&self) -> u32 {
         match self {
             Self::None => 0,
             Self::U8 | Self::U8p => 1,
             Self::S16 | Self::S16p => 2,
-            Self::S32 | Self::S32p | Self::F32 | Self::F32p => 4,
+            Self::S24 | Self::S24p | Self::S32 | Self::S32p | Self::F32 | Self::F32p => 4,
             Self::F64 | Self::F64p => 8,
         }
     }
@@ -60,15 +139,21 @@ impl SampleFormat {
     pub const fn is_planar(&self) -> bool {
         matches!(
             self,
-            Self::U8p | Self::S16p | Self::S32p | Self::F32p | Self::F64p
+            Self::U8p | Self::S16p | Self::S24p | Self::S32p | Self::F32p | Self::F64p
         )
     }
 
+    /// 是否为浮点格式
+    pub const fn is_float(&self) -> bool {
+        matches!(self, Self::F32 | Self::F32p | Self::F64 | Self::F64p)
+    }
+
     /// 获取对应的平面格式
     pub const fn to_planar(&self) -> Self {
         match self {
             Self::U8 => Self::U8p,
             Self::S16 => Self::S16p,
+            Self::S24 => Self::S24p,
             Self::S32 => Self::S32p,
             Self::F32 => Self::F32p,
             Self::F64 => Self::F64p,
@@ -81,6 +166,7 @@ impl SampleFormat {
         match self {
             Self::U8p => Self::U8,
             Self::S16p => Self::S16,
+            Self::S24p => Self::S24,
             Self::S32p => Self::S32,
             Self::F32p => Self::F32,
             Self::F64p => Self::F64,
@@ -91,19 +177,56 @@ impl SampleFormat {
 
 impl fmt::Display for SampleFormat {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let name = match self {
-            Self::None => "none",
-            Self::U8 => "u8",
-            Self::S16 => "s16",
-            Self::S32 => "s32",
-            Self::F32 => "flt",
-            Self::F64 => "dbl",
-            Self::U8p => "u8p",
-            Self::S16p => "s16p",
-            Self::S32p => "s32p",
-            Self::F32p => "fltp",
-            Self::F64p => "dblp",
-        };
-        write!(f, "{name}")
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SampleFormat {
+    type Err = TaoError;
+
+    fn from_str(s: &str) -> TaoResult<Self> {
+        Self::from_name(s).ok_or_else(|| TaoError::InvalidArgument(format!("未知的采样格式: {s}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_format_name_roundtrip() {
+        for format in SampleFormat::all() {
+            assert_eq!(
+                format.to_string().parse::<SampleFormat>().unwrap(),
+                format,
+                "名称应可往返: {format}"
+            );
+            assert_eq!(
+                SampleFormat::from_u32(format.to_u32()),
+                Some(format),
+                "数值 ID 应可往返: {format}"
+            );
+        }
+        assert_eq!(SampleFormat::all().count(), 13, "应列出全部采样格式");
+        assert_eq!(SampleFormat::from_name("f32"), Some(SampleFormat::F32));
+        assert_eq!(SampleFormat::from_name("FLTP"), Some(SampleFormat::F32p));
+        assert!(matches!(
+            "s20".parse::<SampleFormat>(),
+            Err(TaoError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_sample_format_stable_ids() {
+        // 与 tao-ffi 既有约定保持一致
+        assert_eq!(SampleFormat::None.to_u32(), 0);
+        assert_eq!(SampleFormat::S16.to_u32(), 2);
+        assert_eq!(SampleFormat::F64.to_u32(), 5);
+        assert_eq!(SampleFormat::from_u32(3), Some(SampleFormat::S32));
+        assert_eq!(
+            SampleFormat::from_u32(999),
+            None,
+            "未知 ID 不应回退为默认格式"
+        );
     }
 }
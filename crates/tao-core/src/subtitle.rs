@@ -283,6 +283,93 @@ pub fn parse_ass(content: &str) -> TaoResult<SubtitleTrack> {
     })
 }
 
+/// 结构化的 ASS/SSA Dialogue 事件
+///
+/// 按 ASS 默认字段顺序 (Layer, Start, End, Style, Name, MarginL, MarginR, MarginV,
+/// Effect, Text) 解析, 同时保留原始文本和去除覆盖标签后的纯文本.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssDialogue {
+    /// 图层 (SSA 的 `Marked=N` 字段按 0 处理)
+    pub layer: i32,
+    /// 开始时间 (毫秒)
+    pub start_ms: u64,
+    /// 结束时间 (毫秒)
+    pub end_ms: u64,
+    /// 样式名
+    pub style: String,
+    /// 说话人名称 (可为空)
+    pub name: String,
+    /// 原始文本 (保留 `{\...}` 覆盖标签)
+    pub text: String,
+    /// 纯文本 (去除覆盖标签, `\N`/`\n` 转为换行)
+    pub plain_text: String,
+}
+
+/// 解析单行 ASS/SSA Dialogue 事件.
+///
+/// 行首必须为 `Dialogue:` (不区分大小写), 字段按 ASS 默认顺序解析.
+///
+/// # 示例
+/// ```
+/// use tao_core::subtitle::parse_ass_event;
+///
+/// let d = parse_ass_event(r"Dialogue: 1,0:00:01.00,0:00:02.50,Default,,0,0,0,,{\i1}Hi{\i0}")
+///     .unwrap();
+/// assert_eq!(d.layer, 1);
+/// assert_eq!(d.end_ms, 2500);
+/// assert_eq!(d.plain_text, "Hi");
+/// ```
+pub fn parse_ass_event(line: &str) -> TaoResult<AssDialogue> {
+    const PREFIX: &str = "dialogue:";
+    let line = line.trim();
+    // 固定字节偏移可能落在多字节字符中间, 用 get 切片避免 panic
+    if !line
+        .get(..PREFIX.len())
+        .is_some_and(|p| p.eq_ignore_ascii_case(PREFIX))
+    {
+        return Err(TaoError::InvalidData(format!(
+            "不是 ASS Dialogue 行: {}",
+            line
+        )));
+    }
+
+    let parts = split_ass_dialogue(line.get(PREFIX.len()..).unwrap_or_default().trim());
+    if parts.len() < 10 {
+        return Err(TaoError::InvalidData(format!(
+            "ASS Dialogue 字段数不足: 期望 10, 实际 {}",
+            parts.len()
+        )));
+    }
+
+    let layer = if parts[0].to_ascii_lowercase().starts_with("marked=") {
+        0
+    } else {
+        parts[0]
+            .parse()
+            .map_err(|_| TaoError::InvalidData(format!("无效的 ASS Layer: {}", parts[0])))?
+    };
+    let start_ms = parse_ass_timestamp(parts[1])
+        .ok_or_else(|| TaoError::InvalidData(format!("无效的 ASS 开始时间: {}", parts[1])))?;
+    let end_ms = parse_ass_timestamp(parts[2])
+        .ok_or_else(|| TaoError::InvalidData(format!("无效的 ASS 结束时间: {}", parts[2])))?;
+
+    let text = parts[9].to_string();
+    let plain_text = strip_ass_tags(&text)
+        .replace("\\N", "\n")
+        .replace("\\n", "\n")
+        .replace("\\h", " ");
+
+    Ok(AssDialogue {
+        layer,
+        start_ms,
+        end_ms,
+        style: parts[3].to_string(),
+        name: parts[4].to_string(),
+        text,
+        plain_text,
+    })
+}
+
 /// 按逗号分割 ASS Dialogue 行, 正确处理 Text 字段内可能包含的逗号.
 ///
 /// ASS 格式前 9 个字段不含逗号, 第 10 个 (Text) 字段可包含逗号.
@@ -425,4 +512,39 @@ Dialogue: 0,0:00:01.00,0:00:02.00,Title,,0,0,0,,Text two"#;
         assert_eq!(track.events[0].style, Some("Default".to_string()));
         assert_eq!(track.events[1].style, Some("Title".to_string()));
     }
+
+    #[test]
+    fn test_parse_ass_event_fields_and_tags() {
+        let line =
+            r"Dialogue: 2,0:00:03.20,0:00:05.00,Title,Bob,0,0,0,,{\i1}Hello{\i0}, world\Nbye";
+        let d = parse_ass_event(line).unwrap();
+        assert_eq!(d.layer, 2, "Layer 解析错误");
+        assert_eq!(d.start_ms, 3200, "开始时间解析错误");
+        assert_eq!(d.end_ms, 5000, "结束时间解析错误");
+        assert_eq!(d.style, "Title", "样式解析错误");
+        assert_eq!(d.name, "Bob", "说话人解析错误");
+        assert_eq!(
+            d.text, r"{\i1}Hello{\i0}, world\Nbye",
+            "原始文本应保留覆盖标签"
+        );
+        assert_eq!(d.plain_text, "Hello, world\nbye", "纯文本应去除覆盖标签");
+    }
+
+    #[test]
+    fn test_parse_ass_event_ssa_marked_and_errors() {
+        let d =
+            parse_ass_event("dialogue: Marked=0,0:00:00.00,0:00:01.00,Default,,0,0,0,,Hi").unwrap();
+        assert_eq!(d.layer, 0, "SSA Marked 字段应按图层 0 处理");
+
+        assert!(parse_ass_event("Comment: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,x").is_err());
+        assert!(parse_ass_event("Dialogue: 0,0:00:00.00,Default").is_err());
+        assert!(parse_ass_event("Dialogue: 0,bad,0:00:01.00,Default,,0,0,0,,x").is_err());
+    }
+
+    #[test]
+    fn test_parse_ass_event_multibyte_prefix_not_panic() {
+        // 第 9 字节落在 "注" 的中间, 不能按字节切片
+        let err = parse_ass_event("a注释注释注释").unwrap_err();
+        assert!(matches!(err, TaoError::InvalidData(_)));
+    }
 }
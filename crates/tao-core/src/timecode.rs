@@ -0,0 +1,184 @@
+//! SMPTE 时间码, 用于专业视频工作流中的帧级定位.
+//!
+//! 对标 FFmpeg 的 `AVTimecode`, 格式为 `HH:MM:SS:FF`,
+//! 丢帧 (drop-frame) 时间码使用 `HH:MM:SS;FF`.
+
+use crate::error::{TaoError, TaoResult};
+use crate::rational::Rational;
+use std::fmt;
+use std::str::FromStr;
+
+/// SMPTE 时间码
+///
+/// 丢帧模式仅对 29.97/59.94 等 NTSC 帧率有意义: 除每 10 分钟外,
+/// 每分钟开头跳过 2 (或 4) 个帧号, 使时间码与实际时钟对齐.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SmpteTc {
+    /// 小时 (0-23)
+    pub hours: u8,
+    /// 分钟 (0-59)
+    pub minutes: u8,
+    /// 秒 (0-59)
+    pub seconds: u8,
+    /// 帧 (0 到帧率-1)
+    pub frames: u8,
+    /// 是否为丢帧时间码
+    pub drop_frame: bool,
+}
+
+impl SmpteTc {
+    /// 创建时间码
+    pub const fn new(hours: u8, minutes: u8, seconds: u8, frames: u8, drop_frame: bool) -> Self {
+        Self {
+            hours,
+            minutes,
+            seconds,
+            frames,
+            drop_frame,
+        }
+    }
+
+    /// 将时间码转换为帧号 (从 00:00:00:00 起算)
+    ///
+    /// `fps` 为实际帧率 (如 30000/1001), 计数基于取整后的名义帧率.
+    pub fn to_frame_number(&self, fps: Rational) -> u64 {
+        let nominal = nominal_fps(fps);
+        let total_minutes = u64::from(self.hours) * 60 + u64::from(self.minutes);
+        let mut frames =
+            (total_minutes * 60 + u64::from(self.seconds)) * nominal + u64::from(self.frames);
+        if self.drop_frame {
+            let drop = drop_frames_per_minute(nominal);
+            frames -= drop * (total_minutes - total_minutes / 10);
+        }
+        frames
+    }
+
+    /// 由帧号构造时间码
+    ///
+    /// 小时数按 24 小时回绕.
+    pub fn from_frame_number(n: u64, fps: Rational, drop: bool) -> Self {
+        let nominal = nominal_fps(fps);
+        let mut n = n;
+        if drop {
+            let drop_frames = drop_frames_per_minute(nominal);
+            let frames_per_10min = nominal * 600 - drop_frames * 9;
+            let frames_per_min = nominal * 60 - drop_frames;
+            let tens = n / frames_per_10min;
+            let rem = n % frames_per_10min;
+            n += drop_frames * 9 * tens;
+            if rem >= drop_frames {
+                n += drop_frames * ((rem - drop_frames) / frames_per_min);
+            }
+        }
+        Self {
+            hours: ((n / (nominal * 3600)) % 24) as u8,
+            minutes: ((n / (nominal * 60)) % 60) as u8,
+            seconds: ((n / nominal) % 60) as u8,
+            frames: (n % nominal) as u8,
+            drop_frame: drop,
+        }
+    }
+}
+
+/// 取整后的名义帧率 (29.97 → 30), 最小为 1
+fn nominal_fps(fps: Rational) -> u64 {
+    if !fps.is_valid() || fps.num <= 0 {
+        return 1;
+    }
+    (fps.to_f64().round() as u64).max(1)
+}
+
+/// 丢帧模式下每分钟跳过的帧号数 (30 → 2, 60 → 4)
+fn drop_frames_per_minute(nominal: u64) -> u64 {
+    (nominal / 15).max(1)
+}
+
+impl fmt::Display for SmpteTc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sep = if self.drop_frame { ';' } else { ':' };
+        write!(
+            f,
+            "{:02}:{:02}:{:02}{sep}{:02}",
+            self.hours, self.minutes, self.seconds, self.frames
+        )
+    }
+}
+
+impl FromStr for SmpteTc {
+    type Err = TaoError;
+
+    /// 解析 `HH:MM:SS:FF` / `HH:MM:SS;FF` (也接受 `.` 或 `,` 作为帧分隔符)
+    fn from_str(s: &str) -> TaoResult<Self> {
+        let s = s.trim();
+        let invalid = || TaoError::InvalidArgument(format!("无效的 SMPTE 时间码: {s}"));
+        let sep_pos = s.rfind([':', ';', '.', ',']).ok_or_else(invalid)?;
+        let drop_frame = matches!(s.as_bytes()[sep_pos], b';' | b',');
+        let hms: Vec<&str> = s[..sep_pos].split(':').collect();
+        if hms.len() != 3 {
+            return Err(invalid());
+        }
+        let field = |v: &str| v.parse::<u8>().map_err(|_| invalid());
+        let tc = Self {
+            hours: field(hms[0])?,
+            minutes: field(hms[1])?,
+            seconds: field(hms[2])?,
+            frames: field(&s[sep_pos + 1..])?,
+            drop_frame,
+        };
+        if tc.minutes >= 60 || tc.seconds >= 60 {
+            return Err(invalid());
+        }
+        Ok(tc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smpte_tc_display() {
+        assert_eq!(SmpteTc::new(1, 2, 3, 4, false).to_string(), "01:02:03:04");
+        assert_eq!(SmpteTc::new(1, 2, 3, 4, true).to_string(), "01:02:03;04");
+    }
+
+    #[test]
+    fn test_smpte_tc_non_drop_roundtrip() {
+        let fps = Rational::new(25, 1);
+        let tc = SmpteTc::new(1, 2, 3, 4, false);
+        let n = tc.to_frame_number(fps);
+        assert_eq!(n, ((3600 + 120) + 3) * 25 + 4, "帧号计算错误");
+        assert_eq!(SmpteTc::from_frame_number(n, fps, false), tc);
+    }
+
+    #[test]
+    fn test_smpte_tc_drop_frame_skips_minute_boundary() {
+        let fps = Rational::new(30000, 1001);
+        // 第 1800 帧应跳过 00:01:00;00 和 00:01:00;01
+        let tc = SmpteTc::from_frame_number(1800, fps, true);
+        assert_eq!(tc.to_string(), "00:01:00;02");
+        assert_eq!(tc.to_frame_number(fps), 1800);
+        // 每 10 分钟不丢帧
+        let tc = SmpteTc::from_frame_number(17982, fps, true);
+        assert_eq!(tc.to_string(), "00:10:00;00");
+    }
+
+    #[test]
+    fn test_smpte_tc_drop_frame_roundtrip() {
+        let fps = Rational::new(30000, 1001);
+        for n in [0u64, 1, 1799, 1800, 1801, 17981, 17982, 107_892, 2_589_407] {
+            let tc = SmpteTc::from_frame_number(n, fps, true);
+            assert_eq!(tc.to_frame_number(fps), n, "帧号 {n} 往返失败: {tc}");
+        }
+    }
+
+    #[test]
+    fn test_smpte_tc_parse() {
+        let tc: SmpteTc = "10:00:00;12".parse().unwrap();
+        assert_eq!(tc, SmpteTc::new(10, 0, 0, 12, true));
+        let tc: SmpteTc = "01:02:03:04".parse().unwrap();
+        assert!(!tc.drop_frame);
+        assert!("01:02:03".parse::<SmpteTc>().is_err());
+        assert!("01:61:03:00".parse::<SmpteTc>().is_err());
+    }
+}
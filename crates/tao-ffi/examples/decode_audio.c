@@ -25,6 +25,29 @@
 #define TAO_ERROR          -1
 #define TAO_EOF            -2
 #define TAO_NEED_MORE_DATA -3
+#define TAO_ERROR_INVALID_ARGUMENT  -4
+#define TAO_ERROR_UNSUPPORTED       -5
+#define TAO_ERROR_CODEC             -6
+#define TAO_ERROR_FORMAT            -7
+#define TAO_ERROR_IO                -8
+#define TAO_ERROR_OUT_OF_MEMORY     -9
+#define TAO_ERROR_CODEC_NOT_FOUND   -10
+#define TAO_ERROR_FORMAT_NOT_FOUND  -11
+#define TAO_ERROR_FILTER_NOT_FOUND  -12
+#define TAO_ERROR_STREAM_NOT_FOUND  -13
+#define TAO_ERROR_INVALID_DATA      -14
+#define TAO_ERROR_NOT_IMPLEMENTED   -15
+#define TAO_ERROR_INTERNAL          -16
+
+/* 数据包标志位 */
+#define TAO_PKT_FLAG_KEY     0x0001
+#define TAO_PKT_FLAG_CORRUPT 0x0002
+#define TAO_PKT_FLAG_DISCARD 0x0004
+
+/* Seek 标志位 */
+#define TAO_SEEK_BACKWARD 1
+#define TAO_SEEK_BYTE     2
+#define TAO_SEEK_ANY      4
 
 /* 媒体类型 */
 #define TAO_MEDIA_TYPE_AUDIO 1
@@ -45,18 +68,55 @@ extern const char* tao_build_info(void);
 extern void tao_init(void);
 extern void tao_shutdown(void);
 
+/* 日志回调: level 0=error, 1=warn, 2=info, 3=debug, 4=trace */
+typedef void (*TaoLogCallback)(int level, const char* msg);
+extern int tao_set_log_callback(TaoLogCallback cb);
+
+/* 错误信息 */
+extern const char* tao_strerror(int code);
+extern const char* tao_last_error_message(void);
+extern const char* tao_get_last_error(void);
+extern int tao_error_code_from_last(void);
+
 /* 格式 (解封装) */
 extern TaoFormatContext* tao_format_open_input(const char* filename);
 extern int tao_format_read_packet(TaoFormatContext* ctx, TaoPacket** packet);
 extern int tao_format_get_stream_count(const TaoFormatContext* ctx);
 extern int tao_format_get_stream_codec_id(const TaoFormatContext* ctx, int stream_index);
 extern int tao_format_get_stream_media_type(const TaoFormatContext* ctx, int stream_index);
+extern int tao_format_seek(TaoFormatContext* ctx, int stream_index, int64_t timestamp, int flags);
 extern void tao_format_close(TaoFormatContext* ctx);
 
+/* 格式 (封装) */
+typedef struct TaoMuxContext TaoMuxContext;
+extern TaoMuxContext* tao_format_open_output(const char* filename);
+extern int tao_format_add_stream(TaoMuxContext* ctx, int codec_id, int time_base_num,
+                                 int time_base_den, int sample_rate, int channels, int width,
+                                 int height, const uint8_t* extra_data, int extra_data_size);
+extern int tao_format_write_header(TaoMuxContext* ctx);
+extern int tao_format_write_packet(TaoMuxContext* ctx, int stream_index, const TaoPacket* packet);
+extern int tao_format_write_trailer(TaoMuxContext* ctx);
+extern void tao_format_close_output(TaoMuxContext* ctx);
+
 /* 编解码器 */
+#define TAO_CODEC_CAP_DECODE 1
+#define TAO_CODEC_CAP_ENCODE 2
+typedef struct TaoCodecInfo {
+    int codec_id;
+    const char* name;
+    int media_type;
+    int capabilities;
+} TaoCodecInfo;
+extern const TaoCodecInfo* tao_codec_list(int* count_out);
+extern int tao_codec_id_from_name(const char* name);
+extern const char* tao_codec_name_from_id(int codec_id);
 extern TaoCodecContext* tao_codec_create_decoder(int codec_id);
+/* 已弃用, 仅支持音频参数; 视频解码器使用 tao_codec_open_video_decoder */
 extern int tao_codec_open_decoder(TaoCodecContext* ctx, int sample_rate, int channels,
                                    const uint8_t* extra_data, int extra_data_size);
+extern int tao_codec_open_video_decoder(TaoCodecContext* ctx, uint32_t width, uint32_t height,
+                                        uint32_t pixel_format, const uint8_t* extra_data,
+                                        int extra_data_size);
 extern int tao_codec_send_packet(TaoCodecContext* ctx, const TaoPacket* packet);
 extern int tao_codec_receive_frame(TaoCodecContext* ctx, TaoFrame** frame);
 extern void tao_codec_close(TaoCodecContext* ctx);
@@ -66,6 +126,7 @@ extern const uint8_t* tao_packet_data(const TaoPacket* pkt);
 extern int tao_packet_size(const TaoPacket* pkt);
 extern int64_t tao_packet_pts(const TaoPacket* pkt);
 extern int tao_packet_stream_index(const TaoPacket* pkt);
+extern int tao_packet_flags(const TaoPacket* pkt);
 extern void tao_packet_free(TaoPacket* pkt);
 
 /* 帧 */
@@ -77,6 +138,11 @@ extern int tao_frame_width(const TaoFrame* frame);
 extern int tao_frame_height(const TaoFrame* frame);
 extern const uint8_t* tao_frame_data(const TaoFrame* frame, int plane);
 extern int tao_frame_linesize(const TaoFrame* frame, int plane);
+extern TaoFrame* tao_frame_alloc_audio(int nb_samples, int sample_rate,
+                                       uint32_t sample_format, int channels);
+extern TaoFrame* tao_frame_alloc_video(int width, int height, uint32_t pixel_format);
+extern int tao_frame_fill_plane(TaoFrame* frame, int plane,
+                                const uint8_t* data, int size);
 extern void tao_frame_free(TaoFrame* frame);
 
 /* ======================================== */
@@ -101,7 +167,8 @@ int main(int argc, char* argv[]) {
     printf("打开文件: %s\n", input_file);
     TaoFormatContext* fmt_ctx = tao_format_open_input(input_file);
     if (!fmt_ctx) {
-        fprintf(stderr, "错误: 无法打开输入文件\n");
+        fprintf(stderr, "错误: 无法打开输入文件 (%s): %s\n",
+                tao_strerror(tao_error_code_from_last()), tao_get_last_error());
         tao_shutdown();
         return 1;
     }
@@ -136,7 +203,7 @@ int main(int argc, char* argv[]) {
     /* 创建解码器 */
     TaoCodecContext* dec_ctx = tao_codec_create_decoder(audio_codec_id);
     if (!dec_ctx) {
-        fprintf(stderr, "错误: 无法创建解码器\n");
+        fprintf(stderr, "错误: 无法创建解码器: %s\n", tao_last_error_message());
         tao_format_close(fmt_ctx);
         tao_shutdown();
         return 1;
@@ -145,7 +212,8 @@ int main(int argc, char* argv[]) {
     /* 打开解码器 (使用默认参数) */
     int ret = tao_codec_open_decoder(dec_ctx, 44100, 2, NULL, 0);
     if (ret != TAO_OK) {
-        fprintf(stderr, "错误: 无法打开解码器\n");
+        fprintf(stderr, "错误: 无法打开解码器 (%s): %s\n", tao_strerror(ret),
+                tao_last_error_message());
         tao_codec_close(dec_ctx);
         tao_format_close(fmt_ctx);
         tao_shutdown();
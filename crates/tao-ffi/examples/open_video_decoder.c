@@ -0,0 +1,57 @@
+/**
+ * open_video_decoder.c - tao_codec_open_video_decoder 测试程序
+ *
+ * 以已知的 avcC (Baseline 320x240, 含 SPS/PPS) 打开 H.264 解码器,
+ * 验证返回 TAO_OK. 成功时退出码为 0.
+ *
+ * 编译 (假设 tao_ffi 库已构建):
+ *   Linux:   gcc open_video_decoder.c -L../../../target/debug -l:libtao_ffi.a -lm -lpthread -ldl \
+ *              -o open_video_decoder
+ *   Windows: cl open_video_decoder.c /I.. /link tao_ffi.dll.lib
+ */
+
+#include <stdio.h>
+#include <stdint.h>
+
+#define TAO_OK 0
+
+/* 像素格式 (映射同 tao_scale_context_create) */
+#define TAO_PIXEL_FORMAT_YUV420P 0
+
+typedef struct TaoCodecContext TaoCodecContext;
+
+extern int tao_codec_id_from_name(const char* name);
+extern TaoCodecContext* tao_codec_create_decoder(int codec_id);
+extern int tao_codec_open_video_decoder(TaoCodecContext* ctx, uint32_t width, uint32_t height,
+                                        uint32_t pixel_format, const uint8_t* extra_data,
+                                        int extra_data_size);
+extern void tao_codec_close(TaoCodecContext* ctx);
+extern const char* tao_last_error_message(void);
+
+/* avcC: version 1, Baseline, 4 字节长度前缀, 1 个 SPS, 1 个 PPS */
+static const uint8_t AVCC[] = {
+    0x01, 0x42, 0x00, 0x1E, 0xFF, 0xE1,
+    0x00, 0x08, 0x67, 0x42, 0x00, 0x1E, 0xF4, 0x0A, 0x0F, 0xC8, /* SPS */
+    0x01,
+    0x00, 0x04, 0x68, 0xCE, 0x3C, 0x80, /* PPS */
+};
+
+int main(void) {
+    int codec_id = tao_codec_id_from_name("h264");
+    TaoCodecContext* ctx = tao_codec_create_decoder(codec_id);
+    if (!ctx) {
+        fprintf(stderr, "失败: 无法创建 H.264 解码器: %s\n", tao_last_error_message());
+        return 1;
+    }
+
+    int ret = tao_codec_open_video_decoder(ctx, 320, 240, TAO_PIXEL_FORMAT_YUV420P, AVCC,
+                                           (int)sizeof(AVCC));
+    tao_codec_close(ctx);
+    if (ret != TAO_OK) {
+        fprintf(stderr, "失败: 打开视频解码器返回 %d: %s\n", ret, tao_last_error_message());
+        return 1;
+    }
+
+    printf("通过: H.264 解码器已以 320x240 打开\n");
+    return 0;
+}
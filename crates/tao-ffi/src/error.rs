@@ -0,0 +1,118 @@
+//! FFI 错误码映射与线程局部最近错误信息.
+//!
+//! 每个 `tao_*` 函数失败时将可读的错误描述写入当前线程的最近错误槽,
+//! C 调用方可通过 `tao_get_last_error` 读取描述, 通过 `tao_error_code_from_last`
+//! 读取对应的细分错误码. EOF / NeedMoreData 属于正常流程状态, 不写入错误槽.
+
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use tao_core::TaoError;
+
+use crate::{
+    TAO_EOF, TAO_ERROR, TAO_ERROR_CODEC, TAO_ERROR_CODEC_NOT_FOUND, TAO_ERROR_FILTER_NOT_FOUND,
+    TAO_ERROR_FORMAT, TAO_ERROR_FORMAT_NOT_FOUND, TAO_ERROR_INTERNAL, TAO_ERROR_INVALID_ARGUMENT,
+    TAO_ERROR_INVALID_DATA, TAO_ERROR_IO, TAO_ERROR_NOT_IMPLEMENTED, TAO_ERROR_OUT_OF_MEMORY,
+    TAO_ERROR_STREAM_NOT_FOUND, TAO_ERROR_UNSUPPORTED, TAO_NEED_MORE_DATA, TAO_OK,
+};
+
+thread_local! {
+    /// 当前线程最近一次失败的错误码与错误描述
+    static LAST_ERROR: RefCell<Option<(c_int, CString)>> = const { RefCell::new(None) };
+}
+
+/// 将 TaoError 映射为 FFI 错误码
+pub(crate) fn error_code(e: &TaoError) -> c_int {
+    match e {
+        TaoError::Eof => TAO_EOF,
+        TaoError::NeedMoreData => TAO_NEED_MORE_DATA,
+        TaoError::InvalidArgument(_) => TAO_ERROR_INVALID_ARGUMENT,
+        TaoError::Unsupported(_) => TAO_ERROR_UNSUPPORTED,
+        TaoError::Codec(_) => TAO_ERROR_CODEC,
+        TaoError::Format(_) => TAO_ERROR_FORMAT,
+        TaoError::Io(_) => TAO_ERROR_IO,
+        TaoError::OutOfMemory(_) => TAO_ERROR_OUT_OF_MEMORY,
+        TaoError::CodecNotFound(_) => TAO_ERROR_CODEC_NOT_FOUND,
+        TaoError::FormatNotFound(_) => TAO_ERROR_FORMAT_NOT_FOUND,
+        TaoError::FilterNotFound(_) => TAO_ERROR_FILTER_NOT_FOUND,
+        TaoError::StreamNotFound(_) => TAO_ERROR_STREAM_NOT_FOUND,
+        TaoError::InvalidData(_) => TAO_ERROR_INVALID_DATA,
+        TaoError::NotImplemented(_) => TAO_ERROR_NOT_IMPLEMENTED,
+        TaoError::Internal(_) => TAO_ERROR_INTERNAL,
+    }
+}
+
+/// 记录错误并返回对应错误码
+pub(crate) fn record(e: &TaoError) -> c_int {
+    let code = error_code(e);
+    if code != TAO_EOF && code != TAO_NEED_MORE_DATA {
+        store(code, &e.to_string());
+    }
+    code
+}
+
+/// 记录错误并附加上下文 (如文件路径), 返回对应错误码
+pub(crate) fn record_with_context(e: &TaoError, context: &str) -> c_int {
+    let code = error_code(e);
+    if code != TAO_EOF && code != TAO_NEED_MORE_DATA {
+        store(code, &format!("{context}: {e}"));
+    }
+    code
+}
+
+/// 记录参数错误并返回 TAO_ERROR_INVALID_ARGUMENT
+pub(crate) fn invalid_argument(msg: &str) -> c_int {
+    store(TAO_ERROR_INVALID_ARGUMENT, &format!("无效参数: {msg}"));
+    TAO_ERROR_INVALID_ARGUMENT
+}
+
+/// 写入当前线程的最近错误描述 (错误码为 TAO_ERROR)
+pub(crate) fn set_last_error(msg: &str) {
+    store(TAO_ERROR, msg);
+}
+
+/// 写入当前线程的最近错误码与错误描述
+fn store(code: c_int, msg: &str) {
+    let msg = CString::new(msg.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = Some((code, msg)));
+}
+
+/// 获取当前线程最近错误描述的指针, 无错误时返回 null
+pub(crate) fn last_error_ptr() -> *const c_char {
+    LAST_ERROR.with(|slot| {
+        slot.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |(_, msg)| msg.as_ptr())
+    })
+}
+
+/// 获取当前线程最近错误的错误码, 无错误时返回 TAO_OK
+pub(crate) fn last_error_code() -> c_int {
+    LAST_ERROR.with(|slot| slot.borrow().as_ref().map_or(TAO_OK, |(code, _)| *code))
+}
+
+/// 错误码的静态描述
+pub(crate) fn strerror(code: c_int) -> &'static std::ffi::CStr {
+    match code {
+        TAO_OK => c"成功",
+        TAO_ERROR => c"未分类错误",
+        TAO_EOF => c"已到达流末尾",
+        TAO_NEED_MORE_DATA => c"数据不足, 需要更多输入",
+        TAO_ERROR_INVALID_ARGUMENT => c"无效参数",
+        TAO_ERROR_UNSUPPORTED => c"不支持的操作",
+        TAO_ERROR_CODEC => c"编解码器错误",
+        TAO_ERROR_FORMAT => c"格式错误",
+        TAO_ERROR_IO => c"I/O 错误",
+        TAO_ERROR_OUT_OF_MEMORY => c"内存分配失败",
+        TAO_ERROR_CODEC_NOT_FOUND => c"未找到编解码器",
+        TAO_ERROR_FORMAT_NOT_FOUND => c"未找到容器格式",
+        TAO_ERROR_FILTER_NOT_FOUND => c"未找到滤镜",
+        TAO_ERROR_STREAM_NOT_FOUND => c"未找到流",
+        TAO_ERROR_INVALID_DATA => c"无效数据",
+        TAO_ERROR_NOT_IMPLEMENTED => c"功能未实现",
+        TAO_ERROR_INTERNAL => c"内部错误",
+        _ => c"未知错误码",
+    }
+}
@@ -14,17 +14,31 @@
 //! - 由 Tao 分配的内存必须通过对应的 `tao_*_free()` 函数释放
 //! - 调用方分配的缓冲区由调用方负责释放
 
-use std::ffi::CStr;
+mod error;
+mod logging;
+mod registry;
+
+use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int};
 use std::ptr;
-
-use tao_codec::codec_parameters::{AudioCodecParams, CodecParamsType};
-use tao_codec::{CodecId, CodecParameters, CodecRegistry, Decoder, Encoder, Frame, Packet};
-use tao_core::{ChannelLayout, MediaType, SampleFormat, TaoError};
-use tao_format::{FormatRegistry, IoContext};
+use std::sync::OnceLock;
+
+use tao_codec::codec_parameters::{AudioCodecParams, CodecParamsType, VideoCodecParams};
+use tao_codec::{
+    CodecCapabilities, CodecId, CodecParameters, Decoder, Encoder, Frame, Packet, PacketFlags,
+    frame::{AudioFrame, VideoFrame},
+};
+use tao_core::color::ColorRange;
+use tao_core::{ChannelLayout, MediaType, PixelFormat, Rational, SampleFormat, TaoError};
+use tao_format::demuxer::SeekFlags;
+use tao_format::stream::{AudioStreamParams, StreamParams, VideoStreamParams};
+use tao_format::{FormatId, IoContext, Stream};
 use tao_resample::ResampleContext;
 use tao_scale::{ScaleAlgorithm, ScaleContext};
 
+pub use logging::TaoLogCallback;
+pub use registry::{TAO_CODEC_CAP_DECODE, TAO_CODEC_CAP_ENCODE};
+
 // =============================================================================
 // 错误码 (对应 C 头文件中的 #define)
 // =============================================================================
@@ -33,6 +47,37 @@ pub const TAO_OK: c_int = 0;
 pub const TAO_ERROR: c_int = -1;
 pub const TAO_EOF: c_int = -2;
 pub const TAO_NEED_MORE_DATA: c_int = -3;
+// 细分错误码, 与 TaoError 变体一一对应
+pub const TAO_ERROR_INVALID_ARGUMENT: c_int = -4;
+pub const TAO_ERROR_UNSUPPORTED: c_int = -5;
+pub const TAO_ERROR_CODEC: c_int = -6;
+pub const TAO_ERROR_FORMAT: c_int = -7;
+pub const TAO_ERROR_IO: c_int = -8;
+pub const TAO_ERROR_OUT_OF_MEMORY: c_int = -9;
+pub const TAO_ERROR_CODEC_NOT_FOUND: c_int = -10;
+pub const TAO_ERROR_FORMAT_NOT_FOUND: c_int = -11;
+pub const TAO_ERROR_FILTER_NOT_FOUND: c_int = -12;
+pub const TAO_ERROR_STREAM_NOT_FOUND: c_int = -13;
+pub const TAO_ERROR_INVALID_DATA: c_int = -14;
+pub const TAO_ERROR_NOT_IMPLEMENTED: c_int = -15;
+pub const TAO_ERROR_INTERNAL: c_int = -16;
+
+// 数据包标志位 (tao_packet_flags 返回值)
+pub const TAO_PKT_FLAG_KEY: c_int = PacketFlags::KEYFRAME.bits() as c_int;
+pub const TAO_PKT_FLAG_CORRUPT: c_int = PacketFlags::CORRUPT.bits() as c_int;
+pub const TAO_PKT_FLAG_DISCARD: c_int = PacketFlags::DISCARD.bits() as c_int;
+
+// 编解码器能力标志位 (tao_codec_get_capabilities 返回值)
+pub const TAO_CODEC_CAPS_DELAY: u32 = CodecCapabilities::CAPS_DELAY.bits();
+pub const TAO_CODEC_CAPS_INTRA_ONLY: u32 = CodecCapabilities::CAPS_INTRA_ONLY.bits();
+pub const TAO_CODEC_CAPS_LOSSLESS: u32 = CodecCapabilities::CAPS_LOSSLESS.bits();
+pub const TAO_CODEC_CAPS_REORDER: u32 = CodecCapabilities::CAPS_REORDER.bits();
+pub const TAO_CODEC_CAPS_THREAD_SAFE: u32 = CodecCapabilities::CAPS_THREAD_SAFE.bits();
+
+// Seek 标志位 (tao_format_seek 的 flags 参数, 可按位或组合)
+pub const TAO_SEEK_BACKWARD: c_int = 1;
+pub const TAO_SEEK_BYTE: c_int = 2;
+pub const TAO_SEEK_ANY: c_int = 4;
 
 // =============================================================================
 //  opaque 指针类型
@@ -44,6 +89,14 @@ pub struct TaoFormatContext {
     pub(crate) demuxer: Box<dyn tao_format::Demuxer>,
 }
 
+/// 封装上下文 (封装 muxer + io + 输出流列表)
+pub struct TaoMuxContext {
+    pub(crate) io: IoContext,
+    pub(crate) muxer: Box<dyn tao_format::Muxer>,
+    pub(crate) streams: Vec<Stream>,
+    pub(crate) header_written: bool,
+}
+
 /// 编解码器上下文 (封装 decoder 或 encoder)
 pub enum TaoCodecContextInner {
     Decoder(Box<dyn Decoder>),
@@ -54,6 +107,19 @@ pub struct TaoCodecContext {
     pub(crate) inner: TaoCodecContextInner,
 }
 
+/// 编解码器信息 (tao_codec_list 返回的数组元素)
+#[repr(C)]
+pub struct TaoCodecInfo {
+    /// 编解码器 ID (见 CodecId 映射)
+    pub codec_id: c_int,
+    /// 编解码器规范名称 (静态字符串)
+    pub name: *const c_char,
+    /// 媒体类型 (与 tao_format_get_stream_media_type 相同)
+    pub media_type: c_int,
+    /// 能力标志 (TAO_CODEC_CAP_DECODE | TAO_CODEC_CAP_ENCODE)
+    pub capabilities: c_int,
+}
+
 /// 压缩数据包
 pub struct TaoPacket(pub(crate) Packet);
 
@@ -106,6 +172,8 @@ fn codec_id_from_int(id: c_int) -> Option<CodecId> {
         31 => Some(CodecId::Webvtt),
         32 => Some(CodecId::DvdSubtitle),
         33 => Some(CodecId::HdmvPgsSubtitle),
+        34 => Some(CodecId::AdpcmImaWav),
+        35 => Some(CodecId::AdpcmMs),
         _ => None,
     }
 }
@@ -146,6 +214,8 @@ fn codec_id_to_int(id: CodecId) -> c_int {
         CodecId::Webvtt => 31,
         CodecId::DvdSubtitle => 32,
         CodecId::HdmvPgsSubtitle => 33,
+        CodecId::AdpcmImaWav => 34,
+        CodecId::AdpcmMs => 35,
         _ => 0, // 未知编解码器映射到 None
     }
 }
@@ -157,14 +227,7 @@ fn media_type_to_int(mt: MediaType) -> c_int {
         MediaType::Subtitle => 2,
         MediaType::Data => 3,
         MediaType::Attachment => 4,
-    }
-}
-
-fn tao_error_to_int(e: &TaoError) -> c_int {
-    match e {
-        TaoError::Eof => TAO_EOF,
-        TaoError::NeedMoreData => TAO_NEED_MORE_DATA,
-        _ => TAO_ERROR,
+        MediaType::Unknown(_) => 5,
     }
 }
 
@@ -210,14 +273,16 @@ pub unsafe extern "C" fn tao_build_info() -> *const c_char {
 
 /// 初始化 Tao 库
 ///
-/// 在使用其他 Tao 函数前必须先调用此函数. 可安全多次调用.
+/// 初始化进程级编解码器/容器格式注册表. 未调用时在首次使用时自动初始化.
+/// 可安全多次调用, 也可在多线程中并发调用.
 ///
 /// # Safety
 ///
 /// 无特殊安全要求.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_init() {
-    // 初始化全局注册表、日志系统等 (当前为空)
+    registry::codecs();
+    registry::formats();
 }
 
 /// 关闭 Tao 库, 释放全局资源
@@ -227,7 +292,85 @@ pub unsafe extern "C" fn tao_init() {
 /// 无特殊安全要求.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_shutdown() {
-    // 释放全局资源 (当前为空)
+    // 全局注册表为只读且随进程存活, 无需释放
+}
+
+/// 设置日志回调
+///
+/// 注册后 Tao 内部日志将以 (级别, 消息) 形式转交给回调:
+/// 级别 0=error, 1=warn, 2=info, 3=debug, 4=trace, 消息为 null 结尾的 UTF-8 字符串,
+/// 仅在回调期间有效. 传入 null 清除回调, 此时不输出任何日志 (默认行为).
+///
+/// 回调可能在任意调用 Tao 的线程中被调用, 调用方需保证其线程安全.
+///
+/// 返回 TAO_OK; 若宿主进程已安装其他 Rust logger 则返回 TAO_ERROR.
+///
+/// # Safety
+///
+/// cb 若非 null 必须在整个注册期间保持有效.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_set_log_callback(cb: Option<TaoLogCallback>) -> c_int {
+    if logging::set_log_callback(cb) {
+        TAO_OK
+    } else {
+        error::set_last_error("宿主进程已安装其他 logger, 无法注册日志回调");
+        TAO_ERROR
+    }
+}
+
+// =============================================================================
+// 错误信息
+// =============================================================================
+
+/// 获取错误码的静态描述
+///
+/// 未知错误码返回 "未知错误码". 返回的字符串指针为静态分配, 无需释放.
+///
+/// # Safety
+///
+/// 返回的指针在程序生命周期内有效.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_strerror(code: c_int) -> *const c_char {
+    error::strerror(code).as_ptr()
+}
+
+/// 获取当前线程最近一次失败的详细错误信息
+///
+/// 任意 `tao_*` 函数失败 (EOF / NEED_MORE_DATA 除外) 时更新, 成功调用不会清除.
+/// 尚无错误时返回 null.
+///
+/// # Safety
+///
+/// 返回的指针仅在当前线程下一次失败的 `tao_*` 调用前有效, 无需释放.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_last_error_message() -> *const c_char {
+    error::last_error_ptr()
+}
+
+/// 获取当前线程最近一次失败的详细错误信息
+///
+/// 与 `tao_last_error_message` 相同, 命名对齐 `tao_error_code_from_last`.
+/// 尚无错误时返回 null.
+///
+/// # Safety
+///
+/// 返回的指针仅在当前线程下一次失败的 `tao_*` 调用前有效, 无需释放.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_get_last_error() -> *const c_char {
+    error::last_error_ptr()
+}
+
+/// 获取当前线程最近一次失败对应的细分错误码 (TAO_ERROR_*)
+///
+/// 函数返回值无法携带错误码时 (如返回 null 指针的 `tao_format_open_input`),
+/// 可通过本函数区分失败原因. 尚无错误时返回 TAO_OK.
+///
+/// # Safety
+///
+/// 无特殊要求, 可在任意线程调用.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_error_code_from_last() -> c_int {
+    error::last_error_code()
 }
 
 // =============================================================================
@@ -242,26 +385,35 @@ pub unsafe extern "C" fn tao_shutdown() {
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_format_open_input(filename: *const c_char) -> *mut TaoFormatContext {
     if filename.is_null() {
+        error::invalid_argument("filename 为空");
         return ptr::null_mut();
     }
 
     let filename_str = match unsafe { CStr::from_ptr(filename) }.to_str() {
         Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
+        Err(_) => {
+            error::invalid_argument("filename 不是有效的 UTF-8 字符串");
+            return ptr::null_mut();
+        }
     };
 
     let io = match IoContext::open_read(filename_str) {
         Ok(io) => io,
-        Err(_) => return ptr::null_mut(),
+        Err(e) => {
+            error::record_with_context(&e, &format!("无法打开 {filename_str}"));
+            return ptr::null_mut();
+        }
     };
 
-    let mut format_registry = FormatRegistry::new();
-    tao_format::register_all(&mut format_registry);
+    let format_registry = registry::formats();
 
     let mut io = io;
     let demuxer = match format_registry.open_input(&mut io, Some(filename_str)) {
         Ok(d) => d,
-        Err(_) => return ptr::null_mut(),
+        Err(e) => {
+            error::record_with_context(&e, &format!("无法识别 {filename_str} 的格式"));
+            return ptr::null_mut();
+        }
     };
 
     let ctx = TaoFormatContext { io, demuxer };
@@ -281,13 +433,13 @@ pub unsafe extern "C" fn tao_format_read_packet(
     packet: *mut *mut TaoPacket,
 ) -> c_int {
     if ctx.is_null() || packet.is_null() {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 或 packet 为空");
     }
 
     let ctx = unsafe { &mut *ctx };
     let pkt = match ctx.demuxer.read_packet(&mut ctx.io) {
         Ok(p) => p,
-        Err(e) => return tao_error_to_int(&e),
+        Err(e) => return error::record(&e),
     };
 
     let tao_pkt = Box::new(TaoPacket(pkt));
@@ -305,6 +457,7 @@ pub unsafe extern "C" fn tao_format_read_packet(
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_format_get_stream_count(ctx: *const TaoFormatContext) -> c_int {
     if ctx.is_null() {
+        error::invalid_argument("ctx 为空");
         return -1;
     }
     let ctx = unsafe { &*ctx };
@@ -322,20 +475,21 @@ pub unsafe extern "C" fn tao_format_get_stream_codec_id(
     stream_index: c_int,
 ) -> c_int {
     if ctx.is_null() || stream_index < 0 {
+        error::invalid_argument("ctx 为空 或 stream_index 为负数");
         return -1;
     }
     let ctx = unsafe { &*ctx };
     let streams = ctx.demuxer.streams();
     let idx = stream_index as usize;
     if idx >= streams.len() {
-        return -1;
+        return error::record(&TaoError::StreamNotFound(idx));
     }
     codec_id_to_int(streams[idx].codec_id)
 }
 
 /// 获取指定流的媒体类型
 ///
-/// 返回: 0=Video, 1=Audio, 2=Subtitle, 3=Data, 4=Attachment
+/// 返回: 0=Video, 1=Audio, 2=Subtitle, 3=Data, 4=Attachment, 5=Unknown
 ///
 /// # Safety
 ///
@@ -346,17 +500,56 @@ pub unsafe extern "C" fn tao_format_get_stream_media_type(
     stream_index: c_int,
 ) -> c_int {
     if ctx.is_null() || stream_index < 0 {
+        error::invalid_argument("ctx 为空 或 stream_index 为负数");
         return -1;
     }
     let ctx = unsafe { &*ctx };
     let streams = ctx.demuxer.streams();
     let idx = stream_index as usize;
     if idx >= streams.len() {
-        return -1;
+        return error::record(&TaoError::StreamNotFound(idx));
     }
     media_type_to_int(streams[idx].media_type)
 }
 
+/// 定位到指定时间点
+///
+/// timestamp 以目标流的 time_base 为单位. flags 为 TAO_SEEK_* 的按位或:
+/// TAO_SEEK_BACKWARD 定位到目标之前最近的关键帧, TAO_SEEK_ANY 允许定位到非关键帧,
+/// TAO_SEEK_BYTE 表示 timestamp 为字节位置.
+///
+/// # Safety
+///
+/// ctx 必须为由 tao_format_open_input 返回的有效指针.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_format_seek(
+    ctx: *mut TaoFormatContext,
+    stream_index: c_int,
+    timestamp: i64,
+    flags: c_int,
+) -> c_int {
+    if ctx.is_null() || stream_index < 0 {
+        return error::invalid_argument("ctx 为空 或 stream_index 为负数");
+    }
+    if flags & !(TAO_SEEK_BACKWARD | TAO_SEEK_BYTE | TAO_SEEK_ANY) != 0 {
+        return error::invalid_argument(&format!("未知的 seek 标志: {flags:#x}"));
+    }
+    let ctx = unsafe { &mut *ctx };
+    let idx = stream_index as usize;
+    if idx >= ctx.demuxer.streams().len() {
+        return error::record(&TaoError::StreamNotFound(idx));
+    }
+    let seek_flags = SeekFlags {
+        backward: flags & TAO_SEEK_BACKWARD != 0,
+        byte: flags & TAO_SEEK_BYTE != 0,
+        any: flags & TAO_SEEK_ANY != 0,
+    };
+    match ctx.demuxer.seek(&mut ctx.io, idx, timestamp, seek_flags) {
+        Ok(()) => TAO_OK,
+        Err(e) => error::record(&e),
+    }
+}
+
 /// 关闭格式上下文并释放资源
 ///
 /// # Safety
@@ -370,10 +563,326 @@ pub unsafe extern "C" fn tao_format_close(ctx: *mut TaoFormatContext) {
     }
 }
 
+// =============================================================================
+// Format (Muxer)
+// =============================================================================
+
+/// 创建输出文件并按扩展名选择封装格式
+///
+/// 返回的上下文需依次调用 tao_format_add_stream / tao_format_write_header /
+/// tao_format_write_packet / tao_format_write_trailer, 最后用
+/// tao_format_close_output 释放. 失败返回 null.
+///
+/// # Safety
+///
+/// filename 必须指向有效的以 null 结尾的 C 字符串.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_format_open_output(filename: *const c_char) -> *mut TaoMuxContext {
+    if filename.is_null() {
+        error::invalid_argument("filename 为空");
+        return ptr::null_mut();
+    }
+
+    let filename_str = match unsafe { CStr::from_ptr(filename) }.to_str() {
+        Ok(s) => s,
+        Err(_) => {
+            error::invalid_argument("filename 不是有效的 UTF-8 字符串");
+            return ptr::null_mut();
+        }
+    };
+
+    let Some(format_id) = FormatId::from_filename(filename_str) else {
+        error::record(&TaoError::FormatNotFound(format!(
+            "无法根据文件名推断输出格式: {filename_str}"
+        )));
+        return ptr::null_mut();
+    };
+
+    let format_registry = registry::formats();
+    let muxer = match format_registry.create_muxer(format_id) {
+        Ok(m) => m,
+        Err(e) => {
+            error::record(&e);
+            return ptr::null_mut();
+        }
+    };
+
+    let io = match IoContext::open_read_write(filename_str) {
+        Ok(io) => io,
+        Err(e) => {
+            error::record(&e);
+            return ptr::null_mut();
+        }
+    };
+
+    let ctx = TaoMuxContext {
+        io,
+        muxer,
+        streams: Vec::new(),
+        header_written: false,
+    };
+    Box::into_raw(Box::new(ctx))
+}
+
+/// 添加输出流
+///
+/// 媒体类型由 codec_id 推断. 音频流使用 sample_rate/channels, 视频流使用
+/// width/height, 不相关的参数传 0 即可. 时间基为 time_base_num/time_base_den,
+/// 写入的数据包时间戳以此为单位. extra_data 可为 null.
+///
+/// 成功返回新流的索引 (>= 0), 失败返回负的错误码. 必须在 tao_format_write_header 之前调用.
+///
+/// # Safety
+///
+/// ctx 必须为由 tao_format_open_output 返回的有效指针.
+/// extra_data 若非 null 则必须指向至少 extra_data_size 字节的有效内存.
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn tao_format_add_stream(
+    ctx: *mut TaoMuxContext,
+    codec_id: c_int,
+    time_base_num: c_int,
+    time_base_den: c_int,
+    sample_rate: c_int,
+    channels: c_int,
+    width: c_int,
+    height: c_int,
+    extra_data: *const u8,
+    extra_data_size: c_int,
+) -> c_int {
+    if ctx.is_null() {
+        return error::invalid_argument("ctx 为空");
+    }
+    let ctx = unsafe { &mut *ctx };
+    if ctx.header_written {
+        return error::invalid_argument("文件头已写入, 不能再添加流");
+    }
+    let Some(id) = codec_id_from_int(codec_id) else {
+        return error::invalid_argument(&format!("未知的编解码器 ID: {codec_id}"));
+    };
+    if time_base_num <= 0 || time_base_den <= 0 {
+        return error::invalid_argument("时间基必须为正数");
+    }
+
+    let media_type = id.media_type();
+    let params = match media_type {
+        MediaType::Audio => {
+            if sample_rate <= 0 || channels <= 0 {
+                return error::invalid_argument("音频流的采样率/声道数必须为正数");
+            }
+            StreamParams::Audio(AudioStreamParams {
+                sample_rate: sample_rate as u32,
+                channel_layout: ChannelLayout::from_channels(channels as u32),
+                sample_format: SampleFormat::None,
+                bit_rate: 0,
+                frame_size: 0,
+                block_align: 0,
+            })
+        }
+        MediaType::Video => {
+            if width <= 0 || height <= 0 {
+                return error::invalid_argument("视频流的宽高必须为正数");
+            }
+            StreamParams::Video(VideoStreamParams {
+                width: width as u32,
+                height: height as u32,
+                pixel_format: PixelFormat::Yuv420p,
+                frame_rate: Rational::new(0, 1),
+                sample_aspect_ratio: Rational::new(1, 1),
+                bit_rate: 0,
+                color_space: Default::default(),
+                color_range: Default::default(),
+            })
+        }
+        MediaType::Subtitle => StreamParams::Subtitle,
+        _ => StreamParams::Other,
+    };
+
+    let extra = if extra_data.is_null() || extra_data_size <= 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(extra_data, extra_data_size as usize).to_vec() }
+    };
+
+    let index = ctx.streams.len();
+    ctx.streams.push(Stream {
+        index,
+        media_type,
+        codec_id: id,
+        time_base: Rational::new(time_base_num, time_base_den),
+        duration: -1,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data: extra,
+        params,
+        metadata: Vec::new(),
+    });
+    index as c_int
+}
+
+/// 写入文件头
+///
+/// # Safety
+///
+/// ctx 必须为由 tao_format_open_output 返回的有效指针.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_format_write_header(ctx: *mut TaoMuxContext) -> c_int {
+    if ctx.is_null() {
+        return error::invalid_argument("ctx 为空");
+    }
+    let ctx = unsafe { &mut *ctx };
+    if ctx.header_written {
+        return error::invalid_argument("文件头已写入");
+    }
+    if ctx.streams.is_empty() {
+        return error::invalid_argument("尚未添加任何流");
+    }
+    match ctx.muxer.write_header(&mut ctx.io, &ctx.streams) {
+        Ok(()) => {
+            ctx.header_written = true;
+            TAO_OK
+        }
+        Err(e) => error::record(&e),
+    }
+}
+
+/// 写入一个数据包到指定输出流
+///
+/// 数据包的 stream_index 会被替换为 stream_index 参数, 原数据包不被修改,
+/// 仍由调用方负责释放.
+///
+/// # Safety
+///
+/// ctx 必须为由 tao_format_open_output 返回的有效指针, packet 必须为有效的 TaoPacket.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_format_write_packet(
+    ctx: *mut TaoMuxContext,
+    stream_index: c_int,
+    packet: *const TaoPacket,
+) -> c_int {
+    if ctx.is_null() || packet.is_null() {
+        return error::invalid_argument("ctx 或 packet 为空");
+    }
+    let ctx = unsafe { &mut *ctx };
+    if !ctx.header_written {
+        return error::invalid_argument("请先调用 tao_format_write_header");
+    }
+    if stream_index < 0 || stream_index as usize >= ctx.streams.len() {
+        return error::record(&TaoError::StreamNotFound(stream_index.max(0) as usize));
+    }
+
+    let mut pkt = unsafe { (*packet).0.clone() };
+    pkt.stream_index = stream_index as usize;
+    match ctx.muxer.write_packet(&mut ctx.io, &pkt) {
+        Ok(()) => TAO_OK,
+        Err(e) => error::record(&e),
+    }
+}
+
+/// 写入文件尾 (完成时长/索引等回填)
+///
+/// # Safety
+///
+/// ctx 必须为由 tao_format_open_output 返回的有效指针.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_format_write_trailer(ctx: *mut TaoMuxContext) -> c_int {
+    if ctx.is_null() {
+        return error::invalid_argument("ctx 为空");
+    }
+    let ctx = unsafe { &mut *ctx };
+    if !ctx.header_written {
+        return error::invalid_argument("请先调用 tao_format_write_header");
+    }
+    match ctx.muxer.write_trailer(&mut ctx.io) {
+        Ok(()) => TAO_OK,
+        Err(e) => error::record(&e),
+    }
+}
+
+/// 关闭封装上下文并释放资源
+///
+/// 不会自动写入文件尾, 未调用 tao_format_write_trailer 时输出文件可能不完整.
+///
+/// # Safety
+///
+/// ctx 必须为由 tao_format_open_output 返回的有效指针, 调用后不可再使用.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_format_close_output(ctx: *mut TaoMuxContext) {
+    if !ctx.is_null() {
+        let _ = unsafe { Box::from_raw(ctx) };
+    }
+}
+
 // =============================================================================
 // Codec (Decoder/Encoder)
 // =============================================================================
 
+/// 按名称查找编解码器 ID
+///
+/// 名称不区分大小写, 可为编解码器规范名称 (如 "aac", "hevc") 或编码器/解码器注册名称.
+/// 未找到时返回 TAO_ERROR_CODEC_NOT_FOUND.
+///
+/// # Safety
+///
+/// name 必须为有效的 C 字符串指针.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_codec_id_from_name(name: *const c_char) -> c_int {
+    if name.is_null() {
+        return error::invalid_argument("name 为空");
+    }
+    let Ok(name) = (unsafe { CStr::from_ptr(name) }).to_str() else {
+        return error::invalid_argument("name 不是有效的 UTF-8 字符串");
+    };
+
+    let registry = registry::codecs();
+    match registry.find_codec_id_by_name(name) {
+        Some(id) => codec_id_to_int(id),
+        None => error::record(&TaoError::CodecNotFound(format!(
+            "未找到名为 {name} 的编解码器"
+        ))),
+    }
+}
+
+/// 获取编解码器 ID 对应的规范名称
+///
+/// 未知 ID 返回 null. 返回的字符串指针为静态分配, 无需释放.
+///
+/// # Safety
+///
+/// 返回的指针在程序生命周期内有效.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_codec_name_from_id(codec_id: c_int) -> *const c_char {
+    // C 侧 ID 从 0 开始连续编号, 按 ID 下标缓存 NUL 结尾的名称
+    static NAMES: OnceLock<Vec<CString>> = OnceLock::new();
+    let names = NAMES.get_or_init(|| {
+        (0..)
+            .map_while(codec_id_from_int)
+            .map(|id| CString::new(id.name()).unwrap_or_default())
+            .collect()
+    });
+    usize::try_from(codec_id)
+        .ok()
+        .and_then(|i| names.get(i))
+        .map_or(ptr::null(), |n| n.as_ptr())
+}
+
+/// 列出已编译的编解码器
+///
+/// 返回按编解码器 ID 排序的只读数组, 元素个数写入 *count_out (可为 null).
+/// 数组与其中的名称字符串为静态分配, 无需释放.
+///
+/// # Safety
+///
+/// count_out 若非 null 必须指向有效的 int 变量.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_codec_list(count_out: *mut c_int) -> *const TaoCodecInfo {
+    let list = registry::codec_list();
+    if !count_out.is_null() {
+        unsafe { *count_out = list.infos.len() as c_int };
+    }
+    list.infos.as_ptr()
+}
+
 /// 创建解码器
 ///
 /// # Safety
@@ -383,15 +892,20 @@ pub unsafe extern "C" fn tao_format_close(ctx: *mut TaoFormatContext) {
 pub unsafe extern "C" fn tao_codec_create_decoder(codec_id: c_int) -> *mut TaoCodecContext {
     let id = match codec_id_from_int(codec_id) {
         Some(id) => id,
-        None => return ptr::null_mut(),
+        None => {
+            error::invalid_argument(&format!("未知的编解码器 ID: {codec_id}"));
+            return ptr::null_mut();
+        }
     };
 
-    let mut registry = CodecRegistry::new();
-    tao_codec::register_all(&mut registry);
+    let registry = registry::codecs();
 
     let decoder = match registry.create_decoder(id) {
         Ok(d) => d,
-        Err(_) => return ptr::null_mut(),
+        Err(e) => {
+            error::record(&e);
+            return ptr::null_mut();
+        }
     };
 
     let ctx = TaoCodecContext {
@@ -400,13 +914,15 @@ pub unsafe extern "C" fn tao_codec_create_decoder(codec_id: c_int) -> *mut TaoCo
     Box::into_raw(Box::new(ctx))
 }
 
-/// 打开解码器
+/// 打开解码器 (音频参数)
 ///
 /// extra_data 可为 null (extra_data_size 此时应为 0).
+/// 仅能传入音频参数, 打开视频解码器请使用 tao_codec_open_video_decoder.
 ///
 /// # Safety
 ///
 /// extra_data 若非 null 则必须指向至少 extra_data_size 字节的有效内存.
+#[deprecated(note = "仅为 ABI 兼容保留, 视频解码器请使用 tao_codec_open_video_decoder")]
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_codec_open_decoder(
     ctx: *mut TaoCodecContext,
@@ -416,35 +932,139 @@ pub unsafe extern "C" fn tao_codec_open_decoder(
     extra_data_size: c_int,
 ) -> c_int {
     if ctx.is_null() || sample_rate <= 0 || channels <= 0 {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 为空或采样率/声道数不为正数");
     }
 
     let ctx = unsafe { &mut *ctx };
     let TaoCodecContextInner::Decoder(decoder) = &mut ctx.inner else {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 不是解码器上下文");
     };
 
-    let extra = if extra_data.is_null() || extra_data_size <= 0 {
-        Vec::new()
-    } else {
-        unsafe { std::slice::from_raw_parts(extra_data, extra_data_size as usize).to_vec() }
+    let params = CodecParameters {
+        codec_id: decoder.codec_id(),
+        extra_data: unsafe { copy_extra_data(extra_data, extra_data_size) },
+        bit_rate: 0,
+        params: CodecParamsType::Audio(AudioCodecParams {
+            sample_rate: sample_rate as u32,
+            channel_layout: ChannelLayout::from_channels(channels as u32),
+            sample_format: SampleFormat::S16,
+            frame_size: 0,
+            block_align: 0,
+        }),
+    };
+
+    match decoder.open(&params) {
+        Ok(()) => TAO_OK,
+        Err(e) => error::record(&e),
+    }
+}
+
+/// 打开音频解码器
+///
+/// block_align 为容器给出的编码块字节数 (ADPCM 等按块编码的格式需要, 其余可传 0).
+/// extra_data 可为 null (extra_data_size 此时应为 0).
+///
+/// # Safety
+///
+/// ctx 必须为由 tao_codec_create_decoder 返回的有效指针.
+/// extra_data 若非 null 则必须指向至少 extra_data_size 字节的有效内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_codec_open_audio_decoder(
+    ctx: *mut TaoCodecContext,
+    sample_rate: c_int,
+    channels: c_int,
+    block_align: c_int,
+    extra_data: *const u8,
+    extra_data_size: c_int,
+) -> c_int {
+    if ctx.is_null() || sample_rate <= 0 || channels <= 0 || block_align < 0 {
+        return error::invalid_argument("ctx 为空或采样率/声道数/块大小无效");
+    }
+
+    let ctx = unsafe { &mut *ctx };
+    let TaoCodecContextInner::Decoder(decoder) = &mut ctx.inner else {
+        return error::invalid_argument("ctx 不是解码器上下文");
     };
 
     let params = CodecParameters {
         codec_id: decoder.codec_id(),
-        extra_data: extra,
+        extra_data: unsafe { copy_extra_data(extra_data, extra_data_size) },
         bit_rate: 0,
         params: CodecParamsType::Audio(AudioCodecParams {
             sample_rate: sample_rate as u32,
             channel_layout: ChannelLayout::from_channels(channels as u32),
             sample_format: SampleFormat::S16,
             frame_size: 0,
+            block_align: block_align as u32,
+        }),
+    };
+
+    match decoder.open(&params) {
+        Ok(()) => TAO_OK,
+        Err(e) => error::record(&e),
+    }
+}
+
+/// 打开视频解码器
+///
+/// pixel_format 映射同 tao_scale_context_create, 未知 ID 返回 TAO_ERROR_INVALID_ARGUMENT.
+/// extra_data 为编解码器私有数据 (如 H.264 的 avcC), 可为 null (extra_data_size 此时应为 0).
+///
+/// # Safety
+///
+/// ctx 必须为由 tao_codec_create_decoder 返回的有效指针.
+/// extra_data 若非 null 则必须指向至少 extra_data_size 字节的有效内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_codec_open_video_decoder(
+    ctx: *mut TaoCodecContext,
+    width: u32,
+    height: u32,
+    pixel_format: u32,
+    extra_data: *const u8,
+    extra_data_size: c_int,
+) -> c_int {
+    if ctx.is_null() {
+        return error::invalid_argument("ctx 为空");
+    }
+
+    let ctx = unsafe { &mut *ctx };
+    let TaoCodecContextInner::Decoder(decoder) = &mut ctx.inner else {
+        return error::invalid_argument("ctx 不是解码器上下文");
+    };
+    let pixel_format = match pixel_format_from_id(pixel_format) {
+        Ok(pf) => pf,
+        Err(code) => return code,
+    };
+
+    let params = CodecParameters {
+        codec_id: decoder.codec_id(),
+        extra_data: unsafe { copy_extra_data(extra_data, extra_data_size) },
+        bit_rate: 0,
+        params: CodecParamsType::Video(VideoCodecParams {
+            width,
+            height,
+            pixel_format,
+            frame_rate: Rational::UNDEFINED,
+            sample_aspect_ratio: Rational::new(1, 1),
         }),
     };
 
     match decoder.open(&params) {
         Ok(()) => TAO_OK,
-        Err(e) => tao_error_to_int(&e),
+        Err(e) => error::record(&e),
+    }
+}
+
+/// 复制调用方传入的编解码器私有数据, null 或非正长度返回空
+///
+/// # Safety
+///
+/// data 若非 null 则必须指向至少 size 字节的有效内存.
+unsafe fn copy_extra_data(data: *const u8, size: c_int) -> Vec<u8> {
+    if data.is_null() || size <= 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(data, size as usize).to_vec() }
     }
 }
 
@@ -461,12 +1081,12 @@ pub unsafe extern "C" fn tao_codec_send_packet(
     packet: *const TaoPacket,
 ) -> c_int {
     if ctx.is_null() {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 为空");
     }
 
     let ctx = unsafe { &mut *ctx };
     let TaoCodecContextInner::Decoder(decoder) = &mut ctx.inner else {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 不是解码器上下文");
     };
 
     let pkt = if packet.is_null() {
@@ -477,7 +1097,7 @@ pub unsafe extern "C" fn tao_codec_send_packet(
 
     match decoder.send_packet(&pkt) {
         Ok(()) => TAO_OK,
-        Err(e) => tao_error_to_int(&e),
+        Err(e) => error::record(&e),
     }
 }
 
@@ -494,17 +1114,17 @@ pub unsafe extern "C" fn tao_codec_receive_frame(
     frame: *mut *mut TaoFrame,
 ) -> c_int {
     if ctx.is_null() || frame.is_null() {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 或 frame 为空");
     }
 
     let ctx = unsafe { &mut *ctx };
     let TaoCodecContextInner::Decoder(decoder) = &mut ctx.inner else {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 不是解码器上下文");
     };
 
     let f = match decoder.receive_frame() {
         Ok(f) => f,
-        Err(e) => return tao_error_to_int(&e),
+        Err(e) => return error::record(&e),
     };
 
     let tao_frame = Box::new(TaoFrame(f));
@@ -523,15 +1143,20 @@ pub unsafe extern "C" fn tao_codec_receive_frame(
 pub unsafe extern "C" fn tao_codec_create_encoder(codec_id: c_int) -> *mut TaoCodecContext {
     let id = match codec_id_from_int(codec_id) {
         Some(id) => id,
-        None => return ptr::null_mut(),
+        None => {
+            error::invalid_argument(&format!("未知的编解码器 ID: {codec_id}"));
+            return ptr::null_mut();
+        }
     };
 
-    let mut registry = CodecRegistry::new();
-    tao_codec::register_all(&mut registry);
+    let registry = registry::codecs();
 
     let encoder = match registry.create_encoder(id) {
         Ok(e) => e,
-        Err(_) => return ptr::null_mut(),
+        Err(e) => {
+            error::record(&e);
+            return ptr::null_mut();
+        }
     };
 
     let ctx = TaoCodecContext {
@@ -552,12 +1177,12 @@ pub unsafe extern "C" fn tao_codec_open_encoder(
     channels: c_int,
 ) -> c_int {
     if ctx.is_null() || sample_rate <= 0 || channels <= 0 {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 为空或采样率/声道数不为正数");
     }
 
     let ctx = unsafe { &mut *ctx };
     let TaoCodecContextInner::Encoder(encoder) = &mut ctx.inner else {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 不是编码器上下文");
     };
 
     let params = CodecParameters {
@@ -569,12 +1194,13 @@ pub unsafe extern "C" fn tao_codec_open_encoder(
             channel_layout: ChannelLayout::from_channels(channels as u32),
             sample_format: SampleFormat::S16,
             frame_size: 0,
+            block_align: 0,
         }),
     };
 
     match encoder.open(&params) {
         Ok(()) => TAO_OK,
-        Err(e) => tao_error_to_int(&e),
+        Err(e) => error::record(&e),
     }
 }
 
@@ -591,12 +1217,12 @@ pub unsafe extern "C" fn tao_codec_send_frame(
     frame: *const TaoFrame,
 ) -> c_int {
     if ctx.is_null() {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 为空");
     }
 
     let ctx = unsafe { &mut *ctx };
     let TaoCodecContextInner::Encoder(encoder) = &mut ctx.inner else {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 不是编码器上下文");
     };
 
     let frame_ref = if frame.is_null() {
@@ -607,7 +1233,7 @@ pub unsafe extern "C" fn tao_codec_send_frame(
 
     match encoder.send_frame(frame_ref) {
         Ok(()) => TAO_OK,
-        Err(e) => tao_error_to_int(&e),
+        Err(e) => error::record(&e),
     }
 }
 
@@ -624,17 +1250,17 @@ pub unsafe extern "C" fn tao_codec_receive_packet(
     packet: *mut *mut TaoPacket,
 ) -> c_int {
     if ctx.is_null() || packet.is_null() {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 或 packet 为空");
     }
 
     let ctx = unsafe { &mut *ctx };
     let TaoCodecContextInner::Encoder(encoder) = &mut ctx.inner else {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 不是编码器上下文");
     };
 
     let pkt = match encoder.receive_packet() {
         Ok(p) => p,
-        Err(e) => return tao_error_to_int(&e),
+        Err(e) => return error::record(&e),
     };
 
     let tao_pkt = Box::new(TaoPacket(pkt));
@@ -644,6 +1270,27 @@ pub unsafe extern "C" fn tao_codec_receive_packet(
     TAO_OK
 }
 
+/// 获取编解码器能力标志 (TAO_CODEC_CAPS_* 按位或)
+///
+/// ctx 为空时返回 0.
+///
+/// # Safety
+///
+/// ctx 若非 null 必须为有效的 TaoCodecContext.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_codec_get_capabilities(ctx: *const TaoCodecContext) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+
+    let ctx = unsafe { &*ctx };
+    let caps = match &ctx.inner {
+        TaoCodecContextInner::Decoder(decoder) => decoder.codec_capabilities(),
+        TaoCodecContextInner::Encoder(encoder) => encoder.codec_capabilities(),
+    };
+    caps.bits()
+}
+
 /// 关闭编解码器上下文
 ///
 /// # Safety
@@ -668,6 +1315,7 @@ pub unsafe extern "C" fn tao_codec_close(ctx: *mut TaoCodecContext) {
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_packet_data(pkt: *const TaoPacket) -> *const u8 {
     if pkt.is_null() {
+        error::invalid_argument("pkt 为空");
         return ptr::null();
     }
     let pkt = unsafe { &*pkt };
@@ -687,6 +1335,7 @@ pub unsafe extern "C" fn tao_packet_data(pkt: *const TaoPacket) -> *const u8 {
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_packet_size(pkt: *const TaoPacket) -> c_int {
     if pkt.is_null() {
+        error::invalid_argument("pkt 为空");
         return -1;
     }
     unsafe { (*pkt).0.size() as c_int }
@@ -700,6 +1349,7 @@ pub unsafe extern "C" fn tao_packet_size(pkt: *const TaoPacket) -> c_int {
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_packet_pts(pkt: *const TaoPacket) -> i64 {
     if pkt.is_null() {
+        error::invalid_argument("pkt 为空");
         return -1;
     }
     unsafe { (*pkt).0.pts }
@@ -713,11 +1363,28 @@ pub unsafe extern "C" fn tao_packet_pts(pkt: *const TaoPacket) -> i64 {
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_packet_stream_index(pkt: *const TaoPacket) -> c_int {
     if pkt.is_null() {
+        error::invalid_argument("pkt 为空");
         return -1;
     }
     unsafe { (*pkt).0.stream_index as c_int }
 }
 
+/// 获取数据包标志位
+///
+/// 位定义: TAO_PKT_FLAG_KEY (关键帧), TAO_PKT_FLAG_CORRUPT (数据损坏),
+/// TAO_PKT_FLAG_DISCARD (解码后丢弃). pkt 为 null 时返回 0.
+///
+/// # Safety
+///
+/// pkt 必须为有效的 TaoPacket 指针.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_packet_flags(pkt: *const TaoPacket) -> c_int {
+    if pkt.is_null() {
+        return 0;
+    }
+    unsafe { (*pkt).0.flags.bits() as c_int }
+}
+
 /// 释放数据包
 ///
 /// # Safety
@@ -742,7 +1409,7 @@ pub unsafe extern "C" fn tao_frame_is_audio(frame: *const TaoFrame) -> c_int {
     }
     match unsafe { &(*frame).0 } {
         Frame::Audio(_) => 1,
-        Frame::Video(_) => 0,
+        Frame::Video(_) | Frame::Subtitle(_) => 0,
     }
 }
 
@@ -758,7 +1425,7 @@ pub unsafe extern "C" fn tao_frame_is_video(frame: *const TaoFrame) -> c_int {
     }
     match unsafe { &(*frame).0 } {
         Frame::Video(_) => 1,
-        Frame::Audio(_) => 0,
+        Frame::Audio(_) | Frame::Subtitle(_) => 0,
     }
 }
 
@@ -770,11 +1437,12 @@ pub unsafe extern "C" fn tao_frame_is_video(frame: *const TaoFrame) -> c_int {
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_frame_nb_samples(frame: *const TaoFrame) -> c_int {
     if frame.is_null() {
+        error::invalid_argument("frame 为空");
         return -1;
     }
     match unsafe { &(*frame).0 } {
         Frame::Audio(a) => a.nb_samples as c_int,
-        Frame::Video(_) => 0,
+        Frame::Video(_) | Frame::Subtitle(_) => 0,
     }
 }
 
@@ -786,11 +1454,12 @@ pub unsafe extern "C" fn tao_frame_nb_samples(frame: *const TaoFrame) -> c_int {
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_frame_sample_rate(frame: *const TaoFrame) -> c_int {
     if frame.is_null() {
+        error::invalid_argument("frame 为空");
         return -1;
     }
     match unsafe { &(*frame).0 } {
         Frame::Audio(a) => a.sample_rate as c_int,
-        Frame::Video(_) => 0,
+        Frame::Video(_) | Frame::Subtitle(_) => 0,
     }
 }
 
@@ -802,11 +1471,12 @@ pub unsafe extern "C" fn tao_frame_sample_rate(frame: *const TaoFrame) -> c_int
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_frame_width(frame: *const TaoFrame) -> c_int {
     if frame.is_null() {
+        error::invalid_argument("frame 为空");
         return -1;
     }
     match unsafe { &(*frame).0 } {
         Frame::Video(v) => v.width as c_int,
-        Frame::Audio(_) => 0,
+        Frame::Audio(_) | Frame::Subtitle(_) => 0,
     }
 }
 
@@ -818,11 +1488,56 @@ pub unsafe extern "C" fn tao_frame_width(frame: *const TaoFrame) -> c_int {
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_frame_height(frame: *const TaoFrame) -> c_int {
     if frame.is_null() {
+        error::invalid_argument("frame 为空");
         return -1;
     }
     match unsafe { &(*frame).0 } {
         Frame::Video(v) => v.height as c_int,
-        Frame::Audio(_) => 0,
+        Frame::Audio(_) | Frame::Subtitle(_) => 0,
+    }
+}
+
+/// 获取视频帧色彩空间. 音频帧返回 -1.
+///
+/// 取值为 ISO/IEC 23091-2 MatrixCoefficients 码点 (与 FFmpeg `AVColorSpace` 一致):
+/// 0=RGB, 1=BT.709, 2=未指定, 5=BT.470BG, 6=SMPTE 170M (BT.601), 7=SMPTE 240M,
+/// 9=BT.2020 NCL, 10=BT.2020 CL.
+///
+/// # Safety
+///
+/// frame 必须为有效的 TaoFrame 指针.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_frame_color_space(frame: *const TaoFrame) -> c_int {
+    if frame.is_null() {
+        error::invalid_argument("frame 为空");
+        return -1;
+    }
+    match unsafe { &(*frame).0 } {
+        Frame::Video(v) => v.color_space.to_iso_code() as c_int,
+        Frame::Audio(_) | Frame::Subtitle(_) => -1,
+    }
+}
+
+/// 获取视频帧色彩范围. 音频帧返回 -1.
+///
+/// 取值与 FFmpeg `AVColorRange` 一致: 0=未指定, 1=有限范围 (TV), 2=完整范围 (PC).
+///
+/// # Safety
+///
+/// frame 必须为有效的 TaoFrame 指针.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_frame_color_range(frame: *const TaoFrame) -> c_int {
+    if frame.is_null() {
+        error::invalid_argument("frame 为空");
+        return -1;
+    }
+    match unsafe { &(*frame).0 } {
+        Frame::Video(v) => match v.color_range {
+            ColorRange::Unspecified => 0,
+            ColorRange::Limited => 1,
+            ColorRange::Full => 2,
+        },
+        Frame::Audio(_) | Frame::Subtitle(_) => -1,
     }
 }
 
@@ -837,6 +1552,7 @@ pub unsafe extern "C" fn tao_frame_height(frame: *const TaoFrame) -> c_int {
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_frame_data(frame: *const TaoFrame, plane: c_int) -> *const u8 {
     if frame.is_null() || plane < 0 {
+        error::invalid_argument("frame 为空 或 plane 为负数");
         return ptr::null();
     }
     let frame = unsafe { &(*frame).0 };
@@ -844,6 +1560,7 @@ pub unsafe extern "C" fn tao_frame_data(frame: *const TaoFrame, plane: c_int) ->
     let data = match frame {
         Frame::Video(v) => v.data.get(plane_idx),
         Frame::Audio(a) => a.data.get(plane_idx),
+        Frame::Subtitle(_) => None,
     };
     match data {
         Some(d) if !d.is_empty() => d.as_ptr(),
@@ -859,6 +1576,7 @@ pub unsafe extern "C" fn tao_frame_data(frame: *const TaoFrame, plane: c_int) ->
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_frame_linesize(frame: *const TaoFrame, plane: c_int) -> c_int {
     if frame.is_null() || plane < 0 {
+        error::invalid_argument("frame 为空 或 plane 为负数");
         return -1;
     }
     let frame = unsafe { &(*frame).0 };
@@ -866,18 +1584,134 @@ pub unsafe extern "C" fn tao_frame_linesize(frame: *const TaoFrame, plane: c_int
     let linesize: Option<usize> = match frame {
         Frame::Video(v) => v.linesize.get(plane_idx).copied(),
         Frame::Audio(a) => a.data.get(plane_idx).map(|d| d.len()),
+        Frame::Subtitle(_) => None,
     };
     match linesize {
         Some(ls) => ls as c_int,
-        None => -1,
+        None => {
+            error::invalid_argument(&format!("平面索引越界: {plane}"));
+            -1
+        }
+    }
+}
+
+/// 分配音频帧
+///
+/// 采样数据初始化为 0, 可通过 tao_frame_fill_plane 写入. sample_format 映射同
+/// tao_resample_context_create. 失败 (含未知格式 ID) 返回 null. 返回的帧需使用 tao_frame_free 释放.
+///
+/// # Safety
+///
+/// 无特殊安全要求.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_frame_alloc_audio(
+    nb_samples: c_int,
+    sample_rate: c_int,
+    sample_format: u32,
+    channels: c_int,
+) -> *mut TaoFrame {
+    if nb_samples <= 0 || sample_rate <= 0 || channels <= 0 {
+        error::invalid_argument("采样数/采样率/声道数必须为正数");
+        return ptr::null_mut();
+    }
+    let Ok(sf) = sample_format_from_id(sample_format) else {
+        return ptr::null_mut();
+    };
+    if sf == SampleFormat::None {
+        error::invalid_argument("采样格式不能为 None");
+        return ptr::null_mut();
+    }
+
+    let layout = ChannelLayout::from_channels(channels as u32);
+    let mut af = AudioFrame::new(nb_samples as u32, sample_rate as u32, sf, layout);
+    let plane_channels = if sf.is_planar() { 1 } else { channels as usize };
+    let plane_size = nb_samples as usize * plane_channels * sf.bytes_per_sample() as usize;
+    for plane in &mut af.data {
+        *plane = vec![0u8; plane_size].into();
+    }
+    af.pts = 0;
+    af.time_base = Rational::new(1, sample_rate);
+    af.duration = nb_samples as i64;
+    Box::into_raw(Box::new(TaoFrame(Frame::Audio(af))))
+}
+
+/// 分配视频帧
+///
+/// 像素数据初始化为 0, 各平面紧凑排列 (linesize 可通过 tao_frame_linesize 查询).
+/// pixel_format 映射同 tao_scale_context_create. 失败 (含未知格式 ID) 返回 null.
+/// 返回的帧需使用 tao_frame_free 释放.
+///
+/// # Safety
+///
+/// 无特殊安全要求.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_frame_alloc_video(
+    width: c_int,
+    height: c_int,
+    pixel_format: u32,
+) -> *mut TaoFrame {
+    if width <= 0 || height <= 0 {
+        error::invalid_argument("宽度/高度必须为正数");
+        return ptr::null_mut();
     }
+    let (w, h) = (width as u32, height as u32);
+    let Ok(pf) = pixel_format_from_id(pixel_format) else {
+        return ptr::null_mut();
+    };
+    let mut vf = VideoFrame::new(w, h, pf);
+    for p in 0..vf.data.len() {
+        let (Some(ls), Some(ph)) = (pf.plane_linesize(p, w), pf.plane_height(p, h)) else {
+            error::invalid_argument(&format!("不支持的像素格式: {pf}"));
+            return ptr::null_mut();
+        };
+        vf.data[p] = vec![0u8; ls * ph].into();
+        vf.linesize[p] = ls;
+    }
+    vf.pts = 0;
+    Box::into_raw(Box::new(TaoFrame(Frame::Video(vf))))
+}
+
+/// 将数据复制到帧的指定平面
+///
+/// 从平面起始位置写入 size 字节, size 不能超过平面大小.
+///
+/// # Safety
+///
+/// frame 必须为有效的 TaoFrame 指针, data 必须指向至少 size 字节的有效内存.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_frame_fill_plane(
+    frame: *mut TaoFrame,
+    plane: c_int,
+    data: *const u8,
+    size: c_int,
+) -> c_int {
+    if frame.is_null() || data.is_null() || plane < 0 || size < 0 {
+        return error::invalid_argument("frame/data 为空或 plane/size 为负数");
+    }
+    let frame = unsafe { &mut (*frame).0 };
+    let planes = match frame {
+        Frame::Video(v) => &mut v.data,
+        Frame::Audio(a) => &mut a.data,
+        Frame::Subtitle(_) => return error::invalid_argument("字幕帧没有数据平面"),
+    };
+    let Some(dst) = planes.get_mut(plane as usize) else {
+        return error::invalid_argument(&format!("平面索引越界: {plane}"));
+    };
+    let size = size as usize;
+    if size > dst.len() {
+        return error::invalid_argument(&format!("数据大小 {size} 超过平面大小 {}", dst.len()));
+    }
+    let src = unsafe { std::slice::from_raw_parts(data, size) };
+    dst[..size].copy_from_slice(src);
+    TAO_OK
 }
 
 /// 释放帧
 ///
 /// # Safety
 ///
-/// frame 必须为由 tao_codec_receive_frame 返回的有效指针, 调用后不可再使用.
+/// frame 必须为由 tao_codec_receive_frame / tao_frame_alloc_audio / tao_frame_alloc_video
+/// 返回的有效指针, 调用后不可再使用.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn tao_frame_free(frame: *mut TaoFrame) {
     if !frame.is_null() {
@@ -891,8 +1725,8 @@ pub unsafe extern "C" fn tao_frame_free(frame: *mut TaoFrame) {
 
 /// 创建缩放上下文
 ///
-/// src_format 和 dst_format 为像素格式 ID (与 tao-core PixelFormat 对应, 此处简化用 u32).
-/// 常用: 0=Yuv420p, 1=Rgb24 等. 具体映射见 tao-core pixel_format.
+/// src_format 和 dst_format 为像素格式 ID (即 tao-core `PixelFormat::to_u32`).
+/// 常用: 0=Yuv420p, 1=Rgb24, 2=Bgr24, 3=Yuv422p, 4=Yuv444p. 未知 ID 返回 null.
 ///
 /// # Safety
 ///
@@ -906,8 +1740,12 @@ pub unsafe extern "C" fn tao_scale_context_create(
     dst_height: u32,
     dst_format: u32,
 ) -> *mut TaoScaleContext {
-    let src_pf = pixel_format_from_u32(src_format);
-    let dst_pf = pixel_format_from_u32(dst_format);
+    let (Ok(src_pf), Ok(dst_pf)) = (
+        pixel_format_from_id(src_format),
+        pixel_format_from_id(dst_format),
+    ) else {
+        return ptr::null_mut();
+    };
     let ctx = ScaleContext::new(
         src_width,
         src_height,
@@ -922,7 +1760,7 @@ pub unsafe extern "C" fn tao_scale_context_create(
 
 /// 执行图像缩放/格式转换 (单平面格式如 RGB24)
 ///
-/// 适用于单平面格式. 多平面格式需使用其他接口.
+/// 适用于单平面格式. 多平面格式 (如 YUV420P) 请使用 tao_scale_scale_planar.
 ///
 /// # Safety
 ///
@@ -936,7 +1774,7 @@ pub unsafe extern "C" fn tao_scale_scale(
     dst_linesize: c_int,
 ) -> c_int {
     if ctx.is_null() || src_data.is_null() || dst_data.is_null() {
-        return TAO_ERROR;
+        return error::invalid_argument("ctx 或数据缓冲区为空");
     }
     let ctx = unsafe { &*ctx };
     let src_slice = unsafe {
@@ -958,10 +1796,100 @@ pub unsafe extern "C" fn tao_scale_scale(
         &[dst_linesize as usize],
     ) {
         Ok(()) => TAO_OK,
-        Err(_) => TAO_ERROR,
+        Err(e) => error::record(&e),
+    }
+}
+
+/// 执行多平面图像缩放/格式转换 (如 YUV420P/YUV422P/YUV444P)
+///
+/// src_planes/src_linesizes 为长度 num_src_planes 的数组, dst_planes/dst_linesizes
+/// 同理. 平面数必须不少于对应像素格式的平面数, 每个平面缓冲区大小至少为
+/// linesize * 该平面高度 (色度平面按格式下采样后的高度).
+///
+/// # Safety
+///
+/// 所有数组指针必须有效且长度与平面数一致, 各平面指针指向足够大小的缓冲区.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tao_scale_scale_planar(
+    ctx: *mut TaoScaleContext,
+    src_planes: *const *const u8,
+    src_linesizes: *const c_int,
+    num_src_planes: c_int,
+    dst_planes: *mut *mut u8,
+    dst_linesizes: *const c_int,
+    num_dst_planes: c_int,
+) -> c_int {
+    if ctx.is_null()
+        || src_planes.is_null()
+        || src_linesizes.is_null()
+        || dst_planes.is_null()
+        || dst_linesizes.is_null()
+    {
+        return error::invalid_argument("ctx 或平面数组为空");
+    }
+    let ctx = unsafe { &(*ctx).0 };
+    let src_count = ctx.src_format.plane_count() as usize;
+    let dst_count = ctx.dst_format.plane_count() as usize;
+    if num_src_planes < src_count as c_int || num_dst_planes < dst_count as c_int {
+        return error::invalid_argument(&format!(
+            "平面数不足: 源需要 {src_count}, 目标需要 {dst_count}"
+        ));
+    }
+
+    // SAFETY: 调用方保证数组长度不少于平面数, 上面已校验平面数下限.
+    let src_ptrs = unsafe { std::slice::from_raw_parts(src_planes, src_count) };
+    let src_ls = unsafe { std::slice::from_raw_parts(src_linesizes, src_count) };
+    let dst_ptrs = unsafe { std::slice::from_raw_parts(dst_planes, dst_count) };
+    let dst_ls = unsafe { std::slice::from_raw_parts(dst_linesizes, dst_count) };
+
+    if src_ptrs.iter().any(|p| p.is_null()) || dst_ptrs.iter().any(|p| p.is_null()) {
+        return error::invalid_argument("平面指针为空");
+    }
+    let Some(src_sizes) = plane_sizes(ctx.src_format, ctx.src_height, src_ls) else {
+        return error::invalid_argument("源 linesize 非法");
+    };
+    let Some(dst_sizes) = plane_sizes(ctx.dst_format, ctx.dst_height, dst_ls) else {
+        return error::invalid_argument("目标 linesize 非法");
+    };
+
+    // SAFETY: 指针非空, 缓冲区大小由调用方按 linesize * 平面高度保证.
+    let src_slices: Vec<&[u8]> = src_ptrs
+        .iter()
+        .zip(&src_sizes)
+        .map(|(&p, &len)| unsafe { std::slice::from_raw_parts(p, len) })
+        .collect();
+    let mut dst_slices: Vec<&mut [u8]> = dst_ptrs
+        .iter()
+        .zip(&dst_sizes)
+        .map(|(&p, &len)| unsafe { std::slice::from_raw_parts_mut(p, len) })
+        .collect();
+    let src_linesize: Vec<usize> = src_ls.iter().map(|&l| l as usize).collect();
+    let dst_linesize: Vec<usize> = dst_ls.iter().map(|&l| l as usize).collect();
+
+    match ctx.scale(&src_slices, &src_linesize, &mut dst_slices, &dst_linesize) {
+        Ok(()) => TAO_OK,
+        Err(e) => error::record(&e),
     }
 }
 
+/// 计算各平面缓冲区大小 (linesize * 平面高度), linesize 非法时返回 None
+fn plane_sizes(
+    format: tao_core::PixelFormat,
+    height: u32,
+    linesizes: &[c_int],
+) -> Option<Vec<usize>> {
+    linesizes
+        .iter()
+        .enumerate()
+        .map(|(plane, &ls)| {
+            if ls <= 0 {
+                return None;
+            }
+            Some(ls as usize * format.plane_height(plane, height)?)
+        })
+        .collect()
+}
+
 /// 释放缩放上下文
 ///
 /// # Safety
@@ -974,25 +1902,14 @@ pub unsafe extern "C" fn tao_scale_context_free(ctx: *mut TaoScaleContext) {
     }
 }
 
-fn pixel_format_from_u32(id: u32) -> tao_core::PixelFormat {
-    use tao_core::PixelFormat;
-    match id {
-        0 => PixelFormat::Yuv420p,
-        1 => PixelFormat::Rgb24,
-        2 => PixelFormat::Bgr24,
-        3 => PixelFormat::Yuv422p,
-        4 => PixelFormat::Yuv444p,
-        _ => PixelFormat::Yuv420p,
-    }
-}
-
 // =============================================================================
 // Resample 操作
 // =============================================================================
 
 /// 创建重采样上下文
 ///
-/// sample_format: 0=None, 1=U8, 2=S16, 3=S32, 4=F32, 5=F64
+/// sample_format 为采样格式 ID (即 tao-core `SampleFormat::to_u32`):
+/// 0=None, 1=U8, 2=S16, 3=S32, 4=F32, 5=F64. 未知 ID 返回 null.
 ///
 /// # Safety
 ///
@@ -1006,8 +1923,12 @@ pub unsafe extern "C" fn tao_resample_context_create(
     dst_sample_format: u32,
     dst_channels: u32,
 ) -> *mut TaoResampleContext {
-    let src_sf = sample_format_from_u32(src_sample_format);
-    let dst_sf = sample_format_from_u32(dst_sample_format);
+    let (Ok(src_sf), Ok(dst_sf)) = (
+        sample_format_from_id(src_sample_format),
+        sample_format_from_id(dst_sample_format),
+    ) else {
+        return ptr::null_mut();
+    };
     let ctx = ResampleContext::new(
         src_sample_rate,
         src_sf,
@@ -1045,7 +1966,7 @@ pub unsafe extern "C" fn tao_resample_convert(
         || input_size <= 0
         || output_size <= 0
     {
-        return TAO_ERROR;
+        return error::invalid_argument("指针为空或缓冲区大小不为正数");
     }
 
     let ctx = unsafe { &*ctx };
@@ -1054,11 +1975,15 @@ pub unsafe extern "C" fn tao_resample_convert(
 
     let (data, nb_out) = match ctx.0.convert(input_slice, nb_samples) {
         Ok(r) => r,
-        Err(_) => return TAO_ERROR,
+        Err(e) => return error::record(&e),
     };
 
     if data.len() > output_slice.len() {
-        return TAO_ERROR;
+        return error::invalid_argument(&format!(
+            "输出缓冲区不足: 需要 {} 字节, 实际 {} 字节",
+            data.len(),
+            output_slice.len(),
+        ));
     }
     output_slice[..data.len()].copy_from_slice(&data);
     unsafe {
@@ -1079,16 +2004,16 @@ pub unsafe extern "C" fn tao_resample_context_free(ctx: *mut TaoResampleContext)
     }
 }
 
-fn sample_format_from_u32(id: u32) -> SampleFormat {
-    match id {
-        0 => SampleFormat::None,
-        1 => SampleFormat::U8,
-        2 => SampleFormat::S16,
-        3 => SampleFormat::S32,
-        4 => SampleFormat::F32,
-        5 => SampleFormat::F64,
-        _ => SampleFormat::S16,
-    }
+/// 由 C 侧数值 ID 解析像素格式, 未知 ID 记录错误并返回错误码
+fn pixel_format_from_id(id: u32) -> Result<PixelFormat, c_int> {
+    PixelFormat::from_u32(id)
+        .ok_or_else(|| error::invalid_argument(&format!("未知的像素格式 ID: {id}")))
+}
+
+/// 由 C 侧数值 ID 解析采样格式, 未知 ID 记录错误并返回错误码
+fn sample_format_from_id(id: u32) -> Result<SampleFormat, c_int> {
+    SampleFormat::from_u32(id)
+        .ok_or_else(|| error::invalid_argument(&format!("未知的采样格式 ID: {id}")))
 }
 
 // =============================================================================
@@ -1135,4 +2060,562 @@ mod tests {
         assert!(codec_id_from_int(-1).is_none());
         assert!(codec_id_from_int(999).is_none());
     }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_last_error_invalid_argument() {
+        let ret = unsafe { tao_codec_open_decoder(ptr::null_mut(), 44100, 2, ptr::null(), 0) };
+        assert_eq!(ret, TAO_ERROR_INVALID_ARGUMENT, "空上下文应返回参数错误");
+
+        let msg = unsafe { tao_last_error_message() };
+        assert!(!msg.is_null(), "失败后应有错误信息");
+        let msg = unsafe { CStr::from_ptr(msg) }.to_str().unwrap();
+        assert!(!msg.is_empty(), "错误信息不应为空");
+        assert!(msg.contains("无效参数"), "错误信息应说明参数错误: {msg}");
+
+        let desc = unsafe { CStr::from_ptr(tao_strerror(ret)) };
+        assert_eq!(desc.to_str().unwrap(), "无效参数");
+    }
+
+    #[test]
+    fn test_last_error_open_missing_file() {
+        let path = c"data/__tao_ffi_missing__.wav";
+        let ctx = unsafe { tao_format_open_input(path.as_ptr()) };
+        assert!(ctx.is_null(), "打开不存在的文件应失败");
+
+        let msg = unsafe { tao_get_last_error() };
+        assert!(!msg.is_null(), "失败后应有错误信息");
+        let msg = unsafe { CStr::from_ptr(msg) }.to_str().unwrap();
+        assert!(!msg.is_empty(), "错误信息不应为空");
+        assert!(
+            msg.contains("__tao_ffi_missing__.wav"),
+            "错误信息应包含文件路径: {msg}"
+        );
+        assert_eq!(
+            unsafe { tao_error_code_from_last() },
+            TAO_ERROR_IO,
+            "打开不存在的文件应为 I/O 错误"
+        );
+
+        // 细分错误码随最近一次失败更新
+        let ret = unsafe { tao_packet_size(ptr::null()) };
+        assert_eq!(ret, -1);
+        assert_eq!(
+            unsafe { tao_error_code_from_last() },
+            TAO_ERROR_INVALID_ARGUMENT,
+            "空指针访问应记录参数错误"
+        );
+    }
+
+    #[test]
+    fn test_error_code_mapping() {
+        use tao_core::TaoError;
+
+        assert_eq!(error::record(&TaoError::Eof), TAO_EOF);
+        assert_eq!(error::record(&TaoError::NeedMoreData), TAO_NEED_MORE_DATA);
+        assert_eq!(
+            error::record(&TaoError::CodecNotFound("x".into())),
+            TAO_ERROR_CODEC_NOT_FOUND
+        );
+        let msg = unsafe { CStr::from_ptr(tao_last_error_message()) };
+        assert!(msg.to_str().unwrap().contains("未找到编解码器"));
+
+        // 未知错误码仍返回有效描述
+        let desc = unsafe { CStr::from_ptr(tao_strerror(-1000)) };
+        assert!(!desc.to_bytes().is_empty());
+    }
+
+    #[test]
+    fn test_codec_name_lookup() {
+        let aac = std::ffi::CString::new("aac").unwrap();
+        assert_eq!(unsafe { tao_codec_id_from_name(aac.as_ptr()) }, 13);
+        let name = unsafe { CStr::from_ptr(tao_codec_name_from_id(13)) };
+        assert_eq!(name.to_str().unwrap(), "aac");
+
+        // 不区分大小写, 且支持注册名称
+        let hevc = std::ffi::CString::new("HEVC").unwrap();
+        assert_eq!(
+            unsafe { tao_codec_id_from_name(hevc.as_ptr()) },
+            codec_id_to_int(CodecId::H265)
+        );
+
+        let unknown = std::ffi::CString::new("nonexistent").unwrap();
+        assert_eq!(
+            unsafe { tao_codec_id_from_name(unknown.as_ptr()) },
+            TAO_ERROR_CODEC_NOT_FOUND
+        );
+        assert!(unsafe { tao_codec_name_from_id(-1) }.is_null());
+        assert!(unsafe { tao_codec_name_from_id(9999) }.is_null());
+    }
+
+    #[test]
+    fn test_codec_list() {
+        let mut count: c_int = 0;
+        let list = unsafe { tao_codec_list(&mut count) };
+        assert!(!list.is_null() && count > 0, "应列出已编译的编解码器");
+        let infos = unsafe { std::slice::from_raw_parts(list, count as usize) };
+        let aac = infos
+            .iter()
+            .find(|info| info.codec_id == codec_id_to_int(CodecId::Aac))
+            .expect("应包含 AAC");
+        assert_eq!(unsafe { CStr::from_ptr(aac.name) }.to_str().unwrap(), "aac");
+        assert_eq!(aac.media_type, 1);
+        assert_eq!(
+            aac.capabilities,
+            TAO_CODEC_CAP_DECODE | TAO_CODEC_CAP_ENCODE
+        );
+        let h264 = infos
+            .iter()
+            .find(|info| info.codec_id == codec_id_to_int(CodecId::H264))
+            .expect("应包含 H264");
+        assert_eq!(h264.capabilities, TAO_CODEC_CAP_DECODE);
+        assert!(
+            infos.windows(2).all(|w| w[0].codec_id < w[1].codec_id),
+            "应按 ID 升序排列"
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_concurrent_decoder_creation() {
+        let pcm_id = codec_id_to_int(CodecId::PcmS16le);
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    for _ in 0..16 {
+                        unsafe {
+                            if i % 2 == 0 {
+                                tao_init();
+                            }
+                            let ctx = tao_codec_create_decoder(pcm_id);
+                            assert!(!ctx.is_null(), "线程 {i} 创建解码器失败");
+                            assert_eq!(
+                                tao_codec_open_decoder(ctx, 8000, 1, ptr::null(), 0),
+                                TAO_OK
+                            );
+                            tao_codec_close(ctx);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for t in threads {
+            t.join().expect("线程不应 panic");
+        }
+    }
+
+    /// avcC: Baseline 320x240, 1 个 SPS, 1 个 PPS
+    const TEST_AVCC: [u8; 23] = [
+        0x01, 0x42, 0x00, 0x1E, 0xFF, 0xE1, 0x00, 0x08, 0x67, 0x42, 0x00, 0x1E, 0xF4, 0x0A, 0x0F,
+        0xC8, 0x01, 0x00, 0x04, 0x68, 0xCE, 0x3C, 0x80,
+    ];
+
+    #[test]
+    fn test_open_video_decoder_with_avcc() {
+        let ctx = unsafe { tao_codec_create_decoder(codec_id_to_int(CodecId::H264)) };
+        assert!(!ctx.is_null(), "创建 H.264 解码器失败");
+        let ret = unsafe {
+            tao_codec_open_video_decoder(
+                ctx,
+                320,
+                240,
+                0,
+                TEST_AVCC.as_ptr(),
+                TEST_AVCC.len() as c_int,
+            )
+        };
+        assert_eq!(ret, TAO_OK, "携带 SPS/PPS 的 avcC 应打开成功");
+
+        // 音频解码器上下文无法以视频参数打开
+        let pcm = unsafe { tao_codec_create_decoder(codec_id_to_int(CodecId::PcmS16le)) };
+        let ret = unsafe { tao_codec_open_video_decoder(pcm, 320, 240, 0, ptr::null(), 0) };
+        assert_ne!(ret, TAO_OK, "PCM 解码器不应接受视频参数");
+        unsafe {
+            tao_codec_close(ctx);
+            tao_codec_close(pcm);
+        }
+    }
+
+    #[test]
+    fn test_open_video_decoder_null_ctx() {
+        let ret =
+            unsafe { tao_codec_open_video_decoder(ptr::null_mut(), 320, 240, 0, ptr::null(), 0) };
+        assert_eq!(ret, TAO_ERROR_INVALID_ARGUMENT, "空上下文应返回参数错误");
+    }
+
+    #[test]
+    fn test_open_mjpeg_decoder() {
+        let ctx = unsafe { tao_codec_create_decoder(codec_id_to_int(CodecId::Mjpeg)) };
+        assert!(!ctx.is_null(), "创建 MJPEG 解码器失败");
+        let ret = unsafe { tao_codec_open_video_decoder(ctx, 320, 240, 0, ptr::null(), 0) };
+        assert_eq!(ret, TAO_OK, "MJPEG 解码器应以视频参数打开成功");
+        assert_eq!(
+            unsafe { tao_codec_get_capabilities(ctx) },
+            TAO_CODEC_CAPS_INTRA_ONLY,
+            "MJPEG 解码器应声明纯帧内"
+        );
+        unsafe { tao_codec_close(ctx) };
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_open_ac3_decoder() {
+        let ctx = unsafe { tao_codec_create_decoder(codec_id_to_int(CodecId::Ac3)) };
+        assert!(!ctx.is_null(), "创建 AC-3 解码器失败");
+        let ret = unsafe { tao_codec_open_decoder(ctx, 48000, 2, ptr::null(), 0) };
+        assert_eq!(ret, TAO_OK, "AC-3 解码器应以音频参数打开成功");
+        unsafe { tao_codec_close(ctx) };
+    }
+
+    #[test]
+    fn test_decode_ima_adpcm_block() {
+        let ctx = unsafe { tao_codec_create_decoder(codec_id_to_int(CodecId::AdpcmImaWav)) };
+        assert!(!ctx.is_null(), "创建 IMA ADPCM 解码器失败");
+        let ret = unsafe { tao_codec_open_audio_decoder(ctx, 22050, 1, 8, ptr::null(), 0) };
+        assert_eq!(ret, TAO_OK, "IMA ADPCM 解码器应以 block_align 打开成功");
+
+        let block = vec![100u8, 0, 10, 0, 0x77, 0x77, 0x1F, 0x80];
+        let pkt = TaoPacket(Packet::from_data(block));
+        assert_eq!(unsafe { tao_codec_send_packet(ctx, &pkt) }, TAO_OK);
+        let mut frame: *mut TaoFrame = ptr::null_mut();
+        assert_eq!(unsafe { tao_codec_receive_frame(ctx, &mut frame) }, TAO_OK);
+        assert_eq!(unsafe { tao_frame_nb_samples(frame) }, 9);
+        let data = unsafe { std::slice::from_raw_parts(tao_frame_data(frame, 0), 18) };
+        let samples: Vec<i16> = data
+            .chunks_exact(2)
+            .map(|c| i16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        assert_eq!(samples, [100, 134, 210, 375, 730, -35, 293, 392, 302]);
+        unsafe {
+            tao_frame_free(frame);
+            tao_codec_close(ctx);
+        }
+    }
+
+    #[test]
+    fn test_open_audio_decoder_rejects_negative_block_align() {
+        let ctx = unsafe { tao_codec_create_decoder(codec_id_to_int(CodecId::AdpcmMs)) };
+        assert!(!ctx.is_null(), "创建 MS ADPCM 解码器失败");
+        let ret = unsafe { tao_codec_open_audio_decoder(ctx, 22050, 1, -1, ptr::null(), 0) };
+        assert_eq!(ret, TAO_ERROR_INVALID_ARGUMENT);
+        unsafe { tao_codec_close(ctx) };
+    }
+
+    #[test]
+    fn test_registries_are_sync() {
+        fn assert_sync<T: Send + Sync>() {}
+        assert_sync::<tao_codec::CodecRegistry>();
+        assert_sync::<tao_format::FormatRegistry>();
+    }
+
+    #[test]
+    fn test_codec_get_capabilities() {
+        let h264 = unsafe { tao_codec_create_decoder(codec_id_to_int(CodecId::H264)) };
+        assert!(!h264.is_null(), "创建 H.264 解码器失败");
+        assert_eq!(
+            unsafe { tao_codec_get_capabilities(h264) },
+            TAO_CODEC_CAPS_DELAY | TAO_CODEC_CAPS_REORDER,
+            "H.264 解码器应声明延迟输出与帧重排序"
+        );
+
+        let flac = unsafe { tao_codec_create_encoder(codec_id_to_int(CodecId::Flac)) };
+        assert!(!flac.is_null(), "创建 FLAC 编码器失败");
+        let caps = unsafe { tao_codec_get_capabilities(flac) };
+        assert_ne!(caps & TAO_CODEC_CAPS_LOSSLESS, 0, "FLAC 编码器应声明无损");
+        assert_eq!(caps & TAO_CODEC_CAPS_REORDER, 0);
+
+        assert_eq!(unsafe { tao_codec_get_capabilities(ptr::null()) }, 0);
+        unsafe {
+            tao_codec_close(h264);
+            tao_codec_close(flac);
+        }
+    }
+
+    #[test]
+    fn test_packet_flags() {
+        let mut pkt = Packet::from_data(vec![0u8; 4]);
+        pkt.set_keyframe(true);
+        pkt.flags |= PacketFlags::DISCARD;
+        let pkt = Box::into_raw(Box::new(TaoPacket(pkt)));
+        let flags = unsafe { tao_packet_flags(pkt) };
+        assert_eq!(flags, TAO_PKT_FLAG_KEY | TAO_PKT_FLAG_DISCARD);
+        assert_eq!(flags & TAO_PKT_FLAG_CORRUPT, 0);
+        unsafe { tao_packet_free(pkt) };
+        assert_eq!(unsafe { tao_packet_flags(ptr::null()) }, 0);
+    }
+
+    #[test]
+    fn test_frame_alloc_fill_and_encode() {
+        let samples: Vec<u8> = (0..1024 * 2 * 2).map(|i| i as u8).collect();
+        unsafe {
+            // 2 = S16
+            let frame = tao_frame_alloc_audio(1024, 44100, 2, 2);
+            assert!(!frame.is_null(), "分配音频帧失败");
+            assert_eq!(tao_frame_is_audio(frame), 1);
+            assert_eq!(tao_frame_nb_samples(frame), 1024);
+            assert_eq!(tao_frame_sample_rate(frame), 44100);
+            assert_eq!(tao_frame_linesize(frame, 0), samples.len() as c_int);
+            assert_eq!(
+                tao_frame_fill_plane(frame, 0, samples.as_ptr(), samples.len() as c_int),
+                TAO_OK
+            );
+            let data = std::slice::from_raw_parts(tao_frame_data(frame, 0), samples.len());
+            assert_eq!(data, &samples[..], "平面数据应与写入一致");
+            assert_eq!(
+                tao_frame_fill_plane(frame, 0, samples.as_ptr(), samples.len() as c_int + 1),
+                TAO_ERROR_INVALID_ARGUMENT,
+                "超过平面大小应报错"
+            );
+            assert_eq!(
+                tao_frame_fill_plane(frame, 1, samples.as_ptr(), 4),
+                TAO_ERROR_INVALID_ARGUMENT,
+                "平面索引越界应报错"
+            );
+
+            // 送入 PCM 编码器, 输出应与写入数据一致
+            let enc = tao_codec_create_encoder(codec_id_to_int(CodecId::PcmS16le));
+            assert!(!enc.is_null());
+            assert_eq!(tao_codec_open_encoder(enc, 44100, 2), TAO_OK);
+            assert_eq!(tao_codec_send_frame(enc, frame), TAO_OK);
+            let mut pkt: *mut TaoPacket = ptr::null_mut();
+            assert_eq!(tao_codec_receive_packet(enc, &mut pkt), TAO_OK);
+            let size = tao_packet_size(pkt) as usize;
+            let pkt_data = std::slice::from_raw_parts(tao_packet_data(pkt), size);
+            assert_eq!(pkt_data, &samples[..], "编码输出应与输入采样一致");
+            tao_packet_free(pkt);
+            tao_codec_close(enc);
+            tao_frame_free(frame);
+        }
+    }
+
+    #[test]
+    fn test_frame_alloc_video() {
+        unsafe {
+            // 0 = Yuv420p
+            let frame = tao_frame_alloc_video(16, 8, 0);
+            assert!(!frame.is_null(), "分配视频帧失败");
+            assert_eq!(tao_frame_is_video(frame), 1);
+            assert_eq!(tao_frame_width(frame), 16);
+            assert_eq!(tao_frame_height(frame), 8);
+            assert_eq!(tao_frame_linesize(frame, 0), 16);
+            assert_eq!(tao_frame_linesize(frame, 1), 8);
+            let luma = [200u8; 16 * 8];
+            assert_eq!(tao_frame_fill_plane(frame, 0, luma.as_ptr(), 128), TAO_OK);
+            assert_eq!(*tao_frame_data(frame, 0).add(127), 200);
+            assert_eq!(
+                tao_frame_color_space(frame),
+                2,
+                "新分配帧色彩空间应为未指定"
+            );
+            assert_eq!(
+                tao_frame_color_range(frame),
+                0,
+                "新分配帧色彩范围应为未指定"
+            );
+            if let Frame::Video(v) = &mut (*frame).0 {
+                v.color_space = tao_core::color::ColorSpace::Bt2020Ncl;
+                v.color_range = ColorRange::Full;
+            }
+            assert_eq!(tao_frame_color_space(frame), 9);
+            assert_eq!(tao_frame_color_range(frame), 2);
+            tao_frame_free(frame);
+
+            assert!(tao_frame_alloc_video(0, 8, 0).is_null(), "宽度为 0 应失败");
+            assert!(
+                tao_frame_alloc_audio(0, 44100, 2, 2).is_null(),
+                "采样数为 0 应失败"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unknown_format_ids_rejected() {
+        unsafe {
+            assert!(
+                tao_frame_alloc_video(16, 8, 999).is_null(),
+                "未知像素格式 ID 应失败而非回退为 Yuv420p"
+            );
+            let msg = CStr::from_ptr(tao_last_error_message()).to_str().unwrap();
+            assert!(msg.contains("像素格式"), "错误信息应说明像素格式: {msg}");
+            assert!(tao_frame_alloc_audio(1024, 44100, 999, 2).is_null());
+            assert!(tao_scale_context_create(8, 8, 0, 4, 4, 999).is_null());
+            assert!(tao_resample_context_create(44100, 999, 2, 48000, 2, 2).is_null());
+
+            // 扩展后的 ID 与 tao-core 定义一致
+            let frame = tao_frame_alloc_video(16, 8, PixelFormat::Nv12.to_u32());
+            assert!(!frame.is_null());
+            assert_eq!(tao_frame_linesize(frame, 1), 16, "NV12 色度平面为 UV 交错");
+            tao_frame_free(frame);
+        }
+    }
+
+    #[test]
+    fn test_mux_wav_roundtrip() {
+        let path = std::env::temp_dir().join(format!("tao_ffi_mux_{}.wav", std::process::id()));
+        let path_c = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+        let pcm_id = codec_id_to_int(CodecId::PcmS16le);
+        let chunks: [Vec<u8>; 2] = [
+            (0..64u8).collect(),
+            (0..64u8).map(|v| v.wrapping_mul(3)).collect(),
+        ];
+
+        // 封装: 2 个 PCM 数据包 -> WAV
+        unsafe {
+            let ctx = tao_format_open_output(path_c.as_ptr());
+            assert!(!ctx.is_null(), "创建输出上下文失败");
+            let idx = tao_format_add_stream(ctx, pcm_id, 1, 8000, 8000, 2, 0, 0, ptr::null(), 0);
+            assert_eq!(idx, 0, "首个流索引应为 0");
+            assert_eq!(tao_format_write_header(ctx), TAO_OK);
+            for (i, chunk) in chunks.iter().enumerate() {
+                let mut pkt = Packet::from_data(chunk.clone());
+                pkt.pts = i as i64 * 16;
+                let pkt = Box::into_raw(Box::new(TaoPacket(pkt)));
+                assert_eq!(tao_format_write_packet(ctx, idx, pkt), TAO_OK);
+                tao_packet_free(pkt);
+            }
+            assert_eq!(
+                tao_format_write_packet(ctx, 5, ptr::null()),
+                TAO_ERROR_INVALID_ARGUMENT
+            );
+            assert_eq!(tao_format_write_trailer(ctx), TAO_OK);
+            tao_format_close_output(ctx);
+        }
+
+        // 解封装并比对数据
+        let mut read_back = Vec::new();
+        unsafe {
+            let ctx = tao_format_open_input(path_c.as_ptr());
+            assert!(!ctx.is_null(), "重新打开 WAV 失败");
+            assert_eq!(tao_format_get_stream_count(ctx), 1);
+            assert_eq!(tao_format_get_stream_codec_id(ctx, 0), pcm_id);
+            loop {
+                let mut pkt: *mut TaoPacket = ptr::null_mut();
+                let ret = tao_format_read_packet(ctx, &mut pkt);
+                if ret == TAO_EOF {
+                    break;
+                }
+                assert_eq!(ret, TAO_OK);
+                let size = tao_packet_size(pkt) as usize;
+                read_back.extend_from_slice(std::slice::from_raw_parts(tao_packet_data(pkt), size));
+                tao_packet_free(pkt);
+            }
+            tao_format_close(ctx);
+        }
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(read_back, chunks.concat(), "WAV 往返数据不一致");
+    }
+
+    #[test]
+    fn test_format_seek_wav() {
+        let path = std::env::temp_dir().join(format!("tao_ffi_seek_{}.wav", std::process::id()));
+        let path_c = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+
+        // 8000Hz 单声道 S16, 样本值等于样本序号
+        let samples: Vec<i16> = (0..8000).collect();
+        let data: Vec<u8> = samples.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&8000u32.to_le_bytes());
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&data);
+        std::fs::write(&path, &wav).unwrap();
+
+        unsafe {
+            let ctx = tao_format_open_input(path_c.as_ptr());
+            assert!(!ctx.is_null(), "打开 WAV 失败");
+
+            assert_eq!(
+                tao_format_seek(ctx, 0, 4000, TAO_SEEK_BACKWARD),
+                TAO_OK,
+                "WAV seek 应成功"
+            );
+            let mut pkt: *mut TaoPacket = ptr::null_mut();
+            assert_eq!(tao_format_read_packet(ctx, &mut pkt), TAO_OK);
+            assert_eq!(tao_packet_pts(pkt), 4000, "seek 后首包 PTS 应为目标位置");
+            let first = std::slice::from_raw_parts(tao_packet_data(pkt), 2);
+            assert_eq!(
+                i16::from_le_bytes([first[0], first[1]]),
+                4000,
+                "seek 后首个样本应为目标样本"
+            );
+            tao_packet_free(pkt);
+
+            assert_eq!(
+                tao_format_seek(ctx, 3, 0, 0),
+                TAO_ERROR_STREAM_NOT_FOUND,
+                "越界流索引应返回未找到流"
+            );
+            assert_eq!(
+                tao_format_seek(ctx, 0, 0, 0x100),
+                TAO_ERROR_INVALID_ARGUMENT,
+                "未知标志应返回参数错误"
+            );
+            assert_eq!(
+                tao_format_seek(ptr::null_mut(), 0, 0, 0),
+                TAO_ERROR_INVALID_ARGUMENT
+            );
+            tao_format_close(ctx);
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_scale_planar_yuv420p() {
+        // 8x8 YUV420P 缩小到 4x4, 各平面为常量值
+        let ctx = unsafe { tao_scale_context_create(8, 8, 0, 4, 4, 0) };
+        assert!(!ctx.is_null());
+
+        let src_y = [100u8; 8 * 8];
+        let src_u = [50u8; 4 * 4];
+        let src_v = [200u8; 4 * 4];
+        let src_planes = [src_y.as_ptr(), src_u.as_ptr(), src_v.as_ptr()];
+        let src_linesizes: [c_int; 3] = [8, 4, 4];
+
+        let mut dst_y = vec![0u8; 4 * 4];
+        let mut dst_u = vec![0u8; 2 * 2];
+        let mut dst_v = vec![0u8; 2 * 2];
+        let mut dst_planes = [dst_y.as_mut_ptr(), dst_u.as_mut_ptr(), dst_v.as_mut_ptr()];
+        let dst_linesizes: [c_int; 3] = [4, 2, 2];
+
+        let ret = unsafe {
+            tao_scale_scale_planar(
+                ctx,
+                src_planes.as_ptr(),
+                src_linesizes.as_ptr(),
+                3,
+                dst_planes.as_mut_ptr(),
+                dst_linesizes.as_ptr(),
+                3,
+            )
+        };
+        assert_eq!(ret, TAO_OK, "多平面缩放应成功");
+        assert!(dst_y.iter().all(|&v| v == 100), "Y 平面值应保持");
+        assert!(dst_u.iter().all(|&v| v == 50), "U 平面值应保持");
+        assert!(dst_v.iter().all(|&v| v == 200), "V 平面值应保持");
+
+        // 平面数不足应失败
+        let ret = unsafe {
+            tao_scale_scale_planar(
+                ctx,
+                src_planes.as_ptr(),
+                src_linesizes.as_ptr(),
+                1,
+                dst_planes.as_mut_ptr(),
+                dst_linesizes.as_ptr(),
+                3,
+            )
+        };
+        assert_eq!(ret, TAO_ERROR_INVALID_ARGUMENT, "平面数不足应返回参数错误");
+        unsafe { tao_scale_context_free(ctx) };
+    }
 }
@@ -0,0 +1,120 @@
+//! FFI 日志回调适配.
+//!
+//! 将 Rust 侧 `log` (以及经 `tracing` 的 `log` 特性转发的) 日志记录
+//! 转交给 C 调用方注册的回调函数. 未注册回调时为空操作, 不输出任何日志.
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::sync::{Once, RwLock};
+
+/// C 日志回调函数类型
+///
+/// - `level`: 0=error, 1=warn, 2=info, 3=debug, 4=trace
+/// - `msg`: 以 null 结尾的 UTF-8 消息, 仅在回调期间有效
+pub type TaoLogCallback = unsafe extern "C" fn(level: c_int, msg: *const c_char);
+
+/// 当前注册的回调
+static CALLBACK: RwLock<Option<TaoLogCallback>> = RwLock::new(None);
+
+/// 全局 logger 安装标记 (log crate 仅允许安装一次)
+static INSTALL: Once = Once::new();
+
+/// 全局 logger 是否由本模块成功安装
+static INSTALLED: RwLock<bool> = RwLock::new(false);
+
+/// 转发日志到 C 回调的 logger
+struct CallbackLogger;
+
+impl log::Log for CallbackLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        current_callback().is_some()
+    }
+
+    fn log(&self, record: &log::Record) {
+        let Some(cb) = current_callback() else {
+            return;
+        };
+        let text = format!("[{}] {}", record.target(), record.args()).replace('\0', " ");
+        if let Ok(msg) = CString::new(text) {
+            // SAFETY: 回调由调用方注册并保证有效, msg 在调用期间存活.
+            unsafe { cb(level_to_int(record.level()), msg.as_ptr()) };
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// 获取当前回调 (容忍锁中毒)
+fn current_callback() -> Option<TaoLogCallback> {
+    *CALLBACK.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// log 级别转 C 整数级别
+fn level_to_int(level: log::Level) -> c_int {
+    match level {
+        log::Level::Error => 0,
+        log::Level::Warn => 1,
+        log::Level::Info => 2,
+        log::Level::Debug => 3,
+        log::Level::Trace => 4,
+    }
+}
+
+/// 注册或清除日志回调
+///
+/// 首次调用时安装全局 logger. 若宿主进程已安装其他 Rust logger, 返回 false.
+pub(crate) fn set_log_callback(cb: Option<TaoLogCallback>) -> bool {
+    INSTALL.call_once(|| {
+        let ok = log::set_boxed_logger(Box::new(CallbackLogger)).is_ok();
+        *INSTALLED.write().unwrap_or_else(|e| e.into_inner()) = ok;
+    });
+    if !*INSTALLED.read().unwrap_or_else(|e| e.into_inner()) {
+        return false;
+    }
+
+    *CALLBACK.write().unwrap_or_else(|e| e.into_inner()) = cb;
+    log::set_max_level(if cb.is_some() {
+        log::LevelFilter::Trace
+    } else {
+        log::LevelFilter::Off
+    });
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use std::sync::Mutex;
+
+    static RECEIVED: Mutex<Vec<(c_int, String)>> = Mutex::new(Vec::new());
+
+    unsafe extern "C" fn record_cb(level: c_int, msg: *const c_char) {
+        // SAFETY: 由 CallbackLogger 传入的有效 C 字符串
+        let text = unsafe { CStr::from_ptr(msg) }
+            .to_string_lossy()
+            .into_owned();
+        RECEIVED.lock().unwrap().push((level, text));
+    }
+
+    #[test]
+    fn test_log_callback_receives_levels_and_can_be_cleared() {
+        assert!(set_log_callback(Some(record_cb)), "应成功安装回调 logger");
+        log::warn!(target: "tao_ffi_test", "警告 {}", 1);
+        log::trace!(target: "tao_ffi_test", "跟踪");
+
+        assert!(set_log_callback(None), "应成功清除回调");
+        log::error!(target: "tao_ffi_test", "不应被转发");
+
+        let received: Vec<_> = RECEIVED
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, m)| m.contains("tao_ffi_test"))
+            .cloned()
+            .collect();
+        assert_eq!(received.len(), 2, "清除回调后不应再收到日志");
+        assert_eq!(received[0], (1, "[tao_ffi_test] 警告 1".to_string()));
+        assert_eq!(received[1].0, 4, "trace 级别应映射为 4");
+    }
+}
@@ -0,0 +1,84 @@
+//! 进程级全局注册表.
+//!
+//! 编解码器与容器格式注册表在 `tao_init` 或首次使用时初始化一次,
+//! 之后所有 FFI 调用共享只读引用, 可在多线程中并发使用.
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::sync::OnceLock;
+
+use tao_codec::CodecRegistry;
+use tao_format::FormatRegistry;
+
+use crate::{TaoCodecInfo, codec_id_from_int, codec_id_to_int, media_type_to_int};
+
+/// 解码能力标志 (TaoCodecInfo::capabilities)
+pub const TAO_CODEC_CAP_DECODE: c_int = 1;
+/// 编码能力标志 (TaoCodecInfo::capabilities)
+pub const TAO_CODEC_CAP_ENCODE: c_int = 2;
+
+static CODECS: OnceLock<CodecRegistry> = OnceLock::new();
+static FORMATS: OnceLock<FormatRegistry> = OnceLock::new();
+static CODEC_LIST: OnceLock<CodecList> = OnceLock::new();
+
+/// 全局编解码器注册表
+pub(crate) fn codecs() -> &'static CodecRegistry {
+    CODECS.get_or_init(|| {
+        let mut registry = CodecRegistry::new();
+        tao_codec::register_all(&mut registry);
+        registry
+    })
+}
+
+/// 全局容器格式注册表
+pub(crate) fn formats() -> &'static FormatRegistry {
+    FORMATS.get_or_init(|| {
+        let mut registry = FormatRegistry::new();
+        tao_format::register_all(&mut registry);
+        registry
+    })
+}
+
+/// 已编译编解码器列表 (C 侧只读数组及其引用的名称字符串)
+pub(crate) struct CodecList {
+    pub(crate) infos: Vec<TaoCodecInfo>,
+    _names: Vec<CString>,
+}
+
+// SAFETY: infos 中的名称指针指向 _names 持有的只读字符串, 两者生命周期一致且永不修改.
+unsafe impl Send for CodecList {}
+unsafe impl Sync for CodecList {}
+
+/// 按 C 侧编解码器 ID 顺序列出已注册的编解码器
+pub(crate) fn codec_list() -> &'static CodecList {
+    CODEC_LIST.get_or_init(|| {
+        let registry = codecs();
+        let mut infos = Vec::new();
+        let mut names = Vec::new();
+        for id in (0..).map_while(codec_id_from_int) {
+            let mut capabilities = 0;
+            if registry.find_decoder(id).is_some() {
+                capabilities |= TAO_CODEC_CAP_DECODE;
+            }
+            if registry.find_encoder(id).is_some() {
+                capabilities |= TAO_CODEC_CAP_ENCODE;
+            }
+            if capabilities == 0 {
+                continue;
+            }
+            let name = CString::new(id.name()).unwrap_or_default();
+            infos.push(TaoCodecInfo {
+                codec_id: codec_id_to_int(id),
+                name: name.as_ptr() as *const c_char,
+                media_type: media_type_to_int(id.media_type()),
+                capabilities,
+            });
+            // CString 的堆内存在移动后地址不变
+            names.push(name);
+        }
+        CodecList {
+            infos,
+            _names: names,
+        }
+    })
+}
@@ -0,0 +1,298 @@
+//! 音频格式转换滤镜.
+//!
+//! 对标 FFmpeg 的 `aformat` 滤镜, 借助 tao-resample 将音频帧转换到指定的
+//! 采样格式、采样率与声道布局. 未指定的参数保持输入不变.
+
+use tao_codec::frame::{AudioFrame, Frame};
+use tao_core::{ChannelLayout, SampleFormat, TaoError, TaoResult};
+use tao_resample::ResampleContext;
+
+use crate::{Filter, FrameFormat};
+
+/// 音频格式转换滤镜
+///
+/// 重采样在交错格式下进行, 平面格式的输入先交错化, 目标为平面格式时再拆分回各声道平面.
+pub struct AformatFilter {
+    /// 目标采样格式 (None 表示保持输入)
+    sample_format: Option<SampleFormat>,
+    /// 目标采样率 (None 表示保持输入)
+    sample_rate: Option<u32>,
+    /// 目标声道布局 (None 表示保持输入)
+    channel_layout: Option<ChannelLayout>,
+    /// 输出帧缓冲
+    output: Option<Frame>,
+}
+
+impl AformatFilter {
+    /// 创建不做任何转换的滤镜, 通过 `with_*` 指定目标参数
+    pub fn new() -> Self {
+        Self {
+            sample_format: None,
+            sample_rate: None,
+            channel_layout: None,
+            output: None,
+        }
+    }
+
+    /// 指定目标采样格式
+    pub fn with_sample_format(mut self, format: SampleFormat) -> Self {
+        self.sample_format = Some(format);
+        self
+    }
+
+    /// 指定目标采样率
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = Some(sample_rate);
+        self
+    }
+
+    /// 指定目标声道布局
+    pub fn with_channel_layout(mut self, layout: ChannelLayout) -> Self {
+        self.channel_layout = Some(layout);
+        self
+    }
+
+    /// 目标采样格式
+    pub fn sample_format(&self) -> Option<SampleFormat> {
+        self.sample_format
+    }
+
+    /// 目标采样率
+    pub fn sample_rate(&self) -> Option<u32> {
+        self.sample_rate
+    }
+
+    /// 目标声道布局
+    pub fn channel_layout(&self) -> Option<ChannelLayout> {
+        self.channel_layout
+    }
+
+    /// 转换音频帧
+    fn convert_frame(&self, frame: &AudioFrame) -> TaoResult<AudioFrame> {
+        let dst_format = self.sample_format.unwrap_or(frame.sample_format);
+        let dst_rate = self.sample_rate.unwrap_or(frame.sample_rate);
+        let dst_layout = self.channel_layout.unwrap_or(frame.channel_layout);
+        if dst_rate == 0 {
+            return Err(TaoError::InvalidArgument("aformat: 采样率不能为 0".into()));
+        }
+        if dst_format == frame.sample_format
+            && dst_rate == frame.sample_rate
+            && dst_layout.channels == frame.channel_layout.channels
+        {
+            let mut out = frame.clone();
+            out.channel_layout = dst_layout;
+            return Ok(out);
+        }
+
+        let packed = if frame.sample_format.is_planar() {
+            interleave(frame)?
+        } else {
+            frame.clone()
+        };
+        let ctx = ResampleContext::new(
+            packed.sample_rate,
+            packed.sample_format,
+            packed.channel_layout,
+            dst_rate,
+            dst_format.to_interleaved(),
+            dst_layout,
+        );
+        let converted = ctx.convert_frame(&packed)?;
+        if dst_format.is_planar() {
+            Ok(deinterleave(&converted, dst_format))
+        } else {
+            Ok(converted)
+        }
+    }
+}
+
+impl Default for AformatFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 将平面格式音频帧交错化
+fn interleave(frame: &AudioFrame) -> TaoResult<AudioFrame> {
+    let channels = frame.channel_layout.channels as usize;
+    let bps = frame.sample_format.bytes_per_sample() as usize;
+    let nb = frame.nb_samples as usize;
+    if frame.data.len() < channels || frame.data.iter().any(|p| p.len() < nb * bps) {
+        return Err(TaoError::InvalidArgument(format!(
+            "aformat: 平面音频帧数据不足, 期望 {channels} 个平面, 每平面 {} 字节",
+            nb * bps,
+        )));
+    }
+    let mut data = vec![0u8; nb * channels * bps];
+    for (c, plane) in frame.data.iter().take(channels).enumerate() {
+        for i in 0..nb {
+            let dst = (i * channels + c) * bps;
+            data[dst..dst + bps].copy_from_slice(&plane[i * bps..(i + 1) * bps]);
+        }
+    }
+    let mut out = AudioFrame::new(
+        frame.nb_samples,
+        frame.sample_rate,
+        frame.sample_format.to_interleaved(),
+        frame.channel_layout,
+    );
+    out.data[0] = data.into();
+    out.pts = frame.pts;
+    out.time_base = frame.time_base;
+    out.duration = frame.duration;
+    Ok(out)
+}
+
+/// 将交错格式音频帧拆分为平面格式 `format`
+fn deinterleave(frame: &AudioFrame, format: SampleFormat) -> AudioFrame {
+    let channels = frame.channel_layout.channels as usize;
+    let bps = format.bytes_per_sample() as usize;
+    let nb = frame.nb_samples as usize;
+    let mut out = AudioFrame::new(
+        frame.nb_samples,
+        frame.sample_rate,
+        format,
+        frame.channel_layout,
+    );
+    for (c, plane) in out.data.iter_mut().enumerate() {
+        let mut buf = vec![0u8; nb * bps];
+        for i in 0..nb {
+            let src = (i * channels + c) * bps;
+            buf[i * bps..(i + 1) * bps].copy_from_slice(&frame.data[0][src..src + bps]);
+        }
+        *plane = buf.into();
+    }
+    out.pts = frame.pts;
+    out.time_base = frame.time_base;
+    out.duration = frame.duration;
+    out
+}
+
+impl Filter for AformatFilter {
+    fn name(&self) -> &str {
+        "aformat"
+    }
+
+    fn output_format(&self, input: FrameFormat) -> FrameFormat {
+        match (input, self.sample_format) {
+            (FrameFormat::Audio(_), Some(format)) => FrameFormat::Audio(format),
+            _ => input,
+        }
+    }
+
+    fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
+        match frame {
+            Frame::Audio(af) => {
+                let result = self.convert_frame(af)?;
+                self.output = Some(Frame::Audio(result));
+                Ok(())
+            }
+            Frame::Video(_) | Frame::Subtitle(_) => {
+                Err(TaoError::InvalidArgument("aformat 滤镜仅支持音频帧".into()))
+            }
+        }
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        self.output.take().ok_or(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) -> TaoResult<()> {
+        self.output = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tao_core::Rational;
+
+    fn make_s16_stereo_frame(samples: &[(i16, i16)]) -> Frame {
+        let mut af = AudioFrame::new(
+            samples.len() as u32,
+            48000,
+            SampleFormat::S16,
+            ChannelLayout::STEREO,
+        );
+        let mut data = Vec::with_capacity(samples.len() * 4);
+        for &(l, r) in samples {
+            data.extend_from_slice(&l.to_le_bytes());
+            data.extend_from_slice(&r.to_le_bytes());
+        }
+        af.data[0] = data.into();
+        af.pts = 10;
+        af.time_base = Rational::new(1, 48000);
+        af.duration = samples.len() as i64;
+        Frame::Audio(af)
+    }
+
+    fn receive_audio(filter: &mut AformatFilter) -> AudioFrame {
+        match filter.receive_frame().unwrap() {
+            Frame::Audio(af) => af,
+            _ => panic!("期望音频帧"),
+        }
+    }
+
+    #[test]
+    fn test_aformat_s16_to_planar_float() {
+        let mut filter = AformatFilter::new().with_sample_format(SampleFormat::F32p);
+        filter
+            .send_frame(&make_s16_stereo_frame(&[(16384, -16384), (0, 32767)]))
+            .unwrap();
+        let af = receive_audio(&mut filter);
+        assert_eq!(af.sample_format, SampleFormat::F32p);
+        assert_eq!(af.data.len(), 2);
+        assert_eq!((af.pts, af.nb_samples), (10, 2));
+        let plane = |c: usize| -> Vec<f32> {
+            af.data[c]
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect()
+        };
+        let (left, right) = (plane(0), plane(1));
+        assert!((left[0] - 0.5).abs() < 1e-3 && left[1].abs() < 1e-3);
+        assert!((right[0] + 0.5).abs() < 1e-3 && (right[1] - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_aformat_planar_input_rate_and_layout() {
+        let mut filter = AformatFilter::new().with_sample_format(SampleFormat::F32p);
+        filter
+            .send_frame(&make_s16_stereo_frame(&[(1000, 3000); 480]))
+            .unwrap();
+        let planar = Frame::Audio(receive_audio(&mut filter));
+
+        let mut filter = AformatFilter::new()
+            .with_sample_format(SampleFormat::S16)
+            .with_sample_rate(24000)
+            .with_channel_layout(ChannelLayout::MONO);
+        filter.send_frame(&planar).unwrap();
+        let af = receive_audio(&mut filter);
+        assert_eq!(af.sample_format, SampleFormat::S16);
+        assert_eq!(af.sample_rate, 24000);
+        assert_eq!(af.channel_layout, ChannelLayout::MONO);
+        assert_eq!(af.nb_samples, 240);
+        assert_eq!(af.duration, 480, "时长应保持在源时间基下不变");
+        let first = i16::from_le_bytes([af.data[0][0], af.data[0][1]]);
+        assert!((i32::from(first) - 2000).abs() <= 1, "下混应为左右平均");
+    }
+
+    #[test]
+    fn test_aformat_passthrough_and_video_error() {
+        let mut filter = AformatFilter::new().with_sample_rate(48000);
+        let input = make_s16_stereo_frame(&[(1, 2)]);
+        filter.send_frame(&input).unwrap();
+        let Frame::Audio(af) = filter.receive_frame().unwrap() else {
+            panic!("期望音频帧");
+        };
+        assert_eq!(&af.data[0][..], &[1, 0, 2, 0]);
+
+        let vf = Frame::Video(tao_codec::frame::VideoFrame::new(
+            2,
+            2,
+            tao_core::PixelFormat::Rgb24,
+        ));
+        assert!(filter.send_frame(&vf).is_err());
+    }
+}
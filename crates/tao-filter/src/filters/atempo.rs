@@ -0,0 +1,470 @@
+//! 音频变速不变调滤镜.
+//!
+//! 对标 FFmpeg 的 `atempo` 滤镜, 使用 WSOLA (波形相似重叠相加) 算法:
+//! 输出按固定步长 (半个窗口) 以 Hann 窗重叠相加, 输入读取位置按 `步长 × 速度` 推进,
+//! 每次在标称位置附近搜索与上一段自然延续最相似的片段, 避免相位跳变.
+//! 播放速度改变而音调保持不变.
+//!
+//! 仅支持 F32 交错格式. 速度范围 0.5 ~ 2.0, 可在处理过程中调整.
+
+use std::collections::VecDeque;
+
+use tao_codec::frame::{AudioFrame, Frame};
+use tao_core::rational::rescale_q;
+use tao_core::timestamp::NOPTS_VALUE;
+use tao_core::{ChannelLayout, Rational, SampleFormat, TaoError, TaoResult};
+
+use crate::{Filter, FrameFormat};
+
+/// 最小速度
+pub const ATEMPO_MIN: f64 = 0.5;
+/// 最大速度
+pub const ATEMPO_MAX: f64 = 2.0;
+
+/// 分析/合成窗口长度 (秒)
+const WINDOW_SEC: f64 = 0.04;
+
+/// 支持变速的采样格式 (仅交错 F32)
+const SUPPORTED_FORMATS: &[SampleFormat] = &[SampleFormat::F32];
+
+/// 音频变速滤镜
+///
+/// 输出时间戳以首帧为起点按输出采样数递增. 刷新后输出总采样数为输入采样数除以速度.
+pub struct AtempoFilter {
+    /// 播放速度 (1.0 为原速)
+    tempo: f64,
+    /// 由首帧确定的采样率
+    sample_rate: u32,
+    /// 由首帧确定的声道布局
+    channel_layout: ChannelLayout,
+    /// Hann 窗 (长度为窗口采样数)
+    window: Vec<f32>,
+    /// 待处理输入 (交错)
+    input: Vec<f32>,
+    /// 下一段的标称读取位置 (相对 `input` 起点的采样数)
+    position: f64,
+    /// 上一段的自然延续起点, 作为相似度搜索的模板; None 表示尚未输出
+    template: Option<usize>,
+    /// 上一段后半窗加权后的采样, 与下一段前半窗重叠相加
+    tail: Vec<f32>,
+    /// 已合成尚未打包的输出采样 (交错)
+    pending: Vec<f32>,
+    /// 已送入的输入采样数 (每声道)
+    input_samples: u64,
+    /// 已输出的采样数 (每声道)
+    output_samples: u64,
+    /// 首帧 pts (以 1/sample_rate 为单位)
+    start_pts: Option<i64>,
+    /// 输出帧队列
+    output: VecDeque<Frame>,
+}
+
+impl AtempoFilter {
+    /// 创建变速滤镜, 速度超出 [0.5, 2.0] 时返回错误
+    pub fn new(tempo: f64) -> TaoResult<Self> {
+        Self::check_tempo(tempo)?;
+        Ok(Self {
+            tempo,
+            sample_rate: 0,
+            channel_layout: ChannelLayout::from_channels(0),
+            window: Vec::new(),
+            input: Vec::new(),
+            position: 0.0,
+            template: None,
+            tail: Vec::new(),
+            pending: Vec::new(),
+            input_samples: 0,
+            output_samples: 0,
+            start_pts: None,
+            output: VecDeque::new(),
+        })
+    }
+
+    /// 当前速度
+    pub fn tempo(&self) -> f64 {
+        self.tempo
+    }
+
+    /// 调整速度, 对之后送入的采样生效
+    pub fn set_tempo(&mut self, tempo: f64) -> TaoResult<()> {
+        Self::check_tempo(tempo)?;
+        self.tempo = tempo;
+        Ok(())
+    }
+
+    fn check_tempo(tempo: f64) -> TaoResult<()> {
+        if !(ATEMPO_MIN..=ATEMPO_MAX).contains(&tempo) {
+            return Err(TaoError::InvalidArgument(format!(
+                "atempo: 速度 {tempo} 超出范围 [{ATEMPO_MIN}, {ATEMPO_MAX}]"
+            )));
+        }
+        Ok(())
+    }
+
+    fn channels(&self) -> usize {
+        self.channel_layout.channels as usize
+    }
+
+    /// 半窗长度 (合成步长)
+    fn hop(&self) -> usize {
+        self.window.len() / 2
+    }
+
+    /// 按首帧参数初始化窗口, 参数变化时丢弃已缓冲的数据重新开始
+    fn configure(&mut self, frame: &AudioFrame) -> TaoResult<()> {
+        if frame.sample_format != SampleFormat::F32 {
+            return Err(TaoError::Unsupported(format!(
+                "atempo: 不支持采样格式 {:?}",
+                frame.sample_format,
+            )));
+        }
+        if frame.sample_rate == 0 || frame.channel_layout.channels == 0 {
+            return Err(TaoError::InvalidArgument(
+                "atempo: 采样率与声道数不能为 0".to_string(),
+            ));
+        }
+        if frame.sample_rate == self.sample_rate
+            && frame.channel_layout.channels == self.channel_layout.channels
+        {
+            return Ok(());
+        }
+
+        self.sample_rate = frame.sample_rate;
+        self.channel_layout = frame.channel_layout;
+        let len = ((f64::from(frame.sample_rate) * WINDOW_SEC) as usize / 2 * 2).max(16);
+        self.window = (0..len)
+            .map(|n| {
+                let phase = 2.0 * std::f64::consts::PI * n as f64 / len as f64;
+                (0.5 - 0.5 * phase.cos()) as f32
+            })
+            .collect();
+        self.reset();
+        Ok(())
+    }
+
+    /// 清空缓冲状态 (保留参数与速度)
+    fn reset(&mut self) {
+        self.input.clear();
+        self.position = 0.0;
+        self.template = None;
+        self.tail = vec![0.0; self.hop() * self.channels()];
+        self.pending.clear();
+        self.input_samples = 0;
+        self.output_samples = 0;
+        self.start_pts = None;
+    }
+
+    /// 合成一个步长的输出, 输入不足时返回 false
+    fn step(&mut self) -> bool {
+        let channels = self.channels();
+        let win = self.window.len();
+        let hop = self.hop();
+        let search = hop / 4;
+        let available = self.input.len() / channels;
+        let nominal = self.position.round() as usize;
+        if nominal + search + win > available {
+            return false;
+        }
+
+        let start = match self.template {
+            None => nominal,
+            Some(template) => {
+                self.best_offset(template, nominal.saturating_sub(search), nominal + search)
+            }
+        };
+
+        let first = self.template.is_none();
+        for i in 0..hop {
+            let weight = if first { 1.0 } else { self.window[i] };
+            for ch in 0..channels {
+                let sample = self.input[(start + i) * channels + ch];
+                self.pending
+                    .push(self.tail[i * channels + ch] + weight * sample);
+                self.tail[i * channels + ch] =
+                    self.window[hop + i] * self.input[(start + hop + i) * channels + ch];
+            }
+        }
+
+        self.position += hop as f64 * self.tempo;
+        let next_template = start + hop;
+        // 丢弃之后不再访问的输入
+        let drop = (self.position.floor() as usize)
+            .saturating_sub(search)
+            .min(next_template);
+        self.input.drain(..drop * channels);
+        self.position -= drop as f64;
+        self.template = Some(next_template - drop);
+        true
+    }
+
+    /// 在 `[lo, hi]` 中搜索与模板重叠区最相似 (归一化互相关最大) 的片段起点
+    fn best_offset(&self, template: usize, lo: usize, hi: usize) -> usize {
+        let channels = self.channels();
+        let hop = self.hop();
+        let mono = |pos: usize| -> f32 {
+            self.input[pos * channels..(pos + 1) * channels]
+                .iter()
+                .sum()
+        };
+        let reference: Vec<f32> = (template..template + hop).map(mono).collect();
+        let candidates: Vec<f32> = (lo..hi + hop).map(mono).collect();
+
+        let mut best = lo;
+        let mut best_score = f32::NEG_INFINITY;
+        for offset in 0..=hi - lo {
+            let segment = &candidates[offset..offset + hop];
+            let (corr, energy) = reference
+                .iter()
+                .zip(segment)
+                .fold((0.0f32, 0.0f32), |(c, e), (&r, &s)| (c + r * s, e + s * s));
+            let score = corr / (energy.sqrt() + 1e-9);
+            if score > best_score {
+                best_score = score;
+                best = lo + offset;
+            }
+        }
+        best
+    }
+
+    /// 将已合成的采样打包为输出帧
+    fn emit(&mut self) {
+        let channels = self.channels();
+        let nb_samples = self.pending.len() / channels;
+        if nb_samples == 0 {
+            return;
+        }
+        let mut data = Vec::with_capacity(self.pending.len() * 4);
+        for s in self.pending.drain(..) {
+            data.extend_from_slice(&s.to_le_bytes());
+        }
+        let mut frame = AudioFrame::new(
+            nb_samples as u32,
+            self.sample_rate,
+            SampleFormat::F32,
+            self.channel_layout,
+        );
+        frame.data = vec![data.into()];
+        frame.time_base = Rational::new(1, self.sample_rate as i32);
+        frame.pts = self.start_pts.unwrap_or(0) + self.output_samples as i64;
+        frame.duration = nb_samples as i64;
+        self.output_samples += nb_samples as u64;
+        self.output.push_back(Frame::Audio(frame));
+    }
+}
+
+impl Filter for AtempoFilter {
+    fn name(&self) -> &str {
+        "atempo"
+    }
+
+    fn input_formats(&self) -> Vec<FrameFormat> {
+        SUPPORTED_FORMATS
+            .iter()
+            .copied()
+            .map(FrameFormat::Audio)
+            .collect()
+    }
+
+    fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
+        let Frame::Audio(af) = frame else {
+            return Err(TaoError::InvalidArgument("atempo 滤镜仅支持音频帧".into()));
+        };
+        self.configure(af)?;
+        if self.start_pts.is_none() && af.pts != NOPTS_VALUE && af.time_base.is_valid() {
+            self.start_pts = Some(rescale_q(
+                af.pts,
+                af.time_base,
+                Rational::new(1, self.sample_rate as i32),
+            ));
+        }
+
+        let nb_samples = af.nb_samples as usize;
+        if let Some(plane) = af.data.first() {
+            self.input.extend(
+                plane
+                    .chunks_exact(4)
+                    .take(nb_samples * self.channels())
+                    .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]])),
+            );
+        }
+        self.input_samples += nb_samples as u64;
+
+        while self.step() {}
+        self.emit();
+        Ok(())
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        self.output.pop_front().ok_or(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) -> TaoResult<()> {
+        if self.window.is_empty() {
+            return Ok(());
+        }
+        // 末尾补静音, 直到输出长度达到输入长度除以速度
+        let channels = self.channels();
+        let target = (self.input_samples as f64 / self.tempo).round() as u64;
+        while self.output_samples + ((self.pending.len() / channels) as u64) < target {
+            if !self.step() {
+                let len = self.input.len() + self.window.len() * channels;
+                self.input.resize(len, 0.0);
+            }
+        }
+        let keep = (target - self.output_samples) as usize * channels;
+        self.pending.truncate(keep);
+        self.emit();
+        self.reset();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 44100;
+
+    fn make_f32_frame(samples: &[f32], channels: u32, pts: i64) -> Frame {
+        let mut data = Vec::with_capacity(samples.len() * 4);
+        for &s in samples {
+            data.extend_from_slice(&s.to_le_bytes());
+        }
+        Frame::Audio(AudioFrame {
+            data: vec![data.into()],
+            nb_samples: samples.len() as u32 / channels,
+            sample_rate: SAMPLE_RATE,
+            sample_format: SampleFormat::F32,
+            channel_layout: ChannelLayout::from_channels(channels),
+            pts,
+            time_base: Rational::new(1, SAMPLE_RATE as i32),
+            duration: (samples.len() as u32 / channels) as i64,
+        })
+    }
+
+    fn extract_f32(frame: &Frame) -> Vec<f32> {
+        if let Frame::Audio(af) = frame {
+            af.data[0]
+                .chunks_exact(4)
+                .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                .collect()
+        } else {
+            panic!("期望音频帧");
+        }
+    }
+
+    /// 生成交错的正弦波, 各声道相同
+    fn sine(freq: f64, nb_samples: usize, channels: usize) -> Vec<f32> {
+        (0..nb_samples)
+            .flat_map(|i| {
+                let value =
+                    (2.0 * std::f64::consts::PI * freq * i as f64 / SAMPLE_RATE as f64).sin();
+                std::iter::repeat_n(value as f32 * 0.5, channels)
+            })
+            .collect()
+    }
+
+    /// 分块送入全部采样并刷新, 返回全部输出采样
+    fn run(filter: &mut AtempoFilter, samples: &[f32], channels: usize) -> Vec<f32> {
+        let mut out = Vec::new();
+        for (i, chunk) in samples.chunks(1024 * channels).enumerate() {
+            let pts = (i * 1024) as i64;
+            filter
+                .send_frame(&make_f32_frame(chunk, channels as u32, pts))
+                .unwrap();
+            while let Ok(frame) = filter.receive_frame() {
+                out.extend(extract_f32(&frame));
+            }
+        }
+        filter.flush().unwrap();
+        while let Ok(frame) = filter.receive_frame() {
+            out.extend(extract_f32(&frame));
+        }
+        out
+    }
+
+    /// 统计上升过零次数
+    fn rising_crossings(samples: &[f32]) -> usize {
+        samples
+            .windows(2)
+            .filter(|w| w[0] < 0.0 && w[1] >= 0.0)
+            .count()
+    }
+
+    #[test]
+    fn test_atempo_output_length_follows_tempo() {
+        let input = sine(440.0, SAMPLE_RATE as usize, 2);
+        for tempo in [0.5, 0.75, 1.5, 2.0] {
+            let mut filter = AtempoFilter::new(tempo).unwrap();
+            let out = run(&mut filter, &input, 2);
+            let expected = (SAMPLE_RATE as f64 / tempo).round() as usize;
+            assert_eq!(
+                out.len() / 2,
+                expected,
+                "速度 {tempo} 输出采样数应为输入除以速度"
+            );
+        }
+    }
+
+    #[test]
+    fn test_atempo_keeps_pitch() {
+        let input = sine(440.0, SAMPLE_RATE as usize, 1);
+        for tempo in [0.5, 2.0] {
+            let mut filter = AtempoFilter::new(tempo).unwrap();
+            let out = run(&mut filter, &input, 1);
+            // 去掉首尾 0.1 秒后按过零次数估计频率
+            let margin = SAMPLE_RATE as usize / 10;
+            let body = &out[margin..out.len() - margin];
+            let freq = rising_crossings(body) as f64 * SAMPLE_RATE as f64 / body.len() as f64;
+            assert!(
+                (freq - 440.0).abs() < 440.0 * 0.03,
+                "速度 {tempo} 下音调应保持 440Hz, 实际 {freq:.1}Hz"
+            );
+        }
+    }
+
+    #[test]
+    fn test_atempo_unity_passthrough() {
+        let input = sine(1000.0, 8192, 1);
+        let mut filter = AtempoFilter::new(1.0).unwrap();
+        let out = run(&mut filter, &input, 1);
+        assert_eq!(out.len(), input.len());
+        for (i, (a, b)) in input.iter().zip(&out).enumerate() {
+            assert!(
+                (a - b).abs() < 1e-4,
+                "原速时第 {i} 个采样应不变: {a} vs {b}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_atempo_pts_and_invalid_args() {
+        assert!(AtempoFilter::new(0.25).is_err(), "速度过低应报错");
+        assert!(AtempoFilter::new(3.0).is_err(), "速度过高应报错");
+        assert!(AtempoFilter::new(f64::NAN).is_err(), "NaN 速度应报错");
+
+        let mut filter = AtempoFilter::new(2.0).unwrap();
+        assert!(filter.set_tempo(4.0).is_err());
+        filter.set_tempo(1.5).unwrap();
+        assert_eq!(filter.tempo(), 1.5);
+
+        let mut s16 = AudioFrame::new(4, SAMPLE_RATE, SampleFormat::S16, ChannelLayout::MONO);
+        s16.data[0] = vec![0u8; 8].into();
+        assert!(
+            matches!(
+                filter.send_frame(&Frame::Audio(s16)),
+                Err(TaoError::Unsupported(_))
+            ),
+            "非 F32 格式应返回 Unsupported"
+        );
+
+        filter
+            .send_frame(&make_f32_frame(&sine(440.0, 8192, 1), 1, 1000))
+            .unwrap();
+        let Frame::Audio(first) = filter.receive_frame().unwrap() else {
+            panic!("期望音频帧");
+        };
+        assert_eq!(first.pts, 1000, "输出时间戳应从首帧 pts 开始");
+        assert_eq!(first.sample_format, SampleFormat::F32);
+    }
+}
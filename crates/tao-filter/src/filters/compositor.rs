@@ -0,0 +1,385 @@
+//! 多输入视频合成滤镜.
+//!
+//! 将多路视频按图层顺序混合为一帧, 用于画中画等场景.
+//! 第 0 路为底图, 决定输出尺寸与时间戳; 其余各路按顺序叠加在其上.
+
+use std::collections::VecDeque;
+
+use tao_codec::frame::{Frame, VideoFrame};
+use tao_core::{PixelFormat, TaoError, TaoResult};
+
+use crate::{Filter, FrameFormat, MultiInputFilter};
+
+/// 支持合成的像素格式 (与 `plane_layout` 一致)
+const SUPPORTED_FORMATS: &[PixelFormat] = &[
+    PixelFormat::Yuv420p,
+    PixelFormat::Yuv422p,
+    PixelFormat::Yuv444p,
+    PixelFormat::Gray8,
+    PixelFormat::Rgb24,
+    PixelFormat::Bgr24,
+];
+
+/// 合成图层参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompositorLayer {
+    /// 图层左上角在输出帧中的 X 坐标 (可为负, 超出部分被裁剪)
+    pub x: i32,
+    /// 图层左上角在输出帧中的 Y 坐标 (可为负, 超出部分被裁剪)
+    pub y: i32,
+    /// 透明度 (0.0 = 完全透明, 1.0 = 完全不透明)
+    pub alpha: f32,
+}
+
+impl CompositorLayer {
+    /// 创建图层参数
+    pub fn new(x: i32, y: i32, alpha: f32) -> Self {
+        Self {
+            x,
+            y,
+            alpha: alpha.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// 多输入视频合成滤镜
+///
+/// 每路输入对应一个图层. 底图 (第 0 路) 每送入一帧输出一帧;
+/// 叠加层保留最近一帧, 在新帧到达前重复使用.
+/// 所有叠加层都至少收到一帧后才开始输出, 此前的底图帧暂存.
+///
+/// 支持 8 位的 YUV 平面格式、Gray8、RGB24 与 BGR24, 各路像素格式必须一致.
+pub struct CompositorFilter {
+    /// 各图层参数, 下标与输入序号一致
+    layers: Vec<CompositorLayer>,
+    /// 各叠加层最近收到的帧 (下标 0 不使用)
+    latest: Vec<Option<VideoFrame>>,
+    /// 等待叠加层就绪的底图帧
+    pending: VecDeque<VideoFrame>,
+    /// 输出帧队列
+    output: VecDeque<Frame>,
+}
+
+impl CompositorFilter {
+    /// 创建合成滤镜
+    ///
+    /// `layers[0]` 为底图, 其位置与透明度不参与计算.
+    pub fn new(layers: Vec<CompositorLayer>) -> Self {
+        let count = layers.len();
+        Self {
+            layers,
+            latest: vec![None; count],
+            pending: VecDeque::new(),
+            output: VecDeque::new(),
+        }
+    }
+
+    /// 所有叠加层是否都已收到帧
+    fn layers_ready(&self) -> bool {
+        self.latest.iter().skip(1).all(Option::is_some)
+    }
+
+    /// 合成暂存的底图帧
+    ///
+    /// `force` 为真时不等待叠加层, 缺失的图层直接跳过.
+    fn drain_pending(&mut self, force: bool) -> TaoResult<()> {
+        if !force && !self.layers_ready() {
+            return Ok(());
+        }
+        while let Some(base) = self.pending.pop_front() {
+            let composite = self.composite(base)?;
+            self.output.push_back(Frame::Video(composite));
+        }
+        Ok(())
+    }
+
+    /// 将各叠加层依次混合到底图上
+    fn composite(&self, mut base: VideoFrame) -> TaoResult<VideoFrame> {
+        let planes = plane_layout(base.pixel_format).ok_or_else(|| {
+            TaoError::Unsupported(format!("compositor: 不支持像素格式 {}", base.pixel_format))
+        })?;
+        for (layer, frame) in self.layers.iter().zip(&self.latest).skip(1) {
+            let Some(frame) = frame else {
+                continue;
+            };
+            if frame.pixel_format != base.pixel_format {
+                return Err(TaoError::InvalidArgument(format!(
+                    "compositor: 图层像素格式 {} 与底图 {} 不一致",
+                    frame.pixel_format, base.pixel_format
+                )));
+            }
+            for (plane, &(bpp, shift_x, shift_y)) in planes.iter().enumerate() {
+                blend_plane(&mut base, frame, plane, layer, bpp, shift_x, shift_y);
+            }
+        }
+        Ok(base)
+    }
+}
+
+/// 各平面的 (每像素字节数, 水平子采样位移, 垂直子采样位移), 不支持的格式返回 None
+fn plane_layout(format: PixelFormat) -> Option<Vec<(usize, u32, u32)>> {
+    match format {
+        PixelFormat::Yuv420p | PixelFormat::Yuv422p | PixelFormat::Yuv444p => {
+            let (sx, sy) = format.chroma_subsampling();
+            Some(vec![(1, 0, 0), (1, sx, sy), (1, sx, sy)])
+        }
+        PixelFormat::Gray8 => Some(vec![(1, 0, 0)]),
+        PixelFormat::Rgb24 | PixelFormat::Bgr24 => Some(vec![(3, 0, 0)]),
+        _ => None,
+    }
+}
+
+/// 将图层的一个平面混合到底图对应平面, 超出底图的部分被裁剪
+fn blend_plane(
+    base: &mut VideoFrame,
+    layer_frame: &VideoFrame,
+    plane: usize,
+    layer: &CompositorLayer,
+    bpp: usize,
+    shift_x: u32,
+    shift_y: u32,
+) {
+    let (Some(dst_stride), Some(src_stride)) = (
+        base.linesize.get(plane).copied(),
+        layer_frame.linesize.get(plane).copied(),
+    ) else {
+        return;
+    };
+    let Some(src) = layer_frame.data.get(plane) else {
+        return;
+    };
+    if plane >= base.data.len() {
+        return;
+    }
+
+    let dst_w = (base.width >> shift_x) as i64;
+    let dst_h = (base.height >> shift_y) as i64;
+    let src_w = (layer_frame.width >> shift_x) as i64;
+    let src_h = (layer_frame.height >> shift_y) as i64;
+    let off_x = i64::from(layer.x >> shift_x);
+    let off_y = i64::from(layer.y >> shift_y);
+
+    // 图层与底图的相交区域 (底图坐标)
+    let x0 = off_x.max(0);
+    let y0 = off_y.max(0);
+    let x1 = (off_x + src_w).min(dst_w);
+    let y1 = (off_y + src_h).min(dst_h);
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+
+    let alpha = layer.alpha;
+    let dst = &mut base.data[plane];
+    let row_bytes = (x1 - x0) as usize * bpp;
+    for y in y0..y1 {
+        let dst_off = y as usize * dst_stride + x0 as usize * bpp;
+        let src_off = (y - off_y) as usize * src_stride + (x0 - off_x) as usize * bpp;
+        let (Some(dst_row), Some(src_row)) = (
+            dst.get_mut(dst_off..dst_off + row_bytes),
+            src.get(src_off..src_off + row_bytes),
+        ) else {
+            continue;
+        };
+        if alpha >= 1.0 {
+            dst_row.copy_from_slice(src_row);
+        } else {
+            for (d, &s) in dst_row.iter_mut().zip(src_row) {
+                let blended = s as f32 * alpha + *d as f32 * (1.0 - alpha);
+                *d = blended.round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+}
+
+impl Filter for CompositorFilter {
+    fn name(&self) -> &str {
+        "compositor"
+    }
+
+    fn input_formats(&self) -> Vec<FrameFormat> {
+        SUPPORTED_FORMATS
+            .iter()
+            .copied()
+            .map(FrameFormat::Video)
+            .collect()
+    }
+
+    fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
+        self.send_frame_to(0, frame)
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        self.output.pop_front().ok_or(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) -> TaoResult<()> {
+        self.drain_pending(true)
+    }
+}
+
+impl MultiInputFilter for CompositorFilter {
+    fn input_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    fn send_frame_to(&mut self, index: usize, frame: &Frame) -> TaoResult<()> {
+        if index >= self.layers.len() {
+            return Err(TaoError::InvalidArgument(format!(
+                "compositor: 输入序号 {index} 超出范围 (共 {} 路)",
+                self.layers.len()
+            )));
+        }
+        let Frame::Video(vf) = frame else {
+            return Err(TaoError::InvalidArgument(
+                "compositor: 只接受视频帧".to_string(),
+            ));
+        };
+        if index == 0 {
+            self.pending.push_back(vf.clone());
+        } else {
+            self.latest[index] = Some(vf.clone());
+        }
+        self.drain_pending(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tao_core::Rational;
+
+    fn make_rgb_frame(width: u32, height: u32, color: (u8, u8, u8), pts: i64) -> Frame {
+        let stride = (width as usize) * 3;
+        let mut data = Vec::with_capacity(stride * height as usize);
+        for _ in 0..(width * height) {
+            data.extend_from_slice(&[color.0, color.1, color.2]);
+        }
+        let mut vf = VideoFrame::new(width, height, PixelFormat::Rgb24);
+        vf.data = vec![data.into()];
+        vf.linesize = vec![stride];
+        vf.pts = pts;
+        vf.time_base = Rational::new(1, 30);
+        Frame::Video(vf)
+    }
+
+    fn pixel(frame: &Frame, x: usize, y: usize) -> (u8, u8, u8) {
+        let Frame::Video(vf) = frame else {
+            panic!("期望视频帧");
+        };
+        let off = y * vf.linesize[0] + x * 3;
+        (vf.data[0][off], vf.data[0][off + 1], vf.data[0][off + 2])
+    }
+
+    #[test]
+    fn test_picture_in_picture_layers_in_order() {
+        let mut filter = CompositorFilter::new(vec![
+            CompositorLayer::new(0, 0, 1.0),
+            CompositorLayer::new(10, 10, 1.0),
+            CompositorLayer::new(15, 15, 0.5),
+        ]);
+        assert_eq!(filter.input_count(), 3);
+
+        filter
+            .send_frame_to(0, &make_rgb_frame(40, 40, (0, 0, 0), 7))
+            .unwrap();
+        assert!(
+            matches!(filter.receive_frame(), Err(TaoError::NeedMoreData)),
+            "叠加层未就绪时不应输出"
+        );
+        filter
+            .send_frame_to(1, &make_rgb_frame(10, 10, (255, 0, 0), 0))
+            .unwrap();
+        filter
+            .send_frame_to(2, &make_rgb_frame(10, 10, (0, 0, 255), 0))
+            .unwrap();
+
+        let out = filter.receive_frame().unwrap();
+        let Frame::Video(vf) = &out else {
+            panic!("期望视频帧");
+        };
+        assert_eq!(
+            (vf.width, vf.height, vf.pts),
+            (40, 40, 7),
+            "尺寸与时间戳取自底图"
+        );
+        assert_eq!(pixel(&out, 5, 5), (0, 0, 0), "图层之外保持底图");
+        assert_eq!(pixel(&out, 12, 12), (255, 0, 0), "第 1 层不透明覆盖");
+        assert_eq!(
+            pixel(&out, 17, 17),
+            (128, 0, 128),
+            "第 2 层半透明叠加在第 1 层之上"
+        );
+        assert_eq!(
+            pixel(&out, 22, 22),
+            (0, 0, 128),
+            "第 2 层半透明叠加在底图之上"
+        );
+    }
+
+    #[test]
+    fn test_layers_clip_and_repeat_last_frame() {
+        let mut filter = CompositorFilter::new(vec![
+            CompositorLayer::new(0, 0, 1.0),
+            CompositorLayer::new(-5, 15, 1.0),
+        ]);
+        filter
+            .send_frame_to(1, &make_rgb_frame(10, 10, (0, 255, 0), 0))
+            .unwrap();
+        for pts in 0..2 {
+            filter
+                .send_frame(&make_rgb_frame(20, 20, (0, 0, 0), pts))
+                .unwrap();
+            let out = filter.receive_frame().unwrap();
+            assert_eq!(pixel(&out, 0, 15), (0, 255, 0), "负坐标图层应被裁剪后叠加");
+            assert_eq!(pixel(&out, 4, 19), (0, 255, 0));
+            assert_eq!(pixel(&out, 5, 15), (0, 0, 0), "图层右边界之外保持底图");
+        }
+    }
+
+    #[test]
+    fn test_yuv420p_chroma_offset() {
+        let make = |w: u32, h: u32, y: u8, uv: u8| {
+            let mut vf = VideoFrame::new(w, h, PixelFormat::Yuv420p);
+            let (cw, ch) = ((w / 2) as usize, (h / 2) as usize);
+            vf.data = vec![
+                vec![y; (w * h) as usize].into(),
+                vec![uv; cw * ch].into(),
+                vec![uv; cw * ch].into(),
+            ];
+            vf.linesize = vec![w as usize, cw, cw];
+            Frame::Video(vf)
+        };
+        let mut filter = CompositorFilter::new(vec![
+            CompositorLayer::new(0, 0, 1.0),
+            CompositorLayer::new(4, 4, 1.0),
+        ]);
+        filter.send_frame_to(1, &make(4, 4, 200, 50)).unwrap();
+        filter.send_frame_to(0, &make(16, 16, 16, 128)).unwrap();
+        let Frame::Video(vf) = filter.receive_frame().unwrap() else {
+            panic!("期望视频帧");
+        };
+        assert_eq!(vf.data[0][4 * 16 + 4], 200);
+        assert_eq!(vf.data[0][3 * 16 + 3], 16);
+        assert_eq!(vf.data[1][2 * 8 + 2], 50, "色度平面按子采样换算坐标");
+        assert_eq!(vf.data[1][8 + 1], 128);
+    }
+
+    #[test]
+    fn test_flush_and_invalid_input() {
+        let mut filter = CompositorFilter::new(vec![
+            CompositorLayer::new(0, 0, 1.0),
+            CompositorLayer::new(0, 0, 1.0),
+        ]);
+        assert!(
+            filter
+                .send_frame_to(2, &make_rgb_frame(4, 4, (0, 0, 0), 0))
+                .is_err()
+        );
+        filter
+            .send_frame(&make_rgb_frame(4, 4, (9, 9, 9), 0))
+            .unwrap();
+        filter.flush().unwrap();
+        let out = filter.receive_frame().expect("刷新时应输出暂存的底图帧");
+        assert_eq!(pixel(&out, 0, 0), (9, 9, 9), "缺失的图层应跳过");
+    }
+}
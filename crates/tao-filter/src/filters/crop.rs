@@ -5,7 +5,20 @@
 use tao_codec::frame::{Frame, VideoFrame};
 use tao_core::{PixelFormat, TaoError, TaoResult};
 
-use crate::Filter;
+use crate::{Filter, FrameFormat};
+
+/// 支持裁剪的像素格式 (平面 YUV 与 packed 格式)
+const SUPPORTED_FORMATS: &[PixelFormat] = &[
+    PixelFormat::Yuv420p,
+    PixelFormat::Yuv422p,
+    PixelFormat::Yuv444p,
+    PixelFormat::Rgb24,
+    PixelFormat::Bgr24,
+    PixelFormat::Rgba,
+    PixelFormat::Bgra,
+    PixelFormat::Argb,
+    PixelFormat::Gray8,
+];
 
 /// 视频裁剪滤镜
 pub struct CropFilter {
@@ -83,7 +96,7 @@ impl CropFilter {
             }
         }
 
-        out.data = vec![dst];
+        out.data = vec![dst.into()];
         out.linesize = vec![dst_stride];
         Ok(out)
     }
@@ -115,7 +128,7 @@ impl CropFilter {
         let u_plane = crop_plane(&frame.data[1], frame.linesize[1], cx, cy, cw, ch);
         let v_plane = crop_plane(&frame.data[2], frame.linesize[2], cx, cy, cw, ch);
 
-        out.data = vec![y_plane, u_plane, v_plane];
+        out.data = vec![y_plane.into(), u_plane.into(), v_plane.into()];
         out.linesize = vec![self.width as usize, cw, cw];
         Ok(out)
     }
@@ -126,6 +139,14 @@ impl Filter for CropFilter {
         "crop"
     }
 
+    fn input_formats(&self) -> Vec<FrameFormat> {
+        SUPPORTED_FORMATS
+            .iter()
+            .copied()
+            .map(FrameFormat::Video)
+            .collect()
+    }
+
     fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
         match frame {
             Frame::Video(vf) => {
@@ -133,7 +154,9 @@ impl Filter for CropFilter {
                 self.output = Some(Frame::Video(result));
                 Ok(())
             }
-            Frame::Audio(_) => Err(TaoError::InvalidArgument("crop 滤镜仅支持视频帧".into())),
+            Frame::Audio(_) | Frame::Subtitle(_) => {
+                Err(TaoError::InvalidArgument("crop 滤镜仅支持视频帧".into()))
+            }
         }
     }
 
@@ -195,7 +218,7 @@ mod tests {
             }
         }
         let mut vf = VideoFrame::new(width, height, PixelFormat::Rgb24);
-        vf.data = vec![data];
+        vf.data = vec![data.into()];
         vf.linesize = vec![stride];
         vf.time_base = Rational::new(1, 30);
         Frame::Video(vf)
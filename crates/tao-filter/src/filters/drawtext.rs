@@ -233,7 +233,7 @@ impl Filter for DrawtextFilter {
                 }
                 Ok(())
             }
-            Frame::Audio(_) => {
+            Frame::Audio(_) | Frame::Subtitle(_) => {
                 self.output = Some(frame.clone());
                 Ok(())
             }
@@ -259,7 +259,7 @@ mod tests {
         let stride = (width as usize) * 3;
         let data = vec![0u8; stride * (height as usize)];
         let mut vf = VideoFrame::new(width, height, PixelFormat::Rgb24);
-        vf.data = vec![data];
+        vf.data = vec![data.into()];
         vf.linesize = vec![stride];
         vf.time_base = Rational::new(1, 30);
         Frame::Video(vf)
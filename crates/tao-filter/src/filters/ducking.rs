@@ -0,0 +1,533 @@
+//! 音频闪避 (ducking) 滤镜.
+//!
+//! 用于旁白配音的自动混音: 主输入 (人声旁白) 的 RMS 电平超过阈值时,
+//! 将副输入 (背景音乐) 衰减指定的 dB 数, 再与主输入混合输出.
+//! 增益按起音 (attack) / 释放 (release) 时间做指数平滑, 避免音量突变.
+//!
+//! 两路输入的采样率、采样格式与声道数必须一致, 输出沿用相同参数.
+//! 支持 S16/S24/S32/F32/F64 的交错与平面格式.
+
+use std::collections::VecDeque;
+
+use tao_codec::frame::{AudioFrame, Frame};
+use tao_core::rational::rescale_q;
+use tao_core::timestamp::NOPTS_VALUE;
+use tao_core::{ChannelLayout, Rational, SampleFormat, TaoError, TaoResult};
+
+use crate::{Filter, MultiInputFilter};
+
+/// 主输入 (旁白) 的输入序号
+pub const DUCKING_PRIMARY: usize = 0;
+/// 副输入 (背景音乐) 的输入序号
+pub const DUCKING_SECONDARY: usize = 1;
+
+/// RMS 电平检测的平滑窗口 (秒)
+const RMS_WINDOW_SEC: f64 = 0.01;
+/// 电平检测下限 (-120 dBFS), 低于该电平视为静音
+const MIN_MEAN_SQUARE: f64 = 1e-12;
+
+/// 两路输入共用的音频参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AudioFormat {
+    sample_rate: u32,
+    sample_format: SampleFormat,
+    channel_layout: ChannelLayout,
+}
+
+/// 音频闪避滤镜
+///
+/// 两路输入各自缓冲为归一化的交错采样, 两路都有数据时逐采样计算增益并混合输出.
+/// 输出时间戳以副输入首帧为起点按采样数递增. 刷新时较短的一路按静音补齐.
+pub struct DuckingFilter {
+    /// 触发闪避的主输入 RMS 阈值 (dBFS)
+    threshold_db: f64,
+    /// 闪避时副输入的线性增益
+    reduction_gain: f64,
+    /// 起音时间 (秒): 增益从 1.0 降向衰减值的时间常数
+    attack_sec: f64,
+    /// 释放时间 (秒): 增益恢复到 1.0 的时间常数
+    release_sec: f64,
+    /// 由首帧确定的音频参数
+    format: Option<AudioFormat>,
+    /// 主输入缓冲 (交错, 归一化到 [-1, 1])
+    primary: VecDeque<f64>,
+    /// 副输入缓冲 (交错, 归一化到 [-1, 1])
+    secondary: VecDeque<f64>,
+    /// 主输入的均方电平 (指数平滑)
+    mean_square: f64,
+    /// 当前副输入增益 (线性)
+    gain: f64,
+    /// 下一输出帧的 pts (以 1/sample_rate 为单位)
+    next_pts: Option<i64>,
+    /// 输出帧队列
+    output: VecDeque<Frame>,
+}
+
+impl DuckingFilter {
+    /// 创建闪避滤镜
+    ///
+    /// - `threshold_db`: 主输入 RMS 超过该值 (dBFS, 如 -30) 时开始闪避
+    /// - `reduction_db`: 闪避时副输入的衰减量 (dB, 正数, 如 12 表示降低 12dB)
+    /// - `attack_sec` / `release_sec`: 增益下降 / 恢复的时间常数 (秒), 0 表示立即生效
+    pub fn new(threshold_db: f64, reduction_db: f64, attack_sec: f64, release_sec: f64) -> Self {
+        Self {
+            threshold_db,
+            reduction_gain: 10.0_f64.powf(-reduction_db.abs() / 20.0),
+            attack_sec: attack_sec.max(0.0),
+            release_sec: release_sec.max(0.0),
+            format: None,
+            primary: VecDeque::new(),
+            secondary: VecDeque::new(),
+            mean_square: 0.0,
+            gain: 1.0,
+            next_pts: None,
+            output: VecDeque::new(),
+        }
+    }
+
+    /// 送入主输入 (旁白) 的一帧
+    pub fn send_primary(&mut self, frame: &Frame) -> TaoResult<()> {
+        self.send_frame_to(DUCKING_PRIMARY, frame)
+    }
+
+    /// 送入副输入 (背景音乐) 的一帧
+    pub fn send_secondary(&mut self, frame: &Frame) -> TaoResult<()> {
+        self.send_frame_to(DUCKING_SECONDARY, frame)
+    }
+
+    /// 当前副输入增益 (dB, 0 表示未闪避)
+    pub fn current_gain_db(&self) -> f64 {
+        20.0 * self.gain.log10()
+    }
+
+    /// 校验帧参数与已确定的参数一致, 首帧确定参数
+    fn check_format(&mut self, frame: &AudioFrame) -> TaoResult<AudioFormat> {
+        let format = AudioFormat {
+            sample_rate: frame.sample_rate,
+            sample_format: frame.sample_format,
+            channel_layout: frame.channel_layout,
+        };
+        if frame.sample_rate == 0 || frame.channel_layout.channels == 0 {
+            return Err(TaoError::InvalidArgument(
+                "ducking: 采样率与声道数不能为 0".to_string(),
+            ));
+        }
+        match self.format {
+            None => {
+                self.format = Some(format);
+                Ok(format)
+            }
+            Some(expected) if expected == format => Ok(format),
+            Some(expected) => Err(TaoError::InvalidArgument(format!(
+                "ducking: 输入参数 {} Hz/{}/{} 声道与已有输入 {} Hz/{}/{} 声道不一致",
+                format.sample_rate,
+                format.sample_format,
+                format.channel_layout.channels,
+                expected.sample_rate,
+                expected.sample_format,
+                expected.channel_layout.channels,
+            ))),
+        }
+    }
+
+    /// 按时间常数计算每采样的平滑系数
+    fn smoothing_coeff(time_sec: f64, sample_rate: u32) -> f64 {
+        if time_sec <= 0.0 {
+            1.0
+        } else {
+            1.0 - (-1.0 / (time_sec * f64::from(sample_rate))).exp()
+        }
+    }
+
+    /// 混合两路都已缓冲的采样, `pad` 为真时较短的一路按静音补齐
+    fn process(&mut self, pad: bool) -> TaoResult<()> {
+        let Some(format) = self.format else {
+            return Ok(());
+        };
+        let channels = format.channel_layout.channels as usize;
+        let available = if pad {
+            self.primary.len().max(self.secondary.len())
+        } else {
+            self.primary.len().min(self.secondary.len())
+        };
+        let nb_samples = available / channels;
+        if nb_samples == 0 {
+            return Ok(());
+        }
+
+        let rms_coeff = Self::smoothing_coeff(RMS_WINDOW_SEC, format.sample_rate);
+        let attack_coeff = Self::smoothing_coeff(self.attack_sec, format.sample_rate);
+        let release_coeff = Self::smoothing_coeff(self.release_sec, format.sample_rate);
+        let threshold_ms = 10.0_f64.powf(self.threshold_db / 10.0);
+
+        let mut mixed = Vec::with_capacity(nb_samples * channels);
+        let mut primary_frame = vec![0.0; channels];
+        for _ in 0..nb_samples {
+            let mut power = 0.0;
+            for value in primary_frame.iter_mut() {
+                *value = self.primary.pop_front().unwrap_or(0.0);
+                power += *value * *value;
+            }
+            self.mean_square += (power / channels as f64 - self.mean_square) * rms_coeff;
+
+            let target = if self.mean_square.max(MIN_MEAN_SQUARE) > threshold_ms {
+                self.reduction_gain
+            } else {
+                1.0
+            };
+            let coeff = if target < self.gain {
+                attack_coeff
+            } else {
+                release_coeff
+            };
+            self.gain += (target - self.gain) * coeff;
+
+            for &p in &primary_frame {
+                let s = self.secondary.pop_front().unwrap_or(0.0);
+                mixed.push(p + s * self.gain);
+            }
+        }
+
+        let mut frame = AudioFrame::new(
+            nb_samples as u32,
+            format.sample_rate,
+            format.sample_format,
+            format.channel_layout,
+        );
+        frame.data = encode_samples(&mixed, format.sample_format, channels)?;
+        frame.time_base = Rational::new(1, format.sample_rate as i32);
+        frame.pts = self.next_pts.unwrap_or(0);
+        frame.duration = nb_samples as i64;
+        self.next_pts = Some(frame.pts + nb_samples as i64);
+        self.output.push_back(Frame::Audio(frame));
+        Ok(())
+    }
+}
+
+impl Filter for DuckingFilter {
+    fn name(&self) -> &str {
+        "ducking"
+    }
+
+    fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
+        self.send_frame_to(DUCKING_PRIMARY, frame)
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        self.output.pop_front().ok_or(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) -> TaoResult<()> {
+        self.process(true)
+    }
+}
+
+impl MultiInputFilter for DuckingFilter {
+    fn input_count(&self) -> usize {
+        2
+    }
+
+    fn send_frame_to(&mut self, index: usize, frame: &Frame) -> TaoResult<()> {
+        if index > DUCKING_SECONDARY {
+            return Err(TaoError::InvalidArgument(format!(
+                "ducking: 输入序号 {index} 超出范围 (共 2 路)"
+            )));
+        }
+        let Frame::Audio(af) = frame else {
+            return Err(TaoError::InvalidArgument(
+                "ducking: 只接受音频帧".to_string(),
+            ));
+        };
+        let format = self.check_format(af)?;
+        let samples = decode_samples(af, format.channel_layout.channels as usize)?;
+
+        if index == DUCKING_SECONDARY {
+            if self.next_pts.is_none() && af.pts != NOPTS_VALUE && af.time_base.is_valid() {
+                self.next_pts = Some(rescale_q(
+                    af.pts,
+                    af.time_base,
+                    Rational::new(1, format.sample_rate as i32),
+                ));
+            }
+            self.secondary.extend(samples);
+        } else {
+            self.primary.extend(samples);
+        }
+        self.process(false)
+    }
+}
+
+/// 将音频帧解码为归一化到 [-1, 1] 的交错采样
+fn decode_samples(frame: &AudioFrame, channels: usize) -> TaoResult<Vec<f64>> {
+    let nb_samples = frame.nb_samples as usize;
+    let bytes = frame.sample_format.bytes_per_sample() as usize;
+    let read = sample_reader(frame.sample_format)?;
+
+    let mut out = vec![0.0; nb_samples * channels];
+    if frame.sample_format.is_planar() {
+        for (ch, plane) in frame.data.iter().take(channels).enumerate() {
+            for (i, chunk) in plane.chunks_exact(bytes).take(nb_samples).enumerate() {
+                out[i * channels + ch] = read(chunk);
+            }
+        }
+    } else if let Some(plane) = frame.data.first() {
+        for (value, chunk) in out.iter_mut().zip(plane.chunks_exact(bytes)) {
+            *value = read(chunk);
+        }
+    }
+    Ok(out)
+}
+
+/// 将归一化的交错采样编码为指定格式的音频数据 (整数格式饱和裁剪)
+fn encode_samples(
+    samples: &[f64],
+    format: SampleFormat,
+    channels: usize,
+) -> TaoResult<Vec<tao_codec::FrameBuf>> {
+    let write = sample_writer(format)?;
+    let bytes = format.bytes_per_sample() as usize;
+    if format.is_planar() {
+        let nb_samples = samples.len() / channels;
+        Ok((0..channels)
+            .map(|ch| {
+                let mut plane = Vec::with_capacity(nb_samples * bytes);
+                for i in 0..nb_samples {
+                    write(samples[i * channels + ch], &mut plane);
+                }
+                plane.into()
+            })
+            .collect())
+    } else {
+        let mut data = Vec::with_capacity(samples.len() * bytes);
+        for &s in samples {
+            write(s, &mut data);
+        }
+        Ok(vec![data.into()])
+    }
+}
+
+/// 单个采样的读取函数 (小端字节 -> 归一化浮点)
+fn sample_reader(format: SampleFormat) -> TaoResult<fn(&[u8]) -> f64> {
+    let read: fn(&[u8]) -> f64 = match format {
+        SampleFormat::S16 | SampleFormat::S16p => {
+            |b| f64::from(i16::from_le_bytes([b[0], b[1]])) / 32768.0
+        }
+        SampleFormat::S24 | SampleFormat::S24p => {
+            |b| f64::from(i32::from_le_bytes([b[0], b[1], b[2], b[3]])) / 8_388_608.0
+        }
+        SampleFormat::S32 | SampleFormat::S32p => {
+            |b| f64::from(i32::from_le_bytes([b[0], b[1], b[2], b[3]])) / 2_147_483_648.0
+        }
+        SampleFormat::F32 | SampleFormat::F32p => {
+            |b| f64::from(f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        }
+        SampleFormat::F64 | SampleFormat::F64p => {
+            |b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+        }
+        _ => {
+            return Err(TaoError::Unsupported(format!(
+                "ducking 滤镜不支持采样格式 {format}"
+            )));
+        }
+    };
+    Ok(read)
+}
+
+/// 单个采样的写出函数 (归一化浮点 -> 小端字节)
+fn sample_writer(format: SampleFormat) -> TaoResult<fn(f64, &mut Vec<u8>)> {
+    let write: fn(f64, &mut Vec<u8>) = match format {
+        SampleFormat::S16 | SampleFormat::S16p => |v, out| {
+            let s = (v * 32768.0).round().clamp(-32768.0, 32767.0) as i16;
+            out.extend_from_slice(&s.to_le_bytes());
+        },
+        SampleFormat::S24 | SampleFormat::S24p => |v, out| {
+            let s = (v * 8_388_608.0).round().clamp(-8_388_608.0, 8_388_607.0) as i32;
+            out.extend_from_slice(&s.to_le_bytes());
+        },
+        SampleFormat::S32 | SampleFormat::S32p => |v, out| {
+            let s = (v * 2_147_483_648.0)
+                .round()
+                .clamp(i32::MIN as f64, i32::MAX as f64) as i32;
+            out.extend_from_slice(&s.to_le_bytes());
+        },
+        SampleFormat::F32 | SampleFormat::F32p => {
+            |v, out| out.extend_from_slice(&(v as f32).to_le_bytes())
+        }
+        SampleFormat::F64 | SampleFormat::F64p => |v, out| out.extend_from_slice(&v.to_le_bytes()),
+        _ => {
+            return Err(TaoError::Unsupported(format!(
+                "ducking 滤镜不支持采样格式 {format}"
+            )));
+        }
+    };
+    Ok(write)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 1000;
+
+    fn make_f32_frame(samples: &[f32], pts: i64) -> Frame {
+        let mut af = AudioFrame::new(
+            samples.len() as u32,
+            RATE,
+            SampleFormat::F32,
+            ChannelLayout::MONO,
+        );
+        af.data = vec![samples.iter().flat_map(|s| s.to_le_bytes()).collect()];
+        af.pts = pts;
+        af.time_base = Rational::new(1, RATE as i32);
+        af.duration = samples.len() as i64;
+        Frame::Audio(af)
+    }
+
+    fn extract_f32(frame: &Frame) -> Vec<f32> {
+        let Frame::Audio(af) = frame else {
+            panic!("期望音频帧");
+        };
+        af.data[0]
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    }
+
+    /// 主输入为方波人声 (±0.5), 副输入为 0.25 的直流背景音乐
+    fn voice(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| if i % 2 == 0 { 0.5 } else { -0.5 })
+            .collect()
+    }
+
+    #[test]
+    fn test_ducking_silent_primary_passes_secondary() {
+        let mut filter = DuckingFilter::new(-30.0, 12.0, 0.01, 0.1);
+        filter
+            .send_primary(&make_f32_frame(&[0.0; 100], 0))
+            .unwrap();
+        filter
+            .send_secondary(&make_f32_frame(&[0.25; 100], 0))
+            .unwrap();
+        let out = extract_f32(&filter.receive_frame().unwrap());
+        assert_eq!(out.len(), 100);
+        assert!(
+            out.iter().all(|&s| (s - 0.25).abs() < 1e-6),
+            "主输入静音时副输入应原样输出"
+        );
+        assert!(
+            filter.current_gain_db().abs() < 1e-9,
+            "未闪避时增益应为 0dB"
+        );
+    }
+
+    #[test]
+    fn test_ducking_attack_and_release() {
+        // 1 kHz 采样率: 起音 10ms, 释放 100ms, 闪避 20dB
+        let mut filter = DuckingFilter::new(-30.0, 20.0, 0.01, 0.1);
+        let speech = voice(200);
+        filter.send_primary(&make_f32_frame(&speech, 0)).unwrap();
+        filter
+            .send_secondary(&make_f32_frame(&[0.25; 200], 0))
+            .unwrap();
+        let out = extract_f32(&filter.receive_frame().unwrap());
+        let ducked: Vec<f32> = out.iter().zip(&speech).map(|(o, p)| o - p).collect();
+        assert!(ducked[0] > 0.2, "闪避开始前副输入应接近原电平");
+        assert!(
+            ducked.windows(2).all(|w| w[1] <= w[0] + 1e-6),
+            "起音阶段副输入增益应单调下降"
+        );
+        assert!(
+            (ducked[199] - 0.025).abs() < 1e-3,
+            "起音完成后副输入应衰减 20dB, 实际 {}",
+            ducked[199]
+        );
+        assert!((filter.current_gain_db() + 20.0).abs() < 0.1);
+
+        // 人声停止后按释放时间恢复
+        filter
+            .send_primary(&make_f32_frame(&[0.0; 1000], 200))
+            .unwrap();
+        filter
+            .send_secondary(&make_f32_frame(&[0.25; 1000], 200))
+            .unwrap();
+        let out = extract_f32(&filter.receive_frame().unwrap());
+        assert!(out[50] < 0.1, "释放时间内增益应缓慢恢复");
+        assert!(
+            (out[999] - 0.25).abs() < 1e-3,
+            "释放完成后副输入应恢复原电平, 实际 {}",
+            out[999]
+        );
+    }
+
+    #[test]
+    fn test_ducking_waits_for_both_inputs_and_flush_pads() {
+        let mut filter = DuckingFilter::new(-30.0, 12.0, 0.0, 0.0);
+        filter
+            .send_secondary(&make_f32_frame(&[0.25; 100], 500))
+            .unwrap();
+        assert!(
+            matches!(filter.receive_frame(), Err(TaoError::NeedMoreData)),
+            "主输入缺失时不应输出"
+        );
+
+        filter.send_primary(&make_f32_frame(&[0.0; 60], 0)).unwrap();
+        let Frame::Audio(first) = filter.receive_frame().unwrap() else {
+            panic!("期望音频帧");
+        };
+        assert_eq!(first.nb_samples, 60, "只混合两路都已到达的采样");
+        assert_eq!(first.pts, 500, "输出时间戳应以副输入为准");
+
+        filter.flush().unwrap();
+        let Frame::Audio(rest) = filter.receive_frame().unwrap() else {
+            panic!("期望音频帧");
+        };
+        assert_eq!(rest.nb_samples, 40, "刷新时主输入不足部分按静音补齐");
+        assert_eq!(rest.pts, 560);
+    }
+
+    #[test]
+    fn test_ducking_s16_stereo_planar() {
+        let mut filter = DuckingFilter::new(-30.0, 6.0, 0.0, 0.0);
+        let make = |left: i16, right: i16| {
+            let mut af = AudioFrame::new(4, RATE, SampleFormat::S16p, ChannelLayout::STEREO);
+            af.data = vec![
+                [left; 4].iter().flat_map(|s| s.to_le_bytes()).collect(),
+                [right; 4].iter().flat_map(|s| s.to_le_bytes()).collect(),
+            ];
+            Frame::Audio(af)
+        };
+        filter.send_primary(&make(16384, -16384)).unwrap();
+        filter.send_secondary(&make(8000, 8000)).unwrap();
+        let Frame::Audio(out) = filter.receive_frame().unwrap() else {
+            panic!("期望音频帧");
+        };
+        let plane = |p: usize| -> Vec<i16> {
+            out.data[p]
+                .chunks_exact(2)
+                .map(|c| i16::from_le_bytes([c[0], c[1]]))
+                .collect()
+        };
+        // 0 起音时间下立即闪避 6dB: 8000 * 10^(-6/20) ≈ 4009.5
+        assert_eq!(plane(0), vec![20393; 4], "左声道应为人声加衰减后的音乐");
+        assert_eq!(plane(1), vec![-12375; 4], "右声道应为人声加衰减后的音乐");
+    }
+
+    #[test]
+    fn test_ducking_rejects_mismatched_inputs() {
+        let mut filter = DuckingFilter::new(-30.0, 12.0, 0.01, 0.1);
+        filter.send_primary(&make_f32_frame(&[0.0; 10], 0)).unwrap();
+        let mut other = AudioFrame::new(10, 48000, SampleFormat::F32, ChannelLayout::MONO);
+        other.data = vec![vec![0u8; 40].into()];
+        assert!(
+            filter.send_secondary(&Frame::Audio(other)).is_err(),
+            "采样率不一致应报错"
+        );
+        assert!(
+            filter
+                .send_frame_to(2, &make_f32_frame(&[0.0; 10], 0))
+                .is_err(),
+            "输入序号超出范围应报错"
+        );
+    }
+}
@@ -5,7 +5,15 @@
 use tao_codec::frame::{AudioFrame, Frame};
 use tao_core::{SampleFormat, TaoError, TaoResult};
 
-use crate::Filter;
+use crate::{Filter, FrameFormat};
+
+/// 支持均衡的采样格式
+const SUPPORTED_FORMATS: &[SampleFormat] = &[
+    SampleFormat::F32,
+    SampleFormat::F32p,
+    SampleFormat::S16,
+    SampleFormat::S16p,
+];
 
 /// 双二阶滤波器频段
 struct BiquadBand {
@@ -224,6 +232,14 @@ impl Filter for EqualizerFilter {
         "equalizer"
     }
 
+    fn input_formats(&self) -> Vec<FrameFormat> {
+        SUPPORTED_FORMATS
+            .iter()
+            .copied()
+            .map(FrameFormat::Audio)
+            .collect()
+    }
+
     fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
         match frame {
             Frame::Audio(af) => {
@@ -240,7 +256,7 @@ impl Filter for EqualizerFilter {
                 self.output = Some(Frame::Audio(result));
                 Ok(())
             }
-            Frame::Video(_) => Err(TaoError::InvalidArgument(
+            Frame::Video(_) | Frame::Subtitle(_) => Err(TaoError::InvalidArgument(
                 "equalizer 滤镜仅支持音频帧".into(),
             )),
         }
@@ -273,7 +289,7 @@ mod tests {
             data.extend_from_slice(&s.to_le_bytes());
         }
         Frame::Audio(AudioFrame {
-            data: vec![data],
+            data: vec![data.into()],
             nb_samples: samples.len() as u32,
             sample_rate,
             sample_format: SampleFormat::F32,
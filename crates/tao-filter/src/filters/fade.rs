@@ -10,7 +10,15 @@
 use tao_codec::frame::{AudioFrame, Frame, VideoFrame};
 use tao_core::{SampleFormat, TaoError, TaoResult};
 
-use crate::Filter;
+use crate::{Filter, FrameFormat};
+
+/// 支持音频淡入淡出的采样格式 (视频帧不限制像素格式)
+const SUPPORTED_AUDIO_FORMATS: &[SampleFormat] = &[
+    SampleFormat::F32,
+    SampleFormat::F32p,
+    SampleFormat::S16,
+    SampleFormat::S16p,
+];
 
 /// 淡变类型
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -140,10 +148,21 @@ impl Filter for FadeFilter {
         "fade"
     }
 
+    fn input_formats(&self) -> Vec<FrameFormat> {
+        SUPPORTED_AUDIO_FORMATS
+            .iter()
+            .copied()
+            .map(FrameFormat::Audio)
+            .collect()
+    }
+
     fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
         let result = match frame {
             Frame::Audio(af) => Frame::Audio(self.fade_audio(af)?),
             Frame::Video(vf) => Frame::Video(self.fade_video(vf)?),
+            Frame::Subtitle(_) => {
+                return Err(TaoError::InvalidArgument("fade 滤镜仅支持音视频帧".into()));
+            }
         };
         self.output = Some(result);
         Ok(())
@@ -170,7 +189,7 @@ mod tests {
             data.extend_from_slice(&s.to_le_bytes());
         }
         Frame::Audio(AudioFrame {
-            data: vec![data],
+            data: vec![data.into()],
             nb_samples: samples.len() as u32,
             sample_rate: 44100,
             sample_format: SampleFormat::F32,
@@ -235,7 +254,7 @@ mod tests {
         let mut filter = FadeFilter::new(FadeType::In, 0.0, 1.0);
 
         let mut vf = VideoFrame::new(2, 2, PixelFormat::Rgb24);
-        vf.data = vec![vec![255; 12]]; // 2x2 白色
+        vf.data = vec![vec![255; 12].into()]; // 2x2 白色
         vf.linesize = vec![6];
         vf.pts = 0;
         vf.time_base = Rational::new(1, 1);
@@ -0,0 +1,289 @@
+//! 像素格式转换滤镜.
+//!
+//! 对标 FFmpeg 的 `format` 滤镜, 借助 tao-scale 的格式转换将视频帧转换到指定像素格式,
+//! 使只接受特定输入格式的滤镜 (如仅支持 RGB 的滤镜) 可以接在任意解码输出之后.
+
+use tao_codec::frame::{Frame, VideoFrame};
+use tao_core::{PixelFormat, TaoError, TaoResult};
+use tao_scale::convert::{self, ConvertInput, ConvertOutput};
+
+use crate::{Filter, FrameFormat};
+
+/// 像素格式转换滤镜
+///
+/// 输入帧的像素格式在可接受列表中时原样输出, 否则转换为列表中
+/// 第一个可由输入格式转换得到的格式.
+pub struct FormatFilter {
+    /// 可接受的像素格式 (按优先级排列)
+    formats: Vec<PixelFormat>,
+    /// 输出帧缓冲
+    output: Option<Frame>,
+}
+
+impl FormatFilter {
+    /// 创建转换到单一像素格式的滤镜
+    pub fn new(format: PixelFormat) -> Self {
+        Self::with_formats(vec![format])
+    }
+
+    /// 创建接受多个像素格式的滤镜, 需要转换时优先选择靠前的格式
+    pub fn with_formats(formats: Vec<PixelFormat>) -> Self {
+        Self {
+            formats,
+            output: None,
+        }
+    }
+
+    /// 解析 FFmpeg 风格的像素格式列表 (如 `yuv420p|rgb24`)
+    pub fn parse_formats(list: &str) -> TaoResult<Vec<PixelFormat>> {
+        list.split('|')
+            .map(|name| {
+                PixelFormat::from_name(name).ok_or_else(|| {
+                    TaoError::InvalidArgument(format!("format: 未知像素格式 {}", name.trim()))
+                })
+            })
+            .collect()
+    }
+
+    /// 可接受的像素格式
+    pub fn formats(&self) -> &[PixelFormat] {
+        &self.formats
+    }
+
+    /// 选择输入格式对应的输出格式
+    fn select_format(&self, src: PixelFormat) -> TaoResult<PixelFormat> {
+        if self.formats.is_empty() {
+            return Err(TaoError::InvalidArgument(
+                "format: 未指定目标像素格式".into(),
+            ));
+        }
+        if self.formats.contains(&src) {
+            return Ok(src);
+        }
+        self.formats
+            .iter()
+            .copied()
+            .find(|&dst| convert::is_conversion_supported(src, dst))
+            .ok_or_else(|| {
+                TaoError::Unsupported(format!(
+                    "format: 无法将 {src} 转换为 {:?} 中的任一格式",
+                    self.formats,
+                ))
+            })
+    }
+
+    /// 转换视频帧
+    fn convert_frame(&self, frame: &VideoFrame) -> TaoResult<VideoFrame> {
+        let dst_format = self.select_format(frame.pixel_format)?;
+        if dst_format == frame.pixel_format {
+            return Ok(frame.clone());
+        }
+        let (width, height) = (frame.width, frame.height);
+
+        let plane_count = dst_format.plane_count() as usize;
+        let mut dst_bufs = Vec::with_capacity(plane_count);
+        let mut dst_linesizes = Vec::with_capacity(plane_count);
+        for p in 0..plane_count {
+            let (Some(ls), Some(h)) = (
+                dst_format.plane_linesize(p, width),
+                dst_format.plane_height(p, height),
+            ) else {
+                return Err(TaoError::Unsupported(format!(
+                    "format: 不支持像素格式 {dst_format}"
+                )));
+            };
+            dst_bufs.push(vec![0u8; ls * h]);
+            dst_linesizes.push(ls);
+        }
+
+        let color_space = frame.color_space.resolve(height);
+        let input = ConvertInput {
+            planes: frame.data.iter().map(|d| d.as_ref()).collect(),
+            linesize: frame.linesize.clone(),
+            width,
+            height,
+            format: frame.pixel_format,
+            color_space,
+            color_range: frame.color_range,
+        };
+        {
+            let mut output = ConvertOutput {
+                planes: dst_bufs.iter_mut().map(|b| b.as_mut_slice()).collect(),
+                linesize: dst_linesizes.clone(),
+                width,
+                height,
+                format: dst_format,
+                color_space,
+                color_range: frame.color_range,
+            };
+            convert::convert(&input, &mut output)?;
+        }
+
+        let mut out = VideoFrame::new(width, height, dst_format);
+        out.data = dst_bufs.into_iter().map(Into::into).collect();
+        out.linesize = dst_linesizes;
+        out.pts = frame.pts;
+        out.time_base = frame.time_base;
+        out.duration = frame.duration;
+        out.is_keyframe = frame.is_keyframe;
+        out.picture_type = frame.picture_type;
+        out.sample_aspect_ratio = frame.sample_aspect_ratio;
+        out.color_space = frame.color_space;
+        out.color_range = frame.color_range;
+        Ok(out)
+    }
+}
+
+impl Filter for FormatFilter {
+    fn name(&self) -> &str {
+        "format"
+    }
+
+    fn output_format(&self, input: FrameFormat) -> FrameFormat {
+        match input {
+            FrameFormat::Video(src) => self
+                .select_format(src)
+                .map(FrameFormat::Video)
+                .unwrap_or(input),
+            FrameFormat::Audio(_) => input,
+        }
+    }
+
+    fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
+        match frame {
+            Frame::Video(vf) => {
+                let result = self.convert_frame(vf)?;
+                self.output = Some(Frame::Video(result));
+                Ok(())
+            }
+            Frame::Audio(_) | Frame::Subtitle(_) => {
+                Err(TaoError::InvalidArgument("format 滤镜仅支持视频帧".into()))
+            }
+        }
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        self.output.take().ok_or(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) -> TaoResult<()> {
+        self.output = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FilterGraph;
+    use std::sync::{Arc, Mutex};
+    use tao_core::Rational;
+
+    /// 仅接受 RGB24 输入的测试滤镜, 记录收到的像素格式
+    struct RgbOnlyFilter {
+        received: Arc<Mutex<Vec<PixelFormat>>>,
+        output: Option<Frame>,
+    }
+
+    impl Filter for RgbOnlyFilter {
+        fn name(&self) -> &str {
+            "rgb_only"
+        }
+
+        fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
+            let Frame::Video(vf) = frame else {
+                return Err(TaoError::InvalidArgument("仅支持视频帧".into()));
+            };
+            self.received.lock().unwrap().push(vf.pixel_format);
+            if vf.pixel_format != PixelFormat::Rgb24 {
+                return Err(TaoError::Unsupported(format!(
+                    "仅支持 rgb24 输入, 实际为 {}",
+                    vf.pixel_format
+                )));
+            }
+            self.output = Some(frame.clone());
+            Ok(())
+        }
+
+        fn receive_frame(&mut self) -> TaoResult<Frame> {
+            self.output.take().ok_or(TaoError::NeedMoreData)
+        }
+
+        fn flush(&mut self) -> TaoResult<()> {
+            Ok(())
+        }
+    }
+
+    fn make_yuv420p_frame(width: u32, height: u32, y: u8) -> Frame {
+        let (cw, ch) = (width.div_ceil(2) as usize, height.div_ceil(2) as usize);
+        let mut vf = VideoFrame::new(width, height, PixelFormat::Yuv420p);
+        vf.data = vec![
+            vec![y; (width * height) as usize].into(),
+            vec![128u8; cw * ch].into(),
+            vec![128u8; cw * ch].into(),
+        ];
+        vf.linesize = vec![width as usize, cw, cw];
+        vf.pts = 3;
+        vf.time_base = Rational::new(1, 30);
+        Frame::Video(vf)
+    }
+
+    #[test]
+    fn test_format_rgb24_before_rgb_only_filter() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let rgb_only = || RgbOnlyFilter {
+            received: Arc::clone(&received),
+            output: None,
+        };
+
+        let mut graph = FilterGraph::new();
+        graph.add_filter(Box::new(rgb_only()));
+        assert!(
+            graph.process_frame(&make_yuv420p_frame(4, 4, 235)).is_err(),
+            "未转换时 RGB 滤镜应拒绝 YUV 输入"
+        );
+        received.lock().unwrap().clear();
+
+        let formats = FormatFilter::parse_formats("rgb24").unwrap();
+        let mut graph = FilterGraph::new();
+        graph.add_filter(Box::new(FormatFilter::with_formats(formats)));
+        graph.add_filter(Box::new(rgb_only()));
+        let output = graph.process_frame(&make_yuv420p_frame(4, 4, 235)).unwrap();
+        assert_eq!(graph.filter_names(), vec!["format", "rgb_only"]);
+        assert_eq!(*received.lock().unwrap(), vec![PixelFormat::Rgb24]);
+        let Frame::Video(vf) = output else {
+            panic!("期望视频帧");
+        };
+        assert_eq!(vf.pixel_format, PixelFormat::Rgb24);
+        assert_eq!(vf.linesize, vec![12]);
+        assert_eq!((vf.pts, vf.time_base), (3, Rational::new(1, 30)));
+        assert!(
+            vf.data[0].iter().all(|&v| v == 255),
+            "有限范围白色应转换为 RGB 白色"
+        );
+    }
+
+    #[test]
+    fn test_format_passthrough_when_accepted() {
+        let mut filter = FormatFilter::with_formats(vec![PixelFormat::Rgb24, PixelFormat::Yuv420p]);
+        filter.send_frame(&make_yuv420p_frame(4, 4, 16)).unwrap();
+        let Frame::Video(vf) = filter.receive_frame().unwrap() else {
+            panic!("期望视频帧");
+        };
+        assert_eq!(
+            vf.pixel_format,
+            PixelFormat::Yuv420p,
+            "已接受的格式应原样输出"
+        );
+    }
+
+    #[test]
+    fn test_format_unsupported_conversion_error() {
+        let mut filter = FormatFilter::new(PixelFormat::Gray16le);
+        assert!(matches!(
+            filter.send_frame(&make_yuv420p_frame(4, 4, 16)),
+            Err(TaoError::Unsupported(_))
+        ));
+        assert!(FormatFilter::parse_formats("yuv420p|nope").is_err());
+    }
+}
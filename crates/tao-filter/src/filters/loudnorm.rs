@@ -5,7 +5,15 @@
 use tao_codec::frame::{AudioFrame, Frame};
 use tao_core::{SampleFormat, TaoError, TaoResult};
 
-use crate::Filter;
+use crate::{Filter, FrameFormat};
+
+/// 支持响度归一化的采样格式
+const SUPPORTED_FORMATS: &[SampleFormat] = &[
+    SampleFormat::F32,
+    SampleFormat::F32p,
+    SampleFormat::S16,
+    SampleFormat::S16p,
+];
 
 /// EBU R128 响度归一化滤镜
 pub struct LoudnormFilter {
@@ -163,6 +171,14 @@ impl Filter for LoudnormFilter {
         "loudnorm"
     }
 
+    fn input_formats(&self) -> Vec<FrameFormat> {
+        SUPPORTED_FORMATS
+            .iter()
+            .copied()
+            .map(FrameFormat::Audio)
+            .collect()
+    }
+
     fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
         match frame {
             Frame::Audio(af) => {
@@ -179,7 +195,7 @@ impl Filter for LoudnormFilter {
                 self.output = Some(Frame::Audio(result));
                 Ok(())
             }
-            Frame::Video(_) => Err(TaoError::InvalidArgument(
+            Frame::Video(_) | Frame::Subtitle(_) => Err(TaoError::InvalidArgument(
                 "loudnorm 滤镜仅支持音频帧".into(),
             )),
         }
@@ -212,7 +228,7 @@ mod tests {
             data.extend_from_slice(&s.to_le_bytes());
         }
         Frame::Audio(AudioFrame {
-            data: vec![data],
+            data: vec![data.into()],
             nb_samples: samples.len() as u32,
             sample_rate,
             sample_format: SampleFormat::F32,
@@ -2,11 +2,17 @@
 //!
 //! 提供常用的音视频处理滤镜.
 
+pub mod aformat;
+pub mod atempo;
+pub mod compositor;
 pub mod crop;
 pub mod drawtext;
+pub mod ducking;
 pub mod equalizer;
 pub mod fade;
+pub mod format;
 pub mod loudnorm;
 pub mod overlay;
 pub mod pad;
+pub mod scale;
 pub mod volume;
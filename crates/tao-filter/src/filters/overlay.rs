@@ -133,7 +133,7 @@ impl Filter for OverlayFilter {
                 }
                 Ok(())
             }
-            Frame::Audio(_) => {
+            Frame::Audio(_) | Frame::Subtitle(_) => {
                 self.output = Some(frame.clone());
                 Ok(())
             }
@@ -164,7 +164,7 @@ mod tests {
             data[i * 3 + 2] = b;
         }
         let mut vf = VideoFrame::new(width, height, PixelFormat::Rgb24);
-        vf.data = vec![data];
+        vf.data = vec![data.into()];
         vf.linesize = vec![stride];
         vf.time_base = Rational::new(1, 30);
         Frame::Video(vf)
@@ -231,7 +231,11 @@ mod tests {
     fn test_passthrough_non_rgb() {
         let mut filter = OverlayFilter::from_solid_color(0, 0, 10, 10, (255, 0, 0), 1.0);
         let mut vf = VideoFrame::new(100, 100, PixelFormat::Yuv420p);
-        vf.data = vec![vec![128; 100 * 100], vec![128; 50 * 50], vec![128; 50 * 50]];
+        vf.data = vec![
+            vec![128; 100 * 100].into(),
+            vec![128; 50 * 50].into(),
+            vec![128; 50 * 50].into(),
+        ];
         vf.linesize = vec![100, 50, 50];
         let input = Frame::Video(vf.clone());
         filter.send_frame(&input).unwrap();
@@ -5,7 +5,17 @@
 use tao_codec::frame::{Frame, VideoFrame};
 use tao_core::{PixelFormat, TaoError, TaoResult};
 
-use crate::Filter;
+use crate::{Filter, FrameFormat};
+
+/// 支持填充的像素格式 (仅 packed 格式)
+const SUPPORTED_FORMATS: &[PixelFormat] = &[
+    PixelFormat::Rgb24,
+    PixelFormat::Bgr24,
+    PixelFormat::Rgba,
+    PixelFormat::Bgra,
+    PixelFormat::Argb,
+    PixelFormat::Gray8,
+];
 
 /// 填充颜色 (RGB)
 #[derive(Debug, Clone, Copy)]
@@ -134,7 +144,7 @@ impl PadFilter {
             }
         }
 
-        out.data = vec![dst];
+        out.data = vec![dst.into()];
         out.linesize = vec![dst_stride];
         Ok(out)
     }
@@ -145,6 +155,14 @@ impl Filter for PadFilter {
         "pad"
     }
 
+    fn input_formats(&self) -> Vec<FrameFormat> {
+        SUPPORTED_FORMATS
+            .iter()
+            .copied()
+            .map(FrameFormat::Video)
+            .collect()
+    }
+
     fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
         match frame {
             Frame::Video(vf) => {
@@ -152,7 +170,9 @@ impl Filter for PadFilter {
                 self.output = Some(Frame::Video(result));
                 Ok(())
             }
-            Frame::Audio(_) => Err(TaoError::InvalidArgument("pad 滤镜仅支持视频帧".into())),
+            Frame::Audio(_) | Frame::Subtitle(_) => {
+                Err(TaoError::InvalidArgument("pad 滤镜仅支持视频帧".into()))
+            }
         }
     }
 
@@ -194,7 +214,7 @@ mod tests {
             }
         }
         let mut vf = VideoFrame::new(width, height, PixelFormat::Rgb24);
-        vf.data = vec![data];
+        vf.data = vec![data.into()];
         vf.linesize = vec![stride];
         vf.time_base = Rational::new(1, 30);
         Frame::Video(vf)
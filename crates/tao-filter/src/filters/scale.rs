@@ -0,0 +1,322 @@
+//! 视频缩放滤镜.
+//!
+//! 对标 FFmpeg 的 `scale` 滤镜, 借助 tao-scale 的 [`ScaleContext`] 将视频帧缩放到指定尺寸.
+//! 宽或高为负数时按输入宽高比推算: `-1` 保持宽高比, `-n` 额外取整到 n 的倍数.
+
+use tao_codec::frame::{Frame, VideoFrame};
+use tao_core::{Rational, TaoError, TaoResult};
+use tao_scale::{ScaleAlgorithm, ScaleContext};
+
+use crate::Filter;
+
+/// 视频缩放滤镜
+pub struct ScaleFilter {
+    /// 目标宽度 (负数表示按宽高比推算)
+    width: i32,
+    /// 目标高度 (负数表示按宽高比推算)
+    height: i32,
+    /// 缩放算法
+    algorithm: ScaleAlgorithm,
+    /// 缓存的缩放上下文, 输入尺寸、像素格式或色彩参数变化时重建
+    ctx: Option<ScaleContext>,
+    /// 输出帧缓冲
+    output: Option<Frame>,
+}
+
+impl ScaleFilter {
+    /// 创建缩放滤镜 (默认双线性插值)
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            algorithm: ScaleAlgorithm::default(),
+            ctx: None,
+            output: None,
+        }
+    }
+
+    /// 指定缩放算法
+    pub fn with_algorithm(mut self, algorithm: ScaleAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self.ctx = None;
+        self
+    }
+
+    /// 按 FFmpeg `flags` 名称解析缩放算法
+    ///
+    /// 支持 `neighbor`, `fast_bilinear`/`bilinear`, `bicubic`, `lanczos`, `area`.
+    pub fn parse_flags(flags: &str) -> Option<ScaleAlgorithm> {
+        match flags.trim().to_ascii_lowercase().as_str() {
+            "neighbor" | "point" => Some(ScaleAlgorithm::NearestNeighbor),
+            "fast_bilinear" | "bilinear" => Some(ScaleAlgorithm::Bilinear),
+            "bicubic" => Some(ScaleAlgorithm::Bicubic),
+            "lanczos" => Some(ScaleAlgorithm::lanczos()),
+            "area" => Some(ScaleAlgorithm::Area),
+            _ => None,
+        }
+    }
+
+    /// 根据输入尺寸计算输出尺寸
+    fn output_size(&self, in_width: u32, in_height: u32) -> TaoResult<(u32, u32)> {
+        if self.width == 0 || self.height == 0 {
+            return Err(TaoError::InvalidArgument(format!(
+                "scale: 目标尺寸不能为 0, width={}, height={}",
+                self.width, self.height,
+            )));
+        }
+        let (w, h) = match (self.width > 0, self.height > 0) {
+            (true, true) => (self.width as u32, self.height as u32),
+            (true, false) => {
+                let w = self.width as u32;
+                (w, derive_dimension(w, in_height, in_width, self.height))
+            }
+            (false, true) => {
+                let h = self.height as u32;
+                (derive_dimension(h, in_width, in_height, self.width), h)
+            }
+            (false, false) => (in_width, in_height),
+        };
+        if w == 0 || h == 0 {
+            return Err(TaoError::InvalidArgument(format!(
+                "scale: 推算的输出尺寸非法, {}x{} -> {}x{}",
+                in_width, in_height, w, h,
+            )));
+        }
+        Ok((w, h))
+    }
+
+    /// 缩放视频帧
+    fn scale_frame(&mut self, frame: &VideoFrame) -> TaoResult<VideoFrame> {
+        let (dst_w, dst_h) = self.output_size(frame.width, frame.height)?;
+        let fmt = frame.pixel_format;
+
+        let ctx = match self.ctx.take() {
+            Some(ctx)
+                if ctx.src_width == frame.width
+                    && ctx.src_height == frame.height
+                    && ctx.src_format == fmt
+                    && ctx.src_color_space == frame.color_space
+                    && ctx.src_color_range == frame.color_range
+                    && ctx.dst_width == dst_w
+                    && ctx.dst_height == dst_h =>
+            {
+                ctx
+            }
+            _ => ScaleContext::new(
+                frame.width,
+                frame.height,
+                fmt,
+                dst_w,
+                dst_h,
+                fmt,
+                self.algorithm,
+            )
+            .with_src_colorimetry(frame.color_space, frame.color_range)
+            .with_dst_colorimetry(frame.color_space, frame.color_range),
+        };
+
+        let plane_count = fmt.plane_count() as usize;
+        let mut dst_bufs = Vec::with_capacity(plane_count);
+        let mut dst_linesizes = Vec::with_capacity(plane_count);
+        for p in 0..plane_count {
+            let (Some(ls), Some(h)) = (fmt.plane_linesize(p, dst_w), fmt.plane_height(p, dst_h))
+            else {
+                return Err(TaoError::Unsupported(format!(
+                    "scale: 不支持像素格式 {fmt:?}"
+                )));
+            };
+            dst_bufs.push(vec![0u8; ls * h]);
+            dst_linesizes.push(ls);
+        }
+
+        let src_planes: Vec<&[u8]> = frame.data.iter().map(|d| d.as_ref()).collect();
+        {
+            let mut dst_slices: Vec<&mut [u8]> =
+                dst_bufs.iter_mut().map(|b| b.as_mut_slice()).collect();
+            ctx.scale(
+                &src_planes,
+                &frame.linesize,
+                &mut dst_slices,
+                &dst_linesizes,
+            )?;
+        }
+        self.ctx = Some(ctx);
+
+        let mut out = VideoFrame::new(dst_w, dst_h, fmt);
+        out.data = dst_bufs.into_iter().map(Into::into).collect();
+        out.linesize = dst_linesizes;
+        out.pts = frame.pts;
+        out.time_base = frame.time_base;
+        out.duration = frame.duration;
+        out.is_keyframe = frame.is_keyframe;
+        out.picture_type = frame.picture_type;
+        out.color_space = frame.color_space;
+        out.color_range = frame.color_range;
+        // 与 FFmpeg 一致: 调整 SAR 使显示宽高比保持不变
+        let sar = frame.sample_aspect_ratio;
+        out.sample_aspect_ratio = if sar.num > 0 && sar.den > 0 {
+            Rational::from_i64(
+                i64::from(sar.num) * i64::from(dst_h) * i64::from(frame.width),
+                i64::from(sar.den) * i64::from(dst_w) * i64::from(frame.height),
+            )
+        } else {
+            sar
+        };
+        Ok(out)
+    }
+}
+
+/// 按宽高比由已知边推算另一边: `round(known * other_in / (known_in * n)) * n`, `n` 为 `spec` 的绝对值
+fn derive_dimension(known: u32, other_in: u32, known_in: u32, spec: i32) -> u32 {
+    let align = u64::from(spec.unsigned_abs());
+    let den = u64::from(known_in) * align;
+    if den == 0 {
+        return 0;
+    }
+    let units = (u64::from(known) * u64::from(other_in) + den / 2) / den;
+    (units.max(1) * align).min(u64::from(u32::MAX)) as u32
+}
+
+impl Filter for ScaleFilter {
+    fn name(&self) -> &str {
+        "scale"
+    }
+
+    fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
+        match frame {
+            Frame::Video(vf) => {
+                let result = self.scale_frame(vf)?;
+                self.output = Some(Frame::Video(result));
+                Ok(())
+            }
+            Frame::Audio(_) | Frame::Subtitle(_) => {
+                Err(TaoError::InvalidArgument("scale 滤镜仅支持视频帧".into()))
+            }
+        }
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        self.output.take().ok_or(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) -> TaoResult<()> {
+        self.output = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FilterGraph;
+    use tao_core::PixelFormat;
+
+    fn make_gray_frame(width: u32, height: u32, value: u8) -> Frame {
+        let mut vf = VideoFrame::new(width, height, PixelFormat::Gray8);
+        vf.data = vec![vec![value; (width * height) as usize].into()];
+        vf.linesize = vec![width as usize];
+        vf.pts = 7;
+        vf.time_base = Rational::new(1, 25);
+        Frame::Video(vf)
+    }
+
+    fn make_yuv420p_frame(width: u32, height: u32) -> Frame {
+        let (cw, ch) = (width.div_ceil(2) as usize, height.div_ceil(2) as usize);
+        let mut vf = VideoFrame::new(width, height, PixelFormat::Yuv420p);
+        vf.data = vec![
+            vec![100u8; (width * height) as usize].into(),
+            vec![60u8; cw * ch].into(),
+            vec![200u8; cw * ch].into(),
+        ];
+        vf.linesize = vec![width as usize, cw, cw];
+        Frame::Video(vf)
+    }
+
+    #[test]
+    fn test_scale_4x4_to_8x8_in_filter_graph() {
+        let mut graph = FilterGraph::new();
+        graph.add_filter(Box::new(ScaleFilter::new(8, 8)));
+        let output = graph.process_frame(&make_yuv420p_frame(4, 4)).unwrap();
+        let Frame::Video(vf) = output else {
+            panic!("期望视频帧");
+        };
+        assert_eq!((vf.width, vf.height), (8, 8));
+        assert_eq!(vf.pixel_format, PixelFormat::Yuv420p);
+        assert_eq!(vf.linesize, vec![8, 4, 4]);
+        assert!(
+            vf.data[0].iter().all(|&v| v == 100),
+            "均匀亮度缩放后应保持不变"
+        );
+        assert!(vf.data[1].iter().all(|&v| v == 60));
+        assert!(vf.data[2].iter().all(|&v| v == 200));
+    }
+
+    #[test]
+    fn test_scale_keeps_timing_and_reuses_context() {
+        let mut filter = ScaleFilter::new(8, 8).with_algorithm(ScaleAlgorithm::NearestNeighbor);
+        for _ in 0..2 {
+            filter.send_frame(&make_gray_frame(4, 4, 42)).unwrap();
+            let Frame::Video(vf) = filter.receive_frame().unwrap() else {
+                panic!("期望视频帧");
+            };
+            assert_eq!(vf.pts, 7);
+            assert_eq!(vf.time_base, Rational::new(1, 25));
+            assert!(vf.data[0].iter().all(|&v| v == 42));
+        }
+        assert!(filter.ctx.is_some(), "相同输入参数应复用缩放上下文");
+    }
+
+    #[test]
+    fn test_scale_negative_dimension_preserves_aspect() {
+        let filter = ScaleFilter::new(8, -1);
+        assert_eq!(filter.output_size(16, 8).unwrap(), (8, 4));
+        let filter = ScaleFilter::new(-1, 6);
+        assert_eq!(filter.output_size(16, 8).unwrap(), (12, 6));
+        let filter = ScaleFilter::new(10, -4);
+        assert_eq!(
+            filter.output_size(16, 9).unwrap(),
+            (10, 4),
+            "-4 应取整到 4 的倍数"
+        );
+        assert!(ScaleFilter::new(0, 8).output_size(4, 4).is_err());
+    }
+
+    #[test]
+    fn test_scale_adjusts_sample_aspect_ratio() {
+        let mut filter = ScaleFilter::new(8, 4);
+        filter.send_frame(&make_gray_frame(4, 4, 0)).unwrap();
+        let Frame::Video(vf) = filter.receive_frame().unwrap() else {
+            panic!("期望视频帧");
+        };
+        assert_eq!(
+            vf.sample_aspect_ratio,
+            Rational::new(1, 2),
+            "横向拉伸 2 倍、纵向不变时 SAR 应减半以保持显示宽高比"
+        );
+    }
+
+    #[test]
+    fn test_scale_parse_flags() {
+        assert_eq!(
+            ScaleFilter::parse_flags("neighbor"),
+            Some(ScaleAlgorithm::NearestNeighbor)
+        );
+        assert_eq!(
+            ScaleFilter::parse_flags("Lanczos"),
+            Some(ScaleAlgorithm::lanczos())
+        );
+        assert_eq!(ScaleFilter::parse_flags("unknown"), None);
+    }
+
+    #[test]
+    fn test_scale_audio_frame_error() {
+        let mut filter = ScaleFilter::new(8, 8);
+        let af = Frame::Audio(tao_codec::frame::AudioFrame::new(
+            1024,
+            44100,
+            tao_core::SampleFormat::F32,
+            tao_core::ChannelLayout::from_channels(2),
+        ));
+        assert!(filter.send_frame(&af).is_err());
+    }
+}
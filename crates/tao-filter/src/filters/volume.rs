@@ -1,67 +1,208 @@
 //! 音量调节滤镜.
 //!
 //! 对标 FFmpeg 的 `volume` 滤镜, 支持线性倍数和 dB 两种方式指定增益.
+//!
+//! 除统一增益外, 还支持:
+//! - 按声道增益 (`with_per_channel`), 用于左右平衡调节
+//! - dB 渐变 (`ramp`), 按 pts 在多帧之间平滑插值增益
+//!
+//! 支持 S16/S32/F32/F64 的交错与平面格式, 整数格式在原位换算并饱和裁剪.
 
 use tao_codec::frame::{AudioFrame, Frame};
-use tao_core::{SampleFormat, TaoError, TaoResult};
+use tao_core::{SampleFormat, TaoError, TaoResult, timestamp::NOPTS_VALUE};
+
+use crate::{Filter, FrameFormat};
 
-use crate::Filter;
+/// 支持调整音量的采样格式
+const SUPPORTED_FORMATS: &[SampleFormat] = &[
+    SampleFormat::F32,
+    SampleFormat::F32p,
+    SampleFormat::S16,
+    SampleFormat::S16p,
+    SampleFormat::S32,
+    SampleFormat::S32p,
+    SampleFormat::S24,
+    SampleFormat::S24p,
+    SampleFormat::F64,
+    SampleFormat::F64p,
+];
 
 /// 音量调节滤镜
 pub struct VolumeFilter {
     /// 增益系数 (线性, 1.0 = 不变)
     gain: f64,
+    /// 各声道增益 (线性), 为空表示所有声道相同; 超出长度的声道按 1.0 处理
+    channel_gains: Vec<f64>,
+    /// dB 渐变状态
+    ramp: Option<VolumeRamp>,
     /// 输出帧缓冲
     output: Option<Frame>,
 }
 
+/// dB 渐变参数与状态
+struct VolumeRamp {
+    /// 起始增益 (dB)
+    from_db: f64,
+    /// 结束增益 (dB)
+    to_db: f64,
+    /// 渐变时长 (秒)
+    duration_sec: f64,
+    /// 渐变起点 (秒), 由首帧时间确定
+    start_sec: Option<f64>,
+    /// 下一帧的预期起始时间 (秒), 用于无 pts 时累加推算
+    next_sec: f64,
+}
+
+impl VolumeRamp {
+    /// 计算指定时间点的线性增益
+    fn gain_at(&self, time_sec: f64) -> f64 {
+        let elapsed = time_sec - self.start_sec.unwrap_or(time_sec);
+        let progress = if self.duration_sec <= 0.0 {
+            1.0
+        } else {
+            (elapsed / self.duration_sec).clamp(0.0, 1.0)
+        };
+        let db = self.from_db + (self.to_db - self.from_db) * progress;
+        db_to_linear(db)
+    }
+}
+
 impl VolumeFilter {
     /// 使用线性增益创建 (1.0 = 不变, 2.0 = 加倍, 0.5 = 减半)
     pub fn new(gain: f64) -> Self {
-        Self { gain, output: None }
+        Self {
+            gain,
+            channel_gains: Vec::new(),
+            ramp: None,
+            output: None,
+        }
     }
 
     /// 使用 dB 增益创建 (0 = 不变, 6 约 加倍, -6 约 减半)
     pub fn from_db(db: f64) -> Self {
-        Self::new(10.0_f64.powf(db / 20.0))
+        Self::new(db_to_linear(db))
+    }
+
+    /// 使用按声道的线性增益创建 (用于平衡调节)
+    ///
+    /// `gains[i]` 作用于第 i 个声道, 未给出的声道保持不变.
+    pub fn with_per_channel(gains: Vec<f64>) -> Self {
+        Self {
+            channel_gains: gains,
+            ..Self::new(1.0)
+        }
+    }
+
+    /// 创建 dB 渐变滤镜
+    ///
+    /// 以首帧 pts 为起点, 在 `duration_sec` 秒内将增益从 `from_db` 线性过渡到 `to_db`,
+    /// 之后保持 `to_db`. 增益按采样点插值, 跨帧连续.
+    pub fn ramp(from_db: f64, to_db: f64, duration_sec: f64) -> Self {
+        Self {
+            ramp: Some(VolumeRamp {
+                from_db,
+                to_db,
+                duration_sec,
+                start_sec: None,
+                next_sec: 0.0,
+            }),
+            ..Self::new(1.0)
+        }
+    }
+
+    /// 获取指定声道的线性增益
+    fn channel_gain(&self, channel: usize) -> f64 {
+        self.gain * self.channel_gains.get(channel).copied().unwrap_or(1.0)
+    }
+
+    /// 计算本帧各采样位置的渐变增益, 无渐变时返回 None
+    fn ramp_gains(&mut self, frame: &AudioFrame) -> Option<Vec<f64>> {
+        let ramp = self.ramp.as_mut()?;
+        let frame_sec = if frame.pts != NOPTS_VALUE && frame.time_base.is_valid() {
+            frame.pts as f64 * frame.time_base.to_f64()
+        } else {
+            ramp.next_sec
+        };
+        if ramp.start_sec.is_none() {
+            ramp.start_sec = Some(frame_sec);
+        }
+
+        let rate = f64::from(frame.sample_rate.max(1));
+        let gains = (0..frame.nb_samples)
+            .map(|i| ramp.gain_at(frame_sec + f64::from(i) / rate))
+            .collect();
+        ramp.next_sec = frame_sec + f64::from(frame.nb_samples) / rate;
+        Some(gains)
     }
 
     /// 对音频帧应用增益
-    fn apply_gain(&self, frame: &AudioFrame) -> TaoResult<AudioFrame> {
+    fn apply_gain(&mut self, frame: &AudioFrame) -> TaoResult<AudioFrame> {
+        let ramp_gains = self.ramp_gains(frame);
+        let planar = frame.sample_format.is_planar();
+        let channels = (frame.channel_layout.channels as usize).max(1);
         let mut out = frame.clone();
 
+        // 采样序号 -> (声道, 采样位置)
+        let locate = |plane_idx: usize, idx: usize| -> (usize, usize) {
+            if planar {
+                (plane_idx, idx)
+            } else {
+                (idx % channels, idx / channels)
+            }
+        };
+        let gain_for = |plane_idx: usize, idx: usize| -> f64 {
+            let (ch, pos) = locate(plane_idx, idx);
+            let ramp = ramp_gains
+                .as_ref()
+                .and_then(|g| g.get(pos))
+                .copied()
+                .unwrap_or(1.0);
+            self.channel_gain(ch) * ramp
+        };
+
         match frame.sample_format {
             SampleFormat::F32 | SampleFormat::F32p => {
-                for plane in &mut out.data {
+                for (p, plane) in out.data.iter_mut().enumerate() {
                     let samples: &mut [f32] = cast_slice_mut(plane);
-                    for s in samples.iter_mut() {
-                        *s = (*s as f64 * self.gain) as f32;
+                    for (i, s) in samples.iter_mut().enumerate() {
+                        *s = (*s as f64 * gain_for(p, i)) as f32;
                     }
                 }
             }
             SampleFormat::S16 | SampleFormat::S16p => {
-                for plane in &mut out.data {
+                for (p, plane) in out.data.iter_mut().enumerate() {
                     let samples: &mut [i16] = cast_slice_mut(plane);
-                    for s in samples.iter_mut() {
-                        let v = (*s as f64 * self.gain).round();
+                    for (i, s) in samples.iter_mut().enumerate() {
+                        let v = (*s as f64 * gain_for(p, i)).round();
                         *s = v.clamp(i16::MIN as f64, i16::MAX as f64) as i16;
                     }
                 }
             }
             SampleFormat::S32 | SampleFormat::S32p => {
-                for plane in &mut out.data {
+                for (p, plane) in out.data.iter_mut().enumerate() {
                     let samples: &mut [i32] = cast_slice_mut(plane);
-                    for s in samples.iter_mut() {
-                        let v = (*s as f64 * self.gain).round();
+                    for (i, s) in samples.iter_mut().enumerate() {
+                        let v = (*s as f64 * gain_for(p, i)).round();
                         *s = v.clamp(i32::MIN as f64, i32::MAX as f64) as i32;
                     }
                 }
             }
+            SampleFormat::S24 | SampleFormat::S24p => {
+                const S24_MIN: f64 = -8_388_608.0;
+                const S24_MAX: f64 = 8_388_607.0;
+                for (p, plane) in out.data.iter_mut().enumerate() {
+                    let samples: &mut [i32] = cast_slice_mut(plane);
+                    for (i, s) in samples.iter_mut().enumerate() {
+                        let v = (*s as f64 * gain_for(p, i)).round();
+                        *s = v.clamp(S24_MIN, S24_MAX) as i32;
+                    }
+                }
+            }
             SampleFormat::F64 | SampleFormat::F64p => {
-                for plane in &mut out.data {
+                for (p, plane) in out.data.iter_mut().enumerate() {
                     let samples: &mut [f64] = cast_slice_mut(plane);
-                    for s in samples.iter_mut() {
-                        *s *= self.gain;
+                    for (i, s) in samples.iter_mut().enumerate() {
+                        *s *= gain_for(p, i);
                     }
                 }
             }
@@ -82,6 +223,14 @@ impl Filter for VolumeFilter {
         "volume"
     }
 
+    fn input_formats(&self) -> Vec<FrameFormat> {
+        SUPPORTED_FORMATS
+            .iter()
+            .copied()
+            .map(FrameFormat::Audio)
+            .collect()
+    }
+
     fn send_frame(&mut self, frame: &Frame) -> TaoResult<()> {
         match frame {
             Frame::Audio(af) => {
@@ -89,7 +238,9 @@ impl Filter for VolumeFilter {
                 self.output = Some(Frame::Audio(result));
                 Ok(())
             }
-            Frame::Video(_) => Err(TaoError::InvalidArgument("volume 滤镜仅支持音频帧".into())),
+            Frame::Video(_) | Frame::Subtitle(_) => {
+                Err(TaoError::InvalidArgument("volume 滤镜仅支持音频帧".into()))
+            }
         }
     }
 
@@ -103,6 +254,11 @@ impl Filter for VolumeFilter {
     }
 }
 
+/// dB 转线性增益
+fn db_to_linear(db: f64) -> f64 {
+    10.0_f64.powf(db / 20.0)
+}
+
 /// 将字节切片转换为类型切片 (可变)
 fn cast_slice_mut<T: Copy + 'static>(bytes: &mut Vec<u8>) -> &mut [T] {
     let len = bytes.len() / std::mem::size_of::<T>();
@@ -120,7 +276,7 @@ mod tests {
             data.extend_from_slice(&s.to_le_bytes());
         }
         Frame::Audio(AudioFrame {
-            data: vec![data],
+            data: vec![data.into()],
             nb_samples: samples.len() as u32,
             sample_rate: 44100,
             sample_format: SampleFormat::F32,
@@ -188,6 +344,161 @@ mod tests {
         }
     }
 
+    fn make_s16_frame(samples: &[i16], channels: u32, planar: bool) -> Frame {
+        let sample_format = if planar {
+            SampleFormat::S16p
+        } else {
+            SampleFormat::S16
+        };
+        let nb_samples = samples.len() as u32 / channels;
+        let data = if planar {
+            (0..channels as usize)
+                .map(|ch| {
+                    samples[ch * nb_samples as usize..(ch + 1) * nb_samples as usize]
+                        .iter()
+                        .flat_map(|s| s.to_le_bytes())
+                        .collect()
+                })
+                .collect()
+        } else {
+            vec![samples.iter().flat_map(|s| s.to_le_bytes()).collect()]
+        };
+        Frame::Audio(AudioFrame {
+            data,
+            nb_samples,
+            sample_rate: 8,
+            sample_format,
+            channel_layout: ChannelLayout::from_channels(channels),
+            pts: 0,
+            time_base: Rational::new(1, 8),
+            duration: nb_samples as i64,
+        })
+    }
+
+    fn extract_s16(frame: &Frame, plane: usize) -> Vec<i16> {
+        if let Frame::Audio(af) = frame {
+            af.data[plane]
+                .chunks_exact(2)
+                .map(|c| i16::from_le_bytes([c[0], c[1]]))
+                .collect()
+        } else {
+            panic!("期望音频帧");
+        }
+    }
+
+    #[test]
+    fn test_volume_s16_half_exact() {
+        let mut filter = VolumeFilter::new(0.5);
+        let input = make_s16_frame(&[i16::MAX, -i16::MAX, 1000, -1000], 1, false);
+        filter.send_frame(&input).unwrap();
+        let samples = extract_s16(&filter.receive_frame().unwrap(), 0);
+        assert_eq!(
+            samples,
+            vec![16384, -16384, 500, -500],
+            "S16 满幅 0.5 倍应精确减半"
+        );
+    }
+
+    #[test]
+    fn test_volume_s16_double_clips_symmetrically() {
+        let mut filter = VolumeFilter::new(2.0);
+        let input = make_s16_frame(&[i16::MAX, i16::MIN, 20000, -20000], 1, false);
+        filter.send_frame(&input).unwrap();
+        let samples = extract_s16(&filter.receive_frame().unwrap(), 0);
+        assert_eq!(
+            samples,
+            vec![i16::MAX, i16::MIN, i16::MAX, i16::MIN],
+            "S16 2 倍增益应在正负两端同样饱和裁剪"
+        );
+    }
+
+    #[test]
+    fn test_volume_s32_planar() {
+        let mut filter = VolumeFilter::new(0.5);
+        let data: Vec<u8> = [i32::MAX, -1000]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let input = Frame::Audio(AudioFrame {
+            data: vec![data.clone().into(), data.into()],
+            nb_samples: 2,
+            sample_rate: 44100,
+            sample_format: SampleFormat::S32p,
+            channel_layout: ChannelLayout::from_channels(2),
+            pts: 0,
+            time_base: Rational::new(1, 44100),
+            duration: 2,
+        });
+        filter.send_frame(&input).unwrap();
+        let Frame::Audio(af) = filter.receive_frame().unwrap() else {
+            panic!("期望音频帧");
+        };
+        for plane in &af.data {
+            let v: Vec<i32> = plane
+                .chunks_exact(4)
+                .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                .collect();
+            assert_eq!(v, vec![1_073_741_824, -500], "S32 平面各声道都应减半");
+        }
+    }
+
+    #[test]
+    fn test_volume_per_channel_interleaved_and_planar() {
+        let mut filter = VolumeFilter::with_per_channel(vec![1.0, 0.5]);
+        let input = make_s16_frame(&[1000, 1000, -2000, -2000], 2, false);
+        filter.send_frame(&input).unwrap();
+        let samples = extract_s16(&filter.receive_frame().unwrap(), 0);
+        assert_eq!(
+            samples,
+            vec![1000, 500, -2000, -1000],
+            "交错格式右声道应减半"
+        );
+
+        let input = make_s16_frame(&[1000, -2000, 1000, -2000], 2, true);
+        filter.send_frame(&input).unwrap();
+        let output = filter.receive_frame().unwrap();
+        assert_eq!(
+            extract_s16(&output, 0),
+            vec![1000, -2000],
+            "平面格式左声道应不变"
+        );
+        assert_eq!(
+            extract_s16(&output, 1),
+            vec![500, -1000],
+            "平面格式右声道应减半"
+        );
+    }
+
+    #[test]
+    fn test_volume_ramp_across_frames() {
+        // 8Hz 采样率, 每帧 4 个采样 (0.5 秒), 1 秒内从 0dB 渐变到 -20dB
+        let mut filter = VolumeFilter::ramp(0.0, -20.0, 1.0);
+        let mut first = make_s16_frame(&[10000; 4], 1, false);
+        let mut second = make_s16_frame(&[10000; 4], 1, false);
+        let mut third = make_s16_frame(&[10000; 4], 1, false);
+        for (frame, pts) in [(&mut first, 100), (&mut second, 104), (&mut third, 108)] {
+            if let Frame::Audio(af) = frame {
+                af.pts = pts;
+            }
+        }
+
+        filter.send_frame(&first).unwrap();
+        let a = extract_s16(&filter.receive_frame().unwrap(), 0);
+        filter.send_frame(&second).unwrap();
+        let b = extract_s16(&filter.receive_frame().unwrap(), 0);
+        filter.send_frame(&third).unwrap();
+        let c = extract_s16(&filter.receive_frame().unwrap(), 0);
+
+        assert_eq!(a[0], 10000, "渐变起点应为 0dB");
+        assert!(
+            a.windows(2).chain(b.windows(2)).all(|w| w[1] < w[0]),
+            "渐变期间增益应单调递减"
+        );
+        assert!(b[0] < a[3], "跨帧增益应连续递减");
+        assert_eq!(b[0], 3162, "0.5 秒处应为 -10dB");
+        assert!(c.iter().all(|&s| s == 1000), "渐变结束后应保持 -20dB");
+    }
+
     #[test]
     fn test_volume_video_frame_error() {
         use tao_core::PixelFormat;
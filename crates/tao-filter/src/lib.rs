@@ -7,8 +7,16 @@
 //!
 //! ## 支持的滤镜
 //!
-//! - **音频**: volume (音量), fade (淡入淡出), loudnorm (响度归一化), equalizer (均衡器)
-//! - **视频**: crop (裁剪), pad (填充), overlay (叠加), drawtext (文字绘制)
+//! - **音频**: volume (音量), fade (淡入淡出), loudnorm (响度归一化), equalizer (均衡器),
+//!   ducking (旁白闪避混音), atempo (变速不变调), aformat (采样格式转换)
+//! - **视频**: crop (裁剪), pad (填充), scale (缩放), format (像素格式转换), overlay (叠加),
+//!   drawtext (文字绘制), compositor (多路合成)
+//!
+//! ## 格式协商
+//!
+//! 滤镜通过 [`Filter::input_formats`] 声明可接受的输入格式, 通过 [`Filter::output_format`]
+//! 给出输出格式. [`FilterGraph`] 在收到首帧 (或输入格式变化) 时沿滤镜链推导格式,
+//! 在不匹配处自动插入 format/aformat 转换滤镜, 无法转换时返回错误.
 //!
 //! ## 使用示例
 //!
@@ -25,8 +33,60 @@
 
 pub mod filters;
 
+use std::fmt;
+
 use tao_codec::frame::Frame;
-use tao_core::{TaoError, TaoResult};
+use tao_core::{PixelFormat, SampleFormat, TaoError, TaoResult};
+
+/// 帧数据格式, 用于滤镜间的格式协商
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFormat {
+    /// 视频帧像素格式
+    Video(PixelFormat),
+    /// 音频帧采样格式
+    Audio(SampleFormat),
+}
+
+impl FrameFormat {
+    /// 获取帧的数据格式, 字幕帧返回 None
+    pub fn of(frame: &Frame) -> Option<Self> {
+        match frame {
+            Frame::Video(vf) => Some(Self::Video(vf.pixel_format)),
+            Frame::Audio(af) => Some(Self::Audio(af.sample_format)),
+            Frame::Subtitle(_) => None,
+        }
+    }
+
+    /// 是否与 `other` 同为视频或同为音频
+    fn same_kind(self, other: Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::Video(_), Self::Video(_)) | (Self::Audio(_), Self::Audio(_))
+        )
+    }
+
+    /// 能否由自动插入的 format/aformat 滤镜从 `self` 转换为 `dst`
+    fn can_convert_to(self, dst: Self) -> bool {
+        match (self, dst) {
+            (Self::Video(src), Self::Video(dst)) => {
+                tao_scale::convert::is_conversion_supported(src, dst)
+            }
+            (Self::Audio(src), Self::Audio(dst)) => {
+                src != SampleFormat::None && dst != SampleFormat::None
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for FrameFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Video(format) => write!(f, "像素格式 {format}"),
+            Self::Audio(format) => write!(f, "采样格式 {format}"),
+        }
+    }
+}
 
 /// 滤镜 trait
 ///
@@ -36,6 +96,19 @@ pub trait Filter: Send {
     /// 获取滤镜名称
     fn name(&self) -> &str;
 
+    /// 可接受的输入格式 (按偏好排序)
+    ///
+    /// 只约束与声明格式同类 (视频/音频) 的帧, 未声明某类格式表示该类不限制.
+    /// 默认返回空, 表示不限制.
+    fn input_formats(&self) -> Vec<FrameFormat> {
+        Vec::new()
+    }
+
+    /// 输入为 `input` 时的输出格式, 默认与输入相同
+    fn output_format(&self, input: FrameFormat) -> FrameFormat {
+        input
+    }
+
     /// 送入一帧数据
     fn send_frame(&mut self, frame: &Frame) -> TaoResult<()>;
 
@@ -46,14 +119,35 @@ pub trait Filter: Send {
     fn flush(&mut self) -> TaoResult<()>;
 }
 
+/// 多输入滤镜 trait
+///
+/// 接收多路输入的滤镜 (如 compositor) 额外实现此 trait.
+/// `Filter::send_frame` 等价于向第 0 路输入送帧.
+pub trait MultiInputFilter: Filter {
+    /// 输入路数
+    fn input_count(&self) -> usize;
+
+    /// 向指定输入送入一帧数据
+    fn send_frame_to(&mut self, index: usize, frame: &Frame) -> TaoResult<()>;
+}
+
+/// 滤镜链中的一个节点
+struct FilterNode {
+    filter: Box<dyn Filter>,
+    /// 是否为格式协商自动插入的转换滤镜
+    auto_inserted: bool,
+}
+
 /// 滤镜图
 ///
 /// 由多个滤镜组成的处理管线, 数据从输入端流经各个滤镜后到达输出端.
 ///
 /// 滤镜图支持线性链: 数据依次通过每个滤镜处理.
 pub struct FilterGraph {
-    /// 滤镜链中的滤镜列表
-    filters: Vec<Box<dyn Filter>>,
+    /// 滤镜链中的滤镜列表 (含自动插入的转换滤镜)
+    filters: Vec<FilterNode>,
+    /// 已完成协商的输入格式
+    negotiated: Option<FrameFormat>,
 }
 
 impl FilterGraph {
@@ -61,32 +155,94 @@ impl FilterGraph {
     pub fn new() -> Self {
         Self {
             filters: Vec::new(),
+            negotiated: None,
         }
     }
 
     /// 添加滤镜到图中
     pub fn add_filter(&mut self, filter: Box<dyn Filter>) {
-        self.filters.push(filter);
+        self.filters.push(FilterNode {
+            filter,
+            auto_inserted: false,
+        });
+        self.negotiated = None;
     }
 
-    /// 获取滤镜数量
+    /// 获取滤镜数量 (含自动插入的转换滤镜)
     pub fn filter_count(&self) -> usize {
         self.filters.len()
     }
 
+    /// 按输入格式协商滤镜链, 返回链的输出格式
+    ///
+    /// 沿滤镜链推导每个滤镜的输入格式, 不被接受时在其前插入转换滤镜
+    /// (视频为 format, 音频为 aformat), 目标为该滤镜声明的格式中第一个可转换的格式.
+    /// 重新协商时先移除上一次自动插入的转换滤镜.
+    pub fn negotiate(&mut self, input: FrameFormat) -> TaoResult<FrameFormat> {
+        self.filters.retain(|node| !node.auto_inserted);
+        self.negotiated = None;
+
+        // 先推导全部转换点, 成功后再插入, 协商失败时滤镜链保持不变
+        let mut current = input;
+        let mut conversions = Vec::new();
+        for (index, node) in self.filters.iter().enumerate() {
+            let accepted: Vec<FrameFormat> = node
+                .filter
+                .input_formats()
+                .into_iter()
+                .filter(|format| format.same_kind(current))
+                .collect();
+            if !accepted.is_empty() && !accepted.contains(&current) {
+                let target = accepted
+                    .iter()
+                    .copied()
+                    .find(|&dst| current.can_convert_to(dst))
+                    .ok_or_else(|| {
+                        let names: Vec<String> = accepted.iter().map(|f| f.to_string()).collect();
+                        TaoError::Unsupported(format!(
+                            "滤镜 {} 不接受{current}的输入 (可接受: {}), 且无法自动转换",
+                            node.filter.name(),
+                            names.join(", "),
+                        ))
+                    })?;
+                conversions.push((index, target));
+                current = target;
+            }
+            current = node.filter.output_format(current);
+        }
+        for (index, target) in conversions.into_iter().rev() {
+            self.filters.insert(
+                index,
+                FilterNode {
+                    filter: converter_for(target),
+                    auto_inserted: true,
+                },
+            );
+        }
+        self.negotiated = Some(input);
+        Ok(current)
+    }
+
     /// 将帧送入滤镜链, 依次流过每个滤镜, 返回最终输出帧.
     ///
     /// 帧从第一个滤镜开始, 每个滤镜的输出作为下一个滤镜的输入.
+    /// 首帧或输入格式变化时先协商格式 (见 [`negotiate`](Self::negotiate)).
     /// 如果滤镜链为空, 则直接返回输入帧 (透传).
     pub fn process_frame(&mut self, frame: &Frame) -> TaoResult<Frame> {
         if self.filters.is_empty() {
             return Ok(frame.clone());
         }
 
+        if let Some(format) = FrameFormat::of(frame)
+            && self.negotiated != Some(format)
+        {
+            self.negotiate(format)?;
+        }
+
         let mut current = frame.clone();
-        for filter in &mut self.filters {
-            filter.send_frame(&current)?;
-            current = filter.receive_frame()?;
+        for node in &mut self.filters {
+            node.filter.send_frame(&current)?;
+            current = node.filter.receive_frame()?;
         }
         Ok(current)
     }
@@ -97,7 +253,7 @@ impl FilterGraph {
     /// 返回所有剩余帧的列表.
     pub fn flush_all(&mut self) -> TaoResult<Vec<Frame>> {
         let mut remaining = Vec::new();
-        for filter in &mut self.filters {
+        for FilterNode { filter, .. } in &mut self.filters {
             filter.flush()?;
             // 尝试取出刷新产生的帧
             loop {
@@ -113,7 +269,15 @@ impl FilterGraph {
 
     /// 获取滤镜名称列表 (调试用)
     pub fn filter_names(&self) -> Vec<&str> {
-        self.filters.iter().map(|f| f.name()).collect()
+        self.filters.iter().map(|node| node.filter.name()).collect()
+    }
+}
+
+/// 创建转换到 `target` 的滤镜
+fn converter_for(target: FrameFormat) -> Box<dyn Filter> {
+    match target {
+        FrameFormat::Video(format) => Box::new(FormatFilter::new(format)),
+        FrameFormat::Audio(format) => Box::new(AformatFilter::new().with_sample_format(format)),
     }
 }
 
@@ -124,20 +288,25 @@ impl Default for FilterGraph {
 }
 
 // 便捷重导出
+pub use filters::aformat::AformatFilter;
+pub use filters::atempo::AtempoFilter;
+pub use filters::compositor::{CompositorFilter, CompositorLayer};
 pub use filters::crop::CropFilter;
 pub use filters::drawtext::DrawtextFilter;
 pub use filters::equalizer::EqualizerFilter;
 pub use filters::fade::{FadeFilter, FadeType};
+pub use filters::format::FormatFilter;
 pub use filters::loudnorm::LoudnormFilter;
 pub use filters::overlay::OverlayFilter;
 pub use filters::pad::{PadColor, PadFilter};
+pub use filters::scale::ScaleFilter;
 pub use filters::volume::VolumeFilter;
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tao_codec::frame::AudioFrame;
-    use tao_core::{ChannelLayout, Rational, SampleFormat};
+    use tao_codec::frame::{AudioFrame, VideoFrame};
+    use tao_core::{ChannelLayout, Rational};
 
     fn make_f32_frame(samples: &[f32]) -> Frame {
         let mut data = Vec::with_capacity(samples.len() * 4);
@@ -145,7 +314,7 @@ mod tests {
             data.extend_from_slice(&s.to_le_bytes());
         }
         Frame::Audio(AudioFrame {
-            data: vec![data],
+            data: vec![data.into()],
             nb_samples: samples.len() as u32,
             sample_rate: 44100,
             sample_format: SampleFormat::F32,
@@ -216,4 +385,115 @@ mod tests {
         let remaining = graph.flush_all().unwrap();
         assert!(remaining.is_empty());
     }
+
+    fn make_yuv420p_frame(width: u32, height: u32) -> Frame {
+        let (cw, ch) = (width.div_ceil(2) as usize, height.div_ceil(2) as usize);
+        let mut vf = VideoFrame::new(width, height, PixelFormat::Yuv420p);
+        vf.data = vec![
+            vec![128u8; (width * height) as usize].into(),
+            vec![128u8; cw * ch].into(),
+            vec![128u8; cw * ch].into(),
+        ];
+        vf.linesize = vec![width as usize, cw, cw];
+        Frame::Video(vf)
+    }
+
+    /// 仅声明接受 Gray16le 输入的测试滤镜
+    struct Gray16OnlyFilter;
+
+    impl Filter for Gray16OnlyFilter {
+        fn name(&self) -> &str {
+            "gray16_only"
+        }
+
+        fn input_formats(&self) -> Vec<FrameFormat> {
+            vec![FrameFormat::Video(PixelFormat::Gray16le)]
+        }
+
+        fn send_frame(&mut self, _frame: &Frame) -> TaoResult<()> {
+            Ok(())
+        }
+
+        fn receive_frame(&mut self) -> TaoResult<Frame> {
+            Err(TaoError::NeedMoreData)
+        }
+
+        fn flush(&mut self) -> TaoResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_filter_graph_inserts_format_converter_on_mismatch() {
+        let mut graph = FilterGraph::new();
+        graph.add_filter(Box::new(PadFilter::new(6, 6, 1, 1)));
+        let output = graph.process_frame(&make_yuv420p_frame(4, 4)).unwrap();
+        assert_eq!(
+            graph.filter_names(),
+            vec!["format", "pad"],
+            "pad 仅支持 packed 格式, 应在其前自动插入 format"
+        );
+        let Frame::Video(vf) = output else {
+            panic!("期望视频帧");
+        };
+        assert_eq!(vf.pixel_format, PixelFormat::Rgb24);
+        assert_eq!((vf.width, vf.height), (6, 6));
+
+        // 输入格式变化时重新协商, 已被接受的格式不再插入转换
+        let mut rgb = VideoFrame::new(4, 4, PixelFormat::Rgb24);
+        rgb.data = vec![vec![0u8; 4 * 4 * 3].into()];
+        rgb.linesize = vec![12];
+        graph.process_frame(&Frame::Video(rgb)).unwrap();
+        assert_eq!(graph.filter_names(), vec!["pad"]);
+    }
+
+    #[test]
+    fn test_filter_graph_inserts_aformat_and_respects_explicit_format() {
+        let mut graph = FilterGraph::new();
+        graph.add_filter(Box::new(AtempoFilter::new(1.0).unwrap()));
+        let mut af = AudioFrame::new(4, 44100, SampleFormat::S16, ChannelLayout::MONO);
+        af.data[0] = vec![0u8; 8].into();
+        assert_eq!(
+            graph
+                .negotiate(FrameFormat::of(&Frame::Audio(af)).unwrap())
+                .unwrap(),
+            FrameFormat::Audio(SampleFormat::F32)
+        );
+        assert_eq!(graph.filter_names(), vec!["aformat", "atempo"]);
+
+        // 显式 format 已输出可接受的格式时不再自动插入
+        let mut graph = FilterGraph::new();
+        graph.add_filter(Box::new(FormatFilter::new(PixelFormat::Gray8)));
+        graph.add_filter(Box::new(PadFilter::new(4, 4, 0, 0)));
+        let mut rgb = VideoFrame::new(4, 4, PixelFormat::Rgb24);
+        rgb.data = vec![vec![0u8; 4 * 4 * 3].into()];
+        rgb.linesize = vec![12];
+        let output = graph.process_frame(&Frame::Video(rgb)).unwrap();
+        assert_eq!(graph.filter_names(), vec!["format", "pad"]);
+        assert_eq!(
+            FrameFormat::of(&output),
+            Some(FrameFormat::Video(PixelFormat::Gray8))
+        );
+    }
+
+    #[test]
+    fn test_filter_graph_unconvertible_mismatch_error() {
+        let mut graph = FilterGraph::new();
+        graph.add_filter(Box::new(Gray16OnlyFilter));
+        let err = graph
+            .process_frame(&make_yuv420p_frame(4, 4))
+            .expect_err("无法转换时应返回错误");
+        let TaoError::Unsupported(message) = err else {
+            panic!("应返回 Unsupported, 实际 {err:?}");
+        };
+        assert!(
+            message.contains("gray16_only") && message.contains("yuv420p"),
+            "错误信息应指明滤镜与输入格式: {message}"
+        );
+        assert_eq!(
+            graph.filter_names(),
+            vec!["gray16_only"],
+            "协商失败不应留下转换滤镜"
+        );
+    }
 }
@@ -3,7 +3,7 @@
 //! 对标 FFmpeg 的 `AVInputFormat`, 定义了从容器格式中读取数据包的接口.
 
 use tao_codec::Packet;
-use tao_core::TaoResult;
+use tao_core::{TaoError, TaoResult};
 
 use crate::format_id::FormatId;
 use crate::io::IoContext;
@@ -58,6 +58,23 @@ pub trait Demuxer: Send {
     /// 获取格式名称
     fn name(&self) -> &str;
 
+    /// 设置解封装器私有选项
+    ///
+    /// 对标 FFmpeg 解封装器的 `AVOption`, 需在 `open()` 之前调用.
+    /// 默认实现不接受任何选项.
+    fn set_option(&mut self, key: &str, _value: &str) -> TaoResult<()> {
+        Err(TaoError::Unsupported(format!(
+            "解封装器 {} 不支持选项 {}",
+            self.name(),
+            key
+        )))
+    }
+
+    /// 获取解封装器私有选项的当前值, 不支持的选项返回 None
+    fn get_option(&self, _key: &str) -> Option<String> {
+        None
+    }
+
     /// 打开容器并解析头部信息
     ///
     /// 读取容器头部, 解析出所有流的信息.
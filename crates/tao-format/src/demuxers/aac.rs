@@ -256,6 +256,7 @@ impl Demuxer for AacDemuxer {
                 sample_format: SampleFormat::F32,
                 bit_rate: 0,
                 frame_size: self.samples_per_frame,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         };
@@ -298,7 +299,7 @@ impl Demuxer for AacDemuxer {
         pkt.stream_index = 0;
         pkt.pts = pts;
         pkt.dts = pts;
-        pkt.is_keyframe = true; // AAC 帧都可以独立解码
+        pkt.set_keyframe(true); // AAC 帧都可以独立解码
         pkt.time_base = Rational::new(1, self.sample_rate as i32);
         pkt.duration = self.samples_per_frame as i64;
 
@@ -374,7 +375,6 @@ impl FormatProbe for AacProbe {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     /// 构造一个有效的 ADTS 帧
     /// profile=1(LC), sr_index=3(48000), ch=2(stereo), data=payload
@@ -447,8 +447,7 @@ mod tests {
             data.extend_from_slice(&build_adts_frame(&[0xAA; 50]));
         }
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut demuxer = AacDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -474,8 +473,7 @@ mod tests {
             data.extend_from_slice(&build_adts_frame(&payload));
         }
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut demuxer = AacDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -483,7 +481,7 @@ mod tests {
         let pkt0 = demuxer.read_packet(&mut io).unwrap();
         assert_eq!(pkt0.pts, 0);
         assert_eq!(pkt0.data.len(), 50);
-        assert!(pkt0.is_keyframe);
+        assert!(pkt0.is_keyframe());
         assert_eq!(pkt0.duration, 1024);
 
         // 第二个包
@@ -515,8 +513,7 @@ mod tests {
             data.extend_from_slice(&build_adts_frame(&[0xCC; 40]));
         }
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut demuxer = AacDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -531,8 +528,7 @@ mod tests {
             data.extend_from_slice(&build_adts_frame(&[0xAA; 20]));
         }
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut demuxer = AacDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -9,19 +9,71 @@
 //!              + sampleSize(BE16) + sampleRate(80-bit extended)
 //!              [AIFF-C 额外: compressionType(4) + compressionName(pstring)]
 //! SSND chunk:   "SSND" + chunk_size(BE) + offset(BE32) + blockSize(BE32) + PCM data...
+//! MARK chunk:   "MARK" + chunk_size(BE) + numMarkers(BE16)
+//!              + [id(BE16) + position(BE32) + markerName(pstring)]...
+//! INST chunk:   "INST" + chunk_size(BE) + baseNote + detune + lowNote + highNote
+//!              + lowVelocity + highVelocity + gain(BE16) + sustainLoop(6) + releaseLoop(6)
 //! ```
+//!
+//! MARK/INST 常位于 SSND 之后, 可寻址输入会在读完其余块后回到音频数据起始位置.
 
 use log::{debug, warn};
 use tao_codec::CodecId;
 use tao_core::{ChannelLayout, MediaType, Rational, SampleFormat, TaoError, TaoResult};
 
-use crate::demuxer::{Demuxer, SeekFlags};
+use crate::demuxer::{Demuxer, DemuxerChapter, SeekFlags};
 use crate::format_id::FormatId;
 use crate::io::IoContext;
 use crate::probe::{FormatProbe, ProbeScore, SCORE_EXTENSION, SCORE_MAX};
 use crate::stream::{AudioStreamParams, Stream, StreamParams};
 
+/// MARK 块中的标记点
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CuePoint {
+    /// 标记 ID (INST 循环点通过此 ID 引用)
+    pub id: u16,
+    /// 标记位置 (采样帧)
+    pub position: u32,
+    /// 标记名称
+    pub name: String,
+}
+
+/// INST 块中的乐器参数 (循环点取 sustainLoop)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstrumentInfo {
+    /// 基准音高 (MIDI 音符号)
+    pub base_note: i8,
+    /// 微调 (音分, -50 ~ 50)
+    pub detune: i8,
+    /// 适用的最低音符
+    pub low_note: i8,
+    /// 适用的最高音符
+    pub high_note: i8,
+    /// 循环模式: 0 不循环, 1 正向循环, 2 往返循环
+    pub loop_type: u16,
+    /// 循环起点的标记 ID
+    pub loop_start_id: u16,
+    /// 循环终点的标记 ID
+    pub loop_end_id: u16,
+}
+
+impl InstrumentInfo {
+    /// 循环模式名称
+    pub fn loop_type_name(&self) -> &'static str {
+        match self.loop_type {
+            0 => "none",
+            1 => "forward",
+            2 => "forward_backward",
+            _ => "unknown",
+        }
+    }
+}
+
+/// MARK/INST 块的最大解析大小 (超出视为损坏并跳过)
+const MAX_MARKER_CHUNK_SIZE: u64 = 1 << 20;
+
 /// AIFF 解封装器
+#[derive(Default)]
 pub struct AiffDemuxer {
     /// 流信息
     streams: Vec<Stream>,
@@ -41,22 +93,78 @@ pub struct AiffDemuxer {
     metadata: Vec<(String, String)>,
     /// 是否为 AIFF-C 格式
     is_aifc: bool,
+    /// MARK 块标记点
+    cue_points: Vec<CuePoint>,
+    /// INST 块乐器参数
+    instrument: Option<InstrumentInfo>,
+    /// 标记点对应的章节
+    chapters: Vec<DemuxerChapter>,
 }
 
 impl AiffDemuxer {
     /// 创建 AIFF 解封装器实例 (工厂函数)
     pub fn create() -> TaoResult<Box<dyn Demuxer>> {
-        Ok(Box::new(Self {
-            streams: Vec::new(),
-            data_offset: 0,
-            data_size: 0,
-            data_pos: 0,
-            packet_size: 0,
-            block_align: 0,
-            sample_rate: 0,
-            metadata: Vec::new(),
-            is_aifc: false,
-        }))
+        Ok(Box::new(Self::default()))
+    }
+
+    /// MARK 块中的标记点
+    pub fn cue_points(&self) -> &[CuePoint] {
+        &self.cue_points
+    }
+
+    /// INST 块中的乐器参数
+    pub fn instrument(&self) -> Option<&InstrumentInfo> {
+        self.instrument.as_ref()
+    }
+
+    /// 将标记点与乐器参数整理为章节和元数据
+    ///
+    /// 每个标记点对应一个零长度章节; 乐器参数写入容器元数据,
+    /// 循环点解析为标记位置 (采样帧).
+    fn build_markers_metadata(&mut self) {
+        let rate = f64::from(self.sample_rate.max(1));
+        self.chapters = self
+            .cue_points
+            .iter()
+            .map(|cue| {
+                let time = f64::from(cue.position) / rate;
+                let mut metadata = vec![("marker_id".to_string(), cue.id.to_string())];
+                if !cue.name.is_empty() {
+                    metadata.push(("title".to_string(), cue.name.clone()));
+                }
+                DemuxerChapter {
+                    start_time: Some(time),
+                    end_time: Some(time),
+                    metadata,
+                }
+            })
+            .collect();
+
+        if let Some(inst) = self.instrument {
+            let marker_position = |id: u16| {
+                self.cue_points
+                    .iter()
+                    .find(|cue| cue.id == id)
+                    .map(|cue| cue.position)
+            };
+            let mut entries = vec![
+                ("base_note", inst.base_note.to_string()),
+                ("detune", inst.detune.to_string()),
+                ("low_note", inst.low_note.to_string()),
+                ("high_note", inst.high_note.to_string()),
+                ("loop_type", inst.loop_type_name().to_string()),
+            ];
+            if inst.loop_type != 0 {
+                if let Some(pos) = marker_position(inst.loop_start_id) {
+                    entries.push(("loop_start", pos.to_string()));
+                }
+                if let Some(pos) = marker_position(inst.loop_end_id) {
+                    entries.push(("loop_end", pos.to_string()));
+                }
+            }
+            self.metadata
+                .extend(entries.into_iter().map(|(k, v)| (k.to_string(), v)));
+        }
     }
 
     /// 根据位深和压缩类型确定 CodecId
@@ -171,6 +279,54 @@ fn encode_ieee_extended(value: f64) -> [u8; 10] {
     result
 }
 
+/// 解析 MARK 块: numMarkers + [id, position, pstring 名称]
+///
+/// 名称字段 (长度字节 + 文本) 补齐到偶数字节. 截断的条目被丢弃.
+fn parse_mark_chunk(data: &[u8]) -> Vec<CuePoint> {
+    let mut cues = Vec::new();
+    if data.len() < 2 {
+        return cues;
+    }
+    let count = u16::from_be_bytes([data[0], data[1]]);
+    let mut pos = 2;
+    for _ in 0..count {
+        if pos + 7 > data.len() {
+            warn!("AIFF MARK 块被截断, 已解析 {} 个标记", cues.len());
+            break;
+        }
+        let id = u16::from_be_bytes([data[pos], data[pos + 1]]);
+        let position =
+            u32::from_be_bytes([data[pos + 2], data[pos + 3], data[pos + 4], data[pos + 5]]);
+        let name_len = usize::from(data[pos + 6]);
+        let name_end = (pos + 7 + name_len).min(data.len());
+        let name = String::from_utf8_lossy(&data[pos + 7..name_end])
+            .trim_end_matches('\0')
+            .to_string();
+        cues.push(CuePoint { id, position, name });
+        // pstring 总长度 (含长度字节) 为偶数
+        pos += 6 + ((1 + name_len + 1) & !1);
+    }
+    cues
+}
+
+/// 解析 INST 块 (至少 20 字节)
+fn parse_inst_chunk(data: &[u8]) -> Option<InstrumentInfo> {
+    if data.len() < 20 {
+        return None;
+    }
+    let be16 = |i: usize| u16::from_be_bytes([data[i], data[i + 1]]);
+    Some(InstrumentInfo {
+        base_note: data[0] as i8,
+        detune: data[1] as i8,
+        low_note: data[2] as i8,
+        high_note: data[3] as i8,
+        // data[4..6] 为力度范围, data[6..8] 为增益
+        loop_type: be16(8),
+        loop_start_id: be16(10),
+        loop_end_id: be16(12),
+    })
+}
+
 impl Demuxer for AiffDemuxer {
     fn format_id(&self) -> FormatId {
         FormatId::Aiff
@@ -215,13 +371,18 @@ impl Demuxer for AiffDemuxer {
         let mut sample_rate_f64: f64 = 0.0;
         let mut compression_type: Option<[u8; 4]> = None;
 
-        while !ssnd_found {
+        loop {
             let chunk_id = match io.read_tag() {
                 Ok(tag) => tag,
                 Err(TaoError::Eof) => break,
                 Err(e) => return Err(e),
             };
-            let chunk_size = io.read_u32_be()? as u64;
+            let chunk_size = match io.read_u32_be() {
+                Ok(size) => u64::from(size),
+                // 音频数据之后的残缺块不影响播放
+                Err(_) if ssnd_found => break,
+                Err(e) => return Err(e),
+            };
             let chunk_id_str = String::from_utf8_lossy(&chunk_id);
 
             match &chunk_id {
@@ -279,6 +440,28 @@ impl Demuxer for AiffDemuxer {
                         "SSND: data_offset={}, data_size={}",
                         self.data_offset, self.data_size
                     );
+                    // 不可寻址时无法回到音频数据, 不再读取后续块
+                    if !io.is_seekable() {
+                        break;
+                    }
+                    io.skip(self.data_size as usize)?;
+                }
+                b"MARK" | b"INST" if chunk_size <= MAX_MARKER_CHUNK_SIZE => {
+                    let data = match io.read_bytes(chunk_size as usize) {
+                        Ok(data) => data,
+                        Err(_) if ssnd_found => {
+                            warn!("AIFF {} 块被截断, 已忽略", chunk_id_str);
+                            break;
+                        }
+                        Err(e) => return Err(e),
+                    };
+                    if &chunk_id == b"MARK" {
+                        self.cue_points = parse_mark_chunk(&data);
+                        debug!("MARK: {} 个标记", self.cue_points.len());
+                    } else {
+                        self.instrument = parse_inst_chunk(&data);
+                        debug!("INST: {:?}", self.instrument);
+                    }
                 }
                 _ => {
                     warn!("跳过未知 AIFF 块: '{}', 大小={}", chunk_id_str, chunk_size);
@@ -287,8 +470,8 @@ impl Demuxer for AiffDemuxer {
             }
 
             // IFF 块要求偶数对齐
-            if !ssnd_found && chunk_size % 2 != 0 {
-                io.skip(1)?;
+            if chunk_size % 2 != 0 && io.skip(1).is_err() {
+                break;
             }
         }
 
@@ -298,6 +481,9 @@ impl Demuxer for AiffDemuxer {
         if !ssnd_found {
             return Err(TaoError::InvalidData("未找到 SSND 块".into()));
         }
+        if io.is_seekable() {
+            io.seek(std::io::SeekFrom::Start(self.data_offset))?;
+        }
 
         let sample_rate = sample_rate_f64.round() as u32;
         let codec_id =
@@ -324,6 +510,7 @@ impl Demuxer for AiffDemuxer {
                 sample_format,
                 bit_rate,
                 frame_size: 0,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         };
@@ -332,6 +519,7 @@ impl Demuxer for AiffDemuxer {
         self.block_align = block_align;
         self.sample_rate = sample_rate;
         self.data_pos = 0;
+        self.build_markers_metadata();
 
         // 每个数据包读取 ~4096 采样
         let samples_per_packet = 4096u32;
@@ -392,7 +580,7 @@ impl Demuxer for AiffDemuxer {
         pkt.dts = pkt.pts;
         pkt.duration = nb_samples;
         pkt.time_base = Rational::new(1, self.sample_rate as i32);
-        pkt.is_keyframe = true;
+        pkt.set_keyframe(true);
         pkt.pos = (self.data_offset + self.data_pos) as i64;
 
         self.data_pos += aligned_size as u64;
@@ -443,6 +631,10 @@ impl Demuxer for AiffDemuxer {
     fn metadata(&self) -> &[(String, String)] {
         &self.metadata
     }
+
+    fn chapters(&self) -> &[DemuxerChapter] {
+        &self.chapters
+    }
 }
 
 /// AIFF 格式探测器
@@ -477,7 +669,6 @@ impl FormatProbe for AiffProbe {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     /// 构建简单的 AIFF 文件 (大端 S16, 单声道)
     fn make_simple_aiff(pcm_data: &[u8], sample_rate: u32, channels: u16, bits: u16) -> Vec<u8> {
@@ -543,7 +734,7 @@ mod tests {
         let pcm = vec![0x00, 0x01, 0x7F, 0xFF, 0x80, 0x00, 0x00, 0x01];
         let aiff = make_simple_aiff(&pcm, 44100, 1, 16);
 
-        let mut io = IoContext::new(Box::new(MemoryBackend::from_data(aiff)));
+        let mut io = IoContext::from_bytes(aiff);
         let mut demuxer = AiffDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -561,7 +752,7 @@ mod tests {
         let pcm = vec![0x00, 0x01, 0x7F, 0xFF, 0x80, 0x00, 0x00, 0x01];
         let aiff = make_simple_aiff(&pcm, 44100, 1, 16);
 
-        let mut io = IoContext::new(Box::new(MemoryBackend::from_data(aiff)));
+        let mut io = IoContext::from_bytes(aiff);
         let mut demuxer = AiffDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -569,7 +760,7 @@ mod tests {
         assert_eq!(&pkt.data[..], &pcm[..]);
         assert_eq!(pkt.pts, 0);
         assert_eq!(pkt.duration, 4);
-        assert!(pkt.is_keyframe);
+        assert!(pkt.is_keyframe());
 
         let err = demuxer.read_packet(&mut io).unwrap_err();
         assert!(matches!(err, TaoError::Eof));
@@ -581,7 +772,7 @@ mod tests {
         let pcm = vec![0u8; 44100 * 2];
         let aiff = make_simple_aiff(&pcm, 44100, 1, 16);
 
-        let mut io = IoContext::new(Box::new(MemoryBackend::from_data(aiff)));
+        let mut io = IoContext::from_bytes(aiff);
         let mut demuxer = AiffDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -605,10 +796,120 @@ mod tests {
         }
     }
 
+    /// 在 AIFF 数据后追加一个块并更新 FORM 大小
+    fn append_chunk(aiff: &mut Vec<u8>, id: &[u8; 4], payload: &[u8]) {
+        aiff.extend_from_slice(id);
+        aiff.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        aiff.extend_from_slice(payload);
+        if payload.len() % 2 != 0 {
+            aiff.push(0);
+        }
+        let form_size = (aiff.len() - 8) as u32;
+        aiff[4..8].copy_from_slice(&form_size.to_be_bytes());
+    }
+
+    /// 构造 MARK 块内容
+    fn mark_payload(markers: &[(u16, u32, &str)]) -> Vec<u8> {
+        let mut payload = (markers.len() as u16).to_be_bytes().to_vec();
+        for &(id, position, name) in markers {
+            payload.extend_from_slice(&id.to_be_bytes());
+            payload.extend_from_slice(&position.to_be_bytes());
+            payload.push(name.len() as u8);
+            payload.extend_from_slice(name.as_bytes());
+            if name.len() % 2 == 0 {
+                payload.push(0);
+            }
+        }
+        payload
+    }
+
+    #[test]
+    fn test_demux_markers_and_instrument() {
+        let pcm: Vec<u8> = (0..400u32).flat_map(|i| (i as i16).to_be_bytes()).collect();
+        let mut aiff = make_simple_aiff(&pcm, 8000, 1, 16);
+        append_chunk(
+            &mut aiff,
+            b"MARK",
+            &mark_payload(&[(1, 100, "Loop Start"), (2, 300, "End"), (7, 4000, "")]),
+        );
+        let mut inst = vec![60u8, (-5i8) as u8, 0, 127, 1, 127, 0, 0];
+        for v in [1u16, 1, 2, 0, 0, 0] {
+            inst.extend_from_slice(&v.to_be_bytes());
+        }
+        append_chunk(&mut aiff, b"INST", &inst);
+
+        let mut io = IoContext::from_bytes(aiff);
+        let mut demuxer = AiffDemuxer::default();
+        demuxer.open(&mut io).unwrap();
+
+        assert_eq!(
+            demuxer.cue_points(),
+            [
+                CuePoint {
+                    id: 1,
+                    position: 100,
+                    name: "Loop Start".into(),
+                },
+                CuePoint {
+                    id: 2,
+                    position: 300,
+                    name: "End".into(),
+                },
+                CuePoint {
+                    id: 7,
+                    position: 4000,
+                    name: String::new(),
+                },
+            ],
+            "SSND 之后的 MARK 块应被解析 (含奇偶长度名称的补齐)"
+        );
+        let inst = demuxer.instrument().expect("应解析 INST 块");
+        assert_eq!(inst.base_note, 60);
+        assert_eq!(inst.detune, -5);
+        assert_eq!((inst.low_note, inst.high_note), (0, 127));
+        assert_eq!(inst.loop_type_name(), "forward");
+        assert_eq!((inst.loop_start_id, inst.loop_end_id), (1, 2));
+
+        let chapters = demuxer.chapters();
+        assert_eq!(chapters.len(), 3, "每个标记对应一个章节");
+        assert_eq!(chapters[1].start_time, Some(300.0 / 8000.0));
+        assert!(
+            chapters[0]
+                .metadata
+                .contains(&("title".to_string(), "Loop Start".to_string()))
+        );
+        let meta = demuxer.metadata();
+        assert!(meta.contains(&("loop_start".to_string(), "100".to_string())));
+        assert!(meta.contains(&("loop_end".to_string(), "300".to_string())));
+        assert!(meta.contains(&("loop_type".to_string(), "forward".to_string())));
+
+        // 解析尾部块后应回到音频数据起始位置
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!(&pkt.data[..], &pcm[..], "音频数据应完整读出");
+    }
+
+    #[test]
+    fn test_demux_truncated_trailing_chunk() {
+        let pcm = vec![0u8; 16];
+        let mut aiff = make_simple_aiff(&pcm, 8000, 1, 16);
+        append_chunk(&mut aiff, b"MARK", &mark_payload(&[(1, 2, "A")]));
+        // 截断的 INST 块: 声明 20 字节, 实际仅 4 字节
+        aiff.extend_from_slice(b"INST");
+        aiff.extend_from_slice(&20u32.to_be_bytes());
+        aiff.extend_from_slice(&[60, 0, 0, 127]);
+
+        let mut io = IoContext::from_bytes(aiff);
+        let mut demuxer = AiffDemuxer::default();
+        demuxer.open(&mut io).unwrap();
+        assert_eq!(demuxer.cue_points().len(), 1);
+        assert!(demuxer.instrument().is_none(), "截断的 INST 块应被忽略");
+        assert_eq!(demuxer.read_packet(&mut io).unwrap().data.len(), 16);
+    }
+
     #[test]
     fn test_non_form_file_error() {
         let bad = b"NOT_FORM_DATA_HERE".to_vec();
-        let mut io = IoContext::new(Box::new(MemoryBackend::from_data(bad)));
+        let mut io = IoContext::from_bytes(bad);
         let mut demuxer = AiffDemuxer::create().unwrap();
         let err = demuxer.open(&mut io).unwrap_err();
         assert!(matches!(err, TaoError::InvalidData(_)));
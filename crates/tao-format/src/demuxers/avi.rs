@@ -8,11 +8,17 @@
 //!     LIST 'strl' (每流一个)
 //!       strh (流头)
 //!       strf (流格式: BITMAPINFOHEADER 或 WAVEFORMATEX)
+//!       indx (可选 OpenDML 超级索引)
 //!   LIST 'movi' (数据块)
 //!     00dc (视频数据)
 //!     01wb (音频数据)
+//!     ix00 (可选 OpenDML 标准索引)
 //!   idx1 (可选旧式索引)
+//! RIFF 'AVIX' (OpenDML 扩展, 可重复多次)
+//!   LIST 'movi'
 //! ```
+//!
+//! 优先使用 indx/ix## 构建索引, 缺失时回退到 idx1, 都没有时顺序扫描 movi 列表.
 
 use bytes::Bytes;
 use log::debug;
@@ -40,6 +46,13 @@ const WAV_FORMAT_IEEE_FLOAT: u16 = 0x0003;
 /// idx1 索引条目标志: 关键帧
 const AVIIF_KEYFRAME: u32 = 0x10;
 
+/// OpenDML 索引类型: 超级索引 (索引的索引)
+const AVI_INDEX_OF_INDEXES: u8 = 0x00;
+/// OpenDML 索引类型: 标准索引 (数据块索引)
+const AVI_INDEX_OF_CHUNKS: u8 = 0x01;
+/// OpenDML 标准索引条目大小字段的非关键帧标志
+const AVI_INDEX_DELTA_FRAME: u32 = 0x8000_0000;
+
 /// idx1 索引条目
 #[derive(Debug, Clone)]
 struct Idx1Entry {
@@ -47,22 +60,39 @@ struct Idx1Entry {
     chunk_id: [u8; 4],
     /// 标志
     flags: u32,
-    /// 相对于 movi 列表起始的偏移
+    /// 相对于 movi 列表的偏移 (基准由 `resolve_idx1_base` 确定)
     offset: u32,
     /// 数据大小
     size: u32,
 }
 
+/// 统一的数据包索引条目 (由 ix## 或 idx1 构建)
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    /// 流索引
+    stream_index: usize,
+    /// 数据的绝对文件偏移 (不含 8 字节块头)
+    offset: u64,
+    /// 数据大小
+    size: u32,
+    /// 是否为关键帧
+    keyframe: bool,
+}
+
 /// AVI 解封装器
 pub struct AviDemuxer {
     /// 流信息
     streams: Vec<Stream>,
-    /// movi 列表数据起始位置 (跳过 LIST + size + 'movi')
-    movi_data_start: u64,
-    /// movi 列表的字节大小 (不含 LIST 头)
-    movi_list_size: u64,
-    /// idx1 索引条目 (按时间顺序)
+    /// 各 RIFF 块中 movi 列表的数据区间 `[起始, 结束)` (起始已跳过 'movi' 标签)
+    movi_segments: Vec<(u64, u64)>,
+    /// 顺序读取时当前所在的 movi 区间
+    segment_pos: usize,
+    /// idx1 索引条目 (按文件顺序)
     idx1_entries: Vec<Idx1Entry>,
+    /// 每流 indx 超级索引中的 ix## 块偏移: (流索引, 偏移列表)
+    super_indexes: Vec<(usize, Vec<u64>)>,
+    /// 数据包索引 (按文件偏移排序), 为空时顺序扫描
+    index: Vec<IndexEntry>,
     /// 当前读取的索引位置
     idx_pos: usize,
     /// 每流的 PTS 计数器 (视频=帧序号, 音频=累计采样数)
@@ -71,6 +101,10 @@ pub struct AviDemuxer {
     sample_sizes: Vec<u32>,
     /// 元数据
     metadata: Vec<(String, String)>,
+    /// avih 的 dwMicroSecPerFrame
+    micro_sec_per_frame: u32,
+    /// avih 的 dwTotalFrames (仅统计首个 RIFF 块)
+    total_frames: u32,
 }
 
 impl AviDemuxer {
@@ -78,13 +112,17 @@ impl AviDemuxer {
     pub fn create() -> TaoResult<Box<dyn Demuxer>> {
         Ok(Box::new(Self {
             streams: Vec::new(),
-            movi_data_start: 0,
-            movi_list_size: 0,
+            movi_segments: Vec::new(),
+            segment_pos: 0,
             idx1_entries: Vec::new(),
+            super_indexes: Vec::new(),
+            index: Vec::new(),
             idx_pos: 0,
             frame_counts: Vec::new(),
             sample_sizes: Vec::new(),
             metadata: Vec::new(),
+            micro_sec_per_frame: 0,
+            total_frames: 0,
         }))
     }
 
@@ -152,9 +190,11 @@ impl AviDemuxer {
                     bits_per_sample
                 ))),
             },
-            0x0050 => Ok(CodecId::Mp3), // MPEG Layer 3
-            0x0055 => Ok(CodecId::Mp3), // MPEG Layer 3
-            0x0160 => Ok(CodecId::Aac), // WAVE_FORMAT_AAC
+            0x0002 => Ok(CodecId::AdpcmMs),     // Microsoft ADPCM
+            0x0011 => Ok(CodecId::AdpcmImaWav), // IMA ADPCM (DVI)
+            0x0050 => Ok(CodecId::Mp3),         // MPEG Layer 3
+            0x0055 => Ok(CodecId::Mp3),         // MPEG Layer 3
+            0x0160 => Ok(CodecId::Aac),         // WAVE_FORMAT_AAC
             // AVI 中的 OggVorbis 变体封装, 当前 demuxer 尚未拆封装到原生 Vorbis 包.
             // 先标记为 None, 由上层按“非 Vorbis 流”路径回退对比基线.
             0x674F | 0x6750 | 0x6751 | 0x676F | 0x6770 | 0x6771 | 0x7966 => Ok(CodecId::None),
@@ -220,7 +260,7 @@ impl AviDemuxer {
         let is_list = &chunk_id == b"LIST";
         if is_list {
             let list_type = io.read_tag()?;
-            Ok((list_type, chunk_size - 4, true))
+            Ok((list_type, chunk_size.saturating_sub(4), true))
         } else {
             Ok((chunk_id, chunk_size, false))
         }
@@ -248,11 +288,11 @@ impl AviDemuxer {
                     if chunk_size < 56 {
                         return Err(TaoError::InvalidData("avih 块不足 56 字节".into()));
                     }
-                    let _micro_sec_per_frame = io.read_u32_le()?;
+                    self.micro_sec_per_frame = io.read_u32_le()?;
                     let _max_bytes_per_sec = io.read_u32_le()?;
                     let _padding = io.read_u32_le()?;
                     let _flags = io.read_u32_le()?;
-                    let _total_frames = io.read_u32_le()?;
+                    self.total_frames = io.read_u32_le()?;
                     let _initial_frames = io.read_u32_le()?;
                     let _streams = io.read_u32_le()?;
                     let _suggested_buffer_size = io.read_u32_le()?;
@@ -271,11 +311,14 @@ impl AviDemuxer {
                 (b"strl", true) => {
                     debug!("进入 strl 块处理, chunk_size={}", chunk_size);
                     let strl_end = io.position()? + chunk_size as u64;
+                    let strl_stream = stream_index;
+                    let mut indx_offsets = Vec::new();
                     let mut fcc_type = [0u8; 4];
                     let mut fcc_handler = [0u8; 4];
                     let mut scale: u32 = 1;
                     let mut rate: u32 = 1;
                     let mut strh_sample_size: u32 = 0;
+                    let mut strh_length: u32 = 0;
                     let mut stream_format = Vec::new();
 
                     while io.position()? < strl_end {
@@ -308,6 +351,7 @@ impl AviDemuxer {
                                 rate = io.read_u32_le()?; // 4 bytes
                                 let _start = io.read_u32_le()?; // 4 bytes
                                 let length = io.read_u32_le()?; // 4 bytes
+                                strh_length = length;
                                 // 已读取 36 字节
                                 let _suggested_buffer_size = io.read_u32_le()?; // 4 bytes
                                 let _quality = io.read_u32_le()?; // 4 bytes
@@ -349,6 +393,8 @@ impl AviDemuxer {
                                             frame_rate: Rational::new(rate as i32, scale as i32),
                                             sample_aspect_ratio: Rational::new(1, 1),
                                             bit_rate: 0,
+                                            color_space: Default::default(),
+                                            color_range: Default::default(),
                                         }),
                                         metadata: Vec::new(),
                                     };
@@ -361,6 +407,42 @@ impl AviDemuxer {
                                     stream_index += 1;
                                 } else if &fcc_type == FCC_AUDS {
                                     stream_format.clear();
+                                } else {
+                                    // 其他流类型 (字幕/MIDI 等) 仅占位, 保持流编号与数据块编号一致
+                                    debug!(
+                                        "AVI: 未识别的流类型 {:?}, 标记为未知流",
+                                        String::from_utf8_lossy(&fcc_type)
+                                    );
+                                    let media_type = if &fcc_type == b"txts" {
+                                        MediaType::Subtitle
+                                    } else {
+                                        MediaType::Unknown(0)
+                                    };
+                                    let params = if media_type == MediaType::Subtitle {
+                                        StreamParams::Subtitle
+                                    } else {
+                                        StreamParams::Other
+                                    };
+                                    while self.sample_sizes.len() < stream_index {
+                                        self.sample_sizes.push(0);
+                                    }
+                                    self.sample_sizes.push(0);
+                                    self.streams.push(Stream {
+                                        index: stream_index,
+                                        media_type,
+                                        codec_id: CodecId::None,
+                                        time_base: Rational::new(
+                                            scale.max(1) as i32,
+                                            rate.max(1) as i32,
+                                        ),
+                                        duration: -1,
+                                        start_time: 0,
+                                        nb_frames: 0,
+                                        extra_data: Vec::new(),
+                                        params,
+                                        metadata: Vec::new(),
+                                    });
+                                    stream_index += 1;
                                 }
                             }
                             b"strf" => {
@@ -376,7 +458,7 @@ impl AviDemuxer {
                                         stream_format[6],
                                         stream_format[7],
                                     ]);
-                                    let _avg_bytes = u32::from_le_bytes([
+                                    let avg_bytes = u32::from_le_bytes([
                                         stream_format[8],
                                         stream_format[9],
                                         stream_format[10],
@@ -404,21 +486,29 @@ impl AviDemuxer {
                                         Rational::new(1, sample_rate as i32)
                                     };
 
+                                    // dwLength 以时基为单位; dwSampleSize 为零时每块一帧
+                                    let (duration, nb_frames) = match strh_length {
+                                        0 => (-1, 0),
+                                        len if strh_sample_size == 0 => (len as i64, len as u64),
+                                        len => (len as i64, 0),
+                                    };
+
                                     let stream = Stream {
                                         index: stream_index,
                                         media_type: MediaType::Audio,
                                         codec_id,
                                         time_base,
-                                        duration: -1,
+                                        duration,
                                         start_time: 0,
-                                        nb_frames: 0,
+                                        nb_frames,
                                         extra_data: stream_format.clone(),
                                         params: StreamParams::Audio(AudioStreamParams {
                                             sample_rate,
                                             channel_layout,
                                             sample_format,
-                                            bit_rate: 0,
+                                            bit_rate: u64::from(avg_bytes) * 8,
                                             frame_size: block_align as u32,
+                                            block_align: u32::from(block_align),
                                         }),
                                         metadata: Vec::new(),
                                     };
@@ -504,6 +594,8 @@ impl AviDemuxer {
                                                 ),
                                                 sample_aspect_ratio: Rational::new(1, 1),
                                                 bit_rate: 0,
+                                                color_space: Default::default(),
+                                                color_range: Default::default(),
                                             }),
                                             metadata: Vec::new(),
                                         };
@@ -512,6 +604,10 @@ impl AviDemuxer {
                                     }
                                 }
                             }
+                            b"indx" => {
+                                let data = io.read_bytes(sub_size as usize)?;
+                                indx_offsets = Self::parse_super_index(&data)?;
+                            }
                             _ => {
                                 io.skip(sub_size as usize)?;
                             }
@@ -520,6 +616,10 @@ impl AviDemuxer {
                             io.skip(1)?;
                         }
                     }
+                    // 仅记录成功建立了流的 strl 的超级索引
+                    if !indx_offsets.is_empty() && stream_index > strl_stream {
+                        self.super_indexes.push((strl_stream, indx_offsets));
+                    }
                 }
                 _ => {
                     io.skip(chunk_size as usize)?;
@@ -530,17 +630,24 @@ impl AviDemuxer {
             }
         }
 
+        // 视频流头未记录长度时, 使用 avih 的总帧数
+        if self.total_frames > 0
+            && let Some(video) = self
+                .streams
+                .iter_mut()
+                .find(|s| s.media_type == MediaType::Video)
+            && video.nb_frames == 0
+        {
+            video.nb_frames = u64::from(self.total_frames);
+            video.duration = i64::from(self.total_frames);
+        }
+
         self.frame_counts = vec![0; self.streams.len()];
         Ok(())
     }
 
     /// 解析 idx1 索引
-    fn parse_idx1(
-        &mut self,
-        io: &mut IoContext,
-        _movi_start: u64,
-        chunk_size: u32,
-    ) -> TaoResult<()> {
+    fn parse_idx1(&mut self, io: &mut IoContext, chunk_size: u32) -> TaoResult<()> {
         let num_entries = chunk_size as usize / 16;
 
         for _ in 0..num_entries {
@@ -561,46 +668,217 @@ impl AviDemuxer {
         Ok(())
     }
 
-    /// 无 idx1 索引时的回退 seek: 从 movi 起始扫描块头定位到目标帧
-    fn seek_no_idx1(
+    /// 解析 indx 超级索引, 返回各 ix## 标准索引块的文件偏移
+    fn parse_super_index(data: &[u8]) -> TaoResult<Vec<u64>> {
+        if data.len() < 24 {
+            return Err(TaoError::InvalidData("indx 块不足 24 字节".into()));
+        }
+        let longs_per_entry = u16::from_le_bytes([data[0], data[1]]);
+        let index_type = data[3];
+        let entries_in_use = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+        if index_type != AVI_INDEX_OF_INDEXES || longs_per_entry != 4 {
+            debug!(
+                "AVI: 忽略不支持的 indx (type={}, longs_per_entry={})",
+                index_type, longs_per_entry
+            );
+            return Ok(Vec::new());
+        }
+
+        let offsets: Vec<u64> = data[24..]
+            .chunks_exact(16)
+            .take(entries_in_use as usize)
+            .map(|e| u64::from_le_bytes([e[0], e[1], e[2], e[3], e[4], e[5], e[6], e[7]]))
+            .filter(|&offset| offset > 0)
+            .collect();
+        debug!("indx: {} 个标准索引", offsets.len());
+        Ok(offsets)
+    }
+
+    /// 解析位于 `pos` 的 ix## 标准索引, 将条目追加到 `index`
+    fn parse_std_index(
+        &mut self,
+        io: &mut IoContext,
+        stream_index: usize,
+        pos: u64,
+    ) -> TaoResult<()> {
+        io.seek(std::io::SeekFrom::Start(pos))?;
+        let _fcc = io.read_tag()?;
+        let cb = io.read_u32_le()?;
+        let longs_per_entry = io.read_u16_le()?;
+        let _sub_type = io.read_u8()?;
+        let index_type = io.read_u8()?;
+        let entries_in_use = io.read_u32_le()?;
+        let _chunk_id = io.read_tag()?;
+        let base_offset = io.read_u64_le()?;
+        let _reserved = io.read_u32_le()?;
+
+        if index_type != AVI_INDEX_OF_CHUNKS || longs_per_entry != 2 {
+            return Err(TaoError::InvalidData(format!(
+                "AVI: 不支持的 OpenDML 标准索引 (type={}, longs_per_entry={})",
+                index_type, longs_per_entry
+            )));
+        }
+        if 24 + u64::from(entries_in_use) * 8 > u64::from(cb) {
+            return Err(TaoError::InvalidData(format!(
+                "AVI: ix## 条目数 {} 超出块大小 {}",
+                entries_in_use, cb
+            )));
+        }
+
+        let is_audio = self.streams[stream_index].media_type == MediaType::Audio;
+        let data = io.read_bytes(entries_in_use as usize * 8)?;
+        for e in data.chunks_exact(8) {
+            let offset = u32::from_le_bytes([e[0], e[1], e[2], e[3]]);
+            let size = u32::from_le_bytes([e[4], e[5], e[6], e[7]]);
+            self.index.push(IndexEntry {
+                stream_index,
+                offset: base_offset + u64::from(offset),
+                size: size & !AVI_INDEX_DELTA_FRAME,
+                keyframe: is_audio || size & AVI_INDEX_DELTA_FRAME == 0,
+            });
+        }
+        Ok(())
+    }
+
+    /// 确定 idx1 偏移的基准位置
+    ///
+    /// 规范中偏移相对 'movi' 标签, 部分文件相对 movi 数据区或使用绝对偏移,
+    /// 以首个条目指向的块 ID 校验.
+    fn resolve_idx1_base(&self, io: &mut IoContext, movi_data_start: u64) -> TaoResult<u64> {
+        let movi_tag_pos = movi_data_start - 4;
+        let Some(first) = self.idx1_entries.first() else {
+            return Ok(movi_tag_pos);
+        };
+        if io.is_seekable() {
+            for base in [movi_tag_pos, movi_data_start, 0] {
+                io.seek(std::io::SeekFrom::Start(base + u64::from(first.offset)))?;
+                if io.read_tag().ok() == Some(first.chunk_id) {
+                    return Ok(base);
+                }
+            }
+        }
+        Ok(movi_tag_pos)
+    }
+
+    /// 构建数据包索引: 优先 OpenDML 的 indx/ix##, 其次 idx1
+    fn build_index(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        if !self.super_indexes.is_empty() && io.is_seekable() {
+            let super_indexes = std::mem::take(&mut self.super_indexes);
+            let result = super_indexes
+                .iter()
+                .try_for_each(|(stream_index, offsets)| {
+                    offsets
+                        .iter()
+                        .try_for_each(|&pos| self.parse_std_index(io, *stream_index, pos))
+                });
+            self.super_indexes = super_indexes;
+            match result {
+                Ok(()) if !self.index.is_empty() => {
+                    self.index.sort_by_key(|e| e.offset);
+                    debug!("AVI: 使用 OpenDML 索引, {} 个条目", self.index.len());
+                    return Ok(());
+                }
+                Ok(()) => {}
+                Err(e) => debug!("AVI: 解析 OpenDML 索引失败, 回退到 idx1: {}", e),
+            }
+            self.index.clear();
+        }
+
+        // idx1 仅覆盖第一个 RIFF 块, 存在 AVIX 时改为顺序扫描以读取全部数据
+        let Some(&(movi_data_start, _)) = self.movi_segments.first() else {
+            return Ok(());
+        };
+        if self.idx1_entries.is_empty() || self.movi_segments.len() > 1 {
+            return Ok(());
+        }
+        let base = self.resolve_idx1_base(io, movi_data_start)?;
+        for entry in &self.idx1_entries {
+            let Some(stream_index) =
+                Self::chunk_stream_number(&entry.chunk_id).filter(|&n| n < self.streams.len())
+            else {
+                continue;
+            };
+            self.index.push(IndexEntry {
+                stream_index,
+                offset: base + u64::from(entry.offset) + 8,
+                size: entry.size,
+                keyframe: (entry.flags & AVIIF_KEYFRAME) != 0
+                    || self.streams[stream_index].media_type == MediaType::Audio,
+            });
+        }
+        Ok(())
+    }
+
+    /// 按索引更新各流的时长与帧数, 使其覆盖整个文件
+    fn update_stream_lengths(&mut self) {
+        if self.index.is_empty() {
+            return;
+        }
+        let mut durations = vec![0i64; self.streams.len()];
+        let mut packets = vec![0u64; self.streams.len()];
+        for entry in &self.index {
+            durations[entry.stream_index] += self.packet_advance(entry.stream_index, entry.size);
+            packets[entry.stream_index] += 1;
+        }
+        for (i, stream) in self.streams.iter_mut().enumerate() {
+            if packets[i] > 0 {
+                stream.duration = durations[i];
+                stream.nb_frames = packets[i];
+            }
+        }
+    }
+
+    /// 从块 ID 解析流号: 前两个字符必须是 ASCII 数字
+    fn chunk_stream_number(chunk_id: &[u8; 4]) -> Option<usize> {
+        if chunk_id[0].is_ascii_digit() && chunk_id[1].is_ascii_digit() {
+            Some(((chunk_id[0] - b'0') * 10 + (chunk_id[1] - b'0')) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// 数据包的 PTS 增量: PCM 音频按采样数, 压缩音频/视频按帧序号
+    fn packet_advance(&self, stream_index: usize, size: u32) -> i64 {
+        let sample_size = self.sample_sizes.get(stream_index).copied().unwrap_or(0);
+        size.checked_div(sample_size).map_or(1, |n| n.max(1) as i64)
+    }
+
+    /// 无索引时的回退 seek: 从各 movi 起始扫描块头定位到目标帧
+    fn seek_no_index(
         &mut self,
         io: &mut IoContext,
         stream_index: usize,
         timestamp: i64,
     ) -> TaoResult<()> {
         let target_frame = timestamp.max(0);
-
-        // 回到 movi 数据起始位置
-        io.seek(std::io::SeekFrom::Start(self.movi_data_start))?;
         self.frame_counts = vec![0; self.streams.len()];
 
-        let movi_end = self.movi_data_start + self.movi_list_size;
-
         // 记录目标流最后一次出现的块位置和帧计数, 供目标超出末尾时回退
-        let mut last_chunk_start = self.movi_data_start;
+        let mut last_chunk: Option<(usize, u64)> = None;
         let mut last_frame_counts = self.frame_counts.clone();
-        let mut found_any = false;
 
         // 扫描块头, 跳过数据, 直到找到目标流的目标帧
-        while io.position()? < movi_end {
-            let chunk_start = io.position()?;
-            let chunk_id = match io.read_tag() {
-                Ok(tag) => tag,
-                Err(_) => break,
-            };
-            let chunk_size = match io.read_u32_le() {
-                Ok(s) => s,
-                Err(_) => break,
-            };
-
-            // 解析流号: 前两个字符必须是 ASCII 数字
-            if chunk_id[0].is_ascii_digit() && chunk_id[1].is_ascii_digit() {
-                let snum = ((chunk_id[0] - b'0') * 10 + (chunk_id[1] - b'0')) as usize;
-                if snum < self.streams.len() {
+        for (segment, &(movi_start, movi_end)) in self.movi_segments.iter().enumerate() {
+            io.seek(std::io::SeekFrom::Start(movi_start))?;
+            while io.position()? < movi_end {
+                let chunk_start = io.position()?;
+                let chunk_id = match io.read_tag() {
+                    Ok(tag) => tag,
+                    Err(_) => break,
+                };
+                let chunk_size = match io.read_u32_le() {
+                    Ok(s) => s,
+                    Err(_) => break,
+                };
+
+                if let Some(snum) =
+                    Self::chunk_stream_number(&chunk_id).filter(|&n| n < self.streams.len())
+                {
                     if snum == stream_index {
                         if self.frame_counts[snum] >= target_frame {
                             // 找到目标帧, 回退到块头
                             io.seek(std::io::SeekFrom::Start(chunk_start))?;
+                            self.segment_pos = segment;
                             debug!(
                                 "无索引 seek: 流 {} 帧 {} (扫描到 {})",
                                 stream_index, target_frame, chunk_start
@@ -608,29 +886,25 @@ impl AviDemuxer {
                             return Ok(());
                         }
                         // 记录最后可用位置
-                        last_chunk_start = chunk_start;
+                        last_chunk = Some((segment, chunk_start));
                         last_frame_counts.clone_from(&self.frame_counts);
-                        found_any = true;
-                    }
-                    let ss = self.sample_sizes.get(snum).copied().unwrap_or(0);
-                    if ss > 0 {
-                        self.frame_counts[snum] += (chunk_size / ss).max(1) as i64;
-                    } else {
-                        self.frame_counts[snum] += 1;
                     }
+                    let advance = self.packet_advance(snum, chunk_size);
+                    self.frame_counts[snum] += advance;
                 }
-            }
 
-            // 跳过块数据 (必须用 skip, 不能用 SeekFrom::Current, 因为有读缓冲)
-            io.skip(chunk_size as usize)?;
-            if chunk_size % 2 != 0 {
-                io.skip(1)?;
+                // 跳过块数据 (必须用 skip, 不能用 SeekFrom::Current, 因为有读缓冲)
+                io.skip(chunk_size as usize)?;
+                if chunk_size % 2 != 0 {
+                    io.skip(1)?;
+                }
             }
         }
 
-        if found_any {
+        if let Some((segment, chunk_start)) = last_chunk {
             // 目标帧超出末尾: 定位到目标流的最后一帧
-            io.seek(std::io::SeekFrom::Start(last_chunk_start))?;
+            io.seek(std::io::SeekFrom::Start(chunk_start))?;
+            self.segment_pos = segment;
             self.frame_counts = last_frame_counts;
             debug!(
                 "无索引 seek: 流 {} 目标帧 {} 超出末尾, 定位到最后一帧",
@@ -639,7 +913,10 @@ impl AviDemuxer {
             Ok(())
         } else {
             // movi 中没有找到目标流的任何数据
-            io.seek(std::io::SeekFrom::Start(self.movi_data_start))?;
+            self.segment_pos = 0;
+            if let Some(&(movi_start, _)) = self.movi_segments.first() {
+                io.seek(std::io::SeekFrom::Start(movi_start))?;
+            }
             self.frame_counts = vec![0; self.streams.len()];
             Err(TaoError::Eof)
         }
@@ -670,14 +947,15 @@ impl Demuxer for AviDemuxer {
 
         debug!("检测到 RIFF/AVI 文件");
 
-        let mut movi_start: u64 = 0;
-        let mut movi_data_start: u64 = 0;
-        let mut movi_list_size: u64 = 0;
-
         loop {
             let (chunk_id, chunk_size, is_list) = match Self::read_riff_chunk_header(io) {
                 Ok(v) => v,
                 Err(TaoError::Eof) => break,
+                // 已找到数据后, 文件尾部的损坏块不影响播放
+                Err(e) if !self.movi_segments.is_empty() => {
+                    debug!("AVI: 忽略 movi 之后无法解析的块: {}", e);
+                    break;
+                }
                 Err(e) => return Err(e),
             };
 
@@ -686,16 +964,26 @@ impl Demuxer for AviDemuxer {
                     self.parse_hdrl(io, chunk_size)?;
                 }
                 (b"movi", true) => {
-                    movi_start = io.position()? - 8;
-                    movi_data_start = io.position()?;
-                    movi_list_size = chunk_size as u64;
-                    io.seek(std::io::SeekFrom::Current(chunk_size as i64))?;
+                    let movi_data_start = io.position()?;
+                    let movi_end = movi_data_start + u64::from(chunk_size);
+                    self.movi_segments.push((movi_data_start, movi_end));
+                    if !io.is_seekable() {
+                        // 不可寻址时无法跳过 movi 读取后续索引, 直接从此处顺序读取
+                        break;
+                    }
+                    io.seek(std::io::SeekFrom::Start(movi_end))?;
+                }
+                (b"idx1", false) if !self.movi_segments.is_empty() => {
+                    self.parse_idx1(io, chunk_size)?;
                 }
-                (b"idx1", false) => {
-                    if movi_start > 0 {
-                        self.parse_idx1(io, movi_data_start, chunk_size)?;
+                (b"RIFF", false) => {
+                    // OpenDML 后续 RIFF 块: 进入 'AVIX' 继续扫描其中的 movi 列表
+                    let form = io.read_tag()?;
+                    debug!("AVI: 后续 RIFF 块 {:?}", String::from_utf8_lossy(&form));
+                    if &form != b"AVIX" {
+                        io.skip(chunk_size.saturating_sub(4) as usize)?;
                     }
-                    break;
+                    continue;
                 }
                 _ => {
                     io.skip(chunk_size as usize)?;
@@ -705,26 +993,25 @@ impl Demuxer for AviDemuxer {
             if chunk_size % 2 != 0 && !is_list {
                 io.skip(1)?;
             }
-
-            if &chunk_id == b"idx1" {
-                break;
-            }
         }
 
         if self.streams.is_empty() {
             return Err(TaoError::InvalidData("AVI 文件中未找到有效流".into()));
         }
 
-        self.movi_data_start = movi_data_start;
-        self.movi_list_size = movi_list_size;
+        self.build_index(io)?;
+        self.update_stream_lengths();
 
-        if self.idx1_entries.is_empty() {
+        let movi_data_start = self.movi_segments.first().map_or(0, |&(start, _)| start);
+        if self.index.is_empty() && io.is_seekable() {
             io.seek(std::io::SeekFrom::Start(movi_data_start))?;
         }
 
         debug!(
-            "AVI 打开完成: {} 个流, movi 起始={}",
+            "AVI 打开完成: {} 个流, {} 个 movi 列表, 索引条目={}, movi 起始={}",
             self.streams.len(),
+            self.movi_segments.len(),
+            self.index.len(),
             movi_data_start
         );
 
@@ -736,80 +1023,62 @@ impl Demuxer for AviDemuxer {
     }
 
     fn read_packet(&mut self, io: &mut IoContext) -> TaoResult<Packet> {
-        if !self.idx1_entries.is_empty() {
-            if self.idx_pos >= self.idx1_entries.len() {
+        if !self.index.is_empty() {
+            let Some(entry) = self.index.get(self.idx_pos).cloned() else {
                 return Err(TaoError::Eof);
-            }
-
-            let entry = &self.idx1_entries[self.idx_pos];
+            };
             self.idx_pos += 1;
 
-            let chunk_offset = self.movi_data_start + u64::from(entry.offset) + 8;
-            io.seek(std::io::SeekFrom::Start(chunk_offset))?;
-
+            io.seek(std::io::SeekFrom::Start(entry.offset))?;
             let data = io.read_bytes(entry.size as usize)?;
 
-            let stream_num =
-                ((entry.chunk_id[0] - b'0') * 10 + (entry.chunk_id[1] - b'0')) as usize;
-            let stream_index = stream_num.min(self.streams.len().saturating_sub(1));
-
+            let stream_index = entry.stream_index;
             let stream = &self.streams[stream_index];
             let pts = self.frame_counts[stream_index];
-            let sample_size = self.sample_sizes.get(stream_index).copied().unwrap_or(0);
-            // PCM 音频: PTS 按采样数累加; 压缩音频/视频: 按帧序号累加
-            let advance = if sample_size > 0 {
-                (entry.size / sample_size).max(1) as i64
-            } else {
-                1
-            };
+            let advance = self.packet_advance(stream_index, entry.size);
             self.frame_counts[stream_index] += advance;
 
-            let is_keyframe =
-                (entry.flags & AVIIF_KEYFRAME) != 0 || stream.media_type == MediaType::Audio;
-
             let mut pkt = Packet::from_data(Bytes::from(data));
             pkt.stream_index = stream_index;
             pkt.pts = pts;
             pkt.dts = pts;
             pkt.duration = advance;
             pkt.time_base = stream.time_base;
-            pkt.is_keyframe = is_keyframe;
-            pkt.pos = chunk_offset as i64;
+            pkt.set_keyframe(entry.keyframe);
+            pkt.pos = entry.offset as i64;
 
             return Ok(pkt);
         }
 
         loop {
             let pos = io.position()?;
-            if pos >= self.movi_data_start + self.movi_list_size {
+            let Some(&(_, movi_end)) = self.movi_segments.get(self.segment_pos) else {
                 return Err(TaoError::Eof);
+            };
+            if pos >= movi_end {
+                // 当前 movi 读完, 继续下一个 RIFF 块的 movi
+                self.segment_pos += 1;
+                match self.movi_segments.get(self.segment_pos) {
+                    Some(&(next_start, _)) => {
+                        io.seek(std::io::SeekFrom::Start(next_start))?;
+                        continue;
+                    }
+                    None => return Err(TaoError::Eof),
+                }
             }
 
             let chunk_id = io.read_tag()?;
             let chunk_size = io.read_u32_le()?;
 
-            let stream_num = if chunk_id.len() >= 2
-                && chunk_id[0].is_ascii_digit()
-                && chunk_id[1].is_ascii_digit()
-            {
-                ((chunk_id[0] - b'0') * 10 + (chunk_id[1] - b'0')) as usize
-            } else {
-                io.skip(chunk_size as usize)?;
-                if chunk_size % 2 != 0 {
-                    io.skip(1)?;
-                }
-                continue;
-            };
-
-            let code = if chunk_id.len() >= 4 {
-                &chunk_id[2..4]
-            } else {
+            // 非数据块 (如 ix## 标准索引, JUNK) 直接跳过
+            let Some(stream_num) = Self::chunk_stream_number(&chunk_id) else {
                 io.skip(chunk_size as usize)?;
                 if chunk_size % 2 != 0 {
                     io.skip(1)?;
                 }
                 continue;
             };
+            let code = &chunk_id[2..4];
 
             let stream_index = stream_num.min(self.streams.len().saturating_sub(1));
             let is_video = code == b"dc" || code == b"db";
@@ -830,12 +1099,7 @@ impl Demuxer for AviDemuxer {
 
             let stream = &self.streams[stream_index];
             let pts = self.frame_counts[stream_index];
-            let sample_size = self.sample_sizes.get(stream_index).copied().unwrap_or(0);
-            let advance = if sample_size > 0 {
-                (chunk_size / sample_size).max(1) as i64
-            } else {
-                1
-            };
+            let advance = self.packet_advance(stream_index, chunk_size);
             self.frame_counts[stream_index] += advance;
 
             let is_keyframe = is_audio || code == b"db" || code == b"dc";
@@ -846,7 +1110,7 @@ impl Demuxer for AviDemuxer {
             pkt.dts = pts;
             pkt.duration = advance;
             pkt.time_base = stream.time_base;
-            pkt.is_keyframe = is_keyframe;
+            pkt.set_keyframe(is_keyframe);
             pkt.pos = pos as i64;
 
             return Ok(pkt);
@@ -864,69 +1128,52 @@ impl Demuxer for AviDemuxer {
             return Err(TaoError::Unsupported("不支持在非可寻址流上 seek".into()));
         }
 
-        if self.idx1_entries.is_empty() {
-            return self.seek_no_idx1(io, stream_index, timestamp);
+        if self.index.is_empty() {
+            return self.seek_no_index(io, stream_index, timestamp);
         }
 
-        let target = timestamp.max(0) as usize;
+        let target = timestamp.max(0);
         let is_video = stream_index < self.streams.len()
             && self.streams[stream_index].media_type == MediaType::Video;
 
-        // 遍历 idx1, 找到 target 帧位置, 同时记录最近的关键帧位置
+        // 遍历索引, 找到 target 所在的数据包, 同时记录最近的关键帧位置
         let mut idx_pos = 0;
         let mut last_keyframe_idx = 0;
-        let mut count = 0;
+        let mut pts = 0;
         let mut found = false;
 
-        for (i, entry) in self.idx1_entries.iter().enumerate() {
-            let snum = ((entry.chunk_id[0].saturating_sub(b'0')) * 10
-                + (entry.chunk_id[1].saturating_sub(b'0'))) as usize;
-            if snum == stream_index {
-                let is_keyframe = (entry.flags & AVIIF_KEYFRAME) != 0;
-                if is_keyframe || !is_video {
+        for (i, entry) in self.index.iter().enumerate() {
+            if entry.stream_index == stream_index {
+                if entry.keyframe || !is_video {
                     last_keyframe_idx = i;
                 }
-                if count >= target {
+                let advance = self.packet_advance(stream_index, entry.size);
+                if pts + advance > target {
                     idx_pos = i;
                     found = true;
                     break;
                 }
-                count += 1;
+                pts += advance;
             }
         }
 
-        // 视频流: 回退到最近的关键帧, 避免从非关键帧开始解码导致花屏
-        if is_video && found {
-            idx_pos = last_keyframe_idx;
-        } else if is_video && !found {
-            // target 超出范围, 定位到最后一个关键帧
+        // 视频流: 回退到最近的关键帧, 避免从非关键帧开始解码导致花屏;
+        // target 超出范围时定位到最后一个关键帧
+        if is_video || !found {
             idx_pos = last_keyframe_idx;
         }
 
         self.idx_pos = idx_pos;
         self.frame_counts = vec![0; self.streams.len()];
-        for (i, entry) in self.idx1_entries.iter().enumerate() {
-            if i >= idx_pos {
-                break;
-            }
-            let snum = ((entry.chunk_id[0].saturating_sub(b'0')) * 10
-                + (entry.chunk_id[1].saturating_sub(b'0'))) as usize;
-            if snum < self.frame_counts.len() {
-                let ss = self.sample_sizes.get(snum).copied().unwrap_or(0);
-                if ss > 0 {
-                    self.frame_counts[snum] += (entry.size / ss).max(1) as i64;
-                } else {
-                    self.frame_counts[snum] += 1;
-                }
-            }
-        }
-
-        if idx_pos < self.idx1_entries.len() {
-            let entry = &self.idx1_entries[idx_pos];
-            let chunk_offset = self.movi_data_start + u64::from(entry.offset) + 8;
-            io.seek(std::io::SeekFrom::Start(chunk_offset))?;
+        for entry in &self.index[..idx_pos] {
+            let advance = self.packet_advance(entry.stream_index, entry.size);
+            self.frame_counts[entry.stream_index] += advance;
         }
 
+        debug!(
+            "AVI seek: 流 {} 目标 {} -> 索引 {} (偏移 {})",
+            stream_index, timestamp, idx_pos, self.index[idx_pos].offset
+        );
         Ok(())
     }
 
@@ -943,7 +1190,16 @@ impl Demuxer for AviDemuxer {
                 return Some(s.duration as f64 * s.time_base.to_f64());
             }
         }
-        None
+        if self.total_frames > 0 && self.micro_sec_per_frame > 0 {
+            return Some(
+                f64::from(self.total_frames) * f64::from(self.micro_sec_per_frame) / 1_000_000.0,
+            );
+        }
+        self.streams
+            .iter()
+            .filter(|s| s.time_base.is_valid() && s.duration > 0)
+            .map(|s| s.duration as f64 * s.time_base.to_f64())
+            .reduce(f64::max)
     }
 
     fn metadata(&self) -> &[(String, String)] {
@@ -1076,8 +1332,7 @@ mod tests {
     #[test]
     fn test_parse_minimal_avi() {
         let avi = make_minimal_avi();
-        let backend = crate::io::MemoryBackend::from_data(avi);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(avi);
         let mut demuxer = AviDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -1089,9 +1344,64 @@ mod tests {
         let pkt = demuxer.read_packet(&mut io).unwrap();
         assert_eq!(pkt.stream_index, 0);
         assert_eq!(pkt.data.len(), 100);
-        assert!(pkt.is_keyframe);
+        assert!(pkt.is_keyframe());
 
         let err = demuxer.read_packet(&mut io).unwrap_err();
         assert!(matches!(err, TaoError::Eof));
     }
+
+    #[test]
+    fn test_header_frame_counts_and_duration() {
+        let mut avi = make_minimal_avi();
+        let mut io = IoContext::from_bytes(avi.clone());
+        let mut demuxer = AviDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        assert_eq!(
+            demuxer.streams()[0].nb_frames,
+            100,
+            "帧数应来自 strh dwLength"
+        );
+        assert_eq!(demuxer.duration(), Some(4.0), "100 帧 @ 25fps 应为 4 秒");
+
+        // strh 未记录长度时回退到 avih 的 dwTotalFrames × dwMicroSecPerFrame
+        avi[48..52].copy_from_slice(&60u32.to_le_bytes()); // dwTotalFrames
+        avi[140..144].copy_from_slice(&0u32.to_le_bytes()); // strh dwLength
+        let mut io = IoContext::from_bytes(avi.clone());
+        let mut demuxer = AviDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        assert_eq!(
+            demuxer.streams()[0].nb_frames,
+            60,
+            "帧数应来自 avih dwTotalFrames"
+        );
+        assert_eq!(demuxer.streams()[0].duration, 60);
+
+        avi[48..52].copy_from_slice(&0u32.to_le_bytes());
+        let mut io = IoContext::from_bytes(avi);
+        let mut demuxer = AviDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        assert_eq!(demuxer.streams()[0].nb_frames, 0);
+        assert!(demuxer.duration().is_none(), "头部无帧数时不猜测时长");
+    }
+
+    #[test]
+    fn test_parse_idx1_relative_to_movi_data() {
+        // 旧版写入的 idx1 偏移相对 movi 数据区 (而非 'movi' 标签), 应自动识别
+        let mut avi = make_minimal_avi();
+        avi.extend_from_slice(b"idx1");
+        avi.extend_from_slice(&16u32.to_le_bytes());
+        avi.extend_from_slice(b"00dc");
+        avi.extend_from_slice(&0u32.to_le_bytes()); // dwFlags: 非关键帧
+        avi.extend_from_slice(&0u32.to_le_bytes()); // dwOffset
+        avi.extend_from_slice(&100u32.to_le_bytes()); // dwSize
+        let mut io = IoContext::from_bytes(avi);
+        let mut demuxer = AviDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        assert_eq!(demuxer.streams()[0].nb_frames, 1, "帧数应来自索引");
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!(pkt.data.len(), 100);
+        assert!(!pkt.is_keyframe(), "关键帧标志应来自 idx1");
+        assert!(matches!(demuxer.read_packet(&mut io), Err(TaoError::Eof)));
+    }
 }
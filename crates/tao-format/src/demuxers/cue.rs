@@ -230,7 +230,7 @@ impl Demuxer for CueDemuxer {
         // 5. 使用 FormatRegistry 探测并打开音频文件
         let mut registry = FormatRegistry::new();
         crate::register_all(&mut registry);
-        let probe_result = registry.probe_input(&mut audio_io, Some(audio_path_str))?;
+        let probe_result = registry.probe_input_audio_only(&mut audio_io, Some(audio_path_str))?;
 
         let mut demuxer = registry.create_demuxer(probe_result.format_id)?;
 
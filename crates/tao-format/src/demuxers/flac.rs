@@ -484,6 +484,7 @@ impl Demuxer for FlacDemuxer {
                 sample_format,
                 bit_rate,
                 frame_size: u32::from(info.max_block_size),
+                block_align: 0,
             }),
             metadata: Vec::new(),
         };
@@ -568,7 +569,7 @@ impl Demuxer for FlacDemuxer {
         pkt.pts = pts as i64;
         pkt.dts = pkt.pts;
         pkt.time_base = Rational::new(1, self.sample_rate as i32);
-        pkt.is_keyframe = true;
+        pkt.set_keyframe(true);
         pkt.pos = self.current_pos as i64;
 
         // 更新位置
@@ -42,7 +42,7 @@
 
 use bytes::Bytes;
 use log::debug;
-use tao_codec::{CodecId, Packet};
+use tao_codec::{CodecId, Packet, PacketSideData};
 use tao_core::{
     ChannelLayout, MediaType, PixelFormat, Rational, SampleFormat, TaoError, TaoResult,
 };
@@ -84,12 +84,20 @@ pub struct FlvDemuxer {
     video_codec_id: CodecId,
     /// 文件时长 (毫秒, 来自 onMetaData)
     duration_ms: Option<f64>,
+    /// onMetaData 元数据
+    metadata: Option<FlvMetadata>,
+    /// 关键帧索引 (pts 毫秒, 文件字节偏移), 来自 onMetaData.keyframes, 供 seek 使用
+    keyframe_index: Vec<(i64, u64)>,
     /// 数据区起始偏移
     data_offset: u64,
     /// 是否已收到音频 sequence header
     audio_config_received: bool,
     /// 是否已收到视频 sequence header
     video_config_received: bool,
+    /// 中途更新的音频 sequence header, 附加到下一个音频包
+    pending_audio_config: Option<Vec<u8>>,
+    /// 中途更新的视频 sequence header, 附加到下一个视频包
+    pending_video_config: Option<Vec<u8>>,
 }
 
 impl FlvDemuxer {
@@ -102,9 +110,13 @@ impl FlvDemuxer {
             audio_codec_id: CodecId::None,
             video_codec_id: CodecId::None,
             duration_ms: None,
+            metadata: None,
+            keyframe_index: Vec::new(),
             data_offset: 0,
             audio_config_received: false,
             video_config_received: false,
+            pending_audio_config: None,
+            pending_video_config: None,
         }))
     }
 
@@ -211,6 +223,7 @@ impl FlvDemuxer {
                     sample_format: SampleFormat::F32,
                     bit_rate: 0,
                     frame_size: 1024,
+                    block_align: 0,
                 }),
                 metadata: Vec::new(),
             };
@@ -232,6 +245,9 @@ impl FlvDemuxer {
                 let config = io.read_bytes(payload_size as usize)?;
                 debug!("FLV: 收到 AAC sequence header, {} 字节", config.len());
                 if let Some(idx) = self.audio_stream_idx {
+                    if self.audio_config_received && self.streams[idx].extra_data != config {
+                        self.pending_audio_config = Some(config.clone());
+                    }
                     self.streams[idx].extra_data = config;
                 }
                 self.audio_config_received = true;
@@ -245,8 +261,11 @@ impl FlvDemuxer {
             pkt.stream_index = stream_index;
             pkt.pts = i64::from(timestamp);
             pkt.dts = i64::from(timestamp);
-            pkt.is_keyframe = true;
+            pkt.set_keyframe(true);
             pkt.time_base = Rational::new(1, 1000);
+            if let Some(config) = self.pending_audio_config.take() {
+                pkt.side_data.push(PacketSideData::NewExtraData(config));
+            }
             return Ok(Some(pkt));
         }
 
@@ -257,7 +276,7 @@ impl FlvDemuxer {
         pkt.stream_index = stream_index;
         pkt.pts = i64::from(timestamp);
         pkt.dts = i64::from(timestamp);
-        pkt.is_keyframe = true;
+        pkt.set_keyframe(true);
         pkt.time_base = Rational::new(1, 1000);
         Ok(Some(pkt))
     }
@@ -309,10 +328,13 @@ impl FlvDemuxer {
                     frame_rate: Rational::new(0, 1),
                     sample_aspect_ratio: Rational::new(1, 1),
                     bit_rate: 0,
+                    color_space: Default::default(),
+                    color_range: Default::default(),
                 }),
                 metadata: Vec::new(),
             };
             self.streams.push(stream);
+            self.apply_video_metadata();
         }
 
         let remaining = data_size - 1; // 减去 video_header
@@ -343,6 +365,9 @@ impl FlvDemuxer {
                 let config = io.read_bytes(payload_size as usize)?;
                 debug!("FLV: 收到视频 sequence header, {} 字节", config.len());
                 if let Some(idx) = self.video_stream_idx {
+                    if self.video_config_received && self.streams[idx].extra_data != config {
+                        self.pending_video_config = Some(config.clone());
+                    }
                     self.streams[idx].extra_data = config;
                 }
                 self.video_config_received = true;
@@ -365,8 +390,11 @@ impl FlvDemuxer {
             pkt.stream_index = stream_index;
             pkt.pts = pts;
             pkt.dts = dts;
-            pkt.is_keyframe = is_keyframe;
+            pkt.set_keyframe(is_keyframe);
             pkt.time_base = Rational::new(1, 1000);
+            if let Some(config) = self.pending_video_config.take() {
+                pkt.side_data.push(PacketSideData::NewExtraData(config));
+            }
             return Ok(Some(pkt));
         }
 
@@ -377,57 +405,255 @@ impl FlvDemuxer {
         pkt.stream_index = stream_index;
         pkt.pts = i64::from(timestamp);
         pkt.dts = i64::from(timestamp);
-        pkt.is_keyframe = is_keyframe;
+        pkt.set_keyframe(is_keyframe);
         pkt.time_base = Rational::new(1, 1000);
         Ok(Some(pkt))
     }
 
-    /// 简单解析 onMetaData (AMF0), 只提取 duration
+    /// 解析 Script Tag, 提取 onMetaData 中的时长/分辨率/帧率与关键帧索引
     fn parse_script_tag(&mut self, io: &mut IoContext, data_size: u32) -> TaoResult<()> {
-        // AMF0: 简化解析, 只查找 "duration" 字段
         let data = io.read_bytes(data_size as usize)?;
+        let Some(meta) = parse_on_metadata(&data) else {
+            return Ok(());
+        };
+
+        if let Some(dur) = meta.duration.filter(|d| *d > 0.0) {
+            self.duration_ms = Some(dur * 1000.0);
+            debug!("FLV: onMetaData duration={dur}s");
+        }
+        if !meta.keyframes.is_empty() {
+            debug!("FLV: onMetaData 关键帧索引 {} 项", meta.keyframes.len());
+            self.keyframe_index = meta.keyframes.clone();
+        }
+        self.metadata = Some(meta);
+        self.apply_video_metadata();
+        Ok(())
+    }
 
-        // 搜索 "duration" 字符串并在其后读取 AMF0 number
-        let needle = b"duration";
-        if let Some(pos) = data.windows(needle.len()).position(|w| w == needle) {
-            let after = pos + needle.len();
-            // AMF0 Number: type(0x00) + 8 bytes IEEE 754
-            if after + 9 <= data.len() && data[after] == 0x00 {
-                let dur_bytes = &data[after + 1..after + 9];
-                let bits = u64::from_be_bytes(dur_bytes.try_into().unwrap_or([0; 8]));
-                let dur = f64::from_bits(bits);
-                if dur > 0.0 && dur.is_finite() {
-                    self.duration_ms = Some(dur * 1000.0);
-                    debug!("FLV: onMetaData duration={dur}s");
+    /// 将 onMetaData 中的视频参数填入视频流 (仅覆盖未知字段)
+    fn apply_video_metadata(&mut self) {
+        let (Some(meta), Some(idx)) = (&self.metadata, self.video_stream_idx) else {
+            return;
+        };
+        if let StreamParams::Video(ref mut vp) = self.streams[idx].params {
+            if vp.width == 0 {
+                vp.width = meta.width.unwrap_or(0);
+            }
+            if vp.height == 0 {
+                vp.height = meta.height.unwrap_or(0);
+            }
+            if vp.frame_rate.num == 0 {
+                if let Some(fps) = meta.frame_rate {
+                    vp.frame_rate = Rational::from_f64(fps, 1_001_000);
                 }
             }
         }
+    }
+}
 
-        // 搜索 "width" 和 "height"
-        for key in [b"width" as &[u8], b"height"] {
-            if let Some(pos) = data.windows(key.len()).position(|w| w == key) {
-                let after = pos + key.len();
-                if after + 9 <= data.len() && data[after] == 0x00 {
-                    let val_bytes = &data[after + 1..after + 9];
-                    let bits = u64::from_be_bytes(val_bytes.try_into().unwrap_or([0; 8]));
-                    let val = f64::from_bits(bits);
-                    if val > 0.0 && val.is_finite() {
-                        if let Some(idx) = self.video_stream_idx {
-                            if let StreamParams::Video(ref mut vp) = self.streams[idx].params {
-                                if key == b"width" {
-                                    vp.width = val as u32;
-                                } else {
-                                    vp.height = val as u32;
-                                }
-                            }
-                        }
-                    }
+/// onMetaData 中解析出的信息
+#[derive(Debug, Clone, Default, PartialEq)]
+struct FlvMetadata {
+    /// 时长 (秒)
+    duration: Option<f64>,
+    /// 视频宽度
+    width: Option<u32>,
+    /// 视频高度
+    height: Option<u32>,
+    /// 帧率
+    frame_rate: Option<f64>,
+    /// 视频码率 (kbps)
+    video_data_rate: Option<f64>,
+    /// 音频码率 (kbps)
+    audio_data_rate: Option<f64>,
+    /// 关键帧索引: (pts 毫秒, 文件字节偏移)
+    keyframes: Vec<(i64, u64)>,
+}
+
+/// AMF0 值
+#[derive(Debug, Clone, PartialEq)]
+enum Amf0Value {
+    Number(f64),
+    Boolean(bool),
+    String(String),
+    /// Object 与 ECMA Array 均按有序键值对保存
+    Object(Vec<(String, Amf0Value)>),
+    StrictArray(Vec<Amf0Value>),
+    Date(f64),
+    Null,
+}
+
+impl Amf0Value {
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(v) if v.is_finite() => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Amf0Value> {
+        match self {
+            Self::Object(props) => props.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_numbers(&self) -> Vec<f64> {
+        match self {
+            Self::StrictArray(items) => items.iter().filter_map(Self::as_number).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// AMF0 最大嵌套深度, 防止恶意数据导致栈溢出
+const AMF0_MAX_DEPTH: usize = 16;
+
+/// AMF0 读取器
+struct Amf0Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Amf0Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(n)?;
+        let bytes = self.data.get(self.pos..end)?;
+        self.pos = end;
+        Some(bytes)
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn read_f64(&mut self) -> Option<f64> {
+        self.take(8)
+            .map(|b| f64::from_be_bytes(b.try_into().unwrap_or([0; 8])))
+    }
+
+    fn read_string(&mut self, len: usize) -> Option<String> {
+        self.take(len)
+            .map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+
+    /// 读取键值对直到 object end 标记 (0x00 0x00 0x09)
+    fn read_properties(&mut self, depth: usize) -> Option<Vec<(String, Amf0Value)>> {
+        let mut props = Vec::new();
+        loop {
+            let key_len = self.read_u16()? as usize;
+            if key_len == 0 {
+                // 空键后应为 object end 标记; 部分文件省略该标记, 容忍之
+                if self.data.get(self.pos) == Some(&0x09) {
+                    self.pos += 1;
                 }
+                return Some(props);
             }
+            let key = self.read_string(key_len)?;
+            let value = self.read_value(depth + 1)?;
+            props.push((key, value));
         }
+    }
 
-        Ok(())
+    fn read_value(&mut self, depth: usize) -> Option<Amf0Value> {
+        if depth > AMF0_MAX_DEPTH {
+            return None;
+        }
+        let marker = *self.take(1)?.first()?;
+        match marker {
+            0x00 => self.read_f64().map(Amf0Value::Number),
+            0x01 => self.take(1).map(|b| Amf0Value::Boolean(b[0] != 0)),
+            0x02 => {
+                let len = self.read_u16()? as usize;
+                self.read_string(len).map(Amf0Value::String)
+            }
+            0x03 => self.read_properties(depth).map(Amf0Value::Object),
+            0x05 | 0x06 => Some(Amf0Value::Null),
+            0x07 => self.read_u16().map(|_| Amf0Value::Null),
+            0x08 => {
+                // ECMA Array: 近似计数 + 键值对
+                let _count = self.read_u32()?;
+                self.read_properties(depth).map(Amf0Value::Object)
+            }
+            0x0A => {
+                let count = self.read_u32()? as usize;
+                // 每个元素至少 1 字节, 防止伪造计数导致超大分配
+                if count > self.data.len() - self.pos {
+                    return None;
+                }
+                let mut items = Vec::with_capacity(count);
+                for _ in 0..count {
+                    items.push(self.read_value(depth + 1)?);
+                }
+                Some(Amf0Value::StrictArray(items))
+            }
+            0x0B => {
+                let millis = self.read_f64()?;
+                let _tz = self.read_u16()?;
+                Some(Amf0Value::Date(millis))
+            }
+            0x0C => {
+                let len = self.read_u32()? as usize;
+                self.read_string(len).map(Amf0Value::String)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 解析 Script Tag 数据, 若为 onMetaData 则提取常用字段
+fn parse_on_metadata(data: &[u8]) -> Option<FlvMetadata> {
+    let mut reader = Amf0Reader::new(data);
+    match reader.read_value(0)? {
+        Amf0Value::String(name) if name == "onMetaData" => {}
+        _ => return None,
     }
+    let props = reader.read_value(0)?;
+
+    let number = |key: &str| props.get(key).and_then(Amf0Value::as_number);
+    let dimension = |key: &str| {
+        number(key)
+            .filter(|v| *v > 0.0 && *v <= f64::from(u32::MAX))
+            .map(|v| v as u32)
+    };
+
+    let mut meta = FlvMetadata {
+        duration: number("duration"),
+        width: dimension("width"),
+        height: dimension("height"),
+        frame_rate: number("framerate").filter(|v| *v > 0.0),
+        video_data_rate: number("videodatarate").filter(|v| *v > 0.0),
+        audio_data_rate: number("audiodatarate").filter(|v| *v > 0.0),
+        keyframes: Vec::new(),
+    };
+
+    if let Some(keyframes) = props.get("keyframes") {
+        let times = keyframes
+            .get("times")
+            .map(Amf0Value::as_numbers)
+            .unwrap_or_default();
+        let positions = keyframes
+            .get("filepositions")
+            .map(Amf0Value::as_numbers)
+            .unwrap_or_default();
+        meta.keyframes = times
+            .iter()
+            .zip(&positions)
+            .filter(|(_, pos)| **pos >= 0.0)
+            .map(|(t, pos)| ((t * 1000.0).round() as i64, *pos as u64))
+            .collect();
+    }
+
+    Some(meta)
 }
 
 impl Demuxer for FlvDemuxer {
@@ -493,11 +719,28 @@ impl Demuxer for FlvDemuxer {
             }
         }
 
+        // 更新码率 (onMetaData 中单位为 kbps)
+        if let Some(meta) = &self.metadata {
+            for stream in &mut self.streams {
+                match &mut stream.params {
+                    StreamParams::Video(vp) if vp.bit_rate == 0 => {
+                        vp.bit_rate = kbps_to_bps(meta.video_data_rate);
+                    }
+                    StreamParams::Audio(ap) if ap.bit_rate == 0 => {
+                        ap.bit_rate = kbps_to_bps(meta.audio_data_rate);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         // 回到数据区开始, 准备顺序读取
         io.seek(std::io::SeekFrom::Start(self.data_offset))?;
         let _prev = io.read_u32_be()?; // PreviousTagSize0
         self.audio_config_received = false;
         self.video_config_received = false;
+        self.pending_audio_config = None;
+        self.pending_video_config = None;
 
         debug!("FLV: 打开完成, {} 个流", self.streams.len(),);
         Ok(())
@@ -550,6 +793,17 @@ impl Demuxer for FlvDemuxer {
     fn duration(&self) -> Option<f64> {
         self.duration_ms.map(|ms| ms / 1000.0)
     }
+
+    fn bit_rate(&self) -> Option<u64> {
+        let meta = self.metadata.as_ref()?;
+        let total = kbps_to_bps(meta.video_data_rate) + kbps_to_bps(meta.audio_data_rate);
+        (total > 0).then_some(total)
+    }
+}
+
+/// onMetaData 中的 kbps 码率换算为 bps, 未提供时为 0
+fn kbps_to_bps(kbps: Option<f64>) -> u64 {
+    kbps.map(|v| (v * 1000.0).round() as u64).unwrap_or(0)
 }
 
 /// FLV 格式探测器
@@ -583,7 +837,6 @@ impl FormatProbe for FlvProbe {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     /// 构造 FLV 文件头部
     fn build_flv_header(has_audio: bool, has_video: bool) -> Vec<u8> {
@@ -659,6 +912,81 @@ mod tests {
         tag
     }
 
+    /// 构造 FLV 视频 Tag (AVC sequence header)
+    fn build_video_config_tag(timestamp: u32, config: &[u8]) -> Vec<u8> {
+        let mut tag = build_video_tag(timestamp, true, config);
+        tag[12] = 0; // AVCPacketType = 0
+        tag
+    }
+
+    /// AMF0 编码辅助
+    fn amf_string(s: &str) -> Vec<u8> {
+        let mut out = vec![0x02];
+        out.extend_from_slice(&(s.len() as u16).to_be_bytes());
+        out.extend_from_slice(s.as_bytes());
+        out
+    }
+
+    fn amf_number(v: f64) -> Vec<u8> {
+        let mut out = vec![0x00];
+        out.extend_from_slice(&v.to_be_bytes());
+        out
+    }
+
+    fn amf_key(key: &str) -> Vec<u8> {
+        let mut out = (key.len() as u16).to_be_bytes().to_vec();
+        out.extend_from_slice(key.as_bytes());
+        out
+    }
+
+    fn amf_number_array(values: &[f64]) -> Vec<u8> {
+        let mut out = vec![0x0A];
+        out.extend_from_slice(&(values.len() as u32).to_be_bytes());
+        for v in values {
+            out.extend_from_slice(&amf_number(*v));
+        }
+        out
+    }
+
+    /// 构造 onMetaData 脚本数据 (ECMA Array, 含嵌套 keyframes 对象)
+    fn build_on_metadata() -> Vec<u8> {
+        let mut data = amf_string("onMetaData");
+        data.push(0x08);
+        data.extend_from_slice(&7u32.to_be_bytes());
+        for (key, value) in [
+            ("duration", 12.5),
+            ("width", 640.0),
+            ("height", 360.0),
+            ("framerate", 29.97),
+            ("videodatarate", 800.0),
+            ("audiodatarate", 128.0),
+        ] {
+            data.extend_from_slice(&amf_key(key));
+            data.extend_from_slice(&amf_number(value));
+        }
+        data.extend_from_slice(&amf_key("encoder"));
+        data.extend_from_slice(&amf_string("Lavf"));
+        data.extend_from_slice(&amf_key("keyframes"));
+        data.push(0x03);
+        data.extend_from_slice(&amf_key("filepositions"));
+        data.extend_from_slice(&amf_number_array(&[13.0, 4096.0, 9000.0]));
+        data.extend_from_slice(&amf_key("times"));
+        data.extend_from_slice(&amf_number_array(&[0.0, 2.0, 4.004]));
+        data.extend_from_slice(&[0x00, 0x00, 0x09]);
+        data.extend_from_slice(&[0x00, 0x00, 0x09]);
+        data
+    }
+
+    /// 构造 Script Tag
+    fn build_script_tag(payload: &[u8]) -> Vec<u8> {
+        let mut tag = vec![TAG_SCRIPT];
+        tag.extend_from_slice(&(payload.len() as u32).to_be_bytes()[1..]);
+        tag.extend_from_slice(&[0; 7]);
+        tag.extend_from_slice(payload);
+        tag.extend_from_slice(&(11 + payload.len() as u32).to_be_bytes());
+        tag
+    }
+
     /// 构造最小的 FLV 文件
     fn build_minimal_flv() -> Vec<u8> {
         let mut data = build_flv_header(true, true);
@@ -690,8 +1018,7 @@ mod tests {
     #[test]
     fn test_parse_minimal_flv() {
         let flv = build_minimal_flv();
-        let backend = MemoryBackend::from_data(flv);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(flv);
         let mut demuxer = FlvDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -712,8 +1039,7 @@ mod tests {
     #[test]
     fn test_read_flv_packets() {
         let flv = build_minimal_flv();
-        let backend = MemoryBackend::from_data(flv);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(flv);
         let mut demuxer = FlvDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -733,11 +1059,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_midstream_sequence_header_as_side_data() {
+        let mut flv = build_flv_header(false, true);
+        flv.extend_from_slice(&build_video_config_tag(0, &[0x01, 0xAA]));
+        flv.extend_from_slice(&build_video_tag(0, true, &[0xDE, 0xAD]));
+        // 中途更换 sequence header (如分辨率变化)
+        flv.extend_from_slice(&build_video_config_tag(33, &[0x01, 0xBB]));
+        flv.extend_from_slice(&build_video_tag(33, true, &[0xCA, 0xFE]));
+        flv.extend_from_slice(&build_video_tag(66, false, &[0xF0, 0x0D]));
+
+        let mut io = IoContext::from_bytes(flv);
+        let mut demuxer = FlvDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        let pkt0 = demuxer.read_packet(&mut io).unwrap();
+        assert!(
+            pkt0.side_data.is_empty(),
+            "首个 sequence header 不应作为边数据"
+        );
+        let pkt1 = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!(
+            pkt1.new_extra_data(),
+            Some(&[0x01, 0xBB][..]),
+            "中途 sequence header 应附加到下一个视频包"
+        );
+        let pkt2 = demuxer.read_packet(&mut io).unwrap();
+        assert!(pkt2.side_data.is_empty());
+        assert!(!pkt2.is_keyframe());
+    }
+
     #[test]
     fn test_video_keyframe_flag() {
         let flv = build_minimal_flv();
-        let backend = MemoryBackend::from_data(flv);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(flv);
         let mut demuxer = FlvDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -753,7 +1108,7 @@ mod tests {
                         .position(|s| s.media_type == MediaType::Video)
                     {
                         if pkt.stream_index == idx {
-                            if pkt.is_keyframe {
+                            if pkt.is_keyframe() {
                                 video_keyframes += 1;
                             } else {
                                 video_non_keyframes += 1;
@@ -776,8 +1131,7 @@ mod tests {
         data.extend_from_slice(&build_audio_tag(0, &[0xAA; 50]));
         data.extend_from_slice(&build_audio_tag(23, &[0xBB; 50]));
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut demuxer = FlvDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -785,4 +1139,84 @@ mod tests {
         assert_eq!(demuxer.streams()[0].media_type, MediaType::Audio);
         assert_eq!(demuxer.streams()[0].codec_id, CodecId::Aac);
     }
+
+    #[test]
+    fn test_parse_on_metadata_fields() {
+        let meta = parse_on_metadata(&build_on_metadata()).expect("应解析出 onMetaData");
+        assert_eq!(meta.duration, Some(12.5), "duration 应为 12.5 秒");
+        assert_eq!(meta.width, Some(640), "width 应为 640");
+        assert_eq!(meta.height, Some(360), "height 应为 360");
+        assert_eq!(meta.frame_rate, Some(29.97), "framerate 应为 29.97");
+        assert_eq!(meta.video_data_rate, Some(800.0), "videodatarate 应为 800");
+        assert_eq!(meta.audio_data_rate, Some(128.0), "audiodatarate 应为 128");
+        assert_eq!(
+            meta.keyframes,
+            vec![(0, 13), (2000, 4096), (4004, 9000)],
+            "关键帧索引应为 (pts 毫秒, 字节偏移)"
+        );
+    }
+
+    #[test]
+    fn test_parse_on_metadata_rejects_other_scripts() {
+        let mut data = amf_string("onCuePoint");
+        data.extend_from_slice(&amf_number(1.0));
+        assert!(parse_on_metadata(&data).is_none(), "非 onMetaData 应忽略");
+
+        let truncated = build_on_metadata();
+        assert!(
+            parse_on_metadata(&truncated[..truncated.len() / 2]).is_none(),
+            "截断数据不应解析成功"
+        );
+    }
+
+    #[test]
+    fn test_open_reads_metadata_duration_and_video_params() {
+        let mut flv = build_flv_header(true, true);
+        flv.extend_from_slice(&build_script_tag(&build_on_metadata()));
+        flv.extend_from_slice(&build_video_tag(0, true, &[0xDE, 0xAD]));
+        flv.extend_from_slice(&build_audio_tag(0, &[0xBE, 0xEF]));
+
+        let mut io = IoContext::from_bytes(flv);
+        let mut demuxer = FlvDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        assert_eq!(demuxer.duration(), Some(12.5), "应使用 onMetaData 时长");
+        assert_eq!(
+            demuxer.bit_rate(),
+            Some(928_000),
+            "容器码率应为音视频码率之和"
+        );
+        let video = demuxer
+            .streams()
+            .iter()
+            .find(|s| s.media_type == MediaType::Video)
+            .expect("应有视频流");
+        assert_eq!(video.duration, 12500, "流时长应为 12500 毫秒");
+        match &video.params {
+            StreamParams::Video(v) => {
+                assert_eq!((v.width, v.height), (640, 360), "分辨率应来自 onMetaData");
+                assert_eq!(
+                    v.frame_rate,
+                    Rational::new(30000, 1001),
+                    "帧率应识别为 NTSC"
+                );
+                assert_eq!(v.bit_rate, 800_000, "视频码率应来自 videodatarate");
+            }
+            _ => panic!("视频流参数类型错误"),
+        }
+        let audio = demuxer
+            .streams()
+            .iter()
+            .find(|s| s.media_type == MediaType::Audio)
+            .expect("应有音频流");
+        match &audio.params {
+            StreamParams::Audio(a) => {
+                assert_eq!(a.bit_rate, 128_000, "音频码率应来自 audiodatarate")
+            }
+            _ => panic!("音频流参数类型错误"),
+        }
+
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!(pkt.data.as_ref(), &[0xDE, 0xAD], "脚本 Tag 后应读到视频包");
+    }
 }
@@ -1,11 +1,18 @@
 //! H.264 AnnexB Elementary Stream 解封装器.
 //!
 //! 处理以 AnnexB start code (00 00 01 或 00 00 00 01) 分隔的
-//! H.264 NAL 单元裸流. 每个 packet 包含一个完整的访问单元
-//! (从一个 VCL NAL 到下一个 VCL NAL 开始之前的所有 NAL).
+//! H.264 NAL 单元裸流 (.h264/.264/.avc). 每个 packet 包含一个完整的访问单元,
+//! 按 H.264 7.4.1.2.3/7.4.1.2.4 划分, 已有 VCL NAL 后遇到以下情况时开始新的访问单元:
+//! - AUD/SPS/PPS/SEI (NAL 类型 6~9, 14~18)
+//! - `first_mb_in_slice == 0` 的 slice, 或 `frame_num`/IDR 属性与当前图像不同的 slice
+//!
+//! 参数集保留在数据包中 (extra_data 为空). 流的尺寸与帧率取自首个 SPS,
+//! SPS 未携带 VUI timing_info 时按 25fps 生成时间戳.
 
 use bytes::Bytes;
+use tao_codec::parsers::h264::{NalUnit, Sps, parse_sps};
 use tao_codec::{CodecId, Packet};
+use tao_core::bitreader::BitReader;
 use tao_core::{MediaType, PixelFormat, Rational, TaoError, TaoResult};
 
 use crate::demuxer::{Demuxer, SeekFlags};
@@ -14,6 +21,12 @@ use crate::io::IoContext;
 use crate::probe::{FormatProbe, ProbeScore, SCORE_EXTENSION, SCORE_MAX};
 use crate::stream::{Stream, StreamParams, VideoStreamParams};
 
+/// SPS 未携带帧率时的默认帧率
+const DEFAULT_FRAME_RATE: Rational = Rational::new(25, 1);
+
+/// 解析 slice 头部时最多读取的 NAL 字节数
+const SLICE_HEADER_PROBE_LEN: usize = 32;
+
 /// H.264 AnnexB ES 解封装器
 pub struct H264EsDemuxer {
     streams: Vec<Stream>,
@@ -22,6 +35,10 @@ pub struct H264EsDemuxer {
     /// 当前读取偏移
     offset: usize,
     frame_count: u64,
+    /// 最近一个 SPS 的 log2(MaxFrameNum), 用于解析 slice 头部的 frame_num
+    log2_max_frame_num: Option<u32>,
+    /// 时间基 (帧率的倒数)
+    time_base: Rational,
     eof: bool,
 }
 
@@ -32,9 +49,103 @@ impl H264EsDemuxer {
             data: Vec::new(),
             offset: 0,
             frame_count: 0,
+            log2_max_frame_num: None,
+            time_base: DEFAULT_FRAME_RATE.inverse(),
             eof: false,
         }))
     }
+
+    /// 查找第一个 SPS (在首个 VCL NAL 之前)
+    fn find_first_sps(&self) -> Option<Sps> {
+        let mut pos = find_start_code(&self.data, 0)?;
+        loop {
+            let nal = skip_start_code(&self.data, pos);
+            let next = find_start_code(&self.data, nal + 1);
+            let nal_end = next.unwrap_or(self.data.len());
+            match self.data.get(nal).map(|&b| nal_type(b)) {
+                Some(7) => {
+                    if let Some(sps) = parse_sps_nal(&self.data[nal..nal_end]) {
+                        return Some(sps);
+                    }
+                }
+                Some(nt) if is_vcl_nal(nt) => return None,
+                None => return None,
+                _ => {}
+            }
+            pos = next?;
+        }
+    }
+}
+
+/// 访问单元划分所需的 slice 头部字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SliceInfo {
+    first_mb_in_slice: u32,
+    frame_num: u32,
+    idr: bool,
+}
+
+/// 读取 Exp-Golomb 无符号数
+fn read_ue(br: &mut BitReader) -> TaoResult<u32> {
+    let mut leading_zeros = 0u32;
+    while br.read_bit()? == 0 {
+        leading_zeros += 1;
+        if leading_zeros > 31 {
+            return Err(TaoError::InvalidData("H264 ES: Exp-Golomb 码过长".into()));
+        }
+    }
+    if leading_zeros == 0 {
+        return Ok(0);
+    }
+    Ok((1u32 << leading_zeros) - 1 + br.read_bits(leading_zeros)?)
+}
+
+/// 解析 SPS NAL (含头部字节)
+fn parse_sps_nal(nal: &[u8]) -> Option<Sps> {
+    let unit = NalUnit::parse(nal).ok()?;
+    parse_sps(unit.rbsp()).ok()
+}
+
+/// 解析 slice NAL (类型 1/2/5) 头部的 first_mb_in_slice 与 frame_num
+///
+/// 未知 SPS 时 frame_num 记为 0, 仅按 first_mb_in_slice 划分.
+fn parse_slice_info(nal: &[u8], log2_max_frame_num: Option<u32>) -> Option<SliceInfo> {
+    let head = &nal[..nal.len().min(SLICE_HEADER_PROBE_LEN)];
+    let unit = NalUnit::parse(head).ok()?;
+    let mut br = BitReader::new(unit.rbsp());
+    let first_mb_in_slice = read_ue(&mut br).ok()?;
+    let _slice_type = read_ue(&mut br).ok()?;
+    let _pps_id = read_ue(&mut br).ok()?;
+    let frame_num = match log2_max_frame_num {
+        Some(bits) => br.read_bits(bits).ok()?,
+        None => 0,
+    };
+    Some(SliceInfo {
+        first_mb_in_slice,
+        frame_num,
+        idr: nal_type(nal[0]) == 5,
+    })
+}
+
+/// 已有 VCL NAL 的访问单元遇到该 NAL 时是否应结束
+fn starts_new_access_unit(nt: u8, slice: Option<SliceInfo>, current: SliceInfo) -> bool {
+    match nt {
+        6..=9 | 14..=18 => true,
+        1 | 2 | 5 => slice.is_some_and(|s| {
+            s.first_mb_in_slice == 0 || s.frame_num != current.frame_num || s.idr != current.idr
+        }),
+        _ => false,
+    }
+}
+
+/// SPS 色度格式对应的像素格式 (仅 8 位)
+fn sps_pixel_format(sps: &Sps) -> PixelFormat {
+    match (sps.chroma_format_idc, sps.bit_depth_luma) {
+        (0, 8) => PixelFormat::Gray8,
+        (2, 8) => PixelFormat::Yuv422p,
+        (3, 8) => PixelFormat::Yuv444p,
+        _ => PixelFormat::Yuv420p,
+    }
 }
 
 /// 在 data[start..] 中查找下一个 AnnexB start code (00 00 01 或 00 00 00 01).
@@ -123,23 +234,42 @@ impl Demuxer for H264EsDemuxer {
         self.data = buf;
         self.offset = 0;
 
+        let mut params = VideoStreamParams {
+            width: 0,
+            height: 0,
+            pixel_format: PixelFormat::Yuv420p,
+            frame_rate: DEFAULT_FRAME_RATE,
+            sample_aspect_ratio: Rational::new(1, 1),
+            bit_rate: 0,
+            color_space: Default::default(),
+            color_range: Default::default(),
+        };
+        if let Some(sps) = self.find_first_sps() {
+            params.width = sps.width;
+            params.height = sps.height;
+            params.pixel_format = sps_pixel_format(&sps);
+            if let Some(fps) = sps.fps.filter(|f| f.num > 0 && f.den > 0) {
+                params.frame_rate = fps.reduce();
+            }
+            if sps.sar.num > 0 && sps.sar.den > 0 {
+                params.sample_aspect_ratio = sps.sar;
+            }
+            params.color_space = sps.color_space;
+            params.color_range = sps.color_range;
+            self.log2_max_frame_num = Some(sps.log2_max_frame_num);
+        }
+        self.time_base = params.frame_rate.inverse();
+
         let stream = Stream {
             index: 0,
             media_type: MediaType::Video,
             codec_id: CodecId::H264,
-            time_base: Rational::new(1, 25),
+            time_base: self.time_base,
             duration: -1,
             start_time: 0,
             nb_frames: 0,
             extra_data: Vec::new(),
-            params: StreamParams::Video(VideoStreamParams {
-                width: 0,
-                height: 0,
-                pixel_format: PixelFormat::Yuv420p,
-                frame_rate: Rational::new(25, 1),
-                sample_aspect_ratio: Rational::new(1, 1),
-                bit_rate: 0,
-            }),
+            params: StreamParams::Video(params),
             metadata: Vec::new(),
         };
         self.streams.push(stream);
@@ -154,108 +284,77 @@ impl Demuxer for H264EsDemuxer {
         if self.eof || self.offset >= self.data.len() {
             return Err(TaoError::Eof);
         }
-
-        let sc_pos = match find_start_code(&self.data, self.offset) {
-            Some(p) => p,
-            None => {
-                self.eof = true;
-                return Err(TaoError::Eof);
-            }
-        };
-
-        let nal_start = skip_start_code(&self.data, sc_pos);
-        if nal_start >= self.data.len() {
+        let Some(au_start) = find_start_code(&self.data, self.offset) else {
             self.eof = true;
             return Err(TaoError::Eof);
-        }
-
-        let first_nal_type = nal_type(self.data[nal_start]);
-
-        // 收集当前访问单元: 从第一个 NAL 到下一个 VCL NAL 的开始之前
-        let au_start = sc_pos;
-        let mut search_pos = nal_start + 1;
-        let mut found_vcl = is_vcl_nal(first_nal_type);
+        };
 
-        loop {
-            let next_sc = match find_start_code(&self.data, search_pos) {
-                Some(p) => p,
-                None => {
-                    // 文件尾, 当前 AU 包含剩余所有数据
-                    self.offset = self.data.len();
-                    self.eof = true;
-                    break;
-                }
+        // 逐个 NAL 扫描, 直到遇到下一个访问单元的起点
+        let mut pos = au_start;
+        let mut current: Option<SliceInfo> = None;
+        let mut is_keyframe = false;
+        let au_end = loop {
+            let nal = skip_start_code(&self.data, pos);
+            if nal >= self.data.len() {
+                break self.data.len();
+            }
+            let nt = nal_type(self.data[nal]);
+            let next = find_start_code(&self.data, nal + 1);
+            let nal_end = next.unwrap_or(self.data.len());
+            let slice = if matches!(nt, 1 | 2 | 5) {
+                parse_slice_info(&self.data[nal..nal_end], self.log2_max_frame_num)
+            } else {
+                None
             };
 
-            let next_nal_start = skip_start_code(&self.data, next_sc);
-            if next_nal_start >= self.data.len() {
-                self.offset = self.data.len();
-                self.eof = true;
-                break;
+            if let Some(cur) = current {
+                if starts_new_access_unit(nt, slice, cur) {
+                    break pos;
+                }
             }
-
-            let next_nt = nal_type(self.data[next_nal_start]);
-
-            if is_vcl_nal(next_nt) {
-                if found_vcl {
-                    // 新的 VCL NAL 且已有 VCL -> 新的 AU 开始
-                    self.offset = next_sc;
-                    break;
+            if nt == 7 {
+                if let Some(sps) = parse_sps_nal(&self.data[nal..nal_end]) {
+                    self.log2_max_frame_num = Some(sps.log2_max_frame_num);
+                }
+            }
+            if is_vcl_nal(nt) {
+                is_keyframe |= nt == 5;
+                if current.is_none() {
+                    current = Some(slice.unwrap_or(SliceInfo {
+                        first_mb_in_slice: 0,
+                        frame_num: 0,
+                        idr: nt == 5,
+                    }));
                 }
-                found_vcl = true;
             }
 
-            // AUD (NAL type 9) 标志新的 AU
-            if next_nt == 9 && found_vcl {
-                self.offset = next_sc;
-                break;
+            match next {
+                Some(p) => pos = p,
+                None => break self.data.len(),
             }
+        };
 
-            search_pos = next_nal_start + 1;
+        self.offset = au_end;
+        if au_end >= self.data.len() {
+            self.eof = true;
         }
-
-        let au_end = if self.eof {
-            self.data.len()
-        } else {
-            self.offset
-        };
         let packet_data = &self.data[au_start..au_end];
-
         if packet_data.is_empty() {
             self.eof = true;
             return Err(TaoError::Eof);
         }
 
-        let is_keyframe = {
-            let mut s = au_start;
-            let mut kf = false;
-            while let Some(p) = find_start_code(&self.data[..au_end], s) {
-                let ns = skip_start_code(&self.data, p);
-                if ns < au_end {
-                    let nt = nal_type(self.data[ns]);
-                    if nt == 5 {
-                        kf = true;
-                        break;
-                    }
-                }
-                s = ns.max(s + 1);
-            }
-            kf
-        };
-
         let pts = self.frame_count as i64;
         self.frame_count += 1;
 
-        Ok(Packet {
-            stream_index: 0,
-            data: Bytes::copy_from_slice(packet_data),
-            pts,
-            dts: pts,
-            is_keyframe,
-            duration: 1,
-            time_base: Rational::new(1, 25),
-            pos: au_start as i64,
-        })
+        let mut packet = Packet::from_data(Bytes::copy_from_slice(packet_data));
+        packet.pts = pts;
+        packet.dts = pts;
+        packet.set_keyframe(is_keyframe);
+        packet.duration = 1;
+        packet.time_base = self.time_base;
+        packet.pos = au_start as i64;
+        Ok(packet)
     }
 
     fn seek(
@@ -278,9 +377,17 @@ pub struct H264EsProbe;
 
 impl FormatProbe for H264EsProbe {
     fn probe(&self, data: &[u8], filename: Option<&str>) -> Option<ProbeScore> {
+        // Annex B 字节流必须以起始码开头 (允许前导零字节), 否则其他格式负载中
+        // 偶然出现的 00 00 01 会被误判, 此时仅按扩展名判断
+        let leading_zeros = data.iter().take_while(|&&b| b == 0).count();
+        let starts_with_code = leading_zeros >= 2 && data.get(leading_zeros) == Some(&1);
         let mut valid_nal_count = 0u32;
         let mut pos = 0usize;
-        let limit = data.len().min(4096);
+        let limit = if starts_with_code {
+            data.len().min(4096)
+        } else {
+            0
+        };
         while pos + 3 < limit {
             let (sc_end, found) = if pos + 3 < data.len()
                 && data[pos] == 0
@@ -320,7 +427,7 @@ impl FormatProbe for H264EsProbe {
         if let Some(name) = filename {
             if let Some(ext) = name.rsplit('.').next() {
                 let ext_lower = ext.to_lowercase();
-                if ext_lower == "h264" || ext_lower == "264" {
+                if matches!(ext_lower.as_str(), "h264" | "264" | "avc") {
                     return Some(SCORE_EXTENSION);
                 }
             }
@@ -348,8 +455,153 @@ mod tests {
         // 扩展名
         assert!(probe.probe(&[], Some("test.h264")).is_some());
         assert!(probe.probe(&[], Some("test.264")).is_some());
+        assert!(probe.probe(&[], Some("test.avc")).is_some());
         // 无效
         assert!(probe.probe(&[0xFF, 0xD8, 0xFF, 0xE0], None).is_none());
+        // 非起始码开头 (如 ADTS 负载中偶然出现的起始码) 不应被识别
+        assert!(
+            probe
+                .probe(
+                    &[
+                        0xFF, 0xF1, 0x50, 0x00, 0x00, 0x01, 0x65, 0x00, 0x00, 0x01, 0x41
+                    ],
+                    None
+                )
+                .is_none(),
+            "负载中间的起始码不应判定为 H.264 裸流"
+        );
+    }
+
+    /// 按位写入 RBSP 的测试辅助
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        bit_len: usize,
+    }
+
+    impl BitWriter {
+        fn bits(&mut self, value: u32, n: u32) -> &mut Self {
+            for i in (0..n).rev() {
+                if self.bit_len % 8 == 0 {
+                    self.bytes.push(0);
+                }
+                if (value >> i) & 1 == 1 {
+                    *self.bytes.last_mut().unwrap() |= 0x80 >> (self.bit_len % 8);
+                }
+                self.bit_len += 1;
+            }
+            self
+        }
+
+        fn ue(&mut self, value: u32) -> &mut Self {
+            let code = value + 1;
+            let len = 32 - code.leading_zeros();
+            self.bits(0, len - 1).bits(code, len)
+        }
+
+        /// 加上 rbsp_trailing_bits 与防竞争字节, 输出带起始码的 NAL
+        fn nal(&mut self, header: u8) -> Vec<u8> {
+            self.bits(1, 1);
+            let mut out = vec![0, 0, 0, 1, header];
+            let mut zeros = 0;
+            for &b in &self.bytes {
+                if zeros >= 2 && b <= 3 {
+                    out.push(3);
+                    zeros = 0;
+                }
+                out.push(b);
+                zeros = if b == 0 { zeros + 1 } else { 0 };
+            }
+            out
+        }
+    }
+
+    /// 64x48 Baseline SPS, log2_max_frame_num=4, VUI timing_info 为 60000/(2*1001)
+    fn sps_nal() -> Vec<u8> {
+        let mut w = BitWriter::default();
+        w.bits(66, 8).bits(0, 8).bits(30, 8); // profile/constraint/level
+        w.ue(0).ue(0).ue(2).ue(1).bits(0, 1); // sps_id, log2_max_frame_num-4, poc_type, ref, gaps
+        w.ue(3).ue(2).bits(1, 1).bits(1, 1).bits(0, 1); // 4x3 MB, frame_mbs_only, direct8x8, crop
+        w.bits(1, 1); // vui_parameters_present_flag
+        w.bits(0, 1).bits(0, 1).bits(0, 1).bits(0, 1); // aspect/overscan/signal/chroma_loc
+        w.bits(1, 1).bits(1001, 32).bits(60000, 32).bits(1, 1); // timing_info
+        w.bits(0, 1).bits(0, 1).bits(0, 1).bits(0, 1); // nal/vcl hrd, pic_struct, restriction
+        w.nal(0x67)
+    }
+
+    fn slice_nal(idr: bool, first_mb: u32, frame_num: u32) -> Vec<u8> {
+        let mut w = BitWriter::default();
+        w.ue(first_mb)
+            .ue(if idr { 7 } else { 5 })
+            .ue(0)
+            .bits(frame_num, 4);
+        w.bits(0xA5A5, 16);
+        w.nal(if idr { 0x65 } else { 0x41 })
+    }
+
+    fn open_demuxer(data: Vec<u8>) -> (Box<dyn Demuxer>, IoContext) {
+        let mut io = IoContext::new(Box::new(crate::io::MemoryBackend::from_data(data)));
+        let mut demuxer = H264EsDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        (demuxer, io)
+    }
+
+    #[test]
+    fn test_h264es_access_unit_assembly() {
+        let pps = vec![0, 0, 0, 1, 0x68, 0xCE, 0x38, 0x80];
+        let sei = vec![0, 0, 1, 0x06, 0x05, 0x01, 0x00, 0x80];
+        let aud = vec![0, 0, 0, 1, 0x09, 0xF0];
+        let access_units: Vec<Vec<u8>> = vec![
+            // 参数集 + 两个 slice 组成的 IDR 图像
+            [sps_nal(), pps, slice_nal(true, 0, 0), slice_nal(true, 6, 0)].concat(),
+            [slice_nal(false, 0, 1), slice_nal(false, 6, 1)].concat(),
+            // VCL 之后的 SEI 开始新的访问单元
+            [sei, slice_nal(false, 0, 2)].concat(),
+            [aud, slice_nal(false, 0, 3)].concat(),
+            // first_mb_in_slice 非 0, 但 frame_num 变化
+            slice_nal(false, 3, 4),
+        ];
+        let stream: Vec<u8> = access_units.concat();
+        let (mut demuxer, mut io) = open_demuxer(stream.clone());
+
+        let mut packets = Vec::new();
+        loop {
+            match demuxer.read_packet(&mut io) {
+                Ok(pkt) => packets.push(pkt),
+                Err(TaoError::Eof) => break,
+                Err(e) => panic!("读取数据包失败: {e}"),
+            }
+        }
+        assert_eq!(packets.len(), access_units.len(), "访问单元数量不符");
+        for (i, (pkt, au)) in packets.iter().zip(&access_units).enumerate() {
+            assert_eq!(&pkt.data[..], &au[..], "第 {i} 个访问单元内容不符");
+            assert_eq!(pkt.pts, i as i64);
+            assert_eq!(pkt.is_keyframe(), i == 0, "只有 IDR 图像为关键帧");
+        }
+        let joined: Vec<u8> = packets.iter().flat_map(|p| p.data.to_vec()).collect();
+        assert_eq!(joined, stream, "数据包拼接后应与原始字节流一致");
+    }
+
+    #[test]
+    fn test_h264es_stream_params_from_sps() {
+        let data = [sps_nal(), slice_nal(true, 0, 0)].concat();
+        let (demuxer, _io) = open_demuxer(data);
+        let stream = &demuxer.streams()[0];
+        let StreamParams::Video(params) = &stream.params else {
+            panic!("应为视频流");
+        };
+        assert_eq!((params.width, params.height), (64, 48), "尺寸应来自 SPS");
+        assert_eq!(
+            params.frame_rate,
+            Rational::new(30000, 1001),
+            "帧率应来自 VUI"
+        );
+        assert_eq!(stream.time_base, Rational::new(1001, 30000));
+        assert!(stream.extra_data.is_empty(), "参数集保留在数据包中");
+
+        // 无 SPS 时使用默认 25fps
+        let (demuxer, _io) = open_demuxer(slice_nal(true, 0, 0));
+        assert_eq!(demuxer.streams()[0].time_base, Rational::new(1, 25));
     }
 
     #[test]
@@ -88,7 +88,7 @@ impl M4vDemuxer {
         for b in &mut window {
             match io.read_u8() {
                 Ok(val) => *b = val,
-                Err(e) if e.to_string().contains("unexpected end of file") => return Ok(None),
+                Err(TaoError::Eof) => return Ok(None),
                 Err(e) => return Err(e),
             }
         }
@@ -106,7 +106,7 @@ impl M4vDemuxer {
                         window[1] = window[2];
                         window[2] = byte;
                     }
-                    Err(e) if e.to_string().contains("unexpected end of file") => return Ok(None),
+                    Err(TaoError::Eof) => return Ok(None),
                     Err(e) => return Err(e),
                 }
             } else {
@@ -115,7 +115,7 @@ impl M4vDemuxer {
                 window[1] = window[2];
                 match io.read_u8() {
                     Ok(byte) => window[2] = byte,
-                    Err(e) if e.to_string().contains("unexpected end of file") => return Ok(None),
+                    Err(TaoError::Eof) => return Ok(None),
                     Err(e) => return Err(e),
                 }
             }
@@ -134,7 +134,7 @@ impl M4vDemuxer {
                     data.push(val);
                     *b = val;
                 }
-                Err(e) if e.to_string().contains("unexpected end of file") => {
+                Err(TaoError::Eof) => {
                     return Ok(data);
                 }
                 Err(e) => return Err(e),
@@ -156,7 +156,7 @@ impl M4vDemuxer {
                     data.push(byte);
                     window[2] = byte;
                 }
-                Err(e) if e.to_string().contains("unexpected end of file") => {
+                Err(TaoError::Eof) => {
                     return Ok(data);
                 }
                 Err(e) => return Err(e),
@@ -254,6 +254,8 @@ impl Demuxer for M4vDemuxer {
                 frame_rate: Rational::new(25, 1),
                 sample_aspect_ratio: Rational::new(1, 1),
                 bit_rate: 0,
+                color_space: Default::default(),
+                color_range: Default::default(),
             }),
             metadata: Vec::new(),
         };
@@ -283,6 +285,7 @@ impl Demuxer for M4vDemuxer {
         }
 
         let mut found_vop = false;
+        let mut is_keyframe = false;
 
         loop {
             let result = self.find_next_start_code(io)?;
@@ -309,7 +312,10 @@ impl Demuxer for M4vDemuxer {
                     // 包含 start code
                     packet_data.extend_from_slice(&[0x00, 0x00, 0x01, VOP_START]);
                     // 读取到下一个 start code 的数据
-                    packet_data.extend(self.read_until_next_start_code(io)?);
+                    let vop = self.read_until_next_start_code(io)?;
+                    // vop_coding_type 为首字节高 2 位, 0 表示 I-VOP
+                    is_keyframe = vop.first().is_some_and(|b| b >> 6 == 0);
+                    packet_data.extend(vop);
                 }
                 VISUAL_OBJECT_SEQ_END => {
                     self.eof = true;
@@ -340,16 +346,12 @@ impl Demuxer for M4vDemuxer {
         let dts = self.frame_count as i64;
         self.frame_count += 1;
 
-        let packet = Packet {
-            stream_index: 0,
-            data: Bytes::from(packet_data),
-            pts,
-            dts,
-            is_keyframe: true, // 简化处理, 每个数据包都标记为关键帧
-            duration: 1,
-            time_base: self.timebase,
-            pos: -1,
-        };
+        let mut packet = Packet::from_data(Bytes::from(packet_data));
+        packet.pts = pts;
+        packet.dts = dts;
+        packet.set_keyframe(is_keyframe);
+        packet.duration = 1;
+        packet.time_base = self.timebase;
 
         Ok(packet)
     }
@@ -432,4 +434,27 @@ mod tests {
         assert_eq!(probe.probe(&[0x00, 0x00, 0x01, 0xC0], None), None);
         assert_eq!(probe.probe(&[0xFF, 0xD8, 0xFF, 0xE0], None), None);
     }
+
+    #[test]
+    fn test_m4v_keyframe_from_vop_coding_type() {
+        // VOS + VOL 头部, 之后为 I/P/B/I 四个 VOP (vop_coding_type 为首字节高 2 位)
+        let mut data = vec![0x00, 0x00, 0x01, VISUAL_OBJECT_SEQ_START, 0x01];
+        data.extend_from_slice(&[0x00, 0x00, 0x01, 0x20, 0x08, 0x88]);
+        for first in [0x10, 0x50, 0x90, 0x10] {
+            data.extend_from_slice(&[0x00, 0x00, 0x01, VOP_START, first, 0xAA]);
+        }
+        let mut io = IoContext::from_bytes(data);
+        let mut demuxer = M4vDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        let mut flags = Vec::new();
+        loop {
+            match demuxer.read_packet(&mut io) {
+                Ok(pkt) => flags.push(pkt.is_keyframe()),
+                Err(TaoError::Eof) => break,
+                Err(e) => panic!("读取数据包失败: {e}"),
+            }
+        }
+        assert_eq!(flags, [true, false, false, true], "仅 I-VOP 应标记为关键帧");
+    }
 }
@@ -175,6 +175,9 @@ pub const VIDEO_PIXEL_WIDTH: u32 = 0xB0;
 pub const VIDEO_PIXEL_HEIGHT: u32 = 0xBA;
 pub const VIDEO_DISPLAY_WIDTH: u32 = 0x54B0;
 pub const VIDEO_DISPLAY_HEIGHT: u32 = 0x54BA;
+pub const VIDEO_COLOUR: u32 = 0x55B0;
+pub const COLOUR_MATRIX_COEFFICIENTS: u32 = 0x55B1;
+pub const COLOUR_RANGE: u32 = 0x55B9;
 
 // Audio settings
 pub const AUDIO_SETTINGS: u32 = 0xE1;
@@ -189,25 +192,37 @@ pub const SIMPLE_BLOCK: u32 = 0xA3;
 pub const BLOCK_GROUP: u32 = 0xA0;
 pub const BLOCK: u32 = 0xA1;
 
-// Cues (索引, 暂不解析)
+// Cues (时间索引)
 pub const CUES: u32 = 0x1C53_BB6B;
+pub const CUE_POINT: u32 = 0xBB;
+pub const CUE_TIME: u32 = 0xB3;
+pub const CUE_TRACK_POSITIONS: u32 = 0xB7;
+pub const CUE_TRACK: u32 = 0xF7;
+pub const CUE_CLUSTER_POSITION: u32 = 0xF1;
 
 // SeekHead
 pub const SEEK_HEAD: u32 = 0x114D_9B74;
+pub const SEEK: u32 = 0x4DBB;
+pub const SEEK_ID: u32 = 0x53AB;
+pub const SEEK_POSITION: u32 = 0x53AC;
 
 // Tags
 pub const TAGS: u32 = 0x1254_C367;
+pub const TAG: u32 = 0x7373;
+pub const TAG_TARGETS: u32 = 0x63C0;
+pub const TAG_TRACK_UID: u32 = 0x63C5;
+pub const SIMPLE_TAG: u32 = 0x67C8;
+pub const TAG_NAME: u32 = 0x45A3;
+pub const TAG_STRING: u32 = 0x4487;
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     #[test]
     fn test_read_1_byte_vint_id() {
         // 0x81 = 1000_0001 → ID=0x81 (1 byte)
-        let backend = MemoryBackend::from_data(vec![0x81]);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(vec![0x81]);
         let (id, len) = read_element_id(&mut io).unwrap();
         assert_eq!(id, 0x81);
         assert_eq!(len, 1);
@@ -216,8 +231,7 @@ mod tests {
     #[test]
     fn test_read_2_byte_vint_id() {
         // 0x42, 0x86 → ID=0x4286 (2 bytes)
-        let backend = MemoryBackend::from_data(vec![0x42, 0x86]);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(vec![0x42, 0x86]);
         let (id, len) = read_element_id(&mut io).unwrap();
         assert_eq!(id, 0x4286);
         assert_eq!(len, 2);
@@ -226,8 +240,7 @@ mod tests {
     #[test]
     fn test_read_4_byte_vint_id() {
         // EBML Header ID: 0x1A45DFA3
-        let backend = MemoryBackend::from_data(vec![0x1A, 0x45, 0xDF, 0xA3]);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(vec![0x1A, 0x45, 0xDF, 0xA3]);
         let (id, len) = read_element_id(&mut io).unwrap();
         assert_eq!(id, EBML_HEADER);
         assert_eq!(len, 4);
@@ -236,8 +249,7 @@ mod tests {
     #[test]
     fn test_read_vint_size() {
         // 0x85 → size = 0x05 (1 byte, 掩掉标记位 0x80)
-        let backend = MemoryBackend::from_data(vec![0x85]);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(vec![0x85]);
         let (size, len) = read_element_size(&mut io).unwrap();
         assert_eq!(size, 5);
         assert_eq!(len, 1);
@@ -246,8 +258,7 @@ mod tests {
     #[test]
     fn test_read_2_byte_vint_size() {
         // 0x40, 0x20 → size = 0x0020 = 32 (2 bytes)
-        let backend = MemoryBackend::from_data(vec![0x40, 0x20]);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(vec![0x40, 0x20]);
         let (size, len) = read_element_size(&mut io).unwrap();
         assert_eq!(size, 32);
         assert_eq!(len, 2);
@@ -256,8 +267,7 @@ mod tests {
     #[test]
     fn test_unknown_size() {
         // 0xFF → 所有 7 位数据位为 1 → 未知大小
-        let backend = MemoryBackend::from_data(vec![0xFF]);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(vec![0xFF]);
         let (size, _) = read_element_size(&mut io).unwrap();
         assert_eq!(size, EBML_UNKNOWN_SIZE);
     }
@@ -265,8 +275,7 @@ mod tests {
     #[test]
     fn test_read_uint() {
         // 2 字节 uint = 0x0100 = 256
-        let backend = MemoryBackend::from_data(vec![0x01, 0x00]);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(vec![0x01, 0x00]);
         assert_eq!(read_uint(&mut io, 2).unwrap(), 256);
     }
 
@@ -275,8 +284,7 @@ mod tests {
         // f32: 1.0 = 0x3F800000
         let bits = 1.0f32.to_bits();
         let data = bits.to_be_bytes().to_vec();
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let val = read_float(&mut io, 4).unwrap();
         assert!((val - 1.0).abs() < f64::EPSILON);
     }
@@ -284,8 +292,7 @@ mod tests {
     #[test]
     fn test_read_string() {
         let data = b"hello\x00\x00".to_vec();
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let s = read_string(&mut io, 7).unwrap();
         assert_eq!(s, "hello");
     }
@@ -23,7 +23,8 @@ use bytes::Bytes;
 use log::debug;
 use std::collections::VecDeque;
 use tao_codec::{CodecId, Packet};
-use tao_core::{ChannelLayout, MediaType, Rational, SampleFormat, TaoError, TaoResult};
+use tao_core::color::{ColorRange, ColorSpace};
+use tao_core::{ChannelLayout, MediaType, Rational, SampleFormat, SmpteTc, TaoError, TaoResult};
 
 use crate::demuxer::{Demuxer, SeekFlags};
 use crate::format_id::FormatId;
@@ -36,6 +37,7 @@ use self::ebml::*;
 /// Matroska 轨道信息 (解析 Tracks 时暂存)
 struct TrackInfo {
     track_number: u64,
+    track_uid: u64,
     track_type: u64,
     codec_id_str: String,
     codec_private: Vec<u8>,
@@ -43,6 +45,8 @@ struct TrackInfo {
     // 视频
     pixel_width: u32,
     pixel_height: u32,
+    color_space: ColorSpace,
+    color_range: ColorRange,
     // 音频
     sample_rate: f64,
     channels: u32,
@@ -53,12 +57,15 @@ impl TrackInfo {
     fn new() -> Self {
         Self {
             track_number: 0,
+            track_uid: 0,
             track_type: 0,
             codec_id_str: String::new(),
             codec_private: Vec::new(),
             default_duration: 0,
             pixel_width: 0,
             pixel_height: 0,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
             sample_rate: 0.0,
             channels: 0,
             bit_depth: 0,
@@ -66,12 +73,28 @@ impl TrackInfo {
     }
 }
 
+/// Cues 索引项: 某轨道在指定时间的关键帧所在 Cluster
+struct CueEntry {
+    /// 轨道号
+    track_number: u64,
+    /// 时间 (毫秒)
+    time: i64,
+    /// Cluster 绝对偏移
+    cluster_pos: u64,
+}
+
 /// Matroska 解封装器
 pub struct MkvDemuxer {
     /// 流信息
     streams: Vec<Stream>,
     /// 轨道号 → 流索引的映射
     track_map: Vec<(u64, usize)>,
+    /// TrackUID → 流索引的映射 (用于 Tags 目标匹配)
+    track_uid_map: Vec<(u64, usize)>,
+    /// 各流的 DefaultDuration (纳秒, 0 表示未指定), 按流索引
+    default_durations: Vec<u64>,
+    /// 容器级元数据
+    metadata: Vec<(String, String)>,
     /// 时间刻度 (纳秒/tick, 默认 1_000_000 即 1ms)
     timescale_ns: u64,
     /// 时长 (纳秒)
@@ -90,6 +113,10 @@ pub struct MkvDemuxer {
     is_webm: bool,
     /// 由 lacing 拆分后待返回的后续数据包
     pending_packets: VecDeque<Packet>,
+    /// Cues 时间索引 (按时间升序)
+    cues: Vec<CueEntry>,
+    /// 第一个 Cluster 的绝对偏移
+    first_cluster_pos: Option<u64>,
 }
 
 impl MkvDemuxer {
@@ -98,6 +125,9 @@ impl MkvDemuxer {
         Ok(Box::new(Self {
             streams: Vec::new(),
             track_map: Vec::new(),
+            track_uid_map: Vec::new(),
+            default_durations: Vec::new(),
+            metadata: Vec::new(),
             timescale_ns: 1_000_000,
             duration_ns: None,
             segment_offset: 0,
@@ -107,6 +137,8 @@ impl MkvDemuxer {
             in_cluster: false,
             is_webm: false,
             pending_packets: VecDeque::new(),
+            cues: Vec::new(),
+            first_cluster_pos: None,
         }))
     }
 
@@ -186,9 +218,7 @@ impl MkvDemuxer {
             let (eid, esize, _) = read_element_header(io)?;
             match eid {
                 TRACK_NUMBER => track.track_number = read_uint(io, esize)?,
-                TRACK_UID => {
-                    let _uid = read_uint(io, esize)?;
-                }
+                TRACK_UID => track.track_uid = read_uint(io, esize)?,
                 TRACK_TYPE => track.track_type = read_uint(io, esize)?,
                 TRACK_CODEC_ID => {
                     track.codec_id_str = read_string(io, esize)?;
@@ -233,6 +263,34 @@ impl MkvDemuxer {
                 VIDEO_DISPLAY_WIDTH | VIDEO_DISPLAY_HEIGHT => {
                     let _v = read_uint(io, esize)?;
                 }
+                VIDEO_COLOUR => {
+                    self.parse_colour(io, esize, track)?;
+                }
+                _ => {
+                    io.skip(esize as usize)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 解析 Colour 元素 (色彩空间与色彩范围)
+    fn parse_colour(&self, io: &mut IoContext, size: u64, track: &mut TrackInfo) -> TaoResult<()> {
+        let end = io.position()? + size;
+        while io.position()? < end {
+            let (eid, esize, _) = read_element_header(io)?;
+            match eid {
+                COLOUR_MATRIX_COEFFICIENTS => {
+                    track.color_space = ColorSpace::from_iso_code(read_uint(io, esize)?);
+                }
+                COLOUR_RANGE => {
+                    // 0: 未指定, 1: 广播范围, 2: 完整范围, 3: 由矩阵/传输特性决定
+                    track.color_range = match read_uint(io, esize)? {
+                        1 => ColorRange::Limited,
+                        2 => ColorRange::Full,
+                        _ => ColorRange::Unspecified,
+                    };
+                }
                 _ => {
                     io.skip(esize as usize)?;
                 }
@@ -293,6 +351,8 @@ impl MkvDemuxer {
                         frame_rate,
                         sample_aspect_ratio: Rational::new(1, 1),
                         bit_rate: 0,
+                        color_space: track.color_space,
+                        color_range: track.color_range,
                     }),
                 )
             }
@@ -317,6 +377,7 @@ impl MkvDemuxer {
                         sample_format: SampleFormat::F32,
                         bit_rate: 0,
                         frame_size: 0,
+                        block_align: 0,
                     }),
                 )
             }
@@ -324,7 +385,15 @@ impl MkvDemuxer {
                 // 字幕
                 (MediaType::Subtitle, StreamParams::Subtitle)
             }
-            _ => (MediaType::Data, StreamParams::Other),
+            // control / metadata
+            0x20 | 0x21 => (MediaType::Data, StreamParams::Other),
+            other => {
+                debug!("MKV: 未知轨道类型 {other}, 标记为未知流");
+                (
+                    MediaType::Unknown(u8::try_from(other).unwrap_or(0)),
+                    StreamParams::Other,
+                )
+            }
         };
 
         // 时间基: 1ns * timescale_ns → 以 timescale_ns 纳秒为单位
@@ -350,9 +419,269 @@ impl MkvDemuxer {
         );
 
         self.track_map.push((track.track_number, stream_index));
+        self.default_durations.push(track.default_duration);
+        if track.track_uid != 0 {
+            self.track_uid_map.push((track.track_uid, stream_index));
+        }
         self.streams.push(stream);
     }
 
+    /// 解析 SeekHead, 返回 (Cues, Tags) 相对 Segment 数据区的偏移
+    fn parse_seek_head(io: &mut IoContext, size: u64) -> TaoResult<(Option<u64>, Option<u64>)> {
+        let end = io.position()? + size;
+        let mut cues_offset = None;
+        let mut tags_offset = None;
+        while io.position()? < end {
+            let (eid, esize, _) = read_element_header(io)?;
+            if eid != SEEK {
+                io.skip(esize as usize)?;
+                continue;
+            }
+            let seek_end = io.position()? + esize;
+            let mut seek_id = Vec::new();
+            let mut seek_pos = None;
+            while io.position()? < seek_end {
+                let (sid, ssize, _) = read_element_header(io)?;
+                match sid {
+                    SEEK_ID => seek_id = read_binary(io, ssize)?,
+                    SEEK_POSITION => seek_pos = Some(read_uint(io, ssize)?),
+                    _ => io.skip(ssize as usize)?,
+                }
+            }
+            if seek_id == CUES.to_be_bytes() {
+                cues_offset = seek_pos;
+            } else if seek_id == TAGS.to_be_bytes() {
+                tags_offset = seek_pos;
+            }
+        }
+        Ok((cues_offset, tags_offset))
+    }
+
+    /// 解析 Cues 元素, 建立时间索引
+    fn parse_cues(&mut self, io: &mut IoContext, size: u64) -> TaoResult<()> {
+        let end = io.position()? + size;
+        while io.position()? < end {
+            let (eid, esize, _) = read_element_header(io)?;
+            if eid != CUE_POINT {
+                io.skip(esize as usize)?;
+                continue;
+            }
+            let point_end = io.position()? + esize;
+            let mut time = 0i64;
+            let mut positions = Vec::new();
+            while io.position()? < point_end {
+                let (pid, psize, _) = read_element_header(io)?;
+                match pid {
+                    CUE_TIME => time = read_uint(io, psize)? as i64,
+                    CUE_TRACK_POSITIONS => {
+                        let pos_end = io.position()? + psize;
+                        let mut track_number = 0;
+                        let mut cluster_pos = None;
+                        while io.position()? < pos_end {
+                            let (tid, tsize, _) = read_element_header(io)?;
+                            match tid {
+                                CUE_TRACK => track_number = read_uint(io, tsize)?,
+                                CUE_CLUSTER_POSITION => cluster_pos = Some(read_uint(io, tsize)?),
+                                _ => io.skip(tsize as usize)?,
+                            }
+                        }
+                        if let Some(pos) = cluster_pos {
+                            positions.push((track_number, pos));
+                        }
+                    }
+                    _ => io.skip(psize as usize)?,
+                }
+            }
+            let time_ms = time * self.timescale_ns as i64 / 1_000_000;
+            for (track_number, pos) in positions {
+                self.cues.push(CueEntry {
+                    track_number,
+                    time: time_ms,
+                    cluster_pos: self.segment_offset + pos,
+                });
+            }
+        }
+        self.cues.sort_by_key(|c| c.time);
+        debug!("MKV: Cues 索引 {} 项", self.cues.len());
+        Ok(())
+    }
+
+    /// 按 SeekHead 指向的偏移读取 Cues, 读取后恢复原位置
+    ///
+    /// Cues 通常位于所有 Cluster 之后, 仅在可 seek 的输入上读取. 失败不影响打开.
+    fn load_cues_at(&mut self, io: &mut IoContext, offset: u64) -> TaoResult<()> {
+        if !io.is_seekable() {
+            return Ok(());
+        }
+        let saved = io.position()?;
+        io.seek(std::io::SeekFrom::Start(self.segment_offset + offset))?;
+        let result = match read_element_header(io) {
+            Ok((CUES, size, _)) if size != EBML_UNKNOWN_SIZE => self.parse_cues(io, size),
+            Ok((eid, _, _)) => {
+                debug!("MKV: SeekHead 指向的元素 0x{eid:X} 不是 Cues");
+                Ok(())
+            }
+            Err(e) => Err(e),
+        };
+        io.seek(std::io::SeekFrom::Start(saved))?;
+        if let Err(e) = result {
+            debug!("MKV: 读取 Cues 失败, 忽略索引: {e}");
+            self.cues.clear();
+        }
+        Ok(())
+    }
+
+    /// 通过 SeekHead 偏移读取位于 Cluster 之后的 Tags, 失败时忽略
+    fn load_tags_at(&mut self, io: &mut IoContext, offset: u64) -> TaoResult<()> {
+        if !io.is_seekable() {
+            return Ok(());
+        }
+        let saved = io.position()?;
+        io.seek(std::io::SeekFrom::Start(self.segment_offset + offset))?;
+        let result = match read_element_header(io) {
+            Ok((TAGS, size, _)) if size != EBML_UNKNOWN_SIZE => self.parse_tags(io, size),
+            Ok((eid, _, _)) => {
+                debug!("MKV: SeekHead 指向的元素 0x{eid:X} 不是 Tags");
+                Ok(())
+            }
+            Err(e) => Err(e),
+        };
+        io.seek(std::io::SeekFrom::Start(saved))?;
+        if let Err(e) = result {
+            debug!("MKV: 读取 Tags 失败, 忽略: {e}");
+        }
+        Ok(())
+    }
+
+    /// 单个帧的时长 (纳秒): 优先使用 DefaultDuration, Opus 由 TOC 推算
+    fn frame_duration_ns(&self, stream_index: usize, payload: &[u8]) -> u64 {
+        match self.default_durations.get(stream_index) {
+            Some(&d) if d > 0 => d,
+            _ if self.streams[stream_index].codec_id == CodecId::Opus => {
+                opus_packet_duration_ns(payload).unwrap_or(0)
+            }
+            _ => 0,
+        }
+    }
+
+    /// 解析 Tags 元素
+    ///
+    /// 目前提取 `TIMECODE` 标签: 指定 TagTrackUID 时写入对应流的元数据,
+    /// 否则写入容器级元数据. 值统一规范化为 SMPTE 时间码字符串.
+    /// 轨道级的 mkvmerge 统计标签 `NUMBER_OF_FRAMES`/`BPS` 填充流的帧数与码率.
+    fn parse_tags(&mut self, io: &mut IoContext, size: u64) -> TaoResult<()> {
+        let end = io.position()? + size;
+        while io.position()? < end {
+            let (eid, esize, _) = read_element_header(io)?;
+            if eid == TAG {
+                self.parse_tag(io, esize)?;
+            } else {
+                io.skip(esize as usize)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 解析单个 Tag (Targets + SimpleTag 列表)
+    fn parse_tag(&mut self, io: &mut IoContext, size: u64) -> TaoResult<()> {
+        let end = io.position()? + size;
+        let mut target_uids = Vec::new();
+        let mut simple_tags = Vec::new();
+        while io.position()? < end {
+            let (eid, esize, _) = read_element_header(io)?;
+            match eid {
+                TAG_TARGETS => {
+                    let targets_end = io.position()? + esize;
+                    while io.position()? < targets_end {
+                        let (tid, tsize, _) = read_element_header(io)?;
+                        if tid == TAG_TRACK_UID {
+                            target_uids.push(read_uint(io, tsize)?);
+                        } else {
+                            io.skip(tsize as usize)?;
+                        }
+                    }
+                }
+                SIMPLE_TAG => {
+                    simple_tags.push(Self::parse_simple_tag(io, esize)?);
+                }
+                _ => {
+                    io.skip(esize as usize)?;
+                }
+            }
+        }
+
+        for (name, value) in simple_tags {
+            if name.eq_ignore_ascii_case("NUMBER_OF_FRAMES") || name.eq_ignore_ascii_case("BPS") {
+                let Ok(number) = value.trim().parse::<u64>() else {
+                    debug!("MKV: 忽略无效统计标签 {name}={value}");
+                    continue;
+                };
+                for uid in &target_uids {
+                    if let Some(idx) = self.find_stream_index_by_uid(*uid) {
+                        let stream = &mut self.streams[idx];
+                        if name.eq_ignore_ascii_case("BPS") {
+                            match &mut stream.params {
+                                StreamParams::Video(v) => v.bit_rate = number,
+                                StreamParams::Audio(a) => a.bit_rate = number,
+                                _ => {}
+                            }
+                        } else {
+                            stream.nb_frames = number;
+                        }
+                    }
+                }
+                continue;
+            }
+            if !name.eq_ignore_ascii_case("TIMECODE") {
+                continue;
+            }
+            let tc = match value.parse::<SmpteTc>() {
+                Ok(tc) => tc,
+                Err(_) => {
+                    debug!("MKV: 忽略无效 TIMECODE 标签: {value}");
+                    continue;
+                }
+            };
+            let entry = ("timecode".to_string(), tc.to_string());
+            if target_uids.is_empty() {
+                self.metadata.push(entry);
+                continue;
+            }
+            for uid in &target_uids {
+                if let Some(idx) = self.find_stream_index_by_uid(*uid) {
+                    self.streams[idx].metadata.push(entry.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 解析 SimpleTag, 返回 (TagName, TagString)
+    fn parse_simple_tag(io: &mut IoContext, size: u64) -> TaoResult<(String, String)> {
+        let end = io.position()? + size;
+        let mut name = String::new();
+        let mut value = String::new();
+        while io.position()? < end {
+            let (eid, esize, _) = read_element_header(io)?;
+            match eid {
+                TAG_NAME => name = read_string(io, esize)?,
+                TAG_STRING => value = read_string(io, esize)?,
+                _ => {
+                    io.skip(esize as usize)?;
+                }
+            }
+        }
+        Ok((name, value))
+    }
+
+    /// 查找 TrackUID 对应的流索引
+    fn find_stream_index_by_uid(&self, track_uid: u64) -> Option<usize> {
+        self.track_uid_map
+            .iter()
+            .find(|(uid, _)| *uid == track_uid)
+            .map(|(_, idx)| *idx)
+    }
+
     /// 查找轨道号对应的流索引
     fn find_stream_index(&self, track_number: u64) -> Option<usize> {
         self.track_map
@@ -449,8 +778,7 @@ impl MkvDemuxer {
         let block_data = io.read_bytes(data_size as usize)?;
 
         let abs_ts = self.cluster_timestamp + relative_ts;
-        // 转换为毫秒 (time_base = 1/1000)
-        let pts_ms = abs_ts * self.timescale_ns as i64 / 1_000_000;
+        let block_ns = abs_ts * self.timescale_ns as i64;
         let stream_index = self
             .find_stream_index(track_number)
             .ok_or_else(|| TaoError::InvalidData("MKV: Block 轨道号未映射到已知流".into()))?;
@@ -459,16 +787,24 @@ impl MkvDemuxer {
             return Err(TaoError::InvalidData("MKV: Block 无有效帧负载".into()));
         }
 
+        // lacing 中的后续帧按帧时长累加推算时间戳, 转换为毫秒 (time_base = 1/1000)
         let mut packets = Vec::with_capacity(frame_payloads.len());
+        let mut offset_ns = 0i64;
         for payload in frame_payloads {
             if payload.is_empty() {
                 continue;
             }
+            let duration_ns = self.frame_duration_ns(stream_index, &payload) as i64;
+            let pts_ms = (block_ns + offset_ns) / 1_000_000;
+            offset_ns += duration_ns;
             let mut pkt = Packet::from_data(payload);
             pkt.stream_index = stream_index;
             pkt.pts = pts_ms;
             pkt.dts = pts_ms;
-            pkt.is_keyframe = is_keyframe;
+            if duration_ns > 0 {
+                pkt.duration = (block_ns + offset_ns) / 1_000_000 - pts_ms;
+            }
+            pkt.set_keyframe(is_keyframe);
             if let Some(stream) = self.streams.get(stream_index) {
                 pkt.time_base = stream.time_base;
             }
@@ -485,7 +821,7 @@ impl MkvDemuxer {
         for pkt in packets {
             self.pending_packets.push_back(pkt);
         }
-        first.is_keyframe = is_keyframe;
+        first.set_keyframe(is_keyframe);
         Ok(first)
     }
 
@@ -692,6 +1028,10 @@ impl Demuxer for MkvDemuxer {
 
     fn open(&mut self, io: &mut IoContext) -> TaoResult<()> {
         self.pending_packets.clear();
+        self.cues.clear();
+        let mut cues_offset = None;
+        let mut tags_offset = None;
+        let mut tags_parsed = false;
 
         // 1) 解析 EBML 头部
         self.parse_ebml_header(io)?;
@@ -724,13 +1064,30 @@ impl Demuxer for MkvDemuxer {
                 TRACKS => {
                     self.parse_tracks(io, esize)?;
                 }
+                TAGS => {
+                    self.parse_tags(io, esize)?;
+                    tags_parsed = true;
+                }
+                SEEK_HEAD if esize != EBML_UNKNOWN_SIZE => {
+                    let (cues, tags) = Self::parse_seek_head(io, esize)?;
+                    if let Some(offset) = cues {
+                        cues_offset.get_or_insert(offset);
+                    }
+                    if let Some(offset) = tags {
+                        tags_offset.get_or_insert(offset);
+                    }
+                }
+                CUES if esize != EBML_UNKNOWN_SIZE => {
+                    self.parse_cues(io, esize)?;
+                }
                 CLUSTER => {
                     // 到达第一个 Cluster, 记录位置并回退
+                    self.first_cluster_pos = Some(pos);
                     io.seek(std::io::SeekFrom::Start(pos))?;
                     break;
                 }
                 _ => {
-                    // SeekHead, Cues, Tags 等 → 跳过
+                    // 其他元素 → 跳过
                     if esize != EBML_UNKNOWN_SIZE {
                         io.skip(esize as usize)?;
                     } else {
@@ -744,6 +1101,15 @@ impl Demuxer for MkvDemuxer {
             return Err(TaoError::InvalidData("MKV: 未找到任何轨道".into()));
         }
 
+        // Cues 位于 Cluster 之后时, 通过 SeekHead 定位读取
+        if let (true, Some(offset)) = (self.cues.is_empty(), cues_offset) {
+            self.load_cues_at(io, offset)?;
+        }
+        // mkvmerge 将 Tags (含统计标签) 写在 Cluster 之后
+        if let (false, Some(offset)) = (tags_parsed, tags_offset) {
+            self.load_tags_at(io, offset)?;
+        }
+
         // 更新时长
         if let Some(dur_ns) = self.duration_ns {
             for stream in &mut self.streams {
@@ -764,6 +1130,10 @@ impl Demuxer for MkvDemuxer {
         &self.streams
     }
 
+    fn metadata(&self) -> &[(String, String)] {
+        &self.metadata
+    }
+
     fn read_packet(&mut self, io: &mut IoContext) -> TaoResult<Packet> {
         if let Some(pkt) = self.pending_packets.pop_front() {
             return Ok(pkt);
@@ -813,18 +1183,82 @@ impl Demuxer for MkvDemuxer {
 
     fn seek(
         &mut self,
-        _io: &mut IoContext,
-        _stream_index: usize,
-        _timestamp: i64,
-        _flags: SeekFlags,
+        io: &mut IoContext,
+        stream_index: usize,
+        timestamp: i64,
+        flags: SeekFlags,
     ) -> TaoResult<()> {
+        let track_number = self
+            .track_map
+            .iter()
+            .find(|(_, idx)| *idx == stream_index)
+            .map(|(tn, _)| *tn)
+            .ok_or_else(|| TaoError::InvalidArgument(format!("流索引超出范围: {stream_index}")))?;
+        if self.cues.is_empty() {
+            return Err(TaoError::NotImplemented(
+                "MKV: 文件缺少 Cues 索引, 暂不支持 seek".into(),
+            ));
+        }
+
+        // 优先使用目标轨道的索引, 该轨道无索引时 (通常仅视频轨道有 Cues) 使用全部索引
+        let has_track_cues = self.cues.iter().any(|c| c.track_number == track_number);
+        let candidates: Vec<&CueEntry> = self
+            .cues
+            .iter()
+            .filter(|c| !has_track_cues || c.track_number == track_number)
+            .collect();
+        let before = candidates.iter().rev().find(|c| c.time <= timestamp);
+        let after = candidates.iter().find(|c| c.time >= timestamp);
+        let target_pos = if flags.backward {
+            before.map(|c| c.cluster_pos).or(self.first_cluster_pos)
+        } else {
+            after.or(before).map(|c| c.cluster_pos)
+        }
+        .ok_or_else(|| TaoError::InvalidData("MKV: 未找到可用的 Cluster 位置".into()))?;
+
+        io.seek(std::io::SeekFrom::Start(target_pos))?;
         self.pending_packets.clear();
-        Err(TaoError::NotImplemented("MKV seek 尚未实现".into()))
+        self.in_cluster = false;
+        self.cluster_remaining = 0;
+        self.cluster_timestamp = 0;
+        debug!("MKV: seek 流 #{stream_index} 到 {timestamp} ms, Cluster 偏移 {target_pos}");
+        Ok(())
     }
 
     fn duration(&self) -> Option<f64> {
         self.duration_ns.map(|ns| ns / 1_000_000_000.0)
     }
+
+    /// 各流统计标签码率之和, 均未知时返回 None
+    fn bit_rate(&self) -> Option<u64> {
+        let total: u64 = self
+            .streams
+            .iter()
+            .map(|s| match &s.params {
+                StreamParams::Video(v) => v.bit_rate,
+                StreamParams::Audio(a) => a.bit_rate,
+                _ => 0,
+            })
+            .sum();
+        (total > 0).then_some(total)
+    }
+}
+
+/// 由 TOC 字节推算 Opus 数据包时长 (纳秒, RFC 6716 3.1)
+fn opus_packet_duration_ns(data: &[u8]) -> Option<u64> {
+    let toc = *data.first()?;
+    let config = toc >> 3;
+    let frame_ns: u64 = match config {
+        0..=11 => [10_000_000, 20_000_000, 40_000_000, 60_000_000][usize::from(config % 4)],
+        12..=15 => [10_000_000, 20_000_000][usize::from(config % 2)],
+        _ => [2_500_000, 5_000_000, 10_000_000, 20_000_000][usize::from(config % 4)],
+    };
+    let frame_count = match toc & 0x03 {
+        0 => 1,
+        1 | 2 => 2,
+        _ => u64::from(*data.get(1)? & 0x3F),
+    };
+    Some(frame_ns * frame_count)
 }
 
 /// Matroska CodecID → tao CodecId 映射
@@ -899,7 +1333,6 @@ impl FormatProbe for MkvProbe {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     /// 辅助: 写入 EBML 变长整数 (ID, 不掩码)
     fn write_vint_id(buf: &mut Vec<u8>, id: u32) {
@@ -1001,6 +1434,10 @@ mod tests {
             let mut video = Vec::new();
             write_uint_element(&mut video, VIDEO_PIXEL_WIDTH, 1280);
             write_uint_element(&mut video, VIDEO_PIXEL_HEIGHT, 720);
+            let mut colour = Vec::new();
+            write_uint_element(&mut colour, COLOUR_MATRIX_COEFFICIENTS, 1);
+            write_uint_element(&mut colour, COLOUR_RANGE, 2);
+            write_element(&mut video, VIDEO_COLOUR, &colour);
             write_element(&mut track_content, VIDEO_SETTINGS, &video);
 
             write_element(&mut tracks_content, TRACK_ENTRY, &track_content);
@@ -1071,8 +1508,7 @@ mod tests {
     #[test]
     fn test_parse_minimal_mkv() {
         let mkv = build_minimal_mkv();
-        let backend = MemoryBackend::from_data(mkv);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(mkv);
         let mut demuxer = MkvDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -1085,6 +1521,8 @@ mod tests {
         if let StreamParams::Video(ref v) = streams[0].params {
             assert_eq!(v.width, 1280);
             assert_eq!(v.height, 720);
+            assert_eq!(v.color_space, ColorSpace::Bt709);
+            assert_eq!(v.color_range, ColorRange::Full);
         } else {
             panic!("应该是视频参数");
         }
@@ -1100,18 +1538,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unknown_track_type() {
+        let mut data = Vec::new();
+        let mut ebml_content = Vec::new();
+        write_string_element(&mut ebml_content, EBML_DOC_TYPE, "matroska");
+        write_element(&mut data, EBML_HEADER, &ebml_content);
+        write_vint_id(&mut data, SEGMENT);
+        data.push(0x01);
+        data.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+
+        let mut tracks_content = Vec::new();
+        let mut track_content = Vec::new();
+        write_uint_element(&mut track_content, TRACK_NUMBER, 1);
+        write_uint_element(&mut track_content, TRACK_TYPE, 3); // complex
+        write_string_element(&mut track_content, TRACK_CODEC_ID, "X_UNKNOWN");
+        write_element(&mut tracks_content, TRACK_ENTRY, &track_content);
+        write_element(&mut data, TRACKS, &tracks_content);
+
+        let mut io = IoContext::from_bytes(data);
+        let mut demuxer = MkvDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        let streams = demuxer.streams();
+        assert_eq!(streams.len(), 1);
+        assert_eq!(streams[0].media_type, MediaType::Unknown(3));
+        assert!(!streams[0].media_type.is_video());
+        assert!(!streams[0].media_type.is_audio());
+    }
+
     #[test]
     fn test_read_packets() {
         let mkv = build_minimal_mkv();
-        let backend = MemoryBackend::from_data(mkv);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(mkv);
         let mut demuxer = MkvDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
         // 第一个包: 视频
         let pkt0 = demuxer.read_packet(&mut io).unwrap();
         assert_eq!(pkt0.stream_index, 0);
-        assert!(pkt0.is_keyframe);
+        assert!(pkt0.is_keyframe());
         assert_eq!(pkt0.data.as_ref(), &[0xDE, 0xAD]);
 
         // 第二个包: 音频
@@ -1120,11 +1586,70 @@ mod tests {
         assert_eq!(pkt1.data.as_ref(), &[0xBE, 0xEF, 0xCA, 0xFE]);
     }
 
+    #[test]
+    fn test_parse_timecode_tags() {
+        let mut data = Vec::new();
+        let mut ebml_content = Vec::new();
+        write_string_element(&mut ebml_content, EBML_DOC_TYPE, "matroska");
+        write_element(&mut data, EBML_HEADER, &ebml_content);
+        write_vint_id(&mut data, SEGMENT);
+        data.push(0x01);
+        data.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+
+        let mut tracks_content = Vec::new();
+        let mut track_content = Vec::new();
+        write_uint_element(&mut track_content, TRACK_NUMBER, 1);
+        write_uint_element(&mut track_content, TRACK_UID, 0x1234);
+        write_uint_element(&mut track_content, TRACK_TYPE, 1);
+        write_string_element(&mut track_content, TRACK_CODEC_ID, "V_VP9");
+        write_element(&mut tracks_content, TRACK_ENTRY, &track_content);
+        write_element(&mut data, TRACKS, &tracks_content);
+
+        // 一个针对轨道 UID 的 TIMECODE, 一个全局 TIMECODE
+        let mut tags_content = Vec::new();
+        {
+            let mut targets = Vec::new();
+            write_uint_element(&mut targets, TAG_TRACK_UID, 0x1234);
+            let mut simple = Vec::new();
+            write_string_element(&mut simple, TAG_NAME, "TIMECODE");
+            write_string_element(&mut simple, TAG_STRING, "01:00:00;02");
+            let mut tag = Vec::new();
+            write_element(&mut tag, TAG_TARGETS, &targets);
+            write_element(&mut tag, SIMPLE_TAG, &simple);
+            write_element(&mut tags_content, TAG, &tag);
+        }
+        {
+            let mut simple = Vec::new();
+            write_string_element(&mut simple, TAG_NAME, "TIMECODE");
+            write_string_element(&mut simple, TAG_STRING, "10:00:00:00");
+            let mut tag = Vec::new();
+            write_element(&mut tag, SIMPLE_TAG, &simple);
+            write_element(&mut tags_content, TAG, &tag);
+        }
+        write_element(&mut data, TAGS, &tags_content);
+
+        let mut io = IoContext::from_bytes(data);
+        let mut demuxer = MkvDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        let stream_tc = demuxer.streams()[0]
+            .metadata
+            .iter()
+            .find(|(k, _)| k == "timecode")
+            .map(|(_, v)| v.as_str());
+        assert_eq!(stream_tc, Some("01:00:00;02"), "轨道级 TIMECODE 解析错误");
+        let global_tc = demuxer
+            .metadata()
+            .iter()
+            .find(|(k, _)| k == "timecode")
+            .map(|(_, v)| v.as_str());
+        assert_eq!(global_tc, Some("10:00:00:00"), "全局 TIMECODE 解析错误");
+    }
+
     #[test]
     fn test_duration() {
         let mkv = build_minimal_mkv();
-        let backend = MemoryBackend::from_data(mkv);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(mkv);
         let mut demuxer = MkvDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -1133,6 +1658,302 @@ mod tests {
         assert!((dur - 5.0).abs() < 0.01, "时长应约为 5 秒, 实际={dur}");
     }
 
+    /// 构造 EBML 头部与未知大小的 Segment 起始
+    fn build_segment_start() -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut ebml_content = Vec::new();
+        write_string_element(&mut ebml_content, EBML_DOC_TYPE, "matroska");
+        write_element(&mut data, EBML_HEADER, &ebml_content);
+        write_vint_id(&mut data, SEGMENT);
+        data.push(0x01);
+        data.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        data
+    }
+
+    /// 构造 SimpleBlock 内容 (轨道号 < 127)
+    fn simple_block(track: u8, relative_ts: i16, flags: u8, payload: &[u8]) -> Vec<u8> {
+        let mut block = vec![0x80 | track];
+        block.extend_from_slice(&relative_ts.to_be_bytes());
+        block.push(flags);
+        block.extend_from_slice(payload);
+        block
+    }
+
+    #[test]
+    fn test_laced_opus_packets_interpolated_timestamps() {
+        let mut data = build_segment_start();
+        let mut tracks_content = Vec::new();
+        let mut track_content = Vec::new();
+        write_uint_element(&mut track_content, TRACK_NUMBER, 1);
+        write_uint_element(&mut track_content, TRACK_TYPE, 2);
+        write_string_element(&mut track_content, TRACK_CODEC_ID, "A_OPUS");
+        write_element(&mut tracks_content, TRACK_ENTRY, &track_content);
+        write_element(&mut data, TRACKS, &tracks_content);
+
+        // Opus TOC 0xF8: config 31 (CELT 20ms), 单帧; 未指定 DefaultDuration, 由 TOC 推算
+        let frame = |len: usize, tag: u8| {
+            let mut f = vec![0xF8];
+            f.resize(len, tag);
+            f
+        };
+        let mut cluster = Vec::new();
+        write_uint_element(&mut cluster, CLUSTER_TIMESTAMP, 0);
+        {
+            // Xiph lacing: 3 帧, 长度 3/4/5
+            let mut laced = vec![2, 3, 4];
+            laced.extend(frame(3, 1));
+            laced.extend(frame(4, 2));
+            laced.extend(frame(5, 3));
+            write_element(
+                &mut cluster,
+                SIMPLE_BLOCK,
+                &simple_block(1, 0, 0x82, &laced),
+            );
+        }
+        {
+            // EBML lacing: 3 帧, 长度 6/4(差分 -2)/7
+            let mut laced = vec![2, 0x86, 0xBD];
+            laced.extend(frame(6, 4));
+            laced.extend(frame(4, 5));
+            laced.extend(frame(7, 6));
+            write_element(
+                &mut cluster,
+                SIMPLE_BLOCK,
+                &simple_block(1, 60, 0x86, &laced),
+            );
+        }
+        {
+            // Fixed lacing: 2 帧, 各 4 字节
+            let mut laced = vec![1];
+            laced.extend(frame(4, 7));
+            laced.extend(frame(4, 8));
+            write_element(
+                &mut cluster,
+                SIMPLE_BLOCK,
+                &simple_block(1, 120, 0x84, &laced),
+            );
+        }
+        write_element(&mut data, CLUSTER, &cluster);
+
+        let mut io = IoContext::from_bytes(data);
+        let mut demuxer = MkvDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        let mut packets = Vec::new();
+        loop {
+            match demuxer.read_packet(&mut io) {
+                Ok(pkt) => packets.push(pkt),
+                Err(TaoError::Eof) => break,
+                Err(e) => panic!("读取数据包失败: {e}"),
+            }
+        }
+        assert_eq!(packets.len(), 8, "每个 lacing 帧应输出一个数据包");
+        let pts: Vec<i64> = packets.iter().map(|p| p.pts).collect();
+        assert_eq!(
+            pts,
+            [0, 20, 40, 60, 80, 100, 120, 140],
+            "时间戳应按帧时长递增"
+        );
+        let sizes: Vec<usize> = packets.iter().map(|p| p.data.len()).collect();
+        assert_eq!(sizes, [3, 4, 5, 6, 4, 7, 4, 4], "lacing 帧长度拆分错误");
+        assert!(packets.iter().all(|p| p.duration == 20), "帧时长应为 20ms");
+        assert_eq!(packets[7].data[1], 8, "帧内容应与 lacing 顺序一致");
+    }
+
+    #[test]
+    fn test_seek_with_cues_from_seek_head() {
+        // Segment 内容: SeekHead, Tracks, Cluster(0ms), Cluster(1000ms), Cues
+        let mut tracks_content = Vec::new();
+        let mut track_content = Vec::new();
+        write_uint_element(&mut track_content, TRACK_NUMBER, 1);
+        write_uint_element(&mut track_content, TRACK_TYPE, 1);
+        write_string_element(&mut track_content, TRACK_CODEC_ID, "V_VP9");
+        write_element(&mut tracks_content, TRACK_ENTRY, &track_content);
+        let mut tracks = Vec::new();
+        write_element(&mut tracks, TRACKS, &tracks_content);
+
+        let mut clusters = Vec::new();
+        let mut cluster_offsets = Vec::new();
+        for (ts, tag) in [(0u64, 0xAA), (1000, 0xBB)] {
+            cluster_offsets.push(clusters.len());
+            let mut cluster = Vec::new();
+            write_uint_element(&mut cluster, CLUSTER_TIMESTAMP, ts);
+            write_element(
+                &mut cluster,
+                SIMPLE_BLOCK,
+                &simple_block(1, 0, 0x80, &[tag]),
+            );
+            write_element(&mut clusters, CLUSTER, &cluster);
+        }
+
+        // SeekHead 中 SeekPosition 固定 4 字节, 便于预先计算长度
+        let build_seek_head = |cues_offset: u32| {
+            let mut seek = Vec::new();
+            write_element(&mut seek, SEEK_ID, &CUES.to_be_bytes());
+            write_element(&mut seek, SEEK_POSITION, &cues_offset.to_be_bytes());
+            let mut content = Vec::new();
+            write_element(&mut content, SEEK, &seek);
+            let mut head = Vec::new();
+            write_element(&mut head, SEEK_HEAD, &content);
+            head
+        };
+        let head_len = build_seek_head(0).len();
+        let clusters_start = head_len + tracks.len();
+        let cues_offset = clusters_start + clusters.len();
+
+        let mut cues_content = Vec::new();
+        for (ts, offset) in [0u64, 1000].into_iter().zip(&cluster_offsets) {
+            let mut positions = Vec::new();
+            write_uint_element(&mut positions, CUE_TRACK, 1);
+            write_uint_element(
+                &mut positions,
+                CUE_CLUSTER_POSITION,
+                (clusters_start + offset) as u64,
+            );
+            let mut point = Vec::new();
+            write_uint_element(&mut point, CUE_TIME, ts);
+            write_element(&mut point, CUE_TRACK_POSITIONS, &positions);
+            write_element(&mut cues_content, CUE_POINT, &point);
+        }
+
+        let mut data = build_segment_start();
+        data.extend(build_seek_head(cues_offset as u32));
+        data.extend(tracks);
+        data.extend(clusters);
+        write_element(&mut data, CUES, &cues_content);
+
+        let mut io = IoContext::from_bytes(data);
+        let mut demuxer = MkvDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        // 打开后应仍从第一个 Cluster 开始读取
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!((pkt.pts, pkt.data[0]), (0, 0xAA));
+
+        demuxer
+            .seek(&mut io, 0, 1500, SeekFlags::default())
+            .expect("有 Cues 时应支持 seek");
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!(
+            (pkt.pts, pkt.data[0]),
+            (1000, 0xBB),
+            "应定位到 1000ms 的 Cluster"
+        );
+
+        demuxer.seek(&mut io, 0, 200, SeekFlags::default()).unwrap();
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!(pkt.pts, 0, "向后 seek 应定位到目标之前的 Cluster");
+
+        let forward = SeekFlags {
+            backward: false,
+            ..SeekFlags::default()
+        };
+        demuxer.seek(&mut io, 0, 200, forward).unwrap();
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!(pkt.pts, 1000, "向前 seek 应定位到目标之后的 Cluster");
+    }
+
+    #[test]
+    fn test_statistics_tags_after_clusters() {
+        // Segment 内容: SeekHead(→Tags), Info, Tracks, Cluster, Tags (mkvmerge 布局)
+        let mut info_content = Vec::new();
+        write_float_element(&mut info_content, INFO_DURATION, 2000.0);
+        let mut info = Vec::new();
+        write_element(&mut info, SEGMENT_INFO, &info_content);
+
+        let mut tracks_content = Vec::new();
+        for (number, uid, track_type, codec) in [(1, 0x11, 1, "V_VP9"), (2, 0x22, 2, "A_OPUS")] {
+            let mut track_content = Vec::new();
+            write_uint_element(&mut track_content, TRACK_NUMBER, number);
+            write_uint_element(&mut track_content, TRACK_UID, uid);
+            write_uint_element(&mut track_content, TRACK_TYPE, track_type);
+            write_string_element(&mut track_content, TRACK_CODEC_ID, codec);
+            write_element(&mut tracks_content, TRACK_ENTRY, &track_content);
+        }
+        let mut tracks = Vec::new();
+        write_element(&mut tracks, TRACKS, &tracks_content);
+
+        let mut cluster = Vec::new();
+        write_uint_element(&mut cluster, CLUSTER_TIMESTAMP, 0);
+        write_element(
+            &mut cluster,
+            SIMPLE_BLOCK,
+            &simple_block(1, 0, 0x80, &[0xAA]),
+        );
+        let mut clusters = Vec::new();
+        write_element(&mut clusters, CLUSTER, &cluster);
+
+        let mut tags_content = Vec::new();
+        for (uid, frames, bps) in [(0x11, "50", "800000"), (0x22, "100", "96000")] {
+            let mut targets = Vec::new();
+            write_uint_element(&mut targets, TAG_TRACK_UID, uid);
+            let mut tag = Vec::new();
+            write_element(&mut tag, TAG_TARGETS, &targets);
+            for (name, value) in [("BPS", bps), ("NUMBER_OF_FRAMES", frames)] {
+                let mut simple = Vec::new();
+                write_string_element(&mut simple, TAG_NAME, name);
+                write_string_element(&mut simple, TAG_STRING, value);
+                write_element(&mut tag, SIMPLE_TAG, &simple);
+            }
+            write_element(&mut tags_content, TAG, &tag);
+        }
+
+        let build_seek_head = |tags_offset: u32| {
+            let mut seek = Vec::new();
+            write_element(&mut seek, SEEK_ID, &TAGS.to_be_bytes());
+            write_element(&mut seek, SEEK_POSITION, &tags_offset.to_be_bytes());
+            let mut content = Vec::new();
+            write_element(&mut content, SEEK, &seek);
+            let mut head = Vec::new();
+            write_element(&mut head, SEEK_HEAD, &content);
+            head
+        };
+        let tags_offset = build_seek_head(0).len() + info.len() + tracks.len() + clusters.len();
+
+        let mut data = build_segment_start();
+        data.extend(build_seek_head(tags_offset as u32));
+        data.extend(info);
+        data.extend(tracks);
+        data.extend(clusters);
+        write_element(&mut data, TAGS, &tags_content);
+
+        let mut io = IoContext::from_bytes(data);
+        let mut demuxer = MkvDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        let streams = demuxer.streams();
+        assert_eq!(streams[0].nb_frames, 50, "视频帧数应来自 NUMBER_OF_FRAMES");
+        assert_eq!(streams[1].nb_frames, 100, "音频帧数应来自 NUMBER_OF_FRAMES");
+        assert!(matches!(&streams[0].params, StreamParams::Video(v) if v.bit_rate == 800_000));
+        assert!(matches!(&streams[1].params, StreamParams::Audio(a) if a.bit_rate == 96_000));
+        assert_eq!(streams[0].duration, 2000, "流时长应来自 Segment Info");
+        assert_eq!(demuxer.duration(), Some(2.0));
+        assert_eq!(demuxer.bit_rate(), Some(896_000), "容器码率为各流码率之和");
+
+        // 打开后应仍从第一个 Cluster 开始读取
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!(pkt.data[0], 0xAA);
+    }
+
+    #[test]
+    fn test_seek_without_cues_not_supported() {
+        let mut io = IoContext::from_bytes(build_minimal_mkv());
+        let mut demuxer = MkvDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        let err = demuxer.seek(&mut io, 0, 0, SeekFlags::default());
+        assert!(matches!(err, Err(TaoError::NotImplemented(_))));
+    }
+
+    #[test]
+    fn test_opus_packet_duration() {
+        assert_eq!(opus_packet_duration_ns(&[0xF8]), Some(20_000_000));
+        // SILK 60ms, 双帧
+        assert_eq!(opus_packet_duration_ns(&[0x19]), Some(120_000_000));
+        // CELT 2.5ms, code 3 (3 帧)
+        assert_eq!(opus_packet_duration_ns(&[0x83, 0x03]), Some(7_500_000));
+        assert_eq!(opus_packet_duration_ns(&[]), None);
+    }
+
     #[test]
     fn test_codec_id_mapping() {
         assert_eq!(mkv_codec_to_id("V_MPEG4/ISO/AVC"), CodecId::H264);
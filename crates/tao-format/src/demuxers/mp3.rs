@@ -2,7 +2,7 @@
 //!
 //! MP3 文件结构:
 //! ```text
-//! [ID3v2 标签 (可选)]
+//! [ID3v2 标签 (可选, 提取 TIT2/TPE1/TALB 为 title/artist/album 元数据)]
 //! [MPEG 音频帧 #0]
 //!   ├── 帧同步码 (11 bits = 0x7FF)
 //!   ├── 帧头 (版本, 层, 比特率, 采样率, 声道模式等)
@@ -188,6 +188,20 @@ fn parse_frame_header(header: u32) -> Option<FrameHeader> {
     })
 }
 
+/// Xing/Info 或 VBRI 头信息
+struct VbrHeader {
+    /// 总帧数
+    total_frames: Option<u64>,
+    /// 音频数据总字节数
+    total_bytes: Option<u64>,
+    /// Xing 查找表: 第 i 项为 i% 时长处的字节位置 (占总字节数的 x/256)
+    toc: Option<[u8; 100]>,
+    /// Encoder delay (样本)
+    encoder_delay: u32,
+    /// Trailing padding (样本)
+    encoder_padding: u32,
+}
+
 /// MP3 解封装器
 pub struct Mp3Demuxer {
     /// 流信息
@@ -206,6 +220,14 @@ pub struct Mp3Demuxer {
     encoder_delay: u32,
     /// Trailing padding (来自 LAME/iTunSMPB gapless 信息, 单位: 样本)
     encoder_padding: u32,
+    /// 来自 ID3v2 标签的元数据
+    metadata: Vec<(String, String)>,
+    /// Xing TOC 查找表, 存在时按表定位 seek 的字节位置
+    toc: Option<[u8; 100]>,
+    /// TOC 的字节基准: Xing 帧起始偏移
+    toc_base: u64,
+    /// TOC 对应的总字节数 (含 Xing 帧)
+    toc_bytes: u64,
 }
 
 impl Mp3Demuxer {
@@ -220,11 +242,18 @@ impl Mp3Demuxer {
             frames_read: 0,
             encoder_delay: 0,
             encoder_padding: 0,
+            metadata: Vec::new(),
+            toc: None,
+            toc_base: 0,
+            toc_bytes: 0,
         }))
     }
 
-    /// 跳过 ID3v2 标签
-    fn skip_id3v2(io: &mut IoContext) -> TaoResult<u64> {
+    /// 解析并跳过 ID3v2 标签, 返回标签总大小
+    ///
+    /// ID3v2.3/2.4 标签中的 TIT2/TPE1/TALB 帧写入 `self.metadata`,
+    /// 其余帧 (如 APIC 封面) 直接跳过, 不读入内存.
+    fn read_id3v2(&mut self, io: &mut IoContext) -> TaoResult<u64> {
         let mut header = [0u8; 10];
         io.read_exact(&mut header)?;
 
@@ -235,18 +264,109 @@ impl Mp3Demuxer {
             return Ok(0);
         }
 
-        // ID3v2 大小 (syncsafe integer, 4 bytes, 每字节只用 7 位)
-        let size = u64::from(header[6] & 0x7F) << 21
-            | u64::from(header[7] & 0x7F) << 14
-            | u64::from(header[8] & 0x7F) << 7
-            | u64::from(header[9] & 0x7F);
+        let version = header[3];
+        let flags = header[5];
+        // ID3v2 大小 (syncsafe integer, 4 bytes, 每字节只用 7 位), 不含头部与页脚
+        let size = u64::from(syncsafe_u32(&header[6..10]));
+        let footer_size = if version >= 4 && flags & ID3V2_FLAG_FOOTER != 0 {
+            10
+        } else {
+            0
+        };
+        let tag_end = 10 + size;
+        let total_tag_size = tag_end + footer_size;
+
+        // v2.2 帧头格式不同; v2.3 整体反同步时帧边界不可直接读取, 均只跳过
+        if (version == 3 && flags & ID3V2_FLAG_UNSYNC == 0) || version == 4 {
+            if let Err(e) = self.read_id3v2_frames(io, version, flags, tag_end) {
+                debug!("MP3: ID3v2 帧解析失败, 忽略标签内容: {e}");
+            }
+        }
 
-        let total_tag_size = 10 + size;
         io.seek(std::io::SeekFrom::Start(total_tag_size))?;
-        debug!("MP3: 跳过 ID3v2 标签, 大小={total_tag_size} 字节");
+        debug!(
+            "MP3: 跳过 ID3v2.{version} 标签, 大小={total_tag_size} 字节, 元数据 {} 项",
+            self.metadata.len()
+        );
         Ok(total_tag_size)
     }
 
+    /// 逐帧读取 ID3v2.3/2.4 标签, 提取文本元数据
+    fn read_id3v2_frames(
+        &mut self,
+        io: &mut IoContext,
+        version: u8,
+        flags: u8,
+        tag_end: u64,
+    ) -> TaoResult<()> {
+        let mut pos = 10;
+        if flags & ID3V2_FLAG_EXTENDED_HEADER != 0 {
+            let mut buf = [0u8; 4];
+            io.read_exact(&mut buf)?;
+            // v2.3 扩展头大小不含自身 4 字节, v2.4 为 syncsafe 且包含自身
+            pos += if version >= 4 {
+                u64::from(syncsafe_u32(&buf))
+            } else {
+                4 + u64::from(u32::from_be_bytes(buf))
+            };
+        }
+
+        while pos + 10 <= tag_end {
+            io.seek(std::io::SeekFrom::Start(pos))?;
+            let mut frame_header = [0u8; 10];
+            io.read_exact(&mut frame_header)?;
+            // 帧 ID 为 0 表示进入填充区
+            if frame_header[0] == 0 {
+                break;
+            }
+            let frame_size = if version >= 4 {
+                syncsafe_u32(&frame_header[4..8])
+            } else {
+                u32::from_be_bytes([
+                    frame_header[4],
+                    frame_header[5],
+                    frame_header[6],
+                    frame_header[7],
+                ])
+            };
+            let body_start = pos + 10;
+            let body_end = body_start + u64::from(frame_size);
+            if body_end > tag_end {
+                break;
+            }
+            pos = body_end;
+
+            let key = match &frame_header[0..4] {
+                b"TIT2" => "title",
+                b"TPE1" => "artist",
+                b"TALB" => "album",
+                _ => continue,
+            };
+            let format_flags = frame_header[9];
+            // 压缩/加密帧不支持 (v2.3: 0x80/0x40, v2.4: 0x08/0x04)
+            let unsupported = if version >= 4 { 0x0C } else { 0xC0 };
+            if format_flags & unsupported != 0 {
+                continue;
+            }
+            let mut body = io.read_bytes(frame_size as usize)?;
+            if version >= 4 {
+                // 数据长度指示 (4 字节) 位于帧数据之前
+                if format_flags & 0x01 != 0 {
+                    body.drain(..body.len().min(4));
+                }
+                if format_flags & 0x02 != 0 {
+                    body = remove_unsync(&body);
+                }
+            }
+            if let Some(text) = decode_id3v2_text(&body)
+                && !self.metadata.iter().any(|(k, _)| k == key)
+            {
+                self.metadata.push((key.to_string(), text));
+            }
+        }
+        Ok(())
+    }
+
     /// 同步到第一个有效帧
     fn find_first_frame(io: &mut IoContext) -> TaoResult<(u64, FrameHeader)> {
         let start = io.position()?;
@@ -288,12 +408,11 @@ impl Mp3Demuxer {
     }
 
     /// 尝试解析 Xing/Info 或 VBRI 头
-    /// 返回 (total_frames, encoder_delay, encoder_padding)
     fn parse_vbr_header(
         io: &mut IoContext,
         frame_offset: u64,
         fh: &FrameHeader,
-    ) -> TaoResult<Option<(Option<u64>, u32, u32)>> {
+    ) -> TaoResult<Option<VbrHeader>> {
         // Xing/Info 头部偏移取决于版本和声道
         let xing_offset = match (fh.version, fh.channel_mode) {
             (MpegVersion::V1, 3) => 17, // 单声道
@@ -318,15 +437,17 @@ impl Mp3Demuxer {
             } else {
                 None
             };
-            if (flags & 0x2) != 0 {
-                // 跳过 total_bytes
-                let _ = io.read_u32_be();
-            }
-            if (flags & 0x4) != 0 {
-                // 跳过 TOC (100 字节)
+            let total_bytes = if (flags & 0x2) != 0 {
+                Some(u64::from(io.read_u32_be()?))
+            } else {
+                None
+            };
+            let toc = if (flags & 0x4) != 0 {
                 let mut toc = [0u8; 100];
-                let _ = io.read_exact(&mut toc);
-            }
+                io.read_exact(&mut toc).ok().map(|()| toc)
+            } else {
+                None
+            };
             if (flags & 0x8) != 0 {
                 // 跳过 quality
                 let _ = io.read_u32_be();
@@ -360,12 +481,24 @@ impl Mp3Demuxer {
                         "MP3: 发现编码器扩展头 ({:?}), delay={encoder_delay}, padding={encoder_padding}, frames={total_frames:?}",
                         std::str::from_utf8(encoder_tag).unwrap_or("?")
                     );
-                    return Ok(Some((total_frames, encoder_delay, encoder_padding)));
+                    return Ok(Some(VbrHeader {
+                        total_frames,
+                        total_bytes,
+                        toc,
+                        encoder_delay,
+                        encoder_padding,
+                    }));
                 }
             }
 
             debug!("MP3: 发现 Xing 头 (无有效 gapless 扩展), frames={total_frames:?}");
-            return Ok(Some((total_frames, 0, 0)));
+            return Ok(Some(VbrHeader {
+                total_frames,
+                total_bytes,
+                toc,
+                encoder_delay: 0,
+                encoder_padding: 0,
+            }));
         }
 
         // 检查 VBRI 头 (固定在帧头+36 字节处)
@@ -375,18 +508,55 @@ impl Mp3Demuxer {
             let _version = io.read_u16_be()?;
             let _delay = io.read_u16_be()?;
             let _quality = io.read_u16_be()?;
-            let _total_bytes = io.read_u32_be()?;
+            let total_bytes = u64::from(io.read_u32_be()?);
             let total_frames = u64::from(io.read_u32_be()?);
-            debug!("MP3: 发现 VBRI 头, frames={total_frames}");
-            return Ok(Some((Some(total_frames), 0, 0)));
+            debug!("MP3: 发现 VBRI 头, frames={total_frames}, bytes={total_bytes}");
+            return Ok(Some(VbrHeader {
+                total_frames: Some(total_frames),
+                total_bytes: Some(total_bytes),
+                toc: None,
+                encoder_delay: 0,
+                encoder_padding: 0,
+            }));
         }
 
         Ok(None)
     }
 
+    /// 按 Xing TOC 定位目标帧, 返回是否成功
+    ///
+    /// TOC 给出时长百分比到字节位置的映射, 两项之间线性插值; 定位后同步到
+    /// 下一个有效帧. PTS 按目标帧计算, 精度取决于 TOC 的粒度.
+    fn seek_by_toc(&mut self, io: &mut IoContext, target_frame: u64) -> TaoResult<bool> {
+        let Some(toc) = self.toc else {
+            return Ok(false);
+        };
+        if self.total_frames == 0 || self.toc_bytes == 0 {
+            return Ok(false);
+        }
+
+        let percent = (target_frame as f64 * 100.0 / self.total_frames as f64).clamp(0.0, 99.999);
+        let index = percent as usize;
+        let lower = f64::from(toc[index]);
+        let upper = toc.get(index + 1).map_or(256.0, |&v| f64::from(v));
+        let fraction = (lower + (upper - lower) * (percent - index as f64)) / 256.0;
+        let pos = (self.toc_base + (fraction * self.toc_bytes as f64) as u64)
+            .max(self.first_frame_offset);
+
+        io.seek(std::io::SeekFrom::Start(pos))?;
+        let Ok((frame_pos, _)) = Self::find_first_frame(io) else {
+            return Ok(false);
+        };
+        io.seek(std::io::SeekFrom::Start(frame_pos))?;
+        self.frames_read = target_frame;
+        self.current_pts = (target_frame.saturating_mul(self.samples_per_frame as u64)) as i64;
+        debug!("MP3: 按 TOC seek, {percent:.2}% -> 字节 {pos}, 同步到帧 {frame_pos}");
+        Ok(true)
+    }
+
     /// 从第一帧开始按帧跳转到目标帧.
     ///
-    /// MP3 没有统一强制索引结构, 这里采用顺序扫描方式确保正确性.
+    /// 无 Xing TOC 时的回退方案: MP3 没有统一强制索引结构, 这里采用顺序扫描方式确保正确性.
     /// 对于中小文件和常规交互 seek 足够稳定.
     fn seek_to_frame(&mut self, io: &mut IoContext, target_frame: u64) -> TaoResult<()> {
         io.seek(std::io::SeekFrom::Start(self.first_frame_offset))?;
@@ -437,8 +607,8 @@ impl Demuxer for Mp3Demuxer {
     }
 
     fn open(&mut self, io: &mut IoContext) -> TaoResult<()> {
-        // 1) 跳过 ID3v2
-        Self::skip_id3v2(io)?;
+        // 1) 解析并跳过 ID3v2
+        self.read_id3v2(io)?;
 
         // 2) 找到第一个有效帧
         let (frame_offset, fh) = Self::find_first_frame(io)?;
@@ -446,14 +616,28 @@ impl Demuxer for Mp3Demuxer {
         self.samples_per_frame = fh.samples_per_frame;
 
         // 3) 尝试解析 VBR 头 (含 LAME gapless 信息)
-        if let Ok(Some((frames_opt, delay, padding))) =
-            Self::parse_vbr_header(io, frame_offset, &fh)
-        {
-            if let Some(frames) = frames_opt {
+        let mut bit_rate = u64::from(fh.bitrate);
+        if let Ok(Some(vbr)) = Self::parse_vbr_header(io, frame_offset, &fh) {
+            if let Some(frames) = vbr.total_frames {
                 self.total_frames = frames;
             }
-            self.encoder_delay = delay;
-            self.encoder_padding = padding;
+            // VBR 文件首帧码率不代表整体, 按头部记录的总字节数与总帧数计算平均码率
+            if let (Some(frames), Some(bytes)) = (vbr.total_frames, vbr.total_bytes)
+                && frames > 0
+                && bytes > 0
+            {
+                let total_samples = frames * u64::from(fh.samples_per_frame);
+                bit_rate = bytes * 8 * u64::from(fh.sample_rate) / total_samples;
+            }
+            self.encoder_delay = vbr.encoder_delay;
+            self.encoder_padding = vbr.encoder_padding;
+            if let Some(toc) = vbr.toc {
+                // TOC 字节位置相对 Xing 帧起始, 未记录总字节数时取到文件末尾
+                let file_bytes = io.size().map(|size| size.saturating_sub(frame_offset));
+                self.toc_bytes = vbr.total_bytes.or(file_bytes).unwrap_or(0);
+                self.toc_base = frame_offset;
+                self.toc = Some(toc);
+            }
             // Xing/Info 帧本身不算数据帧, 跳过它
             self.first_frame_offset = frame_offset + u64::from(fh.frame_size);
         }
@@ -531,8 +715,9 @@ impl Demuxer for Mp3Demuxer {
                 sample_rate: fh.sample_rate,
                 channel_layout: ChannelLayout::from_channels(channels),
                 sample_format: SampleFormat::F32,
-                bit_rate: u64::from(fh.bitrate),
+                bit_rate,
                 frame_size: fh.samples_per_frame,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         };
@@ -587,7 +772,7 @@ impl Demuxer for Mp3Demuxer {
                 pkt.stream_index = 0;
                 pkt.pts = self.current_pts;
                 pkt.dts = self.current_pts;
-                pkt.is_keyframe = true;
+                pkt.set_keyframe(true);
                 pkt.time_base = self.streams[0].time_base;
 
                 self.current_pts += i64::from(fh.samples_per_frame);
@@ -624,7 +809,9 @@ impl Demuxer for Mp3Demuxer {
             target_frame = target_frame.min(self.total_frames.saturating_sub(1));
         }
 
-        self.seek_to_frame(io, target_frame)?;
+        if target_frame == 0 || !self.seek_by_toc(io, target_frame)? {
+            self.seek_to_frame(io, target_frame)?;
+        }
 
         debug!(
             "MP3 seek: timestamp={}, target_frame={}, pts={}",
@@ -644,6 +831,111 @@ impl Demuxer for Mp3Demuxer {
         }
         None
     }
+
+    fn bit_rate(&self) -> Option<u64> {
+        match self.streams.first().map(|s| &s.params) {
+            Some(StreamParams::Audio(a)) if a.bit_rate > 0 => Some(a.bit_rate),
+            _ => None,
+        }
+    }
+
+    fn metadata(&self) -> &[(String, String)] {
+        &self.metadata
+    }
+}
+
+/// ID3v2 标签标志: 反同步
+const ID3V2_FLAG_UNSYNC: u8 = 0x80;
+/// ID3v2 标签标志: 存在扩展头
+const ID3V2_FLAG_EXTENDED_HEADER: u8 = 0x40;
+/// ID3v2 标签标志: 存在页脚 (仅 v2.4)
+const ID3V2_FLAG_FOOTER: u8 = 0x10;
+
+/// 读取 4 字节 syncsafe 整数 (每字节只用低 7 位)
+fn syncsafe_u32(b: &[u8]) -> u32 {
+    b[..4]
+        .iter()
+        .fold(0u32, |acc, &byte| (acc << 7) | u32::from(byte & 0x7F))
+}
+
+/// 去除反同步插入的字节 (`FF 00` → `FF`)
+fn remove_unsync(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut prev_ff = false;
+    for &byte in data {
+        if !(prev_ff && byte == 0x00) {
+            out.push(byte);
+        }
+        prev_ff = byte == 0xFF;
+    }
+    out
+}
+
+/// 解码 ID3v2 文本帧内容 (首字节为文本编码), 空文本返回 None
+///
+/// 编码: 0 = ISO-8859-1, 1 = 带 BOM 的 UTF-16, 2 = UTF-16BE, 3 = UTF-8.
+/// v2.4 以 NUL 分隔的多个值以 "/" 连接.
+fn decode_id3v2_text(body: &[u8]) -> Option<String> {
+    let (&encoding, data) = body.split_first()?;
+    let text = match encoding {
+        0 => data.iter().map(|&b| char::from(b)).collect(),
+        1 | 2 => {
+            let mut big_endian = encoding == 2;
+            let mut units = Vec::with_capacity(data.len() / 2);
+            for pair in data.chunks_exact(2) {
+                match (pair[0], pair[1]) {
+                    (0xFF, 0xFE) => big_endian = false,
+                    (0xFE, 0xFF) => big_endian = true,
+                    (a, b) if big_endian => units.push(u16::from_be_bytes([a, b])),
+                    (a, b) => units.push(u16::from_le_bytes([a, b])),
+                }
+            }
+            String::from_utf16_lossy(&units)
+        }
+        3 => String::from_utf8_lossy(data).into_owned(),
+        _ => return None,
+    };
+    let values: Vec<&str> = text
+        .split('\0')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .collect();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.join("/"))
+    }
+}
+
+/// 帧不在数据起始处时, 至少需要的连续有效帧数
+const PROBE_MIN_OFFSET_FRAMES: usize = 4;
+
+/// 统计从 `pos` 开始的连续有效帧数
+///
+/// 后续帧的版本/层/采样率必须与首帧一致, 最多统计到 [`PROBE_MIN_OFFSET_FRAMES`] 帧.
+fn count_frame_chain(data: &[u8], mut pos: usize) -> usize {
+    let read_header = |pos: usize| {
+        data.get(pos..pos + 4)
+            .and_then(|b| parse_frame_header(u32::from_be_bytes([b[0], b[1], b[2], b[3]])))
+    };
+    let Some(first) = read_header(pos) else {
+        return 0;
+    };
+    let mut count = 0;
+    while count < PROBE_MIN_OFFSET_FRAMES {
+        match read_header(pos) {
+            Some(fh)
+                if fh.version == first.version
+                    && fh.layer == first.layer
+                    && fh.sample_rate == first.sample_rate =>
+            {
+                count += 1;
+                pos += fh.frame_size as usize;
+            }
+            _ => break,
+        }
+    }
+    count
 }
 
 /// MP3 格式探测器
@@ -661,28 +953,19 @@ impl FormatProbe for Mp3Probe {
             start = 10 + size;
         }
 
-        // 在探测窗口内搜索两个连续有效帧:
+        // 在探测窗口内搜索连续有效帧:
         // 仅检查起始4字节容易漏掉带大 ID3v2/APIC 的样本.
+        // 帧不在数据起始处时, 可能是其他容器 (如 MPEG-TS 的 PES 负载) 中的音频帧,
+        // 需要更长的帧链且只给较低分数, 让真正的容器探测器胜出.
         if data.len() >= start + 8 {
-            let mut pos = start;
-            while pos + 8 <= data.len() {
-                let header =
-                    u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
-                if let Some(fh) = parse_frame_header(header) {
-                    let next_pos = pos + fh.frame_size as usize;
-                    if next_pos + 4 <= data.len() {
-                        let next_header = u32::from_be_bytes([
-                            data[next_pos],
-                            data[next_pos + 1],
-                            data[next_pos + 2],
-                            data[next_pos + 3],
-                        ]);
-                        if parse_frame_header(next_header).is_some() {
-                            return Some(crate::probe::SCORE_MAX - 5);
-                        }
-                    }
+            for pos in start..data.len() - 3 {
+                let chain = count_frame_chain(data, pos);
+                if pos == start && chain >= 2 {
+                    return Some(crate::probe::SCORE_MAX - 5);
+                }
+                if chain >= PROBE_MIN_OFFSET_FRAMES {
+                    return Some(crate::probe::SCORE_MAX - 20);
                 }
-                pos += 1;
             }
         }
 
@@ -706,7 +989,6 @@ impl FormatProbe for Mp3Probe {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     /// 构造一个简单的 MPEG-1 Layer III 帧头
     fn make_mp3_frame_header(bitrate_idx: u8, sr_idx: u8, padding: bool) -> [u8; 4] {
@@ -789,6 +1071,26 @@ mod tests {
         assert!(probe.probe(&data, None).is_some());
     }
 
+    #[test]
+    fn test_probe_offset_frames_score_lower() {
+        let probe = Mp3Probe;
+        let frame = build_mp3_frame(9, 0, false);
+        // 起始处为非帧数据, 其后仅两帧: 不足以认定为 MP3
+        let mut data = vec![0x47; 100];
+        data.extend_from_slice(&frame);
+        data.extend_from_slice(&frame);
+        assert!(probe.probe(&data, None).is_none(), "偏移处的短帧链不应识别");
+
+        // 偏移处有足够长的帧链: 识别但分数低于起始处匹配
+        data.extend_from_slice(&frame);
+        data.extend_from_slice(&frame);
+        assert_eq!(
+            probe.probe(&data, None),
+            Some(crate::probe::SCORE_MAX - 20),
+            "偏移处的长帧链应以较低分数识别"
+        );
+    }
+
     #[test]
     fn test_probe_id3_followed_by_non_mp3_frame() {
         let probe = Mp3Probe;
@@ -822,8 +1124,7 @@ mod tests {
         data.extend_from_slice(&frame);
         data.extend_from_slice(&frame);
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut demuxer = Mp3Demuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -832,6 +1133,121 @@ mod tests {
         assert_eq!(streams[0].codec_id, CodecId::Mp3);
     }
 
+    /// 构造 ID3v2 文本帧
+    fn id3v2_frame(version: u8, id: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let size = body.len() as u32;
+        let size_bytes = if version >= 4 {
+            [
+                (size >> 21) as u8 & 0x7F,
+                (size >> 14) as u8 & 0x7F,
+                (size >> 7) as u8 & 0x7F,
+                size as u8 & 0x7F,
+            ]
+        } else {
+            size.to_be_bytes()
+        };
+        let mut frame = id.to_vec();
+        frame.extend_from_slice(&size_bytes);
+        frame.extend_from_slice(&[0, 0]);
+        frame.extend_from_slice(body);
+        frame
+    }
+
+    /// 构造 ID3v2 标签 (含填充) + 两个 MP3 帧
+    fn id3v2_tagged_mp3(version: u8, frames: &[Vec<u8>], padding: usize) -> Vec<u8> {
+        let mut body: Vec<u8> = frames.concat();
+        body.extend(std::iter::repeat_n(0u8, padding));
+        let size = body.len() as u32;
+        let mut data = b"ID3".to_vec();
+        data.extend_from_slice(&[version, 0, 0]);
+        data.extend_from_slice(&[
+            (size >> 21) as u8 & 0x7F,
+            (size >> 14) as u8 & 0x7F,
+            (size >> 7) as u8 & 0x7F,
+            size as u8 & 0x7F,
+        ]);
+        data.extend_from_slice(&body);
+        let frame = build_mp3_frame(9, 0, false);
+        data.extend_from_slice(&frame);
+        data.extend_from_slice(&frame);
+        data
+    }
+
+    #[test]
+    fn test_id3v2_metadata_v24() {
+        let mut utf16 = vec![1u8, 0xFF, 0xFE];
+        utf16.extend("歌手".encode_utf16().flat_map(u16::to_le_bytes));
+        let frames = vec![
+            id3v2_frame(4, b"TIT2", b"\x03\xE6\xB5\x8B\xE8\xAF\x95\x00"),
+            // 大尺寸非文本帧 (模拟封面) 应被跳过
+            id3v2_frame(4, b"APIC", &[0u8; 300]),
+            id3v2_frame(4, b"TPE1", &utf16),
+            id3v2_frame(4, b"TALB", b"\x00Album\x00Disc"),
+        ];
+        let data = id3v2_tagged_mp3(4, &frames, 64);
+        let tag_size = data.len() - 2 * build_mp3_frame(9, 0, false).len();
+
+        let mut io = IoContext::from_bytes(data);
+        let mut demuxer = Mp3Demuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        assert_eq!(
+            io.position().unwrap(),
+            tag_size as u64,
+            "第一个 MP3 帧应位于 ID3v2 标签之后"
+        );
+        assert_eq!(
+            demuxer.metadata(),
+            &[
+                ("title".to_string(), "测试".to_string()),
+                ("artist".to_string(), "歌手".to_string()),
+                ("album".to_string(), "Album/Disc".to_string()),
+            ],
+            "应解析 TIT2/TPE1/TALB 文本帧"
+        );
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!(&pkt.data[..2], &[0xFF, 0xFB], "首个数据包应为 MP3 帧");
+    }
+
+    #[test]
+    fn test_id3v2_metadata_v23() {
+        // v2.3 帧大小为普通 32 位大端整数, 200 字节可区分 syncsafe 编码
+        let mut title = vec![0u8];
+        title.extend(std::iter::repeat_n(b'a', 199));
+        let mut utf16be = vec![2u8];
+        utf16be.extend("Artist".encode_utf16().flat_map(u16::to_be_bytes));
+        let frames = vec![
+            id3v2_frame(3, b"TIT2", &title),
+            id3v2_frame(3, b"TPE1", &utf16be),
+        ];
+        let data = id3v2_tagged_mp3(3, &frames, 0);
+        let tag_size = data.len() - 2 * build_mp3_frame(9, 0, false).len();
+
+        let mut io = IoContext::from_bytes(data);
+        let mut demuxer = Mp3Demuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        assert_eq!(io.position().unwrap(), tag_size as u64);
+        assert_eq!(
+            demuxer.metadata()[0],
+            ("title".to_string(), "a".repeat(199))
+        );
+        assert_eq!(
+            demuxer.metadata()[1],
+            ("artist".to_string(), "Artist".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_id3v2_text_encodings() {
+        assert_eq!(decode_id3v2_text(b"\x00Caf\xE9"), Some("Café".to_string()));
+        assert_eq!(decode_id3v2_text(b"\x03\x00\x00"), None, "空文本应忽略");
+        assert_eq!(decode_id3v2_text(b"\x07abc"), None, "未知编码应忽略");
+        assert_eq!(decode_id3v2_text(&[]), None);
+        assert_eq!(
+            remove_unsync(&[0xFF, 0x00, 0xE0, 0x00]),
+            vec![0xFF, 0xE0, 0x00]
+        );
+    }
+
     #[test]
     fn test_read_packets() {
         // 构造 3 个连续帧 (多加一个用于验证)
@@ -843,8 +1259,7 @@ mod tests {
             data.extend_from_slice(&frame);
         }
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut demuxer = Mp3Demuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -853,7 +1268,7 @@ mod tests {
         assert_eq!(pkt0.stream_index, 0);
         assert_eq!(pkt0.pts, 0);
         assert_eq!(pkt0.data.len(), fh.frame_size as usize);
-        assert!(pkt0.is_keyframe);
+        assert!(pkt0.is_keyframe());
 
         let pkt1 = demuxer.read_packet(&mut io).unwrap();
         assert_eq!(pkt1.pts, i64::from(fh.samples_per_frame));
@@ -870,8 +1285,7 @@ mod tests {
             data.extend_from_slice(&frame);
         }
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut demuxer = Mp3Demuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -889,6 +1303,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_xing_header_duration_and_bit_rate() {
+        // Xing 帧 (128kbps 帧头) + 10 个 64kbps 数据帧
+        let mut xing = build_mp3_frame(9, 0, false);
+        let data_frame = build_mp3_frame(5, 0, false);
+        let total_bytes = (data_frame.len() * 10) as u32;
+        let mut off = 4 + 32;
+        xing[off..off + 4].copy_from_slice(b"Xing");
+        off += 4;
+        xing[off..off + 4].copy_from_slice(&3u32.to_be_bytes());
+        off += 4;
+        xing[off..off + 4].copy_from_slice(&10u32.to_be_bytes());
+        off += 4;
+        xing[off..off + 4].copy_from_slice(&total_bytes.to_be_bytes());
+
+        let mut data = xing;
+        for _ in 0..10 {
+            data.extend_from_slice(&data_frame);
+        }
+        let mut io = IoContext::from_bytes(data);
+        let mut demuxer = Mp3Demuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        let stream = &demuxer.streams()[0];
+        assert_eq!(stream.nb_frames, 10, "帧数应来自 Xing 头");
+        assert_eq!(stream.duration, 10 * 1152);
+        let duration = demuxer.duration().unwrap();
+        assert!((duration - 11520.0 / 44100.0).abs() < 1e-9);
+        // 平均码率按数据帧计算, 而非 Xing 帧头的 128kbps
+        let expected = u64::from(total_bytes) * 8 * 44100 / 11520;
+        assert!(expected.abs_diff(64_000) < 1000, "平均码率应约为 64kbps");
+        assert_eq!(demuxer.bit_rate(), Some(expected));
+        if let StreamParams::Audio(ref a) = stream.params {
+            assert_eq!(a.bit_rate, expected);
+        } else {
+            panic!("应该是音频流参数");
+        }
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!(
+            pkt.data.len(),
+            data_frame.len(),
+            "Xing 帧不应作为数据包输出"
+        );
+    }
+
+    #[test]
+    fn test_cbr_without_vbr_header_has_no_duration() {
+        let frame = build_mp3_frame(9, 0, false);
+        let mut io = IoContext::from_bytes(frame.repeat(3));
+        let mut demuxer = Mp3Demuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        assert_eq!(demuxer.bit_rate(), Some(128_000), "CBR 使用帧头码率");
+        assert_eq!(demuxer.streams()[0].nb_frames, 0, "无 VBR 头时帧数未知");
+        assert!(demuxer.duration().is_none(), "无 VBR 头时不猜测时长");
+    }
+
     #[test]
     fn test_seek_seek_by_timestamp_then_read() {
         let frame = build_mp3_frame(9, 0, false); // MPEG1-L3, 1152 样本/帧
@@ -897,8 +1367,7 @@ mod tests {
             data.extend_from_slice(&frame);
         }
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut demuxer = Mp3Demuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -914,4 +1383,90 @@ mod tests {
         let pkt = demuxer.read_packet(&mut io).unwrap();
         assert_eq!(pkt.pts, 2 * spf, "seek 后首包 PTS 不正确");
     }
+
+    /// 构造带 TOC 的 Xing 帧 + 10 个大小不一的数据帧 (第 4 字节标记帧序号)
+    ///
+    /// `toc_override` 可覆盖指定 TOC 项, 用于验证 seek 确实按 TOC 定位.
+    fn build_xing_toc_stream(toc_override: &[(usize, usize)]) -> Vec<u8> {
+        let bitrates = [5u8, 9, 11, 5, 13, 9, 5, 14, 9, 5];
+        let frames: Vec<Vec<u8>> = bitrates
+            .iter()
+            .enumerate()
+            .map(|(i, &br)| {
+                let mut frame = build_mp3_frame(br, 0, false);
+                frame[4] = i as u8;
+                frame
+            })
+            .collect();
+        let mut xing = build_mp3_frame(9, 0, false);
+        let total_bytes = xing.len() + frames.iter().map(Vec::len).sum::<usize>();
+
+        // 帧 i 的起始字节 (相对 Xing 帧)
+        let frame_start = |i: usize| xing.len() + frames[..i].iter().map(Vec::len).sum::<usize>();
+        let mut toc = [0u8; 100];
+        for (p, entry) in toc.iter_mut().enumerate() {
+            let idx = p / 10;
+            let pos = frame_start(idx) + frames[idx].len() * (p % 10) / 10;
+            *entry = (pos * 256 / total_bytes) as u8;
+        }
+        for &(p, from) in toc_override {
+            toc[p] = toc[from];
+        }
+
+        let mut off = 4 + 32;
+        xing[off..off + 4].copy_from_slice(b"Xing");
+        off += 4;
+        xing[off..off + 4].copy_from_slice(&7u32.to_be_bytes());
+        off += 4;
+        xing[off..off + 4].copy_from_slice(&10u32.to_be_bytes());
+        off += 4;
+        xing[off..off + 4].copy_from_slice(&(total_bytes as u32).to_be_bytes());
+        off += 4;
+        xing[off..off + 100].copy_from_slice(&toc);
+
+        let mut data = xing;
+        for frame in &frames {
+            data.extend_from_slice(frame);
+        }
+        data
+    }
+
+    #[test]
+    fn test_xing_toc_duration_and_seek() {
+        let mut io = IoContext::from_bytes(build_xing_toc_stream(&[]));
+        let mut demuxer = Mp3Demuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        let duration = demuxer.duration().unwrap();
+        assert!(
+            (duration - 10.0 * 1152.0 / 44100.0).abs() < 1e-9,
+            "时长应为 帧数 x 每帧采样数 / 采样率, 实际 {duration}"
+        );
+
+        for target in [5i64, 3, 9, 0] {
+            demuxer
+                .seek(&mut io, 0, target * 1152, SeekFlags::default())
+                .unwrap();
+            let pkt = demuxer.read_packet(&mut io).unwrap();
+            assert_eq!(pkt.pts, target * 1152, "seek 后首包 PTS 不正确");
+            assert_eq!(
+                i64::from(pkt.data[4]),
+                target,
+                "TOC 定位后应同步到目标帧起始"
+            );
+        }
+    }
+
+    #[test]
+    fn test_xing_toc_takes_precedence_over_scan() {
+        // 将 50% 处的 TOC 项指向第 7 帧: seek 按 TOC 定位, 而非逐帧扫描
+        let mut io = IoContext::from_bytes(build_xing_toc_stream(&[(50, 70), (51, 71)]));
+        let mut demuxer = Mp3Demuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        demuxer
+            .seek(&mut io, 0, 5 * 1152, SeekFlags::default())
+            .unwrap();
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!(pkt.data[4], 7, "应读到 TOC 指向的帧");
+        assert_eq!(pkt.pts, 5 * 1152, "PTS 按目标帧计算");
+    }
 }
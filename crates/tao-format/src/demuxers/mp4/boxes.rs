@@ -58,6 +58,20 @@ pub enum BoxType {
     Stss,
     /// ctts - 合成时间偏移
     Ctts,
+    /// mvex - 影片扩展 (分片 MP4)
+    Mvex,
+    /// trex - 轨道分片默认值
+    Trex,
+    /// moof - 影片分片
+    Moof,
+    /// traf - 轨道分片
+    Traf,
+    /// tfhd - 轨道分片头部
+    Tfhd,
+    /// tfdt - 轨道分片起始解码时间
+    Tfdt,
+    /// trun - 轨道分片采样列表
+    Trun,
     /// mdat - 媒体数据
     Mdat,
     /// free - 自由空间
@@ -92,6 +106,13 @@ impl BoxType {
             b"co64" => Self::Co64,
             b"stss" => Self::Stss,
             b"ctts" => Self::Ctts,
+            b"mvex" => Self::Mvex,
+            b"trex" => Self::Trex,
+            b"moof" => Self::Moof,
+            b"traf" => Self::Traf,
+            b"tfhd" => Self::Tfhd,
+            b"tfdt" => Self::Tfdt,
+            b"trun" => Self::Trun,
             b"mdat" => Self::Mdat,
             b"free" => Self::Free,
             b"skip" => Self::Skip,
@@ -194,7 +215,6 @@ impl FtypBox {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     #[test]
     fn test_box_type_identify() {
@@ -212,8 +232,7 @@ mod tests {
         data.extend_from_slice(b"ftyp");
         data.extend_from_slice(&[0u8; 12]); // content
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
 
         let header = read_box_header(&mut io).unwrap();
         assert_eq!(header.box_type, BoxType::Ftyp);
@@ -231,8 +250,7 @@ mod tests {
         data.extend_from_slice(&1000u32.to_be_bytes()); // 扩展大小低 32 位
         data.extend_from_slice(&[0u8; 984]); // 内容
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
 
         let header = read_box_header(&mut io).unwrap();
         assert_eq!(header.box_type, BoxType::Mdat);
@@ -249,8 +267,7 @@ mod tests {
         content.extend_from_slice(b"isom"); // compatible
         content.extend_from_slice(b"mp41"); // compatible
 
-        let backend = MemoryBackend::from_data(content.clone());
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(content.clone());
 
         let ftyp = FtypBox::parse(&mut io, content.len() as u64).unwrap();
         assert_eq!(&ftyp.major_brand, b"isom");
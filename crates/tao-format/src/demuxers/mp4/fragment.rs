@@ -0,0 +1,299 @@
+//! 分片 MP4 (fMP4 / DASH / CMAF) 解析.
+//!
+//! 分片文件的 moov 中采样表为空, 由 mvex/trex 给出各轨道默认值,
+//! 采样分布在后续的 moof + mdat 中:
+//! ```text
+//! moof                  影片分片
+//! ├── mfhd              分片序号
+//! └── traf              轨道分片 (每个轨道一个)
+//!     ├── tfhd          轨道分片头 (轨道 ID, 基准偏移, 默认值)
+//!     ├── tfdt          分片起始解码时间
+//!     └── trun          采样列表 (大小/时长/标志/合成偏移)
+//! mdat                  媒体数据
+//! ```
+
+use tao_core::TaoResult;
+
+use crate::io::IoContext;
+
+use super::boxes::{BoxType, read_box_header};
+use super::sample_table::FragmentSample;
+
+/// sample_flags 中的 sample_is_non_sync_sample 位
+const SAMPLE_FLAG_NON_SYNC: u32 = 0x0001_0000;
+
+// tfhd flags
+const TFHD_BASE_DATA_OFFSET: u32 = 0x00_0001;
+const TFHD_SAMPLE_DESCRIPTION_INDEX: u32 = 0x00_0002;
+const TFHD_DEFAULT_DURATION: u32 = 0x00_0008;
+const TFHD_DEFAULT_SIZE: u32 = 0x00_0010;
+const TFHD_DEFAULT_FLAGS: u32 = 0x00_0020;
+const TFHD_DEFAULT_BASE_IS_MOOF: u32 = 0x02_0000;
+
+// trun flags
+const TRUN_DATA_OFFSET: u32 = 0x00_0001;
+const TRUN_FIRST_SAMPLE_FLAGS: u32 = 0x00_0004;
+const TRUN_SAMPLE_DURATION: u32 = 0x00_0100;
+const TRUN_SAMPLE_SIZE: u32 = 0x00_0200;
+const TRUN_SAMPLE_FLAGS: u32 = 0x00_0400;
+const TRUN_SAMPLE_CTS_OFFSET: u32 = 0x00_0800;
+
+/// 轨道分片默认值 (trex)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrackExtends {
+    /// 轨道 ID
+    pub track_id: u32,
+    /// 默认采样时长
+    pub default_sample_duration: u32,
+    /// 默认采样大小
+    pub default_sample_size: u32,
+    /// 默认采样标志
+    pub default_sample_flags: u32,
+}
+
+/// 一个 traf 中解析出的采样
+#[derive(Debug, Clone)]
+pub struct TrackFragment {
+    /// 轨道 ID
+    pub track_id: u32,
+    /// tfdt 给出的起始解码时间, 缺失时由调用方接续上一分片
+    pub base_decode_time: Option<i64>,
+    /// 采样列表, dts 相对于分片起始
+    pub samples: Vec<FragmentSample>,
+}
+
+/// 解析 trex (Track Extends Box)
+pub fn parse_trex(io: &mut IoContext) -> TaoResult<TrackExtends> {
+    let _version = io.read_u8()?;
+    let _flags = io.read_bytes(3)?;
+    let track_id = io.read_u32_be()?;
+    let _default_sample_description_index = io.read_u32_be()?;
+    Ok(TrackExtends {
+        track_id,
+        default_sample_duration: io.read_u32_be()?,
+        default_sample_size: io.read_u32_be()?,
+        default_sample_flags: io.read_u32_be()?,
+    })
+}
+
+/// 解析 moof (Movie Fragment Box)
+///
+/// `moof_start` 为 moof box 头部的文件偏移, 是默认的数据基准偏移.
+pub fn parse_moof(
+    io: &mut IoContext,
+    moof_start: u64,
+    moof_end: u64,
+    trex: &[TrackExtends],
+) -> TaoResult<Vec<TrackFragment>> {
+    let mut fragments = Vec::new();
+    // 未显式给出基准偏移时, 后一个 traf 的数据紧接前一个 traf 的数据
+    let mut implicit_offset = moof_start;
+
+    while io.position()? < moof_end {
+        let header = match read_box_header(io) {
+            Ok(h) => h,
+            Err(_) => break,
+        };
+        let box_end = io.position()? + header.content_size();
+
+        if header.box_type == BoxType::Traf
+            && let Some(fragment) = parse_traf(io, box_end, moof_start, &mut implicit_offset, trex)?
+        {
+            fragments.push(fragment);
+        }
+
+        io.seek(std::io::SeekFrom::Start(box_end))?;
+    }
+
+    Ok(fragments)
+}
+
+/// tfhd 中解析出的当前轨道分片参数
+struct TrackFragmentHeader {
+    track_id: u32,
+    base_data_offset: u64,
+    default_sample_duration: u32,
+    default_sample_size: u32,
+    default_sample_flags: u32,
+}
+
+/// 解析 traf (Track Fragment Box), tfhd 缺失时返回 None
+fn parse_traf(
+    io: &mut IoContext,
+    traf_end: u64,
+    moof_start: u64,
+    implicit_offset: &mut u64,
+    trex: &[TrackExtends],
+) -> TaoResult<Option<TrackFragment>> {
+    let mut tfhd: Option<TrackFragmentHeader> = None;
+    let mut base_decode_time = None;
+    let mut samples = Vec::new();
+    let mut next_dts = 0i64;
+    let mut data_cursor = 0u64;
+
+    while io.position()? < traf_end {
+        let header = match read_box_header(io) {
+            Ok(h) => h,
+            Err(_) => break,
+        };
+        let box_end = io.position()? + header.content_size();
+
+        match header.box_type {
+            BoxType::Tfhd => {
+                let parsed = parse_tfhd(io, moof_start, *implicit_offset, trex)?;
+                data_cursor = parsed.base_data_offset;
+                tfhd = Some(parsed);
+            }
+            BoxType::Tfdt => {
+                let version = io.read_u8()?;
+                let _flags = io.read_bytes(3)?;
+                let time = if version == 1 {
+                    let hi = u64::from(io.read_u32_be()?);
+                    let lo = u64::from(io.read_u32_be()?);
+                    (hi << 32) | lo
+                } else {
+                    u64::from(io.read_u32_be()?)
+                };
+                base_decode_time = Some(time as i64);
+            }
+            BoxType::Trun => {
+                if let Some(tfhd) = &tfhd {
+                    parse_trun(io, tfhd, &mut data_cursor, &mut next_dts, &mut samples)?;
+                    *implicit_offset = data_cursor;
+                }
+            }
+            _ => {}
+        }
+
+        io.seek(std::io::SeekFrom::Start(box_end))?;
+    }
+
+    Ok(tfhd.map(|tfhd| TrackFragment {
+        track_id: tfhd.track_id,
+        base_decode_time,
+        samples,
+    }))
+}
+
+/// 解析 tfhd (Track Fragment Header Box), 未给出的默认值取自 trex
+fn parse_tfhd(
+    io: &mut IoContext,
+    moof_start: u64,
+    implicit_offset: u64,
+    trex: &[TrackExtends],
+) -> TaoResult<TrackFragmentHeader> {
+    let _version = io.read_u8()?;
+    let flags = io.read_u24_be()?;
+    let track_id = io.read_u32_be()?;
+    let defaults = trex
+        .iter()
+        .find(|t| t.track_id == track_id)
+        .copied()
+        .unwrap_or_default();
+
+    let base_data_offset = if flags & TFHD_BASE_DATA_OFFSET != 0 {
+        let hi = u64::from(io.read_u32_be()?);
+        let lo = u64::from(io.read_u32_be()?);
+        (hi << 32) | lo
+    } else if flags & TFHD_DEFAULT_BASE_IS_MOOF != 0 {
+        moof_start
+    } else {
+        implicit_offset
+    };
+    if flags & TFHD_SAMPLE_DESCRIPTION_INDEX != 0 {
+        let _sample_description_index = io.read_u32_be()?;
+    }
+    let default_sample_duration = if flags & TFHD_DEFAULT_DURATION != 0 {
+        io.read_u32_be()?
+    } else {
+        defaults.default_sample_duration
+    };
+    let default_sample_size = if flags & TFHD_DEFAULT_SIZE != 0 {
+        io.read_u32_be()?
+    } else {
+        defaults.default_sample_size
+    };
+    let default_sample_flags = if flags & TFHD_DEFAULT_FLAGS != 0 {
+        io.read_u32_be()?
+    } else {
+        defaults.default_sample_flags
+    };
+
+    Ok(TrackFragmentHeader {
+        track_id,
+        base_data_offset,
+        default_sample_duration,
+        default_sample_size,
+        default_sample_flags,
+    })
+}
+
+/// 解析 trun (Track Fragment Run Box), 追加采样并推进数据偏移与解码时间
+fn parse_trun(
+    io: &mut IoContext,
+    tfhd: &TrackFragmentHeader,
+    data_cursor: &mut u64,
+    next_dts: &mut i64,
+    samples: &mut Vec<FragmentSample>,
+) -> TaoResult<()> {
+    let _version = io.read_u8()?;
+    let flags = io.read_u24_be()?;
+    let sample_count = io.read_u32_be()?;
+
+    let mut offset = if flags & TRUN_DATA_OFFSET != 0 {
+        let data_offset = io.read_i32_be()?;
+        tfhd.base_data_offset
+            .wrapping_add_signed(i64::from(data_offset))
+    } else {
+        *data_cursor
+    };
+    let first_sample_flags = if flags & TRUN_FIRST_SAMPLE_FLAGS != 0 {
+        Some(io.read_u32_be()?)
+    } else {
+        None
+    };
+
+    samples.reserve(sample_count as usize);
+    for i in 0..sample_count {
+        let duration = if flags & TRUN_SAMPLE_DURATION != 0 {
+            io.read_u32_be()?
+        } else {
+            tfhd.default_sample_duration
+        };
+        let size = if flags & TRUN_SAMPLE_SIZE != 0 {
+            io.read_u32_be()?
+        } else {
+            tfhd.default_sample_size
+        };
+        let mut sample_flags = if flags & TRUN_SAMPLE_FLAGS != 0 {
+            io.read_u32_be()?
+        } else {
+            tfhd.default_sample_flags
+        };
+        if i == 0
+            && let Some(first) = first_sample_flags
+        {
+            sample_flags = first;
+        }
+        // version 0 规定为无符号, 与 FFmpeg 一致按有符号读取, 兼容写入负值的文件
+        let cts_offset = if flags & TRUN_SAMPLE_CTS_OFFSET != 0 {
+            io.read_i32_be()?
+        } else {
+            0
+        };
+
+        samples.push(FragmentSample {
+            offset,
+            size,
+            dts: *next_dts,
+            duration,
+            cts_offset,
+            is_sync: sample_flags & SAMPLE_FLAG_NON_SYNC == 0,
+        });
+        offset += u64::from(size);
+        *next_dts += i64::from(duration);
+    }
+
+    *data_cursor = offset;
+    Ok(())
+}
@@ -23,16 +23,19 @@
 //!                 ├── co64  块偏移 (64位)
 //!                 ├── stss  同步采样 (关键帧)
 //!                 └── ctts  合成时间偏移
+//! └── mvex              影片扩展 (分片 MP4, 含各轨道 trex 默认值)
+//! moof                  影片分片 (见 `fragment` 模块)
 //! mdat                  媒体数据
 //! ```
 
 mod boxes;
+mod fragment;
 mod sample_table;
 
 use bytes::Bytes;
 use log::debug;
 use tao_codec::{CodecId, Packet};
-use tao_core::{ChannelLayout, MediaType, Rational, SampleFormat, TaoError, TaoResult};
+use tao_core::{ChannelLayout, MediaType, Rational, SampleFormat, SmpteTc, TaoError, TaoResult};
 
 use crate::demuxer::{Demuxer, SeekFlags};
 use crate::format_id::FormatId;
@@ -41,6 +44,7 @@ use crate::probe::FormatProbe;
 use crate::stream::{AudioStreamParams, Stream, StreamParams, VideoStreamParams};
 
 use self::boxes::{BoxType, FtypBox, read_box_header};
+use self::fragment::TrackExtends;
 use self::sample_table::SampleTable;
 
 /// MP4 解封装器
@@ -49,6 +53,10 @@ pub struct Mp4Demuxer {
     streams: Vec<Stream>,
     /// 每个流的采样表
     sample_tables: Vec<SampleTable>,
+    /// 每个流对应的轨道 ID (tkhd), 用于匹配分片中的 tfhd
+    track_ids: Vec<u32>,
+    /// 分片轨道默认值 (mvex/trex)
+    track_extends: Vec<TrackExtends>,
     /// 当前读取的全局采样索引 (所有流中的下一个采样)
     current_sample: Vec<u32>,
     /// 每个流的 PTS 偏移 (由 elst media_time 导出, 单位为该流 time_base)
@@ -67,6 +75,8 @@ impl Mp4Demuxer {
         Ok(Box::new(Self {
             streams: Vec::new(),
             sample_tables: Vec::new(),
+            track_ids: Vec::new(),
+            track_extends: Vec::new(),
             current_sample: Vec::new(),
             stream_pts_offset: Vec::new(),
             mdat_offset: 0,
@@ -93,6 +103,9 @@ impl Mp4Demuxer {
                 BoxType::Trak => {
                     self.parse_trak(io, box_end, timescale)?;
                 }
+                BoxType::Mvex => {
+                    self.parse_mvex(io, box_end)?;
+                }
                 _ => {}
             }
 
@@ -103,6 +116,78 @@ impl Mp4Demuxer {
         Ok(())
     }
 
+    /// 解析 mvex (Movie Extends Box), 收集各轨道的 trex 默认值
+    fn parse_mvex(&mut self, io: &mut IoContext, mvex_end: u64) -> TaoResult<()> {
+        while io.position()? < mvex_end {
+            let header = match read_box_header(io) {
+                Ok(h) => h,
+                Err(_) => break,
+            };
+            let box_end = io.position()? + header.content_size();
+            if header.box_type == BoxType::Trex {
+                self.track_extends.push(fragment::parse_trex(io)?);
+            }
+            io.seek(std::io::SeekFrom::Start(box_end))?;
+        }
+        Ok(())
+    }
+
+    /// 解析 moof, 将各 traf 的采样追加到对应轨道的采样表
+    fn parse_moof(&mut self, io: &mut IoContext, moof_start: u64, moof_end: u64) -> TaoResult<()> {
+        let fragments = fragment::parse_moof(io, moof_start, moof_end, &self.track_extends)?;
+        for frag in fragments {
+            let Some(idx) = self.track_ids.iter().position(|&id| id == frag.track_id) else {
+                debug!("MP4: moof 中的轨道 id={} 不存在, 忽略", frag.track_id);
+                continue;
+            };
+            let st = &mut self.sample_tables[idx];
+            let base_dts = frag
+                .base_decode_time
+                .unwrap_or_else(|| st.next_fragment_dts());
+            for mut sample in frag.samples {
+                sample.dts += base_dts;
+                st.push_fragment_sample(sample);
+            }
+        }
+        Ok(())
+    }
+
+    /// 分片解析完成后, 由全部采样更新帧数与时长
+    ///
+    /// 分片文件的 mdhd/mvhd 时长通常为 0, 此时以最后一个采样的结束时间为准.
+    fn finalize_fragments(&mut self) {
+        let mut max_duration = 0.0f64;
+        for (stream, st) in self.streams.iter_mut().zip(&self.sample_tables) {
+            stream.nb_frames = u64::from(st.sample_count());
+            let end = st.next_fragment_dts();
+            if stream.duration <= 0 && end > 0 {
+                stream.duration = end;
+            }
+            if stream.duration > 0 {
+                max_duration = max_duration.max(stream.duration as f64 * stream.time_base.to_f64());
+            }
+        }
+        if self.file_duration.is_none_or(|d| d <= 0.0) && max_duration > 0.0 {
+            self.file_duration = Some(max_duration);
+        }
+    }
+
+    /// 由采样总字节数与轨道时长计算各流的平均码率
+    fn update_bit_rates(&mut self) {
+        for (stream, st) in self.streams.iter_mut().zip(&self.sample_tables) {
+            if stream.duration <= 0 || !stream.time_base.is_valid() {
+                continue;
+            }
+            let seconds = stream.duration as f64 * stream.time_base.to_f64();
+            let bit_rate = (st.total_size() as f64 * 8.0 / seconds) as u64;
+            match &mut stream.params {
+                StreamParams::Video(v) => v.bit_rate = bit_rate,
+                StreamParams::Audio(a) => a.bit_rate = bit_rate,
+                _ => {}
+            }
+        }
+    }
+
     /// 解析 mvhd (Movie Header Box)
     fn parse_mvhd(&mut self, io: &mut IoContext) -> TaoResult<u32> {
         let version = io.read_u8()?;
@@ -210,6 +295,7 @@ impl Mp4Demuxer {
 
         self.streams.push(stream);
         self.sample_tables.push(sample_table);
+        self.track_ids.push(track_id);
         self.current_sample.push(0);
         self.stream_pts_offset.push(pts_offset);
 
@@ -453,6 +539,8 @@ impl Mp4Demuxer {
                         frame_rate: Rational::new(0, 1),
                         sample_aspect_ratio: Rational::new(1, 1),
                         bit_rate: 0,
+                        color_space: st.color_space,
+                        color_range: st.color_range,
                     }),
                 )
             }
@@ -467,11 +555,64 @@ impl Mp4Demuxer {
                         sample_format: SampleFormat::F32,
                         bit_rate: 0,
                         frame_size: 0,
+                        block_align: 0,
                     }),
                 )
             }
-            _ => (MediaType::Data, CodecId::None, StreamParams::Other),
+            b"tmcd" | b"meta" | b"hint" | b"text" | b"sbtl" | b"subt" => {
+                (MediaType::Data, CodecId::None, StreamParams::Other)
+            }
+            _ => (MediaType::Unknown(0), CodecId::None, StreamParams::Other),
+        }
+    }
+
+    /// 读取 tmcd 轨道首个采样 (起始帧号) 并写入 `timecode` 元数据
+    ///
+    /// 时间码同时附加到尚无时间码的视频流, 与 FFmpeg 行为一致.
+    fn resolve_timecodes(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        for idx in 0..self.sample_tables.len() {
+            let st = &self.sample_tables[idx];
+            let Some(tmcd) = st.timecode else {
+                continue;
+            };
+            if st.sample_count() == 0 || st.sample_size(0) < 4 {
+                continue;
+            }
+            let fps = if tmcd.number_of_frames > 0 {
+                Rational::new(i32::from(tmcd.number_of_frames), 1)
+            } else if tmcd.frame_duration > 0 {
+                Rational::new(tmcd.timescale as i32, tmcd.frame_duration as i32)
+            } else {
+                continue;
+            };
+
+            io.seek(std::io::SeekFrom::Start(st.sample_offset(0)))?;
+            let frame_number = match io.read_u32_be() {
+                Ok(v) => v,
+                Err(TaoError::Eof) => {
+                    debug!("MP4: tmcd 采样数据截断, 忽略时间码");
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let tc = SmpteTc::from_frame_number(u64::from(frame_number), fps, tmcd.drop_frame);
+            debug!("MP4: 轨道 #{idx} 时间码 {tc}");
+
+            let value = tc.to_string();
+            self.streams[idx]
+                .metadata
+                .push(("timecode".to_string(), value.clone()));
+            for stream in &mut self.streams {
+                if stream.media_type == MediaType::Video
+                    && !stream.metadata.iter().any(|(k, _)| k == "timecode")
+                {
+                    stream
+                        .metadata
+                        .push(("timecode".to_string(), value.clone()));
+                }
+            }
         }
+        Ok(())
     }
 
     /// 找到最早的下一个采样 (跨所有流)
@@ -524,6 +665,7 @@ impl Demuxer for Mp4Demuxer {
         // 扫描顶层 box
         let file_size = io.size().unwrap_or(u64::MAX);
 
+        let mut has_fragments = false;
         loop {
             let pos = io.position()?;
             if pos >= file_size {
@@ -551,6 +693,15 @@ impl Demuxer for Mp4Demuxer {
                 BoxType::Moov => {
                     self.parse_moov(io, box_end)?;
                 }
+                BoxType::Moof => {
+                    // 初始化段之后追加的媒体段 (styp/sidx/moof/mdat) 依次解析
+                    if self.streams.is_empty() {
+                        debug!("MP4: moof 出现在 moov 之前, 忽略");
+                    } else {
+                        self.parse_moof(io, pos, box_end)?;
+                        has_fragments = true;
+                    }
+                }
                 BoxType::Mdat => {
                     self.mdat_offset = content_start;
                     self.mdat_size = box_end - content_start;
@@ -561,10 +712,17 @@ impl Demuxer for Mp4Demuxer {
             io.seek(std::io::SeekFrom::Start(box_end))?;
         }
 
+        if has_fragments {
+            self.finalize_fragments();
+        }
+
         if self.streams.is_empty() {
             return Err(TaoError::InvalidData("MP4 文件中未找到任何轨道".into()));
         }
 
+        self.update_bit_rates();
+        self.resolve_timecodes(io)?;
+
         debug!("打开 MP4: {} 个轨道", self.streams.len());
         Ok(())
     }
@@ -595,7 +753,7 @@ impl Demuxer for Mp4Demuxer {
         pkt.stream_index = stream_idx;
         pkt.pts = pts;
         pkt.dts = dts;
-        pkt.is_keyframe = is_keyframe;
+        pkt.set_keyframe(is_keyframe);
 
         if let Some(stream) = self.streams.get(stream_idx) {
             pkt.time_base = stream.time_base;
@@ -681,7 +839,21 @@ impl Demuxer for Mp4Demuxer {
     }
 
     fn duration(&self) -> Option<f64> {
-        self.file_duration
+        self.file_duration.filter(|&d| d > 0.0)
+    }
+
+    /// 各音视频流平均码率之和
+    fn bit_rate(&self) -> Option<u64> {
+        let total: u64 = self
+            .streams
+            .iter()
+            .map(|s| match &s.params {
+                StreamParams::Video(v) => v.bit_rate,
+                StreamParams::Audio(a) => a.bit_rate,
+                _ => 0,
+            })
+            .sum();
+        (total > 0).then_some(total)
     }
 }
 
@@ -729,7 +901,6 @@ impl FormatProbe for Mp4Probe {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     #[test]
     fn test_probe_mp4_ftyp() {
@@ -756,8 +927,7 @@ mod tests {
     fn test_parse_minimal_mp4() {
         // 构造最小的 MP4 (ftyp + moov 含一个空 trak)
         let mp4 = build_minimal_mp4();
-        let backend = MemoryBackend::from_data(mp4);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(mp4);
 
         let mut demuxer = Mp4Demuxer::create().unwrap();
         let result = demuxer.open(&mut io);
@@ -777,8 +947,7 @@ mod tests {
         data.extend_from_slice(&1i16.to_be_bytes()); // media_rate_integer
         data.extend_from_slice(&0i16.to_be_bytes()); // media_rate_fraction
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut media_time = -1i64;
         Mp4Demuxer::parse_elst(&mut io, &mut media_time).unwrap();
         assert_eq!(media_time, 1001, "elst media_time 解析错误");
@@ -801,13 +970,376 @@ mod tests {
         data.extend_from_slice(&1i16.to_be_bytes()); // media_rate_integer
         data.extend_from_slice(&0i16.to_be_bytes()); // media_rate_fraction
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut media_time = -1i64;
         Mp4Demuxer::parse_elst(&mut io, &mut media_time).unwrap();
         assert_eq!(media_time, 500, "应跳过负 media_time, 选择首个有效编辑项");
     }
 
+    /// 测试轨道采样
+    struct TestSample {
+        data: Vec<u8>,
+        duration: u32,
+        cts_offset: i32,
+        is_sync: bool,
+    }
+
+    /// 测试轨道: (track_id, handler, timescale, 采样)
+    struct TestTrack {
+        track_id: u32,
+        handler: &'static [u8; 4],
+        timescale: u32,
+        samples: Vec<TestSample>,
+    }
+
+    /// 视频 6 帧 (90kHz, 含 B 帧重排序, 第 0/3 帧为关键帧) + 音频 4 帧 (48kHz)
+    fn build_test_tracks() -> Vec<TestTrack> {
+        let cts = [6000, 0, 3000, 6000, 0, 3000];
+        let video = (0..6u8)
+            .map(|i| TestSample {
+                data: vec![0x10 + i; 20 + usize::from(i) * 3],
+                duration: 3000,
+                cts_offset: cts[usize::from(i)],
+                is_sync: i % 3 == 0,
+            })
+            .collect();
+        let audio = (0..4u8)
+            .map(|i| TestSample {
+                data: vec![0xA0 + i; 10 + usize::from(i)],
+                duration: 1024,
+                cts_offset: 0,
+                is_sync: true,
+            })
+            .collect();
+        vec![
+            TestTrack {
+                track_id: 1,
+                handler: b"vide",
+                timescale: 90000,
+                samples: video,
+            },
+            TestTrack {
+                track_id: 2,
+                handler: b"soun",
+                timescale: 48000,
+                samples: audio,
+            },
+        ]
+    }
+
+    /// 构造轨道 trak, `fragmented` 为真时采样表为空且时长为 0
+    fn build_test_trak(track: &TestTrack, chunk_offsets: &[u32], fragmented: bool) -> Vec<u8> {
+        let mut tkhd = Vec::new();
+        tkhd.extend_from_slice(&[0u8; 8]); // creation/modification
+        tkhd.extend_from_slice(&track.track_id.to_be_bytes());
+        tkhd.extend_from_slice(&[0u8; 4 + 4 + 8 + 8 + 36]);
+        tkhd.extend_from_slice(&(64u32 << 16).to_be_bytes());
+        tkhd.extend_from_slice(&(48u32 << 16).to_be_bytes());
+
+        let duration: u32 = track.samples.iter().map(|s| s.duration).sum();
+        let mut mdhd = Vec::new();
+        mdhd.extend_from_slice(&[0u8; 8]);
+        mdhd.extend_from_slice(&track.timescale.to_be_bytes());
+        mdhd.extend_from_slice(&(if fragmented { 0 } else { duration }).to_be_bytes());
+        mdhd.extend_from_slice(&[0u8; 4]);
+
+        let mut hdlr = vec![0u8; 4];
+        hdlr.extend_from_slice(track.handler);
+        hdlr.extend_from_slice(&[0u8; 13]);
+
+        let mut entry = Vec::new();
+        entry.extend_from_slice(&[0u8; 6]);
+        entry.extend_from_slice(&1u16.to_be_bytes()); // data_reference_index
+        let fourcc = if track.handler == b"vide" {
+            entry.extend_from_slice(&[0u8; 16]);
+            entry.extend_from_slice(&64u16.to_be_bytes());
+            entry.extend_from_slice(&48u16.to_be_bytes());
+            entry.extend_from_slice(&[0u8; 50]);
+            b"avc1"
+        } else {
+            entry.extend_from_slice(&[0u8; 8]);
+            entry.extend_from_slice(&2u16.to_be_bytes()); // channel_count
+            entry.extend_from_slice(&16u16.to_be_bytes()); // sample_size
+            entry.extend_from_slice(&[0u8; 4]);
+            entry.extend_from_slice(&(track.timescale << 16).to_be_bytes());
+            b"mp4a"
+        };
+        let mut stsd = 1u32.to_be_bytes().to_vec();
+        stsd.extend_from_slice(&build_box(fourcc, &entry));
+
+        let samples: &[TestSample] = if fragmented { &[] } else { &track.samples };
+        let table = |entries: Vec<[u32; 2]>| {
+            let mut d = (entries.len() as u32).to_be_bytes().to_vec();
+            for [a, b] in entries {
+                d.extend_from_slice(&a.to_be_bytes());
+                d.extend_from_slice(&b.to_be_bytes());
+            }
+            d
+        };
+        let mut stbl = build_fullbox(b"stsd", 0, 0, &stsd);
+        stbl.extend(build_fullbox(
+            b"stts",
+            0,
+            0,
+            &table(samples.iter().map(|s| [1, s.duration]).collect()),
+        ));
+        let per_chunk = (samples.len() / chunk_offsets.len().max(1)) as u32;
+        let stsc = if samples.is_empty() {
+            0u32.to_be_bytes().to_vec()
+        } else {
+            let mut d = 1u32.to_be_bytes().to_vec();
+            for v in [1, per_chunk, 1] {
+                d.extend_from_slice(&v.to_be_bytes());
+            }
+            d
+        };
+        stbl.extend(build_fullbox(b"stsc", 0, 0, &stsc));
+        let mut stsz = 0u32.to_be_bytes().to_vec();
+        stsz.extend_from_slice(&(samples.len() as u32).to_be_bytes());
+        for sample in samples {
+            stsz.extend_from_slice(&(sample.data.len() as u32).to_be_bytes());
+        }
+        stbl.extend(build_fullbox(b"stsz", 0, 0, &stsz));
+        let mut stco = (chunk_offsets.len() as u32).to_be_bytes().to_vec();
+        for offset in chunk_offsets {
+            stco.extend_from_slice(&offset.to_be_bytes());
+        }
+        stbl.extend(build_fullbox(b"stco", 0, 0, &stco));
+        if !fragmented && track.handler == b"vide" {
+            let sync: Vec<u32> = (1..=samples.len() as u32)
+                .filter(|&n| samples[n as usize - 1].is_sync)
+                .collect();
+            let mut stss = (sync.len() as u32).to_be_bytes().to_vec();
+            for n in sync {
+                stss.extend_from_slice(&n.to_be_bytes());
+            }
+            stbl.extend(build_fullbox(b"stss", 0, 0, &stss));
+            stbl.extend(build_fullbox(
+                b"ctts",
+                0,
+                0,
+                &table(samples.iter().map(|s| [1, s.cts_offset as u32]).collect()),
+            ));
+        }
+
+        let minf = build_box(b"minf", &build_box(b"stbl", &stbl));
+        let mut mdia = build_fullbox(b"mdhd", 0, 0, &mdhd);
+        mdia.extend(build_fullbox(b"hdlr", 0, 0, &hdlr));
+        mdia.extend(minf);
+        let mut trak = build_fullbox(b"tkhd", 0, 0, &tkhd);
+        trak.extend(build_box(b"mdia", &mdia));
+        build_box(b"trak", &trak)
+    }
+
+    fn build_test_ftyp() -> Vec<u8> {
+        build_box(b"ftyp", b"iso5\0\0\0\0iso5dash")
+    }
+
+    fn build_test_mvhd() -> Vec<u8> {
+        let mut mvhd = vec![0u8; 8];
+        mvhd.extend_from_slice(&1000u32.to_be_bytes());
+        mvhd.extend_from_slice(&[0u8; 84]);
+        build_fullbox(b"mvhd", 0, 0, &mvhd)
+    }
+
+    /// 普通 MP4: 每个轨道分 2 个块, 与分片版本的数据布局一致
+    fn build_flat_mp4(tracks: &[TestTrack]) -> Vec<u8> {
+        let build_moov = |offsets: &[Vec<u32>]| {
+            let mut moov = build_test_mvhd();
+            for (track, offsets) in tracks.iter().zip(offsets) {
+                moov.extend(build_test_trak(track, offsets, false));
+            }
+            build_box(b"moov", &moov)
+        };
+        let ftyp = build_test_ftyp();
+        let moov_len = build_moov(&[vec![0, 0], vec![0, 0]]).len();
+
+        let mut mdat = Vec::new();
+        let mut offsets = vec![Vec::new(), Vec::new()];
+        let data_start = (ftyp.len() + moov_len + 8) as u32;
+        for half in 0..2 {
+            for (ti, track) in tracks.iter().enumerate() {
+                let n = track.samples.len() / 2;
+                offsets[ti].push(data_start + mdat.len() as u32);
+                for sample in &track.samples[half * n..(half + 1) * n] {
+                    mdat.extend_from_slice(&sample.data);
+                }
+            }
+        }
+        let mut file = ftyp;
+        file.extend(build_moov(&offsets));
+        file.extend(build_box(b"mdat", &mdat));
+        file
+    }
+
+    /// 分片 MP4: 初始化段 + 2 个媒体段 (第 2 段带 styp, 模拟追加的 DASH 段)
+    fn build_fragmented_mp4(tracks: &[TestTrack]) -> Vec<u8> {
+        let mut moov = build_test_mvhd();
+        for track in tracks {
+            moov.extend(build_test_trak(track, &[], true));
+        }
+        let mut mvex = Vec::new();
+        for track in tracks {
+            let mut trex = track.track_id.to_be_bytes().to_vec();
+            trex.extend_from_slice(&1u32.to_be_bytes());
+            // 音频使用 trex 默认时长, 视频默认为非同步采样
+            let (duration, flags) = if track.handler == b"soun" {
+                (1024u32, 0u32)
+            } else {
+                (0, 0x0001_0000)
+            };
+            trex.extend_from_slice(&duration.to_be_bytes());
+            trex.extend_from_slice(&0u32.to_be_bytes());
+            trex.extend_from_slice(&flags.to_be_bytes());
+            mvex.extend(build_fullbox(b"trex", 0, 0, &trex));
+        }
+        moov.extend(build_box(b"mvex", &mvex));
+
+        let mut file = build_test_ftyp();
+        file.extend(build_box(b"moov", &moov));
+
+        for half in 0..2usize {
+            if half == 1 {
+                file.extend(build_box(b"styp", b"msdh\0\0\0\0msdhmsix"));
+            }
+            let build_moof = |data_offsets: &[i32]| {
+                let mut moof = build_fullbox(b"mfhd", 0, 0, &(half as u32 + 1).to_be_bytes());
+                for (ti, track) in tracks.iter().enumerate() {
+                    let n = track.samples.len() / 2;
+                    let samples = &track.samples[half * n..(half + 1) * n];
+                    let mut traf =
+                        build_fullbox(b"tfhd", 0, 0x02_0000, &track.track_id.to_be_bytes());
+                    // 视频第 2 段省略 tfdt, 由上一段时长接续
+                    if track.handler == b"soun" || half == 0 {
+                        let dts: u32 = track.samples[..half * n].iter().map(|s| s.duration).sum();
+                        traf.extend(build_fullbox(b"tfdt", 0, 0, &dts.to_be_bytes()));
+                    }
+                    let mut trun = (samples.len() as u32).to_be_bytes().to_vec();
+                    trun.extend_from_slice(&data_offsets[ti].to_be_bytes());
+                    let flags = if track.handler == b"vide" {
+                        trun.extend_from_slice(&0x0200_0000u32.to_be_bytes()); // 首帧为同步采样
+                        for sample in samples {
+                            trun.extend_from_slice(&sample.duration.to_be_bytes());
+                            trun.extend_from_slice(&(sample.data.len() as u32).to_be_bytes());
+                            trun.extend_from_slice(&sample.cts_offset.to_be_bytes());
+                        }
+                        0x0B05
+                    } else {
+                        for sample in samples {
+                            trun.extend_from_slice(&(sample.data.len() as u32).to_be_bytes());
+                        }
+                        0x0201
+                    };
+                    traf.extend(build_fullbox(b"trun", 0, flags, &trun));
+                    moof.extend(build_box(b"traf", &traf));
+                }
+                build_box(b"moof", &moof)
+            };
+            let moof_len = build_moof(&[0, 0]).len();
+            let mut mdat = Vec::new();
+            let mut data_offsets = Vec::new();
+            for track in tracks {
+                let n = track.samples.len() / 2;
+                data_offsets.push((moof_len + 8 + mdat.len()) as i32);
+                for sample in &track.samples[half * n..(half + 1) * n] {
+                    mdat.extend_from_slice(&sample.data);
+                }
+            }
+            file.extend(build_moof(&data_offsets));
+            file.extend(build_box(b"mdat", &mdat));
+        }
+        file
+    }
+
+    /// 数据包摘要: (stream_index, pts, dts, 关键帧, 数据)
+    type PacketSummary = (usize, i64, i64, bool, Vec<u8>);
+
+    /// 读取全部数据包
+    fn read_all_packets(data: Vec<u8>) -> (Box<dyn Demuxer>, Vec<PacketSummary>) {
+        let mut io = IoContext::from_bytes(data);
+        let mut demuxer = Mp4Demuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        let mut packets = Vec::new();
+        loop {
+            match demuxer.read_packet(&mut io) {
+                Ok(pkt) => packets.push((
+                    pkt.stream_index,
+                    pkt.pts,
+                    pkt.dts,
+                    pkt.is_keyframe(),
+                    pkt.data.to_vec(),
+                )),
+                Err(TaoError::Eof) => break,
+                Err(e) => panic!("读取数据包失败: {e}"),
+            }
+        }
+        (demuxer, packets)
+    }
+
+    #[test]
+    fn test_fragmented_mp4_matches_flat_mp4() {
+        let tracks = build_test_tracks();
+        let (_, flat) = read_all_packets(build_flat_mp4(&tracks));
+        let (demuxer, fragmented) = read_all_packets(build_fragmented_mp4(&tracks));
+
+        assert_eq!(flat.len(), 10, "普通 MP4 应读出全部 10 个采样");
+        assert_eq!(fragmented, flat, "分片 MP4 的数据包序列应与普通 MP4 一致");
+
+        let video: Vec<_> = fragmented.iter().filter(|p| p.0 == 0).collect();
+        let keyframes: Vec<bool> = video.iter().map(|p| p.3).collect();
+        assert_eq!(keyframes, [true, false, false, true, false, false]);
+        assert_eq!(video[3].2, 9000, "省略 tfdt 的分片应接续上一分片的 DTS");
+        assert_eq!(video[3].1, 15000, "PTS 应为 DTS 加 trun 合成偏移");
+
+        let streams = demuxer.streams();
+        assert_eq!(streams[0].nb_frames, 6);
+        assert_eq!(streams[0].duration, 18000, "分片流时长应由采样累计得出");
+        assert_eq!(streams[1].duration, 4096);
+        assert_eq!(demuxer.duration(), Some(0.2));
+    }
+
+    #[test]
+    fn test_mp4_bit_rate_and_frames_from_sample_tables() {
+        let tracks = build_test_tracks();
+        for data in [build_flat_mp4(&tracks), build_fragmented_mp4(&tracks)] {
+            let mut io = IoContext::from_bytes(data);
+            let mut demuxer = Mp4Demuxer::create().unwrap();
+            demuxer.open(&mut io).unwrap();
+            let streams = demuxer.streams();
+            assert_eq!(streams[0].nb_frames, 6, "视频帧数应来自采样表");
+            assert_eq!(streams[1].nb_frames, 4, "音频帧数应来自采样表");
+            // 视频 165 字节 / 0.2 秒, 音频 46 字节 / (4096 / 48000) 秒
+            assert!(
+                matches!(&streams[0].params, StreamParams::Video(v) if v.bit_rate == 6600),
+                "视频码率应由采样大小与轨道时长计算"
+            );
+            assert!(matches!(&streams[1].params, StreamParams::Audio(a) if a.bit_rate == 4312));
+            assert_eq!(demuxer.bit_rate(), Some(6600 + 4312));
+        }
+    }
+
+    #[test]
+    fn test_fragmented_mp4_seek_into_second_fragment() {
+        let tracks = build_test_tracks();
+        let mut io = IoContext::from_bytes(build_fragmented_mp4(&tracks));
+        let mut demuxer = Mp4Demuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        // 第 5 帧 (DTS 12000) 回退到第 2 个分片首帧关键帧
+        demuxer
+            .seek(&mut io, 0, 12000, SeekFlags::default())
+            .unwrap();
+        let pkt = loop {
+            let pkt = demuxer.read_packet(&mut io).unwrap();
+            if pkt.stream_index == 0 {
+                break pkt;
+            }
+        };
+        assert_eq!(pkt.dts, 9000, "应定位到第 2 个分片的关键帧");
+        assert!(pkt.is_keyframe());
+        assert_eq!(&pkt.data[..], &tracks[0].samples[3].data[..]);
+    }
+
     /// 构造最小 MP4 文件
     fn build_minimal_mp4() -> Vec<u8> {
         let mut data = Vec::new();
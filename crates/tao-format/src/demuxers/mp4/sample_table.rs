@@ -9,9 +9,12 @@
 //! - stco/co64: 每个块的文件偏移
 //! - stss: 同步采样 (关键帧) 索引列表
 //! - ctts: 合成时间偏移 (B帧重排序)
+//!
+//! 分片 MP4 (fMP4) 的采样来自 moof/trun, 以显式列表追加在 stbl 采样之后.
 
 use tao_codec::CodecId;
 use tao_core::TaoResult;
+use tao_core::color::{ColorRange, ColorSpace};
 
 use crate::io::IoContext;
 
@@ -35,6 +38,19 @@ struct StscEntry {
     _sample_desc_idx: u32,
 }
 
+/// QuickTime 时间码采样描述 (tmcd sample entry)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TmcdEntry {
+    /// 是否为丢帧时间码 (flags bit 0)
+    pub drop_frame: bool,
+    /// 时间刻度
+    pub timescale: u32,
+    /// 每帧时长 (以 timescale 为单位)
+    pub frame_duration: u32,
+    /// 名义帧率 (每秒帧数, 取整)
+    pub number_of_frames: u8,
+}
+
 /// 合成时间偏移条目 (ctts)
 #[derive(Debug, Clone)]
 struct CttsEntry {
@@ -44,6 +60,23 @@ struct CttsEntry {
     offset: i32,
 }
 
+/// 分片采样 (来自 moof/traf/trun)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FragmentSample {
+    /// 文件偏移
+    pub offset: u64,
+    /// 字节大小
+    pub size: u32,
+    /// 解码时间戳
+    pub dts: i64,
+    /// 时长
+    pub duration: u32,
+    /// 合成时间偏移
+    pub cts_offset: i32,
+    /// 是否为同步采样 (关键帧)
+    pub is_sync: bool,
+}
+
 /// 采样表
 pub struct SampleTable {
     // === 来自 stsd 的信息 ===
@@ -55,10 +88,16 @@ pub struct SampleTable {
     pub width: u32,
     /// 视频高度
     pub height: u32,
+    /// 色彩空间 (来自 colr)
+    pub color_space: ColorSpace,
+    /// 色彩范围 (来自 colr nclx)
+    pub color_range: ColorRange,
     /// 音频采样率
     pub sample_rate: u32,
     /// 声道数
     pub channel_count: u32,
+    /// 时间码描述 (仅 tmcd 轨道)
+    pub timecode: Option<TmcdEntry>,
 
     // === stts ===
     /// 时间→采样表
@@ -84,6 +123,9 @@ pub struct SampleTable {
     // === ctts ===
     /// 合成时间偏移表
     ctts_entries: Vec<CttsEntry>,
+    // === moof/trun ===
+    /// 分片采样, 索引接在 stbl 采样之后
+    fragments: Vec<FragmentSample>,
 }
 
 impl SampleTable {
@@ -94,8 +136,11 @@ impl SampleTable {
             extra_data: Vec::new(),
             width: 0,
             height: 0,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
             sample_rate: 0,
             channel_count: 0,
+            timecode: None,
             stts_entries: Vec::new(),
             stsc_entries: Vec::new(),
             default_sample_size: 0,
@@ -105,11 +150,17 @@ impl SampleTable {
             sync_samples: Vec::new(),
             has_stss: false,
             ctts_entries: Vec::new(),
+            fragments: Vec::new(),
         }
     }
 
-    /// 获取总采样数
+    /// 获取总采样数 (stbl 采样 + 分片采样)
     pub fn sample_count(&self) -> u32 {
+        self.stbl_sample_count() + self.fragments.len() as u32
+    }
+
+    /// stbl 中的采样数
+    fn stbl_sample_count(&self) -> u32 {
         if self.total_samples > 0 {
             self.total_samples
         } else {
@@ -117,9 +168,37 @@ impl SampleTable {
         }
     }
 
+    /// 查找分片采样, stbl 范围内的索引返回 None
+    fn fragment(&self, sample_idx: u32) -> Option<&FragmentSample> {
+        sample_idx
+            .checked_sub(self.stbl_sample_count())
+            .and_then(|idx| self.fragments.get(idx as usize))
+    }
+
+    /// 追加一个分片采样
+    pub fn push_fragment_sample(&mut self, sample: FragmentSample) {
+        self.fragments.push(sample);
+    }
+
+    /// 下一个分片的起始 DTS (tfdt 缺失时使用)
+    ///
+    /// 为最后一个分片采样的结束时间; 尚无分片时为 stbl 采样的总时长.
+    pub fn next_fragment_dts(&self) -> i64 {
+        match self.fragments.last() {
+            Some(last) => last.dts + i64::from(last.duration),
+            None => self
+                .stts_entries
+                .iter()
+                .map(|e| i64::from(e.count) * i64::from(e.delta))
+                .sum(),
+        }
+    }
+
     /// 获取指定采样的字节大小
     pub fn sample_size(&self, sample_idx: u32) -> u32 {
-        if self.default_sample_size > 0 {
+        if let Some(frag) = self.fragment(sample_idx) {
+            frag.size
+        } else if self.default_sample_size > 0 {
             self.default_sample_size
         } else if (sample_idx as usize) < self.sample_sizes.len() {
             self.sample_sizes[sample_idx as usize]
@@ -128,8 +207,25 @@ impl SampleTable {
         }
     }
 
+    /// 全部采样的字节数之和
+    pub fn total_size(&self) -> u64 {
+        let stbl = if self.default_sample_size > 0 {
+            u64::from(self.default_sample_size) * u64::from(self.stbl_sample_count())
+        } else {
+            self.sample_sizes.iter().map(|&s| u64::from(s)).sum()
+        };
+        stbl + self
+            .fragments
+            .iter()
+            .map(|f| u64::from(f.size))
+            .sum::<u64>()
+    }
+
     /// 获取指定采样在文件中的偏移量
     pub fn sample_offset(&self, sample_idx: u32) -> u64 {
+        if let Some(frag) = self.fragment(sample_idx) {
+            return frag.offset;
+        }
         // 根据 stsc 找到采样所在的块和块内偏移
         let (chunk_idx, _offset_in_chunk) = self.sample_to_chunk(sample_idx);
 
@@ -151,6 +247,9 @@ impl SampleTable {
 
     /// 获取指定采样的 DTS (解码时间戳, 仅由 stts 决定)
     pub fn sample_dts(&self, sample_idx: u32) -> i64 {
+        if let Some(frag) = self.fragment(sample_idx) {
+            return frag.dts;
+        }
         let mut dts = 0i64;
         let mut remaining = sample_idx;
 
@@ -168,6 +267,9 @@ impl SampleTable {
 
     /// 获取指定采样的 PTS
     pub fn sample_pts(&self, sample_idx: u32) -> i64 {
+        if let Some(frag) = self.fragment(sample_idx) {
+            return frag.dts + i64::from(frag.cts_offset);
+        }
         let mut pts = self.sample_dts(sample_idx);
 
         // 加上 ctts 偏移 (如果有)
@@ -180,6 +282,9 @@ impl SampleTable {
 
     /// 是否为同步采样 (关键帧)
     pub fn is_sync_sample(&self, sample_idx: u32) -> bool {
+        if let Some(frag) = self.fragment(sample_idx) {
+            return frag.is_sync;
+        }
         if !self.has_stss {
             return true; // 无 stss 表示所有采样都是关键帧
         }
@@ -190,6 +295,12 @@ impl SampleTable {
     /// 根据时间戳（以时间刻度为单位）找到对应的采样索引
     /// 返回: 最接近的采样索引（可能小于或等于给定时间戳）
     pub fn timestamp_to_sample(&self, timestamp: i64) -> u32 {
+        if let Some(first) = self.fragments.first()
+            && timestamp >= first.dts
+        {
+            let idx = self.fragments.partition_point(|s| s.dts <= timestamp);
+            return self.stbl_sample_count() + idx.saturating_sub(1) as u32;
+        }
         let mut sample_idx = 0u32;
         let mut accum_time = 0i64;
 
@@ -211,7 +322,20 @@ impl SampleTable {
 
     /// 找到给定采样处或之前的最近关键帧
     /// 返回: 关键帧的采样索引
-    pub fn find_keyframe_at_or_before(&self, sample_idx: u32) -> u32 {
+    pub fn find_keyframe_at_or_before(&self, mut sample_idx: u32) -> u32 {
+        let stbl_count = self.stbl_sample_count();
+        if sample_idx >= stbl_count {
+            let frag_idx = (sample_idx - stbl_count) as usize;
+            let end = (frag_idx + 1).min(self.fragments.len());
+            if let Some(pos) = self.fragments[..end].iter().rposition(|s| s.is_sync) {
+                return stbl_count + pos as u32;
+            }
+            // 分片内没有关键帧, 回退到 stbl 中查找
+            if stbl_count == 0 {
+                return 0;
+            }
+            sample_idx = stbl_count - 1;
+        }
         if !self.has_stss || self.sync_samples.is_empty() {
             // 无关键帧表，返回 sample_idx（所有采样都是关键帧）
             return sample_idx;
@@ -260,6 +384,10 @@ impl SampleTable {
         io.read_bytes(6)?;
         let _data_ref_idx = io.read_u16_be()?;
 
+        if entry_format == *b"tmcd" {
+            self.parse_tmcd_sample_entry(io)?;
+        }
+
         // 根据 handler type 解析不同内容
         match self.codec_id.media_type() {
             tao_core::MediaType::Video => {
@@ -317,6 +445,23 @@ impl SampleTable {
         Ok(())
     }
 
+    /// 解析时间码采样条目 (QuickTime File Format, Timecode Sample Description)
+    fn parse_tmcd_sample_entry(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        let _reserved = io.read_u32_be()?;
+        let flags = io.read_u32_be()?;
+        let timescale = io.read_u32_be()?;
+        let frame_duration = io.read_u32_be()?;
+        let number_of_frames = io.read_u8()?;
+        let _reserved2 = io.read_u8()?;
+        self.timecode = Some(TmcdEntry {
+            drop_frame: flags & 0x0001 != 0,
+            timescale,
+            frame_duration,
+            number_of_frames,
+        });
+        Ok(())
+    }
+
     /// 解析编解码器配置子 box (avcC, hvcC, esds, dOps 等)
     fn parse_codec_config_boxes(&mut self, io: &mut IoContext, end: u64) -> TaoResult<()> {
         while io.position()? + 8 <= end {
@@ -335,10 +480,21 @@ impl SampleTable {
                     // 从 esds 描述符中提取 DecoderSpecificInfo (AudioSpecificConfig)
                     self.extra_data = extract_decoder_specific_info(&data).unwrap_or(data);
                 }
+                b"dfLa" => {
+                    // version(1) + flags(3) + 元数据块头(4), 首个块为 STREAMINFO
+                    let data = io.read_bytes(content_size as usize)?;
+                    if data.len() >= 8 {
+                        self.extra_data = data[8..].to_vec();
+                    }
+                }
                 b"avcC" | b"hvcC" | b"av1C" | b"vpcC" | b"dOps" => {
                     let data = io.read_bytes(content_size as usize)?;
                     self.extra_data = data;
                 }
+                b"colr" => {
+                    let data = io.read_bytes(content_size as usize)?;
+                    self.parse_colr(&data);
+                }
                 _ => {}
             }
 
@@ -347,6 +503,25 @@ impl SampleTable {
         Ok(())
     }
 
+    /// 解析 colr (Colour Information Box)
+    ///
+    /// 仅处理 `nclx` (ISO 14496-12) 与 QuickTime `nclc`, ICC 配置文件忽略.
+    /// 布局: colour_type(4) + primaries(2) + transfer(2) + matrix(2) [+ full_range_flag(1)].
+    fn parse_colr(&mut self, data: &[u8]) {
+        if data.len() < 10 || !matches!(&data[0..4], b"nclx" | b"nclc") {
+            return;
+        }
+        let matrix = u16::from_be_bytes([data[8], data[9]]);
+        self.color_space = ColorSpace::from_iso_code(u64::from(matrix));
+        if &data[0..4] == b"nclx" && data.len() >= 11 {
+            self.color_range = if data[10] & 0x80 != 0 {
+                ColorRange::Full
+            } else {
+                ColorRange::Limited
+            };
+        }
+    }
+
     /// 解析 stts (Time-to-Sample Box)
     pub fn parse_stts(&mut self, io: &mut IoContext) -> TaoResult<()> {
         let _version = io.read_u8()?;
@@ -643,7 +818,6 @@ fn fourcc_to_codec_id(fourcc: &[u8; 4]) -> CodecId {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     #[test]
     fn test_fourcc_mapping() {
@@ -655,6 +829,58 @@ mod tests {
         assert_eq!(fourcc_to_codec_id(b"xxxx"), CodecId::None);
     }
 
+    #[test]
+    fn test_colr_nclx_parse() {
+        let mut data = b"nclx".to_vec();
+        data.extend_from_slice(&1u16.to_be_bytes()); // primaries
+        data.extend_from_slice(&1u16.to_be_bytes()); // transfer
+        data.extend_from_slice(&1u16.to_be_bytes()); // matrix: BT.709
+        data.push(0x80); // full_range_flag
+
+        let mut st = SampleTable::new();
+        st.parse_colr(&data);
+        assert_eq!(st.color_space, ColorSpace::Bt709);
+        assert_eq!(st.color_range, ColorRange::Full);
+
+        data[8..10].copy_from_slice(&6u16.to_be_bytes()); // matrix: SMPTE 170M
+        data[10] = 0;
+        st.parse_colr(&data);
+        assert_eq!(st.color_space, ColorSpace::Smpte170m);
+        assert_eq!(st.color_range, ColorRange::Limited);
+
+        let mut icc = SampleTable::new();
+        icc.parse_colr(b"prof\x00\x00\x00\x00\x00\x00\x00");
+        assert_eq!(icc.color_space, ColorSpace::Unspecified);
+    }
+
+    #[test]
+    fn test_tmcd_sample_entry_parse() {
+        let mut data = Vec::new();
+        data.push(0); // version
+        data.extend_from_slice(&[0, 0, 0]); // flags
+        data.extend_from_slice(&1u32.to_be_bytes()); // entry_count
+        data.extend_from_slice(&34u32.to_be_bytes()); // entry_size
+        data.extend_from_slice(b"tmcd");
+        data.extend_from_slice(&[0; 6]); // reserved
+        data.extend_from_slice(&1u16.to_be_bytes()); // data_reference_index
+        data.extend_from_slice(&0u32.to_be_bytes()); // reserved
+        data.extend_from_slice(&1u32.to_be_bytes()); // flags: drop frame
+        data.extend_from_slice(&30000u32.to_be_bytes()); // timescale
+        data.extend_from_slice(&1001u32.to_be_bytes()); // frame_duration
+        data.push(30); // number_of_frames
+        data.push(0); // reserved
+        let end = data.len() as u64;
+
+        let mut io = IoContext::from_bytes(data);
+        let mut st = SampleTable::new();
+        st.parse_stsd(&mut io, end).unwrap();
+        let tmcd = st.timecode.expect("应解析出 tmcd 描述");
+        assert!(tmcd.drop_frame, "flags bit 0 应表示丢帧");
+        assert_eq!(tmcd.timescale, 30000);
+        assert_eq!(tmcd.frame_duration, 1001);
+        assert_eq!(tmcd.number_of_frames, 30);
+    }
+
     #[test]
     fn test_stts_parse() {
         let mut data = Vec::new();
@@ -666,8 +892,7 @@ mod tests {
         data.extend_from_slice(&50u32.to_be_bytes()); // count
         data.extend_from_slice(&512u32.to_be_bytes()); // delta
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut st = SampleTable::new();
         st.parse_stts(&mut io).unwrap();
 
@@ -691,8 +916,7 @@ mod tests {
         data.extend_from_slice(&200u32.to_be_bytes()); // size[1]
         data.extend_from_slice(&150u32.to_be_bytes()); // size[2]
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut st = SampleTable::new();
         st.parse_stsz(&mut io).unwrap();
 
@@ -710,8 +934,7 @@ mod tests {
         data.extend_from_slice(&1024u32.to_be_bytes()); // default_size
         data.extend_from_slice(&500u32.to_be_bytes()); // sample_count
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut st = SampleTable::new();
         st.parse_stsz(&mut io).unwrap();
 
@@ -730,8 +953,7 @@ mod tests {
         data.extend_from_slice(&30u32.to_be_bytes()); // sample 30
         data.extend_from_slice(&60u32.to_be_bytes()); // sample 60
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
         let mut st = SampleTable::new();
         st.parse_stss(&mut io).unwrap();
 
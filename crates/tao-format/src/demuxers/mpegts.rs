@@ -302,6 +302,8 @@ impl TsDemuxer {
                     frame_rate: Rational::new(0, 1),
                     sample_aspect_ratio: Rational::new(1, 1),
                     bit_rate: 0,
+                    color_space: Default::default(),
+                    color_range: Default::default(),
                 }),
                 MediaType::Audio => {
                     let (sr, ch) = match entry.codec_id {
@@ -316,6 +318,7 @@ impl TsDemuxer {
                         sample_format: SampleFormat::F32,
                         bit_rate: 0,
                         frame_size: 0,
+                        block_align: 0,
                     })
                 }
                 _ => StreamParams::Other,
@@ -385,7 +388,7 @@ impl TsDemuxer {
             pkt.stream_index = buf.stream_index;
             pkt.pts = buf.pts;
             pkt.dts = if buf.dts >= 0 { buf.dts } else { buf.pts };
-            pkt.is_keyframe = buf.random_access;
+            pkt.set_keyframe(buf.random_access);
             pkt.time_base = Rational::new(1, 90000);
 
             self.packet_queue.push(pkt);
@@ -572,6 +575,9 @@ impl Demuxer for TsDemuxer {
     }
 }
 
+/// 探测时校验的连续 TS 包数
+const TS_PROBE_PACKETS: usize = 5;
+
 /// MPEG-TS 格式探测器
 pub struct TsProbe;
 
@@ -585,34 +591,31 @@ impl FormatProbe for TsProbe {
             return None;
         }
 
-        // 检查连续的 TS 同步字节
+        // 检查固定间隔的连续 TS 同步字节.
+        // 0x47 也可能出现在 MP3 等数据中: 首个同步字节须位于第一个包长度内,
+        // 且同步链在数据结束前中断时不予认定.
         if data.len() >= TS_PACKET_SIZE * 2 {
-            let mut sync_count = 0;
-            let mut pos = 0;
-
-            // 搜索第一个同步字节
-            while pos < data.len().min(1024) {
-                if data[pos] == TS_SYNC_BYTE {
-                    // 验证后续包
-                    let mut check_pos = pos;
-                    while check_pos + TS_PACKET_SIZE <= data.len() {
-                        if data[check_pos] == TS_SYNC_BYTE {
-                            sync_count += 1;
-                            check_pos += TS_PACKET_SIZE;
-                        } else {
-                            break;
-                        }
-                    }
-
-                    if sync_count >= 3 {
-                        return Some(crate::probe::SCORE_MAX);
-                    }
-                    if sync_count >= 2 {
-                        return Some(crate::probe::SCORE_MAX - 10);
-                    }
+            for pos in 0..TS_PACKET_SIZE {
+                if data[pos] != TS_SYNC_BYTE {
+                    continue;
+                }
+                let available = (data.len() - pos).div_ceil(TS_PACKET_SIZE);
+                let expected = available.min(TS_PROBE_PACKETS);
+                let sync_count = (0..expected)
+                    .take_while(|i| data[pos + i * TS_PACKET_SIZE] == TS_SYNC_BYTE)
+                    .count();
+                if sync_count < expected {
+                    continue;
+                }
+                if sync_count >= TS_PROBE_PACKETS {
+                    return Some(crate::probe::SCORE_MAX);
+                }
+                if sync_count >= 3 {
+                    return Some(crate::probe::SCORE_MAX - 10);
+                }
+                if sync_count >= 2 {
+                    return Some(crate::probe::SCORE_MAX / 4);
                 }
-                pos += 1;
-                sync_count = 0;
             }
         }
 
@@ -637,7 +640,6 @@ impl FormatProbe for TsProbe {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     /// 构造一个最小的 TS 包 (188 字节)
     fn build_ts_packet(pid: u16, pusi: bool, payload: &[u8]) -> [u8; TS_PACKET_SIZE] {
@@ -863,6 +865,23 @@ mod tests {
         assert_eq!(probe.probe(&ts, None), Some(crate::probe::SCORE_MAX));
     }
 
+    #[test]
+    fn test_probe_ts_broken_sync_rejected() {
+        let probe = TsProbe;
+        let mut ts = build_minimal_ts();
+        assert!(ts.len() >= TS_PACKET_SIZE * TS_PROBE_PACKETS);
+        // 第 3 个包的同步字节被破坏: 同步链在数据结束前中断
+        ts[TS_PACKET_SIZE * 2] = 0xFF;
+        assert!(
+            probe.probe(&ts, None).is_none(),
+            "同步链中断的数据不应识别为 TS"
+        );
+
+        // 数据只够两个包时以低分识别
+        let short = &build_minimal_ts()[..TS_PACKET_SIZE * 2];
+        assert_eq!(probe.probe(short, None), Some(crate::probe::SCORE_MAX / 4));
+    }
+
     #[test]
     fn test_probe_ts_extension() {
         let probe = TsProbe;
@@ -874,8 +893,7 @@ mod tests {
     #[test]
     fn test_parse_pat_pmt() {
         let ts = build_minimal_ts();
-        let backend = MemoryBackend::from_data(ts);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(ts);
         let mut demuxer = TsDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -894,8 +912,7 @@ mod tests {
     #[test]
     fn test_read_packets() {
         let ts = build_minimal_ts();
-        let backend = MemoryBackend::from_data(ts);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(ts);
         let mut demuxer = TsDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -923,8 +940,7 @@ mod tests {
     #[test]
     fn test_pts_timestamp() {
         let ts = build_minimal_ts();
-        let backend = MemoryBackend::from_data(ts);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(ts);
         let mut demuxer = TsDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -937,8 +953,7 @@ mod tests {
     #[test]
     fn test_keyframe_flag() {
         let ts = build_minimal_ts();
-        let backend = MemoryBackend::from_data(ts);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(ts);
         let mut demuxer = TsDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -947,7 +962,7 @@ mod tests {
         loop {
             match demuxer.read_packet(&mut io) {
                 Ok(pkt) => {
-                    if pkt.stream_index == 0 && pkt.is_keyframe {
+                    if pkt.stream_index == 0 && pkt.is_keyframe() {
                         found_keyframe = true;
                         break;
                     }
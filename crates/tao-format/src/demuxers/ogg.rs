@@ -390,7 +390,11 @@ impl OggDemuxer {
         let codec_id = Self::identify_codec(packet_data);
 
         let stream_index = self.streams.len();
-        let media_type = codec_id.media_type();
+        let media_type = if codec_id == CodecId::None {
+            MediaType::Unknown(0)
+        } else {
+            codec_id.media_type()
+        };
 
         let params = match media_type {
             MediaType::Audio => {
@@ -411,6 +415,7 @@ impl OggDemuxer {
                     sample_format,
                     bit_rate: 0,
                     frame_size: 0,
+                    block_align: 0,
                 })
             }
             MediaType::Video => StreamParams::Video(VideoStreamParams {
@@ -420,6 +425,8 @@ impl OggDemuxer {
                 frame_rate: Rational::new(0, 1),
                 sample_aspect_ratio: Rational::new(1, 1),
                 bit_rate: 0,
+                color_space: Default::default(),
+                color_range: Default::default(),
             }),
             _ => StreamParams::Other,
         };
@@ -623,12 +630,18 @@ impl OggDemuxer {
 
     /// 创建并入队一个数据包
     fn emit_packet(&mut self, stream_index: usize, granule: i64, data: Vec<u8>) {
+        // Ogg 页不携带关键帧信息: Theora 数据包首字节 bit6 为 0 表示帧内编码帧
+        // (头部包同样满足), 空包表示重复上一帧; 音频数据包均可独立解码
+        let is_keyframe = match self.streams.get(stream_index).map(|s| s.codec_id) {
+            Some(CodecId::Theora) => data.first().is_some_and(|b| b & 0x40 == 0),
+            _ => true,
+        };
         let mut pkt = Packet::from_data(Bytes::from(data));
         pkt.stream_index = stream_index;
         let granule = Self::normalize_granule(granule);
         pkt.pts = granule;
         pkt.dts = granule;
-        pkt.is_keyframe = true; // Ogg 不直接提供关键帧信息
+        pkt.set_keyframe(is_keyframe);
 
         if let Some(stream) = self.streams.get(stream_index) {
             pkt.time_base = stream.time_base;
@@ -929,7 +942,6 @@ impl FormatProbe for OggProbe {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     /// 手动构造一个简单的 Ogg 文件 (含 Vorbis BOS 页面)
     fn build_minimal_ogg_vorbis() -> Vec<u8> {
@@ -1132,11 +1144,43 @@ mod tests {
         assert_eq!(OggDemuxer::identify_codec(&data), CodecId::Theora);
     }
 
+    #[test]
+    fn test_theora_keyframe_from_frame_type() {
+        let mut ident = vec![0x80];
+        ident.extend_from_slice(b"theora");
+        ident.resize(42, 0);
+        let mut data = build_ogg_page(0x02, 0, 7, 0, &ident);
+        // 帧内帧 (bit6=0), 帧间帧 (bit6=1), 空包 (重复上一帧)
+        for (seq, packet) in [&[0x00, 0x11][..], &[0x40, 0x22], &[]]
+            .into_iter()
+            .enumerate()
+        {
+            data.extend(build_ogg_page(0, seq as i64 + 1, 7, seq as u32 + 1, packet));
+        }
+        let mut io = IoContext::from_bytes(data);
+        let mut demuxer = OggDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        assert_eq!(demuxer.streams()[0].codec_id, CodecId::Theora);
+
+        let mut flags = Vec::new();
+        loop {
+            match demuxer.read_packet(&mut io) {
+                Ok(pkt) => flags.push((pkt.data.first().copied(), pkt.is_keyframe())),
+                Err(TaoError::Eof) => break,
+                Err(e) => panic!("读取数据包失败: {e}"),
+            }
+        }
+        assert_eq!(
+            flags,
+            [(Some(0x00), true), (Some(0x40), false), (None, false)],
+            "仅帧内帧应标记为关键帧"
+        );
+    }
+
     #[test]
     fn test_demux_vorbis_single_stream() {
         let ogg_data = build_minimal_ogg_vorbis();
-        let backend = MemoryBackend::from_data(ogg_data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(ogg_data);
 
         let mut demuxer = OggDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
@@ -1165,8 +1209,7 @@ mod tests {
         data.extend_from_slice(&[0u8; 4]);
         data.extend_from_slice(&build_minimal_ogg_vorbis());
 
-        let backend = MemoryBackend::from_data(data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(data);
 
         let mut demuxer = OggDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
@@ -1179,8 +1222,7 @@ mod tests {
     #[test]
     fn test_read_packets() {
         let ogg_data = build_minimal_ogg_vorbis();
-        let backend = MemoryBackend::from_data(ogg_data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(ogg_data);
 
         let mut demuxer = OggDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
@@ -1194,8 +1236,7 @@ mod tests {
     #[test]
     fn test_duration_estimable() {
         let ogg_data = build_minimal_ogg_vorbis();
-        let backend = MemoryBackend::from_data(ogg_data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(ogg_data);
 
         let mut demuxer = OggDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
@@ -1212,8 +1253,7 @@ mod tests {
     #[test]
     fn test_seek_skip_vorbis_header_packet_page() {
         let ogg_data = build_vorbis_seek_test_ogg();
-        let backend = MemoryBackend::from_data(ogg_data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(ogg_data);
         let mut demuxer = OggDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -1229,8 +1269,7 @@ mod tests {
     #[test]
     fn test_seek_backward_seek_to_page_before_target() {
         let ogg_data = build_vorbis_seek_test_ogg();
-        let backend = MemoryBackend::from_data(ogg_data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(ogg_data);
         let mut demuxer = OggDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -1244,8 +1283,7 @@ mod tests {
     #[test]
     fn test_seek_forward_seek_to_page_after_target() {
         let ogg_data = build_vorbis_seek_test_ogg();
-        let backend = MemoryBackend::from_data(ogg_data);
-        let mut io = IoContext::new(Box::new(backend));
+        let mut io = IoContext::from_bytes(ogg_data);
         let mut demuxer = OggDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -1,6 +1,6 @@
 //! WAV (RIFF WAVE) 解封装器.
 //!
-//! 支持标准 PCM WAV 文件的读取.
+//! 支持标准 PCM WAV 文件以及 IMA/MS ADPCM 块编码 WAV 文件的读取.
 //!
 //! WAV 文件结构:
 //! ```text
@@ -12,6 +12,7 @@
 
 use log::{debug, warn};
 use tao_codec::CodecId;
+use tao_codec::decoders::adpcm;
 use tao_core::{ChannelLayout, MediaType, Rational, SampleFormat, TaoError, TaoResult};
 
 use crate::demuxer::{Demuxer, SeekFlags};
@@ -22,8 +23,12 @@ use crate::stream::{AudioStreamParams, Stream, StreamParams};
 
 /// WAV 音频格式码
 const WAV_FORMAT_PCM: u16 = 0x0001;
+/// WAV Microsoft ADPCM 格式码
+const WAV_FORMAT_ADPCM_MS: u16 = 0x0002;
 /// WAV IEEE 浮点格式码
 const WAV_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+/// WAV IMA ADPCM 格式码
+const WAV_FORMAT_ADPCM_IMA: u16 = 0x0011;
 
 /// WAV 解封装器
 pub struct WavDemuxer {
@@ -39,6 +44,8 @@ pub struct WavDemuxer {
     packet_size: usize,
     /// 块对齐 (每个采样块的字节数)
     block_align: u16,
+    /// 每个块包含的每声道样本数 (PCM 为 1, ADPCM 由 block_align 换算)
+    samples_per_block: u32,
     /// 采样率 (用于计算时间戳)
     sample_rate: u32,
     /// 元数据
@@ -55,6 +62,7 @@ impl WavDemuxer {
             data_pos: 0,
             packet_size: 0,
             block_align: 0,
+            samples_per_block: 1,
             sample_rate: 0,
             metadata: Vec::new(),
         }))
@@ -73,6 +81,8 @@ impl WavDemuxer {
                     bits_per_sample
                 ))),
             },
+            WAV_FORMAT_ADPCM_MS => Ok(CodecId::AdpcmMs),
+            WAV_FORMAT_ADPCM_IMA => Ok(CodecId::AdpcmImaWav),
             WAV_FORMAT_IEEE_FLOAT => match bits_per_sample {
                 32 => Ok(CodecId::PcmF32le),
                 _ => Err(TaoError::Unsupported(format!(
@@ -91,12 +101,17 @@ impl WavDemuxer {
     fn resolve_sample_format(codec_id: CodecId) -> SampleFormat {
         match codec_id {
             CodecId::PcmU8 => SampleFormat::U8,
-            CodecId::PcmS16le => SampleFormat::S16,
+            CodecId::PcmS16le | CodecId::AdpcmImaWav | CodecId::AdpcmMs => SampleFormat::S16,
             CodecId::PcmS24le | CodecId::PcmS32le => SampleFormat::S32,
             CodecId::PcmF32le => SampleFormat::F32,
             _ => SampleFormat::None,
         }
     }
+
+    /// 按块数换算每声道样本数
+    fn blocks_to_samples(&self, blocks: u64) -> u64 {
+        blocks * u64::from(self.samples_per_block)
+    }
 }
 
 impl Demuxer for WavDemuxer {
@@ -202,9 +217,24 @@ impl Demuxer for WavDemuxer {
         let channel_layout = ChannelLayout::from_channels(u32::from(channels));
         let time_base = Rational::new(1, sample_rate as i32);
 
+        self.samples_per_block = match codec_id {
+            CodecId::AdpcmImaWav | CodecId::AdpcmMs => {
+                let spb =
+                    adpcm::samples_per_block(codec_id, u32::from(block_align), u32::from(channels));
+                if spb == 0 {
+                    return Err(TaoError::InvalidData(format!(
+                        "ADPCM block_align {} 无效 ({} 声道)",
+                        block_align, channels
+                    )));
+                }
+                spb
+            }
+            _ => 1,
+        };
+
         // 计算总采样数和时长
         let total_samples = if block_align > 0 {
-            self.data_size / u64::from(block_align)
+            self.blocks_to_samples(self.data_size / u64::from(block_align))
         } else {
             0
         };
@@ -226,6 +256,7 @@ impl Demuxer for WavDemuxer {
                 sample_format,
                 bit_rate,
                 frame_size: 0,
+                block_align: u32::from(block_align),
             }),
             metadata: Vec::new(),
         };
@@ -236,8 +267,8 @@ impl Demuxer for WavDemuxer {
         self.data_pos = 0;
 
         // 每个数据包读取 ~4096 采样 (或至少 1 个 block_align)
-        let samples_per_packet = 4096u32;
-        self.packet_size = (u32::from(block_align) * samples_per_packet) as usize;
+        let blocks_per_packet = (4096 / self.samples_per_block).max(1);
+        self.packet_size = (u32::from(block_align) * blocks_per_packet) as usize;
         if self.packet_size == 0 {
             self.packet_size = 4096;
         }
@@ -279,12 +310,12 @@ impl Demuxer for WavDemuxer {
 
         // 计算时间戳
         let sample_offset = if self.block_align > 0 {
-            self.data_pos / u64::from(self.block_align)
+            self.blocks_to_samples(self.data_pos / u64::from(self.block_align))
         } else {
             0
         };
         let nb_samples = if self.block_align > 0 {
-            aligned_size as i64 / i64::from(self.block_align)
+            self.blocks_to_samples(aligned_size as u64 / u64::from(self.block_align)) as i64
         } else {
             0
         };
@@ -295,7 +326,7 @@ impl Demuxer for WavDemuxer {
         pkt.dts = pkt.pts;
         pkt.duration = nb_samples;
         pkt.time_base = Rational::new(1, self.sample_rate as i32);
-        pkt.is_keyframe = true;
+        pkt.set_keyframe(true);
         pkt.pos = (self.data_offset + self.data_pos) as i64;
 
         self.data_pos += aligned_size as u64;
@@ -317,9 +348,9 @@ impl Demuxer for WavDemuxer {
             return Err(TaoError::InvalidData("block_align 为 0, 无法 seek".into()));
         }
 
-        // 将时间戳 (采样数) 转换为字节偏移
+        // 将时间戳 (采样数) 转换为字节偏移 (ADPCM 落到所在块的起点)
         let sample = timestamp.max(0) as u64;
-        let byte_offset = sample * u64::from(self.block_align);
+        let byte_offset = sample / u64::from(self.samples_per_block) * u64::from(self.block_align);
         let byte_offset = byte_offset.min(self.data_size);
 
         // 对齐到 block_align
@@ -335,7 +366,8 @@ impl Demuxer for WavDemuxer {
 
     fn duration(&self) -> Option<f64> {
         if self.sample_rate > 0 && self.block_align > 0 {
-            let total_samples = self.data_size / u64::from(self.block_align);
+            let total_samples =
+                self.blocks_to_samples(self.data_size / u64::from(self.block_align));
             Some(total_samples as f64 / f64::from(self.sample_rate))
         } else {
             None
@@ -376,7 +408,6 @@ impl FormatProbe for WavProbe {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::MemoryBackend;
 
     /// 构建最简单的 WAV 文件数据 (PCM S16LE, 单声道, 44100Hz)
     fn make_simple_wav(pcm_data: &[u8]) -> Vec<u8> {
@@ -429,7 +460,7 @@ mod tests {
         let pcm = vec![0x00, 0x01, 0xFF, 0x7F, 0x00, 0x80, 0x01, 0x00];
         let wav = make_simple_wav(&pcm);
 
-        let mut io = IoContext::new(Box::new(MemoryBackend::from_data(wav)));
+        let mut io = IoContext::from_bytes(wav);
         let mut demuxer = WavDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -447,7 +478,7 @@ mod tests {
         let pcm = vec![0x00, 0x01, 0xFF, 0x7F, 0x00, 0x80, 0x01, 0x00];
         let wav = make_simple_wav(&pcm);
 
-        let mut io = IoContext::new(Box::new(MemoryBackend::from_data(wav)));
+        let mut io = IoContext::from_bytes(wav);
         let mut demuxer = WavDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -455,7 +486,7 @@ mod tests {
         assert_eq!(&pkt.data[..], &pcm[..]);
         assert_eq!(pkt.pts, 0);
         assert_eq!(pkt.duration, 4); // 4 采样
-        assert!(pkt.is_keyframe);
+        assert!(pkt.is_keyframe());
 
         // 下一次读取应该返回 EOF
         let err = demuxer.read_packet(&mut io).unwrap_err();
@@ -467,7 +498,7 @@ mod tests {
         let pcm = vec![0u8; 44100 * 2]; // 1 秒的 S16LE 单声道
         let wav = make_simple_wav(&pcm);
 
-        let mut io = IoContext::new(Box::new(MemoryBackend::from_data(wav)));
+        let mut io = IoContext::from_bytes(wav);
         let mut demuxer = WavDemuxer::create().unwrap();
         demuxer.open(&mut io).unwrap();
 
@@ -475,10 +506,46 @@ mod tests {
         assert!((duration - 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_demux_ima_adpcm_blocks() {
+        // 单声道 IMA ADPCM, block_align=8 (每块 9 个样本), 共 2 块
+        let block: [u8; 8] = [100, 0, 10, 0, 0x77, 0x77, 0x1F, 0x80];
+        let mut wav = make_simple_wav(&[block, block].concat());
+        wav[20..22].copy_from_slice(&0x0011u16.to_le_bytes());
+        wav[32..34].copy_from_slice(&8u16.to_le_bytes());
+        wav[34..36].copy_from_slice(&4u16.to_le_bytes());
+
+        let mut io = IoContext::from_bytes(wav);
+        let mut demuxer = WavDemuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+
+        let s = &demuxer.streams()[0];
+        assert_eq!(s.codec_id, CodecId::AdpcmImaWav);
+        assert_eq!(s.nb_frames, 18, "总样本数按每块 9 个样本换算");
+        let StreamParams::Audio(a) = &s.params else {
+            panic!("期望音频流参数");
+        };
+        assert_eq!(a.block_align, 8);
+
+        let mut registry = tao_codec::CodecRegistry::new();
+        tao_codec::register_all(&mut registry);
+        let mut decoder = s.open_decoder(&registry).unwrap();
+        let pkt = demuxer.read_packet(&mut io).unwrap();
+        assert_eq!((pkt.pts, pkt.duration), (0, 18));
+        decoder.send_packet(&pkt).unwrap();
+        match decoder.receive_frame().unwrap() {
+            tao_codec::Frame::Audio(af) => {
+                assert_eq!(af.nb_samples, 18);
+                assert_eq!(&af.data[0][..4], &[100, 0, 134, 0]);
+            }
+            _ => panic!("期望音频帧"),
+        }
+    }
+
     #[test]
     fn test_non_riff_file_error() {
         let bad = b"NOT_RIFF_DATA_HERE".to_vec();
-        let mut io = IoContext::new(Box::new(MemoryBackend::from_data(bad)));
+        let mut io = IoContext::from_bytes(bad);
         let mut demuxer = WavDemuxer::create().unwrap();
         let err = demuxer.open(&mut io).unwrap_err();
         assert!(matches!(err, TaoError::InvalidData(_)));
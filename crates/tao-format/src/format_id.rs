@@ -4,6 +4,8 @@
 
 use std::fmt;
 
+use tao_core::MediaType;
+
 /// 容器格式标识符
 ///
 /// 标识一种多媒体容器格式, 如 MP4, MKV, AVI 等.
@@ -128,9 +130,47 @@ impl FormatId {
             Self::RawVideo => &["yuv", "rgb"],
             Self::RawAudio => &["pcm", "raw"],
             Self::Mpeg4Es => &["m4v"],
-            Self::H264Es => &["h264", "264"],
+            Self::H264Es => &["h264", "264", "avc"],
+        }
+    }
+
+    /// 格式可能包含的流类型
+    pub const fn supported_media_types(&self) -> &'static [MediaType] {
+        const AUDIO: &[MediaType] = &[MediaType::Audio];
+        const VIDEO: &[MediaType] = &[MediaType::Video];
+        const AUDIO_VIDEO: &[MediaType] = &[MediaType::Video, MediaType::Audio];
+        const ALL_STREAMS: &[MediaType] = &[
+            MediaType::Video,
+            MediaType::Audio,
+            MediaType::Subtitle,
+            MediaType::Data,
+            MediaType::Attachment,
+        ];
+        match self {
+            Self::Mp4
+            | Self::Matroska
+            | Self::Webm
+            | Self::MpegTs
+            | Self::MpegPs
+            | Self::Mxf
+            | Self::ThreeGp
+            | Self::Asf => ALL_STREAMS,
+            Self::Avi | Self::Flv | Self::Ogg => AUDIO_VIDEO,
+            Self::Wav
+            | Self::FlacContainer
+            | Self::Mp3Container
+            | Self::AacAdts
+            | Self::Aiff
+            | Self::Cue
+            | Self::RawAudio => AUDIO,
+            Self::ImageSequence | Self::RawVideo | Self::Mpeg4Es | Self::H264Es => VIDEO,
         }
     }
+
+    /// 格式是否可能包含指定类型的流
+    pub fn supports_media_type(&self, media_type: MediaType) -> bool {
+        self.supported_media_types().contains(&media_type)
+    }
 }
 
 impl FormatId {
@@ -177,6 +217,18 @@ impl FormatId {
         let ext = filename.rsplit('.').next()?;
         Self::from_extension(ext)
     }
+
+    /// 根据格式名称查找格式 (如 "wav", "matroska")
+    ///
+    /// 先匹配 [`FormatId::name`], 再按扩展名匹配 (如 "mkv"), 不区分大小写.
+    pub fn from_name(name: &str) -> Option<FormatId> {
+        let name_lower = name.to_lowercase();
+        Self::ALL
+            .iter()
+            .find(|id| id.name() == name_lower)
+            .copied()
+            .or_else(|| Self::from_extension(&name_lower))
+    }
 }
 
 impl fmt::Display for FormatId {
@@ -184,3 +236,46 @@ impl fmt::Display for FormatId {
         write!(f, "{}", self.name())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_id_from_name() {
+        assert_eq!(FormatId::from_name("wav"), Some(FormatId::Wav));
+        assert_eq!(FormatId::from_name("Matroska"), Some(FormatId::Matroska));
+        assert_eq!(FormatId::from_name("mpegts"), Some(FormatId::MpegTs));
+        // 名称优先于扩展名: "m4v" 是 Mpeg4Es 的名称, 同时也是 Mp4 的扩展名
+        assert_eq!(FormatId::from_name("m4v"), Some(FormatId::Mpeg4Es));
+        assert_eq!(
+            FormatId::from_name("mkv"),
+            Some(FormatId::Matroska),
+            "应支持扩展名别名"
+        );
+        assert_eq!(FormatId::from_name("nosuchformat"), None);
+        assert_eq!(FormatId::from_name("avc"), Some(FormatId::H264Es));
+        for id in FormatId::ALL {
+            assert_eq!(
+                FormatId::from_name(id.name()),
+                Some(*id),
+                "名称应可往返: {id}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_id_supported_media_types() {
+        assert!(FormatId::Mp3Container.supports_media_type(MediaType::Audio));
+        assert!(!FormatId::Mp3Container.supports_media_type(MediaType::Video));
+        assert!(!FormatId::H264Es.supports_media_type(MediaType::Audio));
+        assert!(FormatId::Matroska.supports_media_type(MediaType::Subtitle));
+        assert!(FormatId::Avi.supports_media_type(MediaType::Video));
+        for id in FormatId::ALL {
+            assert!(
+                !id.supported_media_types().is_empty(),
+                "每种格式至少包含一种流类型: {id}"
+            );
+        }
+    }
+}
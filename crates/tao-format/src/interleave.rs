@@ -0,0 +1,299 @@
+//! 数据包交错 (Interleaver).
+//!
+//! 对标 FFmpeg 的 `ff_interleave_packet_per_dts`: 按流缓存数据包, 以换算到秒的 DTS
+//! 递增顺序释放, 使音视频数据在容器中均匀交错, 避免某一路数据整体领先数秒.
+//!
+//! 释放规则:
+//! - 所有流都有缓存的数据包时, 释放 DTS 最小的队首包
+//! - 缓存跨度 (最新 DTS 与最小队首 DTS 之差) 超过最大缓存时长时强制释放,
+//!   以免某一路长时间没有数据 (如已结束的流) 导致内存无限增长
+//! - 刷新时按 DTS 顺序释放全部剩余数据包
+
+use std::collections::VecDeque;
+
+use tao_codec::Packet;
+use tao_core::timestamp::NOPTS_VALUE;
+use tao_core::{Rational, TaoError, TaoResult};
+
+use crate::io::IoContext;
+use crate::muxer::Muxer;
+use crate::stream::Stream;
+
+/// 数据包交错器
+///
+/// ```rust,no_run
+/// use tao_format::interleave::Interleaver;
+/// # fn run(
+/// #     muxer: &mut dyn tao_format::Muxer,
+/// #     io: &mut tao_format::IoContext,
+/// #     streams: &[tao_format::Stream],
+/// #     packets: Vec<tao_codec::Packet>,
+/// # ) -> tao_core::TaoResult<()> {
+/// let mut interleaver = Interleaver::new(streams);
+/// muxer.write_header(io, streams)?;
+/// for pkt in packets {
+///     interleaver.write_packet(muxer, io, pkt)?;
+/// }
+/// interleaver.flush(muxer, io)?;
+/// muxer.write_trailer(io)
+/// # }
+/// ```
+pub struct Interleaver {
+    /// 各流时间基, 数据包未携带有效时间基时使用
+    time_bases: Vec<Rational>,
+    /// 各流待释放的数据包
+    queues: Vec<VecDeque<Packet>>,
+    /// 最大缓存时长 (秒)
+    max_buffer_duration: f64,
+}
+
+impl Interleaver {
+    /// 默认最大缓存时长 (秒), 与 FFmpeg `max_interleave_delta` 默认值一致
+    pub const DEFAULT_MAX_BUFFER_DURATION: f64 = 10.0;
+
+    /// 按输出流创建交错器
+    pub fn new(streams: &[Stream]) -> Self {
+        let mut interleaver = Self {
+            time_bases: Vec::new(),
+            queues: Vec::new(),
+            max_buffer_duration: Self::DEFAULT_MAX_BUFFER_DURATION,
+        };
+        for stream in streams {
+            interleaver.add_stream(stream);
+        }
+        interleaver
+    }
+
+    /// 设置最大缓存时长 (秒)
+    pub fn with_max_buffer_duration(mut self, seconds: f64) -> Self {
+        self.max_buffer_duration = seconds.max(0.0);
+        self
+    }
+
+    /// 追加一路流, 流索引按添加顺序分配
+    pub fn add_stream(&mut self, stream: &Stream) {
+        self.time_bases.push(stream.time_base);
+        self.queues.push(VecDeque::new());
+    }
+
+    /// 缓存中的数据包总数
+    pub fn buffered_packets(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    /// 缓存一个数据包
+    pub fn push(&mut self, packet: Packet) -> TaoResult<()> {
+        let queue = self
+            .queues
+            .get_mut(packet.stream_index)
+            .ok_or(TaoError::StreamNotFound(packet.stream_index))?;
+        queue.push_back(packet);
+        Ok(())
+    }
+
+    /// 取出下一个可释放的数据包
+    ///
+    /// `flush` 为真时忽略释放条件, 按 DTS 顺序取出全部剩余数据包.
+    pub fn pop(&mut self, flush: bool) -> Option<Packet> {
+        let (idx, head_dts) = self
+            .queues
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, q)| q.front().map(|pkt| (idx, self.dts_seconds(pkt))))
+            .min_by(|a, b| a.1.total_cmp(&b.1))?;
+        if !flush && self.queues.iter().any(VecDeque::is_empty) {
+            let newest_dts = self
+                .queues
+                .iter()
+                .filter_map(|q| q.back().map(|pkt| self.dts_seconds(pkt)))
+                .fold(f64::NEG_INFINITY, f64::max);
+            if newest_dts - head_dts <= self.max_buffer_duration {
+                return None;
+            }
+        }
+        self.queues[idx].pop_front()
+    }
+
+    /// 缓存数据包并写出所有可释放的数据包
+    pub fn write_packet(
+        &mut self,
+        muxer: &mut dyn Muxer,
+        io: &mut IoContext,
+        packet: Packet,
+    ) -> TaoResult<()> {
+        self.push(packet)?;
+        while let Some(pkt) = self.pop(false) {
+            muxer.write_packet(io, &pkt)?;
+        }
+        Ok(())
+    }
+
+    /// 按 DTS 顺序写出全部剩余数据包, 应在 `write_trailer()` 之前调用
+    pub fn flush(&mut self, muxer: &mut dyn Muxer, io: &mut IoContext) -> TaoResult<()> {
+        while let Some(pkt) = self.pop(true) {
+            muxer.write_packet(io, &pkt)?;
+        }
+        Ok(())
+    }
+
+    /// 数据包 DTS 换算为秒, 无 DTS 的数据包视为最早
+    fn dts_seconds(&self, pkt: &Packet) -> f64 {
+        if pkt.dts == NOPTS_VALUE {
+            return f64::NEG_INFINITY;
+        }
+        let time_base = if pkt.time_base.is_valid() {
+            pkt.time_base
+        } else {
+            self.time_bases[pkt.stream_index]
+        };
+        pkt.dts as f64 * time_base.to_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_id::FormatId;
+    use tao_codec::CodecId;
+    use tao_core::MediaType;
+
+    use crate::stream::StreamParams;
+
+    fn make_stream(index: usize, media_type: MediaType, time_base: Rational) -> Stream {
+        Stream {
+            index,
+            media_type,
+            codec_id: CodecId::None,
+            time_base,
+            duration: -1,
+            start_time: 0,
+            nb_frames: 0,
+            extra_data: Vec::new(),
+            params: StreamParams::Other,
+            metadata: Vec::new(),
+        }
+    }
+
+    fn make_packet(stream_index: usize, dts: i64) -> Packet {
+        let mut pkt = Packet::from_data(vec![0u8; 4]);
+        pkt.stream_index = stream_index;
+        pkt.dts = dts;
+        pkt.pts = dts;
+        pkt
+    }
+
+    /// 记录写入顺序的封装器
+    #[derive(Default)]
+    struct RecordingMuxer {
+        written: Vec<(usize, i64)>,
+    }
+
+    impl Muxer for RecordingMuxer {
+        fn format_id(&self) -> FormatId {
+            FormatId::Mp4
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn write_header(&mut self, _io: &mut IoContext, _streams: &[Stream]) -> TaoResult<()> {
+            Ok(())
+        }
+
+        fn write_packet(&mut self, _io: &mut IoContext, packet: &Packet) -> TaoResult<()> {
+            self.written.push((packet.stream_index, packet.dts));
+            Ok(())
+        }
+
+        fn write_trailer(&mut self, _io: &mut IoContext) -> TaoResult<()> {
+            Ok(())
+        }
+    }
+
+    fn av_streams() -> Vec<Stream> {
+        vec![
+            make_stream(0, MediaType::Video, Rational::new(1, 90000)),
+            make_stream(1, MediaType::Audio, Rational::new(1, 48000)),
+        ]
+    }
+
+    #[test]
+    fn test_interleaver_bursty_av_dts_monotonic() {
+        let streams = av_streams();
+        let mut interleaver = Interleaver::new(&streams);
+        let mut muxer = RecordingMuxer::default();
+        let mut io = IoContext::new_memory();
+
+        // 音频每次突发 2 秒 (约 94 包), 视频每次突发 1 秒 (30 帧), 交替到达
+        let mut audio_next = 0i64;
+        let mut video_next = 0i64;
+        for _ in 0..4 {
+            for _ in 0..94 {
+                let pkt = make_packet(1, audio_next * 1024);
+                interleaver.write_packet(&mut muxer, &mut io, pkt).unwrap();
+                audio_next += 1;
+            }
+            for _ in 0..2 {
+                for _ in 0..30 {
+                    let pkt = make_packet(0, video_next * 3000);
+                    interleaver.write_packet(&mut muxer, &mut io, pkt).unwrap();
+                    video_next += 1;
+                }
+            }
+        }
+        interleaver.flush(&mut muxer, &mut io).unwrap();
+
+        assert_eq!(
+            muxer.written.len() as i64,
+            audio_next + video_next,
+            "所有数据包都应写出"
+        );
+        assert_eq!(interleaver.buffered_packets(), 0, "刷新后缓存应为空");
+        let seconds: Vec<f64> = muxer
+            .written
+            .iter()
+            .map(|&(idx, dts)| dts as f64 * streams[idx].time_base.to_f64())
+            .collect();
+        assert!(
+            seconds.windows(2).all(|w| w[0] <= w[1]),
+            "交错后的 DTS (秒) 应单调递增"
+        );
+    }
+
+    #[test]
+    fn test_interleaver_max_buffer_duration_releases() {
+        let streams = av_streams();
+        let mut interleaver = Interleaver::new(&streams).with_max_buffer_duration(1.0);
+
+        // 只有视频到达: 缓存跨度不超过 1 秒时不释放
+        for i in 0..=30 {
+            interleaver.push(make_packet(0, i * 3000)).unwrap();
+        }
+        assert!(interleaver.pop(false).is_none(), "跨度未超限时应等待音频");
+
+        // 跨度超过 1 秒后按 DTS 顺序释放最早的数据包
+        interleaver.push(make_packet(0, 31 * 3000)).unwrap();
+        let pkt = interleaver.pop(false).expect("跨度超限时应释放");
+        assert_eq!(pkt.dts, 0);
+        assert!(interleaver.pop(false).is_none(), "释放后跨度回到上限内");
+    }
+
+    #[test]
+    fn test_interleaver_uses_packet_time_base() {
+        let streams = av_streams();
+        let mut interleaver = Interleaver::new(&streams);
+        // 视频包携带毫秒时间基: 100ms 晚于音频的 1024/48000 ≈ 21ms
+        let mut video = make_packet(0, 100);
+        video.time_base = Rational::new(1, 1000);
+        interleaver.push(video).unwrap();
+        interleaver.push(make_packet(1, 1024)).unwrap();
+        assert_eq!(interleaver.pop(false).unwrap().stream_index, 1);
+        assert!(interleaver.pop(false).is_none(), "音频队列为空时应等待");
+        assert_eq!(interleaver.pop(true).unwrap().stream_index, 0);
+        assert!(
+            interleaver.push(make_packet(5, 0)).is_err(),
+            "未知流索引应报错"
+        );
+    }
+}
@@ -4,7 +4,10 @@
 //! 支持文件、内存缓冲区、网络流等不同后端.
 
 use std::io::{self, Read, Seek, Write};
-use tao_core::TaoResult;
+use tao_codec::Packet;
+use tao_core::{TaoError, TaoResult};
+
+use crate::demuxer::Demuxer;
 
 /// I/O 上下文
 ///
@@ -40,6 +43,10 @@ pub trait IoBackend: Send {
     fn size(&self) -> Option<u64>;
     /// 是否支持 seek
     fn is_seekable(&self) -> bool;
+    /// 内存后端的全部数据, 其他后端返回 None
+    fn memory_data(&self) -> Option<&[u8]> {
+        None
+    }
 }
 
 /// 默认缓冲区大小 (32 KB)
@@ -68,6 +75,20 @@ impl IoContext {
         }
     }
 
+    /// 从内存数据创建 (只读)
+    ///
+    /// 用于测试中直接以字节构造解封装器输入, 无需临时文件.
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        Self::new(Box::new(MemoryBackend::read_only(data)))
+    }
+
+    /// 创建空内存缓冲区 (读写)
+    ///
+    /// 写入完成后可通过 [`IoContext::to_vec`] 取回数据.
+    pub fn new_memory() -> Self {
+        Self::new(Box::new(MemoryBackend::new()))
+    }
+
     /// 从文件路径打开 (只读)
     pub fn open_read(path: &str) -> TaoResult<Self> {
         let file = std::fs::File::open(path)?;
@@ -165,6 +186,13 @@ impl IoContext {
         Ok(i32::from_le_bytes(buf))
     }
 
+    /// 读取 u64 小端
+    pub fn read_u64_le(&mut self) -> TaoResult<u64> {
+        let mut buf = [0u8; 8];
+        self.read_exact(&mut buf)?;
+        Ok(u64::from_le_bytes(buf))
+    }
+
     /// 读取 u16 大端
     pub fn read_u16_be(&mut self) -> TaoResult<u16> {
         let mut buf = [0u8; 2];
@@ -277,6 +305,11 @@ impl IoContext {
         self.write_all(&v.to_le_bytes())
     }
 
+    /// 写入 u64 小端
+    pub fn write_u64_le(&mut self, v: u64) -> TaoResult<()> {
+        self.write_all(&v.to_le_bytes())
+    }
+
     /// 写入 u16 大端
     pub fn write_u16_be(&mut self, v: u16) -> TaoResult<()> {
         self.write_all(&v.to_be_bytes())
@@ -315,6 +348,13 @@ impl IoContext {
     ///
     /// 注意: seek 会清空读缓冲区.
     pub fn seek(&mut self, pos: io::SeekFrom) -> TaoResult<u64> {
+        // 相对偏移以逻辑位置为基准, 需扣除读缓冲区中尚未消耗的数据
+        let pos = match pos {
+            io::SeekFrom::Current(offset) => {
+                io::SeekFrom::Current(offset - (self.buf_len - self.buf_pos) as i64)
+            }
+            other => other,
+        };
         // 清空读缓冲区
         self.buf_pos = 0;
         self.buf_len = 0;
@@ -344,6 +384,17 @@ impl IoContext {
     pub fn source_path(&self) -> Option<&str> {
         self.source_path.as_deref()
     }
+
+    /// 复制内存后端中的全部数据
+    ///
+    /// 仅支持 [`IoContext::from_bytes`] / [`IoContext::new_memory`] 等内存后端,
+    /// 与当前读写位置无关.
+    pub fn to_vec(&self) -> TaoResult<Vec<u8>> {
+        self.inner
+            .memory_data()
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| tao_core::TaoError::Unsupported("非内存 I/O 后端无法导出数据".into()))
+    }
 }
 
 /// 文件 I/O 后端
@@ -397,22 +448,34 @@ pub struct MemoryBackend {
     data: Vec<u8>,
     /// 当前位置
     pos: usize,
+    /// 是否拒绝写入
+    read_only: bool,
 }
 
 impl MemoryBackend {
     /// 从已有数据创建 (用于读取)
     pub fn from_data(data: Vec<u8>) -> Self {
-        Self { data, pos: 0 }
+        Self {
+            data,
+            pos: 0,
+            read_only: false,
+        }
     }
 
-    /// 创建空缓冲区 (用于写入)
-    pub fn new() -> Self {
+    /// 从已有数据创建只读缓冲区, 写入返回 `PermissionDenied`
+    pub fn read_only(data: Vec<u8>) -> Self {
         Self {
-            data: Vec::new(),
+            data,
             pos: 0,
+            read_only: true,
         }
     }
 
+    /// 创建空缓冲区 (用于写入)
+    pub fn new() -> Self {
+        Self::from_data(Vec::new())
+    }
+
     /// 获取内部数据的引用
     pub fn data(&self) -> &[u8] {
         &self.data
@@ -443,6 +506,16 @@ impl IoBackend for MemoryBackend {
     }
 
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.read_only {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "只读内存缓冲区不可写入",
+            ));
+        }
+        // 写入位置超出末尾时以零填充空洞
+        if self.pos > self.data.len() {
+            self.data.resize(self.pos, 0);
+        }
         // 如果当前位置在数据末尾, 追加
         if self.pos >= self.data.len() {
             self.data.extend_from_slice(buf);
@@ -490,6 +563,10 @@ impl IoBackend for MemoryBackend {
     fn is_seekable(&self) -> bool {
         true
     }
+
+    fn memory_data(&self) -> Option<&[u8]> {
+        Some(&self.data)
+    }
 }
 
 // ========================
@@ -769,3 +846,226 @@ fn http_read_body_loop(
         }
     }
 }
+
+/// 解封装数据包迭代器
+///
+/// 封装 `demuxer.read_packet(io)` 读取循环, 遇到 [`TaoError::Eof`] 时结束迭代.
+/// 其他错误作为 `Err` 返回一次, 之后迭代结束.
+///
+/// ```ignore
+/// for pkt in PacketStream::new(demuxer.as_mut(), &mut io) {
+///     let pkt = pkt?;
+///     // ...
+/// }
+/// ```
+pub struct PacketStream<'a> {
+    demuxer: &'a mut dyn Demuxer,
+    io: &'a mut IoContext,
+    finished: bool,
+}
+
+impl<'a> PacketStream<'a> {
+    /// 创建数据包迭代器
+    pub fn new(demuxer: &'a mut dyn Demuxer, io: &'a mut IoContext) -> Self {
+        Self {
+            demuxer,
+            io,
+            finished: false,
+        }
+    }
+}
+
+impl Iterator for PacketStream<'_> {
+    type Item = TaoResult<Packet>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.demuxer.read_packet(self.io) {
+            Ok(packet) => Some(Ok(packet)),
+            Err(TaoError::Eof) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl std::iter::FusedIterator for PacketStream<'_> {}
+
+/// 只返回指定流数据包的 [`PacketStream`]
+///
+/// 其他流的数据包被跳过, 读取错误仍会返回.
+pub struct FilteredPacketStream<'a> {
+    inner: PacketStream<'a>,
+    stream_index: usize,
+}
+
+impl<'a> FilteredPacketStream<'a> {
+    /// 创建只返回 `stream_index` 流数据包的迭代器
+    pub fn new(demuxer: &'a mut dyn Demuxer, io: &'a mut IoContext, stream_index: usize) -> Self {
+        Self {
+            inner: PacketStream::new(demuxer, io),
+            stream_index,
+        }
+    }
+}
+
+impl Iterator for FilteredPacketStream<'_> {
+    type Item = TaoResult<Packet>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stream_index = self.stream_index;
+        self.inner.find(|pkt| {
+            pkt.as_ref()
+                .map_or(true, |p| p.stream_index == stream_index)
+        })
+    }
+}
+
+impl std::iter::FusedIterator for FilteredPacketStream<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_id::FormatId;
+    use crate::stream::Stream;
+
+    #[test]
+    fn test_io_from_bytes_read_seek_and_reject_write() {
+        let mut io = IoContext::from_bytes(vec![0x01, 0x02, 0x03, 0x04, 0x05]);
+        assert_eq!(io.size(), Some(5));
+        assert_eq!(io.read_u16_be().unwrap(), 0x0102);
+        assert_eq!(io.position().unwrap(), 2);
+        io.seek(io::SeekFrom::End(-1)).unwrap();
+        assert_eq!(io.read_u8().unwrap(), 0x05);
+        assert!(matches!(io.read_u8(), Err(tao_core::TaoError::Eof)));
+        assert!(io.write_u8(0xFF).is_err(), "只读内存上下文应拒绝写入");
+        assert_eq!(io.to_vec().unwrap(), vec![0x01, 0x02, 0x03, 0x04, 0x05]);
+    }
+
+    #[test]
+    fn test_io_seek_current_relative_to_buffered_position() {
+        let mut io = IoContext::from_bytes((0u8..16).collect());
+        assert_eq!(io.read_u8().unwrap(), 0);
+        assert_eq!(io.read_u8().unwrap(), 1);
+        // 整段数据已进入读缓冲区, 相对偏移仍应以已消耗的位置为基准
+        assert_eq!(io.seek(io::SeekFrom::Current(-1)).unwrap(), 1);
+        assert_eq!(io.read_u8().unwrap(), 1);
+        io.seek(io::SeekFrom::Current(3)).unwrap();
+        assert_eq!(io.read_u8().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_io_new_memory_write_patch_and_to_vec() {
+        let mut io = IoContext::new_memory();
+        io.write_tag(b"RIFF").unwrap();
+        io.write_u32_le(0).unwrap();
+        io.write_all(b"WAVE").unwrap();
+        // 回写大小字段, 与文件后端的用法一致
+        io.seek(io::SeekFrom::Start(4)).unwrap();
+        io.write_u32_le(4).unwrap();
+        io.seek(io::SeekFrom::End(0)).unwrap();
+        assert_eq!(io.position().unwrap(), 12);
+        assert_eq!(io.to_vec().unwrap(), b"RIFF\x04\x00\x00\x00WAVE");
+
+        // 超出末尾写入时以零填充
+        io.seek(io::SeekFrom::Start(14)).unwrap();
+        io.write_u8(0xAA).unwrap();
+        assert_eq!(&io.to_vec().unwrap()[12..], &[0x00, 0x00, 0xAA]);
+
+        io.seek(io::SeekFrom::Start(0)).unwrap();
+        assert_eq!(&io.read_tag().unwrap(), b"RIFF");
+    }
+
+    /// 按顺序返回预置数据包的解封装器, 可在末尾返回指定错误
+    struct ScriptedDemuxer {
+        packets: std::collections::VecDeque<usize>,
+        error: Option<TaoError>,
+    }
+
+    impl Demuxer for ScriptedDemuxer {
+        fn format_id(&self) -> FormatId {
+            FormatId::RawVideo
+        }
+
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        fn open(&mut self, _io: &mut IoContext) -> TaoResult<()> {
+            Ok(())
+        }
+
+        fn streams(&self) -> &[Stream] {
+            &[]
+        }
+
+        fn read_packet(&mut self, _io: &mut IoContext) -> TaoResult<Packet> {
+            match self.packets.pop_front() {
+                Some(stream_index) => {
+                    let mut pkt = Packet::from_data(vec![stream_index as u8]);
+                    pkt.stream_index = stream_index;
+                    Ok(pkt)
+                }
+                None => Err(self.error.take().unwrap_or(TaoError::Eof)),
+            }
+        }
+
+        fn seek(
+            &mut self,
+            _io: &mut IoContext,
+            _stream_index: usize,
+            _timestamp: i64,
+            _flags: crate::demuxer::SeekFlags,
+        ) -> TaoResult<()> {
+            Ok(())
+        }
+
+        fn duration(&self) -> Option<f64> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_packet_stream_stops_at_eof() {
+        let mut demuxer = ScriptedDemuxer {
+            packets: [0, 1, 0, 1].into(),
+            error: None,
+        };
+        let mut io = IoContext::from_bytes(Vec::new());
+        let indexes: Vec<usize> = PacketStream::new(&mut demuxer, &mut io)
+            .map(|pkt| pkt.unwrap().stream_index)
+            .collect();
+        assert_eq!(indexes, vec![0, 1, 0, 1], "应按顺序返回全部数据包");
+
+        let mut demuxer = ScriptedDemuxer {
+            packets: [0, 1, 0, 1, 1].into(),
+            error: None,
+        };
+        let indexes: Vec<usize> = FilteredPacketStream::new(&mut demuxer, &mut io, 1)
+            .map(|pkt| pkt.unwrap().stream_index)
+            .collect();
+        assert_eq!(indexes, vec![1, 1, 1], "应只返回指定流的数据包");
+    }
+
+    #[test]
+    fn test_packet_stream_propagates_error() {
+        let mut demuxer = ScriptedDemuxer {
+            packets: [0, 0].into(),
+            error: Some(TaoError::InvalidData("坏数据".into())),
+        };
+        let mut io = IoContext::from_bytes(Vec::new());
+        let mut stream = FilteredPacketStream::new(&mut demuxer, &mut io, 1);
+        assert!(
+            matches!(stream.next(), Some(Err(TaoError::InvalidData(_)))),
+            "过滤时读取错误仍应返回"
+        );
+        assert!(stream.next().is_none(), "返回错误后迭代应结束");
+    }
+}
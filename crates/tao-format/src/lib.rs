@@ -25,6 +25,7 @@
 pub mod demuxer;
 pub mod demuxers;
 pub mod format_id;
+pub mod interleave;
 pub mod io;
 pub mod muxer;
 pub mod muxers;
@@ -35,7 +36,8 @@ pub mod stream;
 // 重导出常用类型
 pub use demuxer::Demuxer;
 pub use format_id::FormatId;
-pub use io::IoContext;
+pub use interleave::Interleaver;
+pub use io::{FilteredPacketStream, IoContext, PacketStream};
 pub use muxer::Muxer;
 pub use probe::ProbeResult;
 pub use registry::FormatRegistry;
@@ -3,7 +3,7 @@
 //! 对标 FFmpeg 的 `AVOutputFormat`, 定义了将数据包写入容器格式的接口.
 
 use tao_codec::Packet;
-use tao_core::TaoResult;
+use tao_core::{TaoError, TaoResult};
 
 use crate::format_id::FormatId;
 use crate::io::IoContext;
@@ -25,6 +25,31 @@ pub trait Muxer: Send {
     /// 获取格式名称
     fn name(&self) -> &str;
 
+    /// 设置封装器私有选项 (如 MP4 的 `fragmented`)
+    ///
+    /// 对标 FFmpeg 封装器的 `AVOption`, 需在 `write_header()` 之前调用.
+    /// 默认实现不接受任何选项.
+    fn set_option(&mut self, key: &str, _value: &str) -> TaoResult<()> {
+        Err(TaoError::Unsupported(format!(
+            "封装器 {} 不支持选项 {}",
+            self.name(),
+            key
+        )))
+    }
+
+    /// 获取封装器私有选项的当前值, 不支持的选项返回 None
+    fn get_option(&self, _key: &str) -> Option<String> {
+        None
+    }
+
+    /// 容器是否在头部保存编解码器全局头
+    ///
+    /// 对标 FFmpeg 的 `AVFMT_GLOBALHEADER`. 为 true 时编码器应启用全局头
+    /// (`Encoder::set_global_header`), 并将 `extra_data()` 写入输出流.
+    fn wants_global_header(&self) -> bool {
+        false
+    }
+
     /// 写入容器头部
     ///
     /// # 参数
@@ -82,11 +82,11 @@ impl AacAdtsMuxer {
         let header: [u8; 7] = [
             0xFF,
             0xF1, // sync(4) + ID(0) + Layer(00) + Protection absent(1)
-            (profile << 6) | (sample_rate_index << 2) | ((channel_config >> 1) & 1),
-            ((channel_config & 1) << 7) | ((fl >> 6) & 0x07) as u8,
-            (fl >> 5) as u8,
-            ((fl & 0x1F) << 3) as u8 | 0x3F, // buffer fullness 0x7FF 高 5 位
-            0x7C,                            // buffer fullness 低 6 位 + raw_data_blocks(0)
+            (profile << 6) | (sample_rate_index << 2) | ((channel_config >> 2) & 1),
+            ((channel_config & 3) << 6) | ((fl >> 11) & 0x03) as u8,
+            (fl >> 3) as u8,
+            ((fl & 0x07) << 5) as u8 | 0x1F, // buffer fullness 0x7FF 高 5 位
+            0xFC,                            // buffer fullness 低 6 位 + raw_data_blocks(0)
         ];
         io.write_all(&header)
     }
@@ -129,6 +129,13 @@ impl Muxer for AacAdtsMuxer {
 
         self.sample_rate_index = Self::sample_rate_to_index(audio.sample_rate)?;
         self.channel_config = Self::channels_to_config(audio.channel_layout.channels)?;
+        // AudioSpecificConfig 前 5 位为 audioObjectType, ADTS profile = AOT - 1 (仅支持 1-4)
+        if let Some(&first) = stream.extra_data.first() {
+            let object_type = first >> 3;
+            if (1..=4).contains(&object_type) {
+                self.profile = object_type - 1;
+            }
+        }
 
         debug!(
             "AAC ADTS 写入头部: {} Hz (index={}), {} 声道",
@@ -186,6 +193,7 @@ mod tests {
                 sample_format: SampleFormat::S16,
                 bit_rate: 0,
                 frame_size: 1024,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         }
@@ -214,7 +222,7 @@ mod tests {
 
         // 模拟原始 AAC 帧数据 (任意字节)
         let data = vec![0x12, 0x34, 0x56, 0x78];
-        let pkt = Packet::from_data(bytes::Bytes::from(data));
+        let pkt = Packet::new(data, 0);
         muxer.write_packet(&mut io, &pkt).unwrap();
         muxer.write_trailer(&mut io).unwrap();
 
@@ -225,6 +233,15 @@ mod tests {
         assert!(output.len() >= 7, "应至少有 7 字节 ADTS 头");
         assert_eq!(output[0], 0xFF, "ADTS 同步字第一字节");
         assert_eq!(output[1] & 0xF0, 0xF0, "ADTS 同步字第二字节高 4 位");
+        let frame_length = (u16::from(output[3] & 0x03) << 11)
+            | (u16::from(output[4]) << 3)
+            | u16::from(output[5] >> 5);
+        assert_eq!(frame_length, 11, "frame_length 应为头部 + 数据长度");
+        assert_eq!(
+            ((output[2] & 0x01) << 2) | (output[3] >> 6),
+            1,
+            "声道配置应为单声道"
+        );
         assert_eq!(
             &output[7..11],
             &[0x12, 0x34, 0x56, 0x78],
@@ -214,6 +214,7 @@ mod tests {
                 sample_format: SampleFormat::S16,
                 bit_rate: 0,
                 frame_size: 0,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         }
@@ -230,7 +231,7 @@ mod tests {
         muxer.write_header(&mut io, &[stream]).unwrap();
 
         let pcm = vec![0x00, 0x01, 0x7F, 0xFF, 0x80, 0x00, 0x00, 0x01];
-        let pkt = Packet::from_data(bytes::Bytes::from(pcm));
+        let pkt = Packet::new(pcm, 0);
         muxer.write_packet(&mut io, &pkt).unwrap();
         muxer.write_trailer(&mut io).unwrap();
     }
@@ -246,7 +247,7 @@ mod tests {
 
         // 4 采样的 S16BE 单声道 = 8 字节
         let pcm_data = vec![0x00, 0x01, 0x7F, 0xFF, 0x80, 0x00, 0x00, 0x01];
-        let pkt = Packet::from_data(bytes::Bytes::from(pcm_data.clone()));
+        let pkt = Packet::new(pcm_data.clone(), 0);
         muxer.write_packet(&mut io_w, &pkt).unwrap();
         muxer.write_trailer(&mut io_w).unwrap();
 
@@ -3,9 +3,12 @@
 //! 将音视频数据包封装到 AVI 容器.
 //!
 //! 写入流程:
-//! 1. `write_header()` - 写入 RIFF/AVI 头, hdrl 列表 (avih, strl/strh/strf)
-//! 2. `write_packet()` - 写入数据块 (00dc/01wb 等) 并记录 idx1 条目
-//! 3. `write_trailer()` - 写入 idx1 索引并回填 RIFF 大小
+//! 1. `write_header()` - 写入 RIFF/AVI 头, hdrl 列表 (avih, strl/strh/strf),
+//!    并为 OpenDML 超级索引 (indx) 与 odml 扩展头预留 JUNK 占位块
+//! 2. `write_packet()` - 写入数据块 (00dc/01wb 等) 并记录索引条目.
+//!    当前 RIFF 超过 1 GB 时写入 ix## 标准索引, 结束当前 RIFF 并开启新的 `RIFF 'AVIX'`
+//! 3. `write_trailer()` - 写入 idx1 索引 (仅第一个 RIFF), 多 RIFF 时将占位块
+//!    改写为 indx 超级索引与 `LIST 'odml'`, 并回填各大小与帧数字段
 
 use log::debug;
 use tao_codec::{CodecId, Packet};
@@ -29,38 +32,98 @@ const WAV_FORMAT_IEEE_FLOAT: u16 = 0x0003;
 /// idx1 索引条目标志: 关键帧
 const AVIIF_KEYFRAME: u32 = 0x10;
 
-/// idx1 索引条目
-struct Idx1Entry {
-    chunk_id: [u8; 4],
-    flags: u32,
-    offset: u32,
+/// 单个 RIFF 块的大小上限, 超过后切换到新的 AVIX 块 (与 FFmpeg 一致)
+const AVI_MAX_RIFF_SIZE: u64 = 1 << 30;
+/// 每个流的 indx 超级索引预留条目数
+const AVI_MASTER_INDEX_SIZE: usize = 256;
+/// indx 超级索引块的数据大小 (头部 24 字节 + 每条目 16 字节)
+const AVI_INDX_DATA_SIZE: u32 = 24 + 16 * AVI_MASTER_INDEX_SIZE as u32;
+/// `LIST 'odml'` 的数据大小 ('odml' + dmlh 块头 + 248 字节 dmlh)
+const AVI_ODML_DATA_SIZE: u32 = 4 + 8 + 248;
+/// OpenDML 索引类型: 超级索引 (索引的索引)
+const AVI_INDEX_OF_INDEXES: u8 = 0x00;
+/// OpenDML 索引类型: 标准索引 (数据块索引)
+const AVI_INDEX_OF_CHUNKS: u8 = 0x01;
+/// OpenDML 标准索引条目大小字段的非关键帧标志
+const AVI_INDEX_DELTA_FRAME: u32 = 0x8000_0000;
+
+/// 当前 RIFF 块中的数据块索引条目
+struct IndexEntry {
+    /// 流索引
+    stream_index: usize,
+    /// 块头的绝对文件偏移
+    offset: u64,
+    /// 数据大小
     size: u32,
+    /// 是否为关键帧
+    keyframe: bool,
+}
+
+/// indx 超级索引条目, 指向一个 ix## 标准索引块
+struct SuperIndexEntry {
+    /// ix## 块的绝对文件偏移
+    offset: u64,
+    /// ix## 块的总大小 (含块头)
+    size: u32,
+    /// 该标准索引覆盖的时长 (以流时间基为单位)
+    duration: u32,
 }
 
 /// AVI 封装器
 pub struct AviMuxer {
     /// 流信息
     streams: Vec<Stream>,
-    /// movi 列表数据起始位置 (用于 idx1 的 offset 计算)
-    movi_data_start: u64,
-    /// movi 列表大小字段的文件偏移
-    movi_size_offset: u64,
-    /// RIFF 大小字段的文件偏移
+    /// 每流的 dwSampleSize (PCM 音频为 block_align, 其他为 0)
+    sample_sizes: Vec<u32>,
+    /// 每流的累计长度 (PCM 为采样数, 其他为块数)
+    stream_lengths: Vec<u64>,
+    /// 当前 RIFF 大小字段的文件偏移
     riff_size_offset: u64,
-    /// idx1 索引条目
-    idx1_entries: Vec<Idx1Entry>,
+    /// 当前 movi 列表大小字段的文件偏移
+    movi_size_offset: u64,
+    /// 已开启的 RIFF 块数量 (1 = 仅 'AVI ', 大于 1 表示已写入 AVIX)
+    riff_count: usize,
+    /// 当前 RIFF 块中的索引条目
+    entries: Vec<IndexEntry>,
+    /// 每流的 indx 超级索引条目
+    super_indexes: Vec<Vec<SuperIndexEntry>>,
+    /// 每流 indx 占位块的文件偏移
+    indx_offsets: Vec<u64>,
+    /// odml 占位块的文件偏移
+    odml_offset: u64,
+    /// avih dwTotalFrames 字段的文件偏移
+    total_frames_offset: u64,
+    /// 每流 strh dwLength 字段的文件偏移
+    length_offsets: Vec<u64>,
+    /// 第一个 RIFF 块中的视频帧数 (写入 avih)
+    first_riff_frames: u32,
+    /// 单个 RIFF 块的大小上限
+    max_riff_size: u64,
 }
 
 impl AviMuxer {
-    /// 创建 AVI 封装器实例 (工厂函数)
-    pub fn create() -> TaoResult<Box<dyn Muxer>> {
-        Ok(Box::new(Self {
+    fn new() -> Self {
+        Self {
             streams: Vec::new(),
-            movi_data_start: 0,
-            movi_size_offset: 0,
+            sample_sizes: Vec::new(),
+            stream_lengths: Vec::new(),
             riff_size_offset: 0,
-            idx1_entries: Vec::new(),
-        }))
+            movi_size_offset: 0,
+            riff_count: 0,
+            entries: Vec::new(),
+            super_indexes: Vec::new(),
+            indx_offsets: Vec::new(),
+            odml_offset: 0,
+            total_frames_offset: 0,
+            length_offsets: Vec::new(),
+            first_riff_frames: 0,
+            max_riff_size: AVI_MAX_RIFF_SIZE,
+        }
+    }
+
+    /// 创建 AVI 封装器实例 (工厂函数)
+    pub fn create() -> TaoResult<Box<dyn Muxer>> {
+        Ok(Box::new(Self::new()))
     }
 
     /// 视频 CodecId -> FourCC handler
@@ -96,16 +159,40 @@ impl AviMuxer {
         }
     }
 
-    /// 写入 strh 块
+    /// 音频流的 block_align (每个采样帧的字节数)
+    fn audio_block_align(stream: &Stream) -> TaoResult<u16> {
+        let params = match &stream.params {
+            StreamParams::Audio(a) => a,
+            _ => {
+                return Err(TaoError::InvalidArgument("期望音频流参数".into()));
+            }
+        };
+        let (_, bits_per_sample) = Self::audio_codec_to_wav_format(stream.codec_id)?;
+        Ok(params.channel_layout.channels as u16 * (bits_per_sample / 8))
+    }
+
+    /// 数据块 ID (如 "00dc", "01wb")
+    fn chunk_id(stream_index: usize, media_type: MediaType) -> [u8; 4] {
+        let code = if media_type == MediaType::Video {
+            "dc"
+        } else {
+            "wb"
+        };
+        let mut cid = [0u8; 4];
+        cid.copy_from_slice(format!("{:02}{}", stream_index, code).as_bytes());
+        cid
+    }
+
+    /// 写入 strh 块, 返回 dwLength 字段的文件偏移
     fn write_strh(
         io: &mut IoContext,
-        _stream_index: usize,
         stream: &Stream,
         scale: u32,
         rate: u32,
-    ) -> TaoResult<()> {
+        sample_size: u32,
+    ) -> TaoResult<u64> {
         io.write_tag(b"strh")?;
-        io.write_u32_le(48)?;
+        io.write_u32_le(56)?;
 
         match stream.media_type {
             MediaType::Video => {
@@ -125,20 +212,21 @@ impl AviMuxer {
             }
         }
 
-        io.write_u32_le(0)?;
-        io.write_u16_le(0)?;
-        io.write_u16_le(0)?;
-        io.write_u32_le(0)?;
+        io.write_u32_le(0)?; // dwFlags
+        io.write_u16_le(0)?; // wPriority
+        io.write_u16_le(0)?; // wLanguage
+        io.write_u32_le(0)?; // dwInitialFrames
         io.write_u32_le(scale)?;
         io.write_u32_le(rate)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-
-        Ok(())
+        io.write_u32_le(0)?; // dwStart
+        let length_offset = io.position()?;
+        io.write_u32_le(0)?; // dwLength (尾部回填)
+        io.write_u32_le(0)?; // dwSuggestedBufferSize
+        io.write_u32_le(0xFFFF_FFFF)?; // dwQuality (-1 = 默认)
+        io.write_u32_le(sample_size)?;
+        io.write_all(&[0u8; 8])?; // rcFrame
+
+        Ok(length_offset)
     }
 
     /// 写入 strf 块 (视频: BITMAPINFOHEADER)
@@ -167,10 +255,11 @@ impl AviMuxer {
             _ => 0,
         };
         io.write_u32_le(bi_compression)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
+        io.write_u32_le(0)?; // biSizeImage
+        io.write_u32_le(0)?; // biXPelsPerMeter
+        io.write_u32_le(0)?; // biYPelsPerMeter
+        io.write_u32_le(0)?; // biClrUsed
+        io.write_u32_le(0)?; // biClrImportant
 
         Ok(())
     }
@@ -202,6 +291,204 @@ impl AviMuxer {
 
         Ok(())
     }
+
+    /// 写入指定数据大小的 JUNK 占位块, 返回块头的文件偏移
+    fn write_junk(io: &mut IoContext, data_size: u32) -> TaoResult<u64> {
+        let offset = io.position()?;
+        io.write_tag(b"JUNK")?;
+        io.write_u32_le(data_size)?;
+        io.write_all(&vec![0u8; data_size as usize])?;
+        Ok(offset)
+    }
+
+    /// 回填大小字段: 值为 `end - size_offset - 4`, 写完后回到 `end`
+    fn patch_size(io: &mut IoContext, size_offset: u64, end: u64) -> TaoResult<()> {
+        io.seek(std::io::SeekFrom::Start(size_offset))?;
+        io.write_u32_le((end - size_offset - 4) as u32)?;
+        io.seek(std::io::SeekFrom::Start(end))?;
+        Ok(())
+    }
+
+    /// 开启新的 RIFF 块 (form 为 'AVI ' 或 'AVIX')
+    fn start_riff(&mut self, io: &mut IoContext, form: &[u8; 4]) -> TaoResult<()> {
+        io.write_tag(b"RIFF")?;
+        self.riff_size_offset = io.position()?;
+        io.write_u32_le(0)?;
+        io.write_tag(form)?;
+        self.riff_count += 1;
+        Ok(())
+    }
+
+    /// 开启 movi 列表
+    fn start_movi(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        io.write_tag(b"LIST")?;
+        self.movi_size_offset = io.position()?;
+        io.write_u32_le(0)?;
+        io.write_tag(b"movi")?;
+        Ok(())
+    }
+
+    /// 一个 RIFF 块内某条流的时长 (PCM 为采样数, 其他为块数)
+    fn riff_duration(&self, stream_index: usize) -> u64 {
+        self.entries
+            .iter()
+            .filter(|e| e.stream_index == stream_index)
+            .map(|e| self.chunk_duration(stream_index, e.size))
+            .sum()
+    }
+
+    /// 单个数据块的时长 (PCM 为采样数, 其他为 1)
+    fn chunk_duration(&self, stream_index: usize, size: u32) -> u64 {
+        size.checked_div(self.sample_sizes[stream_index])
+            .map_or(1, u64::from)
+    }
+
+    /// 在当前 movi 列表末尾为每条流写入 ix## 标准索引, 并登记到超级索引
+    fn write_std_indexes(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        // 以当前 RIFF 块起始为基准, 单个 RIFF 不超过 4 GB, 相对偏移可用 32 位表示
+        let base_offset = self.riff_size_offset - 4;
+        for stream_index in 0..self.streams.len() {
+            let entries: Vec<&IndexEntry> = self
+                .entries
+                .iter()
+                .filter(|e| e.stream_index == stream_index)
+                .collect();
+            if entries.is_empty() {
+                continue;
+            }
+            if self.super_indexes[stream_index].len() >= AVI_MASTER_INDEX_SIZE {
+                return Err(TaoError::Unsupported(format!(
+                    "AVI: 流 {} 的 OpenDML 超级索引已满 ({} 条)",
+                    stream_index, AVI_MASTER_INDEX_SIZE
+                )));
+            }
+
+            let ix_offset = io.position()?;
+            let cb = 24 + 8 * entries.len() as u32;
+            io.write_all(format!("ix{:02}", stream_index).as_bytes())?;
+            io.write_u32_le(cb)?;
+            io.write_u16_le(2)?; // wLongsPerEntry
+            io.write_u8(0)?; // bIndexSubType
+            io.write_u8(AVI_INDEX_OF_CHUNKS)?;
+            io.write_u32_le(entries.len() as u32)?;
+            io.write_tag(&Self::chunk_id(
+                stream_index,
+                self.streams[stream_index].media_type,
+            ))?;
+            io.write_u64_le(base_offset)?;
+            io.write_u32_le(0)?; // dwReserved
+            for entry in &entries {
+                // 偏移指向数据 (跳过 8 字节块头)
+                io.write_u32_le((entry.offset + 8 - base_offset) as u32)?;
+                let delta = if entry.keyframe {
+                    0
+                } else {
+                    AVI_INDEX_DELTA_FRAME
+                };
+                io.write_u32_le(entry.size | delta)?;
+            }
+
+            let duration = self.riff_duration(stream_index);
+            self.super_indexes[stream_index].push(SuperIndexEntry {
+                offset: ix_offset,
+                size: 8 + cb,
+                duration: duration as u32,
+            });
+        }
+        Ok(())
+    }
+
+    /// 写入 idx1 旧式索引 (偏移相对 'movi' 标签), 仅覆盖第一个 RIFF 块
+    fn write_idx1(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        let movi_tag_pos = self.movi_size_offset + 4;
+        io.write_tag(b"idx1")?;
+        io.write_u32_le((self.entries.len() * 16) as u32)?;
+        for entry in &self.entries {
+            let media_type = self.streams[entry.stream_index].media_type;
+            io.write_tag(&Self::chunk_id(entry.stream_index, media_type))?;
+            io.write_u32_le(if entry.keyframe { AVIIF_KEYFRAME } else { 0 })?;
+            io.write_u32_le((entry.offset - movi_tag_pos) as u32)?;
+            io.write_u32_le(entry.size)?;
+        }
+
+        if let Some(video) = self
+            .streams
+            .iter()
+            .position(|s| s.media_type == MediaType::Video)
+        {
+            self.first_riff_frames = self.riff_duration(video) as u32;
+        }
+        debug!("AVI 写入 idx1: 条目数={}", self.entries.len());
+        Ok(())
+    }
+
+    /// 结束当前 movi 列表与 RIFF 块 (第一个 RIFF 块附带 idx1)
+    fn finish_riff(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        let seekable = io.is_seekable();
+        let movi_end = io.position()?;
+        if seekable {
+            Self::patch_size(io, self.movi_size_offset, movi_end)?;
+        }
+        if self.riff_count == 1 {
+            self.write_idx1(io)?;
+        }
+        let riff_end = io.position()?;
+        if seekable {
+            Self::patch_size(io, self.riff_size_offset, riff_end)?;
+        }
+        self.entries.clear();
+        Ok(())
+    }
+
+    /// 当前 RIFF 块写满后切换到新的 'AVIX' 块
+    fn start_avix(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        self.write_std_indexes(io)?;
+        self.finish_riff(io)?;
+        self.start_riff(io, b"AVIX")?;
+        self.start_movi(io)?;
+        debug!("AVI: RIFF 超过上限, 开启第 {} 个 RIFF 块", self.riff_count);
+        Ok(())
+    }
+
+    /// 将 indx 与 odml 占位块改写为实际内容
+    fn write_odml_headers(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        let end = io.position()?;
+        for (stream_index, entries) in self.super_indexes.iter().enumerate() {
+            io.seek(std::io::SeekFrom::Start(self.indx_offsets[stream_index]))?;
+            io.write_tag(b"indx")?;
+            io.write_u32_le(AVI_INDX_DATA_SIZE)?;
+            io.write_u16_le(4)?; // wLongsPerEntry
+            io.write_u8(0)?; // bIndexSubType
+            io.write_u8(AVI_INDEX_OF_INDEXES)?;
+            io.write_u32_le(entries.len() as u32)?;
+            io.write_tag(&Self::chunk_id(
+                stream_index,
+                self.streams[stream_index].media_type,
+            ))?;
+            io.write_all(&[0u8; 12])?; // dwReserved[3]
+            for entry in entries {
+                io.write_u64_le(entry.offset)?;
+                io.write_u32_le(entry.size)?;
+                io.write_u32_le(entry.duration)?;
+            }
+        }
+
+        let total_frames = self
+            .streams
+            .iter()
+            .position(|s| s.media_type == MediaType::Video)
+            .map_or(0, |i| self.stream_lengths[i]);
+        io.seek(std::io::SeekFrom::Start(self.odml_offset))?;
+        io.write_tag(b"LIST")?;
+        io.write_u32_le(AVI_ODML_DATA_SIZE)?;
+        io.write_tag(b"odml")?;
+        io.write_tag(b"dmlh")?;
+        io.write_u32_le(AVI_ODML_DATA_SIZE - 12)?;
+        io.write_u32_le(total_frames.min(u64::from(u32::MAX)) as u32)?;
+
+        io.seek(std::io::SeekFrom::Start(end))?;
+        Ok(())
+    }
 }
 
 impl Muxer for AviMuxer {
@@ -219,11 +506,19 @@ impl Muxer for AviMuxer {
         }
 
         self.streams = streams.to_vec();
-
-        io.write_tag(b"RIFF")?;
-        self.riff_size_offset = io.position()?;
-        io.write_u32_le(0)?;
-        io.write_tag(b"AVI ")?;
+        self.sample_sizes = streams
+            .iter()
+            .map(|s| match s.media_type {
+                MediaType::Audio => Self::audio_block_align(s).map(u32::from),
+                _ => Ok(0),
+            })
+            .collect::<TaoResult<_>>()?;
+        self.stream_lengths = vec![0; streams.len()];
+        self.super_indexes = streams.iter().map(|_| Vec::new()).collect();
+        self.indx_offsets.clear();
+        self.length_offsets.clear();
+
+        self.start_riff(io, b"AVI ")?;
 
         let hdrl_start = io.position()?;
         io.write_tag(b"LIST")?;
@@ -233,21 +528,18 @@ impl Muxer for AviMuxer {
 
         io.write_tag(b"avih")?;
         io.write_u32_le(56)?;
-        io.write_u32_le(33367)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0x10)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
+        io.write_u32_le(33367)?; // dwMicroSecPerFrame
+        io.write_u32_le(0)?; // dwMaxBytesPerSec
+        io.write_u32_le(0)?; // dwPaddingGranularity
+        io.write_u32_le(0x10)?; // dwFlags: AVIF_HASINDEX
+        self.total_frames_offset = io.position()?;
+        io.write_u32_le(0)?; // dwTotalFrames (尾部回填)
+        io.write_u32_le(0)?; // dwInitialFrames
         io.write_u32_le(streams.len() as u32)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
-        io.write_u32_le(0)?;
+        io.write_u32_le(0)?; // dwSuggestedBufferSize
+        io.write_u32_le(0)?; // dwWidth
+        io.write_u32_le(0)?; // dwHeight
+        io.write_all(&[0u8; 16])?; // dwReserved[4]
 
         for (idx, stream) in streams.iter().enumerate() {
             let strl_start = io.position()?;
@@ -274,7 +566,8 @@ impl Muxer for AviMuxer {
                 _ => (1, 25),
             };
 
-            Self::write_strh(io, idx, stream, scale, rate)?;
+            let length_offset = Self::write_strh(io, stream, scale, rate, self.sample_sizes[idx])?;
+            self.length_offsets.push(length_offset);
 
             match stream.media_type {
                 MediaType::Video => Self::write_strf_video(io, stream)?,
@@ -282,6 +575,10 @@ impl Muxer for AviMuxer {
                 _ => {}
             }
 
+            // 为 OpenDML 超级索引预留空间, 文件不超过 1 GB 时保持为 JUNK
+            let indx_offset = Self::write_junk(io, AVI_INDX_DATA_SIZE)?;
+            self.indx_offsets.push(indx_offset);
+
             let strl_end = io.position()?;
             let strl_size = (strl_end - strl_start - 8) as u32;
             io.seek(std::io::SeekFrom::Start(strl_size_offset))?;
@@ -289,22 +586,21 @@ impl Muxer for AviMuxer {
             io.seek(std::io::SeekFrom::Start(strl_end))?;
         }
 
+        // 为 LIST 'odml' 预留空间
+        self.odml_offset = Self::write_junk(io, AVI_ODML_DATA_SIZE)?;
+
         let hdrl_end = io.position()?;
         let hdrl_size = (hdrl_end - hdrl_start - 8) as u32;
         io.seek(std::io::SeekFrom::Start(hdrl_size_offset))?;
         io.write_u32_le(hdrl_size)?;
         io.seek(std::io::SeekFrom::Start(hdrl_end))?;
 
-        io.write_tag(b"LIST")?;
-        self.movi_size_offset = io.position()?;
-        io.write_u32_le(0)?;
-        io.write_tag(b"movi")?;
-        self.movi_data_start = io.position()?;
+        self.start_movi(io)?;
 
         debug!(
             "AVI 写入头部: {} 个流, movi 起始={}",
             streams.len(),
-            self.movi_data_start
+            self.movi_size_offset + 8
         );
 
         Ok(())
@@ -318,68 +614,67 @@ impl Muxer for AviMuxer {
         let stream_index = packet
             .stream_index
             .min(self.streams.len().saturating_sub(1));
-        let stream = &self.streams[stream_index];
-
-        let (chunk_id, is_keyframe) = match stream.media_type {
-            MediaType::Video => {
-                let id = format!("{:02}dc", stream_index);
-                let mut cid = [0u8; 4];
-                cid.copy_from_slice(id.as_bytes());
-                (cid, packet.is_keyframe)
-            }
-            MediaType::Audio => {
-                let id = format!("{:02}wb", stream_index);
-                let mut cid = [0u8; 4];
-                cid.copy_from_slice(id.as_bytes());
-                (cid, true)
-            }
+        let media_type = self.streams[stream_index].media_type;
+        let is_keyframe = match media_type {
+            MediaType::Video => packet.is_keyframe(),
+            MediaType::Audio => true,
             _ => return Ok(()),
         };
 
-        let offset = (io.position()? - self.movi_data_start) as u32;
         let size = packet.data.len() as u32;
+        let chunk_size = 8 + u64::from(size) + u64::from(size % 2);
+        let riff_start = self.riff_size_offset - 4;
+        if !self.entries.is_empty()
+            && io.is_seekable()
+            && io.position()? + chunk_size - riff_start > self.max_riff_size
+        {
+            self.start_avix(io)?;
+        }
 
-        io.write_tag(&chunk_id)?;
+        let offset = io.position()?;
+        io.write_tag(&Self::chunk_id(stream_index, media_type))?;
         io.write_u32_le(size)?;
         io.write_all(&packet.data)?;
         if size % 2 != 0 {
             io.write_u8(0)?;
         }
 
-        self.idx1_entries.push(Idx1Entry {
-            chunk_id,
-            flags: if is_keyframe { AVIIF_KEYFRAME } else { 0 },
+        self.stream_lengths[stream_index] += self.chunk_duration(stream_index, size);
+        self.entries.push(IndexEntry {
+            stream_index,
             offset,
             size,
+            keyframe: is_keyframe,
         });
 
         Ok(())
     }
 
     fn write_trailer(&mut self, io: &mut IoContext) -> TaoResult<()> {
-        let pos_before_idx1 = io.position()?;
-        let movi_size = (pos_before_idx1 - self.movi_data_start + 4) as u32;
-
-        io.write_tag(b"idx1")?;
-        let idx1_size = (self.idx1_entries.len() * 16) as u32;
-        io.write_u32_le(idx1_size)?;
-        for entry in &self.idx1_entries {
-            io.write_tag(&entry.chunk_id)?;
-            io.write_u32_le(entry.flags)?;
-            io.write_u32_le(entry.offset)?;
-            io.write_u32_le(entry.size)?;
+        let odml = self.riff_count > 1;
+        if odml {
+            self.write_std_indexes(io)?;
         }
+        self.finish_riff(io)?;
 
         if io.is_seekable() {
-            let file_size = io.position()?;
-            let riff_size = (file_size - 8) as u32;
-            io.seek(std::io::SeekFrom::Start(self.riff_size_offset))?;
-            io.write_u32_le(riff_size)?;
-            io.seek(std::io::SeekFrom::Start(self.movi_size_offset))?;
-            io.write_u32_le(movi_size)?;
+            if odml {
+                self.write_odml_headers(io)?;
+            }
+            let end = io.position()?;
+            io.seek(std::io::SeekFrom::Start(self.total_frames_offset))?;
+            io.write_u32_le(self.first_riff_frames)?;
+            for (offset, length) in self.length_offsets.iter().zip(&self.stream_lengths) {
+                io.seek(std::io::SeekFrom::Start(*offset))?;
+                io.write_u32_le((*length).min(u64::from(u32::MAX)) as u32)?;
+            }
+            io.seek(std::io::SeekFrom::Start(end))?;
         }
 
-        debug!("AVI 写入尾部: idx1 条目数={}", self.idx1_entries.len());
+        debug!(
+            "AVI 写入尾部: RIFF 块数={}, 各流长度={:?}",
+            self.riff_count, self.stream_lengths
+        );
 
         Ok(())
     }
@@ -410,6 +705,8 @@ mod tests {
                 frame_rate: Rational::new(25, 1),
                 sample_aspect_ratio: Rational::new(1, 1),
                 bit_rate: 0,
+                color_space: Default::default(),
+                color_range: Default::default(),
             }),
             metadata: Vec::new(),
         }
@@ -431,6 +728,7 @@ mod tests {
                 sample_format: SampleFormat::S16,
                 bit_rate: 0,
                 frame_size: 4,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         }
@@ -458,7 +756,7 @@ mod tests {
         let mut muxer = AviMuxer::create().unwrap();
         muxer.write_header(&mut io, &[stream]).unwrap();
 
-        let pkt = Packet::from_data(bytes::Bytes::from(vec![0u8; 100]));
+        let pkt = Packet::new(vec![0u8; 100], 0);
         muxer.write_packet(&mut io, &pkt).unwrap();
         muxer.write_trailer(&mut io).unwrap();
     }
@@ -474,7 +772,7 @@ mod tests {
         let mut muxer = AviMuxer::create().unwrap();
         muxer.write_header(&mut io, &[video, audio]).unwrap();
 
-        let pkt = Packet::from_data(bytes::Bytes::from(vec![0u8; 50]));
+        let pkt = Packet::new(vec![0u8; 50], 0);
         muxer.write_packet(&mut io, &pkt).unwrap();
         muxer.write_trailer(&mut io).unwrap();
     }
@@ -488,4 +786,95 @@ mod tests {
         let err = muxer.write_header(&mut io, &[]).unwrap_err();
         assert!(matches!(err, TaoError::InvalidArgument(_)));
     }
+
+    /// 写入 60 个视频包 (每 10 帧一个关键帧) 与 60 个 PCM 音频包, 返回可读取的 IoContext
+    fn mux_av(max_riff_size: u64) -> IoContext {
+        let mut io = IoContext::new(Box::new(MemoryBackend::new()));
+        let mut muxer = AviMuxer::new();
+        muxer.max_riff_size = max_riff_size;
+        muxer
+            .write_header(&mut io, &[make_video_stream(), make_audio_stream()])
+            .unwrap();
+        for i in 0..60u8 {
+            let mut video = Packet::new(vec![i; 301], 0);
+            video.set_keyframe(i % 10 == 0);
+            muxer.write_packet(&mut io, &video).unwrap();
+            // 立体声 S16: 每包 100 个采样
+            muxer
+                .write_packet(&mut io, &Packet::new(vec![i; 400], 1))
+                .unwrap();
+        }
+        muxer.write_trailer(&mut io).unwrap();
+        io.seek(std::io::SeekFrom::Start(0)).unwrap();
+        io
+    }
+
+    fn contains_tag(io: &mut IoContext, tag: &[u8; 4]) -> bool {
+        io.seek(std::io::SeekFrom::Start(0)).unwrap();
+        let mut data = Vec::new();
+        while let Ok(b) = io.read_u8() {
+            data.push(b);
+        }
+        io.seek(std::io::SeekFrom::Start(0)).unwrap();
+        data.windows(4).any(|w| w == tag)
+    }
+
+    fn assert_roundtrip(io: &mut IoContext) {
+        let mut demuxer = crate::demuxers::avi::AviDemuxer::create().unwrap();
+        demuxer.open(io).unwrap();
+        let streams = demuxer.streams();
+        assert_eq!(streams[0].nb_frames, 60, "视频帧数应覆盖整个文件");
+        assert_eq!(streams[0].duration, 60, "视频时长应覆盖整个文件");
+        assert_eq!(streams[1].nb_frames, 60, "音频包数应覆盖整个文件");
+        assert_eq!(streams[1].duration, 6000, "PCM 时长应为采样数");
+
+        let mut count = [0usize; 2];
+        loop {
+            match demuxer.read_packet(io) {
+                Ok(pkt) => {
+                    let i = count[pkt.stream_index];
+                    assert_eq!(pkt.data[0], i as u8, "数据包内容应按写入顺序");
+                    if pkt.stream_index == 0 {
+                        assert_eq!(pkt.data.len(), 301);
+                        assert_eq!(pkt.is_keyframe(), i % 10 == 0, "关键帧标志应保留");
+                        assert_eq!(pkt.pts, i as i64);
+                    } else {
+                        assert_eq!(pkt.pts, i as i64 * 100, "PCM PTS 应按采样数累加");
+                    }
+                    count[pkt.stream_index] += 1;
+                }
+                Err(TaoError::Eof) => break,
+                Err(e) => panic!("读取数据包失败: {e}"),
+            }
+        }
+        assert_eq!(count, [60, 60], "应读出全部数据包");
+
+        // seek 到第 25 帧应回退到第 20 帧的关键帧
+        demuxer
+            .seek(io, 0, 25, crate::demuxer::SeekFlags::default())
+            .unwrap();
+        let pkt = demuxer.read_packet(io).unwrap();
+        assert_eq!((pkt.stream_index, pkt.pts), (0, 20), "应定位到之前的关键帧");
+        assert_eq!(pkt.data[0], 20);
+    }
+
+    #[test]
+    fn test_avi_single_riff_roundtrip() {
+        let mut io = mux_av(AVI_MAX_RIFF_SIZE);
+        assert!(!contains_tag(&mut io, b"AVIX"), "未超过上限时不应写入 AVIX");
+        assert!(
+            !contains_tag(&mut io, b"indx"),
+            "未超过上限时超级索引应保持为 JUNK"
+        );
+        assert_roundtrip(&mut io);
+    }
+
+    #[test]
+    fn test_avi_odml_roundtrip() {
+        let mut io = mux_av(8 * 1024);
+        assert!(contains_tag(&mut io, b"AVIX"), "超过上限时应写入 AVIX");
+        assert!(contains_tag(&mut io, b"indx"), "应写入 indx 超级索引");
+        assert!(contains_tag(&mut io, b"dmlh"), "应写入 odml 扩展头");
+        assert_roundtrip(&mut io);
+    }
 }
@@ -277,6 +277,7 @@ mod tests {
                 sample_format: SampleFormat::S16,
                 bit_rate: 0,
                 frame_size: 4096,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         }
@@ -113,6 +113,10 @@ impl Muxer for FlvMuxer {
         "flv"
     }
 
+    fn wants_global_header(&self) -> bool {
+        true
+    }
+
     fn write_header(&mut self, io: &mut IoContext, streams: &[Stream]) -> TaoResult<()> {
         if streams.is_empty() {
             return Err(TaoError::InvalidArgument("FLV: 没有输入流".into()));
@@ -197,7 +201,7 @@ impl Muxer for FlvMuxer {
             MediaType::Video => {
                 let video_codec_id = Self::codec_to_video_id(stream.codec_id)?;
                 let mut tag_data = Vec::new();
-                let frame_type = if packet.is_keyframe { 1u8 } else { 2u8 };
+                let frame_type = if packet.is_keyframe() { 1u8 } else { 2u8 };
                 tag_data.push((frame_type << 4) | video_codec_id);
                 tag_data.push(1); // AVCPacketType = 1 (NALU)
                 // CompositionTimeOffset (CTS)
@@ -280,6 +284,7 @@ mod tests {
                 sample_format: SampleFormat::S16,
                 bit_rate: 128000,
                 frame_size: 1024,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         }
@@ -302,6 +307,8 @@ mod tests {
                 frame_rate: Rational::new(30, 1),
                 sample_aspect_ratio: Rational::new(1, 1),
                 bit_rate: 0,
+                color_space: Default::default(),
+                color_range: Default::default(),
             }),
             metadata: Vec::new(),
         }
@@ -339,12 +346,14 @@ mod tests {
         let streams = vec![make_audio_stream()];
         muxer.write_header(&mut io, &streams).unwrap();
 
-        let mut packet = Packet::from_data(vec![0xDE, 0xAD, 0xBE, 0xEF]);
-        packet.pts = 0;
-        packet.dts = 0;
-        packet.duration = 1024;
-        packet.stream_index = 0;
-        packet.is_keyframe = true;
+        let packet = Packet::builder()
+            .data(vec![0xDE, 0xAD, 0xBE, 0xEF])
+            .pts(0)
+            .dts(0)
+            .duration(1024)
+            .stream_index(0)
+            .key_frame(true)
+            .build();
         muxer.write_packet(&mut io, &packet).unwrap();
 
         let pos = io.position().unwrap();
@@ -183,6 +183,10 @@ impl Muxer for MkvMuxer {
         if self.webm { "webm" } else { "matroska" }
     }
 
+    fn wants_global_header(&self) -> bool {
+        true
+    }
+
     fn write_header(&mut self, io: &mut IoContext, streams: &[Stream]) -> TaoResult<()> {
         if streams.is_empty() {
             return Err(TaoError::InvalidArgument("MKV: 至少需要一个流".into()));
@@ -282,7 +286,7 @@ impl Muxer for MkvMuxer {
             .unwrap_or(1);
 
         let relative_ts = (timestamp_ms - self.cluster_timestamp) as i16;
-        let flags: u8 = if packet.is_keyframe { 0x80 } else { 0x00 };
+        let flags: u8 = if packet.is_keyframe() { 0x80 } else { 0x00 };
 
         // SimpleBlock 格式:
         // track_number (VINT) + timestamp_delta (2 bytes BE) + flags (1 byte) + frame_data
@@ -556,6 +560,8 @@ mod tests {
                 frame_rate: Rational::new(30, 1),
                 sample_aspect_ratio: Rational::new(1, 1),
                 bit_rate: 0,
+                color_space: Default::default(),
+                color_range: Default::default(),
             }),
             metadata: Vec::new(),
         }
@@ -577,6 +583,7 @@ mod tests {
                 sample_format: SampleFormat::S16,
                 bit_rate: 128000,
                 frame_size: 1024,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         }
@@ -608,11 +615,13 @@ mod tests {
         muxer.write_header(&mut io, &streams).unwrap();
 
         for i in 0..5 {
-            let mut pkt = Packet::from_data(vec![0xAA; 100]);
-            pkt.stream_index = 0;
-            pkt.pts = i * 33;
-            pkt.dts = i * 33;
-            pkt.is_keyframe = i == 0;
+            let pkt = Packet::builder()
+                .data(vec![0xAA; 100])
+                .stream_index(0)
+                .pts(i * 33)
+                .dts(i * 33)
+                .key_frame(i == 0)
+                .build();
             muxer.write_packet(&mut io, &pkt).unwrap();
         }
 
@@ -632,21 +641,25 @@ mod tests {
 
         // 视频
         for i in 0..3 {
-            let mut pkt = Packet::from_data(vec![0xBB; 200]);
-            pkt.stream_index = 0;
-            pkt.pts = i * 33;
-            pkt.dts = i * 33;
-            pkt.is_keyframe = i == 0;
+            let pkt = Packet::builder()
+                .data(vec![0xBB; 200])
+                .stream_index(0)
+                .pts(i * 33)
+                .dts(i * 33)
+                .key_frame(i == 0)
+                .build();
             muxer.write_packet(&mut io, &pkt).unwrap();
         }
 
         // 音频
         for i in 0..5 {
-            let mut pkt = Packet::from_data(vec![0xCC; 50]);
-            pkt.stream_index = 1;
-            pkt.pts = i * 23;
-            pkt.dts = i * 23;
-            pkt.is_keyframe = true;
+            let pkt = Packet::builder()
+                .data(vec![0xCC; 50])
+                .stream_index(1)
+                .pts(i * 23)
+                .dts(i * 23)
+                .key_frame(true)
+                .build();
             muxer.write_packet(&mut io, &pkt).unwrap();
         }
 
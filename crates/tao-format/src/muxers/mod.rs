@@ -10,6 +10,7 @@ pub mod mp3;
 pub mod mp4;
 pub mod mpegts;
 pub mod ogg;
+pub mod raw;
 pub mod wav;
 
 use crate::format_id::FormatId;
@@ -29,4 +30,12 @@ pub fn register_all_muxers(registry: &mut FormatRegistry) {
     registry.register_muxer(FormatId::Mp3Container, "mp3", mp3::Mp3Muxer::create);
     registry.register_muxer(FormatId::MpegTs, "mpegts", mpegts::MpegTsMuxer::create);
     registry.register_muxer(FormatId::Avi, "avi", avi::AviMuxer::create);
+    registry.register_muxer(FormatId::H264Es, "h264", raw::RawMuxer::create_h264);
+    registry.register_muxer(FormatId::Mpeg4Es, "m4v", raw::RawMuxer::create_m4v);
+    registry.register_muxer(FormatId::RawAudio, "pcm", raw::RawMuxer::create_pcm);
+    registry.register_muxer(
+        FormatId::RawVideo,
+        "rawvideo",
+        raw::RawMuxer::create_rawvideo,
+    );
 }
@@ -109,6 +109,7 @@ mod tests {
                 sample_format: SampleFormat::S16,
                 bit_rate: 128000,
                 frame_size: 1152,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         }
@@ -137,7 +138,7 @@ mod tests {
 
         // 模拟 MP3 帧数据 (任意字节)
         let data = vec![0xFF, 0xFB, 0x90, 0x00, 0x01, 0x02, 0x03];
-        let pkt = Packet::from_data(bytes::Bytes::from(data.clone()));
+        let pkt = Packet::new(data.clone(), 0);
         muxer.write_packet(&mut io, &pkt).unwrap();
         muxer.write_trailer(&mut io).unwrap();
 
@@ -0,0 +1,254 @@
+//! 分片 MP4 (fMP4 / CMAF) 输出.
+//!
+//! 对标 FFmpeg 的 `movflags=frag_keyframe+empty_moov`, 布局为:
+//! ```text
+//! ftyp
+//! moov                  初始化段, stbl 为空
+//! └── mvex
+//!     └── trex          各轨道分片默认值
+//! moof + mdat           媒体段 (每个分片一对)
+//! ├── mfhd              分片序号, 从 1 开始递增
+//! └── traf              每个有采样的轨道一个
+//!     ├── tfhd          default-base-is-moof, 数据偏移相对于 moof 起始
+//!     ├── tfdt          分片起始解码时间 (轨道 timescale)
+//!     └── trun          采样时长/大小/标志/合成偏移
+//! mfra                  随机访问索引 (可选)
+//! ├── tfra
+//! └── mfro
+//! ```
+
+use tao_core::{TaoError, TaoResult};
+
+use crate::io::IoContext;
+
+use super::{TrackCollector, write_box_header};
+
+/// tfhd flags: 数据偏移以所在 moof 起始为基准
+const TFHD_DEFAULT_BASE_IS_MOOF: u32 = 0x02_0000;
+
+// trun flags
+const TRUN_DATA_OFFSET: u32 = 0x00_0001;
+const TRUN_SAMPLE_DURATION: u32 = 0x00_0100;
+const TRUN_SAMPLE_SIZE: u32 = 0x00_0200;
+const TRUN_SAMPLE_FLAGS: u32 = 0x00_0400;
+const TRUN_SAMPLE_CTS_OFFSET: u32 = 0x00_0800;
+
+/// 关键帧 sample_flags: sample_depends_on=2 (不依赖其他帧)
+const SAMPLE_FLAGS_SYNC: u32 = 0x0200_0000;
+/// 非关键帧 sample_flags: sample_depends_on=1, sample_is_non_sync_sample=1
+const SAMPLE_FLAGS_NON_SYNC: u32 = 0x0101_0000;
+
+/// 分片输出配置
+#[derive(Debug, Clone, Copy)]
+pub(super) struct FragmentConfig {
+    /// 是否输出分片 MP4
+    pub(super) enabled: bool,
+    /// 分片最短时长 (毫秒), 到达后在参考轨道的下一个关键帧处切分; 0 表示每个关键帧切分
+    pub(super) duration_ms: u64,
+    /// 是否在结尾写入 mfra 随机访问索引
+    pub(super) write_mfra: bool,
+}
+
+impl Default for FragmentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            duration_ms: 0,
+            write_mfra: true,
+        }
+    }
+}
+
+/// mfra/tfra 中的一个随机访问点
+#[derive(Debug, Clone, Copy)]
+pub(super) struct RandomAccessEntry {
+    /// 轨道 ID
+    track_id: u32,
+    /// 关键帧解码时间 (轨道 timescale)
+    time: u64,
+    /// 所在 moof 的文件偏移
+    moof_offset: u64,
+    /// 所在 traf 在 moof 中的序号 (从 1 开始)
+    traf_number: u8,
+}
+
+/// 构建 mvex box (每个轨道一个 trex)
+pub(super) fn build_mvex(tracks: &[TrackCollector]) -> Vec<u8> {
+    let mut inner = Vec::new();
+    for track in tracks {
+        write_box_header(&mut inner, 32, b"trex");
+        // version(1) + flags(3)
+        inner.extend_from_slice(&[0; 4]);
+        inner.extend_from_slice(&track.track_id.to_be_bytes());
+        // default_sample_description_index
+        inner.extend_from_slice(&1u32.to_be_bytes());
+        // default_sample_duration / size / flags, 均由 trun 显式给出
+        inner.extend_from_slice(&[0; 12]);
+    }
+
+    let mut buf = Vec::new();
+    write_box_header(&mut buf, 8 + inner.len() as u32, b"mvex");
+    buf.extend_from_slice(&inner);
+    buf
+}
+
+/// 写出一个 moof + mdat 分片, 并清空各轨道缓存的采样
+///
+/// 返回本分片中各轨道以关键帧开头的随机访问点.
+pub(super) fn write_fragment(
+    io: &mut IoContext,
+    tracks: &mut [TrackCollector],
+    sequence_number: u32,
+) -> TaoResult<Vec<RandomAccessEntry>> {
+    let moof_offset = io.position()?;
+    let mut moof = Vec::new();
+    write_box_header(&mut moof, 0, b"moof"); // 大小在构建完成后回填
+
+    // mfhd
+    write_box_header(&mut moof, 16, b"mfhd");
+    moof.extend_from_slice(&[0; 4]);
+    moof.extend_from_slice(&sequence_number.to_be_bytes());
+
+    // (trun data_offset 字段在 moof 中的位置, 该轨道数据在 mdat 负载中的偏移)
+    let mut data_offset_patches = Vec::new();
+    let mut mdat_payload_size = 0u64;
+    let mut entries = Vec::new();
+    let mut traf_number = 0u8;
+
+    for track in tracks.iter().filter(|t| !t.samples.is_empty()) {
+        traf_number = traf_number.saturating_add(1);
+        let base_decode_time = track.fragment_decode_time();
+        let (traf, data_offset_pos) = build_traf(track, base_decode_time);
+        data_offset_patches.push((moof.len() + data_offset_pos, mdat_payload_size));
+        moof.extend_from_slice(&traf);
+        mdat_payload_size += track.fragment_data.len() as u64;
+
+        if track.samples[0].is_keyframe {
+            entries.push(RandomAccessEntry {
+                track_id: track.track_id,
+                time: base_decode_time,
+                moof_offset,
+                traf_number,
+            });
+        }
+    }
+
+    let moof_size = moof.len() as u64;
+    let mdat_size = 8 + mdat_payload_size;
+    if moof_size + mdat_size > u64::from(u32::MAX) {
+        return Err(TaoError::InvalidArgument(
+            "MP4: 单个分片超过 4GB, 请减小分片时长".into(),
+        ));
+    }
+    moof[0..4].copy_from_slice(&(moof_size as u32).to_be_bytes());
+    for (pos, payload_offset) in data_offset_patches {
+        // default-base-is-moof: 偏移 = moof 大小 + mdat 头 + 轨道数据在负载中的偏移
+        let data_offset = (moof_size + 8 + payload_offset) as u32;
+        moof[pos..pos + 4].copy_from_slice(&data_offset.to_be_bytes());
+    }
+
+    io.write_all(&moof)?;
+    io.write_u32_be(mdat_size as u32)?;
+    io.write_tag(b"mdat")?;
+    for track in tracks.iter_mut() {
+        io.write_all(&track.fragment_data)?;
+        track.samples.clear();
+        track.fragment_data.clear();
+        track.fragment_start_dts = None;
+    }
+
+    Ok(entries)
+}
+
+/// 构建 traf box, 返回 box 数据与 trun data_offset 字段在其中的位置
+fn build_traf(track: &TrackCollector, base_decode_time: u64) -> (Vec<u8>, usize) {
+    let mut buf = Vec::new();
+    write_box_header(&mut buf, 0, b"traf");
+
+    // tfhd (version 0)
+    write_box_header(&mut buf, 16, b"tfhd");
+    buf.extend_from_slice(&TFHD_DEFAULT_BASE_IS_MOOF.to_be_bytes());
+    buf.extend_from_slice(&track.track_id.to_be_bytes());
+
+    // tfdt (version 1, 64 位解码时间)
+    write_box_header(&mut buf, 20, b"tfdt");
+    buf.extend_from_slice(&[1, 0, 0, 0]);
+    buf.extend_from_slice(&base_decode_time.to_be_bytes());
+
+    // trun: 存在负合成偏移时使用 version 1 (有符号)
+    let has_cts = track.samples.iter().any(|s| s.cts_offset != 0);
+    let version: u32 = if track.samples.iter().any(|s| s.cts_offset < 0) {
+        1
+    } else {
+        0
+    };
+    let mut flags = TRUN_DATA_OFFSET | TRUN_SAMPLE_DURATION | TRUN_SAMPLE_SIZE | TRUN_SAMPLE_FLAGS;
+    if has_cts {
+        flags |= TRUN_SAMPLE_CTS_OFFSET;
+    }
+    let per_sample = if has_cts { 16 } else { 12 };
+    let trun_size = 20 + track.samples.len() as u32 * per_sample;
+    write_box_header(&mut buf, trun_size, b"trun");
+    buf.extend_from_slice(&((version << 24) | flags).to_be_bytes());
+    buf.extend_from_slice(&(track.samples.len() as u32).to_be_bytes());
+    let data_offset_pos = buf.len();
+    buf.extend_from_slice(&[0; 4]); // data_offset, 由调用方回填
+    for sample in &track.samples {
+        buf.extend_from_slice(&sample.duration.to_be_bytes());
+        buf.extend_from_slice(&sample.size.to_be_bytes());
+        let sample_flags = if sample.is_keyframe {
+            SAMPLE_FLAGS_SYNC
+        } else {
+            SAMPLE_FLAGS_NON_SYNC
+        };
+        buf.extend_from_slice(&sample_flags.to_be_bytes());
+        if has_cts {
+            buf.extend_from_slice(&sample.cts_offset.to_be_bytes());
+        }
+    }
+
+    let size = buf.len() as u32;
+    buf[0..4].copy_from_slice(&size.to_be_bytes());
+    (buf, data_offset_pos)
+}
+
+/// 写 mfra box (每个轨道一个 tfra, 末尾为 mfro)
+pub(super) fn write_mfra(
+    io: &mut IoContext,
+    tracks: &[TrackCollector],
+    entries: &[RandomAccessEntry],
+) -> TaoResult<()> {
+    let mut inner = Vec::new();
+    for track in tracks {
+        let track_entries: Vec<_> = entries
+            .iter()
+            .filter(|e| e.track_id == track.track_id)
+            .collect();
+        // version 1: time/moof_offset 为 64 位; traf/trun/sample 序号各 1 字节
+        let size = 24 + track_entries.len() as u32 * 19;
+        write_box_header(&mut inner, size, b"tfra");
+        inner.extend_from_slice(&[1, 0, 0, 0]);
+        inner.extend_from_slice(&track.track_id.to_be_bytes());
+        // reserved(26) + length_size_of_traf/trun/sample_num (各 2 位, 0 表示 1 字节)
+        inner.extend_from_slice(&0u32.to_be_bytes());
+        inner.extend_from_slice(&(track_entries.len() as u32).to_be_bytes());
+        for entry in track_entries {
+            inner.extend_from_slice(&entry.time.to_be_bytes());
+            inner.extend_from_slice(&entry.moof_offset.to_be_bytes());
+            inner.push(entry.traf_number);
+            inner.push(1); // trun_number
+            inner.push(1); // sample_number
+        }
+    }
+
+    let mfra_size = 8 + inner.len() as u32 + 16;
+    io.write_u32_be(mfra_size)?;
+    io.write_tag(b"mfra")?;
+    io.write_all(&inner)?;
+    // mfro: 记录 mfra 总大小, 便于读取方从文件末尾定位
+    io.write_u32_be(16)?;
+    io.write_tag(b"mfro")?;
+    io.write_u32_be(0)?;
+    io.write_u32_be(mfra_size)?;
+    Ok(())
+}
@@ -27,6 +27,17 @@
 //!                 ├── stco / co64
 //!                 └── stss (仅视频)
 //! ```
+//!
+//! 设置 `faststart=1` 后在内存中缓存全部数据包, 由 `write_trailer()` 依次写出
+//! moov 与 mdat, 便于网络播放时渐进加载 (对标 FFmpeg 的 `movflags=faststart`).
+//!
+//! 设置 `fragmented=1` 后改为输出分片 MP4, 见 [`fragment`] 模块.
+//!
+//! # 选项
+//! - `faststart`: 0/1, 是否将 moov 置于 mdat 之前 (默认 0, 分片模式下忽略)
+//! - `fragmented`: 0/1, 是否输出分片 MP4 (默认 0)
+//! - `frag_duration`: 分片最短时长 (毫秒), 0 表示每个关键帧切分 (默认 0)
+//! - `mfra`: 0/1, 分片模式下是否在结尾写入 mfra 随机访问索引 (默认 1)
 
 use log::debug;
 use tao_codec::{CodecId, Packet};
@@ -37,6 +48,10 @@ use crate::io::IoContext;
 use crate::muxer::Muxer;
 use crate::stream::{Stream, StreamParams};
 
+mod fragment;
+
+use fragment::{FragmentConfig, RandomAccessEntry};
+
 /// 每个 sample 的元数据
 #[derive(Debug, Clone)]
 struct SampleEntry {
@@ -56,6 +71,8 @@ struct SampleEntry {
 struct TrackCollector {
     /// 流索引
     stream_index: usize,
+    /// 轨道 ID (从 1 开始)
+    track_id: u32,
     /// 流信息的克隆
     stream: Stream,
     /// 时间基
@@ -64,6 +81,72 @@ struct TrackCollector {
     samples: Vec<SampleEntry>,
     /// 上一个 DTS (用于计算 duration)
     last_dts: i64,
+    /// 上一个 sample 的 duration, 缺失 duration 的 sample 沿用该值
+    last_duration: u32,
+    /// 第一个 sample 的 DTS, 分片模式下作为 tfdt 的零点
+    first_dts: Option<i64>,
+    /// 分片模式: 当前分片缓存的 sample 数据
+    fragment_data: Vec<u8>,
+    /// 分片模式: 当前分片第一个 sample 的 DTS
+    fragment_start_dts: Option<i64>,
+}
+
+impl TrackCollector {
+    fn new(stream: &Stream, track_id: u32, timescale: u32) -> Self {
+        Self {
+            stream_index: stream.index,
+            track_id,
+            stream: stream.clone(),
+            timescale,
+            samples: Vec::new(),
+            last_dts: -1,
+            last_duration: 0,
+            first_dts: None,
+            fragment_data: Vec::new(),
+            fragment_start_dts: None,
+        }
+    }
+
+    /// 追加一个 sample, 并用相邻 DTS 差值修正前一个 sample 的 duration
+    fn push_sample(&mut self, packet: &Packet, offset: u64) {
+        let dts = packet.dts;
+        if self.last_dts >= 0 {
+            let delta = (dts - self.last_dts).max(0) as u32;
+            if let Some(prev) = self.samples.last_mut() {
+                prev.duration = delta;
+            }
+            self.last_duration = delta;
+        }
+        // 当前 sample 暂用 packet.duration, 缺失时沿用前一个 sample 的 duration
+        let duration = if packet.duration > 0 {
+            packet.duration as u32
+        } else {
+            self.last_duration
+        };
+
+        // CTS offset (PTS - DTS)
+        let cts_offset = (packet.pts - dts) as i32;
+
+        self.samples.push(SampleEntry {
+            offset,
+            size: packet.data.len() as u32,
+            duration,
+            cts_offset,
+            is_keyframe: packet.is_keyframe(),
+        });
+
+        self.first_dts.get_or_insert(dts);
+        self.fragment_start_dts.get_or_insert(dts);
+        self.last_dts = dts;
+    }
+
+    /// 当前分片的起始解码时间 (相对于轨道第一个 sample)
+    fn fragment_decode_time(&self) -> u64 {
+        match (self.fragment_start_dts, self.first_dts) {
+            (Some(start), Some(first)) => (start - first).max(0) as u64,
+            _ => 0,
+        }
+    }
 }
 
 /// MP4 封装器
@@ -76,6 +159,18 @@ pub struct Mp4Muxer {
     mdat_data_start: u64,
     /// 已写入的 mdat 数据量
     mdat_written: u64,
+    /// 是否将 moov 置于 mdat 之前
+    faststart: bool,
+    /// faststart 模式: 缓存的 mdat 数据, sample 偏移相对于其起始
+    mdat_buffer: Vec<u8>,
+    /// 分片输出配置
+    fragment: FragmentConfig,
+    /// 下一个 moof 的序号
+    sequence_number: u32,
+    /// 分片切分参考轨道 (第一个视频轨道, 无视频时为第一个轨道)
+    reference_track: usize,
+    /// 已写出分片的随机访问点, 用于 mfra
+    random_access: Vec<RandomAccessEntry>,
 }
 
 impl Mp4Muxer {
@@ -86,6 +181,12 @@ impl Mp4Muxer {
             mdat_offset: 0,
             mdat_data_start: 0,
             mdat_written: 0,
+            faststart: false,
+            mdat_buffer: Vec::new(),
+            fragment: FragmentConfig::default(),
+            sequence_number: 1,
+            reference_track: 0,
+            random_access: Vec::new(),
         }))
     }
 
@@ -103,6 +204,85 @@ impl Mp4Muxer {
             }
         }
     }
+
+    /// 分片模式写入数据包: 到达切分点时先写出已缓存的分片, 再缓存当前数据包
+    fn write_fragmented_packet(
+        &mut self,
+        io: &mut IoContext,
+        track_pos: usize,
+        packet: &Packet,
+    ) -> TaoResult<()> {
+        if self.is_fragment_boundary(track_pos, packet) {
+            self.flush_fragment(io)?;
+        }
+        let track = &mut self.tracks[track_pos];
+        let offset = track.fragment_data.len() as u64;
+        track.push_sample(packet, offset);
+        track.fragment_data.extend_from_slice(&packet.data);
+        Ok(())
+    }
+
+    /// 参考轨道的关键帧且当前分片已达到最短时长时切分
+    fn is_fragment_boundary(&self, track_pos: usize, packet: &Packet) -> bool {
+        if track_pos != self.reference_track || !packet.is_keyframe() {
+            return false;
+        }
+        let track = &self.tracks[track_pos];
+        let Some(start) = track.fragment_start_dts else {
+            return false;
+        };
+        let elapsed_ms =
+            (packet.dts - start).max(0) as u64 * 1000 / u64::from(track.timescale.max(1));
+        elapsed_ms >= self.fragment.duration_ms
+    }
+
+    /// 写出当前缓存的分片 (moof + mdat)
+    fn flush_fragment(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        if self.tracks.iter().all(|t| t.samples.is_empty()) {
+            return Ok(());
+        }
+        for track in &mut self.tracks {
+            fix_first_sample_duration(track);
+        }
+        let entries = fragment::write_fragment(io, &mut self.tracks, self.sequence_number)?;
+        self.random_access.extend(entries);
+        self.sequence_number += 1;
+        Ok(())
+    }
+
+    /// faststart 模式: 依次写出 moov 与缓存的 mdat
+    ///
+    /// sample 偏移取决于 moov 大小, 而 stco 切换为 co64 时 moov 会变大,
+    /// 因此反复平移偏移直到 moov 大小稳定.
+    fn write_faststart_trailer(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        for track in &mut self.tracks {
+            fix_first_sample_duration(track);
+        }
+        let mut tracks: Vec<_> = self.tracks.drain(..).collect();
+
+        let moov_start = io.position()?;
+        let mut data_start = 0;
+        loop {
+            let moov_size = 8 + build_moov(&tracks, false)?.len() as u64;
+            let needed = moov_start + moov_size + 8;
+            if needed == data_start {
+                break;
+            }
+            for sample in tracks.iter_mut().flat_map(|t| t.samples.iter_mut()) {
+                sample.offset += needed - data_start;
+            }
+            data_start = needed;
+        }
+        write_moov(io, &tracks, false)?;
+
+        let mdat_total = 8 + self.mdat_written;
+        io.write_u32_be(mdat_total as u32)?;
+        io.write_tag(b"mdat")?;
+        io.write_all(&std::mem::take(&mut self.mdat_buffer))?;
+
+        debug!("MP4: 写入 moov + mdat (faststart), mdat 大小={mdat_total}");
+        Ok(())
+    }
 }
 
 impl Muxer for Mp4Muxer {
@@ -114,25 +294,81 @@ impl Muxer for Mp4Muxer {
         "mp4"
     }
 
+    fn wants_global_header(&self) -> bool {
+        true
+    }
+
+    fn set_option(&mut self, key: &str, value: &str) -> TaoResult<()> {
+        match key {
+            "faststart" => {
+                self.faststart = parse_bool_option(key, value)?;
+            }
+            "fragmented" => {
+                self.fragment.enabled = parse_bool_option(key, value)?;
+            }
+            "frag_duration" => {
+                self.fragment.duration_ms = value.parse::<u64>().map_err(|_| {
+                    TaoError::InvalidArgument(format!(
+                        "MP4: frag_duration 应为毫秒数, 实际为 '{value}'"
+                    ))
+                })?;
+            }
+            "mfra" => {
+                self.fragment.write_mfra = parse_bool_option(key, value)?;
+            }
+            _ => {
+                return Err(TaoError::Unsupported(format!("MP4 封装器不支持选项 {key}")));
+            }
+        }
+        Ok(())
+    }
+
+    fn get_option(&self, key: &str) -> Option<String> {
+        let flag = |enabled: bool| if enabled { "1" } else { "0" }.to_string();
+        match key {
+            "faststart" => Some(flag(self.faststart)),
+            "fragmented" => Some(flag(self.fragment.enabled)),
+            "frag_duration" => Some(self.fragment.duration_ms.to_string()),
+            "mfra" => Some(flag(self.fragment.write_mfra)),
+            _ => None,
+        }
+    }
+
     fn write_header(&mut self, io: &mut IoContext, streams: &[Stream]) -> TaoResult<()> {
         if streams.is_empty() {
             return Err(TaoError::InvalidArgument("MP4: 至少需要一个流".into()));
         }
 
         // 初始化轨道收集器
-        for stream in streams {
+        for (i, stream) in streams.iter().enumerate() {
             let timescale = Self::get_timescale(stream);
-            self.tracks.push(TrackCollector {
-                stream_index: stream.index,
-                stream: stream.clone(),
-                timescale,
-                samples: Vec::new(),
-                last_dts: -1,
-            });
+            self.tracks
+                .push(TrackCollector::new(stream, i as u32 + 1, timescale));
         }
 
         // 写 ftyp box
-        write_ftyp(io)?;
+        write_ftyp(io, self.fragment.enabled)?;
+
+        if self.fragment.enabled {
+            // 分片模式: 先写出 stbl 为空的 moov, 采样在后续 moof 中给出
+            self.reference_track = self
+                .tracks
+                .iter()
+                .position(|t| t.stream.media_type == MediaType::Video)
+                .unwrap_or(0);
+            write_moov(io, &self.tracks, true)?;
+            debug!(
+                "MP4: 写入 ftyp + moov (分片模式), {} 个轨道",
+                self.tracks.len()
+            );
+            return Ok(());
+        }
+
+        if self.faststart {
+            // faststart 模式: mdat 缓存到 trailer 时写在 moov 之后
+            debug!("MP4: 写入 ftyp (faststart), {} 个轨道", self.tracks.len());
+            return Ok(());
+        }
 
         // 写 mdat box (先写 8 字节头, trailer 回填大小)
         self.mdat_offset = io.position()?;
@@ -146,48 +382,48 @@ impl Muxer for Mp4Muxer {
 
     fn write_packet(&mut self, io: &mut IoContext, packet: &Packet) -> TaoResult<()> {
         // 查找轨道
-        let track = self
+        let track_pos = self
             .tracks
-            .iter_mut()
-            .find(|t| t.stream_index == packet.stream_index)
+            .iter()
+            .position(|t| t.stream_index == packet.stream_index)
             .ok_or_else(|| {
                 TaoError::InvalidArgument(format!("MP4: 未知流索引 {}", packet.stream_index))
             })?;
 
-        let offset = io.position()?;
-        io.write_all(&packet.data)?;
-
-        // 计算 duration
-        let dts = packet.dts;
-        let duration = if track.last_dts >= 0 {
-            (dts - track.last_dts).max(0) as u32
-        } else {
-            // 第一个 sample: 使用 packet.duration 或默认
-            if packet.duration > 0 {
-                packet.duration as u32
-            } else {
-                0
-            }
-        };
-
-        // CTS offset (PTS - DTS)
-        let cts_offset = (packet.pts - dts) as i32;
+        if self.fragment.enabled {
+            return self.write_fragmented_packet(io, track_pos, packet);
+        }
 
-        track.samples.push(SampleEntry {
-            offset,
-            size: packet.data.len() as u32,
-            duration,
-            cts_offset,
-            is_keyframe: packet.is_keyframe,
-        });
+        if self.faststart {
+            let offset = self.mdat_buffer.len() as u64;
+            self.mdat_buffer.extend_from_slice(&packet.data);
+            self.tracks[track_pos].push_sample(packet, offset);
+            self.mdat_written += packet.data.len() as u64;
+            return Ok(());
+        }
 
-        track.last_dts = dts;
+        let offset = io.position()?;
+        io.write_all(&packet.data)?;
+        self.tracks[track_pos].push_sample(packet, offset);
         self.mdat_written += packet.data.len() as u64;
 
         Ok(())
     }
 
     fn write_trailer(&mut self, io: &mut IoContext) -> TaoResult<()> {
+        if self.fragment.enabled {
+            self.flush_fragment(io)?;
+            if self.fragment.write_mfra {
+                fragment::write_mfra(io, &self.tracks, &self.random_access)?;
+            }
+            debug!("MP4: 写入 {} 个分片", self.sequence_number - 1);
+            return Ok(());
+        }
+
+        if self.faststart {
+            return self.write_faststart_trailer(io);
+        }
+
         // 回填 mdat 大小 (8 字节头 + 数据)
         let mdat_total = 8 + self.mdat_written;
         if io.is_seekable() {
@@ -204,7 +440,7 @@ impl Muxer for Mp4Muxer {
 
         // 构建 moov box
         let tracks: Vec<_> = self.tracks.drain(..).collect();
-        write_moov(io, &tracks)?;
+        write_moov(io, &tracks, false)?;
 
         debug!("MP4: 写入 moov, mdat 大小={mdat_total}");
         Ok(())
@@ -215,6 +451,17 @@ impl Muxer for Mp4Muxer {
 // 修正工具
 // ============================================================
 
+/// 解析 0/1 形式的布尔选项
+fn parse_bool_option(key: &str, value: &str) -> TaoResult<bool> {
+    match value {
+        "1" | "true" => Ok(true),
+        "0" | "false" => Ok(false),
+        _ => Err(TaoError::InvalidArgument(format!(
+            "MP4: 选项 {key} 应为 0 或 1, 实际为 '{value}'"
+        ))),
+    }
+}
+
 /// 修正第一个 sample 的 duration
 fn fix_first_sample_duration(track: &mut TrackCollector) {
     if track.samples.len() >= 2 && track.samples[0].duration == 0 {
@@ -227,14 +474,19 @@ fn fix_first_sample_duration(track: &mut TrackCollector) {
 // ============================================================
 
 /// 写 ftyp box
-fn write_ftyp(io: &mut IoContext) -> TaoResult<()> {
+fn write_ftyp(io: &mut IoContext, fragmented: bool) -> TaoResult<()> {
     // ftyp: major_brand=isom, minor_version=0x200, compatible=[isom, iso2, mp41]
-    let brands: &[&[u8; 4]] = &[b"isom", b"iso2", b"mp41"];
+    // 分片模式: major_brand=iso5, compatible=[iso5, iso6, mp41] (tfdt/default-base-is-moof)
+    let (major, brands): (&[u8; 4], &[&[u8; 4]]) = if fragmented {
+        (b"iso5", &[b"iso5", b"iso6", b"mp41"])
+    } else {
+        (b"isom", &[b"isom", b"iso2", b"mp41"])
+    };
     let size: u32 = 8 + 4 + 4 + (brands.len() as u32) * 4;
 
     io.write_u32_be(size)?;
     io.write_tag(b"ftyp")?;
-    io.write_tag(b"isom")?; // major_brand
+    io.write_tag(major)?; // major_brand
     io.write_u32_be(0x200)?; // minor_version
     for brand in brands {
         io.write_tag(brand)?;
@@ -244,8 +496,8 @@ fn write_ftyp(io: &mut IoContext) -> TaoResult<()> {
 }
 
 /// 写完整的 moov box
-fn write_moov(io: &mut IoContext, tracks: &[TrackCollector]) -> TaoResult<()> {
-    let moov_data = build_moov(tracks)?;
+fn write_moov(io: &mut IoContext, tracks: &[TrackCollector], fragmented: bool) -> TaoResult<()> {
+    let moov_data = build_moov(tracks, fragmented)?;
 
     io.write_u32_be((8 + moov_data.len()) as u32)?;
     io.write_tag(b"moov")?;
@@ -254,8 +506,8 @@ fn write_moov(io: &mut IoContext, tracks: &[TrackCollector]) -> TaoResult<()> {
     Ok(())
 }
 
-/// 构建 moov box 的内容 (不含 box 头)
-fn build_moov(tracks: &[TrackCollector]) -> TaoResult<Vec<u8>> {
+/// 构建 moov box 的内容 (不含 box 头), 分片模式追加 mvex
+fn build_moov(tracks: &[TrackCollector], fragmented: bool) -> TaoResult<Vec<u8>> {
     let mut buf = Vec::new();
 
     // mvhd
@@ -267,8 +519,12 @@ fn build_moov(tracks: &[TrackCollector]) -> TaoResult<Vec<u8>> {
     buf.extend_from_slice(&build_mvhd(max_duration, tracks.len() as u32));
 
     // trak for each track
-    for (i, track) in tracks.iter().enumerate() {
-        buf.extend_from_slice(&build_trak(track, i as u32 + 1)?);
+    for track in tracks {
+        buf.extend_from_slice(&build_trak(track, track.track_id)?);
+    }
+
+    if fragmented {
+        buf.extend_from_slice(&fragment::build_mvex(tracks));
     }
 
     Ok(buf)
@@ -519,8 +775,8 @@ fn build_stbl(track: &TrackCollector) -> TaoResult<Vec<u8>> {
     inner.extend_from_slice(&build_stsz(track));
     inner.extend_from_slice(&build_stco(track));
 
-    // stss (sync sample table) - 仅视频
-    if track.stream.media_type == MediaType::Video {
+    // stss (sync sample table) - 仅视频, 分片模式的空 stbl 不写
+    if track.stream.media_type == MediaType::Video && !track.samples.is_empty() {
         inner.extend_from_slice(&build_stss(track));
     }
 
@@ -636,9 +892,12 @@ fn build_audio_sample_entry(track: &TrackCollector) -> TaoResult<Vec<u8>> {
     // samplerate (16.16 fixed point)
     entry.extend_from_slice(&(sample_rate << 16).to_be_bytes());
 
-    // esds box (AAC)
-    if track.stream.codec_id == CodecId::Aac {
-        entry.extend_from_slice(&build_esds(track, sample_rate, channels)?);
+    match track.stream.codec_id {
+        CodecId::Aac => entry.extend_from_slice(&build_esds(track, sample_rate, channels)?),
+        CodecId::Flac if track.stream.extra_data.len() >= FLAC_STREAMINFO_LEN => {
+            entry.extend_from_slice(&build_dfla(&track.stream.extra_data));
+        }
+        _ => {}
     }
 
     let mut buf = Vec::new();
@@ -649,9 +908,49 @@ fn build_audio_sample_entry(track: &TrackCollector) -> TaoResult<Vec<u8>> {
     Ok(buf)
 }
 
+/// FLAC STREAMINFO 长度 (字节)
+const FLAC_STREAMINFO_LEN: usize = 34;
+
+/// AAC 采样率索引表
+const AAC_SAMPLE_RATES: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+/// 由流参数构造 AAC-LC 的 AudioSpecificConfig (流未提供 extra_data 时使用)
+fn default_audio_specific_config(sample_rate: u32, channels: u32) -> Option<Vec<u8>> {
+    let sr_index = AAC_SAMPLE_RATES.iter().position(|&sr| sr == sample_rate)? as u16;
+    let channel_config = match channels {
+        1..=6 => channels as u16,
+        8 => 7,
+        _ => return None,
+    };
+    let config = (2 << 11) | (sr_index << 7) | (channel_config << 3);
+    Some(config.to_be_bytes().to_vec())
+}
+
+/// 构建 dfLa box (FLAC 解码配置, 含 STREAMINFO 元数据块)
+fn build_dfla(stream_info: &[u8]) -> Vec<u8> {
+    let mut content = Vec::with_capacity(8 + FLAC_STREAMINFO_LEN);
+    // version(1) + flags(3)
+    content.extend_from_slice(&[0; 4]);
+    // last_metadata_block_flag(1)=1 + block_type(7)=STREAMINFO + length(24)
+    content.push(0x80);
+    content.extend_from_slice(&(FLAC_STREAMINFO_LEN as u32).to_be_bytes()[1..]);
+    content.extend_from_slice(&stream_info[..FLAC_STREAMINFO_LEN]);
+
+    let mut buf = Vec::new();
+    write_box_header(&mut buf, 8 + content.len() as u32, b"dfLa");
+    buf.extend_from_slice(&content);
+    buf
+}
+
 /// 构建 esds box (用于 AAC)
-fn build_esds(track: &TrackCollector, _sample_rate: u32, _channels: u32) -> TaoResult<Vec<u8>> {
-    let extra = &track.stream.extra_data;
+fn build_esds(track: &TrackCollector, sample_rate: u32, channels: u32) -> TaoResult<Vec<u8>> {
+    let extra = if track.stream.extra_data.is_empty() {
+        default_audio_specific_config(sample_rate, channels).unwrap_or_default()
+    } else {
+        track.stream.extra_data.clone()
+    };
 
     // ES_Descriptor 内容
     let mut es_desc = Vec::new();
@@ -677,7 +976,7 @@ fn build_esds(track: &TrackCollector, _sample_rate: u32, _channels: u32) -> TaoR
     if !extra.is_empty() {
         dec_config.push(0x05); // tag
         write_desc_length(&mut dec_config, extra.len());
-        dec_config.extend_from_slice(extra);
+        dec_config.extend_from_slice(&extra);
     }
 
     // 将 DecoderConfigDescriptor 加入 ES_Descriptor
@@ -722,9 +1021,15 @@ fn build_stts(track: &TrackCollector) -> Vec<u8> {
 }
 
 /// stsc box (样本到块映射)
-fn build_stsc(_track: &TrackCollector) -> Vec<u8> {
+fn build_stsc(track: &TrackCollector) -> Vec<u8> {
     // 简单实现: 每个 sample 一个 chunk
     let mut buf = Vec::new();
+    if track.samples.is_empty() {
+        write_box_header(&mut buf, 16, b"stsc");
+        buf.extend_from_slice(&[0; 4]); // version + flags
+        buf.extend_from_slice(&0u32.to_be_bytes()); // entry_count=0
+        return buf;
+    }
     let content_size = 8 + 12; // 1 个条目
     write_box_header(&mut buf, 8 + content_size, b"stsc");
     buf.extend_from_slice(&[0; 4]); // version + flags
@@ -961,6 +1266,8 @@ mod tests {
                 frame_rate: Rational::new(30, 1),
                 sample_aspect_ratio: Rational::new(1, 1),
                 bit_rate: 0,
+                color_space: Default::default(),
+                color_range: Default::default(),
             }),
             metadata: Vec::new(),
         }
@@ -982,6 +1289,7 @@ mod tests {
                 sample_format: SampleFormat::F32,
                 bit_rate: 128000,
                 frame_size: 1024,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         }
@@ -991,7 +1299,7 @@ mod tests {
     fn test_ftyp_write() {
         let backend = MemoryBackend::new();
         let mut io = IoContext::new(Box::new(backend));
-        write_ftyp(&mut io).unwrap();
+        write_ftyp(&mut io, false).unwrap();
         let pos = io.position().unwrap();
         assert_eq!(pos, 28); // 8(header) + 4(major) + 4(minor) + 3*4(brands) = 28
     }
@@ -1007,12 +1315,14 @@ mod tests {
 
         // 写入 3 个视频包
         for i in 0..3 {
-            let mut pkt = Packet::from_data(vec![0xAA; 100]);
-            pkt.stream_index = 0;
-            pkt.pts = i * 3000;
-            pkt.dts = i * 3000;
-            pkt.duration = 3000;
-            pkt.is_keyframe = i == 0;
+            let pkt = Packet::builder()
+                .data(vec![0xAA; 100])
+                .stream_index(0)
+                .pts(i * 3000)
+                .dts(i * 3000)
+                .duration(3000)
+                .key_frame(i == 0)
+                .build();
             muxer.write_packet(&mut io, &pkt).unwrap();
         }
 
@@ -1032,23 +1342,27 @@ mod tests {
 
         // 视频包
         for i in 0..3 {
-            let mut pkt = Packet::from_data(vec![0xBB; 200]);
-            pkt.stream_index = 0;
-            pkt.pts = i * 3000;
-            pkt.dts = i * 3000;
-            pkt.duration = 3000;
-            pkt.is_keyframe = i == 0;
+            let pkt = Packet::builder()
+                .data(vec![0xBB; 200])
+                .stream_index(0)
+                .pts(i * 3000)
+                .dts(i * 3000)
+                .duration(3000)
+                .key_frame(i == 0)
+                .build();
             muxer.write_packet(&mut io, &pkt).unwrap();
         }
 
         // 音频包
         for i in 0..5 {
-            let mut pkt = Packet::from_data(vec![0xCC; 50]);
-            pkt.stream_index = 1;
-            pkt.pts = i * 1024;
-            pkt.dts = i * 1024;
-            pkt.duration = 1024;
-            pkt.is_keyframe = true;
+            let pkt = Packet::builder()
+                .data(vec![0xCC; 50])
+                .stream_index(1)
+                .pts(i * 1024)
+                .dts(i * 1024)
+                .duration(1024)
+                .key_frame(true)
+                .build();
             muxer.write_packet(&mut io, &pkt).unwrap();
         }
 
@@ -1067,35 +1381,30 @@ mod tests {
 
     #[test]
     fn test_rle_duration_compress() {
-        let track = TrackCollector {
-            stream_index: 0,
-            stream: make_video_stream(),
-            timescale: 90000,
-            samples: vec![
-                SampleEntry {
-                    offset: 0,
-                    size: 100,
-                    duration: 3000,
-                    cts_offset: 0,
-                    is_keyframe: true,
-                },
-                SampleEntry {
-                    offset: 100,
-                    size: 100,
-                    duration: 3000,
-                    cts_offset: 0,
-                    is_keyframe: false,
-                },
-                SampleEntry {
-                    offset: 200,
-                    size: 100,
-                    duration: 6000,
-                    cts_offset: 0,
-                    is_keyframe: false,
-                },
-            ],
-            last_dts: 0,
-        };
+        let mut track = TrackCollector::new(&make_video_stream(), 1, 90000);
+        track.samples = vec![
+            SampleEntry {
+                offset: 0,
+                size: 100,
+                duration: 3000,
+                cts_offset: 0,
+                is_keyframe: true,
+            },
+            SampleEntry {
+                offset: 100,
+                size: 100,
+                duration: 3000,
+                cts_offset: 0,
+                is_keyframe: false,
+            },
+            SampleEntry {
+                offset: 200,
+                size: 100,
+                duration: 6000,
+                cts_offset: 0,
+                is_keyframe: false,
+            },
+        ];
         let entries = rle_durations(&track);
         assert_eq!(entries, vec![(2, 3000), (1, 6000)]);
     }
@@ -1107,4 +1416,57 @@ mod tests {
         let mut muxer = Mp4Muxer::create().unwrap();
         assert!(muxer.write_header(&mut io, &[]).is_err());
     }
+
+    /// 封装单个音频流的若干数据包, 再解封装取回流信息
+    fn roundtrip_audio_stream(stream: Stream) -> Stream {
+        let mut io = IoContext::new(Box::new(MemoryBackend::new()));
+        let mut muxer = Mp4Muxer::create().unwrap();
+        assert!(muxer.wants_global_header(), "MP4 应要求全局头");
+        muxer.write_header(&mut io, &[stream]).unwrap();
+        for i in 0..3 {
+            let pkt = Packet::builder()
+                .data(vec![0x21; 40])
+                .stream_index(0)
+                .pts(i * 1024)
+                .dts(i * 1024)
+                .duration(1024)
+                .key_frame(true)
+                .build();
+            muxer.write_packet(&mut io, &pkt).unwrap();
+        }
+        muxer.write_trailer(&mut io).unwrap();
+        io.seek(std::io::SeekFrom::Start(0)).unwrap();
+        let mut demuxer = crate::demuxers::mp4::Mp4Demuxer::create().unwrap();
+        demuxer.open(&mut io).unwrap();
+        demuxer.streams()[0].clone()
+    }
+
+    #[test]
+    fn test_esds_default_asc_from_params() {
+        let mut stream = make_audio_stream();
+        stream.index = 0;
+        stream.extra_data.clear();
+        if let StreamParams::Audio(a) = &mut stream.params {
+            a.sample_rate = 48000;
+            a.channel_layout = ChannelLayout::from_channels(1);
+        }
+        let demuxed = roundtrip_audio_stream(stream);
+        assert_eq!(
+            demuxed.extra_data,
+            vec![0x11, 0x88],
+            "缺少 extra_data 时应按流参数生成 AudioSpecificConfig"
+        );
+    }
+
+    #[test]
+    fn test_flac_dfla_roundtrip() {
+        let stream_info: Vec<u8> = (0..34).collect();
+        let mut stream = make_audio_stream();
+        stream.index = 0;
+        stream.codec_id = CodecId::Flac;
+        stream.extra_data = stream_info.clone();
+        let demuxed = roundtrip_audio_stream(stream);
+        assert_eq!(demuxed.codec_id, CodecId::Flac);
+        assert_eq!(demuxed.extra_data, stream_info, "dfLa 应保存 STREAMINFO");
+    }
 }
@@ -426,6 +426,8 @@ mod tests {
                 frame_rate: Rational::new(30, 1),
                 sample_aspect_ratio: Rational::new(1, 1),
                 bit_rate: 0,
+                color_space: Default::default(),
+                color_range: Default::default(),
             }),
             metadata: Vec::new(),
         }
@@ -447,6 +449,7 @@ mod tests {
                 sample_format: SampleFormat::S16,
                 bit_rate: 128000,
                 frame_size: 1024,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         }
@@ -472,12 +475,14 @@ mod tests {
         let streams = vec![make_video_stream()];
         muxer.write_header(&mut io, &streams).unwrap();
 
-        let mut packet = Packet::from_data(vec![0x00, 0x00, 0x00, 0x01, 0x65, 0xAB, 0xCD]);
-        packet.pts = 90000;
-        packet.dts = 90000;
-        packet.duration = 3000;
-        packet.stream_index = 0;
-        packet.is_keyframe = true;
+        let packet = Packet::builder()
+            .data(vec![0x00, 0x00, 0x00, 0x01, 0x65, 0xAB, 0xCD])
+            .pts(90000)
+            .dts(90000)
+            .duration(3000)
+            .stream_index(0)
+            .key_frame(true)
+            .build();
         muxer.write_packet(&mut io, &packet).unwrap();
 
         let pos = io.position().unwrap();
@@ -495,19 +500,23 @@ mod tests {
         let streams = vec![make_video_stream(), make_audio_stream()];
         muxer.write_header(&mut io, &streams).unwrap();
 
-        let mut v_pkt = Packet::from_data(vec![0x00, 0x00, 0x00, 0x01, 0x65]);
-        v_pkt.pts = 0;
-        v_pkt.dts = 0;
-        v_pkt.duration = 3000;
-        v_pkt.stream_index = 0;
-        v_pkt.is_keyframe = true;
-
-        let mut a_pkt = Packet::from_data(vec![0xFF, 0xF1, 0x50, 0x80]);
-        a_pkt.pts = 0;
-        a_pkt.dts = 0;
-        a_pkt.duration = 2090;
-        a_pkt.stream_index = 1;
-        a_pkt.is_keyframe = true;
+        let v_pkt = Packet::builder()
+            .data(vec![0x00, 0x00, 0x00, 0x01, 0x65])
+            .pts(0)
+            .dts(0)
+            .duration(3000)
+            .stream_index(0)
+            .key_frame(true)
+            .build();
+
+        let a_pkt = Packet::builder()
+            .data(vec![0xFF, 0xF1, 0x50, 0x80])
+            .pts(0)
+            .dts(0)
+            .duration(2090)
+            .stream_index(1)
+            .key_frame(true)
+            .build();
         muxer.write_packet(&mut io, &v_pkt).unwrap();
         muxer.write_packet(&mut io, &a_pkt).unwrap();
 
@@ -6,7 +6,7 @@
 //! ```text
 //! "OggS" (4 bytes)
 //! Version (1 byte = 0)
-//! Header Type (1 byte): 0x02=BOS, 0x04=EOS
+//! Header Type (1 byte): 0x01=续包, 0x02=BOS, 0x04=EOS
 //! Granule Position (8 bytes, LE)
 //! Serial Number (4 bytes, LE)
 //! Page Sequence (4 bytes, LE)
@@ -15,157 +15,413 @@
 //! Segment Table (N bytes)
 //! Page Data
 //! ```
+//!
+//! # 页面规则
+//! - 每条流的 BOS 页面只包含标识头包, 所有 BOS 页面位于文件开头
+//! - 其余头包 (Vorbis comment/setup, OpusTags) 在数据包之前单独结束一页
+//! - 数据包按段表 lacing 打包, 跨页的包在后续页面标记续包标志
+//! - 页面的 granule 为页内最后一个完整包结束时的位置, 无完整包时为 -1
+//! - 每条流的最后一页带 EOS 标志
+//!
+//! # granule position
+//! - Vorbis: 已输出的 PCM 采样数, 由 setup 头包的模式表解析每个包的块大小得到
+//! - Opus: 48 kHz 采样数 (含 pre-skip), 由每个包的 TOC 字节得到
+//! - 其他编解码器: 数据包的 pts + duration
 
-use tao_codec::Packet;
-use tao_core::{TaoError, TaoResult};
+use log::{debug, warn};
+use tao_codec::parsers::{opus, vorbis};
+use tao_codec::{CodecId, Packet};
+use tao_core::rational::rescale_q;
+use tao_core::{Rational, TaoError, TaoResult};
 
 use crate::format_id::FormatId;
 use crate::io::IoContext;
 use crate::muxer::Muxer;
 use crate::stream::{Stream, StreamParams};
 
-/// Ogg 封装器
-pub struct OggMuxer {
-    /// 每个逻辑流的序列号
-    serial_numbers: Vec<u32>,
-    /// 每个逻辑流的页面序号
-    page_sequences: Vec<u32>,
-    /// 每个流的粒度位置追踪
-    granule_positions: Vec<i64>,
-    /// 头部是否已写入
-    header_written: bool,
+/// 续包标志: 页面首个段属于上一页未结束的包
+const FLAG_CONTINUED: u8 = 0x01;
+/// 逻辑流开始标志
+const FLAG_BOS: u8 = 0x02;
+/// 逻辑流结束标志
+const FLAG_EOS: u8 = 0x04;
+/// 每页最多段数
+const MAX_SEGMENTS: usize = 255;
+/// 页面数据达到该大小后, 在下一个包到来前写出页面
+const PAGE_TARGET_SIZE: usize = 4096;
+/// 生成 OpusTags 时使用的 vendor 字符串
+const VENDOR: &str = "tao";
+
+/// 各编解码器的 granule 计算方式
+enum GranuleMode {
+    /// Vorbis: 解析块大小得到每包采样数, 缺少 setup 头包时退化为 duration 累加
+    Vorbis {
+        ident: Vec<u8>,
+        parser: Option<vorbis::VorbisPacketParser>,
+    },
+    /// Opus: 解析 TOC 得到每包 48 kHz 采样数
+    Opus,
+    /// 其他: pts + duration
+    Timestamp,
 }
 
-impl OggMuxer {
-    /// 创建 Ogg 封装器 (工厂函数)
-    pub fn create() -> TaoResult<Box<dyn Muxer>> {
-        Ok(Box::new(Self {
-            serial_numbers: Vec::new(),
-            page_sequences: Vec::new(),
-            granule_positions: Vec::new(),
-            header_written: false,
-        }))
+/// 正在组装的页面
+#[derive(Default)]
+struct PageBuffer {
+    /// 段表
+    segments: Vec<u8>,
+    /// 页面数据
+    data: Vec<u8>,
+    /// 页内最后一个完整包结束时的 granule, -1 表示无完整包
+    granule: i64,
+    /// 首段是否为上一页的续包
+    continued: bool,
+}
+
+impl PageBuffer {
+    fn new() -> Self {
+        Self {
+            granule: -1,
+            ..Default::default()
+        }
     }
+}
+
+/// 单条逻辑流的封装状态
+struct OggStream {
+    serial: u32,
+    /// 下一页的页面序号
+    page_sequence: u32,
+    /// 流时间基, 用于换算数据包 duration
+    time_base: Rational,
+    mode: GranuleMode,
+    /// 头包是否已全部写出 (之后的包均为数据包)
+    headers_done: bool,
+    /// 最近一个完整包结束时的 granule
+    granule: i64,
+    page: PageBuffer,
+}
 
-    /// 写入一个 Ogg 页面
-    fn write_page(
+impl OggStream {
+    /// 将一个完整数据包按 lacing 追加到页面, 段表写满时写出页面并续到下一页
+    fn append_packet(&mut self, io: &mut IoContext, data: &[u8], granule: i64) -> TaoResult<()> {
+        let mut offset = 0;
+        loop {
+            if self.page.segments.len() == MAX_SEGMENTS {
+                self.flush_page(io, 0)?;
+                self.page.continued = true;
+            }
+            let len = (data.len() - offset).min(255);
+            self.page.segments.push(len as u8);
+            self.page
+                .data
+                .extend_from_slice(&data[offset..offset + len]);
+            offset += len;
+            // 长度小于 255 的段结束当前包 (255 整数倍的包以 0 长度段结束)
+            if len < 255 {
+                self.page.granule = granule;
+                self.granule = granule;
+                return Ok(());
+            }
+        }
+    }
+
+    /// 写出当前页面 (空页面仅在带 EOS 时写出)
+    fn flush_page(&mut self, io: &mut IoContext, flags: u8) -> TaoResult<()> {
+        if self.page.segments.is_empty() && flags & FLAG_EOS == 0 {
+            return Ok(());
+        }
+        let page = std::mem::replace(&mut self.page, PageBuffer::new());
+        let mut header_type = flags;
+        if page.continued {
+            header_type |= FLAG_CONTINUED;
+        }
+        // 无任何段的 EOS 页面沿用最后的 granule
+        let granule = if page.segments.is_empty() {
+            self.granule
+        } else {
+            page.granule
+        };
+        write_page(
+            io,
+            header_type,
+            granule,
+            self.serial,
+            self.page_sequence,
+            &page.segments,
+            &page.data,
+        )?;
+        self.page_sequence += 1;
+        Ok(())
+    }
+
+    /// 写出一个头包 (granule 为 0), `last` 为真时结束当前页
+    fn write_header_packet(
+        &mut self,
         io: &mut IoContext,
-        header_type: u8,
-        granule_position: i64,
-        serial_number: u32,
-        page_sequence: u32,
         data: &[u8],
+        last: bool,
     ) -> TaoResult<()> {
-        // 段表: 每段最多 255 字节
-        let mut segments = Vec::new();
-        let mut remaining = data.len();
-        while remaining >= 255 {
-            segments.push(255u8);
-            remaining -= 255;
+        self.append_packet(io, data, 0)?;
+        if last {
+            self.flush_page(io, 0)?;
+            self.headers_done = true;
         }
-        segments.push(remaining as u8);
-
-        let num_segments = segments.len() as u8;
-
-        // 构建页面头部 (不含 CRC)
-        let mut header = Vec::with_capacity(27 + segments.len());
-        header.extend_from_slice(b"OggS"); // capture pattern
-        header.push(0); // version
-        header.push(header_type); // header type
-        header.extend_from_slice(&granule_position.to_le_bytes()); // granule
-        header.extend_from_slice(&serial_number.to_le_bytes()); // serial
-        header.extend_from_slice(&page_sequence.to_le_bytes()); // page seq
-        header.extend_from_slice(&0u32.to_le_bytes()); // CRC placeholder
-        header.push(num_segments); // num segments
-        header.extend_from_slice(&segments); // segment table
-
-        // 计算 CRC
-        let crc = ogg_crc(&header, data);
-        header[22..26].copy_from_slice(&crc.to_le_bytes());
-
-        // 写入
-        io.write_all(&header)?;
-        io.write_all(data)?;
-
         Ok(())
     }
-}
 
-impl Muxer for OggMuxer {
-    fn format_id(&self) -> FormatId {
-        FormatId::Ogg
+    /// 处理头包阶段的数据包, 返回 `true` 表示已作为头包消费
+    fn handle_header_packet(&mut self, io: &mut IoContext, data: &[u8]) -> TaoResult<bool> {
+        match &mut self.mode {
+            GranuleMode::Vorbis { ident, parser } => {
+                if vorbis::is_header_packet(data, 1) {
+                    // 标识头已写入 BOS 页面
+                    return Ok(true);
+                }
+                if vorbis::is_header_packet(data, 3) {
+                    self.write_header_packet(io, data, false)?;
+                    return Ok(true);
+                }
+                if vorbis::is_header_packet(data, 5) {
+                    *parser = parse_vorbis_setup(ident, data);
+                    self.write_header_packet(io, data, true)?;
+                    return Ok(true);
+                }
+                warn!("Ogg: 流 serial={} 缺少 Vorbis setup 头包", self.serial);
+                self.flush_page(io, 0)?;
+                self.headers_done = true;
+                Ok(false)
+            }
+            GranuleMode::Opus => {
+                if opus::is_opus_head(data) {
+                    return Ok(true);
+                }
+                if opus::is_opus_tags(data) {
+                    self.write_header_packet(io, data, true)?;
+                    return Ok(true);
+                }
+                // 上游未提供 OpusTags, 补写一个空注释头
+                self.write_header_packet(io, &opus::build_opus_tags(VENDOR), true)?;
+                Ok(false)
+            }
+            GranuleMode::Timestamp => {
+                self.headers_done = true;
+                Ok(false)
+            }
+        }
     }
 
-    fn name(&self) -> &str {
-        "ogg"
+    /// 计算数据包结束时的 granule
+    fn next_granule(&mut self, packet: &Packet) -> TaoResult<i64> {
+        let samples = match &mut self.mode {
+            GranuleMode::Vorbis {
+                parser: Some(parser),
+                ..
+            } => i64::from(parser.packet_samples(&packet.data)?),
+            GranuleMode::Opus => i64::from(opus::packet_samples(&packet.data)?),
+            GranuleMode::Vorbis { parser: None, .. } => self.packet_duration(packet),
+            GranuleMode::Timestamp => {
+                let duration = self.packet_duration(packet);
+                if packet.pts >= 0 {
+                    let pts = if packet.time_base.is_valid() {
+                        rescale_q(packet.pts, packet.time_base, self.time_base)
+                    } else {
+                        packet.pts
+                    };
+                    return Ok(pts + duration);
+                }
+                duration
+            }
+        };
+        Ok(self.granule.max(0) + samples)
     }
 
-    fn write_header(&mut self, io: &mut IoContext, streams: &[Stream]) -> TaoResult<()> {
-        if streams.is_empty() {
-            return Err(TaoError::InvalidArgument("Ogg: 没有输入流".into()));
+    /// 以流时间基表示的数据包时长
+    fn packet_duration(&self, packet: &Packet) -> i64 {
+        if packet.duration <= 0 {
+            return 0;
         }
+        if packet.time_base.is_valid() && self.time_base.is_valid() {
+            rescale_q(packet.duration, packet.time_base, self.time_base)
+        } else {
+            packet.duration
+        }
+    }
+}
+
+/// Ogg 封装器
+pub struct OggMuxer {
+    /// 每条流的封装状态
+    streams: Vec<OggStream>,
+    /// 头部是否已写入
+    header_written: bool,
+}
 
-        self.serial_numbers.clear();
-        self.page_sequences.clear();
-        self.granule_positions.clear();
+impl OggMuxer {
+    /// 创建 Ogg 封装器 (工厂函数)
+    pub fn create() -> TaoResult<Box<dyn Muxer>> {
+        Ok(Box::new(Self {
+            streams: Vec::new(),
+            header_written: false,
+        }))
+    }
+}
 
-        for (i, stream) in streams.iter().enumerate() {
-            let serial = (i as u32 + 1) * 0x12345;
-            self.serial_numbers.push(serial);
-            self.page_sequences.push(0);
-            self.granule_positions.push(0);
+/// 解析 Vorbis setup 头包, 失败时退化为 duration 累加
+fn parse_vorbis_setup(ident: &[u8], setup: &[u8]) -> Option<vorbis::VorbisPacketParser> {
+    match vorbis::VorbisPacketParser::new(ident, setup) {
+        Ok(parser) => Some(parser),
+        Err(e) => {
+            warn!(
+                "Ogg: Vorbis 头包解析失败, granule 改用数据包时长计算: {}",
+                e
+            );
+            None
+        }
+    }
+}
 
-            // 写入 BOS (Beginning Of Stream) 页面
-            // 包含编解码器标识头
-            let codec_header = match &stream.params {
+/// 从流参数中拆出头包, 首个为 BOS 页面中的标识头
+fn stream_headers(stream: &Stream) -> TaoResult<Vec<Vec<u8>>> {
+    match stream.codec_id {
+        CodecId::Vorbis => {
+            if let Some((ident, comment, setup)) = vorbis::split_xiph_headers(&stream.extra_data)? {
+                return Ok(vec![ident.to_vec(), comment.to_vec(), setup.to_vec()]);
+            }
+            if !vorbis::is_header_packet(&stream.extra_data, 1) {
+                return Err(TaoError::InvalidArgument(
+                    "Ogg: Vorbis 流缺少 identification 头包".into(),
+                ));
+            }
+            Ok(vec![stream.extra_data.clone()])
+        }
+        CodecId::Opus => {
+            if !opus::is_opus_head(&stream.extra_data) {
+                return Err(TaoError::InvalidArgument(
+                    "Ogg: Opus 流缺少 OpusHead 头包".into(),
+                ));
+            }
+            Ok(vec![stream.extra_data.clone()])
+        }
+        _ if !stream.extra_data.is_empty() => Ok(vec![stream.extra_data.clone()]),
+        _ => {
+            // 简化的标识头: 编解码器名称 + 基本参数
+            let codec_name = stream.codec_id.name();
+            let mut hdr = Vec::new();
+            match &stream.params {
                 StreamParams::Audio(audio) => {
-                    // 简化的 Vorbis/Opus 头
-                    let mut hdr = Vec::new();
-                    // 通用头部: 标识字符串
-                    let codec_name = stream.codec_id.name();
                     hdr.push(codec_name.len() as u8);
                     hdr.extend_from_slice(codec_name.as_bytes());
-                    // 采样率
                     hdr.extend_from_slice(&audio.sample_rate.to_le_bytes());
-                    // 声道数
                     hdr.push(audio.channel_layout.channels as u8);
-                    hdr
                 }
                 StreamParams::Video(video) => {
-                    let mut hdr = Vec::new();
-                    let codec_name = stream.codec_id.name();
                     hdr.push(codec_name.len() as u8);
                     hdr.extend_from_slice(codec_name.as_bytes());
                     hdr.extend_from_slice(&video.width.to_le_bytes());
                     hdr.extend_from_slice(&video.height.to_le_bytes());
-                    hdr
                 }
                 _ => {
-                    let mut hdr = Vec::new();
                     hdr.push(4);
                     hdr.extend_from_slice(b"data");
-                    hdr
                 }
-            };
+            }
+            Ok(vec![hdr])
+        }
+    }
+}
 
-            // 如果有 extra_data, 使用它替代简化头
-            let header_data = if !stream.extra_data.is_empty() {
-                &stream.extra_data
-            } else {
-                &codec_header
+/// 写入一个 Ogg 页面
+fn write_page(
+    io: &mut IoContext,
+    header_type: u8,
+    granule_position: i64,
+    serial_number: u32,
+    page_sequence: u32,
+    segments: &[u8],
+    data: &[u8],
+) -> TaoResult<()> {
+    // 构建页面头部 (不含 CRC)
+    let mut header = Vec::with_capacity(27 + segments.len());
+    header.extend_from_slice(b"OggS"); // capture pattern
+    header.push(0); // version
+    header.push(header_type); // header type
+    header.extend_from_slice(&granule_position.to_le_bytes()); // granule
+    header.extend_from_slice(&serial_number.to_le_bytes()); // serial
+    header.extend_from_slice(&page_sequence.to_le_bytes()); // page seq
+    header.extend_from_slice(&0u32.to_le_bytes()); // CRC placeholder
+    header.push(segments.len() as u8); // num segments
+    header.extend_from_slice(segments); // segment table
+
+    // 计算 CRC
+    let crc = ogg_crc(&header, data);
+    header[22..26].copy_from_slice(&crc.to_le_bytes());
+
+    io.write_all(&header)?;
+    io.write_all(data)?;
+    Ok(())
+}
+
+impl Muxer for OggMuxer {
+    fn format_id(&self) -> FormatId {
+        FormatId::Ogg
+    }
+
+    fn name(&self) -> &str {
+        "ogg"
+    }
+
+    fn write_header(&mut self, io: &mut IoContext, streams: &[Stream]) -> TaoResult<()> {
+        if streams.is_empty() {
+            return Err(TaoError::InvalidArgument("Ogg: 没有输入流".into()));
+        }
+
+        let headers = streams
+            .iter()
+            .map(stream_headers)
+            .collect::<TaoResult<Vec<_>>>()?;
+
+        self.streams.clear();
+        for (i, (stream, headers)) in streams.iter().zip(&headers).enumerate() {
+            let mode = match stream.codec_id {
+                CodecId::Vorbis => GranuleMode::Vorbis {
+                    parser: headers
+                        .get(2)
+                        .and_then(|setup| parse_vorbis_setup(&headers[0], setup)),
+                    ident: headers[0].clone(),
+                },
+                CodecId::Opus => GranuleMode::Opus,
+                _ => GranuleMode::Timestamp,
             };
+            let mut state = OggStream {
+                serial: (i as u32 + 1) * 0x12345,
+                page_sequence: 0,
+                time_base: stream.time_base,
+                mode,
+                // Vorbis 三个头包齐全或非 Xiph 编解码器时, 无需等待后续头包
+                headers_done: headers.len() > 1
+                    || !matches!(stream.codec_id, CodecId::Vorbis | CodecId::Opus),
+                granule: 0,
+                page: PageBuffer::new(),
+            };
+
+            // BOS 页面只包含标识头包
+            state.append_packet(io, &headers[0], 0)?;
+            state.flush_page(io, FLAG_BOS)?;
+            self.streams.push(state);
+        }
 
-            Self::write_page(
-                io,
-                0x02, // BOS
-                0,    // granule = 0 for BOS
-                serial,
-                0,
-                header_data,
-            )?;
-            self.page_sequences[i] = 1;
+        // 所有 BOS 页面之后写出其余头包, 并在数据包之前结束页面
+        for (state, headers) in self.streams.iter_mut().zip(&headers) {
+            if let Some((last, rest)) = headers[1..].split_last() {
+                for header in rest {
+                    state.write_header_packet(io, header, false)?;
+                }
+                state.write_header_packet(io, last, true)?;
+            }
         }
 
+        debug!("Ogg: 写入 {} 条流的头部", self.streams.len());
         self.header_written = true;
         Ok(())
     }
@@ -175,34 +431,31 @@ impl Muxer for OggMuxer {
             return Err(TaoError::Codec("Ogg: 头部尚未写入".into()));
         }
 
-        let idx = packet.stream_index;
-        if idx >= self.serial_numbers.len() {
-            return Err(TaoError::StreamNotFound(idx));
+        if packet.data.is_empty() {
+            return Ok(());
         }
 
-        let serial = self.serial_numbers[idx];
-        let page_seq = self.page_sequences[idx];
-
-        // 更新粒度位置 (基于 PTS + duration)
-        let granule = if packet.pts >= 0 {
-            packet.pts + packet.duration
-        } else {
-            self.granule_positions[idx] + packet.duration
-        };
-        self.granule_positions[idx] = granule;
+        let idx = packet.stream_index;
+        let state = self
+            .streams
+            .get_mut(idx)
+            .ok_or(TaoError::StreamNotFound(idx))?;
 
-        Self::write_page(io, 0x00, granule, serial, page_seq, &packet.data)?;
-        self.page_sequences[idx] = page_seq + 1;
+        if !state.headers_done && state.handle_header_packet(io, &packet.data)? {
+            return Ok(());
+        }
 
-        Ok(())
+        let granule = state.next_granule(packet)?;
+        if state.page.data.len() >= PAGE_TARGET_SIZE {
+            state.flush_page(io, 0)?;
+        }
+        state.append_packet(io, &packet.data, granule)
     }
 
     fn write_trailer(&mut self, io: &mut IoContext) -> TaoResult<()> {
-        // 写入 EOS 页面
-        for (i, &serial) in self.serial_numbers.iter().enumerate() {
-            let page_seq = self.page_sequences[i];
-            let granule = self.granule_positions[i];
-            Self::write_page(io, 0x04, granule, serial, page_seq, &[])?;
+        // 每条流的最后一页带 EOS 标志
+        for state in &mut self.streams {
+            state.flush_page(io, FLAG_EOS)?;
         }
         Ok(())
     }
@@ -258,6 +511,7 @@ mod tests {
                 sample_format: SampleFormat::S16,
                 bit_rate: 0,
                 frame_size: 1024,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         }
@@ -282,16 +536,19 @@ mod tests {
         let streams = vec![make_audio_stream()];
         muxer.write_header(&mut io, &streams).unwrap();
 
-        let mut packet = Packet::from_data(vec![1u8, 2, 3, 4, 5]);
-        packet.pts = 0;
-        packet.dts = 0;
-        packet.duration = 1024;
-        packet.stream_index = 0;
-        packet.is_keyframe = true;
+        let packet = Packet::builder()
+            .data(vec![1u8, 2, 3, 4, 5])
+            .pts(0)
+            .dts(0)
+            .duration(1024)
+            .stream_index(0)
+            .key_frame(true)
+            .build();
         muxer.write_packet(&mut io, &packet).unwrap();
+        muxer.write_trailer(&mut io).unwrap();
 
         let pos = io.position().unwrap();
-        // BOS 页面 + 数据页面, 各至少 28 字节
+        // BOS 页面 + 数据页面 (带 EOS), 各至少 28 字节
         assert!(pos > 56, "应写入两个 Ogg 页面");
     }
 
@@ -316,4 +573,141 @@ mod tests {
         let mut io = IoContext::new(Box::new(backend));
         assert!(muxer.write_header(&mut io, &[]).is_err());
     }
+
+    /// 解析出的页面: (header_type, granule, 段表, 数据)
+    type ParsedPage = (u8, i64, Vec<u8>, Vec<u8>);
+
+    fn read_pages(io: &mut IoContext) -> Vec<ParsedPage> {
+        let len = io.position().unwrap() as usize;
+        io.seek(std::io::SeekFrom::Start(0)).unwrap();
+        let bytes = io.read_bytes(len).unwrap();
+        let mut pages = Vec::new();
+        let mut pos = 0;
+        while pos < bytes.len() {
+            assert_eq!(&bytes[pos..pos + 4], b"OggS", "页面同步字错误");
+            let header_type = bytes[pos + 5];
+            let granule = i64::from_le_bytes(bytes[pos + 6..pos + 14].try_into().unwrap());
+            let nsegs = bytes[pos + 26] as usize;
+            let segments = bytes[pos + 27..pos + 27 + nsegs].to_vec();
+            let body = pos + 27 + nsegs;
+            let size: usize = segments.iter().map(|&s| s as usize).sum();
+            pages.push((
+                header_type,
+                granule,
+                segments,
+                bytes[body..body + size].to_vec(),
+            ));
+            pos = body + size;
+        }
+        pages
+    }
+
+    fn make_opus_stream() -> Stream {
+        let mut head = b"OpusHead".to_vec();
+        head.extend_from_slice(&[1, 2]);
+        head.extend_from_slice(&312u16.to_le_bytes());
+        head.extend_from_slice(&48000u32.to_le_bytes());
+        head.extend_from_slice(&[0, 0, 0]);
+        Stream {
+            codec_id: CodecId::Opus,
+            time_base: Rational::new(1, 48000),
+            extra_data: head,
+            ..make_audio_stream()
+        }
+    }
+
+    #[test]
+    fn test_ogg_opus_granule_and_header_pages() {
+        let mut muxer = OggMuxer::create().unwrap();
+        let mut io = IoContext::new(Box::new(MemoryBackend::new()));
+        muxer.write_header(&mut io, &[make_opus_stream()]).unwrap();
+        // CELT FB 20 ms 单帧包: 每包 960 采样
+        for i in 0..100 {
+            let mut data = vec![0u8; 120];
+            data[0] = 31 << 3;
+            let packet = Packet::builder()
+                .data(data)
+                .pts(i * 960)
+                .duration(960)
+                .stream_index(0)
+                .build();
+            muxer.write_packet(&mut io, &packet).unwrap();
+        }
+        muxer.write_trailer(&mut io).unwrap();
+
+        let pages = read_pages(&mut io);
+        let (bos_flags, bos_granule, bos_segments, bos_data) = &pages[0];
+        assert_eq!(*bos_flags, FLAG_BOS);
+        assert_eq!(*bos_granule, 0);
+        assert_eq!(bos_segments.len(), 1, "BOS 页面应只包含 OpusHead");
+        assert!(opus::is_opus_head(bos_data));
+
+        let (tags_flags, tags_granule, tags_segments, tags_data) = &pages[1];
+        assert_eq!((*tags_flags, *tags_granule), (0, 0));
+        assert_eq!(tags_segments.len(), 1, "OpusTags 应单独结束一页");
+        assert!(opus::is_opus_tags(tags_data), "缺少 OpusTags 时应补写");
+
+        let data_pages = &pages[2..];
+        assert!(data_pages.len() > 1, "数据应按页面目标大小分页");
+        let granules: Vec<i64> = data_pages.iter().map(|p| p.1).collect();
+        assert!(
+            granules.windows(2).all(|w| w[0] < w[1]),
+            "granule 应严格递增: {granules:?}"
+        );
+        assert_eq!(
+            *granules.last().unwrap(),
+            100 * 960,
+            "granule 应为 48 kHz 累计采样数"
+        );
+        for (i, page) in pages.iter().enumerate() {
+            assert_eq!(
+                page.0 & FLAG_EOS != 0,
+                i == pages.len() - 1,
+                "只有最后一页应带 EOS"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ogg_large_packet_continues_across_pages() {
+        let mut muxer = OggMuxer::create().unwrap();
+        let mut io = IoContext::new(Box::new(MemoryBackend::new()));
+        muxer.write_header(&mut io, &[make_audio_stream()]).unwrap();
+        // 70000 字节 = 274 个 255 字节段 + 1 个 130 字节段, 超过单页 255 段
+        let payload: Vec<u8> = (0..70000u32).map(|i| i as u8).collect();
+        let packet = Packet::builder()
+            .data(payload.clone())
+            .pts(0)
+            .duration(4096)
+            .stream_index(0)
+            .build();
+        muxer.write_packet(&mut io, &packet).unwrap();
+        muxer.write_trailer(&mut io).unwrap();
+
+        let pages = read_pages(&mut io);
+        assert_eq!(pages.len(), 3, "BOS + 两个数据页面");
+        let (flags, granule, segments, _) = &pages[1];
+        assert_eq!(segments.len(), 255);
+        assert_eq!(*flags, 0);
+        assert_eq!(*granule, -1, "无完整包结束的页面 granule 应为 -1");
+        let (flags, granule, segments, _) = &pages[2];
+        assert_eq!(*flags, FLAG_CONTINUED | FLAG_EOS, "续页应带续包与 EOS 标志");
+        assert_eq!(*granule, 4096);
+        assert_eq!(segments.len(), 20);
+        assert_eq!(*segments.last().unwrap(), (70000 % 255) as u8);
+
+        let joined: Vec<u8> = [pages[1].3.clone(), pages[2].3.clone()].concat();
+        assert_eq!(joined, payload, "跨页拼接后应还原完整数据包");
+    }
+
+    #[test]
+    fn test_ogg_vorbis_requires_ident_header() {
+        let mut muxer = OggMuxer::create().unwrap();
+        let mut io = IoContext::new(Box::new(MemoryBackend::new()));
+        let stream = Stream {
+            codec_id: CodecId::Vorbis,
+            ..make_audio_stream()
+        };
+        assert!(muxer.write_header(&mut io, &[stream]).is_err());
+    }
 }
@@ -0,0 +1,235 @@
+//! 裸流 (Elementary Stream) 封装器.
+//!
+//! 不添加任何容器结构, 直接写出单条流的数据包内容:
+//! - H.264 (.h264/.264): 输出 Annex-B 字节流. AVCC 输入会转换长度前缀为起始码,
+//!   并在每个关键帧前插入 avcC 中的 SPS/PPS.
+//! - MPEG-4 Part 2 (.m4v): 先写出 extra_data 中的 VOS/VOL 头, 再写数据包.
+//! - PCM (.pcm/.raw) 与 Raw 视频 (.yuv/.rgb): 原样写出数据包.
+
+use log::debug;
+use tao_codec::parsers::h264::{avcc_to_annex_b, parse_avcc_config};
+use tao_codec::{CodecId, Packet};
+use tao_core::{MediaType, TaoError, TaoResult};
+
+use crate::format_id::FormatId;
+use crate::io::IoContext;
+use crate::muxer::Muxer;
+use crate::stream::Stream;
+
+/// Annex-B 起始码
+const START_CODE: [u8; 4] = [0x00, 0x00, 0x00, 0x01];
+
+/// 裸流封装器
+pub struct RawMuxer {
+    /// 输出格式
+    format: FormatId,
+    /// AVCC 输入的 NAL 长度前缀大小, `None` 表示数据包已是 Annex-B
+    nal_length_size: Option<usize>,
+    /// 关键帧前插入的参数集 (Annex-B 格式)
+    parameter_sets: Vec<u8>,
+}
+
+impl RawMuxer {
+    fn boxed(format: FormatId) -> Box<dyn Muxer> {
+        Box::new(Self {
+            format,
+            nal_length_size: None,
+            parameter_sets: Vec::new(),
+        })
+    }
+
+    /// 创建 H.264 Annex-B 裸流封装器 (工厂函数)
+    pub fn create_h264() -> TaoResult<Box<dyn Muxer>> {
+        Ok(Self::boxed(FormatId::H264Es))
+    }
+
+    /// 创建 MPEG-4 Part 2 裸流封装器 (工厂函数)
+    pub fn create_m4v() -> TaoResult<Box<dyn Muxer>> {
+        Ok(Self::boxed(FormatId::Mpeg4Es))
+    }
+
+    /// 创建 PCM 裸流封装器 (工厂函数)
+    pub fn create_pcm() -> TaoResult<Box<dyn Muxer>> {
+        Ok(Self::boxed(FormatId::RawAudio))
+    }
+
+    /// 创建 Raw 视频封装器 (工厂函数)
+    pub fn create_rawvideo() -> TaoResult<Box<dyn Muxer>> {
+        Ok(Self::boxed(FormatId::RawVideo))
+    }
+
+    /// 检查流的编解码器是否可写入当前格式
+    fn accepts(&self, stream: &Stream) -> bool {
+        match self.format {
+            FormatId::H264Es => stream.codec_id == CodecId::H264,
+            FormatId::Mpeg4Es => stream.codec_id == CodecId::Mpeg4,
+            FormatId::RawAudio => {
+                stream.media_type == MediaType::Audio && stream.codec_id.name().starts_with("pcm_")
+            }
+            FormatId::RawVideo => stream.codec_id == CodecId::RawVideo,
+            _ => false,
+        }
+    }
+
+    /// 从 avcC 中提取 NAL 长度前缀与参数集
+    fn setup_h264(&mut self, extra_data: &[u8]) -> TaoResult<()> {
+        // 无 extra_data 或已是 Annex-B 起始码时按 Annex-B 直接写出
+        if extra_data.is_empty() || extra_data.first() != Some(&1) {
+            return Ok(());
+        }
+        let config = parse_avcc_config(extra_data)?;
+        for nal in config.sps_list.iter().chain(&config.pps_list) {
+            self.parameter_sets.extend_from_slice(&START_CODE);
+            self.parameter_sets.extend_from_slice(nal);
+        }
+        self.nal_length_size = Some(config.length_size);
+        Ok(())
+    }
+}
+
+impl Muxer for RawMuxer {
+    fn format_id(&self) -> FormatId {
+        self.format
+    }
+
+    fn name(&self) -> &str {
+        self.format.name()
+    }
+
+    fn write_header(&mut self, io: &mut IoContext, streams: &[Stream]) -> TaoResult<()> {
+        let stream = match streams {
+            [stream] => stream,
+            _ => {
+                return Err(TaoError::InvalidArgument(format!(
+                    "{} 裸流仅支持单条流, 当前 {} 条",
+                    self.format,
+                    streams.len()
+                )));
+            }
+        };
+        if !self.accepts(stream) {
+            return Err(TaoError::InvalidArgument(format!(
+                "{} 裸流不支持编解码器 {}",
+                self.format, stream.codec_id
+            )));
+        }
+
+        match self.format {
+            FormatId::H264Es => self.setup_h264(&stream.extra_data)?,
+            FormatId::Mpeg4Es if !stream.extra_data.is_empty() => {
+                io.write_all(&stream.extra_data)?;
+            }
+            _ => {}
+        }
+        debug!(
+            "{} 裸流写入头部: codec={}, avcc={:?}",
+            self.format, stream.codec_id, self.nal_length_size
+        );
+        Ok(())
+    }
+
+    fn write_packet(&mut self, io: &mut IoContext, packet: &Packet) -> TaoResult<()> {
+        if packet.data.is_empty() {
+            return Ok(());
+        }
+        match self.nal_length_size {
+            Some(length_size) => {
+                if packet.is_keyframe() {
+                    io.write_all(&self.parameter_sets)?;
+                }
+                io.write_all(&avcc_to_annex_b(&packet.data, length_size))
+            }
+            None => io.write_all(&packet.data),
+        }
+    }
+
+    fn write_trailer(&mut self, _io: &mut IoContext) -> TaoResult<()> {
+        // 裸流无尾部
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::MemoryBackend;
+    use crate::stream::{StreamParams, VideoStreamParams};
+    use bytes::Bytes;
+    use tao_core::color::{ColorRange, ColorSpace};
+    use tao_core::{PixelFormat, Rational};
+
+    fn make_h264_stream(extra_data: Vec<u8>) -> Stream {
+        Stream {
+            index: 0,
+            media_type: MediaType::Video,
+            codec_id: CodecId::H264,
+            time_base: Rational::new(1, 90000),
+            duration: 0,
+            start_time: 0,
+            nb_frames: 0,
+            extra_data,
+            params: StreamParams::Video(VideoStreamParams {
+                width: 16,
+                height: 16,
+                pixel_format: PixelFormat::Yuv420p,
+                frame_rate: Rational::new(25, 1),
+                sample_aspect_ratio: Rational::new(1, 1),
+                bit_rate: 0,
+                color_space: ColorSpace::Unspecified,
+                color_range: ColorRange::Unspecified,
+            }),
+            metadata: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_raw_h264_avcc_to_annex_b() {
+        // avcC: version 1, 4 字节长度前缀, 1 个 SPS, 1 个 PPS
+        let sps = [0x67, 0x42, 0x00, 0x1E];
+        let pps = [0x68, 0xCE];
+        let mut avcc = vec![1, 0x42, 0x00, 0x1E, 0xFF, 0xE1];
+        avcc.extend_from_slice(&(sps.len() as u16).to_be_bytes());
+        avcc.extend_from_slice(&sps);
+        avcc.push(1);
+        avcc.extend_from_slice(&(pps.len() as u16).to_be_bytes());
+        avcc.extend_from_slice(&pps);
+
+        let mut io = IoContext::new(Box::new(MemoryBackend::new()));
+        let mut muxer = RawMuxer::create_h264().unwrap();
+        muxer
+            .write_header(&mut io, &[make_h264_stream(avcc)])
+            .unwrap();
+
+        let mut pkt = Packet::from_data(Bytes::from_static(&[0, 0, 0, 2, 0x65, 0x88]));
+        pkt.set_keyframe(true);
+        muxer.write_packet(&mut io, &pkt).unwrap();
+        let pkt = Packet::from_data(Bytes::from_static(&[0, 0, 0, 2, 0x41, 0x9A]));
+        muxer.write_packet(&mut io, &pkt).unwrap();
+        muxer.write_trailer(&mut io).unwrap();
+
+        let mut expected = Vec::new();
+        for nal in [&sps[..], &pps, &[0x65, 0x88], &[0x41, 0x9A]] {
+            expected.extend_from_slice(&START_CODE);
+            expected.extend_from_slice(nal);
+        }
+        assert_eq!(
+            io.position().unwrap(),
+            expected.len() as u64,
+            "输出长度不符"
+        );
+        io.seek(std::io::SeekFrom::Start(0)).unwrap();
+        assert_eq!(
+            io.read_bytes(expected.len()).unwrap(),
+            expected,
+            "关键帧前应插入参数集, 长度前缀应替换为起始码"
+        );
+    }
+
+    #[test]
+    fn test_raw_rejects_mismatched_codec() {
+        let mut io = IoContext::new(Box::new(MemoryBackend::new()));
+        let mut muxer = RawMuxer::create_pcm().unwrap();
+        let err = muxer.write_header(&mut io, &[make_h264_stream(Vec::new())]);
+        assert!(err.is_err(), "PCM 裸流不应接受 H.264 流");
+    }
+}
@@ -177,6 +177,7 @@ mod tests {
                 sample_format: SampleFormat::S16,
                 bit_rate: 0,
                 frame_size: 0,
+                block_align: 0,
             }),
             metadata: Vec::new(),
         }
@@ -194,7 +195,7 @@ mod tests {
 
         // 写入 4 采样 = 8 字节
         let pcm = vec![0x00, 0x01, 0xFF, 0x7F, 0x00, 0x80, 0x01, 0x00];
-        let pkt = Packet::from_data(bytes::Bytes::from(pcm));
+        let pkt = Packet::new(pcm, 0);
         muxer.write_packet(&mut io, &pkt).unwrap();
         muxer.write_trailer(&mut io).unwrap();
     }
@@ -202,8 +203,7 @@ mod tests {
     #[test]
     fn test_mux_demux_roundtrip() {
         // 写入 WAV
-        let backend = MemoryBackend::new();
-        let mut io_w = IoContext::new(Box::new(backend));
+        let mut io_w = IoContext::new_memory();
 
         let stream = make_audio_stream(CodecId::PcmS16le, 44100, 2);
         let mut muxer = WavMuxer::create().unwrap();
@@ -215,21 +215,16 @@ mod tests {
             0x03, 0x00, 0x04, 0x00, // 采样 1: L, R
             0x05, 0x00, 0x06, 0x00, // 采样 2: L, R
         ];
-        let pkt = Packet::from_data(bytes::Bytes::from(pcm_data.clone()));
+        let pkt = Packet::new(pcm_data.clone(), 0);
         muxer.write_packet(&mut io_w, &pkt).unwrap();
         muxer.write_trailer(&mut io_w).unwrap();
 
-        // 获取写入的数据
-        // 我们需要从 IoContext 中提取 MemoryBackend 的数据
-        // 但 IoContext 不暴露 inner, 所以换一种方式: seek 到开头然后用读取
-        // 更好的方式: 直接重新创建 IoContext
-        io_w.seek(std::io::SeekFrom::Start(0)).unwrap();
-        let pos = io_w.position().unwrap();
-        assert_eq!(pos, 0);
+        // 取出写入的数据, 以只读内存上下文重新打开
+        let mut io_r = IoContext::from_bytes(io_w.to_vec().unwrap());
 
         // 解封装
         let mut demuxer = WavDemuxer::create().unwrap();
-        demuxer.open(&mut io_w).unwrap();
+        demuxer.open(&mut io_r).unwrap();
 
         let streams = demuxer.streams();
         assert_eq!(streams.len(), 1);
@@ -242,7 +237,7 @@ mod tests {
             panic!("期望音频参数");
         }
 
-        let read_pkt = demuxer.read_packet(&mut io_w).unwrap();
+        let read_pkt = demuxer.read_packet(&mut io_r).unwrap();
         assert_eq!(&read_pkt.data[..], &pcm_data[..]);
     }
 
@@ -268,6 +263,8 @@ mod tests {
                 frame_rate: Rational::new(25, 1),
                 sample_aspect_ratio: Rational::new(1, 1),
                 bit_rate: 0,
+                color_space: Default::default(),
+                color_range: Default::default(),
             }),
             metadata: Vec::new(),
         };
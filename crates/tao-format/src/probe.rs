@@ -9,6 +9,9 @@ use crate::format_id::FormatId;
 /// 数值越高, 表示对格式判断越有信心.
 pub type ProbeScore = u32;
 
+/// 可认定格式的最低分数, 低于此值视为无法识别
+pub const SCORE_MIN: ProbeScore = 10;
+
 /// 最低探测分数 (仅根据扩展名)
 pub const SCORE_EXTENSION: ProbeScore = 50;
 
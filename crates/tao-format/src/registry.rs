@@ -4,13 +4,13 @@
 
 use std::collections::HashMap;
 
-use tao_core::TaoResult;
+use tao_core::{MediaType, TaoError, TaoResult};
 
 use crate::demuxer::Demuxer;
 use crate::format_id::FormatId;
 use crate::io::IoContext;
 use crate::muxer::Muxer;
-use crate::probe::{FormatProbe, ProbeResult};
+use crate::probe::{FormatProbe, ProbeResult, ProbeScore, SCORE_MIN};
 
 /// 解封装器工厂函数类型
 pub type DemuxerFactory = fn() -> TaoResult<Box<dyn Demuxer>>;
@@ -25,7 +25,7 @@ pub struct FormatRegistry {
     /// 封装器工厂映射
     muxers: HashMap<FormatId, MuxerEntry>,
     /// 格式探测器列表
-    probes: Vec<Box<dyn FormatProbe + Send>>,
+    probes: Vec<Box<dyn FormatProbe + Send + Sync>>,
 }
 
 /// 解封装器注册条目
@@ -87,23 +87,25 @@ impl FormatRegistry {
     }
 
     /// 注册一个格式探测器
-    pub fn register_probe(&mut self, probe: Box<dyn FormatProbe + Send>) {
+    pub fn register_probe(&mut self, probe: Box<dyn FormatProbe + Send + Sync>) {
         self.probes.push(probe);
     }
 
     /// 创建指定格式的解封装器实例
     pub fn create_demuxer(&self, format_id: FormatId) -> TaoResult<Box<dyn Demuxer>> {
-        let entry = self.demuxers.get(&format_id).ok_or_else(|| {
-            tao_core::TaoError::FormatNotFound(format!("未找到 {} 的解封装器", format_id))
-        })?;
+        let entry = self
+            .demuxers
+            .get(&format_id)
+            .ok_or_else(|| TaoError::FormatNotFound(format!("未找到 {} 的解封装器", format_id)))?;
         (entry.factory)()
     }
 
     /// 创建指定格式的封装器实例
     pub fn create_muxer(&self, format_id: FormatId) -> TaoResult<Box<dyn Muxer>> {
-        let entry = self.muxers.get(&format_id).ok_or_else(|| {
-            tao_core::TaoError::FormatNotFound(format!("未找到 {} 的封装器", format_id))
-        })?;
+        let entry = self
+            .muxers
+            .get(&format_id)
+            .ok_or_else(|| TaoError::FormatNotFound(format!("未找到 {} 的封装器", format_id)))?;
         (entry.factory)()
     }
 
@@ -111,19 +113,43 @@ impl FormatRegistry {
     ///
     /// 遍历所有已注册的探测器, 返回置信度最高的结果.
     pub fn probe(&self, data: &[u8], filename: Option<&str>) -> Option<ProbeResult> {
-        let mut best: Option<ProbeResult> = None;
-        for probe in &self.probes {
-            if let Some(score) = probe.probe(data, filename) {
-                let is_better = best.as_ref().is_none_or(|b| score > b.score);
-                if is_better {
-                    best = Some(ProbeResult {
-                        format_id: probe.format_id(),
-                        score,
-                    });
-                }
-            }
-        }
-        best
+        self.probe_scores(data, filename).into_iter().next()
+    }
+
+    /// 对数据运行所有已注册的探测器, 按置信度从高到低返回全部命中结果
+    ///
+    /// 分数相同时保持注册顺序.
+    pub fn probe_scores(&self, data: &[u8], filename: Option<&str>) -> Vec<ProbeResult> {
+        self.probe_scores_for(data, filename, None)
+    }
+
+    /// 运行探测器并排序, 指定流类型时跳过不可能包含该类型流的格式
+    ///
+    /// 指定流类型时, 同分结果中只包含该类型的格式 (如音频下的 MP3) 排在
+    /// 通用容器 (如 MKV) 之前.
+    fn probe_scores_for(
+        &self,
+        data: &[u8],
+        filename: Option<&str>,
+        media_type: Option<MediaType>,
+    ) -> Vec<ProbeResult> {
+        let mut results: Vec<ProbeResult> = self
+            .probes
+            .iter()
+            .filter(|probe| media_type.is_none_or(|t| probe.format_id().supports_media_type(t)))
+            .filter_map(|probe| {
+                probe.probe(data, filename).map(|score| ProbeResult {
+                    format_id: probe.format_id(),
+                    score,
+                })
+            })
+            .collect();
+        results.sort_by_key(|r| {
+            let generic =
+                media_type.is_some_and(|t| r.format_id.supported_media_types() != [t].as_slice());
+            (std::cmp::Reverse(r.score), generic)
+        });
+        results
     }
 
     /// 获取所有已注册的解封装器名称
@@ -145,25 +171,69 @@ impl FormatRegistry {
     /// 探测输入文件格式 (不打开解封装器)
     ///
     /// 读取文件头部数据, 自动探测格式, 然后 seek 回起始位置.
+    /// 最高置信度低于 [`SCORE_MIN`] 时视为无法识别, 返回 [`TaoError::Unsupported`].
     pub fn probe_input(
         &self,
         io: &mut IoContext,
         filename: Option<&str>,
     ) -> TaoResult<ProbeResult> {
-        // 对含大 ID3v2/APIC 的 MP3 样本, 8KB 头部不足以完成可靠探测.
-        // 将探测窗口提升到 256KB, 以降低误判为 TS 等格式的概率.
-        let probe_size = io.size().unwrap_or(262_144).min(262_144) as usize;
-        let probe_size = probe_size.max(12); // 至少读取 12 字节
-        let probe_buf = io.read_bytes(probe_size)?;
+        self.probe_input_for(io, filename, None)
+    }
 
-        let result = self.probe(&probe_buf, filename).ok_or_else(|| {
-            tao_core::TaoError::FormatNotFound("无法识别输入文件格式".to_string())
-        })?;
+    /// 探测输入文件格式, 只考虑可能包含音频流的格式
+    ///
+    /// 仅需音频时使用: 跳过 H.264 裸流等纯视频格式的探测, 同分时优先纯音频格式.
+    pub fn probe_input_audio_only(
+        &self,
+        io: &mut IoContext,
+        filename: Option<&str>,
+    ) -> TaoResult<ProbeResult> {
+        self.probe_input_for(io, filename, Some(MediaType::Audio))
+    }
 
-        // seek 回起始位置, 供后续 demuxer 读取
-        io.seek(std::io::SeekFrom::Start(0))?;
+    /// 探测输入文件格式, 只考虑可能包含视频流的格式
+    pub fn probe_input_video_only(
+        &self,
+        io: &mut IoContext,
+        filename: Option<&str>,
+    ) -> TaoResult<ProbeResult> {
+        self.probe_input_for(io, filename, Some(MediaType::Video))
+    }
 
-        Ok(result)
+    fn probe_input_for(
+        &self,
+        io: &mut IoContext,
+        filename: Option<&str>,
+        media_type: Option<MediaType>,
+    ) -> TaoResult<ProbeResult> {
+        let probe_buf = read_probe_buffer(io)?;
+        let ProbeResult { format_id, score } = self
+            .probe_scores_for(&probe_buf, filename, media_type)
+            .into_iter()
+            .next()
+            .ok_or_else(|| TaoError::FormatNotFound("无法识别输入文件格式".to_string()))?;
+        if score < SCORE_MIN {
+            return Err(TaoError::Unsupported(format!(
+                "无法识别输入文件格式: 最可能为 {format_id}, 但置信度 {score} 低于 {SCORE_MIN}"
+            )));
+        }
+        Ok(ProbeResult { format_id, score })
+    }
+
+    /// 探测输入文件, 按置信度从高到低返回所有可能的格式
+    ///
+    /// 读取文件头部数据后 seek 回起始位置. 没有任何探测器命中时返回空列表.
+    pub fn probe_all(
+        &self,
+        io: &mut IoContext,
+        filename: Option<&str>,
+    ) -> TaoResult<Vec<(FormatId, ProbeScore)>> {
+        let probe_buf = read_probe_buffer(io)?;
+        Ok(self
+            .probe_scores(&probe_buf, filename)
+            .into_iter()
+            .map(|r| (r.format_id, r.score))
+            .collect())
     }
 
     /// 根据文件自动探测格式并创建解封装器
@@ -175,14 +245,143 @@ impl FormatRegistry {
         filename: Option<&str>,
     ) -> TaoResult<Box<dyn Demuxer>> {
         let result = self.probe_input(io, filename)?;
-        let mut demuxer = self.create_demuxer(result.format_id)?;
+        self.open_input_as(io, result.format_id)
+    }
+
+    /// 以指定格式打开输入, 跳过探测
+    pub fn open_input_as(
+        &self,
+        io: &mut IoContext,
+        format_id: FormatId,
+    ) -> TaoResult<Box<dyn Demuxer>> {
+        let mut demuxer = self.create_demuxer(format_id)?;
         demuxer.open(io)?;
         Ok(demuxer)
     }
 }
 
+/// 读取用于探测的文件头部数据, 之后 seek 回起始位置
+fn read_probe_buffer(io: &mut IoContext) -> TaoResult<Vec<u8>> {
+    // 对含大 ID3v2/APIC 的 MP3 样本, 8KB 头部不足以完成可靠探测.
+    // 将探测窗口提升到 256KB, 以降低误判为 TS 等格式的概率.
+    let probe_size = io.size().unwrap_or(262_144).min(262_144) as usize;
+    let probe_size = probe_size.max(12); // 至少读取 12 字节
+    let probe_buf = io.read_bytes(probe_size)?;
+
+    // seek 回起始位置, 供后续 demuxer 读取
+    io.seek(std::io::SeekFrom::Start(0))?;
+    Ok(probe_buf)
+}
+
 impl Default for FormatRegistry {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 对任意数据返回固定分数的探测器
+    struct FixedProbe(FormatId, ProbeScore);
+
+    impl FormatProbe for FixedProbe {
+        fn probe(&self, _data: &[u8], _filename: Option<&str>) -> Option<ProbeScore> {
+            Some(self.1)
+        }
+
+        fn format_id(&self) -> FormatId {
+            self.0
+        }
+    }
+
+    fn registry_with(probes: &[(FormatId, ProbeScore)]) -> FormatRegistry {
+        let mut registry = FormatRegistry::new();
+        for &(format_id, score) in probes {
+            registry.register_probe(Box::new(FixedProbe(format_id, score)));
+        }
+        registry
+    }
+
+    #[test]
+    fn test_probe_all_sorted_by_score() {
+        let registry = registry_with(&[
+            (FormatId::Wav, 50),
+            (FormatId::Mp3Container, 95),
+            (FormatId::MpegTs, 50),
+        ]);
+        let mut io = IoContext::from_bytes(vec![0; 64]);
+        let results = registry.probe_all(&mut io, None).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                (FormatId::Mp3Container, 95),
+                (FormatId::Wav, 50),
+                (FormatId::MpegTs, 50),
+            ],
+            "应按分数降序排列, 同分保持注册顺序"
+        );
+        assert_eq!(io.position().unwrap(), 0, "探测后应回到起始位置");
+    }
+
+    #[test]
+    fn test_probe_input_rejects_low_score() {
+        let mut io = IoContext::from_bytes(vec![0; 64]);
+        let registry = registry_with(&[(FormatId::Wav, SCORE_MIN - 1)]);
+        assert!(
+            matches!(
+                registry.probe_input(&mut io, None),
+                Err(TaoError::Unsupported(_))
+            ),
+            "低于阈值的最高分应视为无法识别"
+        );
+
+        let registry = registry_with(&[(FormatId::Wav, SCORE_MIN)]);
+        let result = registry.probe_input(&mut io, None).unwrap();
+        assert_eq!(result.format_id, FormatId::Wav);
+        assert_eq!(result.score, SCORE_MIN);
+
+        let registry = FormatRegistry::new();
+        assert!(
+            matches!(
+                registry.probe_input(&mut io, None),
+                Err(TaoError::FormatNotFound(_))
+            ),
+            "无探测器命中时应返回 FormatNotFound"
+        );
+    }
+
+    #[test]
+    fn test_probe_input_media_type_filter() {
+        let registry = registry_with(&[
+            (FormatId::H264Es, 100),
+            (FormatId::Matroska, 80),
+            (FormatId::Mp3Container, 80),
+        ]);
+        let mut io = IoContext::from_bytes(vec![0; 64]);
+
+        let result = registry.probe_input(&mut io, None).unwrap();
+        assert_eq!(result.format_id, FormatId::H264Es, "不限类型时取最高分");
+
+        let result = registry.probe_input_audio_only(&mut io, None).unwrap();
+        assert_eq!(
+            result.format_id,
+            FormatId::Mp3Container,
+            "仅音频时应跳过纯视频格式, 同分优先纯音频格式"
+        );
+        assert_eq!(io.position().unwrap(), 0, "探测后应回到起始位置");
+
+        let result = registry.probe_input_video_only(&mut io, None).unwrap();
+        assert_eq!(result.format_id, FormatId::H264Es);
+
+        let registry = registry_with(&[(FormatId::Mp3Container, 100)]);
+        assert!(
+            matches!(
+                registry.probe_input_video_only(&mut io, None),
+                Err(TaoError::FormatNotFound(_))
+            ),
+            "仅视频时纯音频格式不应参与探测"
+        );
+    }
+}
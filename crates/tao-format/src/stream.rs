@@ -2,8 +2,10 @@
 //!
 //! 对标 FFmpeg 的 `AVStream`, 描述容器中的一条音视频/字幕流.
 
-use tao_codec::CodecId;
-use tao_core::{ChannelLayout, MediaType, PixelFormat, Rational, SampleFormat};
+use tao_codec::codec_parameters::{AudioCodecParams, CodecParamsType, VideoCodecParams};
+use tao_codec::{CodecId, CodecParameters, CodecRegistry, Decoder};
+use tao_core::color::{ColorRange, ColorSpace};
+use tao_core::{ChannelLayout, MediaType, PixelFormat, Rational, SampleFormat, TaoResult};
 
 /// 流信息
 ///
@@ -32,6 +34,50 @@ pub struct Stream {
     pub metadata: Vec<(String, String)>,
 }
 
+impl Stream {
+    /// 由流信息构建解码器参数 (对标 FFmpeg `avcodec_parameters_to_context`)
+    ///
+    /// 音视频流参数逐项映射并复制 extra_data; 字幕及其他流不携带特定参数.
+    pub fn codec_parameters(&self) -> CodecParameters {
+        let (bit_rate, params) = match &self.params {
+            StreamParams::Audio(a) => (
+                a.bit_rate,
+                CodecParamsType::Audio(AudioCodecParams {
+                    sample_rate: a.sample_rate,
+                    channel_layout: a.channel_layout,
+                    sample_format: a.sample_format,
+                    frame_size: a.frame_size,
+                    block_align: a.block_align,
+                }),
+            ),
+            StreamParams::Video(v) => (
+                v.bit_rate,
+                CodecParamsType::Video(VideoCodecParams {
+                    width: v.width,
+                    height: v.height,
+                    pixel_format: v.pixel_format,
+                    frame_rate: v.frame_rate,
+                    sample_aspect_ratio: v.sample_aspect_ratio,
+                }),
+            ),
+            StreamParams::Subtitle | StreamParams::Other => (0, CodecParamsType::None),
+        };
+        CodecParameters {
+            codec_id: self.codec_id,
+            extra_data: self.extra_data.clone(),
+            bit_rate,
+            params,
+        }
+    }
+
+    /// 从注册表创建该流的解码器, 并以 [`Stream::codec_parameters`] 打开
+    pub fn open_decoder(&self, registry: &CodecRegistry) -> TaoResult<Box<dyn Decoder>> {
+        let mut decoder = registry.create_decoder(self.codec_id)?;
+        decoder.open(&self.codec_parameters())?;
+        Ok(decoder)
+    }
+}
+
 /// 流特定参数
 #[derive(Debug, Clone)]
 pub enum StreamParams {
@@ -60,6 +106,10 @@ pub struct VideoStreamParams {
     pub sample_aspect_ratio: Rational,
     /// 码率 (bps, 0 表示未知)
     pub bit_rate: u64,
+    /// 色彩空间 (YCbCr 矩阵系数)
+    pub color_space: ColorSpace,
+    /// 色彩范围
+    pub color_range: ColorRange,
 }
 
 /// 音频流参数
@@ -75,4 +125,113 @@ pub struct AudioStreamParams {
     pub bit_rate: u64,
     /// 每帧采样数 (如 AAC 为 1024, MP3 为 1152)
     pub frame_size: u32,
+    /// 每个编码块的字节数 (容器中的 block_align, 0 表示未知)
+    pub block_align: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_stream(codec_id: CodecId, params: StreamParams) -> Stream {
+        Stream {
+            index: 0,
+            media_type: MediaType::Audio,
+            codec_id,
+            time_base: Rational::new(1, 44100),
+            duration: -1,
+            start_time: 0,
+            nb_frames: 0,
+            extra_data: vec![0x12, 0x10],
+            params,
+            metadata: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_stream_codec_parameters_maps_audio_and_video() {
+        let audio = make_stream(
+            CodecId::Aac,
+            StreamParams::Audio(AudioStreamParams {
+                sample_rate: 44100,
+                channel_layout: ChannelLayout::STEREO,
+                sample_format: SampleFormat::F32p,
+                bit_rate: 128_000,
+                frame_size: 1024,
+                block_align: 0,
+            }),
+        );
+        let params = audio.codec_parameters();
+        assert_eq!(params.codec_id, CodecId::Aac);
+        assert_eq!(params.extra_data, vec![0x12, 0x10], "应复制 extra_data");
+        assert_eq!(params.bit_rate, 128_000);
+        let CodecParamsType::Audio(a) = params.params else {
+            panic!("音频流应映射为音频参数");
+        };
+        assert_eq!(
+            (
+                a.sample_rate,
+                a.channel_layout,
+                a.sample_format,
+                a.frame_size
+            ),
+            (44100, ChannelLayout::STEREO, SampleFormat::F32p, 1024)
+        );
+
+        let mut video = make_stream(
+            CodecId::H264,
+            StreamParams::Video(VideoStreamParams {
+                width: 720,
+                height: 480,
+                pixel_format: PixelFormat::Yuv420p,
+                frame_rate: Rational::new(30000, 1001),
+                sample_aspect_ratio: Rational::new(32, 27),
+                bit_rate: 2_000_000,
+                color_space: ColorSpace::Unspecified,
+                color_range: ColorRange::Unspecified,
+            }),
+        );
+        video.media_type = MediaType::Video;
+        let params = video.codec_parameters();
+        assert_eq!(params.bit_rate, 2_000_000);
+        let CodecParamsType::Video(v) = params.params else {
+            panic!("视频流应映射为视频参数");
+        };
+        assert_eq!((v.width, v.height), (720, 480));
+        assert_eq!(v.pixel_format, PixelFormat::Yuv420p);
+        assert_eq!(v.frame_rate, Rational::new(30000, 1001));
+        assert_eq!(v.sample_aspect_ratio, Rational::new(32, 27));
+
+        let subtitle = make_stream(CodecId::Srt, StreamParams::Subtitle);
+        assert!(
+            matches!(subtitle.codec_parameters().params, CodecParamsType::None),
+            "字幕流不应携带音视频参数"
+        );
+    }
+
+    #[test]
+    fn test_stream_open_decoder() {
+        let mut registry = CodecRegistry::new();
+        tao_codec::register_all(&mut registry);
+
+        let stream = make_stream(
+            CodecId::PcmS16le,
+            StreamParams::Audio(AudioStreamParams {
+                sample_rate: 44100,
+                channel_layout: ChannelLayout::STEREO,
+                sample_format: SampleFormat::S16,
+                bit_rate: 0,
+                frame_size: 0,
+                block_align: 0,
+            }),
+        );
+        let decoder = stream.open_decoder(&registry).expect("应能打开 PCM 解码器");
+        assert_eq!(decoder.codec_id(), CodecId::PcmS16le);
+
+        let unknown = make_stream(CodecId::None, StreamParams::Other);
+        assert!(
+            unknown.open_decoder(&registry).is_err(),
+            "未注册的编解码器应返回错误"
+        );
+    }
 }
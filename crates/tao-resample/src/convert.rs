@@ -120,6 +120,10 @@ fn decode_sample(data: &[u8], format: SampleFormat) -> TaoResult<f64> {
             let v = i16::from_le_bytes([data[0], data[1]]);
             Ok(v as f64 / 32768.0)
         }
+        SampleFormat::S24 => {
+            let v = i32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+            Ok(v as f64 / 8_388_608.0)
+        }
         SampleFormat::S32 => {
             let v = i32::from_le_bytes([data[0], data[1], data[2], data[3]]);
             Ok(v as f64 / 2_147_483_648.0)
@@ -150,6 +154,12 @@ fn encode_sample(value: f64, format: SampleFormat, output: &mut Vec<u8>) -> TaoR
             let v = (value * 32768.0).round().clamp(-32768.0, 32767.0) as i16;
             output.extend_from_slice(&v.to_le_bytes());
         }
+        SampleFormat::S24 => {
+            let v = (value * 8_388_608.0)
+                .round()
+                .clamp(-8_388_608.0, 8_388_607.0) as i32;
+            output.extend_from_slice(&v.to_le_bytes());
+        }
         SampleFormat::S32 => {
             let v = (value * 2_147_483_648.0)
                 .round()
@@ -191,6 +201,16 @@ mod tests {
         assert_eq!(v, 32767); // 1.0 * 32768 = 32768, clamped to 32767
     }
 
+    #[test]
+    fn test_convert_s16_to_s24() {
+        // S16 -16384 (-0.5) -> S24 -4194304
+        let input = (-16384i16).to_le_bytes().to_vec();
+        let result = convert_samples(&input, SampleFormat::S16, SampleFormat::S24, 1, 1).unwrap();
+        assert_eq!(result.len(), 4);
+        let v = i32::from_le_bytes([result[0], result[1], result[2], result[3]]);
+        assert_eq!(v, -4_194_304);
+    }
+
     #[test]
     fn test_convert_u8_to_s16() {
         // U8 128 = 0.0 -> S16 0
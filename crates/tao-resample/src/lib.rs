@@ -10,6 +10,7 @@
 mod convert;
 mod multichannel;
 
+use tao_codec::frame::AudioFrame;
 use tao_core::{ChannelLayout, SampleFormat, TaoError, TaoResult};
 
 pub use convert::{convert_samples, mix_channels};
@@ -127,6 +128,61 @@ impl ResampleContext {
 
         Ok((data, nb))
     }
+
+    /// 对音频帧执行重采样
+    ///
+    /// 从帧中读取采样数据并调用 [`ResampleContext::convert`], 返回按目标参数
+    /// 构造的新帧, 沿用源帧的 `pts` 与 `time_base`.
+    /// 重采样不改变帧覆盖的时间跨度: 源帧已标注时长时直接沿用,
+    /// 否则按输出采样数换算到源时间基.
+    ///
+    /// 帧的采样率/采样格式/声道数须与上下文的源参数一致, 且为交错格式.
+    pub fn convert_frame(&self, frame: &AudioFrame) -> TaoResult<AudioFrame> {
+        if frame.sample_format.is_planar() {
+            return Err(TaoError::Unsupported(format!(
+                "重采样仅支持交错格式, 实际为 {}",
+                frame.sample_format
+            )));
+        }
+        if frame.sample_rate != self.src_sample_rate
+            || frame.sample_format != self.src_sample_format
+            || frame.channel_layout.channels != self.src_channel_layout.channels
+        {
+            return Err(TaoError::InvalidArgument(format!(
+                "音频帧参数 ({}Hz, {}, {} 声道) 与重采样源参数 ({}Hz, {}, {} 声道) 不一致",
+                frame.sample_rate,
+                frame.sample_format,
+                frame.channel_layout.channels,
+                self.src_sample_rate,
+                self.src_sample_format,
+                self.src_channel_layout.channels,
+            )));
+        }
+        let input = frame
+            .data
+            .first()
+            .ok_or_else(|| TaoError::InvalidArgument("音频帧没有采样数据".into()))?;
+
+        let (output, nb_out) = self.convert(input, frame.nb_samples)?;
+
+        let mut out = AudioFrame::new(
+            nb_out,
+            self.dst_sample_rate,
+            self.dst_sample_format,
+            self.dst_channel_layout,
+        );
+        out.data[0] = output.into();
+        out.pts = frame.pts;
+        out.time_base = frame.time_base;
+        out.duration = if frame.duration > 0 {
+            frame.duration
+        } else if frame.time_base.is_valid() {
+            (out.duration_seconds() / frame.time_base.to_f64()).round() as i64
+        } else {
+            nb_out as i64
+        };
+        Ok(out)
+    }
 }
 
 /// 线性插值重采样
@@ -186,6 +242,15 @@ fn bytes_to_f64(data: &[u8], format: SampleFormat, total_samples: usize) -> TaoR
                 let v = i16::from_le_bytes([data[offset], data[offset + 1]]);
                 v as f64 / 32768.0
             }
+            SampleFormat::S24 => {
+                let v = i32::from_le_bytes([
+                    data[offset],
+                    data[offset + 1],
+                    data[offset + 2],
+                    data[offset + 3],
+                ]);
+                v as f64 / 8_388_608.0
+            }
             SampleFormat::S32 => {
                 let v = i32::from_le_bytes([
                     data[offset],
@@ -238,6 +303,10 @@ fn f64_to_bytes(samples: &[f64], format: SampleFormat) -> TaoResult<Vec<u8>> {
                 let v = (s * 32768.0).round().clamp(-32768.0, 32767.0) as i16;
                 result.extend_from_slice(&v.to_le_bytes());
             }
+            SampleFormat::S24 => {
+                let v = (s * 8_388_608.0).round().clamp(-8_388_608.0, 8_388_607.0) as i32;
+                result.extend_from_slice(&v.to_le_bytes());
+            }
             SampleFormat::S32 => {
                 let v = (s * 2_147_483_648.0)
                     .round()
@@ -262,6 +331,58 @@ fn f64_to_bytes(samples: &[f64], format: SampleFormat) -> TaoResult<Vec<u8>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tao_core::Rational;
+
+    #[test]
+    fn test_convert_frame_sets_dst_params() {
+        let ctx = ResampleContext::new(
+            44100,
+            SampleFormat::S16,
+            ChannelLayout::STEREO,
+            48000,
+            SampleFormat::F32,
+            ChannelLayout::MONO,
+        );
+        let mut af = AudioFrame::new(441, 44100, SampleFormat::S16, ChannelLayout::STEREO);
+        af.data[0] = vec![0u8; 441 * 2 * 2].into();
+        af.pts = 882;
+        af.time_base = Rational::new(1, 44100);
+
+        let out = ctx.convert_frame(&af).unwrap();
+        assert_eq!(out.sample_rate, 48000);
+        assert_eq!(out.sample_format, SampleFormat::F32);
+        assert_eq!(out.channel_layout, ChannelLayout::MONO);
+        assert_eq!(out.nb_samples, 480);
+        assert_eq!(out.data[0].len(), 480 * 4);
+        assert_eq!(out.pts, 882);
+        assert_eq!(out.time_base, Rational::new(1, 44100));
+        assert_eq!(out.duration, 441, "未标注时长时应按输出时长换算到源时间基");
+
+        af.duration = 400;
+        assert_eq!(ctx.convert_frame(&af).unwrap().duration, 400);
+    }
+
+    #[test]
+    fn test_convert_frame_rejects_mismatched_frame() {
+        let ctx = ResampleContext::new(
+            44100,
+            SampleFormat::S16,
+            ChannelLayout::STEREO,
+            48000,
+            SampleFormat::S16,
+            ChannelLayout::STEREO,
+        );
+        let af = AudioFrame::new(16, 48000, SampleFormat::S16, ChannelLayout::STEREO);
+        assert!(matches!(
+            ctx.convert_frame(&af),
+            Err(TaoError::InvalidArgument(_))
+        ));
+        let af = AudioFrame::new(16, 44100, SampleFormat::S16p, ChannelLayout::STEREO);
+        assert!(matches!(
+            ctx.convert_frame(&af),
+            Err(TaoError::Unsupported(_))
+        ));
+    }
 
     #[test]
     fn test_no_conversion_needed() {
@@ -0,0 +1,125 @@
+//! tao-scale 像素格式转换与缩放性能基准测试.
+//!
+//! 所有缓冲区在迭代前一次性分配, 迭代中仅执行转换/缩放本身.
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use tao_core::PixelFormat;
+use tao_scale::{ScaleAlgorithm, ScaleContext};
+
+const SRC_W: u32 = 1920;
+const SRC_H: u32 = 1080;
+const DST_W: u32 = 1280;
+const DST_H: u32 = 720;
+
+/// 按格式分配的图像平面缓冲
+struct Image {
+    planes: Vec<Vec<u8>>,
+    linesize: Vec<usize>,
+}
+
+impl Image {
+    /// 分配图像并以渐变图案填充, 避免全常数输入触发特殊路径
+    fn new(width: u32, height: u32, format: PixelFormat) -> Self {
+        let mut planes = Vec::new();
+        let mut linesize = Vec::new();
+        for i in 0..format.plane_count() as usize {
+            let stride = format.plane_linesize(i, width).unwrap();
+            let rows = format.plane_height(i, height).unwrap();
+            planes.push(
+                (0..stride * rows)
+                    .map(|j| ((j % stride + j / stride + i * 64) % 256) as u8)
+                    .collect(),
+            );
+            linesize.push(stride);
+        }
+        Self { planes, linesize }
+    }
+
+    fn planes(&self) -> Vec<&[u8]> {
+        self.planes.iter().map(Vec::as_slice).collect()
+    }
+}
+
+/// 注册一个基准: 源/目标缓冲与上下文只创建一次
+fn bench_scale(
+    c: &mut Criterion,
+    name: &str,
+    src_format: PixelFormat,
+    (dst_w, dst_h): (u32, u32),
+    dst_format: PixelFormat,
+    algorithm: ScaleAlgorithm,
+) {
+    let src = Image::new(SRC_W, SRC_H, src_format);
+    let mut dst = Image::new(dst_w, dst_h, dst_format);
+    let ctx = ScaleContext::new(
+        SRC_W, SRC_H, src_format, dst_w, dst_h, dst_format, algorithm,
+    );
+    let src_planes = src.planes();
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            let mut dst_planes: Vec<&mut [u8]> =
+                dst.planes.iter_mut().map(Vec::as_mut_slice).collect();
+            ctx.scale(
+                black_box(&src_planes),
+                &src.linesize,
+                &mut dst_planes,
+                &dst.linesize,
+            )
+            .unwrap();
+            black_box(&dst_planes);
+        });
+    });
+}
+
+fn bench_yuv420p_to_rgb24(c: &mut Criterion) {
+    bench_scale(
+        c,
+        "yuv420p_to_rgb24_1920x1080_bilinear",
+        PixelFormat::Yuv420p,
+        (SRC_W, SRC_H),
+        PixelFormat::Rgb24,
+        ScaleAlgorithm::Bilinear,
+    );
+}
+
+fn bench_rgb24_to_yuv420p(c: &mut Criterion) {
+    bench_scale(
+        c,
+        "rgb24_to_yuv420p_1920x1080",
+        PixelFormat::Rgb24,
+        (SRC_W, SRC_H),
+        PixelFormat::Yuv420p,
+        ScaleAlgorithm::Bilinear,
+    );
+}
+
+fn bench_scale_bilinear(c: &mut Criterion) {
+    bench_scale(
+        c,
+        "scale_1920x1080_to_1280x720_bilinear_yuv420p",
+        PixelFormat::Yuv420p,
+        (DST_W, DST_H),
+        PixelFormat::Yuv420p,
+        ScaleAlgorithm::Bilinear,
+    );
+}
+
+fn bench_scale_nearest(c: &mut Criterion) {
+    bench_scale(
+        c,
+        "scale_1920x1080_to_1280x720_nearest_yuv420p",
+        PixelFormat::Yuv420p,
+        (DST_W, DST_H),
+        PixelFormat::Yuv420p,
+        ScaleAlgorithm::NearestNeighbor,
+    );
+}
+
+criterion_group!(
+    benches,
+    bench_yuv420p_to_rgb24,
+    bench_rgb24_to_yuv420p,
+    bench_scale_bilinear,
+    bench_scale_nearest,
+);
+criterion_main!(benches);
@@ -3,22 +3,33 @@
 //! 提供各种像素格式之间的转换功能, 对标 FFmpeg libswscale 的格式转换部分.
 //!
 //! 支持的转换路径:
-//! - RGB24 ↔ YUV420P (BT.601)
+//! - RGB24 ↔ YUV420P
 //! - RGB24 ↔ Gray8
 //! - RGBA → RGB24 / RGB24 → RGBA
 //! - BGR24 ↔ RGB24
 //! - NV12 ↔ YUV420P
-//! - RGB24 → YUV444P
+//! - RGB24 ↔ YUV444P
+//! - YUV422P / YUV444P → YUV420P (色度平均下采样)
 //!
-//! 使用 BT.601 标准色彩矩阵:
+//! RGB ↔ YUV 按 YUV 一侧的色彩空间和色彩范围选择矩阵 (BT.601 / BT.709 / BT.2020,
+//! 有限范围 / 完整范围). 未指定时按分辨率推断: 高度 >= 720 为 BT.709, 否则为 BT.601,
+//! 范围默认为有限范围. 以 BT.601 为例:
 //! ```text
 //! Y  =  0.299 * R + 0.587 * G + 0.114 * B
 //! Cb = -0.169 * R - 0.331 * G + 0.500 * B + 128
 //! Cr =  0.500 * R - 0.419 * G - 0.081 * B + 128
 //! ```
+//! 有限范围下 Y 再压缩到 16-235, Cb/Cr 压缩到 16-240.
+//!
+//! YUV → RGB24 按行转换, x86 上运行时分派到 AVX2 / SSE4.1 实现.
+
+mod simd;
 
+use tao_core::color::{ColorRange, ColorSpace};
 use tao_core::{PixelFormat, TaoError, TaoResult};
 
+use self::simd::{RowKernel, yuv_to_rgb_row};
+
 /// 像素格式转换输入 (各平面数据切片)
 pub struct ConvertInput<'a> {
     /// 各平面数据
@@ -31,6 +42,10 @@ pub struct ConvertInput<'a> {
     pub height: u32,
     /// 像素格式
     pub format: PixelFormat,
+    /// 色彩空间 (仅 YUV 格式使用)
+    pub color_space: ColorSpace,
+    /// 色彩范围 (仅 YUV 格式使用)
+    pub color_range: ColorRange,
 }
 
 /// 像素格式转换输出 (各平面可变数据)
@@ -45,6 +60,10 @@ pub struct ConvertOutput<'a> {
     pub height: u32,
     /// 像素格式
     pub format: PixelFormat,
+    /// 色彩空间 (仅 YUV 格式使用)
+    pub color_space: ColorSpace,
+    /// 色彩范围 (仅 YUV 格式使用)
+    pub color_range: ColorRange,
 }
 
 /// 检查给定的格式转换是否支持
@@ -63,6 +82,8 @@ pub fn is_conversion_supported(src: PixelFormat, dst: PixelFormat) -> bool {
             | (PixelFormat::Yuv420p, PixelFormat::Nv12)
             | (PixelFormat::Rgb24, PixelFormat::Yuv444p)
             | (PixelFormat::Yuv444p, PixelFormat::Rgb24)
+            | (PixelFormat::Yuv422p, PixelFormat::Yuv420p)
+            | (PixelFormat::Yuv444p, PixelFormat::Yuv420p)
     )
 }
 
@@ -87,6 +108,8 @@ pub fn convert(src: &ConvertInput, dst: &mut ConvertOutput) -> TaoResult<()> {
         (PixelFormat::Yuv420p, PixelFormat::Nv12) => yuv420p_to_nv12(src, dst),
         (PixelFormat::Rgb24, PixelFormat::Yuv444p) => rgb24_to_yuv444p(src, dst),
         (PixelFormat::Yuv444p, PixelFormat::Rgb24) => yuv444p_to_rgb24(src, dst),
+        (PixelFormat::Yuv422p, PixelFormat::Yuv420p) => yuv_planar_to_yuv420p(src, dst, 1, 0),
+        (PixelFormat::Yuv444p, PixelFormat::Yuv420p) => yuv_planar_to_yuv420p(src, dst, 0, 0),
         _ => Err(TaoError::Unsupported(format!(
             "不支持的格式转换: {} → {}",
             src.format, dst.format,
@@ -95,48 +118,118 @@ pub fn convert(src: &ConvertInput, dst: &mut ConvertOutput) -> TaoResult<()> {
 }
 
 // ============================================================
-// BT.601 颜色空间转换常量 (定点数, 缩放 256 倍)
+// 颜色空间转换系数 (定点数, 缩放 256 倍)
 // ============================================================
 
-/// Y = 0.299*R + 0.587*G + 0.114*B
+/// 灰度亮度: Y = 0.299*R + 0.587*G + 0.114*B (BT.601, 完整范围)
 const Y_R: i32 = 77; // 0.299 * 256
 const Y_G: i32 = 150; // 0.587 * 256
 const Y_B: i32 = 29; // 0.114 * 256
 
-/// Cb = -0.169*R - 0.331*G + 0.500*B + 128
-const CB_R: i32 = -43; // -0.169 * 256
-const CB_G: i32 = -85; // -0.331 * 256
-const CB_B: i32 = 128; // 0.500 * 256
+/// YCbCr ↔ RGB 定点系数
+///
+/// 由色彩空间的 Kr/Kb 与色彩范围推导. BT.601 有限范围的逆变换系数即经典的
+/// 298/409/100/208/516.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct YuvCoefficients {
+    y_r: i32,
+    y_g: i32,
+    y_b: i32,
+    cb_r: i32,
+    cb_g: i32,
+    cb_b: i32,
+    cr_r: i32,
+    cr_g: i32,
+    cr_b: i32,
+    /// Y 偏移 (有限范围为 16)
+    y_offset: i32,
+    /// 逆变换: Y 扩展系数
+    y_scale: i32,
+    r_v: i32,
+    g_u: i32,
+    g_v: i32,
+    b_u: i32,
+}
 
-/// Cr = 0.500*R - 0.419*G - 0.081*B + 128
-const CR_R: i32 = 128; // 0.500 * 256
-const CR_G: i32 = -107; // -0.419 * 256
-const CR_B: i32 = -21; // -0.081 * 256
+impl YuvCoefficients {
+    /// 按色彩空间/范围计算系数, 未指定时按 `height` 推断
+    fn new(space: ColorSpace, range: ColorRange, height: u32) -> Self {
+        let (kr, kb) = match space.resolve(height) {
+            ColorSpace::Bt709 => (0.2126, 0.0722),
+            ColorSpace::Bt2020Ncl | ColorSpace::Bt2020Cl => (0.2627, 0.0593),
+            ColorSpace::Smpte240m => (0.212, 0.087),
+            _ => (0.299, 0.114),
+        };
+        let kg = 1.0 - kr - kb;
+        let (y_range, c_range, y_offset) = match range.resolve() {
+            ColorRange::Full => (1.0, 1.0, 0),
+            _ => (219.0 / 255.0, 224.0 / 255.0, 16),
+        };
+        let fix = |v: f64| (v * 256.0).round() as i32;
+        let cb_div = 2.0 * (1.0 - kb);
+        let cr_div = 2.0 * (1.0 - kr);
+
+        Self {
+            y_r: fix(kr * y_range),
+            y_g: fix(kg * y_range),
+            y_b: fix(kb * y_range),
+            cb_r: fix(-kr / cb_div * c_range),
+            cb_g: fix(-kg / cb_div * c_range),
+            cb_b: fix(0.5 * c_range),
+            cr_r: fix(0.5 * c_range),
+            cr_g: fix(-kg / cr_div * c_range),
+            cr_b: fix(-kb / cr_div * c_range),
+            y_offset,
+            y_scale: fix(1.0 / y_range),
+            r_v: fix(cr_div / c_range),
+            g_u: fix(cb_div * kb / kg / c_range),
+            g_v: fix(cr_div * kr / kg / c_range),
+            b_u: fix(cb_div / c_range),
+        }
+    }
+
+    /// 源为 YUV 时使用其色彩参数
+    fn for_input(src: &ConvertInput) -> Self {
+        Self::new(src.color_space, src.color_range, src.height)
+    }
+
+    /// 目标为 YUV 时使用其色彩参数
+    fn for_output(dst: &ConvertOutput) -> Self {
+        Self::new(dst.color_space, dst.color_range, dst.height)
+    }
+
+    #[inline(always)]
+    fn luma(&self, r: i32, g: i32, b: i32) -> u8 {
+        (((self.y_r * r + self.y_g * g + self.y_b * b + 128) >> 8) + self.y_offset).clamp(0, 255)
+            as u8
+    }
+
+    #[inline(always)]
+    fn chroma(&self, r: i32, g: i32, b: i32) -> (u8, u8) {
+        let cb = ((self.cb_r * r + self.cb_g * g + self.cb_b * b + 128) >> 8) + 128;
+        let cr = ((self.cr_r * r + self.cr_g * g + self.cr_b * b + 128) >> 8) + 128;
+        (cb.clamp(0, 255) as u8, cr.clamp(0, 255) as u8)
+    }
+
+    #[inline(always)]
+    fn yuv_to_rgb(&self, y: i32, u: i32, v: i32) -> (u8, u8, u8) {
+        let c = self.y_scale * (y - self.y_offset);
+        let d = u - 128;
+        let e = v - 128;
+        let r = ((c + self.r_v * e + 128) >> 8).clamp(0, 255) as u8;
+        let g = ((c - self.g_u * d - self.g_v * e + 128) >> 8).clamp(0, 255) as u8;
+        let b = ((c + self.b_u * d + 128) >> 8).clamp(0, 255) as u8;
+        (r, g, b)
+    }
+}
 
 // ============================================================
 // RGB24 ↔ YUV420P
 // ============================================================
 
-/// SIMD 友好的批量 YUV->RGB 转换 (每次 4 像素)
-/// 使用数组以启用编译器自动向量化
-#[inline(always)]
-fn yuv_to_rgb_batch4(y: [i32; 4], u: i32, v: i32) -> [(u8, u8, u8); 4] {
-    let c = [y[0] - 16, y[1] - 16, y[2] - 16, y[3] - 16];
-    let d = u - 128;
-    let e = v - 128;
-
-    let mut result = [(0u8, 0u8, 0u8); 4];
-    for i in 0..4 {
-        let r = ((298 * c[i] + 409 * e + 128) >> 8).clamp(0, 255) as u8;
-        let g = ((298 * c[i] - 100 * d - 208 * e + 128) >> 8).clamp(0, 255) as u8;
-        let b = ((298 * c[i] + 516 * d + 128) >> 8).clamp(0, 255) as u8;
-        result[i] = (r, g, b);
-    }
-    result
-}
-
-/// RGB24 → YUV420P (BT.601, 2x2 块色度平均)
+/// RGB24 → YUV420P (2x2 块色度平均)
 fn rgb24_to_yuv420p(src: &ConvertInput, dst: &mut ConvertOutput) -> TaoResult<()> {
+    let coef = YuvCoefficients::for_output(dst);
     let w = src.width as usize;
     let h = src.height as usize;
     let src_stride = src.linesize[0];
@@ -160,8 +253,7 @@ fn rgb24_to_yuv420p(src: &ConvertInput, dst: &mut ConvertOutput) -> TaoResult<()
             let r = i32::from(rgb[src_off]);
             let g = i32::from(rgb[src_off + 1]);
             let b = i32::from(rgb[src_off + 2]);
-            let y = ((Y_R * r + Y_G * g + Y_B * b + 128) >> 8).clamp(0, 255);
-            y_data[row * dst_y_stride + col] = y as u8;
+            y_data[row * dst_y_stride + col] = coef.luma(r, g, b);
         }
     }
 
@@ -193,88 +285,41 @@ fn rgb24_to_yuv420p(src: &ConvertInput, dst: &mut ConvertOutput) -> TaoResult<()
             let avg_g = sum_g / count;
             let avg_b = sum_b / count;
 
-            let cb = ((CB_R * avg_r + CB_G * avg_g + CB_B * avg_b + 128) >> 8) + 128;
-            let cr = ((CR_R * avg_r + CR_G * avg_g + CR_B * avg_b + 128) >> 8) + 128;
-
-            u_data[cy * dst_u_stride + cx] = cb.clamp(0, 255) as u8;
-            v_data[cy * dst_v_stride + cx] = cr.clamp(0, 255) as u8;
+            let (cb, cr) = coef.chroma(avg_r, avg_g, avg_b);
+            u_data[cy * dst_u_stride + cx] = cb;
+            v_data[cy * dst_v_stride + cx] = cr;
         }
     }
 
     Ok(())
 }
 
-/// YUV420P → RGB24 (BT.601)
+/// YUV420P → RGB24
 ///
-/// 使用 batch4 优化路径处理 4 像素对齐的列, 剩余像素使用标量回退.
+/// 色度按最近邻上采样, 每 2x2 像素共享一个色度样本.
 fn yuv420p_to_rgb24(src: &ConvertInput, dst: &mut ConvertOutput) -> TaoResult<()> {
+    let coef = YuvCoefficients::for_input(src);
+    let kernel = RowKernel::detect();
     let w = src.width as usize;
     let h = src.height as usize;
-
-    let y_data = src.planes[0];
-    let u_data = src.planes[1];
-    let v_data = src.planes[2];
-    let y_stride = src.linesize[0];
-    let u_stride = src.linesize[1];
-    let v_stride = src.linesize[2];
+    let chroma_w = w.div_ceil(2);
 
     let dst_stride = dst.linesize[0];
     let rgb = &mut dst.planes[0];
 
-    let aligned_end = (w / 4) * 4;
-
     for row in 0..h {
-        let uv_row = row / 2;
-        let u_val = i32::from(u_data[uv_row * u_stride]);
-        let v_val = i32::from(v_data[uv_row * v_stride]);
-
-        let dst_row = row * dst_stride;
-        let y_row = row * y_stride;
-
-        // 批量处理 4 像素对齐的列
-        for col in (0..aligned_end).step_by(4) {
-            let y0 = i32::from(y_data[y_row + col]);
-            let y1 = i32::from(y_data[y_row + col + 1]);
-            let y2 = i32::from(y_data[y_row + col + 2]);
-            let y3 = i32::from(y_data[y_row + col + 3]);
-
-            let u = if col + 2 < w {
-                i32::from(u_data[uv_row * u_stride + (col + 2) / 2])
-            } else {
-                u_val
-            };
-            let v = if col + 2 < w {
-                i32::from(v_data[uv_row * v_stride + (col + 2) / 2])
-            } else {
-                v_val
-            };
-
-            // 4:2:0 子采样: 每 2x2 块共享一个 UV, 这里简化为一列内用同一 UV
-            let u_avg = if col >= 2 { (u_val + u) / 2 } else { u_val };
-            let v_avg = if col >= 2 { (v_val + v) / 2 } else { v_val };
-
-            let batch = yuv_to_rgb_batch4([y0, y1, y2, y3], u_avg, v_avg);
-            for (i, &(r, g, b)) in batch.iter().enumerate() {
-                let dst_off = dst_row + (col + i) * 3;
-                rgb[dst_off] = r;
-                rgb[dst_off + 1] = g;
-                rgb[dst_off + 2] = b;
-            }
-        }
-
-        // 标量回退: 处理非对齐的剩余像素
-        for col in aligned_end..w {
-            let y = i32::from(y_data[y_row + col]);
-            let u = i32::from(u_data[uv_row * u_stride + col / 2]);
-            let v = i32::from(v_data[uv_row * v_stride + col / 2]);
-
-            let batch = yuv_to_rgb_batch4([y, 0, 0, 0], u, v);
-            let (r, g, b) = batch[0];
-            let dst_off = dst_row + col * 3;
-            rgb[dst_off] = r;
-            rgb[dst_off + 1] = g;
-            rgb[dst_off + 2] = b;
-        }
+        let y_off = row * src.linesize[0];
+        let u_off = (row / 2) * src.linesize[1];
+        let v_off = (row / 2) * src.linesize[2];
+        yuv_to_rgb_row(
+            kernel,
+            &coef,
+            &src.planes[0][y_off..y_off + w],
+            &src.planes[1][u_off..u_off + chroma_w],
+            &src.planes[2][v_off..v_off + chroma_w],
+            1,
+            &mut rgb[row * dst_stride..row * dst_stride + w * 3],
+        );
     }
 
     Ok(())
@@ -484,12 +529,63 @@ fn yuv420p_to_nv12(src: &ConvertInput, dst: &mut ConvertOutput) -> TaoResult<()>
     Ok(())
 }
 
+// ============================================================
+// YUV422P / YUV444P → YUV420P
+// ============================================================
+
+/// 8bit 平面 YUV → YUV420P: 复制 Y 平面, 色度按覆盖的 2x2 亮度块取平均
+///
+/// `sub_x`/`sub_y` 为源色度平面的水平/垂直子采样位移 (YUV422P 为 1/0, YUV444P 为 0/0).
+fn yuv_planar_to_yuv420p(
+    src: &ConvertInput,
+    dst: &mut ConvertOutput,
+    sub_x: u32,
+    sub_y: u32,
+) -> TaoResult<()> {
+    let w = src.width as usize;
+    let h = src.height as usize;
+
+    let (y_plane, uv_rest) = dst.planes.split_at_mut(1);
+    for row in 0..h {
+        let src_off = row * src.linesize[0];
+        let dst_off = row * dst.linesize[0];
+        y_plane[0][dst_off..dst_off + w].copy_from_slice(&src.planes[0][src_off..src_off + w]);
+    }
+
+    let chroma_w = w.div_ceil(2);
+    let chroma_h = h.div_ceil(2);
+    for (plane, dst_plane) in uv_rest.iter_mut().enumerate() {
+        let src_plane = src.planes[plane + 1];
+        let src_stride = src.linesize[plane + 1];
+        let dst_stride = dst.linesize[plane + 1];
+        for row in 0..chroma_h {
+            // 奇数尺寸时最后一行/列只覆盖一个亮度样点
+            let rows = (row * 2..(row * 2 + 2).min(h)).map(|y| (y >> sub_y) * src_stride);
+            for col in 0..chroma_w {
+                let cols = col * 2..(col * 2 + 2).min(w);
+                let mut sum = 0u32;
+                let mut count = 0u32;
+                for src_row in rows.clone() {
+                    for x in cols.clone() {
+                        sum += u32::from(src_plane[src_row + (x >> sub_x)]);
+                        count += 1;
+                    }
+                }
+                dst_plane[row * dst_stride + col] = ((sum + count / 2) / count) as u8;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 // ============================================================
 // RGB24 ↔ YUV444P
 // ============================================================
 
-/// RGB24 → YUV444P (BT.601, 无子采样)
+/// RGB24 → YUV444P (无子采样)
 fn rgb24_to_yuv444p(src: &ConvertInput, dst: &mut ConvertOutput) -> TaoResult<()> {
+    let coef = YuvCoefficients::for_output(dst);
     let w = src.width as usize;
     let h = src.height as usize;
     let src_stride = src.linesize[0];
@@ -509,49 +605,37 @@ fn rgb24_to_yuv444p(src: &ConvertInput, dst: &mut ConvertOutput) -> TaoResult<()
             let g = i32::from(rgb[off + 1]);
             let b = i32::from(rgb[off + 2]);
 
-            let y = ((Y_R * r + Y_G * g + Y_B * b + 128) >> 8).clamp(0, 255);
-            let cb = (((CB_R * r + CB_G * g + CB_B * b + 128) >> 8) + 128).clamp(0, 255);
-            let cr = (((CR_R * r + CR_G * g + CR_B * b + 128) >> 8) + 128).clamp(0, 255);
-
-            y_plane[0][row * dst_y_stride + col] = y as u8;
-            u_plane[0][row * dst_u_stride + col] = cb as u8;
-            v_plane[0][row * dst_v_stride + col] = cr as u8;
+            let (cb, cr) = coef.chroma(r, g, b);
+            y_plane[0][row * dst_y_stride + col] = coef.luma(r, g, b);
+            u_plane[0][row * dst_u_stride + col] = cb;
+            v_plane[0][row * dst_v_stride + col] = cr;
         }
     }
 
     Ok(())
 }
 
-/// YUV444P → RGB24 (BT.601)
+/// YUV444P → RGB24
 fn yuv444p_to_rgb24(src: &ConvertInput, dst: &mut ConvertOutput) -> TaoResult<()> {
+    let coef = YuvCoefficients::for_input(src);
+    let kernel = RowKernel::detect();
     let w = src.width as usize;
     let h = src.height as usize;
 
-    let y_data = src.planes[0];
-    let u_data = src.planes[1];
-    let v_data = src.planes[2];
-    let y_stride = src.linesize[0];
-    let u_stride = src.linesize[1];
-    let v_stride = src.linesize[2];
-
     let dst_stride = dst.linesize[0];
     let rgb = &mut dst.planes[0];
 
     for row in 0..h {
-        for col in 0..w {
-            let y = i32::from(y_data[row * y_stride + col]);
-            let u = i32::from(u_data[row * u_stride + col]) - 128;
-            let v = i32::from(v_data[row * v_stride + col]) - 128;
-
-            let r = (y + ((v * 359 + 128) >> 8)).clamp(0, 255);
-            let g = (y - ((u * 88 + v * 183 + 128) >> 8)).clamp(0, 255);
-            let b = (y + ((u * 454 + 128) >> 8)).clamp(0, 255);
-
-            let off = row * dst_stride + col * 3;
-            rgb[off] = r as u8;
-            rgb[off + 1] = g as u8;
-            rgb[off + 2] = b as u8;
-        }
+        let [y_off, u_off, v_off] = [0, 1, 2].map(|i| row * src.linesize[i]);
+        yuv_to_rgb_row(
+            kernel,
+            &coef,
+            &src.planes[0][y_off..y_off + w],
+            &src.planes[1][u_off..u_off + w],
+            &src.planes[2][v_off..v_off + w],
+            0,
+            &mut rgb[row * dst_stride..row * dst_stride + w * 3],
+        );
     }
 
     Ok(())
@@ -590,6 +674,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Rgb24,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         let mut output = ConvertOutput {
             planes: vec![&mut y_buf, &mut u_buf, &mut v_buf],
@@ -597,11 +683,13 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Yuv420p,
+            color_space: ColorSpace::Smpte170m,
+            color_range: ColorRange::Full,
         };
 
         convert(&input, &mut output).unwrap();
 
-        // BT.601: 纯红 → Y≈76, Cb≈84, Cr≈255
+        // BT.601 完整范围: 纯红 → Y≈76, Cb≈84, Cr≈255
         assert!((y_buf[0] as i32 - 76).abs() <= 2, "Y={}", y_buf[0]);
         assert!((u_buf[0] as i32 - 84).abs() <= 2, "Cb={}", u_buf[0]);
         assert!((v_buf[0] as i32 - 255).abs() <= 2, "Cr={}", v_buf[0]);
@@ -632,6 +720,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Rgb24,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         let mut yuv_output = ConvertOutput {
             planes: vec![&mut y_buf, &mut u_buf, &mut v_buf],
@@ -639,6 +729,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Yuv420p,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         convert(&input, &mut yuv_output).unwrap();
 
@@ -650,6 +742,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Yuv420p,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         let mut rgb_output = ConvertOutput {
             planes: vec![&mut rgb_result],
@@ -657,11 +751,12 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Rgb24,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         convert(&yuv_input, &mut rgb_output).unwrap();
 
         // 由于 4:2:0 子采样丢失色度精度, 允许较大误差
-        // BT.601 有限范围公式 (Y-16) 与 RGB->YUV 全范围输出的组合也会引入额外偏差
         let mut max_diff = 0i32;
         for i in 0..rgb_original.len() {
             let diff = (rgb_original[i] as i32 - rgb_result[i] as i32).abs();
@@ -684,6 +779,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Rgb24,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         let mut gray_out = ConvertOutput {
             planes: vec![&mut gray],
@@ -691,6 +788,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Gray8,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         convert(&input, &mut gray_out).unwrap();
 
@@ -707,6 +806,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Gray8,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         let mut rgb_out = ConvertOutput {
             planes: vec![&mut rgb_result],
@@ -714,6 +815,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Rgb24,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         convert(&gray_input, &mut rgb_out).unwrap();
 
@@ -743,6 +846,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Rgba,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         let mut output = ConvertOutput {
             planes: vec![&mut rgb],
@@ -750,6 +855,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Rgb24,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         convert(&input, &mut output).unwrap();
 
@@ -772,6 +879,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Bgr24,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         let mut output = ConvertOutput {
             planes: vec![&mut rgb],
@@ -779,6 +888,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Rgb24,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         convert(&input, &mut output).unwrap();
 
@@ -811,6 +922,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Nv12,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         let mut output = ConvertOutput {
             planes: vec![&mut yuv_y, &mut yuv_u, &mut yuv_v],
@@ -818,6 +931,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Yuv420p,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         convert(&input, &mut output).unwrap();
 
@@ -837,6 +952,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Yuv420p,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         let mut nv12_output = ConvertOutput {
             planes: vec![&mut nv12_y2, &mut nv12_uv2],
@@ -844,6 +961,8 @@ mod tests {
             width: w,
             height: h,
             format: PixelFormat::Nv12,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
         };
         convert(&yuv_input, &mut nv12_output).unwrap();
 
@@ -851,12 +970,12 @@ mod tests {
         assert_eq!(nv12_uv2, nv12_uv);
     }
 
-    /// 验证 batch4 与标量路径输出一致
+    /// 验证定点系数与经典 BT.601 公式及各行转换实现一致
     #[test]
-    fn test_yuv_to_rgb_batch4_matches_scalar() {
-        use super::yuv_to_rgb_batch4;
+    fn test_yuv_to_rgb_matches_bt601_formula() {
+        let coef = YuvCoefficients::new(ColorSpace::Smpte170m, ColorRange::Limited, 480);
 
-        // 标量单像素转换 (与 batch4 使用相同 BT.601 公式)
+        // 经典 BT.601 有限范围整数公式
         fn scalar_yuv_to_rgb(y: i32, u: i32, v: i32) -> (u8, u8, u8) {
             let c = y - 16;
             let d = u - 128;
@@ -881,25 +1000,226 @@ mod tests {
 
         for (y, u, v) in test_cases {
             let scalar = scalar_yuv_to_rgb(y, u, v);
-            let batch = yuv_to_rgb_batch4([y, 0, 0, 0], u, v);
+            let actual = coef.yuv_to_rgb(y, u, v);
             assert_eq!(
-                scalar, batch[0],
-                "Y={y} U={u} V={v}: scalar={:?} batch={:?}",
-                scalar, batch[0],
+                scalar, actual,
+                "Y={y} U={u} V={v}: 公式={:?} 系数={:?}",
+                scalar, actual,
             );
         }
 
-        // 4 像素批量与 4 次标量一致
-        let y_arr = [16, 128, 200, 235];
-        let u = 128i32;
-        let v = 128i32;
-        let batch = yuv_to_rgb_batch4(y_arr, u, v);
-        for (i, &y) in y_arr.iter().enumerate() {
-            let scalar = scalar_yuv_to_rgb(y, u, v);
-            assert_eq!(scalar, batch[i], "像素 {i}: Y={y}");
+        // 整行转换 (运行时选择的实现) 与逐像素公式一致
+        let y_row: Vec<u8> = (0..24).map(|i| 16 + i * 9).collect();
+        let u_row: Vec<u8> = (0..12).map(|i| 40 + i * 17).collect();
+        let v_row: Vec<u8> = (0..12).map(|i| 230 - i * 15).collect();
+        let mut rgb = [0u8; 24 * 3];
+        yuv_to_rgb_row(
+            RowKernel::detect(),
+            &coef,
+            &y_row,
+            &u_row,
+            &v_row,
+            1,
+            &mut rgb,
+        );
+        for (i, &y) in y_row.iter().enumerate() {
+            let (u, v) = (i32::from(u_row[i / 2]), i32::from(v_row[i / 2]));
+            let (r, g, b) = scalar_yuv_to_rgb(i32::from(y), u, v);
+            assert_eq!(&rgb[i * 3..i * 3 + 3], &[r, g, b], "像素 {i}: Y={y}");
+        }
+    }
+
+    /// 单像素 RGB24 → YUV444P
+    fn rgb_pixel_to_yuv(rgb: [u8; 3], space: ColorSpace, range: ColorRange) -> [u8; 3] {
+        let (mut y, mut u, mut v) = ([0u8; 1], [0u8; 1], [0u8; 1]);
+        let input = ConvertInput {
+            planes: vec![&rgb],
+            linesize: vec![3],
+            width: 1,
+            height: 1,
+            format: PixelFormat::Rgb24,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
+        };
+        let mut output = ConvertOutput {
+            planes: vec![&mut y, &mut u, &mut v],
+            linesize: vec![1, 1, 1],
+            width: 1,
+            height: 1,
+            format: PixelFormat::Yuv444p,
+            color_space: space,
+            color_range: range,
+        };
+        convert(&input, &mut output).unwrap();
+        [y[0], u[0], v[0]]
+    }
+
+    /// 单像素 YUV444P → RGB24
+    fn yuv_pixel_to_rgb(yuv: [u8; 3], space: ColorSpace, range: ColorRange) -> [u8; 3] {
+        let mut rgb = [0u8; 3];
+        let input = ConvertInput {
+            planes: vec![&yuv[0..1], &yuv[1..2], &yuv[2..3]],
+            linesize: vec![1, 1, 1],
+            width: 1,
+            height: 1,
+            format: PixelFormat::Yuv444p,
+            color_space: space,
+            color_range: range,
+        };
+        let mut output = ConvertOutput {
+            planes: vec![&mut rgb],
+            linesize: vec![3],
+            width: 1,
+            height: 1,
+            format: PixelFormat::Rgb24,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
+        };
+        convert(&input, &mut output).unwrap();
+        rgb
+    }
+
+    fn assert_close(actual: [u8; 3], expected: [u8; 3], tolerance: i32, what: &str) {
+        for i in 0..3 {
+            assert!(
+                (i32::from(actual[i]) - i32::from(expected[i])).abs() <= tolerance,
+                "{what}: 实际 {actual:?}, 期望 {expected:?}",
+            );
+        }
+    }
+
+    /// 参考值: (RGB, 色彩空间, 色彩范围, 期望 YCbCr)
+    const REFERENCE_TRIPLETS: [([u8; 3], ColorSpace, ColorRange, [u8; 3]); 16] = [
+        (
+            [255, 0, 0],
+            ColorSpace::Smpte170m,
+            ColorRange::Limited,
+            [81, 90, 240],
+        ),
+        (
+            [0, 255, 0],
+            ColorSpace::Smpte170m,
+            ColorRange::Limited,
+            [145, 54, 34],
+        ),
+        (
+            [0, 0, 255],
+            ColorSpace::Smpte170m,
+            ColorRange::Limited,
+            [41, 240, 110],
+        ),
+        (
+            [255, 255, 255],
+            ColorSpace::Smpte170m,
+            ColorRange::Limited,
+            [235, 128, 128],
+        ),
+        (
+            [255, 0, 0],
+            ColorSpace::Smpte170m,
+            ColorRange::Full,
+            [76, 85, 255],
+        ),
+        (
+            [0, 255, 0],
+            ColorSpace::Smpte170m,
+            ColorRange::Full,
+            [150, 44, 21],
+        ),
+        (
+            [0, 0, 255],
+            ColorSpace::Smpte170m,
+            ColorRange::Full,
+            [29, 255, 107],
+        ),
+        (
+            [255, 255, 255],
+            ColorSpace::Smpte170m,
+            ColorRange::Full,
+            [255, 128, 128],
+        ),
+        (
+            [255, 0, 0],
+            ColorSpace::Bt709,
+            ColorRange::Limited,
+            [63, 102, 240],
+        ),
+        (
+            [0, 255, 0],
+            ColorSpace::Bt709,
+            ColorRange::Limited,
+            [173, 42, 26],
+        ),
+        (
+            [0, 0, 255],
+            ColorSpace::Bt709,
+            ColorRange::Limited,
+            [32, 240, 118],
+        ),
+        (
+            [255, 255, 255],
+            ColorSpace::Bt709,
+            ColorRange::Limited,
+            [235, 128, 128],
+        ),
+        (
+            [255, 0, 0],
+            ColorSpace::Bt709,
+            ColorRange::Full,
+            [54, 99, 255],
+        ),
+        (
+            [0, 255, 0],
+            ColorSpace::Bt709,
+            ColorRange::Full,
+            [182, 30, 12],
+        ),
+        (
+            [0, 0, 255],
+            ColorSpace::Bt709,
+            ColorRange::Full,
+            [18, 255, 116],
+        ),
+        (
+            [255, 255, 255],
+            ColorSpace::Bt709,
+            ColorRange::Full,
+            [255, 128, 128],
+        ),
+    ];
+
+    #[test]
+    fn test_rgb_to_yuv_reference_triplets() {
+        for (rgb, space, range, expected) in REFERENCE_TRIPLETS {
+            let yuv = rgb_pixel_to_yuv(rgb, space, range);
+            assert_close(yuv, expected, 1, &format!("{space:?}/{range:?} RGB{rgb:?}"));
         }
     }
 
+    #[test]
+    fn test_yuv_to_rgb_reference_triplets() {
+        for (rgb, space, range, yuv) in REFERENCE_TRIPLETS {
+            let actual = yuv_pixel_to_rgb(yuv, space, range);
+            assert_close(actual, rgb, 3, &format!("{space:?}/{range:?} YUV{yuv:?}"));
+        }
+    }
+
+    #[test]
+    fn test_unspecified_colorimetry_defaults_by_height() {
+        let sd = YuvCoefficients::new(ColorSpace::Unspecified, ColorRange::Unspecified, 576);
+        let hd = YuvCoefficients::new(ColorSpace::Unspecified, ColorRange::Unspecified, 720);
+        assert_eq!(
+            sd,
+            YuvCoefficients::new(ColorSpace::Smpte170m, ColorRange::Limited, 576),
+            "标清默认应为 BT.601 有限范围"
+        );
+        assert_eq!(
+            hd,
+            YuvCoefficients::new(ColorSpace::Bt709, ColorRange::Limited, 720),
+            "高清默认应为 BT.709 有限范围"
+        );
+    }
+
     #[test]
     fn test_is_conversion_supported() {
         assert!(is_conversion_supported(
@@ -918,5 +1238,67 @@ mod tests {
             PixelFormat::Rgb24,
             PixelFormat::Nv12
         ));
+        assert!(is_conversion_supported(
+            PixelFormat::Yuv422p,
+            PixelFormat::Yuv420p
+        ));
+        assert!(is_conversion_supported(
+            PixelFormat::Yuv444p,
+            PixelFormat::Yuv420p
+        ));
+    }
+
+    #[test]
+    fn test_yuv422p_yuv444p_to_yuv420p_averages_chroma() {
+        // 3x3 奇数尺寸, 420 色度为 2x2
+        let w = 3u32;
+        let h = 3u32;
+        let luma: Vec<u8> = (0..9).map(|i| i * 10).collect();
+        let cases: [(PixelFormat, usize, Vec<u8>, Vec<u8>); 2] = [
+            // 422: 色度 2x3, 垂直方向两行取平均
+            (
+                PixelFormat::Yuv422p,
+                2,
+                vec![10, 20, 30, 40, 50, 60],
+                vec![20, 30, 50, 60],
+            ),
+            // 444: 色度 3x3, 2x2 块取平均, 边缘按实际覆盖样点数
+            (
+                PixelFormat::Yuv444p,
+                3,
+                vec![10, 20, 30, 40, 50, 60, 70, 80, 90],
+                vec![30, 45, 75, 90],
+            ),
+        ];
+        for (format, chroma_stride, chroma, expected) in cases {
+            let v: Vec<u8> = chroma.iter().map(|c| 255 - c).collect();
+            let input = ConvertInput {
+                planes: vec![&luma, &chroma, &v],
+                linesize: vec![w as usize, chroma_stride, chroma_stride],
+                width: w,
+                height: h,
+                format,
+                color_space: ColorSpace::Unspecified,
+                color_range: ColorRange::Unspecified,
+            };
+            let mut out_y = vec![0u8; 9];
+            let mut out_u = vec![0u8; 4];
+            let mut out_v = vec![0u8; 4];
+            let mut output = ConvertOutput {
+                planes: vec![&mut out_y, &mut out_u, &mut out_v],
+                linesize: vec![w as usize, 2, 2],
+                width: w,
+                height: h,
+                format: PixelFormat::Yuv420p,
+                color_space: ColorSpace::Unspecified,
+                color_range: ColorRange::Unspecified,
+            };
+            convert(&input, &mut output).unwrap();
+
+            assert_eq!(out_y, luma, "{format} 亮度平面应原样复制");
+            assert_eq!(out_u, expected, "{format} U 平面下采样结果错误");
+            let expected_v: Vec<u8> = expected.iter().map(|c| 255 - c).collect();
+            assert_eq!(out_v, expected_v, "{format} V 平面下采样结果错误");
+        }
     }
 }
@@ -0,0 +1,303 @@
+//! YUV → RGB24 行转换的 SIMD 实现.
+//!
+//! x86/x86_64 上首次使用时检测 AVX2 / SSE4.1 并分派到对应实现 (每次迭代 8 / 4 像素),
+//! 其他平台或 CPU 不支持时使用标量实现. 向量实现在 i32 通道内按与标量相同的
+//! 定点公式计算, 输出与标量逐字节一致.
+
+use std::sync::OnceLock;
+
+use super::YuvCoefficients;
+
+/// 行转换实现
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RowKernel {
+    /// 标量回退
+    Scalar,
+    /// SSE4.1, 每次 4 像素
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    Sse41,
+    /// AVX2, 每次 8 像素
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    Avx2,
+}
+
+impl RowKernel {
+    /// 当前 CPU 可用的最快实现 (首次调用时检测并缓存)
+    pub(super) fn detect() -> Self {
+        static KERNEL: OnceLock<RowKernel> = OnceLock::new();
+        *KERNEL.get_or_init(|| {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            {
+                if is_x86_feature_detected!("avx2") {
+                    return Self::Avx2;
+                }
+                if is_x86_feature_detected!("sse4.1") {
+                    return Self::Sse41;
+                }
+            }
+            Self::Scalar
+        })
+    }
+}
+
+/// 转换一行像素: `y` 为该行亮度 (长度即宽度), `u`/`v` 为对应色度行,
+/// 水平方向每 `1 << chroma_shift` 个像素共享一个色度样本
+pub(super) fn yuv_to_rgb_row(
+    kernel: RowKernel,
+    coef: &YuvCoefficients,
+    y: &[u8],
+    u: &[u8],
+    v: &[u8],
+    chroma_shift: u32,
+    rgb: &mut [u8],
+) {
+    let width = y.len();
+    let chroma_width = (width + (1 << chroma_shift) - 1) >> chroma_shift;
+    assert!(
+        u.len() >= chroma_width && v.len() >= chroma_width && rgb.len() >= width * 3,
+        "行缓冲长度不足"
+    );
+    // 向量实现只处理 4:4:4 与水平 2:1 子采样, 其余情况按标量转换
+    let kernel = if chroma_shift > 1 {
+        RowKernel::Scalar
+    } else {
+        kernel
+    };
+    let done = match kernel {
+        RowKernel::Scalar => 0,
+        // SAFETY: 仅在检测到对应 CPU 特性时选择该实现, 且上面已校验缓冲长度
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        RowKernel::Sse41 => unsafe { x86::row_sse41(coef, y, u, v, chroma_shift, rgb) },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        RowKernel::Avx2 => unsafe { x86::row_avx2(coef, y, u, v, chroma_shift, rgb) },
+    };
+    for col in done..width {
+        let (r, g, b) = coef.yuv_to_rgb(
+            i32::from(y[col]),
+            i32::from(u[col >> chroma_shift]),
+            i32::from(v[col >> chroma_shift]),
+        );
+        rgb[col * 3..col * 3 + 3].copy_from_slice(&[r, g, b]);
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+    use std::ptr;
+
+    use super::YuvCoefficients;
+
+    /// AVX2 实现, 返回已处理的像素数 (8 的倍数)
+    ///
+    /// # Safety
+    /// 调用方需保证 CPU 支持 AVX2, `chroma_shift <= 1`, 且 `u`/`v`/`rgb` 长度满足
+    /// `yuv_to_rgb_row` 的要求.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn row_avx2(
+        coef: &YuvCoefficients,
+        y: &[u8],
+        u: &[u8],
+        v: &[u8],
+        chroma_shift: u32,
+        rgb: &mut [u8],
+    ) -> usize {
+        let width = y.len() / 8 * 8;
+        let mut tmp = [0u8; 32];
+        // SAFETY: 每次迭代满足 `col + 8 <= width <= y.len()`, 亮度读取不越界.
+        // `yuv_to_rgb_row` 已断言 `u`/`v` 长度不小于 `ceil(y.len() >> chroma_shift)`,
+        // 且仅在 `chroma_shift <= 1` 时调用本函数: 无子采样时读取 `[col, col + 8)`,
+        // 子采样时读取 `[col / 2, col / 2 + 4)`, 均不超过色度行长度. 输出经切片索引写入.
+        unsafe {
+            let y_offset = _mm256_set1_epi32(coef.y_offset);
+            let y_scale = _mm256_set1_epi32(coef.y_scale);
+            let (r_v, g_u) = (_mm256_set1_epi32(coef.r_v), _mm256_set1_epi32(coef.g_u));
+            let (g_v, b_u) = (_mm256_set1_epi32(coef.g_v), _mm256_set1_epi32(coef.b_u));
+            let bias = _mm256_set1_epi32(128);
+            // 每个 128 位通道内 [R0..R3 G0..G3 B0..B3 0000] -> [R0 G0 B0 R1 ...]
+            let interleave = _mm256_setr_epi8(
+                0, 4, 8, 1, 5, 9, 2, 6, 10, 3, 7, 11, -1, -1, -1, -1, 0, 4, 8, 1, 5, 9, 2, 6, 10,
+                3, 7, 11, -1, -1, -1, -1,
+            );
+            let load_chroma = |plane: &[u8], col: usize| {
+                if chroma_shift == 0 {
+                    _mm256_cvtepu8_epi32(_mm_loadl_epi64(plane.as_ptr().add(col).cast()))
+                } else {
+                    let packed = ptr::read_unaligned(plane.as_ptr().add(col / 2).cast::<i32>());
+                    let packed = _mm_cvtsi32_si128(packed);
+                    _mm256_cvtepu8_epi32(_mm_unpacklo_epi8(packed, packed))
+                }
+            };
+            for col in (0..width).step_by(8) {
+                let luma = _mm256_cvtepu8_epi32(_mm_loadl_epi64(y.as_ptr().add(col).cast()));
+                let c = _mm256_mullo_epi32(_mm256_sub_epi32(luma, y_offset), y_scale);
+                let d = _mm256_sub_epi32(load_chroma(u, col), bias);
+                let e = _mm256_sub_epi32(load_chroma(v, col), bias);
+                let c = _mm256_add_epi32(c, bias);
+
+                let r = _mm256_add_epi32(c, _mm256_mullo_epi32(e, r_v));
+                let g = _mm256_sub_epi32(
+                    _mm256_sub_epi32(c, _mm256_mullo_epi32(d, g_u)),
+                    _mm256_mullo_epi32(e, g_v),
+                );
+                let b = _mm256_add_epi32(c, _mm256_mullo_epi32(d, b_u));
+                let (r, g, b) = (
+                    _mm256_srai_epi32::<8>(r),
+                    _mm256_srai_epi32::<8>(g),
+                    _mm256_srai_epi32::<8>(b),
+                );
+
+                // 饱和打包即 clamp(0, 255)
+                let rg = _mm256_packs_epi32(r, g);
+                let b0 = _mm256_packs_epi32(b, _mm256_setzero_si256());
+                let pixels = _mm256_shuffle_epi8(_mm256_packus_epi16(rg, b0), interleave);
+                _mm256_storeu_si256(tmp.as_mut_ptr().cast(), pixels);
+
+                let out = &mut rgb[col * 3..col * 3 + 24];
+                out[..12].copy_from_slice(&tmp[..12]);
+                out[12..].copy_from_slice(&tmp[16..28]);
+            }
+        }
+        width
+    }
+
+    /// SSE4.1 实现, 返回已处理的像素数 (4 的倍数)
+    ///
+    /// # Safety
+    /// 调用方需保证 CPU 支持 SSE4.1, `chroma_shift <= 1`, 且 `u`/`v`/`rgb` 长度满足
+    /// `yuv_to_rgb_row` 的要求.
+    #[target_feature(enable = "sse4.1")]
+    pub(super) unsafe fn row_sse41(
+        coef: &YuvCoefficients,
+        y: &[u8],
+        u: &[u8],
+        v: &[u8],
+        chroma_shift: u32,
+        rgb: &mut [u8],
+    ) -> usize {
+        let width = y.len() / 4 * 4;
+        let mut tmp = [0u8; 16];
+        // SAFETY: 每次迭代满足 `col + 4 <= width <= y.len()`, 亮度读取不越界.
+        // `yuv_to_rgb_row` 已断言 `u`/`v` 长度不小于 `ceil(y.len() >> chroma_shift)`,
+        // 且仅在 `chroma_shift <= 1` 时调用本函数: 无子采样时读取 `[col, col + 4)`,
+        // 子采样时读取 `[col / 2, col / 2 + 2)`, 均不超过色度行长度. 输出经切片索引写入.
+        unsafe {
+            let y_offset = _mm_set1_epi32(coef.y_offset);
+            let y_scale = _mm_set1_epi32(coef.y_scale);
+            let (r_v, g_u) = (_mm_set1_epi32(coef.r_v), _mm_set1_epi32(coef.g_u));
+            let (g_v, b_u) = (_mm_set1_epi32(coef.g_v), _mm_set1_epi32(coef.b_u));
+            let bias = _mm_set1_epi32(128);
+            let interleave = _mm_setr_epi8(0, 4, 8, 1, 5, 9, 2, 6, 10, 3, 7, 11, -1, -1, -1, -1);
+            let load_chroma = |plane: &[u8], col: usize| {
+                if chroma_shift == 0 {
+                    let packed = ptr::read_unaligned(plane.as_ptr().add(col).cast::<i32>());
+                    _mm_cvtepu8_epi32(_mm_cvtsi32_si128(packed))
+                } else {
+                    let packed = ptr::read_unaligned(plane.as_ptr().add(col / 2).cast::<u16>());
+                    let packed = _mm_cvtsi32_si128(i32::from(packed));
+                    _mm_cvtepu8_epi32(_mm_unpacklo_epi8(packed, packed))
+                }
+            };
+            for col in (0..width).step_by(4) {
+                let luma = ptr::read_unaligned(y.as_ptr().add(col).cast::<i32>());
+                let luma = _mm_cvtepu8_epi32(_mm_cvtsi32_si128(luma));
+                let c = _mm_mullo_epi32(_mm_sub_epi32(luma, y_offset), y_scale);
+                let d = _mm_sub_epi32(load_chroma(u, col), bias);
+                let e = _mm_sub_epi32(load_chroma(v, col), bias);
+                let c = _mm_add_epi32(c, bias);
+
+                let r = _mm_add_epi32(c, _mm_mullo_epi32(e, r_v));
+                let g = _mm_sub_epi32(
+                    _mm_sub_epi32(c, _mm_mullo_epi32(d, g_u)),
+                    _mm_mullo_epi32(e, g_v),
+                );
+                let b = _mm_add_epi32(c, _mm_mullo_epi32(d, b_u));
+                let (r, g, b) = (
+                    _mm_srai_epi32::<8>(r),
+                    _mm_srai_epi32::<8>(g),
+                    _mm_srai_epi32::<8>(b),
+                );
+
+                let rg = _mm_packs_epi32(r, g);
+                let b0 = _mm_packs_epi32(b, _mm_setzero_si128());
+                let pixels = _mm_shuffle_epi8(_mm_packus_epi16(rg, b0), interleave);
+                _mm_storeu_si128(tmp.as_mut_ptr().cast(), pixels);
+
+                rgb[col * 3..col * 3 + 12].copy_from_slice(&tmp[..12]);
+            }
+        }
+        width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tao_core::color::{ColorRange, ColorSpace};
+
+    /// 当前 CPU 支持的全部实现
+    fn available_kernels() -> Vec<RowKernel> {
+        let mut kernels = vec![RowKernel::Scalar];
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if is_x86_feature_detected!("sse4.1") {
+                kernels.push(RowKernel::Sse41);
+            }
+            if is_x86_feature_detected!("avx2") {
+                kernels.push(RowKernel::Avx2);
+            }
+        }
+        kernels
+    }
+
+    #[test]
+    fn test_yuv_to_rgb_row_kernels_match_scalar() {
+        let mut seed = 0x1234_5678u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) as u8
+        };
+        let samples: Vec<u8> = (0..256 * 3).map(|_| next()).collect();
+        let coefs = [
+            YuvCoefficients::new(ColorSpace::Smpte170m, ColorRange::Limited, 480),
+            YuvCoefficients::new(ColorSpace::Bt709, ColorRange::Full, 1080),
+            YuvCoefficients::new(ColorSpace::Bt2020Ncl, ColorRange::Limited, 2160),
+        ];
+        for kernel in available_kernels() {
+            for coef in &coefs {
+                for chroma_shift in [0, 1, 2] {
+                    // 覆盖向量主体与标量尾部的各种宽度
+                    for width in (1..40).chain([255, 256]) {
+                        let y = &samples[..width];
+                        let u = &samples[256..512];
+                        let v = &samples[512..];
+                        let mut rgb = vec![0u8; width * 3];
+                        yuv_to_rgb_row(kernel, coef, y, u, v, chroma_shift, &mut rgb);
+                        for col in 0..width {
+                            let (r, g, b) = coef.yuv_to_rgb(
+                                i32::from(y[col]),
+                                i32::from(u[col >> chroma_shift]),
+                                i32::from(v[col >> chroma_shift]),
+                            );
+                            assert_eq!(
+                                &rgb[col * 3..col * 3 + 3],
+                                &[r, g, b],
+                                "{kernel:?} 宽度 {width} 色度移位 {chroma_shift} 第 {col} 像素与标量不一致"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_row_kernel_detect_is_stable() {
+        let kernel = RowKernel::detect();
+        assert_eq!(kernel, RowKernel::detect(), "检测结果应缓存");
+        assert!(available_kernels().contains(&kernel), "检测到的实现应可用");
+    }
+}
@@ -4,11 +4,12 @@
 //!
 //! 本 crate 对标 FFmpeg 的 libswscale, 提供:
 //! - 像素格式转换 (YUV ↔ RGB, 位深转换等)
-//! - 图像缩放 (双线性, 双三次, Lanczos 等算法, 待实现)
+//! - 图像缩放 (最近邻, 双线性, 双三次, Lanczos, 区域平均)
 
 pub mod convert;
 pub mod scale;
 
+use tao_core::color::{ColorRange, ColorSpace};
 use tao_core::{PixelFormat, TaoResult};
 
 /// 缩放算法
@@ -21,15 +22,82 @@ pub enum ScaleAlgorithm {
     Bilinear,
     /// 双三次插值 (质量较高)
     Bicubic,
-    /// Lanczos (质量最高, 速度较慢)
-    Lanczos,
+    /// Lanczos (质量最高, 速度较慢), `lobes` 为核函数瓣数
+    Lanczos {
+        /// 核函数瓣数 (窗口半宽), 默认 3
+        lobes: usize,
+    },
     /// Area 平均 (缩小时效果好)
     Area,
 }
 
+impl ScaleAlgorithm {
+    /// Lanczos 默认瓣数
+    pub const DEFAULT_LANCZOS_LOBES: usize = 3;
+
+    /// 默认瓣数 (3) 的 Lanczos 算法
+    pub const fn lanczos() -> Self {
+        Self::Lanczos {
+            lobes: Self::DEFAULT_LANCZOS_LOBES,
+        }
+    }
+}
+
+/// 缩放与像素格式转换的先后顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ScaleOrder {
+    /// 自动选择: 目标色度分辨率不低于源时 (如 YUV → RGB, 4:2:0 → 4:4:4) 先转换再缩放,
+    /// 否则先在源格式下缩放再转换
+    #[default]
+    Auto,
+    /// 先在源格式下缩放, 再转换到目标格式
+    ScaleFirst,
+    /// 先在源尺寸下转换到目标格式, 再缩放
+    ConvertFirst,
+}
+
+/// 子采样色度平面的采样位置, 决定色度缩放时的插值相位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ChromaPosition {
+    /// 色度样本位于所覆盖亮度样本的中心 (JPEG/MPEG-1 方式)
+    #[default]
+    Center,
+    /// 色度样本与左上角亮度样本共址
+    CoSited,
+}
+
+impl ChromaPosition {
+    /// 子采样 `1 << sub` 倍的轴上的插值相位 (1/256 像素)
+    pub(crate) fn phase(self, sub: u32) -> u32 {
+        match self {
+            Self::Center => 128,
+            // 共址时色度坐标 = 亮度坐标 / 2^sub, 相位相应缩小
+            Self::CoSited => 128 >> sub,
+        }
+    }
+}
+
+/// 缩放选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ScaleFlags {
+    /// 缩放与格式转换的先后顺序 (仅在格式与尺寸都变化时生效)
+    pub order: ScaleOrder,
+    /// 缩放子采样 YUV 色度平面时使用的采样位置
+    pub chroma_position: ChromaPosition,
+}
+
+/// 色度分辨率相对亮度的移位 (水平, 垂直), 无色度的灰度格式为 None
+fn chroma_shift(format: PixelFormat) -> Option<(u32, u32)> {
+    match format {
+        PixelFormat::Gray8 | PixelFormat::Gray16le => None,
+        _ => Some(format.chroma_subsampling()),
+    }
+}
+
 /// 图像缩放/转换上下文
 ///
 /// 配置一次后可多次复用, 用于在不同像素格式和分辨率之间转换.
+/// 缩放滤波系数在 [`ScaleContext::new`] 中预计算, 之后每次缩放直接复用.
 pub struct ScaleContext {
     /// 源宽度
     pub src_width: u32,
@@ -45,6 +113,18 @@ pub struct ScaleContext {
     pub dst_format: PixelFormat,
     /// 缩放算法
     pub algorithm: ScaleAlgorithm,
+    /// 源色彩空间 (未指定时按源高度推断)
+    pub src_color_space: ColorSpace,
+    /// 源色彩范围 (未指定时视为有限范围)
+    pub src_color_range: ColorRange,
+    /// 目标色彩空间 (未指定时按目标高度推断)
+    pub dst_color_space: ColorSpace,
+    /// 目标色彩范围 (未指定时视为有限范围)
+    pub dst_color_range: ColorRange,
+    /// 缩放选项
+    pub flags: ScaleFlags,
+    /// 预计算的缩放系数 (缩放所用格式, 目标尺寸), 无需缩放或格式不支持时为 None
+    scaler: Option<scale::ImageScaler>,
 }
 
 impl ScaleContext {
@@ -58,7 +138,7 @@ impl ScaleContext {
         dst_format: PixelFormat,
         algorithm: ScaleAlgorithm,
     ) -> Self {
-        Self {
+        let mut ctx = Self {
             src_width,
             src_height,
             src_format,
@@ -66,9 +146,83 @@ impl ScaleContext {
             dst_height,
             dst_format,
             algorithm,
+            src_color_space: ColorSpace::Unspecified,
+            src_color_range: ColorRange::Unspecified,
+            dst_color_space: ColorSpace::Unspecified,
+            dst_color_range: ColorRange::Unspecified,
+            flags: ScaleFlags::default(),
+            scaler: None,
+        };
+        ctx.scaler = ctx.build_scaler();
+        ctx
+    }
+
+    /// 设置缩放选项 (缩放顺序与色度采样位置), 并按新选项重新预计算缩放系数
+    pub fn with_flags(mut self, flags: ScaleFlags) -> Self {
+        self.flags = flags;
+        self.scaler = self.build_scaler();
+        self
+    }
+
+    /// 实际采用的缩放顺序 (已解析 [`ScaleOrder::Auto`])
+    pub fn resolved_order(&self) -> ScaleOrder {
+        match self.flags.order {
+            ScaleOrder::Auto => {
+                let keeps_chroma =
+                    match (chroma_shift(self.src_format), chroma_shift(self.dst_format)) {
+                        (_, None) => false,
+                        (None, Some(_)) => true,
+                        (Some((sh, sv)), Some((dh, dv))) => dh <= sh && dv <= sv,
+                    };
+                if keeps_chroma && scale::is_format_supported(self.dst_format) {
+                    ScaleOrder::ConvertFirst
+                } else {
+                    ScaleOrder::ScaleFirst
+                }
+            }
+            order => order,
         }
     }
 
+    /// 缩放所用的像素格式
+    fn resize_format(&self) -> PixelFormat {
+        if self.src_format != self.dst_format && self.resolved_order() == ScaleOrder::ConvertFirst {
+            self.dst_format
+        } else {
+            self.src_format
+        }
+    }
+
+    fn build_scaler(&self) -> Option<scale::ImageScaler> {
+        if self.src_width == self.dst_width && self.src_height == self.dst_height {
+            return None;
+        }
+        scale::ImageScaler::new(
+            self.src_width,
+            self.src_height,
+            self.resize_format(),
+            self.dst_width,
+            self.dst_height,
+            self.algorithm,
+            self.flags.chroma_position,
+        )
+        .ok()
+    }
+
+    /// 设置源图像的色彩空间与色彩范围
+    pub fn with_src_colorimetry(mut self, space: ColorSpace, range: ColorRange) -> Self {
+        self.src_color_space = space;
+        self.src_color_range = range;
+        self
+    }
+
+    /// 设置目标图像的色彩空间与色彩范围
+    pub fn with_dst_colorimetry(mut self, space: ColorSpace, range: ColorRange) -> Self {
+        self.dst_color_space = space;
+        self.dst_color_range = range;
+        self
+    }
+
     /// 执行图像缩放/格式转换
     ///
     /// # 参数
@@ -82,6 +236,32 @@ impl ScaleContext {
         src_linesize: &[usize],
         dst_data: &mut [&mut [u8]],
         dst_linesize: &[usize],
+    ) -> TaoResult<()> {
+        self.scale_impl(src_data, src_linesize, dst_data, dst_linesize, 1)
+    }
+
+    /// 多线程执行图像缩放/格式转换
+    ///
+    /// 双线性/双三次缩放时将目标行均分为 `n_threads` 个切片, 由 [`std::thread::scope`]
+    /// 并行计算, 输出与 [`ScaleContext::scale`] 逐位一致. 其他算法及格式转换仍为单线程.
+    pub fn scale_slices(
+        &self,
+        src_data: &[&[u8]],
+        src_linesize: &[usize],
+        dst_data: &mut [&mut [u8]],
+        dst_linesize: &[usize],
+        n_threads: usize,
+    ) -> TaoResult<()> {
+        self.scale_impl(src_data, src_linesize, dst_data, dst_linesize, n_threads)
+    }
+
+    fn scale_impl(
+        &self,
+        src_data: &[&[u8]],
+        src_linesize: &[usize],
+        dst_data: &mut [&mut [u8]],
+        dst_linesize: &[usize],
+        threads: usize,
     ) -> TaoResult<()> {
         // 分辨率相同时只做格式转换
         if self.src_width == self.dst_width && self.src_height == self.dst_height {
@@ -97,6 +277,8 @@ impl ScaleContext {
                 width: self.src_width,
                 height: self.src_height,
                 format: self.src_format,
+                color_space: self.src_color_space.resolve(self.src_height),
+                color_range: self.src_color_range,
             };
             let mut output = convert::ConvertOutput {
                 planes: dst_data.iter_mut().map(|s| &mut **s).collect(),
@@ -104,72 +286,143 @@ impl ScaleContext {
                 width: self.dst_width,
                 height: self.dst_height,
                 format: self.dst_format,
+                color_space: self.dst_color_space.resolve(self.dst_height),
+                color_range: self.dst_color_range,
             };
             return convert::convert(&input, &mut output);
         }
 
-        // 不同格式 + 不同分辨率: 先缩放(同格式), 再转换
+        // 不同格式 + 不同分辨率: 按缩放顺序组合缩放与转换
         if self.src_format != self.dst_format {
-            return self.scale_with_convert(src_data, src_linesize, dst_data, dst_linesize);
+            return match self.resolved_order() {
+                ScaleOrder::ConvertFirst => {
+                    self.convert_then_scale(src_data, src_linesize, dst_data, dst_linesize, threads)
+                }
+                _ => {
+                    self.scale_then_convert(src_data, src_linesize, dst_data, dst_linesize, threads)
+                }
+            };
         }
 
         // 同格式不同分辨率: 直接缩放
-        scale::scale_image(
-            src_data,
-            src_linesize,
+        self.resize(src_data, src_linesize, dst_data, dst_linesize, threads)
+    }
+
+    /// 以 `resize_format()` 缩放到目标尺寸, 优先复用预计算系数
+    fn resize(
+        &self,
+        src_data: &[&[u8]],
+        src_linesize: &[usize],
+        dst_data: &mut [&mut [u8]],
+        dst_linesize: &[usize],
+        threads: usize,
+    ) -> TaoResult<()> {
+        let format = self.resize_format();
+        if let Some(scaler) = &self.scaler {
+            if scaler.matches(
+                self.src_width,
+                self.src_height,
+                format,
+                self.dst_width,
+                self.dst_height,
+                self.algorithm,
+                self.flags.chroma_position,
+            ) {
+                return scaler.scale(src_data, src_linesize, dst_data, dst_linesize, threads);
+            }
+        }
+
+        // 无预计算系数或公开字段在创建后被修改: 按当前参数重新计算
+        scale::ImageScaler::new(
             self.src_width,
             self.src_height,
-            self.src_format,
-            dst_data,
-            dst_linesize,
+            format,
             self.dst_width,
             self.dst_height,
             self.algorithm,
-        )
+            self.flags.chroma_position,
+        )?
+        .scale(src_data, src_linesize, dst_data, dst_linesize, threads)
     }
 
-    /// 不同格式 + 不同分辨率: 先缩放到目标尺寸, 再做格式转换
-    fn scale_with_convert(
+    /// 按格式与尺寸分配中间缓冲区, 返回各平面数据与行字节数
+    fn alloc_planes(format: PixelFormat, width: u32, height: u32) -> (Vec<Vec<u8>>, Vec<usize>) {
+        (0..format.plane_count() as usize)
+            .map(|p| {
+                let ls = format.plane_linesize(p, width).unwrap_or(0);
+                let h = format.plane_height(p, height).unwrap_or(0);
+                (vec![0u8; ls * h], ls)
+            })
+            .unzip()
+    }
+
+    /// 不同格式 + 不同分辨率: 先在源尺寸下转换到目标格式, 再缩放
+    ///
+    /// 色度在全分辨率 (或目标色度分辨率) 下插值, 放大时色彩边缘更锐利.
+    fn convert_then_scale(
         &self,
         src_data: &[&[u8]],
         src_linesize: &[usize],
         dst_data: &mut [&mut [u8]],
         dst_linesize: &[usize],
+        threads: usize,
     ) -> TaoResult<()> {
-        // 分配中间缓冲区 (目标尺寸, 源格式)
-        let planes = self.src_format.plane_count() as usize;
-        let mut tmp_bufs: Vec<Vec<u8>> = Vec::with_capacity(planes);
-        let mut tmp_linesizes = Vec::with_capacity(planes);
+        // 中间缓冲区 (源尺寸, 目标格式)
+        let (mut tmp_bufs, tmp_linesizes) =
+            Self::alloc_planes(self.dst_format, self.src_width, self.src_height);
 
-        for p in 0..planes {
-            let ls = self
-                .src_format
-                .plane_linesize(p, self.dst_width)
-                .unwrap_or(0);
-            let h = self
-                .src_format
-                .plane_height(p, self.dst_height)
-                .unwrap_or(0);
-            tmp_bufs.push(vec![0u8; ls * h]);
-            tmp_linesizes.push(ls);
+        // 第一步: 格式转换 (源尺寸)
+        {
+            let input = convert::ConvertInput {
+                planes: src_data.to_vec(),
+                linesize: src_linesize.to_vec(),
+                width: self.src_width,
+                height: self.src_height,
+                format: self.src_format,
+                color_space: self.src_color_space.resolve(self.src_height),
+                color_range: self.src_color_range,
+            };
+            let mut output = convert::ConvertOutput {
+                planes: tmp_bufs.iter_mut().map(|b| b.as_mut_slice()).collect(),
+                linesize: tmp_linesizes.clone(),
+                width: self.src_width,
+                height: self.src_height,
+                format: self.dst_format,
+                color_space: self.dst_color_space.resolve(self.dst_height),
+                color_range: self.dst_color_range,
+            };
+            convert::convert(&input, &mut output)?;
         }
 
+        // 第二步: 缩放 (目标格式)
+        let tmp_refs: Vec<&[u8]> = tmp_bufs.iter().map(|b| b.as_slice()).collect();
+        self.resize(&tmp_refs, &tmp_linesizes, dst_data, dst_linesize, threads)
+    }
+
+    /// 不同格式 + 不同分辨率: 先缩放到目标尺寸, 再做格式转换
+    fn scale_then_convert(
+        &self,
+        src_data: &[&[u8]],
+        src_linesize: &[usize],
+        dst_data: &mut [&mut [u8]],
+        dst_linesize: &[usize],
+        threads: usize,
+    ) -> TaoResult<()> {
+        // 分配中间缓冲区 (目标尺寸, 源格式)
+        let (mut tmp_bufs, tmp_linesizes) =
+            Self::alloc_planes(self.src_format, self.dst_width, self.dst_height);
+
         // 第一步: 缩放 (保持源格式)
         {
             let mut tmp_slices: Vec<&mut [u8]> =
                 tmp_bufs.iter_mut().map(|b| b.as_mut_slice()).collect();
             let mut tmp_refs: Vec<&mut [u8]> = tmp_slices.iter_mut().map(|s| &mut **s).collect();
-            scale::scale_image(
+            self.resize(
                 src_data,
                 src_linesize,
-                self.src_width,
-                self.src_height,
-                self.src_format,
                 &mut tmp_refs,
                 &tmp_linesizes,
-                self.dst_width,
-                self.dst_height,
-                self.algorithm,
+                threads,
             )?;
         }
 
@@ -181,6 +434,8 @@ impl ScaleContext {
             width: self.dst_width,
             height: self.dst_height,
             format: self.src_format,
+            color_space: self.src_color_space.resolve(self.src_height),
+            color_range: self.src_color_range,
         };
         let mut output = convert::ConvertOutput {
             planes: dst_data.iter_mut().map(|s| &mut **s).collect(),
@@ -188,6 +443,8 @@ impl ScaleContext {
             width: self.dst_width,
             height: self.dst_height,
             format: self.dst_format,
+            color_space: self.dst_color_space.resolve(self.dst_height),
+            color_range: self.dst_color_range,
         };
         convert::convert(&input, &mut output)
     }
@@ -270,6 +527,37 @@ mod tests {
         assert!(y[0] > 140 && y[0] < 160, "Y={}", y[0]);
     }
 
+    #[test]
+    fn test_colorimetry_selects_matrix() {
+        let rgb = [255u8, 0, 0].repeat(16);
+        let to_yuv = |ctx: ScaleContext| {
+            let (mut y, mut u, mut v) = (vec![0u8; 16], vec![0u8; 4], vec![0u8; 4]);
+            ctx.scale(&[&rgb], &[12], &mut [&mut y, &mut u, &mut v], &[4, 2, 2])
+                .unwrap();
+            (y[0], u[0], v[0])
+        };
+        let new_ctx = || {
+            ScaleContext::new(
+                4,
+                4,
+                PixelFormat::Rgb24,
+                4,
+                4,
+                PixelFormat::Yuv420p,
+                ScaleAlgorithm::Bilinear,
+            )
+        };
+
+        let (y, _, _) = to_yuv(new_ctx());
+        assert!(
+            (y as i32 - 81).abs() <= 1,
+            "标清默认 BT.601 有限范围, Y={y}"
+        );
+        let (y, _, v) = to_yuv(new_ctx().with_dst_colorimetry(ColorSpace::Bt709, ColorRange::Full));
+        assert!((y as i32 - 54).abs() <= 1, "BT.709 完整范围, Y={y}");
+        assert!(v >= 254, "BT.709 完整范围, Cr={v}");
+    }
+
     #[test]
     fn test_resolution_scale_bilinear() {
         let ctx = ScaleContext::new(
@@ -313,4 +601,274 @@ mod tests {
         // 绿色 Y 应接近 150
         assert!(y[0] > 140 && y[0] < 160, "Y={}", y[0]);
     }
+
+    #[test]
+    fn test_auto_order_by_chroma_resolution() {
+        let order = |src, dst| {
+            ScaleContext::new(16, 16, src, 32, 32, dst, ScaleAlgorithm::Bilinear).resolved_order()
+        };
+        use PixelFormat::*;
+        assert_eq!(
+            order(Yuv420p, Rgb24),
+            ScaleOrder::ConvertFirst,
+            "YUV→RGB 应先转换"
+        );
+        assert_eq!(
+            order(Nv12, Yuv444p),
+            ScaleOrder::ConvertFirst,
+            "4:2:0→4:4:4 应先转换"
+        );
+        assert_eq!(order(Gray8, Rgb24), ScaleOrder::ConvertFirst);
+        assert_eq!(
+            order(Rgb24, Yuv420p),
+            ScaleOrder::ScaleFirst,
+            "目标子采样更多时应先缩放"
+        );
+        assert_eq!(order(Rgb24, Gray8), ScaleOrder::ScaleFirst);
+        // 目标格式不支持缩放时只能先缩放
+        assert_eq!(order(Yuv420p, Nv12), ScaleOrder::ScaleFirst);
+
+        let forced = ScaleContext::new(16, 16, Yuv420p, 32, 32, Rgb24, ScaleAlgorithm::Bilinear)
+            .with_flags(ScaleFlags {
+                order: ScaleOrder::ScaleFirst,
+                ..Default::default()
+            });
+        assert_eq!(
+            forced.resolved_order(),
+            ScaleOrder::ScaleFirst,
+            "应可强制指定顺序"
+        );
+    }
+
+    /// YUV420P 彩条 (每条 8 像素宽) 放大 4 倍到 RGB24, 返回中间一行
+    fn upscale_color_bars(flags: ScaleFlags) -> Vec<[u8; 3]> {
+        const BARS: [(u8, u8, u8); 8] = [
+            (235, 128, 128),
+            (210, 16, 146),
+            (170, 166, 16),
+            (145, 54, 34),
+            (106, 202, 222),
+            (81, 90, 240),
+            (41, 240, 110),
+            (16, 128, 128),
+        ];
+        let (sw, sh, dw, dh) = (64usize, 8usize, 256usize, 32usize);
+        let y: Vec<u8> = (0..sw * sh).map(|i| BARS[i % sw / 8].0).collect();
+        let u: Vec<u8> = (0..sw * sh / 4).map(|i| BARS[i % (sw / 2) / 4].1).collect();
+        let v: Vec<u8> = (0..sw * sh / 4).map(|i| BARS[i % (sw / 2) / 4].2).collect();
+        let ctx = ScaleContext::new(
+            sw as u32,
+            sh as u32,
+            PixelFormat::Yuv420p,
+            dw as u32,
+            dh as u32,
+            PixelFormat::Rgb24,
+            ScaleAlgorithm::Bilinear,
+        )
+        .with_flags(flags);
+        let mut rgb = vec![0u8; dw * dh * 3];
+        ctx.scale(
+            &[&y, &u, &v],
+            &[sw, sw / 2, sw / 2],
+            &mut [&mut rgb],
+            &[dw * 3],
+        )
+        .unwrap();
+        rgb[dh / 2 * dw * 3..(dh / 2 + 1) * dw * 3]
+            .chunks_exact(3)
+            .map(|p| [p[0], p[1], p[2]])
+            .collect()
+    }
+
+    /// 彩条边缘的过渡像素数: 与两侧色条中心颜色都相差明显的像素
+    fn transition_pixels(row: &[[u8; 3]]) -> usize {
+        let bar_width = row.len() / 8;
+        let plateau: Vec<[u8; 3]> = (0..8).map(|b| row[b * bar_width + bar_width / 2]).collect();
+        let dist = |a: [u8; 3], b: [u8; 3]| (0..3).map(|c| a[c].abs_diff(b[c])).max().unwrap();
+        row.iter()
+            .enumerate()
+            .filter(|&(x, &p)| {
+                let bar = x / bar_width;
+                let neighbors = [bar.saturating_sub(1), bar, (bar + 1).min(7)];
+                neighbors.iter().all(|&b| dist(p, plateau[b]) > 16)
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_convert_first_sharper_color_edges_on_upscale() {
+        let flags = |order| ScaleFlags {
+            order,
+            ..Default::default()
+        };
+        let convert_first = transition_pixels(&upscale_color_bars(flags(ScaleOrder::ConvertFirst)));
+        let scale_first = transition_pixels(&upscale_color_bars(flags(ScaleOrder::ScaleFirst)));
+        let auto = transition_pixels(&upscale_color_bars(flags(ScaleOrder::Auto)));
+        assert!(
+            convert_first * 3 < scale_first * 2,
+            "先转换再缩放的色彩边缘应明显更锐利: 先转换 {convert_first}, 先缩放 {scale_first}"
+        );
+        assert_eq!(auto, convert_first, "YUV→RGB 放大默认应先转换");
+    }
+
+    #[test]
+    fn test_chroma_position_changes_chroma_phase() {
+        let scale_u = |chroma_position| {
+            let ctx = ScaleContext::new(
+                8,
+                2,
+                PixelFormat::Yuv420p,
+                16,
+                2,
+                PixelFormat::Yuv420p,
+                ScaleAlgorithm::Bilinear,
+            )
+            .with_flags(ScaleFlags {
+                chroma_position,
+                ..Default::default()
+            });
+            let y = [128u8; 16];
+            let u = [0u8, 64, 128, 255];
+            let v = [128u8; 4];
+            let (mut dy, mut du, mut dv) = ([0u8; 32], [0u8; 8], [0u8; 8]);
+            ctx.scale(
+                &[&y, &u, &v],
+                &[8, 4, 4],
+                &mut [&mut dy, &mut du, &mut dv],
+                &[16, 8, 8],
+            )
+            .unwrap();
+            du
+        };
+        let center = scale_u(ChromaPosition::Center);
+        let cosited = scale_u(ChromaPosition::CoSited);
+        // 目标色度 1 -> 源 0.25 (中心) / 0.375 (共址)
+        assert_eq!(center[1], 16);
+        assert_eq!(cosited[1], 24);
+        assert_ne!(center, cosited, "色度位置应影响色度插值相位");
+    }
+
+    /// 构造带图案的测试平面
+    fn pattern(len: usize, seed: usize) -> Vec<u8> {
+        (0..len)
+            .map(|i| ((i * 37 + seed * 11) ^ (i >> 3)) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn test_coefficients_reused_across_frames() {
+        let builds = || scale::COEFF_BUILDS.with(|c| c.get());
+        let (sw, sh, dw, dh) = (320u32, 240u32, 160u32, 120u32);
+        let src_y = pattern((sw * sh) as usize, 0);
+        let src_u = pattern((sw * sh / 4) as usize, 1);
+        let src_v = pattern((sw * sh / 4) as usize, 2);
+        let src: [&[u8]; 3] = [&src_y, &src_u, &src_v];
+        let src_ls = [sw as usize, sw as usize / 2, sw as usize / 2];
+        let dst_ls = [dw as usize, dw as usize / 2, dw as usize / 2];
+        let mut y = vec![0u8; (dw * dh) as usize];
+        let mut u = vec![0u8; (dw * dh / 4) as usize];
+        let mut v = vec![0u8; (dw * dh / 4) as usize];
+        const FRAMES: usize = 20;
+
+        let before = builds();
+        let ctx = ScaleContext::new(
+            sw,
+            sh,
+            PixelFormat::Yuv420p,
+            dw,
+            dh,
+            PixelFormat::Yuv420p,
+            ScaleAlgorithm::Bicubic,
+        );
+        assert_eq!(builds() - before, 2, "创建时应为亮度/色度各构建一次系数表");
+
+        for _ in 0..FRAMES {
+            ctx.scale(&src, &src_ls, &mut [&mut y, &mut u, &mut v], &dst_ls)
+                .unwrap();
+        }
+        assert_eq!(builds() - before, 2, "逐帧缩放不应重新构建系数表");
+
+        for _ in 0..FRAMES {
+            scale::scale_image(
+                &src,
+                &src_ls,
+                sw,
+                sh,
+                PixelFormat::Yuv420p,
+                &mut [&mut y, &mut u, &mut v],
+                &dst_ls,
+                dw,
+                dh,
+                ScaleAlgorithm::Bicubic,
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            builds() - before,
+            2 + FRAMES * 2,
+            "scale_image 每次调用都会重新构建系数表"
+        );
+    }
+
+    #[test]
+    fn test_scale_slices_bit_identical() {
+        let cases = [
+            (
+                PixelFormat::Gray8,
+                PixelFormat::Gray8,
+                97u32,
+                61u32,
+                203u32,
+                129u32,
+            ),
+            (PixelFormat::Rgb24, PixelFormat::Rgb24, 64, 48, 37, 29),
+            (PixelFormat::Yuv420p, PixelFormat::Yuv420p, 96, 64, 50, 130),
+            (PixelFormat::Rgb24, PixelFormat::Yuv420p, 40, 30, 82, 62),
+        ];
+        for (src_fmt, dst_fmt, sw, sh, dw, dh) in cases {
+            for algorithm in [ScaleAlgorithm::Bilinear, ScaleAlgorithm::Bicubic] {
+                let ctx = ScaleContext::new(sw, sh, src_fmt, dw, dh, dst_fmt, algorithm);
+                let src_planes: Vec<Vec<u8>> = (0..src_fmt.plane_count() as usize)
+                    .map(|p| {
+                        let len = src_fmt.plane_linesize(p, sw).unwrap()
+                            * src_fmt.plane_height(p, sh).unwrap();
+                        pattern(len, p)
+                    })
+                    .collect();
+                let src: Vec<&[u8]> = src_planes.iter().map(|p| p.as_slice()).collect();
+                let src_ls: Vec<usize> = (0..src.len())
+                    .map(|p| src_fmt.plane_linesize(p, sw).unwrap())
+                    .collect();
+                let dst_ls: Vec<usize> = (0..dst_fmt.plane_count() as usize)
+                    .map(|p| dst_fmt.plane_linesize(p, dw).unwrap())
+                    .collect();
+                let alloc = || -> Vec<Vec<u8>> {
+                    (0..dst_ls.len())
+                        .map(|p| vec![0u8; dst_ls[p] * dst_fmt.plane_height(p, dh).unwrap()])
+                        .collect()
+                };
+
+                let mut expected = alloc();
+                {
+                    let mut dst: Vec<&mut [u8]> =
+                        expected.iter_mut().map(|p| p.as_mut_slice()).collect();
+                    ctx.scale(&src, &src_ls, &mut dst, &dst_ls).unwrap();
+                }
+
+                for n_threads in [2, 3, 4, 7, 512] {
+                    let mut actual = alloc();
+                    {
+                        let mut dst: Vec<&mut [u8]> =
+                            actual.iter_mut().map(|p| p.as_mut_slice()).collect();
+                        ctx.scale_slices(&src, &src_ls, &mut dst, &dst_ls, n_threads)
+                            .unwrap();
+                    }
+                    assert_eq!(
+                        actual, expected,
+                        "{src_fmt}->{dst_fmt} {sw}x{sh}->{dw}x{dh} {algorithm:?} {n_threads} 线程结果应与单线程一致"
+                    );
+                }
+            }
+        }
+    }
 }
@@ -3,6 +3,9 @@
 //! 支持的算法:
 //! - **最近邻插值** (`NearestNeighbor`): 速度最快, 适合像素艺术/整数倍缩放
 //! - **双线性插值** (`Bilinear`): 速度与质量均衡, 最常用
+//! - **双三次插值** (`Bicubic`): 质量较高
+//! - **Lanczos** (`Lanczos { lobes }`): 可分离加窗 sinc 滤波, 质量最高
+//! - **区域平均** (`Area`): 缩小时抗锯齿
 //!
 //! 支持的像素格式:
 //! - RGB24 / BGR24 (packed, 每像素 3 字节)
@@ -10,14 +13,18 @@
 //! - Gray8 (单通道, 每像素 1 字节)
 //! - YUV420P / YUV422P / YUV444P (planar, 每平面独立缩放)
 
+use std::ops::Range;
+
 use tao_core::{PixelFormat, TaoError, TaoResult};
 
-use super::ScaleAlgorithm;
+use super::{ChromaPosition, ScaleAlgorithm};
 
 /// 执行图像缩放
 ///
 /// 根据指定算法将源图像缩放到目标尺寸.
 /// 源和目标像素格式必须相同 (格式转换应在缩放前/后单独进行).
+///
+/// 每次调用都会重新计算滤波系数; 同一尺寸反复缩放时应使用 [`crate::ScaleContext`].
 #[allow(clippy::too_many_arguments)]
 pub fn scale_image(
     src_data: &[&[u8]],
@@ -31,266 +38,504 @@ pub fn scale_image(
     dst_height: u32,
     algorithm: ScaleAlgorithm,
 ) -> TaoResult<()> {
+    ImageScaler::new(
+        src_width,
+        src_height,
+        format,
+        dst_width,
+        dst_height,
+        algorithm,
+        ChromaPosition::Center,
+    )?
+    .scale(src_data, src_linesize, dst_data, dst_linesize, 1)
+}
+
+/// 缩放支持的像素格式每像素字节数 (planar 格式为每平面 1 字节)
+fn bytes_per_pixel(format: PixelFormat) -> Option<usize> {
     match format {
-        PixelFormat::Rgb24 | PixelFormat::Bgr24 => scale_packed(
-            src_data[0],
-            src_linesize[0],
-            src_width,
-            src_height,
-            dst_data[0],
-            dst_linesize[0],
-            dst_width,
-            dst_height,
-            3,
-            algorithm,
-        ),
-        PixelFormat::Rgba | PixelFormat::Bgra | PixelFormat::Argb => scale_packed(
-            src_data[0],
-            src_linesize[0],
+        PixelFormat::Rgb24 | PixelFormat::Bgr24 => Some(3),
+        PixelFormat::Rgba | PixelFormat::Bgra | PixelFormat::Argb => Some(4),
+        PixelFormat::Gray8 | PixelFormat::Yuv420p | PixelFormat::Yuv422p | PixelFormat::Yuv444p => {
+            Some(1)
+        }
+        _ => None,
+    }
+}
+
+/// 是否支持在该像素格式下缩放
+pub(crate) fn is_format_supported(format: PixelFormat) -> bool {
+    bytes_per_pixel(format).is_some()
+}
+
+#[cfg(test)]
+thread_local! {
+    /// 当前线程构建平面系数表的次数 (测试用于验证系数复用)
+    pub(crate) static COEFF_BUILDS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// 整幅图像的缩放器, 持有各平面预计算的滤波系数
+pub(crate) struct ImageScaler {
+    /// 构建时的参数 (源宽, 源高, 格式, 目标宽, 目标高, 算法, 色度位置)
+    params: (
+        u32,
+        u32,
+        PixelFormat,
+        u32,
+        u32,
+        ScaleAlgorithm,
+        ChromaPosition,
+    ),
+    /// 亮度平面 / packed 单平面
+    luma: PlaneScaler,
+    /// planar YUV 的色度平面 (U/V 共用)
+    chroma: Option<PlaneScaler>,
+}
+
+impl ImageScaler {
+    /// 按源/目标尺寸与算法预计算滤波系数
+    pub(crate) fn new(
+        src_width: u32,
+        src_height: u32,
+        format: PixelFormat,
+        dst_width: u32,
+        dst_height: u32,
+        algorithm: ScaleAlgorithm,
+        chroma_position: ChromaPosition,
+    ) -> TaoResult<Self> {
+        let bpp = bytes_per_pixel(format)
+            .ok_or_else(|| TaoError::Unsupported(format!("图像缩放不支持像素格式: {format}")))?;
+
+        let luma = PlaneScaler::new(
             src_width,
             src_height,
-            dst_data[0],
-            dst_linesize[0],
             dst_width,
             dst_height,
-            4,
+            bpp,
             algorithm,
-        ),
-        PixelFormat::Gray8 => scale_packed(
+            [CENTER_PHASE; 2],
+        )?;
+        let chroma = if format.is_planar() {
+            // 色度平面按子采样比例缩放
+            let (sub_h, sub_v) = format.chroma_subsampling();
+            Some(PlaneScaler::new(
+                src_width >> sub_h,
+                src_height >> sub_v,
+                dst_width >> sub_h,
+                dst_height >> sub_v,
+                1,
+                algorithm,
+                [sub_h, sub_v].map(|sub| chroma_position.phase(sub)),
+            )?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            params: (
+                src_width,
+                src_height,
+                format,
+                dst_width,
+                dst_height,
+                algorithm,
+                chroma_position,
+            ),
+            luma,
+            chroma,
+        })
+    }
+
+    /// 预计算系数是否适用于给定参数
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn matches(
+        &self,
+        src_width: u32,
+        src_height: u32,
+        format: PixelFormat,
+        dst_width: u32,
+        dst_height: u32,
+        algorithm: ScaleAlgorithm,
+        chroma_position: ChromaPosition,
+    ) -> bool {
+        self.params
+            == (
+                src_width,
+                src_height,
+                format,
+                dst_width,
+                dst_height,
+                algorithm,
+                chroma_position,
+            )
+    }
+
+    /// 使用预计算系数缩放图像
+    ///
+    /// `threads > 1` 时双线性/双三次按目标行切片并行, 输出与单线程逐位一致.
+    pub(crate) fn scale(
+        &self,
+        src_data: &[&[u8]],
+        src_linesize: &[usize],
+        dst_data: &mut [&mut [u8]],
+        dst_linesize: &[usize],
+        threads: usize,
+    ) -> TaoResult<()> {
+        self.luma.scale(
             src_data[0],
             src_linesize[0],
-            src_width,
-            src_height,
             dst_data[0],
             dst_linesize[0],
-            dst_width,
-            dst_height,
-            1,
-            algorithm,
-        ),
-        PixelFormat::Yuv420p | PixelFormat::Yuv422p | PixelFormat::Yuv444p => scale_planar_yuv(
-            src_data,
-            src_linesize,
-            src_width,
-            src_height,
-            format,
-            dst_data,
-            dst_linesize,
-            dst_width,
-            dst_height,
-            algorithm,
-        ),
-        _ => Err(TaoError::Unsupported(format!(
-            "图像缩放不支持像素格式: {format}",
-        ))),
+            threads,
+        );
+        if let Some(chroma) = &self.chroma {
+            for plane in 1..3 {
+                chroma.scale(
+                    src_data[plane],
+                    src_linesize[plane],
+                    dst_data[plane],
+                    dst_linesize[plane],
+                    threads,
+                );
+            }
+        }
+        Ok(())
     }
 }
 
-/// 缩放 packed 格式 (RGB24, RGBA, Gray8 等)
+/// 单个平面的缩放器
 ///
-/// 所有像素存储在单个平面中, 每像素 `bpp` 字节.
-#[allow(clippy::too_many_arguments)]
-fn scale_packed(
-    src: &[u8],
-    src_stride: usize,
-    src_w: u32,
-    src_h: u32,
-    dst: &mut [u8],
-    dst_stride: usize,
-    dst_w: u32,
-    dst_h: u32,
+/// 水平/垂直方向的采样位置与权重在构建时一次算好, 缩放时只做查表与累加.
+struct PlaneScaler {
+    dst_w: usize,
+    dst_h: usize,
     bpp: usize,
-    algorithm: ScaleAlgorithm,
-) -> TaoResult<()> {
-    match algorithm {
-        ScaleAlgorithm::NearestNeighbor => scale_plane_nearest(
-            src, src_stride, src_w, src_h, dst, dst_stride, dst_w, dst_h, bpp,
-        ),
-        ScaleAlgorithm::Bilinear => scale_plane_bilinear(
-            src, src_stride, src_w, src_h, dst, dst_stride, dst_w, dst_h, bpp,
-        ),
-        ScaleAlgorithm::Bicubic => scale_plane_bicubic(
-            src, src_stride, src_w, src_h, dst, dst_stride, dst_w, dst_h, bpp,
-        ),
-        ScaleAlgorithm::Lanczos => scale_plane_lanczos(
-            src, src_stride, src_w, src_h, dst, dst_stride, dst_w, dst_h, bpp,
-        ),
-        ScaleAlgorithm::Area => scale_plane_area(
-            src, src_stride, src_w, src_h, dst, dst_stride, dst_w, dst_h, bpp,
-        ),
-    }
+    filter: PlaneFilter,
 }
 
-/// 缩放 planar YUV 格式 (每个平面独立缩放)
-#[allow(clippy::too_many_arguments)]
-fn scale_planar_yuv(
-    src_data: &[&[u8]],
-    src_linesize: &[usize],
-    src_w: u32,
-    src_h: u32,
-    format: PixelFormat,
-    dst_data: &mut [&mut [u8]],
-    dst_linesize: &[usize],
-    dst_w: u32,
-    dst_h: u32,
-    algorithm: ScaleAlgorithm,
-) -> TaoResult<()> {
-    let (sub_h, sub_v) = format.chroma_subsampling();
-
-    // 亮度平面 (plane 0): 全分辨率
-    scale_packed(
-        src_data[0],
-        src_linesize[0],
-        src_w,
-        src_h,
-        dst_data[0],
-        dst_linesize[0],
-        dst_w,
-        dst_h,
-        1,
-        algorithm,
-    )?;
-
-    // 色度平面 (plane 1, 2): 按子采样比例缩放
-    let src_cw = src_w >> sub_h;
-    let src_ch = src_h >> sub_v;
-    let dst_cw = dst_w >> sub_h;
-    let dst_ch = dst_h >> sub_v;
-
-    for plane in 1..3 {
-        scale_packed(
-            src_data[plane],
-            src_linesize[plane],
-            src_cw,
-            src_ch,
-            dst_data[plane],
-            dst_linesize[plane],
-            dst_cw,
-            dst_ch,
-            1,
-            algorithm,
-        )?;
-    }
-
-    Ok(())
+/// 各算法的预计算系数
+enum PlaneFilter {
+    /// 每个目标坐标对应的源坐标
+    Nearest { xs: Vec<usize>, ys: Vec<usize> },
+    /// 每个目标坐标对应的 (idx0, idx1, frac)
+    Bilinear {
+        xs: Vec<(usize, usize, u32)>,
+        ys: Vec<(usize, usize, u32)>,
+    },
+    /// 每个目标坐标对应的 4 个采样点与权重
+    Bicubic {
+        xs: Vec<CubicTaps>,
+        ys: Vec<CubicTaps>,
+    },
+    /// 可分离 Lanczos 权重表, `src_h` 为水平滤波中间缓冲行数
+    Lanczos {
+        h: LanczosTable,
+        v: LanczosTable,
+        src_h: usize,
+    },
+    /// 每个目标坐标对应的源区间 [start, end), `src_w`/`src_h` 用于空区间回退
+    Area {
+        xs: Vec<(usize, usize)>,
+        ys: Vec<(usize, usize)>,
+        src_w: usize,
+        src_h: usize,
+    },
 }
 
-// ============================================================
-// 最近邻插值
-// ============================================================
-
-/// 最近邻插值缩放单个平面
-///
-/// 对于每个目标像素, 找到源图像中最近的像素并直接复制.
-/// 速度最快, 但缩放时会产生明显的锯齿.
-#[allow(clippy::too_many_arguments)]
-fn scale_plane_nearest(
-    src: &[u8],
-    src_stride: usize,
-    src_w: u32,
-    src_h: u32,
-    dst: &mut [u8],
-    dst_stride: usize,
-    dst_w: u32,
-    dst_h: u32,
-    bpp: usize,
-) -> TaoResult<()> {
-    for dy in 0..dst_h as usize {
-        // 映射目标行到源行
-        let sy = (dy * src_h as usize) / dst_h as usize;
-        let sy = sy.min(src_h as usize - 1);
-
-        let dst_row = dy * dst_stride;
-        let src_row = sy * src_stride;
+/// 双三次的 4 个采样点 (已 clamp) 与定点权重
+#[derive(Clone, Copy)]
+struct CubicTaps {
+    idx: [usize; 4],
+    weight: [i32; 4],
+}
 
-        for dx in 0..dst_w as usize {
-            // 映射目标列到源列
-            let sx = (dx * src_w as usize) / dst_w as usize;
-            let sx = sx.min(src_w as usize - 1);
+impl CubicTaps {
+    fn new(dst_idx: usize, dst_size: u32, src_size: u32, phase: u32) -> Self {
+        let (pos, frac) = map_coord_float(dst_idx, dst_size, src_size, phase);
+        let max = src_size as i32 - 1;
+        let mut idx = [0usize; 4];
+        let mut weight = [0i32; 4];
+        for (k, off) in (-1..=2i32).enumerate() {
+            idx[k] = (pos + off).clamp(0, max) as usize;
+            weight[k] = bicubic_weight((off * 256 - frac).abs());
+        }
+        Self { idx, weight }
+    }
+}
 
-            let dst_off = dst_row + dx * bpp;
-            let src_off = src_row + sx * bpp;
+impl PlaneScaler {
+    /// `phase` 为水平/垂直方向的采样相位 (1/256 像素), 仅影响插值类算法
+    fn new(
+        src_w: u32,
+        src_h: u32,
+        dst_w: u32,
+        dst_h: u32,
+        bpp: usize,
+        algorithm: ScaleAlgorithm,
+        phase: [u32; 2],
+    ) -> TaoResult<Self> {
+        #[cfg(test)]
+        COEFF_BUILDS.with(|c| c.set(c.get() + 1));
+
+        let [phase_x, phase_y] = phase;
+        let axis =
+            |dst: u32, src: u32, phase: u32| (0..dst as usize).map(move |d| (d, dst, src, phase));
+        let filter = match algorithm {
+            ScaleAlgorithm::NearestNeighbor => {
+                let nearest = |(d, dst, src, _): (usize, u32, u32, u32)| {
+                    ((d * src as usize) / dst as usize).min(src as usize - 1)
+                };
+                PlaneFilter::Nearest {
+                    xs: axis(dst_w, src_w, phase_x).map(nearest).collect(),
+                    ys: axis(dst_h, src_h, phase_y).map(nearest).collect(),
+                }
+            }
+            ScaleAlgorithm::Area if src_w >= dst_w && src_h >= dst_h => {
+                let range = |(d, dst, src, _): (usize, u32, u32, u32)| {
+                    let start = (d * src as usize) / dst as usize;
+                    let end = (((d + 1) * src as usize) / dst as usize).min(src as usize);
+                    (start, end)
+                };
+                PlaneFilter::Area {
+                    xs: axis(dst_w, src_w, phase_x).map(range).collect(),
+                    ys: axis(dst_h, src_h, phase_y).map(range).collect(),
+                    src_w: src_w as usize,
+                    src_h: src_h as usize,
+                }
+            }
+            // 放大时区域平均无意义, 退化为双线性
+            ScaleAlgorithm::Bilinear | ScaleAlgorithm::Area => {
+                let linear = |(d, dst, src, phase)| map_coord(d, dst, src, phase);
+                PlaneFilter::Bilinear {
+                    xs: axis(dst_w, src_w, phase_x).map(linear).collect(),
+                    ys: axis(dst_h, src_h, phase_y).map(linear).collect(),
+                }
+            }
+            ScaleAlgorithm::Bicubic => {
+                let cubic = |(d, dst, src, phase)| CubicTaps::new(d, dst, src, phase);
+                PlaneFilter::Bicubic {
+                    xs: axis(dst_w, src_w, phase_x).map(cubic).collect(),
+                    ys: axis(dst_h, src_h, phase_y).map(cubic).collect(),
+                }
+            }
+            ScaleAlgorithm::Lanczos { lobes } => {
+                if lobes == 0 {
+                    return Err(TaoError::InvalidArgument("Lanczos 瓣数必须大于 0".into()));
+                }
+                PlaneFilter::Lanczos {
+                    h: LanczosTable::new(src_w, dst_w, lobes, phase_x),
+                    v: LanczosTable::new(src_h, dst_h, lobes, phase_y),
+                    src_h: src_h as usize,
+                }
+            }
+        };
+
+        Ok(Self {
+            dst_w: dst_w as usize,
+            dst_h: dst_h as usize,
+            bpp,
+            filter,
+        })
+    }
 
-            dst[dst_off..dst_off + bpp].copy_from_slice(&src[src_off..src_off + bpp]);
+    /// 缩放整个平面, `threads > 1` 时对双线性/双三次按行切片并行
+    fn scale(
+        &self,
+        src: &[u8],
+        src_stride: usize,
+        dst: &mut [u8],
+        dst_stride: usize,
+        threads: usize,
+    ) {
+        let parallel = matches!(
+            self.filter,
+            PlaneFilter::Bilinear { .. } | PlaneFilter::Bicubic { .. }
+        );
+        let threads = threads.clamp(1, self.dst_h.max(1));
+        if !parallel || threads == 1 {
+            self.scale_rows(src, src_stride, dst, dst_stride, 0..self.dst_h);
+            return;
         }
+
+        let rows_per_slice = self.dst_h.div_ceil(threads);
+        std::thread::scope(|scope| {
+            for (i, chunk) in dst.chunks_mut(rows_per_slice * dst_stride).enumerate() {
+                let start = i * rows_per_slice;
+                let end = (start + rows_per_slice).min(self.dst_h);
+                if start >= end {
+                    break;
+                }
+                scope
+                    .spawn(move || self.scale_rows(src, src_stride, chunk, dst_stride, start..end));
+            }
+        });
     }
-    Ok(())
-}
 
-// ============================================================
-// 双线性插值
-// ============================================================
+    /// 计算目标行 `rows`, `dst` 从第 `rows.start` 行开始
+    fn scale_rows(
+        &self,
+        src: &[u8],
+        src_stride: usize,
+        dst: &mut [u8],
+        dst_stride: usize,
+        rows: Range<usize>,
+    ) {
+        let bpp = self.bpp;
+        let first_row = rows.start;
+        match &self.filter {
+            PlaneFilter::Nearest { xs, ys } => {
+                for dy in rows {
+                    let src_row = ys[dy] * src_stride;
+                    let dst_row = (dy - first_row) * dst_stride;
+                    for (dx, &sx) in xs.iter().enumerate() {
+                        let dst_off = dst_row + dx * bpp;
+                        let src_off = src_row + sx * bpp;
+                        dst[dst_off..dst_off + bpp].copy_from_slice(&src[src_off..src_off + bpp]);
+                    }
+                }
+            }
+            PlaneFilter::Bilinear { xs, ys } => {
+                for dy in rows {
+                    let (sy0, sy1, frac_y) = ys[dy];
+                    let inv_y = 256 - frac_y;
+                    let src_row0 = sy0 * src_stride;
+                    let src_row1 = sy1 * src_stride;
+                    let dst_row = (dy - first_row) * dst_stride;
+
+                    for (dx, &(sx0, sx1, frac_x)) in xs.iter().enumerate() {
+                        let inv_x = 256 - frac_x;
+
+                        // 权重 (定点数, 和 = 256*256 = 65536)
+                        let w00 = inv_x * inv_y;
+                        let w10 = frac_x * inv_y;
+                        let w01 = inv_x * frac_y;
+                        let w11 = frac_x * frac_y;
+
+                        let off00 = src_row0 + sx0 * bpp;
+                        let off10 = src_row0 + sx1 * bpp;
+                        let off01 = src_row1 + sx0 * bpp;
+                        let off11 = src_row1 + sx1 * bpp;
+                        let dst_off = dst_row + dx * bpp;
+
+                        for c in 0..bpp {
+                            let v = (u32::from(src[off00 + c]) * w00
+                                + u32::from(src[off10 + c]) * w10
+                                + u32::from(src[off01 + c]) * w01
+                                + u32::from(src[off11 + c]) * w11
+                                + 32768) // 四舍五入
+                                >> 16;
+                            dst[dst_off + c] = v as u8;
+                        }
+                    }
+                }
+            }
+            PlaneFilter::Bicubic { xs, ys } => {
+                for dy in rows {
+                    let ty = &ys[dy];
+                    let dst_row = (dy - first_row) * dst_stride;
+
+                    for (dx, tx) in xs.iter().enumerate() {
+                        let dst_off = dst_row + dx * bpp;
+
+                        for c in 0..bpp {
+                            let mut sum: i32 = 0;
+                            let mut weight_sum: i32 = 0;
+
+                            for (&sy, &wy) in ty.idx.iter().zip(&ty.weight) {
+                                let src_row = sy * src_stride;
+                                for (&sx, &wx) in tx.idx.iter().zip(&tx.weight) {
+                                    let w = (wy * wx) >> 8;
+                                    sum += src[src_row + sx * bpp + c] as i32 * w;
+                                    weight_sum += w;
+                                }
+                            }
+
+                            let val = if weight_sum > 0 {
+                                ((sum + weight_sum / 2) / weight_sum).clamp(0, 255)
+                            } else {
+                                0
+                            };
+                            dst[dst_off + c] = val as u8;
+                        }
+                    }
+                }
+            }
+            PlaneFilter::Lanczos { h, v, src_h } => {
+                let row_len = self.dst_w * bpp;
+
+                // 水平滤波: src_h 行 x dst_w 列, 保留 LANCZOS_SHIFT 位小数以减少二次取整误差
+                let mut tmp = vec![0i32; src_h * row_len];
+                for sy in 0..*src_h {
+                    let src_row = &src[sy * src_stride..];
+                    let tmp_row = &mut tmp[sy * row_len..(sy + 1) * row_len];
+                    for dx in 0..self.dst_w {
+                        let (idx, w) = h.taps_of(dx);
+                        for c in 0..bpp {
+                            let sum: i32 = idx
+                                .iter()
+                                .zip(w)
+                                .map(|(&sx, &wk)| src_row[sx * bpp + c] as i32 * wk)
+                                .sum();
+                            tmp_row[dx * bpp + c] = sum;
+                        }
+                    }
+                }
 
-/// 双线性插值缩放单个平面
-///
-/// 对于每个目标像素, 根据在源图像中的浮点坐标,
-/// 用周围 4 个最近像素进行加权平均, 权重由距离决定.
-///
-/// ```text
-/// (x0,y0)---t-----(x1,y0)
-///    |              |
-///    s    (x,y)     |
-///    |              |
-/// (x0,y1)---------(x1,y1)
-///
-/// 权重:
-///   w00 = (1-t)*(1-s)   w10 = t*(1-s)
-///   w01 = (1-t)*s       w11 = t*s
-/// ```
-///
-/// 使用 16 位定点数 (精度 1/256) 避免浮点运算.
-#[allow(clippy::too_many_arguments)]
-fn scale_plane_bilinear(
-    src: &[u8],
-    src_stride: usize,
-    src_w: u32,
-    src_h: u32,
-    dst: &mut [u8],
-    dst_stride: usize,
-    dst_w: u32,
-    dst_h: u32,
-    bpp: usize,
-) -> TaoResult<()> {
-    // 预计算水平坐标映射表 (避免内循环中重复计算)
-    let h_map: Vec<(usize, usize, u32)> = (0..dst_w as usize)
-        .map(|dx| map_coord(dx, dst_w, src_w))
-        .collect();
-
-    for dy in 0..dst_h as usize {
-        let (sy0, sy1, frac_y) = map_coord(dy, dst_h, src_h);
-        let inv_y = 256 - frac_y;
-
-        let src_row0 = sy0 * src_stride;
-        let src_row1 = sy1 * src_stride;
-        let dst_row = dy * dst_stride;
-
-        for (dx, &(sx0, sx1, frac_x)) in h_map.iter().enumerate() {
-            let inv_x = 256 - frac_x;
-
-            // 权重 (定点数, 和 = 256*256 = 65536)
-            let w00 = inv_x * inv_y;
-            let w10 = frac_x * inv_y;
-            let w01 = inv_x * frac_y;
-            let w11 = frac_x * frac_y;
-
-            let off00 = src_row0 + sx0 * bpp;
-            let off10 = src_row0 + sx1 * bpp;
-            let off01 = src_row1 + sx0 * bpp;
-            let off11 = src_row1 + sx1 * bpp;
-            let dst_off = dst_row + dx * bpp;
-
-            for c in 0..bpp {
-                let v = (u32::from(src[off00 + c]) * w00
-                    + u32::from(src[off10 + c]) * w10
-                    + u32::from(src[off01 + c]) * w01
-                    + u32::from(src[off11 + c]) * w11
-                    + 32768) // 四舍五入
-                    >> 16;
-                dst[dst_off + c] = v as u8;
+                // 垂直滤波
+                let total_shift = LANCZOS_SHIFT * 2;
+                let total_round = 1i64 << (total_shift - 1);
+                for dy in rows {
+                    let (idx, w) = v.taps_of(dy);
+                    let dst_row = (dy - first_row) * dst_stride;
+                    for (i, out) in dst[dst_row..dst_row + row_len].iter_mut().enumerate() {
+                        let sum: i64 = idx
+                            .iter()
+                            .zip(w)
+                            .map(|(&sy, &wk)| tmp[sy * row_len + i] as i64 * wk as i64)
+                            .sum();
+                        *out = ((sum + total_round) >> total_shift).clamp(0, 255) as u8;
+                    }
+                }
+            }
+            PlaneFilter::Area {
+                xs,
+                ys,
+                src_w,
+                src_h,
+            } => {
+                for dy in rows {
+                    let (sy0, sy1) = ys[dy];
+                    let dst_row = (dy - first_row) * dst_stride;
+
+                    for (dx, &(sx0, sx1)) in xs.iter().enumerate() {
+                        let dst_off = dst_row + dx * bpp;
+
+                        let count = (sx1 - sx0) * (sy1 - sy0);
+                        if count == 0 {
+                            // 边界情况: 取最近像素
+                            let sy = sy0.min(src_h - 1);
+                            let sx = sx0.min(src_w - 1);
+                            let src_off = sy * src_stride + sx * bpp;
+                            dst[dst_off..dst_off + bpp]
+                                .copy_from_slice(&src[src_off..src_off + bpp]);
+                        } else {
+                            let count_u64 = count as u64;
+                            for c in 0..bpp {
+                                let mut sum: u64 = 0;
+                                for sy in sy0..sy1 {
+                                    let src_row = sy * src_stride;
+                                    for sx in sx0..sx1 {
+                                        sum += u64::from(src[src_row + sx * bpp + c]);
+                                    }
+                                }
+                                // 四舍五入
+                                dst[dst_off + c] = ((sum + count_u64 / 2) / count_u64) as u8;
+                            }
+                        }
+                    }
+                }
             }
         }
     }
-    Ok(())
 }
 
 // ============================================================
@@ -327,241 +572,141 @@ fn bicubic_weight(t_256: i32) -> i32 {
     }
 }
 
-/// 双三次插值缩放单个平面
-///
-/// 使用 4x4 邻域的 Catmull-Rom 核进行插值, 可分离实现 (先水平后垂直).
-#[allow(clippy::too_many_arguments)]
-fn scale_plane_bicubic(
-    src: &[u8],
-    src_stride: usize,
-    src_w: u32,
-    src_h: u32,
-    dst: &mut [u8],
-    dst_stride: usize,
-    dst_w: u32,
-    dst_h: u32,
-    bpp: usize,
-) -> TaoResult<()> {
-    let max_x = src_w as i32 - 1;
-    let max_y = src_h as i32 - 1;
-
-    // 预计算水平映射
-    let h_map: Vec<(i32, i32)> = (0..dst_w as usize)
-        .map(|dx| map_coord_float(dx, dst_w, src_w))
-        .collect();
-
-    for dy in 0..dst_h as usize {
-        let (src_y, frac_y) = map_coord_float(dy, dst_h, src_h);
-        let dst_row = dy * dst_stride;
-
-        for (dx, &(src_x, frac_x)) in h_map.iter().enumerate() {
-            let dst_off = dst_row + dx * bpp;
-
-            for c in 0..bpp {
-                let mut sum: i32 = 0;
-                let mut weight_sum: i32 = 0;
-
-                for ky in -1..=2i32 {
-                    let sy = (src_y + ky).clamp(0, max_y) as usize;
-                    let wy = bicubic_weight((ky * 256 - frac_y).abs());
-
-                    for kx in -1..=2i32 {
-                        let sx = (src_x + kx).clamp(0, max_x) as usize;
-                        let wx = bicubic_weight((kx * 256 - frac_x).abs());
-
-                        let w = (wy * wx) >> 8;
-                        let pixel = src[sy * src_stride + sx * bpp + c] as i32;
-                        sum += pixel * w;
-                        weight_sum += w;
-                    }
-                }
-
-                let val = if weight_sum > 0 {
-                    ((sum + weight_sum / 2) / weight_sum).clamp(0, 255)
-                } else {
-                    0
-                };
-                dst[dst_off + c] = val as u8;
-            }
-        }
-    }
-    Ok(())
-}
-
 // ============================================================
 // Lanczos 插值
 // ============================================================
 
-/// Lanczos 窗口大小
-const LANCZOS_A: i32 = 3;
+/// Lanczos 权重定点精度 (权重之和为 1 << LANCZOS_SHIFT)
+const LANCZOS_SHIFT: u32 = 14;
 
-/// Lanczos 核函数: sinc(x) * sinc(x/a)
-///
-/// 使用查表 + 线性插值近似 sinc 函数, a=3.
-fn lanczos_weight(t_256: i32) -> i32 {
-    let t_abs = t_256.unsigned_abs();
-    if t_abs == 0 {
-        return 256;
+/// Lanczos 核函数: sinc(x) * sinc(x / lobes), |x| < lobes
+fn lanczos_kernel(x: f64, lobes: f64) -> f64 {
+    let x = x.abs();
+    if x < 1e-9 {
+        return 1.0;
     }
-    let a_256 = (LANCZOS_A as u32) * 256;
-    if t_abs >= a_256 {
-        return 0;
+    if x >= lobes {
+        return 0.0;
     }
-
-    // sinc(x) = sin(π*x) / (π*x)
-    // 使用浮点计算 (足够快, 在缩放中不是瓶颈)
-    let x = t_abs as f64 / 256.0;
     let pi_x = std::f64::consts::PI * x;
-    let pi_x_a = pi_x / LANCZOS_A as f64;
-    let sinc_x = pi_x.sin() / pi_x;
-    let sinc_x_a = pi_x_a.sin() / pi_x_a;
-    let w = sinc_x * sinc_x_a;
-
-    (w * 256.0) as i32
+    let pi_x_a = pi_x / lobes;
+    (pi_x.sin() / pi_x) * (pi_x_a.sin() / pi_x_a)
 }
 
-/// Lanczos 插值缩放单个平面
+/// 一维 Lanczos 滤波权重表
 ///
-/// 使用 2a x 2a 邻域 (默认 a=3, 即 6x6 窗口).
-/// 质量最高但计算量最大.
-#[allow(clippy::too_many_arguments)]
-fn scale_plane_lanczos(
-    src: &[u8],
-    src_stride: usize,
-    src_w: u32,
-    src_h: u32,
-    dst: &mut [u8],
-    dst_stride: usize,
-    dst_w: u32,
-    dst_h: u32,
-    bpp: usize,
-) -> TaoResult<()> {
-    let max_x = src_w as i32 - 1;
-    let max_y = src_h as i32 - 1;
-
-    let h_map: Vec<(i32, i32)> = (0..dst_w as usize)
-        .map(|dx| map_coord_float(dx, dst_w, src_w))
-        .collect();
-
-    let a = LANCZOS_A;
-
-    for dy in 0..dst_h as usize {
-        let (src_y, frac_y) = map_coord_float(dy, dst_h, src_h);
-        let dst_row = dy * dst_stride;
-
-        for (dx, &(src_x, frac_x)) in h_map.iter().enumerate() {
-            let dst_off = dst_row + dx * bpp;
-
-            for c in 0..bpp {
-                let mut sum: i32 = 0;
-                let mut weight_sum: i32 = 0;
-
-                for ky in (1 - a)..=a {
-                    let sy = (src_y + ky).clamp(0, max_y) as usize;
-                    let wy = lanczos_weight((ky * 256 - frac_y).abs());
-
-                    for kx in (1 - a)..=a {
-                        let sx = (src_x + kx).clamp(0, max_x) as usize;
-                        let wx = lanczos_weight((kx * 256 - frac_x).abs());
+/// 每个目标像素对应 `taps` 个源像素索引 (已 clamp 到有效范围) 和定点权重.
+struct LanczosTable {
+    taps: usize,
+    indices: Vec<usize>,
+    weights: Vec<i32>,
+}
 
-                        let w = (wy * wx) >> 8;
-                        let pixel = src[sy * src_stride + sx * bpp + c] as i32;
-                        sum += pixel * w;
-                        weight_sum += w;
-                    }
-                }
+impl LanczosTable {
+    /// 预计算 `src_size` → `dst_size` 的权重表
+    ///
+    /// 缩小时按缩放比例展宽核函数, 起到低通抗锯齿作用.
+    fn new(src_size: u32, dst_size: u32, lobes: usize, phase: u32) -> Self {
+        let scale = src_size as f64 / dst_size as f64;
+        let filter_scale = scale.max(1.0);
+        let support = lobes as f64 * filter_scale;
+        let taps = (support.ceil() as usize) * 2;
+        let max_idx = src_size as i64 - 1;
+        let one = 1i32 << LANCZOS_SHIFT;
+
+        let mut indices = Vec::with_capacity(dst_size as usize * taps);
+        let mut weights = Vec::with_capacity(dst_size as usize * taps);
+        let mut row = vec![0f64; taps];
+
+        for d in 0..dst_size as usize {
+            // src_pos = (dst_idx + φ) * scale - φ, 中心对齐时 φ = 0.5
+            let phase = f64::from(phase) / 256.0;
+            let center = (d as f64 + phase) * scale - phase;
+            let first = (center - support).floor() as i64 + 1;
+
+            let mut total = 0.0;
+            for (k, w) in row.iter_mut().enumerate() {
+                let pos = (first + k as i64) as f64;
+                *w = lanczos_kernel((pos - center) / filter_scale, lobes as f64);
+                total += *w;
+            }
 
-                let val = if weight_sum > 0 {
-                    ((sum + weight_sum / 2) / weight_sum).clamp(0, 255)
-                } else {
-                    0
-                };
-                dst[dst_off + c] = val as u8;
+            // 归一化为定点权重, 舍入误差补到权重最大的采样点上
+            let base = weights.len();
+            for (k, &w) in row.iter().enumerate() {
+                weights.push((w / total * one as f64).round() as i32);
+                indices.push((first + k as i64).clamp(0, max_idx) as usize);
             }
+            let fixed = &mut weights[base..];
+            let fixed_sum: i32 = fixed.iter().sum();
+            if let Some(peak) = fixed.iter_mut().max() {
+                *peak += one - fixed_sum;
+            }
+        }
+
+        Self {
+            taps,
+            indices,
+            weights,
         }
     }
-    Ok(())
-}
 
-// ============================================================
-// Area 平均 (Box Filter)
-// ============================================================
+    /// 目标像素 `d` 的源索引与权重
+    #[inline]
+    fn taps_of(&self, d: usize) -> (&[usize], &[i32]) {
+        let off = d * self.taps;
+        (
+            &self.indices[off..off + self.taps],
+            &self.weights[off..off + self.taps],
+        )
+    }
+}
 
-/// Area 缩放单个平面
-///
-/// 对每个目标像素, 计算其对应的源矩形, 对该矩形内所有源像素取平均.
-/// 适合缩小 (downscale), 可避免锯齿.
+/// 使用 Lanczos 算法缩放图像
 ///
-/// 放大时每个目标像素对应 < 1 个源像素, 无意义, 退化为双线性插值.
+/// 与 [`scale_image`] 相同, 但可指定 Lanczos 核的瓣数 `lobes` (常用 2 或 3).
 #[allow(clippy::too_many_arguments)]
-fn scale_plane_area(
-    src: &[u8],
-    src_stride: usize,
-    src_w: u32,
-    src_h: u32,
-    dst: &mut [u8],
-    dst_stride: usize,
-    dst_w: u32,
-    dst_h: u32,
-    bpp: usize,
+pub fn scale_image_lanczos(
+    src_data: &[&[u8]],
+    src_linesize: &[usize],
+    src_width: u32,
+    src_height: u32,
+    format: PixelFormat,
+    dst_data: &mut [&mut [u8]],
+    dst_linesize: &[usize],
+    dst_width: u32,
+    dst_height: u32,
+    lobes: usize,
 ) -> TaoResult<()> {
-    // 放大时退化为双线性
-    if src_w < dst_w || src_h < dst_h {
-        return scale_plane_bilinear(
-            src, src_stride, src_w, src_h, dst, dst_stride, dst_w, dst_h, bpp,
-        );
-    }
-
-    for dy in 0..dst_h as usize {
-        let sy0 = (dy * src_h as usize) / dst_h as usize;
-        let sy1 = (((dy + 1) * src_h as usize) / dst_h as usize).min(src_h as usize);
-
-        let dst_row = dy * dst_stride;
-
-        for dx in 0..dst_w as usize {
-            let sx0 = (dx * src_w as usize) / dst_w as usize;
-            let sx1 = (((dx + 1) * src_w as usize) / dst_w as usize).min(src_w as usize);
-
-            let dst_off = dst_row + dx * bpp;
-
-            let count = (sx1 - sx0) * (sy1 - sy0);
-            if count == 0 {
-                // 边界情况: 取最近像素
-                let sy = sy0.min(src_h as usize - 1);
-                let sx = sx0.min(src_w as usize - 1);
-                let src_off = sy * src_stride + sx * bpp;
-                dst[dst_off..dst_off + bpp].copy_from_slice(&src[src_off..src_off + bpp]);
-            } else {
-                let count_u64 = count as u64;
-                for c in 0..bpp {
-                    let mut sum: u64 = 0;
-                    for sy in sy0..sy1 {
-                        let src_row = sy * src_stride;
-                        for sx in sx0..sx1 {
-                            sum += u64::from(src[src_row + sx * bpp + c]);
-                        }
-                    }
-                    // 四舍五入
-                    let avg = ((sum + count_u64 / 2) / count_u64) as u8;
-                    dst[dst_off + c] = avg;
-                }
-            }
-        }
-    }
-    Ok(())
+    scale_image(
+        src_data,
+        src_linesize,
+        src_width,
+        src_height,
+        format,
+        dst_data,
+        dst_linesize,
+        dst_width,
+        dst_height,
+        ScaleAlgorithm::Lanczos { lobes },
+    )
 }
 
 // ============================================================
 // 坐标映射工具
 // ============================================================
 
+/// 中心对齐的采样相位 (1/256 像素)
+const CENTER_PHASE: u32 = 128;
+
 /// 将目标坐标映射到源坐标 (返回整数部分和小数部分*256)
 ///
 /// 用于 Bicubic/Lanczos 需要负偏移采样点的情况.
-fn map_coord_float(dst_idx: usize, dst_size: u32, src_size: u32) -> (i32, i32) {
-    // 中心对齐: src_pos = (dst_idx + 0.5) * src_size / dst_size - 0.5
+fn map_coord_float(dst_idx: usize, dst_size: u32, src_size: u32, phase: u32) -> (i32, i32) {
+    // src_pos = (dst_idx + φ) * src_size / dst_size - φ, 中心对齐时 φ = 0.5
+    let phase = i64::from(phase);
     let src_pos_256 =
-        ((dst_idx as i64 * 2 + 1) * src_size as i64 * 128 / dst_size as i64 - 128) as i32;
+        ((dst_idx as i64 * 256 + phase) * src_size as i64 / dst_size as i64 - phase) as i32;
 
     let idx = src_pos_256 >> 8;
     let frac = src_pos_256 & 0xFF;
@@ -576,10 +721,11 @@ fn map_coord_float(dst_idx: usize, dst_size: u32, src_size: u32) -> (i32, i32) {
 /// - `idx1`: 右/下采样点索引 (已 clamp)
 /// - `frac`: 小数部分 (0..256 定点数)
 #[inline]
-fn map_coord(dst_idx: usize, dst_size: u32, src_size: u32) -> (usize, usize, u32) {
-    // 使用中心对齐映射: src_pos = (dst_idx + 0.5) * src_size / dst_size - 0.5
+fn map_coord(dst_idx: usize, dst_size: u32, src_size: u32, phase: u32) -> (usize, usize, u32) {
+    // src_pos = (dst_idx + φ) * src_size / dst_size - φ, 中心对齐时 φ = 0.5
+    let phase = u64::from(phase);
     let src_pos_256 =
-        ((dst_idx as u64 * 2 + 1) * src_size as u64 * 128 / dst_size as u64).saturating_sub(128);
+        ((dst_idx as u64 * 256 + phase) * src_size as u64 / dst_size as u64).saturating_sub(phase);
 
     let idx0 = (src_pos_256 >> 8) as usize;
     let frac = (src_pos_256 & 0xFF) as u32;
@@ -781,19 +927,31 @@ mod tests {
     #[test]
     fn test_map_coord_boundary() {
         // 1:1 映射
-        let (i0, i1, _frac) = map_coord(0, 4, 4);
+        let (i0, i1, _frac) = map_coord(0, 4, 4, CENTER_PHASE);
         assert!(i0 < 4);
         assert!(i1 < 4);
 
         // 放大: dst=8, src=4
-        let (i0, _, _) = map_coord(7, 8, 4);
+        let (i0, _, _) = map_coord(7, 8, 4, CENTER_PHASE);
         assert!(i0 < 4, "索引不应越界: i0={i0}");
 
         // 缩小: dst=2, src=8
-        let (i0, i1, _) = map_coord(1, 2, 8);
+        let (i0, i1, _) = map_coord(1, 2, 8, CENTER_PHASE);
         assert!(i0 < 8 && i1 < 8);
     }
 
+    #[test]
+    fn test_map_coord_phase() {
+        // 2 倍放大, 中心对齐: 目标 1 -> 源 0.25
+        assert_eq!(map_coord(1, 8, 4, CENTER_PHASE), (0, 1, 64));
+        // 4:2:0 色度共址: φ = 0.25, 目标 1 -> 源 0.375
+        let cosited = ChromaPosition::CoSited.phase(1);
+        assert_eq!(map_coord(1, 8, 4, cosited), (0, 1, 96));
+        assert_eq!(map_coord_float(3, 8, 4, cosited), (1, 96));
+        // 无子采样的轴两种位置一致
+        assert_eq!(ChromaPosition::CoSited.phase(0), CENTER_PHASE);
+    }
+
     #[test]
     fn test_bicubic_upscale_2x_gray() {
         let src = [0u8, 100, 200, 50];
@@ -895,7 +1053,7 @@ mod tests {
             &[4],
             4,
             4,
-            ScaleAlgorithm::Lanczos,
+            ScaleAlgorithm::Lanczos { lobes: 3 },
         )
         .unwrap();
 
@@ -923,7 +1081,7 @@ mod tests {
             &[2],
             2,
             2,
-            ScaleAlgorithm::Lanczos,
+            ScaleAlgorithm::Lanczos { lobes: 3 },
         )
         .unwrap();
 
@@ -947,7 +1105,7 @@ mod tests {
             &[4],
             4,
             4,
-            ScaleAlgorithm::Lanczos,
+            ScaleAlgorithm::Lanczos { lobes: 3 },
         )
         .unwrap();
 
@@ -975,7 +1133,7 @@ mod tests {
             &[24],
             8,
             8,
-            ScaleAlgorithm::Lanczos,
+            ScaleAlgorithm::Lanczos { lobes: 3 },
         )
         .unwrap();
 
@@ -1183,4 +1341,102 @@ mod tests {
             assert_eq!(v, 200, "均匀色 200 缩小后应保持 200");
         }
     }
+
+    /// 4 倍放大 16x16 的 45° 对角线, 返回对角线上 (中段) 的像素值
+    fn upscale_diagonal_line(algorithm: ScaleAlgorithm) -> Vec<f64> {
+        const N: usize = 16;
+        const F: usize = 4;
+        let mut src = vec![0u8; N * N];
+        for i in 0..N {
+            src[i * N + i] = 255;
+        }
+        let m = N * F;
+        let mut dst = vec![0u8; m * m];
+        scale_image(
+            &[&src],
+            &[N],
+            N as u32,
+            N as u32,
+            PixelFormat::Gray8,
+            &mut [&mut dst],
+            &[m],
+            m as u32,
+            m as u32,
+            algorithm,
+        )
+        .unwrap();
+        // 跳过边缘, 避免边界 clamp 干扰
+        (2 * F..m - 2 * F).map(|i| dst[i * m + i] as f64).collect()
+    }
+
+    fn mean_and_std(values: &[f64]) -> (f64, f64) {
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        (mean, var.sqrt())
+    }
+
+    #[test]
+    fn test_lanczos_diagonal_line_less_staircasing_than_bilinear() {
+        let (bl_mean, bl_std) = mean_and_std(&upscale_diagonal_line(ScaleAlgorithm::Bilinear));
+        let (lz_mean, lz_std) = mean_and_std(&upscale_diagonal_line(ScaleAlgorithm::lanczos()));
+
+        // 阶梯效应表现为沿线亮度周期性起伏
+        assert!(
+            lz_std < bl_std,
+            "Lanczos 沿对角线亮度起伏应小于双线性: lanczos={lz_std:.2}, bilinear={bl_std:.2}"
+        );
+        assert!(
+            lz_mean > bl_mean,
+            "Lanczos 对角线应更锐利: lanczos={lz_mean:.1}, bilinear={bl_mean:.1}"
+        );
+    }
+
+    #[test]
+    fn test_lanczos_lobes_configurable() {
+        let src: Vec<u8> = (0..8 * 8).map(|i| (i * 4) as u8).collect();
+        let mut dst2 = vec![0u8; 16 * 16];
+        let mut dst3 = vec![0u8; 16 * 16];
+        scale_image_lanczos(
+            &[&src],
+            &[8],
+            8,
+            8,
+            PixelFormat::Gray8,
+            &mut [&mut dst2],
+            &[16],
+            16,
+            16,
+            2,
+        )
+        .unwrap();
+        scale_image_lanczos(
+            &[&src],
+            &[8],
+            8,
+            8,
+            PixelFormat::Gray8,
+            &mut [&mut dst3],
+            &[16],
+            16,
+            16,
+            3,
+        )
+        .unwrap();
+        assert_ne!(dst2, dst3, "不同瓣数应得到不同结果");
+
+        let mut dst = vec![0u8; 16 * 16];
+        let err = scale_image_lanczos(
+            &[&src],
+            &[8],
+            8,
+            8,
+            PixelFormat::Gray8,
+            &mut [&mut dst],
+            &[16],
+            16,
+            16,
+            0,
+        );
+        assert!(err.is_err(), "瓣数为 0 应报错");
+    }
 }
@@ -161,6 +161,7 @@ fn decode_aac_with_tao(path: &str) -> DecodeResult {
             channel_layout,
             sample_format,
             frame_size,
+            block_align: 0,
         }),
     };
 
@@ -162,6 +162,7 @@ fn decode_flac_with_tao(path: &str) -> DecodeResult {
             channel_layout,
             sample_format,
             frame_size: 0,
+            block_align: 0,
         }),
     };
 
@@ -97,6 +97,7 @@ fn decode_mp3_with_tao(path: &str) -> DecodeResult {
             channel_layout,
             sample_format: SampleFormat::F32,
             frame_size: 1152,
+            block_align: 0,
         }),
     };
 
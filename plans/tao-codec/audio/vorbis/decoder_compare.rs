@@ -629,6 +629,7 @@ fn decode_vorbis_with_tao(path: &str) -> DecodeResult {
             channel_layout,
             sample_format: SampleFormat::F32,
             frame_size: 0,
+            block_align: 0,
         }),
     };
 
@@ -0,0 +1,544 @@
+#!/usr/bin/env python3
+"""生成 H.264 CABAC I 帧黄金测试向量.
+
+输出 64x48 Main Profile AnnexB 裸流 (2 个 IDR 帧), 全部宏块为 I_16x16,
+覆盖 V/H/DC/Plane 四种 16x16 预测、亮度 DC 残差与色度 DC 残差的 CABAC 编码.
+去块滤波关闭, 脚本同时按标准流程计算参考重建图像, 用于核对解码器输出.
+
+用法:
+    python3 scripts/gen_golden_h264_cabac.py <输出 .h264> [参考 .yuv]
+
+CABAC 状态表与 I slice 上下文初始化参数取自解码器源码 cabac.rs, 与标准表 9-12 ~ 9-33 一致.
+"""
+
+import re
+import sys
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parent.parent
+CABAC_RS = ROOT / "crates/tao-codec/src/decoders/h264/cabac.rs"
+
+WIDTH_MBS = 4
+HEIGHT_MBS = 3
+QP = 26
+
+
+# ============================================================
+# CABAC 表
+# ============================================================
+
+
+def load_tables():
+    src = CABAC_RS.read_text(encoding="utf-8")
+
+    def block(name):
+        m = re.search(r"const " + name + r":[^=]*=\s*\[(.*?)\];", src, re.S)
+        body = re.sub(r"//[^\n]*", "", m.group(1))
+        return [int(v) for v in re.findall(r"-?\d+", body)]
+
+    lps = block("RANGE_TAB_LPS")
+    range_lps = [lps[i * 4 : i * 4 + 4] for i in range(64)]
+    trans_lps = block("TRANS_IDX_LPS")
+    trans_mps = block("TRANS_IDX_MPS")
+    init = block("CABAC_INIT_I")
+    init_i = [(init[i * 2], init[i * 2 + 1]) for i in range(len(init) // 2)]
+    return range_lps, trans_lps, trans_mps, init_i
+
+
+RANGE_LPS, TRANS_LPS, TRANS_MPS, INIT_I = load_tables()
+
+
+class Ctx:
+    def __init__(self, m, n, qp):
+        pre = max(1, min(126, ((m * qp) >> 4) + n))
+        if pre <= 63:
+            self.state, self.mps = 63 - pre, 0
+        else:
+            self.state, self.mps = pre - 64, 1
+
+
+class BitWriter:
+    def __init__(self):
+        self.bits = []
+
+    def u(self, n, v):
+        for i in range(n - 1, -1, -1):
+            self.bits.append((v >> i) & 1)
+
+    def ue(self, v):
+        v += 1
+        n = v.bit_length()
+        self.u(n - 1, 0)
+        self.u(n, v)
+
+    def se(self, v):
+        self.ue(2 * v - 1 if v > 0 else -2 * v)
+
+    def trailing(self):
+        self.bits.append(1)
+        self.align(0)
+
+    def align(self, bit):
+        while len(self.bits) % 8:
+            self.bits.append(bit)
+
+    def bytes(self):
+        assert len(self.bits) % 8 == 0
+        out = bytearray()
+        for i in range(0, len(self.bits), 8):
+            b = 0
+            for bit in self.bits[i : i + 8]:
+                b = (b << 1) | bit
+            out.append(b)
+        return bytes(out)
+
+
+class CabacEncoder:
+    """标准 9.3.4 算术编码器"""
+
+    def __init__(self, bw):
+        self.bw = bw
+        self.low = 0
+        self.range = 510
+        self.first = True
+        self.outstanding = 0
+        self.ctx = [Ctx(m, n, QP) for m, n in INIT_I]
+
+    def put_bit(self, b):
+        if self.first:
+            self.first = False
+        else:
+            self.bw.bits.append(b)
+        while self.outstanding > 0:
+            self.bw.bits.append(1 - b)
+            self.outstanding -= 1
+
+    def renorm(self):
+        while self.range < 256:
+            if self.low < 256:
+                self.put_bit(0)
+            elif self.low >= 512:
+                self.low -= 512
+                self.put_bit(1)
+            else:
+                self.low -= 256
+                self.outstanding += 1
+            self.range <<= 1
+            self.low <<= 1
+
+    def decision(self, idx, b):
+        c = self.ctx[idx]
+        r_lps = RANGE_LPS[c.state][(self.range >> 6) & 3]
+        self.range -= r_lps
+        if b != c.mps:
+            self.low += self.range
+            self.range = r_lps
+            if c.state == 0:
+                c.mps = 1 - c.mps
+            c.state = TRANS_LPS[c.state]
+        else:
+            c.state = TRANS_MPS[c.state]
+        self.renorm()
+
+    def bypass(self, b):
+        self.low <<= 1
+        if b:
+            self.low += self.range
+        if self.low >= 1024:
+            self.put_bit(1)
+            self.low -= 1024
+        elif self.low < 512:
+            self.put_bit(0)
+        else:
+            self.low -= 512
+            self.outstanding += 1
+
+    def terminate(self, b):
+        self.range -= 2
+        if b:
+            self.low += self.range
+            self.range = 2
+            self.renorm()
+            self.put_bit((self.low >> 9) & 1)
+            self.bw.u(2, ((self.low >> 7) & 3) | 1)
+        else:
+            self.renorm()
+
+
+# ============================================================
+# 残差编码 (9.3.2.3 / 9.3.3.1.3)
+# ============================================================
+
+CBF_OFFSET = {0: 85 + 0, 3: 85 + 12}
+SIG_OFFSET = {0: 105 + 0, 3: 105 + 44}
+LAST_OFFSET = {0: 166 + 0, 3: 166 + 44}
+ABS_OFFSET = {0: 227 + 0, 3: 227 + 30}
+
+
+def encode_residual(enc, cat, coeffs, cbf_inc):
+    """编码一个残差块, coeffs 为扫描顺序系数"""
+    nonzero = [i for i, v in enumerate(coeffs) if v != 0]
+    coded = 1 if nonzero else 0
+    enc.decision(CBF_OFFSET[cat] + cbf_inc, coded)
+    if not coded:
+        return 0
+    num = len(coeffs)
+    last = nonzero[-1]
+    for i in range(num - 1):
+        inc = min(i, 2) if cat == 3 else i
+        sig = 1 if coeffs[i] != 0 else 0
+        enc.decision(SIG_OFFSET[cat] + inc, sig)
+        if sig:
+            enc.decision(LAST_OFFSET[cat] + inc, 1 if i == last else 0)
+            if i == last:
+                break
+    eq1 = 0
+    gt1 = 0
+    for i in reversed(nonzero):
+        v = abs(coeffs[i]) - 1
+        inc0 = 0 if gt1 else min(4, 1 + eq1)
+        incn = 5 + min(4 - (1 if cat == 3 else 0), gt1)
+        prefix = min(v, 14)
+        for k in range(prefix):
+            enc.decision(ABS_OFFSET[cat] + (inc0 if k == 0 else incn), 1)
+        if prefix < 14:
+            enc.decision(ABS_OFFSET[cat] + (inc0 if prefix == 0 else incn), 0)
+        else:
+            # UEG0 后缀 (旁路)
+            suf = v - 14
+            k = 0
+            while suf >= (1 << k):
+                enc.bypass(1)
+                suf -= 1 << k
+                k += 1
+            enc.bypass(0)
+            while k > 0:
+                k -= 1
+                enc.bypass((suf >> k) & 1)
+        enc.bypass(1 if coeffs[i] < 0 else 0)
+        if v == 0:
+            eq1 += 1
+        else:
+            gt1 += 1
+    return 1
+
+
+# ============================================================
+# 重建模型
+# ============================================================
+
+ZIGZAG = [(0, 0), (0, 1), (1, 0), (2, 0), (1, 1), (0, 2), (0, 3), (1, 2),
+          (2, 1), (3, 0), (3, 1), (2, 2), (1, 3), (2, 3), (3, 2), (3, 3)]
+HAD4 = [[1, 1, 1, 1], [1, 1, -1, -1], [1, -1, -1, 1], [1, -1, 1, -1]]
+LEVEL_SCALE = [10, 11, 13, 14, 16, 18]
+
+
+def matmul(a, b):
+    return [[sum(a[i][k] * b[k][j] for k in range(len(b))) for j in range(len(b[0]))]
+            for i in range(len(a))]
+
+
+def clip1(v):
+    return max(0, min(255, v))
+
+
+def luma_dc_residual(levels):
+    c = [[0] * 4 for _ in range(4)]
+    for idx, (i, j) in enumerate(ZIGZAG):
+        c[i][j] = levels[idx]
+    f = matmul(matmul(HAD4, c), HAD4)
+    ls = 16 * LEVEL_SCALE[QP % 6]
+    shift = QP // 6
+    assert QP < 36
+    dc = [[(f[i][j] * ls + (1 << (5 - shift))) >> (6 - shift) for j in range(4)]
+          for i in range(4)]
+    # 仅 DC 的 4x4 反变换: 全部样点为 (d + 32) >> 6
+    return [[(dc[i][j] + 32) >> 6 for j in range(4)] for i in range(4)]
+
+
+def chroma_dc_residual(levels):
+    c = [[levels[0], levels[1]], [levels[2], levels[3]]]
+    h = [[1, 1], [1, -1]]
+    f = matmul(matmul(h, c), h)
+    qpc = QP  # chroma_qp_index_offset = 0 且 QP < 30
+    ls = 16 * LEVEL_SCALE[qpc % 6]
+    dc = [[((f[i][j] * ls) << (qpc // 6)) >> 5 for j in range(2)] for i in range(2)]
+    return [[(dc[i][j] + 32) >> 6 for j in range(2)] for i in range(2)]
+
+
+def predict_luma(plane, mx, my, mode):
+    x0, y0 = mx * 16, my * 16
+    has_top, has_left = my > 0, mx > 0
+    top = [plane[y0 - 1][x0 + x] for x in range(16)] if has_top else None
+    left = [plane[y0 + y][x0 - 1] for y in range(16)] if has_left else None
+    if mode == 0:
+        assert has_top
+        return [[top[x] for x in range(16)] for _ in range(16)]
+    if mode == 1:
+        assert has_left
+        return [[left[y]] * 16 for y in range(16)]
+    if mode == 2:
+        if has_top and has_left:
+            dc = (sum(top) + sum(left) + 16) >> 5
+        elif has_left:
+            dc = (sum(left) + 8) >> 4
+        elif has_top:
+            dc = (sum(top) + 8) >> 4
+        else:
+            dc = 128
+        return [[dc] * 16 for _ in range(16)]
+    assert has_top and has_left
+    tl = plane[y0 - 1][x0 - 1]
+
+    def p_top(x):
+        return tl if x < 0 else top[x]
+
+    def p_left(y):
+        return tl if y < 0 else left[y]
+
+    hh = sum((x + 1) * (p_top(8 + x) - p_top(6 - x)) for x in range(8))
+    vv = sum((y + 1) * (p_left(8 + y) - p_left(6 - y)) for y in range(8))
+    a = 16 * (left[15] + top[15])
+    b = (5 * hh + 32) >> 6
+    c = (5 * vv + 32) >> 6
+    return [[clip1((a + b * (x - 7) + c * (y - 7) + 16) >> 5) for x in range(16)]
+            for y in range(16)]
+
+
+def predict_chroma_dc(plane, mx, my):
+    x0, y0 = mx * 8, my * 8
+    has_top, has_left = my > 0, mx > 0
+    pred = [[0] * 8 for _ in range(8)]
+    for by in range(2):
+        for bx in range(2):
+            xo, yo = bx * 4, by * 4
+            st = sum(plane[y0 - 1][x0 + xo + i] for i in range(4)) if has_top else None
+            sl = sum(plane[y0 + yo + i][x0 - 1] for i in range(4)) if has_left else None
+            if (xo, yo) in ((0, 0), (4, 4)):
+                if st is not None and sl is not None:
+                    v = (st + sl + 4) >> 3
+                elif sl is not None:
+                    v = (sl + 2) >> 2
+                elif st is not None:
+                    v = (st + 2) >> 2
+                else:
+                    v = 128
+            elif (xo, yo) == (4, 0):
+                if st is not None:
+                    v = (st + 2) >> 2
+                elif sl is not None:
+                    v = (sl + 2) >> 2
+                else:
+                    v = 128
+            else:
+                if sl is not None:
+                    v = (sl + 2) >> 2
+                elif st is not None:
+                    v = (st + 2) >> 2
+                else:
+                    v = 128
+            for y in range(4):
+                for x in range(4):
+                    pred[yo + y][xo + x] = v
+    return pred
+
+
+# ============================================================
+# 帧内容
+# ============================================================
+
+
+def mb_plan(frame, mx, my):
+    """返回 (16x16 预测模式, 亮度 DC 系数, Cb DC 系数, Cr DC 系数)"""
+    seed = frame * 37 + my * 11 + mx * 5
+    if mx == 0 and my == 0:
+        mode = 2
+    elif my == 0:
+        mode = 1 if (mx + frame) % 2 else 2
+    elif mx == 0:
+        mode = 0 if (my + frame) % 2 else 2
+    else:
+        mode = [3, 0, 1, 2][(seed // 3) % 4]
+    levels = [0] * 16
+    if seed % 5 != 3:
+        levels[0] = (seed % 9) - 4 or 6
+        levels[1] = ((seed * 3) % 5) - 2
+        levels[2] = ((seed * 7) % 5) - 2
+        if seed % 4 == 0:
+            levels[5] = 1
+        if seed % 7 == 1:
+            levels[9] = -1
+        if (mx, my) == (1, 1):
+            levels[0] = 20  # 覆盖 UEG0 后缀
+    cb = [0] * 4
+    cr = [0] * 4
+    if seed % 3 != 1:
+        cb = [((seed * 5) % 7) - 3, (seed % 3) - 1, 0, 1 if seed % 2 else 0]
+        cr = [(seed % 5) - 2, 0, ((seed * 3) % 3) - 1, 0]
+    return mode, levels, cb, cr
+
+
+def encode_frame(frame, idr_pic_id):
+    w, h = WIDTH_MBS * 16, HEIGHT_MBS * 16
+    luma = [[0] * w for _ in range(h)]
+    cb_plane = [[0] * (w // 2) for _ in range(h // 2)]
+    cr_plane = [[0] * (w // 2) for _ in range(h // 2)]
+
+    bw = BitWriter()
+    # slice_header
+    bw.ue(0)  # first_mb_in_slice
+    bw.ue(7)  # slice_type: I (全部)
+    bw.ue(0)  # pic_parameter_set_id
+    bw.u(4, 0)  # frame_num
+    bw.ue(idr_pic_id)
+    bw.u(1, 0)  # no_output_of_prior_pics_flag
+    bw.u(1, 0)  # long_term_reference_flag
+    bw.se(0)  # slice_qp_delta
+    bw.ue(1)  # disable_deblocking_filter_idc
+    bw.align(1)  # cabac_alignment_one_bit
+
+    enc = CabacEncoder(bw)
+    luma_cbf = {}
+    chroma_cbf = {}
+    chroma_coded = {}
+    total = WIDTH_MBS * HEIGHT_MBS
+    for addr in range(total):
+        mx, my = addr % WIDTH_MBS, addr // WIDTH_MBS
+        mode, levels, cb, cr = mb_plan(frame, mx, my)
+        chroma_cbp = 1 if any(cb) or any(cr) else 0
+
+        # mb_type: I_16x16, 邻块均为 I_16x16 (condTerm=1), 不可用为 0
+        inc = (1 if mx > 0 else 0) + (1 if my > 0 else 0)
+        enc.decision(3 + inc, 1)
+        enc.terminate(0)
+        enc.decision(3 + 3, 0)  # 亮度 cbp = 0
+        enc.decision(3 + 4, chroma_cbp)
+        if chroma_cbp:
+            enc.decision(3 + 5, 0)  # chroma cbp != 2
+            enc.decision(3 + 6, mode >> 1)
+            enc.decision(3 + 7, mode & 1)
+        else:
+            enc.decision(3 + 6, mode >> 1)
+            enc.decision(3 + 7, mode & 1)
+        # intra_chroma_pred_mode = DC (0), 邻块模式均为 0
+        enc.decision(64, 0)
+        # mb_qp_delta = 0
+        enc.decision(60, 0)
+
+        # 亮度 DC
+        def cbf_term(table, n, comp=None):
+            if n is None:
+                return 1
+            key = n if comp is None else (n, comp)
+            return table.get(key, 0)
+
+        a = (mx - 1, my) if mx > 0 else None
+        b = (mx, my - 1) if my > 0 else None
+        cbf_inc = cbf_term(luma_cbf, a) + 2 * cbf_term(luma_cbf, b)
+        luma_cbf[(mx, my)] = encode_residual(enc, 0, levels, cbf_inc)
+
+        # 色度 DC
+        chroma_coded[(mx, my)] = chroma_cbp
+        if chroma_cbp:
+            for comp, coeffs in ((0, cb), (1, cr)):
+                ta = cbf_term(chroma_cbf, a, comp)
+                tb = cbf_term(chroma_cbf, b, comp)
+                chroma_cbf[((mx, my), comp)] = encode_residual(enc, 3, coeffs, ta + 2 * tb)
+        else:
+            chroma_cbf[((mx, my), 0)] = 0
+            chroma_cbf[((mx, my), 1)] = 0
+
+        enc.terminate(1 if addr == total - 1 else 0)
+
+        # 重建
+        pred = predict_luma(luma, mx, my, mode)
+        res = luma_dc_residual(levels)
+        for y in range(16):
+            for x in range(16):
+                luma[my * 16 + y][mx * 16 + x] = clip1(pred[y][x] + res[y // 4][x // 4])
+        for plane, coeffs in ((cb_plane, cb), (cr_plane, cr)):
+            pred = predict_chroma_dc(plane, mx, my)
+            res = chroma_dc_residual(coeffs)
+            for y in range(8):
+                for x in range(8):
+                    plane[my * 8 + y][mx * 8 + x] = clip1(pred[y][x] + res[y // 4][x // 4])
+
+    bw.align(0)
+    yuv = bytes(v for row in luma for v in row)
+    yuv += bytes(v for row in cb_plane for v in row)
+    yuv += bytes(v for row in cr_plane for v in row)
+    return bw.bytes(), yuv
+
+
+def nal(nal_ref_idc, nal_type, rbsp):
+    out = bytearray(b"\x00\x00\x00\x01")
+    out.append((nal_ref_idc << 5) | nal_type)
+    zeros = 0
+    for byte in rbsp:
+        if zeros >= 2 and byte <= 3:
+            out.append(3)
+            zeros = 0
+        out.append(byte)
+        zeros = zeros + 1 if byte == 0 else 0
+    return bytes(out)
+
+
+def sps():
+    bw = BitWriter()
+    bw.u(8, 77)  # profile_idc: Main
+    bw.u(8, 0)  # constraint flags
+    bw.u(8, 10)  # level_idc
+    bw.ue(0)  # seq_parameter_set_id
+    bw.ue(0)  # log2_max_frame_num_minus4
+    bw.ue(2)  # pic_order_cnt_type
+    bw.ue(1)  # max_num_ref_frames
+    bw.u(1, 0)  # gaps_in_frame_num_value_allowed_flag
+    bw.ue(WIDTH_MBS - 1)
+    bw.ue(HEIGHT_MBS - 1)
+    bw.u(1, 1)  # frame_mbs_only_flag
+    bw.u(1, 1)  # direct_8x8_inference_flag
+    bw.u(1, 0)  # frame_cropping_flag
+    bw.u(1, 0)  # vui_parameters_present_flag
+    bw.trailing()
+    return bw.bytes()
+
+
+def pps():
+    bw = BitWriter()
+    bw.ue(0)  # pic_parameter_set_id
+    bw.ue(0)  # seq_parameter_set_id
+    bw.u(1, 1)  # entropy_coding_mode_flag: CABAC
+    bw.u(1, 0)  # bottom_field_pic_order_in_frame_present_flag
+    bw.ue(0)  # num_slice_groups_minus1
+    bw.ue(0)  # num_ref_idx_l0_default_active_minus1
+    bw.ue(0)  # num_ref_idx_l1_default_active_minus1
+    bw.u(1, 0)  # weighted_pred_flag
+    bw.u(2, 0)  # weighted_bipred_idc
+    bw.se(QP - 26)  # pic_init_qp_minus26
+    bw.se(0)  # pic_init_qs_minus26
+    bw.se(0)  # chroma_qp_index_offset
+    bw.u(1, 1)  # deblocking_filter_control_present_flag
+    bw.u(1, 0)  # constrained_intra_pred_flag
+    bw.u(1, 0)  # redundant_pic_cnt_present_flag
+    bw.trailing()
+    return bw.bytes()
+
+
+def main():
+    if len(sys.argv) < 2:
+        print(__doc__)
+        sys.exit(1)
+    stream = nal(3, 7, sps()) + nal(3, 8, pps())
+    recon = b""
+    for frame in range(2):
+        slice_data, yuv = encode_frame(frame, frame)
+        stream += nal(3, 5, slice_data)
+        recon += yuv
+    Path(sys.argv[1]).write_bytes(stream)
+    if len(sys.argv) > 2:
+        Path(sys.argv[2]).write_bytes(recon)
+    print(f"码流 {len(stream)} 字节, 参考重建 {len(recon)} 字节")
+
+
+if __name__ == "__main__":
+    main()
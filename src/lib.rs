@@ -10,6 +10,9 @@
 //! - **tao-resample**: 音频重采样和格式转换
 //! - **tao-ffi**: C FFI 导出层
 //!
+//! 在此之上, [`transcode`] 模块提供 [`MediaReader`] / [`MediaWriter`] / [`Transcoder`]
+//! 等高层接口.
+//!
 //! ## 使用示例
 //!
 //! ```rust,no_run
@@ -27,6 +30,14 @@
 /// 日志模块 (基于 tracing)
 pub mod logging;
 
+/// 高层转码 API (MediaReader / MediaWriter / Transcoder)
+pub mod transcode;
+
+pub use transcode::{
+    AudioEncodeParams, MediaReader, MediaWriter, ProgressReport, StreamCodec, TranscodeStats,
+    Transcoder, VideoEncodeParams,
+};
+
 /// 核心类型与工具 (对标 libavutil)
 pub use tao_core as core;
 
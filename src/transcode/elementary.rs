@@ -0,0 +1,41 @@
+//! 裸流/基本流 (elementary stream) 输出目标识别.
+//!
+//! `.aac` (ADTS), `.h264` (Annex-B), `.m4v`, `.pcm`, `.yuv` 等输出只能容纳单条流.
+//! 未指定流映射时自动选择第一条匹配类型的流; 输入编解码器与目标格式一致且
+//! 未指定编码器时直接复制, 写出基本流字节.
+
+use tao_codec::CodecId;
+use tao_core::MediaType;
+use tao_format::FormatId;
+use tao_format::stream::Stream;
+
+/// 裸流输出格式对应的媒体类型, 非裸流格式返回 `None`
+pub(crate) fn elementary_media_type(format: FormatId) -> Option<MediaType> {
+    match format {
+        FormatId::AacAdts | FormatId::RawAudio => Some(MediaType::Audio),
+        FormatId::H264Es | FormatId::Mpeg4Es | FormatId::RawVideo => Some(MediaType::Video),
+        _ => None,
+    }
+}
+
+/// 输入编解码器能否不经转码直接写入裸流格式
+pub(crate) fn accepts_codec(format: FormatId, codec_id: CodecId) -> bool {
+    match format {
+        FormatId::AacAdts => codec_id == CodecId::Aac,
+        FormatId::H264Es => codec_id == CodecId::H264,
+        FormatId::Mpeg4Es => codec_id == CodecId::Mpeg4,
+        FormatId::RawAudio => codec_id.name().starts_with("pcm_"),
+        FormatId::RawVideo => codec_id == CodecId::RawVideo,
+        _ => false,
+    }
+}
+
+/// 未指定流映射时为裸流输出选择流: 第一条匹配媒体类型的流
+pub(crate) fn select_stream(format: FormatId, streams: &[Stream]) -> Result<usize, String> {
+    let media_type =
+        elementary_media_type(format).ok_or_else(|| format!("{format} 不是裸流输出格式"))?;
+    streams
+        .iter()
+        .position(|s| s.media_type == media_type)
+        .ok_or_else(|| format!("输入中没有可写入 {format} 裸流的{media_type}流"))
+}
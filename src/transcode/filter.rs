@@ -1,6 +1,10 @@
-use tao_codec::CodecId;
-use tao_core::{Rational, SampleFormat};
+//! 滤镜链解析与滤镜图构建.
+//!
+//! 滤镜链字符串形如 `volume=0.5,fade=in:0:3`, 参数以 `:` 分隔, 对标 ffmpeg `-af` / `-vf`.
+
+use tao_core::Rational;
 use tao_filter::FilterGraph;
+use tracing::{debug, warn};
 
 #[derive(Debug, Clone)]
 pub(crate) struct FilterSpec {
@@ -51,7 +55,7 @@ pub(crate) fn build_audio_filter_graph(filters: &Option<Vec<FilterSpec>>) -> Opt
                     .unwrap_or(1.0);
                 let filter = tao_filter::filters::volume::VolumeFilter::new(gain);
                 graph.add_filter(Box::new(filter));
-                eprintln!("  [af] volume: gain={gain}");
+                debug!("[af] volume: gain={gain}");
             }
             "fade" => {
                 // fade=in:start_sec:duration_sec 或 fade=out:start_sec:duration_sec
@@ -65,10 +69,48 @@ pub(crate) fn build_audio_filter_graph(filters: &Option<Vec<FilterSpec>>) -> Opt
                 };
                 let filter = tao_filter::filters::fade::FadeFilter::new(ft, start, dur);
                 graph.add_filter(Box::new(filter));
-                eprintln!("  [af] fade: type={fade_type}, start={start}s, duration={dur}s");
+                debug!("[af] fade: type={fade_type}, start={start}s, duration={dur}s");
+            }
+            "aformat" => {
+                // aformat=sample_fmts=s16:sample_rates=44100:channel_layouts=stereo
+                // 未带键名时按 sample_fmts:sample_rates:channel_layouts 顺序解析, 列表取首项
+                let mut filter = tao_filter::filters::aformat::AformatFilter::new();
+                let keys = ["sample_fmts", "sample_rates", "channel_layouts"];
+                for (i, arg) in spec.args.iter().enumerate() {
+                    let (key, value) = match arg.split_once('=') {
+                        Some((k, v)) => (k, v),
+                        None => match keys.get(i) {
+                            Some(k) => (*k, arg.as_str()),
+                            None => continue,
+                        },
+                    };
+                    let value = value.split('|').next().unwrap_or("");
+                    match key {
+                        "sample_fmts" => match tao_core::SampleFormat::from_name(value) {
+                            Some(fmt) => filter = filter.with_sample_format(fmt),
+                            None => warn!("[af] aformat: 未知采样格式 {value}, 忽略"),
+                        },
+                        "sample_rates" => match value.parse::<u32>() {
+                            Ok(rate) if rate > 0 => filter = filter.with_sample_rate(rate),
+                            _ => warn!("[af] aformat: 采样率无效 {value}, 忽略"),
+                        },
+                        "channel_layouts" => match tao_core::ChannelLayout::from_name(value) {
+                            Some(layout) => filter = filter.with_channel_layout(layout),
+                            None => warn!("[af] aformat: 未知声道布局 {value}, 忽略"),
+                        },
+                        other => warn!("[af] aformat: 未知参数 {other}, 忽略"),
+                    }
+                }
+                debug!(
+                    "[af] aformat: fmt={:?}, rate={:?}, layout={:?}",
+                    filter.sample_format(),
+                    filter.sample_rate(),
+                    filter.channel_layout(),
+                );
+                graph.add_filter(Box::new(filter));
             }
             other => {
-                eprintln!("  [af] 未知滤镜: {other}, 跳过");
+                warn!("[af] 未知滤镜: {other}, 跳过");
             }
         }
     }
@@ -100,7 +142,7 @@ pub(crate) fn build_video_filter_graph(filters: &Option<Vec<FilterSpec>>) -> Opt
                 if w > 0 && h > 0 {
                     let filter = tao_filter::filters::crop::CropFilter::new(x, y, w, h);
                     graph.add_filter(Box::new(filter));
-                    eprintln!("  [vf] crop: {w}x{h}+{x}+{y}");
+                    debug!("[vf] crop: {w}x{h}+{x}+{y}");
                 }
             }
             "pad" => {
@@ -112,7 +154,43 @@ pub(crate) fn build_video_filter_graph(filters: &Option<Vec<FilterSpec>>) -> Opt
                 if w > 0 && h > 0 {
                     let filter = tao_filter::filters::pad::PadFilter::new(w, h, x, y);
                     graph.add_filter(Box::new(filter));
-                    eprintln!("  [vf] pad: {w}x{h}+{x}+{y}");
+                    debug!("[vf] pad: {w}x{h}+{x}+{y}");
+                }
+            }
+            "scale" => {
+                // scale=width:height[:flags=algo], 宽或高为 -1 时保持宽高比
+                let mut positional = spec.args.iter().filter(|s| !s.contains('='));
+                let w: i32 = positional.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+                let h: i32 = positional.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+                if w == 0 || h == 0 {
+                    warn!("[vf] scale: 参数无效 {:?}, 跳过", spec.args);
+                    continue;
+                }
+                let mut filter = tao_filter::filters::scale::ScaleFilter::new(w, h);
+                if let Some(flags) = spec.args.iter().find_map(|s| s.strip_prefix("flags=")) {
+                    match tao_filter::filters::scale::ScaleFilter::parse_flags(flags) {
+                        Some(algorithm) => filter = filter.with_algorithm(algorithm),
+                        None => warn!("[vf] scale: 未知缩放算法 {flags}, 使用默认算法"),
+                    }
+                }
+                graph.add_filter(Box::new(filter));
+                debug!("[vf] scale: {w}x{h}");
+            }
+            "format" => {
+                // format=rgb24 或 format=pix_fmts=yuv420p|rgb24
+                let list = spec
+                    .args
+                    .first()
+                    .map(|s| s.strip_prefix("pix_fmts=").unwrap_or(s))
+                    .unwrap_or("");
+                match tao_filter::filters::format::FormatFilter::parse_formats(list) {
+                    Ok(formats) => {
+                        debug!("[vf] format: {formats:?}");
+                        let filter =
+                            tao_filter::filters::format::FormatFilter::with_formats(formats);
+                        graph.add_filter(Box::new(filter));
+                    }
+                    Err(e) => warn!("[vf] format: {e}, 跳过"),
                 }
             }
             "fade" => {
@@ -126,10 +204,10 @@ pub(crate) fn build_video_filter_graph(filters: &Option<Vec<FilterSpec>>) -> Opt
                 };
                 let filter = tao_filter::filters::fade::FadeFilter::new(ft, start, dur);
                 graph.add_filter(Box::new(filter));
-                eprintln!("  [vf] fade: type={fade_type}, start={start}s, duration={dur}s");
+                debug!("[vf] fade: type={fade_type}, start={start}s, duration={dur}s");
             }
             other => {
-                eprintln!("  [vf] 未知滤镜: {other}, 跳过");
+                warn!("[vf] 未知滤镜: {other}, 跳过");
             }
         }
     }
@@ -145,72 +223,10 @@ pub(crate) fn build_video_filter_graph(filters: &Option<Vec<FilterSpec>>) -> Opt
 // 解析辅助
 // ============================================================
 
-/// 解析分辨率字符串 (如 "1280x720")
-pub(crate) fn parse_size(s: &str) -> Option<(u32, u32)> {
-    let parts: Vec<&str> = s.split('x').collect();
-    if parts.len() == 2 {
-        let w = parts[0].parse().ok()?;
-        let h = parts[1].parse().ok()?;
-        Some((w, h))
-    } else {
-        None
-    }
-}
-
-/// 解析帧率字符串 (如 "25" 或 "30000/1001")
-pub(crate) fn parse_rate(s: &str) -> Option<Rational> {
-    if let Some(slash) = s.find('/') {
-        let num: i32 = s[..slash].parse().ok()?;
-        let den: i32 = s[slash + 1..].parse().ok()?;
-        Some(Rational::new(num, den))
-    } else {
-        let fps: f64 = s.parse().ok()?;
-        if fps > 0.0 {
-            Some(Rational::new((fps * 1000.0) as i32, 1000))
-        } else {
-            None
-        }
-    }
-}
-
 /// PTS 转秒
-pub(crate) fn pts_to_sec(pts: i64, num: i32, den: i32) -> f64 {
-    if den == 0 {
+pub(crate) fn pts_to_sec(pts: i64, time_base: Rational) -> f64 {
+    if !time_base.is_valid() {
         return 0.0;
     }
-    pts as f64 * num as f64 / den as f64
-}
-
-/// 根据 CodecId 获取对应的采样格式
-pub(crate) fn codec_id_to_sample_format(codec_id: CodecId) -> Option<SampleFormat> {
-    match codec_id {
-        CodecId::PcmU8 => Some(SampleFormat::U8),
-        CodecId::PcmS16le | CodecId::PcmS16be => Some(SampleFormat::S16),
-        CodecId::PcmS24le => Some(SampleFormat::S32),
-        CodecId::PcmS32le => Some(SampleFormat::S32),
-        CodecId::PcmF32le => Some(SampleFormat::F32),
-        CodecId::Aac => Some(SampleFormat::F32),
-        CodecId::Flac => Some(SampleFormat::S16),
-        _ => None,
-    }
-}
-
-/// 解析编解码器名称为 CodecId
-pub(crate) fn parse_codec_name(name: &str) -> CodecId {
-    match name.to_lowercase().as_str() {
-        "pcm_u8" => CodecId::PcmU8,
-        "pcm_s16le" => CodecId::PcmS16le,
-        "pcm_s16be" => CodecId::PcmS16be,
-        "pcm_s24le" => CodecId::PcmS24le,
-        "pcm_s32le" => CodecId::PcmS32le,
-        "pcm_f32le" => CodecId::PcmF32le,
-        "rawvideo" => CodecId::RawVideo,
-        "aac" => CodecId::Aac,
-        "flac" => CodecId::Flac,
-        "mp3" => CodecId::Mp3,
-        other => {
-            eprintln!("警告: 未知编解码器 '{other}', 使用默认");
-            CodecId::PcmS16le
-        }
-    }
+    pts as f64 * time_base.to_f64()
 }
@@ -0,0 +1,112 @@
+//! 输出帧数限制 (对标 ffmpeg `-frames` / `-vframes` / `-aframes`).
+//!
+//! 按输出流统计已写出的帧 (数据包) 数, 达到上限后丢弃该流后续的数据包;
+//! 所有输出流都达到上限时主循环停止读取, 随后照常刷新编码器.
+
+use tao_core::MediaType;
+use tao_format::stream::Stream;
+
+/// 各输出流的帧数限制
+pub(crate) struct FrameLimiter {
+    /// 每个输出流的帧数上限, None 表示不限制
+    limits: Vec<Option<u64>>,
+    /// 每个输出流已写出的帧数
+    counts: Vec<u64>,
+}
+
+impl FrameLimiter {
+    /// 按输出流类型构建限制
+    ///
+    /// 按类型指定的 `vframes` / `aframes` 优先于对所有流生效的 `frames`.
+    pub(crate) fn new(
+        output_streams: &[Stream],
+        frames: Option<u64>,
+        vframes: Option<u64>,
+        aframes: Option<u64>,
+    ) -> Self {
+        let limits: Vec<Option<u64>> = output_streams
+            .iter()
+            .map(|s| match s.media_type {
+                MediaType::Video => vframes.or(frames),
+                MediaType::Audio => aframes.or(frames),
+                _ => frames,
+            })
+            .collect();
+        Self {
+            counts: vec![0; limits.len()],
+            limits,
+        }
+    }
+
+    /// 指定输出流是否已达到上限
+    pub(crate) fn is_reached(&self, out_idx: usize) -> bool {
+        matches!(
+            (self.limits.get(out_idx), self.counts.get(out_idx)),
+            (Some(Some(limit)), Some(count)) if count >= limit
+        )
+    }
+
+    /// 申请写出一帧: 未达上限时计数并返回 true
+    pub(crate) fn admit(&mut self, out_idx: usize) -> bool {
+        if self.is_reached(out_idx) {
+            return false;
+        }
+        if let Some(count) = self.counts.get_mut(out_idx) {
+            *count += 1;
+        }
+        true
+    }
+
+    /// 是否所有输出流都设有上限且均已达到
+    pub(crate) fn all_reached(&self) -> bool {
+        !self.limits.is_empty()
+            && self.limits.iter().all(Option::is_some)
+            && (0..self.limits.len()).all(|i| self.is_reached(i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tao_codec::CodecId;
+    use tao_core::Rational;
+    use tao_format::stream::StreamParams;
+
+    fn stream(media_type: MediaType) -> Stream {
+        Stream {
+            index: 0,
+            media_type,
+            codec_id: CodecId::None,
+            time_base: Rational::new(1, 1000),
+            duration: 0,
+            start_time: 0,
+            nb_frames: 0,
+            extra_data: Vec::new(),
+            params: StreamParams::Other,
+            metadata: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_per_type_limits_override_global() {
+        let streams = [stream(MediaType::Video), stream(MediaType::Audio)];
+        let mut limiter = FrameLimiter::new(&streams, Some(3), Some(1), None);
+
+        assert!(limiter.admit(0));
+        assert!(!limiter.admit(0), "vframes=1 应只允许一帧视频");
+        for _ in 0..3 {
+            assert!(limiter.admit(1));
+        }
+        assert!(!limiter.admit(1), "音频沿用 frames=3");
+        assert!(limiter.all_reached());
+    }
+
+    #[test]
+    fn test_unlimited_stream_never_finishes() {
+        let streams = [stream(MediaType::Video), stream(MediaType::Audio)];
+        let mut limiter = FrameLimiter::new(&streams, None, Some(1), None);
+        assert!(limiter.admit(0));
+        assert!(limiter.admit(1) && limiter.admit(1), "未限制的流不受影响");
+        assert!(!limiter.all_reached(), "存在未限制的流时不应提前结束");
+    }
+}
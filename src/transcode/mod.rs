@@ -0,0 +1,106 @@
+//! 高层转码 API.
+//!
+//! 基于格式/编解码器注册表封装完整的 解封装 → 解码 → 滤镜 → 编码 → 封装 流水线:
+//!
+//! - [`MediaReader`]: 打开输入, 逐帧输出解码后的音视频帧
+//! - [`MediaWriter`]: 添加输出流, 逐帧写入, 内部完成编码与交错封装
+//! - [`Transcoder`]: 输入到输出的一站式转码, 支持按流指定编码方式、滤镜、裁剪与流映射
+//! - [`ProgressReport`]: 转码进度 (输出时间、字节数、速度), 经回调定期报告
+//!
+//! tao-cli 即基于本模块实现.
+//!
+//! ## WAV 转 FLAC
+//!
+//! ```rust
+//! use tao::codec::CodecId;
+//! use tao::{StreamCodec, Transcoder};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let dir = std::env::temp_dir().join(format!("tao_doc_transcoder_{}", std::process::id()));
+//! # std::fs::create_dir_all(&dir)?;
+//! # let input = dir.join("input.wav").to_string_lossy().into_owned();
+//! # let output = dir.join("output.flac").to_string_lossy().into_owned();
+//! # write_test_wav(&input)?;
+//!
+//! let stats = Transcoder::new(&input, &output)
+//!     .audio_codec(StreamCodec::Encode(CodecId::Flac))
+//!     .audio_option("compression_level", "8")
+//!     .run()?;
+//! assert!(stats.packets > 0);
+//! # std::fs::remove_dir_all(&dir)?;
+//! # Ok(())
+//! # }
+//! # fn write_test_wav(path: &str) -> tao::core::TaoResult<()> {
+//! #     use tao::codec::{AudioFrame, Frame};
+//! #     use tao::core::{ChannelLayout, SampleFormat};
+//! #     let mut writer = tao::MediaWriter::create(path)?;
+//! #     let out = writer.add_audio_stream(tao::AudioEncodeParams::new(
+//! #         CodecId::PcmS16le, 44100, ChannelLayout::MONO, SampleFormat::S16,
+//! #     ))?;
+//! #     let samples: Vec<u8> = (0..44100)
+//! #         .flat_map(|i| (((i as f64 * 0.0626).sin() * 8000.0) as i16).to_le_bytes())
+//! #         .collect();
+//! #     let mut frame = AudioFrame::new(44100, 44100, SampleFormat::S16, ChannelLayout::MONO);
+//! #     frame.data[0] = samples.into();
+//! #     writer.write_frame(out, Frame::Audio(frame))?;
+//! #     writer.finish()
+//! # }
+//! ```
+//!
+//! ## 逐帧读取与写入
+//!
+//! ```rust
+//! use tao::codec::CodecId;
+//! use tao::{AudioEncodeParams, MediaReader, MediaWriter};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let dir = std::env::temp_dir().join(format!("tao_doc_reader_writer_{}", std::process::id()));
+//! # std::fs::create_dir_all(&dir)?;
+//! # let input = dir.join("input.wav").to_string_lossy().into_owned();
+//! # let output = dir.join("output.flac").to_string_lossy().into_owned();
+//! # write_test_wav(&input)?;
+//!
+//! let mut reader = MediaReader::open(&input)?;
+//! let mut writer = MediaWriter::create(&output)?;
+//! let params = AudioEncodeParams::from_stream(&reader.streams()[0], CodecId::Flac)?;
+//! let out = writer.add_audio_stream(params)?;
+//! for item in reader.frames() {
+//!     let (_, frame) = item?;
+//!     writer.write_frame(out, frame)?;
+//! }
+//! writer.finish()?;
+//! # std::fs::remove_dir_all(&dir)?;
+//! # Ok(())
+//! # }
+//! # fn write_test_wav(path: &str) -> tao::core::TaoResult<()> {
+//! #     use tao::codec::{AudioFrame, Frame};
+//! #     use tao::core::{ChannelLayout, SampleFormat};
+//! #     let mut writer = tao::MediaWriter::create(path)?;
+//! #     let out = writer.add_audio_stream(tao::AudioEncodeParams::new(
+//! #         CodecId::PcmS16le, 44100, ChannelLayout::STEREO, SampleFormat::S16,
+//! #     ))?;
+//! #     let samples: Vec<u8> = (0..44100)
+//! #         .flat_map(|i| (((i as f64 * 0.0626).sin() * 8000.0) as i16).to_le_bytes().repeat(2))
+//! #         .collect();
+//! #     let mut frame = AudioFrame::new(44100, 44100, SampleFormat::S16, ChannelLayout::STEREO);
+//! #     frame.data[0] = samples.into();
+//! #     writer.write_frame(out, Frame::Audio(frame))?;
+//! #     writer.finish()
+//! # }
+//! ```
+
+mod elementary;
+mod filter;
+mod limit;
+mod processor;
+mod progress;
+mod reader;
+mod stream_map;
+mod transcoder;
+mod trim;
+mod writer;
+
+pub use progress::{DEFAULT_PROGRESS_INTERVAL, ProgressReport};
+pub use reader::{Frames, MediaReader};
+pub use transcoder::{
+    StreamAction, StreamCodec, StreamMapping, TranscodeJob, TranscodeStats, Transcoder,
+};
+pub use writer::{AudioEncodeParams, MediaWriter, VideoEncodeParams};
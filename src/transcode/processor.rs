@@ -0,0 +1,1273 @@
+//! 单条流的转码处理: 解码 → 裁剪 → 滤镜 → 缩放/重采样 → 编码.
+//!
+//! [`StreamProcessor`] 负责解码侧, 编码侧由 [`FrameEncoder`] 完成,
+//! 后者同时被 [`MediaWriter`](super::MediaWriter) 用于逐帧编码.
+
+use tao_codec::codec_parameters::{AudioCodecParams, CodecParamsType, VideoCodecParams};
+use tao_codec::frame::{AudioFrame, VideoFrame};
+use tao_codec::{
+    AudioFifo, CodecId, CodecParameters, CodecRegistry, Decoder, Encoder, Frame, FrameBuf,
+    FramePool, Packet,
+};
+use tao_core::color::{ColorRange, ColorSpace};
+use tao_core::rational::rescale_q;
+use tao_core::timestamp::NOPTS_VALUE;
+use tao_core::{ChannelLayout, MediaType, PixelFormat, Rational, SampleFormat, TaoError};
+use tao_filter::FilterGraph;
+use tao_format::FormatId;
+use tao_format::stream::{AudioStreamParams, Stream, StreamParams, VideoStreamParams};
+use tao_resample::ResampleContext;
+use tao_scale::{ScaleAlgorithm, ScaleContext};
+
+use super::filter::{FilterSpec, build_audio_filter_graph, build_video_filter_graph};
+use super::trim::{TrimWindow, TrimmedFrame, trim_frame};
+
+/// 音频解码输出缓冲池保留的空闲缓冲数量
+const AUDIO_FRAME_POOL_SIZE: usize = 8;
+
+/// 单条流的转码处理器
+pub(crate) struct StreamProcessor {
+    decoder: Box<dyn Decoder>,
+    filter_graph: Option<FilterGraph>,
+    encoder: FrameEncoder,
+    /// 起始时间/时长裁剪窗口
+    trim: TrimWindow,
+    /// 是否已输出到裁剪窗口末尾
+    finished: bool,
+}
+
+impl StreamProcessor {
+    /// 设置裁剪窗口
+    pub(crate) fn set_trim(&mut self, window: TrimWindow) {
+        self.trim = window;
+    }
+
+    /// 是否已到达裁剪窗口末尾, 之后的数据包无需再解码
+    pub(crate) fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// 视频编码的关键帧控制 (对标 FFmpeg 的 `-g` 与 `-force_key_frames`)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct KeyframeControl {
+    /// 关键帧间隔 (帧数), None 表示由编码器决定
+    pub(crate) interval: Option<u32>,
+    /// 强制关键帧的时间点 (秒, 升序)
+    pub(crate) forced_times: Vec<f64>,
+}
+
+/// 编码侧处理: 视频缩放、音频重采样与按编码器帧长重新分块, 然后编码
+pub(crate) struct FrameEncoder {
+    encoder: Box<dyn Encoder>,
+    resampler: Option<ResampleContext>,
+    /// 音频编码前的重新分块与 PTS 生成
+    audio_fifo: Option<AudioFifo>,
+    video_scaler: Option<VideoScaleConfig>,
+    /// 尚未到达的强制关键帧时间点 (秒, 升序)
+    forced_keyframes: Vec<f64>,
+    /// 下一个强制关键帧时间点在 `forced_keyframes` 中的位置
+    next_forced_keyframe: usize,
+}
+
+impl FrameEncoder {
+    /// 编码一帧, 输出的数据包追加到 `output_packets`
+    pub(crate) fn encode(
+        &mut self,
+        frame: Frame,
+        out_stream_idx: usize,
+        output_packets: &mut Vec<Packet>,
+    ) -> Result<(), TaoError> {
+        // 视频缩放
+        let mut scaled_frame = if let Some(ref mut scale_cfg) = self.video_scaler {
+            scale_video_frame(&frame, scale_cfg)?
+        } else {
+            frame
+        };
+        if let Frame::Video(vf) = &mut scaled_frame {
+            self.mark_forced_keyframe(vf);
+        }
+
+        // 音频重采样
+        let frame_to_encode = self.resample(scaled_frame)?;
+
+        // 音频按编码器帧长重新分块, PTS 由累计输出采样数生成
+        match (&mut self.audio_fifo, &frame_to_encode) {
+            (Some(fifo), Frame::Audio(af)) => {
+                fifo.push(af)?;
+                let frame_size = self.encoder.frame_size();
+                while let Some(chunk) = next_encoder_frame(fifo, frame_size, false) {
+                    encode_frame(
+                        self.encoder.as_mut(),
+                        &Frame::Audio(chunk),
+                        out_stream_idx,
+                        output_packets,
+                    )?;
+                }
+                Ok(())
+            }
+            _ => encode_frame(
+                self.encoder.as_mut(),
+                &frame_to_encode,
+                out_stream_idx,
+                output_packets,
+            ),
+        }
+    }
+
+    /// 帧 PTS 到达下一个强制关键帧时间点时标记该帧, 跳过已经过去的时间点
+    fn mark_forced_keyframe(&mut self, vf: &mut VideoFrame) {
+        if vf.pts == NOPTS_VALUE || !vf.time_base.is_valid() {
+            return;
+        }
+        let tb = vf.time_base;
+        let to_pts = |sec: f64| (sec * f64::from(tb.den) / f64::from(tb.num)).round() as i64;
+        while let Some(&sec) = self.forced_keyframes.get(self.next_forced_keyframe) {
+            if vf.pts < to_pts(sec) {
+                break;
+            }
+            vf.force_keyframe = true;
+            self.next_forced_keyframe += 1;
+        }
+    }
+
+    /// 重采样到编码器输入格式
+    ///
+    /// 帧的实际参数与创建时声明的不一致 (如解码输出格式与容器声明不同) 时,
+    /// 按实际参数重建重采样器.
+    fn resample(&mut self, frame: Frame) -> Result<Frame, TaoError> {
+        if let (Frame::Audio(af), Some(fifo)) = (&frame, &self.audio_fifo)
+            && af.sample_rate > 0
+        {
+            let src = (af.sample_rate, af.sample_format, af.channel_layout);
+            let dst = (
+                fifo.sample_rate(),
+                fifo.sample_format(),
+                fifo.channel_layout(),
+            );
+            if src == dst {
+                return Ok(frame);
+            }
+            let reusable = self.resampler.as_ref().is_some_and(|r| {
+                (r.src_sample_rate, r.src_sample_format, r.src_channel_layout) == src
+            });
+            if !reusable {
+                self.resampler = Some(ResampleContext::new(
+                    src.0, src.1, src.2, dst.0, dst.1, dst.2,
+                ));
+            }
+        }
+        match &self.resampler {
+            Some(resampler) => resample_frame(resampler, &frame),
+            None => Ok(frame),
+        }
+    }
+
+    /// 刷新: 编码 FIFO 中剩余的采样, 并取出编码器缓存的全部数据包
+    pub(crate) fn flush(&mut self, out_stream_idx: usize) -> Result<Vec<Packet>, TaoError> {
+        let mut output_packets = Vec::new();
+
+        // 先编码 FIFO 中剩余的不足一帧的采样
+        if let Some(fifo) = &mut self.audio_fifo {
+            let frame_size = self.encoder.frame_size();
+            while let Some(chunk) = next_encoder_frame(fifo, frame_size, true) {
+                encode_frame(
+                    self.encoder.as_mut(),
+                    &Frame::Audio(chunk),
+                    out_stream_idx,
+                    &mut output_packets,
+                )?;
+            }
+        }
+
+        self.encoder.send_frame(None)?;
+
+        loop {
+            match self.encoder.receive_packet() {
+                Ok(mut pkt) => {
+                    pkt.stream_index = out_stream_idx;
+                    output_packets.push(pkt);
+                }
+                Err(TaoError::NeedMoreData) | Err(TaoError::Eof) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(output_packets)
+    }
+}
+
+/// 视频缩放配置
+pub(crate) struct VideoScaleConfig {
+    dst_width: u32,
+    dst_height: u32,
+    dst_pixel_format: PixelFormat,
+    /// 容器声明的源色彩参数, 解码帧未携带时使用
+    stream_colorimetry: (ColorSpace, ColorRange),
+    /// 缓存的缩放上下文, 输入尺寸或像素格式变化时重建
+    ctx: Option<ScaleContext>,
+}
+
+impl VideoScaleConfig {
+    /// 返回与输入帧参数匹配的缩放上下文, 必要时重建
+    fn context_for(&mut self, frame: &VideoFrame) -> &ScaleContext {
+        let (stream_space, stream_range) = self.stream_colorimetry;
+        let color_space = match frame.color_space {
+            ColorSpace::Unspecified => stream_space,
+            space => space,
+        };
+        let color_range = match frame.color_range {
+            ColorRange::Unspecified => stream_range,
+            range => range,
+        };
+        let ctx = match self.ctx.take() {
+            Some(ctx)
+                if ctx.src_width == frame.width
+                    && ctx.src_height == frame.height
+                    && ctx.src_format == frame.pixel_format
+                    && ctx.src_color_space == color_space
+                    && ctx.src_color_range == color_range =>
+            {
+                ctx
+            }
+            _ => ScaleContext::new(
+                frame.width,
+                frame.height,
+                frame.pixel_format,
+                self.dst_width,
+                self.dst_height,
+                self.dst_pixel_format,
+                ScaleAlgorithm::Bilinear,
+            )
+            .with_src_colorimetry(color_space, color_range),
+        };
+        self.ctx.insert(ctx)
+    }
+}
+
+// ============================================================
+// 转码/刷新
+// ============================================================
+
+/// 转码一个数据包
+pub(crate) fn transcode_packet(
+    proc: &mut StreamProcessor,
+    input_pkt: &Packet,
+    out_stream_idx: usize,
+) -> Result<Vec<Packet>, TaoError> {
+    let mut output_packets = Vec::new();
+    if proc.finished {
+        return Ok(output_packets);
+    }
+
+    proc.decoder.send_packet(input_pkt)?;
+
+    loop {
+        match proc.decoder.receive_frame() {
+            Ok(frame) => {
+                // 按裁剪窗口处理: 早于起点的帧仍需解码 (作为参考帧), 但不编码
+                let frame = match trim_frame(frame, &proc.trim) {
+                    TrimmedFrame::Keep(frame) if !proc.finished => frame,
+                    TrimmedFrame::After => {
+                        proc.finished = true;
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                // 应用滤镜
+                let filtered_frame = if let Some(ref mut graph) = proc.filter_graph {
+                    graph.process_frame(&frame)?
+                } else {
+                    frame
+                };
+
+                proc.encoder
+                    .encode(filtered_frame, out_stream_idx, &mut output_packets)?;
+            }
+            Err(TaoError::NeedMoreData) => break,
+            Err(TaoError::Eof) => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(output_packets)
+}
+
+/// 从 FIFO 取出一帧待编码的音频
+///
+/// 帧长不限制 (`frame_size` 为 0) 时取出全部缓冲采样; 否则仅在凑满一帧时取出.
+/// `flush` 为真时取出剩余的不足一帧的采样, 并以静音补齐到编码器帧长.
+fn next_encoder_frame(fifo: &mut AudioFifo, frame_size: u32, flush: bool) -> Option<AudioFrame> {
+    match frame_size {
+        0 => fifo.pop(fifo.len()),
+        size if fifo.len() >= size => fifo.pop(size),
+        size if flush => fifo.pop_padded(size),
+        _ => None,
+    }
+}
+
+/// 送入一帧并取出编码器当前可输出的全部数据包
+fn encode_frame(
+    encoder: &mut dyn Encoder,
+    frame: &Frame,
+    out_stream_idx: usize,
+    output_packets: &mut Vec<Packet>,
+) -> Result<(), TaoError> {
+    encoder.send_frame(Some(frame))?;
+
+    loop {
+        match encoder.receive_packet() {
+            Ok(mut pkt) => {
+                pkt.stream_index = out_stream_idx;
+                output_packets.push(pkt);
+            }
+            Err(TaoError::NeedMoreData) => break,
+            Err(TaoError::Eof) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// 刷新编码器
+pub(crate) fn flush_encoder(
+    proc: &mut StreamProcessor,
+    out_stream_idx: usize,
+) -> Result<Vec<Packet>, TaoError> {
+    proc.encoder.flush(out_stream_idx)
+}
+
+// ============================================================
+// 直接复制
+// ============================================================
+
+/// 构建直接复制时的输出流描述
+///
+/// 时间基按输出容器的约定选择 (MP4 音频为采样率, 视频与 MPEG-TS 为 90kHz),
+/// 其余容器沿用输入时间基. 时长与起始时间随之换算.
+pub(crate) fn create_copy_stream(
+    input_stream: &Stream,
+    out_stream_idx: usize,
+    output_format: FormatId,
+) -> Stream {
+    let in_tb = input_stream.time_base;
+    let out_tb = match (output_format, &input_stream.params) {
+        (FormatId::Mp4, StreamParams::Audio(a)) if a.sample_rate > 0 => {
+            Rational::new(1, a.sample_rate as i32)
+        }
+        (FormatId::Mp4 | FormatId::MpegTs, _) => Rational::new(1, 90000),
+        _ => in_tb,
+    };
+
+    let mut out_stream = input_stream.clone();
+    out_stream.index = out_stream_idx;
+    out_stream.time_base = out_tb;
+    if out_stream.duration > 0 {
+        out_stream.duration = rescale_ts(out_stream.duration, in_tb, out_tb);
+    }
+    out_stream.start_time = rescale_ts(out_stream.start_time, in_tb, out_tb);
+    out_stream
+}
+
+/// 将数据包的 pts/dts/duration 从输入时间基换算到输出时间基
+pub(crate) fn rescale_packet(pkt: &mut Packet, in_tb: Rational, out_tb: Rational) {
+    if in_tb == out_tb {
+        return;
+    }
+    pkt.pts = rescale_ts(pkt.pts, in_tb, out_tb);
+    pkt.dts = rescale_ts(pkt.dts, in_tb, out_tb);
+    if pkt.duration > 0 {
+        pkt.duration = rescale_q(pkt.duration, in_tb, out_tb);
+    }
+    pkt.time_base = out_tb;
+}
+
+/// 换算单个时间戳, 保留无效时间戳
+fn rescale_ts(ts: i64, in_tb: Rational, out_tb: Rational) -> i64 {
+    if ts == NOPTS_VALUE {
+        ts
+    } else {
+        rescale_q(ts, in_tb, out_tb)
+    }
+}
+
+// ============================================================
+// 视频缩放
+// ============================================================
+
+/// 缩放视频帧
+pub(crate) fn scale_video_frame(
+    frame: &Frame,
+    config: &mut VideoScaleConfig,
+) -> Result<Frame, TaoError> {
+    match frame {
+        Frame::Video(vf) => {
+            if vf.width == config.dst_width
+                && vf.height == config.dst_height
+                && vf.pixel_format == config.dst_pixel_format
+            {
+                return Ok(frame.clone());
+            }
+
+            // 准备源数据
+            let src_planes: Vec<&[u8]> = vf.data.iter().map(|d| d.as_slice()).collect();
+            let src_linesize: Vec<usize> = vf.linesize.clone();
+
+            // 分配目标帧
+            let dst_fmt = config.dst_pixel_format;
+            let dst_w = config.dst_width;
+            let dst_h = config.dst_height;
+            let plane_count = dst_fmt.plane_count() as usize;
+
+            let mut dst_bufs: Vec<Vec<u8>> = Vec::with_capacity(plane_count);
+            let mut dst_linesizes: Vec<usize> = Vec::with_capacity(plane_count);
+
+            for p in 0..plane_count {
+                let ls = dst_fmt
+                    .plane_linesize(p, dst_w)
+                    .unwrap_or(dst_w as usize * 3);
+                let h = dst_fmt.plane_height(p, dst_h).unwrap_or(dst_h as usize);
+                dst_bufs.push(vec![0u8; ls * h]);
+                dst_linesizes.push(ls);
+            }
+
+            {
+                let mut dst_slices: Vec<&mut [u8]> =
+                    dst_bufs.iter_mut().map(|b| b.as_mut_slice()).collect();
+                let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+                config.context_for(vf).scale_slices(
+                    &src_planes,
+                    &src_linesize,
+                    &mut dst_slices,
+                    &dst_linesizes,
+                    threads,
+                )?;
+            }
+
+            let mut out_frame = VideoFrame::new(dst_w, dst_h, dst_fmt);
+            out_frame.data = dst_bufs.into_iter().map(FrameBuf::from).collect();
+            out_frame.linesize = dst_linesizes;
+            out_frame.pts = vf.pts;
+            out_frame.time_base = vf.time_base;
+            out_frame.duration = frame.duration();
+            if vf.pixel_format == dst_fmt {
+                // 仅缩放时色彩参数不变; 格式转换后按目标默认值 (未指定) 处理
+                out_frame.color_space = vf.color_space;
+                out_frame.color_range = vf.color_range;
+            }
+
+            Ok(Frame::Video(out_frame))
+        }
+        _ => Ok(frame.clone()),
+    }
+}
+
+// ============================================================
+// 音频重采样
+// ============================================================
+
+/// 重采样一帧音频
+pub(crate) fn resample_frame(
+    resampler: &ResampleContext,
+    frame: &Frame,
+) -> Result<Frame, TaoError> {
+    match frame {
+        Frame::Audio(audio) => Ok(Frame::Audio(resampler.convert_frame(audio)?)),
+        _ => Err(TaoError::Unsupported("视频帧重采样尚未实现".to_string())),
+    }
+}
+
+// ============================================================
+// 编码器格式协商
+// ============================================================
+
+/// 从编码器声明的采样格式中选择与源格式最接近的一个
+///
+/// 重采样器只输出交错格式, 因此仅在交错格式中选择. 优先级:
+/// 源格式本身 > 不损失精度 > 同为整数/浮点 > 位宽差最小 > 声明顺序.
+/// 编码器未声明时沿用源格式; 无可用交错格式时返回 None.
+pub(crate) fn choose_sample_format(
+    src: SampleFormat,
+    supported: &[SampleFormat],
+) -> Option<SampleFormat> {
+    if supported.is_empty() || supported.contains(&src) {
+        return Some(src);
+    }
+    let src_bytes = src.bytes_per_sample();
+    supported
+        .iter()
+        .copied()
+        .filter(|f| !f.is_planar() && *f != SampleFormat::None)
+        .min_by_key(|f| {
+            let bytes = f.bytes_per_sample();
+            (
+                bytes < src_bytes,
+                f.is_float() != src.is_float(),
+                bytes.abs_diff(src_bytes),
+            )
+        })
+}
+
+/// 从编码器声明的采样率中选择与期望值最接近的一个, 未声明时沿用期望值
+pub(crate) fn choose_sample_rate(requested: u32, supported: Option<&[u32]>) -> u32 {
+    supported
+        .and_then(|rates| rates.iter().copied().min_by_key(|r| r.abs_diff(requested)))
+        .unwrap_or(requested)
+}
+
+/// 从编码器声明的像素格式中选择: 支持源格式则沿用, 否则取首选格式
+pub(crate) fn choose_pixel_format(
+    src: PixelFormat,
+    supported: &[PixelFormat],
+) -> Option<PixelFormat> {
+    if supported.is_empty() || supported.contains(&src) {
+        return Some(src);
+    }
+    supported.first().copied()
+}
+
+// ============================================================
+// 编码侧创建
+// ============================================================
+
+impl FrameEncoder {
+    /// 为音频创建编码侧, 返回输出流描述 (索引与元数据由调用方填写)
+    ///
+    /// `src` 描述送入的帧; 按编码器声明的能力选择与期望值 (默认为源参数) 最接近的
+    /// 采样格式/采样率, 与源参数不一致时插入重采样. `encoder_options` 为编码器私有选项
+    /// (如 FLAC 的 `compression_level`), 在打开编码器前设置. `global_header` 表示
+    /// 容器保存全局头, 编码器的 `extra_data()` 写入输出流.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn audio(
+        src: &AudioStreamParams,
+        output_codec_id: CodecId,
+        encoder_name: Option<&str>,
+        codec_registry: &CodecRegistry,
+        target_sample_rate: Option<u32>,
+        target_channels: Option<u32>,
+        target_sample_format: Option<SampleFormat>,
+        encoder_options: &[(String, String)],
+        global_header: bool,
+    ) -> Result<(Self, Stream), TaoError> {
+        // 创建编码器
+        let mut encoder = match encoder_name {
+            Some(name) => codec_registry.create_encoder_by_name(name)?,
+            None => codec_registry.create_encoder(output_codec_id)?,
+        };
+        for (key, value) in encoder_options {
+            encoder.set_option(key, value)?;
+        }
+        encoder.set_global_header(global_header);
+
+        // 确定输出参数: 按编码器声明的能力选择最接近的采样格式/采样率
+        let requested_rate = target_sample_rate.unwrap_or(src.sample_rate);
+        let out_sample_rate = choose_sample_rate(requested_rate, encoder.supported_sample_rates());
+        let out_channels = target_channels.unwrap_or(src.channel_layout.channels);
+        let out_channel_layout = ChannelLayout::from_channels(out_channels);
+
+        // 未指定目标格式且编码器不支持源格式时, 转换为编码器首选格式
+        let supported = encoder.supported_sample_formats();
+        let preferred = encoder.preferred_sample_format();
+        let requested_format = match target_sample_format {
+            Some(format) => format,
+            None if !supported.contains(&src.sample_format) && !preferred.is_planar() => preferred,
+            None => src.sample_format,
+        };
+        if requested_format.is_planar() && requested_format != src.sample_format {
+            return Err(TaoError::InvalidArgument(format!(
+                "重采样仅支持输出交错采样格式, 无法转换为 {requested_format}"
+            )));
+        }
+        let out_sample_format =
+            choose_sample_format(requested_format, supported).ok_or_else(|| {
+                TaoError::Unsupported(format!(
+                    "编码器 {} 不支持任何可转换的采样格式 (声明: {:?})",
+                    encoder.name(),
+                    encoder.supported_sample_formats(),
+                ))
+            })?;
+        let enc_params = CodecParameters {
+            codec_id: output_codec_id,
+            extra_data: Vec::new(),
+            bit_rate: 0,
+            params: CodecParamsType::Audio(AudioCodecParams {
+                sample_rate: out_sample_rate,
+                channel_layout: out_channel_layout,
+                sample_format: out_sample_format,
+                frame_size: 0,
+                block_align: 0,
+            }),
+        };
+        encoder.open(&enc_params)?;
+
+        // 判断是否需要重采样
+        let need_resample = src.sample_rate != out_sample_rate
+            || src.channel_layout.channels != out_channels
+            || src.sample_format != out_sample_format;
+
+        let resampler = if need_resample {
+            Some(ResampleContext::new(
+                src.sample_rate,
+                src.sample_format,
+                src.channel_layout,
+                out_sample_rate,
+                out_sample_format,
+                out_channel_layout,
+            ))
+        } else {
+            None
+        };
+
+        // 构建输出流描述
+        let out_stream = Stream {
+            index: 0,
+            media_type: MediaType::Audio,
+            codec_id: output_codec_id,
+            time_base: Rational::new(1, out_sample_rate as i32),
+            duration: 0,
+            start_time: 0,
+            nb_frames: 0,
+            extra_data: encoder.extra_data(),
+            params: StreamParams::Audio(AudioStreamParams {
+                sample_rate: out_sample_rate,
+                channel_layout: out_channel_layout,
+                sample_format: out_sample_format,
+                bit_rate: 0,
+                frame_size: 0,
+                block_align: 0,
+            }),
+            metadata: Vec::new(),
+        };
+
+        let audio_fifo = AudioFifo::new(out_sample_rate, out_sample_format, out_channel_layout);
+
+        let frame_encoder = Self {
+            encoder,
+            resampler,
+            audio_fifo: Some(audio_fifo),
+            video_scaler: None,
+            forced_keyframes: Vec::new(),
+            next_forced_keyframe: 0,
+        };
+        Ok((frame_encoder, out_stream))
+    }
+
+    /// 为视频创建编码侧, 返回输出流描述 (索引与元数据由调用方填写)
+    ///
+    /// `src` 描述送入的帧; 尺寸或像素格式与输出不一致时插入缩放.
+    /// `keyframes` 的间隔在打开编码器前设置, 强制时间点在编码时与帧 PTS 比较.
+    /// `global_header` 表示容器保存全局头, 编码器的 `extra_data()` 写入输出流.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn video(
+        src: &VideoStreamParams,
+        output_codec_id: CodecId,
+        encoder_name: Option<&str>,
+        codec_registry: &CodecRegistry,
+        target_size: Option<(u32, u32)>,
+        target_pixel_format: Option<PixelFormat>,
+        target_rate: Option<Rational>,
+        keyframes: &KeyframeControl,
+        global_header: bool,
+    ) -> Result<(Self, Stream), TaoError> {
+        // 确定输出参数
+        let (out_width, out_height) = target_size.unwrap_or((src.width, src.height));
+        let out_frame_rate = target_rate.unwrap_or(src.frame_rate);
+
+        // 创建编码器
+        let mut encoder = match encoder_name {
+            Some(name) => codec_registry.create_encoder_by_name(name)?,
+            None => codec_registry.create_encoder(output_codec_id)?,
+        };
+        if let Some(interval) = keyframes.interval {
+            encoder.set_keyframe_interval(interval);
+        }
+        encoder.set_global_header(global_header);
+
+        // 按编码器声明的能力选择像素格式
+        let requested_format = target_pixel_format.unwrap_or(src.pixel_format);
+        let out_pixel_format =
+            choose_pixel_format(requested_format, encoder.supported_pixel_formats()).ok_or_else(
+                || TaoError::Unsupported(format!("编码器 {} 未声明可用的像素格式", encoder.name())),
+            )?;
+        let enc_params = CodecParameters {
+            codec_id: output_codec_id,
+            extra_data: Vec::new(),
+            bit_rate: 0,
+            params: CodecParamsType::Video(VideoCodecParams {
+                width: out_width,
+                height: out_height,
+                pixel_format: out_pixel_format,
+                frame_rate: out_frame_rate,
+                sample_aspect_ratio: src.sample_aspect_ratio,
+            }),
+        };
+        encoder.open(&enc_params)?;
+
+        // 缩放配置
+        let needs_scale = out_width != src.width
+            || out_height != src.height
+            || out_pixel_format != src.pixel_format;
+        let video_scaler = if needs_scale {
+            Some(VideoScaleConfig {
+                dst_width: out_width,
+                dst_height: out_height,
+                dst_pixel_format: out_pixel_format,
+                stream_colorimetry: (src.color_space, src.color_range),
+                ctx: None,
+            })
+        } else {
+            None
+        };
+
+        // 像素格式不变时沿用源色彩参数, 否则为转换后的默认值
+        let (color_space, color_range) = if out_pixel_format == src.pixel_format {
+            (src.color_space, src.color_range)
+        } else {
+            Default::default()
+        };
+
+        // 构建输出流描述
+        let out_stream = Stream {
+            index: 0,
+            media_type: MediaType::Video,
+            codec_id: output_codec_id,
+            time_base: out_frame_rate.inverse(),
+            duration: 0,
+            start_time: 0,
+            nb_frames: 0,
+            extra_data: encoder.extra_data(),
+            params: StreamParams::Video(VideoStreamParams {
+                width: out_width,
+                height: out_height,
+                pixel_format: out_pixel_format,
+                frame_rate: out_frame_rate,
+                sample_aspect_ratio: src.sample_aspect_ratio,
+                bit_rate: 0,
+                color_space,
+                color_range,
+            }),
+            metadata: Vec::new(),
+        };
+
+        let mut forced_keyframes = keyframes.forced_times.clone();
+        forced_keyframes.sort_by(f64::total_cmp);
+        let frame_encoder = Self {
+            encoder,
+            resampler: None,
+            audio_fifo: None,
+            video_scaler,
+            forced_keyframes,
+            next_forced_keyframe: 0,
+        };
+        Ok((frame_encoder, out_stream))
+    }
+}
+
+// ============================================================
+// 音频处理器创建
+// ============================================================
+
+/// 为音频流创建处理器
+///
+/// `encoder_options` 为编码器私有选项 (如 FLAC 的 `compression_level`), 在打开编码器前设置.
+/// `global_header` 为输出容器是否保存编解码器全局头 (见 `Muxer::wants_global_header`).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_audio_processor(
+    input_stream: &Stream,
+    output_codec_id: CodecId,
+    encoder_name: Option<&str>,
+    codec_registry: &CodecRegistry,
+    target_sample_rate: Option<u32>,
+    target_channels: Option<u32>,
+    target_sample_format: Option<SampleFormat>,
+    audio_filters: &Option<Vec<FilterSpec>>,
+    encoder_options: &[(String, String)],
+    global_header: bool,
+) -> Result<(StreamProcessor, Stream), TaoError> {
+    let audio_params = match &input_stream.params {
+        StreamParams::Audio(a) => a,
+        _ => {
+            return Err(TaoError::InvalidArgument("不是音频流".to_string()));
+        }
+    };
+
+    // 创建解码器
+    let mut decoder = codec_registry.create_decoder(input_stream.codec_id)?;
+    // 解码帧在重采样/分块后即被释放, 缓冲归还池中供后续帧复用
+    decoder.set_frame_pool(&FramePool::new(AUDIO_FRAME_POOL_SIZE));
+    decoder.open(&input_stream.codec_parameters())?;
+
+    let (encoder, mut out_stream) = FrameEncoder::audio(
+        audio_params,
+        output_codec_id,
+        encoder_name,
+        codec_registry,
+        target_sample_rate,
+        target_channels,
+        target_sample_format,
+        encoder_options,
+        global_header,
+    )?;
+    out_stream.index = input_stream.index;
+    out_stream.metadata = input_stream.metadata.clone();
+
+    let processor = StreamProcessor {
+        decoder,
+        filter_graph: build_audio_filter_graph(audio_filters),
+        encoder,
+        trim: TrimWindow::unbounded(),
+        finished: false,
+    };
+
+    Ok((processor, out_stream))
+}
+
+// ============================================================
+// 视频处理器创建
+// ============================================================
+
+/// 为视频流创建处理器
+///
+/// `keyframes` 为关键帧间隔与强制关键帧时间点 (见 [`KeyframeControl`]).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_video_processor(
+    input_stream: &Stream,
+    output_codec_id: CodecId,
+    encoder_name: Option<&str>,
+    codec_registry: &CodecRegistry,
+    target_size: Option<(u32, u32)>,
+    target_pixel_format: Option<PixelFormat>,
+    target_rate: Option<Rational>,
+    video_filters: &Option<Vec<FilterSpec>>,
+    keyframes: &KeyframeControl,
+    global_header: bool,
+) -> Result<(StreamProcessor, Stream), TaoError> {
+    let video_params = match &input_stream.params {
+        StreamParams::Video(v) => v,
+        _ => {
+            return Err(TaoError::InvalidArgument("不是视频流".to_string()));
+        }
+    };
+
+    // 创建解码器
+    let mut decoder = codec_registry.create_decoder(input_stream.codec_id)?;
+    decoder.open(&input_stream.codec_parameters())?;
+
+    let (encoder, mut out_stream) = FrameEncoder::video(
+        video_params,
+        output_codec_id,
+        encoder_name,
+        codec_registry,
+        target_size,
+        target_pixel_format,
+        target_rate,
+        keyframes,
+        global_header,
+    )?;
+    out_stream.index = input_stream.index;
+    out_stream.metadata = input_stream.metadata.clone();
+
+    let processor = StreamProcessor {
+        decoder,
+        filter_graph: build_video_filter_graph(video_filters),
+        encoder,
+        trim: TrimWindow::unbounded(),
+        finished: false,
+    };
+
+    Ok((processor, out_stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tao_codec::KeyframeScheduler;
+    use tao_codec::frame::AudioFrame;
+
+    fn make_s16_stream(sample_rate: u32) -> Stream {
+        Stream {
+            index: 0,
+            media_type: MediaType::Audio,
+            codec_id: CodecId::PcmS16le,
+            time_base: Rational::new(1, sample_rate as i32),
+            duration: 0,
+            start_time: 0,
+            nb_frames: 0,
+            extra_data: Vec::new(),
+            params: StreamParams::Audio(AudioStreamParams {
+                sample_rate,
+                channel_layout: ChannelLayout::STEREO,
+                sample_format: SampleFormat::S16,
+                bit_rate: 0,
+                frame_size: 0,
+                block_align: 0,
+            }),
+            metadata: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_resample_frame_keeps_source_duration() {
+        let resampler = ResampleContext::new(
+            44100,
+            SampleFormat::S16,
+            ChannelLayout::MONO,
+            48000,
+            SampleFormat::S16,
+            ChannelLayout::MONO,
+        );
+        let mut af = AudioFrame::new(441, 44100, SampleFormat::S16, ChannelLayout::MONO);
+        af.data[0] = vec![0u8; 441 * 2].into();
+        af.pts = 0;
+        af.time_base = Rational::new(1, 44100);
+        let mut frame = Frame::Audio(af);
+
+        // 源帧已标注时长: 直接沿用
+        frame.set_duration(441);
+        let out = resample_frame(&resampler, &frame).unwrap();
+        assert_eq!(out.duration(), 441, "重采样后时长应与源帧一致");
+
+        // 源帧未标注时长: 按输出采样数换算回源时间基
+        frame.set_duration(0);
+        let out = resample_frame(&resampler, &frame).unwrap();
+        let Frame::Audio(out_af) = &out else {
+            panic!("期望音频帧");
+        };
+        let expected = (out_af.duration_seconds() * 44100.0).round() as i64;
+        assert_eq!(out.duration(), expected, "应按输出时长换算到源时间基");
+    }
+
+    #[test]
+    fn test_audio_processor_aac_inserts_s16_to_f32_resample() {
+        let mut registry = CodecRegistry::new();
+        tao_codec::register_all(&mut registry);
+
+        let (processor, out_stream) = create_audio_processor(
+            &make_s16_stream(44100),
+            CodecId::Aac,
+            None,
+            &registry,
+            None,
+            None,
+            None,
+            &None,
+            &[],
+            false,
+        )
+        .expect("S16 -> AAC 处理器创建失败");
+
+        let resampler = processor
+            .encoder
+            .resampler
+            .as_ref()
+            .expect("应自动插入重采样步骤");
+        assert_eq!(
+            resampler.dst_sample_format,
+            SampleFormat::F32,
+            "应选择 AAC 编码器声明的 F32"
+        );
+        let StreamParams::Audio(params) = &out_stream.params else {
+            panic!("输出流应为音频");
+        };
+        assert_eq!(params.sample_format, SampleFormat::F32);
+    }
+
+    #[test]
+    fn test_audio_processor_uses_preferred_sample_format() {
+        let mut registry = CodecRegistry::new();
+        tao_codec::register_all(&mut registry);
+        let mut input = make_s16_stream(44100);
+        if let StreamParams::Audio(params) = &mut input.params {
+            params.sample_format = SampleFormat::F32;
+        }
+
+        let (processor, out_stream) = create_audio_processor(
+            &input,
+            CodecId::Flac,
+            None,
+            &registry,
+            None,
+            None,
+            None,
+            &None,
+            &[],
+            false,
+        )
+        .expect("处理器创建失败");
+        let StreamParams::Audio(params) = &out_stream.params else {
+            panic!("输出流应为音频");
+        };
+        assert_eq!(
+            params.sample_format,
+            SampleFormat::S16,
+            "源格式不受支持时应转换为编码器首选格式"
+        );
+        assert!(
+            processor.encoder.resampler.is_some(),
+            "格式转换应插入重采样"
+        );
+
+        let (processor, _) = create_audio_processor(
+            &make_s16_stream(44100),
+            CodecId::PcmS16le,
+            None,
+            &registry,
+            None,
+            None,
+            None,
+            &None,
+            &[],
+            false,
+        )
+        .expect("处理器创建失败");
+        assert!(
+            processor.encoder.resampler.is_none(),
+            "源格式即首选格式时不应重采样"
+        );
+    }
+
+    #[test]
+    fn test_audio_processor_snaps_to_supported_sample_rate() {
+        let mut registry = CodecRegistry::new();
+        tao_codec::register_all(&mut registry);
+
+        let (processor, out_stream) = create_audio_processor(
+            &make_s16_stream(44100),
+            CodecId::Aac,
+            None,
+            &registry,
+            Some(44000),
+            None,
+            None,
+            &None,
+            &[],
+            false,
+        )
+        .expect("处理器创建失败");
+        assert!(processor.encoder.resampler.is_some());
+        let StreamParams::Audio(params) = &out_stream.params else {
+            panic!("输出流应为音频");
+        };
+        assert_eq!(params.sample_rate, 44100, "应选择最接近的 AAC 标准采样率");
+    }
+
+    #[test]
+    fn test_audio_processor_target_sample_format() {
+        let mut registry = CodecRegistry::new();
+        tao_codec::register_all(&mut registry);
+        let create = |format| {
+            create_audio_processor(
+                &make_s16_stream(44100),
+                CodecId::Flac,
+                None,
+                &registry,
+                None,
+                None,
+                Some(format),
+                &None,
+                &[],
+                false,
+            )
+        };
+
+        let (processor, out_stream) = create(SampleFormat::S32).expect("处理器创建失败");
+        let StreamParams::Audio(params) = &out_stream.params else {
+            panic!("输出流应为音频");
+        };
+        assert_eq!(
+            params.sample_format,
+            SampleFormat::S32,
+            "应使用指定的采样格式"
+        );
+        assert!(
+            processor.encoder.resampler.is_some(),
+            "格式变化时应插入重采样"
+        );
+
+        let (_, out_stream) = create(SampleFormat::F32).expect("处理器创建失败");
+        let StreamParams::Audio(params) = &out_stream.params else {
+            panic!("输出流应为音频");
+        };
+        assert_eq!(
+            params.sample_format,
+            SampleFormat::S24,
+            "编码器不支持时应选择最接近的格式 (32 位容器的整数格式)"
+        );
+
+        assert!(
+            matches!(
+                create(SampleFormat::S32p),
+                Err(TaoError::InvalidArgument(_))
+            ),
+            "重采样无法输出平面格式"
+        );
+    }
+
+    #[test]
+    fn test_choose_sample_format_rules() {
+        let s = SampleFormat::S16;
+        assert_eq!(choose_sample_format(s, &[]), Some(s), "未声明时沿用源格式");
+        assert_eq!(
+            choose_sample_format(SampleFormat::S32, &[SampleFormat::S16, SampleFormat::S32]),
+            Some(SampleFormat::S32),
+            "支持源格式时不转换"
+        );
+        assert_eq!(
+            choose_sample_format(SampleFormat::F32, &[SampleFormat::S16, SampleFormat::S32]),
+            Some(SampleFormat::S32),
+            "应优先不损失精度的格式"
+        );
+        assert_eq!(
+            choose_sample_format(s, &[SampleFormat::F32p]),
+            None,
+            "仅支持平面格式时无法通过重采样器转换"
+        );
+    }
+
+    fn make_gray_frame(width: u32, height: u32, value: u8) -> Frame {
+        let mut vf = tao_codec::frame::VideoFrame::new(width, height, PixelFormat::Gray8);
+        vf.data = vec![FrameBuf::from(vec![value; (width * height) as usize])];
+        vf.linesize = vec![width as usize];
+        Frame::Video(vf)
+    }
+
+    /// 模拟预测编码器: 按关键帧调度器决定每帧是否为关键帧
+    struct MockPredictiveEncoder {
+        scheduler: KeyframeScheduler,
+        output: Option<Packet>,
+    }
+
+    impl MockPredictiveEncoder {
+        fn create() -> Result<Box<dyn Encoder>, TaoError> {
+            Ok(Box::new(Self {
+                scheduler: KeyframeScheduler::new(250),
+                output: None,
+            }))
+        }
+    }
+
+    impl Encoder for MockPredictiveEncoder {
+        fn codec_id(&self) -> CodecId {
+            CodecId::Mpeg4
+        }
+
+        fn name(&self) -> &str {
+            "mock_predictive"
+        }
+
+        fn set_keyframe_interval(&mut self, interval: u32) {
+            self.scheduler.set_interval(interval);
+        }
+
+        fn send_frame(&mut self, frame: Option<&Frame>) -> Result<(), TaoError> {
+            let Some(Frame::Video(vf)) = frame else {
+                return Ok(());
+            };
+            let mut pkt = Packet::from_data(vec![0u8; 4]);
+            pkt.pts = vf.pts;
+            pkt.set_keyframe(self.scheduler.next_frame(vf.force_keyframe));
+            self.output = Some(pkt);
+            Ok(())
+        }
+
+        fn receive_packet(&mut self) -> Result<Packet, TaoError> {
+            self.output.take().ok_or(TaoError::NeedMoreData)
+        }
+
+        fn flush(&mut self) {
+            self.scheduler.reset();
+        }
+    }
+
+    #[test]
+    fn test_video_encoder_honors_interval_and_forced_keyframes() {
+        let mut registry = CodecRegistry::new();
+        registry.register_encoder(
+            CodecId::Mpeg4,
+            "mock_predictive",
+            MockPredictiveEncoder::create,
+        );
+        let src = VideoStreamParams {
+            width: 4,
+            height: 4,
+            pixel_format: PixelFormat::Gray8,
+            frame_rate: Rational::new(25, 1),
+            sample_aspect_ratio: Rational::new(1, 1),
+            bit_rate: 0,
+            color_space: ColorSpace::Unspecified,
+            color_range: ColorRange::Unspecified,
+        };
+        let keyframes = KeyframeControl {
+            interval: Some(8),
+            // 乱序给出; 0.61s 换算后与 0.6s 同为第 15 帧, 只强制一次
+            forced_times: vec![0.6, 0.2, 0.61],
+        };
+        let (mut encoder, _) = FrameEncoder::video(
+            &src,
+            CodecId::Mpeg4,
+            Some("mock_predictive"),
+            &registry,
+            None,
+            None,
+            None,
+            &keyframes,
+            false,
+        )
+        .expect("视频编码侧创建失败");
+
+        let mut packets = Vec::new();
+        for pts in 0..20 {
+            let Frame::Video(mut vf) = make_gray_frame(4, 4, 0) else {
+                unreachable!();
+            };
+            vf.pts = pts;
+            vf.time_base = Rational::new(1, 25);
+            encoder.encode(Frame::Video(vf), 0, &mut packets).unwrap();
+        }
+        let keyframe_pts: Vec<i64> = packets
+            .iter()
+            .filter(|pkt| pkt.is_keyframe())
+            .map(|pkt| pkt.pts)
+            .collect();
+        assert_eq!(
+            keyframe_pts,
+            vec![0, 5, 13, 15],
+            "首帧, 0.2s 强制帧, 其后间隔 8 帧与 0.6s 强制帧应为关键帧"
+        );
+    }
+
+    #[test]
+    fn test_scale_context_reused_until_input_size_changes() {
+        let mut config = VideoScaleConfig {
+            dst_width: 4,
+            dst_height: 4,
+            dst_pixel_format: PixelFormat::Gray8,
+            stream_colorimetry: (ColorSpace::Unspecified, ColorRange::Unspecified),
+            ctx: None,
+        };
+
+        for _ in 0..3 {
+            let out = scale_video_frame(&make_gray_frame(8, 8, 100), &mut config).unwrap();
+            match out {
+                Frame::Video(vf) => {
+                    assert_eq!((vf.width, vf.height), (4, 4), "输出尺寸应为目标尺寸");
+                    assert!(
+                        vf.data[0].iter().all(|&v| v == 100),
+                        "均匀色缩放后应保持不变"
+                    );
+                }
+                _ => panic!("应输出视频帧"),
+            }
+        }
+        let ctx = config.ctx.as_ref().expect("应缓存缩放上下文");
+        assert_eq!((ctx.src_width, ctx.src_height), (8, 8));
+
+        scale_video_frame(&make_gray_frame(12, 6, 50), &mut config).unwrap();
+        let ctx = config.ctx.as_ref().unwrap();
+        assert_eq!(
+            (ctx.src_width, ctx.src_height),
+            (12, 6),
+            "输入尺寸变化时应重建缩放上下文"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! 转码进度跟踪.
+//!
+//! [`TranscodeJob::run_with_progress`](super::TranscodeJob::run_with_progress) 每写出一个
+//! 数据包更新一次统计, 按固定间隔 (默认 500ms) 回调一次 [`ProgressReport`],
+//! 结束时再回调一次 `finished` 为真的最终进度.
+
+use std::time::{Duration, Instant};
+
+use tao_codec::Packet;
+use tao_core::timestamp::NOPTS_VALUE;
+use tao_core::{Rational, Timestamp};
+use tao_format::stream::Stream;
+
+/// 默认进度回调间隔
+pub const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 进度时间基 (微秒)
+const PROGRESS_TIME_BASE: Rational = Rational::new(1, 1_000_000);
+
+/// 某一时刻的转码进度
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressReport {
+    /// 当前输出时间 (已写出数据包的最大结束时间, 以微秒为时间基)
+    pub out_time: Timestamp,
+    /// 预计输出总时长 (秒), 输入时长未知时为 None
+    pub total_duration: Option<f64>,
+    /// 已写出的视频帧数 (无视频输出流时为数据包数)
+    pub frames: u64,
+    /// 已写出的数据包数
+    pub packets: u64,
+    /// 已写出的字节数
+    pub bytes: u64,
+    /// 处理速度 (输出时长 / 墙钟时长), 尚无法计算时为 0
+    pub speed: f64,
+    /// 已耗费的墙钟时间
+    pub elapsed: Duration,
+    /// 是否为转码结束时的最终进度
+    pub finished: bool,
+}
+
+impl ProgressReport {
+    /// 当前输出时间 (微秒)
+    pub fn out_time_us(&self) -> i64 {
+        self.out_time.rescale(PROGRESS_TIME_BASE).pts
+    }
+
+    /// 平均每秒处理帧数
+    pub fn fps(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.frames as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// 完成比例 (0.0 ~ 1.0), 总时长未知时为 None
+    pub fn ratio(&self) -> Option<f64> {
+        self.total_duration
+            .filter(|&total| total > 0.0)
+            .map(|total| (self.out_time.to_seconds() / total).clamp(0.0, 1.0))
+    }
+}
+
+/// 进度统计与回调限频
+pub(crate) struct ProgressTracker {
+    interval: Duration,
+    start: Instant,
+    last_report: Instant,
+    total_duration: Option<f64>,
+    /// 是否有视频输出流 (决定 frames 的计数口径)
+    has_video: bool,
+    frames: u64,
+    packets: u64,
+    bytes: u64,
+    out_time_us: i64,
+}
+
+impl ProgressTracker {
+    pub(crate) fn new(
+        output_streams: &[Stream],
+        total_duration: Option<f64>,
+        interval: Duration,
+        start: Instant,
+    ) -> Self {
+        Self {
+            interval,
+            start,
+            last_report: start,
+            total_duration,
+            has_video: output_streams.iter().any(|s| s.media_type.is_video()),
+            frames: 0,
+            packets: 0,
+            bytes: 0,
+            out_time_us: 0,
+        }
+    }
+
+    /// 记录一个已写出的数据包
+    pub(crate) fn record(&mut self, pkt: &Packet, stream: &Stream) {
+        self.packets += 1;
+        self.bytes += pkt.data.len() as u64;
+        if !self.has_video || stream.media_type.is_video() {
+            self.frames += 1;
+        }
+        if pkt.pts != NOPTS_VALUE && stream.time_base.is_valid() {
+            let end = Timestamp::new(pkt.pts + pkt.duration.max(0), stream.time_base);
+            self.out_time_us = self.out_time_us.max(end.rescale(PROGRESS_TIME_BASE).pts);
+        }
+    }
+
+    /// 距上次报告超过间隔时返回一次进度
+    pub(crate) fn poll(&mut self, now: Instant) -> Option<ProgressReport> {
+        if now.saturating_duration_since(self.last_report) < self.interval {
+            return None;
+        }
+        self.last_report = now;
+        Some(self.report(now, false))
+    }
+
+    /// 生成进度快照
+    pub(crate) fn report(&self, now: Instant, finished: bool) -> ProgressReport {
+        let elapsed = now.saturating_duration_since(self.start);
+        let secs = elapsed.as_secs_f64();
+        let speed = if secs > 0.0 {
+            self.out_time_us as f64 / 1_000_000.0 / secs
+        } else {
+            0.0
+        };
+        ProgressReport {
+            out_time: Timestamp::new(self.out_time_us, PROGRESS_TIME_BASE),
+            total_duration: self.total_duration,
+            frames: self.frames,
+            packets: self.packets,
+            bytes: self.bytes,
+            speed,
+            elapsed,
+            finished,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tao_codec::CodecId;
+    use tao_core::MediaType;
+    use tao_format::stream::StreamParams;
+
+    fn make_stream(index: usize, media_type: MediaType, time_base: Rational) -> Stream {
+        Stream {
+            index,
+            media_type,
+            codec_id: CodecId::None,
+            time_base,
+            duration: -1,
+            start_time: 0,
+            nb_frames: 0,
+            extra_data: Vec::new(),
+            params: StreamParams::Other,
+            metadata: Vec::new(),
+        }
+    }
+
+    fn make_packet(stream_index: usize, pts: i64, duration: i64, size: usize) -> Packet {
+        let mut pkt = Packet::from_data(vec![0u8; size]);
+        pkt.stream_index = stream_index;
+        pkt.pts = pts;
+        pkt.dts = pts;
+        pkt.duration = duration;
+        pkt
+    }
+
+    #[test]
+    fn test_progress_tracker_rate_limit() {
+        let streams = [make_stream(0, MediaType::Audio, Rational::new(1, 1000))];
+        let start = Instant::now();
+        let mut tracker = ProgressTracker::new(&streams, None, Duration::from_millis(500), start);
+
+        let mut reports = 0;
+        // 2 秒内每 10ms 写出一个数据包, 应只回调约 4 次
+        for i in 0..200u64 {
+            tracker.record(&make_packet(0, i as i64 * 10, 10, 4), &streams[0]);
+            if tracker
+                .poll(start + Duration::from_millis(i * 10 + 10))
+                .is_some()
+            {
+                reports += 1;
+            }
+        }
+        assert_eq!(reports, 4, "500ms 间隔内最多回调一次");
+        assert!(
+            tracker.poll(start + Duration::from_millis(2100)).is_none(),
+            "刚回调后不应再次回调"
+        );
+        let last = tracker
+            .poll(start + Duration::from_millis(2500))
+            .expect("超过间隔后应回调");
+        assert_eq!(last.packets, 200);
+        assert!(!last.finished);
+    }
+
+    #[test]
+    fn test_progress_report_fields() {
+        let streams = [
+            make_stream(0, MediaType::Video, Rational::new(1, 25)),
+            make_stream(1, MediaType::Audio, Rational::new(1, 48000)),
+        ];
+        let start = Instant::now();
+        let mut tracker =
+            ProgressTracker::new(&streams, Some(10.0), DEFAULT_PROGRESS_INTERVAL, start);
+        for i in 0..50 {
+            tracker.record(&make_packet(0, i, 1, 100), &streams[0]);
+        }
+        tracker.record(&make_packet(1, 0, 1024, 10), &streams[1]);
+
+        let report = tracker.report(start + Duration::from_secs(1), true);
+        assert_eq!(report.frames, 50, "有视频流时只统计视频帧");
+        assert_eq!(report.packets, 51);
+        assert_eq!(report.bytes, 5010);
+        assert_eq!(
+            report.out_time_us(),
+            2_000_000,
+            "输出时间应为 50 帧的结束时间"
+        );
+        assert!(
+            (report.speed - 2.0).abs() < 1e-9,
+            "1 秒处理 2 秒媒体应为 2x, 实际 {}",
+            report.speed
+        );
+        assert!((report.fps() - 50.0).abs() < 1e-9);
+        assert_eq!(report.ratio(), Some(0.2));
+        assert!(report.finished);
+
+        let idle = tracker.report(start, false);
+        assert_eq!(idle.speed, 0.0, "尚未耗时时速度为 0");
+    }
+}
@@ -0,0 +1,191 @@
+//! 媒体读取: 解封装 + 解码, 逐帧输出.
+
+use std::collections::VecDeque;
+
+use tao_codec::{CodecRegistry, Decoder, Frame, Packet};
+use tao_core::{MediaType, TaoError, TaoResult};
+use tao_format::stream::Stream;
+use tao_format::{Demuxer, FormatId, FormatRegistry, IoContext};
+use tracing::warn;
+
+/// 打开输入: 先按 URL 打开, 失败时作为本地文件打开; 指定格式时跳过探测
+pub(crate) fn open_input(
+    path: &str,
+    format: Option<FormatId>,
+    registry: &FormatRegistry,
+) -> TaoResult<(IoContext, Box<dyn Demuxer>)> {
+    let mut io = match IoContext::open_url(path) {
+        Ok(io) => io,
+        Err(_) => IoContext::open_read(path)?,
+    };
+    let demuxer = match format {
+        Some(format_id) => registry.open_input_as(&mut io, format_id)?,
+        None => registry.open_input(&mut io, Some(path))?,
+    };
+    Ok((io, demuxer))
+}
+
+/// 媒体读取器
+///
+/// 打开输入文件, 按数据包顺序解码所有音视频流, 输出 `(流索引, 帧)`.
+/// 解码器在首次读取时创建, 没有可用解码器的流 (字幕、数据等) 被忽略.
+///
+/// ```rust,no_run
+/// let mut reader = tao::MediaReader::open("input.wav")?;
+/// for item in reader.frames() {
+///     let (stream_index, frame) = item?;
+///     println!("流 #{stream_index}: 时长 {}", frame.duration());
+/// }
+/// # Ok::<(), tao::core::TaoError>(())
+/// ```
+pub struct MediaReader {
+    io: IoContext,
+    demuxer: Box<dyn Demuxer>,
+    streams: Vec<Stream>,
+    codec_registry: CodecRegistry,
+    /// 按流索引排列的解码器, None 表示该流不解码
+    decoders: Option<Vec<Option<Box<dyn Decoder>>>>,
+    /// 已解码但尚未返回的帧
+    pending: VecDeque<(usize, Frame)>,
+    eof: bool,
+}
+
+impl MediaReader {
+    /// 打开输入文件, 自动探测容器格式
+    pub fn open(path: &str) -> TaoResult<Self> {
+        Self::open_with(path, None)
+    }
+
+    /// 以指定容器格式打开输入文件, 跳过格式探测
+    pub fn open_format(path: &str, format: FormatId) -> TaoResult<Self> {
+        Self::open_with(path, Some(format))
+    }
+
+    fn open_with(path: &str, format: Option<FormatId>) -> TaoResult<Self> {
+        let (io, demuxer) = open_input(path, format, &crate::default_format_registry())?;
+        let streams = demuxer.streams().to_vec();
+        Ok(Self {
+            io,
+            demuxer,
+            streams,
+            codec_registry: crate::default_codec_registry(),
+            decoders: None,
+            pending: VecDeque::new(),
+            eof: false,
+        })
+    }
+
+    /// 容器格式名称
+    pub fn format_name(&self) -> &str {
+        self.demuxer.name()
+    }
+
+    /// 输入中的全部流
+    pub fn streams(&self) -> &[Stream] {
+        &self.streams
+    }
+
+    /// 总时长 (秒), 未知时返回 None
+    pub fn duration(&self) -> Option<f64> {
+        self.demuxer.duration()
+    }
+
+    /// 读取下一帧, 输入结束且解码器已刷新完毕时返回 `Ok(None)`
+    ///
+    /// 帧的时间戳以所属流的时间基为单位.
+    pub fn read_frame(&mut self) -> TaoResult<Option<(usize, Frame)>> {
+        if self.decoders.is_none() {
+            self.decoders = Some(self.create_decoders());
+        }
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Ok(Some(item));
+            }
+            if self.eof {
+                return Ok(None);
+            }
+            match self.demuxer.read_packet(&mut self.io) {
+                Ok(pkt) => {
+                    if let Err(e) = self.decode(pkt.stream_index, &pkt) {
+                        self.eof = true;
+                        return Err(e);
+                    }
+                }
+                Err(TaoError::Eof) => {
+                    self.eof = true;
+                    // 刷新所有解码器缓存的帧
+                    for idx in 0..self.streams.len() {
+                        self.decode(idx, &Packet::empty())?;
+                    }
+                }
+                Err(e) => {
+                    self.eof = true;
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// 逐帧迭代, 每项为 `(流索引, 帧)`
+    pub fn frames(&mut self) -> Frames<'_> {
+        Frames { reader: self }
+    }
+
+    fn create_decoders(&self) -> Vec<Option<Box<dyn Decoder>>> {
+        self.streams
+            .iter()
+            .map(|stream| {
+                if !matches!(stream.media_type, MediaType::Audio | MediaType::Video) {
+                    return None;
+                }
+                match stream.open_decoder(&self.codec_registry) {
+                    Ok(decoder) => Some(decoder),
+                    Err(e) => {
+                        warn!(
+                            "流 #{} ({}) 无法解码, 已忽略: {e}",
+                            stream.index, stream.codec_id
+                        );
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// 送入一个数据包, 取出的帧追加到待返回队列
+    fn decode(&mut self, stream_index: usize, pkt: &Packet) -> TaoResult<()> {
+        let Some(Some(decoder)) = self.decoders.as_mut().and_then(|d| d.get_mut(stream_index))
+        else {
+            return Ok(());
+        };
+        let time_base = self.streams[stream_index].time_base;
+        decoder.send_packet(pkt)?;
+        loop {
+            match decoder.receive_frame() {
+                Ok(mut frame) => {
+                    match &mut frame {
+                        Frame::Audio(af) if !af.time_base.is_valid() => af.time_base = time_base,
+                        Frame::Video(vf) if !vf.time_base.is_valid() => vf.time_base = time_base,
+                        _ => {}
+                    }
+                    self.pending.push_back((stream_index, frame));
+                }
+                Err(TaoError::NeedMoreData) | Err(TaoError::Eof) => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// [`MediaReader::frames`] 返回的帧迭代器
+pub struct Frames<'a> {
+    reader: &'a mut MediaReader,
+}
+
+impl Iterator for Frames<'_> {
+    type Item = TaoResult<(usize, Frame)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.read_frame().transpose()
+    }
+}
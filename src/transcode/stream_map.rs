@@ -0,0 +1,225 @@
+//! 流映射说明符解析.
+//!
+//! 对标 FFmpeg 的 `-map`, 支持以下形式 (输入文件索引目前只能为 0):
+//! - `0`: 全部流
+//! - `0:1`: 第 1 条流 (按容器内流索引)
+//! - `0:a` / `0:v` / `0:s` / `0:d` / `0:t`: 指定类型的全部流
+//! - `0:a:0`: 指定类型的第 0 条流
+
+use tao_core::MediaType;
+use tao_format::stream::Stream;
+
+/// 流选择器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StreamSelector {
+    /// 全部流
+    All,
+    /// 按流索引选择
+    Index(usize),
+    /// 按媒体类型选择, 可附带该类型内的序号
+    Type(MediaType, Option<usize>),
+}
+
+/// 单个流映射说明符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct StreamMap {
+    /// 输入文件索引
+    pub(crate) input: usize,
+    /// 流选择器
+    pub(crate) selector: StreamSelector,
+}
+
+/// 解析流映射说明符
+pub(crate) fn parse_map(spec: &str) -> Result<StreamMap, String> {
+    let mut parts = spec.split(':');
+    let input = parts
+        .next()
+        .and_then(|s| s.parse::<usize>().ok())
+        .ok_or_else(|| format!("无效的流映射 '{spec}': 缺少输入文件索引"))?;
+
+    let selector = match (parts.next(), parts.next(), parts.next()) {
+        (None, _, _) => StreamSelector::All,
+        (Some(first), second, None) => {
+            if let Ok(index) = first.parse::<usize>() {
+                if second.is_some() {
+                    return Err(format!("无效的流映射 '{spec}'"));
+                }
+                StreamSelector::Index(index)
+            } else {
+                let media_type = parse_media_type(first)
+                    .ok_or_else(|| format!("无效的流映射 '{spec}': 未知流类型 '{first}'"))?;
+                let nth = match second {
+                    Some(s) => Some(
+                        s.parse::<usize>()
+                            .map_err(|_| format!("无效的流映射 '{spec}': 无效序号 '{s}'"))?,
+                    ),
+                    None => None,
+                };
+                StreamSelector::Type(media_type, nth)
+            }
+        }
+        _ => return Err(format!("无效的流映射 '{spec}'")),
+    };
+
+    Ok(StreamMap { input, selector })
+}
+
+fn parse_media_type(s: &str) -> Option<MediaType> {
+    match s {
+        "v" => Some(MediaType::Video),
+        "a" => Some(MediaType::Audio),
+        "s" => Some(MediaType::Subtitle),
+        "d" => Some(MediaType::Data),
+        "t" => Some(MediaType::Attachment),
+        _ => None,
+    }
+}
+
+/// 按映射顺序解析出输出流对应的输入流下标
+///
+/// 同一条输入流被多次选中时只保留第一次. 任一说明符未匹配到流时返回错误.
+pub(crate) fn resolve_maps(maps: &[StreamMap], streams: &[Stream]) -> Result<Vec<usize>, String> {
+    let mut selected = Vec::new();
+    for map in maps {
+        if map.input != 0 {
+            return Err(format!("输入文件 #{} 不存在, 仅支持单个输入", map.input));
+        }
+        let matched: Vec<usize> = match map.selector {
+            StreamSelector::All => (0..streams.len()).collect(),
+            StreamSelector::Index(index) => streams
+                .iter()
+                .position(|s| s.index == index)
+                .into_iter()
+                .collect(),
+            StreamSelector::Type(media_type, nth) => {
+                let of_type = streams
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, s)| s.media_type == media_type)
+                    .map(|(i, _)| i);
+                match nth {
+                    Some(n) => of_type.skip(n).take(1).collect(),
+                    None => of_type.collect(),
+                }
+            }
+        };
+        if matched.is_empty() {
+            return Err(format!("流映射 {} 未匹配到任何流", describe(map)));
+        }
+        for i in matched {
+            if !selected.contains(&i) {
+                selected.push(i);
+            }
+        }
+    }
+    Ok(selected)
+}
+
+fn describe(map: &StreamMap) -> String {
+    let kind = |t: MediaType| match t {
+        MediaType::Video => "v",
+        MediaType::Audio => "a",
+        MediaType::Subtitle => "s",
+        MediaType::Data => "d",
+        MediaType::Attachment => "t",
+        MediaType::Unknown(_) => "?",
+    };
+    match map.selector {
+        StreamSelector::All => format!("{}", map.input),
+        StreamSelector::Index(i) => format!("{}:{i}", map.input),
+        StreamSelector::Type(t, None) => format!("{}:{}", map.input, kind(t)),
+        StreamSelector::Type(t, Some(n)) => format!("{}:{}:{n}", map.input, kind(t)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tao_codec::CodecId;
+    use tao_core::Rational;
+    use tao_format::stream::StreamParams;
+
+    /// 模拟流列表: #0 视频, #1 音频, #2 字幕, #3 音频
+    fn mock_streams() -> Vec<Stream> {
+        [
+            MediaType::Video,
+            MediaType::Audio,
+            MediaType::Subtitle,
+            MediaType::Audio,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(index, media_type)| Stream {
+            index,
+            media_type,
+            codec_id: CodecId::None,
+            time_base: Rational::new(1, 1000),
+            duration: 0,
+            start_time: 0,
+            nb_frames: 0,
+            extra_data: Vec::new(),
+            params: StreamParams::Other,
+            metadata: Vec::new(),
+        })
+        .collect()
+    }
+
+    fn resolve(specs: &[&str]) -> Result<Vec<usize>, String> {
+        let maps: Vec<StreamMap> = specs.iter().map(|s| parse_map(s).unwrap()).collect();
+        resolve_maps(&maps, &mock_streams())
+    }
+
+    #[test]
+    fn test_parse_map_specifiers() {
+        assert_eq!(
+            parse_map("0:1").unwrap().selector,
+            StreamSelector::Index(1),
+            "0:1 应按流索引选择"
+        );
+        assert_eq!(
+            parse_map("0:a").unwrap().selector,
+            StreamSelector::Type(MediaType::Audio, None),
+            "0:a 应选择全部音频流"
+        );
+        assert_eq!(
+            parse_map("0:v:0").unwrap().selector,
+            StreamSelector::Type(MediaType::Video, Some(0)),
+            "0:v:0 应选择第一条视频流"
+        );
+        assert_eq!(parse_map("0").unwrap().selector, StreamSelector::All);
+        assert!(parse_map("x:a").is_err(), "缺少输入索引应报错");
+        assert!(parse_map("0:q").is_err(), "未知类型应报错");
+        assert!(parse_map("0:a:x").is_err(), "无效序号应报错");
+        assert!(parse_map("0:1:2").is_err(), "流索引后不应再有序号");
+    }
+
+    #[test]
+    fn test_resolve_maps_against_streams() {
+        assert_eq!(resolve(&["0:1"]).unwrap(), vec![1], "0:1 应选中流 #1");
+        assert_eq!(
+            resolve(&["0:a"]).unwrap(),
+            vec![1, 3],
+            "0:a 应选中全部音频流"
+        );
+        assert_eq!(resolve(&["0:v:0"]).unwrap(), vec![0], "0:v:0 应选中视频流");
+        assert_eq!(
+            resolve(&["0:a:1"]).unwrap(),
+            vec![3],
+            "0:a:1 应选中第二条音频流"
+        );
+        assert_eq!(resolve(&["0:s"]).unwrap(), vec![2], "0:s 应选中字幕流");
+    }
+
+    #[test]
+    fn test_resolve_maps_order_and_errors() {
+        assert_eq!(
+            resolve(&["0:a:1", "0:v", "0:a"]).unwrap(),
+            vec![3, 0, 1],
+            "输出顺序应与映射顺序一致, 重复流只保留一次"
+        );
+        assert!(resolve(&["0:9"]).is_err(), "不存在的流索引应报错");
+        assert!(resolve(&["0:a:5"]).is_err(), "超出范围的类型序号应报错");
+        assert!(resolve(&["0:d"]).is_err(), "无匹配类型应报错");
+        assert!(resolve(&["1:a"]).is_err(), "不存在的输入文件应报错");
+    }
+}
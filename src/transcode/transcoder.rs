@@ -0,0 +1,739 @@
+//! 转码器: 输入 → (解码 → 滤镜 → 编码 | 直接复制) → 输出.
+
+use std::time::{Duration, Instant};
+
+use tao_codec::{CodecId, CodecRegistry, Packet};
+use tao_core::{MediaType, PixelFormat, Rational, SampleFormat, TaoError, TaoResult};
+use tao_format::demuxer::SeekFlags;
+use tao_format::stream::Stream;
+use tao_format::{Demuxer, FormatId, Interleaver, IoContext, Muxer};
+use tracing::warn;
+
+use super::elementary::{accepts_codec, elementary_media_type, select_stream};
+use super::filter::parse_filter_chain;
+use super::limit::FrameLimiter;
+use super::processor::{
+    KeyframeControl, StreamProcessor, create_audio_processor, create_copy_stream,
+    create_video_processor, flush_encoder, rescale_packet, transcode_packet,
+};
+use super::progress::{DEFAULT_PROGRESS_INTERVAL, ProgressReport, ProgressTracker};
+use super::reader::open_input;
+use super::stream_map::{parse_map, resolve_maps};
+use super::trim::{PacketTrimmer, TrimWindow};
+
+/// 单条流的编码方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamCodec {
+    /// 不解码, 直接复制数据包
+    Copy,
+    /// 以该编解码器的首选编码器编码
+    Encode(CodecId),
+    /// 以指定注册名称的编码器编码 (如 "pcm_s16le")
+    Encoder(String),
+}
+
+/// 输入流的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamAction {
+    /// 直接复制
+    Copy,
+    /// 解码后重新编码
+    Transcode {
+        /// 输出编解码器
+        codec_id: CodecId,
+    },
+    /// 不输出 (未指定视频编码器的视频流、无法识别类型的流等)
+    Skip,
+}
+
+/// 输入流到输出流的映射
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamMapping {
+    /// 输入流索引
+    pub input_index: usize,
+    /// 输出流索引, 跳过时为 None
+    pub output_index: Option<usize>,
+    /// 处理方式
+    pub action: StreamAction,
+}
+
+/// 转码结果统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TranscodeStats {
+    /// 写出的数据包数
+    pub packets: u64,
+    /// 写出的数据总字节数
+    pub total_size: u64,
+}
+
+/// 转码器构建器
+///
+/// 未指定编码方式时: 音频流以输入编解码器重新编码; 视频流仅在指定了视频编码器、
+/// 尺寸、帧率或滤镜时输出, 指定流映射时默认直接复制; 其余类型的流仅在流映射选中时复制.
+/// 输出为裸流格式 (如 `.aac`, `.h264`) 时只能包含一条流.
+///
+/// ```rust,no_run
+/// use tao::{StreamCodec, Transcoder};
+/// use tao::codec::CodecId;
+///
+/// let stats = Transcoder::new("input.mkv", "output.mkv")
+///     .audio_codec(StreamCodec::Encode(CodecId::Flac))
+///     .video_codec(StreamCodec::Copy)
+///     .start_time(10.0)
+///     .duration(30.0)
+///     .run()?;
+/// println!("写出 {} 个数据包", stats.packets);
+/// # Ok::<(), tao::core::TaoError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Transcoder {
+    input: String,
+    output: String,
+    input_format: Option<FormatId>,
+    output_format: Option<FormatId>,
+    audio_codec: Option<StreamCodec>,
+    video_codec: Option<StreamCodec>,
+    /// 按输入流索引覆盖编码方式
+    stream_codecs: Vec<(usize, StreamCodec)>,
+    audio_options: Vec<(String, String)>,
+    sample_rate: Option<u32>,
+    channels: Option<u32>,
+    sample_format: Option<SampleFormat>,
+    video_size: Option<(u32, u32)>,
+    pixel_format: Option<PixelFormat>,
+    frame_rate: Option<Rational>,
+    keyframe_interval: Option<u32>,
+    force_key_frames: Vec<f64>,
+    audio_filter: Option<String>,
+    video_filter: Option<String>,
+    start_time: Option<f64>,
+    duration: Option<f64>,
+    max_frames: Option<u64>,
+    max_video_frames: Option<u64>,
+    max_audio_frames: Option<u64>,
+    maps: Vec<String>,
+    muxer_options: Vec<(String, String)>,
+    max_interleave_delta: Option<f64>,
+}
+
+impl Transcoder {
+    /// 创建转码器, 输出格式默认由输出文件扩展名推断
+    pub fn new(input: &str, output: &str) -> Self {
+        Self {
+            input: input.to_string(),
+            output: output.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// 强制输入容器格式, 跳过探测
+    pub fn input_format(mut self, format: FormatId) -> Self {
+        self.input_format = Some(format);
+        self
+    }
+
+    /// 强制输出容器格式
+    pub fn output_format(mut self, format: FormatId) -> Self {
+        self.output_format = Some(format);
+        self
+    }
+
+    /// 所有音频流的编码方式
+    pub fn audio_codec(mut self, codec: StreamCodec) -> Self {
+        self.audio_codec = Some(codec);
+        self
+    }
+
+    /// 所有视频流的编码方式
+    pub fn video_codec(mut self, codec: StreamCodec) -> Self {
+        self.video_codec = Some(codec);
+        self
+    }
+
+    /// 单条输入流的编码方式, 优先于按类型指定的编码方式
+    pub fn stream_codec(mut self, input_index: usize, codec: StreamCodec) -> Self {
+        self.stream_codecs.retain(|(idx, _)| *idx != input_index);
+        self.stream_codecs.push((input_index, codec));
+        self
+    }
+
+    /// 音频编码器私有选项 (如 FLAC 的 `compression_level`)
+    pub fn audio_option(mut self, key: &str, value: &str) -> Self {
+        self.audio_options
+            .push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// 目标采样率 (Hz)
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = Some(sample_rate);
+        self
+    }
+
+    /// 目标声道数
+    pub fn channels(mut self, channels: u32) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+
+    /// 目标采样格式 (编码器不支持时选择最接近的格式)
+    pub fn sample_format(mut self, format: SampleFormat) -> Self {
+        self.sample_format = Some(format);
+        self
+    }
+
+    /// 目标视频分辨率
+    pub fn video_size(mut self, width: u32, height: u32) -> Self {
+        self.video_size = Some((width, height));
+        self
+    }
+
+    /// 目标像素格式 (编码器不支持时选择其首选格式)
+    pub fn pixel_format(mut self, format: PixelFormat) -> Self {
+        self.pixel_format = Some(format);
+        self
+    }
+
+    /// 目标帧率
+    pub fn frame_rate(mut self, rate: Rational) -> Self {
+        self.frame_rate = Some(rate);
+        self
+    }
+
+    /// 视频关键帧间隔 (帧数, 对标 FFmpeg 的 `-g`)
+    ///
+    /// 仅对预测编码器生效, 帧内编码器 (如 rawvideo) 每帧均为关键帧.
+    pub fn keyframe_interval(mut self, frames: u32) -> Self {
+        self.keyframe_interval = Some(frames);
+        self
+    }
+
+    /// 强制视频关键帧的时间点 (秒), PTS 首次到达各时间点的帧编码为关键帧
+    pub fn force_key_frames(mut self, times: &[f64]) -> Self {
+        self.force_key_frames.extend_from_slice(times);
+        self
+    }
+
+    /// 音频滤镜链 (如 "volume=0.5,fade=in:0:3")
+    pub fn audio_filter(mut self, chain: &str) -> Self {
+        self.audio_filter = Some(chain.to_string());
+        self
+    }
+
+    /// 视频滤镜链 (如 "crop=640:480:0:0,pad=800:600:80:60")
+    pub fn video_filter(mut self, chain: &str) -> Self {
+        self.video_filter = Some(chain.to_string());
+        self
+    }
+
+    /// 起始时间偏移 (秒)
+    pub fn start_time(mut self, seconds: f64) -> Self {
+        self.start_time = Some(seconds);
+        self
+    }
+
+    /// 持续时间限制 (秒)
+    pub fn duration(mut self, seconds: f64) -> Self {
+        self.duration = Some(seconds);
+        self
+    }
+
+    /// 每个输出流最多输出的帧数
+    pub fn max_frames(mut self, frames: u64) -> Self {
+        self.max_frames = Some(frames);
+        self
+    }
+
+    /// 最多输出的视频帧数, 优先于 [`max_frames`](Self::max_frames)
+    pub fn max_video_frames(mut self, frames: u64) -> Self {
+        self.max_video_frames = Some(frames);
+        self
+    }
+
+    /// 最多输出的音频帧数, 优先于 [`max_frames`](Self::max_frames)
+    pub fn max_audio_frames(mut self, frames: u64) -> Self {
+        self.max_audio_frames = Some(frames);
+        self
+    }
+
+    /// 追加流映射 (如 "0:v:0", "0:a", "0:1"), 指定后只输出被选中的流并按映射顺序排列
+    pub fn map(mut self, spec: &str) -> Self {
+        self.maps.push(spec.to_string());
+        self
+    }
+
+    /// 封装器私有选项 (如 MP4 的 `fragmented`)
+    pub fn muxer_option(mut self, key: &str, value: &str) -> Self {
+        self.muxer_options
+            .push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// 多路输出交错时的最大缓存时长 (秒), 默认 [`Interleaver::DEFAULT_MAX_BUFFER_DURATION`]
+    pub fn max_interleave_delta(mut self, seconds: f64) -> Self {
+        self.max_interleave_delta = Some(seconds);
+        self
+    }
+
+    /// 执行转码
+    pub fn run(self) -> TaoResult<TranscodeStats> {
+        self.prepare()?.run()
+    }
+
+    /// 执行转码, 按默认间隔回调进度, 详见 [`TranscodeJob::run_with_progress`]
+    pub fn run_with_progress<F>(self, on_progress: F) -> TaoResult<TranscodeStats>
+    where
+        F: FnMut(&ProgressReport),
+    {
+        self.prepare()?
+            .run_with_progress(DEFAULT_PROGRESS_INTERVAL, on_progress)
+    }
+
+    /// 打开输入输出并创建各流的处理器, 返回可查看映射后再执行的任务
+    pub fn prepare(self) -> TaoResult<TranscodeJob> {
+        let format_registry = crate::default_format_registry();
+        let codec_registry = crate::default_codec_registry();
+
+        let (input_io, demuxer) = open_input(&self.input, self.input_format, &format_registry)?;
+        let input_streams = demuxer.streams().to_vec();
+        if input_streams.is_empty() {
+            return Err(TaoError::InvalidData("输入文件中没有找到任何流".into()));
+        }
+
+        let output_format = match self.output_format {
+            Some(format) => format,
+            None => FormatId::from_filename(&self.output).ok_or_else(|| {
+                TaoError::FormatNotFound(format!(
+                    "无法从输出文件名确定格式: '{}', 可指定输出格式",
+                    self.output
+                ))
+            })?,
+        };
+        let elementary = elementary_media_type(output_format).is_some();
+
+        // 确定输出流及其顺序: 指定映射时按映射选择, 否则按类型隐式选择全部流
+        let explicit_map = !self.maps.is_empty();
+        let selected: Vec<usize> = if explicit_map {
+            let maps = self
+                .maps
+                .iter()
+                .map(|spec| parse_map(spec))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(TaoError::InvalidArgument)?;
+            resolve_maps(&maps, &input_streams).map_err(TaoError::InvalidArgument)?
+        } else if elementary {
+            vec![select_stream(output_format, &input_streams).map_err(TaoError::InvalidArgument)?]
+        } else {
+            (0..input_streams.len()).collect()
+        };
+        if elementary && selected.len() != 1 {
+            return Err(TaoError::InvalidArgument(format!(
+                "{output_format} 裸流输出仅支持单条流, 流映射选中了 {} 条",
+                selected.len()
+            )));
+        }
+
+        let trim = TrimWindow::new(self.start_time.unwrap_or(0.0), self.duration);
+        let audio_filters = self.audio_filter.as_deref().map(parse_filter_chain);
+        let video_filters = self.video_filter.as_deref().map(parse_filter_chain);
+        let keyframes = KeyframeControl {
+            interval: self.keyframe_interval,
+            forced_times: self.force_key_frames.clone(),
+        };
+
+        // 编码器需按容器是否保存全局头决定输出形式, 故先创建封装器
+        let mut muxer = format_registry.create_muxer(output_format)?;
+        let mut processors: Vec<Option<StreamProcessor>> =
+            input_streams.iter().map(|_| None).collect();
+        let mut copy_flags = vec![false; input_streams.len()];
+        let mut output_indices: Vec<Option<usize>> = vec![None; input_streams.len()];
+        let mut output_streams: Vec<Stream> = Vec::new();
+        let mut mappings = Vec::with_capacity(selected.len());
+
+        for &in_idx in &selected {
+            let stream = &input_streams[in_idx];
+            let out_idx = output_streams.len();
+            let codec = self.codec_for(in_idx, stream.media_type);
+            let video_processing = codec.is_some()
+                || self.video_size.is_some()
+                || self.pixel_format.is_some()
+                || self.frame_rate.is_some()
+                || video_filters.is_some();
+            // 裸流输出: 编解码器与目标格式一致且未指定编码器时直接复制
+            let elementary_copy = elementary && accepts_codec(output_format, stream.codec_id);
+            let is_copy = codec == Some(&StreamCodec::Copy);
+            let copy = match stream.media_type {
+                MediaType::Audio => is_copy || (elementary_copy && codec.is_none()),
+                MediaType::Video => {
+                    is_copy || ((explicit_map || elementary_copy) && !video_processing)
+                }
+                // 无法识别的流类型无法封装, 始终跳过
+                MediaType::Unknown(_) => false,
+                _ => explicit_map || is_copy,
+            };
+
+            let mut mapping = StreamMapping {
+                input_index: in_idx,
+                output_index: None,
+                action: StreamAction::Skip,
+            };
+            if copy {
+                output_streams.push(create_copy_stream(stream, out_idx, output_format));
+                copy_flags[in_idx] = true;
+                mapping.output_index = Some(out_idx);
+                mapping.action = StreamAction::Copy;
+            } else if stream.media_type == MediaType::Audio
+                || (stream.media_type == MediaType::Video && video_processing)
+            {
+                let (codec_id, encoder_name) = resolve_codec(codec, stream, &codec_registry)?;
+                let (mut processor, mut out_stream) = if stream.media_type == MediaType::Audio {
+                    create_audio_processor(
+                        stream,
+                        codec_id,
+                        encoder_name,
+                        &codec_registry,
+                        self.sample_rate,
+                        self.channels,
+                        self.sample_format,
+                        &audio_filters,
+                        &self.audio_options,
+                        muxer.wants_global_header(),
+                    )?
+                } else {
+                    create_video_processor(
+                        stream,
+                        codec_id,
+                        encoder_name,
+                        &codec_registry,
+                        self.video_size,
+                        self.pixel_format,
+                        self.frame_rate,
+                        &video_filters,
+                        &keyframes,
+                        muxer.wants_global_header(),
+                    )?
+                };
+                processor.set_trim(trim);
+                out_stream.index = out_idx;
+                output_streams.push(out_stream);
+                processors[in_idx] = Some(processor);
+                mapping.output_index = Some(out_idx);
+                mapping.action = StreamAction::Transcode { codec_id };
+            }
+            if let Some(out_idx) = mapping.output_index {
+                output_indices[in_idx] = Some(out_idx);
+            }
+            mappings.push(mapping);
+        }
+
+        if output_streams.is_empty() {
+            return Err(TaoError::InvalidArgument("没有可输出的流".into()));
+        }
+
+        let output_io = IoContext::open_read_write(&self.output)?;
+        // 封装器私有选项需在写入头部前设置
+        for (key, value) in &self.muxer_options {
+            muxer.set_option(key, value)?;
+        }
+
+        let mut interleaver = Interleaver::new(&output_streams);
+        if let Some(seconds) = self.max_interleave_delta {
+            interleaver = interleaver.with_max_buffer_duration(seconds);
+        }
+        let limiter = FrameLimiter::new(
+            &output_streams,
+            self.max_frames,
+            self.max_video_frames,
+            self.max_audio_frames,
+        );
+        Ok(TranscodeJob {
+            input_io,
+            demuxer,
+            input_streams,
+            output_io,
+            muxer,
+            output_format,
+            output_streams,
+            selected,
+            mappings,
+            processors,
+            copy_flags,
+            output_indices,
+            trim,
+            limiter,
+            interleaver,
+        })
+    }
+
+    /// 输入流的编码方式: 单流覆盖 > 按类型指定
+    fn codec_for(&self, input_index: usize, media_type: MediaType) -> Option<&StreamCodec> {
+        self.stream_codecs
+            .iter()
+            .find(|(idx, _)| *idx == input_index)
+            .map(|(_, codec)| codec)
+            .or(match media_type {
+                MediaType::Audio => self.audio_codec.as_ref(),
+                MediaType::Video => self.video_codec.as_ref(),
+                _ => None,
+            })
+    }
+}
+
+/// 确定输出编解码器与具体编码器名称, 未指定时沿用输入编解码器
+fn resolve_codec<'a>(
+    codec: Option<&'a StreamCodec>,
+    stream: &Stream,
+    registry: &CodecRegistry,
+) -> TaoResult<(CodecId, Option<&'a str>)> {
+    match codec {
+        Some(StreamCodec::Encode(codec_id)) => Ok((*codec_id, None)),
+        Some(StreamCodec::Encoder(name)) => registry
+            .find_encoder_by_name(name)
+            .map(|desc| (desc.id, Some(name.as_str())))
+            .ok_or_else(|| TaoError::CodecNotFound(format!("未找到名为 {name} 的编码器"))),
+        Some(StreamCodec::Copy) | None => Ok((stream.codec_id, None)),
+    }
+}
+
+/// 已准备好的转码任务
+pub struct TranscodeJob {
+    input_io: IoContext,
+    demuxer: Box<dyn Demuxer>,
+    input_streams: Vec<Stream>,
+    output_io: IoContext,
+    muxer: Box<dyn Muxer>,
+    output_format: FormatId,
+    output_streams: Vec<Stream>,
+    /// 被选中的输入流, 按输出顺序排列
+    selected: Vec<usize>,
+    mappings: Vec<StreamMapping>,
+    /// 按输入流索引排列的转码处理器
+    processors: Vec<Option<StreamProcessor>>,
+    /// 按输入流索引标记直接复制的流
+    copy_flags: Vec<bool>,
+    /// 输入流索引 → 输出流索引
+    output_indices: Vec<Option<usize>>,
+    trim: TrimWindow,
+    limiter: FrameLimiter,
+    /// 按 DTS 交错各输出流的数据包
+    interleaver: Interleaver,
+}
+
+impl TranscodeJob {
+    /// 输入容器格式名称
+    pub fn input_format_name(&self) -> &str {
+        self.demuxer.name()
+    }
+
+    /// 全部输入流
+    pub fn input_streams(&self) -> &[Stream] {
+        &self.input_streams
+    }
+
+    /// 输出容器格式
+    pub fn output_format(&self) -> FormatId {
+        self.output_format
+    }
+
+    /// 全部输出流
+    pub fn output_streams(&self) -> &[Stream] {
+        &self.output_streams
+    }
+
+    /// 被选中的输入流的映射, 按输出顺序排列
+    pub fn mappings(&self) -> &[StreamMapping] {
+        &self.mappings
+    }
+
+    /// 执行转码
+    pub fn run(self) -> TaoResult<TranscodeStats> {
+        self.run_with(|_, _| {})
+    }
+
+    /// 预计输出总时长 (秒), 由输入时长与裁剪窗口确定, 输入时长未知时为 None
+    pub fn total_duration(&self) -> Option<f64> {
+        let input = self
+            .demuxer
+            .duration()
+            .filter(|d| d.is_finite() && *d > 0.0);
+        let end = match (input, self.trim.end) {
+            (Some(input), Some(end)) => input.min(end),
+            (input, end) => input.or(end)?,
+        };
+        Some((end - self.trim.start).max(0.0))
+    }
+
+    /// 执行转码, 距上次回调超过 `interval` 时回调一次进度, 结束时回调最终进度
+    pub fn run_with_progress<F>(
+        self,
+        interval: Duration,
+        mut on_progress: F,
+    ) -> TaoResult<TranscodeStats>
+    where
+        F: FnMut(&ProgressReport),
+    {
+        let mut tracker = ProgressTracker::new(
+            &self.output_streams,
+            self.total_duration(),
+            interval,
+            Instant::now(),
+        );
+        let stats = self.run_with(|pkt, stream| {
+            tracker.record(pkt, stream);
+            if let Some(report) = tracker.poll(Instant::now()) {
+                on_progress(&report);
+            }
+        })?;
+        on_progress(&tracker.report(Instant::now(), true));
+        Ok(stats)
+    }
+
+    /// 执行转码, 每写出一个数据包调用一次 `on_packet(数据包, 所属输出流)`
+    pub fn run_with<F>(mut self, mut on_packet: F) -> TaoResult<TranscodeStats>
+    where
+        F: FnMut(&Packet, &Stream),
+    {
+        self.muxer
+            .write_header(&mut self.output_io, &self.output_streams)?;
+        self.seek_to_start();
+
+        // 直接复制的流以数据包为单位裁剪
+        let mut copy_trimmers: Vec<Option<PacketTrimmer>> = self
+            .copy_flags
+            .iter()
+            .map(|&copy| copy.then(|| PacketTrimmer::new(self.trim)))
+            .collect();
+        let mut stats = TranscodeStats::default();
+
+        loop {
+            // 所有输出流都到达结束时间后停止读取
+            if self.trim.end.is_some() && self.all_streams_finished(&copy_trimmers) {
+                break;
+            }
+            // 所有输出流都达到帧数上限后停止读取
+            if self.limiter.all_reached() {
+                break;
+            }
+
+            let input_pkt = match self.demuxer.read_packet(&mut self.input_io) {
+                Ok(pkt) => pkt,
+                Err(TaoError::Eof) => break,
+                Err(e) => return Err(e),
+            };
+            let stream_idx = input_pkt.stream_index;
+            let Some(out_idx) = self.output_indices.get(stream_idx).copied().flatten() else {
+                continue;
+            };
+            if self.limiter.is_reached(out_idx) {
+                continue;
+            }
+
+            let packets = if let Some(trimmer) = &mut copy_trimmers[stream_idx] {
+                // 直接复制路径: 时间戳换算到输出流时间基
+                let in_tb = self.input_streams[stream_idx].time_base;
+                let mut packets = trimmer.push(input_pkt, in_tb);
+                for pkt in &mut packets {
+                    pkt.stream_index = out_idx;
+                    rescale_packet(pkt, in_tb, self.output_streams[out_idx].time_base);
+                }
+                packets
+            } else if let Some(processor) = &mut self.processors[stream_idx] {
+                transcode_packet(processor, &input_pkt, out_idx)?
+            } else {
+                continue;
+            };
+            self.write_packets(out_idx, packets, &mut stats, &mut on_packet)?;
+        }
+
+        // 刷新编码器缓存, 出错时仅告警并保留已写出的数据
+        for idx in 0..self.processors.len() {
+            let (Some(processor), Some(out_idx)) =
+                (&mut self.processors[idx], self.output_indices[idx])
+            else {
+                continue;
+            };
+            match flush_encoder(processor, out_idx) {
+                Ok(packets) => self.write_packets(out_idx, packets, &mut stats, &mut on_packet)?,
+                Err(e) => warn!("刷新流 #{idx} 的编码器时出错: {e}"),
+            }
+        }
+
+        self.write_interleaved(true, &mut stats, &mut on_packet)?;
+        self.muxer.write_trailer(&mut self.output_io)?;
+        Ok(stats)
+    }
+
+    /// 定位到起始时间之前最近的关键帧, 之后逐帧解码并丢弃早于起点的帧
+    fn seek_to_start(&mut self) {
+        if self.trim.start <= 0.0 {
+            return;
+        }
+        let reference = self
+            .selected
+            .iter()
+            .copied()
+            .find(|&i| self.input_streams[i].media_type == MediaType::Video)
+            .or_else(|| self.selected.first().copied());
+        if let Some(idx) = reference {
+            let tb = self.input_streams[idx].time_base;
+            let ts = (self.trim.start * f64::from(tb.den) / f64::from(tb.num)).floor() as i64;
+            if let Err(e) = self
+                .demuxer
+                .seek(&mut self.input_io, idx, ts, SeekFlags::default())
+            {
+                warn!("无法定位到起始时间, 将从头读取: {e}");
+            }
+        }
+    }
+
+    /// 按帧数限制接收一组数据包, 经交错后写出
+    fn write_packets<F>(
+        &mut self,
+        out_idx: usize,
+        packets: Vec<Packet>,
+        stats: &mut TranscodeStats,
+        on_packet: &mut F,
+    ) -> TaoResult<()>
+    where
+        F: FnMut(&Packet, &Stream),
+    {
+        for pkt in packets {
+            if !self.limiter.admit(out_idx) {
+                break;
+            }
+            self.interleaver.push(pkt)?;
+        }
+        self.write_interleaved(false, stats, on_packet)
+    }
+
+    /// 写出交错器中可释放的数据包, `flush` 为真时写出全部剩余数据包
+    fn write_interleaved<F>(
+        &mut self,
+        flush: bool,
+        stats: &mut TranscodeStats,
+        on_packet: &mut F,
+    ) -> TaoResult<()>
+    where
+        F: FnMut(&Packet, &Stream),
+    {
+        while let Some(pkt) = self.interleaver.pop(flush) {
+            self.muxer.write_packet(&mut self.output_io, &pkt)?;
+            stats.packets += 1;
+            stats.total_size += pkt.data.len() as u64;
+            on_packet(&pkt, &self.output_streams[pkt.stream_index]);
+        }
+        Ok(())
+    }
+
+    /// 所有输出流是否都已到达裁剪窗口末尾
+    fn all_streams_finished(&self, copy_trimmers: &[Option<PacketTrimmer>]) -> bool {
+        self.selected
+            .iter()
+            .all(|&i| match (&copy_trimmers[i], &self.processors[i]) {
+                (Some(trimmer), _) => trimmer.is_finished(),
+                (None, Some(processor)) => processor.is_finished(),
+                // 未输出的流不影响结束判断
+                (None, None) => true,
+            })
+    }
+}
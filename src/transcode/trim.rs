@@ -0,0 +1,301 @@
+//! 起始时间/时长精确裁剪 (对标 ffmpeg `-ss` / `-t`).
+//!
+//! 转码路径从起始时间之前最近的关键帧开始解码, 丢弃早于起始时间的解码帧,
+//! 音频按采样裁剪首尾两帧, 使输出恰好覆盖 `[ss, ss + t)`.
+//! 直接复制路径无法解码, 以数据包为单位裁剪, 并保留起始时间所在 GOP 的关键帧.
+
+use tao_codec::frame::AudioFrame;
+use tao_codec::{Frame, FrameBuf, Packet};
+use tao_core::Rational;
+use tao_core::rational::rescale_q;
+use tao_core::timestamp::NOPTS_VALUE;
+
+use super::filter::pts_to_sec;
+
+/// 裁剪时间窗口 (秒)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TrimWindow {
+    /// 起始时间
+    pub(crate) start: f64,
+    /// 结束时间 (不含), None 表示到流结束
+    pub(crate) end: Option<f64>,
+}
+
+impl TrimWindow {
+    /// 由起始时间与时长构建
+    pub(crate) fn new(start: f64, duration: Option<f64>) -> Self {
+        let start = start.max(0.0);
+        Self {
+            start,
+            end: duration.map(|d| start + d.max(0.0)),
+        }
+    }
+
+    /// 不裁剪的窗口
+    pub(crate) fn unbounded() -> Self {
+        Self::new(0.0, None)
+    }
+}
+
+/// 单帧的裁剪结果
+#[derive(Debug)]
+pub(crate) enum TrimmedFrame {
+    /// 整帧早于起始时间, 丢弃
+    Before,
+    /// 帧 (可能已按采样截断) 落在窗口内
+    Keep(Frame),
+    /// 帧已到达结束时间, 该流结束
+    After,
+}
+
+/// 按窗口裁剪一帧解码数据
+///
+/// 无有效时间戳的帧原样保留.
+pub(crate) fn trim_frame(frame: Frame, window: &TrimWindow) -> TrimmedFrame {
+    let (pts, time_base) = match &frame {
+        Frame::Video(vf) => (vf.pts, vf.time_base),
+        Frame::Audio(af) => (af.pts, af.time_base),
+        // 字幕帧不携带时间基, 原样保留
+        Frame::Subtitle(sf) => (sf.pts, Rational::UNDEFINED),
+    };
+    if pts == NOPTS_VALUE || !time_base.is_valid() {
+        return TrimmedFrame::Keep(frame);
+    }
+
+    match frame {
+        Frame::Audio(af) if af.sample_rate > 0 => trim_audio_frame(af, window),
+        frame => {
+            let t = pts_to_sec(pts, time_base);
+            if window.end.is_some_and(|end| t >= end) {
+                TrimmedFrame::After
+            } else if t < window.start {
+                TrimmedFrame::Before
+            } else {
+                TrimmedFrame::Keep(frame)
+            }
+        }
+    }
+}
+
+/// 按采样裁剪音频帧
+fn trim_audio_frame(af: AudioFrame, window: &TrimWindow) -> TrimmedFrame {
+    let sample_rate = f64::from(af.sample_rate);
+    let sample_tb = Rational::new(1, af.sample_rate as i32);
+    let frame_start = rescale_q(af.pts, af.time_base, sample_tb);
+    let frame_end = frame_start + i64::from(af.nb_samples);
+    let start = (window.start * sample_rate).round() as i64;
+    let end = window.end.map(|e| (e * sample_rate).round() as i64);
+
+    if end.is_some_and(|end| frame_start >= end) {
+        return TrimmedFrame::After;
+    }
+    if frame_end <= start {
+        return TrimmedFrame::Before;
+    }
+
+    let skip = (start - frame_start).max(0) as u32;
+    let keep_end = end.map_or(af.nb_samples, |end| {
+        (end - frame_start).min(i64::from(af.nb_samples)) as u32
+    });
+    if skip == 0 && keep_end == af.nb_samples {
+        return TrimmedFrame::Keep(Frame::Audio(af));
+    }
+    TrimmedFrame::Keep(Frame::Audio(slice_audio_frame(&af, skip, keep_end)))
+}
+
+/// 截取音频帧中 `[from, to)` 范围的采样, 时间戳随之调整
+pub(crate) fn slice_audio_frame(af: &AudioFrame, from: u32, to: u32) -> AudioFrame {
+    let bytes_per_sample = af.sample_format.bytes_per_sample() as usize;
+    let stride = if af.sample_format.is_planar() {
+        bytes_per_sample
+    } else {
+        bytes_per_sample * af.channel_layout.channels as usize
+    };
+    let (begin, end) = (from as usize * stride, to as usize * stride);
+
+    let mut out = af.clone();
+    out.data = af
+        .data
+        .iter()
+        .map(|plane| {
+            let len = plane.len();
+            FrameBuf::from(&plane[begin.min(len)..end.min(len)])
+        })
+        .collect();
+    out.nb_samples = to - from;
+    let sample_tb = Rational::new(1, af.sample_rate as i32);
+    out.pts = af.pts + rescale_q(i64::from(from), sample_tb, af.time_base);
+    out.duration = rescale_q(i64::from(out.nb_samples), sample_tb, af.time_base);
+    out
+}
+
+/// 直接复制路径的数据包裁剪器
+///
+/// 起始时间之前的数据包先暂存 (遇到关键帧时清空), 到达起始时间后连同暂存的
+/// 关键帧一起写出, 保证输出从可解码的位置开始.
+pub(crate) struct PacketTrimmer {
+    window: TrimWindow,
+    preroll: Vec<Packet>,
+    started: bool,
+    finished: bool,
+}
+
+impl PacketTrimmer {
+    /// 创建裁剪器
+    pub(crate) fn new(window: TrimWindow) -> Self {
+        Self {
+            window,
+            preroll: Vec::new(),
+            started: window.start <= 0.0,
+            finished: false,
+        }
+    }
+
+    /// 是否已到达结束时间
+    pub(crate) fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// 送入一个数据包, 返回应写出的数据包
+    pub(crate) fn push(&mut self, pkt: Packet, time_base: Rational) -> Vec<Packet> {
+        if self.finished {
+            return Vec::new();
+        }
+        if pkt.pts == NOPTS_VALUE {
+            if self.started {
+                return vec![pkt];
+            }
+            self.preroll.push(pkt);
+            return Vec::new();
+        }
+
+        let t = pts_to_sec(pkt.pts, time_base);
+        if self.window.end.is_some_and(|end| t >= end) {
+            self.finished = true;
+            return Vec::new();
+        }
+        if self.started {
+            return vec![pkt];
+        }
+        if t < self.window.start {
+            if pkt.is_keyframe() {
+                self.preroll.clear();
+            }
+            self.preroll.push(pkt);
+            return Vec::new();
+        }
+
+        self.started = true;
+        let mut out = std::mem::take(&mut self.preroll);
+        if pkt.is_keyframe() {
+            // 起点本身可独立解码: 仅保留跨越起始时间的关键帧 (如音频帧)
+            let start = self.window.start;
+            out.retain(|p| {
+                p.is_keyframe()
+                    && p.duration > 0
+                    && pts_to_sec(p.pts + p.duration, time_base) > start
+            });
+        }
+        out.push(pkt);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tao_core::{ChannelLayout, SampleFormat};
+
+    /// 构造 44.1kHz 立体声 S16 帧, 采样值为全局采样序号
+    fn audio_frame(first_sample: i64, nb_samples: u32) -> Frame {
+        let mut af = AudioFrame::new(
+            nb_samples,
+            44100,
+            SampleFormat::S16,
+            ChannelLayout::from_channels(2),
+        );
+        let mut data = Vec::with_capacity(nb_samples as usize * 4);
+        for i in 0..i64::from(nb_samples) {
+            let v = (first_sample + i) as i16;
+            data.extend_from_slice(&v.to_le_bytes());
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        af.data = vec![FrameBuf::from(data)];
+        af.pts = first_sample;
+        af.time_base = Rational::new(1, 44100);
+        af.duration = i64::from(nb_samples);
+        Frame::Audio(af)
+    }
+
+    fn kept_audio(result: TrimmedFrame) -> AudioFrame {
+        match result {
+            TrimmedFrame::Keep(Frame::Audio(af)) => af,
+            other => panic!("应保留音频帧, 实际为 {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_trim_audio_frame_at_sample_level() {
+        // 窗口 [1000, 2000) 采样
+        let window = TrimWindow::new(1000.0 / 44100.0, Some(1000.0 / 44100.0));
+
+        assert!(
+            matches!(
+                trim_frame(audio_frame(0, 1000), &window),
+                TrimmedFrame::Before
+            ),
+            "结束于起点的帧应丢弃"
+        );
+
+        let head = kept_audio(trim_frame(audio_frame(900, 200), &window));
+        assert_eq!(head.nb_samples, 100, "跨越起点的帧应只保留起点之后的采样");
+        assert_eq!(head.pts, 1000, "截断后 PTS 应为起点");
+        assert_eq!(
+            &head.data[0][0..2],
+            &1000i16.to_le_bytes(),
+            "首个采样应为第 1000 个"
+        );
+
+        let tail = kept_audio(trim_frame(audio_frame(1900, 300), &window));
+        assert_eq!(tail.nb_samples, 100, "跨越终点的帧应截断到终点");
+        assert_eq!(tail.data[0].len(), 100 * 4, "数据长度应与采样数一致");
+
+        assert!(
+            matches!(
+                trim_frame(audio_frame(2000, 100), &window),
+                TrimmedFrame::After
+            ),
+            "起于终点的帧应结束该流"
+        );
+    }
+
+    #[test]
+    fn test_packet_trimmer_keeps_gop_before_start() {
+        let tb = Rational::new(1, 1000);
+        let packet = |pts: i64, key: bool| {
+            let mut pkt = Packet::empty();
+            pkt.pts = pts;
+            pkt.dts = pts;
+            pkt.duration = 40;
+            pkt.set_keyframe(key);
+            pkt
+        };
+        let mut trimmer = PacketTrimmer::new(TrimWindow::new(0.1, Some(0.1)));
+
+        assert!(trimmer.push(packet(0, true), tb).is_empty());
+        assert!(trimmer.push(packet(40, true), tb).is_empty());
+        assert!(trimmer.push(packet(80, false), tb).is_empty());
+        let out: Vec<i64> = trimmer
+            .push(packet(120, false), tb)
+            .iter()
+            .map(|p| p.pts)
+            .collect();
+        assert_eq!(out, vec![40, 80, 120], "应从起点之前最近的关键帧开始输出");
+        assert_eq!(trimmer.push(packet(160, false), tb).len(), 1);
+        assert!(
+            trimmer.push(packet(200, true), tb).is_empty(),
+            "到达终点后不再输出"
+        );
+        assert!(trimmer.is_finished());
+    }
+}
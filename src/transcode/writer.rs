@@ -0,0 +1,333 @@
+//! 媒体写入: 逐帧编码 + 交错封装.
+
+use tao_codec::{CodecId, CodecRegistry, Frame};
+use tao_core::rational::rescale_q;
+use tao_core::timestamp::NOPTS_VALUE;
+use tao_core::{ChannelLayout, PixelFormat, Rational, SampleFormat, TaoError, TaoResult};
+use tao_format::stream::{AudioStreamParams, Stream, StreamParams, VideoStreamParams};
+use tao_format::{FormatId, Interleaver, IoContext, Muxer};
+
+use super::processor::{FrameEncoder, KeyframeControl};
+
+/// 音频输出流参数
+///
+/// 采样率/声道布局/采样格式描述送入 [`MediaWriter::write_frame`] 的帧.
+/// 编码器不支持该采样格式或采样率时自动选择最接近的一种并插入重采样;
+/// 实际送入的帧参数与此不同时同样会被转换.
+#[derive(Debug, Clone)]
+pub struct AudioEncodeParams {
+    /// 输出编解码器
+    pub codec_id: CodecId,
+    /// 采样率 (Hz)
+    pub sample_rate: u32,
+    /// 声道布局
+    pub channel_layout: ChannelLayout,
+    /// 采样格式
+    pub sample_format: SampleFormat,
+    /// 编码器私有选项 (如 FLAC 的 `compression_level`)
+    pub options: Vec<(String, String)>,
+}
+
+impl AudioEncodeParams {
+    /// 创建音频输出流参数
+    pub fn new(
+        codec_id: CodecId,
+        sample_rate: u32,
+        channel_layout: ChannelLayout,
+        sample_format: SampleFormat,
+    ) -> Self {
+        Self {
+            codec_id,
+            sample_rate,
+            channel_layout,
+            sample_format,
+            options: Vec::new(),
+        }
+    }
+
+    /// 沿用输入音频流的采样参数, 以 `codec_id` 编码
+    pub fn from_stream(stream: &Stream, codec_id: CodecId) -> TaoResult<Self> {
+        match &stream.params {
+            StreamParams::Audio(a) => Ok(Self::new(
+                codec_id,
+                a.sample_rate,
+                a.channel_layout,
+                a.sample_format,
+            )),
+            _ => Err(TaoError::InvalidArgument(format!(
+                "流 #{} 不是音频流",
+                stream.index
+            ))),
+        }
+    }
+
+    /// 追加一个编码器私有选项
+    pub fn with_option(mut self, key: &str, value: &str) -> Self {
+        self.options.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    fn stream_params(&self) -> AudioStreamParams {
+        AudioStreamParams {
+            sample_rate: self.sample_rate,
+            channel_layout: self.channel_layout,
+            sample_format: self.sample_format,
+            bit_rate: 0,
+            frame_size: 0,
+            block_align: 0,
+        }
+    }
+}
+
+/// 视频输出流参数
+///
+/// 宽高/像素格式描述送入 [`MediaWriter::write_frame`] 的帧, 编码器不支持该像素格式时
+/// 自动转换. 帧时间戳以 `1/frame_rate` 为单位; 帧携带其他有效时间基时自动换算.
+#[derive(Debug, Clone)]
+pub struct VideoEncodeParams {
+    /// 输出编解码器
+    pub codec_id: CodecId,
+    /// 宽度 (像素)
+    pub width: u32,
+    /// 高度 (像素)
+    pub height: u32,
+    /// 像素格式
+    pub pixel_format: PixelFormat,
+    /// 帧率
+    pub frame_rate: Rational,
+}
+
+impl VideoEncodeParams {
+    /// 创建视频输出流参数
+    pub fn new(
+        codec_id: CodecId,
+        width: u32,
+        height: u32,
+        pixel_format: PixelFormat,
+        frame_rate: Rational,
+    ) -> Self {
+        Self {
+            codec_id,
+            width,
+            height,
+            pixel_format,
+            frame_rate,
+        }
+    }
+
+    /// 沿用输入视频流的尺寸/像素格式/帧率, 以 `codec_id` 编码
+    pub fn from_stream(stream: &Stream, codec_id: CodecId) -> TaoResult<Self> {
+        match &stream.params {
+            StreamParams::Video(v) => Ok(Self::new(
+                codec_id,
+                v.width,
+                v.height,
+                v.pixel_format,
+                v.frame_rate,
+            )),
+            _ => Err(TaoError::InvalidArgument(format!(
+                "流 #{} 不是视频流",
+                stream.index
+            ))),
+        }
+    }
+
+    fn stream_params(&self) -> VideoStreamParams {
+        VideoStreamParams {
+            width: self.width,
+            height: self.height,
+            pixel_format: self.pixel_format,
+            frame_rate: self.frame_rate,
+            sample_aspect_ratio: Rational::new(1, 1),
+            bit_rate: 0,
+            color_space: Default::default(),
+            color_range: Default::default(),
+        }
+    }
+}
+
+/// 媒体写入器
+///
+/// 添加输出流后逐帧写入, 内部完成编码并按 DTS 交错各流的数据包.
+/// 容器头部在首次写入时输出, 必须调用 [`finish`](Self::finish) 刷新编码器并写入尾部.
+///
+/// ```rust,no_run
+/// use tao::MediaWriter;
+/// use tao::codec::CodecId;
+///
+/// let mut reader = tao::MediaReader::open("input.wav")?;
+/// let mut writer = MediaWriter::create("output.flac")?;
+/// let params = tao::AudioEncodeParams::from_stream(&reader.streams()[0], CodecId::Flac)?;
+/// let out = writer.add_audio_stream(params)?;
+/// while let Some((_, frame)) = reader.read_frame()? {
+///     writer.write_frame(out, frame)?;
+/// }
+/// writer.finish()?;
+/// # Ok::<(), tao::core::TaoError>(())
+/// ```
+pub struct MediaWriter {
+    io: IoContext,
+    muxer: Box<dyn Muxer>,
+    codec_registry: CodecRegistry,
+    streams: Vec<Stream>,
+    encoders: Vec<FrameEncoder>,
+    /// 按 DTS 交错各流已编码的数据包
+    interleaver: Interleaver,
+    /// 是否已开始写入 (之后不能再添加流或设置选项)
+    started: bool,
+    header_written: bool,
+}
+
+impl MediaWriter {
+    /// 创建输出文件, 容器格式由扩展名推断
+    pub fn create(path: &str) -> TaoResult<Self> {
+        let format = FormatId::from_filename(path).ok_or_else(|| {
+            TaoError::FormatNotFound(format!("无法从输出文件名确定格式: '{path}'"))
+        })?;
+        Self::create_format(path, format)
+    }
+
+    /// 以指定容器格式创建输出文件
+    pub fn create_format(path: &str, format: FormatId) -> TaoResult<Self> {
+        let muxer = crate::default_format_registry().create_muxer(format)?;
+        let io = IoContext::open_read_write(path)?;
+        Ok(Self::with_muxer(io, muxer))
+    }
+
+    pub(crate) fn with_muxer(io: IoContext, muxer: Box<dyn Muxer>) -> Self {
+        Self {
+            io,
+            muxer,
+            codec_registry: crate::default_codec_registry(),
+            streams: Vec::new(),
+            encoders: Vec::new(),
+            interleaver: Interleaver::new(&[]),
+            started: false,
+            header_written: false,
+        }
+    }
+
+    /// 设置封装器私有选项 (如 MP4 的 `fragmented`), 须在首次写入前设置
+    pub fn set_option(&mut self, key: &str, value: &str) -> TaoResult<()> {
+        self.ensure_not_started()?;
+        self.muxer.set_option(key, value)
+    }
+
+    /// 添加音频输出流, 返回输出流索引
+    pub fn add_audio_stream(&mut self, params: AudioEncodeParams) -> TaoResult<usize> {
+        self.ensure_not_started()?;
+        let (encoder, stream) = FrameEncoder::audio(
+            &params.stream_params(),
+            params.codec_id,
+            None,
+            &self.codec_registry,
+            None,
+            None,
+            None,
+            &params.options,
+            self.muxer.wants_global_header(),
+        )?;
+        Ok(self.push_stream(encoder, stream))
+    }
+
+    /// 添加视频输出流, 返回输出流索引
+    pub fn add_video_stream(&mut self, params: VideoEncodeParams) -> TaoResult<usize> {
+        self.ensure_not_started()?;
+        let (encoder, stream) = FrameEncoder::video(
+            &params.stream_params(),
+            params.codec_id,
+            None,
+            &self.codec_registry,
+            None,
+            None,
+            None,
+            &KeyframeControl::default(),
+            self.muxer.wants_global_header(),
+        )?;
+        Ok(self.push_stream(encoder, stream))
+    }
+
+    /// 全部输出流
+    pub fn streams(&self) -> &[Stream] {
+        &self.streams
+    }
+
+    /// 编码并写入一帧
+    pub fn write_frame(&mut self, stream_index: usize, frame: Frame) -> TaoResult<()> {
+        if stream_index >= self.streams.len() {
+            return Err(TaoError::StreamNotFound(stream_index));
+        }
+        self.started = true;
+        let frame = match frame {
+            Frame::Video(mut vf) => {
+                let time_base = self.streams[stream_index].time_base;
+                if vf.time_base.is_valid() && vf.time_base != time_base {
+                    if vf.pts != NOPTS_VALUE {
+                        vf.pts = rescale_q(vf.pts, vf.time_base, time_base);
+                    }
+                    vf.duration = rescale_q(vf.duration, vf.time_base, time_base);
+                    vf.time_base = time_base;
+                }
+                Frame::Video(vf)
+            }
+            frame => frame,
+        };
+        let mut packets = Vec::new();
+        self.encoders[stream_index].encode(frame, stream_index, &mut packets)?;
+        for pkt in packets {
+            self.interleaver.push(pkt)?;
+        }
+        self.write_interleaved(false)
+    }
+
+    /// 刷新编码器, 写出剩余数据包与容器尾部
+    pub fn finish(mut self) -> TaoResult<()> {
+        self.started = true;
+        for (idx, encoder) in self.encoders.iter_mut().enumerate() {
+            for pkt in encoder.flush(idx)? {
+                self.interleaver.push(pkt)?;
+            }
+        }
+        self.write_interleaved(true)?;
+        self.write_header_once()?;
+        self.muxer.write_trailer(&mut self.io)
+    }
+
+    fn ensure_not_started(&self) -> TaoResult<()> {
+        if self.started {
+            return Err(TaoError::InvalidArgument(
+                "已开始写入, 不能再添加流或设置选项".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn push_stream(&mut self, encoder: FrameEncoder, mut stream: Stream) -> usize {
+        let index = self.streams.len();
+        stream.index = index;
+        self.interleaver.add_stream(&stream);
+        self.streams.push(stream);
+        self.encoders.push(encoder);
+        index
+    }
+
+    fn write_header_once(&mut self) -> TaoResult<()> {
+        if !self.header_written {
+            if self.streams.is_empty() {
+                return Err(TaoError::InvalidArgument("没有添加任何输出流".into()));
+            }
+            self.muxer.write_header(&mut self.io, &self.streams)?;
+            self.header_written = true;
+        }
+        Ok(())
+    }
+
+    /// 写出交错器中可释放的数据包, `flush` 为真时写出全部剩余数据包
+    fn write_interleaved(&mut self, flush: bool) -> TaoResult<()> {
+        while let Some(pkt) = self.interleaver.pop(flush) {
+            self.write_header_once()?;
+            self.muxer.write_packet(&mut self.io, &pkt)?;
+        }
+        Ok(())
+    }
+}
@@ -57,6 +57,7 @@ fn make_aac_stream(sample_rate: u32, channels: u32) -> Stream {
             sample_format: SampleFormat::F32,
             bit_rate: 0,
             frame_size: 1024,
+            block_align: 0,
         }),
         metadata: Vec::new(),
     }
@@ -79,6 +80,7 @@ fn make_mp3_stream(sample_rate: u32, channels: u32) -> Stream {
             sample_format: SampleFormat::F32,
             bit_rate: 128000,
             frame_size: 1152,
+            block_align: 0,
         }),
         metadata: Vec::new(),
     }
@@ -142,6 +144,7 @@ fn test_aac_encode_silence_frame() {
             channel_layout: ChannelLayout::from_channels(2),
             sample_format: SampleFormat::F32,
             frame_size: 1024,
+            block_align: 0,
         }),
     };
     encoder.open(&params).unwrap();
@@ -149,7 +152,7 @@ fn test_aac_encode_silence_frame() {
     // 创建静音帧
     let pcm_data = generate_silence_f32(1024, 2);
     let frame = AudioFrame {
-        data: vec![pcm_data],
+        data: vec![pcm_data.into()],
         nb_samples: 1024,
         sample_rate: 44100,
         channel_layout: ChannelLayout::from_channels(2),
@@ -183,13 +186,14 @@ fn test_aac_codec_roundtrip_mute() {
             channel_layout: ChannelLayout::from_channels(2),
             sample_format: SampleFormat::F32,
             frame_size: 1024,
+            block_align: 0,
         }),
     };
     encoder.open(&params).unwrap();
 
     let pcm_data = generate_silence_f32(1024, 2);
     let frame = AudioFrame {
-        data: vec![pcm_data],
+        data: vec![pcm_data.into()],
         nb_samples: 1024,
         sample_rate: 44100,
         channel_layout: ChannelLayout::from_channels(2),
@@ -213,6 +217,7 @@ fn test_aac_codec_roundtrip_mute() {
             channel_layout: ChannelLayout::from_channels(2),
             sample_format: SampleFormat::F32,
             frame_size: 1024,
+            block_align: 0,
         }),
     };
     decoder.open(&dec_params).unwrap();
@@ -289,6 +294,7 @@ fn test_aac_full_pipeline_encode_mux_demux_decode() {
             channel_layout: ChannelLayout::from_channels(2),
             sample_format: SampleFormat::F32,
             frame_size: 1024,
+            block_align: 0,
         }),
     };
     encoder.open(&params).unwrap();
@@ -298,7 +304,7 @@ fn test_aac_full_pipeline_encode_mux_demux_decode() {
     for i in 0..2 {
         let pcm_data = generate_silence_f32(1024, 2);
         let frame = AudioFrame {
-            data: vec![pcm_data],
+            data: vec![pcm_data.into()],
             nb_samples: 1024,
             sample_rate: 44100,
             channel_layout: ChannelLayout::from_channels(2),
@@ -363,6 +369,7 @@ fn test_aac_full_pipeline_encode_mux_demux_decode() {
             channel_layout: ChannelLayout::from_channels(2),
             sample_format: SampleFormat::F32,
             frame_size: 1024,
+            block_align: 0,
         }),
     };
     decoder.open(&dec_params).unwrap();
@@ -424,6 +431,7 @@ fn test_aac_encode_sine_wave() {
             channel_layout: ChannelLayout::from_channels(1),
             sample_format: SampleFormat::F32,
             frame_size: 1024,
+            block_align: 0,
         }),
     };
     encoder.open(&params).unwrap();
@@ -431,7 +439,7 @@ fn test_aac_encode_sine_wave() {
     // 编码 440Hz 正弦波
     let pcm_data = generate_sine_f32(44100, 440.0, 1024, 1);
     let frame = AudioFrame {
-        data: vec![pcm_data],
+        data: vec![pcm_data.into()],
         nb_samples: 1024,
         sample_rate: 44100,
         channel_layout: ChannelLayout::from_channels(1),
@@ -0,0 +1,185 @@
+//! AVI OpenDML 集成测试.
+//!
+//! 使用稀疏内存后端生成超过 4 GiB 的 AVI 文件 (全零数据不实际占用内存),
+//! 验证封装器写出 AVIX/indx/ix## 后, 解封装器能读取完整时长并定位到 4 GiB 之后的数据.
+
+use std::collections::HashMap;
+use std::io;
+
+use bytes::Bytes;
+use tao_codec::{CodecId, Packet};
+use tao_core::{MediaType, PixelFormat, Rational, TaoError};
+use tao_format::demuxer::SeekFlags;
+use tao_format::format_id::FormatId;
+use tao_format::io::{IoBackend, IoContext};
+use tao_format::registry::FormatRegistry;
+use tao_format::stream::{Stream, StreamParams, VideoStreamParams};
+
+/// 稀疏后端的页大小
+const PAGE_SIZE: usize = 4096;
+/// 全零页, 用于快速判断写入内容是否为零
+static ZERO_PAGE: [u8; PAGE_SIZE] = [0; PAGE_SIZE];
+
+/// 每个视频包的大小 (64 MiB)
+const PACKET_SIZE: usize = 64 << 20;
+/// 视频包数量, 总数据量 4.5 GiB
+const PACKET_COUNT: usize = 72;
+
+/// 稀疏内存后端: 只保存包含非零数据的页, 未写入或全零的页读取为零
+struct SparseBackend {
+    pages: HashMap<u64, Box<[u8; PAGE_SIZE]>>,
+    len: u64,
+    pos: u64,
+}
+
+impl SparseBackend {
+    fn new() -> Self {
+        Self {
+            pages: HashMap::new(),
+            len: 0,
+            pos: 0,
+        }
+    }
+}
+
+impl IoBackend for SparseBackend {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let to_read = (self.len.saturating_sub(self.pos) as usize).min(buf.len());
+        let mut done = 0;
+        while done < to_read {
+            let page = self.pos / PAGE_SIZE as u64;
+            let in_page = (self.pos % PAGE_SIZE as u64) as usize;
+            let n = (PAGE_SIZE - in_page).min(to_read - done);
+            match self.pages.get(&page) {
+                Some(data) => buf[done..done + n].copy_from_slice(&data[in_page..in_page + n]),
+                None => buf[done..done + n].fill(0),
+            }
+            done += n;
+            self.pos += n as u64;
+        }
+        Ok(to_read)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut done = 0;
+        while done < buf.len() {
+            let page = self.pos / PAGE_SIZE as u64;
+            let in_page = (self.pos % PAGE_SIZE as u64) as usize;
+            let n = (PAGE_SIZE - in_page).min(buf.len() - done);
+            let src = &buf[done..done + n];
+            if let Some(data) = self.pages.get_mut(&page) {
+                data[in_page..in_page + n].copy_from_slice(src);
+            } else if src != &ZERO_PAGE[..n] {
+                let mut data = Box::new([0u8; PAGE_SIZE]);
+                data[in_page..in_page + n].copy_from_slice(src);
+                self.pages.insert(page, data);
+            }
+            done += n;
+            self.pos += n as u64;
+        }
+        self.len = self.len.max(self.pos);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.write(buf).map(|_| ())
+    }
+
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.pos = match pos {
+            io::SeekFrom::Start(p) => p,
+            io::SeekFrom::Current(d) => self.pos.checked_add_signed(d).unwrap(),
+            io::SeekFrom::End(d) => self.len.checked_add_signed(d).unwrap(),
+        };
+        Ok(self.pos)
+    }
+
+    fn position(&mut self) -> io::Result<u64> {
+        Ok(self.pos)
+    }
+
+    fn size(&self) -> Option<u64> {
+        Some(self.len)
+    }
+
+    fn is_seekable(&self) -> bool {
+        true
+    }
+}
+
+fn make_video_stream() -> Stream {
+    Stream {
+        index: 0,
+        media_type: MediaType::Video,
+        codec_id: CodecId::RawVideo,
+        time_base: Rational::new(1, 25),
+        duration: -1,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data: Vec::new(),
+        params: StreamParams::Video(VideoStreamParams {
+            width: 4096,
+            height: 4096,
+            pixel_format: PixelFormat::Rgba,
+            frame_rate: Rational::new(25, 1),
+            sample_aspect_ratio: Rational::new(1, 1),
+            bit_rate: 0,
+            color_space: Default::default(),
+            color_range: Default::default(),
+        }),
+        metadata: Vec::new(),
+    }
+}
+
+#[test]
+fn test_avi_opendml_over_4gib_roundtrip() {
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+
+    // 1) 写入 4.5 GiB 的全零视频帧, 所有数据包共享同一块缓冲
+    let mut io = IoContext::new(Box::new(SparseBackend::new()));
+    let mut muxer = registry.create_muxer(FormatId::Avi).unwrap();
+    muxer.write_header(&mut io, &[make_video_stream()]).unwrap();
+    let frame = Bytes::from(vec![0u8; PACKET_SIZE]);
+    for i in 0..PACKET_COUNT {
+        let mut pkt = Packet::from_data(frame.clone());
+        pkt.stream_index = 0;
+        pkt.set_keyframe(i % 10 == 0);
+        muxer.write_packet(&mut io, &pkt).unwrap();
+    }
+    muxer.write_trailer(&mut io).unwrap();
+    let file_size = io.position().unwrap();
+    assert!(file_size > 4 << 30, "文件应超过 4 GiB, 实际 {file_size}");
+
+    // 2) 重新打开, 时长与帧数应覆盖全部 RIFF 块
+    io.seek(std::io::SeekFrom::Start(0)).unwrap();
+    let mut demuxer = registry.create_demuxer(FormatId::Avi).unwrap();
+    demuxer.open(&mut io).unwrap();
+    let stream = &demuxer.streams()[0];
+    assert_eq!(
+        stream.nb_frames, PACKET_COUNT as u64,
+        "nb_frames 应覆盖整个文件"
+    );
+    assert_eq!(stream.duration, PACKET_COUNT as i64, "时长应覆盖整个文件");
+    let duration = demuxer.duration().expect("应有时长");
+    assert!(
+        (duration - PACKET_COUNT as f64 / 25.0).abs() < 1e-6,
+        "时长应为 {} 秒, 实际 {duration}",
+        PACKET_COUNT as f64 / 25.0
+    );
+
+    // 3) 定位到末尾附近: 回退到第 70 帧关键帧, 位于 4 GiB 之后
+    demuxer
+        .seek(&mut io, 0, PACKET_COUNT as i64 - 1, SeekFlags::default())
+        .unwrap();
+    let pkt = demuxer.read_packet(&mut io).unwrap();
+    assert_eq!(pkt.pts, 70, "应定位到最近的关键帧");
+    assert!(pkt.is_keyframe(), "定位后的首个数据包应为关键帧");
+    assert!(pkt.pos > 4 << 30, "数据偏移应超过 4 GiB, 实际 {}", pkt.pos);
+    assert_eq!(pkt.data.len(), PACKET_SIZE, "数据包大小应保持不变");
+
+    let pkt = demuxer.read_packet(&mut io).unwrap();
+    assert_eq!(pkt.pts, 71);
+    assert!(!pkt.is_keyframe(), "非关键帧标志应来自 ix## 索引");
+    assert!(matches!(demuxer.read_packet(&mut io), Err(TaoError::Eof)));
+}
@@ -0,0 +1,130 @@
+//! 集成测试: 在 tao-codec 外部注册自定义编解码器.
+//!
+//! 验证下游 crate 可在运行时插入自有解码器/编码器, 并按名称创建与查询能力描述.
+
+use tao::codec::{
+    CodecDescriptor, CodecId, CodecRegistry, Decoder, Encoder, Frame, Packet, frame::AudioFrame,
+};
+use tao::core::{ChannelLayout, SampleFormat, TaoError, TaoResult};
+
+/// 测试用的外部解码器: 每个包输出一帧静音
+struct DummyDecoder {
+    pending: Option<Frame>,
+}
+
+impl DummyDecoder {
+    fn create() -> TaoResult<Box<dyn Decoder>> {
+        Ok(Box::new(Self { pending: None }))
+    }
+}
+
+impl Decoder for DummyDecoder {
+    fn codec_id(&self) -> CodecId {
+        CodecId::Opus
+    }
+
+    fn name(&self) -> &str {
+        "dummy_opus"
+    }
+
+    fn send_packet(&mut self, _packet: &Packet) -> TaoResult<()> {
+        let mut af = AudioFrame::new(4, 48000, SampleFormat::S16, ChannelLayout::MONO);
+        af.data[0] = vec![0u8; 8].into();
+        self.pending = Some(Frame::Audio(af));
+        Ok(())
+    }
+
+    fn receive_frame(&mut self) -> TaoResult<Frame> {
+        self.pending.take().ok_or(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) {
+        self.pending = None;
+    }
+}
+
+/// 测试用的外部编码器: 不产生任何数据包
+struct DummyEncoder;
+
+impl DummyEncoder {
+    fn create() -> TaoResult<Box<dyn Encoder>> {
+        Ok(Box::new(Self))
+    }
+}
+
+impl Encoder for DummyEncoder {
+    fn codec_id(&self) -> CodecId {
+        CodecId::PcmS16le
+    }
+
+    fn name(&self) -> &str {
+        "dummy_pcm"
+    }
+
+    fn send_frame(&mut self, _frame: Option<&Frame>) -> TaoResult<()> {
+        Ok(())
+    }
+
+    fn receive_packet(&mut self) -> TaoResult<Packet> {
+        Err(TaoError::NeedMoreData)
+    }
+
+    fn flush(&mut self) {}
+}
+
+#[test]
+fn test_codec_registry_external_decoder_by_name() {
+    let mut registry = CodecRegistry::new();
+    tao::codec::register_all(&mut registry);
+    registry.register_decoder_with_name(CodecId::Opus, "dummy_opus", DummyDecoder::create);
+
+    // 按名称创建并解码一个包
+    let mut dec = registry
+        .create_decoder_by_name("dummy_opus")
+        .expect("应能按名称创建外部解码器");
+    assert_eq!(dec.name(), "dummy_opus", "应创建外部注册的实现");
+    dec.send_packet(&Packet::from_data(vec![1, 2, 3]))
+        .expect("送包失败");
+    let frame = dec.receive_frame().expect("应输出一帧");
+    assert!(matches!(frame, Frame::Audio(_)), "应输出音频帧");
+
+    // 按 CodecId 查找同样得到外部实现
+    let desc = registry.find_decoder(CodecId::Opus).expect("应能查到描述");
+    assert_eq!(desc.name, "dummy_opus", "描述名称不匹配");
+    assert!(
+        registry.create_decoder(CodecId::Opus).is_ok(),
+        "按 ID 创建失败"
+    );
+}
+
+#[test]
+fn test_codec_registry_external_encoder_overrides_builtin() {
+    let mut registry = CodecRegistry::new();
+    tao::codec::register_all(&mut registry);
+    registry.register_encoder_with_name(CodecId::PcmS16le, "dummy_pcm", DummyEncoder::create);
+
+    // 首选实现被替换, 内置实现仍可按名称选择
+    let enc = registry
+        .create_encoder(CodecId::PcmS16le)
+        .expect("按 ID 创建失败");
+    assert_eq!(enc.name(), "dummy_pcm", "外部实现应成为首选");
+    let builtin = registry
+        .create_encoder_by_name("pcm_s16le")
+        .expect("内置实现应仍可按名称创建");
+    assert_eq!(builtin.codec_id(), CodecId::PcmS16le);
+
+    // 带能力描述注册
+    registry.register_encoder_descriptor(
+        CodecDescriptor::new(CodecId::Opus, "dummy_opus_enc")
+            .with_sample_formats(&[SampleFormat::F32, SampleFormat::S16]),
+        DummyEncoder::create,
+    );
+    let desc = registry
+        .find_encoder_by_name("dummy_opus_enc")
+        .expect("应能按名称查询描述");
+    assert_eq!(
+        desc.sample_formats,
+        vec![SampleFormat::F32, SampleFormat::S16],
+        "采样格式能力不匹配"
+    );
+}
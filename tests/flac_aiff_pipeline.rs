@@ -58,6 +58,7 @@ fn mux_aiff_in_memory(
             sample_format: SampleFormat::S16,
             bit_rate: 0,
             frame_size: 0,
+            block_align: 0,
         }),
         metadata: Vec::new(),
     };
@@ -144,6 +145,7 @@ fn test_aiff_decode_s16be_roundtrip() {
             channel_layout: ChannelLayout::MONO,
             sample_format: SampleFormat::S16,
             frame_size: 0,
+            block_align: 0,
         }),
     };
     decoder.open(&params).unwrap();
@@ -448,6 +450,7 @@ fn test_flac_full_pipeline_demux_decode_constant() {
             channel_layout: ChannelLayout::MONO,
             sample_format: SampleFormat::S16,
             frame_size: 256,
+            block_align: 0,
         }),
     };
     decoder.open(&params).unwrap();
@@ -507,6 +510,7 @@ fn test_flac_full_pipeline_stereo_constant() {
             channel_layout: ChannelLayout::STEREO,
             sample_format: SampleFormat::S16,
             frame_size: 256,
+            block_align: 0,
         }),
     };
     decoder.open(&params).unwrap();
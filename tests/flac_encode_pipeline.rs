@@ -72,6 +72,7 @@ fn encode_to_flac_memory(
             channel_layout: ChannelLayout::from_channels(channels),
             sample_format: SampleFormat::S16,
             frame_size: block_size,
+            block_align: 0,
         }),
     };
     encoder.open(&enc_params).unwrap();
@@ -98,6 +99,7 @@ fn encode_to_flac_memory(
             sample_format: SampleFormat::S16,
             bit_rate: 0,
             frame_size: block_size,
+            block_align: 0,
         }),
         metadata: Vec::new(),
     };
@@ -120,7 +122,7 @@ fn encode_to_flac_memory(
             SampleFormat::S16,
             ChannelLayout::from_channels(channels),
         );
-        af.data[0] = chunk.to_vec();
+        af.data[0] = chunk.to_vec().into();
         af.pts = i64::from(sample_offset);
         af.time_base = Rational::new(1, sample_rate as i32);
 
@@ -173,6 +175,7 @@ fn decode_from_flac_memory(
             channel_layout: audio_params.channel_layout,
             sample_format: audio_params.sample_format,
             frame_size: audio_params.frame_size,
+            block_align: 0,
         }),
     };
     decoder.open(&dec_params).unwrap();
@@ -0,0 +1,147 @@
+//! 帧缓冲池集成测试.
+//!
+//! 使用计数分配器验证 RawVideo 解码输出复用池化缓冲, 稳定后每帧不再分配平面内存,
+//! 同时输出内容与未池化时一致.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use tao_codec::codec_parameters::{CodecParamsType, VideoCodecParams};
+use tao_codec::{CodecId, CodecParameters, CodecRegistry, Decoder, Frame, Packet};
+use tao_core::{PixelFormat, Rational};
+
+/// 按线程统计不小于 LARGE_ALLOC 字节的分配次数 (测试并行运行, 互不干扰)
+struct CountingAlloc;
+
+const LARGE_ALLOC: usize = 64 * 1024;
+
+thread_local! {
+    static LARGE_ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count_large(size: usize) {
+    if size >= LARGE_ALLOC {
+        let _ = LARGE_ALLOCS.try_with(|c| c.set(c.get() + 1));
+    }
+}
+
+fn large_allocs() -> usize {
+    LARGE_ALLOCS.with(Cell::get)
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_large(layout.size());
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_large(new_size);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const WIDTH: u32 = 640;
+const HEIGHT: u32 = 480;
+const FRAME_COUNT: usize = 32;
+
+fn hash_bytes(data: &[u8]) -> u64 {
+    let mut h = DefaultHasher::new();
+    data.hash(&mut h);
+    h.finish()
+}
+
+fn make_packet(index: usize) -> Packet {
+    let size = WIDTH as usize * HEIGHT as usize * 3 / 2;
+    let data: Vec<u8> = (0..size).map(|i| (i + index * 7) as u8).collect();
+    let mut pkt = Packet::from_data(data);
+    pkt.pts = index as i64;
+    pkt.time_base = Rational::new(1, 25);
+    pkt
+}
+
+fn open_rawvideo_decoder() -> Box<dyn Decoder> {
+    let mut registry = CodecRegistry::new();
+    tao_codec::register_all(&mut registry);
+    let mut decoder = registry.create_decoder(CodecId::RawVideo).unwrap();
+    decoder
+        .open(&CodecParameters {
+            codec_id: CodecId::RawVideo,
+            extra_data: Vec::new(),
+            bit_rate: 0,
+            params: CodecParamsType::Video(VideoCodecParams {
+                width: WIDTH,
+                height: HEIGHT,
+                pixel_format: PixelFormat::Yuv420p,
+                frame_rate: Rational::new(25, 1),
+                sample_aspect_ratio: Rational::new(1, 1),
+            }),
+        })
+        .unwrap();
+    decoder
+}
+
+#[test]
+fn test_rawvideo_decode_reuses_pooled_buffers() {
+    let mut decoder = open_rawvideo_decoder();
+
+    let packets: Vec<Packet> = (0..FRAME_COUNT).map(make_packet).collect();
+
+    // 预热: 首帧从分配器获取平面内存, 释放后归还缓冲池
+    decoder.send_packet(&packets[0]).unwrap();
+    drop(decoder.receive_frame().unwrap());
+
+    let before = large_allocs();
+    for pkt in &packets[1..] {
+        decoder.send_packet(pkt).unwrap();
+        let frame = decoder.receive_frame().unwrap();
+        let Frame::Video(vf) = &frame else {
+            panic!("应输出视频帧");
+        };
+        let mut out = Vec::with_capacity(pkt.data.len());
+        for plane in &vf.data {
+            out.extend_from_slice(plane);
+        }
+        assert_eq!(
+            hash_bytes(&out),
+            hash_bytes(&pkt.data),
+            "第 {} 帧输出内容与输入不一致",
+            pkt.pts
+        );
+    }
+    let after = large_allocs();
+
+    // 每帧仅校验用的 out 缓冲会产生一次大块分配, 平面缓冲全部来自缓冲池
+    assert_eq!(
+        after - before,
+        FRAME_COUNT - 1,
+        "稳定后解码输出不应再分配平面内存"
+    );
+}
+
+#[test]
+fn test_frame_clone_shares_planes() {
+    let mut decoder = open_rawvideo_decoder();
+    decoder.send_packet(&make_packet(0)).unwrap();
+    let frame = decoder.receive_frame().unwrap();
+
+    let before = large_allocs();
+    let cloned = frame.clone();
+    assert_eq!(large_allocs(), before, "克隆帧不应复制平面数据");
+
+    let (Frame::Video(a), Frame::Video(mut b)) = (frame, cloned) else {
+        panic!("应输出视频帧");
+    };
+    assert!(a.data[0].ptr_eq(&b.data[0]), "克隆帧应共享平面缓冲");
+    b.data[0][0] ^= 0xFF;
+    assert_ne!(a.data[0][0], b.data[0][0], "写入克隆帧不应影响原帧");
+}
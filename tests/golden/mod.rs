@@ -0,0 +1,413 @@
+//! 解码器黄金文件回归测试框架.
+//!
+//! 解封装并解码一个短测试向量, 逐帧与 `tests/fixtures/golden/` 下的黄金文件比较.
+//! 视频按可见区域逐平面比较 (8bit 样点), 音频按采样格式逐样本比较.
+//!
+//! 设置环境变量 `TAO_UPDATE_GOLDEN=1` 运行测试时, 以当前解码输出重写黄金文件.
+//! 重写后须人工核对输出正确再提交.
+//!
+//! 黄金文件格式 (小端):
+//! - 魔数 `TAOGOLD\0`, 版本 u32
+//! - 帧数 u32
+//! - 每帧: 媒体类型 u8 (0 视频 / 1 音频), 格式名 (u32 长度 + UTF-8),
+//!   尺寸 u32 x 2 (视频为宽高, 音频为样本数与声道数), 平面数 u32,
+//!   每个平面 u32 长度 + 数据
+
+use std::path::{Path, PathBuf};
+
+use tao::codec::codec_parameters::{AudioCodecParams, CodecParamsType, VideoCodecParams};
+use tao::codec::frame::Frame;
+use tao::codec::packet::Packet;
+use tao::codec::{CodecId, CodecParameters, CodecRegistry};
+use tao::core::{SampleFormat, TaoError};
+use tao::format::stream::StreamParams;
+use tao::format::{FormatRegistry, IoContext};
+
+const MAGIC: &[u8; 8] = b"TAOGOLD\0";
+const VERSION: u32 = 1;
+const MEDIA_VIDEO: u8 = 0;
+const MEDIA_AUDIO: u8 = 1;
+
+/// 单帧解码结果
+#[derive(Debug, Clone, PartialEq)]
+struct GoldenFrame {
+    media: u8,
+    format: String,
+    dims: [u32; 2],
+    planes: Vec<Vec<u8>>,
+}
+
+/// 解码 `input_file` 并与 `golden_file` 逐帧比较
+///
+/// 路径相对于仓库根目录. `tolerance` 为允许的最大逐样点绝对误差:
+/// 视频按 8bit 样点计, 音频整数格式按样本值计, 浮点格式按 16bit 量化单位计.
+pub fn assert_decode_matches(codec: CodecId, input_file: &str, golden_file: &str, tolerance: u8) {
+    let input = repo_path(input_file);
+    let golden = repo_path(golden_file);
+    let actual = decode_file(codec, &input);
+    assert!(!actual.is_empty(), "{input_file} 未解码出任何帧");
+
+    if std::env::var_os("TAO_UPDATE_GOLDEN").is_some() {
+        std::fs::write(&golden, encode_golden(&actual))
+            .unwrap_or_else(|e| panic!("写入黄金文件 {} 失败: {e}", golden.display()));
+        return;
+    }
+
+    let data = std::fs::read(&golden).unwrap_or_else(|e| {
+        panic!(
+            "读取黄金文件 {} 失败: {e} (可设置 TAO_UPDATE_GOLDEN=1 生成)",
+            golden.display()
+        )
+    });
+    let expected = decode_golden(&data);
+    assert_eq!(
+        actual.len(),
+        expected.len(),
+        "{input_file} 解码帧数与黄金文件不一致"
+    );
+    for (index, (a, e)) in actual.iter().zip(&expected).enumerate() {
+        compare_frame(index, a, e, tolerance);
+    }
+}
+
+fn repo_path(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(path)
+}
+
+/// 解封装并解码指定编解码器的第一条流, 返回全部输出帧
+fn decode_file(codec: CodecId, path: &Path) -> Vec<GoldenFrame> {
+    let mut format_registry = FormatRegistry::new();
+    tao::format::register_all(&mut format_registry);
+    let mut codec_registry = CodecRegistry::new();
+    tao::codec::register_all(&mut codec_registry);
+
+    let path_str = path.to_str().expect("测试向量路径应为 UTF-8");
+    let mut io = IoContext::open_read(path_str).expect("打开测试向量失败");
+    let mut demuxer = format_registry
+        .open_input(&mut io, Some(path_str))
+        .expect("打开 demuxer 失败");
+    let stream = demuxer
+        .streams()
+        .iter()
+        .find(|s| s.codec_id == codec)
+        .unwrap_or_else(|| panic!("测试向量中未找到 {codec} 流"))
+        .clone();
+
+    let params = match &stream.params {
+        StreamParams::Video(v) => CodecParamsType::Video(VideoCodecParams {
+            width: v.width,
+            height: v.height,
+            pixel_format: v.pixel_format,
+            frame_rate: v.frame_rate,
+            sample_aspect_ratio: v.sample_aspect_ratio,
+        }),
+        StreamParams::Audio(a) => CodecParamsType::Audio(AudioCodecParams {
+            sample_rate: a.sample_rate,
+            channel_layout: a.channel_layout,
+            sample_format: a.sample_format,
+            frame_size: a.frame_size,
+            block_align: 0,
+        }),
+        _ => panic!("不支持的流类型: {:?}", stream.media_type),
+    };
+    let mut decoder = codec_registry
+        .create_decoder(codec)
+        .expect("创建解码器失败");
+    decoder
+        .open(&CodecParameters {
+            codec_id: codec,
+            extra_data: stream.extra_data.clone(),
+            bit_rate: 0,
+            params,
+        })
+        .expect("打开解码器失败");
+
+    let mut frames = Vec::new();
+    let mut demux_eof = false;
+    loop {
+        if !demux_eof {
+            match demuxer.read_packet(&mut io) {
+                Ok(pkt) if pkt.stream_index == stream.index => {
+                    decoder.send_packet(&pkt).expect("送入数据包失败");
+                }
+                Ok(_) => continue,
+                Err(TaoError::Eof) => {
+                    decoder
+                        .send_packet(&Packet::empty())
+                        .expect("送入刷新包失败");
+                    demux_eof = true;
+                }
+                Err(e) => panic!("读取数据包失败: {e}"),
+            }
+        }
+
+        loop {
+            match decoder.receive_frame() {
+                Ok(frame) => frames.push(capture_frame(&frame)),
+                Err(TaoError::NeedMoreData) => break,
+                Err(TaoError::Eof) => return frames,
+                Err(e) => panic!("解码第 {} 帧失败: {e}", frames.len()),
+            }
+        }
+
+        if demux_eof {
+            return frames;
+        }
+    }
+}
+
+/// 提取帧的有效数据 (去除视频行填充)
+fn capture_frame(frame: &Frame) -> GoldenFrame {
+    match frame {
+        Frame::Video(vf) => {
+            let planes = (0..vf.data.len())
+                .map(|p| {
+                    let row = vf
+                        .pixel_format
+                        .plane_linesize(p, vf.width)
+                        .expect("无法计算平面行宽");
+                    let rows = vf
+                        .pixel_format
+                        .plane_height(p, vf.height)
+                        .expect("无法计算平面高度");
+                    let stride = vf.linesize[p];
+                    let mut out = Vec::with_capacity(row * rows);
+                    for y in 0..rows {
+                        out.extend_from_slice(&vf.data[p][y * stride..y * stride + row]);
+                    }
+                    out
+                })
+                .collect();
+            GoldenFrame {
+                media: MEDIA_VIDEO,
+                format: format!("{:?}", vf.pixel_format),
+                dims: [vf.width, vf.height],
+                planes,
+            }
+        }
+        Frame::Audio(af) => GoldenFrame {
+            media: MEDIA_AUDIO,
+            format: format!("{:?}", af.sample_format),
+            dims: [af.nb_samples, af.channel_layout.channels],
+            planes: af.data.iter().map(|p| p.to_vec()).collect(),
+        },
+        Frame::Subtitle(_) => panic!("黄金样本仅覆盖音视频帧"),
+    }
+}
+
+fn compare_frame(index: usize, actual: &GoldenFrame, expected: &GoldenFrame, tolerance: u8) {
+    assert_eq!(
+        (actual.media, &actual.format, actual.dims),
+        (expected.media, &expected.format, expected.dims),
+        "第 {index} 帧格式或尺寸与黄金文件不一致"
+    );
+    assert_eq!(
+        actual.planes.len(),
+        expected.planes.len(),
+        "第 {index} 帧平面数与黄金文件不一致"
+    );
+    for (p, (a, e)) in actual.planes.iter().zip(&expected.planes).enumerate() {
+        assert_eq!(a.len(), e.len(), "第 {index} 帧平面 {p} 长度不一致");
+        let mismatch = if actual.media == MEDIA_VIDEO {
+            first_mismatch(a, e, 1, |s| f64::from(s[0]), f64::from(tolerance))
+        } else {
+            let format = sample_format_from_name(&actual.format);
+            audio_mismatch(format, a, e, tolerance)
+        };
+        if let Some((pos, got, want)) = mismatch {
+            panic!(
+                "第 {index} 帧平面 {p} 第 {pos} 个样点超出容差 {tolerance}: 实际 {got}, 期望 {want}"
+            );
+        }
+    }
+}
+
+fn audio_mismatch(
+    format: SampleFormat,
+    actual: &[u8],
+    expected: &[u8],
+    tolerance: u8,
+) -> Option<(usize, f64, f64)> {
+    let tol = f64::from(tolerance);
+    match format.to_interleaved() {
+        SampleFormat::U8 => first_mismatch(actual, expected, 1, |s| f64::from(s[0]), tol),
+        SampleFormat::S16 => first_mismatch(
+            actual,
+            expected,
+            2,
+            |s| f64::from(i16::from_le_bytes([s[0], s[1]])),
+            tol,
+        ),
+        SampleFormat::S24 | SampleFormat::S32 => first_mismatch(
+            actual,
+            expected,
+            4,
+            |s| f64::from(i32::from_le_bytes([s[0], s[1], s[2], s[3]])),
+            tol,
+        ),
+        SampleFormat::F32 => first_mismatch(
+            actual,
+            expected,
+            4,
+            |s| f64::from(f32::from_le_bytes([s[0], s[1], s[2], s[3]])),
+            tol / 32768.0,
+        ),
+        SampleFormat::F64 => first_mismatch(
+            actual,
+            expected,
+            8,
+            |s| f64::from_le_bytes(s.try_into().unwrap()),
+            tol / 32768.0,
+        ),
+        other => panic!("不支持比较的采样格式: {other:?}"),
+    }
+}
+
+/// 按 `width` 字节切分样点, 返回第一个超出容差的 (样点序号, 实际值, 期望值)
+fn first_mismatch(
+    actual: &[u8],
+    expected: &[u8],
+    width: usize,
+    value: impl Fn(&[u8]) -> f64,
+    tolerance: f64,
+) -> Option<(usize, f64, f64)> {
+    actual
+        .chunks_exact(width)
+        .zip(expected.chunks_exact(width))
+        .map(|(a, e)| (value(a), value(e)))
+        .enumerate()
+        .find(|(_, (a, e))| (a - e).abs() > tolerance)
+        .map(|(pos, (a, e))| (pos, a, e))
+}
+
+fn sample_format_from_name(name: &str) -> SampleFormat {
+    [
+        SampleFormat::U8,
+        SampleFormat::S16,
+        SampleFormat::S24,
+        SampleFormat::S32,
+        SampleFormat::F32,
+        SampleFormat::F64,
+        SampleFormat::U8p,
+        SampleFormat::S16p,
+        SampleFormat::S24p,
+        SampleFormat::S32p,
+        SampleFormat::F32p,
+        SampleFormat::F64p,
+    ]
+    .into_iter()
+    .find(|f| format!("{f:?}") == name)
+    .unwrap_or_else(|| panic!("未知采样格式: {name}"))
+}
+
+// ============================================================
+// 黄金文件读写
+// ============================================================
+
+fn encode_golden(frames: &[GoldenFrame]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&VERSION.to_le_bytes());
+    out.extend_from_slice(&(frames.len() as u32).to_le_bytes());
+    for frame in frames {
+        out.push(frame.media);
+        out.extend_from_slice(&(frame.format.len() as u32).to_le_bytes());
+        out.extend_from_slice(frame.format.as_bytes());
+        for d in frame.dims {
+            out.extend_from_slice(&d.to_le_bytes());
+        }
+        out.extend_from_slice(&(frame.planes.len() as u32).to_le_bytes());
+        for plane in &frame.planes {
+            out.extend_from_slice(&(plane.len() as u32).to_le_bytes());
+            out.extend_from_slice(plane);
+        }
+    }
+    out
+}
+
+fn decode_golden(data: &[u8]) -> Vec<GoldenFrame> {
+    let mut r = Reader { data, pos: 0 };
+    assert_eq!(r.take(8), MAGIC, "黄金文件魔数错误");
+    assert_eq!(r.u32(), VERSION, "黄金文件版本不支持");
+    let count = r.u32() as usize;
+    let mut frames = Vec::with_capacity(count);
+    for _ in 0..count {
+        let media = r.take(1)[0];
+        let len = r.u32() as usize;
+        let format = String::from_utf8(r.take(len).to_vec()).expect("黄金文件格式名无效");
+        let dims = [r.u32(), r.u32()];
+        let plane_count = r.u32() as usize;
+        let planes = (0..plane_count)
+            .map(|_| {
+                let len = r.u32() as usize;
+                r.take(len).to_vec()
+            })
+            .collect();
+        frames.push(GoldenFrame {
+            media,
+            format,
+            dims,
+            planes,
+        });
+    }
+    assert_eq!(r.pos, data.len(), "黄金文件存在多余数据");
+    frames
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> &[u8] {
+        let end = self.pos + len;
+        assert!(end <= self.data.len(), "黄金文件被截断");
+        let out = &self.data[self.pos..end];
+        self.pos = end;
+        out
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.take(4).try_into().unwrap())
+    }
+}
+
+#[test]
+fn test_golden_roundtrip() {
+    let frames = vec![
+        GoldenFrame {
+            media: MEDIA_VIDEO,
+            format: "Yuv420p".into(),
+            dims: [2, 2],
+            planes: vec![vec![1, 2, 3, 4], vec![5], vec![6]],
+        },
+        GoldenFrame {
+            media: MEDIA_AUDIO,
+            format: "S16".into(),
+            dims: [2, 1],
+            planes: vec![vec![0, 1, 0xFF, 0xFF]],
+        },
+    ];
+    assert_eq!(
+        decode_golden(&encode_golden(&frames)),
+        frames,
+        "黄金文件读写应往返一致"
+    );
+}
+
+#[test]
+fn test_golden_tolerance() {
+    let base = GoldenFrame {
+        media: MEDIA_VIDEO,
+        format: "Gray8".into(),
+        dims: [4, 1],
+        planes: vec![vec![10, 20, 30, 40]],
+    };
+    let mut near = base.clone();
+    near.planes[0][2] = 32;
+    compare_frame(0, &near, &base, 2);
+
+    let result = std::panic::catch_unwind(|| compare_frame(0, &near, &base, 1));
+    assert!(result.is_err(), "超出容差时应判定失败");
+}
@@ -0,0 +1,33 @@
+//! 解码器黄金文件回归测试.
+//!
+//! 测试向量与黄金文件位于 `tests/fixtures/golden/`:
+//! - `h264_cabac_iframes.h264`: 64x48 Main Profile CABAC, 2 个 IDR 帧,
+//!   由 `scripts/gen_golden_h264_cabac.py` 生成, 黄金输出与脚本参考重建逐字节一致.
+//! - `flac_mono.flac`: 8kHz 单声道 S16 FLAC, 黄金输出即原始 PCM.
+//!
+//! 解码输出变化时, 核对正确后以 `TAO_UPDATE_GOLDEN=1` 重新生成黄金文件.
+
+mod golden;
+
+use golden::assert_decode_matches;
+use tao::codec::CodecId;
+
+#[test]
+fn test_golden_h264_cabac_iframes() {
+    assert_decode_matches(
+        CodecId::H264,
+        "tests/fixtures/golden/h264_cabac_iframes.h264",
+        "tests/fixtures/golden/h264_cabac_iframes.golden",
+        0,
+    );
+}
+
+#[test]
+fn test_golden_flac_mono() {
+    assert_decode_matches(
+        CodecId::Flac,
+        "tests/fixtures/golden/flac_mono.flac",
+        "tests/fixtures/golden/flac_mono.golden",
+        0,
+    );
+}
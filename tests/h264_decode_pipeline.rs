@@ -4,6 +4,7 @@
 //! - SPS/PPS 参数解析
 //! - 解码器创建与打开
 //! - 空包与无效数据安全处理
+//! - 关键帧标志与图像类型从解封装到解码端到端传递
 
 #[cfg(test)]
 mod tests {
@@ -121,4 +122,141 @@ mod tests {
         // 应该安全处理，不崩溃
         let _ = result;
     }
+
+    /// 按位写入 RBSP, 输出带起始码与防竞争字节的 NAL
+    #[derive(Default)]
+    struct BitWriter {
+        bits: Vec<bool>,
+    }
+
+    impl BitWriter {
+        fn bits(&mut self, value: u32, n: u32) -> &mut Self {
+            self.bits
+                .extend((0..n).rev().map(|i| (value >> i) & 1 == 1));
+            self
+        }
+
+        fn ue(&mut self, value: u32) -> &mut Self {
+            let code = value + 1;
+            let len = 32 - code.leading_zeros();
+            self.bits(0, len - 1).bits(code, len)
+        }
+
+        fn nal(&mut self, header: u8) -> Vec<u8> {
+            self.bits(1, 1); // rbsp_stop_one_bit
+            while self.bits.len() % 8 != 0 {
+                self.bits.push(false);
+            }
+            let mut out = vec![0, 0, 0, 1, header];
+            let mut zeros = 0;
+            for chunk in self.bits.chunks(8) {
+                let b = chunk.iter().fold(0u8, |acc, &bit| (acc << 1) | bit as u8);
+                if zeros >= 2 && b <= 3 {
+                    out.push(3);
+                    zeros = 0;
+                }
+                out.push(b);
+                zeros = if b == 0 { zeros + 1 } else { 0 };
+            }
+            out
+        }
+    }
+
+    /// 构造 64x48 的 Annex B 码流: IDR 图像 (全部为无残差 I_16x16 DC 宏块) + 全跳过的 P 图像
+    fn build_idr_p_stream() -> Vec<u8> {
+        const MB_COUNT: u32 = 4 * 3;
+        let mut sps = BitWriter::default();
+        sps.bits(66, 8).bits(0, 8).bits(30, 8); // Baseline, level 3.0
+        sps.ue(0).ue(0).ue(2).ue(1).bits(0, 1); // sps_id, log2_max_frame_num-4, poc_type=2, 1 参考帧
+        sps.ue(3).ue(2).bits(1, 1).bits(1, 1).bits(0, 1).bits(0, 1); // 4x3 宏块, 无裁剪/VUI
+
+        let mut pps = BitWriter::default();
+        pps.ue(0).ue(0).bits(0, 1).bits(0, 1).ue(0); // pps/sps id, CAVLC, 单 slice group
+        pps.ue(0).ue(0).bits(0, 1).bits(0, 2); // 参考索引数, 无加权预测
+        pps.ue(1).ue(1).ue(1).bits(0, 3); // QP/QS/色度偏移均为 0, 无去块控制
+
+        let mut idr = BitWriter::default();
+        idr.ue(0).ue(7).ue(0).bits(0, 4).ue(0); // first_mb, I slice, pps_id, frame_num, idr_pic_id
+        idr.bits(0, 2).ue(1); // dec_ref_pic_marking, slice_qp_delta=0
+        for _ in 0..MB_COUNT {
+            idr.ue(3).ue(0).ue(1).bits(1, 1); // I_16x16 DC, 色度 DC, mb_qp_delta=0, DC 无系数
+        }
+
+        let mut p = BitWriter::default();
+        p.ue(0).ue(5).ue(0).bits(1, 4); // first_mb, P slice, pps_id, frame_num=1
+        p.bits(0, 3).ue(1); // 不覆盖参考数/不修改列表/滑窗标记, slice_qp_delta=0
+        p.ue(MB_COUNT); // mb_skip_run 覆盖全部宏块
+
+        [sps.nal(0x67), pps.nal(0x68), idr.nal(0x65), p.nal(0x41)].concat()
+    }
+
+    /// IDR 数据包应标记为关键帧, 解码输出的图像类型应为 I
+    #[test]
+    fn test_h264_idr_packet_keyframe_and_picture_type() {
+        use tao_codec::frame::{Frame, PictureType};
+        use tao_core::TaoError;
+        use tao_format::stream::StreamParams;
+        use tao_format::{FormatId, FormatRegistry, IoContext};
+
+        let mut format_registry = FormatRegistry::new();
+        tao_format::register_all(&mut format_registry);
+        let mut io = IoContext::from_bytes(build_idr_p_stream());
+        let mut demuxer = format_registry
+            .open_input_as(&mut io, FormatId::H264Es)
+            .expect("打开 H.264 裸流失败");
+        let StreamParams::Video(video) = &demuxer.streams()[0].params else {
+            panic!("应为视频流");
+        };
+        let params = CodecParameters {
+            codec_id: CodecId::H264,
+            bit_rate: 0,
+            extra_data: Vec::new(),
+            params: CodecParamsType::Video(VideoCodecParams {
+                width: video.width,
+                height: video.height,
+                pixel_format: PixelFormat::Yuv420p,
+                frame_rate: video.frame_rate,
+                sample_aspect_ratio: Rational::new(1, 1),
+            }),
+        };
+        let mut decoder = create_h264_decoder();
+        decoder.open(&params).expect("打开解码器失败");
+
+        let mut key_flags = Vec::new();
+        let mut frames = Vec::new();
+        let mut eof = false;
+        while !eof {
+            let packet = match demuxer.read_packet(&mut io) {
+                Ok(pkt) => {
+                    key_flags.push(pkt.is_keyframe());
+                    pkt
+                }
+                Err(TaoError::Eof) => {
+                    eof = true;
+                    Packet::empty()
+                }
+                Err(e) => panic!("读取数据包失败: {e}"),
+            };
+            decoder.send_packet(&packet).expect("送入数据包失败");
+            loop {
+                match decoder.receive_frame() {
+                    Ok(Frame::Video(vf)) => frames.push(vf),
+                    Ok(_) => panic!("应输出视频帧"),
+                    Err(TaoError::NeedMoreData | TaoError::Eof) => break,
+                    Err(e) => panic!("解码失败: {e}"),
+                }
+            }
+        }
+
+        assert_eq!(key_flags, [true, false], "仅 IDR 数据包应标记为关键帧");
+        let types: Vec<_> = frames
+            .iter()
+            .map(|f| (f.picture_type, f.is_keyframe))
+            .collect();
+        assert_eq!(
+            types,
+            [(PictureType::I, true), (PictureType::P, false)],
+            "IDR 图像应解码为 I 帧关键帧, 后续 P 图像为非关键帧"
+        );
+    }
 }
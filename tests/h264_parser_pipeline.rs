@@ -283,7 +283,7 @@ fn test_full_pipeline_annex_b_extract_sps_parse_params() {
 
     // 提取 RBSP 并解析
     let rbsp = sps_nalu.unwrap().rbsp();
-    let sps = parse_sps(&rbsp).unwrap();
+    let sps = parse_sps(rbsp).unwrap();
 
     assert_eq!(sps.width, 1920);
     assert_eq!(sps.height, 1080);
@@ -289,16 +289,16 @@ fn test_keyframe_flag() {
     // Cluster 0: video keyframe, audio keyframe
     let pkt0 = demuxer.read_packet(&mut io).unwrap();
     assert_eq!(pkt0.stream_index, 0);
-    assert!(pkt0.is_keyframe, "第一个视频帧应该是关键帧");
+    assert!(pkt0.is_keyframe(), "第一个视频帧应该是关键帧");
 
     let pkt1 = demuxer.read_packet(&mut io).unwrap();
     assert_eq!(pkt1.stream_index, 1);
-    assert!(pkt1.is_keyframe, "音频帧应该是关键帧");
+    assert!(pkt1.is_keyframe(), "音频帧应该是关键帧");
 
     // Cluster 1: video non-keyframe
     let pkt2 = demuxer.read_packet(&mut io).unwrap();
     assert_eq!(pkt2.stream_index, 0);
-    assert!(!pkt2.is_keyframe, "第二个视频帧不应该是关键帧");
+    assert!(!pkt2.is_keyframe(), "第二个视频帧不应该是关键帧");
 }
 
 fn create_registry() -> tao_format::registry::FormatRegistry {
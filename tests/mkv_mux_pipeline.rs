@@ -26,6 +26,8 @@ fn make_video_stream() -> Stream {
             frame_rate: Rational::new(30, 1),
             sample_aspect_ratio: Rational::new(1, 1),
             bit_rate: 0,
+            color_space: Default::default(),
+            color_range: Default::default(),
         }),
         metadata: Vec::new(),
     }
@@ -47,6 +49,7 @@ fn make_audio_stream() -> Stream {
             sample_format: SampleFormat::S16,
             bit_rate: 128000,
             frame_size: 1024,
+            block_align: 0,
         }),
         metadata: Vec::new(),
     }
@@ -88,7 +91,7 @@ fn test_video_only_mux() {
         pkt.stream_index = 0;
         pkt.pts = i as i64 * 33;
         pkt.dts = i as i64 * 33;
-        pkt.is_keyframe = i == 0;
+        pkt.set_keyframe(i == 0);
         packets.push(pkt);
     }
 
@@ -107,7 +110,7 @@ fn test_video_only_mux_demux_roundtrip() {
     pkt.stream_index = 0;
     pkt.pts = 0;
     pkt.dts = 0;
-    pkt.is_keyframe = true;
+    pkt.set_keyframe(true);
 
     let mut io = mux_packets(&[stream], &[pkt]);
 
@@ -145,7 +148,7 @@ fn test_av_mux() {
         pkt.stream_index = 0;
         pkt.pts = i * 33;
         pkt.dts = i * 33;
-        pkt.is_keyframe = i == 0;
+        pkt.set_keyframe(i == 0);
         packets.push(pkt);
     }
 
@@ -155,7 +158,7 @@ fn test_av_mux() {
         pkt.stream_index = 1;
         pkt.pts = i * 23;
         pkt.dts = i * 23;
-        pkt.is_keyframe = true;
+        pkt.set_keyframe(true);
         packets.push(pkt);
     }
 
@@ -174,7 +177,7 @@ fn test_keyframe_flag() {
     kf.stream_index = 0;
     kf.pts = 0;
     kf.dts = 0;
-    kf.is_keyframe = true;
+    kf.set_keyframe(true);
     packets.push(kf);
 
     // 非关键帧
@@ -182,7 +185,7 @@ fn test_keyframe_flag() {
     nkf.stream_index = 0;
     nkf.pts = 33;
     nkf.dts = 33;
-    nkf.is_keyframe = false;
+    nkf.set_keyframe(false);
     packets.push(nkf);
 
     let mut io = mux_packets(&[stream], &packets);
@@ -193,8 +196,8 @@ fn test_keyframe_flag() {
     demuxer.open(&mut io).unwrap();
 
     let pkt1 = demuxer.read_packet(&mut io).unwrap();
-    assert!(pkt1.is_keyframe, "第一个包应为关键帧");
+    assert!(pkt1.is_keyframe(), "第一个包应为关键帧");
 
     let pkt2 = demuxer.read_packet(&mut io).unwrap();
-    assert!(!pkt2.is_keyframe, "第二个包应为非关键帧");
+    assert!(!pkt2.is_keyframe(), "第二个包应为非关键帧");
 }
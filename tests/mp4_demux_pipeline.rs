@@ -486,9 +486,9 @@ fn test_video_track_read_packets() {
 
         // 只有第一帧是关键帧
         if i == 0 {
-            assert!(pkt.is_keyframe, "采样 0 应该是关键帧");
+            assert!(pkt.is_keyframe(), "采样 0 应该是关键帧");
         } else {
-            assert!(!pkt.is_keyframe, "采样 {} 不应是关键帧", i);
+            assert!(!pkt.is_keyframe(), "采样 {} 不应是关键帧", i);
         }
     }
 
@@ -544,7 +544,7 @@ fn test_audio_track_read_packets() {
         assert_eq!(pkt.pts, (i as i64) * 1024);
 
         // 无 stss 表示所有帧都是关键帧 (音频通常如此)
-        assert!(pkt.is_keyframe);
+        assert!(pkt.is_keyframe());
     }
 
     let eof = demuxer.read_packet(&mut io);
@@ -38,6 +38,8 @@ fn make_video_stream(width: u32, height: u32, timescale: i32) -> Stream {
             frame_rate: Rational::new(30, 1),
             sample_aspect_ratio: Rational::new(1, 1),
             bit_rate: 0,
+            color_space: Default::default(),
+            color_range: Default::default(),
         }),
         metadata: Vec::new(),
     }
@@ -59,6 +61,7 @@ fn make_audio_stream(sample_rate: u32, channels: u32) -> Stream {
             sample_format: SampleFormat::F32,
             bit_rate: 128000,
             frame_size: 1024,
+            block_align: 0,
         }),
         metadata: Vec::new(),
     }
@@ -112,7 +115,7 @@ fn test_video_only_mux_demux_roundtrip() {
         pkt.pts = (i as i64) * frame_delta;
         pkt.dts = (i as i64) * frame_delta;
         pkt.duration = frame_delta;
-        pkt.is_keyframe = i == 0;
+        pkt.set_keyframe(i == 0);
         pkt.time_base = Rational::new(1, 90000);
         packets.push(pkt);
     }
@@ -170,7 +173,7 @@ fn test_audio_only_mux_demux_roundtrip() {
         pkt.pts = (i as i64) * frame_delta;
         pkt.dts = (i as i64) * frame_delta;
         pkt.duration = frame_delta;
-        pkt.is_keyframe = true;
+        pkt.set_keyframe(true);
         pkt.time_base = Rational::new(1, 44100);
         packets.push(pkt);
     }
@@ -217,7 +220,7 @@ fn test_av_mux_demux_roundtrip() {
         pkt.pts = (i as i64) * 3000;
         pkt.dts = (i as i64) * 3000;
         pkt.duration = 3000;
-        pkt.is_keyframe = i == 0;
+        pkt.set_keyframe(i == 0);
         pkt.time_base = Rational::new(1, 90000);
         packets.push(pkt);
     }
@@ -229,7 +232,7 @@ fn test_av_mux_demux_roundtrip() {
         pkt.pts = (i as i64) * 1024;
         pkt.dts = (i as i64) * 1024;
         pkt.duration = 1024;
-        pkt.is_keyframe = true;
+        pkt.set_keyframe(true);
         pkt.time_base = Rational::new(1, 48000);
         packets.push(pkt);
     }
@@ -286,7 +289,7 @@ fn test_keyframe_flag_roundtrip() {
         pkt.pts = (i as i64) * 1001;
         pkt.dts = (i as i64) * 1001;
         pkt.duration = 1001;
-        pkt.is_keyframe = i % 5 == 0;
+        pkt.set_keyframe(i % 5 == 0);
         pkt.time_base = Rational::new(1, 30000);
         packets.push(pkt);
     }
@@ -300,9 +303,12 @@ fn test_keyframe_flag_roundtrip() {
         let pkt = demuxer.read_packet(&mut io).unwrap();
         let expected_kf = i % 5 == 0;
         assert_eq!(
-            pkt.is_keyframe, expected_kf,
+            pkt.is_keyframe(),
+            expected_kf,
             "帧 {} 关键帧标记不匹配 (期望={}, 实际={})",
-            i, expected_kf, pkt.is_keyframe,
+            i,
+            expected_kf,
+            pkt.is_keyframe(),
         );
     }
 }
@@ -318,7 +324,7 @@ fn test_avcc_extra_data_roundtrip() {
     pkt.pts = 0;
     pkt.dts = 0;
     pkt.duration = 3000;
-    pkt.is_keyframe = true;
+    pkt.set_keyframe(true);
     pkt.time_base = Rational::new(1, 90000);
 
     let mut io = mux_to_io(&streams, &[pkt]);
@@ -344,7 +350,7 @@ fn test_esds_aac_extra_data_roundtrip() {
     pkt.pts = 0;
     pkt.dts = 0;
     pkt.duration = 1024;
-    pkt.is_keyframe = true;
+    pkt.set_keyframe(true);
     pkt.time_base = Rational::new(1, 44100);
 
     let mut io = mux_to_io(&[audio_stream], &[pkt]);
@@ -368,3 +374,240 @@ fn test_esds_aac_extra_data_roundtrip() {
         extra,
     );
 }
+
+// ========================
+// 分片 MP4
+// ========================
+
+/// 60 帧视频 (每 10 帧一个关键帧, 非关键帧带合成偏移) 与按时间交错的音频包
+fn make_fragment_packets() -> Vec<Packet> {
+    let mut packets = Vec::new();
+    let mut audio_index = 0i64;
+    for i in 0..60i64 {
+        let dts = i * 3000;
+        // 音频: 48000Hz, 每包 1024 采样, 按解码时间先于视频帧写入
+        while audio_index * 1024 * 90000 / 48000 <= dts {
+            let mut pkt =
+                Packet::from_data(vec![audio_index as u8; 64 + (audio_index % 7) as usize]);
+            pkt.stream_index = 1;
+            pkt.pts = audio_index * 1024;
+            pkt.dts = audio_index * 1024;
+            pkt.duration = 1024;
+            pkt.set_keyframe(true);
+            pkt.time_base = Rational::new(1, 48000);
+            packets.push(pkt);
+            audio_index += 1;
+        }
+
+        let mut pkt = Packet::from_data(vec![i as u8; 300 + (i as usize) * 3]);
+        pkt.stream_index = 0;
+        pkt.dts = dts;
+        pkt.pts = if i % 10 == 0 { dts } else { dts + 6000 };
+        pkt.duration = 3000;
+        pkt.set_keyframe(i % 10 == 0);
+        pkt.time_base = Rational::new(1, 90000);
+        packets.push(pkt);
+    }
+    packets
+}
+
+fn mux_fragmented(streams: &[Stream], packets: &[Packet], options: &[(&str, &str)]) -> Vec<u8> {
+    let mut io = IoContext::new_memory();
+    let mut muxer = Mp4Muxer::create().unwrap();
+    for (key, value) in options {
+        muxer.set_option(key, value).unwrap();
+    }
+    muxer.write_header(&mut io, streams).unwrap();
+    for pkt in packets {
+        muxer.write_packet(&mut io, pkt).unwrap();
+    }
+    muxer.write_trailer(&mut io).unwrap();
+    io.to_vec().unwrap()
+}
+
+/// 顶层 box 列表: (fourcc, 起始偏移, 大小)
+fn top_level_boxes(data: &[u8]) -> Vec<([u8; 4], usize, usize)> {
+    let mut boxes = Vec::new();
+    let mut pos = 0;
+    while pos + 8 <= data.len() {
+        let size = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+        let fourcc: [u8; 4] = data[pos + 4..pos + 8].try_into().unwrap();
+        assert!(size >= 8 && pos + size <= data.len(), "box 大小非法");
+        boxes.push((fourcc, pos, size));
+        pos += size;
+    }
+    assert_eq!(pos, data.len(), "顶层 box 应恰好覆盖整个文件");
+    boxes
+}
+
+/// 解封装得到的数据包摘要: (dts, pts, 是否关键帧, 数据)
+type DemuxedPacket = (i64, i64, bool, Vec<u8>);
+
+/// 按流分组读取全部数据包
+fn demux_by_stream(data: Vec<u8>) -> Vec<Vec<DemuxedPacket>> {
+    let mut io = IoContext::from_bytes(data);
+    let mut demuxer = Mp4Demuxer::create().unwrap();
+    demuxer.open(&mut io).unwrap();
+    let mut per_stream = vec![Vec::new(); demuxer.streams().len()];
+    while let Ok(pkt) = demuxer.read_packet(&mut io) {
+        per_stream[pkt.stream_index].push((pkt.dts, pkt.pts, pkt.is_keyframe(), pkt.data.to_vec()));
+    }
+    per_stream
+}
+
+#[test]
+fn test_fragmented_mux_demux_roundtrip() {
+    let streams = vec![
+        make_video_stream(320, 240, 90000),
+        make_audio_stream(48000, 2),
+    ];
+    let packets = make_fragment_packets();
+
+    let fragmented = mux_fragmented(&streams, &packets, &[("fragmented", "1")]);
+    let boxes = top_level_boxes(&fragmented);
+    let kinds: Vec<&[u8; 4]> = boxes.iter().map(|(kind, _, _)| kind).collect();
+    assert_eq!(kinds[0], b"ftyp");
+    assert_eq!(kinds[1], b"moov", "分片模式应先写出 moov");
+    assert_eq!(*kinds.last().unwrap(), b"mfra", "结尾应有 mfra 索引");
+    let moofs: Vec<_> = boxes
+        .iter()
+        .filter(|(kind, _, _)| kind == b"moof")
+        .collect();
+    assert_eq!(moofs.len(), 6, "每个关键帧应切分一个分片");
+    for (i, (_, pos, _)) in moofs.iter().enumerate() {
+        // moof 第一个子 box 为 mfhd, 序号从 1 开始递增
+        assert_eq!(&fragmented[pos + 12..pos + 16], b"mfhd");
+        let seq = u32::from_be_bytes(fragmented[pos + 20..pos + 24].try_into().unwrap());
+        assert_eq!(seq, i as u32 + 1, "mfhd 序号应连续递增");
+    }
+    let (_, moov_pos, moov_size) = boxes[1];
+    let moov = &fragmented[moov_pos..moov_pos + moov_size];
+    assert!(moov.windows(4).any(|w| w == b"mvex"), "moov 应包含 mvex");
+    assert!(moov.windows(4).any(|w| w == b"trex"), "moov 应包含 trex");
+
+    // 与普通 MP4 的解封装结果逐包一致 (时间戳、关键帧标记、数据)
+    let flat = mux_to_io(&streams, &packets).to_vec().unwrap();
+    let expected = demux_by_stream(flat);
+    let actual = demux_by_stream(fragmented);
+    assert_eq!(actual.len(), 2, "应有 2 个轨道");
+    assert_eq!(expected[0].len(), 60);
+    assert_eq!(actual, expected, "分片 MP4 解封装结果应与普通 MP4 一致");
+}
+
+#[test]
+fn test_fragmented_mux_respects_frag_duration() {
+    let streams = vec![
+        make_video_stream(320, 240, 90000),
+        make_audio_stream(48000, 2),
+    ];
+    let packets = make_fragment_packets();
+
+    // 60 帧 @30fps = 2 秒, 关键帧间隔 1/3 秒: 最短 1000ms 时在第 0/30 帧切分
+    let data = mux_fragmented(
+        &streams,
+        &packets,
+        &[
+            ("fragmented", "1"),
+            ("frag_duration", "1000"),
+            ("mfra", "0"),
+        ],
+    );
+    let boxes = top_level_boxes(&data);
+    let moof_count = boxes.iter().filter(|(kind, _, _)| kind == b"moof").count();
+    assert_eq!(moof_count, 2, "1000ms 分片时长应产生 2 个分片");
+    assert!(
+        boxes.iter().all(|(kind, _, _)| kind != b"mfra"),
+        "mfra=0 时不应写入 mfra"
+    );
+
+    let per_stream = demux_by_stream(data);
+    assert_eq!(per_stream[0].len(), 60, "视频包数量应保持不变");
+    assert_eq!(
+        per_stream[0][30].0,
+        30 * 3000,
+        "第二个分片的 tfdt 应接续第一个分片"
+    );
+    assert!(per_stream[0][30].2, "第二个分片应从关键帧开始");
+}
+
+#[test]
+fn test_fragmented_mux_rejects_invalid_options() {
+    let mut muxer = Mp4Muxer::create().unwrap();
+    assert!(
+        muxer.set_option("fragmented", "yes").is_err(),
+        "非 0/1 取值应报错"
+    );
+    assert!(
+        muxer.set_option("frag_duration", "-5").is_err(),
+        "负时长应报错"
+    );
+    assert!(
+        muxer.set_option("no_such_option", "1").is_err(),
+        "未知选项应报错"
+    );
+}
+
+// ========================
+// faststart
+// ========================
+
+#[test]
+fn test_faststart_mux_demux_roundtrip() {
+    let streams = vec![
+        make_video_stream(320, 240, 90000),
+        make_audio_stream(48000, 2),
+    ];
+    let packets = make_fragment_packets();
+
+    let data = mux_fragmented(&streams, &packets, &[("faststart", "1")]);
+    let boxes = top_level_boxes(&data);
+    let kinds: Vec<&[u8; 4]> = boxes.iter().map(|(kind, _, _)| kind).collect();
+    assert_eq!(
+        kinds,
+        vec![b"ftyp", b"moov", b"mdat"],
+        "faststart 模式应将 moov 置于 mdat 之前"
+    );
+
+    let flat = mux_to_io(&streams, &packets).to_vec().unwrap();
+    let flat_kinds: Vec<[u8; 4]> = top_level_boxes(&flat).iter().map(|b| b.0).collect();
+    assert_eq!(flat_kinds, vec![*b"ftyp", *b"mdat", *b"moov"]);
+    assert_eq!(
+        data.len(),
+        flat.len(),
+        "faststart 只调整 box 顺序, 文件大小应不变"
+    );
+    assert_eq!(
+        demux_by_stream(data),
+        demux_by_stream(flat),
+        "faststart MP4 解封装结果应与普通 MP4 一致"
+    );
+}
+
+#[test]
+fn test_muxer_demuxer_option_api() {
+    let mut muxer = Mp4Muxer::create().unwrap();
+    assert_eq!(muxer.get_option("faststart").as_deref(), Some("0"));
+    muxer.set_option("faststart", "true").unwrap();
+    muxer.set_option("frag_duration", "500").unwrap();
+    assert_eq!(muxer.get_option("faststart").as_deref(), Some("1"));
+    assert_eq!(muxer.get_option("frag_duration").as_deref(), Some("500"));
+    assert_eq!(
+        muxer.get_option("no_such_option"),
+        None,
+        "未知选项应返回 None"
+    );
+    assert!(
+        muxer.set_option("faststart", "2").is_err(),
+        "非 0/1 取值应报错"
+    );
+
+    let mut demuxer = Mp4Demuxer::create().unwrap();
+    assert!(
+        matches!(
+            demuxer.set_option("any", "1"),
+            Err(tao_core::TaoError::Unsupported(_))
+        ),
+        "默认实现应返回 Unsupported"
+    );
+    assert_eq!(demuxer.get_option("any"), None);
+}
@@ -283,11 +283,11 @@ fn test_keyframe_flag() {
     }
 
     assert!(!video_packets.is_empty(), "应该有视频包");
-    assert!(video_packets[0].is_keyframe, "第一个视频包应是关键帧");
+    assert!(video_packets[0].is_keyframe(), "第一个视频包应是关键帧");
 
     // 后续视频包应该不是关键帧
     if video_packets.len() > 1 {
-        assert!(!video_packets[1].is_keyframe, "第二个视频包不应是关键帧");
+        assert!(!video_packets[1].is_keyframe(), "第二个视频包不应是关键帧");
     }
 }
 
@@ -0,0 +1,270 @@
+//! Ogg 封装 granule position 与分页规则集成测试.
+//!
+//! 构造带真实 setup 头包的 Vorbis 流 (长短块混合), 封装后按 oggz-validate 的规则检查:
+//! - 每条流首页为只含标识头的 BOS 页, 其余头包在数据之前单独结束一页
+//! - 数据页 granule 严格递增, 仅最后一页带 EOS
+//! - 解封装得到的时长与源采样数一致, 再次封装结果不变
+
+use bytes::Bytes;
+use tao_codec::{CodecId, Packet};
+use tao_core::{ChannelLayout, MediaType, Rational, SampleFormat, TaoError};
+use tao_format::format_id::FormatId;
+use tao_format::io::{IoContext, MemoryBackend};
+use tao_format::registry::FormatRegistry;
+use tao_format::stream::{AudioStreamParams, Stream, StreamParams};
+
+const SAMPLE_RATE: u32 = 44100;
+/// 短块 256, 长块 2048
+const BLOCKSIZES: [u32; 2] = [256, 2048];
+
+/// LSB 优先位写入器, 用于构造 setup 头包
+struct BitWriter {
+    data: Vec<u8>,
+    bit: usize,
+}
+
+impl BitWriter {
+    fn new(prefix: &[u8]) -> Self {
+        Self {
+            data: prefix.to_vec(),
+            bit: prefix.len() * 8,
+        }
+    }
+
+    fn put(&mut self, value: u32, bits: u32) {
+        for i in 0..bits {
+            if self.bit / 8 == self.data.len() {
+                self.data.push(0);
+            }
+            if value >> i & 1 != 0 {
+                self.data[self.bit / 8] |= 1 << (self.bit % 8);
+            }
+            self.bit += 1;
+        }
+    }
+}
+
+fn build_ident() -> Vec<u8> {
+    let mut h = vec![0x01];
+    h.extend_from_slice(b"vorbis");
+    h.extend_from_slice(&0u32.to_le_bytes());
+    h.push(1);
+    h.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    h.extend_from_slice(&[0; 12]);
+    h.push(0xB8);
+    h.push(0x01);
+    h
+}
+
+fn build_comment() -> Vec<u8> {
+    let mut h = vec![0x03];
+    h.extend_from_slice(b"vorbis");
+    h.extend_from_slice(&3u32.to_le_bytes());
+    h.extend_from_slice(b"tao");
+    h.extend_from_slice(&0u32.to_le_bytes());
+    h.push(0x01);
+    h
+}
+
+/// 最小 setup: 1 个码本, 1 个 floor1, 1 个 residue, 1 个 mapping, 2 个模式 (短块/长块)
+fn build_setup() -> Vec<u8> {
+    let mut w = BitWriter::new(b"\x05vorbis");
+    for (value, bits) in [
+        (0, 8),
+        (0x564342, 24),
+        (1, 16),
+        (2, 24),
+        (0, 1),
+        (0, 1),
+        (0, 5),
+        (0, 5),
+        (0, 4),
+        (0, 6),
+        (0, 16),
+        (0, 6),
+        (1, 16),
+        (0, 5),
+        (0, 3),
+        (0, 2),
+        (0, 8),
+        (0, 2),
+        (0, 4),
+        (0, 6),
+        (0, 16),
+        (0, 24),
+        (0, 24),
+        (0, 24),
+        (0, 6),
+        (0, 8),
+        (0, 3),
+        (0, 1),
+        (0, 6),
+        (0, 16),
+        (0, 1),
+        (0, 1),
+        (0, 2),
+        (0, 24),
+        (1, 6),
+    ] {
+        w.put(value, bits);
+    }
+    for block_flag in [0, 1] {
+        w.put(block_flag, 1);
+        w.put(0, 16); // window type
+        w.put(0, 16); // transform type
+        w.put(0, 8); // mapping
+    }
+    w.put(1, 1);
+    w.data
+}
+
+fn make_vorbis_stream() -> Stream {
+    let ident = build_ident();
+    let comment = build_comment();
+    let mut extra_data = vec![0x02, ident.len() as u8, comment.len() as u8];
+    extra_data.extend_from_slice(&ident);
+    extra_data.extend_from_slice(&comment);
+    extra_data.extend_from_slice(&build_setup());
+    Stream {
+        index: 0,
+        media_type: MediaType::Audio,
+        codec_id: CodecId::Vorbis,
+        time_base: Rational::new(1, SAMPLE_RATE as i32),
+        duration: -1,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data,
+        params: StreamParams::Audio(AudioStreamParams {
+            sample_rate: SAMPLE_RATE,
+            channel_layout: ChannelLayout::MONO,
+            sample_format: SampleFormat::F32,
+            bit_rate: 0,
+            frame_size: 0,
+            block_align: 0,
+        }),
+        metadata: Vec::new(),
+    }
+}
+
+/// 生成长短块混合的音频包, 返回数据包与解码后的总采样数
+fn make_vorbis_packets() -> (Vec<Packet>, i64) {
+    let mut packets = Vec::new();
+    let mut total = 0i64;
+    let mut prev: Option<u32> = None;
+    for i in 0..400u32 {
+        let long = i % 7 < 4;
+        let blocksize = BLOCKSIZES[usize::from(long)];
+        // 首字节: 包类型位 0, 模式号位于第 1 位; 其余为填充数据, 长度随包变化
+        let mut data = vec![u8::from(long) << 1];
+        data.extend((0..(i * 37 % 700)).map(|b| b as u8));
+        packets.push(Packet::from_data(Bytes::from(data)));
+        if let Some(prev) = prev {
+            total += i64::from(prev / 4 + blocksize / 4);
+        }
+        prev = Some(blocksize);
+    }
+    (packets, total)
+}
+
+fn mux(streams: &[Stream], packets: &[Packet]) -> Vec<u8> {
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    let mut io = IoContext::new(Box::new(MemoryBackend::new()));
+    let mut muxer = registry.create_muxer(FormatId::Ogg).unwrap();
+    muxer.write_header(&mut io, streams).unwrap();
+    for packet in packets {
+        muxer.write_packet(&mut io, packet).unwrap();
+    }
+    muxer.write_trailer(&mut io).unwrap();
+    let len = io.position().unwrap() as usize;
+    io.seek(std::io::SeekFrom::Start(0)).unwrap();
+    io.read_bytes(len).unwrap()
+}
+
+/// 页面信息: (header_type, granule, 段数)
+fn parse_pages(bytes: &[u8]) -> Vec<(u8, i64, usize)> {
+    let mut pages = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        assert_eq!(&bytes[pos..pos + 4], b"OggS", "页面同步字错误");
+        let header_type = bytes[pos + 5];
+        let granule = i64::from_le_bytes(bytes[pos + 6..pos + 14].try_into().unwrap());
+        let nsegs = bytes[pos + 26] as usize;
+        let size: usize = bytes[pos + 27..pos + 27 + nsegs]
+            .iter()
+            .map(|&s| s as usize)
+            .sum();
+        pages.push((header_type, granule, nsegs));
+        pos += 27 + nsegs + size;
+    }
+    pages
+}
+
+/// 解封装, 返回流信息、全部数据包与时长
+fn demux(bytes: Vec<u8>) -> (Stream, Vec<Packet>, Option<f64>) {
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+    let mut io = IoContext::new(Box::new(MemoryBackend::from_data(bytes)));
+    let mut demuxer = registry.create_demuxer(FormatId::Ogg).unwrap();
+    demuxer.open(&mut io).unwrap();
+    let stream = demuxer.streams()[0].clone();
+    let mut packets = Vec::new();
+    loop {
+        match demuxer.read_packet(&mut io) {
+            Ok(packet) => packets.push(packet),
+            Err(TaoError::Eof) => break,
+            Err(e) => panic!("读取数据包失败: {e}"),
+        }
+    }
+    (stream, packets, demuxer.duration())
+}
+
+#[test]
+fn test_ogg_vorbis_granule_and_page_invariants() {
+    let (packets, total_samples) = make_vorbis_packets();
+    let bytes = mux(&[make_vorbis_stream()], &packets);
+    let pages = parse_pages(&bytes);
+
+    // BOS 页只含标识头, comment + setup 单独结束一页
+    assert_eq!(pages[0], (0x02, 0, 1), "BOS 页应只包含 identification 头");
+    assert_eq!(pages[1].0, 0);
+    assert_eq!(pages[1].1, 0, "头包页 granule 应为 0");
+    assert_eq!(pages[1].2, 2, "comment 与 setup 应在数据前结束一页");
+
+    let data_pages = &pages[2..];
+    let granules: Vec<i64> = data_pages.iter().map(|p| p.1).filter(|&g| g >= 0).collect();
+    assert!(
+        granules.windows(2).all(|w| w[0] < w[1]),
+        "granule 应严格递增: {granules:?}"
+    );
+    assert_eq!(
+        *granules.last().unwrap(),
+        total_samples,
+        "最后一页 granule 应为 PCM 总采样数"
+    );
+    for (i, page) in pages.iter().enumerate() {
+        assert_eq!(
+            page.0 & 0x04 != 0,
+            i == pages.len() - 1,
+            "只有最后一页带 EOS"
+        );
+        assert_eq!(page.0 & 0x02 != 0, i == 0, "只有首页带 BOS");
+    }
+
+    // 解封装: 时长与源一致, 数据包完整还原 (comment/setup 作为数据包输出)
+    let (stream, demuxed, duration) = demux(bytes.clone());
+    assert_eq!(stream.duration, total_samples, "流时长应等于源采样数");
+    let expected = total_samples as f64 / f64::from(SAMPLE_RATE);
+    assert!(
+        (duration.expect("应有时长") - expected).abs() < 1e-6,
+        "时长应为 {expected} 秒"
+    );
+    assert_eq!(demuxed.len(), packets.len() + 2);
+    for (out, src) in demuxed[2..].iter().zip(&packets) {
+        assert_eq!(out.data, src.data, "数据包内容应保持不变");
+    }
+
+    // 再次封装: 头包来自数据包而非 extra_data, 结果应逐字节一致
+    let remuxed = mux(&[stream], &demuxed);
+    assert_eq!(remuxed, bytes, "Ogg 转封装结果应与原始封装一致");
+}
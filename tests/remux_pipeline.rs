@@ -0,0 +1,124 @@
+//! 重封装 (remux) 集成测试.
+//!
+//! 验证数据包在 MP4 → MKV → FLV 多次重封装后, 关键帧位置与数据保持不变.
+
+use tao_codec::{CodecId, Packet};
+use tao_core::{MediaType, PixelFormat, Rational, TaoError};
+use tao_format::format_id::FormatId;
+use tao_format::io::{IoContext, MemoryBackend};
+use tao_format::registry::FormatRegistry;
+use tao_format::stream::{Stream, StreamParams, VideoStreamParams};
+
+/// 不规则的关键帧分布, 用于检测位置偏移
+const KEYFRAME_PATTERN: [bool; 12] = [
+    true, false, false, true, false, true, true, false, false, false, false, true,
+];
+
+fn make_video_stream() -> Stream {
+    Stream {
+        index: 0,
+        media_type: MediaType::Video,
+        codec_id: CodecId::H264,
+        time_base: Rational::new(1, 1000),
+        duration: -1,
+        start_time: 0,
+        nb_frames: 0,
+        extra_data: vec![
+            // 最小 avcC: version=1, profile=66, level=30, lengthSize=4, 1 SPS, 1 PPS
+            0x01, 0x42, 0x00, 0x1E, 0xFF, 0xE1, 0x00, 0x04, 0x67, 0x42, 0x00, 0x1E, 0x01, 0x00,
+            0x02, 0x68, 0xCE,
+        ],
+        params: StreamParams::Video(VideoStreamParams {
+            width: 320,
+            height: 240,
+            pixel_format: PixelFormat::Yuv420p,
+            frame_rate: Rational::new(25, 1),
+            sample_aspect_ratio: Rational::new(1, 1),
+            bit_rate: 0,
+            color_space: Default::default(),
+            color_range: Default::default(),
+        }),
+        metadata: Vec::new(),
+    }
+}
+
+fn make_source_packets() -> Vec<Packet> {
+    KEYFRAME_PATTERN
+        .iter()
+        .enumerate()
+        .map(|(i, &kf)| {
+            let mut pkt = Packet::from_data(vec![i as u8; 64 + i]);
+            pkt.stream_index = 0;
+            pkt.pts = i as i64 * 40;
+            pkt.dts = i as i64 * 40;
+            pkt.duration = 40;
+            pkt.time_base = Rational::new(1, 1000);
+            pkt.set_keyframe(kf);
+            pkt
+        })
+        .collect()
+}
+
+/// 封装到内存, 再解封装读出全部数据包和流信息
+fn remux(format: FormatId, streams: &[Stream], packets: &[Packet]) -> (Vec<Stream>, Vec<Packet>) {
+    let mut registry = FormatRegistry::new();
+    tao_format::register_all(&mut registry);
+
+    let mut io = IoContext::new(Box::new(MemoryBackend::new()));
+    let mut muxer = registry.create_muxer(format).unwrap();
+    muxer.write_header(&mut io, streams).unwrap();
+    for pkt in packets {
+        muxer.write_packet(&mut io, pkt).unwrap();
+    }
+    muxer.write_trailer(&mut io).unwrap();
+
+    io.seek(std::io::SeekFrom::Start(0)).unwrap();
+    let mut demuxer = registry.create_demuxer(format).unwrap();
+    demuxer.open(&mut io).unwrap();
+    let out_streams = demuxer.streams().to_vec();
+    let mut out_packets = Vec::new();
+    loop {
+        match demuxer.read_packet(&mut io) {
+            Ok(pkt) => out_packets.push(pkt),
+            Err(TaoError::Eof) => break,
+            Err(e) => panic!("{format:?} 读包失败: {e}"),
+        }
+    }
+    (out_streams, out_packets)
+}
+
+fn keyframe_positions(packets: &[Packet]) -> Vec<usize> {
+    packets
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.is_keyframe())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[test]
+fn test_remux_preserves_keyframe_positions() {
+    let source = make_source_packets();
+    let expected = keyframe_positions(&source);
+
+    let mut streams = vec![make_video_stream()];
+    let mut packets = source.clone();
+    for format in [FormatId::Mp4, FormatId::Matroska, FormatId::Flv] {
+        let (out_streams, out_packets) = remux(format, &streams, &packets);
+        assert_eq!(
+            out_packets.len(),
+            source.len(),
+            "{format:?} 重封装后数据包数量不一致"
+        );
+        assert_eq!(
+            keyframe_positions(&out_packets),
+            expected,
+            "{format:?} 重封装后关键帧位置不一致"
+        );
+        for (i, (out, src)) in out_packets.iter().zip(&source).enumerate() {
+            assert_eq!(out.data, src.data, "{format:?} 第 {i} 个数据包内容不一致");
+        }
+        streams = out_streams;
+        packets = out_packets;
+    }
+}
@@ -0,0 +1,287 @@
+//! 高层转码 API 集成测试.
+//!
+//! 使用 MediaWriter 生成输入文件, 经 Transcoder 转码后用 MediaReader 读回验证.
+
+use std::time::Duration;
+
+use tao::codec::{AudioFrame, CodecId, Frame, VideoFrame};
+use tao::core::{ChannelLayout, MediaType, PixelFormat, Rational, SampleFormat, TaoError};
+use tao::format::stream::StreamParams;
+use tao::transcode::StreamAction;
+use tao::{
+    AudioEncodeParams, MediaReader, MediaWriter, StreamCodec, Transcoder, VideoEncodeParams,
+};
+use tempfile::tempdir;
+
+const SAMPLE_RATE: u32 = 44100;
+
+/// 生成 1 秒立体声 S16 正弦波 (交错)
+fn sine_s16_stereo() -> Vec<u8> {
+    (0..SAMPLE_RATE)
+        .flat_map(|i| {
+            let value = (i as f64 * 440.0 * 2.0 * std::f64::consts::PI / SAMPLE_RATE as f64).sin();
+            ((value * 12000.0) as i16).to_le_bytes().repeat(2)
+        })
+        .collect()
+}
+
+fn write_wav(path: &str, pcm: &[u8]) {
+    let mut writer = MediaWriter::create(path).unwrap();
+    let out = writer
+        .add_audio_stream(AudioEncodeParams::new(
+            CodecId::PcmS16le,
+            SAMPLE_RATE,
+            ChannelLayout::STEREO,
+            SampleFormat::S16,
+        ))
+        .unwrap();
+    // 分多帧写入, 验证编码器按帧长重新分块
+    for chunk in pcm.chunks(4096 * 4) {
+        let mut frame = AudioFrame::new(
+            (chunk.len() / 4) as u32,
+            SAMPLE_RATE,
+            SampleFormat::S16,
+            ChannelLayout::STEREO,
+        );
+        frame.data[0] = chunk.to_vec().into();
+        writer.write_frame(out, Frame::Audio(frame)).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+/// 读回全部音频采样 (交错字节)
+fn read_audio(path: &str) -> (Vec<u8>, SampleFormat) {
+    let mut reader = MediaReader::open(path).unwrap();
+    let mut data = Vec::new();
+    let mut format = SampleFormat::None;
+    for item in reader.frames() {
+        let (_, frame) = item.unwrap();
+        if let Frame::Audio(af) = frame {
+            format = af.sample_format;
+            data.extend_from_slice(&af.data[0]);
+        }
+    }
+    (data, format)
+}
+
+#[test]
+fn test_transcoder_wav_to_flac_lossless() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav").to_string_lossy().into_owned();
+    let output = dir
+        .path()
+        .join("output.flac")
+        .to_string_lossy()
+        .into_owned();
+    let pcm = sine_s16_stereo();
+    write_wav(&input, &pcm);
+
+    let job = Transcoder::new(&input, &output)
+        .audio_codec(StreamCodec::Encode(CodecId::Flac))
+        .prepare()
+        .unwrap();
+    assert_eq!(job.input_format_name(), "wav", "输入格式应探测为 WAV");
+    assert_eq!(
+        job.mappings()[0].action,
+        StreamAction::Transcode {
+            codec_id: CodecId::Flac
+        },
+        "音频流应转码为 FLAC"
+    );
+    let stats = job.run().unwrap();
+    assert!(stats.packets > 0, "应写出数据包");
+
+    let reader = MediaReader::open(&output).unwrap();
+    assert_eq!(reader.format_name(), "flac", "输出应为 FLAC 容器");
+    let (decoded, format) = read_audio(&output);
+    assert_eq!(format, SampleFormat::S16, "16 位 FLAC 应解码为 S16");
+    assert_eq!(decoded, pcm, "FLAC 往返应无损");
+}
+
+#[test]
+fn test_transcoder_resample_and_trim() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav").to_string_lossy().into_owned();
+    let output = dir.path().join("output.wav").to_string_lossy().into_owned();
+    write_wav(&input, &sine_s16_stereo());
+
+    Transcoder::new(&input, &output)
+        .audio_codec(StreamCodec::Encoder("pcm_s16le".into()))
+        .sample_rate(22050)
+        .channels(1)
+        .duration(0.5)
+        .run()
+        .unwrap();
+
+    let reader = MediaReader::open(&output).unwrap();
+    let stream = &reader.streams()[0];
+    assert_eq!(stream.media_type, MediaType::Audio);
+    let (decoded, _) = read_audio(&output);
+    let samples = decoded.len() / 2;
+    assert!(
+        (samples as i64 - 11025).abs() <= 64,
+        "0.5 秒单声道 22050Hz 应约为 11025 个采样, 实际 {samples}"
+    );
+}
+
+#[test]
+fn test_media_writer_audio_video_roundtrip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("av.avi").to_string_lossy().into_owned();
+
+    let mut writer = MediaWriter::create(&path).unwrap();
+    let video = writer
+        .add_video_stream(VideoEncodeParams::new(
+            CodecId::RawVideo,
+            64,
+            48,
+            PixelFormat::Bgr24,
+            Rational::new(25, 1),
+        ))
+        .unwrap();
+    let audio = writer
+        .add_audio_stream(AudioEncodeParams::new(
+            CodecId::PcmS16le,
+            SAMPLE_RATE,
+            ChannelLayout::STEREO,
+            SampleFormat::S16,
+        ))
+        .unwrap();
+    let pcm = sine_s16_stereo();
+    let samples_per_frame = SAMPLE_RATE as usize / 25;
+    for i in 0..25 {
+        let mut vf = VideoFrame::new(64, 48, PixelFormat::Bgr24);
+        vf.data = vec![vec![i as u8; 64 * 48 * 3].into()];
+        vf.linesize = vec![64 * 3];
+        vf.pts = i;
+        vf.time_base = Rational::new(1, 25);
+        writer.write_frame(video, Frame::Video(vf)).unwrap();
+
+        let bytes =
+            &pcm[i as usize * samples_per_frame * 4..(i as usize + 1) * samples_per_frame * 4];
+        let mut af = AudioFrame::new(
+            samples_per_frame as u32,
+            SAMPLE_RATE,
+            SampleFormat::S16,
+            ChannelLayout::STEREO,
+        );
+        af.data[0] = bytes.to_vec().into();
+        writer.write_frame(audio, Frame::Audio(af)).unwrap();
+    }
+    assert!(
+        writer
+            .add_audio_stream(AudioEncodeParams::new(
+                CodecId::PcmS16le,
+                SAMPLE_RATE,
+                ChannelLayout::MONO,
+                SampleFormat::S16,
+            ))
+            .is_err(),
+        "开始写入后不应允许添加流"
+    );
+    writer.finish().unwrap();
+
+    let mut reader = MediaReader::open(&path).unwrap();
+    assert_eq!(reader.streams().len(), 2, "应有两条输出流");
+    assert_eq!(
+        reader.streams()[video].nb_frames,
+        25,
+        "视频帧数应来自 AVI 头部"
+    );
+    assert!(
+        matches!(&reader.streams()[audio].params, StreamParams::Audio(a) if a.bit_rate == 1_411_200),
+        "音频码率应来自 nAvgBytesPerSec"
+    );
+    let duration = reader.duration().expect("AVI 应有时长");
+    assert!(
+        (duration - 1.0).abs() < 1e-6,
+        "时长应为 1 秒, 实际 {duration}"
+    );
+    let mut video_frames = Vec::new();
+    let mut audio_bytes = 0;
+    while let Some((idx, frame)) = reader.read_frame().unwrap() {
+        match frame {
+            Frame::Video(vf) => {
+                assert_eq!(idx, video, "视频帧应来自视频流");
+                video_frames.push(vf.data[0][0]);
+            }
+            Frame::Audio(af) => audio_bytes += af.data[0].len(),
+            Frame::Subtitle(_) => panic!("输入不含字幕流"),
+        }
+    }
+    assert_eq!(
+        video_frames,
+        (0..25).collect::<Vec<u8>>(),
+        "视频帧应按顺序完整读回"
+    );
+    assert_eq!(audio_bytes, pcm.len(), "音频采样应完整读回");
+}
+
+#[test]
+fn test_transcoder_skip_and_errors() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav").to_string_lossy().into_owned();
+    write_wav(&input, &sine_s16_stereo());
+
+    let err = Transcoder::new(&input, &dir.path().join("out.unknown").to_string_lossy())
+        .run()
+        .unwrap_err();
+    assert!(
+        matches!(err, TaoError::FormatNotFound(_)),
+        "无法识别的扩展名应返回 FormatNotFound: {err}"
+    );
+
+    let output = dir.path().join("output.wav").to_string_lossy().into_owned();
+    let err = Transcoder::new(&input, &output)
+        .map("0:v")
+        .run()
+        .unwrap_err();
+    assert!(
+        matches!(err, TaoError::InvalidArgument(_)),
+        "映射不到任何流时应报错: {err}"
+    );
+
+    let err = Transcoder::new(&input, &output)
+        .audio_codec(StreamCodec::Encoder("no_such_encoder".into()))
+        .run()
+        .unwrap_err();
+    assert!(
+        matches!(err, TaoError::CodecNotFound(_)),
+        "未知编码器名应返回 CodecNotFound: {err}"
+    );
+}
+
+#[test]
+fn test_transcoder_progress_callback() {
+    let dir = tempdir().unwrap();
+    let input = dir.path().join("input.wav").to_string_lossy().into_owned();
+    let output = dir.path().join("output.wav").to_string_lossy().into_owned();
+    write_wav(&input, &sine_s16_stereo());
+
+    let job = Transcoder::new(&input, &output)
+        .duration(0.5)
+        .prepare()
+        .unwrap();
+    let total = job.total_duration().expect("WAV 应有时长");
+    assert!(
+        (total - 0.5).abs() < 1e-6,
+        "总时长应受 -t 限制, 实际 {total}"
+    );
+
+    let mut reports = Vec::new();
+    let stats = job
+        .run_with_progress(Duration::from_secs(3600), |report| reports.push(*report))
+        .unwrap();
+    assert_eq!(reports.len(), 1, "间隔足够长时只应回调最终进度");
+    let last = &reports[0];
+    assert!(last.finished, "最终进度应标记为结束");
+    assert_eq!(last.packets, stats.packets);
+    assert_eq!(last.bytes, stats.total_size);
+    assert_eq!(last.frames, stats.packets, "纯音频输出按数据包计帧");
+    assert!(
+        (last.out_time.to_seconds() - 0.5).abs() < 0.05,
+        "输出时间应约为 0.5 秒, 实际 {}",
+        last.out_time
+    );
+    assert_eq!(last.ratio().map(|r| r > 0.9), Some(true));
+}
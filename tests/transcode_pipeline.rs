@@ -39,6 +39,12 @@ fn mux_wav_in_memory(
     sample_rate: u32,
     channels: u32,
 ) -> IoContext {
+    let mut codec_registry = CodecRegistry::new();
+    tao_codec::register_all(&mut codec_registry);
+    let sample_format = codec_registry
+        .create_encoder(codec_id)
+        .unwrap()
+        .preferred_sample_format();
     let mut muxer = format_registry.create_muxer(FormatId::Wav).unwrap();
     let backend = MemoryBackend::new();
     let mut io = IoContext::new(Box::new(backend));
@@ -54,9 +60,10 @@ fn mux_wav_in_memory(
         params: StreamParams::Audio(AudioStreamParams {
             sample_rate,
             channel_layout: ChannelLayout::from_channels(channels),
-            sample_format: codec_id_to_sample_format(codec_id),
+            sample_format,
             bit_rate: 0,
             frame_size: 0,
+            block_align: 0,
         }),
         metadata: Vec::new(),
     };
@@ -70,16 +77,6 @@ fn mux_wav_in_memory(
     io
 }
 
-fn codec_id_to_sample_format(codec_id: CodecId) -> SampleFormat {
-    match codec_id {
-        CodecId::PcmU8 => SampleFormat::U8,
-        CodecId::PcmS16le | CodecId::PcmS16be => SampleFormat::S16,
-        CodecId::PcmS24le | CodecId::PcmS32le => SampleFormat::S32,
-        CodecId::PcmF32le => SampleFormat::F32,
-        _ => SampleFormat::None,
-    }
-}
-
 /// 完整转码流程:
 /// 输入 WAV → demux → decode → (resample) → encode → mux → 输出 WAV
 fn transcode_wav(
@@ -119,6 +116,7 @@ fn transcode_wav(
                 channel_layout: audio_params.channel_layout,
                 sample_format: audio_params.sample_format,
                 frame_size: 0,
+                block_align: 0,
             }),
         })
         .unwrap();
@@ -127,7 +125,8 @@ fn transcode_wav(
     let out_sample_rate = target_sample_rate.unwrap_or(audio_params.sample_rate);
     let out_channels = target_channels.unwrap_or(audio_params.channel_layout.channels);
     let out_channel_layout = ChannelLayout::from_channels(out_channels);
-    let out_sample_format = codec_id_to_sample_format(output_codec_id);
+    let mut encoder = codec_registry.create_encoder(output_codec_id).unwrap();
+    let out_sample_format = encoder.preferred_sample_format();
 
     // 4. 创建重采样器 (如果需要)
     let resampler = if audio_params.sample_rate != out_sample_rate
@@ -146,8 +145,7 @@ fn transcode_wav(
         None
     };
 
-    // 5. 创建编码器
-    let mut encoder = codec_registry.create_encoder(output_codec_id).unwrap();
+    // 5. 打开编码器
     encoder
         .open(&CodecParameters {
             codec_id: output_codec_id,
@@ -158,6 +156,7 @@ fn transcode_wav(
                 channel_layout: out_channel_layout,
                 sample_format: out_sample_format,
                 frame_size: 0,
+                block_align: 0,
             }),
         })
         .unwrap();
@@ -178,6 +177,7 @@ fn transcode_wav(
             sample_format: out_sample_format,
             bit_rate: 0,
             frame_size: 0,
+            block_align: 0,
         }),
         metadata: Vec::new(),
     };
@@ -253,7 +253,7 @@ fn resample_audio_frame(
                 dst_sample_format,
                 ChannelLayout::from_channels(dst_channels),
             );
-            out.data[0] = output_data;
+            out.data[0] = output_data.into();
             out.pts = audio.pts;
             out.time_base = audio.time_base;
             Frame::Audio(out)
@@ -45,6 +45,7 @@ fn run_vorbis_until_output_frames(url: &str) -> Result<(), Box<dyn std::error::E
             channel_layout,
             sample_format: SampleFormat::F32,
             frame_size: 0,
+            block_align: 0,
         }),
     };
 
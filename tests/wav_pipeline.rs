@@ -52,6 +52,7 @@ fn make_audio_stream(codec_id: CodecId, sample_rate: u32, channels: u32) -> Stre
             sample_format: SampleFormat::S16,
             bit_rate: 0,
             frame_size: 0,
+            block_align: 0,
         }),
         metadata: Vec::new(),
     }
@@ -68,6 +69,7 @@ fn make_audio_params(codec_id: CodecId, sample_rate: u32, channels: u32) -> Code
             channel_layout: ChannelLayout::from_channels(channels),
             sample_format: SampleFormat::None,
             frame_size: 0,
+            block_align: 0,
         }),
     }
 }
@@ -147,7 +149,7 @@ fn test_full_pipeline_encode_mux_demux_decode_roundtrip() {
         SampleFormat::S16,
         ChannelLayout::from_channels(channels),
     );
-    frame.data[0] = pcm_data.clone();
+    frame.data[0] = pcm_data.clone().into();
     frame.pts = 0;
     frame.time_base = Rational::new(1, sample_rate as i32);
 
@@ -295,3 +297,81 @@ fn test_wav_not_same_pcm_format() {
         );
     }
 }
+
+#[test]
+fn test_wav_s24_roundtrip_decode_s24() {
+    let sample_rate = 48000u32;
+    let channels = 2u32;
+
+    // 1. 原始 24 位样本 (含正负极值)
+    let original: Vec<i32> = vec![
+        0, -1, 1, 8_388_607, -8_388_608, 0x123456, -0x123456, 4_000_000,
+    ];
+    let mut s24_bytes = Vec::with_capacity(original.len() * 3);
+    for v in &original {
+        s24_bytes.extend_from_slice(&v.to_le_bytes()[..3]);
+    }
+
+    // 2. 封装为 24 位 WAV
+    let format_registry = tao::default_format_registry();
+    let mut muxer = format_registry.create_muxer(FormatId::Wav).unwrap();
+    let mut io = IoContext::new(Box::new(MemoryBackend::new()));
+    let stream = make_audio_stream(CodecId::PcmS24le, sample_rate, channels);
+    muxer.write_header(&mut io, &[stream]).unwrap();
+    muxer
+        .write_packet(&mut io, &Packet::from_data(s24_bytes))
+        .unwrap();
+    muxer.write_trailer(&mut io).unwrap();
+
+    // 3. 解封装
+    io.seek(std::io::SeekFrom::Start(0)).unwrap();
+    let mut demuxer = format_registry.create_demuxer(FormatId::Wav).unwrap();
+    demuxer.open(&mut io).unwrap();
+    assert_eq!(
+        demuxer.streams()[0].codec_id,
+        CodecId::PcmS24le,
+        "应识别为 24 位 PCM"
+    );
+
+    // 4. 以 S24 输出格式打开解码器
+    let codec_registry = tao::default_codec_registry();
+    let mut decoder = codec_registry.create_decoder(CodecId::PcmS24le).unwrap();
+    let mut params = make_audio_params(CodecId::PcmS24le, sample_rate, channels);
+    if let CodecParamsType::Audio(a) = &mut params.params {
+        a.sample_format = SampleFormat::S24;
+    }
+    decoder.open(&params).unwrap();
+
+    let mut decoded = Vec::new();
+    loop {
+        match demuxer.read_packet(&mut io) {
+            Ok(pkt) => {
+                decoder.send_packet(&pkt).unwrap();
+                match decoder.receive_frame() {
+                    Ok(Frame::Audio(af)) => {
+                        assert_eq!(af.sample_format, SampleFormat::S24, "输出应为 S24");
+                        assert_eq!(
+                            af.data[0].len(),
+                            af.nb_samples as usize
+                                * channels as usize
+                                * SampleFormat::S24.bytes_per_sample() as usize,
+                            "缓冲区大小应按 4 字节容器计算",
+                        );
+                        decoded.extend(
+                            af.data[0]
+                                .chunks_exact(4)
+                                .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]])),
+                        );
+                    }
+                    Ok(_) => panic!("期望音频帧"),
+                    Err(e) => panic!("解码失败: {e}"),
+                }
+            }
+            Err(tao::core::TaoError::Eof) => break,
+            Err(e) => panic!("读包失败: {e}"),
+        }
+    }
+
+    // 5. 验证与原始样本值一致
+    assert_eq!(decoded, original, "S24 WAV 往返样本值不一致");
+}